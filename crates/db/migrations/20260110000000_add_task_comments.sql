@@ -0,0 +1,11 @@
+-- Free-form comments attached to a task, written by users or automation plugins
+CREATE TABLE task_comments (
+    id          BLOB PRIMARY KEY,
+    task_id     BLOB NOT NULL,
+    author      TEXT NOT NULL,
+    body        TEXT NOT NULL CHECK(body != ''),
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_comments_task_id ON task_comments(task_id);
@@ -0,0 +1,14 @@
+-- User-defined automation rules: a condition expression plus an action to run when it matches
+CREATE TABLE automation_rules (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB,
+    name        TEXT NOT NULL,
+    condition   TEXT NOT NULL CHECK(condition != ''),
+    action      TEXT NOT NULL,
+    enabled     INTEGER NOT NULL DEFAULT 1,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_automation_rules_project_id ON automation_rules(project_id);
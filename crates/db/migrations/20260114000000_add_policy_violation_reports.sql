@@ -0,0 +1,12 @@
+-- Changes rejected by an executor policy, recorded when an execution or push is blocked
+CREATE TABLE policy_violation_reports (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    execution_process_id BLOB,
+    violations           TEXT NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_policy_violation_reports_workspace_id ON policy_violation_reports(workspace_id);
@@ -0,0 +1,9 @@
+-- Secret scan findings a user marked as false positives, so they no longer block pushes
+CREATE TABLE secret_scan_acknowledgements (
+    id           BLOB PRIMARY KEY,
+    workspace_id BLOB NOT NULL,
+    fingerprint  TEXT NOT NULL,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    UNIQUE (workspace_id, fingerprint)
+);
@@ -0,0 +1,12 @@
+-- Dependency policy results for manifest changes, attached to the run that made them
+CREATE TABLE dependency_reports (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    execution_process_id BLOB,
+    findings             TEXT NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_dependency_reports_execution_process_id ON dependency_reports(execution_process_id);
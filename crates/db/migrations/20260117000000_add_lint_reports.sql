@@ -0,0 +1,13 @@
+-- Diagnostics from the post-execution lint stage, attached to the run they were collected after
+CREATE TABLE lint_reports (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    execution_process_id BLOB,
+    findings             TEXT NOT NULL,
+    fix_requested        INTEGER NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_lint_reports_workspace_id ON lint_reports(workspace_id);
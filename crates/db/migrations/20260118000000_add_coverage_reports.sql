@@ -0,0 +1,19 @@
+-- Line coverage of a workspace branch per repository, with the target branch baseline when known
+CREATE TABLE coverage_reports (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    execution_process_id BLOB,
+    repo_id              BLOB NOT NULL,
+    lines_total          INTEGER NOT NULL,
+    lines_covered        INTEGER NOT NULL,
+    base_lines_total     INTEGER,
+    base_lines_covered   INTEGER,
+    patch_lines_total    INTEGER NOT NULL,
+    patch_lines_covered  INTEGER NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_coverage_reports_workspace_id ON coverage_reports(workspace_id);
@@ -0,0 +1,30 @@
+-- Outcomes of setup/cleanup scripts and lint commands, used to spot flaky commands
+CREATE TABLE command_runs (
+    id                   BLOB PRIMARY KEY,
+    repo_id              BLOB NOT NULL,
+    workspace_id         BLOB NOT NULL,
+    execution_process_id BLOB,
+    kind                 TEXT NOT NULL CHECK (kind IN ('setupscript', 'cleanupscript', 'lint')),
+    command_hash         TEXT NOT NULL,
+    command              TEXT NOT NULL,
+    head_commit          TEXT,
+    succeeded            INTEGER NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_command_runs_repo_id_command_hash ON command_runs(repo_id, command_hash);
+
+-- Commands whose failures no longer trigger fix-up turns
+CREATE TABLE command_quarantines (
+    id           BLOB PRIMARY KEY,
+    repo_id      BLOB NOT NULL,
+    command_hash TEXT NOT NULL,
+    command      TEXT NOT NULL,
+    automatic    INTEGER NOT NULL DEFAULT 0,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    UNIQUE (repo_id, command_hash)
+);
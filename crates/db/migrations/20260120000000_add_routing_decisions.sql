@@ -0,0 +1,20 @@
+-- Executor profile chosen for each coding agent turn when model routing is enabled
+CREATE TABLE routing_decisions (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL UNIQUE,
+    workspace_id         BLOB NOT NULL,
+    purpose              TEXT NOT NULL CHECK (purpose IN ('initial', 'followup', 'question', 'prdescription', 'lintfix')),
+    task_size            TEXT NOT NULL CHECK (task_size IN ('small', 'medium', 'large')),
+    requested_profile    TEXT NOT NULL,
+    selected_profile     TEXT NOT NULL,
+    rule_name            TEXT,
+    reason               TEXT NOT NULL,
+    previous_failures    INTEGER NOT NULL DEFAULT 0,
+    budget_remaining     REAL,
+    estimated_cost       REAL NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_routing_decisions_workspace_id ON routing_decisions(workspace_id);
@@ -0,0 +1,17 @@
+-- Context blocks built from earlier turns and the current diff for turns that cannot resume the
+-- agent's own session
+CREATE TABLE context_compactions (
+    id                   BLOB PRIMARY KEY,
+    session_id           BLOB NOT NULL,
+    execution_process_id BLOB NOT NULL UNIQUE,
+    turns_summarized     INTEGER NOT NULL,
+    turns_included       INTEGER NOT NULL,
+    diff_files           INTEGER NOT NULL,
+    source_chars         INTEGER NOT NULL,
+    content              TEXT NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_context_compactions_session_id ON context_compactions(session_id);
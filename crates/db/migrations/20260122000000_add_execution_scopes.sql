@@ -0,0 +1,9 @@
+-- Files and hunks a follow-up was scoped to from the diff view
+CREATE TABLE execution_scopes (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL UNIQUE,
+    files                TEXT NOT NULL,
+    restrict_writes      INTEGER NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,10 @@
+-- Repositories whose git data lives in a server-managed primary clone
+CREATE TABLE managed_repos (
+    repo_id         BLOB PRIMARY KEY,
+    -- User checkout the clone was made from, when it was adopted rather than cloned by URL
+    source_path     TEXT,
+    remote_url      TEXT,
+    last_fetched_at TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
@@ -0,0 +1,13 @@
+-- Background fetch settings and outcome per repository
+CREATE TABLE repo_fetch_states (
+    repo_id              BLOB PRIMARY KEY,
+    enabled              INTEGER NOT NULL DEFAULT 1,
+    -- Overrides the global fetch interval when set
+    interval_minutes     INTEGER,
+    last_attempt_at      TEXT,
+    last_success_at      TEXT,
+    last_error           TEXT,
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
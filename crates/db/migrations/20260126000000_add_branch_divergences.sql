@@ -0,0 +1,15 @@
+-- Ahead/behind counts of workspace branches, refreshed after background fetches
+CREATE TABLE branch_divergences (
+    workspace_id          BLOB NOT NULL,
+    repo_id               BLOB NOT NULL,
+    target_branch         TEXT NOT NULL,
+    commits_ahead         INTEGER NOT NULL,
+    commits_behind        INTEGER NOT NULL,
+    -- NULL when the branch has not been pushed
+    remote_commits_ahead  INTEGER,
+    remote_commits_behind INTEGER,
+    computed_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (workspace_id, repo_id),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
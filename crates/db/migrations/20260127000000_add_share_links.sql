@@ -0,0 +1,15 @@
+-- Read-only links to a task, or to one of its attempts, for people without access to the app
+CREATE TABLE share_links (
+    id            BLOB PRIMARY KEY,
+    token         TEXT NOT NULL UNIQUE,
+    task_id       BLOB NOT NULL,
+    -- NULL shares every attempt of the task
+    workspace_id  BLOB,
+    expires_at    TEXT,
+    revoked_at    TEXT,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_share_links_task_id ON share_links(task_id);
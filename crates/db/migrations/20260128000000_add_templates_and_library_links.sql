@@ -0,0 +1,22 @@
+-- Reusable prompts and task descriptions
+CREATE TABLE templates (
+    id          BLOB PRIMARY KEY,
+    kind        TEXT NOT NULL CHECK (kind IN ('prompt', 'task')),
+    name        TEXT NOT NULL,
+    -- Title of tasks created from a task template
+    title       TEXT,
+    content     TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Local templates and automation rules that were published to, or pulled from, an organization
+-- library, with the library version they were last in sync with. local_id points into
+-- templates or automation_rules depending on the kind of the library item.
+CREATE TABLE library_links (
+    library_item_id  BLOB PRIMARY KEY,
+    organization_id  BLOB NOT NULL,
+    local_id         BLOB NOT NULL UNIQUE,
+    version          INTEGER NOT NULL,
+    synced_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
@@ -0,0 +1,20 @@
+-- Projects kept in sync with their remote project, so another instance can work on the same board
+CREATE TABLE project_mirrors (
+    project_id      BLOB PRIMARY KEY,
+    last_synced_at  TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Last version of a mirrored task both sides agreed on, to tell which side changed what
+CREATE TABLE task_mirror_states (
+    task_id           BLOB PRIMARY KEY,
+    shared_task_id    BLOB NOT NULL,
+    base_title        TEXT NOT NULL,
+    base_description  TEXT,
+    base_status       TEXT NOT NULL,
+    -- Remote version of the task when both sides changed the same field differently
+    conflict          TEXT,
+    synced_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
@@ -0,0 +1,24 @@
+-- Persistent background jobs, run by the worker pool of the job runner
+CREATE TABLE jobs (
+    id              BLOB PRIMARY KEY,
+    kind            TEXT NOT NULL,
+    payload         TEXT NOT NULL DEFAULT '{}',
+    status          TEXT NOT NULL DEFAULT 'pending'
+                       CHECK (status IN ('pending', 'running', 'succeeded', 'dead')),
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    max_attempts    INTEGER NOT NULL DEFAULT 5,
+    -- Recurring jobs are rescheduled this many seconds after each run instead of finishing
+    interval_secs   INTEGER,
+    run_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    started_at      TEXT,
+    finished_at     TEXT,
+    last_error      TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_jobs_due ON jobs(status, run_at);
+
+-- At most one live occurrence of each recurring job
+CREATE UNIQUE INDEX idx_jobs_recurring ON jobs(kind)
+    WHERE interval_secs IS NOT NULL AND status IN ('pending', 'running');
@@ -0,0 +1,24 @@
+-- Append-only log of task changes. The activity feed, the audit log and share publishing are all
+-- read from it. There is no foreign key to tasks so the history outlives deleted tasks.
+CREATE TABLE task_events (
+    id          BLOB PRIMARY KEY,
+    task_id     BLOB NOT NULL,
+    project_id  BLOB NOT NULL,
+    kind        TEXT NOT NULL CHECK (kind IN ('created', 'updated', 'deleted')),
+    source      TEXT NOT NULL CHECK (source IN ('user', 'automation', 'webhook', 'system')),
+    -- Who made the change within its source, e.g. `rule:Move reviewed cards` or `pr_monitor`
+    actor       TEXT,
+    -- Task fields before and after the change, as JSON; NULL before creation and after deletion
+    before      TEXT,
+    after       TEXT,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_task_events_task ON task_events(task_id, created_at);
+CREATE INDEX idx_task_events_project ON task_events(project_id, created_at);
+
+CREATE TRIGGER trg_task_events_immutable
+BEFORE UPDATE ON task_events
+BEGIN
+    SELECT RAISE(ABORT, 'task events are immutable');
+END;
@@ -0,0 +1,4 @@
+-- Undo and redo are recorded as ordinary updates that point at the change they revert or reapply,
+-- so the undo and redo stacks of a task can be rebuilt from its log.
+ALTER TABLE task_events ADD COLUMN undo_of BLOB;
+ALTER TABLE task_events ADD COLUMN redo_of BLOB;
@@ -0,0 +1,22 @@
+-- Non-interactive identities that integrations act as, so the audit log can tell them apart from
+-- people. Only a hash of each API key is stored; the key itself is shown once when it is issued.
+CREATE TABLE service_accounts (
+    id              BLOB PRIMARY KEY,
+    name            TEXT NOT NULL UNIQUE,
+    description     TEXT,
+    -- JSON array of scopes, e.g. ["read", "write_tasks"]
+    scopes          TEXT NOT NULL DEFAULT '[]',
+    key_hash        TEXT UNIQUE,
+    -- Start of the key, to recognize it in configuration files
+    key_prefix      TEXT,
+    key_issued_at   TEXT,
+    last_used_at    TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- The built-in integrations; none of them has a key until one is issued
+INSERT INTO service_accounts (id, name, description, scopes) VALUES
+    (randomblob(16), 'webhook', 'Inbound provider webhooks', '["receive_webhooks"]'),
+    (randomblob(16), 'scheduler', 'Scheduled jobs that create and update tasks', '["read", "write_tasks", "start_attempts"]'),
+    (randomblob(16), 'mcp', 'The MCP task server', '["read", "write_tasks", "start_attempts"]');
@@ -0,0 +1,41 @@
+-- Isolated organizations sharing one server. Projects, repositories and service accounts belong to
+-- exactly one; everything else is reached through them. Existing rows go to the default tenant,
+-- which has the nil id so that it can be used as a column default.
+CREATE TABLE tenants (
+    id          BLOB PRIMARY KEY,
+    name        TEXT NOT NULL UNIQUE,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+INSERT INTO tenants (id, name) VALUES (X'00000000000000000000000000000000', 'Default');
+
+ALTER TABLE projects ADD COLUMN tenant_id BLOB NOT NULL DEFAULT X'00000000000000000000000000000000';
+ALTER TABLE repos ADD COLUMN tenant_id BLOB NOT NULL DEFAULT X'00000000000000000000000000000000';
+
+CREATE INDEX idx_projects_tenant ON projects(tenant_id);
+CREATE INDEX idx_repos_tenant ON repos(tenant_id);
+
+-- Service account names only need to be unique within a tenant
+CREATE TABLE service_accounts_new (
+    id              BLOB PRIMARY KEY,
+    tenant_id       BLOB NOT NULL DEFAULT X'00000000000000000000000000000000'
+                    REFERENCES tenants(id) ON DELETE CASCADE,
+    name            TEXT NOT NULL,
+    description     TEXT,
+    scopes          TEXT NOT NULL DEFAULT '[]',
+    key_hash        TEXT UNIQUE,
+    key_prefix      TEXT,
+    key_issued_at   TEXT,
+    last_used_at    TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (tenant_id, name)
+);
+
+INSERT INTO service_accounts_new (id, name, description, scopes, key_hash, key_prefix, key_issued_at, last_used_at, created_at, updated_at)
+SELECT id, name, description, scopes, key_hash, key_prefix, key_issued_at, last_used_at, created_at, updated_at
+FROM service_accounts;
+
+DROP TABLE service_accounts;
+ALTER TABLE service_accounts_new RENAME TO service_accounts;
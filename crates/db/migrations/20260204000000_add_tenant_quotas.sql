@@ -0,0 +1,14 @@
+-- Usage limits of an organization; a missing row or a NULL column means no limit
+CREATE TABLE tenant_quotas (
+    tenant_id                  BLOB PRIMARY KEY REFERENCES tenants(id) ON DELETE CASCADE,
+    max_concurrent_executions  INTEGER,
+    -- Estimated coding agent spend per calendar month (UTC), as recorded by model routing
+    monthly_spend_limit        REAL,
+    storage_limit_bytes        INTEGER,
+    updated_at                 TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Uploaded images count against the storage of the organization that uploaded them
+ALTER TABLE images ADD COLUMN tenant_id BLOB NOT NULL DEFAULT X'00000000000000000000000000000000';
+
+CREATE INDEX idx_images_tenant ON images(tenant_id);
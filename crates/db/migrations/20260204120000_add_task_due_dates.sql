@@ -0,0 +1,5 @@
+-- When a task is due, in UTC like every other timestamp. Clients send due dates with their offset
+-- and read them back in the user's timezone.
+ALTER TABLE tasks ADD COLUMN due_at TEXT;
+
+CREATE INDEX idx_tasks_due_at ON tasks(project_id, due_at) WHERE due_at IS NOT NULL;
@@ -0,0 +1,12 @@
+-- Named transitions: a sequence of automation rule actions applied to tasks on demand
+CREATE TABLE automation_transitions (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB,
+    name        TEXT NOT NULL CHECK(name != ''),
+    steps       TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_automation_transitions_project_id ON automation_transitions(project_id);
@@ -0,0 +1,12 @@
+-- Workspaces opted into follow-ups from new pull request review comments; NULL when not opted in
+ALTER TABLE workspaces ADD COLUMN review_follow_ups_enabled_at TEXT;
+
+-- Pull request comments already sent to the agent, or seen when they arrived, per merge
+CREATE TABLE seen_pr_comments (
+    id          BLOB PRIMARY KEY,
+    merge_id    BLOB NOT NULL,
+    comment_key TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (merge_id) REFERENCES merges(id) ON DELETE CASCADE,
+    UNIQUE (merge_id, comment_key)
+);
@@ -0,0 +1,20 @@
+-- Named board views of a project: a filter expression, a sort order and a grouping of the cards
+CREATE TABLE saved_views (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    -- Signed-in user who saved the view; NULL for views saved while signed out
+    owner_user_id   BLOB,
+    name            TEXT NOT NULL CHECK(name != ''),
+    filter          TEXT NOT NULL DEFAULT '',
+    sort_field      TEXT NOT NULL DEFAULT 'created_at'
+                       CHECK (sort_field IN ('created_at', 'updated_at', 'title')),
+    sort_descending BOOLEAN NOT NULL DEFAULT TRUE,
+    grouping        TEXT NOT NULL DEFAULT 'status'
+                       CHECK (grouping IN ('status', 'executor', 'pr_status', 'none')),
+    shared          BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_saved_views_project_id ON saved_views(project_id);
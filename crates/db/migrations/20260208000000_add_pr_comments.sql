@@ -0,0 +1,18 @@
+-- Comments fetched from pull requests, keyed by the provider's comment id, and whether they were
+-- addressed; addressed_by_execution_id is the agent turn that handled the comment, if any
+CREATE TABLE pr_comments (
+    id                        BLOB PRIMARY KEY,
+    merge_id                  BLOB NOT NULL,
+    comment_key               TEXT NOT NULL,
+    author                    TEXT NOT NULL,
+    body                      TEXT NOT NULL,
+    url                       TEXT NOT NULL,
+    commented_at              TEXT NOT NULL,
+    addressed_at              TEXT,
+    addressed_by_execution_id BLOB,
+    created_at                TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at                TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (merge_id) REFERENCES merges(id) ON DELETE CASCADE,
+    FOREIGN KEY (addressed_by_execution_id) REFERENCES execution_processes(id) ON DELETE SET NULL,
+    UNIQUE (merge_id, comment_key)
+);
@@ -0,0 +1,28 @@
+-- Longest a task should stay in a board column of a project, and what happens once it stayed longer
+CREATE TABLE column_slas (
+    id         BLOB PRIMARY KEY,
+    project_id BLOB NOT NULL,
+    status     TEXT NOT NULL
+                  CHECK (status IN ('todo', 'inprogress', 'inreview', 'done', 'cancelled')),
+    max_hours  INTEGER NOT NULL CHECK (max_hours > 0),
+    action     TEXT NOT NULL DEFAULT 'label'
+                  CHECK (action IN ('label', 'notify', 'nudge')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, status)
+);
+
+-- Tasks escalated for staying in a column too long, once per time they entered it
+CREATE TABLE sla_escalations (
+    id         BLOB PRIMARY KEY,
+    task_id    BLOB NOT NULL,
+    status     TEXT NOT NULL,
+    entered_at TEXT NOT NULL,
+    action     TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    UNIQUE (task_id, status, entered_at)
+);
+
+CREATE INDEX idx_sla_escalations_task_id ON sla_escalations(task_id);
@@ -0,0 +1,18 @@
+-- Who may work in a tenant, by the user id of the account they sign in with. The default tenant
+-- is open to everyone and has no members.
+CREATE TABLE tenant_members (
+    tenant_id   BLOB NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    user_id     BLOB NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (tenant_id, user_id)
+);
+
+CREATE INDEX idx_tenant_members_user ON tenant_members(user_id);
+
+-- Settings of each tenant other than the default one, which keeps using the config file. Tokens
+-- and secrets in them stay with their tenant.
+CREATE TABLE tenant_configs (
+    tenant_id   BLOB PRIMARY KEY REFERENCES tenants(id) ON DELETE CASCADE,
+    config      TEXT NOT NULL,
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
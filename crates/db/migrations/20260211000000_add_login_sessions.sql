@@ -0,0 +1,11 @@
+-- Browsers signed in to the app, so each request says who is making it instead of taking the
+-- account the server is signed in with. Only the hash of the session token is stored.
+CREATE TABLE login_sessions (
+    id          BLOB PRIMARY KEY,
+    user_id     BLOB NOT NULL,
+    token_hash  TEXT NOT NULL UNIQUE,
+    expires_at  TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_login_sessions_user ON login_sessions(user_id);
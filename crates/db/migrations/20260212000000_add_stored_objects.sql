@@ -0,0 +1,15 @@
+-- Every object the server keeps in object storage, with the tenant it counts against. Storage
+-- quotas add these up, so attachments, execution artifacts and exported transcripts all count.
+CREATE TABLE stored_objects (
+    key         TEXT PRIMARY KEY,
+    tenant_id   BLOB NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    kind        TEXT NOT NULL CHECK (kind IN ('image', 'artifact', 'transcript')),
+    size_bytes  INTEGER NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_stored_objects_tenant ON stored_objects(tenant_id);
+
+INSERT INTO stored_objects (key, tenant_id, kind, size_bytes, created_at)
+SELECT 'images/' || file_path, tenant_id, 'image', size_bytes, created_at
+FROM images;
@@ -0,0 +1,168 @@
+-- Counter bumped by every change to the rows the task list and board of a project are built
+-- from, so conditional reads can be answered before loading them
+CREATE TABLE project_versions (
+    project_id BLOB PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    version    INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT INTO project_versions (project_id, version)
+SELECT id, 1 FROM projects;
+
+-- Tasks
+CREATE TRIGGER trg_tasks_insert_project_version
+AFTER INSERT ON tasks
+BEGIN
+    INSERT INTO project_versions (project_id, version) VALUES (NEW.project_id, 1)
+    ON CONFLICT(project_id) DO UPDATE SET version = version + 1;
+END;
+
+CREATE TRIGGER trg_tasks_update_project_version
+AFTER UPDATE ON tasks
+BEGIN
+    INSERT INTO project_versions (project_id, version) VALUES (NEW.project_id, 1)
+    ON CONFLICT(project_id) DO UPDATE SET version = version + 1;
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = OLD.project_id AND OLD.project_id != NEW.project_id;
+END;
+
+CREATE TRIGGER trg_tasks_delete_project_version
+AFTER DELETE ON tasks
+BEGIN
+    UPDATE project_versions SET version = version + 1 WHERE project_id = OLD.project_id;
+END;
+
+-- Workspaces
+CREATE TRIGGER trg_workspaces_insert_project_version
+AFTER INSERT ON workspaces
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (SELECT project_id FROM tasks WHERE id = NEW.task_id);
+END;
+
+CREATE TRIGGER trg_workspaces_update_project_version
+AFTER UPDATE ON workspaces
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (SELECT project_id FROM tasks WHERE id = NEW.task_id);
+END;
+
+CREATE TRIGGER trg_workspaces_delete_project_version
+AFTER DELETE ON workspaces
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (SELECT project_id FROM tasks WHERE id = OLD.task_id);
+END;
+
+-- Sessions
+CREATE TRIGGER trg_sessions_insert_project_version
+AFTER INSERT ON sessions
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (
+        SELECT t.project_id
+          FROM workspaces w
+          JOIN tasks t ON t.id = w.task_id
+         WHERE w.id = NEW.workspace_id
+     );
+END;
+
+CREATE TRIGGER trg_sessions_update_project_version
+AFTER UPDATE ON sessions
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (
+        SELECT t.project_id
+          FROM workspaces w
+          JOIN tasks t ON t.id = w.task_id
+         WHERE w.id = NEW.workspace_id
+     );
+END;
+
+CREATE TRIGGER trg_sessions_delete_project_version
+AFTER DELETE ON sessions
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (
+        SELECT t.project_id
+          FROM workspaces w
+          JOIN tasks t ON t.id = w.task_id
+         WHERE w.id = OLD.workspace_id
+     );
+END;
+
+-- Execution processes
+CREATE TRIGGER trg_execution_processes_insert_project_version
+AFTER INSERT ON execution_processes
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (
+        SELECT t.project_id
+          FROM sessions s
+          JOIN workspaces w ON w.id = s.workspace_id
+          JOIN tasks t ON t.id = w.task_id
+         WHERE s.id = NEW.session_id
+     );
+END;
+
+CREATE TRIGGER trg_execution_processes_update_project_version
+AFTER UPDATE ON execution_processes
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (
+        SELECT t.project_id
+          FROM sessions s
+          JOIN workspaces w ON w.id = s.workspace_id
+          JOIN tasks t ON t.id = w.task_id
+         WHERE s.id = NEW.session_id
+     );
+END;
+
+CREATE TRIGGER trg_execution_processes_delete_project_version
+AFTER DELETE ON execution_processes
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (
+        SELECT t.project_id
+          FROM sessions s
+          JOIN workspaces w ON w.id = s.workspace_id
+          JOIN tasks t ON t.id = w.task_id
+         WHERE s.id = OLD.session_id
+     );
+END;
+
+-- Merges, for the pull request badges of the board
+CREATE TRIGGER trg_merges_insert_project_version
+AFTER INSERT ON merges
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (
+        SELECT t.project_id
+          FROM workspaces w
+          JOIN tasks t ON t.id = w.task_id
+         WHERE w.id = NEW.workspace_id
+     );
+END;
+
+CREATE TRIGGER trg_merges_update_project_version
+AFTER UPDATE ON merges
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (
+        SELECT t.project_id
+          FROM workspaces w
+          JOIN tasks t ON t.id = w.task_id
+         WHERE w.id = NEW.workspace_id
+     );
+END;
+
+CREATE TRIGGER trg_merges_delete_project_version
+AFTER DELETE ON merges
+BEGIN
+    UPDATE project_versions SET version = version + 1
+     WHERE project_id = (
+        SELECT t.project_id
+          FROM workspaces w
+          JOIN tasks t ON t.id = w.task_id
+         WHERE w.id = OLD.workspace_id
+     );
+END;
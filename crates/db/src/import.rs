@@ -0,0 +1,773 @@
+//! Import from a vibe-kanban database.
+//!
+//! Copies the projects, tasks, attempts and merges of a vibe-kanban SQLite database into this one
+//! under new ids, from databases written before as well as after attempts became workspaces.
+//! Repositories already registered here under the same path are reused. Worktrees are not carried
+//! over: imported workspaces get new ones when next started, which leaves the other tool's
+//! worktrees alone. The source schema depends on the version that wrote it, so its columns are
+//! looked up first and the queries here are not checked at compile time.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use sqlx::{
+    Row, SqliteConnection, SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::models::task::TaskStatus;
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("No database found at {}", .0.display())]
+    NotFound(PathBuf),
+    #[error("{} is not a vibe-kanban database: it has no {table} table", path.display())]
+    NotVibeKanban { path: PathBuf, table: &'static str },
+}
+
+/// Rows of one kind in the source database
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct SourceCounts {
+    pub projects: usize,
+    pub tasks: usize,
+    pub attempts: usize,
+    pub merges: usize,
+}
+
+/// New ids of the imported rows, keyed by their id in the source database
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IdMapping {
+    pub projects: BTreeMap<Uuid, Uuid>,
+    pub tasks: BTreeMap<Uuid, Uuid>,
+    /// Attempts, which are workspaces here
+    pub workspaces: BTreeMap<Uuid, Uuid>,
+    pub merges: BTreeMap<Uuid, Uuid>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub source: SourceCounts,
+    pub ids: IdMapping,
+    pub repos_created: usize,
+    /// Repositories that were already registered here under the same path
+    pub repos_reused: usize,
+    /// Rows left out, with the reason
+    pub skipped: Vec<String>,
+    /// Differences found reading the imported rows back; nothing is kept when there are any
+    pub mismatches: Vec<String>,
+    /// Whether the import was kept; false for dry runs and failed verification
+    pub committed: bool,
+}
+
+struct ProjectRepo {
+    path: String,
+    display_name: Option<String>,
+    setup_script: Option<String>,
+    cleanup_script: Option<String>,
+    copy_files: Option<String>,
+    parallel_setup_script: bool,
+}
+
+struct Project {
+    id: Uuid,
+    name: String,
+    repos: Vec<ProjectRepo>,
+    created_at: String,
+    updated_at: String,
+}
+
+struct Task {
+    id: Uuid,
+    project_id: Uuid,
+    title: String,
+    description: Option<String>,
+    status: String,
+    parent_attempt_id: Option<Uuid>,
+    created_at: String,
+    updated_at: String,
+}
+
+struct Attempt {
+    id: Uuid,
+    task_id: Uuid,
+    branch: Option<String>,
+    executor: Option<String>,
+    /// Repository path and target branch of each repository the attempt works in
+    targets: Vec<(String, String)>,
+    created_at: String,
+    updated_at: String,
+}
+
+struct Merge {
+    id: Uuid,
+    attempt_id: Uuid,
+    repo_path: Option<String>,
+    merge_type: String,
+    merge_commit: Option<String>,
+    target_branch_name: Option<String>,
+    pr_number: Option<i64>,
+    pr_url: Option<String>,
+    pr_status: Option<String>,
+    pr_merged_at: Option<String>,
+    pr_merge_commit_sha: Option<String>,
+    created_at: String,
+}
+
+#[derive(Default)]
+struct Source {
+    projects: Vec<Project>,
+    tasks: Vec<Task>,
+    attempts: Vec<Attempt>,
+    merges: Vec<Merge>,
+    skipped: Vec<String>,
+}
+
+/// Open a database read-only
+pub async fn open_source(path: &Path) -> Result<SqlitePool, ImportError> {
+    if !path.is_file() {
+        return Err(ImportError::NotFound(path.to_path_buf()));
+    }
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    Ok(SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await?)
+}
+
+/// Copy the source database into `target` in one transaction, which is only committed when
+/// `commit` is set and reading the rows back matched the source
+pub async fn import(
+    target: &SqlitePool,
+    source_path: &Path,
+    commit: bool,
+) -> Result<ImportReport, ImportError> {
+    let source_pool = open_source(source_path).await?;
+    let source = read_source(&source_pool, source_path).await;
+    source_pool.close().await;
+    let source = source?;
+
+    let mut report = ImportReport {
+        source: SourceCounts {
+            projects: source.projects.len(),
+            tasks: source.tasks.len(),
+            attempts: source.attempts.len(),
+            merges: source.merges.len(),
+        },
+        skipped: source.skipped.clone(),
+        ..Default::default()
+    };
+
+    let mut tx = target.begin().await?;
+    write(&mut tx, &source, &mut report).await?;
+    verify(&mut tx, &source, &mut report).await?;
+    if commit && report.mismatches.is_empty() {
+        tx.commit().await?;
+        report.committed = true;
+    } else {
+        tx.rollback().await?;
+    }
+    Ok(report)
+}
+
+async fn tables(pool: &SqlitePool) -> Result<HashSet<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table'")
+        .fetch_all(pool)
+        .await
+        .map(|names| names.into_iter().collect())
+}
+
+async fn columns(pool: &SqlitePool, table: &str) -> Result<HashSet<String>, sqlx::Error> {
+    let rows = sqlx::query(&format!("PRAGMA table_info({table})"))
+        .fetch_all(pool)
+        .await?;
+    rows.iter().map(|row| row.try_get("name")).collect()
+}
+
+/// The first of `names` the table has, or NULL so that the query still runs without it
+fn column<'a>(columns: &HashSet<String>, names: &[&'a str]) -> &'a str {
+    names
+        .iter()
+        .find(|name| columns.contains(**name))
+        .copied()
+        .unwrap_or("NULL")
+}
+
+async fn read_source(pool: &SqlitePool, path: &Path) -> Result<Source, ImportError> {
+    let tables = tables(pool).await?;
+    for required in ["projects", "tasks"] {
+        if !tables.contains(required) {
+            return Err(ImportError::NotVibeKanban {
+                path: path.to_path_buf(),
+                table: required,
+            });
+        }
+    }
+    let attempts_table = if tables.contains("workspaces") {
+        "workspaces"
+    } else if tables.contains("task_attempts") {
+        "task_attempts"
+    } else {
+        return Err(ImportError::NotVibeKanban {
+            path: path.to_path_buf(),
+            table: "workspaces or task_attempts",
+        });
+    };
+
+    let mut source = Source::default();
+
+    // Repositories became their own table once projects could have several; before that each
+    // project pointed at one
+    let repo_paths: HashMap<Uuid, (String, Option<String>)> = if tables.contains("repos") {
+        sqlx::query("SELECT id, path, display_name FROM repos")
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| {
+                Ok((
+                    row.try_get("id")?,
+                    (row.try_get("path")?, row.try_get("display_name")?),
+                ))
+            })
+            .collect::<Result<_, sqlx::Error>>()?
+    } else {
+        HashMap::new()
+    };
+
+    let project_columns = columns(pool, "projects").await?;
+    let rows = sqlx::query(&format!(
+        "SELECT id, name, {} AS git_repo_path, {} AS setup_script, {} AS cleanup_script,
+                {} AS copy_files, {} AS parallel_setup_script, created_at, updated_at
+         FROM projects ORDER BY created_at",
+        column(&project_columns, &["git_repo_path"]),
+        column(&project_columns, &["setup_script"]),
+        column(&project_columns, &["cleanup_script"]),
+        column(&project_columns, &["copy_files"]),
+        column(&project_columns, &["parallel_setup_script"]),
+    ))
+    .fetch_all(pool)
+    .await?;
+    for row in rows {
+        let git_repo_path: Option<String> = row.try_get("git_repo_path")?;
+        let repos = git_repo_path
+            .filter(|path| !path.is_empty())
+            .map(|path| ProjectRepo {
+                path,
+                display_name: None,
+                setup_script: row.try_get("setup_script").ok().flatten(),
+                cleanup_script: row.try_get("cleanup_script").ok().flatten(),
+                copy_files: row.try_get("copy_files").ok().flatten(),
+                parallel_setup_script: row
+                    .try_get::<Option<bool>, _>("parallel_setup_script")
+                    .ok()
+                    .flatten()
+                    .unwrap_or(false),
+            })
+            .into_iter()
+            .collect();
+        source.projects.push(Project {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            repos,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        });
+    }
+
+    if tables.contains("project_repos") {
+        let rows = sqlx::query(
+            "SELECT project_id, repo_id, setup_script, cleanup_script, copy_files,
+                    parallel_setup_script
+             FROM project_repos",
+        )
+        .fetch_all(pool)
+        .await?;
+        for row in rows {
+            let project_id: Uuid = row.try_get("project_id")?;
+            let repo_id: Uuid = row.try_get("repo_id")?;
+            let Some((path, display_name)) = repo_paths.get(&repo_id) else {
+                continue;
+            };
+            let Some(project) = source.projects.iter_mut().find(|p| p.id == project_id) else {
+                continue;
+            };
+            if project.repos.iter().any(|repo| &repo.path == path) {
+                continue;
+            }
+            project.repos.push(ProjectRepo {
+                path: path.clone(),
+                display_name: display_name.clone(),
+                setup_script: row.try_get("setup_script")?,
+                cleanup_script: row.try_get("cleanup_script")?,
+                copy_files: row.try_get("copy_files")?,
+                parallel_setup_script: row.try_get("parallel_setup_script")?,
+            });
+        }
+    }
+
+    let task_columns = columns(pool, "tasks").await?;
+    let rows = sqlx::query(&format!(
+        "SELECT id, project_id, title, description, status, {} AS parent_attempt_id,
+                created_at, updated_at
+         FROM tasks ORDER BY created_at",
+        column(
+            &task_columns,
+            &["parent_workspace_id", "parent_task_attempt"]
+        ),
+    ))
+    .fetch_all(pool)
+    .await?;
+    for row in rows {
+        source.tasks.push(Task {
+            id: row.try_get("id")?,
+            project_id: row.try_get("project_id")?,
+            title: row.try_get("title")?,
+            description: row.try_get("description")?,
+            status: row.try_get("status")?,
+            parent_attempt_id: row.try_get("parent_attempt_id")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        });
+    }
+
+    // Before projects had several repositories, an attempt's one target branch was on the
+    // attempt and its repository was the project's
+    let sole_repo = |task_id: Uuid| -> Option<String> {
+        let task = source.tasks.iter().find(|task| task.id == task_id)?;
+        let project = source.projects.iter().find(|p| p.id == task.project_id)?;
+        match project.repos.as_slice() {
+            [repo] => Some(repo.path.clone()),
+            _ => None,
+        }
+    };
+
+    let attempt_columns = columns(pool, attempts_table).await?;
+    let rows = sqlx::query(&format!(
+        "SELECT id, task_id, {} AS branch, {} AS executor, {} AS target_branch,
+                created_at, updated_at
+         FROM {attempts_table} ORDER BY created_at",
+        column(&attempt_columns, &["branch"]),
+        column(&attempt_columns, &["executor"]),
+        column(&attempt_columns, &["target_branch", "base_branch"]),
+    ))
+    .fetch_all(pool)
+    .await?;
+    let mut attempts = Vec::with_capacity(rows.len());
+    for row in rows {
+        let task_id: Uuid = row.try_get("task_id")?;
+        let target_branch: Option<String> = row.try_get("target_branch")?;
+        let targets = target_branch
+            .zip(sole_repo(task_id))
+            .map(|(branch, path)| (path, branch))
+            .into_iter()
+            .collect();
+        attempts.push(Attempt {
+            id: row.try_get("id")?,
+            task_id,
+            branch: row.try_get("branch")?,
+            executor: row.try_get("executor")?,
+            targets,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        });
+    }
+
+    if let Some((table, attempt_column)) = [
+        ("workspace_repos", "workspace_id"),
+        ("attempt_repos", "attempt_id"),
+    ]
+    .into_iter()
+    .find(|(table, _)| tables.contains(*table))
+    {
+        let rows = sqlx::query(&format!(
+            "SELECT {attempt_column} AS attempt_id, repo_id, target_branch FROM {table}"
+        ))
+        .fetch_all(pool)
+        .await?;
+        for row in rows {
+            let attempt_id: Uuid = row.try_get("attempt_id")?;
+            let repo_id: Uuid = row.try_get("repo_id")?;
+            let Some((path, _)) = repo_paths.get(&repo_id) else {
+                continue;
+            };
+            if let Some(attempt) = attempts.iter_mut().find(|a| a.id == attempt_id) {
+                attempt
+                    .targets
+                    .push((path.clone(), row.try_get("target_branch")?));
+            }
+        }
+    }
+
+    // Since attempts became workspaces, the executor is on their sessions
+    if tables.contains("sessions") && columns(pool, "sessions").await?.contains("workspace_id") {
+        let rows = sqlx::query(
+            "SELECT workspace_id, executor FROM sessions
+             WHERE executor IS NOT NULL ORDER BY created_at",
+        )
+        .fetch_all(pool)
+        .await?;
+        for row in rows {
+            let workspace_id: Uuid = row.try_get("workspace_id")?;
+            if let Some(attempt) = attempts
+                .iter_mut()
+                .find(|a| a.id == workspace_id && a.executor.is_none())
+            {
+                attempt.executor = row.try_get("executor")?;
+            }
+        }
+    }
+    source.attempts = attempts;
+
+    if tables.contains("merges") {
+        let merge_columns = columns(pool, "merges").await?;
+        let rows = sqlx::query(&format!(
+            "SELECT id, {} AS attempt_id, {} AS repo_id, merge_type, merge_commit,
+                    target_branch_name, pr_number, pr_url, pr_status, pr_merged_at,
+                    pr_merge_commit_sha, created_at
+             FROM merges ORDER BY created_at",
+            column(&merge_columns, &["workspace_id", "task_attempt_id"]),
+            column(&merge_columns, &["repo_id"]),
+        ))
+        .fetch_all(pool)
+        .await?;
+        for row in rows {
+            let attempt_id: Uuid = row.try_get("attempt_id")?;
+            let repo_id: Option<Uuid> = row.try_get("repo_id")?;
+            let repo_path = match repo_id {
+                Some(repo_id) => repo_paths.get(&repo_id).map(|(path, _)| path.clone()),
+                None => source
+                    .attempts
+                    .iter()
+                    .find(|a| a.id == attempt_id)
+                    .and_then(|attempt| sole_repo(attempt.task_id)),
+            };
+            source.merges.push(Merge {
+                id: row.try_get("id")?,
+                attempt_id,
+                repo_path,
+                merge_type: row.try_get("merge_type")?,
+                merge_commit: row.try_get("merge_commit")?,
+                target_branch_name: row.try_get("target_branch_name")?,
+                pr_number: row.try_get("pr_number")?,
+                pr_url: row.try_get("pr_url")?,
+                pr_status: row.try_get("pr_status")?,
+                pr_merged_at: row.try_get("pr_merged_at")?,
+                pr_merge_commit_sha: row.try_get("pr_merge_commit_sha")?,
+                created_at: row.try_get("created_at")?,
+            });
+        }
+    } else {
+        source.skipped.push(
+            "The source predates the merges table; its merge history is not imported".to_string(),
+        );
+    }
+
+    Ok(source)
+}
+
+async fn write(
+    conn: &mut SqliteConnection,
+    source: &Source,
+    report: &mut ImportReport,
+) -> Result<(), sqlx::Error> {
+    let mut repo_ids: HashMap<&str, Uuid> = HashMap::new();
+    for project in &source.projects {
+        let project_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO projects (id, name, created_at, updated_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(project_id)
+        .bind(&project.name)
+        .bind(&project.created_at)
+        .bind(&project.updated_at)
+        .execute(&mut *conn)
+        .await?;
+        report.ids.projects.insert(project.id, project_id);
+
+        for repo in &project.repos {
+            let repo_id = match repo_ids.get(repo.path.as_str()) {
+                Some(id) => *id,
+                None => {
+                    let id = find_or_create_repo(conn, repo, report).await?;
+                    repo_ids.insert(&repo.path, id);
+                    id
+                }
+            };
+            sqlx::query(
+                "INSERT INTO project_repos
+                     (id, project_id, repo_id, setup_script, cleanup_script, copy_files,
+                      parallel_setup_script)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(project_id)
+            .bind(repo_id)
+            .bind(&repo.setup_script)
+            .bind(&repo.cleanup_script)
+            .bind(&repo.copy_files)
+            .bind(repo.parallel_setup_script)
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    for task in &source.tasks {
+        let Some(project_id) = report.ids.projects.get(&task.project_id).copied() else {
+            report
+                .skipped
+                .push(format!("Task {} belongs to no project", task.id));
+            continue;
+        };
+        let status = match task.status.parse::<TaskStatus>() {
+            Ok(_) => task.status.as_str(),
+            Err(_) => {
+                report.skipped.push(format!(
+                    "Task {} has unknown status '{}'; imported as todo",
+                    task.id, task.status
+                ));
+                "todo"
+            }
+        };
+        let task_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO tasks (id, project_id, title, description, status, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(task_id)
+        .bind(project_id)
+        .bind(&task.title)
+        .bind(&task.description)
+        .bind(status)
+        .bind(&task.created_at)
+        .bind(&task.updated_at)
+        .execute(&mut *conn)
+        .await?;
+        report.ids.tasks.insert(task.id, task_id);
+    }
+
+    for attempt in &source.attempts {
+        let Some(task_id) = report.ids.tasks.get(&attempt.task_id).copied() else {
+            report.skipped.push(format!(
+                "Attempt {} belongs to no imported task",
+                attempt.id
+            ));
+            continue;
+        };
+        let Some(branch) = &attempt.branch else {
+            report
+                .skipped
+                .push(format!("Attempt {} never got a branch", attempt.id));
+            continue;
+        };
+        let workspace_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO workspaces (id, task_id, branch, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(workspace_id)
+        .bind(task_id)
+        .bind(branch)
+        .bind(&attempt.created_at)
+        .bind(&attempt.updated_at)
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query(
+            "INSERT INTO sessions (id, workspace_id, executor, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(workspace_id)
+        .bind(&attempt.executor)
+        .bind(&attempt.created_at)
+        .bind(&attempt.updated_at)
+        .execute(&mut *conn)
+        .await?;
+        for (path, target_branch) in &attempt.targets {
+            let Some(repo_id) = repo_ids.get(path.as_str()) else {
+                continue;
+            };
+            sqlx::query(
+                "INSERT INTO workspace_repos (id, workspace_id, repo_id, target_branch)
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(workspace_id)
+            .bind(repo_id)
+            .bind(target_branch)
+            .execute(&mut *conn)
+            .await?;
+        }
+        report.ids.workspaces.insert(attempt.id, workspace_id);
+    }
+
+    // Subtasks point at the attempt they were made from, which only exists now
+    for task in &source.tasks {
+        if let Some(parent) = task.parent_attempt_id
+            && let Some(task_id) = report.ids.tasks.get(&task.id)
+            && let Some(workspace_id) = report.ids.workspaces.get(&parent)
+        {
+            sqlx::query("UPDATE tasks SET parent_workspace_id = $1 WHERE id = $2")
+                .bind(workspace_id)
+                .bind(task_id)
+                .execute(&mut *conn)
+                .await?;
+        }
+    }
+
+    for merge in &source.merges {
+        let Some(workspace_id) = report.ids.workspaces.get(&merge.attempt_id).copied() else {
+            report
+                .skipped
+                .push(format!("Merge {} belongs to no imported attempt", merge.id));
+            continue;
+        };
+        let Some(repo_id) = merge
+            .repo_path
+            .as_deref()
+            .and_then(|path| repo_ids.get(path))
+        else {
+            report
+                .skipped
+                .push(format!("Merge {} has no known repository", merge.id));
+            continue;
+        };
+        let Some(target_branch_name) = &merge.target_branch_name else {
+            report
+                .skipped
+                .push(format!("Merge {} has no target branch", merge.id));
+            continue;
+        };
+        let merge_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO merges
+                 (id, workspace_id, repo_id, merge_type, merge_commit, target_branch_name,
+                  pr_number, pr_url, pr_status, pr_merged_at, pr_merge_commit_sha, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        )
+        .bind(merge_id)
+        .bind(workspace_id)
+        .bind(repo_id)
+        .bind(&merge.merge_type)
+        .bind(&merge.merge_commit)
+        .bind(target_branch_name)
+        .bind(merge.pr_number)
+        .bind(&merge.pr_url)
+        .bind(&merge.pr_status)
+        .bind(&merge.pr_merged_at)
+        .bind(&merge.pr_merge_commit_sha)
+        .bind(&merge.created_at)
+        .execute(&mut *conn)
+        .await?;
+        report.ids.merges.insert(merge.id, merge_id);
+    }
+
+    Ok(())
+}
+
+async fn find_or_create_repo(
+    conn: &mut SqliteConnection,
+    repo: &ProjectRepo,
+    report: &mut ImportReport,
+) -> Result<Uuid, sqlx::Error> {
+    let existing: Option<Uuid> = sqlx::query_scalar("SELECT id FROM repos WHERE path = $1")
+        .bind(&repo.path)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if let Some(id) = existing {
+        report.repos_reused += 1;
+        return Ok(id);
+    }
+
+    let id = Uuid::new_v4();
+    let name = Path::new(&repo.path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| id.to_string());
+    let display_name = repo
+        .display_name
+        .clone()
+        .filter(|display_name| display_name != "__NEEDS_BACKFILL__")
+        .unwrap_or_else(|| name.clone());
+    sqlx::query("INSERT INTO repos (id, path, name, display_name) VALUES ($1, $2, $3, $4)")
+        .bind(id)
+        .bind(&repo.path)
+        .bind(name)
+        .bind(display_name)
+        .execute(&mut *conn)
+        .await?;
+    report.repos_created += 1;
+    Ok(id)
+}
+
+/// Read the imported rows back and compare them with the source
+async fn verify(
+    conn: &mut SqliteConnection,
+    source: &Source,
+    report: &mut ImportReport,
+) -> Result<(), sqlx::Error> {
+    for task in &source.tasks {
+        let Some(task_id) = report.ids.tasks.get(&task.id) else {
+            continue;
+        };
+        let row: Option<(Uuid, String)> =
+            sqlx::query_as("SELECT project_id, title FROM tasks WHERE id = $1")
+                .bind(task_id)
+                .fetch_optional(&mut *conn)
+                .await?;
+        let expected_project = report.ids.projects.get(&task.project_id);
+        match row {
+            Some((project_id, title))
+                if Some(&project_id) == expected_project && title == task.title => {}
+            _ => report
+                .mismatches
+                .push(format!("Task {} did not import as {}", task.id, task_id)),
+        }
+    }
+
+    for attempt in &source.attempts {
+        let Some(workspace_id) = report.ids.workspaces.get(&attempt.id) else {
+            continue;
+        };
+        let row: Option<(Uuid, String)> =
+            sqlx::query_as("SELECT task_id, branch FROM workspaces WHERE id = $1")
+                .bind(workspace_id)
+                .fetch_optional(&mut *conn)
+                .await?;
+        let expected_task = report.ids.tasks.get(&attempt.task_id);
+        match row {
+            Some((task_id, branch))
+                if Some(&task_id) == expected_task && attempt.branch.as_ref() == Some(&branch) => {}
+            _ => report.mismatches.push(format!(
+                "Attempt {} did not import as workspace {}",
+                attempt.id, workspace_id
+            )),
+        }
+    }
+
+    for merge in &source.merges {
+        let Some(merge_id) = report.ids.merges.get(&merge.id) else {
+            continue;
+        };
+        let row: Option<(Uuid, String)> =
+            sqlx::query_as("SELECT workspace_id, merge_type FROM merges WHERE id = $1")
+                .bind(merge_id)
+                .fetch_optional(&mut *conn)
+                .await?;
+        let expected_workspace = report.ids.workspaces.get(&merge.attempt_id);
+        match row {
+            Some((workspace_id, merge_type))
+                if Some(&workspace_id) == expected_workspace && merge_type == merge.merge_type => {}
+            _ => report
+                .mismatches
+                .push(format!("Merge {} did not import as {}", merge.id, merge_id)),
+        }
+    }
+
+    Ok(())
+}
@@ -1,31 +1,89 @@
-use std::{str::FromStr, sync::Arc};
+use std::{str::FromStr, sync::Arc, time::Duration};
 
+use serde::{Deserialize, Serialize};
 use sqlx::{
-    Error, Pool, Sqlite, SqlitePool,
-    sqlite::{SqliteConnectOptions, SqliteConnection, SqlitePoolOptions},
+    Error, Pool, Sqlite,
+    sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions},
 };
-use utils::assets::asset_dir;
+use ts_rs::TS;
+use utils::assets::database_path;
 
+pub mod import;
+pub mod migrate;
 pub mod models;
+pub mod seed;
+
+/// SQLite connection settings. Concurrent execution logging writes a lot, so the defaults use
+/// write-ahead logging and wait for locks instead of failing with `database is locked`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct DatabaseTuning {
+    /// Write-ahead logging, so reads do not block on writes and the other way around
+    pub wal: bool,
+    /// How long a connection waits for a lock before giving up
+    pub busy_timeout_ms: u64,
+    /// Bytes of the database file to memory-map for reads; 0 turns it off
+    pub mmap_size: u64,
+    /// Connections of the main pool
+    pub max_connections: u32,
+    /// Connections of a separate read-only pool for heavy queries like reports and board
+    /// snapshots; 0 runs them on the main pool. Only used with write-ahead logging.
+    pub read_pool_connections: u32,
+}
+
+impl Default for DatabaseTuning {
+    fn default() -> Self {
+        Self {
+            wal: true,
+            busy_timeout_ms: 5_000,
+            mmap_size: 0,
+            max_connections: 10,
+            read_pool_connections: 0,
+        }
+    }
+}
+
+impl DatabaseTuning {
+    fn connect_options(&self) -> Result<SqliteConnectOptions, Error> {
+        let database_url = format!("sqlite://{}", database_path().to_string_lossy());
+        let journal_mode = if self.wal {
+            SqliteJournalMode::Wal
+        } else {
+            SqliteJournalMode::Delete
+        };
+        let mut options = SqliteConnectOptions::from_str(&database_url)?
+            .create_if_missing(true)
+            .journal_mode(journal_mode)
+            .busy_timeout(Duration::from_millis(self.busy_timeout_ms));
+        if self.mmap_size > 0 {
+            options = options.pragma("mmap_size", self.mmap_size.to_string());
+        }
+        Ok(options)
+    }
+}
 
 #[derive(Clone)]
 pub struct DBService {
     pub pool: Pool<Sqlite>,
+    /// Pool for heavy read-only queries; the main pool unless a separate read pool is configured
+    pub read_pool: Pool<Sqlite>,
 }
 
 impl DBService {
-    pub async fn new() -> Result<DBService, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(true);
-        let pool = SqlitePool::connect_with(options).await?;
-        sqlx::migrate!("./migrations").run(&pool).await?;
-        Ok(DBService { pool })
+    pub async fn new(tuning: &DatabaseTuning) -> Result<DBService, Error> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(tuning.max_connections.max(1))
+            .connect_with(tuning.connect_options()?)
+            .await?;
+        Self::migrate(&pool).await?;
+        let read_pool = Self::create_read_pool(tuning, &pool).await?;
+        Ok(DBService { pool, read_pool })
     }
 
-    pub async fn new_with_after_connect<F>(after_connect: F) -> Result<DBService, Error>
+    pub async fn new_with_after_connect<F>(
+        tuning: &DatabaseTuning,
+        after_connect: F,
+    ) -> Result<DBService, Error>
     where
         F: for<'a> Fn(
                 &'a mut SqliteConnection,
@@ -35,11 +93,15 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let pool = Self::create_pool(Some(Arc::new(after_connect))).await?;
-        Ok(DBService { pool })
+        let pool = Self::create_pool(tuning, Some(Arc::new(after_connect))).await?;
+        let read_pool = Self::create_read_pool(tuning, &pool).await?;
+        Ok(DBService { pool, read_pool })
     }
 
-    async fn create_pool<F>(after_connect: Option<Arc<F>>) -> Result<Pool<Sqlite>, Error>
+    async fn create_pool<F>(
+        tuning: &DatabaseTuning,
+        after_connect: Option<Arc<F>>,
+    ) -> Result<Pool<Sqlite>, Error>
     where
         F: for<'a> Fn(
                 &'a mut SqliteConnection,
@@ -49,14 +111,11 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(true);
+        let options = tuning.connect_options()?;
+        let pool_options = SqlitePoolOptions::new().max_connections(tuning.max_connections.max(1));
 
         let pool = if let Some(hook) = after_connect {
-            SqlitePoolOptions::new()
+            pool_options
                 .after_connect(move |conn, _meta| {
                     let hook = hook.clone();
                     Box::pin(async move {
@@ -67,10 +126,48 @@ impl DBService {
                 .connect_with(options)
                 .await?
         } else {
-            SqlitePool::connect_with(options).await?
+            pool_options.connect_with(options).await?
         };
 
-        sqlx::migrate!("./migrations").run(&pool).await?;
+        Self::migrate(&pool).await?;
         Ok(pool)
     }
+
+    /// Apply pending migrations, backing up an existing database first
+    async fn migrate(pool: &Pool<Sqlite>) -> Result<(), Error> {
+        match migrate::run_pending(pool, true).await {
+            Ok(Some(backup)) => {
+                tracing::info!(
+                    "Backed up the database to {} before migrating it",
+                    backup.display()
+                );
+                Ok(())
+            }
+            Ok(None) => Ok(()),
+            Err(e) => {
+                tracing::error!("Database migration failed: {}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Separate read-only pool when configured. Without write-ahead logging readers would block
+    /// the writers, so the main pool is shared then.
+    async fn create_read_pool(
+        tuning: &DatabaseTuning,
+        pool: &Pool<Sqlite>,
+    ) -> Result<Pool<Sqlite>, Error> {
+        if tuning.read_pool_connections == 0 || !tuning.wal {
+            return Ok(pool.clone());
+        }
+        SqlitePoolOptions::new()
+            .max_connections(tuning.read_pool_connections)
+            .connect_with(
+                tuning
+                    .connect_options()?
+                    .create_if_missing(false)
+                    .read_only(true),
+            )
+            .await
+    }
 }
@@ -0,0 +1,282 @@
+//! Inspection, backup and rollback around the schema migrations.
+//!
+//! Migrations only go forward, so stepping back restores the backup taken before the last
+//! migration ran. Those backups are copies made with `VACUUM INTO` whenever pending migrations are
+//! applied to an existing database, at startup as well as from the `migrate` command. The queries
+//! here read `_sqlx_migrations`, which only exists once a migration ran, so they are not checked
+//! at compile time.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use sqlx::{
+    SqlitePool,
+    migrate::{MigrateError, Migrator},
+    sqlite::SqlitePoolOptions,
+};
+use thiserror::Error;
+use utils::assets::{asset_dir, database_path};
+
+use crate::DatabaseTuning;
+
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Backups kept in the backup directory; older ones are removed after a new one is taken
+const KEPT_BACKUPS: usize = 10;
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Migrate(#[from] MigrateError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(
+        "The database was migrated by a newer version of the app (migration {0} is unknown to this one); run the newer version or restore a backup"
+    )]
+    UnknownMigration(i64),
+    #[error("Migration {0} failed part-way; restore a backup taken before it")]
+    Failed(i64),
+    #[error("No migration has been applied, so there is nothing to undo")]
+    NothingToUndo,
+    #[error("No backup taken before migration {version} was found in {}", dir.display())]
+    NoBackup { version: i64, dir: PathBuf },
+}
+
+impl From<MigrationError> for sqlx::Error {
+    fn from(e: MigrationError) -> Self {
+        match e {
+            MigrationError::Database(e) => e,
+            MigrationError::Migrate(e) => sqlx::Error::Migrate(Box::new(e)),
+            MigrationError::Io(e) => sqlx::Error::Io(e),
+            other => sqlx::Error::Configuration(other.to_string().into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MigrationStatus {
+    /// Applied migrations, oldest first
+    pub applied: Vec<MigrationInfo>,
+    /// Migrations of this version not yet applied, oldest first
+    pub pending: Vec<MigrationInfo>,
+    /// Applied migrations this version does not know, left by a newer version
+    pub unknown: Vec<i64>,
+    /// A migration that failed part-way
+    pub failed: Option<i64>,
+}
+
+impl MigrationStatus {
+    /// Latest applied migration; 0 for a database without any
+    pub fn current_version(&self) -> i64 {
+        self.applied.last().map_or(0, |m| m.version)
+    }
+
+    /// Refuse to touch a database this version cannot handle
+    pub fn check(&self) -> Result<(), MigrationError> {
+        if let Some(version) = self.failed {
+            return Err(MigrationError::Failed(version));
+        }
+        if let Some(&version) = self.unknown.first() {
+            return Err(MigrationError::UnknownMigration(version));
+        }
+        Ok(())
+    }
+}
+
+/// Open the database without migrating it
+pub async fn connect(tuning: &DatabaseTuning) -> Result<SqlitePool, MigrationError> {
+    Ok(SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(tuning.connect_options()?)
+        .await?)
+}
+
+pub async fn status(pool: &SqlitePool) -> Result<MigrationStatus, MigrationError> {
+    let has_table: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(pool)
+    .await?;
+    let rows: Vec<(i64, String, bool)> = if has_table {
+        sqlx::query_as(
+            "SELECT version, description, success FROM _sqlx_migrations ORDER BY version",
+        )
+        .fetch_all(pool)
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    let known: Vec<MigrationInfo> = MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| MigrationInfo {
+            version: m.version,
+            description: m.description.to_string(),
+        })
+        .collect();
+
+    let mut status = MigrationStatus::default();
+    for (version, description, success) in rows {
+        if !success {
+            status.failed = Some(version);
+        } else if known.iter().any(|m| m.version == version) {
+            status.applied.push(MigrationInfo {
+                version,
+                description,
+            });
+        } else {
+            status.unknown.push(version);
+        }
+    }
+    status.pending = known
+        .into_iter()
+        .filter(|m| {
+            !status.applied.iter().any(|a| a.version == m.version)
+                && status.failed != Some(m.version)
+        })
+        .collect();
+    Ok(status)
+}
+
+pub fn backup_dir() -> PathBuf {
+    asset_dir().join("backups")
+}
+
+/// Copy the database into the backup directory, named after its latest applied migration
+pub async fn backup(
+    pool: &SqlitePool,
+    status: &MigrationStatus,
+) -> Result<PathBuf, MigrationError> {
+    let dir = backup_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "db-{}-{}.sqlite",
+        Utc::now().format("%Y%m%d%H%M%S%3f"),
+        status.current_version()
+    ));
+    sqlx::query("VACUUM INTO $1")
+        .bind(path.to_string_lossy().into_owned())
+        .execute(pool)
+        .await?;
+    prune_backups(&dir)?;
+    Ok(path)
+}
+
+/// Migration a backup file was taken at, from its name
+fn backup_version(path: &Path) -> Option<i64> {
+    let name = path.file_name()?.to_str()?;
+    let stem = name.strip_prefix("db-")?.strip_suffix(".sqlite")?;
+    stem.rsplit_once('-')?.1.parse().ok()
+}
+
+/// Backups in the directory, oldest first
+fn list_backups(dir: &Path) -> Result<Vec<PathBuf>, MigrationError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| backup_version(path).is_some())
+        .collect();
+    // The timestamp leads the name, so names sort by age
+    backups.sort();
+    Ok(backups)
+}
+
+fn prune_backups(dir: &Path) -> Result<(), MigrationError> {
+    let backups = list_backups(dir)?;
+    let excess = backups.len().saturating_sub(KEPT_BACKUPS);
+    for path in &backups[..excess] {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Apply pending migrations, backing up an existing database first when `backup_first` is set.
+/// Returns the backup taken, if any.
+pub async fn run_pending(
+    pool: &SqlitePool,
+    backup_first: bool,
+) -> Result<Option<PathBuf>, MigrationError> {
+    let status = status(pool).await?;
+    status.check()?;
+    if status.pending.is_empty() {
+        return Ok(None);
+    }
+    let backup = if backup_first && !status.applied.is_empty() {
+        Some(backup(pool, &status).await?)
+    } else {
+        None
+    };
+    MIGRATOR.run(pool).await?;
+    Ok(backup)
+}
+
+/// What undoing the latest migration restores
+#[derive(Debug, Clone)]
+pub struct Downgrade {
+    /// The latest applied migration
+    pub undone: i64,
+    /// Newest backup taken before it ran
+    pub backup: PathBuf,
+    /// Latest migration applied in the backup; older than `undone` by more than one step when
+    /// several migrations ran together
+    pub restores_to: i64,
+}
+
+pub fn downgrade_target(status: &MigrationStatus) -> Result<Downgrade, MigrationError> {
+    let Some(last) = status.applied.last() else {
+        return Err(MigrationError::NothingToUndo);
+    };
+    let dir = backup_dir();
+    list_backups(&dir)?
+        .into_iter()
+        .rev()
+        .find_map(|path| {
+            let version = backup_version(&path)?;
+            (version < last.version).then_some(Downgrade {
+                undone: last.version,
+                backup: path,
+                restores_to: version,
+            })
+        })
+        .ok_or(MigrationError::NoBackup {
+            version: last.version,
+            dir,
+        })
+}
+
+/// Replace the database with a backup. The current database is backed up first, and that copy
+/// is returned. Nothing else may have the database open.
+pub async fn restore(
+    pool: SqlitePool,
+    status: &MigrationStatus,
+    from: &Path,
+) -> Result<PathBuf, MigrationError> {
+    let current = backup(&pool, status).await?;
+    pool.close().await;
+
+    let database = database_path();
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = database.clone().into_os_string();
+        sidecar.push(suffix);
+        match std::fs::remove_file(&sidecar) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    std::fs::copy(from, &database)?;
+    Ok(current)
+}
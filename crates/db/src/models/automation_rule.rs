@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+/// What an automation rule does to the matched task
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    Comment {
+        body: String,
+    },
+    SetStatus {
+        status: TaskStatus,
+    },
+    /// Start a new attempt; uses the configured executor profile when not set
+    StartTask {
+        executor_profile_id: Option<ExecutorProfileId>,
+    },
+    /// Mark the task's open pull requests ready for review
+    MarkPrReady,
+    /// Request reviews on the task's open pull requests, by provider username
+    RequestReviewers {
+        reviewers: Vec<String>,
+    },
+    /// Send a desktop notification about the task
+    Notify {
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AutomationRule {
+    pub id: Uuid,
+    /// Limit the rule to one project; `None` applies it to every project
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    /// Condition expression, e.g. `event == "task.updated" and task.status == "inreview"`
+    pub condition: String,
+    #[ts(type = "RuleAction")]
+    pub action: sqlx::types::Json<RuleAction>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateAutomationRule {
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub condition: String,
+    pub action: RuleAction,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateAutomationRule {
+    pub name: Option<String>,
+    pub condition: Option<String>,
+    pub action: Option<RuleAction>,
+    pub enabled: Option<bool>,
+}
+
+impl AutomationRule {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AutomationRule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, condition, action as "action!: sqlx::types::Json<RuleAction>", enabled as "enabled!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM automation_rules
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Rules of the projects of a tenant, and the rules that apply to every project
+    pub async fn find_for_tenant(
+        pool: &SqlitePool,
+        tenant_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AutomationRule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, condition, action as "action!: sqlx::types::Json<RuleAction>", enabled as "enabled!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM automation_rules
+               WHERE project_id IS NULL
+                  OR project_id IN (SELECT id FROM projects WHERE tenant_id = $1)
+               ORDER BY created_at ASC"#,
+            tenant_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_enabled(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AutomationRule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, condition, action as "action!: sqlx::types::Json<RuleAction>", enabled as "enabled!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM automation_rules
+               WHERE enabled = 1
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AutomationRule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, condition, action as "action!: sqlx::types::Json<RuleAction>", enabled as "enabled!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM automation_rules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateAutomationRule,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let action = sqlx::types::Json(&data.action);
+        let enabled = data.enabled.unwrap_or(true);
+        sqlx::query_as!(
+            AutomationRule,
+            r#"INSERT INTO automation_rules (id, project_id, name, condition, action, enabled)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", name, condition, action as "action!: sqlx::types::Json<RuleAction>", enabled as "enabled!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.condition,
+            action,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateAutomationRule,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let condition = data.condition.as_ref().unwrap_or(&existing.condition);
+        let action = sqlx::types::Json(data.action.as_ref().unwrap_or(&existing.action.0));
+        let enabled = data.enabled.unwrap_or(existing.enabled);
+
+        sqlx::query_as!(
+            AutomationRule,
+            r#"UPDATE automation_rules
+               SET name = $2, condition = $3, action = $4, enabled = $5, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", name, condition, action as "action!: sqlx::types::Json<RuleAction>", enabled as "enabled!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            condition,
+            action,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM automation_rules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::automation_rule::RuleAction;
+
+/// A named sequence of rule actions applied to tasks on demand, e.g. "start review round"
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AutomationTransition {
+    pub id: Uuid,
+    /// Limit the transition to tasks of one project; `None` allows it in every project
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    /// Actions run in order on each task
+    #[ts(type = "Array<RuleAction>")]
+    pub steps: sqlx::types::Json<Vec<RuleAction>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateAutomationTransition {
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub steps: Vec<RuleAction>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateAutomationTransition {
+    pub name: Option<String>,
+    pub steps: Option<Vec<RuleAction>>,
+}
+
+impl AutomationTransition {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AutomationTransition,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, steps as "steps!: sqlx::types::Json<Vec<RuleAction>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM automation_transitions
+               ORDER BY name ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AutomationTransition,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, steps as "steps!: sqlx::types::Json<Vec<RuleAction>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM automation_transitions
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateAutomationTransition,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let steps = sqlx::types::Json(&data.steps);
+        sqlx::query_as!(
+            AutomationTransition,
+            r#"INSERT INTO automation_transitions (id, project_id, name, steps)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", name, steps as "steps!: sqlx::types::Json<Vec<RuleAction>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            steps
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateAutomationTransition,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let steps = sqlx::types::Json(data.steps.as_ref().unwrap_or(&existing.steps.0));
+
+        sqlx::query_as!(
+            AutomationTransition,
+            r#"UPDATE automation_transitions
+               SET name = $2, steps = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", name, steps as "steps!: sqlx::types::Json<Vec<RuleAction>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            steps
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM automation_transitions WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{merge::MergeStatus, task::TaskStatus};
+
+/// Columns of the board, in display order
+const COLUMNS: [TaskStatus; 5] = [
+    TaskStatus::Todo,
+    TaskStatus::InProgress,
+    TaskStatus::InReview,
+    TaskStatus::Done,
+    TaskStatus::Cancelled,
+];
+
+/// A task as the board renders it: key metadata and status badges, without the description
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BoardCard {
+    pub id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+    pub parent_workspace_id: Option<Uuid>,
+    pub shared_task_id: Option<Uuid>,
+    pub due_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub workspace_count: i64,
+    pub has_in_progress_attempt: bool,
+    pub last_attempt_failed: bool,
+    /// Executor of the most recent session
+    pub executor: Option<String>,
+    /// Most recent pull request of any workspace of the task
+    pub pr_status: Option<MergeStatus>,
+    pub pr_number: Option<i64>,
+    pub pr_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BoardColumn {
+    pub status: TaskStatus,
+    pub tasks: Vec<BoardCard>,
+}
+
+/// Everything the board needs for its first render
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BoardSnapshot {
+    pub project_id: Uuid,
+    pub columns: Vec<BoardColumn>,
+}
+
+impl BoardSnapshot {
+    /// Load the board of a project in one statement. The badges are computed once per project with
+    /// window functions instead of per task, and SQLite aggregates the cards into a single JSON
+    /// array, so the cost stays flat as the number of tasks grows.
+    pub async fn for_project(pool: &SqlitePool, project_id: Uuid) -> Result<Self, sqlx::Error> {
+        let cards_json = sqlx::query_scalar!(
+            r#"WITH project_workspaces AS (
+    SELECT w.id, w.task_id
+      FROM workspaces w
+      JOIN tasks t ON t.id = w.task_id
+     WHERE t.project_id = $1
+),
+runs AS (
+    SELECT pw.task_id,
+           ep.status,
+           ROW_NUMBER() OVER (PARTITION BY pw.task_id ORDER BY ep.created_at DESC) AS rn
+      FROM project_workspaces pw
+      JOIN sessions s ON s.workspace_id = pw.id
+      JOIN execution_processes ep ON ep.session_id = s.id
+     WHERE ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+),
+run_badges AS (
+    SELECT task_id,
+           MAX(status = 'running')                          AS in_progress,
+           MAX(rn = 1 AND status IN ('failed','killed'))   AS last_failed
+      FROM runs
+     GROUP BY task_id
+),
+latest_sessions AS (
+    SELECT pw.task_id,
+           s.executor,
+           ROW_NUMBER() OVER (PARTITION BY pw.task_id ORDER BY s.created_at DESC) AS rn
+      FROM project_workspaces pw
+      JOIN sessions s ON s.workspace_id = pw.id
+),
+latest_prs AS (
+    SELECT pw.task_id,
+           m.pr_status,
+           m.pr_number,
+           m.pr_url,
+           ROW_NUMBER() OVER (PARTITION BY pw.task_id ORDER BY m.created_at DESC) AS rn
+      FROM project_workspaces pw
+      JOIN merges m ON m.workspace_id = pw.id
+     WHERE m.merge_type = 'pr'
+),
+workspace_counts AS (
+    SELECT task_id, COUNT(*) AS n
+      FROM project_workspaces
+     GROUP BY task_id
+),
+cards AS (
+    SELECT json_object(
+               'id',                      lower(hex(t.id)),
+               'title',                   t.title,
+               'status',                  t.status,
+               'parent_workspace_id',     NULLIF(lower(hex(t.parent_workspace_id)), ''),
+               'shared_task_id',          NULLIF(lower(hex(t.shared_task_id)), ''),
+               'due_at',                  strftime('%Y-%m-%dT%H:%M:%fZ', t.due_at),
+               'created_at',              strftime('%Y-%m-%dT%H:%M:%fZ', t.created_at),
+               'updated_at',              strftime('%Y-%m-%dT%H:%M:%fZ', t.updated_at),
+               'workspace_count',         COALESCE(wc.n, 0),
+               'has_in_progress_attempt', json(CASE WHEN rb.in_progress THEN 'true' ELSE 'false' END),
+               'last_attempt_failed',     json(CASE WHEN rb.last_failed THEN 'true' ELSE 'false' END),
+               'executor',                ls.executor,
+               'pr_status',               lp.pr_status,
+               'pr_number',               lp.pr_number,
+               'pr_url',                  lp.pr_url
+           ) AS card
+      FROM tasks t
+      LEFT JOIN run_badges rb       ON rb.task_id = t.id
+      LEFT JOIN latest_sessions ls  ON ls.task_id = t.id AND ls.rn = 1
+      LEFT JOIN latest_prs lp       ON lp.task_id = t.id AND lp.rn = 1
+      LEFT JOIN workspace_counts wc ON wc.task_id = t.id
+     WHERE t.project_id = $1
+)
+SELECT json_group_array(json(card)) AS "cards!: String"
+  FROM cards"#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let mut cards: Vec<BoardCard> =
+            serde_json::from_str(&cards_json).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        // Aggregation order is not guaranteed, so restore newest-first here
+        cards.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        let mut columns: Vec<BoardColumn> = COLUMNS
+            .into_iter()
+            .map(|status| BoardColumn {
+                status,
+                tasks: Vec::new(),
+            })
+            .collect();
+        for card in cards {
+            if let Some(column) = columns
+                .iter_mut()
+                .find(|column| column.status == card.status)
+            {
+                column.tasks.push(card);
+            }
+        }
+
+        Ok(Self {
+            project_id,
+            columns,
+        })
+    }
+}
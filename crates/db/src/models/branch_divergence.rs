@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How far a workspace branch has moved from its target branch and from its remote tracking
+/// branch, as of the last fetch of the repository
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct BranchDivergence {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub commits_ahead: i64,
+    /// Commits on the target branch that the workspace branch does not have yet
+    pub commits_behind: i64,
+    /// None when the branch has not been pushed
+    pub remote_commits_ahead: Option<i64>,
+    pub remote_commits_behind: Option<i64>,
+    pub computed_at: DateTime<Utc>,
+}
+
+impl BranchDivergence {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            BranchDivergence,
+            r#"SELECT workspace_id as "workspace_id!: Uuid", repo_id as "repo_id!: Uuid", target_branch, commits_ahead as "commits_ahead!: i64", commits_behind as "commits_behind!: i64", remote_commits_ahead, remote_commits_behind, computed_at as "computed_at!: DateTime<Utc>"
+               FROM branch_divergences
+               WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(pool: &SqlitePool, divergence: &Self) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO branch_divergences (workspace_id, repo_id, target_branch, commits_ahead, commits_behind, remote_commits_ahead, remote_commits_behind, computed_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               ON CONFLICT(workspace_id, repo_id) DO UPDATE SET
+                   target_branch = excluded.target_branch,
+                   commits_ahead = excluded.commits_ahead,
+                   commits_behind = excluded.commits_behind,
+                   remote_commits_ahead = excluded.remote_commits_ahead,
+                   remote_commits_behind = excluded.remote_commits_behind,
+                   computed_at = excluded.computed_at"#,
+            divergence.workspace_id,
+            divergence.repo_id,
+            divergence.target_branch,
+            divergence.commits_ahead,
+            divergence.commits_behind,
+            divergence.remote_commits_ahead,
+            divergence.remote_commits_behind,
+            divergence.computed_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+/// What happens to a task once it stayed in its column longer than the column's SLA
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, Default)]
+#[sqlx(type_name = "sla_action", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SlaAction {
+    /// Comment on the task that it stalled
+    #[default]
+    Label,
+    /// Comment on the task and send a notification
+    Notify,
+    /// Comment on the task and ask the agent of its latest workspace to summarize its blockers
+    Nudge,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ColumnSla {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub status: TaskStatus,
+    /// Longest a task should stay in the column
+    pub max_hours: i64,
+    pub action: SlaAction,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetColumnSla {
+    pub status: TaskStatus,
+    pub max_hours: i64,
+    pub action: Option<SlaAction>,
+}
+
+/// A task escalated for staying in a column too long
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct SlaEscalation {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub status: TaskStatus,
+    /// When the task entered the column it stalled in
+    pub entered_at: DateTime<Utc>,
+    pub action: SlaAction,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ColumnSla {
+    /// SLAs of a project in board column order
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ColumnSla,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", status as "status!: TaskStatus", max_hours, action as "action!: SlaAction", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM column_slas
+               WHERE project_id = $1
+               ORDER BY CASE status WHEN 'todo' THEN 0 WHEN 'inprogress' THEN 1 WHEN 'inreview' THEN 2 WHEN 'done' THEN 3 ELSE 4 END"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Projects that have at least one SLA
+    pub async fn find_project_ids(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(r#"SELECT DISTINCT project_id as "project_id!: Uuid" FROM column_slas"#)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Set the SLA of one column of a project, replacing the one it had
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &SetColumnSla,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let action = data.action.unwrap_or_default();
+        sqlx::query_as!(
+            ColumnSla,
+            r#"INSERT INTO column_slas (id, project_id, status, max_hours, action)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(project_id, status) DO UPDATE SET
+                   max_hours = excluded.max_hours,
+                   action = excluded.action,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", status as "status!: TaskStatus", max_hours, action as "action!: SlaAction", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.status,
+            data.max_hours,
+            action
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        status: TaskStatus,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM column_slas WHERE project_id = $1 AND status = $2",
+            project_id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl SlaEscalation {
+    /// Escalations of the tasks of a project, most recent first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SlaEscalation,
+            r#"SELECT e.id as "id!: Uuid", e.task_id as "task_id!: Uuid", e.status as "status!: TaskStatus", e.entered_at as "entered_at!: DateTime<Utc>", e.action as "action!: SlaAction", e.created_at as "created_at!: DateTime<Utc>"
+               FROM sla_escalations e
+               JOIN tasks t ON t.id = e.task_id
+               WHERE t.project_id = $1
+               ORDER BY e.created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record that a task stalled in a column it entered at `entered_at`. Returns `false` when it
+    /// was already escalated for that stay, so each stay is only escalated once.
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        status: TaskStatus,
+        entered_at: DateTime<Utc>,
+        action: SlaAction,
+    ) -> Result<bool, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO sla_escalations (id, task_id, status, entered_at, action)
+               VALUES ($1, $2, $3, $4, $5)"#,
+            id,
+            task_id,
+            status,
+            entered_at,
+            action
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
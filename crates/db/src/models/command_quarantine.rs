@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct CommandQuarantine {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    pub command_hash: String,
+    pub command: String,
+    /// Quarantined by flaky detection rather than by a user
+    pub automatic: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CommandQuarantine {
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CommandQuarantine,
+            r#"SELECT id as "id!: Uuid", repo_id as "repo_id!: Uuid", command_hash, command, automatic as "automatic!: bool", created_at as "created_at!: DateTime<Utc>"
+               FROM command_quarantines
+               WHERE repo_id = $1
+               ORDER BY created_at DESC"#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Quarantine a command; a no-op if it already is
+    pub async fn create(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        command_hash: &str,
+        command: &str,
+        automatic: bool,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT OR IGNORE INTO command_quarantines (id, repo_id, command_hash, command, automatic)
+               VALUES ($1, $2, $3, $4, $5)"#,
+            id,
+            repo_id,
+            command_hash,
+            command,
+            automatic
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        command_hash: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM command_quarantines WHERE repo_id = $1 AND command_hash = $2",
+            repo_id,
+            command_hash
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Runs kept per repository when classifying flaky commands
+const HISTORY_LIMIT: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "command_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CommandKind {
+    SetupScript,
+    CleanupScript,
+    Lint,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct CommandRun {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    pub workspace_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    pub kind: CommandKind,
+    pub command_hash: String,
+    pub command: String,
+    /// Worktree HEAD when the command ran; a pass after a failure at the same commit marks the
+    /// failure as flaky
+    pub head_commit: Option<String>,
+    pub succeeded: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateCommandRun {
+    pub repo_id: Uuid,
+    pub workspace_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    pub kind: CommandKind,
+    pub command_hash: String,
+    pub command: String,
+    pub head_commit: Option<String>,
+    pub succeeded: bool,
+}
+
+impl CommandRun {
+    /// Recent runs in a repository, oldest first
+    pub async fn find_recent_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut runs = sqlx::query_as!(
+            CommandRun,
+            r#"SELECT id as "id!: Uuid", repo_id as "repo_id!: Uuid", workspace_id as "workspace_id!: Uuid", execution_process_id as "execution_process_id?: Uuid", kind as "kind!: CommandKind", command_hash, command, head_commit, succeeded as "succeeded!: bool", created_at as "created_at!: DateTime<Utc>"
+               FROM command_runs
+               WHERE repo_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            repo_id,
+            HISTORY_LIMIT
+        )
+        .fetch_all(pool)
+        .await?;
+        runs.reverse();
+        Ok(runs)
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateCommandRun) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            CommandRun,
+            r#"INSERT INTO command_runs (id, repo_id, workspace_id, execution_process_id, kind, command_hash, command, head_commit, succeeded)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid", repo_id as "repo_id!: Uuid", workspace_id as "workspace_id!: Uuid", execution_process_id as "execution_process_id?: Uuid", kind as "kind!: CommandKind", command_hash, command, head_commit, succeeded as "succeeded!: bool", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.repo_id,
+            data.workspace_id,
+            data.execution_process_id,
+            data.kind,
+            data.command_hash,
+            data.command,
+            data.head_commit,
+            data.succeeded
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ContextCompaction {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    /// The turn whose prompt the context was prepended to
+    pub execution_process_id: Uuid,
+    /// Earlier turns reduced to a single line
+    pub turns_summarized: i64,
+    /// Recent turns carried over with their prompt and outcome
+    pub turns_included: i64,
+    pub diff_files: i64,
+    /// Size of the turns and diffs before compaction
+    pub source_chars: i64,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateContextCompaction {
+    pub session_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub turns_summarized: i64,
+    pub turns_included: i64,
+    pub diff_files: i64,
+    pub source_chars: i64,
+    pub content: String,
+}
+
+impl ContextCompaction {
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ContextCompaction,
+            r#"SELECT id as "id!: Uuid", session_id as "session_id!: Uuid", execution_process_id as "execution_process_id!: Uuid", turns_summarized, turns_included, diff_files, source_chars, content, created_at as "created_at!: DateTime<Utc>"
+               FROM context_compactions
+               WHERE session_id = $1
+               ORDER BY created_at DESC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateContextCompaction,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ContextCompaction,
+            r#"INSERT INTO context_compactions (id, session_id, execution_process_id, turns_summarized, turns_included, diff_files, source_chars, content)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id as "id!: Uuid", session_id as "session_id!: Uuid", execution_process_id as "execution_process_id!: Uuid", turns_summarized, turns_included, diff_files, source_chars, content, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.session_id,
+            data.execution_process_id,
+            data.turns_summarized,
+            data.turns_included,
+            data.diff_files,
+            data.source_chars,
+            data.content
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
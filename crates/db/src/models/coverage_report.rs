@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct CoverageReport {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    pub repo_id: Uuid,
+    pub lines_total: i64,
+    pub lines_covered: i64,
+    /// Coverage of the target branch; `None` when no baseline report was available
+    pub base_lines_total: Option<i64>,
+    pub base_lines_covered: Option<i64>,
+    /// Instrumented lines added or changed by the workspace branch
+    pub patch_lines_total: i64,
+    pub patch_lines_covered: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateCoverageReport {
+    pub workspace_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    pub repo_id: Uuid,
+    pub lines_total: i64,
+    pub lines_covered: i64,
+    pub base_lines_total: Option<i64>,
+    pub base_lines_covered: Option<i64>,
+    pub patch_lines_total: i64,
+    pub patch_lines_covered: i64,
+}
+
+impl CoverageReport {
+    /// Percentage of covered lines, `None` when nothing is instrumented
+    pub fn line_rate(&self) -> Option<f64> {
+        rate(self.lines_covered, self.lines_total)
+    }
+
+    pub fn base_line_rate(&self) -> Option<f64> {
+        rate(self.base_lines_covered?, self.base_lines_total?)
+    }
+
+    pub fn patch_line_rate(&self) -> Option<f64> {
+        rate(self.patch_lines_covered, self.patch_lines_total)
+    }
+
+    /// Change in line coverage against the target branch, in percentage points
+    pub fn delta(&self) -> Option<f64> {
+        Some(self.line_rate()? - self.base_line_rate()?)
+    }
+
+    /// The most recent report for each repository in a workspace
+    pub async fn find_latest_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CoverageReport,
+            r#"SELECT c.id as "id!: Uuid", c.workspace_id as "workspace_id!: Uuid", c.execution_process_id as "execution_process_id?: Uuid", c.repo_id as "repo_id!: Uuid", c.lines_total, c.lines_covered, c.base_lines_total, c.base_lines_covered, c.patch_lines_total, c.patch_lines_covered, c.created_at as "created_at!: DateTime<Utc>"
+               FROM coverage_reports c
+               WHERE c.workspace_id = $1
+                 AND c.created_at = (
+                     SELECT MAX(created_at) FROM coverage_reports
+                     WHERE workspace_id = c.workspace_id AND repo_id = c.repo_id
+                 )
+               ORDER BY c.created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateCoverageReport,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            CoverageReport,
+            r#"INSERT INTO coverage_reports (id, workspace_id, execution_process_id, repo_id, lines_total, lines_covered, base_lines_total, base_lines_covered, patch_lines_total, patch_lines_covered)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid", workspace_id as "workspace_id!: Uuid", execution_process_id as "execution_process_id?: Uuid", repo_id as "repo_id!: Uuid", lines_total, lines_covered, base_lines_total, base_lines_covered, patch_lines_total, patch_lines_covered, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.workspace_id,
+            data.execution_process_id,
+            data.repo_id,
+            data.lines_total,
+            data.lines_covered,
+            data.base_lines_total,
+            data.base_lines_covered,
+            data.patch_lines_total,
+            data.patch_lines_covered
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
+
+fn rate(covered: i64, total: i64) -> Option<f64> {
+    (total > 0).then(|| covered as f64 * 100.0 / total as f64)
+}
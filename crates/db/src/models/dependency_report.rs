@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyEcosystem {
+    Cargo,
+    Npm,
+    Go,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingSeverity {
+    /// Blocks pull request creation when the policy asks for it
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DependencyFindingKind {
+    BannedPackage,
+    LicenseNotAllowed {
+        license: String,
+    },
+    /// The registry did not report a license, or could not be reached
+    LicenseUnknown,
+    UnpinnedVersion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct DependencyFinding {
+    pub repo: String,
+    pub manifest: String,
+    pub ecosystem: DependencyEcosystem,
+    pub package: String,
+    pub version: String,
+    pub kind: DependencyFindingKind,
+    pub severity: FindingSeverity,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DependencyReport {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    /// Empty when the changed dependencies passed every check
+    #[ts(type = "Array<DependencyFinding>")]
+    pub findings: sqlx::types::Json<Vec<DependencyFinding>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DependencyReport {
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DependencyReport,
+            r#"SELECT id as "id!: Uuid", workspace_id as "workspace_id!: Uuid", execution_process_id as "execution_process_id?: Uuid", findings as "findings!: sqlx::types::Json<Vec<DependencyFinding>>", created_at as "created_at!: DateTime<Utc>"
+               FROM dependency_reports
+               WHERE execution_process_id = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        execution_process_id: Option<Uuid>,
+        findings: &[DependencyFinding],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let findings = sqlx::types::Json(findings);
+        sqlx::query_as!(
+            DependencyReport,
+            r#"INSERT INTO dependency_reports (id, workspace_id, execution_process_id, findings)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", workspace_id as "workspace_id!: Uuid", execution_process_id as "execution_process_id?: Uuid", findings as "findings!: sqlx::types::Json<Vec<DependencyFinding>>", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            execution_process_id,
+            findings
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
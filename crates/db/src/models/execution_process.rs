@@ -83,6 +83,13 @@ pub struct CreateExecutionProcess {
     pub run_reason: ExecutionProcessRunReason,
 }
 
+/// How many coding agent runs a tenant may have going at once
+#[derive(Debug, Clone, Copy)]
+pub struct ConcurrencyLimit {
+    pub tenant_id: Uuid,
+    pub max_running: i64,
+}
+
 #[derive(Debug, Deserialize, TS)]
 #[allow(dead_code)]
 pub struct UpdateExecutionProcess {
@@ -441,20 +448,37 @@ impl ExecutionProcess {
     /// async task that queries `find_by_rowid` on a different connection.
     /// If we used a transaction, that query would not see the uncommitted row,
     /// causing the WebSocket event to be lost.
+    /// Create a running execution. None when it would take the tenant over `limit`; the running
+    /// coding agents are counted by the insert itself, so runs started at the same time cannot
+    /// all slip under the limit.
     pub async fn create(
         pool: &SqlitePool,
         data: &CreateExecutionProcess,
         process_id: Uuid,
         repo_states: &[CreateExecutionProcessRepoState],
-    ) -> Result<Self, sqlx::Error> {
+        limit: Option<ConcurrencyLimit>,
+    ) -> Result<Option<Self>, sqlx::Error> {
         let now = Utc::now();
         let executor_action_json = sqlx::types::Json(&data.executor_action);
+        let tenant_id = limit.map(|limit| limit.tenant_id);
+        let max_running = limit.map(|limit| limit.max_running);
 
-        sqlx::query!(
+        let result = sqlx::query!(
             r#"INSERT INTO execution_processes (
                     id, session_id, run_reason, executor_action,
                     status, exit_code, started_at, completed_at, created_at, updated_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                )
+               SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+               WHERE $11 IS NULL
+                  OR (SELECT COUNT(*)
+                      FROM execution_processes ep
+                      JOIN sessions s ON s.id = ep.session_id
+                      JOIN workspaces w ON w.id = s.workspace_id
+                      JOIN tasks t ON t.id = w.task_id
+                      JOIN projects p ON p.id = t.project_id
+                      WHERE p.tenant_id = $12
+                        AND ep.status = 'running'
+                        AND ep.run_reason = 'codingagent') < $11"#,
             process_id,
             data.session_id,
             data.run_reason,
@@ -464,16 +488,19 @@ impl ExecutionProcess {
             now,
             None::<DateTime<Utc>>,
             now,
-            now
+            now,
+            max_running,
+            tenant_id
         )
         .execute(pool)
         .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
 
         ExecutionProcessRepoState::create_many(pool, process_id, repo_states).await?;
 
-        Self::find_by_id(pool, process_id)
-            .await?
-            .ok_or(sqlx::Error::RowNotFound)
+        Self::find_by_id(pool, process_id).await
     }
 
     pub async fn was_stopped(pool: &SqlitePool, id: Uuid) -> bool {
@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A file selected in the diff view, optionally narrowed to some of its hunks
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct ScopedFile {
+    pub repo: String,
+    /// Path relative to the repository root
+    pub path: String,
+    /// Selected hunks as unified diff text; empty selects the whole file
+    #[serde(default)]
+    pub hunks: Vec<String>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionScope {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    #[ts(type = "Array<ScopedFile>")]
+    pub files: sqlx::types::Json<Vec<ScopedFile>>,
+    /// Changes outside the selected files fail the run
+    pub restrict_writes: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExecutionScope {
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionScope,
+            r#"SELECT id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", files as "files!: sqlx::types::Json<Vec<ScopedFile>>", restrict_writes as "restrict_writes!: bool", created_at as "created_at!: DateTime<Utc>"
+               FROM execution_scopes
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        files: &[ScopedFile],
+        restrict_writes: bool,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let files = sqlx::types::Json(files);
+        sqlx::query_as!(
+            ExecutionScope,
+            r#"INSERT INTO execution_scopes (id, execution_process_id, files, restrict_writes)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", files as "files!: sqlx::types::Json<Vec<ScopedFile>>", restrict_writes as "restrict_writes!: bool", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            files,
+            restrict_writes
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
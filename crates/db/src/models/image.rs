@@ -40,12 +40,16 @@ pub struct CreateTaskImage {
 }
 
 impl Image {
-    pub async fn create(pool: &SqlitePool, data: &CreateImage) -> Result<Self, sqlx::Error> {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateImage,
+        tenant_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
         let id = Uuid::new_v4();
         sqlx::query_as!(
             Image,
-            r#"INSERT INTO images (id, file_path, original_name, mime_type, size_bytes, hash)
-               VALUES ($1, $2, $3, $4, $5, $6)
+            r#"INSERT INTO images (id, file_path, original_name, mime_type, size_bytes, hash, tenant_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
                RETURNING id as "id!: Uuid", 
                          file_path as "file_path!", 
                          original_name as "original_name!", 
@@ -60,6 +64,7 @@ impl Image {
             data.mime_type,
             data.size_bytes,
             data.hash,
+            tenant_id,
         )
         .fetch_one(pool)
         .await
@@ -0,0 +1,365 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, Type, PartialEq, Eq)]
+#[sqlx(type_name = "job_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Waiting for `run_at`, including failed jobs that will be retried
+    Pending,
+    Running,
+    Succeeded,
+    /// Out of attempts; stays in the dead-letter queue until retried or deleted
+    Dead,
+}
+
+/// A unit of background work, persisted so it survives restarts
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Job {
+    pub id: Uuid,
+    /// Name of the handler that runs the job, e.g. `repo_fetch.fetch_due`
+    pub kind: String,
+    #[ts(type = "unknown")]
+    pub payload: Json<Value>,
+    pub status: JobStatus,
+    pub attempts: i64,
+    pub max_attempts: i64,
+    /// Seconds between runs of a recurring job; None for one-off jobs
+    pub interval_secs: Option<i64>,
+    pub run_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Error of the last failed attempt
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct JobStatusCount {
+    pub status: JobStatus,
+    pub count: i64,
+}
+
+/// Which jobs to list; unset fields match every job
+#[derive(Debug, Clone, Default)]
+pub struct JobFilter {
+    pub status: Option<JobStatus>,
+    /// Any of these kinds; empty matches every kind
+    pub kinds: Vec<String>,
+    /// Created at least this many seconds ago
+    pub min_age_secs: Option<i64>,
+    /// Created at most this many seconds ago
+    pub max_age_secs: Option<i64>,
+}
+
+impl JobFilter {
+    fn kinds_json(&self) -> Option<String> {
+        (!self.kinds.is_empty()).then(|| Value::from(self.kinds.clone()).to_string())
+    }
+}
+
+/// Offset for SQLite's `datetime` modifiers, `seconds` ago
+fn seconds_ago(seconds: Option<i64>) -> Option<String> {
+    seconds.map(|seconds| format!("-{} seconds", seconds.max(0)))
+}
+
+impl Job {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"SELECT id as "id!: Uuid", kind, payload as "payload!: Json<Value>", status as "status!: JobStatus", attempts as "attempts!: i64", max_attempts as "max_attempts!: i64", interval_secs, run_at as "run_at!: DateTime<Utc>", started_at as "started_at?: DateTime<Utc>", finished_at as "finished_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM jobs
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Most recent jobs first
+    pub async fn list(
+        pool: &SqlitePool,
+        filter: &JobFilter,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let kinds = filter.kinds_json();
+        let created_before = seconds_ago(filter.min_age_secs);
+        let created_after = seconds_ago(filter.max_age_secs);
+        sqlx::query_as!(
+            Job,
+            r#"SELECT id as "id!: Uuid", kind, payload as "payload!: Json<Value>", status as "status!: JobStatus", attempts as "attempts!: i64", max_attempts as "max_attempts!: i64", interval_secs, run_at as "run_at!: DateTime<Utc>", started_at as "started_at?: DateTime<Utc>", finished_at as "finished_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM jobs
+               WHERE ($1 IS NULL OR status = $1)
+                 AND ($2 IS NULL OR kind IN (SELECT value FROM json_each($2)))
+                 AND ($3 IS NULL OR created_at <= datetime('now', 'subsec', $3))
+                 AND ($4 IS NULL OR created_at >= datetime('now', 'subsec', $4))
+               ORDER BY created_at DESC
+               LIMIT $5"#,
+            filter.status,
+            kinds,
+            created_before,
+            created_after,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Jobs per status, of the given kinds or of every kind when `kinds` is empty
+    pub async fn count_by_status(
+        pool: &SqlitePool,
+        kinds: &[String],
+    ) -> Result<Vec<JobStatusCount>, sqlx::Error> {
+        let kinds = (!kinds.is_empty()).then(|| Value::from(kinds.to_vec()).to_string());
+        sqlx::query_as!(
+            JobStatusCount,
+            r#"SELECT status as "status!: JobStatus", COUNT(*) as "count!: i64"
+               FROM jobs
+               WHERE ($1 IS NULL OR kind IN (SELECT value FROM json_each($1)))
+               GROUP BY status"#,
+            kinds
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// When the longest-waiting due job became due. A time far in the past while workers are
+    /// idle means jobs are not being picked up.
+    pub async fn oldest_due_at(pool: &SqlitePool) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT MIN(run_at) as "run_at?: DateTime<Utc>"
+               FROM jobs
+               WHERE status = 'pending' AND run_at <= datetime('now', 'subsec')"#
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Queue a one-off job to run after `delay_secs`
+    pub async fn enqueue<'e, E>(
+        executor: E,
+        kind: &str,
+        payload: &Value,
+        max_attempts: i64,
+        delay_secs: i64,
+    ) -> Result<Self, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let id = Uuid::new_v4();
+        let payload = Json(payload);
+        let run_at_offset = format!("+{delay_secs} seconds");
+        sqlx::query_as!(
+            Job,
+            r#"INSERT INTO jobs (id, kind, payload, max_attempts, run_at)
+               VALUES ($1, $2, $3, $4, datetime('now', 'subsec', $5))
+               RETURNING id as "id!: Uuid", kind, payload as "payload!: Json<Value>", status as "status!: JobStatus", attempts as "attempts!: i64", max_attempts as "max_attempts!: i64", interval_secs, run_at as "run_at!: DateTime<Utc>", started_at as "started_at?: DateTime<Utc>", finished_at as "finished_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            kind,
+            payload,
+            max_attempts,
+            run_at_offset
+        )
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Make sure a recurring job of `kind` is scheduled, running it right away when it is new. An
+    /// existing one keeps its next run but takes the new interval.
+    pub async fn ensure_recurring(
+        pool: &SqlitePool,
+        kind: &str,
+        interval_secs: i64,
+        max_attempts: i64,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT OR IGNORE INTO jobs (id, kind, interval_secs, max_attempts)
+               VALUES ($1, $2, $3, $4)"#,
+            id,
+            kind,
+            interval_secs,
+            max_attempts
+        )
+        .execute(pool)
+        .await?;
+        sqlx::query!(
+            r#"UPDATE jobs
+               SET interval_secs = $2, max_attempts = $3, updated_at = datetime('now', 'subsec')
+               WHERE kind = $1
+                 AND interval_secs IS NOT NULL
+                 AND status IN ('pending', 'running')"#,
+            kind,
+            interval_secs,
+            max_attempts
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Take the job that has been due the longest and mark it running. SQLite serializes writes,
+    /// so two workers never claim the same job.
+    pub async fn claim_next(pool: &SqlitePool) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"UPDATE jobs
+               SET status = 'running',
+                   attempts = attempts + 1,
+                   started_at = datetime('now', 'subsec'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = (
+                   SELECT id FROM jobs
+                   WHERE status = 'pending' AND run_at <= datetime('now', 'subsec')
+                   ORDER BY run_at
+                   LIMIT 1
+               )
+               RETURNING id as "id!: Uuid", kind, payload as "payload!: Json<Value>", status as "status!: JobStatus", attempts as "attempts!: i64", max_attempts as "max_attempts!: i64", interval_secs, run_at as "run_at!: DateTime<Utc>", started_at as "started_at?: DateTime<Utc>", finished_at as "finished_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn mark_succeeded(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE jobs
+               SET status = 'succeeded',
+                   last_error = NULL,
+                   finished_at = datetime('now', 'subsec'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Put a job back in the queue to run after `delay_secs`: a retry after a failed attempt, or
+    /// the next run of a recurring job, which starts over with a fresh set of attempts
+    pub async fn reschedule(
+        pool: &SqlitePool,
+        id: Uuid,
+        delay_secs: i64,
+        error: Option<&str>,
+        reset_attempts: bool,
+    ) -> Result<(), sqlx::Error> {
+        let run_at_offset = format!("+{delay_secs} seconds");
+        sqlx::query!(
+            r#"UPDATE jobs
+               SET status = 'pending',
+                   run_at = datetime('now', 'subsec', $2),
+                   last_error = $3,
+                   attempts = CASE WHEN $4 THEN 0 ELSE attempts END,
+                   finished_at = datetime('now', 'subsec'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            run_at_offset,
+            error,
+            reset_attempts
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Move a job that is out of attempts to the dead-letter queue
+    pub async fn mark_dead(pool: &SqlitePool, id: Uuid, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE jobs
+               SET status = 'dead',
+                   last_error = $2,
+                   finished_at = datetime('now', 'subsec'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Requeue jobs that were running when the app stopped. Call before any worker starts.
+    pub async fn requeue_interrupted(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE jobs
+               SET status = 'pending', updated_at = datetime('now', 'subsec')
+               WHERE status = 'running'"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Give a dead job a fresh set of attempts, starting now
+    pub async fn retry(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"UPDATE jobs
+               SET status = 'pending',
+                   attempts = 0,
+                   run_at = datetime('now', 'subsec'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'dead'
+               RETURNING id as "id!: Uuid", kind, payload as "payload!: Json<Value>", status as "status!: JobStatus", attempts as "attempts!: i64", max_attempts as "max_attempts!: i64", interval_secs, run_at as "run_at!: DateTime<Utc>", started_at as "started_at?: DateTime<Utc>", finished_at as "finished_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Dead-letter a job that has not started yet, so it stays visible and can be retried
+    pub async fn cancel_pending(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE jobs
+               SET status = 'dead',
+                   last_error = 'Cancelled',
+                   finished_at = datetime('now', 'subsec'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'pending'"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Delete a job unless a worker is running it
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM jobs WHERE id = $1 AND status != 'running'"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Delete succeeded jobs finished more than `succeeded_days` ago and dead ones finished more
+    /// than `dead_days` ago
+    pub async fn prune_finished(
+        pool: &SqlitePool,
+        succeeded_days: i64,
+        dead_days: i64,
+    ) -> Result<u64, sqlx::Error> {
+        let succeeded_cutoff = format!("-{succeeded_days} days");
+        let dead_cutoff = format!("-{dead_days} days");
+        let result = sqlx::query!(
+            r#"DELETE FROM jobs
+               WHERE (status = 'succeeded' AND finished_at < datetime('now', $1))
+                  OR (status = 'dead' AND finished_at < datetime('now', $2))"#,
+            succeeded_cutoff,
+            dead_cutoff
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
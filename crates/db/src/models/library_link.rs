@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A local template or automation rule kept in sync with an item of an organization library
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct LibraryLink {
+    pub library_item_id: Uuid,
+    pub organization_id: Uuid,
+    /// The template or automation rule on this instance
+    pub local_id: Uuid,
+    /// Library version the local item was last published or pulled at
+    pub version: i64,
+    pub synced_at: DateTime<Utc>,
+}
+
+impl LibraryLink {
+    pub async fn find_by_organization_id(
+        pool: &SqlitePool,
+        organization_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LibraryLink,
+            r#"SELECT library_item_id as "library_item_id!: Uuid", organization_id as "organization_id!: Uuid", local_id as "local_id!: Uuid", version as "version!: i64", synced_at as "synced_at!: DateTime<Utc>"
+               FROM library_links
+               WHERE organization_id = $1"#,
+            organization_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_library_item_id(
+        pool: &SqlitePool,
+        library_item_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LibraryLink,
+            r#"SELECT library_item_id as "library_item_id!: Uuid", organization_id as "organization_id!: Uuid", local_id as "local_id!: Uuid", version as "version!: i64", synced_at as "synced_at!: DateTime<Utc>"
+               FROM library_links
+               WHERE library_item_id = $1"#,
+            library_item_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_local_id(
+        pool: &SqlitePool,
+        local_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LibraryLink,
+            r#"SELECT library_item_id as "library_item_id!: Uuid", organization_id as "organization_id!: Uuid", local_id as "local_id!: Uuid", version as "version!: i64", synced_at as "synced_at!: DateTime<Utc>"
+               FROM library_links
+               WHERE local_id = $1"#,
+            local_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record that `local_id` matches `version` of the library item
+    pub async fn upsert(
+        pool: &SqlitePool,
+        library_item_id: Uuid,
+        organization_id: Uuid,
+        local_id: Uuid,
+        version: i64,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            LibraryLink,
+            r#"INSERT INTO library_links (library_item_id, organization_id, local_id, version)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(library_item_id) DO UPDATE SET
+                   local_id = excluded.local_id,
+                   version = excluded.version,
+                   synced_at = datetime('now', 'subsec')
+               RETURNING library_item_id as "library_item_id!: Uuid", organization_id as "organization_id!: Uuid", local_id as "local_id!: Uuid", version as "version!: i64", synced_at as "synced_at!: DateTime<Utc>""#,
+            library_item_id,
+            organization_id,
+            local_id,
+            version
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, library_item_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM library_links WHERE library_item_id = $1",
+            library_item_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct LintFinding {
+    pub repo: String,
+    /// Name of the configured lint command that reported it
+    pub command: String,
+    /// Relative to the repository root
+    pub path: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub severity: LintSeverity,
+    /// Tool-specific rule identifier, e.g. `clippy::needless_return` or `no-unused-vars`
+    pub rule: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct LintReport {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    #[ts(type = "Array<LintFinding>")]
+    pub findings: sqlx::types::Json<Vec<LintFinding>>,
+    /// Whether a follow-up turn was started asking the agent to fix the findings
+    pub fix_requested: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl LintReport {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LintReport,
+            r#"SELECT id as "id!: Uuid", workspace_id as "workspace_id!: Uuid", execution_process_id as "execution_process_id?: Uuid", findings as "findings!: sqlx::types::Json<Vec<LintFinding>>", fix_requested as "fix_requested!: bool", created_at as "created_at!: DateTime<Utc>"
+               FROM lint_reports
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Number of fix-up turns requested in a row since the lint stage last passed
+    pub async fn count_consecutive_fix_requests(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<usize, sqlx::Error> {
+        let reports = Self::find_by_workspace_id(pool, workspace_id).await?;
+        Ok(reports
+            .iter()
+            .take_while(|report| report.fix_requested)
+            .count())
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        execution_process_id: Option<Uuid>,
+        findings: &[LintFinding],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let findings = sqlx::types::Json(findings);
+        sqlx::query_as!(
+            LintReport,
+            r#"INSERT INTO lint_reports (id, workspace_id, execution_process_id, findings)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", workspace_id as "workspace_id!: Uuid", execution_process_id as "execution_process_id?: Uuid", findings as "findings!: sqlx::types::Json<Vec<LintFinding>>", fix_requested as "fix_requested!: bool", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            execution_process_id,
+            findings
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn mark_fix_requested(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE lint_reports SET fix_requested = 1 WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
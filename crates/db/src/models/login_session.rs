@@ -0,0 +1,75 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// A browser signed in to the app as one account, found by the hash of its session token
+#[derive(Debug, Clone, FromRow)]
+pub struct LoginSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl LoginSession {
+    pub async fn create(
+        pool: &SqlitePool,
+        user_id: Uuid,
+        token_hash: &str,
+        lifetime: Duration,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let expires_at = Utc::now() + lifetime;
+        sqlx::query_as!(
+            LoginSession,
+            r#"INSERT INTO login_sessions (id, user_id, token_hash, expires_at)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", user_id as "user_id!: Uuid", expires_at as "expires_at!: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            user_id,
+            token_hash,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The unexpired session whose token hashes to `token_hash`
+    pub async fn authenticate(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LoginSession,
+            r#"SELECT id as "id!: Uuid", user_id as "user_id!: Uuid", expires_at as "expires_at!: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM login_sessions
+               WHERE token_hash = $1
+                 AND julianday(expires_at) > julianday('now')"#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM login_sessions WHERE token_hash = $1",
+            token_hash
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_expired(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM login_sessions WHERE julianday(expires_at) <= julianday('now')"
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
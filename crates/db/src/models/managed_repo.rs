@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A repository whose path is a primary clone owned by the server. Workspaces are worktrees of
+/// that clone, so the user's own checkout is never touched.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ManagedRepo {
+    pub repo_id: Uuid,
+    /// The user checkout the clone was made from, if it was adopted
+    pub source_path: Option<String>,
+    pub remote_url: Option<String>,
+    pub last_fetched_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ManagedRepo {
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ManagedRepo,
+            r#"SELECT repo_id as "repo_id!: Uuid", source_path, remote_url, last_fetched_at as "last_fetched_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM managed_repos
+               WHERE repo_id = $1"#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ManagedRepo,
+            r#"SELECT repo_id as "repo_id!: Uuid", source_path, remote_url, last_fetched_at as "last_fetched_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM managed_repos"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        source_path: Option<&str>,
+        remote_url: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ManagedRepo,
+            r#"INSERT INTO managed_repos (repo_id, source_path, remote_url, last_fetched_at)
+               VALUES ($1, $2, $3, datetime('now', 'subsec'))
+               ON CONFLICT(repo_id) DO UPDATE SET remote_url = excluded.remote_url
+               RETURNING repo_id as "repo_id!: Uuid", source_path, remote_url, last_fetched_at as "last_fetched_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            repo_id,
+            source_path,
+            remote_url
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn mark_fetched(pool: &SqlitePool, repo_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE managed_repos SET last_fetched_at = datetime('now', 'subsec') WHERE repo_id = $1",
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
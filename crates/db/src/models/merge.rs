@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -51,6 +51,15 @@ pub struct PullRequestInfo {
     pub merge_commit_sha: Option<String>,
 }
 
+/// A merged PR along with the title of the task it was opened for
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct MergedPr {
+    pub number: i64,
+    pub url: String,
+    pub title: String,
+    pub merged_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[sqlx(type_name = "TEXT", rename_all = "snake_case")]
 pub enum MergeType {
@@ -194,13 +203,48 @@ impl Merge {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
-    /// Update PR status for a workspace
-    pub async fn update_status(
+    /// Open PRs of every workspace of a task, newest first
+    pub async fn find_open_prs_by_task_id(
         pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<PrMerge>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            MergeRow,
+            r#"SELECT
+                m.id as "id!: Uuid",
+                m.workspace_id as "workspace_id!: Uuid",
+                m.repo_id as "repo_id!: Uuid",
+                m.merge_type as "merge_type!: MergeType",
+                m.merge_commit,
+                m.pr_number,
+                m.pr_url,
+                m.pr_status as "pr_status?: MergeStatus",
+                m.pr_merged_at as "pr_merged_at?: DateTime<Utc>",
+                m.pr_merge_commit_sha,
+                m.created_at as "created_at!: DateTime<Utc>",
+                m.target_branch_name as "target_branch_name!: String"
+               FROM merges m
+               JOIN workspaces w ON w.id = m.workspace_id
+               WHERE w.task_id = $1 AND m.merge_type = 'pr' AND m.pr_status = 'open'
+               ORDER BY m.created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Update PR status for a workspace
+    pub async fn update_status<'e, E>(
+        executor: E,
         merge_id: Uuid,
         pr_status: MergeStatus,
         merge_commit_sha: Option<String>,
-    ) -> Result<(), sqlx::Error> {
+    ) -> Result<(), sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let merged_at = if matches!(pr_status, MergeStatus::Merged) {
             Some(Utc::now())
         } else {
@@ -218,11 +262,28 @@ impl Merge {
             merged_at,
             merge_id
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
 
         Ok(())
     }
+
+    /// Record that a PR now merges into another branch
+    pub async fn update_target_branch(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        target_branch_name: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE merges SET target_branch_name = $1 WHERE id = $2",
+            target_branch_name,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Find all merges for a workspace (returns both direct and PR merges)
     pub async fn find_by_workspace_id(
         pool: &SqlitePool,
@@ -256,6 +317,35 @@ impl Merge {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    /// PRs of a repo merged after `since`, or all of them, oldest first
+    pub async fn find_merged_prs_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<MergedPr>, sqlx::Error> {
+        sqlx::query_as!(
+            MergedPr,
+            r#"SELECT
+                m.pr_number as "number!: i64",
+                m.pr_url as "url!: String",
+                t.title as "title!: String",
+                m.pr_merged_at as "merged_at!: DateTime<Utc>"
+            FROM merges m
+            JOIN workspaces w ON w.id = m.workspace_id
+            JOIN tasks t ON t.id = w.task_id
+            WHERE m.repo_id = $1
+              AND m.merge_type = 'pr'
+              AND m.pr_status = 'merged'
+              AND m.pr_merged_at IS NOT NULL
+              AND ($2 IS NULL OR m.pr_merged_at > $2)
+            ORDER BY m.pr_merged_at ASC"#,
+            repo_id,
+            since
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Find all merges for a workspace and specific repo
     pub async fn find_by_workspace_and_repo_id(
         pool: &SqlitePool,
@@ -1,15 +1,49 @@
+pub mod automation_rule;
+pub mod automation_transition;
+pub mod board;
+pub mod branch_divergence;
 pub mod coding_agent_turn;
+pub mod column_sla;
+pub mod command_quarantine;
+pub mod command_run;
+pub mod context_compaction;
+pub mod coverage_report;
+pub mod dependency_report;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod execution_scope;
 pub mod image;
+pub mod job;
+pub mod library_link;
+pub mod lint_report;
+pub mod login_session;
+pub mod managed_repo;
 pub mod merge;
+pub mod policy_violation_report;
+pub mod pr_comment;
 pub mod project;
+pub mod project_mirror;
 pub mod project_repo;
 pub mod repo;
+pub mod repo_chain_step;
+pub mod repo_fetch_state;
+pub mod routing_decision;
+pub mod saved_view;
 pub mod scratch;
+pub mod secret_scan_acknowledgement;
+pub mod seen_pr_comment;
+pub mod service_account;
 pub mod session;
+pub mod share_link;
+pub mod stored_object;
 pub mod tag;
 pub mod task;
+pub mod task_comment;
+pub mod task_event;
+pub mod task_metrics;
+pub mod template;
+pub mod tenant;
+pub mod tenant_quota;
 pub mod workspace;
 pub mod workspace_repo;
@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PolicyViolationKind {
+    /// The executor changed a repository outside its allowlist
+    RepoNotAllowed,
+    /// The executor changed a path matching a protected pattern
+    ProtectedPath { pattern: String },
+    /// The executor changed a file outside the scope of a scoped follow-up
+    OutsideScope,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct PolicyViolation {
+    pub executor_profile_id: ExecutorProfileId,
+    pub repo: String,
+    pub path: String,
+    pub kind: PolicyViolationKind,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PolicyViolationReport {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    /// The blocked coding agent run; `None` when a push was blocked
+    pub execution_process_id: Option<Uuid>,
+    #[ts(type = "Array<PolicyViolation>")]
+    pub violations: sqlx::types::Json<Vec<PolicyViolation>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PolicyViolationReport {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PolicyViolationReport,
+            r#"SELECT id as "id!: Uuid", workspace_id as "workspace_id!: Uuid", execution_process_id as "execution_process_id?: Uuid", violations as "violations!: sqlx::types::Json<Vec<PolicyViolation>>", created_at as "created_at!: DateTime<Utc>"
+               FROM policy_violation_reports
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        execution_process_id: Option<Uuid>,
+        violations: &[PolicyViolation],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let violations = sqlx::types::Json(violations);
+        sqlx::query_as!(
+            PolicyViolationReport,
+            r#"INSERT INTO policy_violation_reports (id, workspace_id, execution_process_id, violations)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", workspace_id as "workspace_id!: Uuid", execution_process_id as "execution_process_id?: Uuid", violations as "violations!: sqlx::types::Json<Vec<PolicyViolation>>", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            execution_process_id,
+            violations
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A comment fetched from a pull request, and whether it was addressed
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PrComment {
+    pub id: Uuid,
+    pub merge_id: Uuid,
+    /// Kind and provider id of the comment, as `UnifiedComment::key`, e.g. `review:1234`
+    pub comment_key: String,
+    pub author: String,
+    pub body: String,
+    pub url: String,
+    pub commented_at: DateTime<Utc>,
+    pub addressed_at: Option<DateTime<Utc>>,
+    /// Agent turn that addressed the comment; `None` when it was handled by hand
+    pub addressed_by_execution_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpsertPrComment {
+    pub comment_key: String,
+    pub author: String,
+    pub body: String,
+    pub url: String,
+    pub commented_at: DateTime<Utc>,
+}
+
+/// An addressed comment, for listing next to the comments fetched from the provider
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AddressedPrComment {
+    pub comment_key: String,
+    pub addressed_at: DateTime<Utc>,
+    pub addressed_by_execution_id: Option<Uuid>,
+}
+
+impl PrComment {
+    pub async fn find_by_merge_and_key(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        comment_key: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PrComment,
+            r#"SELECT id as "id!: Uuid", merge_id as "merge_id!: Uuid", comment_key, author, body, url, commented_at as "commented_at!: DateTime<Utc>", addressed_at as "addressed_at: DateTime<Utc>", addressed_by_execution_id as "addressed_by_execution_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM pr_comments
+               WHERE merge_id = $1 AND comment_key = $2"#,
+            merge_id,
+            comment_key
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_addressed_by_merge_id(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+    ) -> Result<Vec<AddressedPrComment>, sqlx::Error> {
+        sqlx::query_as!(
+            AddressedPrComment,
+            r#"SELECT comment_key, addressed_at as "addressed_at!: DateTime<Utc>", addressed_by_execution_id as "addressed_by_execution_id: Uuid"
+               FROM pr_comments
+               WHERE merge_id = $1 AND addressed_at IS NOT NULL
+               ORDER BY addressed_at ASC"#,
+            merge_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Store the comments as last fetched; edited comments keep whether they were addressed
+    pub async fn upsert_many(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        comments: &[UpsertPrComment],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for comment in comments {
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO pr_comments (id, merge_id, comment_key, author, body, url, commented_at)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7)
+                   ON CONFLICT (merge_id, comment_key) DO UPDATE
+                   SET body = excluded.body, url = excluded.url, updated_at = datetime('now', 'subsec')
+                   WHERE body IS NOT excluded.body OR url IS NOT excluded.url"#,
+                id,
+                merge_id,
+                comment.comment_key,
+                comment.author,
+                comment.body,
+                comment.url,
+                comment.commented_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Mark the comment addressed, optionally by an agent turn, or clear it with `None`
+    pub async fn set_addressed(
+        pool: &SqlitePool,
+        id: Uuid,
+        addressed_at: Option<DateTime<Utc>>,
+        addressed_by_execution_id: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            PrComment,
+            r#"UPDATE pr_comments
+               SET addressed_at = $2, addressed_by_execution_id = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", merge_id as "merge_id!: Uuid", comment_key, author, body, url, commented_at as "commented_at!: DateTime<Utc>", addressed_at as "addressed_at: DateTime<Utc>", addressed_by_execution_id as "addressed_by_execution_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            addressed_at,
+            addressed_by_execution_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
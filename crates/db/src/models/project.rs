@@ -84,6 +84,29 @@ impl Project {
         .await
     }
 
+    pub async fn find_by_tenant_id(
+        pool: &SqlitePool,
+        tenant_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Project,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      dev_script,
+                      dev_script_working_dir,
+                      default_agent_working_dir,
+                      remote_project_id as "remote_project_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM projects
+               WHERE tenant_id = $1
+               ORDER BY created_at DESC"#,
+            tenant_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Find the most actively used projects based on recent task activity
     pub async fn find_most_active(pool: &SqlitePool, limit: i32) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
@@ -127,6 +150,18 @@ impl Project {
         .await
     }
 
+    /// Version of the task list and board of a project. Triggers bump it in the transaction of
+    /// every change to its tasks and to their workspaces, sessions, execution processes and merges.
+    pub async fn tasks_version(pool: &SqlitePool, id: Uuid) -> Result<i64, sqlx::Error> {
+        let version = sqlx::query_scalar!(
+            r#"SELECT version as "version!: i64" FROM project_versions WHERE project_id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(version.unwrap_or(0))
+    }
+
     pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
@@ -173,14 +208,16 @@ impl Project {
         executor: impl Executor<'_, Database = Sqlite>,
         data: &CreateProject,
         project_id: Uuid,
+        tenant_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             Project,
             r#"INSERT INTO projects (
                     id,
-                    name
+                    name,
+                    tenant_id
                 ) VALUES (
-                    $1, $2
+                    $1, $2, $3
                 )
                 RETURNING id as "id!: Uuid",
                           name,
@@ -192,6 +229,7 @@ impl Project {
                           updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
             data.name,
+            tenant_id,
         )
         .fetch_one(executor)
         .await
@@ -0,0 +1,207 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{Task, TaskStatus};
+
+/// A project kept in sync with its remote project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectMirror {
+    pub project_id: Uuid,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The parts of a task that mirroring keeps in sync
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct MirroredFields {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+}
+
+impl From<&Task> for MirroredFields {
+    fn from(task: &Task) -> Self {
+        Self {
+            title: task.title.clone(),
+            description: task.description.clone(),
+            status: task.status.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct TaskMirrorState {
+    pub task_id: Uuid,
+    pub shared_task_id: Uuid,
+    pub base_title: String,
+    pub base_description: Option<String>,
+    pub base_status: TaskStatus,
+    /// Remote version of the task while it conflicts with the local one
+    pub conflict: Option<Json<MirroredFields>>,
+    pub synced_at: DateTime<Utc>,
+}
+
+impl ProjectMirror {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectMirror,
+            r#"SELECT project_id as "project_id!: Uuid", last_synced_at as "last_synced_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM project_mirrors
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn enable(pool: &SqlitePool, project_id: Uuid) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectMirror,
+            r#"INSERT INTO project_mirrors (project_id)
+               VALUES ($1)
+               ON CONFLICT(project_id) DO UPDATE SET project_id = excluded.project_id
+               RETURNING project_id as "project_id!: Uuid", last_synced_at as "last_synced_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Stop mirroring and forget what was synced; the tasks stay shared
+    pub async fn disable(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM task_mirror_states WHERE task_id IN (SELECT id FROM tasks WHERE project_id = $1)",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        let result = sqlx::query!(
+            "DELETE FROM project_mirrors WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn mark_synced(pool: &SqlitePool, project_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE project_mirrors SET last_synced_at = datetime('now', 'subsec') WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl TaskMirrorState {
+    /// The version both sides last agreed on
+    pub fn base(&self) -> MirroredFields {
+        MirroredFields {
+            title: self.base_title.clone(),
+            description: self.base_description.clone(),
+            status: self.base_status.clone(),
+        }
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskMirrorState,
+            r#"SELECT task_id as "task_id!: Uuid", shared_task_id as "shared_task_id!: Uuid", base_title, base_description, base_status as "base_status!: TaskStatus", conflict as "conflict?: Json<MirroredFields>", synced_at as "synced_at!: DateTime<Utc>"
+               FROM task_mirror_states
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_conflicts_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskMirrorState,
+            r#"SELECT s.task_id as "task_id!: Uuid", s.shared_task_id as "shared_task_id!: Uuid", s.base_title, s.base_description, s.base_status as "base_status!: TaskStatus", s.conflict as "conflict?: Json<MirroredFields>", s.synced_at as "synced_at!: DateTime<Utc>"
+               FROM task_mirror_states s
+               JOIN tasks t ON t.id = s.task_id
+               WHERE t.project_id = $1 AND s.conflict IS NOT NULL
+               ORDER BY s.synced_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record `base` as agreed on by both sides, clearing any conflict
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        shared_task_id: Uuid,
+        base: &MirroredFields,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO task_mirror_states (task_id, shared_task_id, base_title, base_description, base_status)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   shared_task_id = excluded.shared_task_id,
+                   base_title = excluded.base_title,
+                   base_description = excluded.base_description,
+                   base_status = excluded.base_status,
+                   conflict = NULL,
+                   synced_at = datetime('now', 'subsec')"#,
+            task_id,
+            shared_task_id,
+            base.title,
+            base.description,
+            base.status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Keep the remote version of a conflicting task until someone picks a side
+    pub async fn set_conflict(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        shared_task_id: Uuid,
+        base: &MirroredFields,
+        remote: &MirroredFields,
+    ) -> Result<(), sqlx::Error> {
+        let remote = Json(remote);
+        sqlx::query!(
+            r#"INSERT INTO task_mirror_states (task_id, shared_task_id, base_title, base_description, base_status, conflict)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   conflict = excluded.conflict,
+                   synced_at = datetime('now', 'subsec')"#,
+            task_id,
+            shared_task_id,
+            base.title,
+            base.description,
+            base.status,
+            remote
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, task_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_mirror_states WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
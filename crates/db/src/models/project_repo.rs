@@ -175,8 +175,9 @@ impl ProjectRepo {
         project_id: Uuid,
         repo_path: &str,
         repo_name: &str,
+        tenant_id: Uuid,
     ) -> Result<Repo, ProjectRepoError> {
-        let repo = Repo::find_or_create(pool, Path::new(repo_path), repo_name).await?;
+        let repo = Repo::find_or_create(pool, Path::new(repo_path), repo_name, tenant_id).await?;
 
         if Self::find_by_project_and_repo(pool, project_id, repo.id)
             .await?
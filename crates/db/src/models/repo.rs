@@ -63,6 +63,55 @@ impl Repo {
         Ok(())
     }
 
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Repo,
+            r#"SELECT id as "id!: Uuid",
+                      path,
+                      name,
+                      display_name,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repos
+               ORDER BY display_name ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_tenant_id(
+        pool: &SqlitePool,
+        tenant_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Repo,
+            r#"SELECT id as "id!: Uuid",
+                      path,
+                      name,
+                      display_name,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repos
+               WHERE tenant_id = $1
+               ORDER BY display_name ASC"#,
+            tenant_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn update_path(pool: &SqlitePool, id: Uuid, path: &Path) -> Result<(), sqlx::Error> {
+        let path_str = path.to_string_lossy().to_string();
+        sqlx::query!(
+            "UPDATE repos SET path = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            path_str,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Repo,
@@ -84,6 +133,7 @@ impl Repo {
         executor: E,
         path: &Path,
         display_name: &str,
+        tenant_id: Uuid,
     ) -> Result<Self, sqlx::Error>
     where
         E: Executor<'e, Database = Sqlite>,
@@ -95,12 +145,14 @@ impl Repo {
             .map(|name| name.to_string_lossy().to_string())
             .unwrap_or_else(|| id.to_string());
 
-        // Use INSERT OR IGNORE + SELECT to handle race conditions atomically
+        // Use INSERT OR IGNORE + SELECT to handle race conditions atomically. A path registered by
+        // another tenant is not updated, so no row is returned and the lookup fails as not found.
         sqlx::query_as!(
             Repo,
-            r#"INSERT INTO repos (id, path, name, display_name)
-               VALUES ($1, $2, $3, $4)
+            r#"INSERT INTO repos (id, path, name, display_name, tenant_id)
+               VALUES ($1, $2, $3, $4, $5)
                ON CONFLICT(path) DO UPDATE SET updated_at = updated_at
+                   WHERE repos.tenant_id = excluded.tenant_id
                RETURNING id as "id!: Uuid",
                          path,
                          name,
@@ -111,6 +163,7 @@ impl Repo {
             path_str,
             repo_name,
             display_name,
+            tenant_id,
         )
         .fetch_one(executor)
         .await
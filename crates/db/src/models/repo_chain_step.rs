@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "repo_chain_step_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum RepoChainStepStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// One repository in the order a multi-repo workspace is worked through
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct RepoChainStep {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub position: i64,
+    /// Extra instructions for this repository on top of the task
+    pub instructions: Option<String>,
+    pub status: RepoChainStepStatus,
+    /// The coding agent turn that worked on this repository
+    pub execution_process_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateRepoChainStep {
+    pub repo_id: Uuid,
+    pub instructions: Option<String>,
+}
+
+impl RepoChainStep {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoChainStep,
+            r#"SELECT id as "id!: Uuid", workspace_id as "workspace_id!: Uuid", repo_id as "repo_id!: Uuid", position as "position!: i64", instructions, status as "status!: RepoChainStepStatus", execution_process_id as "execution_process_id?: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_chain_steps
+               WHERE workspace_id = $1
+               ORDER BY position ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace the declared order of a workspace; every step starts out pending
+    pub async fn replace_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        steps: &[CreateRepoChainStep],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM repo_chain_steps WHERE workspace_id = $1",
+            workspace_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        let mut created = Vec::with_capacity(steps.len());
+        for (position, step) in steps.iter().enumerate() {
+            let id = Uuid::new_v4();
+            let position = position as i64;
+            created.push(
+                sqlx::query_as!(
+                    RepoChainStep,
+                    r#"INSERT INTO repo_chain_steps (id, workspace_id, repo_id, position, instructions)
+                       VALUES ($1, $2, $3, $4, $5)
+                       RETURNING id as "id!: Uuid", workspace_id as "workspace_id!: Uuid", repo_id as "repo_id!: Uuid", position as "position!: i64", instructions, status as "status!: RepoChainStepStatus", execution_process_id as "execution_process_id?: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+                    id,
+                    workspace_id,
+                    step.repo_id,
+                    position,
+                    step.instructions
+                )
+                .fetch_one(&mut *tx)
+                .await?,
+            );
+        }
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    pub async fn mark_running(
+        pool: &SqlitePool,
+        id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE repo_chain_steps
+               SET status = 'running', execution_process_id = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: RepoChainStepStatus,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE repo_chain_steps
+               SET status = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Background fetch settings of a repository and the outcome of its last fetch
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct RepoFetchState {
+    pub repo_id: Uuid,
+    pub enabled: bool,
+    /// Overrides the global fetch interval when set
+    pub interval_minutes: Option<i64>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// Error of the last fetch, cleared once a fetch succeeds
+    pub last_error: Option<String>,
+    pub consecutive_failures: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateRepoFetchSettings {
+    pub enabled: bool,
+    pub interval_minutes: Option<i64>,
+}
+
+impl RepoFetchState {
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoFetchState,
+            r#"SELECT repo_id as "repo_id!: Uuid", enabled as "enabled!: bool", interval_minutes, last_attempt_at as "last_attempt_at?: DateTime<Utc>", last_success_at as "last_success_at?: DateTime<Utc>", last_error, consecutive_failures as "consecutive_failures!: i64", updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_fetch_states
+               WHERE repo_id = $1"#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoFetchState,
+            r#"SELECT repo_id as "repo_id!: Uuid", enabled as "enabled!: bool", interval_minutes, last_attempt_at as "last_attempt_at?: DateTime<Utc>", last_success_at as "last_success_at?: DateTime<Utc>", last_error, consecutive_failures as "consecutive_failures!: i64", updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_fetch_states"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_tenant_id(
+        pool: &SqlitePool,
+        tenant_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoFetchState,
+            r#"SELECT s.repo_id as "repo_id!: Uuid", s.enabled as "enabled!: bool", s.interval_minutes, s.last_attempt_at as "last_attempt_at?: DateTime<Utc>", s.last_success_at as "last_success_at?: DateTime<Utc>", s.last_error, s.consecutive_failures as "consecutive_failures!: i64", s.updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_fetch_states s
+               JOIN repos r ON r.id = s.repo_id
+               WHERE r.tenant_id = $1"#,
+            tenant_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn update_settings(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        settings: &UpdateRepoFetchSettings,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            RepoFetchState,
+            r#"INSERT INTO repo_fetch_states (repo_id, enabled, interval_minutes)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(repo_id) DO UPDATE SET
+                   enabled = excluded.enabled,
+                   interval_minutes = excluded.interval_minutes,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING repo_id as "repo_id!: Uuid", enabled as "enabled!: bool", interval_minutes, last_attempt_at as "last_attempt_at?: DateTime<Utc>", last_success_at as "last_success_at?: DateTime<Utc>", last_error, consecutive_failures as "consecutive_failures!: i64", updated_at as "updated_at!: DateTime<Utc>""#,
+            repo_id,
+            settings.enabled,
+            settings.interval_minutes
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn record_success(pool: &SqlitePool, repo_id: Uuid) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            RepoFetchState,
+            r#"INSERT INTO repo_fetch_states (repo_id, last_attempt_at, last_success_at)
+               VALUES ($1, datetime('now', 'subsec'), datetime('now', 'subsec'))
+               ON CONFLICT(repo_id) DO UPDATE SET
+                   last_attempt_at = excluded.last_attempt_at,
+                   last_success_at = excluded.last_success_at,
+                   last_error = NULL,
+                   consecutive_failures = 0,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING repo_id as "repo_id!: Uuid", enabled as "enabled!: bool", interval_minutes, last_attempt_at as "last_attempt_at?: DateTime<Utc>", last_success_at as "last_success_at?: DateTime<Utc>", last_error, consecutive_failures as "consecutive_failures!: i64", updated_at as "updated_at!: DateTime<Utc>""#,
+            repo_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn record_failure(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        error: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            RepoFetchState,
+            r#"INSERT INTO repo_fetch_states (repo_id, last_attempt_at, last_error, consecutive_failures)
+               VALUES ($1, datetime('now', 'subsec'), $2, 1)
+               ON CONFLICT(repo_id) DO UPDATE SET
+                   last_attempt_at = excluded.last_attempt_at,
+                   last_error = excluded.last_error,
+                   consecutive_failures = consecutive_failures + 1,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING repo_id as "repo_id!: Uuid", enabled as "enabled!: bool", interval_minutes, last_attempt_at as "last_attempt_at?: DateTime<Utc>", last_success_at as "last_success_at?: DateTime<Utc>", last_error, consecutive_failures as "consecutive_failures!: i64", updated_at as "updated_at!: DateTime<Utc>""#,
+            repo_id,
+            error
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
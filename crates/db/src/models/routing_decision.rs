@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What a coding agent turn is for
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "turn_purpose", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum TurnPurpose {
+    Initial,
+    FollowUp,
+    Question,
+    PrDescription,
+    LintFix,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "task_size", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum TaskSize {
+    Small,
+    Medium,
+    Large,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct RoutingDecision {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub purpose: TurnPurpose,
+    pub task_size: TaskSize,
+    /// Profile the turn was started with before routing
+    #[ts(type = "ExecutorProfileId")]
+    pub requested_profile: sqlx::types::Json<ExecutorProfileId>,
+    #[ts(type = "ExecutorProfileId")]
+    pub selected_profile: sqlx::types::Json<ExecutorProfileId>,
+    /// Name of the matching rule; None when no rule applied
+    pub rule_name: Option<String>,
+    pub reason: String,
+    /// Consecutive failed coding agent turns in the session before this one
+    pub previous_failures: i64,
+    /// Attempt budget left before this turn; None without a budget
+    pub budget_remaining: Option<f64>,
+    pub estimated_cost: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateRoutingDecision {
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub purpose: TurnPurpose,
+    pub task_size: TaskSize,
+    pub requested_profile: ExecutorProfileId,
+    pub selected_profile: ExecutorProfileId,
+    pub rule_name: Option<String>,
+    pub reason: String,
+    pub previous_failures: i64,
+    pub budget_remaining: Option<f64>,
+    pub estimated_cost: f64,
+}
+
+impl RoutingDecision {
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RoutingDecision,
+            r#"SELECT id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", workspace_id as "workspace_id!: Uuid", purpose as "purpose!: TurnPurpose", task_size as "task_size!: TaskSize", requested_profile as "requested_profile!: sqlx::types::Json<ExecutorProfileId>", selected_profile as "selected_profile!: sqlx::types::Json<ExecutorProfileId>", rule_name, reason, previous_failures as "previous_failures!: i64", budget_remaining, estimated_cost as "estimated_cost!: f64", created_at as "created_at!: DateTime<Utc>"
+               FROM routing_decisions
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Estimated cost of all routed turns in a workspace
+    pub async fn total_cost_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<f64, sqlx::Error> {
+        let total = sqlx::query_scalar!(
+            r#"SELECT COALESCE(SUM(estimated_cost), 0.0) as "total!: f64"
+               FROM routing_decisions
+               WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(total)
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateRoutingDecision,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let requested_profile = sqlx::types::Json(&data.requested_profile);
+        let selected_profile = sqlx::types::Json(&data.selected_profile);
+        sqlx::query_as!(
+            RoutingDecision,
+            r#"INSERT INTO routing_decisions (id, execution_process_id, workspace_id, purpose, task_size, requested_profile, selected_profile, rule_name, reason, previous_failures, budget_remaining, estimated_cost)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+               RETURNING id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", workspace_id as "workspace_id!: Uuid", purpose as "purpose!: TurnPurpose", task_size as "task_size!: TaskSize", requested_profile as "requested_profile!: sqlx::types::Json<ExecutorProfileId>", selected_profile as "selected_profile!: sqlx::types::Json<ExecutorProfileId>", rule_name, reason, previous_failures as "previous_failures!: i64", budget_remaining, estimated_cost as "estimated_cost!: f64", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.execution_process_id,
+            data.workspace_id,
+            data.purpose,
+            data.task_size,
+            requested_profile,
+            selected_profile,
+            data.rule_name,
+            data.reason,
+            data.previous_failures,
+            data.budget_remaining,
+            data.estimated_cost
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
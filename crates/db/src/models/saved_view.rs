@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Card field a view sorts by
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, Default)]
+#[sqlx(type_name = "view_sort_field", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ViewSortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Title,
+}
+
+/// How a view groups its cards
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, Default)]
+#[sqlx(type_name = "view_grouping", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ViewGrouping {
+    /// The board columns
+    #[default]
+    Status,
+    /// Executor of the latest session
+    Executor,
+    /// Status of the latest pull request
+    PrStatus,
+    /// A single list
+    None,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct SavedView {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Signed-in user who saved the view; `None` for views saved while signed out
+    pub owner_user_id: Option<Uuid>,
+    pub name: String,
+    /// Condition expression each card has to match, e.g.
+    /// `task.status == "inprogress" and task.last_attempt_failed`; empty matches every card
+    pub filter: String,
+    pub sort_field: ViewSortField,
+    pub sort_descending: bool,
+    pub grouping: ViewGrouping,
+    /// Whether everyone working on the project sees the view, not just its owner
+    pub shared: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateSavedView {
+    pub project_id: Uuid,
+    pub name: String,
+    pub filter: Option<String>,
+    pub sort_field: Option<ViewSortField>,
+    pub sort_descending: Option<bool>,
+    pub grouping: Option<ViewGrouping>,
+    pub shared: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateSavedView {
+    pub name: Option<String>,
+    pub filter: Option<String>,
+    pub sort_field: Option<ViewSortField>,
+    pub sort_descending: Option<bool>,
+    pub grouping: Option<ViewGrouping>,
+    pub shared: Option<bool>,
+}
+
+impl SavedView {
+    /// Views of a project the user sees: the shared ones and their own, by name
+    pub async fn find_visible(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SavedView,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", owner_user_id as "owner_user_id?: Uuid", name, filter, sort_field as "sort_field!: ViewSortField", sort_descending as "sort_descending!: bool", grouping as "grouping!: ViewGrouping", shared as "shared!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM saved_views
+               WHERE project_id = $1 AND (shared = TRUE OR owner_user_id IS $2)
+               ORDER BY name ASC"#,
+            project_id,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SavedView,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", owner_user_id as "owner_user_id?: Uuid", name, filter, sort_field as "sort_field!: ViewSortField", sort_descending as "sort_descending!: bool", grouping as "grouping!: ViewGrouping", shared as "shared!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM saved_views
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateSavedView,
+        owner_user_id: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let filter = data.filter.as_deref().unwrap_or_default().trim();
+        let sort_field = data.sort_field.unwrap_or_default();
+        let sort_descending = data.sort_descending.unwrap_or(true);
+        let grouping = data.grouping.unwrap_or_default();
+        let shared = data.shared.unwrap_or(false);
+        sqlx::query_as!(
+            SavedView,
+            r#"INSERT INTO saved_views (id, project_id, owner_user_id, name, filter, sort_field, sort_descending, grouping, shared)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", owner_user_id as "owner_user_id?: Uuid", name, filter, sort_field as "sort_field!: ViewSortField", sort_descending as "sort_descending!: bool", grouping as "grouping!: ViewGrouping", shared as "shared!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            owner_user_id,
+            data.name,
+            filter,
+            sort_field,
+            sort_descending,
+            grouping,
+            shared
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateSavedView,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let filter = data.filter.as_ref().unwrap_or(&existing.filter).trim();
+        let sort_field = data.sort_field.unwrap_or(existing.sort_field);
+        let sort_descending = data.sort_descending.unwrap_or(existing.sort_descending);
+        let grouping = data.grouping.unwrap_or(existing.grouping);
+        let shared = data.shared.unwrap_or(existing.shared);
+
+        sqlx::query_as!(
+            SavedView,
+            r#"UPDATE saved_views
+               SET name = $2, filter = $3, sort_field = $4, sort_descending = $5, grouping = $6, shared = $7, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", owner_user_id as "owner_user_id?: Uuid", name, filter, sort_field as "sort_field!: ViewSortField", sort_descending as "sort_descending!: bool", grouping as "grouping!: ViewGrouping", shared as "shared!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            filter,
+            sort_field,
+            sort_descending,
+            grouping,
+            shared
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM saved_views WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,42 @@
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Fingerprints of secret scan findings acknowledged as false positives, per workspace
+pub struct SecretScanAcknowledgement;
+
+impl SecretScanAcknowledgement {
+    pub async fn find_fingerprints_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT fingerprint as "fingerprint!: String"
+               FROM secret_scan_acknowledgements
+               WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create_many(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        fingerprints: &[String],
+    ) -> Result<(), sqlx::Error> {
+        for fingerprint in fingerprints {
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT OR IGNORE INTO secret_scan_acknowledgements (id, workspace_id, fingerprint)
+                   VALUES ($1, $2, $3)"#,
+                id,
+                workspace_id,
+                fingerprint
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
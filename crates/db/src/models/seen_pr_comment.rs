@@ -0,0 +1,42 @@
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Keys of the pull request comments review follow-ups already handled, per merge
+pub struct SeenPrComment;
+
+impl SeenPrComment {
+    pub async fn find_keys_by_merge_id(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT comment_key as "comment_key!: String"
+               FROM seen_pr_comments
+               WHERE merge_id = $1"#,
+            merge_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create_many(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        comment_keys: &[String],
+    ) -> Result<(), sqlx::Error> {
+        for comment_key in comment_keys {
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT OR IGNORE INTO seen_pr_comments (id, merge_id, comment_key)
+                   VALUES ($1, $2, $3)"#,
+                id,
+                merge_id,
+                comment_key
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
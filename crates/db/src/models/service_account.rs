@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What a service account may do through the API
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceAccountScope {
+    /// Read anything the API serves
+    Read,
+    /// Create, edit and delete tasks
+    WriteTasks,
+    /// Start attempts and send follow-ups to their coding agents
+    StartAttempts,
+    /// Deliver provider webhooks
+    ReceiveWebhooks,
+}
+
+/// Non-interactive identity of an integration, like the scheduler or the MCP server. Changes made
+/// with its API key are recorded under its name instead of as a person's.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ServiceAccount {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    #[ts(type = "Array<ServiceAccountScope>")]
+    pub scopes: Json<Vec<ServiceAccountScope>>,
+    /// Start of the current API key; None until a key is issued
+    pub key_prefix: Option<String>,
+    pub key_issued_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateServiceAccount {
+    pub name: String,
+    pub description: Option<String>,
+    pub scopes: Vec<ServiceAccountScope>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateServiceAccount {
+    pub description: Option<String>,
+    pub scopes: Option<Vec<ServiceAccountScope>>,
+}
+
+impl ServiceAccount {
+    pub fn has_scope(&self, scope: ServiceAccountScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    pub async fn find_by_tenant_id(
+        pool: &SqlitePool,
+        tenant_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ServiceAccount,
+            r#"SELECT id as "id!: Uuid", tenant_id as "tenant_id!: Uuid", name, description, scopes as "scopes!: Json<Vec<ServiceAccountScope>>", key_prefix, key_issued_at as "key_issued_at?: DateTime<Utc>", last_used_at as "last_used_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM service_accounts
+               WHERE tenant_id = $1
+               ORDER BY name ASC"#,
+            tenant_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ServiceAccount,
+            r#"SELECT id as "id!: Uuid", tenant_id as "tenant_id!: Uuid", name, description, scopes as "scopes!: Json<Vec<ServiceAccountScope>>", key_prefix, key_issued_at as "key_issued_at?: DateTime<Utc>", last_used_at as "last_used_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM service_accounts
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The account whose current key hashes to `key_hash`, marking it as used
+    pub async fn authenticate(
+        pool: &SqlitePool,
+        key_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ServiceAccount,
+            r#"UPDATE service_accounts
+               SET last_used_at = datetime('now', 'subsec')
+               WHERE key_hash = $1
+               RETURNING id as "id!: Uuid", tenant_id as "tenant_id!: Uuid", name, description, scopes as "scopes!: Json<Vec<ServiceAccountScope>>", key_prefix, key_issued_at as "key_issued_at?: DateTime<Utc>", last_used_at as "last_used_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            key_hash
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateServiceAccount,
+        tenant_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let scopes = Json(&data.scopes);
+        sqlx::query_as!(
+            ServiceAccount,
+            r#"INSERT INTO service_accounts (id, tenant_id, name, description, scopes)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid", tenant_id as "tenant_id!: Uuid", name, description, scopes as "scopes!: Json<Vec<ServiceAccountScope>>", key_prefix, key_issued_at as "key_issued_at?: DateTime<Utc>", last_used_at as "last_used_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            tenant_id,
+            data.name,
+            data.description,
+            scopes
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateServiceAccount,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let Some(existing) = Self::find_by_id(pool, id).await? else {
+            return Ok(None);
+        };
+        let description = data.description.as_ref().or(existing.description.as_ref());
+        let scopes = Json(data.scopes.as_ref().unwrap_or(&existing.scopes.0));
+        sqlx::query_as!(
+            ServiceAccount,
+            r#"UPDATE service_accounts
+               SET description = $2, scopes = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", tenant_id as "tenant_id!: Uuid", name, description, scopes as "scopes!: Json<Vec<ServiceAccountScope>>", key_prefix, key_issued_at as "key_issued_at?: DateTime<Utc>", last_used_at as "last_used_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            description,
+            scopes
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Replace the API key of the account; the previous key stops working right away
+    pub async fn set_key(
+        pool: &SqlitePool,
+        id: Uuid,
+        key_hash: &str,
+        key_prefix: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ServiceAccount,
+            r#"UPDATE service_accounts
+               SET key_hash = $2, key_prefix = $3, key_issued_at = datetime('now', 'subsec'), updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", tenant_id as "tenant_id!: Uuid", name, description, scopes as "scopes!: Json<Vec<ServiceAccountScope>>", key_prefix, key_issued_at as "key_issued_at?: DateTime<Utc>", last_used_at as "last_used_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            key_hash,
+            key_prefix
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Remove the API key, leaving the account without access until a new key is issued
+    pub async fn revoke_key(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ServiceAccount,
+            r#"UPDATE service_accounts
+               SET key_hash = NULL, key_prefix = NULL, key_issued_at = NULL, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", tenant_id as "tenant_id!: Uuid", name, description, scopes as "scopes!: Json<Vec<ServiceAccountScope>>", key_prefix, key_issued_at as "key_issued_at?: DateTime<Utc>", last_used_at as "last_used_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM service_accounts WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
+use utils::response::KeysetPage;
 use uuid::Uuid;
 
 #[derive(Debug, Error)]
@@ -50,6 +51,17 @@ impl Session {
         pool: &SqlitePool,
         workspace_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
+        Self::find_page_by_workspace_id(pool, workspace_id, KeysetPage::ALL).await
+    }
+
+    /// One page of the sessions of a workspace, newest first
+    pub async fn find_page_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        page: KeysetPage,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let after_created_at = page.after.map(|after| after.created_at);
+        let after_id = page.after.map(|after| after.id);
         sqlx::query_as!(
             Session,
             r#"SELECT id AS "id!: Uuid",
@@ -59,13 +71,32 @@ impl Session {
                       updated_at AS "updated_at!: DateTime<Utc>"
                FROM sessions
                WHERE workspace_id = $1
-               ORDER BY created_at DESC"#,
-            workspace_id
+                 AND ($2 IS NULL
+                      OR julianday(created_at) < julianday($2)
+                      OR (julianday(created_at) = julianday($2) AND id < $3))
+               ORDER BY julianday(created_at) DESC, id DESC
+               LIMIT $4"#,
+            workspace_id,
+            after_created_at,
+            after_id,
+            page.fetch
         )
         .fetch_all(pool)
         .await
     }
 
+    pub async fn count_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM sessions WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
     /// Find the latest session for a workspace
     pub async fn find_latest_by_workspace_id(
         pool: &SqlitePool,
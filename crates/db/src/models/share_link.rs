@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Read-only link to a task, or to one of its attempts, that works without access to the app
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ShareLink {
+    pub id: Uuid,
+    /// Secret part of the link
+    pub token: String,
+    pub task_id: Uuid,
+    /// Only this attempt is shared when set, otherwise every attempt of the task
+    pub workspace_id: Option<Uuid>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateShareLink {
+    pub token: String,
+    pub task_id: Uuid,
+    pub workspace_id: Option<Uuid>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ShareLink {
+    /// Whether the link can still be opened at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ShareLink,
+            r#"SELECT id as "id!: Uuid", token, task_id as "task_id!: Uuid", workspace_id as "workspace_id?: Uuid", expires_at as "expires_at?: DateTime<Utc>", revoked_at as "revoked_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM share_links
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_token(
+        pool: &SqlitePool,
+        token: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ShareLink,
+            r#"SELECT id as "id!: Uuid", token, task_id as "task_id!: Uuid", workspace_id as "workspace_id?: Uuid", expires_at as "expires_at?: DateTime<Utc>", revoked_at as "revoked_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM share_links
+               WHERE token = $1"#,
+            token
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Links of a task, newest first, including revoked and expired ones
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ShareLink,
+            r#"SELECT id as "id!: Uuid", token, task_id as "task_id!: Uuid", workspace_id as "workspace_id?: Uuid", expires_at as "expires_at?: DateTime<Utc>", revoked_at as "revoked_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM share_links
+               WHERE task_id = $1
+               ORDER BY created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateShareLink) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ShareLink,
+            r#"INSERT INTO share_links (id, token, task_id, workspace_id, expires_at)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid", token, task_id as "task_id!: Uuid", workspace_id as "workspace_id?: Uuid", expires_at as "expires_at?: DateTime<Utc>", revoked_at as "revoked_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.token,
+            data.task_id,
+            data.workspace_id,
+            data.expires_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Revoke the link; revoking it again keeps the first revocation time
+    pub async fn revoke(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ShareLink,
+            r#"UPDATE share_links
+               SET revoked_at = COALESCE(revoked_at, datetime('now', 'subsec'))
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", token, task_id as "task_id!: Uuid", workspace_id as "workspace_id?: Uuid", expires_at as "expires_at?: DateTime<Utc>", revoked_at as "revoked_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, Type, PartialEq, Eq)]
+#[sqlx(type_name = "stored_object_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum StoredObjectKind {
+    /// An image attached to a task or draft
+    Image,
+    /// Output kept from an execution, like its logs
+    Artifact,
+    /// A session exported for download
+    Transcript,
+}
+
+/// An object in object storage and the tenant whose storage quota it counts against
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct StoredObject {
+    pub key: String,
+    pub tenant_id: Uuid,
+    pub kind: StoredObjectKind,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl StoredObject {
+    pub async fn find_by_key(pool: &SqlitePool, key: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            StoredObject,
+            r#"SELECT key as "key!", tenant_id as "tenant_id!: Uuid", kind as "kind!: StoredObjectKind", size_bytes, created_at as "created_at!: DateTime<Utc>"
+               FROM stored_objects
+               WHERE key = $1"#,
+            key
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record an object that was written; writing the same key again replaces its size
+    pub async fn record(
+        pool: &SqlitePool,
+        key: &str,
+        tenant_id: Uuid,
+        kind: StoredObjectKind,
+        size_bytes: i64,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            StoredObject,
+            r#"INSERT INTO stored_objects (key, tenant_id, kind, size_bytes)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(key) DO UPDATE SET
+                   tenant_id = excluded.tenant_id,
+                   kind = excluded.kind,
+                   size_bytes = excluded.size_bytes
+               RETURNING key as "key!", tenant_id as "tenant_id!: Uuid", kind as "kind!: StoredObjectKind", size_bytes, created_at as "created_at!: DateTime<Utc>""#,
+            key,
+            tenant_id,
+            kind,
+            size_bytes
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, key: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM stored_objects WHERE key = $1", key)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Bytes a tenant keeps in object storage
+    pub async fn total_for_tenant(pool: &SqlitePool, tenant_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COALESCE(SUM(size_bytes), 0) as "total!: i64"
+               FROM stored_objects
+               WHERE tenant_id = $1"#,
+            tenant_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
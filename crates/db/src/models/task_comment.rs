@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use utils::response::KeysetPage;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskComment {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    /// Display name of the writer, e.g. a username or `plugin:<name>`
+    pub author: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTaskComment {
+    pub author: String,
+    pub body: String,
+}
+
+impl TaskComment {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        Self::find_page_by_task_id(pool, task_id, KeysetPage::ALL).await
+    }
+
+    /// One page of the comments on a task, oldest first
+    pub async fn find_page_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        page: KeysetPage,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let after_created_at = page.after.map(|after| after.created_at);
+        let after_id = page.after.map(|after| after.id);
+        sqlx::query_as!(
+            TaskComment,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", author, body, created_at as "created_at!: DateTime<Utc>"
+               FROM task_comments
+               WHERE task_id = $1
+                 AND ($2 IS NULL
+                      OR julianday(created_at) > julianday($2)
+                      OR (julianday(created_at) = julianday($2) AND id > $3))
+               ORDER BY julianday(created_at) ASC, id ASC
+               LIMIT $4"#,
+            task_id,
+            after_created_at,
+            after_id,
+            page.fetch
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn count_by_task_id(pool: &SqlitePool, task_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM task_comments WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &CreateTaskComment,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            TaskComment,
+            r#"INSERT INTO task_comments (id, task_id, author, body)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", author, body, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            data.author,
+            data.body
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,251 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use utils::response::KeysetPage;
+use uuid::Uuid;
+
+use super::task::{Task, TaskStatus};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, Type, PartialEq, Eq)]
+#[sqlx(type_name = "task_event_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// What made a change to a task
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, Type, PartialEq, Eq)]
+#[sqlx(type_name = "task_event_source", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskEventSource {
+    /// Someone using the app, the MCP server or the API
+    User,
+    /// Automation rules and plugins
+    Automation,
+    /// Provider callbacks and pollers, e.g. a merged pull request
+    Webhook,
+    /// Bookkeeping by the app itself, e.g. finishing an attempt
+    System,
+}
+
+/// The fields of a task recorded before and after each change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct TaskSnapshot {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    pub parent_workspace_id: Option<Uuid>,
+    pub shared_task_id: Option<Uuid>,
+    /// Missing from events recorded before tasks had due dates
+    #[serde(default)]
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+impl From<&Task> for TaskSnapshot {
+    fn from(task: &Task) -> Self {
+        Self {
+            title: task.title.clone(),
+            description: task.description.clone(),
+            status: task.status.clone(),
+            parent_workspace_id: task.parent_workspace_id,
+            shared_task_id: task.shared_task_id,
+            due_at: task.due_at,
+        }
+    }
+}
+
+/// One change to a task. Events are never updated, so the log is the history of the task.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskEvent {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub kind: TaskEventKind,
+    pub source: TaskEventSource,
+    /// Who made the change within its source, e.g. `rule:Close merged` or `pr_monitor`
+    pub actor: Option<String>,
+    /// None for created tasks
+    #[ts(type = "TaskSnapshot | null")]
+    pub before: Option<Json<TaskSnapshot>>,
+    /// None for deleted tasks
+    #[ts(type = "TaskSnapshot | null")]
+    pub after: Option<Json<TaskSnapshot>>,
+    /// The change this event undid
+    pub undo_of: Option<Uuid>,
+    /// The undone change this event reapplied
+    pub redo_of: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateTaskEvent {
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub kind: TaskEventKind,
+    pub source: TaskEventSource,
+    pub actor: Option<String>,
+    pub before: Option<TaskSnapshot>,
+    pub after: Option<TaskSnapshot>,
+    pub undo_of: Option<Uuid>,
+    pub redo_of: Option<Uuid>,
+}
+
+/// Which events to list; unset fields match every event
+#[derive(Debug, Clone, Default)]
+pub struct TaskEventFilter {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub source: Option<TaskEventSource>,
+    pub actor: Option<String>,
+    /// Created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Created before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// A task entering a board column, or leaving the board when `status` is `None`
+#[derive(Debug, Clone, FromRow)]
+pub struct StatusChange {
+    pub task_id: Uuid,
+    pub title: Option<String>,
+    pub status: Option<TaskStatus>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskEvent {
+    pub async fn create<'e, E>(executor: E, data: &CreateTaskEvent) -> Result<Self, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let id = Uuid::new_v4();
+        let before = data.before.clone().map(Json);
+        let after = data.after.clone().map(Json);
+        sqlx::query_as!(
+            TaskEvent,
+            r#"INSERT INTO task_events (id, task_id, project_id, kind, source, actor, before, after, undo_of, redo_of)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", project_id as "project_id!: Uuid", kind as "kind!: TaskEventKind", source as "source!: TaskEventSource", actor, before as "before: Json<TaskSnapshot>", after as "after: Json<TaskSnapshot>", undo_of as "undo_of: Uuid", redo_of as "redo_of: Uuid", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.task_id,
+            data.project_id,
+            data.kind,
+            data.source,
+            data.actor,
+            before,
+            after,
+            data.undo_of,
+            data.redo_of
+        )
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskEvent,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", project_id as "project_id!: Uuid", kind as "kind!: TaskEventKind", source as "source!: TaskEventSource", actor, before as "before: Json<TaskSnapshot>", after as "after: Json<TaskSnapshot>", undo_of as "undo_of: Uuid", redo_of as "redo_of: Uuid", created_at as "created_at!: DateTime<Utc>"
+               FROM task_events
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Activity of a task, most recent first
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        Self::find_page_by_task_id(pool, task_id, KeysetPage::ALL).await
+    }
+
+    /// One page of the activity of a task, most recent first
+    pub async fn find_page_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        page: KeysetPage,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let after_created_at = page.after.map(|after| after.created_at);
+        let after_id = page.after.map(|after| after.id);
+        sqlx::query_as!(
+            TaskEvent,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", project_id as "project_id!: Uuid", kind as "kind!: TaskEventKind", source as "source!: TaskEventSource", actor, before as "before: Json<TaskSnapshot>", after as "after: Json<TaskSnapshot>", undo_of as "undo_of: Uuid", redo_of as "redo_of: Uuid", created_at as "created_at!: DateTime<Utc>"
+               FROM task_events
+               WHERE task_id = $1
+                 AND ($2 IS NULL
+                      OR julianday(created_at) < julianday($2)
+                      OR (julianday(created_at) = julianday($2) AND id < $3))
+               ORDER BY julianday(created_at) DESC, id DESC
+               LIMIT $4"#,
+            task_id,
+            after_created_at,
+            after_id,
+            page.fetch
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn count_by_task_id(pool: &SqlitePool, task_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM task_events WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Most recent events first
+    pub async fn list(
+        pool: &SqlitePool,
+        filter: &TaskEventFilter,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskEvent,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", project_id as "project_id!: Uuid", kind as "kind!: TaskEventKind", source as "source!: TaskEventSource", actor, before as "before: Json<TaskSnapshot>", after as "after: Json<TaskSnapshot>", undo_of as "undo_of: Uuid", redo_of as "redo_of: Uuid", created_at as "created_at!: DateTime<Utc>"
+               FROM task_events
+               WHERE ($1 IS NULL OR project_id = $1)
+                 AND ($2 IS NULL OR task_id = $2)
+                 AND ($3 IS NULL OR source = $3)
+                 AND ($4 IS NULL OR actor = $4)
+                 AND ($5 IS NULL OR julianday(created_at) >= julianday($5))
+                 AND ($6 IS NULL OR julianday(created_at) < julianday($6))
+               ORDER BY created_at DESC
+               LIMIT $7"#,
+            filter.project_id,
+            filter.task_id,
+            filter.source,
+            filter.actor,
+            filter.since,
+            filter.until,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Column entries of the tasks of a project, oldest first: creations, deletions and the
+    /// updates that changed the status
+    pub async fn find_status_changes(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<StatusChange>, sqlx::Error> {
+        sqlx::query_as!(
+            StatusChange,
+            r#"SELECT task_id as "task_id!: Uuid", json_extract(after, '$.title') as "title?: String", json_extract(after, '$.status') as "status?: TaskStatus", created_at as "created_at!: DateTime<Utc>"
+               FROM task_events
+               WHERE project_id = $1
+                 AND (kind != 'updated'
+                      OR json_extract(before, '$.status') IS NOT json_extract(after, '$.status'))
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What it took to get a finished task done, summed over all of its attempts
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskMetrics {
+    pub task_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    /// Coding agent turns that ran to an end
+    pub turns: i64,
+    /// Wall-clock time spent in coding agent turns
+    pub duration_seconds: f64,
+    /// Sum of the routing cost estimates of those turns
+    pub estimated_cost: f64,
+}
+
+impl TaskMetrics {
+    /// Metrics of the done tasks in a project that had at least one coding agent turn
+    pub async fn find_done_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskMetrics,
+            r#"SELECT t.id as "task_id!: Uuid",
+                      t.title,
+                      t.description,
+                      COUNT(ep.id) as "turns!: i64",
+                      COALESCE(SUM((julianday(ep.completed_at) - julianday(ep.started_at)) * 86400.0), 0.0) as "duration_seconds!: f64",
+                      COALESCE(SUM(rd.estimated_cost), 0.0) as "estimated_cost!: f64"
+               FROM tasks t
+               JOIN workspaces w ON w.task_id = t.id
+               JOIN sessions s ON s.workspace_id = w.id
+               JOIN execution_processes ep ON ep.session_id = s.id
+               LEFT JOIN routing_decisions rd ON rd.execution_process_id = ep.id
+               WHERE t.project_id = $1
+                 AND t.status = 'done'
+                 AND ep.run_reason = 'codingagent'
+                 AND ep.status IN ('completed', 'failed')
+                 AND ep.completed_at IS NOT NULL
+                 AND ep.dropped = FALSE
+               GROUP BY t.id
+               ORDER BY t.updated_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
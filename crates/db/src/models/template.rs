@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "template_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateKind {
+    /// Text to start a follow-up or new attempt with
+    Prompt,
+    /// Title and description of a new task
+    Task,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Template {
+    pub id: Uuid,
+    pub kind: TemplateKind,
+    pub name: String,
+    /// Title of tasks created from a task template
+    pub title: Option<String>,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateTemplate {
+    pub kind: TemplateKind,
+    pub name: String,
+    pub title: Option<String>,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateTemplate {
+    pub name: Option<String>,
+    pub title: Option<String>,
+    pub content: Option<String>,
+}
+
+impl Template {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Template,
+            r#"SELECT id as "id!: Uuid", kind as "kind!: TemplateKind", name, title, content, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM templates
+               ORDER BY name ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Template,
+            r#"SELECT id as "id!: Uuid", kind as "kind!: TemplateKind", name, title, content, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM templates
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateTemplate) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            Template,
+            r#"INSERT INTO templates (id, kind, name, title, content)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid", kind as "kind!: TemplateKind", name, title, content, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.kind,
+            data.name,
+            data.title,
+            data.content
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateTemplate,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let title = data.title.as_ref().or(existing.title.as_ref());
+        let content = data.content.as_ref().unwrap_or(&existing.content);
+
+        sqlx::query_as!(
+            Template,
+            r#"UPDATE templates
+               SET name = $2, title = $3, content = $4, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", kind as "kind!: TemplateKind", name, title, content, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            title,
+            content
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM templates WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
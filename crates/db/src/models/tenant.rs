@@ -0,0 +1,355 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// An isolated organization on a shared server. Projects, repositories and service accounts
+/// belong to one tenant, and tasks, attempts and sessions to the tenant of their project.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Tenant {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Someone who may work in a tenant, by the user id of the account they sign in with. The default
+/// tenant has no members since everyone may work in it.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TenantMember {
+    pub tenant_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct AddTenantMember {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateTenant {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateTenant {
+    pub name: String,
+}
+
+impl Tenant {
+    /// The tenant of everything created before tenants existed, and of requests that pick none
+    pub const DEFAULT_ID: Uuid = Uuid::nil();
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Tenant,
+            r#"SELECT id as "id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tenants
+               ORDER BY name ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The default tenant and the tenants a user is a member of
+    pub async fn find_for_user(pool: &SqlitePool, user_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Tenant,
+            r#"SELECT id as "id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tenants
+               WHERE id = $1
+                  OR id IN (SELECT tenant_id FROM tenant_members WHERE user_id = $2)
+               ORDER BY name ASC"#,
+            Self::DEFAULT_ID,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Tenant,
+            r#"SELECT id as "id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tenants
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_name(pool: &SqlitePool, name: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Tenant,
+            r#"SELECT id as "id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tenants
+               WHERE name = $1"#,
+            name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateTenant) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            Tenant,
+            r#"INSERT INTO tenants (id, name)
+               VALUES ($1, $2)
+               RETURNING id as "id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn rename(
+        pool: &SqlitePool,
+        id: Uuid,
+        name: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Tenant,
+            r#"UPDATE tenants
+               SET name = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Delete a tenant without projects or repositories; its service accounts go with it
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM tenants
+               WHERE id = $1
+                 AND NOT EXISTS (SELECT 1 FROM projects WHERE tenant_id = $1)
+                 AND NOT EXISTS (SELECT 1 FROM repos WHERE tenant_id = $1)"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Whether a user may work in a tenant; everyone may work in the default one
+    pub async fn has_member(
+        pool: &SqlitePool,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        if id == Self::DEFAULT_ID {
+            return Ok(true);
+        }
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM tenant_members WHERE tenant_id = $1 AND user_id = $2) as "member!: bool""#,
+            id,
+            user_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_members(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Vec<TenantMember>, sqlx::Error> {
+        sqlx::query_as!(
+            TenantMember,
+            r#"SELECT tenant_id as "tenant_id!: Uuid", user_id as "user_id!: Uuid", created_at as "created_at!: DateTime<Utc>"
+               FROM tenant_members
+               WHERE tenant_id = $1
+               ORDER BY created_at ASC"#,
+            id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn add_member(
+        pool: &SqlitePool,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<TenantMember, sqlx::Error> {
+        sqlx::query_as!(
+            TenantMember,
+            r#"INSERT INTO tenant_members (tenant_id, user_id)
+               VALUES ($1, $2)
+               ON CONFLICT (tenant_id, user_id) DO UPDATE SET tenant_id = excluded.tenant_id
+               RETURNING tenant_id as "tenant_id!: Uuid", user_id as "user_id!: Uuid", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            user_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Remove a member unless they are the last one, so a tenant always has someone to manage it
+    pub async fn remove_member(
+        pool: &SqlitePool,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM tenant_members
+               WHERE tenant_id = $1
+                 AND user_id = $2
+                 AND (SELECT COUNT(*) FROM tenant_members WHERE tenant_id = $1) > 1"#,
+            id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Settings of a tenant other than the default one, as JSON; None until they are first saved
+    pub async fn find_config(pool: &SqlitePool, id: Uuid) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT config FROM tenant_configs WHERE tenant_id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn save_config(pool: &SqlitePool, id: Uuid, config: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO tenant_configs (tenant_id, config)
+               VALUES ($1, $2)
+               ON CONFLICT (tenant_id) DO UPDATE
+               SET config = excluded.config, updated_at = datetime('now', 'subsec')"#,
+            id,
+            config
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Tenant of a project; None when the project does not exist
+    pub async fn id_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT tenant_id as "tenant_id!: Uuid" FROM projects WHERE id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn owns_project(
+        pool: &SqlitePool,
+        id: Uuid,
+        project_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM projects WHERE id = $1 AND tenant_id = $2) as "owned!: bool""#,
+            project_id,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn owns_repo(
+        pool: &SqlitePool,
+        id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM repos WHERE id = $1 AND tenant_id = $2) as "owned!: bool""#,
+            repo_id,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn owns_task(
+        pool: &SqlitePool,
+        id: Uuid,
+        task_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                   SELECT 1 FROM tasks t
+                   JOIN projects p ON p.id = t.project_id
+                   WHERE t.id = $1 AND p.tenant_id = $2
+               ) as "owned!: bool""#,
+            task_id,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn owns_workspace(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                   SELECT 1 FROM workspaces w
+                   JOIN tasks t ON t.id = w.task_id
+                   JOIN projects p ON p.id = t.project_id
+                   WHERE w.id = $1 AND p.tenant_id = $2
+               ) as "owned!: bool""#,
+            workspace_id,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn owns_session(
+        pool: &SqlitePool,
+        id: Uuid,
+        session_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                   SELECT 1 FROM sessions s
+                   JOIN workspaces w ON w.id = s.workspace_id
+                   JOIN tasks t ON t.id = w.task_id
+                   JOIN projects p ON p.id = t.project_id
+                   WHERE s.id = $1 AND p.tenant_id = $2
+               ) as "owned!: bool""#,
+            session_id,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn owns_execution_process(
+        pool: &SqlitePool,
+        id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                   SELECT 1 FROM execution_processes ep
+                   JOIN sessions s ON s.id = ep.session_id
+                   JOIN workspaces w ON w.id = s.workspace_id
+                   JOIN tasks t ON t.id = w.task_id
+                   JOIN projects p ON p.id = t.project_id
+                   WHERE ep.id = $1 AND p.tenant_id = $2
+               ) as "owned!: bool""#,
+            execution_process_id,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
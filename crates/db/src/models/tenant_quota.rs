@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::stored_object::StoredObject;
+
+/// Usage limits of a tenant; `None` means no limit
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TenantQuota {
+    pub tenant_id: Uuid,
+    /// Coding agent runs allowed at the same time
+    pub max_concurrent_executions: Option<i64>,
+    /// Estimated coding agent spend allowed per calendar month (UTC)
+    pub monthly_spend_limit: Option<f64>,
+    /// Bytes allowed in object storage: attachments, execution artifacts and exported transcripts
+    pub storage_limit_bytes: Option<i64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateTenantQuota {
+    pub max_concurrent_executions: Option<i64>,
+    pub monthly_spend_limit: Option<f64>,
+    pub storage_limit_bytes: Option<i64>,
+}
+
+/// What a tenant currently uses of each quota
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct TenantUsage {
+    pub running_executions: i64,
+    /// Estimated coding agent spend since the start of the month (UTC)
+    pub monthly_spend: f64,
+    /// Bytes in object storage
+    pub storage_bytes: i64,
+}
+
+impl TenantQuota {
+    pub async fn find_by_tenant_id(
+        pool: &SqlitePool,
+        tenant_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TenantQuota,
+            r#"SELECT tenant_id as "tenant_id!: Uuid", max_concurrent_executions, monthly_spend_limit, storage_limit_bytes, updated_at as "updated_at!: DateTime<Utc>"
+               FROM tenant_quotas
+               WHERE tenant_id = $1"#,
+            tenant_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Replace the quotas of a tenant; limits left out are removed
+    pub async fn upsert(
+        pool: &SqlitePool,
+        tenant_id: Uuid,
+        data: &UpdateTenantQuota,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TenantQuota,
+            r#"INSERT INTO tenant_quotas (tenant_id, max_concurrent_executions, monthly_spend_limit, storage_limit_bytes)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(tenant_id) DO UPDATE SET
+                   max_concurrent_executions = excluded.max_concurrent_executions,
+                   monthly_spend_limit = excluded.monthly_spend_limit,
+                   storage_limit_bytes = excluded.storage_limit_bytes,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING tenant_id as "tenant_id!: Uuid", max_concurrent_executions, monthly_spend_limit, storage_limit_bytes, updated_at as "updated_at!: DateTime<Utc>""#,
+            tenant_id,
+            data.max_concurrent_executions,
+            data.monthly_spend_limit,
+            data.storage_limit_bytes
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
+
+impl TenantUsage {
+    pub async fn for_tenant(pool: &SqlitePool, tenant_id: Uuid) -> Result<Self, sqlx::Error> {
+        let running_executions = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               JOIN projects p ON p.id = t.project_id
+               WHERE p.tenant_id = $1
+                 AND ep.status = 'running'
+                 AND ep.run_reason = 'codingagent'"#,
+            tenant_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let monthly_spend = sqlx::query_scalar!(
+            r#"SELECT COALESCE(SUM(rd.estimated_cost), 0.0) as "total!: f64"
+               FROM routing_decisions rd
+               JOIN workspaces w ON w.id = rd.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               JOIN projects p ON p.id = t.project_id
+               WHERE p.tenant_id = $1
+                 AND rd.created_at >= datetime('now', 'start of month')"#,
+            tenant_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let storage_bytes = StoredObject::total_for_tenant(pool, tenant_id).await?;
+
+        Ok(Self {
+            running_executions,
+            monthly_spend,
+            storage_bytes,
+        })
+    }
+}
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
+use utils::response::KeysetPage;
 use uuid::Uuid;
 
 use super::{
@@ -53,6 +54,8 @@ pub struct Workspace {
     pub agent_working_dir: Option<String>,
     pub git_provider: Option<String>,
     pub setup_completed_at: Option<DateTime<Utc>>,
+    /// When new pull request review comments started becoming follow-ups; None when they don't
+    pub review_follow_ups_enabled_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -104,6 +107,17 @@ impl Workspace {
         pool: &SqlitePool,
         task_id: Option<Uuid>,
     ) -> Result<Vec<Self>, WorkspaceError> {
+        Self::fetch_page(pool, task_id, KeysetPage::ALL).await
+    }
+
+    /// One page of the workspaces, optionally filtered by task_id. Newest first.
+    pub async fn fetch_page(
+        pool: &SqlitePool,
+        task_id: Option<Uuid>,
+        page: KeysetPage,
+    ) -> Result<Vec<Self>, WorkspaceError> {
+        let after_created_at = page.after.map(|after| after.created_at);
+        let after_id = page.after.map(|after| after.id);
         let workspaces = match task_id {
             Some(tid) => sqlx::query_as!(
                 Workspace,
@@ -114,12 +128,20 @@ impl Workspace {
                               agent_working_dir,
                               git_provider,
                               setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                              review_follow_ups_enabled_at AS "review_follow_ups_enabled_at: DateTime<Utc>",
                               created_at AS "created_at!: DateTime<Utc>",
                               updated_at AS "updated_at!: DateTime<Utc>"
                        FROM workspaces
                        WHERE task_id = $1
-                       ORDER BY created_at DESC"#,
-                tid
+                         AND ($2 IS NULL
+                              OR julianday(created_at) < julianday($2)
+                              OR (julianday(created_at) = julianday($2) AND id < $3))
+                       ORDER BY julianday(created_at) DESC, id DESC
+                       LIMIT $4"#,
+                tid,
+                after_created_at,
+                after_id,
+                page.fetch
             )
             .fetch_all(pool)
             .await
@@ -133,10 +155,18 @@ impl Workspace {
                               agent_working_dir,
                               git_provider,
                               setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                              review_follow_ups_enabled_at AS "review_follow_ups_enabled_at: DateTime<Utc>",
                               created_at AS "created_at!: DateTime<Utc>",
                               updated_at AS "updated_at!: DateTime<Utc>"
                        FROM workspaces
-                       ORDER BY created_at DESC"#
+                       WHERE $1 IS NULL
+                          OR julianday(created_at) < julianday($1)
+                          OR (julianday(created_at) = julianday($1) AND id < $2)
+                       ORDER BY julianday(created_at) DESC, id DESC
+                       LIMIT $3"#,
+                after_created_at,
+                after_id,
+                page.fetch
             )
             .fetch_all(pool)
             .await
@@ -146,6 +176,15 @@ impl Workspace {
         Ok(workspaces)
     }
 
+    pub async fn count(pool: &SqlitePool, task_id: Option<Uuid>) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM workspaces WHERE $1 IS NULL OR task_id = $1"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
     /// Load workspace with full validation - ensures workspace belongs to task and task belongs to project
     pub async fn load_context(
         pool: &SqlitePool,
@@ -162,6 +201,7 @@ impl Workspace {
                        w.agent_working_dir,
                        w.git_provider,
                        w.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       w.review_follow_ups_enabled_at AS "review_follow_ups_enabled_at: DateTime<Utc>",
                        w.created_at        AS "created_at!: DateTime<Utc>",
                        w.updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    workspaces w
@@ -237,6 +277,7 @@ impl Workspace {
                        agent_working_dir,
                        git_provider,
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       review_follow_ups_enabled_at AS "review_follow_ups_enabled_at: DateTime<Utc>",
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    workspaces
@@ -257,6 +298,7 @@ impl Workspace {
                        agent_working_dir,
                        git_provider,
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       review_follow_ups_enabled_at AS "review_follow_ups_enabled_at: DateTime<Utc>",
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    workspaces
@@ -296,6 +338,7 @@ impl Workspace {
                 w.agent_working_dir,
                 w.git_provider,
                 w.setup_completed_at as "setup_completed_at: DateTime<Utc>",
+                w.review_follow_ups_enabled_at as "review_follow_ups_enabled_at: DateTime<Utc>",
                 w.created_at as "created_at!: DateTime<Utc>",
                 w.updated_at as "updated_at!: DateTime<Utc>"
             FROM workspaces w
@@ -339,7 +382,7 @@ impl Workspace {
             Workspace,
             r#"INSERT INTO workspaces (id, task_id, container_ref, branch, agent_working_dir, git_provider, setup_completed_at)
                VALUES ($1, $2, $3, $4, $5, $6, $7)
-               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, agent_working_dir, git_provider, setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, agent_working_dir, git_provider, setup_completed_at as "setup_completed_at: DateTime<Utc>", review_follow_ups_enabled_at as "review_follow_ups_enabled_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             task_id,
             Option::<String>::None,
@@ -352,19 +395,57 @@ impl Workspace {
         .await?)
     }
 
+    /// Returns false when the workspace is gone or no longer has `expected_updated_at`
     pub async fn update_branch_name(
         pool: &SqlitePool,
         workspace_id: Uuid,
         new_branch_name: &str,
-    ) -> Result<(), WorkspaceError> {
-        sqlx::query!(
-            "UPDATE workspaces SET branch = $1, updated_at = datetime('now') WHERE id = $2",
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, WorkspaceError> {
+        let result = sqlx::query!(
+            r#"UPDATE workspaces SET branch = $1, updated_at = datetime('now', 'subsec')
+               WHERE id = $2 AND ($3 IS NULL OR julianday(updated_at) = julianday($3))"#,
             new_branch_name,
             workspace_id,
+            expected_updated_at
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Bump `updated_at` for a change to the workspace's repos, like a new target branch.
+    /// Returns false when the workspace is gone or no longer has `expected_updated_at`.
+    pub async fn touch(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE workspaces SET updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND ($2 IS NULL OR julianday(updated_at) = julianday($2))"#,
+            workspace_id,
+            expected_updated_at
         )
         .execute(pool)
         .await?;
+        Ok(result.rows_affected() > 0)
+    }
 
+    /// Opt into follow-ups from review comments posted after `enabled_at`, or out with None
+    pub async fn set_review_follow_ups_enabled_at(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        enabled_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspaces SET review_follow_ups_enabled_at = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            enabled_at,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
         Ok(())
     }
 
@@ -6,7 +6,7 @@ use sqlx::{FromRow, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::repo::Repo;
+use super::{repo::Repo, task::TaskStatus};
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct WorkspaceRepo {
@@ -34,6 +34,27 @@ pub struct RepoWithTargetBranch {
     pub target_branch: String,
 }
 
+/// The branch of a workspace in one repository, for workspaces that have worktrees
+#[derive(Debug, Clone)]
+pub struct WorkspaceBranch {
+    pub workspace_id: Uuid,
+    pub branch: String,
+    pub target_branch: String,
+}
+
+/// A workspace branch together with the task it was created for
+#[derive(Debug, Clone)]
+pub struct TaskBranch {
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub task_status: TaskStatus,
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub branch: String,
+    pub target_branch: String,
+}
+
 /// Repo info with copy_files configuration from project_repos.
 #[derive(Debug, Clone)]
 pub struct RepoWithCopyFiles {
@@ -241,6 +262,65 @@ impl WorkspaceRepo {
         .await
     }
 
+    /// Number of workspaces that currently have a worktree of the repo on disk
+    pub async fn count_workspaces_with_worktrees(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM workspace_repos wr
+               JOIN workspaces w ON w.id = wr.workspace_id
+               WHERE wr.repo_id = $1 AND w.container_ref IS NOT NULL"#,
+            repo_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_branches_with_worktrees(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<WorkspaceBranch>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceBranch,
+            r#"SELECT w.id as "workspace_id!: Uuid", w.branch, wr.target_branch
+               FROM workspace_repos wr
+               JOIN workspaces w ON w.id = wr.workspace_id
+               WHERE wr.repo_id = $1 AND w.container_ref IS NOT NULL"#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Branches of all workspaces, newest first, optionally only those in `repo_id`
+    pub async fn find_task_branches(
+        pool: &SqlitePool,
+        repo_id: Option<Uuid>,
+    ) -> Result<Vec<TaskBranch>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskBranch,
+            r#"SELECT w.id as "workspace_id!: Uuid",
+                      t.id as "task_id!: Uuid",
+                      t.title as task_title,
+                      t.status as "task_status!: TaskStatus",
+                      r.id as "repo_id!: Uuid",
+                      r.name as repo_name,
+                      w.branch,
+                      wr.target_branch
+               FROM workspace_repos wr
+               JOIN workspaces w ON w.id = wr.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               JOIN repos r ON r.id = wr.repo_id
+               WHERE $1 IS NULL OR wr.repo_id = $1
+               ORDER BY w.created_at DESC"#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Find repos for a workspace with their copy_files configuration.
     /// Uses LEFT JOIN so repos without project_repo entries still appear (with NULL copy_files).
     pub async fn find_repos_with_copy_files(
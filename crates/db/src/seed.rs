@@ -0,0 +1,106 @@
+//! Fixed data for test mode.
+//!
+//! Seeds an empty database with one project, its repository and a task in each column of the
+//! board, under ids and timestamps that are the same on every run, so end-to-end tests can
+//! address them directly. The queries are not checked at compile time, like those of the import.
+
+use std::path::Path;
+
+use sqlx::SqlitePool;
+use utils::test_mode::frozen_time;
+use uuid::Uuid;
+
+use crate::models::task::TaskStatus;
+
+pub const PROJECT_ID: Uuid = Uuid::from_u128(0x7e57_0000_0000_4000_8000_0000_0000_0001);
+pub const REPO_ID: Uuid = Uuid::from_u128(0x7e57_0000_0000_4000_8000_0000_0000_0002);
+const PROJECT_REPO_ID: Uuid = Uuid::from_u128(0x7e57_0000_0000_4000_8000_0000_0000_0003);
+
+/// Tasks of the seeded project: id, title, description and column
+pub const TASKS: &[(Uuid, &str, &str, TaskStatus)] = &[
+    (
+        Uuid::from_u128(0x7e57_0000_0000_4000_8000_0000_0001_0001),
+        "Add a README",
+        "Describe what the demo repository is for.",
+        TaskStatus::Todo,
+    ),
+    (
+        Uuid::from_u128(0x7e57_0000_0000_4000_8000_0000_0001_0002),
+        "Add a license",
+        "Pick a license and add it at the root.",
+        TaskStatus::InProgress,
+    ),
+    (
+        Uuid::from_u128(0x7e57_0000_0000_4000_8000_0000_0001_0003),
+        "Set up CI",
+        "Run the checks on every push.",
+        TaskStatus::InReview,
+    ),
+    (
+        Uuid::from_u128(0x7e57_0000_0000_4000_8000_0000_0001_0004),
+        "Initial commit",
+        "Create the repository.",
+        TaskStatus::Done,
+    ),
+];
+
+/// Insert the fixed data into a database without projects. Returns whether anything was
+/// inserted; a database that already has projects is left alone, so restarting a test server on
+/// the same data directory keeps what the tests did.
+pub async fn seed(pool: &SqlitePool, repo_path: &Path) -> Result<bool, sqlx::Error> {
+    let projects: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM projects")
+        .fetch_one(pool)
+        .await?;
+    if projects > 0 {
+        return Ok(false);
+    }
+
+    let at = frozen_time();
+    let name = repo_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "demo".to_string());
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "INSERT INTO repos (id, path, name, display_name, created_at, updated_at)
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(REPO_ID)
+    .bind(repo_path.to_string_lossy().to_string())
+    .bind(&name)
+    .bind(&name)
+    .bind(at)
+    .bind(at)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("INSERT INTO projects (id, name, created_at, updated_at) VALUES ($1, $2, $3, $4)")
+        .bind(PROJECT_ID)
+        .bind("Demo project")
+        .bind(at)
+        .bind(at)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("INSERT INTO project_repos (id, project_id, repo_id) VALUES ($1, $2, $3)")
+        .bind(PROJECT_REPO_ID)
+        .bind(PROJECT_ID)
+        .bind(REPO_ID)
+        .execute(&mut *tx)
+        .await?;
+    for (id, title, description, status) in TASKS {
+        sqlx::query(
+            "INSERT INTO tasks (id, project_id, title, description, status, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(id)
+        .bind(PROJECT_ID)
+        .bind(title)
+        .bind(description)
+        .bind(status)
+        .bind(at)
+        .bind(at)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(true)
+}
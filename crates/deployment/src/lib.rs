@@ -8,6 +8,7 @@ use db::{
     models::{
         project::{CreateProject, Project},
         project_repo::CreateProjectRepo,
+        tenant::Tenant,
         workspace::WorkspaceError,
     },
 };
@@ -18,7 +19,9 @@ use serde_json::Value;
 use services::services::{
     analytics::{AnalyticsContext, AnalyticsService},
     approvals::Approvals,
+    artifacts::{ArtifactService, STORE_EXECUTION_LOGS_JOB},
     auth::AuthContext,
+    commit_status::PUBLISH_AGENT_RUN_STATUS_JOB,
     config::{Config, ConfigError},
     container::{ContainerError, ContainerService},
     events::{EventError, EventService},
@@ -26,12 +29,18 @@ use services::services::{
     filesystem::{FilesystemError, FilesystemService},
     filesystem_watcher::FilesystemWatcherError,
     git::{GitService, GitServiceError},
-    image::{ImageError, ImageService},
-    pr_monitor::PrMonitorService,
+    image::{DELETE_ORPHANED_IMAGES_JOB, ImageError, ImageService},
+    jobs::{JobQueue, JobRunner},
+    notification::DELIVER_NOTIFICATION_JOB,
+    object_store::{ObjectStore, StorageError},
+    offline::{OfflineService, REPLAY_PROVIDER_WRITE_JOB},
+    pr_monitor::{PR_MONITOR_INTERVAL, PR_MONITOR_JOB, PrMonitorService},
     project::ProjectService,
     queued_message::QueuedMessageService,
     repo::RepoService,
+    repo_fetch::{REPO_FETCH_INTERVAL, REPO_FETCH_JOB, RepoFetchScheduler},
     share::SharePublisher,
+    task_events::{PUBLISH_TASK_EVENT_JOB, READY_PRS_FOR_REVIEW_JOB, TaskEventLog},
     worktree_manager::WorktreeError,
 };
 use sqlx::Error as SqlxError;
@@ -64,6 +73,8 @@ pub enum DeploymentError {
     #[error(transparent)]
     Image(#[from] ImageError),
     #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
     Filesystem(#[from] FilesystemError),
     #[error(transparent)]
     Worktree(#[from] WorktreeError),
@@ -99,6 +110,11 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn image(&self) -> &ImageService;
 
+    /// Object storage for attachments, execution artifacts and exported transcripts
+    fn storage(&self) -> &Arc<dyn ObjectStore>;
+
+    fn artifacts(&self) -> &ArtifactService;
+
     fn filesystem(&self) -> &FilesystemService;
 
     fn events(&self) -> &EventService;
@@ -113,6 +129,16 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn share_publisher(&self) -> Result<SharePublisher, RemoteClientNotConfigured>;
 
+    fn jobs(&self) -> &JobQueue;
+
+    /// Provider calls that serve cached reads and queue writes while the provider is unreachable
+    fn offline(&self) -> &OfflineService;
+
+    /// The task event log, where every change to a task is recorded
+    fn task_events(&self) -> &TaskEventLog {
+        self.container().task_events()
+    }
+
     async fn update_sentry_scope(&self) -> Result<(), DeploymentError> {
         let user_id = self.user_id();
         let config = self.config().read().await;
@@ -123,8 +149,9 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         Ok(())
     }
 
-    async fn spawn_pr_monitor_service(&self) -> tokio::task::JoinHandle<()> {
-        let db = self.db().clone();
+    /// Job runner with the handlers of the built-in background work; callers can register more
+    /// before spawning it
+    fn job_runner(&self) -> JobRunner {
         let analytics = self
             .analytics()
             .as_ref()
@@ -132,8 +159,54 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 user_id: self.user_id().to_string(),
                 analytics_service: analytics_service.clone(),
             });
+        let pr_monitor =
+            PrMonitorService::new(self.db().clone(), analytics, self.task_events().clone());
+        let repo_fetch = RepoFetchScheduler::new(
+            self.db().clone(),
+            self.repo().clone(),
+            self.config().clone(),
+        );
+        let notifications = self.container().notification_service().clone();
+        let image = self.image().clone();
+        let artifacts = self.artifacts().clone();
+        let db = self.db().clone();
+        let ready_prs_db = self.db().clone();
         let publisher = self.share_publisher().ok();
-        PrMonitorService::spawn(db, analytics, publisher).await
+        let offline = self.offline().clone();
+        let commit_statuses = self.container().commit_statuses().clone();
+
+        JobRunner::new(self.jobs().clone())
+            .recurring(PR_MONITOR_JOB, PR_MONITOR_INTERVAL, pr_monitor)
+            .recurring(REPO_FETCH_JOB, REPO_FETCH_INTERVAL, repo_fetch)
+            .handler(DELIVER_NOTIFICATION_JOB, move |payload: Value| {
+                let notifications = notifications.clone();
+                async move { notifications.deliver_job(payload).await }
+            })
+            .handler(DELETE_ORPHANED_IMAGES_JOB, move |_: Value| {
+                let image = image.clone();
+                async move { Ok::<_, anyhow::Error>(image.delete_orphaned_images().await?) }
+            })
+            .handler(STORE_EXECUTION_LOGS_JOB, move |payload: Value| {
+                let artifacts = artifacts.clone();
+                async move { artifacts.store_execution_logs_job(payload).await }
+            })
+            .handler(PUBLISH_TASK_EVENT_JOB, move |payload: Value| {
+                let db = db.clone();
+                let publisher = publisher.clone();
+                async move { TaskEventLog::publish_job(&db, publisher.as_ref(), payload).await }
+            })
+            .handler(READY_PRS_FOR_REVIEW_JOB, move |payload: Value| {
+                let db = ready_prs_db.clone();
+                async move { TaskEventLog::ready_prs_for_review_job(&db, payload).await }
+            })
+            .handler(REPLAY_PROVIDER_WRITE_JOB, move |payload: Value| {
+                let offline = offline.clone();
+                async move { offline.replay_job(payload).await }
+            })
+            .handler(PUBLISH_AGENT_RUN_STATUS_JOB, move |payload: Value| {
+                let commit_statuses = commit_statuses.clone();
+                async move { commit_statuses.publish_job(payload).await }
+            })
     }
 
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
@@ -176,7 +249,12 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
                     match self
                         .project()
-                        .create_project(&self.db().pool, self.repo(), create_data.clone())
+                        .create_project(
+                            &self.db().pool,
+                            self.repo(),
+                            create_data.clone(),
+                            Tenant::DEFAULT_ID,
+                        )
                         .await
                     {
                         Ok(project) => {
@@ -210,12 +288,13 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         }
     }
 
+    /// Updates of every record of a tenant, history first
     async fn stream_events(
         &self,
+        tenant: &Tenant,
     ) -> futures::stream::BoxStream<'static, Result<Event, std::io::Error>> {
         self.events()
-            .msg_store()
-            .history_plus_stream()
+            .stream_events_for_tenant(tenant.id)
             .map_ok(|m| m.to_sse_event())
             .boxed()
     }
@@ -3,6 +3,7 @@ use std::{path::Path, sync::Arc};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
+use workspace_utils::path::join_relative;
 
 use crate::{
     actions::Executable,
@@ -24,6 +25,9 @@ pub struct CodingAgentFollowUpRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Run with a read-only tool policy, for questions that should not change the workspace
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 impl CodingAgentFollowUpRequest {
@@ -46,7 +50,7 @@ impl Executable for CodingAgentFollowUpRequest {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let effective_dir = match &self.working_dir {
-            Some(rel_path) => current_dir.join(rel_path),
+            Some(rel_path) => join_relative(current_dir, rel_path),
             None => current_dir.to_path_buf(),
         };
 
@@ -57,6 +61,12 @@ impl Executable for CodingAgentFollowUpRequest {
                 executor_profile_id.to_string(),
             ))?;
 
+        if self.read_only {
+            agent = agent.into_read_only().ok_or_else(|| {
+                ExecutorError::ReadOnlyNotSupported(executor_profile_id.to_string())
+            })?;
+        }
+
         agent.use_approvals(approvals.clone());
 
         agent
@@ -3,6 +3,7 @@ use std::{path::Path, sync::Arc};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
+use workspace_utils::path::join_relative;
 
 use crate::{
     actions::Executable,
@@ -41,7 +42,7 @@ impl Executable for CodingAgentInitialRequest {
     ) -> Result<SpawnedChild, ExecutorError> {
         // Use working_dir if specified, otherwise use current_dir
         let effective_dir = match &self.working_dir {
-            Some(rel_path) => current_dir.join(rel_path),
+            Some(rel_path) => join_relative(current_dir, rel_path),
             None => current_dir.to_path_buf(),
         };
 
@@ -13,6 +13,7 @@ use crate::{
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
     executors::{BaseCodingAgent, ExecutorError, SpawnedChild},
+    profile::ExecutorProfileId,
 };
 pub mod coding_agent_follow_up;
 pub mod coding_agent_initial;
@@ -63,6 +64,65 @@ impl ExecutorAction {
             ExecutorActionType::ScriptRequest(_) => None,
         }
     }
+
+    pub fn executor_profile_id(&self) -> Option<ExecutorProfileId> {
+        match self.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                Some(request.executor_profile_id.clone())
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                Some(request.get_executor_profile_id())
+            }
+            ExecutorActionType::ScriptRequest(_) => None,
+        }
+    }
+
+    /// Whether this action runs the agent without permission to modify the workspace
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self.typ(),
+            ExecutorActionType::CodingAgentFollowUpRequest(request) if request.read_only
+        )
+    }
+
+    /// The prompt of this action, if it is a coding agent request
+    pub fn prompt(&self) -> Option<&str> {
+        match self.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => Some(&request.prompt),
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => Some(&request.prompt),
+            ExecutorActionType::ScriptRequest(_) => None,
+        }
+    }
+
+    /// Run this action's coding agent request with a different profile; the rest of the chain is
+    /// left as is
+    pub fn with_executor_profile_id(mut self, executor_profile_id: ExecutorProfileId) -> Self {
+        match &mut self.typ {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                request.executor_profile_id = executor_profile_id;
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                request.executor_profile_id = executor_profile_id;
+            }
+            ExecutorActionType::ScriptRequest(_) => {}
+        }
+        self
+    }
+
+    /// Rewrite the prompt of every coding agent request in this action chain
+    pub fn map_prompts(mut self, f: &impl Fn(String) -> String) -> Self {
+        match &mut self.typ {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                request.prompt = f(std::mem::take(&mut request.prompt));
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                request.prompt = f(std::mem::take(&mut request.prompt));
+            }
+            ExecutorActionType::ScriptRequest(_) => {}
+        }
+        self.next_action = self.next_action.map(|next| Box::new(next.map_prompts(f)));
+        self
+    }
 }
 
 #[async_trait]
@@ -5,7 +5,7 @@ use command_group::AsyncCommandGroup;
 use serde::{Deserialize, Serialize};
 use tokio::process::Command;
 use ts_rs::TS;
-use workspace_utils::shell::get_shell_command;
+use workspace_utils::{path::join_relative, shell::get_script_shell_command};
 
 use crate::{
     actions::Executable,
@@ -48,11 +48,11 @@ impl Executable for ScriptRequest {
     ) -> Result<SpawnedChild, ExecutorError> {
         // Use working_dir if specified, otherwise use current_dir
         let effective_dir = match &self.working_dir {
-            Some(rel_path) => current_dir.join(rel_path),
+            Some(rel_path) => join_relative(current_dir, rel_path),
             None => current_dir.to_path_buf(),
         };
 
-        let (shell_cmd, shell_arg) = get_shell_command();
+        let (shell_cmd, shell_arg) = get_script_shell_command(&self.script).await;
         let mut command = Command::new(shell_cmd);
         command
             .kill_on_drop(true)
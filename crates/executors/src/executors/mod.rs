@@ -70,6 +70,8 @@ pub enum ExecutorError {
     SetupHelperNotSupported,
     #[error("Auth required: {0}")]
     AuthRequired(String),
+    #[error("Read-only mode is not supported by {0}")]
+    ReadOnlyNotSupported(String),
 }
 
 #[enum_dispatch]
@@ -169,6 +171,34 @@ impl CodingAgent {
             Self::Copilot(_) => vec![],
         }
     }
+
+    /// Reconfigure the agent so it can read the workspace but not modify it, for answering
+    /// questions about its work. Returns `None` when the agent has no reliable way to enforce
+    /// this.
+    pub fn into_read_only(self) -> Option<Self> {
+        match self {
+            Self::ClaudeCode(mut agent) => {
+                agent.plan = Some(true);
+                agent.approvals = Some(false);
+                agent.dangerously_skip_permissions = Some(false);
+                Some(Self::ClaudeCode(agent))
+            }
+            Self::Codex(mut agent) => {
+                agent.sandbox = Some(codex::SandboxMode::ReadOnly);
+                agent.ask_for_approval = Some(codex::AskForApproval::Never);
+                Some(Self::Codex(agent))
+            }
+            // Gemini and Qwen without yolo, Cursor without force and Droid at normal autonomy can
+            // still modify files, so none of them has a mode that keeps them read-only
+            Self::Gemini(_)
+            | Self::QwenCode(_)
+            | Self::CursorAgent(_)
+            | Self::Droid(_)
+            | Self::Amp(_)
+            | Self::Opencode(_)
+            | Self::Copilot(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
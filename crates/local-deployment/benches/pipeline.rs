@@ -0,0 +1,280 @@
+//! Load benchmarks of the execution pipeline
+//!
+//! Runs a real [`LocalDeployment`] in test mode, on a fresh data directory with the seeded demo
+//! project and [`TASKS`] tasks on its board. Executions are setup scripts that exit right away,
+//! so the numbers measure the container service and the database rather than an agent.
+//!
+//! `cargo bench -p local-deployment --bench pipeline` runs them; `scripts/check-bench.sh`
+//! compares a branch against its base and fails on regressions.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use db::{
+    models::{
+        execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+        session::{CreateSession, Session},
+        task::{CreateTask, Task},
+        workspace::{CreateWorkspace, Workspace},
+        workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+    },
+    seed,
+};
+use deployment::Deployment;
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use futures::future::join_all;
+use local_deployment::LocalDeployment;
+use services::services::container::ContainerService;
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+use utils::{assets::set_data_dir, msg_store::MsgStore, test_mode};
+use uuid::Uuid;
+
+/// Tasks on the benchmarked board
+const TASKS: usize = 1_000;
+/// Executions started at once
+const CONCURRENT_EXECUTIONS: usize = 100;
+/// Log lines written per ingestion run
+const LOG_LINES: usize = 1_000;
+
+struct Fixture {
+    deployment: LocalDeployment,
+    tasks: Vec<Uuid>,
+    /// Workspaces with a session each, to run the concurrent executions in
+    sessions: Vec<(Workspace, Session)>,
+    /// A finished execution whose logs the ingestion benchmark appends to
+    execution_id: Uuid,
+    _data_dir: TempDir,
+}
+
+async fn fixture() -> Fixture {
+    let data_dir = TempDir::new().unwrap();
+    set_data_dir(data_dir.path().to_path_buf());
+    test_mode::enable();
+
+    let deployment = LocalDeployment::new().await.unwrap();
+    let repo_path = data_dir.path().join("bench-repo");
+    deployment
+        .git()
+        .initialize_repo_with_main_branch(&repo_path)
+        .unwrap();
+    seed::seed(&deployment.db().pool, &repo_path).await.unwrap();
+
+    let mut tasks = Vec::with_capacity(TASKS);
+    for i in 0..TASKS {
+        let task = Task::create(
+            &deployment.db().pool,
+            &CreateTask::from_title_description(
+                seed::PROJECT_ID,
+                format!("Benchmark task {i}"),
+                None,
+            ),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        tasks.push(task.id);
+    }
+
+    let mut sessions = Vec::with_capacity(CONCURRENT_EXECUTIONS);
+    for task_id in tasks.iter().take(CONCURRENT_EXECUTIONS) {
+        let workspace = create_workspace(&deployment, *task_id).await;
+        let session = Session::create(
+            &deployment.db().pool,
+            &CreateSession { executor: None },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await
+        .unwrap();
+        sessions.push((workspace, session));
+    }
+
+    let (workspace, session) = &sessions[0];
+    let execution = deployment
+        .container()
+        .start_execution(
+            workspace,
+            session,
+            &noop_action(),
+            &ExecutionProcessRunReason::SetupScript,
+        )
+        .await
+        .unwrap();
+    wait_for_exit(&deployment, &[execution.id]).await;
+
+    Fixture {
+        deployment,
+        tasks,
+        sessions,
+        execution_id: execution.id,
+        _data_dir: data_dir,
+    }
+}
+
+/// A workspace of `task_id` on the demo repository, with its worktree created
+async fn create_workspace(deployment: &LocalDeployment, task_id: Uuid) -> Workspace {
+    let pool = &deployment.db().pool;
+    let id = Uuid::new_v4();
+    let workspace = Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: format!("bench/{id}"),
+            agent_working_dir: None,
+        },
+        id,
+        task_id,
+    )
+    .await
+    .unwrap();
+    WorkspaceRepo::create_many(
+        pool,
+        workspace.id,
+        &[CreateWorkspaceRepo {
+            repo_id: seed::REPO_ID,
+            target_branch: "main".to_string(),
+        }],
+    )
+    .await
+    .unwrap();
+    deployment.container().create(&workspace).await.unwrap();
+    Workspace::find_by_id(pool, id).await.unwrap().unwrap()
+}
+
+/// The synthetic executor: a setup script that does nothing
+fn noop_action() -> ExecutorAction {
+    ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            script: "true".to_string(),
+            language: ScriptRequestLanguage::Bash,
+            context: ScriptContext::SetupScript,
+            working_dir: None,
+        }),
+        None,
+    )
+}
+
+async fn wait_for_exit(deployment: &LocalDeployment, execution_ids: &[Uuid]) {
+    for id in execution_ids {
+        while ExecutionProcess::find_by_id(&deployment.db().pool, *id)
+            .await
+            .unwrap()
+            .is_some_and(|process| process.status == ExecutionProcessStatus::Running)
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+}
+
+/// Start an execution in every fixture workspace at once and wait for all of them to exit.
+/// Returns the time until all were started, and until all had exited.
+async fn run_concurrent_executions(fixture: &Fixture) -> (Duration, Duration) {
+    let action = noop_action();
+    let start = Instant::now();
+    let executions = join_all(fixture.sessions.iter().map(|(workspace, session)| {
+        fixture.deployment.container().start_execution(
+            workspace,
+            session,
+            &action,
+            &ExecutionProcessRunReason::SetupScript,
+        )
+    }))
+    .await;
+    let scheduled = start.elapsed();
+    let ids: Vec<Uuid> = executions
+        .into_iter()
+        .map(|execution| execution.unwrap().id)
+        .collect();
+    wait_for_exit(&fixture.deployment, &ids).await;
+    (scheduled, start.elapsed())
+}
+
+fn pipeline(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let fixture = &runtime.block_on(fixture());
+    let pool = &fixture.deployment.db().pool;
+
+    let mut group = c.benchmark_group("pipeline");
+    group.sample_size(10);
+
+    group.bench_function("board_query", |b| {
+        b.to_async(&runtime).iter(|| async {
+            Task::find_by_project_id_with_attempt_status(pool, seed::PROJECT_ID)
+                .await
+                .unwrap()
+        })
+    });
+
+    let mut next_task = CONCURRENT_EXECUTIONS;
+    group.bench_function("workspace_creation", |b| {
+        b.to_async(&runtime).iter(|| {
+            // Spread the workspaces over the tasks without executions
+            let task_id = fixture.tasks[next_task];
+            next_task += 1;
+            if next_task == TASKS {
+                next_task = CONCURRENT_EXECUTIONS;
+            }
+            create_workspace(&fixture.deployment, task_id)
+        })
+    });
+
+    group.bench_function("execution_scheduling", |b| {
+        b.to_async(&runtime).iter_custom(|iters| async move {
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                total += run_concurrent_executions(fixture).await.0;
+            }
+            total
+        })
+    });
+
+    group.bench_function("execution_completion", |b| {
+        b.to_async(&runtime).iter_custom(|iters| async move {
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                total += run_concurrent_executions(fixture).await.1;
+            }
+            total
+        })
+    });
+
+    group.bench_function("log_ingestion", |b| {
+        b.to_async(&runtime).iter(|| async {
+            let container = fixture.deployment.container();
+            let store = Arc::new(MsgStore::new());
+            for i in 0..LOG_LINES {
+                store.push_stdout(format!("benchmark output line {i}\n"));
+            }
+            store.push_finished();
+            container
+                .msg_stores()
+                .write()
+                .await
+                .insert(fixture.execution_id, store);
+            container
+                .spawn_stream_raw_logs_to_db(&fixture.execution_id)
+                .await
+                .unwrap();
+        })
+    });
+
+    group.finish();
+
+    // Workspaces live outside the data directory, under the shared worktree directory
+    runtime.block_on(async {
+        for workspace in Workspace::fetch_all(pool, None).await.unwrap() {
+            if let Some(dir) = workspace.container_ref {
+                let _ = std::fs::remove_dir_all(dir);
+            }
+        }
+    });
+}
+
+criterion_group!(benches, pipeline);
+criterion_main!(benches);
@@ -13,14 +13,25 @@ use db::{
     DBService,
     models::{
         coding_agent_turn::CodingAgentTurn,
+        command_run::CommandKind,
+        coverage_report::{CoverageReport, CreateCoverageReport},
+        dependency_report::DependencyReport,
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
+        execution_scope::ExecutionScope,
+        lint_report::{LintFinding, LintReport, LintSeverity},
+        managed_repo::ManagedRepo,
+        merge::Merge,
+        policy_violation_report::{PolicyViolation, PolicyViolationKind, PolicyViolationReport},
         project_repo::ProjectRepo,
         repo::Repo,
+        repo_chain_step::{RepoChainStep, RepoChainStepStatus},
+        routing_decision::TurnPurpose,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
-        task::{Task, TaskStatus},
+        session::Session,
+        task::TaskStatus,
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
     },
@@ -30,7 +41,7 @@ use executors::{
     actions::{
         Executable, ExecutorAction, ExecutorActionType,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
-        coding_agent_initial::CodingAgentInitialRequest,
+        coding_agent_initial::CodingAgentInitialRequest, script::ScriptContext,
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::ExecutionEnv,
@@ -43,14 +54,33 @@ use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
+    artifacts::ArtifactService,
+    changes_summary::{self, TurnChanges},
+    circuit_breaker,
+    commit_status::CommitStatusService,
+    compaction::{CompactionConfig, RepoDiff},
     config::Config,
     container::{ContainerError, ContainerRef, ContainerService},
+    coverage,
+    dependency_policy::{self, DependencyChecker},
     diff_stream::{self, DiffStreamHandle},
-    git::{Commit, GitCli, GitService},
+    flaky::{self, CommandOutcome},
+    git::{Commit, DiffTarget, GitCli, GitService, GitServiceError},
     image::ImageService,
+    jobs::JobQueue,
+    lint,
     notification::NotificationService,
+    offline::OfflineService,
+    policy::{self, ExecutorPolicy, PolicyError},
     queued_message::QueuedMessageService,
+    replay,
+    repo::RepoService,
+    repo_chain::{self, EarlierStep},
+    routing::RoutingConfig,
+    scope,
     share::SharePublisher,
+    task_events::{TaskActor, TaskEventLog},
+    throttle::ExecutorThrottle,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
 use tokio::{sync::RwLock, task::JoinHandle};
@@ -78,6 +108,10 @@ pub struct LocalContainerService {
     queued_message_service: QueuedMessageService,
     publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     notification_service: NotificationService,
+    task_events: TaskEventLog,
+    commit_statuses: CommitStatusService,
+    artifacts: ArtifactService,
+    throttle: ExecutorThrottle,
 }
 
 impl LocalContainerService {
@@ -92,10 +126,16 @@ impl LocalContainerService {
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
         publisher: Result<SharePublisher, RemoteClientNotConfigured>,
+        jobs: JobQueue,
+        offline: OfflineService,
+        artifacts: ArtifactService,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
-        let notification_service = NotificationService::new(config.clone());
+        let notification_service = NotificationService::new(config.clone()).with_jobs(jobs.clone());
+        let task_events = TaskEventLog::new(db.clone(), jobs.clone());
+        let commit_statuses =
+            CommitStatusService::new(db.clone(), git.clone(), config.clone(), jobs, offline);
 
         let container = LocalContainerService {
             db,
@@ -110,6 +150,10 @@ impl LocalContainerService {
             queued_message_service,
             publisher,
             notification_service,
+            task_events,
+            commit_statuses,
+            artifacts,
+            throttle: ExecutorThrottle::default(),
         };
 
         container.spawn_workspace_cleanup().await;
@@ -338,6 +382,811 @@ impl LocalContainerService {
         any_committed
     }
 
+    /// Check the changes of a finished coding agent run against the executor policies and, for
+    /// scoped follow-ups that restrict writes, the selected files, before they are committed. On
+    /// a violation the changes are left uncommitted for review, the process is marked failed and
+    /// a report is stored. Returns whether the run was blocked.
+    async fn enforce_executor_policies(&self, ctx: &ExecutionContext) -> bool {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent
+        ) {
+            return false;
+        }
+        let policies = self.config.read().await.executor_policies.clone();
+        let Some(profile) = ctx
+            .execution_process
+            .executor_action()
+            .ok()
+            .and_then(|action| action.executor_profile_id())
+        else {
+            return false;
+        };
+        let exec_id = ctx.execution_process.id;
+        let scope = match ExecutionScope::find_by_execution_process_id(&self.db.pool, exec_id).await
+        {
+            Ok(scope) => scope.filter(|scope| scope.restrict_writes),
+            Err(e) => {
+                tracing::error!("Failed to load scope for execution {}: {}", exec_id, e);
+                None
+            }
+        };
+        if scope.is_none() && !policies.iter().any(|p| p.applies_to(&profile)) {
+            return false;
+        }
+
+        let violations = match self
+            .collect_policy_violations(ctx, &policies, &profile, scope.as_ref())
+            .await
+        {
+            Ok(violations) if violations.is_empty() => return false,
+            Ok(violations) => violations,
+            Err(e) => {
+                // Fail closed: changes that could not be inspected are not committed
+                tracing::error!("Policy check failed for execution {}: {}", exec_id, e);
+                self.push_stderr(exec_id, format!("Executor policy check failed: {e}"))
+                    .await;
+                self.fail_for_policy(exec_id).await;
+                return true;
+            }
+        };
+
+        tracing::warn!(
+            "Execution {} violated executor policy ({} paths)",
+            exec_id,
+            violations.len()
+        );
+        let summary = violations
+            .iter()
+            .map(|v| match &v.kind {
+                PolicyViolationKind::RepoNotAllowed => {
+                    format!("  {}/{} (repository not allowed)", v.repo, v.path)
+                }
+                PolicyViolationKind::ProtectedPath { pattern } => {
+                    format!("  {}/{} (protected by `{}`)", v.repo, v.path, pattern)
+                }
+                PolicyViolationKind::OutsideScope => {
+                    format!("  {}/{} (outside the follow-up scope)", v.repo, v.path)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.push_stderr(
+            exec_id,
+            format!(
+                "Changes were not committed because they violate the executor policy:\n{summary}\n"
+            ),
+        )
+        .await;
+
+        if let Err(e) = PolicyViolationReport::create(
+            &self.db.pool,
+            ctx.workspace.id,
+            Some(exec_id),
+            &violations,
+        )
+        .await
+        {
+            tracing::error!("Failed to store policy violation report: {}", e);
+        }
+        self.fail_for_policy(exec_id).await;
+
+        if self.config.read().await.analytics_enabled
+            && let Some(analytics) = &self.analytics
+        {
+            analytics.analytics_service.track_event(
+                &analytics.user_id,
+                "executor_policy_violated",
+                Some(json!({
+                    "workspace_id": ctx.workspace.id.to_string(),
+                    "executor": profile.executor.to_string(),
+                    "violation_count": violations.len(),
+                    "stage": "execution",
+                })),
+            );
+        }
+        true
+    }
+
+    async fn collect_policy_violations(
+        &self,
+        ctx: &ExecutionContext,
+        policies: &[ExecutorPolicy],
+        profile: &ExecutorProfileId,
+        scope: Option<&ExecutionScope>,
+    ) -> Result<Vec<PolicyViolation>, PolicyError> {
+        let workspace_root = ctx
+            .workspace
+            .container_ref
+            .as_ref()
+            .map(PathBuf::from)
+            .ok_or_else(|| {
+                PolicyError::GitService(GitServiceError::InvalidRepository(
+                    "Container reference not found".to_string(),
+                ))
+            })?;
+        let repo_states = ExecutionProcessRepoState::find_by_execution_process_id(
+            &self.db.pool,
+            ctx.execution_process.id,
+        )
+        .await?;
+
+        let mut violations = Vec::new();
+        for repo in &ctx.repos {
+            let Some(before) = repo_states
+                .iter()
+                .find(|state| state.repo_id == repo.id)
+                .and_then(|state| state.before_head_commit.as_deref())
+            else {
+                continue;
+            };
+            let base = Commit::from_sha(before)?;
+            let paths = policy::changed_paths(&self.git, &workspace_root.join(&repo.name), &base)?;
+            let mut repo_violations =
+                policy::check_policies(policies, profile, &repo.name, &paths)?;
+            if let Some(scope) = scope {
+                for violation in scope::check_scope(&scope.files, profile, &repo.name, &paths) {
+                    if !repo_violations.iter().any(|v| v.path == violation.path) {
+                        repo_violations.push(violation);
+                    }
+                }
+            }
+            violations.extend(repo_violations);
+        }
+        Ok(violations)
+    }
+
+    async fn push_stderr(&self, exec_id: Uuid, message: String) {
+        if let Some(store) = self.get_msg_store_by_id(&exec_id).await {
+            store.push_stderr(message);
+        }
+    }
+
+    async fn fail_for_policy(&self, exec_id: Uuid) {
+        if let Err(e) = ExecutionProcess::update_completion(
+            &self.db.pool,
+            exec_id,
+            ExecutionProcessStatus::Failed,
+            Some(1),
+        )
+        .await
+        {
+            tracing::error!("Failed to mark execution {} as failed: {}", exec_id, e);
+        }
+    }
+
+    /// Rewrite the workspace's CHANGES.md from the tool calls of its coding agent turns. The
+    /// finished turn's logs are only complete once its log store is closed, so this runs in the
+    /// background.
+    fn spawn_changes_summary(&self, ctx: &ExecutionContext) {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent
+        ) {
+            return;
+        }
+        let Some(workspace_root) = ctx.workspace.container_ref.as_ref().map(PathBuf::from) else {
+            return;
+        };
+
+        let container = self.clone();
+        let workspace_id = ctx.workspace.id;
+        let task_title = ctx.task.title.clone();
+        let current_id = ctx.execution_process.id;
+        let current_succeeded = ctx.execution_process.status == ExecutionProcessStatus::Completed;
+        tokio::spawn(async move {
+            let pool = &container.db.pool;
+            let mut turns = Vec::new();
+            for session in Session::find_by_workspace_id(pool, workspace_id)
+                .await
+                .unwrap_or_default()
+            {
+                for turn in CodingAgentTurn::find_by_session_id(pool, session.id)
+                    .await
+                    .unwrap_or_default()
+                {
+                    let Ok(Some(process)) =
+                        ExecutionProcess::find_by_id(pool, turn.execution_process_id).await
+                    else {
+                        continue;
+                    };
+                    let succeeded = if process.id == current_id {
+                        Some(current_succeeded)
+                    } else {
+                        match process.status {
+                            ExecutionProcessStatus::Running => None,
+                            status => Some(status == ExecutionProcessStatus::Completed),
+                        }
+                    };
+                    // Another turn's stream would only end once that turn does
+                    let entries = match succeeded {
+                        Some(_) => match container.stream_normalized_logs(&process.id).await {
+                            Some(stream) => replay::collect_entries(stream).await,
+                            None => Vec::new(),
+                        },
+                        None => Vec::new(),
+                    };
+                    turns.push(TurnChanges::from_entries(
+                        process.started_at,
+                        turn.prompt,
+                        turn.summary,
+                        succeeded,
+                        &entries,
+                        &workspace_root,
+                    ));
+                }
+            }
+            turns.sort_by_key(|turn| turn.started_at);
+
+            let content = changes_summary::render(&task_title, &turns);
+            if let Err(e) =
+                tokio::fs::write(workspace_root.join(changes_summary::FILE_NAME), content).await
+            {
+                tracing::warn!(
+                    "Failed to write changes summary for {}: {}",
+                    workspace_id,
+                    e
+                );
+            }
+        });
+    }
+
+    /// Check dependencies the coding agent added or changed against the dependency policy and
+    /// attach the report to the execution. Registry lookups can be slow, so the check runs in
+    /// the background and never holds up the next action.
+    async fn spawn_dependency_check(&self, ctx: &ExecutionContext) {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent
+        ) {
+            return;
+        }
+        let policy = self.config.read().await.dependency_policy.clone();
+        if !policy.enabled {
+            return;
+        }
+        let Some(workspace_root) = ctx.workspace.container_ref.as_ref().map(PathBuf::from) else {
+            return;
+        };
+
+        let container = self.clone();
+        let exec_id = ctx.execution_process.id;
+        let workspace_id = ctx.workspace.id;
+        let repos = ctx.repos.clone();
+        tokio::spawn(async move {
+            let repo_states = match ExecutionProcessRepoState::find_by_execution_process_id(
+                &container.db.pool,
+                exec_id,
+            )
+            .await
+            {
+                Ok(states) => states,
+                Err(e) => {
+                    tracing::error!("Failed to load repo states for {}: {}", exec_id, e);
+                    return;
+                }
+            };
+
+            let mut checker = DependencyChecker::new(policy);
+            let mut findings = Vec::new();
+            let mut manifests_changed = false;
+            for repo in &repos {
+                let Some(base) = repo_states
+                    .iter()
+                    .find(|state| state.repo_id == repo.id)
+                    .and_then(|state| state.before_head_commit.as_deref())
+                    .and_then(|sha| Commit::from_sha(sha).ok())
+                else {
+                    continue;
+                };
+                let worktree_path = workspace_root.join(&repo.name);
+                let diffs = match container.git.get_diffs(
+                    DiffTarget::Worktree {
+                        worktree_path: &worktree_path,
+                        base_commit: &base,
+                    },
+                    None,
+                ) {
+                    Ok(diffs) => diffs,
+                    Err(e) => {
+                        tracing::warn!("Failed to diff {} for dependency check: {}", repo.name, e);
+                        continue;
+                    }
+                };
+                if dependency_policy::changed_dependencies(&diffs).is_empty() {
+                    continue;
+                }
+                manifests_changed = true;
+                findings.extend(checker.check(&repo.name, &diffs).await);
+            }
+            if !manifests_changed {
+                return;
+            }
+
+            if let Err(e) =
+                DependencyReport::create(&container.db.pool, workspace_id, Some(exec_id), &findings)
+                    .await
+            {
+                tracing::error!("Failed to store dependency report: {}", e);
+            }
+        });
+    }
+
+    /// Lint and coverage stages run after the last process of a coding agent turn. Returns
+    /// whether a fix-up turn was started instead of finalizing the task.
+    async fn run_post_execution_stages(&self, ctx: &ExecutionContext) -> bool {
+        let fix_up_started = self.run_lint_stage(ctx).await;
+        self.record_coverage(ctx).await;
+        fix_up_started || self.advance_repo_chain(ctx).await
+    }
+
+    /// Complete the running step of the workspace's repository chain and start the turn for the
+    /// next repository with the earlier repositories' changes. Returns whether a turn was started.
+    async fn advance_repo_chain(&self, ctx: &ExecutionContext) -> bool {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::CleanupScript
+        ) {
+            return false;
+        }
+        let pool = &self.db.pool;
+        let steps = match RepoChainStep::find_by_workspace_id(pool, ctx.workspace.id).await {
+            Ok(steps) => steps,
+            Err(e) => {
+                tracing::error!("Failed to load repository chain: {}", e);
+                return false;
+            }
+        };
+        let Some(current) = steps
+            .iter()
+            .position(|step| step.status == RepoChainStepStatus::Running)
+        else {
+            return false;
+        };
+        if let Err(e) =
+            RepoChainStep::update_status(pool, steps[current].id, RepoChainStepStatus::Completed)
+                .await
+        {
+            tracing::error!("Failed to complete repository chain step: {}", e);
+        }
+        let Some(next) = steps
+            .iter()
+            .skip(current + 1)
+            .find(|step| step.status == RepoChainStepStatus::Pending)
+        else {
+            return false;
+        };
+        let Some(next_repo) = ctx.repos.iter().find(|repo| repo.id == next.repo_id) else {
+            tracing::warn!(
+                "Repository {} of the chain is no longer in workspace {}",
+                next.repo_id,
+                ctx.workspace.id
+            );
+            let _ = RepoChainStep::update_status(pool, next.id, RepoChainStepStatus::Failed).await;
+            return false;
+        };
+
+        let target_branches = WorkspaceRepo::find_by_workspace_id(pool, ctx.workspace.id)
+            .await
+            .unwrap_or_default();
+        let workspace_root = ctx.workspace.container_ref.as_ref().map(PathBuf::from);
+        let mut earlier = Vec::new();
+        for step in steps[..=current]
+            .iter()
+            .filter(|step| step.repo_id != next.repo_id)
+        {
+            let Some(repo) = ctx.repos.iter().find(|repo| repo.id == step.repo_id) else {
+                continue;
+            };
+            let pr_url = Merge::find_by_workspace_and_repo_id(pool, ctx.workspace.id, repo.id)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .find_map(|merge| match merge {
+                    Merge::Pr(pr) => Some(pr.pr_info.url),
+                    Merge::Direct(_) => None,
+                });
+            let changes = target_branches
+                .iter()
+                .find(|wr| wr.repo_id == repo.id)
+                .zip(workspace_root.as_ref())
+                .and_then(|(wr, root)| {
+                    let worktree_path = root.join(&repo.name);
+                    self.git
+                        .get_base_commit(&repo.path, &ctx.workspace.branch, &wr.target_branch)
+                        .and_then(|base| {
+                            self.git.get_diffs(
+                                DiffTarget::Worktree {
+                                    worktree_path: &worktree_path,
+                                    base_commit: &base,
+                                },
+                                None,
+                            )
+                        })
+                        .inspect_err(|e| {
+                            tracing::warn!(
+                                "Failed to diff {} for the next chain step: {}",
+                                repo.name,
+                                e
+                            )
+                        })
+                        .ok()
+                })
+                .unwrap_or_default()
+                .into_iter()
+                .map(|diff| RepoDiff {
+                    repo: repo.name.clone(),
+                    diff,
+                })
+                .collect();
+            earlier.push(EarlierStep {
+                repo: repo.name.clone(),
+                pr_url,
+                changes,
+            });
+        }
+
+        let variant = ExecutionProcess::latest_executor_profile_for_session(pool, ctx.session.id)
+            .await
+            .ok()
+            .and_then(|profile| profile.variant);
+        let follow_up = DraftFollowUpData {
+            message: repo_chain::step_prompt(
+                &ctx.task.to_prompt(),
+                &next_repo.name,
+                next.instructions.as_deref(),
+                &earlier,
+                repo_chain::MAX_EARLIER_DIFF_CHARS,
+            ),
+            variant,
+        };
+        match self.start_queued_follow_up(ctx, &follow_up, None).await {
+            Ok(execution_process) => {
+                if let Err(e) =
+                    RepoChainStep::mark_running(pool, next.id, execution_process.id).await
+                {
+                    tracing::error!("Failed to mark repository chain step running: {}", e);
+                }
+                true
+            }
+            Err(e) => {
+                tracing::error!("Failed to start turn for {}: {}", next_repo.name, e);
+                let _ =
+                    RepoChainStep::update_status(pool, next.id, RepoChainStepStatus::Failed).await;
+                false
+            }
+        }
+    }
+
+    /// Fail the running step of the workspace's repository chain, leaving later steps pending
+    async fn stop_repo_chain(&self, ctx: &ExecutionContext) {
+        let steps = RepoChainStep::find_by_workspace_id(&self.db.pool, ctx.workspace.id)
+            .await
+            .unwrap_or_default();
+        for step in steps
+            .iter()
+            .filter(|step| step.status == RepoChainStepStatus::Running)
+        {
+            if let Err(e) =
+                RepoChainStep::update_status(&self.db.pool, step.id, RepoChainStepStatus::Failed)
+                    .await
+            {
+                tracing::error!("Failed to fail repository chain step: {}", e);
+            }
+        }
+    }
+
+    /// Run the configured lint commands once a coding agent turn and its cleanup script have
+    /// finished, and store the findings on the attempt. Returns whether a fix-up turn was started
+    /// instead of finalizing the task.
+    async fn run_lint_stage(&self, ctx: &ExecutionContext) -> bool {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::CleanupScript
+        ) || ctx
+            .execution_process
+            .executor_action()
+            .is_ok_and(|action| action.is_read_only())
+        {
+            return false;
+        }
+        let lint = self.config.read().await.lint.clone();
+        if !lint.enabled || lint.commands.is_empty() {
+            return false;
+        }
+        let Some(workspace_root) = ctx.workspace.container_ref.as_ref().map(PathBuf::from) else {
+            return false;
+        };
+
+        let exec_id = ctx.execution_process.id;
+        let target_branches = WorkspaceRepo::find_by_workspace_id(&self.db.pool, ctx.workspace.id)
+            .await
+            .unwrap_or_default();
+        let timeout = Duration::from_secs(lint.timeout_secs.into());
+
+        let mut findings = Vec::new();
+        let mut quarantined_commands: Vec<(String, String)> = Vec::new();
+        for repo in &ctx.repos {
+            let worktree_path = workspace_root.join(&repo.name);
+            let mut repo_findings = Vec::new();
+            let quarantined = flaky::quarantined_hashes(&self.db.pool, repo.id)
+                .await
+                .unwrap_or_default();
+            let head_commit = self.git.get_head_info(&worktree_path).ok().map(|h| h.oid);
+            for command in lint.commands_for_repo(&repo.name) {
+                let result = lint::run_command(&repo.name, &worktree_path, command, timeout).await;
+                let succeeded = result
+                    .as_ref()
+                    .is_ok_and(|found| !found.iter().any(|f| f.severity == LintSeverity::Error));
+                self.record_command_run(
+                    ctx,
+                    repo.id,
+                    CommandKind::Lint,
+                    &command.command,
+                    head_commit.clone(),
+                    succeeded,
+                )
+                .await;
+                if quarantined.contains(&flaky::command_hash(&command.command)) {
+                    quarantined_commands.push((repo.name.clone(), command.name.clone()));
+                }
+                match result {
+                    Ok(found) => repo_findings.extend(found),
+                    Err(e) => {
+                        tracing::warn!("Lint command '{}' failed: {}", command.name, e);
+                        self.push_stderr(exec_id, format!("Lint stage: {e}\n"))
+                            .await;
+                    }
+                }
+            }
+
+            if lint.changed_files_only
+                && let Some(target) = target_branches.iter().find(|wr| wr.repo_id == repo.id)
+            {
+                match self
+                    .git
+                    .get_base_commit(&repo.path, &ctx.workspace.branch, &target.target_branch)
+                    .map_err(PolicyError::from)
+                    .and_then(|base| policy::changed_paths(&self.git, &worktree_path, &base))
+                {
+                    Ok(changed) => lint::retain_changed(&mut repo_findings, &changed),
+                    Err(e) => {
+                        tracing::warn!("Failed to list changed files in {}: {}", repo.name, e)
+                    }
+                }
+            }
+            findings.extend(repo_findings);
+        }
+
+        let report =
+            match LintReport::create(&self.db.pool, ctx.workspace.id, Some(exec_id), &findings)
+                .await
+            {
+                Ok(report) => report,
+                Err(e) => {
+                    tracing::error!("Failed to store lint report: {}", e);
+                    return false;
+                }
+            };
+
+        if self.config.read().await.analytics_enabled
+            && let Some(analytics) = &self.analytics
+        {
+            analytics.analytics_service.track_event(
+                &analytics.user_id,
+                "lint_stage_completed",
+                Some(json!({
+                    "workspace_id": ctx.workspace.id.to_string(),
+                    "finding_count": findings.len(),
+                })),
+            );
+        }
+
+        // Failures of quarantined commands are flaky, not something the agent can fix
+        let actionable: Vec<LintFinding> = findings
+            .iter()
+            .filter(|f| {
+                !quarantined_commands
+                    .iter()
+                    .any(|(repo, command)| *repo == f.repo && *command == f.command)
+            })
+            .cloned()
+            .collect();
+        if actionable.is_empty() || !lint.auto_fix {
+            return false;
+        }
+        let attempts = LintReport::count_consecutive_fix_requests(&self.db.pool, ctx.workspace.id)
+            .await
+            .unwrap_or(usize::MAX);
+        if attempts >= lint.max_fix_attempts as usize {
+            tracing::info!(
+                "Leaving {} lint findings for review in workspace {} after {} fix-up turns",
+                actionable.len(),
+                ctx.workspace.id,
+                attempts
+            );
+            return false;
+        }
+
+        let variant =
+            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, ctx.session.id)
+                .await
+                .ok()
+                .and_then(|profile| profile.variant);
+        let follow_up = DraftFollowUpData {
+            message: lint::fix_prompt(&actionable),
+            variant,
+        };
+        match self
+            .start_queued_follow_up(ctx, &follow_up, Some(TurnPurpose::LintFix))
+            .await
+        {
+            Ok(_) => {
+                if let Err(e) = LintReport::mark_fix_requested(&self.db.pool, report.id).await {
+                    tracing::error!("Failed to mark lint report {}: {}", report.id, e);
+                }
+                true
+            }
+            Err(e) => {
+                tracing::error!("Failed to start lint fix-up turn: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Record the outcome of a setup or cleanup script for flaky detection. Killed scripts say
+    /// nothing about the script itself and are skipped.
+    async fn record_script_run(&self, ctx: &ExecutionContext, succeeded: bool) {
+        if matches!(ctx.execution_process.status, ExecutionProcessStatus::Killed) {
+            return;
+        }
+        let Ok(action) = ctx.execution_process.executor_action() else {
+            return;
+        };
+        let ExecutorActionType::ScriptRequest(request) = action.typ() else {
+            return;
+        };
+        let kind = match request.context {
+            ScriptContext::SetupScript => CommandKind::SetupScript,
+            ScriptContext::CleanupScript => CommandKind::CleanupScript,
+            ScriptContext::DevServer | ScriptContext::ToolInstallScript => return,
+        };
+        let Some(repo) = ctx
+            .repos
+            .iter()
+            .find(|repo| request.working_dir.as_deref() == Some(repo.name.as_str()))
+        else {
+            return;
+        };
+        let head_commit = ctx
+            .workspace
+            .container_ref
+            .as_ref()
+            .map(|root| PathBuf::from(root).join(&repo.name))
+            .and_then(|worktree| self.git.get_head_info(&worktree).ok())
+            .map(|head| head.oid);
+        self.record_command_run(ctx, repo.id, kind, &request.script, head_commit, succeeded)
+            .await;
+    }
+
+    async fn record_command_run(
+        &self,
+        ctx: &ExecutionContext,
+        repo_id: Uuid,
+        kind: CommandKind,
+        command: &str,
+        head_commit: Option<String>,
+        succeeded: bool,
+    ) {
+        let threshold = self.config.read().await.flaky.auto_quarantine_threshold;
+        let outcome = CommandOutcome {
+            repo_id,
+            workspace_id: ctx.workspace.id,
+            execution_process_id: Some(ctx.execution_process.id),
+            kind,
+            command,
+            head_commit,
+            succeeded,
+        };
+        match flaky::record(&self.db.pool, outcome, threshold).await {
+            Ok(true) => {
+                if self.config.read().await.analytics_enabled
+                    && let Some(analytics) = &self.analytics
+                {
+                    analytics.analytics_service.track_event(
+                        &analytics.user_id,
+                        "flaky_command_quarantined",
+                        Some(json!({
+                            "repo_id": repo_id.to_string(),
+                            "kind": kind,
+                        })),
+                    );
+                }
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to record command run: {}", e),
+        }
+    }
+
+    /// Read the coverage reports left in each worktree by the turn's test commands and store
+    /// them with the target branch baseline and the coverage of the changed lines
+    async fn record_coverage(&self, ctx: &ExecutionContext) {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::CleanupScript
+        ) {
+            return;
+        }
+        let config = self.config.read().await.coverage.clone();
+        if !config.enabled {
+            return;
+        }
+        let Some(workspace_root) = ctx.workspace.container_ref.as_ref().map(PathBuf::from) else {
+            return;
+        };
+        let target_branches = WorkspaceRepo::find_by_workspace_id(&self.db.pool, ctx.workspace.id)
+            .await
+            .unwrap_or_default();
+
+        for repo in &ctx.repos {
+            let worktree_path = workspace_root.join(&repo.name);
+            let Some(head) = coverage::load(&worktree_path, &config.report_paths) else {
+                continue;
+            };
+            let (lines_total, lines_covered) = head.totals();
+            let target_branch = target_branches
+                .iter()
+                .find(|wr| wr.repo_id == repo.id)
+                .map(|wr| wr.target_branch.as_str());
+
+            // The main checkout only serves as a baseline while it is on the target branch
+            let baseline = target_branch
+                .filter(|target| {
+                    self.git
+                        .get_current_branch(&repo.path)
+                        .is_ok_and(|current| {
+                            *target == current || target.ends_with(&format!("/{current}"))
+                        })
+                })
+                .and_then(|_| coverage::load(&repo.path, &config.report_paths))
+                .map(|base| base.totals());
+
+            let (patch_lines_total, patch_lines_covered) = target_branch
+                .and_then(|target| {
+                    let base = self
+                        .git
+                        .get_base_commit(&repo.path, &ctx.workspace.branch, target)
+                        .ok()?;
+                    self.git
+                        .get_diffs(
+                            DiffTarget::Worktree {
+                                worktree_path: &worktree_path,
+                                base_commit: &base,
+                            },
+                            None,
+                        )
+                        .ok()
+                })
+                .map(|diffs| head.patch_totals(&diffs))
+                .unwrap_or((0, 0));
+
+            let report = CreateCoverageReport {
+                workspace_id: ctx.workspace.id,
+                execution_process_id: Some(ctx.execution_process.id),
+                repo_id: repo.id,
+                lines_total,
+                lines_covered,
+                base_lines_total: baseline.map(|(total, _)| total),
+                base_lines_covered: baseline.map(|(_, covered)| covered),
+                patch_lines_total,
+                patch_lines_covered,
+            };
+            if let Err(e) = CoverageReport::create(&self.db.pool, &report).await {
+                tracing::error!("Failed to store coverage report for {}: {}", repo.name, e);
+            }
+        }
+    }
+
     /// Spawn a background task that polls the child process for completion and
     /// cleans up the execution entry when it exits.
     pub fn spawn_exit_monitor(
@@ -352,7 +1201,6 @@ impl LocalContainerService {
         let config = self.config.clone();
         let container = self.clone();
         let analytics = self.analytics.clone();
-        let publisher = self.publisher.clone();
 
         let mut process_exit_rx = self.spawn_os_exit_watcher(exec_id);
 
@@ -403,6 +1251,8 @@ impl LocalContainerService {
                 Err(_) => (None, ExecutionProcessStatus::Failed),
             };
 
+            container.throttle.release(&exec_id);
+
             if !ExecutionProcess::was_stopped(&db.pool, exec_id).await
                 && let Err(e) =
                     ExecutionProcess::update_completion(&db.pool, exec_id, status, exit_code).await
@@ -410,17 +1260,31 @@ impl LocalContainerService {
                 tracing::error!("Failed to update execution process completion: {}", e);
             }
 
-            if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
+            if let Ok(mut ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
                 // Update executor session summary if available
                 if let Err(e) = container.update_executor_session_summary(&exec_id).await {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
-                let success = matches!(
+                let mut success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
                 ) && exit_code == Some(0);
 
+                container.record_script_run(&ctx, success).await;
+
+                // Policy violations fail the run before anything is committed
+                if success && container.enforce_executor_policies(&ctx).await {
+                    ctx.execution_process.status = ExecutionProcessStatus::Failed;
+                    ctx.execution_process.exit_code = Some(1);
+                    success = false;
+                }
+
+                if success {
+                    container.spawn_dependency_check(&ctx).await;
+                }
+                container.spawn_changes_summary(&ctx);
+
                 let cleanup_done = matches!(
                     ctx.execution_process.run_reason,
                     ExecutionProcessRunReason::CleanupScript
@@ -461,7 +1325,7 @@ impl LocalContainerService {
                         );
 
                         // Manually finalize task since we're bypassing normal execution flow
-                        container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                        container.finalize_task(&ctx).await;
                     }
                 }
 
@@ -472,6 +1336,9 @@ impl LocalContainerService {
                         ctx.execution_process.status,
                         ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
                     );
+                    if !should_execute_queued {
+                        container.stop_repo_chain(&ctx).await;
+                    }
 
                     if let Some(queued_msg) =
                         container.queued_message_service.take_queued(ctx.session.id)
@@ -498,12 +1365,12 @@ impl LocalContainerService {
 
                             // Execute the queued follow-up
                             if let Err(e) = container
-                                .start_queued_follow_up(&ctx, &queued_msg.data)
+                                .start_queued_follow_up(&ctx, &queued_msg.data, None)
                                 .await
                             {
                                 tracing::error!("Failed to start queued follow-up: {}", e);
                                 // Fall back to finalization if follow-up fails
-                                container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                                container.finalize_task(&ctx).await;
                             }
                         } else {
                             // Execution failed or was killed - discard the queued message and finalize
@@ -512,13 +1379,34 @@ impl LocalContainerService {
                                 ctx.session.id,
                                 ctx.execution_process.status
                             );
-                            container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                            container.finalize_task(&ctx).await;
                         }
+                    } else if should_execute_queued
+                        && container.run_post_execution_stages(&ctx).await
+                    {
+                        // A fix-up turn is running; the task is finalized once it finishes
                     } else {
-                        container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                        container.finalize_task(&ctx).await;
                     }
                 }
 
+                if matches!(
+                    &ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::CodingAgent
+                ) {
+                    container
+                        .commit_statuses
+                        .queue_agent_run(ctx.workspace.id)
+                        .await;
+                }
+
+                if !matches!(
+                    &ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::DevServer
+                ) {
+                    container.artifacts.queue_execution_logs(exec_id).await;
+                }
+
                 // Fire analytics event when CodingAgent execution has finished
                 if config.read().await.analytics_enabled
                     && matches!(
@@ -604,18 +1492,29 @@ impl LocalContainerService {
     }
 
     async fn track_child_msgs_in_store(&self, id: Uuid, child: &mut AsyncGroupChild) {
-        let store = Arc::new(MsgStore::new());
+        // Runs held back by executor limits already have a store that log streaming follows
+        let existing = self.msg_stores().read().await.get(&id).cloned();
+        let store = existing.unwrap_or_else(|| Arc::new(MsgStore::new()));
 
         let out = child.inner().stdout.take().expect("no stdout");
         let err = child.inner().stderr.take().expect("no stderr");
 
+        // Secrets are scrubbed per chunk before anything reaches the store, so the live view,
+        // normalizers and persisted logs never see them. A secret split across two reads can
+        // slip through the pattern detectors; env values are usually printed whole.
+        let redactor = utils::redact::global();
+        let redact = move |chunk: bytes::Bytes| {
+            redactor
+                .redact(&String::from_utf8_lossy(&chunk))
+                .into_owned()
+        };
+
         // Map stdout bytes -> LogMsg::Stdout
-        let out = ReaderStream::new(out)
-            .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
+        let redact_out = redact.clone();
+        let out = ReaderStream::new(out).map_ok(move |chunk| LogMsg::Stdout(redact_out(chunk)));
 
         // Map stderr bytes -> LogMsg::Stderr
-        let err = ReaderStream::new(err)
-            .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+        let err = ReaderStream::new(err).map_ok(move |chunk| LogMsg::Stderr(redact(chunk)));
 
         // If you have a JSON Patch source, map it to LogMsg::JsonPatch too, then select all three.
 
@@ -697,97 +1596,261 @@ impl LocalContainerService {
         Ok(())
     }
 
-    /// Copy project files and images to the workspace.
-    /// Skips files/images that already exist (fast no-op if all exist).
-    async fn copy_files_and_images(
+    /// Copy project files and images to the workspace.
+    /// Skips files/images that already exist (fast no-op if all exist).
+    async fn copy_files_and_images(
+        &self,
+        workspace_dir: &Path,
+        workspace: &Workspace,
+    ) -> Result<(), ContainerError> {
+        let repos = WorkspaceRepo::find_repos_with_copy_files(&self.db.pool, workspace.id).await?;
+
+        for repo in &repos {
+            if let Some(copy_files) = &repo.copy_files
+                && !copy_files.trim().is_empty()
+            {
+                let worktree_path = workspace_dir.join(&repo.name);
+                self.copy_project_files(&repo.path, &worktree_path, copy_files)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::warn!(
+                            "Failed to copy project files for repo '{}': {}",
+                            repo.name,
+                            e
+                        );
+                    });
+            }
+        }
+
+        if let Err(e) = self
+            .image_service
+            .copy_images_by_task_to_worktree(workspace_dir, workspace.task_id)
+            .await
+        {
+            tracing::warn!("Failed to copy task images to workspace: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Create workspace-level CLAUDE.md and AGENTS.md files that import from each repo.
+    /// Uses the @import syntax to reference each repo's config files.
+    /// Skips creating files if they already exist or if no repos have the source file.
+    async fn create_workspace_config_files(
+        workspace_dir: &Path,
+        repos: &[Repo],
+    ) -> Result<(), ContainerError> {
+        const CONFIG_FILES: [&str; 2] = ["CLAUDE.md", "AGENTS.md"];
+
+        for config_file in CONFIG_FILES {
+            let workspace_config_path = workspace_dir.join(config_file);
+
+            if workspace_config_path.exists() {
+                tracing::debug!(
+                    "Workspace config file {} already exists, skipping",
+                    config_file
+                );
+                continue;
+            }
+
+            let mut import_lines = Vec::new();
+            for repo in repos {
+                let repo_config_path = workspace_dir.join(&repo.name).join(config_file);
+                if repo_config_path.exists() {
+                    import_lines.push(format!("@{}/{}", repo.name, config_file));
+                }
+            }
+
+            if import_lines.is_empty() {
+                tracing::debug!(
+                    "No repos have {}, skipping workspace config creation",
+                    config_file
+                );
+                continue;
+            }
+
+            let content = import_lines.join("\n") + "\n";
+            if let Err(e) = tokio::fs::write(&workspace_config_path, &content).await {
+                tracing::warn!(
+                    "Failed to create workspace config file {}: {}",
+                    config_file,
+                    e
+                );
+                continue;
+            }
+
+            tracing::info!(
+                "Created workspace {} with {} import(s)",
+                config_file,
+                import_lines.len()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Spawn the process of an execution and start monitoring it
+    async fn spawn_execution(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Result<(), ContainerError> {
+        // Get the worktree path
+        let container_ref = workspace
+            .container_ref
+            .as_ref()
+            .ok_or(ContainerError::Other(anyhow!(
+                "Container ref not found for workspace"
+            )))?;
+        let current_dir = PathBuf::from(container_ref);
+
+        let approvals_service: Arc<dyn ExecutorApprovalService> =
+            match executor_action.base_executor() {
+                Some(
+                    BaseCodingAgent::Codex
+                    | BaseCodingAgent::ClaudeCode
+                    | BaseCodingAgent::Gemini
+                    | BaseCodingAgent::QwenCode
+                    | BaseCodingAgent::Opencode,
+                ) => ExecutorApprovalBridge::new(
+                    self.approvals.clone(),
+                    self.db.clone(),
+                    self.notification_service.clone(),
+                    self.task_events.clone(),
+                    execution_process.id,
+                ),
+                _ => Arc::new(NoopExecutorApprovalService {}),
+            };
+
+        // Build ExecutionEnv with VK_* variables
+        let mut env = ExecutionEnv::new();
+
+        // Load task and project context for environment variables
+        let task = workspace
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(ContainerError::Other(anyhow!(
+                "Task not found for workspace"
+            )))?;
+        let project = task
+            .parent_project(&self.db.pool)
+            .await?
+            .ok_or(ContainerError::Other(anyhow!("Project not found for task")))?;
+
+        env.insert("VK_PROJECT_NAME", &project.name);
+        env.insert("VK_PROJECT_ID", project.id.to_string());
+        env.insert("VK_TASK_ID", task.id.to_string());
+        env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
+        env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
+
+        // Repeated launch failures of an executor, like a broken install, pause its launches
+        // instead of failing every queued run in turn
+        let breaker = executor_action
+            .base_executor()
+            .map(|executor| circuit_breaker::breaker(&format!("{executor} executor")));
+        if let Some(breaker) = &breaker {
+            breaker
+                .check()
+                .map_err(|e| ContainerError::Other(e.into()))?;
+        }
+
+        // Create the child and stream, add to execution tracker with timeout
+        let spawn_result = tokio::time::timeout(
+            Duration::from_secs(30),
+            executor_action.spawn(&current_dir, approvals_service, &env),
+        )
+        .await
+        .map_err(|_| {
+            ContainerError::Other(anyhow!(
+                "Timeout: process took more than 30 seconds to start"
+            ))
+        })
+        .and_then(|spawned| spawned.map_err(ContainerError::from));
+        if let Some(breaker) = &breaker {
+            match &spawn_result {
+                Ok(_) => breaker.record_success(),
+                Err(e) => breaker.record_failure(e),
+            }
+        }
+        let mut spawned = spawn_result?;
+
+        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
+            .await;
+
+        self.add_child_to_store(execution_process.id, spawned.child)
+            .await;
+
+        // Store interrupt sender for graceful shutdown
+        if let Some(interrupt_sender) = spawned.interrupt_sender {
+            self.add_interrupt_sender(execution_process.id, interrupt_sender)
+                .await;
+        }
+
+        // Spawn unified exit monitor: watches OS exit and optional executor signal
+        let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
+
+        Ok(())
+    }
+
+    /// Spawn an execution that was held back by executor limits. It may have been stopped while
+    /// waiting; failures are reported on the execution since nobody is waiting for the result.
+    async fn start_throttled_execution(
         &self,
-        workspace_dir: &Path,
         workspace: &Workspace,
-    ) -> Result<(), ContainerError> {
-        let repos = WorkspaceRepo::find_repos_with_copy_files(&self.db.pool, workspace.id).await?;
-
-        for repo in &repos {
-            if let Some(copy_files) = &repo.copy_files
-                && !copy_files.trim().is_empty()
-            {
-                let worktree_path = workspace_dir.join(&repo.name);
-                self.copy_project_files(&repo.path, &worktree_path, copy_files)
-                    .await
-                    .unwrap_or_else(|e| {
-                        tracing::warn!(
-                            "Failed to copy project files for repo '{}': {}",
-                            repo.name,
-                            e
-                        );
-                    });
-            }
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) {
+        let still_running = ExecutionProcess::find_by_id(&self.db.pool, execution_process.id)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|process| process.status == ExecutionProcessStatus::Running);
+        if !still_running {
+            self.throttle.release(&execution_process.id);
+            return;
         }
 
-        if let Err(e) = self
-            .image_service
-            .copy_images_by_task_to_worktree(workspace_dir, workspace.task_id)
+        let Err(e) = self
+            .spawn_execution(workspace, execution_process, executor_action)
             .await
+        else {
+            return;
+        };
+        tracing::error!(
+            "Failed to start throttled execution {}: {}",
+            execution_process.id,
+            e
+        );
+        self.throttle.release(&execution_process.id);
+        if let Err(update_error) = ExecutionProcess::update_completion(
+            &self.db.pool,
+            execution_process.id,
+            ExecutionProcessStatus::Failed,
+            None,
+        )
+        .await
         {
-            tracing::warn!("Failed to copy task images to workspace: {}", e);
-        }
-
-        Ok(())
-    }
-
-    /// Create workspace-level CLAUDE.md and AGENTS.md files that import from each repo.
-    /// Uses the @import syntax to reference each repo's config files.
-    /// Skips creating files if they already exist or if no repos have the source file.
-    async fn create_workspace_config_files(
-        workspace_dir: &Path,
-        repos: &[Repo],
-    ) -> Result<(), ContainerError> {
-        const CONFIG_FILES: [&str; 2] = ["CLAUDE.md", "AGENTS.md"];
-
-        for config_file in CONFIG_FILES {
-            let workspace_config_path = workspace_dir.join(config_file);
-
-            if workspace_config_path.exists() {
-                tracing::debug!(
-                    "Workspace config file {} already exists, skipping",
-                    config_file
-                );
-                continue;
-            }
-
-            let mut import_lines = Vec::new();
-            for repo in repos {
-                let repo_config_path = workspace_dir.join(&repo.name).join(config_file);
-                if repo_config_path.exists() {
-                    import_lines.push(format!("@{}/{}", repo.name, config_file));
-                }
-            }
-
-            if import_lines.is_empty() {
-                tracing::debug!(
-                    "No repos have {}, skipping workspace config creation",
-                    config_file
-                );
-                continue;
-            }
-
-            let content = import_lines.join("\n") + "\n";
-            if let Err(e) = tokio::fs::write(&workspace_config_path, &content).await {
-                tracing::warn!(
-                    "Failed to create workspace config file {}: {}",
-                    config_file,
-                    e
-                );
-                continue;
-            }
-
-            tracing::info!(
-                "Created workspace {} with {} import(s)",
-                config_file,
-                import_lines.len()
+            tracing::error!(
+                "Failed to mark execution process {} as failed: {}",
+                execution_process.id,
+                update_error
             );
         }
-
-        Ok(())
+        if let Some(store) = self.msg_stores.write().await.remove(&execution_process.id) {
+            store.push_stderr(format!("Failed to start execution: {e}"));
+            store.push_finished();
+        }
+        if let Ok(Some(task)) = workspace.parent_task(&self.db.pool).await {
+            let _ = self
+                .task_events
+                .set_status(
+                    task.id,
+                    TaskStatus::InReview,
+                    &TaskActor::system("execution"),
+                )
+                .await;
+        }
     }
 
     /// Start a follow-up execution from a queued message
@@ -795,6 +1858,7 @@ impl LocalContainerService {
         &self,
         ctx: &ExecutionContext,
         queued_data: &DraftFollowUpData,
+        purpose: Option<TurnPurpose>,
     ) -> Result<ExecutionProcess, ContainerError> {
         // Get executor profile from the latest CodingAgent process in this session
         let initial_executor_profile_id =
@@ -833,6 +1897,7 @@ impl LocalContainerService {
                 session_id: agent_session_id,
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir: working_dir.clone(),
+                read_only: false,
             })
         } else {
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
@@ -844,11 +1909,12 @@ impl LocalContainerService {
 
         let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
 
-        self.start_execution(
+        self.start_execution_for_purpose(
             &ctx.workspace,
             &ctx.session,
             &action,
             &ExecutionProcessRunReason::CodingAgent,
+            purpose,
         )
         .await
     }
@@ -889,10 +1955,34 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn task_events(&self) -> &TaskEventLog {
+        &self.task_events
+    }
+
+    fn commit_statuses(&self) -> &CommitStatusService {
+        &self.commit_statuses
+    }
+
+    fn executor_throttle(&self) -> &ExecutorThrottle {
+        &self.throttle
+    }
+
     async fn git_branch_prefix(&self) -> String {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn routing_config(&self) -> RoutingConfig {
+        self.config.read().await.routing.clone()
+    }
+
+    async fn compaction_config(&self) -> CompactionConfig {
+        self.config.read().await.compaction.clone()
+    }
+
+    async fn content_locale(&self) -> Option<String> {
+        self.config.read().await.content_locale.clone()
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -923,6 +2013,35 @@ impl ContainerService for LocalContainerService {
             .map(|wr| (wr.repo_id, wr.target_branch.clone()))
             .collect();
 
+        // The fetch scheduler keeps managed clones fresh; only wait for a fetch when the target
+        // branch has not reached the clone yet
+        for repo in &repositories {
+            if !matches!(
+                ManagedRepo::find_by_repo_id(&self.db.pool, repo.id).await,
+                Ok(Some(_))
+            ) {
+                continue;
+            }
+            let target_branch = target_branches.get(&repo.id).cloned().unwrap_or_default();
+            if self
+                .git
+                .check_branch_exists(&repo.path, &target_branch)
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            if let Err(e) = RepoService::new()
+                .sync_managed(&self.db.pool, &GitCli::new(), repo)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to fetch {} before creating worktree: {}",
+                    repo.name,
+                    e
+                );
+            }
+        }
+
         let workspace_inputs: Vec<RepoWorkspaceInput> = repositories
             .iter()
             .map(|repo| {
@@ -1039,80 +2158,47 @@ impl ContainerService for LocalContainerService {
         execution_process: &ExecutionProcess,
         executor_action: &ExecutorAction,
     ) -> Result<(), ContainerError> {
-        // Get the worktree path
-        let container_ref = workspace
-            .container_ref
-            .as_ref()
-            .ok_or(ContainerError::Other(anyhow!(
-                "Container ref not found for workspace"
-            )))?;
-        let current_dir = PathBuf::from(container_ref);
-
-        let approvals_service: Arc<dyn ExecutorApprovalService> =
-            match executor_action.base_executor() {
-                Some(
-                    BaseCodingAgent::Codex
-                    | BaseCodingAgent::ClaudeCode
-                    | BaseCodingAgent::Gemini
-                    | BaseCodingAgent::QwenCode
-                    | BaseCodingAgent::Opencode,
-                ) => ExecutorApprovalBridge::new(
-                    self.approvals.clone(),
-                    self.db.clone(),
-                    self.notification_service.clone(),
-                    execution_process.id,
-                ),
-                _ => Arc::new(NoopExecutorApprovalService {}),
-            };
-
-        // Build ExecutionEnv with VK_* variables
-        let mut env = ExecutionEnv::new();
-
-        // Load task and project context for environment variables
-        let task = workspace
-            .parent_task(&self.db.pool)
-            .await?
-            .ok_or(ContainerError::Other(anyhow!(
-                "Task not found for workspace"
-            )))?;
-        let project = task
-            .parent_project(&self.db.pool)
-            .await?
-            .ok_or(ContainerError::Other(anyhow!("Project not found for task")))?;
-
-        env.insert("VK_PROJECT_NAME", &project.name);
-        env.insert("VK_PROJECT_ID", project.id.to_string());
-        env.insert("VK_TASK_ID", task.id.to_string());
-        env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
-        env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
-
-        // Create the child and stream, add to execution tracker with timeout
-        let mut spawned = tokio::time::timeout(
-            Duration::from_secs(30),
-            executor_action.spawn(&current_dir, approvals_service, &env),
-        )
-        .await
-        .map_err(|_| {
-            ContainerError::Other(anyhow!(
-                "Timeout: process took more than 30 seconds to start"
-            ))
-        })??;
-
-        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
-            .await;
-
-        self.add_child_to_store(execution_process.id, spawned.child)
-            .await;
-
-        // Store interrupt sender for graceful shutdown
-        if let Some(interrupt_sender) = spawned.interrupt_sender {
-            self.add_interrupt_sender(execution_process.id, interrupt_sender)
+        let Some(profile) = executor_action.executor_profile_id() else {
+            return self
+                .spawn_execution(workspace, execution_process, executor_action)
+                .await;
+        };
+        let limits = self.config.read().await.executor_limits.clone();
+        if self
+            .throttle
+            .try_acquire(execution_process.id, &profile, &limits)
+        {
+            let result = self
+                .spawn_execution(workspace, execution_process, executor_action)
                 .await;
+            if result.is_err() {
+                self.throttle.release(&execution_process.id);
+            }
+            return result;
         }
 
-        // Spawn unified exit monitor: watches OS exit and optional executor signal
-        let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
-
+        tracing::info!(
+            "Execution {} is waiting for a free {} slot",
+            execution_process.id,
+            profile
+        );
+        self.msg_stores()
+            .write()
+            .await
+            .insert(execution_process.id, Arc::new(MsgStore::new()));
+        let container = self.clone();
+        let workspace = workspace.clone();
+        let execution_process = execution_process.clone();
+        let executor_action = executor_action.clone();
+        tokio::spawn(async move {
+            container
+                .throttle
+                .acquire(execution_process.id, &profile, &container.config)
+                .await;
+            container
+                .start_throttled_execution(&workspace, &execution_process, &executor_action)
+                .await;
+        });
         Ok(())
     }
 
@@ -1121,12 +2207,20 @@ impl ContainerService for LocalContainerService {
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
-        let child = self
-            .get_child_from_store(&execution_process.id)
-            .await
-            .ok_or_else(|| {
-                ContainerError::Other(anyhow!("Child process not found for execution"))
-            })?;
+        let child = self.get_child_from_store(&execution_process.id).await;
+        // Runs still waiting for an executor slot have no process yet; marking them stopped keeps
+        // them from being spawned once a slot frees up
+        if child.is_none()
+            && !self
+                .msg_stores
+                .read()
+                .await
+                .contains_key(&execution_process.id)
+        {
+            return Err(ContainerError::Other(anyhow!(
+                "Child process not found for execution"
+            )));
+        }
         let exit_code = if status == ExecutionProcessStatus::Completed {
             Some(0)
         } else {
@@ -1136,49 +2230,53 @@ impl ContainerService for LocalContainerService {
         ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, exit_code)
             .await?;
 
-        // Try graceful interrupt first, then force kill
-        if let Some(interrupt_sender) = self.take_interrupt_sender(&execution_process.id).await {
-            // Send interrupt signal (ignore error if receiver dropped)
-            let _ = interrupt_sender.send(());
+        if let Some(child) = child {
+            // Try graceful interrupt first, then force kill
+            if let Some(interrupt_sender) = self.take_interrupt_sender(&execution_process.id).await
+            {
+                // Send interrupt signal (ignore error if receiver dropped)
+                let _ = interrupt_sender.send(());
 
-            // Wait for graceful exit with timeout
-            let graceful_exit = {
-                let mut child_guard = child.write().await;
-                tokio::time::timeout(Duration::from_secs(5), child_guard.wait()).await
-            };
+                // Wait for graceful exit with timeout
+                let graceful_exit = {
+                    let mut child_guard = child.write().await;
+                    tokio::time::timeout(Duration::from_secs(5), child_guard.wait()).await
+                };
 
-            match graceful_exit {
-                Ok(Ok(_)) => {
-                    tracing::debug!(
-                        "Process {} exited gracefully after interrupt",
-                        execution_process.id
-                    );
-                }
-                Ok(Err(e)) => {
-                    tracing::info!("Error waiting for process {}: {}", execution_process.id, e);
-                }
-                Err(_) => {
-                    tracing::debug!(
-                        "Graceful shutdown timed out for process {}, force killing",
-                        execution_process.id
-                    );
+                match graceful_exit {
+                    Ok(Ok(_)) => {
+                        tracing::debug!(
+                            "Process {} exited gracefully after interrupt",
+                            execution_process.id
+                        );
+                    }
+                    Ok(Err(e)) => {
+                        tracing::info!("Error waiting for process {}: {}", execution_process.id, e);
+                    }
+                    Err(_) => {
+                        tracing::debug!(
+                            "Graceful shutdown timed out for process {}, force killing",
+                            execution_process.id
+                        );
+                    }
                 }
             }
-        }
 
-        // Kill the child process and remove from the store
-        {
-            let mut child_guard = child.write().await;
-            if let Err(e) = command::kill_process_group(&mut child_guard).await {
-                tracing::error!(
-                    "Failed to stop execution process {}: {}",
-                    execution_process.id,
-                    e
-                );
-                return Err(e);
+            // Kill the child process and remove from the store
+            {
+                let mut child_guard = child.write().await;
+                if let Err(e) = command::kill_process_group(&mut child_guard).await {
+                    tracing::error!(
+                        "Failed to stop execution process {}: {}",
+                        execution_process.id,
+                        e
+                    );
+                    return Err(e);
+                }
             }
+            self.remove_child_from_store(&execution_process.id).await;
         }
-        self.remove_child_from_store(&execution_process.id).await;
+        self.throttle.release(&execution_process.id);
 
         // Mark the process finished in the MsgStore
         if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
@@ -1191,22 +2289,17 @@ impl ContainerService for LocalContainerService {
                 ctx.execution_process.run_reason,
                 ExecutionProcessRunReason::DevServer
             )
+            && !ctx
+                .execution_process
+                .executor_action()
+                .is_ok_and(|action| action.is_read_only())
         {
-            match Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await {
-                Ok(_) => {
-                    if let Some(publisher) = self.share_publisher()
-                        && let Err(err) = publisher.update_shared_task_by_id(ctx.task.id).await
-                    {
-                        tracing::warn!(
-                            ?err,
-                            "Failed to propagate shared task update for {}",
-                            ctx.task.id
-                        );
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Failed to update task status to InReview: {e}");
-                }
+            if let Err(e) = self
+                .task_events
+                .set_status(ctx.task.id, TaskStatus::InReview, &TaskActor::user())
+                .await
+            {
+                tracing::error!("Failed to update task status to InReview: {e}");
             }
         }
 
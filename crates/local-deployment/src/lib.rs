@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use db::DBService;
@@ -7,6 +7,7 @@ use executors::profile::ExecutorConfigs;
 use services::services::{
     analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
     approvals::Approvals,
+    artifacts::ArtifactService,
     auth::AuthContext,
     config::{Config, load_config_from_file, save_config_to_file},
     container::ContainerService,
@@ -14,12 +15,18 @@ use services::services::{
     file_search_cache::FileSearchCache,
     filesystem::FilesystemService,
     git::GitService,
-    image::ImageService,
+    git_provider,
+    image::{DELETE_ORPHANED_IMAGES_JOB, ImageService},
+    jobs::JobQueue,
     oauth_credentials::OAuthCredentials,
+    object_store::{self, ObjectStore},
+    offline::OfflineService,
+    plugins::{PluginApi, PluginHost, plugins_dir},
     project::ProjectService,
     queued_message::QueuedMessageService,
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
+    rules::{RuleCache, RuleEngine},
     share::{ShareConfig, SharePublisher},
 };
 use tokio::sync::RwLock;
@@ -30,10 +37,11 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::container::LocalContainerService;
+use crate::{container::LocalContainerService, plugin_api::LocalPluginApi};
 mod command;
 pub mod container;
 mod copy;
+pub mod plugin_api;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
@@ -46,6 +54,8 @@ pub struct LocalDeployment {
     project: ProjectService,
     repo: RepoService,
     image: ImageService,
+    storage: Arc<dyn ObjectStore>,
+    artifacts: ArtifactService,
     filesystem: FilesystemService,
     events: EventService,
     file_search_cache: Arc<FileSearchCache>,
@@ -56,6 +66,9 @@ pub struct LocalDeployment {
     remote_client: Result<RemoteClient, RemoteClientNotConfigured>,
     auth_context: AuthContext,
     oauth_handoffs: Arc<RwLock<HashMap<Uuid, PendingHandoff>>>,
+    jobs: JobQueue,
+    offline: OfflineService,
+    automation_rules: RuleCache,
 }
 
 #[derive(Debug, Clone)]
@@ -88,12 +101,37 @@ impl Deployment for LocalDeployment {
             }
         }
 
+        // Test mode turns off whatever reaches outside the machine or waits on a person
+        if utils::test_mode::is_enabled() {
+            raw_config.analytics_enabled = false;
+            raw_config.disclaimer_acknowledged = true;
+            raw_config.onboarding_acknowledged = true;
+            raw_config.show_release_notes = false;
+            raw_config.notifications.sound_enabled = false;
+            raw_config.notifications.push_enabled = false;
+            raw_config.update.check_on_startup = false;
+            raw_config.log_shipping.enabled = false;
+        }
+
         // Always save config (may have been migrated or version updated)
         save_config_to_file(&raw_config, &config_path()).await?;
 
+        utils::redact::configure(&(&raw_config.redaction).into());
+        utils::log_shipping::configure(&raw_config.log_shipping);
+        git_provider::configure_host_mappings(raw_config.git_provider_hosts.clone());
+        git_provider::configure_max_pages(raw_config.git_provider_max_pages as usize);
+        git_provider::configure_retry_policies(raw_config.git_provider_retry.clone());
+        if utils::test_mode::is_enabled() {
+            git_provider::use_mock_providers();
+        }
+
+        let database_tuning = raw_config.database.clone();
+        let storage_config = raw_config.storage.clone();
         let config = Arc::new(RwLock::new(raw_config));
         let user_id = generate_user_id();
-        let analytics = AnalyticsConfig::new().map(AnalyticsService::new);
+        let analytics = AnalyticsConfig::new()
+            .filter(|_| !utils::test_mode::is_enabled())
+            .map(AnalyticsService::new);
         let git = GitService::new();
         let project = ProjectService::new();
         let repo = RepoService::new();
@@ -109,21 +147,30 @@ impl Deployment for LocalDeployment {
             let hook = EventService::create_hook(
                 events_msg_store.clone(),
                 events_entry_count.clone(),
-                DBService::new().await?, // Temporary DB service for the hook
+                DBService::new(&database_tuning).await?, // Temporary DB service for the hook
             );
-            DBService::new_with_after_connect(hook).await?
+            DBService::new_with_after_connect(&database_tuning, hook).await?
         };
 
-        let image = ImageService::new(db.clone().pool)?;
+        let storage = object_store::from_config(&storage_config).await?;
+        let image = ImageService::new(
+            db.clone().pool,
+            storage.clone(),
+            Duration::from_secs(storage_config.signed_url_ttl_secs),
+        );
+        let jobs = JobQueue::new(db.pool.clone());
+        if let Err(e) = jobs
+            .enqueue(DELETE_ORPHANED_IMAGES_JOB, serde_json::json!({}))
+            .await
         {
-            let image_service = image.clone();
-            tokio::spawn(async move {
-                tracing::info!("Starting orphaned image cleanup...");
-                if let Err(e) = image_service.delete_orphaned_images().await {
-                    tracing::error!("Failed to clean up orphaned images: {}", e);
-                }
-            });
+            tracing::error!("Failed to queue orphaned image cleanup: {}", e);
         }
+        let artifacts = ArtifactService::new(
+            db.pool.clone(),
+            storage.clone(),
+            jobs.clone(),
+            Duration::from_secs(storage_config.signed_url_ttl_secs),
+        );
 
         let approvals = Approvals::new(msg_stores.clone());
         let queued_message_service = QueuedMessageService::new();
@@ -172,6 +219,7 @@ impl Deployment for LocalDeployment {
             user_id: user_id.clone(),
             analytics_service: s.clone(),
         });
+        let offline = OfflineService::new(events_msg_store.clone(), jobs.clone());
         let container = LocalContainerService::new(
             db.clone(),
             msg_stores.clone(),
@@ -182,6 +230,9 @@ impl Deployment for LocalDeployment {
             approvals.clone(),
             queued_message_service.clone(),
             share_publisher.clone(),
+            jobs.clone(),
+            offline.clone(),
+            artifacts.clone(),
         )
         .await;
 
@@ -199,6 +250,8 @@ impl Deployment for LocalDeployment {
             project,
             repo,
             image,
+            storage,
+            artifacts,
             filesystem,
             events,
             file_search_cache,
@@ -209,6 +262,9 @@ impl Deployment for LocalDeployment {
             remote_client,
             auth_context,
             oauth_handoffs,
+            jobs,
+            offline,
+            automation_rules: RuleCache::default(),
         };
 
         Ok(deployment)
@@ -250,6 +306,14 @@ impl Deployment for LocalDeployment {
         &self.image
     }
 
+    fn storage(&self) -> &Arc<dyn ObjectStore> {
+        &self.storage
+    }
+
+    fn artifacts(&self) -> &ArtifactService {
+        &self.artifacts
+    }
+
     fn filesystem(&self) -> &FilesystemService {
         &self.filesystem
     }
@@ -277,9 +341,47 @@ impl Deployment for LocalDeployment {
     fn auth_context(&self) -> &AuthContext {
         &self.auth_context
     }
+
+    fn jobs(&self) -> &JobQueue {
+        &self.jobs
+    }
+
+    fn offline(&self) -> &OfflineService {
+        &self.offline
+    }
 }
 
 impl LocalDeployment {
+    /// The operations plugins, automation rules and transitions act through
+    pub fn plugin_api(&self) -> Arc<dyn PluginApi> {
+        Arc::new(LocalPluginApi::new(self.clone()))
+    }
+
+    /// Start every enabled plugin found in the plugins directory
+    pub async fn spawn_plugin_host(&self) -> Vec<tokio::task::JoinHandle<()>> {
+        PluginHost::spawn(
+            &plugins_dir(),
+            self.events.msg_store().clone(),
+            self.plugin_api(),
+        )
+        .await
+    }
+
+    /// Evaluate automation rules against board events
+    pub fn spawn_rule_engine(&self) -> tokio::task::JoinHandle<()> {
+        RuleEngine::spawn(
+            self.db.clone(),
+            self.events.msg_store().clone(),
+            self.plugin_api(),
+            self.automation_rules.clone(),
+        )
+    }
+
+    /// Enabled automation rules the rule engine evaluates, to invalidate when rules change
+    pub fn automation_rules(&self) -> &RuleCache {
+        &self.automation_rules
+    }
+
     pub fn remote_client(&self) -> Result<RemoteClient, RemoteClientNotConfigured> {
         self.remote_client.clone()
     }
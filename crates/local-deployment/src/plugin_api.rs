@@ -0,0 +1,359 @@
+use async_trait::async_trait;
+use db::models::{
+    execution_process::ExecutionProcess,
+    merge::{Merge, PrMerge},
+    project::Project,
+    project_repo::ProjectRepo,
+    repo::Repo,
+    task::{CreateTask, Task},
+    task_comment::{CreateTaskComment, TaskComment},
+    workspace::{CreateWorkspace, Workspace},
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use deployment::Deployment;
+use serde_json::json;
+use services::services::{
+    container::ContainerService,
+    git_provider::{self, GitProvider, RepoIdentifier},
+    plugins::{
+        CommentParams, CreateTaskParams, MarkPrReadyParams, NotifyParams, PluginApi, PluginError,
+        RequestReviewersParams, SetStatusParams, StartTaskParams,
+    },
+    task_events::TaskActor,
+};
+use uuid::Uuid;
+
+use crate::LocalDeployment;
+
+/// [`PluginApi`] backed by the local deployment, so plugin and rule actions go through the
+/// same services as requests from the UI and show up in analytics the same way.
+pub struct LocalPluginApi {
+    deployment: LocalDeployment,
+}
+
+/// An open pull request of a task, with the provider to act on it through
+struct OpenPr {
+    merge: PrMerge,
+    provider: Box<dyn GitProvider>,
+    repo: RepoIdentifier,
+}
+
+impl LocalPluginApi {
+    pub fn new(deployment: LocalDeployment) -> Self {
+        Self { deployment }
+    }
+
+    /// The open pull requests of a task; having none rejects the action
+    async fn open_prs(&self, task_id: Uuid) -> Result<Vec<OpenPr>, PluginError> {
+        let pool = &self.deployment.db().pool;
+        Task::find_by_id(pool, task_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| PluginError::NotFound(format!("Task {task_id}")))?;
+        let merges = Merge::find_open_prs_by_task_id(pool, task_id)
+            .await
+            .map_err(internal)?;
+        if merges.is_empty() {
+            return Err(PluginError::Rejected(
+                "Task has no open pull request".to_string(),
+            ));
+        }
+
+        let mut prs = Vec::with_capacity(merges.len());
+        for merge in merges {
+            let repo = Repo::find_by_id(pool, merge.repo_id)
+                .await
+                .map_err(internal)?
+                .ok_or_else(|| PluginError::NotFound(format!("Repository {}", merge.repo_id)))?;
+            let provider = git_provider::create_provider(&repo.path).map_err(internal)?;
+            let (_, repo_identifier) =
+                git_provider::detect_provider(&repo.path).map_err(internal)?;
+            prs.push(OpenPr {
+                merge,
+                provider,
+                repo: repo_identifier,
+            });
+        }
+        Ok(prs)
+    }
+}
+
+fn internal(e: impl std::fmt::Display) -> PluginError {
+    PluginError::Internal(e.to_string())
+}
+
+#[async_trait]
+impl PluginApi for LocalPluginApi {
+    async fn create_task(
+        &self,
+        actor: &str,
+        params: CreateTaskParams,
+    ) -> Result<Task, PluginError> {
+        let pool = &self.deployment.db().pool;
+        if params.title.trim().is_empty() {
+            return Err(PluginError::Rejected("title must not be empty".to_string()));
+        }
+        Project::find_by_id(pool, params.project_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| PluginError::NotFound(format!("Project {}", params.project_id)))?;
+
+        let data =
+            CreateTask::from_title_description(params.project_id, params.title, params.description);
+        let task = Task::create(pool, &data, Uuid::new_v4())
+            .await
+            .map_err(internal)?;
+        self.deployment
+            .task_events()
+            .created(&task, &TaskActor::automation(actor))
+            .await
+            .map_err(internal)?;
+
+        self.deployment
+            .track_if_analytics_allowed(
+                "task_created",
+                json!({
+                    "task_id": task.id.to_string(),
+                    "project_id": task.project_id,
+                    "has_description": task.description.is_some(),
+                    "has_images": false,
+                    "trigger": "automation",
+                    "actor": actor,
+                }),
+            )
+            .await;
+
+        Ok(task)
+    }
+
+    async fn comment(
+        &self,
+        actor: &str,
+        params: CommentParams,
+    ) -> Result<TaskComment, PluginError> {
+        let pool = &self.deployment.db().pool;
+        if params.body.trim().is_empty() {
+            return Err(PluginError::Rejected("body must not be empty".to_string()));
+        }
+        Task::find_by_id(pool, params.task_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| PluginError::NotFound(format!("Task {}", params.task_id)))?;
+
+        TaskComment::create(
+            pool,
+            params.task_id,
+            &CreateTaskComment {
+                author: actor.to_string(),
+                body: params.body,
+            },
+        )
+        .await
+        .map_err(internal)
+    }
+
+    async fn set_status(&self, actor: &str, params: SetStatusParams) -> Result<Task, PluginError> {
+        let pool = &self.deployment.db().pool;
+        let task = Task::find_by_id(pool, params.task_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| PluginError::NotFound(format!("Task {}", params.task_id)))?;
+        if task.status == params.status {
+            return Ok(task);
+        }
+
+        let updated = self
+            .deployment
+            .task_events()
+            .set_status(
+                task.id,
+                params.status.clone(),
+                &TaskActor::automation(actor),
+            )
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| PluginError::NotFound(format!("Task {}", task.id)))?;
+        tracing::info!(
+            "{} moved task {} from {} to {}",
+            actor,
+            task.id,
+            task.status,
+            params.status
+        );
+        Ok(updated)
+    }
+
+    /// Start a fresh attempt for the task against every repository of its project,
+    /// branching from each repository's current branch.
+    async fn start_task(
+        &self,
+        actor: &str,
+        params: StartTaskParams,
+    ) -> Result<ExecutionProcess, PluginError> {
+        let pool = &self.deployment.db().pool;
+        let task = Task::find_by_id(pool, params.task_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| PluginError::NotFound(format!("Task {}", params.task_id)))?;
+        let project = task
+            .parent_project(pool)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| PluginError::NotFound(format!("Project {}", task.project_id)))?;
+
+        let repos = ProjectRepo::find_repos_for_project(pool, project.id)
+            .await
+            .map_err(internal)?;
+        if repos.is_empty() {
+            return Err(PluginError::Rejected(
+                "Project has no repositories".to_string(),
+            ));
+        }
+        let mut workspace_repos = Vec::with_capacity(repos.len());
+        for repo in &repos {
+            let target_branch = self
+                .deployment
+                .git()
+                .get_current_branch(&repo.path)
+                .map_err(internal)?;
+            workspace_repos.push(CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch,
+            });
+        }
+
+        let executor_profile_id = match params.executor_profile_id {
+            Some(profile) => profile,
+            None => self
+                .deployment
+                .config()
+                .read()
+                .await
+                .executor_profile
+                .clone(),
+        };
+
+        let workspace_id = Uuid::new_v4();
+        let branch = self
+            .deployment
+            .container()
+            .git_branch_from_workspace(&workspace_id, &task.title)
+            .await;
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch,
+                agent_working_dir: project
+                    .default_agent_working_dir
+                    .clone()
+                    .filter(|dir| !dir.is_empty()),
+            },
+            workspace_id,
+            task.id,
+        )
+        .await
+        .map_err(internal)?;
+        WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos)
+            .await
+            .map_err(internal)?;
+
+        let process = self
+            .deployment
+            .container()
+            .start_workspace(&workspace, executor_profile_id.clone())
+            .await
+            .map_err(internal)?;
+
+        self.deployment
+            .track_if_analytics_allowed(
+                "task_attempt_started",
+                json!({
+                    "task_id": task.id.to_string(),
+                    "executor": &executor_profile_id.executor,
+                    "variant": &executor_profile_id.variant,
+                    "workspace_id": workspace.id.to_string(),
+                    "trigger": "automation",
+                    "actor": actor,
+                }),
+            )
+            .await;
+
+        Ok(process)
+    }
+
+    async fn mark_pr_ready(
+        &self,
+        actor: &str,
+        params: MarkPrReadyParams,
+    ) -> Result<(), PluginError> {
+        for pr in self.open_prs(params.task_id).await? {
+            let number = pr.merge.pr_info.number as u64;
+            let pr_info = pr
+                .provider
+                .set_draft(&pr.repo, number, false)
+                .await
+                .map_err(internal)?;
+            Merge::update_status(
+                &self.deployment.db().pool,
+                pr.merge.id,
+                pr_info.state.into(),
+                pr_info.merge_commit_sha,
+            )
+            .await
+            .map_err(internal)?;
+            tracing::info!("{} marked PR #{} ready for review", actor, number);
+        }
+        Ok(())
+    }
+
+    async fn request_reviewers(
+        &self,
+        actor: &str,
+        params: RequestReviewersParams,
+    ) -> Result<(), PluginError> {
+        let reviewers: Vec<String> = params
+            .reviewers
+            .iter()
+            .map(|reviewer| reviewer.trim().trim_start_matches('@').to_string())
+            .filter(|reviewer| !reviewer.is_empty())
+            .collect();
+        if reviewers.is_empty() {
+            return Err(PluginError::Rejected(
+                "reviewers must not be empty".to_string(),
+            ));
+        }
+
+        for pr in self.open_prs(params.task_id).await? {
+            let number = pr.merge.pr_info.number as u64;
+            pr.provider
+                .request_reviewers(&pr.repo, number, &reviewers)
+                .await
+                .map_err(internal)?;
+            tracing::info!(
+                "{} requested reviews from {} on PR #{}",
+                actor,
+                reviewers.join(", "),
+                number
+            );
+        }
+        Ok(())
+    }
+
+    async fn notify(&self, _actor: &str, params: NotifyParams) -> Result<(), PluginError> {
+        if params.message.trim().is_empty() {
+            return Err(PluginError::Rejected(
+                "message must not be empty".to_string(),
+            ));
+        }
+        let task = Task::find_by_id(&self.deployment.db().pool, params.task_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| PluginError::NotFound(format!("Task {}", params.task_id)))?;
+        self.deployment
+            .container()
+            .notification_service()
+            .notify(&task.title, &params.message)
+            .await;
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+-- Prompt templates, task templates and automation rules published to an organization
+CREATE TYPE library_item_kind AS ENUM ('prompt_template', 'task_template', 'automation_rule');
+
+CREATE TABLE IF NOT EXISTS library_items (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    kind            library_item_kind NOT NULL,
+    name            TEXT NOT NULL,
+    content         JSONB NOT NULL,
+    version         BIGINT NOT NULL DEFAULT 1,
+    published_by    UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_library_items_organization ON library_items (organization_id);
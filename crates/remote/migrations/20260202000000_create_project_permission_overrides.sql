@@ -0,0 +1,22 @@
+-- Per-project exceptions to what organization roles may do. An override applies either to every
+-- member with a role or to one member; the latter wins.
+CREATE TYPE project_permission AS ENUM ('start_execution', 'create_pr', 'merge', 'edit_automation_rules');
+
+CREATE TABLE IF NOT EXISTS project_permission_overrides (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id  UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    role        member_role,
+    user_id     UUID REFERENCES users(id) ON DELETE CASCADE,
+    permission  project_permission NOT NULL,
+    allowed     BOOLEAN NOT NULL,
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((role IS NULL) <> (user_id IS NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_project_permission_overrides_role
+    ON project_permission_overrides (project_id, role, permission)
+    WHERE role IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_project_permission_overrides_user
+    ON project_permission_overrides (project_id, user_id, permission)
+    WHERE user_id IS NOT NULL;
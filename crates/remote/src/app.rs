@@ -27,10 +27,17 @@ impl Server {
         fields(listen_addr = %config.listen_addr)
     )]
     pub async fn run(config: RemoteServerConfig) -> anyhow::Result<()> {
-        let pool = db::create_pool(&config.database_url)
+        let pool = db::create_pool(&config.database_url, config.database_max_connections)
             .await
             .context("failed to create postgres pool")?;
 
+        let read_pool = match config.database_read_url.as_deref() {
+            Some(read_url) => db::create_pool(read_url, config.database_max_connections)
+                .await
+                .context("failed to create postgres read replica pool")?,
+            None => pool.clone(),
+        };
+
         db::migrate(&pool)
             .await
             .context("failed to run database migrations")?;
@@ -126,6 +133,7 @@ impl Server {
 
         let state = AppState::new(
             pool.clone(),
+            read_pool,
             config.clone(),
             jwt,
             handoff_service,
@@ -7,6 +7,9 @@ use thiserror::Error;
 #[derive(Debug, Clone)]
 pub struct RemoteServerConfig {
     pub database_url: String,
+    /// Read replica for heavy read-only queries; the primary serves them when unset
+    pub database_read_url: Option<String>,
+    pub database_max_connections: u32,
     pub listen_addr: String,
     pub server_public_base_url: Option<String>,
     pub auth: AuthConfig,
@@ -130,6 +133,17 @@ impl RemoteServerConfig {
             .or_else(|_| env::var("DATABASE_URL"))
             .map_err(|_| ConfigError::MissingVar("SERVER_DATABASE_URL"))?;
 
+        let database_read_url = env::var("SERVER_DATABASE_READ_URL").ok();
+
+        let database_max_connections = match env::var("SERVER_DATABASE_MAX_CONNECTIONS") {
+            Ok(v) => v
+                .parse()
+                .ok()
+                .filter(|max| *max > 0)
+                .ok_or(ConfigError::InvalidVar("SERVER_DATABASE_MAX_CONNECTIONS"))?,
+            Err(_) => 10,
+        };
+
         let listen_addr =
             env::var("SERVER_LISTEN_ADDR").unwrap_or_else(|_| "0.0.0.0:8081".to_string());
 
@@ -156,6 +170,8 @@ impl RemoteServerConfig {
 
         Ok(Self {
             database_url,
+            database_read_url,
+            database_max_connections,
             listen_addr,
             server_public_base_url,
             auth,
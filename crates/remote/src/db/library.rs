@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::PgPool;
+use thiserror::Error;
+pub use utils::api::library::{LibraryItem, LibraryItemKind};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct PublishLibraryItemData {
+    pub organization_id: Uuid,
+    pub kind: LibraryItemKind,
+    pub name: String,
+    pub content: Value,
+    pub published_by: Uuid,
+}
+
+#[derive(Debug, Error)]
+pub enum LibraryError {
+    #[error("library item not found")]
+    NotFound,
+    #[error("library item content must be a JSON object")]
+    InvalidContent,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+pub struct LibraryRepository;
+
+impl LibraryRepository {
+    pub async fn list_by_organization(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<Vec<LibraryItem>, LibraryError> {
+        let items = sqlx::query_as!(
+            LibraryItem,
+            r#"
+            SELECT
+                id               AS "id!: Uuid",
+                organization_id  AS "organization_id!: Uuid",
+                kind             AS "kind!: LibraryItemKind",
+                name             AS "name!",
+                content          AS "content!: Value",
+                version          AS "version!",
+                published_by     AS "published_by?: Uuid",
+                created_at       AS "created_at!: DateTime<Utc>",
+                updated_at       AS "updated_at!: DateTime<Utc>"
+            FROM library_items
+            WHERE organization_id = $1
+            ORDER BY kind, name
+            "#,
+            organization_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(items)
+    }
+
+    pub async fn fetch_by_id(pool: &PgPool, id: Uuid) -> Result<Option<LibraryItem>, LibraryError> {
+        let item = sqlx::query_as!(
+            LibraryItem,
+            r#"
+            SELECT
+                id               AS "id!: Uuid",
+                organization_id  AS "organization_id!: Uuid",
+                kind             AS "kind!: LibraryItemKind",
+                name             AS "name!",
+                content          AS "content!: Value",
+                version          AS "version!",
+                published_by     AS "published_by?: Uuid",
+                created_at       AS "created_at!: DateTime<Utc>",
+                updated_at       AS "updated_at!: DateTime<Utc>"
+            FROM library_items
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(item)
+    }
+
+    pub async fn publish(
+        pool: &PgPool,
+        data: PublishLibraryItemData,
+    ) -> Result<LibraryItem, LibraryError> {
+        if !data.content.is_object() {
+            return Err(LibraryError::InvalidContent);
+        }
+        let item = sqlx::query_as!(
+            LibraryItem,
+            r#"
+            INSERT INTO library_items (organization_id, kind, name, content, published_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING
+                id               AS "id!: Uuid",
+                organization_id  AS "organization_id!: Uuid",
+                kind             AS "kind!: LibraryItemKind",
+                name             AS "name!",
+                content          AS "content!: Value",
+                version          AS "version!",
+                published_by     AS "published_by?: Uuid",
+                created_at       AS "created_at!: DateTime<Utc>",
+                updated_at       AS "updated_at!: DateTime<Utc>"
+            "#,
+            data.organization_id,
+            data.kind as LibraryItemKind,
+            data.name,
+            data.content,
+            data.published_by
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(item)
+    }
+
+    /// Replace the item's name and content, publishing them as the next version
+    pub async fn update(
+        pool: &PgPool,
+        id: Uuid,
+        name: &str,
+        content: &Value,
+        published_by: Uuid,
+    ) -> Result<LibraryItem, LibraryError> {
+        if !content.is_object() {
+            return Err(LibraryError::InvalidContent);
+        }
+        sqlx::query_as!(
+            LibraryItem,
+            r#"
+            UPDATE library_items
+            SET name = $2,
+                content = $3,
+                published_by = $4,
+                version = version + 1,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING
+                id               AS "id!: Uuid",
+                organization_id  AS "organization_id!: Uuid",
+                kind             AS "kind!: LibraryItemKind",
+                name             AS "name!",
+                content          AS "content!: Value",
+                version          AS "version!",
+                published_by     AS "published_by?: Uuid",
+                created_at       AS "created_at!: DateTime<Utc>",
+                updated_at       AS "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            name,
+            content,
+            published_by
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(LibraryError::NotFound)
+    }
+
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<(), LibraryError> {
+        let result = sqlx::query!("DELETE FROM library_items WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(LibraryError::NotFound);
+        }
+        Ok(())
+    }
+}
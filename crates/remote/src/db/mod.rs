@@ -2,10 +2,12 @@ pub mod auth;
 pub mod github_app;
 pub mod identity_errors;
 pub mod invitations;
+pub mod library;
 pub mod oauth;
 pub mod oauth_accounts;
 pub mod organization_members;
 pub mod organizations;
+pub mod project_permissions;
 pub mod projects;
 pub mod reviews;
 pub mod tasks;
@@ -19,9 +21,12 @@ pub(crate) async fn migrate(pool: &PgPool) -> Result<(), MigrateError> {
     sqlx::migrate!("./migrations").run(pool).await
 }
 
-pub(crate) async fn create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
+pub(crate) async fn create_pool(
+    database_url: &str,
+    max_connections: u32,
+) -> Result<PgPool, sqlx::Error> {
     PgPoolOptions::new()
-        .max_connections(10)
+        .max_connections(max_connections)
         .connect(database_url)
         .await
 }
@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+pub use utils::api::projects::{ProjectPermission, ProjectPermissionOverride};
+use uuid::Uuid;
+
+use super::organization_members::MemberRole;
+
+pub struct ProjectPermissionRepository;
+
+impl ProjectPermissionRepository {
+    pub async fn list_by_project(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<ProjectPermissionOverride>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectPermissionOverride,
+            r#"
+            SELECT
+                project_id  AS "project_id!: Uuid",
+                role        AS "role?: MemberRole",
+                user_id     AS "user_id?: Uuid",
+                permission  AS "permission!: ProjectPermission",
+                allowed     AS "allowed!",
+                updated_at  AS "updated_at!: DateTime<Utc>"
+            FROM project_permission_overrides
+            WHERE project_id = $1
+            ORDER BY permission, role, user_id
+            "#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Set the override of a role or of one member; `None` removes it
+    pub async fn set(
+        pool: &PgPool,
+        project_id: Uuid,
+        role: Option<MemberRole>,
+        user_id: Option<Uuid>,
+        permission: ProjectPermission,
+        allowed: Option<bool>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM project_permission_overrides
+            WHERE project_id = $1
+              AND role IS NOT DISTINCT FROM $2
+              AND user_id IS NOT DISTINCT FROM $3
+              AND permission = $4
+            "#,
+            project_id,
+            role as Option<MemberRole>,
+            user_id,
+            permission as ProjectPermission
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if let Some(allowed) = allowed {
+            sqlx::query!(
+                r#"
+                INSERT INTO project_permission_overrides (project_id, role, user_id, permission, allowed)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+                project_id,
+                role as Option<MemberRole>,
+                user_id,
+                permission as ProjectPermission,
+                allowed
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+}
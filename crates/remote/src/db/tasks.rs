@@ -70,6 +70,8 @@ pub struct UpdateSharedTaskData {
     pub description: Option<String>,
     pub status: Option<TaskStatus>,
     pub acting_user_id: Uuid,
+    /// Only update when the task still has this `updated_at`
+    pub expected_updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -142,6 +144,40 @@ impl<'a> SharedTaskRepository<'a> {
         Ok(task)
     }
 
+    /// All tasks of a project, deleted ones included so mirrors can drop them too
+    pub async fn list_by_project(
+        &self,
+        project_id: Uuid,
+    ) -> Result<Vec<SharedTask>, SharedTaskError> {
+        let tasks = sqlx::query_as!(
+            SharedTask,
+            r#"
+            SELECT
+                id                  AS "id!",
+                organization_id     AS "organization_id!: Uuid",
+                project_id          AS "project_id!",
+                creator_user_id     AS "creator_user_id?: Uuid",
+                assignee_user_id    AS "assignee_user_id?: Uuid",
+                deleted_by_user_id  AS "deleted_by_user_id?: Uuid",
+                title               AS "title!",
+                description         AS "description?",
+                status              AS "status!: TaskStatus",
+                deleted_at          AS "deleted_at?",
+                shared_at           AS "shared_at?",
+                created_at          AS "created_at!",
+                updated_at          AS "updated_at!"
+            FROM shared_tasks
+            WHERE project_id = $1
+            ORDER BY created_at
+            "#,
+            project_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(tasks)
+    }
+
     pub async fn create(
         &self,
         data: CreateSharedTaskData,
@@ -231,6 +267,7 @@ impl<'a> SharedTaskRepository<'a> {
         WHERE t.id = $1
           AND t.assignee_user_id = $5
           AND t.deleted_at IS NULL
+          AND ($6::timestamptz IS NULL OR t.updated_at = $6)
         RETURNING
             t.id                AS "id!",
             t.organization_id   AS "organization_id!: Uuid",
@@ -250,11 +287,15 @@ impl<'a> SharedTaskRepository<'a> {
             data.title,
             data.description,
             data.status as Option<TaskStatus>,
-            data.acting_user_id
+            data.acting_user_id,
+            data.expected_updated_at
         )
         .fetch_optional(&mut *tx)
         .await?
-        .ok_or_else(|| SharedTaskError::NotFound)?;
+        .ok_or_else(|| match data.expected_updated_at {
+            Some(_) => SharedTaskError::Conflict("task changed since it was read".to_string()),
+            None => SharedTaskError::NotFound,
+        })?;
 
         ensure_text_size(&task.title, task.description.as_deref())?;
 
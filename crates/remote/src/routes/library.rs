@@ -0,0 +1,160 @@
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{get, patch},
+};
+use serde::Deserialize;
+use tracing::instrument;
+use utils::api::library::{
+    LibraryItem, ListLibraryItemsResponse, PublishLibraryItemRequest, UpdateLibraryItemRequest,
+};
+use uuid::Uuid;
+
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_admin_access, ensure_member_access},
+};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::library::{LibraryError, LibraryRepository, PublishLibraryItemData},
+};
+
+#[derive(Debug, Deserialize)]
+struct LibraryQuery {
+    organization_id: Uuid,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/library",
+            get(list_library_items).post(publish_library_item),
+        )
+        .route(
+            "/library/{item_id}",
+            patch(update_library_item).delete(delete_library_item),
+        )
+}
+
+fn library_error_response(error: LibraryError, context: &str) -> ErrorResponse {
+    match error {
+        LibraryError::NotFound => {
+            ErrorResponse::new(StatusCode::NOT_FOUND, "library item not found")
+        }
+        LibraryError::InvalidContent => {
+            ErrorResponse::new(StatusCode::BAD_REQUEST, "content must be a JSON object")
+        }
+        LibraryError::Database(error) => {
+            tracing::error!(?error, "{context}");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        }
+    }
+}
+
+async fn load_item(state: &AppState, item_id: Uuid) -> Result<LibraryItem, ErrorResponse> {
+    LibraryRepository::fetch_by_id(state.pool(), item_id)
+        .await
+        .map_err(|error| library_error_response(error, "failed to load library item"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "library item not found"))
+}
+
+#[instrument(
+    name = "library.list_library_items",
+    skip(state, ctx, params),
+    fields(org_id = %params.organization_id, user_id = %ctx.user.id)
+)]
+async fn list_library_items(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(params): Query<LibraryQuery>,
+) -> Result<Json<ListLibraryItemsResponse>, ErrorResponse> {
+    ensure_member_access(state.pool(), params.organization_id, ctx.user.id).await?;
+
+    let items = LibraryRepository::list_by_organization(state.read_pool(), params.organization_id)
+        .await
+        .map_err(|error| library_error_response(error, "failed to list library items"))?;
+
+    Ok(Json(ListLibraryItemsResponse { items }))
+}
+
+#[instrument(
+    name = "library.publish_library_item",
+    skip(state, ctx, payload),
+    fields(org_id = %payload.organization_id, user_id = %ctx.user.id)
+)]
+async fn publish_library_item(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<PublishLibraryItemRequest>,
+) -> Result<(StatusCode, Json<LibraryItem>), ErrorResponse> {
+    ensure_member_access(state.pool(), payload.organization_id, ctx.user.id).await?;
+
+    let item = LibraryRepository::publish(
+        state.pool(),
+        PublishLibraryItemData {
+            organization_id: payload.organization_id,
+            kind: payload.kind,
+            name: payload.name,
+            content: payload.content,
+            published_by: ctx.user.id,
+        },
+    )
+    .await
+    .map_err(|error| library_error_response(error, "failed to publish library item"))?;
+
+    Ok((StatusCode::CREATED, Json(item)))
+}
+
+#[instrument(
+    name = "library.update_library_item",
+    skip(state, ctx, payload),
+    fields(item_id = %item_id, user_id = %ctx.user.id)
+)]
+async fn update_library_item(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<UpdateLibraryItemRequest>,
+) -> Result<Json<LibraryItem>, ErrorResponse> {
+    let item = load_item(&state, item_id).await?;
+    ensure_member_access(state.pool(), item.organization_id, ctx.user.id).await?;
+
+    let item = LibraryRepository::update(
+        state.pool(),
+        item_id,
+        &payload.name,
+        &payload.content,
+        ctx.user.id,
+    )
+    .await
+    .map_err(|error| library_error_response(error, "failed to update library item"))?;
+
+    Ok(Json(item))
+}
+
+#[instrument(
+    name = "library.delete_library_item",
+    skip(state, ctx),
+    fields(item_id = %item_id, user_id = %ctx.user.id)
+)]
+async fn delete_library_item(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(item_id): Path<Uuid>,
+) -> Result<StatusCode, ErrorResponse> {
+    let item = load_item(&state, item_id).await?;
+    // Anyone in the organization may update an item, but only its publisher or an admin removes it
+    if item.published_by == Some(ctx.user.id) {
+        ensure_member_access(state.pool(), item.organization_id, ctx.user.id).await?;
+    } else {
+        ensure_admin_access(state.pool(), item.organization_id, ctx.user.id).await?;
+    }
+
+    LibraryRepository::delete(state.pool(), item_id)
+        .await
+        .map_err(|error| library_error_response(error, "failed to delete library item"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -18,6 +18,7 @@ mod electric_proxy;
 mod error;
 mod github_app;
 mod identity;
+mod library;
 mod oauth;
 pub(crate) mod organization_members;
 mod organizations;
@@ -58,6 +59,7 @@ pub fn router(state: AppState) -> Router {
     let v1_protected = Router::<AppState>::new()
         .merge(identity::router())
         .merge(projects::router())
+        .merge(library::router())
         .merge(tasks::router())
         .merge(organizations::router())
         .merge(organization_members::protected_router())
@@ -7,14 +7,28 @@ use axum::{
 use serde::Deserialize;
 use serde_json::Value;
 use tracing::instrument;
-use utils::api::projects::{ListProjectsResponse, RemoteProject};
+use utils::api::{
+    organizations::MemberRole,
+    projects::{
+        EffectiveProjectPermissions, ListProjectPermissionOverridesResponse, ListProjectsResponse,
+        RemoteProject, SetProjectPermissionOverrideRequest,
+    },
+};
 use uuid::Uuid;
 
-use super::{error::ErrorResponse, organization_members::ensure_member_access};
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_admin_access, ensure_member_access, ensure_project_access},
+};
 use crate::{
     AppState,
     auth::RequestContext,
-    db::projects::{CreateProjectData, Project, ProjectError, ProjectRepository},
+    db::{
+        organization_members,
+        organizations::OrganizationRepository,
+        project_permissions::ProjectPermissionRepository,
+        projects::{CreateProjectData, Project, ProjectError, ProjectRepository},
+    },
 };
 
 #[derive(Debug, Deserialize)]
@@ -34,6 +48,14 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/projects", get(list_projects).post(create_project))
         .route("/projects/{project_id}", get(get_project))
+        .route(
+            "/projects/{project_id}/permissions",
+            get(list_permission_overrides).patch(set_permission_override),
+        )
+        .route(
+            "/projects/{project_id}/permissions/me",
+            get(get_my_permissions),
+        )
 }
 
 #[instrument(
@@ -153,6 +175,119 @@ async fn create_project(
     Ok(Json(to_remote_project(project)))
 }
 
+async fn load_permission_overrides(
+    state: &AppState,
+    project_id: Uuid,
+) -> Result<ListProjectPermissionOverridesResponse, ErrorResponse> {
+    let overrides = ProjectPermissionRepository::list_by_project(state.pool(), project_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %project_id, "failed to load permission overrides");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        })?;
+    Ok(ListProjectPermissionOverridesResponse { overrides })
+}
+
+#[instrument(
+    name = "projects.list_permission_overrides",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn list_permission_overrides(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<ListProjectPermissionOverridesResponse>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    Ok(Json(load_permission_overrides(&state, project_id).await?))
+}
+
+#[instrument(
+    name = "projects.set_permission_override",
+    skip(state, ctx, payload),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn set_permission_override(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<SetProjectPermissionOverrideRequest>,
+) -> Result<Json<ListProjectPermissionOverridesResponse>, ErrorResponse> {
+    let organization_id = ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    ensure_admin_access(state.pool(), organization_id, ctx.user.id).await?;
+
+    match (payload.role, payload.user_id) {
+        (Some(_), None) => {}
+        (None, Some(user_id)) => {
+            let is_member = organization_members::is_member(state.pool(), organization_id, user_id)
+                .await
+                .map_err(|error| {
+                    tracing::error!(?error, %user_id, "failed to check organization membership");
+                    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                })?;
+            if !is_member {
+                return Err(ErrorResponse::new(
+                    StatusCode::BAD_REQUEST,
+                    "user is not a member of the organization",
+                ));
+            }
+        }
+        _ => {
+            return Err(ErrorResponse::new(
+                StatusCode::BAD_REQUEST,
+                "exactly one of role and user_id must be set",
+            ));
+        }
+    }
+
+    ProjectPermissionRepository::set(
+        state.pool(),
+        project_id,
+        payload.role,
+        payload.user_id,
+        payload.permission,
+        payload.allowed,
+    )
+    .await
+    .map_err(|error| {
+        tracing::error!(?error, %project_id, "failed to set permission override");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+    })?;
+
+    Ok(Json(load_permission_overrides(&state, project_id).await?))
+}
+
+/// What the caller may do in the project, so clients can hide actions they would be refused
+#[instrument(
+    name = "projects.get_my_permissions",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn get_my_permissions(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<EffectiveProjectPermissions>, ErrorResponse> {
+    let organization_id = ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    let role = OrganizationRepository::new(state.pool())
+        .check_user_role(organization_id, ctx.user.id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %organization_id, "failed to load member role");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        })?
+        .unwrap_or(MemberRole::Member);
+    let overrides = load_permission_overrides(&state, project_id)
+        .await?
+        .overrides;
+
+    Ok(Json(EffectiveProjectPermissions::resolve(
+        role,
+        ctx.user.id,
+        &overrides,
+    )))
+}
+
 fn to_remote_project(project: Project) -> RemoteProject {
     RemoteProject {
         id: project.id,
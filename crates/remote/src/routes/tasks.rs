@@ -5,6 +5,7 @@ use axum::{
     response::{IntoResponse, Response},
     routing::{delete, get, patch, post},
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::{Span, instrument};
@@ -31,7 +32,7 @@ use crate::{
 
 pub fn router() -> Router<AppState> {
     Router::new()
-        .route("/tasks", post(create_shared_task))
+        .route("/tasks", get(list_shared_tasks).post(create_shared_task))
         .route("/tasks/check", post(check_tasks_existence))
         .route("/tasks/{task_id}", patch(update_shared_task))
         .route("/tasks/{task_id}", delete(delete_shared_task))
@@ -81,6 +82,37 @@ pub async fn get_task_assignees_by_project(
     (StatusCode::OK, Json(assignees)).into_response()
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ListSharedTasksQuery {
+    pub project_id: Uuid,
+}
+
+#[instrument(
+    name = "tasks.list_shared_tasks",
+    skip(state, ctx, query),
+    fields(user_id = %ctx.user.id, project_id = %query.project_id, org_id = tracing::field::Empty)
+)]
+pub async fn list_shared_tasks(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<ListSharedTasksQuery>,
+) -> Response {
+    let pool = state.pool();
+
+    match ensure_project_access(pool, ctx.user.id, query.project_id).await {
+        Ok(org_id) => {
+            Span::current().record("org_id", format_args!("{org_id}"));
+        }
+        Err(error) => return error.into_response(),
+    }
+
+    let repo = SharedTaskRepository::new(pool);
+    match repo.list_by_project(query.project_id).await {
+        Ok(tasks) => (StatusCode::OK, Json(ListSharedTasksResponse { tasks })).into_response(),
+        Err(error) => task_error_response(error, "failed to list shared tasks"),
+    }
+}
+
 #[instrument(
     name = "tasks.create_shared_task",
     skip(state, ctx, payload),
@@ -180,8 +212,16 @@ pub async fn update_shared_task(
         title,
         description,
         status,
+        expected_updated_at,
     } = payload;
 
+    if expected_updated_at.is_some_and(|expected| expected != existing.updated_at) {
+        return task_error_response(
+            SharedTaskError::Conflict("task changed since it was read".to_string()),
+            "stale shared task update",
+        );
+    }
+
     let next_title = title.as_deref().unwrap_or(existing.title.as_str());
     let next_description = description.as_deref().or(existing.description.as_deref());
 
@@ -194,6 +234,7 @@ pub async fn update_shared_task(
         description,
         status,
         acting_user_id: ctx.user.id,
+        expected_updated_at,
     };
 
     match repo.update(task_id, data).await {
@@ -349,6 +390,14 @@ pub struct UpdateSharedTaskRequest {
     pub title: Option<String>,
     pub description: Option<String>,
     pub status: Option<TaskStatus>,
+    /// Reject the update with a conflict when the task was changed after this time
+    #[serde(default)]
+    pub expected_updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListSharedTasksResponse {
+    pub tasks: Vec<SharedTask>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
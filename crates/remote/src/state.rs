@@ -13,6 +13,9 @@ use crate::{
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
+    /// Read replica for heavy read-only queries, or the primary when none is configured. Reads
+    /// here can lag behind writes, so anything read back right after a write uses `pool`.
+    pub read_pool: PgPool,
     pub config: RemoteServerConfig,
     pub jwt: Arc<JwtService>,
     pub mailer: Arc<dyn Mailer>,
@@ -28,6 +31,7 @@ impl AppState {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         pool: PgPool,
+        read_pool: PgPool,
         config: RemoteServerConfig,
         jwt: Arc<JwtService>,
         handoff: Arc<OAuthHandoffService>,
@@ -40,6 +44,7 @@ impl AppState {
     ) -> Self {
         Self {
             pool,
+            read_pool,
             config,
             jwt,
             mailer,
@@ -56,6 +61,10 @@ impl AppState {
         &self.pool
     }
 
+    pub fn read_pool(&self) -> &PgPool {
+        &self.read_pool
+    }
+
     pub fn config(&self) -> &RemoteServerConfig {
         &self.config
     }
@@ -21,6 +21,10 @@ fn generate_types_content() -> String {
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
         db::models::repo::Repo::decl(),
+        db::models::managed_repo::ManagedRepo::decl(),
+        db::models::repo_fetch_state::RepoFetchState::decl(),
+        db::models::repo_fetch_state::UpdateRepoFetchSettings::decl(),
+        db::models::branch_divergence::BranchDivergence::decl(),
         db::models::project_repo::ProjectRepo::decl(),
         db::models::project_repo::CreateProjectRepo::decl(),
         db::models::project_repo::UpdateProjectRepo::decl(),
@@ -33,6 +37,75 @@ fn generate_types_content() -> String {
         db::models::task::TaskStatus::decl(),
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
+        db::models::board::BoardCard::decl(),
+        db::models::board::BoardColumn::decl(),
+        db::models::board::BoardSnapshot::decl(),
+        db::models::saved_view::ViewSortField::decl(),
+        db::models::saved_view::ViewGrouping::decl(),
+        db::models::saved_view::SavedView::decl(),
+        db::models::saved_view::CreateSavedView::decl(),
+        db::models::saved_view::UpdateSavedView::decl(),
+        services::services::saved_views::ViewGroup::decl(),
+        services::services::saved_views::ViewBoard::decl(),
+        db::models::column_sla::SlaAction::decl(),
+        db::models::column_sla::ColumnSla::decl(),
+        db::models::column_sla::SetColumnSla::decl(),
+        db::models::column_sla::SlaEscalation::decl(),
+        services::services::sla::SlaBreach::decl(),
+        db::models::task_comment::TaskComment::decl(),
+        db::models::task_comment::CreateTaskComment::decl(),
+        db::models::task_event::TaskEventKind::decl(),
+        db::models::task_event::TaskEventSource::decl(),
+        db::models::task_event::TaskSnapshot::decl(),
+        db::models::task_event::TaskEvent::decl(),
+        db::models::share_link::ShareLink::decl(),
+        db::models::service_account::ServiceAccountScope::decl(),
+        db::models::service_account::ServiceAccount::decl(),
+        db::models::service_account::CreateServiceAccount::decl(),
+        db::models::service_account::UpdateServiceAccount::decl(),
+        db::models::tenant::Tenant::decl(),
+        db::models::tenant::TenantMember::decl(),
+        db::models::tenant::AddTenantMember::decl(),
+        db::models::tenant::CreateTenant::decl(),
+        db::models::tenant::UpdateTenant::decl(),
+        db::models::tenant_quota::TenantQuota::decl(),
+        db::models::tenant_quota::UpdateTenantQuota::decl(),
+        db::models::tenant_quota::TenantUsage::decl(),
+        db::models::project_mirror::ProjectMirror::decl(),
+        db::models::project_mirror::MirroredFields::decl(),
+        db::models::template::TemplateKind::decl(),
+        db::models::template::Template::decl(),
+        db::models::template::CreateTemplate::decl(),
+        db::models::template::UpdateTemplate::decl(),
+        db::models::library_link::LibraryLink::decl(),
+        db::models::automation_rule::RuleAction::decl(),
+        db::models::automation_rule::AutomationRule::decl(),
+        db::models::automation_transition::AutomationTransition::decl(),
+        db::models::job::JobStatus::decl(),
+        db::models::job::Job::decl(),
+        db::models::job::JobStatusCount::decl(),
+        db::models::policy_violation_report::PolicyViolationKind::decl(),
+        db::models::policy_violation_report::PolicyViolation::decl(),
+        db::models::policy_violation_report::PolicyViolationReport::decl(),
+        db::models::dependency_report::DependencyEcosystem::decl(),
+        db::models::dependency_report::FindingSeverity::decl(),
+        db::models::dependency_report::DependencyFindingKind::decl(),
+        db::models::dependency_report::DependencyFinding::decl(),
+        db::models::dependency_report::DependencyReport::decl(),
+        db::models::lint_report::LintSeverity::decl(),
+        db::models::lint_report::LintFinding::decl(),
+        db::models::lint_report::LintReport::decl(),
+        db::models::coverage_report::CoverageReport::decl(),
+        db::models::command_run::CommandKind::decl(),
+        db::models::command_quarantine::CommandQuarantine::decl(),
+        db::models::context_compaction::ContextCompaction::decl(),
+        db::models::routing_decision::TurnPurpose::decl(),
+        db::models::routing_decision::TaskSize::decl(),
+        db::models::routing_decision::RoutingDecision::decl(),
+        db::models::automation_rule::CreateAutomationRule::decl(),
+        db::models::automation_rule::UpdateAutomationRule::decl(),
+        db::models::automation_transition::CreateAutomationTransition::decl(),
+        db::models::automation_transition::UpdateAutomationTransition::decl(),
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
@@ -50,17 +123,26 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
+        db::models::execution_scope::ScopedFile::decl(),
+        db::models::execution_scope::ExecutionScope::decl(),
+        db::models::repo_chain_step::RepoChainStepStatus::decl(),
+        db::models::repo_chain_step::RepoChainStep::decl(),
+        db::models::repo_chain_step::CreateRepoChainStep::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
         db::models::merge::MergeStatus::decl(),
         db::models::merge::PullRequestInfo::decl(),
+        db::models::pr_comment::PrComment::decl(),
+        db::models::pr_comment::AddressedPrComment::decl(),
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::CreateApprovalRequest::decl(),
         utils::approvals::ApprovalResponse::decl(),
         utils::diff::Diff::decl(),
         utils::diff::DiffChangeKind::decl(),
         utils::response::ApiResponse::<()>::decl(),
+        utils::response::Pagination::decl(),
+        utils::response::PageQuery::decl(),
         utils::api::oauth::LoginStatus::decl(),
         utils::api::oauth::ProfileResponse::decl(),
         utils::api::oauth::ProviderProfile::decl(),
@@ -86,13 +168,54 @@ fn generate_types_content() -> String {
         utils::api::organizations::ListMembersResponse::decl(),
         utils::api::organizations::UpdateMemberRoleRequest::decl(),
         utils::api::organizations::UpdateMemberRoleResponse::decl(),
+        utils::api::library::LibraryItemKind::decl(),
+        utils::api::library::LibraryItem::decl(),
+        utils::api::library::ListLibraryItemsResponse::decl(),
+        utils::api::library::PublishLibraryItemRequest::decl(),
+        utils::api::library::UpdateLibraryItemRequest::decl(),
+        services::services::library::LibraryEntry::decl(),
         utils::api::projects::RemoteProject::decl(),
         utils::api::projects::ListProjectsResponse::decl(),
         utils::api::projects::RemoteProjectMembersResponse::decl(),
+        utils::api::projects::ProjectPermission::decl(),
+        utils::api::projects::ProjectPermissionOverride::decl(),
+        utils::api::projects::ListProjectPermissionOverridesResponse::decl(),
+        utils::api::projects::SetProjectPermissionOverrideRequest::decl(),
+        utils::api::projects::EffectiveProjectPermissions::decl(),
         server::routes::projects::CreateRemoteProjectRequest::decl(),
         server::routes::projects::LinkToExistingRequest::decl(),
+        services::services::git_provider::ProviderType::decl(),
+        services::services::git_provider::ProviderHostMapping::decl(),
+        services::services::git_provider::RetryPolicy::decl(),
+        services::services::git_provider::ProviderRetryPolicy::decl(),
+        services::services::git_provider::MergeMethod::decl(),
+        services::services::git_provider::ReviewState::decl(),
+        services::services::git_provider::ReviewVerdict::decl(),
+        services::services::git_provider::UnifiedReview::decl(),
+        services::services::git_provider::CheckStatus::decl(),
+        services::services::git_provider::CommitStatusState::decl(),
+        services::services::git_provider::CheckRun::decl(),
+        services::services::git_provider::Milestone::decl(),
+        services::services::git_provider::BranchProtection::decl(),
+        services::services::git_provider::ReviewRequest::decl(),
+        services::services::git_provider::ReleaseInfo::decl(),
+        services::services::offline::ProviderStatus::decl(),
+        services::services::offline::PostedComment::decl(),
+        server::routes::health::HealthStatus::decl(),
+        services::services::compatibility::CompatibilityReport::decl(),
+        services::services::compatibility::ToolCompatibility::decl(),
+        services::services::compatibility::CompatibilityStatus::decl(),
+        services::services::circuit_breaker::CircuitStatus::decl(),
+        services::services::circuit_breaker::CircuitState::decl(),
+        services::services::git_provider::RateLimitStatus::decl(),
         server::routes::repo::RegisterRepoRequest::decl(),
+        server::routes::repo::CloneRepoRequest::decl(),
+        server::routes::repo::ClonedRepo::decl(),
         server::routes::repo::InitRepoRequest::decl(),
+        server::routes::repo::QuarantineCommandRequest::decl(),
+        server::routes::repo::CreateReleaseRequest::decl(),
+        server::routes::repo::ReviewRequestsQuery::decl(),
+        server::routes::repo::RepoReviewRequests::decl(),
         server::routes::tags::TagSearchParams::decl(),
         server::routes::oauth::TokenResponse::decl(),
         server::routes::config::UserSystemInfo::decl(),
@@ -103,8 +226,13 @@ fn generate_types_content() -> String {
         server::routes::config::CheckEditorAvailabilityQuery::decl(),
         server::routes::config::CheckEditorAvailabilityResponse::decl(),
         server::routes::config::CheckAgentAvailabilityQuery::decl(),
+        server::routes::config::UpsertProviderHostRequest::decl(),
+        server::routes::setup::SetupStatus::decl(),
+        server::routes::setup::CompleteSetupRequest::decl(),
         server::routes::oauth::CurrentUserResponse::decl(),
         server::routes::sessions::CreateFollowUpAttempt::decl(),
+        server::routes::sessions::CreateScopedFollowUp::decl(),
+        server::routes::sessions::AskAgentRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchResponse::decl(),
         server::routes::task_attempts::MergeTaskAttemptRequest::decl(),
@@ -114,13 +242,51 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::OpenEditorRequest::decl(),
         server::routes::task_attempts::OpenEditorResponse::decl(),
         server::routes::shared_tasks::AssignSharedTaskRequest::decl(),
+        server::routes::share_links::CreateShareLinkRequest::decl(),
+        server::routes::share_links::SharedTaskView::decl(),
+        server::routes::share_links::SharedAttempt::decl(),
+        server::routes::share_links::SharedRepoChanges::decl(),
+        server::routes::share_links::SharedDiffSummary::decl(),
+        server::routes::share_links::SharedPullRequest::decl(),
+        server::routes::share_links::SharedTimelineEntry::decl(),
+        server::routes::service_accounts::IssuedServiceAccountKey::decl(),
+        server::routes::tenants::TenantUsageReport::decl(),
+        server::routes::project_mirrors::ProjectMirrorStatus::decl(),
+        server::routes::project_mirrors::MirrorConflict::decl(),
+        server::routes::project_mirrors::ResolveMirrorConflictRequest::decl(),
+        server::routes::project_metrics::BoardMetricsQuery::decl(),
+        server::routes::project_metrics::BoardMetricsExportQuery::decl(),
+        server::routes::column_slas::ColumnSlaQuery::decl(),
         server::routes::tasks::ShareTaskResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        server::routes::tasks::CreateTaskCommentRequest::decl(),
+        server::routes::tasks::EstimateTaskRequest::decl(),
+        server::routes::tasks::RefineTaskRequest::decl(),
+        server::validation::FieldError::decl(),
+        server::routes::tasks::RefineTaskResponse::decl(),
+        server::routes::library::PublishToLibraryRequest::decl(),
+        server::routes::library::PullFromLibraryRequest::decl(),
+        server::routes::webhooks::CommentTriggerOutcome::decl(),
+        server::routes::automation_rules::ValidateConditionRequest::decl(),
+        server::routes::automation_rules::ValidateConditionResponse::decl(),
+        server::routes::automation_transitions::ApplyTransitionRequest::decl(),
+        services::services::rules::TransitionOutcome::decl(),
+        server::routes::batch::BatchOperation::decl(),
+        server::routes::batch::BatchRequest::decl(),
+        server::routes::batch::BatchData::decl(),
+        server::routes::batch::BatchResult::decl(),
+        server::routes::jobs::JobListQuery::decl(),
+        server::routes::admin::QueueOverview::decl(),
+        server::routes::admin::QueuedAgeQuery::decl(),
+        server::routes::admin::AuditLogQuery::decl(),
         server::routes::task_attempts::pr::CreateGitHubPrRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
         server::routes::task_attempts::WorkspaceRepoInput::decl(),
+        server::routes::task_attempts::AdoptBranchRepo::decl(),
+        server::routes::task_attempts::AdoptBranchRequest::decl(),
+        server::routes::task_attempts::BranchMapping::decl(),
         server::routes::task_attempts::RunAgentSetupRequest::decl(),
         server::routes::task_attempts::RunAgentSetupResponse::decl(),
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
@@ -128,6 +294,8 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::AbortConflictsRequest::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::PushError::decl(),
+        server::routes::task_attempts::AcknowledgeSecretsRequest::decl(),
+        server::routes::task_attempts::StartRepoChainRequest::decl(),
         server::routes::task_attempts::pr::CreatePrError::decl(),
         server::routes::task_attempts::BranchStatus::decl(),
         server::routes::task_attempts::RunScriptError::decl(),
@@ -136,11 +304,43 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::pr::PrCommentsResponse::decl(),
         server::routes::task_attempts::pr::GetPrCommentsError::decl(),
         server::routes::task_attempts::pr::GetPrCommentsQuery::decl(),
+        server::routes::task_attempts::mr::MergePrRequest::decl(),
+        server::routes::task_attempts::mr::ListMilestonesQuery::decl(),
+        server::routes::task_attempts::mr::BranchProtectionQuery::decl(),
+        server::routes::task_attempts::mr::PrDetailsQuery::decl(),
+        server::routes::task_attempts::mr::PrDetailsResponse::decl(),
+        server::routes::task_attempts::mr::CheckLogsQuery::decl(),
+        server::routes::task_attempts::mr::CheckLogsResponse::decl(),
+        server::routes::task_attempts::mr::ClosePrRequest::decl(),
+        server::routes::task_attempts::mr::ReopenPrRequest::decl(),
+        server::routes::task_attempts::mr::SetPrDraftRequest::decl(),
+        server::routes::task_attempts::mr::UpdatePrRequest::decl(),
+        server::routes::task_attempts::review_follow_ups::SetReviewFollowUpsRequest::decl(),
+        server::routes::task_attempts::mr::PostPrCommentRequest::decl(),
+        server::routes::task_attempts::mr::PostPrReviewCommentRequest::decl(),
+        server::routes::task_attempts::mr::ReplyToPrCommentRequest::decl(),
+        server::routes::task_attempts::mr::MarkPrCommentAddressedRequest::decl(),
+        server::routes::task_attempts::mr::ApplySuggestionsRequest::decl(),
+        server::routes::task_attempts::mr::ApplySuggestionsResponse::decl(),
+        server::routes::task_attempts::mr::ApplySuggestionsError::decl(),
+        server::routes::task_attempts::mr::PrActionResponse::decl(),
+        server::routes::task_attempts::mr::PrActionError::decl(),
         services::services::github::UnifiedPrComment::decl(),
         server::routes::task_attempts::RepoBranchStatus::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
         services::services::config::Config::decl(),
+        db::DatabaseTuning::decl(),
+        services::services::object_store::StorageConfig::decl(),
+        services::services::object_store::StorageBackend::decl(),
+        services::services::object_store::SignedUrl::decl(),
+        utils::log_shipping::LogShippingConfig::decl(),
+        utils::log_shipping::LogSink::decl(),
+        services::services::self_update::UpdateConfig::decl(),
+        services::services::self_update::UpdateChannel::decl(),
+        services::services::self_update::UpdateStatus::decl(),
+        services::services::self_update::UpdateState::decl(),
+        services::services::self_update::InstallKind::decl(),
         services::services::config::NotificationConfig::decl(),
         services::services::config::ThemeMode::decl(),
         services::services::config::EditorConfig::decl(),
@@ -150,8 +350,51 @@ fn generate_types_content() -> String {
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
+        services::services::config::PrBrandingConfig::decl(),
+        services::services::config::RedactionConfig::decl(),
+        services::services::policy::ExecutorPolicy::decl(),
+        services::services::throttle::ExecutorLimit::decl(),
+        services::services::throttle::ThrottledExecution::decl(),
+        services::services::secret_scan::SecretFinding::decl(),
+        services::services::dependency_policy::DependencyPolicyConfig::decl(),
+        services::services::lint::LintFormat::decl(),
+        services::services::lint::LintCommand::decl(),
+        services::services::lint::LintConfig::decl(),
+        services::services::coverage::CoverageConfig::decl(),
+        services::services::flaky::FlakyConfig::decl(),
+        services::services::flaky::FlakyCommand::decl(),
+        services::services::routing::RoutingConfig::decl(),
+        services::services::routing::RoutingRule::decl(),
+        services::services::routing::ProfileCost::decl(),
+        services::services::compaction::CompactionConfig::decl(),
+        services::services::repo_fetch::FetchConfig::decl(),
+        services::services::comment_triggers::CommentTriggerConfig::decl(),
+        services::services::schedule::WeekStart::decl(),
+        services::services::schedule::ScheduleConfig::decl(),
+        services::services::schedule::Sprint::decl(),
+        services::services::schedule::DueTask::decl(),
+        services::services::schedule::DueDigest::decl(),
+        services::services::replay::ReplayStep::decl(),
+        services::services::replay::ReplayRequest::decl(),
+        services::services::replay::StepOutcome::decl(),
+        services::services::replay::ReplayStepResult::decl(),
+        services::services::replay::RepoReplayResult::decl(),
+        services::services::replay::ReplayReport::decl(),
+        services::services::refine::TaskRefinement::decl(),
+        services::services::estimation::Effort::decl(),
+        services::services::estimation::SimilarTask::decl(),
+        services::services::estimation::TaskEstimate::decl(),
+        services::services::board_metrics::TaskCycleTime::decl(),
+        services::services::board_metrics::CycleTimeSummary::decl(),
+        services::services::board_metrics::WeeklyThroughput::decl(),
+        services::services::board_metrics::AgingTask::decl(),
+        services::services::board_metrics::ColumnAging::decl(),
+        services::services::board_metrics::BoardMetrics::decl(),
+        services::services::board_metrics::MetricsReport::decl(),
         services::services::git::GitBranch::decl(),
         services::services::share::SharedTaskDetails::decl(),
+        services::services::share::MirrorSide::decl(),
+        services::services::share::MirrorSyncReport::decl(),
         services::services::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
         services::services::git::ConflictOp::decl(),
@@ -52,15 +52,16 @@ fn main() -> anyhow::Result<()> {
                 url
             };
 
-            let service = TaskServer::new(&base_url)
-                .init()
-                .await
-                .serve(stdio())
-                .await
-                .map_err(|e| {
-                    tracing::error!("serving error: {:?}", e);
-                    e
-                })?;
+            let mut server = TaskServer::new(&base_url);
+            if let Ok(key) = std::env::var("VIBE_SERVICE_ACCOUNT_KEY") {
+                tracing::info!("[MCP] Acting as the service account of VIBE_SERVICE_ACCOUNT_KEY");
+                server = server.with_api_key(&key);
+            }
+
+            let service = server.init().await.serve(stdio()).await.map_err(|e| {
+                tracing::error!("serving error: {:?}", e);
+                e
+            })?;
 
             service.waiting().await?;
             Ok(())
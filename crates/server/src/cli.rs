@@ -0,0 +1,168 @@
+//! Command-line arguments for the server binary.
+//!
+//! Parsing is intentionally hand-rolled: the binary only accepts a handful of flags and is
+//! usually launched by the npx wrapper, which forwards its own arguments unchanged.
+
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+pub const USAGE: &str = "\
+Usage: vibe-kanban [OPTIONS]
+       vibe-kanban migrate [status|up|down] [OPTIONS]
+       vibe-kanban import <PATH> [OPTIONS]
+
+Commands:
+  migrate status     List applied and pending database migrations (the default)
+  migrate up         Apply pending migrations, backing up the database first
+  migrate down       Undo the latest migration by restoring the backup taken before it;
+                     start the previous version of the app afterwards, as this one would
+                     migrate the database again
+  import <PATH>      Copy the projects, tasks, attempts and merges of the vibe-kanban
+                     database at PATH into this one under new ids, backing up this one
+                     first; importing the same database twice copies it twice
+
+Options:
+  --data-dir <PATH>  Store the database, config and credentials in PATH
+                     (defaults to the platform data directory, or $VK_DATA_DIR)
+  --dry-run          With migrate up or down or import, print what would happen and
+                     change nothing
+  --no-backup        With migrate up or import, skip the backup
+  --test-mode        Run for end-to-end tests: no analytics, error reports, browser or
+                     notifications, a mock in place of every git provider, a seeded
+                     database and a frozen clock where possible; without --data-dir,
+                     data goes to a fresh temporary directory
+  -h, --help         Print this help and exit";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CliError {
+    #[error("Missing value for {0}")]
+    MissingValue(&'static str),
+    #[error("Unknown argument: {0}")]
+    UnknownArgument(String),
+    #[error("{0} only applies to the migrate and import commands")]
+    CommandOnly(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MigrateAction {
+    #[default]
+    Status,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrateArgs {
+    pub action: MigrateAction,
+    /// Print what would happen without changing the database
+    pub dry_run: bool,
+    /// Skip the backup before applying migrations
+    pub no_backup: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportArgs {
+    /// Database to import from
+    pub source: PathBuf,
+    /// Report what would be imported without changing the database
+    pub dry_run: bool,
+    /// Skip the backup before importing
+    pub no_backup: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Migrate(MigrateArgs),
+    Import(ImportArgs),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliArgs {
+    /// Directory holding the database, config and credentials
+    pub data_dir: Option<PathBuf>,
+    /// `--help` was passed
+    pub help: bool,
+    /// Run deterministically for end-to-end tests, see `utils::test_mode`
+    pub test_mode: bool,
+    /// Subcommand to run instead of the server
+    pub command: Option<Command>,
+}
+
+impl CliArgs {
+    /// Parse the arguments of the current process
+    pub fn parse() -> Result<Self, CliError> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from<I, S>(args: I) -> Result<Self, CliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut parsed = CliArgs::default();
+        let mut args = args.into_iter().map(Into::into);
+        let mut migrate = false;
+        let mut import = None;
+        let mut action = MigrateAction::default();
+        let mut dry_run = false;
+        let mut no_backup = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "--dry-run" => dry_run = true,
+                "--no-backup" => no_backup = true,
+                "--test-mode" => parsed.test_mode = true,
+                "migrate" if !migrate && import.is_none() => migrate = true,
+                "import" if !migrate && import.is_none() => {
+                    let value = args
+                        .next()
+                        .filter(|v| !v.is_empty())
+                        .ok_or(CliError::MissingValue("import"))?;
+                    import = Some(utils::path::expand_tilde(&value));
+                }
+                "status" if migrate => action = MigrateAction::Status,
+                "up" if migrate => action = MigrateAction::Up,
+                "down" if migrate => action = MigrateAction::Down,
+                "--data-dir" => {
+                    let value = args
+                        .next()
+                        .filter(|v| !v.is_empty())
+                        .ok_or(CliError::MissingValue("--data-dir"))?;
+                    parsed.data_dir = Some(utils::path::expand_tilde(&value));
+                }
+                other => {
+                    if let Some(value) = other.strip_prefix("--data-dir=") {
+                        if value.is_empty() {
+                            return Err(CliError::MissingValue("--data-dir"));
+                        }
+                        parsed.data_dir = Some(utils::path::expand_tilde(value));
+                    } else {
+                        return Err(CliError::UnknownArgument(other.to_string()));
+                    }
+                }
+            }
+        }
+
+        if migrate {
+            parsed.command = Some(Command::Migrate(MigrateArgs {
+                action,
+                dry_run,
+                no_backup,
+            }));
+        } else if let Some(source) = import {
+            parsed.command = Some(Command::Import(ImportArgs {
+                source,
+                dry_run,
+                no_backup,
+            }));
+        } else if dry_run {
+            return Err(CliError::CommandOnly("--dry-run"));
+        } else if no_backup {
+            return Err(CliError::CommandOnly("--no-backup"));
+        }
+
+        Ok(parsed)
+    }
+}
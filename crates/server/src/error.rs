@@ -1,7 +1,7 @@
 use axum::{
     Json,
     extract::multipart::MultipartError,
-    http::StatusCode,
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
 use db::models::{
@@ -12,20 +12,30 @@ use db::models::{
 use deployment::{DeploymentError, RemoteClientNotConfigured};
 use executors::executors::ExecutorError;
 use git2::Error as Git2Error;
+use serde::Serialize;
 use services::services::{
+    artifacts::ArtifactError,
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
     git::GitServiceError,
     github::GitHubServiceError,
     image::ImageError,
+    library::LibraryError,
+    object_store::StorageError,
     project::ProjectServiceError,
-    remote_client::RemoteClientError,
+    quota::QuotaError,
+    refine::RefineError,
+    remote_client::{HandoffErrorCode, RemoteClientError},
     repo::RepoError as RepoServiceError,
+    self_update::SelfUpdateError,
     share::ShareError,
+    task_events::UndoError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
-use utils::response::ApiResponse;
+use utils::response::{InvalidCursor, PROBLEM_JSON, ProblemDetails};
+
+use crate::validation::FieldError;
 
 #[derive(Debug, Error, ts_rs::TS)]
 #[ts(type = "string")]
@@ -60,6 +70,12 @@ pub enum ApiError {
     Config(#[from] ConfigError),
     #[error(transparent)]
     Image(#[from] ImageError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
+    Artifact(#[from] ArtifactError),
+    #[error(transparent)]
+    SelfUpdate(#[from] SelfUpdateError),
     #[error("Multipart error: {0}")]
     Multipart(#[from] MultipartError),
     #[error("IO error: {0}")]
@@ -68,6 +84,8 @@ pub enum ApiError {
     EditorOpen(#[from] EditorOpenError),
     #[error(transparent)]
     RemoteClient(#[from] RemoteClientError),
+    #[error(transparent)]
+    Refine(#[from] RefineError),
     #[error("Unauthorized")]
     Unauthorized,
     #[error("Bad request: {0}")]
@@ -76,6 +94,10 @@ pub enum ApiError {
     Conflict(String),
     #[error("Forbidden: {0}")]
     Forbidden(String),
+    #[error("{}", .0.title)]
+    Problem(Box<ProblemDetails>),
+    #[error("Invalid request")]
+    Validation(Vec<FieldError>),
 }
 
 impl From<&'static str> for ApiError {
@@ -96,90 +118,148 @@ impl From<RemoteClientNotConfigured> for ApiError {
     }
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
-        let (status_code, error_type) = match &self {
-            ApiError::Project(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ProjectError"),
-            ApiError::Repo(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ProjectRepoError"),
-            ApiError::Workspace(_) => (StatusCode::INTERNAL_SERVER_ERROR, "WorkspaceError"),
-            ApiError::Session(_) => (StatusCode::INTERNAL_SERVER_ERROR, "SessionError"),
-            ApiError::ScratchError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ScratchError"),
+impl From<InvalidCursor> for ApiError {
+    fn from(err: InvalidCursor) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+/// Typed route errors that are sent as problem details, with the variant as the problem type
+pub trait ProblemType: Serialize {
+    fn status(&self) -> StatusCode;
+}
+
+impl ApiError {
+    /// Error response for a typed route error, e.g. `CreatePrError::TargetBranchNotFound`
+    pub fn problem<E: ProblemType>(error: E) -> Self {
+        ApiError::Problem(Box::new(ProblemDetails::from_error_data(
+            error.status().as_u16(),
+            &error,
+        )))
+    }
+
+    /// A write based on a stale read, e.g. a task edited in another tab since it was loaded
+    pub fn stale(what: &str) -> Self {
+        ApiError::Problem(Box::new(
+            ProblemDetails::new(StatusCode::CONFLICT.as_u16(), "stale_write").with_detail(format!(
+                "The {what} was changed since it was loaded. Reload it and try again."
+            )),
+        ))
+    }
+
+    fn status_and_code(&self) -> (StatusCode, &'static str) {
+        match self {
+            ApiError::Project(_) => (StatusCode::INTERNAL_SERVER_ERROR, "project_error"),
+            ApiError::Repo(_) => (StatusCode::INTERNAL_SERVER_ERROR, "project_repo_error"),
+            ApiError::Workspace(_) => (StatusCode::INTERNAL_SERVER_ERROR, "workspace_error"),
+            ApiError::Session(_) => (StatusCode::INTERNAL_SERVER_ERROR, "session_error"),
+            ApiError::ScratchError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "scratch_error"),
             ApiError::ExecutionProcess(err) => match err {
                 ExecutionProcessError::ExecutionProcessNotFound => {
-                    (StatusCode::NOT_FOUND, "ExecutionProcessError")
+                    (StatusCode::NOT_FOUND, "execution_process_not_found")
                 }
-                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutionProcessError"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "execution_process_error"),
             },
             // Promote certain GitService errors to conflict status with concise messages
             ApiError::GitService(git_err) => match git_err {
-                services::services::git::GitServiceError::MergeConflicts(_) => {
-                    (StatusCode::CONFLICT, "GitServiceError")
-                }
-                services::services::git::GitServiceError::RebaseInProgress => {
-                    (StatusCode::CONFLICT, "GitServiceError")
-                }
-                _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
+                GitServiceError::MergeConflicts(_) => (StatusCode::CONFLICT, "merge_conflicts"),
+                GitServiceError::RebaseInProgress => (StatusCode::CONFLICT, "rebase_in_progress"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "git_service_error"),
             },
-            ApiError::GitHubService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
-            ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
-            ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
-            ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
-            ApiError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError"),
-            ApiError::Worktree(_) => (StatusCode::INTERNAL_SERVER_ERROR, "WorktreeError"),
-            ApiError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ConfigError"),
+            ApiError::GitHubService(_) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "github_service_error")
+            }
+            ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "deployment_error"),
+            ApiError::Container(ContainerError::Quota(QuotaError::Exceeded(_)))
+            | ApiError::Image(ImageError::Quota(QuotaError::Exceeded(_)))
+            | ApiError::Artifact(ArtifactError::Quota(QuotaError::Exceeded(_))) => {
+                (StatusCode::TOO_MANY_REQUESTS, "quota_exceeded")
+            }
+            ApiError::Container(ContainerError::UpdatePending) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "update_pending")
+            }
+            ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "container_error"),
+            ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "executor_error"),
+            ApiError::Database(sqlx::Error::RowNotFound) => (StatusCode::NOT_FOUND, "not_found"),
+            ApiError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "database_error"),
+            ApiError::Worktree(_) => (StatusCode::INTERNAL_SERVER_ERROR, "worktree_error"),
+            ApiError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, "config_error"),
             ApiError::Image(img_err) => match img_err {
-                ImageError::InvalidFormat => (StatusCode::BAD_REQUEST, "InvalidImageFormat"),
-                ImageError::TooLarge(_, _) => (StatusCode::PAYLOAD_TOO_LARGE, "ImageTooLarge"),
-                ImageError::NotFound => (StatusCode::NOT_FOUND, "ImageNotFound"),
-                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ImageError"),
+                ImageError::InvalidFormat => (StatusCode::BAD_REQUEST, "invalid_image_format"),
+                ImageError::TooLarge(_, _) => (StatusCode::PAYLOAD_TOO_LARGE, "image_too_large"),
+                ImageError::NotFound => (StatusCode::NOT_FOUND, "image_not_found"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "image_error"),
             },
-            ApiError::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "IoError"),
+            ApiError::Storage(err) => match err {
+                StorageError::NotFound(_) => (StatusCode::NOT_FOUND, "object_not_found"),
+                StorageError::InvalidKey(_) => (StatusCode::BAD_REQUEST, "invalid_object_key"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "storage_error"),
+            },
+            ApiError::Artifact(ArtifactError::Database(sqlx::Error::RowNotFound)) => {
+                (StatusCode::NOT_FOUND, "not_found")
+            }
+            ApiError::Artifact(_) => (StatusCode::INTERNAL_SERVER_ERROR, "artifact_error"),
+            ApiError::SelfUpdate(err) => match err {
+                SelfUpdateError::NotSupported(_)
+                | SelfUpdateError::NoReleaseServer
+                | SelfUpdateError::UpToDate(_)
+                | SelfUpdateError::NothingStaged
+                | SelfUpdateError::InProgress(_) => (StatusCode::CONFLICT, "update_unavailable"),
+                SelfUpdateError::Verification(_) => {
+                    (StatusCode::BAD_GATEWAY, "update_verification_failed")
+                }
+                _ => (StatusCode::BAD_GATEWAY, "update_error"),
+            },
+            ApiError::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "io_error"),
             ApiError::EditorOpen(err) => match err {
                 EditorOpenError::LaunchFailed { .. } => {
-                    (StatusCode::INTERNAL_SERVER_ERROR, "EditorLaunchError")
+                    (StatusCode::INTERNAL_SERVER_ERROR, "editor_launch_error")
                 }
-                _ => (StatusCode::BAD_REQUEST, "EditorOpenError"),
+                _ => (StatusCode::BAD_REQUEST, "editor_open_error"),
             },
-            ApiError::Multipart(_) => (StatusCode::BAD_REQUEST, "MultipartError"),
-            ApiError::RemoteClient(err) => match err {
-                RemoteClientError::Auth => (StatusCode::UNAUTHORIZED, "RemoteClientError"),
-                RemoteClientError::Timeout => (StatusCode::GATEWAY_TIMEOUT, "RemoteClientError"),
-                RemoteClientError::Transport(_) => (StatusCode::BAD_GATEWAY, "RemoteClientError"),
-                RemoteClientError::Http { status, .. } => (
-                    StatusCode::from_u16(*status).unwrap_or(StatusCode::BAD_GATEWAY),
-                    "RemoteClientError",
-                ),
-                RemoteClientError::Token(_) => (StatusCode::BAD_GATEWAY, "RemoteClientError"),
-                RemoteClientError::Api(code) => match code {
-                    services::services::remote_client::HandoffErrorCode::NotFound => {
-                        (StatusCode::NOT_FOUND, "RemoteClientError")
-                    }
-                    services::services::remote_client::HandoffErrorCode::Expired => {
-                        (StatusCode::UNAUTHORIZED, "RemoteClientError")
-                    }
-                    services::services::remote_client::HandoffErrorCode::AccessDenied => {
-                        (StatusCode::FORBIDDEN, "RemoteClientError")
+            ApiError::Multipart(_) => (StatusCode::BAD_REQUEST, "multipart_error"),
+            ApiError::RemoteClient(err) => {
+                let status = match err {
+                    RemoteClientError::Auth => StatusCode::UNAUTHORIZED,
+                    RemoteClientError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+                    RemoteClientError::Transport(_) => StatusCode::BAD_GATEWAY,
+                    RemoteClientError::Http { status, .. } => {
+                        StatusCode::from_u16(*status).unwrap_or(StatusCode::BAD_GATEWAY)
                     }
-                    services::services::remote_client::HandoffErrorCode::ProviderError
-                    | services::services::remote_client::HandoffErrorCode::InternalError => {
-                        (StatusCode::BAD_GATEWAY, "RemoteClientError")
+                    RemoteClientError::Token(_) => StatusCode::BAD_GATEWAY,
+                    RemoteClientError::Api(code) => match code {
+                        HandoffErrorCode::NotFound => StatusCode::NOT_FOUND,
+                        HandoffErrorCode::Expired => StatusCode::UNAUTHORIZED,
+                        HandoffErrorCode::AccessDenied => StatusCode::FORBIDDEN,
+                        HandoffErrorCode::ProviderError | HandoffErrorCode::InternalError => {
+                            StatusCode::BAD_GATEWAY
+                        }
+                        _ => StatusCode::BAD_REQUEST,
+                    },
+                    RemoteClientError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                    RemoteClientError::Serde(_) | RemoteClientError::Url(_) => {
+                        StatusCode::BAD_REQUEST
                     }
-                    _ => (StatusCode::BAD_REQUEST, "RemoteClientError"),
-                },
-                RemoteClientError::Storage(_) => {
-                    (StatusCode::INTERNAL_SERVER_ERROR, "RemoteClientError")
-                }
-                RemoteClientError::Serde(_) | RemoteClientError::Url(_) => {
-                    (StatusCode::BAD_REQUEST, "RemoteClientError")
-                }
+                };
+                (status, "remote_client_error")
+            }
+            ApiError::Refine(err) => match err {
+                RefineError::Timeout => (StatusCode::GATEWAY_TIMEOUT, "refine_timeout"),
+                _ => (StatusCode::BAD_GATEWAY, "refine_error"),
             },
-            ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
-            ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "BadRequest"),
-            ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
-            ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, "ForbiddenError"),
-        };
+            ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "unauthorized"),
+            ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "bad_request"),
+            ApiError::Conflict(_) => (StatusCode::CONFLICT, "conflict"),
+            ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, "forbidden"),
+            ApiError::Validation(_) => (StatusCode::UNPROCESSABLE_ENTITY, "validation_failed"),
+            // Typed errors carry their own status and code, see `to_problem`
+            ApiError::Problem(_) => (StatusCode::INTERNAL_SERVER_ERROR, "error"),
+        }
+    }
 
-        let error_message = match &self {
+    /// Human-readable explanation shown to the user
+    fn detail(&self) -> String {
+        match self {
             ApiError::Image(img_err) => match img_err {
                 ImageError::InvalidFormat => "This file type is not supported. Please upload an image file (PNG, JPG, GIF, WebP, or BMP).".to_string(),
                 ImageError::TooLarge(size, max) => format!(
@@ -188,16 +268,17 @@ impl IntoResponse for ApiError {
                     *max as f64 / 1_048_576.0
                 ),
                 ImageError::NotFound => "Image not found.".to_string(),
+                ImageError::Quota(QuotaError::Exceeded(exceeded)) => exceeded.to_string(),
                 _ => {
                     "Failed to process image. Please try again.".to_string()
                 }
             },
             ApiError::GitService(git_err) => match git_err {
-                services::services::git::GitServiceError::MergeConflicts(msg) => msg.clone(),
-                services::services::git::GitServiceError::RebaseInProgress => {
+                GitServiceError::MergeConflicts(msg) => msg.clone(),
+                GitServiceError::RebaseInProgress => {
                     "A rebase is already in progress. Resolve conflicts or abort the rebase, then retry.".to_string()
                 }
-                _ => format!("{}: {}", error_type, self),
+                _ => self.to_string(),
             },
             ApiError::Multipart(_) => "Failed to upload file. Please ensure the file is valid and try again.".to_string(),
             ApiError::RemoteClient(err) => match err {
@@ -218,31 +299,31 @@ impl IntoResponse for ApiError {
                     "Failed to persist credentials locally. Please retry.".to_string()
                 }
                 RemoteClientError::Api(code) => match code {
-                    services::services::remote_client::HandoffErrorCode::NotFound => {
+                    HandoffErrorCode::NotFound => {
                         "The requested resource was not found.".to_string()
                     }
-                    services::services::remote_client::HandoffErrorCode::Expired => {
+                    HandoffErrorCode::Expired => {
                         "The link or token has expired.".to_string()
                     }
-                    services::services::remote_client::HandoffErrorCode::AccessDenied => {
+                    HandoffErrorCode::AccessDenied => {
                         "Access denied.".to_string()
                     }
-                    services::services::remote_client::HandoffErrorCode::UnsupportedProvider => {
+                    HandoffErrorCode::UnsupportedProvider => {
                         "Unsupported authentication provider.".to_string()
                     }
-                    services::services::remote_client::HandoffErrorCode::InvalidReturnUrl => {
+                    HandoffErrorCode::InvalidReturnUrl => {
                         "Invalid return URL.".to_string()
                     }
-                    services::services::remote_client::HandoffErrorCode::InvalidChallenge => {
+                    HandoffErrorCode::InvalidChallenge => {
                         "Invalid authentication challenge.".to_string()
                     }
-                    services::services::remote_client::HandoffErrorCode::ProviderError => {
+                    HandoffErrorCode::ProviderError => {
                         "Authentication provider error. Please try again.".to_string()
                     }
-                    services::services::remote_client::HandoffErrorCode::InternalError => {
+                    HandoffErrorCode::InternalError => {
                         "Internal remote service error. Please try again.".to_string()
                     }
-                    services::services::remote_client::HandoffErrorCode::Other(msg) => {
+                    HandoffErrorCode::Other(msg) => {
                         format!("Authentication error: {}", msg)
                     }
                 },
@@ -253,10 +334,53 @@ impl IntoResponse for ApiError {
             ApiError::BadRequest(msg) => msg.clone(),
             ApiError::Conflict(msg) => msg.clone(),
             ApiError::Forbidden(msg) => msg.clone(),
-            _ => format!("{}: {}", error_type, self),
-        };
-        let response = ApiResponse::<()>::error(&error_message);
-        (status_code, Json(response)).into_response()
+            ApiError::Validation(errors) => errors
+                .iter()
+                .map(|e| format!("{}: {}", e.field, e.message))
+                .collect::<Vec<_>>()
+                .join("; "),
+            _ => self.to_string(),
+        }
+    }
+
+    pub fn to_problem(&self) -> ProblemDetails {
+        if let ApiError::Problem(problem) = self {
+            return problem.as_ref().clone();
+        }
+        let (status, code) = self.status_and_code();
+        let problem = ProblemDetails::new(status.as_u16(), code).with_detail(self.detail());
+        match self {
+            ApiError::Image(ImageError::TooLarge(size, max)) => problem
+                .with_extension("size_bytes", *size)
+                .with_extension("max_bytes", *max),
+            ApiError::RemoteClient(RemoteClientError::Http { status, .. }) => {
+                problem.with_extension("upstream_status", *status)
+            }
+            ApiError::Validation(errors) => {
+                problem.with_extension("errors", serde_json::to_value(errors).unwrap_or_default())
+            }
+            _ => problem,
+        }
+    }
+}
+
+/// `application/problem+json` response. The problem is also left in the response extensions so
+/// `middleware::problem::problem_instance` can fill in the request path.
+pub fn problem_response(problem: ProblemDetails) -> Response {
+    let status = StatusCode::from_u16(problem.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut response = (
+        status,
+        [(header::CONTENT_TYPE, PROBLEM_JSON)],
+        Json(&problem),
+    )
+        .into_response();
+    response.extensions_mut().insert(problem);
+    response
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        problem_response(self.to_problem())
     }
 }
 
@@ -265,6 +389,12 @@ impl From<ShareError> for ApiError {
         match err {
             ShareError::Database(db_err) => ApiError::Database(db_err),
             ShareError::AlreadyShared(_) => ApiError::Conflict("Task already shared".to_string()),
+            ShareError::MirrorNotEnabled(_) => {
+                ApiError::Conflict("Mirroring is not enabled for this project".to_string())
+            }
+            ShareError::NoMirrorConflict(_) => {
+                ApiError::Conflict("Task has no mirror conflict to resolve".to_string())
+            }
             ShareError::TaskNotFound(_) => {
                 ApiError::Conflict("Task not found for sharing".to_string())
             }
@@ -313,6 +443,39 @@ impl From<ShareError> for ApiError {
     }
 }
 
+impl From<UndoError> for ApiError {
+    fn from(err: UndoError) -> Self {
+        match err {
+            UndoError::Database(db_err) => ApiError::Database(db_err),
+            UndoError::Stale => ApiError::stale("task"),
+            UndoError::NothingToUndo | UndoError::NothingToRedo | UndoError::Expired => {
+                ApiError::Conflict(err.to_string())
+            }
+        }
+    }
+}
+
+impl From<LibraryError> for ApiError {
+    fn from(err: LibraryError) -> Self {
+        match err {
+            LibraryError::Database(db_err) => ApiError::Database(db_err),
+            LibraryError::Remote(remote_err) => ApiError::RemoteClient(remote_err),
+            LibraryError::InvalidContent(err) => {
+                ApiError::BadRequest(format!("Library item has invalid content: {err}"))
+            }
+            LibraryError::LocalItemNotFound(..) => {
+                ApiError::BadRequest("Template or automation rule not found".to_string())
+            }
+            LibraryError::ItemNotFound(_) => {
+                ApiError::BadRequest("Library item not found in this organization".to_string())
+            }
+            LibraryError::LinkedElsewhere(_) => {
+                ApiError::Conflict("Already published to another organization".to_string())
+            }
+        }
+    }
+}
+
 impl From<ProjectServiceError> for ApiError {
     fn from(err: ProjectServiceError) -> Self {
         match err {
@@ -372,6 +535,19 @@ impl From<RepoServiceError> for ApiError {
             RepoServiceError::InvalidFolderName(name) => {
                 ApiError::BadRequest(format!("Invalid folder name: {}", name))
             }
+            RepoServiceError::InvalidRemoteUrl(url) => {
+                ApiError::BadRequest(format!("Invalid remote URL: {}", url))
+            }
+            RepoServiceError::Clone(git_err) => {
+                ApiError::BadRequest(format!("Clone failed: {}", git_err))
+            }
+            RepoServiceError::GitCli(git_err) => {
+                ApiError::BadRequest(format!("Git error: {}", git_err))
+            }
+            RepoServiceError::InUse(count) => ApiError::Conflict(format!(
+                "Repository is used by {} workspaces with worktrees; clean them up first",
+                count
+            )),
         }
     }
 }
@@ -0,0 +1,119 @@
+//! The `import` command: copy a vibe-kanban database into this one without starting the server.
+
+use std::path::PathBuf;
+
+use chrono::Utc;
+use db::{
+    import::{self, ImportError, ImportReport},
+    migrate::{self, MigrationError},
+};
+use services::services::config::load_config_from_file;
+use thiserror::Error;
+use utils::assets::{asset_dir, config_path, database_path};
+
+use crate::cli::ImportArgs;
+
+#[derive(Debug, Error)]
+pub enum ImportCommandError {
+    #[error(transparent)]
+    Import(#[from] ImportError),
+    #[error(transparent)]
+    Migration(#[from] MigrationError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("{} is the database being imported into", .0.display())]
+    SameDatabase(PathBuf),
+    #[error("The database has pending migrations; a dry run needs them applied first")]
+    PendingMigrations,
+    #[error("{0} imported rows did not read back as they were in the source; nothing was imported")]
+    VerificationFailed(usize),
+}
+
+pub async fn run(args: &ImportArgs) -> Result<(), ImportCommandError> {
+    let database = database_path();
+    if let (Ok(source), Ok(target)) = (args.source.canonicalize(), database.canonicalize())
+        && source == target
+    {
+        return Err(ImportCommandError::SameDatabase(source));
+    }
+
+    let tuning = load_config_from_file(&config_path()).await.database;
+    println!("Database: {}", database.display());
+    println!("Importing from: {}", args.source.display());
+    let pool = migrate::connect(&tuning).await?;
+    let status = migrate::status(&pool).await?;
+    status.check()?;
+
+    // The import writes the current schema, so bring the database up to it first
+    let mut backed_up = false;
+    if !status.pending.is_empty() {
+        if args.dry_run {
+            return Err(ImportCommandError::PendingMigrations);
+        }
+        if let Some(backup) = migrate::run_pending(&pool, !args.no_backup).await? {
+            println!("Backed up the database to {}", backup.display());
+            backed_up = true;
+        }
+        println!("Applied {} pending migrations", status.pending.len());
+    }
+    if !args.dry_run && !args.no_backup && !backed_up && !status.applied.is_empty() {
+        let backup = migrate::backup(&pool, &status).await?;
+        println!("Backed up the database to {}", backup.display());
+    }
+
+    let report = import::import(&pool, &args.source, !args.dry_run).await?;
+    print_report(&report);
+    if !report.mismatches.is_empty() {
+        return Err(ImportCommandError::VerificationFailed(
+            report.mismatches.len(),
+        ));
+    }
+    if report.committed {
+        let dir = asset_dir().join("imports");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!(
+            "vibe-kanban-{}.json",
+            Utc::now().format("%Y%m%d%H%M%S")
+        ));
+        std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+        println!("Wrote the report with the id mapping to {}", path.display());
+    }
+    Ok(())
+}
+
+fn print_report(report: &ImportReport) {
+    let verb = if report.committed {
+        "Imported"
+    } else {
+        "Would import"
+    };
+    println!(
+        "{verb} {} of {} projects, {} of {} tasks, {} of {} attempts and {} of {} merges",
+        report.ids.projects.len(),
+        report.source.projects,
+        report.ids.tasks.len(),
+        report.source.tasks,
+        report.ids.workspaces.len(),
+        report.source.attempts,
+        report.ids.merges.len(),
+        report.source.merges,
+    );
+    println!(
+        "Repositories: {} new, {} already registered",
+        report.repos_created, report.repos_reused
+    );
+    if !report.skipped.is_empty() {
+        println!("Skipped:");
+        for reason in &report.skipped {
+            println!("  {reason}");
+        }
+    }
+    if !report.mismatches.is_empty() {
+        println!("Verification failed:");
+        for mismatch in &report.mismatches {
+            println!("  {mismatch}");
+        }
+    }
+}
@@ -1,7 +1,13 @@
+pub mod cli;
 pub mod error;
+pub mod import;
 pub mod mcp;
 pub mod middleware;
+pub mod migrate;
+pub mod permissions;
 pub mod routes;
+pub mod test_mode;
+pub mod validation;
 
 // #[cfg(feature = "cloud")]
 // type DeploymentImpl = vibe_kanban_cloud::deployment::CloudDeployment;
@@ -1,16 +1,29 @@
 use anyhow::{self, Error as AnyhowError};
 use deployment::{Deployment, DeploymentError};
-use server::{DeploymentImpl, routes};
-use services::services::container::ContainerService;
+use server::{
+    DeploymentImpl,
+    cli::{CliArgs, Command, USAGE},
+    routes,
+};
+use services::services::{
+    comment_triggers::COMMENT_TRIGGER_JOB,
+    compatibility,
+    container::ContainerService,
+    review_follow_ups::{REVIEW_FOLLOW_UP_INTERVAL, REVIEW_FOLLOW_UP_JOB},
+    self_update,
+    sla::{SLA_ESCALATION_INTERVAL, SLA_ESCALATION_JOB},
+};
 use sqlx::Error as SqlxError;
 use strip_ansi_escapes::strip;
 use thiserror::Error;
 use tracing_subscriber::{EnvFilter, prelude::*};
 use utils::{
-    assets::asset_dir,
+    assets::{asset_dir, set_data_dir},
     browser::open_browser,
+    log_shipping,
     port_file::write_port_file,
     sentry::{self as sentry_utils, SentrySource, sentry_layer},
+    test_mode,
 };
 
 #[derive(Debug, Error)]
@@ -27,16 +40,59 @@ pub enum VibeKanbanError {
 
 #[tokio::main]
 async fn main() -> Result<(), VibeKanbanError> {
-    sentry_utils::init_once(SentrySource::Backend);
+    let args = match CliArgs::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    if args.test_mode {
+        test_mode::enable();
+    }
+    // Must happen before anything resolves the asset dir (config, db, credentials)
+    if let Some(data_dir) = args.data_dir {
+        set_data_dir(data_dir);
+    } else if args.test_mode {
+        set_data_dir(server::test_mode::data_dir()?);
+    }
+    match &args.command {
+        Some(Command::Migrate(migrate)) => {
+            if let Err(e) = server::migrate::run(migrate).await {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Command::Import(import)) => {
+            if let Err(e) = server::import::run(import).await {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+
+    if !test_mode::is_enabled() {
+        sentry_utils::init_once(SentrySource::Backend);
+    }
 
     let log_level = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
     let filter_string = format!(
         "warn,server={level},services={level},db={level},executors={level},deployment={level},local_deployment={level},utils={level}",
         level = log_level
     );
-    let env_filter = EnvFilter::try_new(filter_string).expect("Failed to create tracing filter");
+    let env_filter = EnvFilter::try_new(&filter_string).expect("Failed to create tracing filter");
+    let shipping_filter =
+        EnvFilter::try_new(&filter_string).expect("Failed to create tracing filter");
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer().with_filter(env_filter))
+        .with(log_shipping::layer().with_filter(shipping_filter))
         .with(sentry_layer())
         .init();
 
@@ -46,6 +102,9 @@ async fn main() -> Result<(), VibeKanbanError> {
     }
 
     let deployment = DeploymentImpl::new().await?;
+    if test_mode::is_enabled() {
+        server::test_mode::seed(&deployment).await?;
+    }
     deployment.update_sentry_scope().await?;
     deployment
         .container()
@@ -62,7 +121,26 @@ async fn main() -> Result<(), VibeKanbanError> {
         .backfill_repo_names()
         .await
         .map_err(DeploymentError::from)?;
-    deployment.spawn_pr_monitor_service().await;
+    deployment
+        .job_runner()
+        .handler(
+            COMMENT_TRIGGER_JOB,
+            routes::webhooks::comment_trigger_handler(deployment.clone()),
+        )
+        .recurring(
+            REVIEW_FOLLOW_UP_JOB,
+            REVIEW_FOLLOW_UP_INTERVAL,
+            routes::task_attempts::review_follow_ups::review_follow_up_handler(deployment.clone()),
+        )
+        .recurring(
+            SLA_ESCALATION_JOB,
+            SLA_ESCALATION_INTERVAL,
+            routes::column_slas::sla_escalation_handler(deployment.clone()),
+        )
+        .spawn()
+        .await?;
+    deployment.spawn_plugin_host().await;
+    deployment.spawn_rule_engine();
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -88,6 +166,14 @@ async fn main() -> Result<(), VibeKanbanError> {
         }
     });
 
+    // Warn about gh, glab and agent tool versions the app is not known to work with
+    tokio::spawn(compatibility::log_warnings());
+    if !test_mode::is_enabled() {
+        tokio::spawn(self_update::log_available(
+            deployment.config().read().await.update.clone(),
+        ));
+    }
+
     let app_router = routes::router(deployment.clone());
 
     let port = std::env::var("BACKEND_PORT")
@@ -114,8 +200,10 @@ async fn main() -> Result<(), VibeKanbanError> {
     }
 
     tracing::info!("Server running on http://{host}:{actual_port}");
+    tracing::info!("Data directory: {}", asset_dir().display());
 
-    if !cfg!(debug_assertions) {
+    // A restart after an update keeps the port, so the open tab reconnects by itself
+    if !cfg!(debug_assertions) && !self_update::restarted() && !test_mode::is_enabled() {
         tracing::info!("Opening browser...");
         tokio::spawn(async move {
             if let Err(e) = open_browser(&format!("http://127.0.0.1:{actual_port}")).await {
@@ -134,6 +222,12 @@ async fn main() -> Result<(), VibeKanbanError> {
 
     perform_cleanup_actions(&deployment).await;
 
+    if self_update::restart_pending() {
+        tracing::info!("Restarting into the updated binary");
+        let e = self_update::restart(actual_port);
+        tracing::error!("Failed to restart after the update: {e}");
+    }
+
     Ok(())
 }
 
@@ -163,13 +257,17 @@ pub async fn shutdown_signal() {
         tokio::select! {
             _ = ctrl_c => {},
             _ = terminate => {},
+            _ = self_update::restart_requested() => {},
         }
     }
 
     #[cfg(not(unix))]
     {
-        // Only ctrl_c is available, so just await it
-        ctrl_c.await;
+        // Only ctrl_c is available besides the update restart
+        tokio::select! {
+            _ = ctrl_c => {},
+            _ = self_update::restart_requested() => {},
+        }
     }
 }
 
@@ -19,11 +19,12 @@ use rmcp::{
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json;
+use utils::response::ProblemDetails;
 use uuid::Uuid;
 
 use crate::routes::{
     containers::ContainerQuery,
-    task_attempts::{CreateTaskAttemptBody, WorkspaceRepoInput},
+    task_attempts::{CreateTaskAttemptBody, WorkspaceRepoInput, mr::UpdatePrRequest},
 };
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -145,6 +146,8 @@ pub struct TaskDetails {
     pub description: Option<String>,
     #[schemars(description = "Current status of the task")]
     pub status: String,
+    #[schemars(description = "When the task is due, in UTC")]
+    pub due_at: Option<String>,
     #[schemars(description = "When the task was created")]
     pub created_at: String,
     #[schemars(description = "When the task was last updated")]
@@ -162,6 +165,7 @@ impl TaskDetails {
             title: task.title,
             description: task.description,
             status: task.status.to_string(),
+            due_at: task.due_at.map(|due_at| due_at.to_rfc3339()),
             created_at: task.created_at.to_rfc3339(),
             updated_at: task.updated_at.to_rfc3339(),
             has_in_progress_attempt: None,
@@ -251,6 +255,26 @@ pub struct GetTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct UpdatePullRequestRequest {
+    #[schemars(description = "The ID of the workspace the pull request was opened from")]
+    pub workspace_id: Uuid,
+    #[schemars(description = "The ID of the repository the pull request belongs to")]
+    pub repo_id: Uuid,
+    #[schemars(description = "New title for the pull request")]
+    pub title: Option<String>,
+    #[schemars(description = "New description for the pull request")]
+    pub description: Option<String>,
+    #[schemars(description = "New branch for the pull request to merge into")]
+    pub base_branch: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct UpdatePullRequestResponse {
+    pub workspace_id: String,
+    pub repo_id: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     client: reqwest::Client,
@@ -292,6 +316,27 @@ impl TaskServer {
         }
     }
 
+    /// Call the API as the service account of `key`, so changes made through MCP are recorded
+    /// under its name instead of as the person's using the app
+    pub fn with_api_key(mut self, key: &str) -> Self {
+        let mut headers = reqwest::header::HeaderMap::new();
+        match reqwest::header::HeaderValue::from_str(&format!("Bearer {key}")) {
+            Ok(mut value) => {
+                value.set_sensitive(true);
+                headers.insert(reqwest::header::AUTHORIZATION, value);
+            }
+            Err(e) => {
+                tracing::warn!("Ignoring invalid service account key: {}", e);
+                return self;
+            }
+        }
+        match reqwest::Client::builder().default_headers(headers).build() {
+            Ok(client) => self.client = client,
+            Err(e) => tracing::warn!("Failed to build client with service account key: {}", e),
+        }
+        self
+    }
+
     pub async fn init(mut self) -> Self {
         let context = self.fetch_context_at_startup().await;
 
@@ -399,9 +444,18 @@ impl TaskServer {
 
         if !resp.status().is_success() {
             let status = resp.status();
-            return Err(
-                Self::err(format!("VK API returned error status: {}", status), None).unwrap(),
-            );
+            return Err(match resp.json::<ProblemDetails>().await {
+                Ok(problem) => Self::err_value(serde_json::json!({
+                    "success": false,
+                    "error": problem.title,
+                    "code": problem.code(),
+                    "details": problem.detail,
+                }))
+                .unwrap(),
+                Err(_) => {
+                    Self::err(format!("VK API returned error status: {}", status), None).unwrap()
+                }
+            });
         }
 
         let api_response = resp.json::<ApiResponseEnvelope<T>>().await.map_err(|e| {
@@ -756,6 +810,9 @@ impl TaskServer {
             status,
             parent_workspace_id: None,
             image_ids: None,
+            due_at: None,
+            clear_due_at: false,
+            expected_updated_at: None,
         };
         let url = self.url(&format!("/api/tasks/{}", task_id));
         let updated_task: Task = match self.send_json(self.client.put(&url).json(&payload)).await {
@@ -768,6 +825,43 @@ impl TaskServer {
         TaskServer::success(&repsonse)
     }
 
+    #[tool(
+        description = "Update the title, description, or base branch of the pull request (GitHub) or merge request (GitLab) opened from a workspace. `workspace_id` and `repo_id` are required! `title`, `description`, and `base_branch` are optional."
+    )]
+    async fn update_pull_request(
+        &self,
+        Parameters(UpdatePullRequestRequest {
+            workspace_id,
+            repo_id,
+            title,
+            description,
+            base_branch,
+        }): Parameters<UpdatePullRequestRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = UpdatePrRequest {
+            repo_id,
+            title,
+            body: description,
+            base_branch,
+        };
+        let url = self.url(&format!(
+            "/api/task-attempts/{}/merge-request",
+            workspace_id
+        ));
+        if let Err(e) = self
+            .send_json::<serde_json::Value>(self.client.patch(&url).json(&payload))
+            .await
+        {
+            return Ok(e);
+        }
+
+        let response = UpdatePullRequestResponse {
+            workspace_id: workspace_id.to_string(),
+            repo_id: repo_id.to_string(),
+        };
+        TaskServer::success(&response)
+    }
+
     #[tool(
         description = "Delete a task/ticket from a project. `project_id` and `task_id` are required!"
     )]
@@ -813,7 +907,7 @@ impl TaskServer {
 #[tool_handler]
 impl ServerHandler for TaskServer {
     fn get_info(&self) -> ServerInfo {
-        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
+        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos', 'update_pull_request'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
         if self.context.is_some() {
             let context_instruction = "Use 'get_context' to fetch project/task/workspace metadata for the active Vibe Kanban workspace session when available.";
             instruction = format!("{} {}", context_instruction, instruction);
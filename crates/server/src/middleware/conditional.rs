@@ -0,0 +1,93 @@
+use axum::{
+    Extension,
+    extract::{Query, Request, State},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use db::models::{project::Project, tenant::Tenant};
+use deployment::Deployment;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::tenants::ensure_project_in_tenant};
+
+#[derive(Debug, Deserialize)]
+struct ProjectQuery {
+    project_id: Uuid,
+}
+
+/// Entity tags and `If-None-Match` handling for the task list and board of a project.
+///
+/// The tag comes from the version of the project's tasks, which triggers bump with every change
+/// to the rows the response is built from, so it is known before the handler runs: clients that
+/// poll get `304 Not Modified` without the tasks being loaded while nothing changed, and the
+/// response body is streamed as is otherwise.
+pub async fn conditional_project_tasks(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if !is_conditional_read(&request) {
+        return Ok(next.run(request).await);
+    }
+    // Missing or malformed ids are for the handler to reject
+    let Ok(Query(query)) = Query::<ProjectQuery>::try_from_uri(request.uri()) else {
+        return Ok(next.run(request).await);
+    };
+    let pool = &deployment.db().pool;
+    ensure_project_in_tenant(pool, &tenant, query.project_id).await?;
+    let version = Project::tasks_version(pool, query.project_id).await?;
+    Ok(respond_conditionally(request, next, &format!("{}:{version}", tenant.id)).await)
+}
+
+fn is_conditional_read(request: &Request) -> bool {
+    let is_read = request.method() == Method::GET || request.method() == Method::HEAD;
+    // WebSocket upgrades and event streams are not cacheable reads
+    is_read && !request.headers().contains_key(header::UPGRADE)
+}
+
+/// Answer a read whose response only changes with `version`, read before the handler runs. A
+/// change that lands between reading the version and running the handler tags the new response
+/// with the old version, which only costs the next request a full response.
+async fn respond_conditionally(request: Request, next: Next, version: &str) -> Response {
+    let mut hasher = Sha256::new();
+    hasher.update(version.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(request.uri().to_string().as_bytes());
+    let etag = format!("W/\"{:x}\"", hasher.finalize());
+    let Ok(etag_value) = HeaderValue::from_str(&etag) else {
+        return next.run(request).await;
+    };
+
+    let matches = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| matches_etag(value, &etag));
+    let mut response = if matches {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let response = next.run(request).await;
+        if response.status() != StatusCode::OK {
+            return response;
+        }
+        response
+    };
+
+    let headers = response.headers_mut();
+    headers.insert(header::ETAG, etag_value);
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    response
+}
+
+/// Weak comparison of an `If-None-Match` list against our tag
+fn matches_etag(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+    if_none_match
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
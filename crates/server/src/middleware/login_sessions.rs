@@ -0,0 +1,79 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::{OptionalFromRequestParts, Request, State},
+    http::{HeaderMap, header, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use db::models::login_session::LoginSession;
+use deployment::Deployment;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::service_accounts::hash_key};
+
+/// Cookie with the session token of a browser that signed in through the app
+pub const SESSION_COOKIE: &str = "vk_session";
+
+/// How long a browser stays signed in
+pub const SESSION_LIFETIME_DAYS: i64 = 30;
+
+/// The account a request is signed in as, from its session cookie. Inserted as an extension;
+/// requests without a valid session have none.
+#[derive(Debug, Clone, Copy)]
+pub struct SignedInUser(pub Uuid);
+
+/// The session token a request carries, if any
+pub fn session_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, token)| token)
+        .filter(|token| !token.is_empty())
+}
+
+/// `Set-Cookie` value that keeps a session token in the browser
+pub fn session_cookie(token: &str) -> String {
+    let max_age = SESSION_LIFETIME_DAYS * 24 * 60 * 60;
+    format!("{SESSION_COOKIE}={token}; Path=/; HttpOnly; SameSite=Lax; Max-Age={max_age}")
+}
+
+/// `Set-Cookie` value that removes the session token from the browser
+pub fn expired_session_cookie() -> String {
+    format!("{SESSION_COOKIE}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0")
+}
+
+/// Requests with a valid session cookie are signed in as its account. Unknown and expired
+/// sessions are treated as signed out rather than rejected, so a stale cookie never locks anyone
+/// out of the default organization.
+pub async fn authenticate_login_session(
+    State(deployment): State<DeploymentImpl>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let session = match session_token(request.headers()) {
+        Some(token) => LoginSession::authenticate(&deployment.db().pool, &hash_key(token)).await?,
+        None => None,
+    };
+    if let Some(session) = session {
+        request
+            .extensions_mut()
+            .insert(SignedInUser(session.user_id));
+    }
+    Ok(next.run(request).await)
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for SignedInUser {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        Ok(parts.extensions.get::<SignedInUser>().copied())
+    }
+}
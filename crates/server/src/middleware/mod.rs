@@ -1,3 +1,8 @@
+pub mod conditional;
+pub mod login_sessions;
 pub mod model_loaders;
+pub mod problem;
+pub mod service_accounts;
+pub mod tenants;
 
 pub use model_loaders::*;
@@ -1,24 +1,49 @@
 use axum::{
+    Extension,
     extract::{Path, Request, State},
     http::StatusCode,
     middleware::Next,
     response::Response,
 };
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, session::Session, tag::Tag, task::Task,
-    workspace::Workspace,
+    automation_rule::AutomationRule, automation_transition::AutomationTransition,
+    execution_process::ExecutionProcess, project::Project, saved_view::SavedView, session::Session,
+    tag::Tag, task::Task, tenant::Tenant, workspace::Workspace,
 };
 use deployment::Deployment;
+use utils::api::oauth::LoginStatus;
 use uuid::Uuid;
 
 use crate::DeploymentImpl;
 
+/// Models of another tenant are reported as missing, so their ids reveal nothing
+fn ensure_owned(owned: Result<bool, sqlx::Error>, kind: &str, id: Uuid) -> Result<(), StatusCode> {
+    match owned {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            tracing::warn!("{} {} belongs to another tenant", kind, id);
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            tracing::error!("Failed to check the tenant of {} {}: {}", kind, id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 pub async fn load_project_middleware(
     State(deployment): State<DeploymentImpl>,
     Path(project_id): Path<Uuid>,
+    Extension(tenant): Extension<Tenant>,
     request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
+    ensure_owned(
+        Tenant::owns_project(&deployment.db().pool, tenant.id, project_id).await,
+        "Project",
+        project_id,
+    )?;
+
     // Load the project from the database
     let project = match Project::find_by_id(&deployment.db().pool, project_id).await {
         Ok(Some(project)) => project,
@@ -43,9 +68,16 @@ pub async fn load_project_middleware(
 pub async fn load_task_middleware(
     State(deployment): State<DeploymentImpl>,
     Path(task_id): Path<Uuid>,
+    Extension(tenant): Extension<Tenant>,
     request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
+    ensure_owned(
+        Tenant::owns_task(&deployment.db().pool, tenant.id, task_id).await,
+        "Task",
+        task_id,
+    )?;
+
     // Load the task and validate it belongs to the project
     let task = match Task::find_by_id(&deployment.db().pool, task_id).await {
         Ok(Some(task)) => task,
@@ -70,9 +102,16 @@ pub async fn load_task_middleware(
 pub async fn load_workspace_middleware(
     State(deployment): State<DeploymentImpl>,
     Path(workspace_id): Path<Uuid>,
+    Extension(tenant): Extension<Tenant>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
+    ensure_owned(
+        Tenant::owns_workspace(&deployment.db().pool, tenant.id, workspace_id).await,
+        "Workspace",
+        workspace_id,
+    )?;
+
     // Load the Workspace from the database
     let workspace = match Workspace::find_by_id(&deployment.db().pool, workspace_id).await {
         Ok(Some(w)) => w,
@@ -96,9 +135,16 @@ pub async fn load_workspace_middleware(
 pub async fn load_execution_process_middleware(
     State(deployment): State<DeploymentImpl>,
     Path(process_id): Path<Uuid>,
+    Extension(tenant): Extension<Tenant>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
+    ensure_owned(
+        Tenant::owns_execution_process(&deployment.db().pool, tenant.id, process_id).await,
+        "ExecutionProcess",
+        process_id,
+    )?;
+
     // Load the execution process from the database
     let execution_process =
         match ExecutionProcess::find_by_id(&deployment.db().pool, process_id).await {
@@ -151,9 +197,16 @@ pub async fn load_tag_middleware(
 pub async fn load_session_middleware(
     State(deployment): State<DeploymentImpl>,
     Path(session_id): Path<Uuid>,
+    Extension(tenant): Extension<Tenant>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
+    ensure_owned(
+        Tenant::owns_session(&deployment.db().pool, tenant.id, session_id).await,
+        "Session",
+        session_id,
+    )?;
+
     let session = match Session::find_by_id(&deployment.db().pool, session_id).await {
         Ok(Some(session)) => session,
         Ok(None) => {
@@ -169,3 +222,99 @@ pub async fn load_session_middleware(
     request.extensions_mut().insert(session);
     Ok(next.run(request).await)
 }
+
+pub async fn load_automation_rule_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(rule_id): Path<Uuid>,
+    Extension(tenant): Extension<Tenant>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let rule = match AutomationRule::find_by_id(&deployment.db().pool, rule_id).await {
+        Ok(Some(rule)) => rule,
+        Ok(None) => {
+            tracing::warn!("Automation rule {} not found", rule_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch automation rule {}: {}", rule_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if let Some(project_id) = rule.project_id {
+        ensure_owned(
+            Tenant::owns_project(&deployment.db().pool, tenant.id, project_id).await,
+            "Automation rule",
+            rule_id,
+        )?;
+    }
+
+    request.extensions_mut().insert(rule);
+    Ok(next.run(request).await)
+}
+
+pub async fn load_automation_transition_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(transition_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let transition =
+        match AutomationTransition::find_by_id(&deployment.db().pool, transition_id).await {
+            Ok(Some(transition)) => transition,
+            Ok(None) => {
+                tracing::warn!("Automation transition {} not found", transition_id);
+                return Err(StatusCode::NOT_FOUND);
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to fetch automation transition {}: {}",
+                    transition_id,
+                    e
+                );
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+    request.extensions_mut().insert(transition);
+    Ok(next.run(request).await)
+}
+
+/// Personal views of other users are reported as missing, like models of another tenant
+pub async fn load_saved_view_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(view_id): Path<Uuid>,
+    Extension(tenant): Extension<Tenant>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let view = match SavedView::find_by_id(&deployment.db().pool, view_id).await {
+        Ok(Some(view)) => view,
+        Ok(None) => {
+            tracing::warn!("Saved view {} not found", view_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch saved view {}: {}", view_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    ensure_owned(
+        Tenant::owns_project(&deployment.db().pool, tenant.id, view.project_id).await,
+        "Saved view",
+        view_id,
+    )?;
+    if !view.shared {
+        let user_id = match deployment.get_login_status().await {
+            LoginStatus::LoggedIn { profile } => Some(profile.user_id),
+            LoginStatus::LoggedOut => None,
+        };
+        if view.owner_user_id != user_id {
+            tracing::warn!("Saved view {} belongs to another user", view_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+    }
+
+    request.extensions_mut().insert(view);
+    Ok(next.run(request).await)
+}
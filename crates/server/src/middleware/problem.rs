@@ -0,0 +1,58 @@
+use axum::{
+    body::to_bytes,
+    extract::Request,
+    http::{HeaderMap, header},
+    middleware::Next,
+    response::Response,
+};
+use utils::response::ProblemDetails;
+
+use crate::error::problem_response;
+
+/// Plain-text error bodies longer than this are cut off in the problem detail
+const MAX_TEXT_DETAIL: usize = 64 * 1024;
+
+/// Makes every error response an RFC 7807 problem.
+///
+/// `ApiError` responses get the request path as their `instance`. Other errors, like extractor
+/// rejections for malformed JSON or a bare `StatusCode::NOT_FOUND` from a model loader, are plain
+/// text or empty; their text becomes the `detail` of a problem typed after the status code.
+pub async fn problem_instance(request: Request, next: Next) -> Response {
+    let instance = request.uri().path().to_string();
+    let mut response = next.run(request).await;
+
+    if let Some(mut problem) = response.extensions_mut().remove::<ProblemDetails>() {
+        problem.instance = Some(instance);
+        return problem_response(problem);
+    }
+
+    let status = response.status();
+    if !(status.is_client_error() || status.is_server_error())
+        || !is_plain_text_or_empty(response.headers())
+    {
+        return response;
+    }
+
+    let code = status
+        .canonical_reason()
+        .map(|reason| reason.to_lowercase().replace([' ', '-'], "_"))
+        .unwrap_or_else(|| "error".to_string());
+    let mut problem = ProblemDetails::new(status.as_u16(), &code);
+    problem.instance = Some(instance);
+    let (_, body) = response.into_parts();
+    if let Ok(bytes) = to_bytes(body, MAX_TEXT_DETAIL).await
+        && !bytes.is_empty()
+    {
+        problem.detail = Some(String::from_utf8_lossy(&bytes).into_owned());
+    }
+    problem_response(problem)
+}
+
+fn is_plain_text_or_empty(headers: &HeaderMap) -> bool {
+    match headers.get(header::CONTENT_TYPE) {
+        Some(value) => value
+            .to_str()
+            .is_ok_and(|value| value.starts_with("text/plain")),
+        None => true,
+    }
+}
@@ -0,0 +1,102 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::{FromRequestParts, Request, State},
+    http::{Method, header, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use db::models::service_account::{ServiceAccount, ServiceAccountScope};
+use deployment::Deployment;
+use services::services::task_events::TaskActor;
+use sha2::{Digest, Sha256};
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Start of every service account key, so other bearer tokens are left alone
+pub const KEY_PREFIX: &str = "vksa_";
+
+/// Parts of the API a service account can read with the `read` scope. Settings, credentials and
+/// the service accounts themselves are left out.
+const READABLE: &[&str] = &[
+    "projects",
+    "tasks",
+    "task-attempts",
+    "sessions",
+    "execution-processes",
+    "containers",
+    "tags",
+    "events",
+];
+
+/// Only the hash of a key is stored
+pub fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+/// The scope a service account needs for a request; None when no scope allows it
+fn required_scope(method: &Method, path: &str) -> Option<ServiceAccountScope> {
+    let section = path.trim_start_matches('/').split('/').next()?;
+    if section == "webhooks" {
+        return Some(ServiceAccountScope::ReceiveWebhooks);
+    }
+    if matches!(*method, Method::GET | Method::HEAD) {
+        return READABLE
+            .contains(&section)
+            .then_some(ServiceAccountScope::Read);
+    }
+    match section {
+        "tasks" => Some(ServiceAccountScope::WriteTasks),
+        "task-attempts" | "sessions" => Some(ServiceAccountScope::StartAttempts),
+        _ => None,
+    }
+}
+
+/// Requests with a service account key in `Authorization: Bearer` act as that account, within
+/// its scopes. Requests without one are the person using the app, as before.
+pub async fn authenticate_service_account(
+    State(deployment): State<DeploymentImpl>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(key) = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .filter(|key| key.starts_with(KEY_PREFIX))
+    else {
+        return Ok(next.run(request).await);
+    };
+
+    let account = ServiceAccount::authenticate(&deployment.db().pool, &hash_key(key))
+        .await?
+        .ok_or(ApiError::Unauthorized)?;
+    let allowed = required_scope(request.method(), request.uri().path())
+        .is_some_and(|scope| account.has_scope(scope));
+    if !allowed {
+        return Err(ApiError::Forbidden(format!(
+            "Service account '{}' is not allowed to {} {}",
+            account.name,
+            request.method(),
+            request.uri().path()
+        )));
+    }
+
+    request.extensions_mut().insert(account);
+    Ok(next.run(request).await)
+}
+
+/// Who made a request, for the task event log: a service account or the person using the app
+pub struct Actor(pub TaskActor);
+
+impl<S: Send + Sync> FromRequestParts<S> for Actor {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Actor(match parts.extensions.get::<ServiceAccount>() {
+            Some(account) => TaskActor::service_account(&account.name),
+            None => TaskActor::user(),
+        }))
+    }
+}
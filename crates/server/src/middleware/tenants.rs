@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use axum::{
+    Extension,
+    extract::{Path, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use db::models::{service_account::ServiceAccount, tenant::Tenant};
+use deployment::Deployment;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::login_sessions::SignedInUser};
+
+/// Header the app sends with the organization picked in the switcher
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// Query parameter that stands in for the header on WebSocket and event stream requests, which
+/// browsers send without custom headers
+const TENANT_QUERY_PARAM: &str = "tenant_id";
+
+/// Comma-separated user ids of the accounts that administer the instance
+pub const INSTANCE_ADMINS_ENV: &str = "VK_INSTANCE_ADMINS";
+
+fn instance_admins() -> Vec<Uuid> {
+    std::env::var(INSTANCE_ADMINS_ENV)
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|id| Uuid::parse_str(id.trim()).ok())
+        .collect()
+}
+
+/// Fail unless the request may change what applies to every organization, like their quotas and
+/// automation rules without a project. That takes an account listed in `VK_INSTANCE_ADMINS`;
+/// without any listed, only an install that never added a second organization is administered
+/// by whoever uses it.
+pub async fn require_instance_admin(
+    deployment: &DeploymentImpl,
+    user: Option<SignedInUser>,
+) -> Result<(), ApiError> {
+    let admins = instance_admins();
+    let allowed = if admins.is_empty() {
+        Tenant::find_all(&deployment.db().pool).await?.len() <= 1
+    } else {
+        user.is_some_and(|SignedInUser(user_id)| admins.contains(&user_id))
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(format!(
+            "Only an instance admin can do this; admins are listed in {INSTANCE_ADMINS_ENV}"
+        )))
+    }
+}
+
+fn requested_tenant(request: &Request) -> Result<Option<Uuid>, ApiError> {
+    let header = request
+        .headers()
+        .get(TENANT_HEADER)
+        .map(|value| value.to_str().unwrap_or_default().to_string());
+    let query = || {
+        url::form_urlencoded::parse(request.uri().query().unwrap_or_default().as_bytes())
+            .find(|(name, _)| name == TENANT_QUERY_PARAM)
+            .map(|(_, value)| value.into_owned())
+    };
+    header
+        .or_else(query)
+        .map(|value| {
+            Uuid::parse_str(value.trim())
+                .map_err(|_| ApiError::BadRequest(format!("Invalid {TENANT_HEADER} header")))
+        })
+        .transpose()
+}
+
+/// Resolve the tenant a request works in and insert it as an extension. Service accounts always
+/// work in their own tenant. Other requests pick one with `X-Tenant-Id` and fall back to the
+/// default tenant, so single-team installs never need to send it; any other tenant needs the
+/// account the request's session is signed in as to be one of its members.
+pub async fn resolve_tenant(
+    State(deployment): State<DeploymentImpl>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let pool = &deployment.db().pool;
+    let requested = requested_tenant(&request)?;
+
+    let tenant_id = match request.extensions().get::<ServiceAccount>() {
+        Some(account) => {
+            if requested.is_some_and(|id| id != account.tenant_id) {
+                return Err(ApiError::Forbidden(format!(
+                    "Service account '{}' belongs to another tenant",
+                    account.name
+                )));
+            }
+            account.tenant_id
+        }
+        None => match requested {
+            None => Tenant::DEFAULT_ID,
+            Some(id) if id == Tenant::DEFAULT_ID => id,
+            Some(id) => {
+                let Some(&SignedInUser(user_id)) = request.extensions().get::<SignedInUser>()
+                else {
+                    return Err(ApiError::Forbidden(
+                        "Sign in to work in an organization other than the default one".to_string(),
+                    ));
+                };
+                if !Tenant::has_member(pool, id, user_id).await? {
+                    return Err(ApiError::Forbidden(format!(
+                        "You are not a member of organization {id}"
+                    )));
+                }
+                id
+            }
+        },
+    };
+
+    let tenant = Tenant::find_by_id(pool, tenant_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest(format!("Unknown tenant {tenant_id}")))?;
+
+    request.extensions_mut().insert(tenant);
+    Ok(next.run(request).await)
+}
+
+/// Fail as not found when a project given in a query or body belongs to another tenant
+pub async fn ensure_project_in_tenant(
+    pool: &SqlitePool,
+    tenant: &Tenant,
+    project_id: Uuid,
+) -> Result<(), ApiError> {
+    if Tenant::owns_project(pool, tenant.id, project_id).await? {
+        Ok(())
+    } else {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    }
+}
+
+/// Guard for routes that take `project_id` or `repo_id` path parameters without a model loader:
+/// ids of another tenant are reported as missing.
+pub async fn require_tenant_access(
+    State(deployment): State<DeploymentImpl>,
+    Path(params): Path<HashMap<String, String>>,
+    Extension(tenant): Extension<Tenant>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let pool = &deployment.db().pool;
+    let parse = |name: &str| {
+        params
+            .get(name)
+            .map(|value| {
+                Uuid::parse_str(value)
+                    .map_err(|_| ApiError::BadRequest(format!("Invalid {name}: {value}")))
+            })
+            .transpose()
+    };
+
+    if let Some(project_id) = parse("project_id")? {
+        ensure_project_in_tenant(pool, &tenant, project_id).await?;
+    }
+    if let Some(repo_id) = parse("repo_id")?
+        && !Tenant::owns_repo(pool, tenant.id, repo_id).await?
+    {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+
+    Ok(next.run(request).await)
+}
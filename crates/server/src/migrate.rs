@@ -0,0 +1,117 @@
+//! The `migrate` command: inspect, apply and undo database migrations without starting the
+//! server.
+
+use db::migrate::{self, MigrationError, MigrationInfo, MigrationStatus};
+use services::services::config::load_config_from_file;
+use sqlx::SqlitePool;
+use utils::assets::{config_path, database_path};
+
+use crate::cli::{MigrateAction, MigrateArgs};
+
+pub async fn run(args: &MigrateArgs) -> Result<(), MigrationError> {
+    let tuning = load_config_from_file(&config_path()).await.database;
+    println!("Database: {}", database_path().display());
+    let pool = migrate::connect(&tuning).await?;
+    let status = migrate::status(&pool).await?;
+    match args.action {
+        MigrateAction::Status => print_status(&status),
+        MigrateAction::Up => up(&pool, &status, args).await?,
+        MigrateAction::Down => down(pool, &status, args).await?,
+    }
+    Ok(())
+}
+
+fn print_migrations(migrations: &[MigrationInfo]) {
+    for migration in migrations {
+        println!("  {} {}", migration.version, migration.description);
+    }
+}
+
+fn print_status(status: &MigrationStatus) {
+    match status.applied.last() {
+        Some(latest) => println!(
+            "Applied: {} (latest {} {})",
+            status.applied.len(),
+            latest.version,
+            latest.description
+        ),
+        None => println!("Applied: none"),
+    }
+    println!("Pending: {}", status.pending.len());
+    print_migrations(&status.pending);
+    if !status.unknown.is_empty() {
+        println!("Applied by a newer version of the app:");
+        for version in &status.unknown {
+            println!("  {version}");
+        }
+    }
+    if let Some(version) = status.failed {
+        println!("Failed part-way: {version}");
+    }
+}
+
+async fn up(
+    pool: &SqlitePool,
+    status: &MigrationStatus,
+    args: &MigrateArgs,
+) -> Result<(), MigrationError> {
+    status.check()?;
+    if status.pending.is_empty() {
+        println!("The database is up to date");
+        return Ok(());
+    }
+    if args.dry_run {
+        println!("Would apply {} migrations:", status.pending.len());
+        print_migrations(&status.pending);
+        if !args.no_backup && !status.applied.is_empty() {
+            println!(
+                "after backing up the database to {}",
+                migrate::backup_dir().display()
+            );
+        }
+        return Ok(());
+    }
+
+    if let Some(backup) = migrate::run_pending(pool, !args.no_backup).await? {
+        println!("Backed up the database to {}", backup.display());
+    }
+    println!("Applied {} migrations:", status.pending.len());
+    print_migrations(&status.pending);
+    Ok(())
+}
+
+async fn down(
+    pool: SqlitePool,
+    status: &MigrationStatus,
+    args: &MigrateArgs,
+) -> Result<(), MigrationError> {
+    status.check()?;
+    let target = migrate::downgrade_target(status)?;
+    let restores = if target.restores_to == 0 {
+        "an empty database".to_string()
+    } else {
+        format!("the database as of migration {}", target.restores_to)
+    };
+    if args.dry_run {
+        println!(
+            "Would undo migration {} by restoring {} from {}",
+            target.undone,
+            restores,
+            target.backup.display()
+        );
+        return Ok(());
+    }
+
+    let current = migrate::restore(pool, status, &target.backup).await?;
+    println!(
+        "Undid migration {} by restoring {} from {}",
+        target.undone,
+        restores,
+        target.backup.display()
+    );
+    println!(
+        "The database before the downgrade was backed up to {}",
+        current.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! Per-project permissions of the signed-in user. Projects linked to a remote project inherit the
+//! organization role of the user, adjusted by the overrides set on the remote project. Everything is
+//! allowed in local-only projects and while signed out, as there is no organization to answer to.
+
+use db::models::{project::Project, workspace::Workspace};
+use deployment::Deployment;
+use utils::api::{
+    oauth::LoginStatus,
+    projects::{EffectiveProjectPermissions, ProjectPermission},
+};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn effective_permissions(
+    deployment: &DeploymentImpl,
+    project: &Project,
+) -> Result<EffectiveProjectPermissions, ApiError> {
+    let Some(remote_project_id) = project.remote_project_id else {
+        return Ok(EffectiveProjectPermissions::unrestricted());
+    };
+    let Ok(client) = deployment.remote_client() else {
+        return Ok(EffectiveProjectPermissions::unrestricted());
+    };
+    if let LoginStatus::LoggedOut = deployment.get_login_status().await {
+        return Ok(EffectiveProjectPermissions::unrestricted());
+    }
+    Ok(client.get_my_project_permissions(remote_project_id).await?)
+}
+
+/// `Err(ApiError::Forbidden)` unless the user has `permission` in the project
+pub async fn require_permission(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    permission: ProjectPermission,
+) -> Result<(), ApiError> {
+    let project = Project::find_by_id(&deployment.db().pool, project_id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    if effective_permissions(deployment, &project)
+        .await?
+        .allows(permission)
+    {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(format!(
+            "You are not allowed to {} in this project",
+            permission.action()
+        )))
+    }
+}
+
+/// [`require_permission`] in the project of a task attempt
+pub async fn require_workspace_permission(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    permission: ProjectPermission,
+) -> Result<(), ApiError> {
+    let task = workspace
+        .parent_task(&deployment.db().pool)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    require_permission(deployment, task.project_id, permission).await
+}
@@ -0,0 +1,208 @@
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{delete, get, post},
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    job::{Job, JobStatusCount},
+    task_event::{TaskEvent, TaskEventFilter, TaskEventSource},
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    comment_triggers::COMMENT_TRIGGER_JOB, container::ContainerService,
+    notification::DELIVER_NOTIFICATION_JOB, queued_message::QueuedMessage,
+    throttle::ThrottledExecution,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, routes::jobs::JobListQuery};
+
+const AUDIT_LOG_LIMIT: i64 = 100;
+const MAX_AUDIT_LOG_LIMIT: i64 = 1000;
+
+/// Job kinds that deliver something outside the app
+fn delivery_kinds() -> Vec<String> {
+    [COMMENT_TRIGGER_JOB, DELIVER_NOTIFICATION_JOB]
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Everything that is waiting on something, to answer "why is nothing happening"
+#[derive(Debug, Serialize, TS)]
+pub struct QueueOverview {
+    pub jobs: Vec<JobStatusCount>,
+    /// When the longest-waiting due job became due; long ago means the workers are stuck or busy
+    pub oldest_due_job_at: Option<DateTime<Utc>>,
+    /// Webhook and notification deliveries per status
+    pub deliveries: Vec<JobStatusCount>,
+    /// Coding agent runs waiting for a slot under the executor limits
+    pub queued_executions: Vec<ThrottledExecution>,
+    /// Follow-ups waiting for the running turn of their session to finish
+    pub queued_messages: Vec<QueuedMessage>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct QueuedAgeQuery {
+    /// Only entries waiting at least this many seconds
+    pub min_age_secs: Option<i64>,
+}
+
+impl QueuedAgeQuery {
+    fn admits(&self, since: DateTime<Utc>) -> bool {
+        self.min_age_secs
+            .is_none_or(|secs| Utc::now() - since >= chrono::Duration::seconds(secs))
+    }
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct AuditLogQuery {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub source: Option<TaskEventSource>,
+    /// e.g. `rule:Close merged` for the changes of one automation rule
+    pub actor: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+pub async fn get_queue_overview(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<QueueOverview>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let overview = QueueOverview {
+        jobs: Job::count_by_status(pool, &[]).await?,
+        oldest_due_job_at: Job::oldest_due_at(pool).await?,
+        deliveries: Job::count_by_status(pool, &delivery_kinds()).await?,
+        queued_executions: deployment.container().executor_throttle().waiting(),
+        queued_messages: deployment.queued_message_service().list(),
+    };
+    Ok(ResponseJson(ApiResponse::success(overview)))
+}
+
+/// Webhook and notification deliveries, filtered like the job list
+pub async fn list_deliveries(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<JobListQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Job>>>, ApiError> {
+    let kinds = match &query.kind {
+        Some(kind) if delivery_kinds().contains(kind) => vec![kind.clone()],
+        Some(kind) => {
+            return Err(ApiError::BadRequest(format!(
+                "{kind} is not a delivery job"
+            )));
+        }
+        None => delivery_kinds(),
+    };
+    let deliveries = Job::list(&deployment.db().pool, &query.filter(kinds), query.limit()).await?;
+    Ok(ResponseJson(ApiResponse::success(deliveries)))
+}
+
+pub async fn list_queued_executions(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<QueuedAgeQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ThrottledExecution>>>, ApiError> {
+    let queued = deployment
+        .container()
+        .executor_throttle()
+        .waiting()
+        .into_iter()
+        .filter(|execution| query.admits(execution.waiting_since))
+        .collect();
+    Ok(ResponseJson(ApiResponse::success(queued)))
+}
+
+/// Stop a run that is still waiting for an executor slot
+pub async fn cancel_queued_execution(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let throttle = deployment.container().executor_throttle();
+    if !throttle
+        .waiting()
+        .iter()
+        .any(|execution| execution.execution_process_id == id)
+    {
+        return Err(ApiError::Conflict(
+            "The execution is not waiting for an executor slot".to_string(),
+        ));
+    }
+    let execution_process = ExecutionProcess::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    deployment
+        .container()
+        .stop_execution(&execution_process, ExecutionProcessStatus::Killed)
+        .await?;
+    throttle.release(&id);
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn list_queued_messages(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<QueuedAgeQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueuedMessage>>>, ApiError> {
+    let queued = deployment
+        .queued_message_service()
+        .list()
+        .into_iter()
+        .filter(|message| query.admits(message.queued_at))
+        .collect();
+    Ok(ResponseJson(ApiResponse::success(queued)))
+}
+
+pub async fn cancel_queued_message(
+    State(deployment): State<DeploymentImpl>,
+    Path(session_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<QueuedMessage>>, ApiError> {
+    let message = deployment
+        .queued_message_service()
+        .cancel_queued(session_id)
+        .ok_or(sqlx::Error::RowNotFound)?;
+    Ok(ResponseJson(ApiResponse::success(message)))
+}
+
+/// Audit log of task changes, most recent first
+pub async fn list_task_events(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskEvent>>>, ApiError> {
+    let filter = TaskEventFilter {
+        project_id: query.project_id,
+        task_id: query.task_id,
+        source: query.source,
+        actor: query.actor,
+        since: query.since,
+        until: query.until,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(AUDIT_LOG_LIMIT)
+        .clamp(1, MAX_AUDIT_LOG_LIMIT);
+    let events = TaskEvent::list(&deployment.db().pool, &filter, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(events)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/admin/queues", get(get_queue_overview))
+        .route("/admin/deliveries", get(list_deliveries))
+        .route("/admin/executions/queued", get(list_queued_executions))
+        .route(
+            "/admin/executions/{id}/cancel",
+            post(cancel_queued_execution),
+        )
+        .route("/admin/queued-messages", get(list_queued_messages))
+        .route("/admin/task-events", get(list_task_events))
+        .route(
+            "/admin/queued-messages/{session_id}",
+            delete(cancel_queued_message),
+        )
+}
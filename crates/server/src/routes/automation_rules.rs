@@ -0,0 +1,179 @@
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use db::models::{
+    automation_rule::{AutomationRule, CreateAutomationRule, UpdateAutomationRule},
+    tenant::Tenant,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::rules::{ExprError, validate_condition};
+use ts_rs::TS;
+use utils::{api::projects::ProjectPermission, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{
+        load_automation_rule_middleware,
+        login_sessions::SignedInUser,
+        tenants::{ensure_project_in_tenant, require_instance_admin},
+    },
+    permissions,
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ValidateConditionRequest {
+    pub condition: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ValidateConditionResponse {
+    pub valid: bool,
+    pub error: Option<String>,
+    /// Character offset of a parse error
+    pub position: Option<usize>,
+}
+
+fn check_condition(condition: &str) -> Result<(), ApiError> {
+    validate_condition(condition)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid rule condition: {e}")))
+}
+
+/// Rules without a project apply to every organization, so only an instance admin edits them;
+/// project rules need the project to be in the request's tenant and the permission there
+async fn ensure_can_edit(
+    deployment: &DeploymentImpl,
+    tenant: &Tenant,
+    user: Option<SignedInUser>,
+    project_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    match project_id {
+        Some(project_id) => {
+            ensure_project_in_tenant(&deployment.db().pool, tenant, project_id).await?;
+            permissions::require_permission(
+                deployment,
+                project_id,
+                ProjectPermission::EditAutomationRules,
+            )
+            .await
+        }
+        None => require_instance_admin(deployment, user).await,
+    }
+}
+
+/// Rules of the tenant's projects, and those that apply to every project
+pub async fn get_automation_rules(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+) -> Result<ResponseJson<ApiResponse<Vec<AutomationRule>>>, ApiError> {
+    let rules = AutomationRule::find_for_tenant(&deployment.db().pool, tenant.id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn create_automation_rule(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+    user: Option<SignedInUser>,
+    Json(payload): Json<CreateAutomationRule>,
+) -> Result<ResponseJson<ApiResponse<AutomationRule>>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest("Rule name is required".to_string()));
+    }
+    check_condition(&payload.condition)?;
+    ensure_can_edit(&deployment, &tenant, user, payload.project_id).await?;
+
+    let rule = AutomationRule::create(&deployment.db().pool, &payload).await?;
+    deployment.automation_rules().invalidate().await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "automation_rule_created",
+            serde_json::json!({
+                "rule_id": rule.id.to_string(),
+                "project_scoped": rule.project_id.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+pub async fn update_automation_rule(
+    Extension(rule): Extension<AutomationRule>,
+    Extension(tenant): Extension<Tenant>,
+    State(deployment): State<DeploymentImpl>,
+    user: Option<SignedInUser>,
+    Json(payload): Json<UpdateAutomationRule>,
+) -> Result<ResponseJson<ApiResponse<AutomationRule>>, ApiError> {
+    if let Some(condition) = &payload.condition {
+        check_condition(condition)?;
+    }
+    ensure_can_edit(&deployment, &tenant, user, rule.project_id).await?;
+
+    let updated = AutomationRule::update(&deployment.db().pool, rule.id, &payload).await?;
+    deployment.automation_rules().invalidate().await;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_automation_rule(
+    Extension(rule): Extension<AutomationRule>,
+    Extension(tenant): Extension<Tenant>,
+    State(deployment): State<DeploymentImpl>,
+    user: Option<SignedInUser>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_can_edit(&deployment, &tenant, user, rule.project_id).await?;
+    let rows_affected = AutomationRule::delete(&deployment.db().pool, rule.id).await?;
+    deployment.automation_rules().invalidate().await;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Parse a condition without saving it, so the editor can show errors as the user types
+pub async fn validate_automation_condition(
+    Json(payload): Json<ValidateConditionRequest>,
+) -> ResponseJson<ApiResponse<ValidateConditionResponse>> {
+    let response = match validate_condition(&payload.condition) {
+        Ok(()) => ValidateConditionResponse {
+            valid: true,
+            error: None,
+            position: None,
+        },
+        Err(e) => ValidateConditionResponse {
+            valid: false,
+            position: match &e {
+                ExprError::Parse { pos, .. } => Some(*pos),
+                ExprError::Eval(_) => None,
+            },
+            error: Some(e.to_string()),
+        },
+    };
+    ResponseJson(ApiResponse::success(response))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let rule_router = Router::new()
+        .route(
+            "/",
+            put(update_automation_rule).delete(delete_automation_rule),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_automation_rule_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_automation_rules).post(create_automation_rule))
+        .route("/validate", post(validate_automation_condition))
+        .nest("/{rule_id}", rule_router);
+
+    Router::new().nest("/automation-rules", inner)
+}
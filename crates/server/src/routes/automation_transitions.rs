@@ -0,0 +1,259 @@
+//! Named transitions, e.g. "start review round", applied to tasks on request.
+//!
+//! Every task is checked before any step runs: it has to exist, belong to the tenant and the
+//! transition's project, and have an open pull request when a step acts on one. A failing step
+//! then only stops the remaining steps of its own task, since changes already made on the
+//! provider can't be rolled back.
+
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use db::models::{
+    automation_rule::RuleAction,
+    automation_transition::{
+        AutomationTransition, CreateAutomationTransition, UpdateAutomationTransition,
+    },
+    merge::Merge,
+    task::Task,
+    tenant::Tenant,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::rules::{self, TransitionOutcome, acts_on_pr};
+use ts_rs::TS;
+use utils::{api::projects::ProjectPermission, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_automation_transition_middleware, permissions,
+};
+
+/// Tasks accepted per apply request
+const MAX_TASKS: usize = 100;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ApplyTransitionRequest {
+    pub task_ids: Vec<Uuid>,
+}
+
+fn check_steps(steps: &[RuleAction]) -> Result<(), ApiError> {
+    if steps.is_empty() {
+        return Err(ApiError::BadRequest(
+            "A transition needs at least one step".to_string(),
+        ));
+    }
+    for step in steps {
+        if let RuleAction::RequestReviewers { reviewers } = step
+            && reviewers.iter().all(|r| r.trim().is_empty())
+        {
+            return Err(ApiError::BadRequest(
+                "Requesting reviewers needs at least one reviewer".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Transitions without a project belong to this instance; project transitions need the
+/// permission there
+async fn ensure_can_edit(
+    deployment: &DeploymentImpl,
+    project_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    match project_id {
+        Some(project_id) => {
+            permissions::require_permission(
+                deployment,
+                project_id,
+                ProjectPermission::EditAutomationRules,
+            )
+            .await
+        }
+        None => Ok(()),
+    }
+}
+
+/// Permissions the steps need in the project of a task they are applied to
+fn required_permissions(steps: &[RuleAction]) -> Vec<ProjectPermission> {
+    let mut required = Vec::new();
+    if steps.iter().any(acts_on_pr) {
+        required.push(ProjectPermission::CreatePr);
+    }
+    if steps
+        .iter()
+        .any(|step| matches!(step, RuleAction::StartTask { .. }))
+    {
+        required.push(ProjectPermission::StartExecution);
+    }
+    required
+}
+
+pub async fn get_automation_transitions(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<AutomationTransition>>>, ApiError> {
+    let transitions = AutomationTransition::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(transitions)))
+}
+
+pub async fn create_automation_transition(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateAutomationTransition>,
+) -> Result<ResponseJson<ApiResponse<AutomationTransition>>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Transition name is required".to_string(),
+        ));
+    }
+    check_steps(&payload.steps)?;
+    ensure_can_edit(&deployment, payload.project_id).await?;
+
+    let transition = AutomationTransition::create(&deployment.db().pool, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "automation_transition_created",
+            serde_json::json!({
+                "transition_id": transition.id.to_string(),
+                "project_scoped": transition.project_id.is_some(),
+                "steps": transition.steps.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(transition)))
+}
+
+pub async fn update_automation_transition(
+    Extension(transition): Extension<AutomationTransition>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateAutomationTransition>,
+) -> Result<ResponseJson<ApiResponse<AutomationTransition>>, ApiError> {
+    if payload
+        .name
+        .as_ref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        return Err(ApiError::BadRequest(
+            "Transition name is required".to_string(),
+        ));
+    }
+    if let Some(steps) = &payload.steps {
+        check_steps(steps)?;
+    }
+    ensure_can_edit(&deployment, transition.project_id).await?;
+
+    let updated =
+        AutomationTransition::update(&deployment.db().pool, transition.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_automation_transition(
+    Extension(transition): Extension<AutomationTransition>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_can_edit(&deployment, transition.project_id).await?;
+    let rows_affected = AutomationTransition::delete(&deployment.db().pool, transition.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Run the transition's steps on each task, in order
+pub async fn apply_automation_transition(
+    Extension(transition): Extension<AutomationTransition>,
+    Extension(tenant): Extension<Tenant>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ApplyTransitionRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<TransitionOutcome>>>, ApiError> {
+    if payload.task_ids.is_empty() {
+        return Err(ApiError::BadRequest("No tasks given".to_string()));
+    }
+    if payload.task_ids.len() > MAX_TASKS {
+        return Err(ApiError::BadRequest(format!(
+            "A transition can be applied to at most {MAX_TASKS} tasks at once"
+        )));
+    }
+
+    let pool = &deployment.db().pool;
+    let steps = &transition.steps.0;
+    let required = required_permissions(steps);
+    let needs_pr = steps.iter().any(acts_on_pr);
+    for &task_id in &payload.task_ids {
+        if !Tenant::owns_task(pool, tenant.id, task_id).await? {
+            return Err(ApiError::Database(sqlx::Error::RowNotFound));
+        }
+        let task = Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        if transition
+            .project_id
+            .is_some_and(|project_id| project_id != task.project_id)
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Transition \"{}\" does not apply to tasks of the project of \"{}\"",
+                transition.name, task.title
+            )));
+        }
+        for &permission in &required {
+            permissions::require_permission(&deployment, task.project_id, permission).await?;
+        }
+        if needs_pr
+            && Merge::find_open_prs_by_task_id(pool, task_id)
+                .await?
+                .is_empty()
+        {
+            return Err(ApiError::Conflict(format!(
+                "Task \"{}\" has no open pull request",
+                task.title
+            )));
+        }
+    }
+
+    let outcomes = rules::apply_transition(
+        deployment.plugin_api().as_ref(),
+        &transition,
+        &payload.task_ids,
+    )
+    .await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "automation_transition_applied",
+            serde_json::json!({
+                "transition_id": transition.id.to_string(),
+                "tasks": outcomes.len(),
+                "failed": outcomes.iter().filter(|o| o.error.is_some()).count(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(outcomes)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let transition_router = Router::new()
+        .route(
+            "/",
+            put(update_automation_transition).delete(delete_automation_transition),
+        )
+        .route("/apply", post(apply_automation_transition))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_automation_transition_middleware,
+        ));
+
+    let inner = Router::new()
+        .route(
+            "/",
+            get(get_automation_transitions).post(create_automation_transition),
+        )
+        .nest("/{transition_id}", transition_router);
+
+    Router::new().nest("/automation-transitions", inner)
+}
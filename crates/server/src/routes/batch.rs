@@ -0,0 +1,166 @@
+use axum::{
+    Extension, Json, Router, extract::State, response::Json as ResponseJson, routing::post,
+};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    merge::Merge,
+    task::Task,
+    tenant::Tenant,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Operations accepted per batch
+const MAX_OPERATIONS: usize = 50;
+
+/// A read that can be part of a batch
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOperation {
+    Task {
+        task_id: Uuid,
+    },
+    Workspaces {
+        task_id: Uuid,
+    },
+    Merges {
+        workspace_id: Uuid,
+    },
+    /// Latest execution of the workspace for a run reason, the coding agent by default
+    LatestExecution {
+        workspace_id: Uuid,
+        run_reason: Option<ExecutionProcessRunReason>,
+    },
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BatchRequest {
+    pub operations: Vec<BatchOperation>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchData {
+    Task {
+        task: Task,
+    },
+    Workspaces {
+        workspaces: Vec<Workspace>,
+    },
+    Merges {
+        merges: Vec<Merge>,
+    },
+    LatestExecution {
+        execution_process: Option<ExecutionProcess>,
+    },
+}
+
+/// Outcome of one operation; a failed operation does not fail the others
+#[derive(Debug, Serialize, TS)]
+pub struct BatchResult {
+    pub data: Option<BatchData>,
+    pub error: Option<String>,
+}
+
+/// Fail as not found when the task or workspace of an operation belongs to another tenant
+async fn ensure_in_tenant(
+    deployment: &DeploymentImpl,
+    tenant: &Tenant,
+    operation: &BatchOperation,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let owned = match operation {
+        BatchOperation::Task { task_id } | BatchOperation::Workspaces { task_id } => {
+            Tenant::owns_task(pool, tenant.id, *task_id).await?
+        }
+        BatchOperation::Merges { workspace_id }
+        | BatchOperation::LatestExecution { workspace_id, .. } => {
+            Tenant::owns_workspace(pool, tenant.id, *workspace_id).await?
+        }
+    };
+    if owned {
+        Ok(())
+    } else {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    }
+}
+
+async fn run_operation(
+    deployment: &DeploymentImpl,
+    tenant: &Tenant,
+    operation: BatchOperation,
+) -> Result<BatchData, ApiError> {
+    ensure_in_tenant(deployment, tenant, &operation).await?;
+    let pool = &deployment.db().pool;
+    Ok(match operation {
+        BatchOperation::Task { task_id } => BatchData::Task {
+            task: Task::find_by_id(pool, task_id)
+                .await?
+                .ok_or(SqlxError::RowNotFound)?,
+        },
+        BatchOperation::Workspaces { task_id } => BatchData::Workspaces {
+            workspaces: Workspace::fetch_all(pool, Some(task_id)).await?,
+        },
+        BatchOperation::Merges { workspace_id } => BatchData::Merges {
+            merges: Merge::find_by_workspace_id(pool, workspace_id).await?,
+        },
+        BatchOperation::LatestExecution {
+            workspace_id,
+            run_reason,
+        } => BatchData::LatestExecution {
+            execution_process: ExecutionProcess::find_latest_by_workspace_and_run_reason(
+                pool,
+                workspace_id,
+                &run_reason.unwrap_or(ExecutionProcessRunReason::CodingAgent),
+            )
+            .await?,
+        },
+    })
+}
+
+/// Run several reads concurrently; results come back in the order of the operations
+pub async fn run_batch(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+    Json(payload): Json<BatchRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<BatchResult>>>, ApiError> {
+    if payload.operations.len() > MAX_OPERATIONS {
+        return Err(ApiError::BadRequest(format!(
+            "A batch holds at most {MAX_OPERATIONS} operations"
+        )));
+    }
+
+    let results = join_all(
+        payload
+            .operations
+            .into_iter()
+            .map(|operation| run_operation(&deployment, &tenant, operation)),
+    )
+    .await
+    .into_iter()
+    .map(|result| match result {
+        Ok(data) => BatchResult {
+            data: Some(data),
+            error: None,
+        },
+        Err(e) => BatchResult {
+            data: None,
+            error: Some(e.to_string()),
+        },
+    })
+    .collect();
+
+    Ok(ResponseJson(ApiResponse::success(results)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/batch", post(run_batch))
+}
@@ -0,0 +1,229 @@
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use chrono::Utc;
+use db::models::{
+    board::BoardSnapshot,
+    column_sla::{ColumnSla, SetColumnSla, SlaAction, SlaEscalation},
+    project::Project,
+    task::TaskStatus,
+    task_comment::{CreateTaskComment, TaskComment},
+    task_event::TaskEvent,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use serde_json::Value;
+use services::services::{
+    container::ContainerService,
+    jobs::JobHandler,
+    sla::{self, SlaBreach},
+};
+use ts_rs::TS;
+use utils::{api::projects::ProjectPermission, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    permissions,
+    routes::sessions::{FollowUpStart, start_or_queue_follow_up},
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ColumnSlaQuery {
+    pub status: TaskStatus,
+}
+
+pub async fn get_column_slas(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ColumnSla>>>, ApiError> {
+    let slas = ColumnSla::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(slas)))
+}
+
+/// Set the SLA of one column of the project
+pub async fn set_column_sla(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetColumnSla>,
+) -> Result<ResponseJson<ApiResponse<ColumnSla>>, ApiError> {
+    if payload.max_hours <= 0 {
+        return Err(ApiError::BadRequest(
+            "An SLA has to allow at least one hour".to_string(),
+        ));
+    }
+    permissions::require_permission(
+        &deployment,
+        project.id,
+        ProjectPermission::EditAutomationRules,
+    )
+    .await?;
+
+    let sla = ColumnSla::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "column_sla_set",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "status": sla.status.to_string(),
+                "max_hours": sla.max_hours,
+                "action": sla.action,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(sla)))
+}
+
+pub async fn delete_column_sla(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ColumnSlaQuery>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    permissions::require_permission(
+        &deployment,
+        project.id,
+        ProjectPermission::EditAutomationRules,
+    )
+    .await?;
+    let rows_affected = ColumnSla::delete(&deployment.db().pool, project.id, query.status).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Tasks of the project currently past the SLA of their column, longest stalled first
+pub async fn get_sla_breaches(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<SlaBreach>>>, ApiError> {
+    let breaches = project_breaches(&deployment, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(breaches)))
+}
+
+/// Escalations of the project's tasks so far, most recent first
+pub async fn get_sla_escalations(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<SlaEscalation>>>, ApiError> {
+    let escalations = SlaEscalation::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(escalations)))
+}
+
+async fn project_breaches(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+) -> Result<Vec<SlaBreach>, ApiError> {
+    let pool = &deployment.db().read_pool;
+    let slas = ColumnSla::find_by_project_id(pool, project_id).await?;
+    if slas.is_empty() {
+        return Ok(Vec::new());
+    }
+    let board = BoardSnapshot::for_project(pool, project_id).await?;
+    let changes = TaskEvent::find_status_changes(pool, project_id).await?;
+    Ok(sla::breaches(&board, &changes, &slas, Utc::now()))
+}
+
+/// Escalate one stalled task by the action of its column's SLA, unless this stay in the column
+/// was escalated before
+async fn escalate(deployment: &DeploymentImpl, breach: &SlaBreach) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let escalated = SlaEscalation::create(
+        pool,
+        breach.task_id,
+        breach.status.clone(),
+        breach.entered_at,
+        breach.action,
+    )
+    .await?;
+    if !escalated {
+        return Ok(());
+    }
+
+    let comment = sla::escalation_comment(breach);
+    TaskComment::create(
+        pool,
+        breach.task_id,
+        &CreateTaskComment {
+            author: sla::SLA_COMMENT_AUTHOR.to_string(),
+            body: comment.clone(),
+        },
+    )
+    .await?;
+
+    let mut queued = None;
+    match breach.action {
+        SlaAction::Label => {}
+        SlaAction::Notify => {
+            deployment
+                .container()
+                .notification_service()
+                .notify(&breach.title, &comment)
+                .await;
+        }
+        SlaAction::Nudge => {
+            // The newest workspace is the one the agent is working on the task in
+            match Workspace::fetch_all(pool, Some(breach.task_id))
+                .await?
+                .into_iter()
+                .next()
+            {
+                Some(workspace) => {
+                    let started =
+                        start_or_queue_follow_up(deployment, &workspace, sla::nudge_prompt(breach))
+                            .await?;
+                    queued = Some(matches!(started, FollowUpStart::Queued));
+                }
+                None => tracing::debug!(
+                    "Task {} stalled without a workspace to nudge the agent in",
+                    breach.task_id
+                ),
+            }
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "sla_escalated",
+            serde_json::json!({
+                "task_id": breach.task_id.to_string(),
+                "status": breach.status.to_string(),
+                "action": breach.action,
+                "queued": queued,
+            }),
+        )
+        .await;
+
+    Ok(())
+}
+
+/// Handler of the recurring [`sla::SLA_ESCALATION_JOB`]
+pub fn sla_escalation_handler(deployment: DeploymentImpl) -> impl JobHandler {
+    move |_: Value| {
+        let deployment = deployment.clone();
+        async move {
+            for project_id in ColumnSla::find_project_ids(&deployment.db().pool).await? {
+                let breaches = match project_breaches(&deployment, project_id).await {
+                    Ok(breaches) => breaches,
+                    Err(e) => {
+                        tracing::warn!("Failed to check the SLAs of project {}: {}", project_id, e);
+                        continue;
+                    }
+                };
+                for breach in &breaches {
+                    if let Err(e) = escalate(&deployment, breach).await {
+                        tracing::warn!("Failed to escalate stalled task {}: {}", breach.task_id, e);
+                    }
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        }
+    }
+}
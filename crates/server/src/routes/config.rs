@@ -1,13 +1,14 @@
 use std::collections::HashMap;
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     body::Body,
     extract::{Path, Query, State},
     http,
     response::{Json as ResponseJson, Response},
     routing::{get, put},
 };
+use db::models::tenant::Tenant;
 use deployment::{Deployment, DeploymentError};
 use executors::{
     executors::{
@@ -18,10 +19,13 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use services::services::config::{
-    Config, ConfigError, SoundFile,
-    editor::{EditorConfig, EditorType},
-    save_config_to_file,
+use services::services::{
+    config::{
+        Config, ConfigError, SoundFile,
+        editor::{EditorConfig, EditorType},
+        save_config_to_file,
+    },
+    git_provider::{self, ProviderHostMapping, ProviderType},
 };
 use tokio::fs;
 use ts_rs::TS;
@@ -41,6 +45,11 @@ pub fn router() -> Router<DeploymentImpl> {
             get(check_editor_availability),
         )
         .route("/agents/check-availability", get(check_agent_availability))
+        .route("/git-provider-hosts", get(list_provider_hosts))
+        .route(
+            "/git-provider-hosts/{host}",
+            put(upsert_provider_host).delete(delete_provider_host),
+        )
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -79,18 +88,36 @@ pub struct UserSystemInfo {
     pub environment: Environment,
     /// Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
     pub capabilities: HashMap<String, Vec<BaseAgentCapability>>,
+    /// The server runs with `--test-mode`, so the frontend sends no telemetry either
+    pub test_mode: bool,
+}
+
+/// Settings of the tenant a request works in. The default tenant uses the config file; other
+/// tenants keep theirs in the database, starting from the file's settings without its tokens.
+pub(crate) async fn tenant_config(
+    deployment: &DeploymentImpl,
+    tenant: &Tenant,
+) -> Result<Config, ApiError> {
+    if tenant.id == Tenant::DEFAULT_ID {
+        return Ok(deployment.config().read().await.clone());
+    }
+    match Tenant::find_config(&deployment.db().pool, tenant.id).await? {
+        Some(raw) => Ok(Config::from(raw)),
+        None => Ok(deployment.config().read().await.without_secrets()),
+    }
 }
 
 // TODO: update frontend, BE schema has changed, this replaces GET /config and /config/constants
 #[axum::debug_handler]
 async fn get_user_system_info(
     State(deployment): State<DeploymentImpl>,
-) -> ResponseJson<ApiResponse<UserSystemInfo>> {
-    let config = deployment.config().read().await;
+    Extension(tenant): Extension<Tenant>,
+) -> Result<ResponseJson<ApiResponse<UserSystemInfo>>, ApiError> {
+    let config = tenant_config(&deployment, &tenant).await?;
     let login_status = deployment.get_login_status().await;
 
     let user_system_info = UserSystemInfo {
-        config: config.clone(),
+        config,
         analytics_user_id: deployment.user_id().to_string(),
         login_status,
         profiles: ExecutorConfigs::get_cached(),
@@ -105,24 +132,107 @@ async fn get_user_system_info(
             }
             caps
         },
+        test_mode: utils::test_mode::is_enabled(),
     };
 
-    ResponseJson(ApiResponse::success(user_system_info))
+    Ok(ResponseJson(ApiResponse::success(user_system_info)))
 }
 
+/// Save the settings of the tenant a request works in. Only the default tenant's settings are
+/// written to the config file and applied to the server as a whole.
 async fn update_config(
     State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
     Json(new_config): Json<Config>,
-) -> ResponseJson<ApiResponse<Config>> {
+) -> Result<ResponseJson<ApiResponse<Config>>, ApiError> {
     let config_path = config_path();
 
     // Validate git branch prefix
     if !utils::git::is_valid_branch_prefix(&new_config.git_branch_prefix) {
-        return ResponseJson(ApiResponse::error(
-            "Invalid git branch prefix. Must be a valid git branch name component without slashes.",
+        return Err(ApiError::BadRequest(
+            "Invalid git branch prefix. Must be a valid git branch name component without slashes."
+                .to_string(),
+        ));
+    }
+
+    if let Some((pattern, e)) = new_config
+        .redaction
+        .extra_patterns
+        .iter()
+        .find_map(|p| utils::redact::validate_pattern(p).err().map(|e| (p, e)))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid redaction pattern '{}': {}",
+            pattern, e
+        )));
+    }
+
+    if let Err(e) = new_config
+        .executor_policies
+        .iter()
+        .try_for_each(|policy| policy.validate())
+    {
+        return Err(ApiError::BadRequest(e.to_string()));
+    }
+
+    if let Err(e) = new_config
+        .executor_limits
+        .iter()
+        .try_for_each(|limit| limit.validate())
+    {
+        return Err(ApiError::BadRequest(e));
+    }
+
+    if new_config
+        .lint
+        .commands
+        .iter()
+        .any(|c| c.name.trim().is_empty() || c.command.trim().is_empty())
+    {
+        return Err(ApiError::BadRequest(
+            "Lint commands need both a name and a command.".to_string(),
         ));
     }
 
+    let routing = &new_config.routing;
+    if routing.rules.iter().any(|rule| rule.name.trim().is_empty()) {
+        return Err(ApiError::BadRequest(
+            "Routing rules need a name.".to_string(),
+        ));
+    }
+    if routing.profile_costs.iter().any(|c| c.cost_per_turn < 0.0)
+        || routing.attempt_budget.is_some_and(|budget| budget < 0.0)
+    {
+        return Err(ApiError::BadRequest(
+            "Routing costs and budgets cannot be negative.".to_string(),
+        ));
+    }
+
+    if new_config
+        .git_provider_hosts
+        .iter()
+        .any(|mapping| git_provider::normalize_host(&mapping.host).is_none())
+    {
+        return Err(ApiError::BadRequest(
+            "Git provider host mappings need a valid host name.".to_string(),
+        ));
+    }
+
+    if let Err(e) = new_config.log_shipping.validate() {
+        return Err(ApiError::BadRequest(e));
+    }
+
+    if let Err(e) = new_config.schedule.validate() {
+        return Err(ApiError::BadRequest(e));
+    }
+
+    if tenant.id != Tenant::DEFAULT_ID {
+        let raw = serde_json::to_string(&new_config)
+            .map_err(|e| ApiError::Config(ConfigError::Json(e)))?;
+        Tenant::save_config(&deployment.db().pool, tenant.id, &raw).await?;
+        return Ok(ResponseJson(ApiResponse::success(new_config)));
+    }
+
     // Get old config state before updating
     let old_config = deployment.config().read().await.clone();
 
@@ -135,9 +245,9 @@ async fn update_config(
             // Track config events when fields transition from false → true and run side effects
             handle_config_events(&deployment, &old_config, &new_config).await;
 
-            ResponseJson(ApiResponse::success(new_config))
+            Ok(ResponseJson(ApiResponse::success(new_config)))
         }
-        Err(e) => ResponseJson(ApiResponse::error(&format!("Failed to save config: {}", e))),
+        Err(e) => Err(ApiError::Config(e)),
     }
 }
 
@@ -173,9 +283,29 @@ async fn track_config_events(deployment: &DeploymentImpl, old: &Config, new: &Co
     }
 }
 
-async fn handle_config_events(deployment: &DeploymentImpl, old: &Config, new: &Config) {
+pub(crate) async fn handle_config_events(deployment: &DeploymentImpl, old: &Config, new: &Config) {
     track_config_events(deployment, old, new).await;
 
+    if old.redaction != new.redaction {
+        utils::redact::configure(&(&new.redaction).into());
+    }
+
+    if old.log_shipping != new.log_shipping {
+        utils::log_shipping::configure(&new.log_shipping);
+    }
+
+    if old.git_provider_hosts != new.git_provider_hosts {
+        git_provider::configure_host_mappings(new.git_provider_hosts.clone());
+    }
+
+    if old.git_provider_max_pages != new.git_provider_max_pages {
+        git_provider::configure_max_pages(new.git_provider_max_pages as usize);
+    }
+
+    if old.git_provider_retry != new.git_provider_retry {
+        git_provider::configure_retry_policies(new.git_provider_retry.clone());
+    }
+
     if !old.disclaimer_acknowledged && new.disclaimer_acknowledged {
         // Spawn auto project setup as background task to avoid blocking config response
         let deployment_clone = deployment.clone();
@@ -185,6 +315,83 @@ async fn handle_config_events(deployment: &DeploymentImpl, old: &Config, new: &C
     }
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertProviderHostRequest {
+    pub provider: ProviderType,
+}
+
+async fn list_provider_hosts(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<Vec<ProviderHostMapping>>> {
+    let mappings = deployment.config().read().await.git_provider_hosts.clone();
+    ResponseJson(ApiResponse::success(mappings))
+}
+
+/// Save a changed list of host mappings to the config
+async fn save_provider_hosts(
+    deployment: &DeploymentImpl,
+    update: impl FnOnce(&mut Vec<ProviderHostMapping>) -> Result<(), ApiError>,
+) -> Result<(), ApiError> {
+    let old_config = deployment.config().read().await.clone();
+    let mut new_config = old_config.clone();
+    update(&mut new_config.git_provider_hosts)?;
+
+    save_config_to_file(&new_config, &config_path()).await?;
+    *deployment.config().write().await = new_config.clone();
+    handle_config_events(deployment, &old_config, &new_config).await;
+    Ok(())
+}
+
+/// Map a host to a provider, replacing an existing mapping of the host
+async fn upsert_provider_host(
+    State(deployment): State<DeploymentImpl>,
+    Path(host): Path<String>,
+    Json(payload): Json<UpsertProviderHostRequest>,
+) -> Result<ResponseJson<ApiResponse<ProviderHostMapping>>, ApiError> {
+    let host = git_provider::normalize_host(&host)
+        .ok_or_else(|| ApiError::BadRequest(format!("Invalid host name: {host}")))?;
+    let mapping = ProviderHostMapping {
+        host,
+        provider: payload.provider,
+    };
+
+    let saved = mapping.clone();
+    save_provider_hosts(&deployment, move |mappings| {
+        mappings.retain(|existing| {
+            git_provider::normalize_host(&existing.host).as_deref() != Some(saved.host.as_str())
+        });
+        mappings.push(saved);
+        Ok(())
+    })
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "git_provider_host_mapped",
+            serde_json::json!({ "provider": mapping.provider }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(mapping)))
+}
+
+async fn delete_provider_host(
+    State(deployment): State<DeploymentImpl>,
+    Path(host): Path<String>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let host = git_provider::normalize_host(&host);
+    save_provider_hosts(&deployment, |mappings| {
+        let before = mappings.len();
+        mappings.retain(|existing| git_provider::normalize_host(&existing.host) != host);
+        if mappings.len() == before {
+            return Err(ApiError::Database(sqlx::Error::RowNotFound));
+        }
+        Ok(())
+    })
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 async fn get_sound(Path(sound): Path<SoundFile>) -> Result<Response, ApiError> {
     let sound = sound.serve().await.map_err(DeploymentError::Other)?;
     let response = Response::builder()
@@ -226,18 +433,18 @@ async fn get_mcp_servers(
         ))?;
 
     if !coding_agent.supports_mcp() {
-        return Ok(ResponseJson(ApiResponse::error(
-            "MCP not supported by this executor",
-        )));
+        return Err(ApiError::BadRequest(
+            "MCP not supported by this executor".to_string(),
+        ));
     }
 
     // Resolve supplied config path or agent default
     let config_path = match coding_agent.default_mcp_config_path() {
         Some(path) => path,
         None => {
-            return Ok(ResponseJson(ApiResponse::error(
-                "Could not determine config file path",
-            )));
+            return Err(ApiError::BadRequest(
+                "Could not determine config file path".to_string(),
+            ));
         }
     };
 
@@ -264,28 +471,28 @@ async fn update_mcp_servers(
         ))?;
 
     if !agent.supports_mcp() {
-        return Ok(ResponseJson(ApiResponse::error(
-            "This executor does not support MCP servers",
-        )));
+        return Err(ApiError::BadRequest(
+            "This executor does not support MCP servers".to_string(),
+        ));
     }
 
     // Resolve supplied config path or agent default
     let config_path = match agent.default_mcp_config_path() {
         Some(path) => path.to_path_buf(),
         None => {
-            return Ok(ResponseJson(ApiResponse::error(
-                "Could not determine config file path",
-            )));
+            return Err(ApiError::BadRequest(
+                "Could not determine config file path".to_string(),
+            ));
         }
     };
 
     let mcpc = agent.get_mcp_config();
     match update_mcp_servers_in_config(&config_path, &mcpc, payload.servers).await {
         Ok(message) => Ok(ResponseJson(ApiResponse::success(message))),
-        Err(e) => Ok(ResponseJson(ApiResponse::error(&format!(
+        Err(e) => Err(ApiError::BadRequest(format!(
             "Failed to update MCP servers: {}",
             e
-        )))),
+        ))),
     }
 }
 
@@ -408,7 +615,7 @@ async fn get_profiles(
 async fn update_profiles(
     State(_deployment): State<DeploymentImpl>,
     body: String,
-) -> ResponseJson<ApiResponse<String>> {
+) -> Result<ResponseJson<ApiResponse<String>>, ApiError> {
     // Try to parse as ExecutorProfileConfigs format
     match serde_json::from_str::<ExecutorConfigs>(&body) {
         Ok(executor_profiles) => {
@@ -418,20 +625,20 @@ async fn update_profiles(
                     tracing::info!("Executor profiles saved successfully");
                     // Reload the cached profiles
                     ExecutorConfigs::reload();
-                    ResponseJson(ApiResponse::success(
+                    Ok(ResponseJson(ApiResponse::success(
                         "Executor profiles updated successfully".to_string(),
-                    ))
+                    )))
                 }
                 Err(e) => {
                     tracing::error!("Failed to save executor profiles: {}", e);
-                    ResponseJson(ApiResponse::error(&format!(
+                    Err(ApiError::BadRequest(format!(
                         "Failed to save executor profiles: {}",
                         e
                     )))
                 }
             }
         }
-        Err(e) => ResponseJson(ApiResponse::error(&format!(
+        Err(e) => Err(ApiError::BadRequest(format!(
             "Invalid executor profiles format: {}",
             e
         ))),
@@ -1,5 +1,5 @@
 use axum::{
-    BoxError, Router,
+    BoxError, Extension, Router,
     extract::State,
     response::{
         Sse,
@@ -7,6 +7,7 @@ use axum::{
     },
     routing::get,
 };
+use db::models::tenant::Tenant;
 use deployment::Deployment;
 use futures_util::TryStreamExt;
 
@@ -14,10 +15,11 @@ use crate::DeploymentImpl;
 
 pub async fn events(
     State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
 ) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, BoxError>>>, axum::http::StatusCode>
 {
-    // Ask the container service for a combined "history + live" stream
-    let stream = deployment.stream_events().await;
+    // Ask the container service for a combined "history + live" stream of the tenant's records
+    let stream = deployment.stream_events(&tenant).await;
     Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
 }
 
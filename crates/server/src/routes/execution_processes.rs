@@ -1,6 +1,6 @@
 use anyhow;
 use axum::{
-    Extension, Router,
+    Extension, Json, Router,
     extract::{
         Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
@@ -10,13 +10,22 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    dependency_report::DependencyReport,
     execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus},
     execution_process_repo_state::ExecutionProcessRepoState,
+    execution_scope::ExecutionScope,
+    routing_decision::RoutingDecision,
+    workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
-use services::services::container::ContainerService;
+use services::services::{
+    container::ContainerService,
+    git::GitCli,
+    object_store::SignedUrl,
+    replay::{self, ReplayError, ReplayRepo, ReplayReport, ReplayRequest, ReplayStep},
+};
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
@@ -243,11 +252,152 @@ pub async fn get_execution_process_repo_states(
     Ok(ResponseJson(ApiResponse::success(repo_states)))
 }
 
+pub async fn get_execution_process_dependency_report(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<DependencyReport>>>, ApiError> {
+    let report =
+        DependencyReport::find_by_execution_process_id(&deployment.db().pool, execution_process.id)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub async fn get_execution_process_routing_decision(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<RoutingDecision>>>, ApiError> {
+    let decision =
+        RoutingDecision::find_by_execution_process_id(&deployment.db().pool, execution_process.id)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(decision)))
+}
+
+pub async fn get_execution_process_scope(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ExecutionScope>>>, ApiError> {
+    let scope =
+        ExecutionScope::find_by_execution_process_id(&deployment.db().pool, execution_process.id)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(scope)))
+}
+
+/// Signed URL to download the logs of a finished execution from object storage, null until they
+/// are stored
+pub async fn get_execution_process_logs_url(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<SignedUrl>>>, ApiError> {
+    let url = deployment
+        .artifacts()
+        .execution_logs_url(execution_process.id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(url)))
+}
+
+async fn recorded_replay_steps(
+    deployment: &DeploymentImpl,
+    execution_process: &ExecutionProcess,
+) -> Result<Vec<ReplayStep>, ApiError> {
+    if execution_process.status == ExecutionProcessStatus::Running {
+        return Err(ApiError::Conflict(
+            "Execution process is still running".to_string(),
+        ));
+    }
+    let stream = deployment
+        .container()
+        .stream_normalized_logs(&execution_process.id)
+        .await
+        .ok_or_else(|| {
+            ApiError::ExecutionProcess(ExecutionProcessError::ExecutionProcessNotFound)
+        })?;
+    let entries = replay::collect_entries(stream).await;
+    Ok(replay::steps_from_entries(&entries))
+}
+
+/// The file edits and commands an execution made, for use as executor test fixtures
+pub async fn get_execution_process_replay_steps(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ReplayStep>>>, ApiError> {
+    let steps = recorded_replay_steps(&deployment, &execution_process).await?;
+    Ok(ResponseJson(ApiResponse::success(steps)))
+}
+
+/// Re-apply an execution's recorded tool calls to fresh worktrees without calling the agent
+pub async fn replay_execution_process(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<ReplayRequest>,
+) -> Result<ResponseJson<ApiResponse<ReplayReport>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let (workspace, _) = execution_process
+        .parent_workspace_and_session(pool)
+        .await?
+        .ok_or_else(|| {
+            ApiError::ExecutionProcess(ExecutionProcessError::ExecutionProcessNotFound)
+        })?;
+    let Some(workspace_root) = workspace.container_ref.clone() else {
+        return Err(ApiError::Conflict(
+            "Workspace has no container to replay against".to_string(),
+        ));
+    };
+    let steps = recorded_replay_steps(&deployment, &execution_process).await?;
+
+    let repo_states =
+        ExecutionProcessRepoState::find_by_execution_process_id(pool, execution_process.id).await?;
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id)
+        .await?
+        .into_iter()
+        .filter_map(|repo| {
+            let state = repo_states.iter().find(|state| state.repo_id == repo.id)?;
+            Some(ReplayRepo {
+                name: repo.name,
+                repo_path: repo.path,
+                before_commit: state.before_head_commit.clone()?,
+                after_commit: state.after_head_commit.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+    if repos.is_empty() {
+        return Err(ApiError::Conflict(
+            "No starting commit was recorded for this execution".to_string(),
+        ));
+    }
+
+    let report = replay::replay(
+        &GitCli::new(),
+        execution_process.id,
+        std::path::Path::new(&workspace_root),
+        &repos,
+        steps,
+        &request,
+    )
+    .await
+    .map_err(|e| match e {
+        ReplayError::Io(e) => ApiError::Io(e),
+        e => ApiError::Conflict(format!("Replay failed: {e}")),
+    })?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let workspace_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
+        .route(
+            "/dependency-report",
+            get(get_execution_process_dependency_report),
+        )
+        .route(
+            "/routing-decision",
+            get(get_execution_process_routing_decision),
+        )
+        .route("/scope", get(get_execution_process_scope))
+        .route("/logs-url", get(get_execution_process_logs_url))
+        .route("/replay", post(replay_execution_process))
+        .route("/replay-steps", get(get_execution_process_replay_steps))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
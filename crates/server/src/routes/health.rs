@@ -1,6 +1,29 @@
 use axum::response::Json;
+use serde::Serialize;
+use services::services::{
+    circuit_breaker::{self, CircuitStatus},
+    compatibility::{self, CompatibilityReport},
+    git_provider::{RateLimitStatus, rate_limit_statuses},
+};
+use ts_rs::TS;
 use utils::response::ApiResponse;
 
-pub async fn health_check() -> Json<ApiResponse<String>> {
-    Json(ApiResponse::success("OK".to_string()))
+#[derive(Debug, Serialize, TS)]
+pub struct HealthStatus {
+    pub status: String,
+    /// Installed versions of gh, glab and the agent tools against the ones known to work
+    pub compatibility: CompatibilityReport,
+    /// Circuit breakers of the provider APIs and executors used so far
+    pub circuits: Vec<CircuitStatus>,
+    /// Latest rate-limit quota of each provider API used so far
+    pub rate_limits: Vec<RateLimitStatus>,
+}
+
+pub async fn health_check() -> Json<ApiResponse<HealthStatus>> {
+    Json(ApiResponse::success(HealthStatus {
+        status: "OK".to_string(),
+        compatibility: compatibility::report().await,
+        circuits: circuit_breaker::statuses(),
+        rate_limits: rate_limit_statuses(),
+    }))
 }
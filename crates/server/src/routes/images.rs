@@ -1,7 +1,7 @@
 use std::path::Path as StdPath;
 
 use axum::{
-    Router,
+    Extension, Router,
     body::Body,
     extract::{DefaultBodyLimit, Multipart, Path, Query, State},
     http::{StatusCode, header},
@@ -11,14 +11,12 @@ use axum::{
 use chrono::{DateTime, Utc};
 use db::models::{
     image::{Image, TaskImage},
-    task::Task,
+    tenant::Tenant,
 };
 use deployment::Deployment;
 use serde::{Deserialize, Serialize};
-use services::services::image::ImageError;
+use services::services::{image::ImageError, object_store::SignedUrl};
 use sqlx::Error as SqlxError;
-use tokio::fs::File;
-use tokio_util::io::ReaderStream;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -74,9 +72,10 @@ pub struct ImageMetadata {
 
 pub async fn upload_image(
     State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
     multipart: Multipart,
 ) -> Result<ResponseJson<ApiResponse<ImageResponse>>, ApiError> {
-    let image_response = process_image_upload(&deployment, multipart, None).await?;
+    let image_response = process_image_upload(&deployment, multipart, None, tenant.id).await?;
     Ok(ResponseJson(ApiResponse::success(image_response)))
 }
 
@@ -84,6 +83,7 @@ pub(crate) async fn process_image_upload(
     deployment: &DeploymentImpl,
     mut multipart: Multipart,
     link_task_id: Option<Uuid>,
+    tenant_id: Uuid,
 ) -> Result<ImageResponse, ApiError> {
     let image_service = deployment.image();
 
@@ -95,7 +95,9 @@ pub(crate) async fn process_image_upload(
                 .unwrap_or_else(|| "image.png".to_string());
 
             let data = field.bytes().await?;
-            let image = image_service.store_image(&data, &filename).await?;
+            let image = image_service
+                .store_image(&data, &filename, tenant_id)
+                .await?;
 
             if let Some(task_id) = link_task_id {
                 TaskImage::associate_many_dedup(
@@ -128,13 +130,15 @@ pub(crate) async fn process_image_upload(
 pub async fn upload_task_image(
     Path(task_id): Path<Uuid>,
     State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
     multipart: Multipart,
 ) -> Result<ResponseJson<ApiResponse<ImageResponse>>, ApiError> {
-    Task::find_by_id(&deployment.db().pool, task_id)
-        .await?
-        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    if !Tenant::owns_task(&deployment.db().pool, tenant.id, task_id).await? {
+        return Err(ApiError::Database(SqlxError::RowNotFound));
+    }
 
-    let image_response = process_image_upload(&deployment, multipart, Some(task_id)).await?;
+    let image_response =
+        process_image_upload(&deployment, multipart, Some(task_id), tenant.id).await?;
     Ok(ResponseJson(ApiResponse::success(image_response)))
 }
 
@@ -148,13 +152,8 @@ pub async fn serve_image(
         .get_image(image_id)
         .await?
         .ok_or_else(|| ApiError::Image(ImageError::NotFound))?;
-    let file_path = image_service.get_absolute_path(&image);
-
-    let file = File::open(&file_path).await?;
-    let metadata = file.metadata().await?;
-
-    let stream = ReaderStream::new(file);
-    let body = Body::from_stream(stream);
+    let data = image_service.read_image(&image).await?;
+    let content_length = data.len();
 
     let content_type = image
         .mime_type
@@ -164,14 +163,28 @@ pub async fn serve_image(
     let response = Response::builder()
         .status(StatusCode::OK)
         .header(header::CONTENT_TYPE, content_type)
-        .header(header::CONTENT_LENGTH, metadata.len())
+        .header(header::CONTENT_LENGTH, content_length)
         .header(header::CACHE_CONTROL, "public, max-age=31536000") // Cache for 1 year
-        .body(body)
+        .body(Body::from(data))
         .map_err(|e| ApiError::Image(ImageError::ResponseBuildError(e.to_string())))?;
 
     Ok(response)
 }
 
+/// Signed URL to download an image without authentication, e.g. from object storage directly
+pub async fn get_image_url(
+    Path(image_id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<SignedUrl>>, ApiError> {
+    let image_service = deployment.image();
+    let image = image_service
+        .get_image(image_id)
+        .await?
+        .ok_or_else(|| ApiError::Image(ImageError::NotFound))?;
+    let url = image_service.download_url(&image).await?;
+    Ok(ResponseJson(ApiResponse::success(url)))
+}
+
 pub async fn delete_image(
     Path(image_id): Path<Uuid>,
     State(deployment): State<DeploymentImpl>,
@@ -260,6 +273,7 @@ pub fn routes() -> Router<DeploymentImpl> {
             post(upload_image).layer(DefaultBodyLimit::max(20 * 1024 * 1024)), // 20MB limit
         )
         .route("/{id}/file", get(serve_image))
+        .route("/{id}/url", get(get_image_url))
         .route("/{id}", delete(delete_image))
         .route("/task/{task_id}", get(get_task_images))
         .route("/task/{task_id}/metadata", get(get_task_image_metadata))
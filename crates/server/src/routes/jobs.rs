@@ -0,0 +1,143 @@
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::job::{Job, JobFilter, JobStatus, JobStatusCount};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::jobs::CancelOutcome;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct JobListQuery {
+    pub status: Option<JobStatus>,
+    pub kind: Option<String>,
+    /// Only jobs created at least this many seconds ago
+    pub min_age_secs: Option<i64>,
+    /// Only jobs created at most this many seconds ago
+    pub max_age_secs: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+impl JobListQuery {
+    pub fn filter(&self, kinds: Vec<String>) -> JobFilter {
+        JobFilter {
+            status: self.status,
+            kinds,
+            min_age_secs: self.min_age_secs,
+            max_age_secs: self.max_age_secs,
+        }
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+}
+
+/// Recent jobs, e.g. `?status=dead` for the dead-letter queue or `?status=pending&min_age_secs=600`
+/// for jobs that have been waiting for ten minutes
+pub async fn list_jobs(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<JobListQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Job>>>, ApiError> {
+    let filter = query.filter(query.kind.iter().cloned().collect());
+    let jobs = Job::list(&deployment.db().pool, &filter, query.limit()).await?;
+    Ok(ResponseJson(ApiResponse::success(jobs)))
+}
+
+pub async fn get_job_stats(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<JobStatusCount>>>, ApiError> {
+    let counts = Job::count_by_status(&deployment.db().pool, &[]).await?;
+    Ok(ResponseJson(ApiResponse::success(counts)))
+}
+
+pub async fn get_job(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Job>>, ApiError> {
+    let job = Job::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    Ok(ResponseJson(ApiResponse::success(job)))
+}
+
+/// Move a dead job back into the queue with a fresh set of attempts
+pub async fn retry_job(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Job>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(job) = Job::retry(pool, id).await? else {
+        return match Job::find_by_id(pool, id).await? {
+            Some(_) => Err(ApiError::Conflict(
+                "Only dead jobs can be retried".to_string(),
+            )),
+            None => Err(ApiError::Database(sqlx::Error::RowNotFound)),
+        };
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "job_retried",
+            serde_json::json!({
+                "job_id": job.id.to_string(),
+                "kind": job.kind,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(job)))
+}
+
+/// Stop a pending or running job; it ends up in the dead-letter queue
+pub async fn cancel_job(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    match deployment.jobs().cancel(id).await? {
+        CancelOutcome::Cancelled | CancelOutcome::Aborted => {
+            Ok(ResponseJson(ApiResponse::success(())))
+        }
+        CancelOutcome::NotCancellable => match Job::find_by_id(&deployment.db().pool, id).await? {
+            Some(_) => Err(ApiError::Conflict(
+                "Only pending or running jobs can be cancelled".to_string(),
+            )),
+            None => Err(ApiError::Database(sqlx::Error::RowNotFound)),
+        },
+    }
+}
+
+pub async fn delete_job(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if Job::delete(pool, id).await? > 0 {
+        return Ok(ResponseJson(ApiResponse::success(())));
+    }
+    match Job::find_by_id(pool, id).await? {
+        Some(_) => Err(ApiError::Conflict(
+            "The job is running and cannot be deleted".to_string(),
+        )),
+        None => Err(ApiError::Database(sqlx::Error::RowNotFound)),
+    }
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/jobs", get(list_jobs))
+        .route("/jobs/stats", get(get_job_stats))
+        .route("/jobs/{id}", get(get_job).delete(delete_job))
+        .route("/jobs/{id}/retry", post(retry_job))
+        .route("/jobs/{id}/cancel", post(cancel_job))
+}
@@ -0,0 +1,104 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::library::{self, LibraryEntry};
+use ts_rs::TS;
+use utils::{api::library::LibraryItemKind, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct LibraryQuery {
+    pub organization_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PublishToLibraryRequest {
+    pub organization_id: Uuid,
+    pub kind: LibraryItemKind,
+    /// Id of the local template or automation rule
+    pub local_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PullFromLibraryRequest {
+    pub organization_id: Uuid,
+}
+
+pub async fn get_library(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<LibraryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<LibraryEntry>>>, ApiError> {
+    let client = deployment.remote_client()?;
+    let entries = library::list(&deployment.db().pool, &client, query.organization_id).await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+pub async fn publish_to_library(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PublishToLibraryRequest>,
+) -> Result<ResponseJson<ApiResponse<LibraryEntry>>, ApiError> {
+    let client = deployment.remote_client()?;
+    let entry = library::publish(
+        &deployment.db().pool,
+        &client,
+        payload.organization_id,
+        payload.kind,
+        payload.local_id,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "library_item_published",
+            serde_json::json!({
+                "kind": entry.item.kind,
+                "version": entry.item.version,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+pub async fn pull_from_library(
+    State(deployment): State<DeploymentImpl>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<PullFromLibraryRequest>,
+) -> Result<ResponseJson<ApiResponse<LibraryEntry>>, ApiError> {
+    let client = deployment.remote_client()?;
+    let entry = library::pull(
+        &deployment.db().pool,
+        &client,
+        payload.organization_id,
+        item_id,
+    )
+    .await?;
+    // A pulled rule may replace the condition of one the engine has cached
+    deployment.automation_rules().invalidate().await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "library_item_pulled",
+            serde_json::json!({
+                "kind": entry.item.kind,
+                "version": entry.item.version,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/library", get(get_library))
+        .route("/library/publish", post(publish_to_library))
+        .route("/library/{item_id}/pull", post(pull_from_library))
+}
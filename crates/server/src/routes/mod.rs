@@ -1,11 +1,23 @@
 use axum::{
     Router,
+    middleware::{from_fn, from_fn_with_state},
     routing::{IntoMakeService, get},
 };
 
-use crate::DeploymentImpl;
+use crate::{
+    DeploymentImpl,
+    middleware::{
+        login_sessions::authenticate_login_session, problem::problem_instance,
+        service_accounts::authenticate_service_account, tenants::resolve_tenant,
+    },
+};
 
+pub mod admin;
 pub mod approvals;
+pub mod automation_rules;
+pub mod automation_transitions;
+pub mod batch;
+pub mod column_slas;
 pub mod config;
 pub mod containers;
 pub mod filesystem;
@@ -15,38 +27,77 @@ pub mod execution_processes;
 pub mod frontend;
 pub mod health;
 pub mod images;
+pub mod jobs;
+pub mod library;
 pub mod oauth;
 pub mod organizations;
+pub mod project_metrics;
+pub mod project_mirrors;
+pub mod project_schedule;
 pub mod projects;
 pub mod repo;
+pub mod saved_views;
 pub mod scratch;
+pub mod service_accounts;
 pub mod sessions;
+pub mod setup;
+pub mod share_links;
 pub mod shared_tasks;
+pub mod storage;
 pub mod tags;
 pub mod task_attempts;
 pub mod tasks;
+pub mod templates;
+pub mod tenants;
+pub mod update;
+pub mod webhooks;
 
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
     let base_routes = Router::new()
         .route("/health", get(health::health_check))
         .merge(config::router())
+        .merge(setup::router())
         .merge(containers::router(&deployment))
         .merge(projects::router(&deployment))
         .merge(tasks::router(&deployment))
         .merge(shared_tasks::router())
+        .merge(share_links::router())
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
+        .merge(automation_rules::router(&deployment))
+        .merge(automation_transitions::router(&deployment))
+        .merge(saved_views::router(&deployment))
+        .merge(templates::router())
+        .merge(library::router())
         .merge(oauth::router())
         .merge(organizations::router())
         .merge(filesystem::router())
-        .merge(repo::router())
+        .merge(repo::router(&deployment))
         .merge(events::router(&deployment))
         .merge(approvals::router())
         .merge(scratch::router(&deployment))
         .merge(sessions::router(&deployment))
+        .merge(webhooks::router())
+        .merge(batch::router())
+        .merge(jobs::router())
+        .merge(admin::router())
+        .merge(service_accounts::router())
+        .merge(tenants::router())
+        .merge(storage::router())
+        .merge(update::router())
         .nest("/images", images::routes())
+        .layer(from_fn_with_state(deployment.clone(), resolve_tenant))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            authenticate_login_session,
+        ))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            authenticate_service_account,
+        ))
+        .layer(from_fn(problem_instance))
         .with_state(deployment);
 
     Router::new()
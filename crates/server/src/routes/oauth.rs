@@ -1,11 +1,12 @@
 use axum::{
     Router,
     extract::{Json, Query, State},
-    http::{Response, StatusCode},
-    response::Json as ResponseJson,
+    http::{HeaderMap, HeaderValue, Response, StatusCode, header},
+    response::{IntoResponse, Json as ResponseJson},
     routing::{get, post},
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use db::models::login_session::LoginSession;
 use deployment::Deployment;
 use rand::{Rng, distributions::Alphanumeric};
 use serde::{Deserialize, Serialize};
@@ -16,12 +17,21 @@ use ts_rs::TS;
 use utils::{
     api::oauth::{HandoffInitRequest, HandoffRedeemRequest, StatusResponse},
     assets::config_path,
-    jwt::extract_expiration,
+    jwt::{extract_expiration, extract_subject},
     response::ApiResponse,
 };
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{
+        login_sessions::{
+            SESSION_LIFETIME_DAYS, expired_session_cookie, session_cookie, session_token,
+        },
+        service_accounts::hash_key,
+    },
+};
 
 /// Response from GET /api/auth/token - returns the current access token
 #[derive(Debug, Serialize, TS)]
@@ -142,6 +152,8 @@ async fn handoff_complete(
 
     let expires_at = extract_expiration(&redeem.access_token)
         .map_err(|err| ApiError::BadRequest(format!("Invalid access token: {err}")))?;
+    let user_id = extract_subject(&redeem.access_token)
+        .map_err(|err| ApiError::BadRequest(format!("Invalid access token: {err}")))?;
     let credentials = Credentials {
         access_token: Some(redeem.access_token.clone()),
         refresh_token: redeem.refresh_token.clone(),
@@ -217,12 +229,37 @@ async fn handoff_complete(
         });
     }
 
-    Ok(close_window_response(format!(
+    // The browser that completed the handoff gets a session of its own, which is what tells
+    // its requests apart from those of other people using the same server
+    let pool = &deployment.db().pool;
+    LoginSession::delete_expired(pool).await?;
+    let token = generate_secret();
+    LoginSession::create(
+        pool,
+        user_id,
+        &hash_key(&token),
+        Duration::days(SESSION_LIFETIME_DAYS),
+    )
+    .await?;
+
+    let mut response = close_window_response(format!(
         "Signed in with {provider}. You can return to the app."
-    )))
+    ));
+    response.headers_mut().insert(
+        header::SET_COOKIE,
+        HeaderValue::from_str(&session_cookie(&token)).unwrap(),
+    );
+    Ok(response)
 }
 
-async fn logout(State(deployment): State<DeploymentImpl>) -> Result<StatusCode, ApiError> {
+async fn logout(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    if let Some(token) = session_token(&headers) {
+        LoginSession::delete_by_token_hash(&deployment.db().pool, &hash_key(token)).await?;
+    }
+
     let auth_context = deployment.auth_context();
 
     if let Ok(client) = deployment.remote_client() {
@@ -236,7 +273,10 @@ async fn logout(State(deployment): State<DeploymentImpl>) -> Result<StatusCode,
 
     auth_context.clear_profile().await;
 
-    Ok(StatusCode::NO_CONTENT)
+    Ok((
+        StatusCode::NO_CONTENT,
+        [(header::SET_COOKIE, expired_session_cookie())],
+    ))
 }
 
 async fn status(
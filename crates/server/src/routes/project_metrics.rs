@@ -0,0 +1,98 @@
+use axum::{
+    Extension,
+    body::Body,
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::{Json as ResponseJson, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use db::models::{board::BoardSnapshot, project::Project, task_event::TaskEvent};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::board_metrics::{self, BoardMetrics, MetricsReport};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BoardMetricsQuery {
+    /// Defaults to twelve weeks before `until`
+    pub since: Option<DateTime<Utc>>,
+    /// Defaults to now
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BoardMetricsExportQuery {
+    pub report: MetricsReport,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+async fn board_metrics(
+    deployment: &DeploymentImpl,
+    project: &Project,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Result<BoardMetrics, ApiError> {
+    let now = Utc::now();
+    let until = until.unwrap_or(now);
+    let since = since.unwrap_or(until - Duration::weeks(board_metrics::DEFAULT_WEEKS));
+    if since >= until {
+        return Err(ApiError::BadRequest(
+            "The period has to start before it ends".to_string(),
+        ));
+    }
+    if until - since > Duration::weeks(board_metrics::MAX_WEEKS) {
+        return Err(ApiError::BadRequest(format!(
+            "Reports cover at most {} weeks",
+            board_metrics::MAX_WEEKS
+        )));
+    }
+
+    let pool = &deployment.db().read_pool;
+    let board = BoardSnapshot::for_project(pool, project.id).await?;
+    let changes = TaskEvent::find_status_changes(pool, project.id).await?;
+    Ok(board_metrics::compute(&board, &changes, since, until, now))
+}
+
+/// Cycle time, weekly throughput and aging work in progress of the project's board
+pub async fn get_board_metrics(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<BoardMetricsQuery>,
+) -> Result<ResponseJson<ApiResponse<BoardMetrics>>, ApiError> {
+    let metrics = board_metrics(&deployment, &project, query.since, query.until).await?;
+    Ok(ResponseJson(ApiResponse::success(metrics)))
+}
+
+/// One report of [`get_board_metrics`] as a CSV download
+pub async fn export_board_metrics(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<BoardMetricsExportQuery>,
+) -> Result<Response, ApiError> {
+    let metrics = board_metrics(&deployment, &project, query.since, query.until).await?;
+    let csv = board_metrics::to_csv(&metrics, query.report);
+
+    deployment
+        .track_if_analytics_allowed(
+            "board_metrics_exported",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "report": query.report.file_name(),
+            }),
+        )
+        .await;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/csv; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", query.report.file_name()),
+        )
+        .body(Body::from(csv))
+        .map_err(|e| ApiError::Io(std::io::Error::other(e)))
+}
@@ -0,0 +1,166 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    project::Project,
+    project_mirror::{MirroredFields, ProjectMirror, TaskMirrorState},
+    task::Task,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::share::{MirrorSide, MirrorSyncReport, ShareError, SharePublisher};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::service_accounts::Actor};
+
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectMirrorStatus {
+    /// None when the project is not mirrored
+    pub mirror: Option<ProjectMirror>,
+    pub conflicts: Vec<MirrorConflict>,
+}
+
+/// A task changed differently here and on another instance since they last agreed
+#[derive(Debug, Serialize, TS)]
+pub struct MirrorConflict {
+    pub task_id: Uuid,
+    pub base: MirroredFields,
+    pub local: MirroredFields,
+    pub remote: MirroredFields,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ResolveMirrorConflictRequest {
+    pub task_id: Uuid,
+    pub keep: MirrorSide,
+}
+
+fn share_publisher(deployment: &DeploymentImpl) -> Result<SharePublisher, ApiError> {
+    deployment
+        .share_publisher()
+        .map_err(|_| ShareError::MissingConfig("share publisher unavailable").into())
+}
+
+pub async fn get_project_mirror(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectMirrorStatus>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mirror = ProjectMirror::find_by_project_id(pool, project.id).await?;
+
+    let mut conflicts = Vec::new();
+    for state in TaskMirrorState::find_conflicts_by_project_id(pool, project.id).await? {
+        let (Some(task), Some(remote)) = (
+            Task::find_by_id(pool, state.task_id).await?,
+            state.conflict.clone(),
+        ) else {
+            continue;
+        };
+        conflicts.push(MirrorConflict {
+            task_id: task.id,
+            base: state.base(),
+            local: MirroredFields::from(&task),
+            remote: remote.0,
+        });
+    }
+
+    Ok(ResponseJson(ApiResponse::success(ProjectMirrorStatus {
+        mirror,
+        conflicts,
+    })))
+}
+
+pub async fn enable_project_mirror(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectMirror>>, ApiError> {
+    if project.remote_project_id.is_none() {
+        return Err(ApiError::Conflict(
+            "Link this project to a remote project before mirroring it.".to_string(),
+        ));
+    }
+
+    let mirror = ProjectMirror::enable(&deployment.db().pool, project.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_mirror_enabled",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(mirror)))
+}
+
+pub async fn disable_project_mirror(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectMirror::disable(&deployment.db().pool, project.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub async fn sync_project_mirror(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<MirrorSyncReport>>, ApiError> {
+    let publisher = share_publisher(&deployment)?;
+    let profile = deployment
+        .auth_context()
+        .cached_profile()
+        .await
+        .ok_or(ShareError::MissingAuth)?;
+
+    let report = publisher
+        .sync_project(project.id, profile.user_id, deployment.task_events())
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_mirror_synced",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "published": report.published,
+                "created": report.created,
+                "pushed": report.pushed,
+                "pulled": report.pulled,
+                "conflicts": report.conflicts,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub async fn resolve_mirror_conflict(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
+    Json(payload): Json<ResolveMirrorConflictRequest>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let in_project = Task::find_by_id(&deployment.db().pool, payload.task_id)
+        .await?
+        .is_some_and(|task| task.project_id == project.id);
+    if !in_project {
+        return Err(ApiError::BadRequest(
+            "The task does not belong to this project".to_string(),
+        ));
+    }
+
+    let publisher = share_publisher(&deployment)?;
+    let task = publisher
+        .resolve_mirror_conflict(
+            payload.task_id,
+            payload.keep,
+            deployment.task_events(),
+            &actor,
+        )
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
@@ -0,0 +1,62 @@
+use axum::{
+    Extension,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Json as ResponseJson},
+};
+use db::models::{project::Project, task::Task};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    calendar,
+    schedule::{self, DueDigest, Schedule, ScheduleConfig},
+};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleQuery {
+    /// IANA timezone to use instead of the configured one, for teammates elsewhere
+    pub timezone: Option<String>,
+}
+
+async fn schedule(
+    deployment: &DeploymentImpl,
+    query: &ScheduleQuery,
+) -> Result<Schedule, ApiError> {
+    let config = deployment.config().read().await.schedule.clone();
+    let Some(timezone) = &query.timezone else {
+        return Ok(Schedule::new(&config));
+    };
+    schedule::parse_timezone(timezone).map_err(ApiError::BadRequest)?;
+    Ok(Schedule::new(&ScheduleConfig {
+        timezone: Some(timezone.clone()),
+        ..config
+    }))
+}
+
+pub async fn get_due_digest(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ScheduleQuery>,
+) -> Result<ResponseJson<ApiResponse<DueDigest>>, ApiError> {
+    let schedule = schedule(&deployment, &query).await?;
+    let tasks = Task::find_with_due_date(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        schedule.digest(&tasks, utils::test_mode::now()),
+    )))
+}
+
+pub async fn get_calendar_feed(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ScheduleQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let schedule = schedule(&deployment, &query).await?;
+    let tasks = Task::find_with_due_date(&deployment.db().pool, project.id).await?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        calendar::feed(&project.name, &tasks, &schedule),
+    ))
+}
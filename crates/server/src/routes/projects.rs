@@ -16,6 +16,7 @@ use db::models::{
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
     project_repo::{CreateProjectRepo, ProjectRepo, UpdateProjectRepo},
     repo::Repo,
+    tenant::Tenant,
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
@@ -26,12 +27,21 @@ use services::services::{
 };
 use ts_rs::TS;
 use utils::{
-    api::projects::{RemoteProject, RemoteProjectMembersResponse},
+    api::projects::{
+        EffectiveProjectPermissions, ListProjectPermissionOverridesResponse, RemoteProject,
+        RemoteProjectMembersResponse, SetProjectPermissionOverrideRequest,
+    },
     response::ApiResponse,
 };
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{load_project_middleware, tenants::require_tenant_access},
+    permissions,
+    routes::{column_slas, project_metrics, project_mirrors, project_schedule},
+};
 
 #[derive(Deserialize, TS)]
 pub struct LinkToExistingRequest {
@@ -46,26 +56,32 @@ pub struct CreateRemoteProjectRequest {
 
 pub async fn get_projects(
     State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Project>>>, ApiError> {
-    let projects = Project::find_all(&deployment.db().pool).await?;
+    let projects = Project::find_by_tenant_id(&deployment.db().pool, tenant.id).await?;
     Ok(ResponseJson(ApiResponse::success(projects)))
 }
 
 pub async fn stream_projects_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
 ) -> impl IntoResponse {
     ws.on_upgrade(move |socket| async move {
-        if let Err(e) = handle_projects_ws(socket, deployment).await {
+        if let Err(e) = handle_projects_ws(socket, deployment, tenant).await {
             tracing::warn!("projects WS closed: {}", e);
         }
     })
 }
 
-async fn handle_projects_ws(socket: WebSocket, deployment: DeploymentImpl) -> anyhow::Result<()> {
+async fn handle_projects_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    tenant: Tenant,
+) -> anyhow::Result<()> {
     let mut stream = deployment
         .events()
-        .stream_projects_raw()
+        .stream_projects_raw(tenant.id)
         .await?
         .map_ok(|msg| msg.to_ws_message_unchecked());
 
@@ -187,6 +203,48 @@ pub async fn get_project_remote_members(
     )))
 }
 
+/// What the signed-in user may do in the project, so the UI can hide the actions they would be
+/// refused
+pub async fn get_project_permissions(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<EffectiveProjectPermissions>>, ApiError> {
+    let permissions = permissions::effective_permissions(&deployment, &project).await?;
+    Ok(ResponseJson(ApiResponse::success(permissions)))
+}
+
+pub async fn get_project_permission_overrides(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<ListProjectPermissionOverridesResponse>>, ApiError> {
+    let remote_project_id = project.remote_project_id.ok_or_else(|| {
+        ApiError::Conflict("Project is not linked to a remote project".to_string())
+    })?;
+
+    let client = deployment.remote_client()?;
+    let overrides = client
+        .list_project_permission_overrides(remote_project_id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(overrides)))
+}
+
+/// Grant or revoke a permission for a role or member of the organization; admins only
+pub async fn set_project_permission_override(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Json(payload): Json<SetProjectPermissionOverrideRequest>,
+) -> Result<ResponseJson<ApiResponse<ListProjectPermissionOverridesResponse>>, ApiError> {
+    let remote_project_id = project.remote_project_id.ok_or_else(|| {
+        ApiError::Conflict("Project is not linked to a remote project".to_string())
+    })?;
+
+    let client = deployment.remote_client()?;
+    let overrides = client
+        .set_project_permission_override(remote_project_id, &payload)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(overrides)))
+}
+
 async fn apply_remote_project_link(
     deployment: &DeploymentImpl,
     project: Project,
@@ -217,6 +275,7 @@ async fn apply_remote_project_link(
 
 pub async fn create_project(
     State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
     Json(payload): Json<CreateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
     tracing::debug!("Creating project '{}'", payload.name);
@@ -224,7 +283,7 @@ pub async fn create_project(
 
     match deployment
         .project()
-        .create_project(&deployment.db().pool, deployment.repo(), payload)
+        .create_project(&deployment.db().pool, deployment.repo(), payload, tenant.id)
         .await
     {
         Ok(project) => {
@@ -242,21 +301,21 @@ pub async fn create_project(
 
             Ok(ResponseJson(ApiResponse::success(project)))
         }
-        Err(ProjectServiceError::DuplicateGitRepoPath) => Ok(ResponseJson(ApiResponse::error(
-            "Duplicate repository path provided",
-        ))),
-        Err(ProjectServiceError::DuplicateRepositoryName) => Ok(ResponseJson(ApiResponse::error(
-            "Duplicate repository name provided",
-        ))),
-        Err(ProjectServiceError::PathNotFound(_)) => Ok(ResponseJson(ApiResponse::error(
-            "The specified path does not exist",
-        ))),
-        Err(ProjectServiceError::PathNotDirectory(_)) => Ok(ResponseJson(ApiResponse::error(
-            "The specified path is not a directory",
-        ))),
-        Err(ProjectServiceError::NotGitRepository(_)) => Ok(ResponseJson(ApiResponse::error(
-            "The specified directory is not a git repository",
-        ))),
+        Err(ProjectServiceError::DuplicateGitRepoPath) => Err(ApiError::Conflict(
+            "Duplicate repository path provided".to_string(),
+        )),
+        Err(ProjectServiceError::DuplicateRepositoryName) => Err(ApiError::Conflict(
+            "Duplicate repository name provided".to_string(),
+        )),
+        Err(ProjectServiceError::PathNotFound(_)) => Err(ApiError::BadRequest(
+            "The specified path does not exist".to_string(),
+        )),
+        Err(ProjectServiceError::PathNotDirectory(_)) => Err(ApiError::BadRequest(
+            "The specified path is not a directory".to_string(),
+        )),
+        Err(ProjectServiceError::NotGitRepository(_)) => Err(ApiError::BadRequest(
+            "The specified directory is not a git repository".to_string(),
+        )),
         Err(e) => Err(ProjectError::CreateFailed(e.to_string()).into()),
     }
 }
@@ -386,9 +445,9 @@ pub async fn search_project_files(
     Query(search_query): Query<SearchQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<SearchResult>>>, StatusCode> {
     if search_query.q.trim().is_empty() {
-        return Ok(ResponseJson(ApiResponse::error(
-            "Query parameter 'q' is required and cannot be empty",
-        )));
+        return Err(ApiError::BadRequest(
+            "Query parameter 'q' is required and cannot be empty".to_string(),
+        ));
     }
 
     let repositories = match deployment
@@ -433,6 +492,7 @@ pub async fn get_project_repositories(
 
 pub async fn add_project_repository(
     Extension(project): Extension<Project>,
+    Extension(tenant): Extension<Tenant>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateProjectRepo>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
@@ -450,6 +510,7 @@ pub async fn add_project_repository(
             deployment.repo(),
             project.id,
             &payload,
+            tenant.id,
         )
         .await
     {
@@ -471,45 +532,45 @@ pub async fn add_project_repository(
                 "Failed to add repository to project {}: path does not exist",
                 project.id
             );
-            Ok(ResponseJson(ApiResponse::error(
-                "The specified path does not exist",
-            )))
+            Err(ApiError::BadRequest(
+                "The specified path does not exist".to_string(),
+            ))
         }
         Err(ProjectServiceError::PathNotDirectory(_)) => {
             tracing::warn!(
                 "Failed to add repository to project {}: path is not a directory",
                 project.id
             );
-            Ok(ResponseJson(ApiResponse::error(
-                "The specified path is not a directory",
-            )))
+            Err(ApiError::BadRequest(
+                "The specified path is not a directory".to_string(),
+            ))
         }
         Err(ProjectServiceError::NotGitRepository(_)) => {
             tracing::warn!(
                 "Failed to add repository to project {}: not a git repository",
                 project.id
             );
-            Ok(ResponseJson(ApiResponse::error(
-                "The specified directory is not a git repository",
-            )))
+            Err(ApiError::BadRequest(
+                "The specified directory is not a git repository".to_string(),
+            ))
         }
         Err(ProjectServiceError::DuplicateRepositoryName) => {
             tracing::warn!(
                 "Failed to add repository to project {}: duplicate repository name",
                 project.id
             );
-            Ok(ResponseJson(ApiResponse::error(
-                "A repository with this name already exists in the project",
-            )))
+            Err(ApiError::Conflict(
+                "A repository with this name already exists in the project".to_string(),
+            ))
         }
         Err(ProjectServiceError::DuplicateGitRepoPath) => {
             tracing::warn!(
                 "Failed to add repository to project {}: duplicate repository path",
                 project.id
             );
-            Ok(ResponseJson(ApiResponse::error(
-                "A repository with this path already exists in the project",
-            )))
+            Err(ApiError::Conflict(
+                "A repository with this path already exists in the project".to_string(),
+            ))
         }
         Err(e) => Err(e.into()),
     }
@@ -549,7 +610,7 @@ pub async fn delete_project_repository(
                 repo_id,
                 project_id
             );
-            Ok(ResponseJson(ApiResponse::error("Repository not found")))
+            Err(ApiError::BadRequest("Repository not found".to_string()))
         }
         Err(e) => Err(e.into()),
     }
@@ -589,6 +650,24 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             get(get_project).put(update_project).delete(delete_project),
         )
         .route("/remote/members", get(get_project_remote_members))
+        .route("/permissions", get(get_project_permissions))
+        .route(
+            "/permissions/overrides",
+            get(get_project_permission_overrides).patch(set_project_permission_override),
+        )
+        .route("/metrics", get(project_metrics::get_board_metrics))
+        .route(
+            "/metrics/export",
+            get(project_metrics::export_board_metrics),
+        )
+        .route(
+            "/slas",
+            get(column_slas::get_column_slas)
+                .put(column_slas::set_column_sla)
+                .delete(column_slas::delete_column_sla),
+        )
+        .route("/slas/breaches", get(column_slas::get_sla_breaches))
+        .route("/slas/escalations", get(column_slas::get_sla_escalations))
         .route("/search", get(search_project_files))
         .route("/open-editor", post(open_project_in_editor))
         .route(
@@ -596,6 +675,19 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             post(link_project_to_existing_remote).delete(unlink_project),
         )
         .route("/link/create", post(create_and_link_remote_project))
+        .route(
+            "/mirror",
+            get(project_mirrors::get_project_mirror)
+                .put(project_mirrors::enable_project_mirror)
+                .delete(project_mirrors::disable_project_mirror),
+        )
+        .route("/mirror/sync", post(project_mirrors::sync_project_mirror))
+        .route(
+            "/mirror/resolve",
+            post(project_mirrors::resolve_mirror_conflict),
+        )
+        .route("/due-digest", get(project_schedule::get_due_digest))
+        .route("/calendar.ics", get(project_schedule::get_calendar_feed))
         .route(
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
@@ -611,7 +703,11 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/{project_id}/repositories/{repo_id}",
             get(get_project_repository)
                 .put(update_project_repository)
-                .delete(delete_project_repository),
+                .delete(delete_project_repository)
+                .route_layer(from_fn_with_state(
+                    deployment.clone(),
+                    require_tenant_access,
+                )),
         )
         .route("/stream/ws", get(stream_projects_ws))
         .nest("/{id}", project_id_router);
@@ -1,18 +1,38 @@
 use axum::{
-    Router,
-    extract::{Path, State},
+    Extension, Router,
+    extract::{Path, Query, State},
+    middleware::from_fn_with_state,
     response::Json as ResponseJson,
-    routing::{get, post},
+    routing::{delete, get, post},
+};
+use db::models::{
+    command_quarantine::CommandQuarantine,
+    managed_repo::ManagedRepo,
+    merge::Merge,
+    repo::Repo,
+    repo_fetch_state::{RepoFetchState, UpdateRepoFetchSettings},
+    tenant::Tenant,
 };
-use db::models::repo::Repo;
 use deployment::Deployment;
-use serde::Deserialize;
-use services::services::git::GitBranch;
+use executors::profile::{ExecutorConfigs, ExecutorProfileId};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    flaky::{self, FlakyCommand},
+    git::{GitBranch, GitCli},
+    git_provider::{self, ProviderError, ProviderType, ReleaseInfo, ReviewRequest},
+    refine, release, repo_fetch,
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::tenants::require_tenant_access,
+    routes::task_attempts::mr::{PrActionError, pr_action_failed},
+};
 
 #[derive(Debug, Deserialize, TS)]
 #[ts(export)]
@@ -21,6 +41,25 @@ pub struct RegisterRepoRequest {
     pub display_name: Option<String>,
 }
 
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CloneRepoRequest {
+    /// HTTPS or SSH remote URL
+    pub url: String,
+    pub display_name: Option<String>,
+    /// Fetch the whole history instead of a shallow clone
+    #[serde(default)]
+    pub full_clone: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct ClonedRepo {
+    pub repo: Repo,
+    /// Hosting provider detected from the URL, if it is a known one
+    pub provider: Option<ProviderType>,
+}
+
 #[derive(Debug, Deserialize, TS)]
 #[ts(export)]
 pub struct InitRepoRequest {
@@ -28,8 +67,49 @@ pub struct InitRepoRequest {
     pub folder_name: String,
 }
 
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct QuarantineCommandRequest {
+    pub command_hash: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateReleaseRequest {
+    pub tag: String,
+    /// Defaults to the tag
+    pub name: Option<String>,
+    /// Notes written by hand; made from the PRs merged since the previous release when absent
+    pub notes: Option<String>,
+    /// Have a coding agent write the notes from the merged PRs instead of listing them
+    #[serde(default)]
+    pub generate_notes: bool,
+    /// Agent writing the notes; defaults to the configured executor profile
+    pub executor_profile_id: Option<ExecutorProfileId>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct ReviewRequestsQuery {
+    /// Reviewer's username on the providers; defaults to the configured GitHub username
+    pub username: Option<String>,
+}
+
+/// Open MRs/PRs of one repository waiting for the user's review
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct RepoReviewRequests {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub provider: ProviderType,
+    pub review_requests: Vec<ReviewRequest>,
+    /// Why the provider could not be asked, in which case there are no review requests
+    pub error: Option<String>,
+}
+
 pub async fn register_repo(
     State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
     ResponseJson(payload): ResponseJson<RegisterRepoRequest>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
     let repo = deployment
@@ -38,14 +118,51 @@ pub async fn register_repo(
             &deployment.db().pool,
             &payload.path,
             payload.display_name.as_deref(),
+            tenant.id,
         )
         .await?;
 
     Ok(ResponseJson(ApiResponse::success(repo)))
 }
 
+/// Register a repository by its remote URL, cloning it into the managed repositories directory
+pub async fn clone_repo(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+    ResponseJson(payload): ResponseJson<CloneRepoRequest>,
+) -> Result<ResponseJson<ApiResponse<ClonedRepo>>, ApiError> {
+    let (repo, provider) = deployment
+        .repo()
+        .register_from_url(
+            &deployment.db().pool,
+            &GitCli::new(),
+            &payload.url,
+            payload.display_name.as_deref(),
+            payload.full_clone,
+            tenant.id,
+        )
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "repo_cloned",
+            serde_json::json!({
+                "repo_id": repo.id.to_string(),
+                "provider": provider.map(|p| p.to_string()),
+                "full_clone": payload.full_clone,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(ClonedRepo {
+        repo,
+        provider,
+    })))
+}
+
 pub async fn init_repo(
     State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
     ResponseJson(payload): ResponseJson<InitRepoRequest>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
     let repo = deployment
@@ -55,6 +172,7 @@ pub async fn init_repo(
             deployment.git(),
             &payload.parent_path,
             &payload.folder_name,
+            tenant.id,
         )
         .await?;
 
@@ -74,9 +192,307 @@ pub async fn get_repo_branches(
     Ok(ResponseJson(ApiResponse::success(branches)))
 }
 
-pub fn router() -> Router<DeploymentImpl> {
+pub async fn get_managed_storage(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<ManagedRepo>>>, ApiError> {
+    let managed = ManagedRepo::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(managed)))
+}
+
+/// Move a repository from the user's checkout to a managed primary clone
+pub async fn adopt_managed_storage(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .adopt_into_managed_storage(&deployment.db().pool, &GitCli::new(), repo_id)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "repo_moved_to_managed_storage",
+            serde_json::json!({
+                "repo_id": repo.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(repo)))
+}
+
+/// Background fetch settings and last outcome of every repository of the tenant that has been
+/// fetched
+pub async fn get_fetch_states(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoFetchState>>>, ApiError> {
+    let states = RepoFetchState::find_by_tenant_id(&deployment.db().pool, tenant.id).await?;
+    Ok(ResponseJson(ApiResponse::success(states)))
+}
+
+/// MRs/PRs waiting for the user's review across the tenant's repositories. Repositories whose
+/// provider cannot list review requests are left out; the ones whose provider failed are listed
+/// with the error.
+pub async fn get_review_requests(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+    Query(query): Query<ReviewRequestsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoReviewRequests>>>, ApiError> {
+    let username = match query.username {
+        Some(username) => Some(username),
+        None => deployment.config().read().await.github.username.clone(),
+    };
+    let Some(username) = username.filter(|username| !username.trim().is_empty()) else {
+        return Err(ApiError::BadRequest(
+            "A username is needed to find review requests".to_string(),
+        ));
+    };
+    let username = username.trim();
+
+    let repos = Repo::find_by_tenant_id(&deployment.db().pool, tenant.id).await?;
+    let inbox = join_all(repos.into_iter().map(|repo| async move {
+        let (provider_type, repo_identifier) = git_provider::detect_provider(&repo.path).ok()?;
+        let result = match git_provider::create_provider(&repo.path) {
+            Ok(provider) => {
+                provider
+                    .list_mrs_for_reviewer(&repo_identifier, username)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        let (review_requests, error) = match result {
+            Ok(review_requests) => (review_requests, None),
+            Err(ProviderError::NotSupported { .. }) => return None,
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+        Some(RepoReviewRequests {
+            repo_id: repo.id,
+            repo_name: repo.display_name,
+            provider: provider_type,
+            review_requests,
+            error,
+        })
+    }))
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+    Ok(ResponseJson(ApiResponse::success(inbox)))
+}
+
+pub async fn get_fetch_state(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<RepoFetchState>>>, ApiError> {
+    let state = RepoFetchState::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(state)))
+}
+
+pub async fn update_fetch_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<UpdateRepoFetchSettings>,
+) -> Result<ResponseJson<ApiResponse<RepoFetchState>>, ApiError> {
+    if payload.interval_minutes.is_some_and(|minutes| minutes < 1) {
+        return Err(ApiError::BadRequest(
+            "The fetch interval must be at least one minute".to_string(),
+        ));
+    }
+    let pool = &deployment.db().pool;
+    deployment.repo().get_by_id(pool, repo_id).await?;
+    let state = RepoFetchState::update_settings(pool, repo_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(state)))
+}
+
+/// Fetch a repository now instead of waiting for the scheduler
+pub async fn fetch_repo(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RepoFetchState>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let repo = deployment.repo().get_by_id(pool, repo_id).await?;
+    let Some(state) =
+        repo_fetch::fetch_repo(pool, deployment.repo(), &GitCli::new(), &repo).await?
+    else {
+        return Err(ApiError::BadRequest(
+            "Repository has no remote to fetch from".to_string(),
+        ));
+    };
+    Ok(ResponseJson(ApiResponse::success(state)))
+}
+
+/// Commands that failed in this repository, most flaky first, with their quarantine state
+pub async fn get_flaky_commands(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<FlakyCommand>>>, ApiError> {
+    let commands = flaky::flaky_commands(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(commands)))
+}
+
+pub async fn quarantine_command(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<QuarantineCommandRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(command) = flaky::flaky_commands(pool, repo_id)
+        .await?
+        .into_iter()
+        .find(|command| command.command_hash == payload.command_hash)
+    else {
+        return Err(ApiError::BadRequest(
+            "Only commands that have failed in this repository can be quarantined".to_string(),
+        ));
+    };
+    CommandQuarantine::create(
+        pool,
+        repo_id,
+        &command.command_hash,
+        &command.command,
+        false,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "flaky_command_quarantined",
+            serde_json::json!({
+                "repo_id": repo_id.to_string(),
+                "kind": command.kind,
+                "automatic": false,
+            }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn release_command(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, command_hash)): Path<(Uuid, String)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected =
+        CommandQuarantine::delete(&deployment.db().pool, repo_id, &command_hash).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Publish a release of the repository on its provider. Unless given, the notes cover the PRs
+/// merged since the newest tag, listed or written up by the agent.
+pub async fn create_release(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<CreateReleaseRequest>,
+) -> Result<ResponseJson<ApiResponse<ReleaseInfo, PrActionError>>, ApiError> {
+    let tag = payload.tag.trim();
+    if tag.is_empty() {
+        return Err(ApiError::BadRequest("A release needs a tag".to_string()));
+    }
+    let pool = &deployment.db().pool;
+    let repo = deployment.repo().get_by_id(pool, repo_id).await?;
+    let (provider, repo_id_on_provider) = match (
+        git_provider::create_provider(&repo.path),
+        git_provider::detect_provider(&repo.path),
+    ) {
+        (Ok(provider), Ok((_, repo_identifier))) => (provider, repo_identifier),
+        (Err(e), _) | (_, Err(e)) => return pr_action_failed(e),
+    };
+
+    let notes = match payload.notes {
+        Some(notes) => notes,
+        None => {
+            let since = release::latest_tag_date(&GitCli::new(), &repo.path);
+            let merged = Merge::find_merged_prs_by_repo_id(pool, repo.id, since).await?;
+            if payload.generate_notes {
+                let executor_profile_id = match payload.executor_profile_id {
+                    Some(profile) => profile,
+                    None => deployment.config().read().await.executor_profile.clone(),
+                };
+                let agent = ExecutorConfigs::get_cached()
+                    .get_coding_agent(&executor_profile_id)
+                    .and_then(|agent| agent.into_read_only())
+                    .ok_or_else(|| {
+                        ApiError::BadRequest(format!(
+                            "{} cannot write release notes without being able to edit files",
+                            executor_profile_id.executor
+                        ))
+                    })?;
+                let prompt = release::notes_prompt(tag, &merged);
+                refine::run_agent_once(agent, &repo.path, &prompt)
+                    .await?
+                    .trim()
+                    .to_string()
+            } else {
+                release::list_notes(&merged)
+            }
+        }
+    };
+    let name = payload
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(tag);
+
+    let release = match provider
+        .create_release(&repo_id_on_provider, tag, name, &notes)
+        .await
+    {
+        Ok(release) => release,
+        Err(e) => return pr_action_failed(e),
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "release_created",
+            serde_json::json!({
+                "repo_id": repo.id.to_string(),
+                "provider": provider.provider_type(),
+                "generated_notes": payload.generate_notes,
+            }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(release)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let repo_id_router = Router::new()
+        .route("/repos/{repo_id}/branches", get(get_repo_branches))
+        .route(
+            "/repos/{repo_id}/managed-storage",
+            get(get_managed_storage).post(adopt_managed_storage),
+        )
+        .route(
+            "/repos/{repo_id}/fetch",
+            get(get_fetch_state)
+                .put(update_fetch_settings)
+                .post(fetch_repo),
+        )
+        .route("/repos/{repo_id}/releases", post(create_release))
+        .route("/repos/{repo_id}/flaky-commands", get(get_flaky_commands))
+        .route(
+            "/repos/{repo_id}/flaky-commands/quarantine",
+            post(quarantine_command),
+        )
+        .route(
+            "/repos/{repo_id}/flaky-commands/quarantine/{command_hash}",
+            delete(release_command),
+        )
+        .route_layer(from_fn_with_state(
+            deployment.clone(),
+            require_tenant_access,
+        ));
+
     Router::new()
         .route("/repos", post(register_repo))
         .route("/repos/init", post(init_repo))
-        .route("/repos/{repo_id}/branches", get(get_repo_branches))
+        .route("/repos/clone", post(clone_repo))
+        .route("/repos/fetch-status", get(get_fetch_states))
+        .route("/repos/review-requests", get(get_review_requests))
+        .merge(repo_id_router)
 }
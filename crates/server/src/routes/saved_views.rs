@@ -0,0 +1,169 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::{
+    board::BoardSnapshot,
+    saved_view::{CreateSavedView, SavedView, UpdateSavedView},
+    tenant::Tenant,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::saved_views::{self, ViewBoard};
+use utils::{api::oauth::LoginStatus, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{load_saved_view_middleware, tenants::ensure_project_in_tenant},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct SavedViewQuery {
+    pub project_id: Uuid,
+}
+
+/// Signed-in user the views belong to; views saved while signed out have no owner
+async fn current_user_id(deployment: &DeploymentImpl) -> Option<Uuid> {
+    match deployment.get_login_status().await {
+        LoginStatus::LoggedIn { profile } => Some(profile.user_id),
+        LoginStatus::LoggedOut => None,
+    }
+}
+
+fn check_filter(filter: &str) -> Result<(), ApiError> {
+    saved_views::validate_filter(filter)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid view filter: {e}")))
+}
+
+/// Only the owner changes a view, shared or not
+async fn ensure_owner(deployment: &DeploymentImpl, view: &SavedView) -> Result<(), ApiError> {
+    if view.owner_user_id == current_user_id(deployment).await {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Only the owner of a view can change it".to_string(),
+        ))
+    }
+}
+
+pub async fn get_saved_views(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+    Query(query): Query<SavedViewQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SavedView>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    ensure_project_in_tenant(pool, &tenant, query.project_id).await?;
+    let user_id = current_user_id(&deployment).await;
+    let views = SavedView::find_visible(pool, query.project_id, user_id).await?;
+    Ok(ResponseJson(ApiResponse::success(views)))
+}
+
+pub async fn create_saved_view(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+    Json(payload): Json<CreateSavedView>,
+) -> Result<ResponseJson<ApiResponse<SavedView>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest("View name is required".to_string()));
+    }
+    if let Some(filter) = &payload.filter {
+        check_filter(filter)?;
+    }
+    ensure_project_in_tenant(pool, &tenant, payload.project_id).await?;
+
+    let owner_user_id = current_user_id(&deployment).await;
+    let view = SavedView::create(pool, &payload, owner_user_id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "saved_view_created",
+            serde_json::json!({
+                "view_id": view.id.to_string(),
+                "project_id": view.project_id.to_string(),
+                "has_filter": !view.filter.is_empty(),
+                "grouping": view.grouping,
+                "shared": view.shared,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(view)))
+}
+
+pub async fn get_saved_view(
+    Extension(view): Extension<SavedView>,
+) -> Result<ResponseJson<ApiResponse<SavedView>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(view)))
+}
+
+pub async fn update_saved_view(
+    Extension(view): Extension<SavedView>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateSavedView>,
+) -> Result<ResponseJson<ApiResponse<SavedView>>, ApiError> {
+    if payload
+        .name
+        .as_ref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        return Err(ApiError::BadRequest("View name is required".to_string()));
+    }
+    if let Some(filter) = &payload.filter {
+        check_filter(filter)?;
+    }
+    ensure_owner(&deployment, &view).await?;
+
+    let updated = SavedView::update(&deployment.db().pool, view.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_saved_view(
+    Extension(view): Extension<SavedView>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_owner(&deployment, &view).await?;
+    let rows_affected = SavedView::delete(&deployment.db().pool, view.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// The board of the view's project, filtered, sorted and grouped as the view says
+pub async fn get_saved_view_board(
+    Extension(view): Extension<SavedView>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ViewBoard>>, ApiError> {
+    let board = BoardSnapshot::for_project(&deployment.db().read_pool, view.project_id).await?;
+    let board = saved_views::apply(&view, board)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid view filter: {e}")))?;
+    Ok(ResponseJson(ApiResponse::success(board)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let view_router = Router::new()
+        .route(
+            "/",
+            get(get_saved_view)
+                .put(update_saved_view)
+                .delete(delete_saved_view),
+        )
+        .route("/board", get(get_saved_view_board))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_saved_view_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_saved_views).post(create_saved_view))
+        .nest("/{view_id}", view_router);
+
+    Router::new().nest("/saved-views", inner)
+}
@@ -0,0 +1,210 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, patch, post},
+};
+use db::models::{
+    service_account::{CreateServiceAccount, ServiceAccount, UpdateServiceAccount},
+    tenant::Tenant,
+};
+use deployment::Deployment;
+use rand::{Rng, distributions::Alphanumeric};
+use serde::Serialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::service_accounts::{KEY_PREFIX, hash_key},
+    validation::{Validate, Validator},
+};
+
+/// Length of the random part of a key
+const KEY_LENGTH: usize = 40;
+
+/// Characters of a key kept to recognize it later, including the prefix
+const SHOWN_KEY_LENGTH: usize = 12;
+
+/// A newly issued key. It is only returned here; afterwards only its prefix is known.
+#[derive(Debug, Serialize, TS)]
+pub struct IssuedServiceAccountKey {
+    pub account: ServiceAccount,
+    pub key: String,
+}
+
+impl Validate for CreateServiceAccount {
+    fn validate(&self, v: &mut Validator) {
+        v.title("name", &self.name);
+        v.body("description", self.description.as_deref());
+        v.not_empty("scopes", &self.scopes);
+    }
+}
+
+impl Validate for UpdateServiceAccount {
+    fn validate(&self, v: &mut Validator) {
+        v.body("description", self.description.as_deref());
+        if let Some(scopes) = &self.scopes {
+            v.not_empty("scopes", scopes);
+        }
+    }
+}
+
+fn generate_key() -> String {
+    let random: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(KEY_LENGTH)
+        .map(char::from)
+        .collect();
+    format!("{KEY_PREFIX}{random}")
+}
+
+/// Service accounts of another tenant are reported as missing
+async fn ensure_account_in_tenant(
+    deployment: &DeploymentImpl,
+    tenant: &Tenant,
+    id: Uuid,
+) -> Result<(), ApiError> {
+    match ServiceAccount::find_by_id(&deployment.db().pool, id).await? {
+        Some(account) if account.tenant_id == tenant.id => Ok(()),
+        _ => Err(ApiError::Database(sqlx::Error::RowNotFound)),
+    }
+}
+
+pub async fn list_service_accounts(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+) -> Result<ResponseJson<ApiResponse<Vec<ServiceAccount>>>, ApiError> {
+    let accounts = ServiceAccount::find_by_tenant_id(&deployment.db().pool, tenant.id).await?;
+    Ok(ResponseJson(ApiResponse::success(accounts)))
+}
+
+/// Create an account without a key; issue one with `POST /service-accounts/{id}/key`
+pub async fn create_service_account(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+    Json(payload): Json<CreateServiceAccount>,
+) -> Result<ResponseJson<ApiResponse<ServiceAccount>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Validator::check(&payload).finish()?;
+    let name = payload.name.trim();
+    if ServiceAccount::find_by_tenant_id(pool, tenant.id)
+        .await?
+        .iter()
+        .any(|account| account.name == name)
+    {
+        return Err(ApiError::Conflict(format!(
+            "A service account named '{name}' already exists"
+        )));
+    }
+
+    let account = ServiceAccount::create(
+        pool,
+        &CreateServiceAccount {
+            name: name.to_string(),
+            description: payload.description,
+            scopes: payload.scopes,
+        },
+        tenant.id,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "service_account_created",
+            serde_json::json!({
+                "scopes": account.scopes.0,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(account)))
+}
+
+pub async fn update_service_account(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Extension(tenant): Extension<Tenant>,
+    Json(payload): Json<UpdateServiceAccount>,
+) -> Result<ResponseJson<ApiResponse<ServiceAccount>>, ApiError> {
+    ensure_account_in_tenant(&deployment, &tenant, id).await?;
+    Validator::check(&payload).finish()?;
+    let account = ServiceAccount::update(&deployment.db().pool, id, &payload)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(account)))
+}
+
+pub async fn delete_service_account(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Extension(tenant): Extension<Tenant>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_account_in_tenant(&deployment, &tenant, id).await?;
+    if ServiceAccount::delete(&deployment.db().pool, id).await? == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Issue a new key for the account. The previous key, if any, stops working right away, so keys
+/// of different integrations are rotated independently.
+pub async fn issue_service_account_key(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Extension(tenant): Extension<Tenant>,
+) -> Result<ResponseJson<ApiResponse<IssuedServiceAccountKey>>, ApiError> {
+    ensure_account_in_tenant(&deployment, &tenant, id).await?;
+    let key = generate_key();
+    let account = ServiceAccount::set_key(
+        &deployment.db().pool,
+        id,
+        &hash_key(&key),
+        &key[..SHOWN_KEY_LENGTH],
+    )
+    .await?
+    .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "service_account_key_issued",
+            serde_json::json!({
+                "service_account_id": account.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        IssuedServiceAccountKey { account, key },
+    )))
+}
+
+pub async fn revoke_service_account_key(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Extension(tenant): Extension<Tenant>,
+) -> Result<ResponseJson<ApiResponse<ServiceAccount>>, ApiError> {
+    ensure_account_in_tenant(&deployment, &tenant, id).await?;
+    let account = ServiceAccount::revoke_key(&deployment.db().pool, id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(account)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/service-accounts",
+            get(list_service_accounts).post(create_service_account),
+        )
+        .route(
+            "/service-accounts/{id}",
+            patch(update_service_account).delete(delete_service_account),
+        )
+        .route(
+            "/service-accounts/{id}/key",
+            post(issue_service_account_key).delete(revoke_service_account_key),
+        )
+}
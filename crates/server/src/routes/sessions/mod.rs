@@ -8,28 +8,34 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    context_compaction::ContextCompaction,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    execution_scope::{ExecutionScope, ScopedFile},
     project_repo::ProjectRepo,
-    scratch::{Scratch, ScratchType},
+    scratch::{DraftFollowUpData, Scratch, ScratchType},
     session::{CreateSession, Session},
     workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
     },
-    profile::ExecutorProfileId,
+    profile::{ExecutorConfigs, ExecutorProfileId},
 };
 use serde::Deserialize;
-use services::services::container::ContainerService;
+use services::services::{container::ContainerService, object_store::SignedUrl, scope};
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{
+    api::projects::ProjectPermission,
+    response::{ApiResponse, Keyset, PageQuery},
+};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_session_middleware,
+    DeploymentImpl, error::ApiError, middleware::load_session_middleware, permissions,
     routes::task_attempts::util::restore_worktrees_to_process,
 };
 
@@ -47,10 +53,18 @@ pub struct CreateSessionRequest {
 pub async fn get_sessions(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<SessionQuery>,
+    Query(page): Query<PageQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Session>>>, ApiError> {
     let pool = &deployment.db().pool;
-    let sessions = Session::find_by_workspace_id(pool, query.workspace_id).await?;
-    Ok(ResponseJson(ApiResponse::success(sessions)))
+    let keyset = page.keyset()?;
+    let sessions = Session::find_page_by_workspace_id(pool, query.workspace_id, keyset).await?;
+    let total = Session::count_by_workspace_id(pool, query.workspace_id).await?;
+    let (sessions, pagination) = page.keyset_page(sessions, total as u64, |session| {
+        Keyset::new(session.created_at, session.id)
+    });
+    Ok(ResponseJson(ApiResponse::success_page(
+        sessions, pagination,
+    )))
 }
 
 pub async fn get_session(
@@ -59,6 +73,26 @@ pub async fn get_session(
     Ok(ResponseJson(ApiResponse::success(session)))
 }
 
+/// Context blocks carried into this session's turns, newest first
+pub async fn get_context_compactions(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ContextCompaction>>>, ApiError> {
+    let compactions =
+        ContextCompaction::find_by_session_id(&deployment.db().pool, session.id).await?;
+    Ok(ResponseJson(ApiResponse::success(compactions)))
+}
+
+/// Export the session as a Markdown transcript in object storage and return a signed URL to
+/// download it
+pub async fn export_transcript(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<SignedUrl>>, ApiError> {
+    let url = deployment.artifacts().export_transcript(&session).await?;
+    Ok(ResponseJson(ApiResponse::success(url)))
+}
+
 pub async fn create_session(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateSessionRequest>,
@@ -135,6 +169,8 @@ pub async fn follow_up(
         .parent_project(pool)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
+    permissions::require_permission(&deployment, project.id, ProjectPermission::StartExecution)
+        .await?;
 
     // If retry settings provided, perform replace-logic before proceeding
     if let Some(proc_id) = payload.retry_process_id {
@@ -171,12 +207,157 @@ pub async fn follow_up(
         let _ = ExecutionProcess::drop_at_and_after(pool, process.session_id, proc_id).await?;
     }
 
+    let execution_process = start_follow_up(
+        &deployment,
+        &session,
+        &workspace,
+        project.id,
+        payload.prompt,
+        executor_profile_id,
+    )
+    .await?;
+
+    // Clear the draft follow-up scratch on successful spawn
+    // This ensures the scratch is wiped even if the user navigates away quickly
+    if let Err(e) = Scratch::delete(pool, session.id, &ScratchType::DraftFollowUp).await {
+        // Log but don't fail the request - scratch deletion is best-effort
+        tracing::debug!(
+            "Failed to delete draft follow-up scratch for session {}: {}",
+            session.id,
+            e
+        );
+    }
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateScopedFollowUp {
+    pub prompt: String,
+    pub variant: Option<String>,
+    /// Files and hunks selected in the diff view
+    pub files: Vec<ScopedFile>,
+    /// Fail the run if it changes anything outside the selected files
+    #[serde(default)]
+    pub restrict_writes: bool,
+}
+
+/// Start a follow-up limited to files or hunks selected in the diff view, for surgical fixes
+/// after review
+pub async fn scoped_follow_up(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateScopedFollowUp>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if payload.files.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Select at least one file or hunk".to_string(),
+        ));
+    }
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    for file in &payload.files {
+        if !repos.iter().any(|repo| repo.name == file.repo) {
+            return Err(ApiError::BadRequest(format!(
+                "Repository '{}' is not part of this workspace",
+                file.repo
+            )));
+        }
+        let path = std::path::Path::new(&file.path);
+        if file.path.is_empty()
+            || path.is_absolute()
+            || path
+                .components()
+                .any(|c| matches!(c, std::path::Component::ParentDir))
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid path '{}'",
+                file.path
+            )));
+        }
+    }
+    permissions::require_workspace_permission(
+        &deployment,
+        &workspace,
+        ProjectPermission::StartExecution,
+    )
+    .await?;
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let initial_executor_profile_id =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?;
+    let executor_profile_id = ExecutorProfileId {
+        executor: initial_executor_profile_id.executor,
+        variant: payload.variant,
+    };
+
+    let project = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?
+        .parent_project(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let prompt = scope::scoped_prompt(&payload.prompt, &payload.files, payload.restrict_writes);
+    let execution_process = start_follow_up(
+        &deployment,
+        &session,
+        &workspace,
+        project.id,
+        prompt,
+        executor_profile_id,
+    )
+    .await?;
+    ExecutionScope::create(
+        pool,
+        execution_process.id,
+        &payload.files,
+        payload.restrict_writes,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "scoped_follow_up_started",
+            serde_json::json!({
+                "session_id": session.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "file_count": payload.files.len(),
+                "restrict_writes": payload.restrict_writes,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+/// Start a coding agent turn that continues the session's agent session when there is one
+pub(crate) async fn start_follow_up(
+    deployment: &DeploymentImpl,
+    session: &Session,
+    workspace: &Workspace,
+    project_id: Uuid,
+    prompt: String,
+    executor_profile_id: ExecutorProfileId,
+) -> Result<ExecutionProcess, ApiError> {
+    let pool = &deployment.db().pool;
     let latest_agent_session_id =
         ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id).await?;
 
-    let prompt = payload.prompt;
-
-    let project_repos = ProjectRepo::find_by_project_id_with_names(pool, project.id).await?;
+    let project_repos = ProjectRepo::find_by_project_id_with_names(pool, project_id).await?;
     let cleanup_action = deployment
         .container()
         .cleanup_actions_for_repos(&project_repos);
@@ -189,16 +370,17 @@ pub async fn follow_up(
 
     let action_type = if let Some(agent_session_id) = latest_agent_session_id {
         ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
-            prompt: prompt.clone(),
+            prompt,
             session_id: agent_session_id,
-            executor_profile_id: executor_profile_id.clone(),
-            working_dir: working_dir.clone(),
+            executor_profile_id,
+            working_dir,
+            read_only: false,
         })
     } else {
         ExecutorActionType::CodingAgentInitialRequest(
             executors::actions::coding_agent_initial::CodingAgentInitialRequest {
                 prompt,
-                executor_profile_id: executor_profile_id.clone(),
+                executor_profile_id,
                 working_dir,
             },
         )
@@ -206,6 +388,182 @@ pub async fn follow_up(
 
     let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
 
+    let execution_process = deployment
+        .container()
+        .start_execution(
+            workspace,
+            session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+
+    Ok(execution_process)
+}
+
+/// How a follow-up sent on the user's behalf was handled
+pub(crate) enum FollowUpStart {
+    Started(ExecutionProcess),
+    /// The workspace is busy, so the prompt waits in the session's queue
+    Queued,
+}
+
+/// Start a follow-up in the latest session of the workspace, or queue it behind the running turn
+pub(crate) async fn start_or_queue_follow_up(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    prompt: String,
+) -> Result<FollowUpStart, ApiError> {
+    let pool = &deployment.db().pool;
+    let session = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+        Some(session) => session,
+        None => {
+            Session::create(
+                pool,
+                &CreateSession { executor: None },
+                Uuid::new_v4(),
+                workspace.id,
+            )
+            .await?
+        }
+    };
+
+    let busy =
+        ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+            .await?;
+    if busy {
+        // One message is queued per session, so add to what is already waiting
+        let queue = deployment.queued_message_service();
+        let data = match queue.get_queued(session.id) {
+            Some(queued) => DraftFollowUpData {
+                message: format!("{}\n\n{}", queued.data.message, prompt),
+                variant: queued.data.variant,
+            },
+            None => DraftFollowUpData {
+                message: prompt,
+                variant: None,
+            },
+        };
+        queue.queue_message(session.id, data);
+        return Ok(FollowUpStart::Queued);
+    }
+
+    deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    let executor_profile_id =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let execution_process = start_follow_up(
+        deployment,
+        &session,
+        workspace,
+        task.project_id,
+        prompt,
+        executor_profile_id,
+    )
+    .await?;
+    Ok(FollowUpStart::Started(execution_process))
+}
+
+/// Instructions prepended to questions asked outside a coding turn
+const ASK_PREAMBLE: &str = "The user has a question about the work in this session. Answer it \
+using the conversation so far and by reading files if needed. Do not modify any files, run \
+commands that change state, or start new work.";
+
+#[derive(Debug, Deserialize, TS)]
+pub struct AskAgentRequest {
+    pub question: String,
+    pub variant: Option<String>,
+}
+
+/// Ask the agent a question about this session without starting a coding turn. The agent runs
+/// with a read-only tool policy and the exchange is recorded in the session transcript like any
+/// other turn, but the task status is left untouched.
+pub async fn ask_agent(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<AskAgentRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if payload.question.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Question must not be empty".to_string(),
+        ));
+    }
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "The agent is busy. Ask again once the current run has finished.".to_string(),
+        ));
+    }
+    permissions::require_workspace_permission(
+        &deployment,
+        &workspace,
+        ProjectPermission::StartExecution,
+    )
+    .await?;
+
+    let Some(agent_session_id) =
+        ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id).await?
+    else {
+        return Err(ApiError::BadRequest(
+            "The agent has not worked on this session yet".to_string(),
+        ));
+    };
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let initial_executor_profile_id =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?;
+    let executor_profile_id = ExecutorProfileId {
+        executor: initial_executor_profile_id.executor,
+        variant: payload.variant,
+    };
+    let supports_read_only = ExecutorConfigs::get_cached()
+        .get_coding_agent(&executor_profile_id)
+        .and_then(|agent| agent.into_read_only())
+        .is_some();
+    if !supports_read_only {
+        return Err(ApiError::BadRequest(format!(
+            "{} cannot answer questions without being able to edit files",
+            executor_profile_id.executor
+        )));
+    }
+
+    let working_dir = workspace
+        .agent_working_dir
+        .as_ref()
+        .filter(|dir| !dir.is_empty())
+        .cloned();
+
+    let action = ExecutorAction::new(
+        ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+            prompt: format!("{ASK_PREAMBLE}\n\n{}", payload.question.trim()),
+            session_id: agent_session_id,
+            executor_profile_id: executor_profile_id.clone(),
+            working_dir,
+            read_only: true,
+        }),
+        None,
+    );
+
     let execution_process = deployment
         .container()
         .start_execution(
@@ -216,16 +574,16 @@ pub async fn follow_up(
         )
         .await?;
 
-    // Clear the draft follow-up scratch on successful spawn
-    // This ensures the scratch is wiped even if the user navigates away quickly
-    if let Err(e) = Scratch::delete(pool, session.id, &ScratchType::DraftFollowUp).await {
-        // Log but don't fail the request - scratch deletion is best-effort
-        tracing::debug!(
-            "Failed to delete draft follow-up scratch for session {}: {}",
-            session.id,
-            e
-        );
-    }
+    deployment
+        .track_if_analytics_allowed(
+            "session_question_asked",
+            serde_json::json!({
+                "session_id": session.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "executor": executor_profile_id.executor.to_string(),
+            }),
+        )
+        .await;
 
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
@@ -234,6 +592,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let session_id_router = Router::new()
         .route("/", get(get_session))
         .route("/follow-up", post(follow_up))
+        .route("/scoped-follow-up", post(scoped_follow_up))
+        .route("/ask", post(ask_agent))
+        .route("/context-compactions", get(get_context_compactions))
+        .route("/transcript", post(export_transcript))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
@@ -0,0 +1,115 @@
+//! First-run setup endpoints used by the onboarding wizard of single-binary installs.
+
+use axum::{
+    Json, Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::project::Project;
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use services::services::config::save_config_to_file;
+use ts_rs::TS;
+use utils::{
+    assets::{asset_dir, config_path, database_path},
+    response::ApiResponse,
+};
+
+use crate::{DeploymentImpl, error::ApiError, routes::frontend::Assets};
+
+#[derive(Debug, Serialize, TS)]
+pub struct SetupStatus {
+    /// True until the wizard has been completed once
+    pub first_run: bool,
+    pub data_dir: String,
+    pub database_path: String,
+    pub config_path: String,
+    pub project_count: i64,
+    /// Whether this binary was built with the frontend bundle embedded
+    pub frontend_embedded: bool,
+    pub executor_profile: ExecutorProfileId,
+    pub workspace_dir: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CompleteSetupRequest {
+    pub executor_profile: Option<ExecutorProfileId>,
+    pub workspace_dir: Option<String>,
+    pub git_branch_prefix: Option<String>,
+    pub analytics_enabled: Option<bool>,
+}
+
+pub async fn get_setup_status(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<SetupStatus>>, ApiError> {
+    let project_count = Project::count(&deployment.db().pool).await?;
+    let config = deployment.config().read().await;
+
+    Ok(ResponseJson(ApiResponse::success(SetupStatus {
+        first_run: !(config.disclaimer_acknowledged && config.onboarding_acknowledged),
+        data_dir: asset_dir().to_string_lossy().to_string(),
+        database_path: database_path().to_string_lossy().to_string(),
+        config_path: config_path().to_string_lossy().to_string(),
+        project_count,
+        frontend_embedded: Assets::get("index.html").is_some(),
+        executor_profile: config.executor_profile.clone(),
+        workspace_dir: config.workspace_dir.clone(),
+    })))
+}
+
+/// Apply the wizard choices and mark onboarding as done in a single config write
+pub async fn complete_setup(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CompleteSetupRequest>,
+) -> Result<ResponseJson<ApiResponse<SetupStatus>>, ApiError> {
+    if let Some(prefix) = &payload.git_branch_prefix
+        && !utils::git::is_valid_branch_prefix(prefix)
+    {
+        return Err(ApiError::BadRequest(
+            "Invalid git branch prefix. Must be a valid git branch name component without slashes."
+                .to_string(),
+        ));
+    }
+
+    if let Some(dir) = payload.workspace_dir.as_deref().filter(|d| !d.is_empty()) {
+        let path = utils::path::expand_tilde(dir);
+        std::fs::create_dir_all(&path).map_err(|e| {
+            ApiError::BadRequest(format!(
+                "Cannot use {} as workspace directory: {}",
+                path.display(),
+                e
+            ))
+        })?;
+    }
+
+    let old_config = deployment.config().read().await.clone();
+    let mut new_config = old_config.clone();
+    new_config.disclaimer_acknowledged = true;
+    new_config.onboarding_acknowledged = true;
+    if let Some(profile) = payload.executor_profile {
+        new_config.executor_profile = profile;
+    }
+    if let Some(dir) = payload.workspace_dir {
+        new_config.workspace_dir = Some(dir).filter(|d| !d.is_empty());
+    }
+    if let Some(prefix) = payload.git_branch_prefix {
+        new_config.git_branch_prefix = prefix;
+    }
+    if let Some(analytics_enabled) = payload.analytics_enabled {
+        new_config.analytics_enabled = analytics_enabled;
+    }
+
+    save_config_to_file(&new_config, &config_path()).await?;
+    *deployment.config().write().await = new_config.clone();
+    super::config::handle_config_events(&deployment, &old_config, &new_config).await;
+
+    get_setup_status(State(deployment)).await
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/setup/status", get(get_setup_status))
+        .route("/setup/complete", post(complete_setup))
+}
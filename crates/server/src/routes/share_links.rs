@@ -0,0 +1,312 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use chrono::{DateTime, Duration, Utc};
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    merge::{Merge, MergeStatus},
+    session::Session,
+    share_link::{CreateShareLink, ShareLink},
+    task::{Task, TaskStatus},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use rand::{Rng, distributions::Alphanumeric};
+use serde::{Deserialize, Serialize};
+use services::services::git::DiffTarget;
+use ts_rs::TS;
+use utils::{diff::compute_line_change_counts, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Length of the random token in a share link
+const TOKEN_LENGTH: usize = 40;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateShareLinkRequest {
+    /// Share only this attempt instead of every attempt of the task
+    pub workspace_id: Option<Uuid>,
+    /// Hours until the link stops working; it never expires when unset
+    pub expires_in_hours: Option<u32>,
+}
+
+/// What a share link shows. Logs, file contents and local paths are left out on purpose.
+#[derive(Debug, Serialize, TS)]
+pub struct SharedTaskView {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Newest first
+    pub attempts: Vec<SharedAttempt>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SharedAttempt {
+    pub branch: String,
+    pub executor: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub repos: Vec<SharedRepoChanges>,
+    /// Coding agent turns, oldest first
+    pub timeline: Vec<SharedTimelineEntry>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SharedRepoChanges {
+    pub repo_name: String,
+    pub target_branch: String,
+    /// Committed changes against the target branch, or None when the branch is gone
+    pub diff: Option<SharedDiffSummary>,
+    /// Latest pull request opened from the branch
+    pub pull_request: Option<SharedPullRequest>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SharedDiffSummary {
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SharedPullRequest {
+    pub number: i64,
+    pub url: String,
+    pub status: MergeStatus,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SharedTimelineEntry {
+    pub at: DateTime<Utc>,
+    pub prompt: Option<String>,
+    pub summary: Option<String>,
+}
+
+fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+pub async fn get_share_links(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ShareLink>>>, ApiError> {
+    let links = ShareLink::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(links)))
+}
+
+pub async fn create_share_link(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateShareLinkRequest>,
+) -> Result<ResponseJson<ApiResponse<ShareLink>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if let Some(workspace_id) = payload.workspace_id {
+        let belongs_to_task = Workspace::find_by_id(pool, workspace_id)
+            .await?
+            .is_some_and(|workspace| workspace.task_id == task.id);
+        if !belongs_to_task {
+            return Err(ApiError::BadRequest(
+                "The attempt does not belong to this task".to_string(),
+            ));
+        }
+    }
+    if payload.expires_in_hours == Some(0) {
+        return Err(ApiError::BadRequest(
+            "Expiry must be at least one hour".to_string(),
+        ));
+    }
+
+    let link = ShareLink::create(
+        pool,
+        &CreateShareLink {
+            token: generate_token(),
+            task_id: task.id,
+            workspace_id: payload.workspace_id,
+            expires_at: payload
+                .expires_in_hours
+                .map(|hours| Utc::now() + Duration::hours(hours as i64)),
+        },
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "share_link_created",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "attempt_only": link.workspace_id.is_some(),
+                "expires": link.expires_at.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(link)))
+}
+
+pub async fn revoke_share_link(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<ShareLink>>, ApiError> {
+    let link = ShareLink::revoke(&deployment.db().pool, id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "share_link_revoked",
+            serde_json::json!({
+                "task_id": link.task_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(link)))
+}
+
+async fn shared_attempt(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<SharedAttempt, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let mut repos = Vec::new();
+    let workspace_repos = WorkspaceRepo::find_by_workspace_id(pool, workspace.id).await?;
+    for repo in WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await? {
+        let Some(target_branch) = workspace_repos
+            .iter()
+            .find(|wr| wr.repo_id == repo.id)
+            .map(|wr| wr.target_branch.clone())
+        else {
+            continue;
+        };
+        let diff = deployment
+            .git()
+            .get_diffs(
+                DiffTarget::Branch {
+                    repo_path: &repo.path,
+                    branch_name: &workspace.branch,
+                    base_branch: &target_branch,
+                },
+                None,
+            )
+            .map(|diffs| {
+                let mut summary = SharedDiffSummary {
+                    files_changed: diffs.len(),
+                    additions: 0,
+                    deletions: 0,
+                };
+                for diff in &diffs {
+                    let (additions, deletions) = match (diff.additions, diff.deletions) {
+                        (Some(additions), Some(deletions)) => (additions, deletions),
+                        _ => compute_line_change_counts(
+                            diff.old_content.as_deref().unwrap_or_default(),
+                            diff.new_content.as_deref().unwrap_or_default(),
+                        ),
+                    };
+                    summary.additions += additions;
+                    summary.deletions += deletions;
+                }
+                summary
+            })
+            .inspect_err(|e| {
+                tracing::debug!("No diff of {} for a share link: {}", repo.name, e);
+            })
+            .ok();
+        let pull_request = Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo.id)
+            .await?
+            .into_iter()
+            .find_map(|merge| match merge {
+                Merge::Pr(pr) => Some(SharedPullRequest {
+                    number: pr.pr_info.number,
+                    url: pr.pr_info.url,
+                    status: pr.pr_info.status,
+                }),
+                Merge::Direct(_) => None,
+            });
+        repos.push(SharedRepoChanges {
+            repo_name: repo.name,
+            target_branch,
+            diff,
+            pull_request,
+        });
+    }
+
+    let sessions = Session::find_by_workspace_id(pool, workspace.id).await?;
+    let mut timeline = Vec::new();
+    for session in &sessions {
+        for turn in CodingAgentTurn::find_by_session_id(pool, session.id).await? {
+            timeline.push(SharedTimelineEntry {
+                at: turn.created_at,
+                prompt: turn.prompt,
+                summary: turn.summary,
+            });
+        }
+    }
+    timeline.sort_by_key(|entry| entry.at);
+
+    Ok(SharedAttempt {
+        branch: workspace.branch.clone(),
+        executor: sessions.iter().find_map(|session| session.executor.clone()),
+        created_at: workspace.created_at,
+        repos,
+        timeline,
+    })
+}
+
+/// Read-only view of a shared task; needs nothing but the token
+pub async fn get_shared_task(
+    State(deployment): State<DeploymentImpl>,
+    Path(token): Path<String>,
+) -> Result<ResponseJson<ApiResponse<SharedTaskView>>, ApiError> {
+    let pool = &deployment.db().pool;
+    // Unknown, expired and revoked links look the same from outside
+    let link = ShareLink::find_by_token(pool, &token)
+        .await?
+        .filter(|link| link.is_active(Utc::now()))
+        .ok_or_else(|| {
+            ApiError::Forbidden("This link is invalid, expired or revoked".to_string())
+        })?;
+    let task = Task::find_by_id(pool, link.task_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+
+    let workspaces = match link.workspace_id {
+        Some(workspace_id) => Workspace::find_by_id(pool, workspace_id)
+            .await?
+            .into_iter()
+            .collect(),
+        None => Workspace::fetch_all(pool, Some(task.id)).await?,
+    };
+    let mut attempts = Vec::new();
+    for workspace in &workspaces {
+        attempts.push(shared_attempt(&deployment, workspace).await?);
+    }
+    attempts.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    Ok(ResponseJson(ApiResponse::success(SharedTaskView {
+        title: task.title,
+        description: task.description,
+        status: task.status,
+        created_at: task.created_at,
+        updated_at: task.updated_at,
+        expires_at: link.expires_at,
+        attempts,
+    })))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/share-links/{id}", delete(revoke_share_link))
+        .route("/shared/{token}", get(get_shared_task))
+}
@@ -0,0 +1,52 @@
+use axum::{
+    Router,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::Response,
+    routing::get,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct SignedUrlQuery {
+    /// Unix timestamp after which the URL stops working
+    pub expires: i64,
+    pub signature: String,
+}
+
+/// Download an object of the local storage backend by a URL it signed. The signature is the
+/// only authentication, so these URLs can be handed to browsers and other tools.
+pub async fn download_object(
+    Path(key): Path<String>,
+    Query(query): Query<SignedUrlQuery>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<Response, ApiError> {
+    let storage = deployment.storage();
+    if !storage.verify_signature(&key, query.expires, &query.signature) {
+        return Err(ApiError::Forbidden(
+            "The download link is invalid or has expired".to_string(),
+        ));
+    }
+    let data = storage.get(&key).await?;
+
+    let content_type = mime_guess::from_path(&key).first_or_octet_stream();
+    let cache_seconds = (query.expires - chrono::Utc::now().timestamp()).max(0);
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type.as_ref())
+        .header(header::CONTENT_LENGTH, data.len())
+        .header(
+            header::CACHE_CONTROL,
+            format!("private, max-age={cache_seconds}"),
+        )
+        .body(Body::from(data))
+        .map_err(|e| ApiError::Io(std::io::Error::other(e)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/storage/{*key}", get(download_object))
+}
@@ -4,6 +4,7 @@ pub mod gh_cli_setup;
 pub mod images;
 pub mod mr;
 pub mod pr;
+pub mod review_follow_ups;
 pub mod util;
 
 use std::{
@@ -20,13 +21,20 @@ use axum::{
     http::StatusCode,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    routing::{get, post, put},
 };
+use chrono::{DateTime, Utc};
 use db::models::{
+    branch_divergence::BranchDivergence,
+    coverage_report::CoverageReport,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    lint_report::LintReport,
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
+    policy_violation_report::{PolicyViolation, PolicyViolationReport},
     project_repo::ProjectRepo,
     repo::{Repo, RepoError},
+    repo_chain_step::{CreateRepoChainStep, RepoChainStep},
+    secret_scan_acknowledgement::SecretScanAcknowledgement,
     session::{CreateSession, Session},
     task::{Task, TaskRelationships, TaskStatus},
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
@@ -45,17 +53,27 @@ use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
+    divergence,
     git::{ConflictOp, GitCliError, GitServiceError},
     github::GitHubService,
+    repo_chain,
+    secret_scan::SecretFinding,
 };
-use sqlx::Error as SqlxError;
+use sqlx::{Error as SqlxError, SqlitePool};
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{
+    api::projects::ProjectPermission,
+    response::{ApiResponse, Keyset, PageQuery},
+};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
-    routes::task_attempts::gh_cli_setup::GhCliSetupError,
+    DeploymentImpl,
+    error::{ApiError, ProblemType},
+    middleware::{load_workspace_middleware, service_accounts::Actor},
+    permissions,
+    routes::{sessions, task_attempts::gh_cli_setup::GhCliSetupError},
+    validation::{Validate, Validator},
 };
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -78,11 +96,39 @@ pub enum GitOperationError {
     RebaseInProgress,
 }
 
+impl ProblemType for GitOperationError {
+    fn status(&self) -> StatusCode {
+        StatusCode::CONFLICT
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TaskAttemptQuery {
     pub task_id: Option<Uuid>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct BranchMappingQuery {
+    pub repo_id: Option<Uuid>,
+}
+
+/// Which task and pull request a workspace branch belongs to
+#[derive(Debug, Serialize, TS)]
+pub struct BranchMapping {
+    pub branch: String,
+    pub target_branch: String,
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub task_status: TaskStatus,
+    /// Latest pull request opened from the branch
+    pub pr_number: Option<i64>,
+    pub pr_url: Option<String>,
+    pub pr_status: Option<MergeStatus>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DiffStreamQuery {
     #[serde(default)]
@@ -92,10 +138,54 @@ pub struct DiffStreamQuery {
 pub async fn get_task_attempts(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<TaskAttemptQuery>,
+    Query(page): Query<PageQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Workspace>>>, ApiError> {
     let pool = &deployment.db().pool;
-    let workspaces = Workspace::fetch_all(pool, query.task_id).await?;
-    Ok(ResponseJson(ApiResponse::success(workspaces)))
+    let keyset = page.keyset()?;
+    let workspaces = Workspace::fetch_page(pool, query.task_id, keyset).await?;
+    let total = Workspace::count(pool, query.task_id).await?;
+    let (workspaces, pagination) = page.keyset_page(workspaces, total as u64, |workspace| {
+        Keyset::new(workspace.created_at, workspace.id)
+    });
+    Ok(ResponseJson(ApiResponse::success_page(
+        workspaces, pagination,
+    )))
+}
+
+/// Branch to task to pull request mapping of every workspace, for git tooling outside the app
+pub async fn get_branch_mapping(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<BranchMappingQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<BranchMapping>>>, ApiError> {
+    let pool = &deployment.db().read_pool;
+    let mut mappings = Vec::new();
+    for task_branch in WorkspaceRepo::find_task_branches(pool, query.repo_id).await? {
+        let pr = Merge::find_by_workspace_and_repo_id(
+            pool,
+            task_branch.workspace_id,
+            task_branch.repo_id,
+        )
+        .await?
+        .into_iter()
+        .find_map(|merge| match merge {
+            Merge::Pr(pr) => Some(pr.pr_info),
+            Merge::Direct(_) => None,
+        });
+        mappings.push(BranchMapping {
+            branch: task_branch.branch,
+            target_branch: task_branch.target_branch,
+            repo_id: task_branch.repo_id,
+            repo_name: task_branch.repo_name,
+            workspace_id: task_branch.workspace_id,
+            task_id: task_branch.task_id,
+            task_title: task_branch.task_title,
+            task_status: task_branch.task_status,
+            pr_number: pr.as_ref().map(|pr| pr.number),
+            pr_url: pr.as_ref().map(|pr| pr.url.clone()),
+            pr_status: pr.map(|pr| pr.status),
+        });
+    }
+    Ok(ResponseJson(ApiResponse::success(mappings)))
 }
 
 pub async fn get_task_attempt(
@@ -117,6 +207,32 @@ pub struct WorkspaceRepoInput {
     pub target_branch: String,
 }
 
+/// At least one repository, each with a valid target branch
+pub fn validate_repos(v: &mut Validator, repos: &[WorkspaceRepoInput]) {
+    v.not_empty("repos", repos);
+    for (i, repo) in repos.iter().enumerate() {
+        v.branch_name(&format!("repos[{i}].target_branch"), &repo.target_branch);
+    }
+}
+
+pub async fn check_repo_references(
+    v: &mut Validator,
+    pool: &SqlitePool,
+    repos: &[WorkspaceRepoInput],
+) -> Result<(), ApiError> {
+    for (i, repo) in repos.iter().enumerate() {
+        v.repo_exists(pool, &format!("repos[{i}].repo_id"), repo.repo_id)
+            .await?;
+    }
+    Ok(())
+}
+
+impl Validate for CreateTaskAttemptBody {
+    fn validate(&self, v: &mut Validator) {
+        validate_repos(v, &self.repos);
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct RunAgentSetupRequest {
     pub executor_profile_id: ExecutorProfileId,
@@ -131,17 +247,22 @@ pub async fn create_task_attempt(
     Json(payload): Json<CreateTaskAttemptBody>,
 ) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
     let executor_profile_id = payload.executor_profile_id.clone();
+    let pool = &deployment.db().pool;
 
-    if payload.repos.is_empty() {
-        return Err(ApiError::BadRequest(
-            "At least one repository is required".to_string(),
-        ));
-    }
+    let mut v = Validator::check(&payload);
+    v.task_exists(pool, "task_id", payload.task_id).await?;
+    check_repo_references(&mut v, pool, &payload.repos).await?;
+    v.finish()?;
 
-    let pool = &deployment.db().pool;
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
+    permissions::require_permission(
+        &deployment,
+        task.project_id,
+        ProjectPermission::StartExecution,
+    )
+    .await?;
 
     let project = task
         .parent_project(pool)
@@ -207,6 +328,153 @@ pub async fn create_task_attempt(
     Ok(ResponseJson(ApiResponse::success(workspace)))
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct AdoptBranchRepo {
+    pub repo_id: Uuid,
+    /// Inferred from the branch history when not given
+    pub target_branch: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct AdoptBranchRequest {
+    pub task_id: Uuid,
+    pub executor_profile_id: ExecutorProfileId,
+    /// A local branch, or a branch of the default remote named without the remote prefix
+    pub branch: String,
+    pub repos: Vec<AdoptBranchRepo>,
+}
+
+/// Create a workspace on a branch that was started outside the app and let the agent take over.
+/// The branch is used as it is, and any pull request already open for it is attached.
+pub async fn adopt_branch(
+    State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
+    Json(payload): Json<AdoptBranchRequest>,
+) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+    if !deployment.git().is_branch_name_valid(&payload.branch) {
+        return Err(ApiError::BadRequest(format!(
+            "'{}' is not a valid branch name",
+            payload.branch
+        )));
+    }
+
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let project = task
+        .parent_project(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let mut workspace_repos = Vec::with_capacity(payload.repos.len());
+    for adopted in &payload.repos {
+        let repo = Repo::find_by_id(pool, adopted.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+        // A branch checked out in the user's checkout cannot get a worktree of its own
+        if deployment
+            .git()
+            .get_current_branch(&repo.path)
+            .is_ok_and(|current| current == payload.branch)
+        {
+            return Err(ApiError::Conflict(format!(
+                "Branch '{}' is checked out in {}. Switch to another branch there first.",
+                payload.branch,
+                repo.path.display()
+            )));
+        }
+        match deployment
+            .git()
+            .ensure_local_branch(&repo.path, &payload.branch)
+        {
+            Ok(()) => {}
+            Err(GitServiceError::BranchNotFound(_)) => {
+                return Err(ApiError::BadRequest(format!(
+                    "Branch '{}' does not exist in repository '{}'",
+                    payload.branch, repo.name
+                )));
+            }
+            Err(e) => return Err(e.into()),
+        }
+        let target_branch = match &adopted.target_branch {
+            Some(target_branch) => target_branch.clone(),
+            None => deployment
+                .git()
+                .infer_base_branch(&repo.path, &payload.branch)?
+                .ok_or_else(|| {
+                    ApiError::BadRequest(format!(
+                        "Could not infer the target branch of '{}' in repository '{}'",
+                        payload.branch, repo.name
+                    ))
+                })?,
+        };
+        workspace_repos.push(CreateWorkspaceRepo {
+            repo_id: repo.id,
+            target_branch,
+        });
+    }
+
+    let agent_working_dir = project
+        .default_agent_working_dir
+        .as_ref()
+        .filter(|dir| !dir.is_empty())
+        .cloned();
+    let workspace = Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: payload.branch.clone(),
+            agent_working_dir,
+        },
+        Uuid::new_v4(),
+        payload.task_id,
+    )
+    .await?;
+    WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
+
+    let mut pr_attached = false;
+    for workspace_repo in &workspace_repos {
+        match pr::attach_pr_for_branch(&deployment, &workspace, workspace_repo.repo_id, &actor)
+            .await
+        {
+            Ok(response) => pr_attached |= response.pr_attached,
+            Err(e) => tracing::warn!(
+                "Failed to look up a PR for adopted branch {}: {}",
+                payload.branch,
+                e
+            ),
+        }
+    }
+
+    if let Err(err) = deployment
+        .container()
+        .start_workspace(&workspace, payload.executor_profile_id.clone())
+        .await
+    {
+        tracing::error!("Failed to start adopted task attempt: {}", err);
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_branch_adopted",
+            serde_json::json!({
+                "task_id": workspace.task_id.to_string(),
+                "executor": &payload.executor_profile_id.executor,
+                "workspace_id": workspace.id.to_string(),
+                "repository_count": workspace_repos.len(),
+                "pr_attached": pr_attached,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(workspace)))
+}
+
 #[axum::debug_handler]
 pub async fn run_agent_setup(
     Extension(workspace): Extension<Workspace>,
@@ -315,9 +583,12 @@ pub struct PushTaskAttemptRequest {
 pub async fn merge_task_attempt(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
     Json(request): Json<MergeTaskAttemptRequest>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     let pool = &deployment.db().pool;
+    permissions::require_workspace_permission(&deployment, &workspace, ProjectPermission::Merge)
+        .await?;
 
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
@@ -368,7 +639,10 @@ pub async fn merge_task_attempt(
         &merge_commit_id,
     )
     .await?;
-    Task::update_status(pool, task.id, TaskStatus::Done).await?;
+    deployment
+        .task_events()
+        .set_status(task.id, TaskStatus::Done, &actor)
+        .await?;
 
     // Stop any running dev servers for this workspace
     let dev_servers =
@@ -395,22 +669,6 @@ pub async fn merge_task_attempt(
         }
     }
 
-    // Try broadcast update to other users in organization
-    if let Ok(publisher) = deployment.share_publisher() {
-        if let Err(err) = publisher.update_shared_task_by_id(task.id).await {
-            tracing::warn!(
-                ?err,
-                "Failed to propagate shared task update for {}",
-                task.id
-            );
-        }
-    } else {
-        tracing::debug!(
-            "Share publisher unavailable; skipping remote update for {}",
-            task.id
-        );
-    }
-
     deployment
         .track_if_analytics_allowed(
             "task_attempt_merged",
@@ -450,14 +708,36 @@ pub async fn push_task_attempt_branch(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
+    let violations = util::check_push_policies(&deployment, &workspace, &repo).await?;
+    if !violations.is_empty() {
+        return Err(ApiError::problem(PushError::PolicyViolation { violations }));
+    }
+    let findings = util::check_push_secrets(
+        &deployment,
+        &workspace,
+        &repo,
+        &workspace_repo.target_branch,
+    )
+    .await?;
+    if !findings.is_empty() {
+        return Err(ApiError::problem(PushError::SecretsDetected { findings }));
+    }
+
     match deployment
         .git()
         .push_to_github(&worktree_path, &workspace.branch, false)
     {
-        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
-        Err(GitServiceError::GitCLI(GitCliError::PushRejected(_))) => Ok(ResponseJson(
-            ApiResponse::error_with_data(PushError::ForcePushRequired),
-        )),
+        Ok(_) => {
+            deployment
+                .container()
+                .commit_statuses()
+                .queue_agent_run(workspace.id)
+                .await;
+            Ok(ResponseJson(ApiResponse::success(())))
+        }
+        Err(GitServiceError::GitCLI(GitCliError::PushRejected(_))) => {
+            Err(ApiError::problem(PushError::ForcePushRequired))
+        }
         Err(e) => Err(ApiError::GitService(e)),
     }
 }
@@ -488,9 +768,29 @@ pub async fn force_push_task_attempt_branch(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
+    let violations = util::check_push_policies(&deployment, &workspace, &repo).await?;
+    if !violations.is_empty() {
+        return Err(ApiError::problem(PushError::PolicyViolation { violations }));
+    }
+    let findings = util::check_push_secrets(
+        &deployment,
+        &workspace,
+        &repo,
+        &workspace_repo.target_branch,
+    )
+    .await?;
+    if !findings.is_empty() {
+        return Err(ApiError::problem(PushError::SecretsDetected { findings }));
+    }
+
     deployment
         .git()
         .push_to_github(&worktree_path, &workspace.branch, true)?;
+    deployment
+        .container()
+        .commit_statuses()
+        .queue_agent_run(workspace.id)
+        .await;
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -499,6 +799,19 @@ pub async fn force_push_task_attempt_branch(
 #[ts(tag = "type", rename_all = "snake_case")]
 pub enum PushError {
     ForcePushRequired,
+    PolicyViolation { violations: Vec<PolicyViolation> },
+    SecretsDetected { findings: Vec<SecretFinding> },
+}
+
+impl ProblemType for PushError {
+    fn status(&self) -> StatusCode {
+        match self {
+            PushError::ForcePushRequired => StatusCode::CONFLICT,
+            PushError::PolicyViolation { .. } | PushError::SecretsDetected { .. } => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+        }
+    }
 }
 
 #[derive(serde::Deserialize, TS)]
@@ -743,6 +1056,9 @@ pub async fn get_task_attempt_branch_status(
 pub struct ChangeTargetBranchRequest {
     pub repo_id: Uuid,
     pub new_target_branch: String,
+    /// Only change the target branch when the attempt still has this `updated_at`
+    #[serde(default)]
+    pub expected_updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(serde::Serialize, Debug, TS)]
@@ -755,6 +1071,9 @@ pub struct ChangeTargetBranchResponse {
 #[derive(serde::Deserialize, Debug, TS)]
 pub struct RenameBranchRequest {
     pub new_branch_name: String,
+    /// Only rename when the attempt still has this `updated_at`
+    #[serde(default)]
+    pub expected_updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(serde::Serialize, Debug, TS)]
@@ -774,6 +1093,35 @@ pub enum RenameBranchError {
     RenameFailed { repo_name: String, message: String },
 }
 
+impl ProblemType for RenameBranchError {
+    fn status(&self) -> StatusCode {
+        match self {
+            RenameBranchError::EmptyBranchName | RenameBranchError::InvalidBranchNameFormat => {
+                StatusCode::BAD_REQUEST
+            }
+            RenameBranchError::OpenPullRequest
+            | RenameBranchError::BranchAlreadyExists { .. }
+            | RenameBranchError::RebaseInProgress { .. } => StatusCode::CONFLICT,
+            RenameBranchError::RenameFailed { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Ahead/behind counts of the workspace branch as of the last background fetch. Unlike
+/// `branch-status` this never fetches, so it is cheap enough to poll.
+pub async fn get_task_attempt_divergence(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<BranchDivergence>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut divergences = BranchDivergence::find_by_workspace_id(pool, workspace.id).await?;
+    if divergences.is_empty() {
+        divergences =
+            divergence::refresh_workspace(pool, deployment.git(), &workspace, None).await?;
+    }
+    Ok(ResponseJson(ApiResponse::success(divergences)))
+}
+
 #[axum::debug_handler]
 pub async fn change_target_branch(
     Extension(workspace): Extension<Workspace>,
@@ -792,16 +1140,18 @@ pub async fn change_target_branch(
         .git()
         .check_branch_exists(&repo.path, &new_target_branch)?
     {
-        return Ok(ResponseJson(ApiResponse::error(
-            format!(
-                "Branch '{}' does not exist in repository '{}'",
-                new_target_branch, repo.name
-            )
-            .as_str(),
+        return Err(ApiError::BadRequest(format!(
+            "Branch '{}' does not exist in repository '{}'",
+            new_target_branch, repo.name
         )));
     };
 
+    // Claims the attempt's current version, so a concurrent change with the same one fails
+    if !Workspace::touch(pool, workspace.id, payload.expected_updated_at).await? {
+        return Err(ApiError::stale("task attempt"));
+    }
     WorkspaceRepo::update_target_branch(pool, workspace.id, repo_id, &new_target_branch).await?;
+    divergence::refresh_workspace(pool, deployment.git(), &workspace, Some(repo_id)).await?;
 
     let status =
         deployment
@@ -833,17 +1183,22 @@ pub async fn rename_branch(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<RenameBranchRequest>,
 ) -> Result<ResponseJson<ApiResponse<RenameBranchResponse, RenameBranchError>>, ApiError> {
+    if payload
+        .expected_updated_at
+        .is_some_and(|expected| expected != workspace.updated_at)
+    {
+        return Err(ApiError::stale("task attempt"));
+    }
+
     let new_branch_name = payload.new_branch_name.trim();
 
     if new_branch_name.is_empty() {
-        return Ok(ResponseJson(ApiResponse::error_with_data(
-            RenameBranchError::EmptyBranchName,
-        )));
+        return Err(ApiError::problem(RenameBranchError::EmptyBranchName));
     }
     if !deployment.git().is_branch_name_valid(new_branch_name) {
-        return Ok(ResponseJson(ApiResponse::error_with_data(
+        return Err(ApiError::problem(
             RenameBranchError::InvalidBranchNameFormat,
-        )));
+        ));
     }
     if new_branch_name == workspace.branch {
         return Ok(ResponseJson(ApiResponse::success(RenameBranchResponse {
@@ -859,9 +1214,7 @@ pub async fn rename_branch(
         matches!(merge, Merge::Pr(pr_merge) if matches!(pr_merge.pr_info.status, MergeStatus::Open))
     });
     if has_open_pr {
-        return Ok(ResponseJson(ApiResponse::error_with_data(
-            RenameBranchError::OpenPullRequest,
-        )));
+        return Err(ApiError::problem(RenameBranchError::OpenPullRequest));
     }
 
     let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
@@ -878,25 +1231,37 @@ pub async fn rename_branch(
             .git()
             .check_branch_exists(&repo.path, new_branch_name)?
         {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                RenameBranchError::BranchAlreadyExists {
-                    repo_name: repo.name.clone(),
-                },
-            )));
+            return Err(ApiError::problem(RenameBranchError::BranchAlreadyExists {
+                repo_name: repo.name.clone(),
+            }));
         }
 
         if deployment.git().is_rebase_in_progress(&worktree_path)? {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                RenameBranchError::RebaseInProgress {
-                    repo_name: repo.name.clone(),
-                },
-            )));
+            return Err(ApiError::problem(RenameBranchError::RebaseInProgress {
+                repo_name: repo.name.clone(),
+            }));
         }
     }
 
     // Rename all repos with rollback
     let old_branch = workspace.branch.clone();
     let mut renamed_repos: Vec<&Repo> = Vec::new();
+    let rollback = |renamed_repos: &[&Repo]| {
+        for renamed_repo in renamed_repos {
+            let rollback_path = workspace_dir.join(&renamed_repo.name);
+            if let Err(rollback_err) =
+                deployment
+                    .git()
+                    .rename_local_branch(&rollback_path, new_branch_name, &old_branch)
+            {
+                tracing::error!(
+                    "Failed to rollback branch rename in '{}': {}",
+                    renamed_repo.name,
+                    rollback_err
+                );
+            }
+        }
+    };
 
     for repo in &repos {
         let worktree_path = workspace_dir.join(&repo.name);
@@ -910,32 +1275,30 @@ pub async fn rename_branch(
                 renamed_repos.push(repo);
             }
             Err(e) => {
-                // Rollback already renamed repos
-                for renamed_repo in &renamed_repos {
-                    let rollback_path = workspace_dir.join(&renamed_repo.name);
-                    if let Err(rollback_err) = deployment.git().rename_local_branch(
-                        &rollback_path,
-                        new_branch_name,
-                        &old_branch,
-                    ) {
-                        tracing::error!(
-                            "Failed to rollback branch rename in '{}': {}",
-                            renamed_repo.name,
-                            rollback_err
-                        );
-                    }
-                }
-                return Ok(ResponseJson(ApiResponse::error_with_data(
-                    RenameBranchError::RenameFailed {
-                        repo_name: repo.name.clone(),
-                        message: e.to_string(),
-                    },
-                )));
+                rollback(&renamed_repos);
+                return Err(ApiError::problem(RenameBranchError::RenameFailed {
+                    repo_name: repo.name.clone(),
+                    message: e.to_string(),
+                }));
             }
         }
     }
 
-    Workspace::update_branch_name(pool, workspace.id, new_branch_name).await?;
+    // The timestamp is checked again on write, in case the attempt changed during the rename
+    if !Workspace::update_branch_name(
+        pool,
+        workspace.id,
+        new_branch_name,
+        payload.expected_updated_at,
+    )
+    .await?
+    {
+        rollback(&renamed_repos);
+        return Err(match payload.expected_updated_at {
+            Some(_) => ApiError::stale("task attempt"),
+            None => ApiError::Database(SqlxError::RowNotFound),
+        });
+    }
     // What will become of me?
     let updated_children_count = WorkspaceRepo::update_target_branch_for_children_of_workspace(
         pool,
@@ -1005,12 +1368,9 @@ pub async fn rebase_task_attempt(
             .await?;
         }
         false => {
-            return Ok(ResponseJson(ApiResponse::error(
-                format!(
-                    "Branch '{}' does not exist in the repository",
-                    new_base_branch
-                )
-                .as_str(),
+            return Err(ApiError::BadRequest(format!(
+                "Branch '{}' does not exist in the repository",
+                new_base_branch
             )));
         }
     }
@@ -1032,25 +1392,22 @@ pub async fn rebase_task_attempt(
     if let Err(e) = result {
         use services::services::git::GitServiceError;
         return match e {
-            GitServiceError::MergeConflicts(msg) => Ok(ResponseJson(ApiResponse::<
-                (),
-                GitOperationError,
-            >::error_with_data(
-                GitOperationError::MergeConflicts {
+            GitServiceError::MergeConflicts(msg) => {
+                Err(ApiError::problem(GitOperationError::MergeConflicts {
                     message: msg,
                     op: ConflictOp::Rebase,
-                },
-            ))),
-            GitServiceError::RebaseInProgress => Ok(ResponseJson(ApiResponse::<
-                (),
-                GitOperationError,
-            >::error_with_data(
-                GitOperationError::RebaseInProgress,
-            ))),
+                }))
+            }
+            GitServiceError::RebaseInProgress => {
+                Err(ApiError::problem(GitOperationError::RebaseInProgress))
+            }
             other => Err(ApiError::GitService(other)),
         };
     }
 
+    divergence::refresh_workspace(pool, deployment.git(), &workspace, Some(payload.repo_id))
+        .await?;
+
     deployment
         .track_if_analytics_allowed(
             "task_attempt_rebased",
@@ -1143,9 +1500,9 @@ pub async fn start_dev_server(
     let dev_script = match &project.dev_script {
         Some(script) if !script.is_empty() => script.clone(),
         _ => {
-            return Ok(ResponseJson(ApiResponse::error(
-                "No dev server script configured for this project",
-            )));
+            return Err(ApiError::BadRequest(
+                "No dev server script configured for this project".to_string(),
+            ));
         }
     };
 
@@ -1261,6 +1618,15 @@ pub enum RunScriptError {
     ProcessAlreadyRunning,
 }
 
+impl ProblemType for RunScriptError {
+    fn status(&self) -> StatusCode {
+        match self {
+            RunScriptError::NoScriptConfigured => StatusCode::UNPROCESSABLE_ENTITY,
+            RunScriptError::ProcessAlreadyRunning => StatusCode::CONFLICT,
+        }
+    }
+}
+
 #[axum::debug_handler]
 pub async fn run_setup_script(
     Extension(workspace): Extension<Workspace>,
@@ -1272,9 +1638,7 @@ pub async fn run_setup_script(
     if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
         .await?
     {
-        return Ok(ResponseJson(ApiResponse::error_with_data(
-            RunScriptError::ProcessAlreadyRunning,
-        )));
+        return Err(ApiError::problem(RunScriptError::ProcessAlreadyRunning));
     }
 
     deployment
@@ -1299,9 +1663,7 @@ pub async fn run_setup_script(
     {
         Some(action) => action,
         None => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                RunScriptError::NoScriptConfigured,
-            )));
+            return Err(ApiError::problem(RunScriptError::NoScriptConfigured));
         }
     };
 
@@ -1356,9 +1718,7 @@ pub async fn run_cleanup_script(
     if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
         .await?
     {
-        return Ok(ResponseJson(ApiResponse::error_with_data(
-            RunScriptError::ProcessAlreadyRunning,
-        )));
+        return Err(ApiError::problem(RunScriptError::ProcessAlreadyRunning));
     }
 
     deployment
@@ -1383,9 +1743,7 @@ pub async fn run_cleanup_script(
     {
         Some(action) => action,
         None => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                RunScriptError::NoScriptConfigured,
-            )));
+            return Err(ApiError::problem(RunScriptError::NoScriptConfigured));
         }
     };
 
@@ -1478,6 +1836,210 @@ pub async fn get_task_attempt_repos(
     Ok(ResponseJson(ApiResponse::success(repos)))
 }
 
+pub async fn get_policy_violations(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<PolicyViolationReport>>>, ApiError> {
+    let reports =
+        PolicyViolationReport::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(reports)))
+}
+
+pub async fn get_coverage_reports(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<CoverageReport>>>, ApiError> {
+    let reports =
+        CoverageReport::find_latest_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(reports)))
+}
+
+pub async fn get_lint_reports(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<LintReport>>>, ApiError> {
+    let reports = LintReport::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(reports)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SecretScanQuery {
+    pub repo_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct AcknowledgeSecretsRequest {
+    pub fingerprints: Vec<String>,
+}
+
+pub async fn get_secret_scan(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SecretScanQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SecretFinding>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let findings = util::scan_branch_for_secrets(
+        &deployment,
+        &workspace,
+        &repo,
+        &workspace_repo.target_branch,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(findings)))
+}
+
+pub async fn acknowledge_secrets(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<AcknowledgeSecretsRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if request.fingerprints.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one fingerprint is required".to_string(),
+        ));
+    }
+    SecretScanAcknowledgement::create_many(
+        &deployment.db().pool,
+        workspace.id,
+        &request.fingerprints,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "secret_scan_findings_acknowledged",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "count": request.fingerprints.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn get_repo_chain(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoChainStep>>>, ApiError> {
+    let steps = RepoChainStep::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(steps)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct StartRepoChainRequest {
+    /// Repositories in the order they are changed, each in its own turn
+    pub steps: Vec<CreateRepoChainStep>,
+    pub variant: Option<String>,
+}
+
+/// Declare the order of the workspace's repositories and start the turn for the first one. Each
+/// later repository starts once the previous turn has finished successfully.
+pub async fn start_repo_chain(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<StartRepoChainRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoChainStep>>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if request.steps.len() < 2 {
+        return Err(ApiError::BadRequest(
+            "A repository order needs at least two repositories".to_string(),
+        ));
+    }
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    for (index, step) in request.steps.iter().enumerate() {
+        if !repos.iter().any(|repo| repo.id == step.repo_id) {
+            return Err(ApiError::BadRequest(format!(
+                "Repository {} is not part of this workspace",
+                step.repo_id
+            )));
+        }
+        if request.steps[..index]
+            .iter()
+            .any(|earlier| earlier.repo_id == step.repo_id)
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Repository {} appears more than once",
+                step.repo_id
+            )));
+        }
+    }
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running process to finish before starting a repository order".to_string(),
+        ));
+    }
+    let session = Session::find_latest_by_workspace_id(pool, workspace.id)
+        .await?
+        .ok_or(ApiError::BadRequest(
+            "The attempt has no session to continue".to_string(),
+        ))?;
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let initial_executor_profile_id =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?;
+    let executor_profile_id = ExecutorProfileId {
+        executor: initial_executor_profile_id.executor,
+        variant: request.variant,
+    };
+
+    let created = RepoChainStep::replace_for_workspace(pool, workspace.id, &request.steps).await?;
+    let first = &created[0];
+    let first_repo = repos
+        .iter()
+        .find(|repo| repo.id == first.repo_id)
+        .ok_or(RepoError::NotFound)?;
+    let prompt = repo_chain::step_prompt(
+        &task.to_prompt(),
+        &first_repo.name,
+        first.instructions.as_deref(),
+        &[],
+        repo_chain::MAX_EARLIER_DIFF_CHARS,
+    );
+    let execution_process = sessions::start_follow_up(
+        &deployment,
+        &session,
+        &workspace,
+        task.project_id,
+        prompt,
+        executor_profile_id,
+    )
+    .await?;
+    RepoChainStep::mark_running(pool, first.id, execution_process.id).await?;
+    let steps = RepoChainStep::find_by_workspace_id(pool, workspace.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "repo_chain_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_count": steps.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(steps)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_attempt_id_router = Router::new()
         .route("/", get(get_task_attempt))
@@ -1487,6 +2049,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/run-setup-script", post(run_setup_script))
         .route("/run-cleanup-script", post(run_cleanup_script))
         .route("/branch-status", get(get_task_attempt_branch_status))
+        .route("/divergence", get(get_task_attempt_divergence))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
         .route("/merge", post(merge_task_attempt))
         .route("/push", post(push_task_attempt_branch))
@@ -1498,15 +2061,58 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/pr/attach", post(pr::attach_existing_pr))
         .route("/pr/comments", get(pr::get_pr_comments))
         // New unified MR routes (provider-agnostic)
-        .route("/merge-request", post(mr::create_github_pr))
+        .route(
+            "/merge-request",
+            post(mr::create_github_pr).patch(mr::update_pr),
+        )
         .route("/merge-request/attach", post(mr::attach_existing_pr))
-        .route("/merge-request/comments", get(mr::get_pr_comments))
+        .route("/merge-request/merge", post(mr::merge_pr))
+        .route("/merge-request/close", post(mr::close_pr))
+        .route("/merge-request/reopen", post(mr::reopen_pr))
+        .route("/merge-request/draft", post(mr::set_pr_draft))
+        .route(
+            "/merge-request/comments",
+            get(mr::get_pr_comments).post(mr::post_pr_comment),
+        )
+        .route(
+            "/merge-request/comments/review",
+            post(mr::post_pr_review_comment),
+        )
+        .route(
+            "/merge-request/comments/reply",
+            post(mr::reply_to_pr_comment),
+        )
+        .route(
+            "/merge-request/comments/addressed",
+            post(mr::mark_pr_comment_addressed),
+        )
+        .route(
+            "/merge-request/suggestions/apply",
+            post(mr::apply_pr_suggestions),
+        )
+        .route(
+            "/merge-request/review-follow-ups",
+            put(review_follow_ups::set_review_follow_ups),
+        )
+        .route("/merge-request/milestones", get(mr::list_milestones))
+        .route(
+            "/merge-request/branch-protection",
+            get(mr::get_branch_protection),
+        )
+        .route("/merge-request/details", get(mr::get_pr_details))
+        .route("/merge-request/checks/logs", get(mr::get_pr_check_logs))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/children", get(get_task_attempt_children))
         .route("/stop", post(stop_task_attempt_execution))
         .route("/change-target-branch", post(change_target_branch))
         .route("/rename-branch", post(rename_branch))
         .route("/repos", get(get_task_attempt_repos))
+        .route("/policy-violations", get(get_policy_violations))
+        .route("/lint-reports", get(get_lint_reports))
+        .route("/coverage", get(get_coverage_reports))
+        .route("/secret-scan", get(get_secret_scan))
+        .route("/secret-scan/acknowledge", post(acknowledge_secrets))
+        .route("/repo-chain", get(get_repo_chain).post(start_repo_chain))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_workspace_middleware,
@@ -1514,6 +2120,8 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_attempts_router = Router::new()
         .route("/", get(get_task_attempts).post(create_task_attempt))
+        .route("/adopt-branch", post(adopt_branch))
+        .route("/branch-mapping", get(get_branch_mapping))
         .nest("/{id}", task_attempt_id_router)
         .nest("/{id}/images", images::router(deployment));
 
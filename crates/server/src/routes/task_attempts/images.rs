@@ -9,7 +9,7 @@ use axum::{
     response::{Json as ResponseJson, Response},
     routing::{get, post},
 };
-use db::models::{task::Task, workspace::Workspace};
+use db::models::{task::Task, tenant::Tenant, workspace::Workspace};
 use deployment::Deployment;
 use serde::Deserialize;
 use services::services::{container::ContainerService, image::ImageError};
@@ -35,6 +35,7 @@ pub struct ImageMetadataQuery {
 /// This allows images to be available in the container before follow-up is sent.
 pub async fn upload_image(
     Extension(workspace): Extension<Workspace>,
+    Extension(tenant): Extension<Tenant>,
     State(deployment): State<DeploymentImpl>,
     multipart: Multipart,
 ) -> Result<ResponseJson<ApiResponse<ImageResponse>>, ApiError> {
@@ -44,7 +45,8 @@ pub async fn upload_image(
         .ok_or_else(|| ApiError::Image(ImageError::NotFound))?;
 
     // Process upload (store in cache, associate with task)
-    let image_response = process_image_upload(&deployment, multipart, Some(task.id)).await?;
+    let image_response =
+        process_image_upload(&deployment, multipart, Some(task.id), tenant.id).await?;
 
     let container_ref = deployment
         .container()
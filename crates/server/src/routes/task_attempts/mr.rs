@@ -3,12 +3,18 @@ use std::path::PathBuf;
 use axum::{
     Extension, Json,
     extract::{Query, State},
+    http::StatusCode,
     response::Json as ResponseJson,
 };
+use chrono::{DateTime, Utc};
 use db::models::{
+    dependency_report::DependencyFinding,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
-    merge::{Merge, MergeStatus},
+    merge::{Merge, MergeStatus, PrMerge},
+    policy_violation_report::PolicyViolation,
+    pr_comment::{AddressedPrComment, PrComment},
     repo::{Repo, RepoError},
+    routing_decision::TurnPurpose,
     session::{CreateSession, Session},
     task::{Task, TaskStatus},
     workspace::{Workspace, WorkspaceError},
@@ -24,13 +30,30 @@ use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
     git::{GitCliError, GitServiceError},
-    git_provider::{self, CreateMrRequest, ProviderError, UnifiedComment},
+    git_provider::{
+        self, BranchProtection, CheckRun, CreateMrRequest, GitProvider, MergeMethod, Milestone,
+        PrInfo, PrState, ProviderError, RepoIdentifier, ReviewState, UnifiedComment, UnifiedReview,
+        UpdateMrRequest,
+    },
+    offline::{OfflineService, PostedComment, ProviderWrite},
+    secret_scan::SecretFinding,
+    suggestions::{self, SuggestionError},
+    task_events::TaskActor,
 };
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{
+    api::projects::ProjectPermission,
+    response::{ApiResponse, PageQuery},
+};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    error::{ApiError, ProblemType},
+    middleware::service_accounts::Actor,
+    permissions,
+    validation::{Validate, Validator},
+};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct CreateGitHubPrRequest {
@@ -41,6 +64,25 @@ pub struct CreateGitHubPrRequest {
     pub repo_id: Uuid,
     #[serde(default)]
     pub auto_generate_description: bool,
+    /// Title of a milestone to assign, from the repository's milestones
+    #[serde(default)]
+    pub milestone: Option<String>,
+    /// Have the provider merge the PR with this method once its checks and approvals pass
+    #[serde(default)]
+    pub auto_merge: Option<MergeMethod>,
+}
+
+impl Validate for CreateGitHubPrRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.title("title", &self.title);
+        v.body("body", self.body.as_deref());
+        if let Some(target_branch) = &self.target_branch {
+            v.branch_name("target_branch", target_branch);
+        }
+        if let Some(milestone) = &self.milestone {
+            v.title("milestone", milestone);
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -52,6 +94,24 @@ pub enum CreatePrError {
     GitCliNotLoggedIn,
     GitCliNotInstalled,
     TargetBranchNotFound { branch: String },
+    PolicyViolation { violations: Vec<PolicyViolation> },
+    SecretsDetected { findings: Vec<SecretFinding> },
+    DependencyPolicyViolation { findings: Vec<DependencyFinding> },
+}
+
+impl ProblemType for CreatePrError {
+    fn status(&self) -> StatusCode {
+        match self {
+            CreatePrError::GithubCliNotInstalled
+            | CreatePrError::GithubCliNotLoggedIn
+            | CreatePrError::GitCliNotLoggedIn
+            | CreatePrError::GitCliNotInstalled => StatusCode::FAILED_DEPENDENCY,
+            CreatePrError::TargetBranchNotFound { .. }
+            | CreatePrError::PolicyViolation { .. }
+            | CreatePrError::SecretsDetected { .. }
+            | CreatePrError::DependencyPolicyViolation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -60,6 +120,8 @@ pub struct AttachPrResponse {
     pub pr_url: Option<String>,
     pub pr_number: Option<i64>,
     pub pr_status: Option<MergeStatus>,
+    /// Summary of the reviews, when the provider reports them
+    pub review_state: Option<ReviewState>,
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -70,6 +132,11 @@ pub struct AttachExistingPrRequest {
 #[derive(Debug, Serialize, TS)]
 pub struct PrCommentsResponse {
     pub comments: Vec<UnifiedComment>,
+    /// Set when the provider is unreachable and these are the comments last fetched, to when
+    /// they were fetched
+    pub cached_at: Option<DateTime<Utc>>,
+    /// Comments of the MR/PR marked addressed, on any page
+    pub addressed: Vec<AddressedPrComment>,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -79,25 +146,311 @@ pub enum GetPrCommentsError {
     NoPrAttached,
     GithubCliNotInstalled,
     GithubCliNotLoggedIn,
+    /// The provider can't be reached and no comments were fetched before
+    ProviderUnreachable,
+}
+
+impl ProblemType for GetPrCommentsError {
+    fn status(&self) -> StatusCode {
+        match self {
+            GetPrCommentsError::NoPrAttached => StatusCode::NOT_FOUND,
+            GetPrCommentsError::GithubCliNotInstalled
+            | GetPrCommentsError::GithubCliNotLoggedIn => StatusCode::FAILED_DEPENDENCY,
+            GetPrCommentsError::ProviderUnreachable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, TS)]
 pub struct GetPrCommentsQuery {
     pub repo_id: Uuid,
+    /// Only comments created at or after this time, asked of the provider when it can filter
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    /// Leave out the comments of bots, like CI reports
+    #[serde(default)]
+    pub exclude_bots: bool,
+    /// Comma-separated usernames to keep the comments of
+    #[serde(default)]
+    pub authors: Option<String>,
+    /// Leave out the comments marked addressed
+    #[serde(default)]
+    pub only_unresolved: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ListMilestonesQuery {
+    pub repo_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BranchProtectionQuery {
+    pub repo_id: Uuid,
+    /// Branch the MR/PR would target; the workspace's target branch when unset
+    pub target_branch: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PrDetailsQuery {
+    pub repo_id: Uuid,
+}
+
+/// The attached MR/PR with its checks, reviews and comments, fetched together
+#[derive(Debug, Serialize, TS)]
+pub struct PrDetailsResponse {
+    pub pr_number: i64,
+    pub pr_url: String,
+    pub pr_status: MergeStatus,
+    /// Summary of the reviews, when the provider reports them
+    pub review_state: Option<ReviewState>,
+    /// Checks on the head commit; empty on providers that don't report them
+    pub checks: Vec<CheckRun>,
+    pub reviews: Vec<UnifiedReview>,
+    pub comments: Vec<UnifiedComment>,
+    /// Set when the provider is unreachable and this is the response last fetched, to when it
+    /// was fetched
+    pub cached_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CheckLogsQuery {
+    pub repo_id: Uuid,
+    /// `id` of one of the checks in [`PrDetailsResponse`]
+    pub check_id: String,
+}
+
+/// Log of the CI job behind a check of the attached MR/PR
+#[derive(Debug, Serialize, TS)]
+pub struct CheckLogsResponse {
+    /// The end of the log, where jobs report why they failed
+    pub log: String,
+    /// Whether earlier output was cut off to keep the log prompt-sized
+    pub truncated: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct MergePrRequest {
+    pub repo_id: Uuid,
+    #[serde(default)]
+    pub method: MergeMethod,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ClosePrRequest {
+    pub repo_id: Uuid,
+    /// Also move the task to cancelled, for abandoning the work
+    #[serde(default)]
+    pub abandon_task: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ReopenPrRequest {
+    pub repo_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct SetPrDraftRequest {
+    pub repo_id: Uuid,
+    /// `false` marks the PR ready for review and moves an in-progress task to in review
+    pub draft: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct UpdatePrRequest {
+    pub repo_id: Uuid,
+    pub title: Option<String>,
+    pub body: Option<String>,
+    /// Branch the PR merges into; the workspace targets it from then on
+    pub base_branch: Option<String>,
+}
+
+impl Validate for UpdatePrRequest {
+    fn validate(&self, v: &mut Validator) {
+        if self.title.is_none() && self.body.is_none() && self.base_branch.is_none() {
+            v.error("title", "nothing to update");
+        }
+        if let Some(title) = &self.title {
+            v.title("title", title);
+        }
+        v.body("body", self.body.as_deref());
+        if let Some(base_branch) = &self.base_branch {
+            v.branch_name("base_branch", base_branch);
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct PostPrCommentRequest {
+    pub repo_id: Uuid,
+    pub body: String,
+}
+
+impl Validate for PostPrCommentRequest {
+    fn validate(&self, v: &mut Validator) {
+        if self.body.trim().is_empty() {
+            v.error("body", "must not be empty");
+        } else {
+            v.body("body", Some(&self.body));
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct PostPrReviewCommentRequest {
+    pub repo_id: Uuid,
+    /// File path relative to the repository root
+    pub path: String,
+    /// Line in the new version of the file, starting at 1
+    pub line: i64,
+    pub body: String,
+}
+
+impl Validate for PostPrReviewCommentRequest {
+    fn validate(&self, v: &mut Validator) {
+        if self.path.trim().is_empty() {
+            v.error("path", "must not be empty");
+        }
+        if self.line < 1 {
+            v.error("line", "must be at least 1");
+        }
+        if self.body.trim().is_empty() {
+            v.error("body", "must not be empty");
+        } else {
+            v.body("body", Some(&self.body));
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ReplyToPrCommentRequest {
+    pub repo_id: Uuid,
+    /// Id of any comment in the thread, as in `UnifiedComment`
+    pub thread_id: String,
+    pub body: String,
+}
+
+impl Validate for ReplyToPrCommentRequest {
+    fn validate(&self, v: &mut Validator) {
+        if self.thread_id.trim().is_empty() {
+            v.error("thread_id", "must not be empty");
+        }
+        if self.body.trim().is_empty() {
+            v.error("body", "must not be empty");
+        } else {
+            v.body("body", Some(&self.body));
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct MarkPrCommentAddressedRequest {
+    pub repo_id: Uuid,
+    /// Key of the comment, `general:<id>` or `review:<id>` with its id as in `UnifiedComment`
+    pub comment_key: String,
+    /// False to mark the comment not addressed again
+    pub addressed: bool,
+    /// Agent turn of this workspace that addressed the comment
+    #[serde(default)]
+    pub execution_process_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ApplySuggestionsRequest {
+    pub repo_id: Uuid,
+    /// Review comment whose suggestions to apply; every suggestion on the MR/PR when None
+    #[serde(default)]
+    pub comment_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ApplySuggestionsResponse {
+    /// Review comments whose suggestions were applied
+    pub applied: Vec<i64>,
+    /// False when the worktree already read as suggested
+    pub committed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum ApplySuggestionsError {
+    NoPrAttached,
+    NoSuggestions,
+    /// The suggested lines changed since the review comment was written
+    SuggestionOutdated {
+        path: String,
+        line: u64,
+    },
+    SuggestionsOverlap {
+        path: String,
+    },
+    GithubCliNotInstalled,
+    GithubCliNotLoggedIn,
+}
+
+impl ProblemType for ApplySuggestionsError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ApplySuggestionsError::NoPrAttached | ApplySuggestionsError::NoSuggestions => {
+                StatusCode::NOT_FOUND
+            }
+            ApplySuggestionsError::SuggestionOutdated { .. }
+            | ApplySuggestionsError::SuggestionsOverlap { .. } => StatusCode::CONFLICT,
+            ApplySuggestionsError::GithubCliNotInstalled
+            | ApplySuggestionsError::GithubCliNotLoggedIn => StatusCode::FAILED_DEPENDENCY,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PrActionResponse {
+    pub pr_status: MergeStatus,
+    pub merge_commit_sha: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum PrActionError {
+    NoPrAttached,
+    PrNotOpen { status: MergeStatus },
+    PrNotClosed { status: MergeStatus },
+    GithubCliNotInstalled,
+    GithubCliNotLoggedIn,
+    ProviderNotSupported,
+    ProviderUnreachable,
+}
+
+impl ProblemType for PrActionError {
+    fn status(&self) -> StatusCode {
+        match self {
+            PrActionError::NoPrAttached => StatusCode::NOT_FOUND,
+            PrActionError::PrNotOpen { .. } | PrActionError::PrNotClosed { .. } => {
+                StatusCode::CONFLICT
+            }
+            PrActionError::GithubCliNotInstalled | PrActionError::GithubCliNotLoggedIn => {
+                StatusCode::FAILED_DEPENDENCY
+            }
+            PrActionError::ProviderNotSupported => StatusCode::NOT_IMPLEMENTED,
+            PrActionError::ProviderUnreachable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
 }
 
-pub const DEFAULT_PR_DESCRIPTION_PROMPT: &str = r#"Update the GitHub PR that was just created with a better title and description.
+pub const DEFAULT_PR_DESCRIPTION_PROMPT: &str = r#"Update the pull request that was just created with a better title and description.
 The PR number is #{pr_number} and the URL is {pr_url}.
 
 Analyze the changes in this branch and write:
-1. A concise, descriptive title that summarizes the changes, postfixed with "(Vibe Kanban)"
+1. A concise, descriptive title that summarizes the changes{title_branding}
 2. A detailed description that explains:
    - What changes were made
    - Why they were made (based on the task context)
-   - Any important implementation details
-   - At the end, include a note: "This PR was written using [Vibe Kanban](https://vibekanban.com)"
+   - Any important implementation details{footer_branding}
 
-Use `gh pr edit` to update the PR."#;
+Write the title and description in {language}.
+
+Use the `update_pull_request` tool of the vibe-kanban MCP server to update the PR, with the
+workspace and repository ids from `get_context`."#;
 
 async fn trigger_pr_description_follow_up(
     deployment: &DeploymentImpl,
@@ -116,6 +469,7 @@ async fn trigger_pr_description_follow_up(
     let prompt = prompt_template
         .replace("{pr_number}", &pr_number.to_string())
         .replace("{pr_url}", pr_url);
+    let prompt = config.pr_branding.apply(&prompt);
 
     drop(config); // Release the lock before async operations
 
@@ -159,6 +513,7 @@ async fn trigger_pr_description_follow_up(
             session_id: agent_session_id,
             executor_profile_id: executor_profile_id.clone(),
             working_dir: working_dir.clone(),
+            read_only: false,
         })
     } else {
         ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
@@ -172,11 +527,12 @@ async fn trigger_pr_description_follow_up(
 
     deployment
         .container()
-        .start_execution(
+        .start_execution_for_purpose(
             workspace,
             &session,
             &action,
             &ExecutionProcessRunReason::CodingAgent,
+            Some(TurnPurpose::PrDescription),
         )
         .await?;
 
@@ -192,6 +548,12 @@ pub async fn create_github_pr(
 ) -> Result<ResponseJson<ApiResponse<String, CreatePrError>>, ApiError> {
     let pool = &deployment.db().pool;
 
+    let mut v = Validator::check(&request);
+    v.repo_exists(pool, "repo_id", request.repo_id).await?;
+    v.finish()?;
+    permissions::require_workspace_permission(&deployment, &workspace, ProjectPermission::CreatePr)
+        .await?;
+
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -201,7 +563,7 @@ pub async fn create_github_pr(
         .await?
         .ok_or(RepoError::NotFound)?;
 
-    let repo_path = repo.path;
+    let repo_path = repo.path.clone();
     let target_branch = if let Some(branch) = request.target_branch {
         branch
     } else {
@@ -213,53 +575,77 @@ pub async fn create_github_pr(
         .ensure_container_exists(&workspace)
         .await?;
     let workspace_path = PathBuf::from(&container_ref);
-    let worktree_path = workspace_path.join(repo.name);
+    let worktree_path = workspace_path.join(&repo.name);
 
     match deployment
         .git()
         .check_remote_branch_exists(&repo_path, &target_branch)
     {
         Ok(false) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                CreatePrError::TargetBranchNotFound {
-                    branch: target_branch.clone(),
-                },
-            )));
+            return Err(ApiError::problem(CreatePrError::TargetBranchNotFound {
+                branch: target_branch.clone(),
+            }));
         }
         Err(GitServiceError::GitCLI(GitCliError::AuthFailed(_))) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                CreatePrError::GitCliNotLoggedIn,
-            )));
+            return Err(ApiError::problem(CreatePrError::GitCliNotLoggedIn));
         }
         Err(GitServiceError::GitCLI(GitCliError::NotAvailable)) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                CreatePrError::GitCliNotInstalled,
-            )));
+            return Err(ApiError::problem(CreatePrError::GitCliNotInstalled));
         }
         Err(e) => return Err(ApiError::GitService(e)),
         Ok(true) => {}
     }
 
-    // Push the branch to remote first (GitHub/GitLab agnostic)
-    if let Err(e) = deployment
-        .git()
-        .push_to_github(&worktree_path, &workspace.branch, false)
+    let violations = super::util::check_push_policies(&deployment, &workspace, &repo).await?;
+    if !violations.is_empty() {
+        return Err(ApiError::problem(CreatePrError::PolicyViolation {
+            violations,
+        }));
+    }
+    let findings =
+        super::util::check_push_secrets(&deployment, &workspace, &repo, &target_branch).await?;
+    if !findings.is_empty() {
+        return Err(ApiError::problem(CreatePrError::SecretsDetected {
+            findings,
+        }));
+    }
+    let findings =
+        super::util::check_pr_dependencies(&deployment, &workspace, &repo, &target_branch)
+            .await?;
+    if !findings.is_empty() {
+        return Err(ApiError::problem(
+            CreatePrError::DependencyPolicyViolation { findings },
+        ));
+    }
+
+    // Detect provider and create appropriate service
+    let provider = git_provider::create_provider(&repo_path)
+        .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
+    let (_, repo_id) = git_provider::detect_provider(&repo_path)
+        .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
+
+    // Push the branch to remote first, unless the provider pushes it when creating (Gerrit)
+    if !provider.pushes_on_create()
+        && let Err(e) = deployment
+            .git()
+            .push_to_github(&worktree_path, &workspace.branch, false)
     {
         tracing::error!("Failed to push branch to remote: {}", e);
         match e {
             GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => {
-                return Ok(ResponseJson(ApiResponse::error_with_data(
-                    CreatePrError::GitCliNotLoggedIn,
-                )));
+                return Err(ApiError::problem(CreatePrError::GitCliNotLoggedIn));
             }
             GitServiceError::GitCLI(GitCliError::NotAvailable) => {
-                return Ok(ResponseJson(ApiResponse::error_with_data(
-                    CreatePrError::GitCliNotInstalled,
-                )));
+                return Err(ApiError::problem(CreatePrError::GitCliNotInstalled));
             }
             _ => return Err(ApiError::GitService(e)),
         }
     }
+    deployment
+        .container()
+        .commit_statuses()
+        .queue_agent_run(workspace.id)
+        .await;
 
     let norm_target_branch_name = if matches!(
         deployment
@@ -281,20 +667,25 @@ pub async fn create_github_pr(
         target_branch
     };
     // Create the MR/PR using provider abstraction
+    let body = super::util::pr_body_with_coverage(
+        &deployment,
+        workspace.id,
+        workspace_repo.repo_id,
+        request.body.clone(),
+    )
+    .await;
+    // In a fork checkout the branch was pushed to the fork and the PR targets upstream
+    let fork = git_provider::detect_fork(&repo_path).ok().flatten();
     let pr_request = CreateMrRequest {
         title: request.title.clone(),
-        body: request.body.clone(),
+        body,
         head_branch: workspace.branch.clone(),
         base_branch: norm_target_branch_name.clone(),
         draft: request.draft,
+        head_repo: fork.map(|fork| fork.fork),
+        milestone: request.milestone.clone(),
     };
 
-    // Detect provider and create appropriate service
-    let provider = git_provider::create_provider(&repo_path)
-        .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
-    let (_, repo_id) = git_provider::detect_provider(&repo_path)
-        .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
-
     match provider.create_merge_request(&repo_id, &pr_request).await {
         Ok(pr_info) => {
             // Update the workspace with PR information
@@ -311,6 +702,20 @@ pub async fn create_github_pr(
                 tracing::error!("Failed to update workspace PR status: {}", e);
             }
 
+            // The PR exists either way; a failure here only leaves it to be merged by hand
+            if let Some(method) = request.auto_merge
+                && let Err(e) = provider
+                    .enable_auto_merge(&repo_id, pr_info.number as u64, method)
+                    .await
+            {
+                tracing::warn!(
+                    "Failed to enable auto-merge on MR/PR #{} for attempt {}: {}",
+                    pr_info.number,
+                    workspace.id,
+                    e
+                );
+            }
+
             // Auto-open PR/MR in browser
             if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
                 tracing::warn!("Failed to open MR/PR in browser: {}", e);
@@ -350,12 +755,12 @@ pub async fn create_github_pr(
                 e
             );
             match &e {
-                ProviderError::NotInstalled { .. } => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotInstalled),
-                )),
-                ProviderError::NotAuthenticated(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotLoggedIn),
-                )),
+                ProviderError::NotInstalled { .. } => {
+                    Err(ApiError::problem(CreatePrError::GithubCliNotInstalled))
+                }
+                ProviderError::NotAuthenticated(_) => {
+                    Err(ApiError::problem(CreatePrError::GithubCliNotLoggedIn))
+                }
                 _ => Err(ApiError::GitService(GitServiceError::InvalidRepository(e.to_string()))),
             }
         }
@@ -365,6 +770,7 @@ pub async fn create_github_pr(
 pub async fn attach_existing_pr(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
     Json(request): Json<AttachExistingPrRequest>,
 ) -> Result<ResponseJson<ApiResponse<AttachPrResponse>>, ApiError> {
     let pool = &deployment.db().pool;
@@ -391,6 +797,8 @@ pub async fn attach_existing_pr(
             pr_url: Some(pr_merge.pr_info.url.clone()),
             pr_number: Some(pr_merge.pr_info.number),
             pr_status: Some(pr_merge.pr_info.status.clone()),
+            review_state: super::util::fetch_review_state(&repo.path, pr_merge.pr_info.number)
+                .await,
         })));
     }
 
@@ -408,6 +816,8 @@ pub async fn attach_existing_pr(
 
     // Take the first MR/PR (prefer open, but also accept merged/closed)
     if let Some(pr_info) = prs.into_iter().next() {
+        let pr_info = super::util::with_review_state(provider.as_ref(), &repo_id, pr_info).await;
+
         // Save PR info to database
         let merge = Merge::create_pr(
             pool,
@@ -435,23 +845,7 @@ pub async fn attach_existing_pr(
 
         // If MR/PR is merged, mark task as done
         if matches!(merge_status, MergeStatus::Merged) {
-            Task::update_status(pool, task.id, TaskStatus::Done).await?;
-
-            // Try broadcast update to other users in organization
-            if let Ok(publisher) = deployment.share_publisher() {
-                if let Err(err) = publisher.update_shared_task_by_id(task.id).await {
-                    tracing::warn!(
-                        ?err,
-                        "Failed to propagate shared task update for {}",
-                        task.id
-                    );
-                }
-            } else {
-                tracing::debug!(
-                    "Share publisher unavailable; skipping remote update for {}",
-                    task.id
-                );
-            }
+            mark_task_done(&deployment, &task, &actor).await?;
         }
 
         Ok(ResponseJson(ApiResponse::success(AttachPrResponse {
@@ -459,6 +853,7 @@ pub async fn attach_existing_pr(
             pr_url: Some(pr_info.url),
             pr_number: Some(pr_info.number as i64),
             pr_status: Some(merge_status),
+            review_state: pr_info.review_state,
         })))
     } else {
         Ok(ResponseJson(ApiResponse::success(AttachPrResponse {
@@ -466,38 +861,57 @@ pub async fn attach_existing_pr(
             pr_url: None,
             pr_number: None,
             pr_status: None,
+            review_state: None,
         })))
     }
 }
 
-pub async fn get_pr_comments(
-    Extension(workspace): Extension<Workspace>,
-    State(deployment): State<DeploymentImpl>,
-    Query(query): Query<GetPrCommentsQuery>,
-) -> Result<ResponseJson<ApiResponse<PrCommentsResponse, GetPrCommentsError>>, ApiError> {
+/// Mark the task of a merged MR/PR as done
+async fn mark_task_done(
+    deployment: &DeploymentImpl,
+    task: &Task,
+    actor: &TaskActor,
+) -> Result<(), ApiError> {
+    deployment
+        .task_events()
+        .set_status(task.id, TaskStatus::Done, actor)
+        .await?;
+    Ok(())
+}
+
+/// The MR/PR attached to one repository of a workspace, with the provider hosting it
+struct AttachedPr {
+    task: Task,
+    pr_merge: PrMerge,
+    provider: Box<dyn GitProvider>,
+    repo_id: RepoIdentifier,
+    repo_path: PathBuf,
+}
+
+/// Look up the MR/PR attached to a repository of the workspace; None when there is none
+async fn find_attached_pr(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<Option<AttachedPr>, ApiError> {
     let pool = &deployment.db().pool;
 
-    // Look up the specific repo using the multi-repo pattern
-    let workspace_repo =
-        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
 
-    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+    let workspace_repo = WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo_id)
         .await?
         .ok_or(RepoError::NotFound)?;
 
-    // Find the merge/PR for this specific repo
-    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id).await?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
 
-    // Ensure there's an attached PR/MR for this repo
-    let pr_info = match merges.into_iter().next() {
-        Some(Merge::Pr(pr_merge)) => pr_merge.pr_info,
-        _ => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                GetPrCommentsError::NoPrAttached,
-            )));
-        }
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo_id).await?;
+    let Some(Merge::Pr(pr_merge)) = merges.into_iter().next() else {
+        return Ok(None);
     };
 
     // Detect provider and create appropriate service
@@ -506,30 +920,958 @@ pub async fn get_pr_comments(
     let (_, repo_id) = git_provider::detect_provider(&repo.path)
         .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
 
-    // Fetch comments from provider
-    match provider
-        .get_comments(&repo_id, pr_info.number as u64)
+    Ok(Some(AttachedPr {
+        task,
+        pr_merge,
+        provider,
+        repo_id,
+        repo_path: repo.path,
+    }))
+}
+
+/// Response for a provider failure of a MR/PR action
+pub fn pr_action_failed<T>(
+    e: ProviderError,
+) -> Result<ResponseJson<ApiResponse<T, PrActionError>>, ApiError> {
+    match &e {
+        ProviderError::NotInstalled { .. } => {
+            Err(ApiError::problem(PrActionError::GithubCliNotInstalled))
+        }
+        ProviderError::NotAuthenticated(_) => {
+            Err(ApiError::problem(PrActionError::GithubCliNotLoggedIn))
+        }
+        ProviderError::NotSupported { .. } => {
+            Err(ApiError::problem(PrActionError::ProviderNotSupported))
+        }
+        _ if e.is_unreachable() => Err(ApiError::problem(PrActionError::ProviderUnreachable)),
+        _ => Err(ApiError::GitService(GitServiceError::InvalidRepository(e.to_string()))),
+    }
+}
+
+/// Record the MR/PR status after an action together with the task status it implies, in one
+/// transaction so the board never shows one without the other
+async fn record_pr_action(
+    deployment: &DeploymentImpl,
+    attached: &AttachedPr,
+    pr_info: &PrInfo,
+    task_status: Option<TaskStatus>,
+    actor: &TaskActor,
+) -> Result<MergeStatus, ApiError> {
+    let merge_status: MergeStatus = pr_info.state.into();
+
+    let mut tx = deployment.db().pool.begin().await?;
+    Merge::update_status(
+        &mut *tx,
+        attached.pr_merge.id,
+        merge_status.clone(),
+        pr_info.merge_commit_sha.clone(),
+    )
+    .await?;
+    if let Some(status) = &task_status {
+        Task::update_status(&mut *tx, attached.task.id, status.clone()).await?;
+        if let Some(task) = Task::find_by_id(&mut *tx, attached.task.id).await? {
+            deployment
+                .task_events()
+                .updated_in(&mut *tx, &attached.task, &task, actor)
+                .await?;
+        }
+    }
+    tx.commit().await?;
+    Ok(merge_status)
+}
+
+/// Merge the attached MR/PR on the provider, then record the result and mark the task done
+pub async fn merge_pr(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
+    Json(request): Json<MergePrRequest>,
+) -> Result<ResponseJson<ApiResponse<PrActionResponse, PrActionError>>, ApiError> {
+    permissions::require_workspace_permission(&deployment, &workspace, ProjectPermission::Merge)
+        .await?;
+    let Some(attached) = find_attached_pr(&deployment, &workspace, request.repo_id).await? else {
+        return Err(ApiError::problem(PrActionError::NoPrAttached));
+    };
+    let pr_number = attached.pr_merge.pr_info.number;
+    if !matches!(attached.pr_merge.pr_info.status, MergeStatus::Open) {
+        return Err(ApiError::problem(PrActionError::PrNotOpen {
+            status: attached.pr_merge.pr_info.status,
+        }));
+    }
+
+    let pr_info = match attached
+        .provider
+        .merge_mr(&attached.repo_id, pr_number as u64, request.method)
         .await
     {
-        Ok(comments) => Ok(ResponseJson(ApiResponse::success(PrCommentsResponse {
-            comments,
-        }))),
+        Ok(pr_info) => pr_info,
         Err(e) => {
             tracing::error!(
-                "Failed to fetch MR/PR comments for attempt {}, number #{}: {}",
+                "Failed to merge MR/PR #{} for attempt {}: {}",
+                pr_number,
                 workspace.id,
-                pr_info.number,
                 e
             );
-            match &e {
-                ProviderError::NotInstalled { .. } => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotInstalled),
-                )),
-                ProviderError::NotAuthenticated(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotLoggedIn),
-                )),
-                _ => Err(ApiError::GitService(GitServiceError::InvalidRepository(e.to_string()))),
-            }
+            return pr_action_failed(e);
         }
-    }
+    };
+
+    // The provider may accept the merge without completing it yet, e.g. in a merge queue; the PR
+    // monitor picks up the merge later in that case
+    let task_status =
+        matches!(pr_info.state, PrState::Merged).then_some(TaskStatus::Done);
+    let pr_status = record_pr_action(&deployment, &attached, &pr_info, task_status, &actor).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_merged",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "method": request.method,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(PrActionResponse {
+        pr_status,
+        merge_commit_sha: pr_info.merge_commit_sha,
+    })))
+}
+
+/// Close the attached MR/PR without merging it, optionally abandoning the task with it
+pub async fn close_pr(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
+    Json(request): Json<ClosePrRequest>,
+) -> Result<ResponseJson<ApiResponse<PrActionResponse, PrActionError>>, ApiError> {
+    let Some(attached) = find_attached_pr(&deployment, &workspace, request.repo_id).await? else {
+        return Err(ApiError::problem(PrActionError::NoPrAttached));
+    };
+    let pr_number = attached.pr_merge.pr_info.number;
+    if !matches!(attached.pr_merge.pr_info.status, MergeStatus::Open) {
+        return Err(ApiError::problem(PrActionError::PrNotOpen {
+            status: attached.pr_merge.pr_info.status,
+        }));
+    }
+
+    let pr_info = match attached
+        .provider
+        .close_mr(&attached.repo_id, pr_number as u64)
+        .await
+    {
+        Ok(pr_info) => pr_info,
+        Err(e) => {
+            tracing::error!(
+                "Failed to close MR/PR #{} for attempt {}: {}",
+                pr_number,
+                workspace.id,
+                e
+            );
+            return pr_action_failed(e);
+        }
+    };
+
+    let task_status = request.abandon_task.then_some(TaskStatus::Cancelled);
+    let pr_status = record_pr_action(&deployment, &attached, &pr_info, task_status, &actor).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_closed",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "abandon_task": request.abandon_task,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(PrActionResponse {
+        pr_status,
+        merge_commit_sha: pr_info.merge_commit_sha,
+    })))
+}
+
+/// Reopen the attached MR/PR. A task abandoned along with it goes back to review.
+pub async fn reopen_pr(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
+    Json(request): Json<ReopenPrRequest>,
+) -> Result<ResponseJson<ApiResponse<PrActionResponse, PrActionError>>, ApiError> {
+    let Some(attached) = find_attached_pr(&deployment, &workspace, request.repo_id).await? else {
+        return Err(ApiError::problem(PrActionError::NoPrAttached));
+    };
+    let pr_number = attached.pr_merge.pr_info.number;
+    if !matches!(attached.pr_merge.pr_info.status, MergeStatus::Closed) {
+        return Err(ApiError::problem(PrActionError::PrNotClosed {
+            status: attached.pr_merge.pr_info.status,
+        }));
+    }
+
+    let pr_info = match attached
+        .provider
+        .reopen_mr(&attached.repo_id, pr_number as u64)
+        .await
+    {
+        Ok(pr_info) => pr_info,
+        Err(e) => {
+            tracing::error!(
+                "Failed to reopen MR/PR #{} for attempt {}: {}",
+                pr_number,
+                workspace.id,
+                e
+            );
+            return pr_action_failed(e);
+        }
+    };
+
+    let task_status = matches!(attached.task.status, TaskStatus::Cancelled)
+        .then_some(TaskStatus::InReview);
+    let pr_status = record_pr_action(&deployment, &attached, &pr_info, task_status, &actor).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_reopened",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(PrActionResponse {
+        pr_status,
+        merge_commit_sha: pr_info.merge_commit_sha,
+    })))
+}
+
+/// Flip the attached MR/PR between draft and ready for review
+pub async fn set_pr_draft(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
+    Json(request): Json<SetPrDraftRequest>,
+) -> Result<ResponseJson<ApiResponse<PrActionResponse, PrActionError>>, ApiError> {
+    let Some(attached) = find_attached_pr(&deployment, &workspace, request.repo_id).await? else {
+        return Err(ApiError::problem(PrActionError::NoPrAttached));
+    };
+    let pr_number = attached.pr_merge.pr_info.number;
+    if !matches!(attached.pr_merge.pr_info.status, MergeStatus::Open) {
+        return Err(ApiError::problem(PrActionError::PrNotOpen {
+            status: attached.pr_merge.pr_info.status,
+        }));
+    }
+
+    let pr_info = match attached
+        .provider
+        .set_draft(&attached.repo_id, pr_number as u64, request.draft)
+        .await
+    {
+        Ok(pr_info) => pr_info,
+        Err(e) => {
+            tracing::error!(
+                "Failed to update draft state of MR/PR #{} for attempt {}: {}",
+                pr_number,
+                workspace.id,
+                e
+            );
+            return pr_action_failed(e);
+        }
+    };
+
+    let task_status = (!request.draft && matches!(attached.task.status, TaskStatus::InProgress))
+        .then_some(TaskStatus::InReview);
+    let pr_status = record_pr_action(&deployment, &attached, &pr_info, task_status, &actor).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_draft_toggled",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "draft": request.draft,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(PrActionResponse {
+        pr_status,
+        merge_commit_sha: pr_info.merge_commit_sha,
+    })))
+}
+
+/// Change the title, description or target branch of the attached MR/PR through its provider
+pub async fn update_pr(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
+    Json(request): Json<UpdatePrRequest>,
+) -> Result<ResponseJson<ApiResponse<PrActionResponse, PrActionError>>, ApiError> {
+    Validator::check(&request).finish()?;
+    permissions::require_workspace_permission(&deployment, &workspace, ProjectPermission::CreatePr)
+        .await?;
+    let Some(attached) = find_attached_pr(&deployment, &workspace, request.repo_id).await? else {
+        return Err(ApiError::problem(PrActionError::NoPrAttached));
+    };
+    let pr_number = attached.pr_merge.pr_info.number;
+    if !matches!(attached.pr_merge.pr_info.status, MergeStatus::Open) {
+        return Err(ApiError::problem(PrActionError::PrNotOpen {
+            status: attached.pr_merge.pr_info.status,
+        }));
+    }
+
+    let update = UpdateMrRequest {
+        title: request.title.clone(),
+        body: request.body.clone(),
+        base_branch: request.base_branch.clone(),
+    };
+    let pr_info = match attached
+        .provider
+        .update_mr(&attached.repo_id, pr_number as u64, &update)
+        .await
+    {
+        Ok(pr_info) => pr_info,
+        Err(e) => {
+            tracing::error!(
+                "Failed to update MR/PR #{} for attempt {}: {}",
+                pr_number,
+                workspace.id,
+                e
+            );
+            return pr_action_failed(e);
+        }
+    };
+
+    if let Some(base_branch) = &request.base_branch {
+        let pool = &deployment.db().pool;
+        Merge::update_target_branch(pool, attached.pr_merge.id, base_branch).await?;
+        WorkspaceRepo::update_target_branch(pool, workspace.id, request.repo_id, base_branch)
+            .await?;
+    }
+    let pr_status = record_pr_action(&deployment, &attached, &pr_info, None, &actor).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_updated",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "title": request.title.is_some(),
+                "body": request.body.is_some(),
+                "base_branch": request.base_branch.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(PrActionResponse {
+        pr_status,
+        merge_commit_sha: pr_info.merge_commit_sha,
+    })))
+}
+
+/// Open milestones of a repository of the workspace, to offer when creating an MR/PR
+pub async fn list_milestones(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ListMilestonesQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Milestone>, PrActionError>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let provider = git_provider::create_provider(&repo.path)
+        .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
+    let (_, repo_id) = git_provider::detect_provider(&repo.path)
+        .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
+
+    match provider.list_milestones(&repo_id).await {
+        Ok(milestones) => Ok(ResponseJson(ApiResponse::success(milestones))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to list milestones of {} for attempt {}: {}",
+                repo.name,
+                workspace.id,
+                e
+            );
+            pr_action_failed(e)
+        }
+    }
+}
+
+/// Protection of the branch an MR/PR would target, so creating one can warn up front that it
+/// won't be mergeable right away and suggest auto-merge
+pub async fn get_branch_protection(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<BranchProtectionQuery>,
+) -> Result<ResponseJson<ApiResponse<BranchProtection, PrActionError>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let target_branch = query.target_branch.unwrap_or(workspace_repo.target_branch);
+    // Remote branches are named {remote}/{branch} locally
+    let target_branch = if matches!(
+        deployment
+            .git()
+            .find_branch_type(&repo.path, &target_branch)?,
+        BranchType::Remote
+    ) {
+        let remote = deployment
+            .git()
+            .get_remote_name_from_branch_name(&repo.path, &target_branch)?;
+        target_branch
+            .strip_prefix(&format!("{remote}/"))
+            .unwrap_or(&target_branch)
+            .to_string()
+    } else {
+        target_branch
+    };
+
+    let provider = git_provider::create_provider(&repo.path)
+        .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
+    let (_, repo_id) = git_provider::detect_provider(&repo.path)
+        .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
+
+    match provider
+        .get_branch_protection(&repo_id, &target_branch)
+        .await
+    {
+        Ok(protection) => Ok(ResponseJson(ApiResponse::success(protection))),
+        Err(e) => {
+            tracing::warn!(
+                "Failed to read protection of {} in {} for attempt {}: {}",
+                target_branch,
+                repo.name,
+                workspace.id,
+                e
+            );
+            pr_action_failed(e)
+        }
+    }
+}
+
+/// Status, checks, reviews and comments of the attached MR/PR, in as few provider requests as
+/// the provider allows: a single GraphQL query on GitHub. While the provider is unreachable, the
+/// details last fetched are returned.
+pub async fn get_pr_details(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<PrDetailsQuery>,
+) -> Result<ResponseJson<ApiResponse<PrDetailsResponse, PrActionError>>, ApiError> {
+    let Some(attached) = find_attached_pr(&deployment, &workspace, query.repo_id).await? else {
+        return Err(ApiError::problem(PrActionError::NoPrAttached));
+    };
+    let pr_number = attached.pr_merge.pr_info.number;
+
+    let key = OfflineService::cache_key(&attached.repo_id, pr_number as u64, "details");
+    let fetched = match deployment
+        .offline()
+        .read(
+            attached.provider.provider_type(),
+            key,
+            attached
+                .provider
+                .get_mr_details(&attached.repo_id, pr_number as u64),
+        )
+        .await
+    {
+        Ok(fetched) => fetched,
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch MR/PR #{} for attempt {}: {}",
+                pr_number,
+                workspace.id,
+                e
+            );
+            return pr_action_failed(e);
+        }
+    };
+
+    let details = fetched.value;
+    Ok(ResponseJson(ApiResponse::success(PrDetailsResponse {
+        pr_number,
+        pr_url: details.info.url,
+        pr_status: details.info.state.into(),
+        review_state: details.info.review_state,
+        checks: details.checks,
+        reviews: details.reviews,
+        comments: details.comments,
+        cached_at: fetched.cached_at,
+    })))
+}
+
+/// Most of a CI log returned by [`get_pr_check_logs`], so it fits in a follow-up prompt
+const MAX_CHECK_LOG_BYTES: usize = 64 * 1024;
+
+/// Log of a failed check on the attached MR/PR, to hand to the agent in a follow-up that fixes
+/// the build: GitHub Actions job logs, or GitLab job traces
+pub async fn get_pr_check_logs(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<CheckLogsQuery>,
+) -> Result<ResponseJson<ApiResponse<CheckLogsResponse, PrActionError>>, ApiError> {
+    let Some(attached) = find_attached_pr(&deployment, &workspace, query.repo_id).await? else {
+        return Err(ApiError::problem(PrActionError::NoPrAttached));
+    };
+    let pr_number = attached.pr_merge.pr_info.number;
+
+    let log = match attached
+        .provider
+        .get_check_logs(&attached.repo_id, pr_number as u64, &query.check_id)
+        .await
+    {
+        Ok(log) => log,
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch the log of check {} on MR/PR #{} for attempt {}: {}",
+                query.check_id,
+                pr_number,
+                workspace.id,
+                e
+            );
+            return pr_action_failed(e);
+        }
+    };
+
+    let mut start = log.len().saturating_sub(MAX_CHECK_LOG_BYTES);
+    while !log.is_char_boundary(start) {
+        start += 1;
+    }
+    Ok(ResponseJson(ApiResponse::success(CheckLogsResponse {
+        log: log[start..].to_string(),
+        truncated: start > 0,
+    })))
+}
+
+/// Post a general comment on the attached MR/PR, e.g. a status update about the attempt. It is
+/// queued while the provider is unreachable.
+pub async fn post_pr_comment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<PostPrCommentRequest>,
+) -> Result<ResponseJson<ApiResponse<PostedComment, PrActionError>>, ApiError> {
+    Validator::check(&request).finish()?;
+    let Some(attached) = find_attached_pr(&deployment, &workspace, request.repo_id).await? else {
+        return Err(ApiError::problem(PrActionError::NoPrAttached));
+    };
+    let pr_number = attached.pr_merge.pr_info.number;
+
+    let comment = match deployment
+        .offline()
+        .write(
+            attached.provider.as_ref(),
+            &attached.repo_path,
+            &attached.repo_id,
+            pr_number as u64,
+            ProviderWrite::Comment {
+                body: request.body.clone(),
+            },
+        )
+        .await
+    {
+        Ok(comment) => comment,
+        Err(e) => {
+            tracing::error!(
+                "Failed to comment on MR/PR #{} for attempt {}: {}",
+                pr_number,
+                workspace.id,
+                e
+            );
+            return pr_action_failed(e);
+        }
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_comment_posted",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "queued": matches!(comment, PostedComment::Queued { .. }),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+/// Post an inline review comment on a line of the attached MR/PR diff; it is queued while the
+/// provider is unreachable
+pub async fn post_pr_review_comment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<PostPrReviewCommentRequest>,
+) -> Result<ResponseJson<ApiResponse<PostedComment, PrActionError>>, ApiError> {
+    Validator::check(&request).finish()?;
+    let Some(attached) = find_attached_pr(&deployment, &workspace, request.repo_id).await? else {
+        return Err(ApiError::problem(PrActionError::NoPrAttached));
+    };
+    let pr_number = attached.pr_merge.pr_info.number;
+
+    let comment = match deployment
+        .offline()
+        .write(
+            attached.provider.as_ref(),
+            &attached.repo_path,
+            &attached.repo_id,
+            pr_number as u64,
+            ProviderWrite::ReviewComment {
+                path: request.path.trim().to_string(),
+                line: request.line as u64,
+                body: request.body.clone(),
+            },
+        )
+        .await
+    {
+        Ok(comment) => comment,
+        Err(e) => {
+            tracing::error!(
+                "Failed to comment on {}:{} of MR/PR #{} for attempt {}: {}",
+                request.path,
+                request.line,
+                pr_number,
+                workspace.id,
+                e
+            );
+            return pr_action_failed(e);
+        }
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_review_comment_posted",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "queued": matches!(comment, PostedComment::Queued { .. }),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+/// Reply to a comment thread of the attached MR/PR; the reply is returned as posted, or queued
+/// while the provider is unreachable
+pub async fn reply_to_pr_comment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<ReplyToPrCommentRequest>,
+) -> Result<ResponseJson<ApiResponse<PostedComment, PrActionError>>, ApiError> {
+    Validator::check(&request).finish()?;
+    let Some(attached) = find_attached_pr(&deployment, &workspace, request.repo_id).await? else {
+        return Err(ApiError::problem(PrActionError::NoPrAttached));
+    };
+    let pr_number = attached.pr_merge.pr_info.number;
+
+    let reply = match deployment
+        .offline()
+        .write(
+            attached.provider.as_ref(),
+            &attached.repo_path,
+            &attached.repo_id,
+            pr_number as u64,
+            ProviderWrite::Reply {
+                thread_id: request.thread_id.clone(),
+                body: request.body.clone(),
+            },
+        )
+        .await
+    {
+        Ok(reply) => reply,
+        Err(e) => {
+            tracing::error!(
+                "Failed to reply to comment {} of MR/PR #{} for attempt {}: {}",
+                request.thread_id,
+                pr_number,
+                workspace.id,
+                e
+            );
+            return pr_action_failed(e);
+        }
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_comment_replied",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "queued": matches!(reply, PostedComment::Queued { .. }),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(reply)))
+}
+
+/// Mark a comment fetched from the attached MR/PR addressed, or not addressed again
+pub async fn mark_pr_comment_addressed(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<MarkPrCommentAddressedRequest>,
+) -> Result<ResponseJson<ApiResponse<PrComment, PrActionError>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id).await?;
+    let Some(Merge::Pr(pr_merge)) = merges.into_iter().next() else {
+        return Err(ApiError::problem(PrActionError::NoPrAttached));
+    };
+    let comment = PrComment::find_by_merge_and_key(pool, pr_merge.id, &request.comment_key)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+
+    if let Some(execution_process_id) = request.execution_process_id {
+        let process = ExecutionProcess::find_by_id(pool, execution_process_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let session = Session::find_by_id(pool, process.session_id).await?;
+        if session.is_none_or(|session| session.workspace_id != workspace.id) {
+            return Err(ApiError::BadRequest(
+                "The execution process belongs to another workspace".to_string(),
+            ));
+        }
+    }
+
+    let (addressed_at, execution_process_id) = if request.addressed {
+        (Some(Utc::now()), request.execution_process_id)
+    } else {
+        (None, None)
+    };
+    let comment =
+        PrComment::set_addressed(pool, comment.id, addressed_at, execution_process_id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_comment_addressed",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "addressed": request.addressed,
+                "by_execution": execution_process_id.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+pub async fn get_pr_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<GetPrCommentsQuery>,
+    Query(page): Query<PageQuery>,
+) -> Result<ResponseJson<ApiResponse<PrCommentsResponse, GetPrCommentsError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    // Look up the specific repo using the multi-repo pattern
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    // Find the merge/PR for this specific repo
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id).await?;
+
+    // Ensure there's an attached PR/MR for this repo
+    let pr_merge = match merges.into_iter().next() {
+        Some(Merge::Pr(pr_merge)) => pr_merge,
+        _ => {
+            return Err(ApiError::problem(GetPrCommentsError::NoPrAttached));
+        }
+    };
+
+    // Detect provider and create appropriate service
+    let provider = git_provider::create_provider(&repo.path)
+        .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
+    let (_, repo_id) = git_provider::detect_provider(&repo.path)
+        .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
+
+    // Fetch comments from provider, or the last fetched while it is unreachable
+    let number = pr_merge.pr_info.number as u64;
+    let (what, fetch) = match query.since {
+        Some(since) => (
+            format!("comments?since={}", since.timestamp()),
+            provider.get_comments_since(&repo_id, number, since),
+        ),
+        None => (
+            "comments".to_string(),
+            provider.get_comments(&repo_id, number),
+        ),
+    };
+    let key = OfflineService::cache_key(&repo_id, number, &what);
+    match deployment
+        .offline()
+        .read(provider.provider_type(), key, fetch)
+        .await
+    {
+        Ok(fetched) => {
+            let addressed =
+                super::util::record_pr_comments(pool, pr_merge.id, &fetched.value).await?;
+            let filter = super::util::comment_filter(
+                query.since,
+                query.exclude_bots,
+                query.authors.as_deref(),
+            );
+            let comments = super::util::filter_pr_comments(
+                fetched.value,
+                &filter,
+                query.only_unresolved,
+                &addressed,
+            );
+            let (comments, pagination) = page.paginate(comments, UnifiedComment::key)?;
+            Ok(ResponseJson(ApiResponse::success_page(
+                PrCommentsResponse {
+                    comments,
+                    cached_at: fetched.cached_at,
+                    addressed,
+                },
+                pagination,
+            )))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch MR/PR comments for attempt {}, number #{}: {}",
+                workspace.id,
+                pr_merge.pr_info.number,
+                e
+            );
+            match &e {
+                ProviderError::NotInstalled { .. } => {
+                    Err(ApiError::problem(GetPrCommentsError::GithubCliNotInstalled))
+                }
+                ProviderError::NotAuthenticated(_) => {
+                    Err(ApiError::problem(GetPrCommentsError::GithubCliNotLoggedIn))
+                }
+                _ if e.is_unreachable() => {
+                    Err(ApiError::problem(GetPrCommentsError::ProviderUnreachable))
+                }
+                _ => Err(ApiError::GitService(GitServiceError::InvalidRepository(e.to_string()))),
+            }
+        }
+    }
+}
+
+/// Commit the ```suggestion blocks of review comments on the attached MR/PR into the workspace
+/// worktree, either those of one comment or all of them
+pub async fn apply_pr_suggestions(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<ApplySuggestionsRequest>,
+) -> Result<ResponseJson<ApiResponse<ApplySuggestionsResponse, ApplySuggestionsError>>, ApiError> {
+    permissions::require_workspace_permission(
+        &deployment,
+        &workspace,
+        ProjectPermission::StartExecution,
+    )
+    .await?;
+    let Some(attached) = find_attached_pr(&deployment, &workspace, request.repo_id).await? else {
+        return Err(ApiError::problem(ApplySuggestionsError::NoPrAttached));
+    };
+    let pr_number = attached.pr_merge.pr_info.number;
+
+    let comments = match attached
+        .provider
+        .get_comments(&attached.repo_id, pr_number as u64)
+        .await
+    {
+        Ok(comments) => comments,
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch MR/PR comments for attempt {}, number #{}: {}",
+                workspace.id,
+                pr_number,
+                e
+            );
+            return match &e {
+                ProviderError::NotInstalled { .. } => Err(ApiError::problem(
+                    ApplySuggestionsError::GithubCliNotInstalled,
+                )),
+                ProviderError::NotAuthenticated(_) => Err(ApiError::problem(
+                    ApplySuggestionsError::GithubCliNotLoggedIn,
+                )),
+                _ => Err(ApiError::GitService(GitServiceError::InvalidRepository(
+                    e.to_string(),
+                ))),
+            };
+        }
+    };
+
+    let suggestions: Vec<_> = comments
+        .iter()
+        .filter(|comment| match (request.comment_id, comment) {
+            (None, _) => true,
+            (Some(wanted), UnifiedComment::Review { id, .. }) => *id == wanted,
+            (Some(_), UnifiedComment::General { .. }) => false,
+        })
+        .flat_map(suggestions::suggestions_from_comment)
+        .collect();
+    if suggestions.is_empty() {
+        return Err(ApiError::problem(ApplySuggestionsError::NoSuggestions));
+    }
+    let mut applied: Vec<i64> = suggestions.iter().map(|s| s.comment_id).collect();
+    applied.dedup();
+
+    let repo = Repo::find_by_id(&deployment.db().pool, request.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = PathBuf::from(&container_ref).join(&repo.name);
+
+    let message = if suggestions.len() == 1 {
+        "Apply suggestion from code review".to_string()
+    } else {
+        format!("Apply {} suggestions from code review", suggestions.len())
+    };
+    let committed = match suggestions::apply_suggestions(
+        deployment.git(),
+        &worktree_path,
+        &suggestions,
+        &message,
+    ) {
+        Ok(committed) => committed,
+        Err(SuggestionError::Outdated { path, line })
+        | Err(SuggestionError::OutOfRange { path, line }) => {
+            return Err(ApiError::problem(
+                ApplySuggestionsError::SuggestionOutdated { path, line },
+            ));
+        }
+        Err(SuggestionError::Overlapping { path }) => {
+            return Err(ApiError::problem(
+                ApplySuggestionsError::SuggestionsOverlap { path },
+            ));
+        }
+        Err(SuggestionError::InvalidPath(path)) => {
+            return Err(ApiError::BadRequest(format!(
+                "Suggestion path is outside the repository: {path}"
+            )));
+        }
+        Err(SuggestionError::Git(e)) => return Err(ApiError::GitService(e)),
+        Err(SuggestionError::Io(e)) => return Err(ApiError::Io(e)),
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_suggestions_applied",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "suggestions": suggestions.len(),
+                "single_comment": request.comment_id.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        ApplySuggestionsResponse { applied, committed },
+    )))
 }
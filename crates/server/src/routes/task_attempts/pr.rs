@@ -3,14 +3,20 @@ use std::path::PathBuf;
 use axum::{
     Extension, Json,
     extract::{Query, State},
+    http::StatusCode,
     response::Json as ResponseJson,
 };
+use chrono::{DateTime, Utc};
 use db::models::{
+    dependency_report::DependencyFinding,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     merge::{Merge, MergeStatus},
+    policy_violation_report::PolicyViolation,
+    pr_comment::AddressedPrComment,
     repo::{Repo, RepoError},
+    routing_decision::TurnPurpose,
     session::{CreateSession, Session},
-    task::{Task, TaskStatus},
+    task::TaskStatus,
     workspace::{Workspace, WorkspaceError},
     workspace_repo::WorkspaceRepo,
 };
@@ -24,13 +30,25 @@ use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
     git::{GitCliError, GitServiceError},
-    git_provider::{self, CreateMrRequest, ProviderError, UnifiedComment},
+    git_provider::{self, CreateMrRequest, ProviderError, ReviewState, UnifiedComment},
+    offline::OfflineService,
+    secret_scan::SecretFinding,
+    task_events::TaskActor,
 };
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{
+    api::projects::ProjectPermission,
+    response::{ApiResponse, PageQuery},
+};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    error::{ApiError, ProblemType},
+    middleware::service_accounts::Actor,
+    permissions,
+    validation::{Validate, Validator},
+};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct CreateGitHubPrRequest {
@@ -41,6 +59,22 @@ pub struct CreateGitHubPrRequest {
     pub repo_id: Uuid,
     #[serde(default)]
     pub auto_generate_description: bool,
+    /// Title of a milestone to assign, from the repository's milestones
+    #[serde(default)]
+    pub milestone: Option<String>,
+}
+
+impl Validate for CreateGitHubPrRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.title("title", &self.title);
+        v.body("body", self.body.as_deref());
+        if let Some(target_branch) = &self.target_branch {
+            v.branch_name("target_branch", target_branch);
+        }
+        if let Some(milestone) = &self.milestone {
+            v.title("milestone", milestone);
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -52,6 +86,24 @@ pub enum CreatePrError {
     GitCliNotLoggedIn,
     GitCliNotInstalled,
     TargetBranchNotFound { branch: String },
+    PolicyViolation { violations: Vec<PolicyViolation> },
+    SecretsDetected { findings: Vec<SecretFinding> },
+    DependencyPolicyViolation { findings: Vec<DependencyFinding> },
+}
+
+impl ProblemType for CreatePrError {
+    fn status(&self) -> StatusCode {
+        match self {
+            CreatePrError::GithubCliNotInstalled
+            | CreatePrError::GithubCliNotLoggedIn
+            | CreatePrError::GitCliNotLoggedIn
+            | CreatePrError::GitCliNotInstalled => StatusCode::FAILED_DEPENDENCY,
+            CreatePrError::TargetBranchNotFound { .. }
+            | CreatePrError::PolicyViolation { .. }
+            | CreatePrError::SecretsDetected { .. }
+            | CreatePrError::DependencyPolicyViolation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -60,6 +112,8 @@ pub struct AttachPrResponse {
     pub pr_url: Option<String>,
     pub pr_number: Option<i64>,
     pub pr_status: Option<MergeStatus>,
+    /// Summary of the reviews, when the provider reports them
+    pub review_state: Option<ReviewState>,
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -70,6 +124,11 @@ pub struct AttachExistingPrRequest {
 #[derive(Debug, Serialize, TS)]
 pub struct PrCommentsResponse {
     pub comments: Vec<UnifiedComment>,
+    /// Set when the provider is unreachable and these are the comments last fetched, to when
+    /// they were fetched
+    pub cached_at: Option<DateTime<Utc>>,
+    /// Comments of the PR marked addressed, on any page
+    pub addressed: Vec<AddressedPrComment>,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -79,25 +138,52 @@ pub enum GetPrCommentsError {
     NoPrAttached,
     GithubCliNotInstalled,
     GithubCliNotLoggedIn,
+    /// The provider can't be reached and no comments were fetched before
+    ProviderUnreachable,
+}
+
+impl ProblemType for GetPrCommentsError {
+    fn status(&self) -> StatusCode {
+        match self {
+            GetPrCommentsError::NoPrAttached => StatusCode::NOT_FOUND,
+            GetPrCommentsError::GithubCliNotInstalled
+            | GetPrCommentsError::GithubCliNotLoggedIn => StatusCode::FAILED_DEPENDENCY,
+            GetPrCommentsError::ProviderUnreachable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, TS)]
 pub struct GetPrCommentsQuery {
     pub repo_id: Uuid,
+    /// Only comments created at or after this time, asked of the provider when it can filter
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    /// Leave out the comments of bots, like CI reports
+    #[serde(default)]
+    pub exclude_bots: bool,
+    /// Comma-separated usernames to keep the comments of
+    #[serde(default)]
+    pub authors: Option<String>,
+    /// Leave out the comments marked addressed
+    #[serde(default)]
+    pub only_unresolved: bool,
 }
 
-pub const DEFAULT_PR_DESCRIPTION_PROMPT: &str = r#"Update the GitHub PR that was just created with a better title and description.
+pub const DEFAULT_PR_DESCRIPTION_PROMPT: &str = r#"Update the pull request that was just created with a better title and description.
 The PR number is #{pr_number} and the URL is {pr_url}.
 
 Analyze the changes in this branch and write:
-1. A concise, descriptive title that summarizes the changes, postfixed with "(Vibe Kanban)"
+1. A concise, descriptive title that summarizes the changes{title_branding}
 2. A detailed description that explains:
    - What changes were made
    - Why they were made (based on the task context)
-   - Any important implementation details
-   - At the end, include a note: "This PR was written using [Vibe Kanban](https://vibekanban.com)"
+   - Any important implementation details{footer_branding}
+
+Write the title and description in {language}.
 
-Use `gh pr edit` to update the PR."#;
+Use the `update_pull_request` tool of the vibe-kanban MCP server to update the PR, with the
+workspace and repository ids from `get_context`."#;
 
 async fn trigger_pr_description_follow_up(
     deployment: &DeploymentImpl,
@@ -116,6 +202,7 @@ async fn trigger_pr_description_follow_up(
     let prompt = prompt_template
         .replace("{pr_number}", &pr_number.to_string())
         .replace("{pr_url}", pr_url);
+    let prompt = config.pr_branding.apply(&prompt);
 
     drop(config); // Release the lock before async operations
 
@@ -159,6 +246,7 @@ async fn trigger_pr_description_follow_up(
             session_id: agent_session_id,
             executor_profile_id: executor_profile_id.clone(),
             working_dir: working_dir.clone(),
+            read_only: false,
         })
     } else {
         ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
@@ -172,11 +260,12 @@ async fn trigger_pr_description_follow_up(
 
     deployment
         .container()
-        .start_execution(
+        .start_execution_for_purpose(
             workspace,
             &session,
             &action,
             &ExecutionProcessRunReason::CodingAgent,
+            Some(TurnPurpose::PrDescription),
         )
         .await?;
 
@@ -190,6 +279,12 @@ pub async fn create_github_pr(
 ) -> Result<ResponseJson<ApiResponse<String, CreatePrError>>, ApiError> {
     let pool = &deployment.db().pool;
 
+    let mut v = Validator::check(&request);
+    v.repo_exists(pool, "repo_id", request.repo_id).await?;
+    v.finish()?;
+    permissions::require_workspace_permission(&deployment, &workspace, ProjectPermission::CreatePr)
+        .await?;
+
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -199,7 +294,7 @@ pub async fn create_github_pr(
         .await?
         .ok_or(RepoError::NotFound)?;
 
-    let repo_path = repo.path;
+    let repo_path = repo.path.clone();
     let target_branch = if let Some(branch) = request.target_branch {
         branch
     } else {
@@ -211,33 +306,49 @@ pub async fn create_github_pr(
         .ensure_container_exists(&workspace)
         .await?;
     let workspace_path = PathBuf::from(&container_ref);
-    let worktree_path = workspace_path.join(repo.name);
+    let worktree_path = workspace_path.join(&repo.name);
 
     match deployment
         .git()
         .check_remote_branch_exists(&repo_path, &target_branch)
     {
         Ok(false) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                CreatePrError::TargetBranchNotFound {
-                    branch: target_branch.clone(),
-                },
-            )));
+            return Err(ApiError::problem(CreatePrError::TargetBranchNotFound {
+                branch: target_branch.clone(),
+            }));
         }
         Err(GitServiceError::GitCLI(GitCliError::AuthFailed(_))) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                CreatePrError::GitCliNotLoggedIn,
-            )));
+            return Err(ApiError::problem(CreatePrError::GitCliNotLoggedIn));
         }
         Err(GitServiceError::GitCLI(GitCliError::NotAvailable)) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                CreatePrError::GitCliNotInstalled,
-            )));
+            return Err(ApiError::problem(CreatePrError::GitCliNotInstalled));
         }
         Err(e) => return Err(ApiError::GitService(e)),
         Ok(true) => {}
     }
 
+    let violations = super::util::check_push_policies(&deployment, &workspace, &repo).await?;
+    if !violations.is_empty() {
+        return Err(ApiError::problem(CreatePrError::PolicyViolation {
+            violations,
+        }));
+    }
+    let findings =
+        super::util::check_push_secrets(&deployment, &workspace, &repo, &target_branch).await?;
+    if !findings.is_empty() {
+        return Err(ApiError::problem(CreatePrError::SecretsDetected {
+            findings,
+        }));
+    }
+    let findings =
+        super::util::check_pr_dependencies(&deployment, &workspace, &repo, &target_branch)
+            .await?;
+    if !findings.is_empty() {
+        return Err(ApiError::problem(
+            CreatePrError::DependencyPolicyViolation { findings },
+        ));
+    }
+
     // Push the branch to GitHub first
     if let Err(e) = deployment
         .git()
@@ -246,18 +357,19 @@ pub async fn create_github_pr(
         tracing::error!("Failed to push branch to GitHub: {}", e);
         match e {
             GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => {
-                return Ok(ResponseJson(ApiResponse::error_with_data(
-                    CreatePrError::GitCliNotLoggedIn,
-                )));
+                return Err(ApiError::problem(CreatePrError::GitCliNotLoggedIn));
             }
             GitServiceError::GitCLI(GitCliError::NotAvailable) => {
-                return Ok(ResponseJson(ApiResponse::error_with_data(
-                    CreatePrError::GitCliNotInstalled,
-                )));
+                return Err(ApiError::problem(CreatePrError::GitCliNotInstalled));
             }
             _ => return Err(ApiError::GitService(e)),
         }
     }
+    deployment
+        .container()
+        .commit_statuses()
+        .queue_agent_run(workspace.id)
+        .await;
 
     let norm_target_branch_name = if matches!(
         deployment
@@ -279,12 +391,23 @@ pub async fn create_github_pr(
         target_branch
     };
     // Create the PR using provider abstraction
+    let body = super::util::pr_body_with_coverage(
+        &deployment,
+        workspace.id,
+        workspace_repo.repo_id,
+        request.body.clone(),
+    )
+    .await;
+    // In a fork checkout the branch was pushed to the fork and the PR targets upstream
+    let fork = git_provider::detect_fork(&repo_path).ok().flatten();
     let pr_request = CreateMrRequest {
         title: request.title.clone(),
-        body: request.body.clone(),
+        body,
         head_branch: workspace.branch.clone(),
         base_branch: norm_target_branch_name.clone(),
         draft: request.draft,
+        head_repo: fork.map(|fork| fork.fork),
+        milestone: request.milestone.clone(),
     };
 
     // Detect provider and create appropriate service
@@ -348,12 +471,12 @@ pub async fn create_github_pr(
                 e
             );
             match &e {
-                ProviderError::NotInstalled { .. } => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotInstalled),
-                )),
-                ProviderError::NotAuthenticated(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotLoggedIn),
-                )),
+                ProviderError::NotInstalled { .. } => {
+                    Err(ApiError::problem(CreatePrError::GithubCliNotInstalled))
+                }
+                ProviderError::NotAuthenticated(_) => {
+                    Err(ApiError::problem(CreatePrError::GithubCliNotLoggedIn))
+                }
                 _ => Err(ApiError::GitService(GitServiceError::InvalidRepository(e.to_string()))),
             }
         }
@@ -363,8 +486,20 @@ pub async fn create_github_pr(
 pub async fn attach_existing_pr(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
     Json(request): Json<AttachExistingPrRequest>,
 ) -> Result<ResponseJson<ApiResponse<AttachPrResponse>>, ApiError> {
+    let response = attach_pr_for_branch(&deployment, &workspace, request.repo_id, &actor).await?;
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+/// Attach the PR opened for the workspace branch in `repo_id`, if there is one
+pub(crate) async fn attach_pr_for_branch(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_id: Uuid,
+    actor: &TaskActor,
+) -> Result<AttachPrResponse, ApiError> {
     let pool = &deployment.db().pool;
 
     let task = workspace
@@ -372,24 +507,25 @@ pub async fn attach_existing_pr(
         .await?
         .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
 
-    let workspace_repo =
-        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
+    let workspace_repo = WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
 
     let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
         .await?
         .ok_or(RepoError::NotFound)?;
 
     // Check if PR already attached for this repo
-    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id).await?;
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo_id).await?;
     if let Some(Merge::Pr(pr_merge)) = merges.into_iter().next() {
-        return Ok(ResponseJson(ApiResponse::success(AttachPrResponse {
+        return Ok(AttachPrResponse {
             pr_attached: true,
             pr_url: Some(pr_merge.pr_info.url.clone()),
             pr_number: Some(pr_merge.pr_info.number),
             pr_status: Some(pr_merge.pr_info.status.clone()),
-        })));
+            review_state: super::util::fetch_review_state(&repo.path, pr_merge.pr_info.number)
+                .await,
+        });
     }
 
     // Detect provider and create appropriate service
@@ -406,6 +542,8 @@ pub async fn attach_existing_pr(
 
     // Take the first PR (prefer open, but also accept merged/closed)
     if let Some(pr_info) = prs.into_iter().next() {
+        let pr_info = super::util::with_review_state(provider.as_ref(), &repo_id, pr_info).await;
+
         // Save PR info to database
         let merge = Merge::create_pr(
             pool,
@@ -433,38 +571,27 @@ pub async fn attach_existing_pr(
 
         // If PR is merged, mark task as done
         if matches!(merge_status, MergeStatus::Merged) {
-            Task::update_status(pool, task.id, TaskStatus::Done).await?;
-
-            // Try broadcast update to other users in organization
-            if let Ok(publisher) = deployment.share_publisher() {
-                if let Err(err) = publisher.update_shared_task_by_id(task.id).await {
-                    tracing::warn!(
-                        ?err,
-                        "Failed to propagate shared task update for {}",
-                        task.id
-                    );
-                }
-            } else {
-                tracing::debug!(
-                    "Share publisher unavailable; skipping remote update for {}",
-                    task.id
-                );
-            }
+            deployment
+                .task_events()
+                .set_status(task.id, TaskStatus::Done, actor)
+                .await?;
         }
 
-        Ok(ResponseJson(ApiResponse::success(AttachPrResponse {
+        Ok(AttachPrResponse {
             pr_attached: true,
             pr_url: Some(pr_info.url),
             pr_number: Some(pr_info.number as i64),
             pr_status: Some(merge_status),
-        })))
+            review_state: pr_info.review_state,
+        })
     } else {
-        Ok(ResponseJson(ApiResponse::success(AttachPrResponse {
+        Ok(AttachPrResponse {
             pr_attached: false,
             pr_url: None,
             pr_number: None,
             pr_status: None,
-        })))
+            review_state: None,
+        })
     }
 }
 
@@ -472,6 +599,7 @@ pub async fn get_pr_comments(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<GetPrCommentsQuery>,
+    Query(page): Query<PageQuery>,
 ) -> Result<ResponseJson<ApiResponse<PrCommentsResponse, GetPrCommentsError>>, ApiError> {
     let pool = &deployment.db().pool;
 
@@ -489,12 +617,10 @@ pub async fn get_pr_comments(
     let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id).await?;
 
     // Ensure there's an attached PR for this repo
-    let pr_info = match merges.into_iter().next() {
-        Some(Merge::Pr(pr_merge)) => pr_merge.pr_info,
+    let pr_merge = match merges.into_iter().next() {
+        Some(Merge::Pr(pr_merge)) => pr_merge,
         _ => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                GetPrCommentsError::NoPrAttached,
-            )));
+            return Err(ApiError::problem(GetPrCommentsError::NoPrAttached));
         }
     };
 
@@ -504,28 +630,65 @@ pub async fn get_pr_comments(
     let (_, repo_id) = git_provider::detect_provider(&repo.path)
         .map_err(|e| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string())))?;
 
-    // Fetch comments from provider
-    match provider
-        .get_comments(&repo_id, pr_info.number as u64)
+    // Fetch comments from provider, or the last fetched while it is unreachable
+    let number = pr_merge.pr_info.number as u64;
+    let (what, fetch) = match query.since {
+        Some(since) => (
+            format!("comments?since={}", since.timestamp()),
+            provider.get_comments_since(&repo_id, number, since),
+        ),
+        None => (
+            "comments".to_string(),
+            provider.get_comments(&repo_id, number),
+        ),
+    };
+    let key = OfflineService::cache_key(&repo_id, number, &what);
+    match deployment
+        .offline()
+        .read(provider.provider_type(), key, fetch)
         .await
     {
-        Ok(comments) => Ok(ResponseJson(ApiResponse::success(PrCommentsResponse {
-            comments,
-        }))),
+        Ok(fetched) => {
+            let addressed =
+                super::util::record_pr_comments(pool, pr_merge.id, &fetched.value).await?;
+            let filter = super::util::comment_filter(
+                query.since,
+                query.exclude_bots,
+                query.authors.as_deref(),
+            );
+            let comments = super::util::filter_pr_comments(
+                fetched.value,
+                &filter,
+                query.only_unresolved,
+                &addressed,
+            );
+            let (comments, pagination) = page.paginate(comments, UnifiedComment::key)?;
+            Ok(ResponseJson(ApiResponse::success_page(
+                PrCommentsResponse {
+                    comments,
+                    cached_at: fetched.cached_at,
+                    addressed,
+                },
+                pagination,
+            )))
+        }
         Err(e) => {
             tracing::error!(
                 "Failed to fetch PR comments for attempt {}, PR #{}: {}",
                 workspace.id,
-                pr_info.number,
+                pr_merge.pr_info.number,
                 e
             );
             match &e {
-                ProviderError::NotInstalled { .. } => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotInstalled),
-                )),
-                ProviderError::NotAuthenticated(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotLoggedIn),
-                )),
+                ProviderError::NotInstalled { .. } => {
+                    Err(ApiError::problem(GetPrCommentsError::GithubCliNotInstalled))
+                }
+                ProviderError::NotAuthenticated(_) => {
+                    Err(ApiError::problem(GetPrCommentsError::GithubCliNotLoggedIn))
+                }
+                _ if e.is_unreachable() => {
+                    Err(ApiError::problem(GetPrCommentsError::ProviderUnreachable))
+                }
                 _ => Err(ApiError::GitService(GitServiceError::InvalidRepository(e.to_string()))),
             }
         }
@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use chrono::Utc;
+use db::models::{
+    merge::{Merge, PrMerge},
+    repo::Repo,
+    seen_pr_comment::SeenPrComment,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use serde_json::Value;
+use services::services::{
+    comment_triggers::CommentTriggerConfig,
+    git::GitServiceError,
+    git_provider::{self, ProviderError},
+    jobs::JobHandler,
+    review_follow_ups,
+};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::{api::projects::ProjectPermission, response::ApiResponse};
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    permissions,
+    routes::sessions::{FollowUpStart, start_or_queue_follow_up},
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetReviewFollowUpsRequest {
+    pub enabled: bool,
+}
+
+/// Opt the workspace into or out of follow-ups from new review comments on its open MRs/PRs.
+/// Only comments posted after opting in are sent to the agent.
+pub async fn set_review_follow_ups(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<SetReviewFollowUpsRequest>,
+) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if request.enabled {
+        permissions::require_workspace_permission(
+            &deployment,
+            &workspace,
+            ProjectPermission::StartExecution,
+        )
+        .await?;
+    }
+
+    let enabled_at = match (request.enabled, workspace.review_follow_ups_enabled_at) {
+        (true, Some(enabled_at)) => Some(enabled_at),
+        (true, None) => Some(Utc::now()),
+        (false, _) => None,
+    };
+    Workspace::set_review_follow_ups_enabled_at(pool, workspace.id, enabled_at).await?;
+    let workspace = Workspace::find_by_id(pool, workspace.id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "review_follow_ups_toggled",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "enabled": request.enabled,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(workspace)))
+}
+
+/// Send the new review comments of one open MR/PR to the agent of its workspace, if it opted in.
+/// Comments by the account the provider is signed in as are the agent's own replies and skipped;
+/// `configured_username` stands in for it when the provider cannot tell.
+async fn follow_up_pr(
+    deployment: &DeploymentImpl,
+    pr_merge: &PrMerge,
+    triggers: &CommentTriggerConfig,
+    configured_username: Option<&str>,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(workspace) = Workspace::find_by_id(pool, pr_merge.workspace_id).await? else {
+        return Ok(());
+    };
+    let Some(enabled_at) = workspace.review_follow_ups_enabled_at else {
+        return Ok(());
+    };
+    let repo = Repo::find_by_id(pool, pr_merge.repo_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let invalid_repo =
+        |e: ProviderError| ApiError::GitService(GitServiceError::InvalidRepository(e.to_string()));
+    let provider = git_provider::create_provider(&repo.path).map_err(invalid_repo)?;
+    let (_, repo_id) = git_provider::detect_provider(&repo.path).map_err(invalid_repo)?;
+    let own_username = match provider.authenticated_username().await {
+        Ok(username) => Some(username),
+        Err(e) => {
+            tracing::debug!(
+                "Could not look up the signed-in user for {}: {}",
+                pr_merge.pr_info.url,
+                e
+            );
+            configured_username.map(str::to_string)
+        }
+    };
+
+    let comments = provider
+        .get_comments_since(&repo_id, pr_merge.pr_info.number as u64, enabled_at)
+        .await
+        .map_err(invalid_repo)?;
+    let seen: HashSet<String> = SeenPrComment::find_keys_by_merge_id(pool, pr_merge.id)
+        .await?
+        .into_iter()
+        .collect();
+    let new = review_follow_ups::new_comments(
+        &comments,
+        &seen,
+        enabled_at,
+        triggers,
+        own_username.as_deref(),
+    );
+    if new.is_empty() {
+        return Ok(());
+    }
+
+    let prompt = review_follow_ups::follow_up_prompt(&pr_merge.pr_info.url, &new);
+    let started = start_or_queue_follow_up(deployment, &workspace, prompt).await?;
+    let keys: Vec<String> = new.iter().map(|comment| comment.key()).collect();
+    SeenPrComment::create_many(pool, pr_merge.id, &keys).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "review_follow_up",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "comments": keys.len(),
+                "queued": matches!(started, FollowUpStart::Queued),
+            }),
+        )
+        .await;
+
+    Ok(())
+}
+
+/// Handler of the recurring [`review_follow_ups::REVIEW_FOLLOW_UP_JOB`]
+pub fn review_follow_up_handler(deployment: DeploymentImpl) -> impl JobHandler {
+    move |_: Value| {
+        let deployment = deployment.clone();
+        async move {
+            let (triggers, configured_username) = {
+                let config = deployment.config().read().await;
+                (
+                    config.comment_triggers.clone(),
+                    config.github.username.clone(),
+                )
+            };
+            for pr_merge in Merge::get_open_prs(&deployment.db().pool).await? {
+                if let Err(e) = follow_up_pr(
+                    &deployment,
+                    &pr_merge,
+                    &triggers,
+                    configured_username.as_deref(),
+                )
+                .await
+                {
+                    tracing::warn!(
+                        "Failed to follow up on review comments of {}: {}",
+                        pr_merge.pr_info.url,
+                        e
+                    );
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        }
+    }
+}
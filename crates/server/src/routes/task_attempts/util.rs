@@ -1,12 +1,35 @@
-use std::path::PathBuf;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
+use chrono::{DateTime, Utc};
 use db::models::{
-    execution_process::ExecutionProcess, execution_process_repo_state::ExecutionProcessRepoState,
-    workspace::Workspace, workspace_repo::WorkspaceRepo,
+    coverage_report::CoverageReport,
+    dependency_report::DependencyFinding,
+    execution_process::ExecutionProcess,
+    execution_process_repo_state::ExecutionProcessRepoState,
+    policy_violation_report::{PolicyViolation, PolicyViolationReport},
+    pr_comment::{AddressedPrComment, PrComment, UpsertPrComment},
+    repo::Repo,
+    secret_scan_acknowledgement::SecretScanAcknowledgement,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
-use services::services::{container::ContainerService, git::WorktreeResetOptions};
+use services::services::{
+    container::ContainerService,
+    coverage,
+    dependency_policy::{self, DependencyChecker},
+    git::{DiffTarget, WorktreeResetOptions},
+    git_provider::{
+        self, CommentFilter, GitProvider, PrInfo, RepoIdentifier, ReviewState, UnifiedComment,
+    },
+    policy::{self, PolicyError},
+    secret_scan::{SecretFinding, SecretScanner},
+};
 use sqlx::SqlitePool;
+use utils::redact::RedactionOptions;
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
@@ -81,3 +104,280 @@ pub async fn restore_worktrees_to_process(
 
     Ok(())
 }
+
+/// Check what the coding agents of a workspace changed against their executors' policies, before
+/// its branch is pushed. Violations are recorded as a report and returned.
+pub async fn check_push_policies(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo: &Repo,
+) -> Result<Vec<PolicyViolation>, ApiError> {
+    let policies = deployment.config().read().await.executor_policies.clone();
+    if policies.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pool = &deployment.db().pool;
+
+    let violations =
+        policy::check_agent_commits(pool, deployment.git(), &policies, workspace.id, repo)
+            .await
+            .map_err(|e| match e {
+                PolicyError::GitService(e) => ApiError::GitService(e),
+                PolicyError::Database(e) => ApiError::Database(e),
+                e @ PolicyError::InvalidPattern { .. } => ApiError::BadRequest(e.to_string()),
+            })?;
+
+    if !violations.is_empty() {
+        PolicyViolationReport::create(pool, workspace.id, None, &violations).await?;
+        deployment
+            .track_if_analytics_allowed(
+                "executor_policy_violated",
+                serde_json::json!({
+                    "workspace_id": workspace.id.to_string(),
+                    "violation_count": violations.len(),
+                    "stage": "push",
+                }),
+            )
+            .await;
+    }
+    Ok(violations)
+}
+
+/// Scan what the workspace branch adds on top of `target_branch` for secrets, marking findings
+/// that were acknowledged as false positives
+pub async fn scan_branch_for_secrets(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo: &Repo,
+    target_branch: &str,
+) -> Result<Vec<SecretFinding>, ApiError> {
+    let options = {
+        let config = deployment.config().read().await;
+        RedactionOptions {
+            enabled: true,
+            entropy_detection: config.redaction.entropy_detection,
+            redact_env_values: true,
+            extra_patterns: config.redaction.extra_patterns.clone(),
+        }
+    };
+    let mut findings = SecretScanner::new(&options).scan_branch(
+        deployment.git(),
+        &repo.name,
+        &repo.path,
+        &workspace.branch,
+        target_branch,
+    )?;
+
+    let acknowledged = SecretScanAcknowledgement::find_fingerprints_by_workspace_id(
+        &deployment.db().pool,
+        workspace.id,
+    )
+    .await?;
+    for finding in &mut findings {
+        finding.acknowledged = acknowledged.contains(&finding.fingerprint);
+    }
+    Ok(findings)
+}
+
+/// Unacknowledged secrets that should block a push, or nothing when scanning is disabled
+pub async fn check_push_secrets(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo: &Repo,
+    target_branch: &str,
+) -> Result<Vec<SecretFinding>, ApiError> {
+    if !deployment.config().read().await.secret_scan_enabled {
+        return Ok(Vec::new());
+    }
+    let findings: Vec<SecretFinding> =
+        scan_branch_for_secrets(deployment, workspace, repo, target_branch)
+            .await?
+            .into_iter()
+            .filter(|finding| !finding.acknowledged)
+            .collect();
+
+    if !findings.is_empty() {
+        tracing::warn!(
+            "Blocked push of workspace {}: {} possible secrets in '{}'",
+            workspace.id,
+            findings.len(),
+            repo.name
+        );
+        deployment
+            .track_if_analytics_allowed(
+                "secret_scan_blocked_push",
+                serde_json::json!({
+                    "workspace_id": workspace.id.to_string(),
+                    "finding_count": findings.len(),
+                }),
+            )
+            .await;
+    }
+    Ok(findings)
+}
+
+/// Dependency findings that should block a pull request, or nothing unless the dependency
+/// policy asks for pull requests to be blocked
+pub async fn check_pr_dependencies(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo: &Repo,
+    target_branch: &str,
+) -> Result<Vec<DependencyFinding>, ApiError> {
+    let policy = deployment.config().read().await.dependency_policy.clone();
+    if !policy.enabled || !policy.block_pull_requests {
+        return Ok(Vec::new());
+    }
+    let diffs = deployment.git().get_diffs(
+        DiffTarget::Branch {
+            repo_path: &repo.path,
+            branch_name: &workspace.branch,
+            base_branch: target_branch,
+        },
+        None,
+    )?;
+    let findings = DependencyChecker::new(policy)
+        .check(&repo.name, &diffs)
+        .await;
+    if !dependency_policy::has_blocking_findings(&findings) {
+        return Ok(Vec::new());
+    }
+
+    tracing::warn!(
+        "Blocked pull request for workspace {}: dependency policy findings in '{}'",
+        workspace.id,
+        repo.name
+    );
+    deployment
+        .track_if_analytics_allowed(
+            "dependency_policy_blocked_pr",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "finding_count": findings.len(),
+            }),
+        )
+        .await;
+    Ok(findings)
+}
+
+/// Append the latest coverage summary for `repo_id` to a pull request body, if configured
+pub async fn pr_body_with_coverage(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+    repo_id: Uuid,
+    body: Option<String>,
+) -> Option<String> {
+    let config = deployment.config().read().await.coverage.clone();
+    if !config.enabled || !config.include_in_pr_body {
+        return body;
+    }
+    let section = CoverageReport::find_latest_by_workspace_id(&deployment.db().pool, workspace_id)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .find(|report| report.repo_id == repo_id)
+        .and_then(coverage::pr_body_section);
+    match (body, section) {
+        (Some(body), Some(section)) if !body.trim().is_empty() => {
+            Some(format!("{}\n\n{}", body.trim_end(), section))
+        }
+        (_, Some(section)) => Some(section),
+        (body, None) => body,
+    }
+}
+
+/// Attach the review summary to an MR/PR. Reviews are best-effort: providers that can't report
+/// them leave the state unknown.
+pub async fn with_review_state(
+    provider: &dyn GitProvider,
+    repo_id: &RepoIdentifier,
+    pr_info: PrInfo,
+) -> PrInfo {
+    match provider.get_reviews(repo_id, pr_info.number).await {
+        Ok(reviews) => pr_info.with_reviews(&reviews),
+        Err(e) => {
+            tracing::debug!("Reviews of #{} unavailable: {}", pr_info.number, e);
+            pr_info
+        }
+    }
+}
+
+/// Review summary of an MR/PR already attached to a workspace
+pub async fn fetch_review_state(repo_path: &Path, number: i64) -> Option<ReviewState> {
+    let provider = git_provider::create_provider(repo_path).ok()?;
+    let (_, repo_id) = git_provider::detect_provider(repo_path).ok()?;
+    match provider.get_reviews(&repo_id, number as u64).await {
+        Ok(reviews) => Some(ReviewState::summarize(&reviews)),
+        Err(e) => {
+            tracing::debug!("Reviews of #{} unavailable: {}", number, e);
+            None
+        }
+    }
+}
+
+/// Store the comments fetched from an MR/PR and list those addressed so far
+pub async fn record_pr_comments(
+    pool: &SqlitePool,
+    merge_id: Uuid,
+    comments: &[UnifiedComment],
+) -> Result<Vec<AddressedPrComment>, sqlx::Error> {
+    let comments: Vec<UpsertPrComment> = comments
+        .iter()
+        .map(|comment| {
+            let (author, body, url) = match comment {
+                UnifiedComment::General {
+                    author, body, url, ..
+                }
+                | UnifiedComment::Review {
+                    author, body, url, ..
+                } => (author, body, url),
+            };
+            UpsertPrComment {
+                comment_key: comment.key(),
+                author: author.clone(),
+                body: body.clone(),
+                url: url.clone(),
+                commented_at: comment.created_at(),
+            }
+        })
+        .collect();
+    PrComment::upsert_many(pool, merge_id, &comments).await?;
+    PrComment::find_addressed_by_merge_id(pool, merge_id).await
+}
+
+/// Filter of the comments of an MR/PR from query parameters, with `authors` comma-separated
+pub fn comment_filter(
+    since: Option<DateTime<Utc>>,
+    exclude_bots: bool,
+    authors: Option<&str>,
+) -> CommentFilter {
+    CommentFilter {
+        since,
+        exclude_bots,
+        authors: authors
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|author| !author.is_empty())
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+/// Comments the filter keeps, leaving out the addressed ones with `only_unresolved`
+pub fn filter_pr_comments(
+    comments: Vec<UnifiedComment>,
+    filter: &CommentFilter,
+    only_unresolved: bool,
+    addressed: &[AddressedPrComment],
+) -> Vec<UnifiedComment> {
+    let addressed: HashSet<&str> = addressed
+        .iter()
+        .map(|comment| comment.comment_key.as_str())
+        .collect();
+    comments
+        .into_iter()
+        .filter(|comment| filter.matches(comment))
+        .filter(|comment| !(only_unresolved && addressed.contains(comment.key().as_str())))
+        .collect()
+}
@@ -13,28 +13,51 @@ use axum::{
     routing::{delete, get, post, put},
 };
 use db::models::{
+    board::BoardSnapshot,
     image::TaskImage,
     project::{Project, ProjectError},
+    project_repo::ProjectRepo,
     repo::Repo,
     task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
+    task_comment::{CreateTaskComment, TaskComment},
+    task_event::TaskEvent,
+    task_metrics::TaskMetrics,
+    tenant::Tenant,
     workspace::{CreateWorkspace, Workspace},
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
 use deployment::Deployment;
-use executors::profile::ExecutorProfileId;
+use executors::profile::{ExecutorConfigs, ExecutorProfileId};
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::ContainerService, share::ShareError, workspace_manager::WorkspaceManager,
+    container::ContainerService,
+    estimation::{self, TaskEstimate},
+    refine::{self, TaskRefinement},
+    share::ShareError,
+    workspace_manager::WorkspaceManager,
 };
-use sqlx::Error as SqlxError;
+use sqlx::{Error as SqlxError, SqlitePool};
 use ts_rs::TS;
-use utils::{api::oauth::LoginStatus, response::ApiResponse};
+use utils::{
+    api::{oauth::LoginStatus, projects::ProjectPermission},
+    response::{ApiResponse, Keyset, PageQuery},
+};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_task_middleware,
-    routes::task_attempts::WorkspaceRepoInput,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{
+        conditional::conditional_project_tasks, load_task_middleware, service_accounts::Actor,
+        tenants::ensure_project_in_tenant,
+    },
+    permissions,
+    routes::{
+        share_links,
+        task_attempts::{self, WorkspaceRepoInput},
+    },
+    validation::{Validate, Validator},
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -44,13 +67,31 @@ pub struct TaskQuery {
 
 pub async fn get_tasks(
     State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
     Query(query): Query<TaskQuery>,
+    Query(page): Query<PageQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>, ApiError> {
-    let tasks =
-        Task::find_by_project_id_with_attempt_status(&deployment.db().pool, query.project_id)
-            .await?;
+    let pool = &deployment.db().pool;
+    ensure_project_in_tenant(pool, &tenant, query.project_id).await?;
+    let keyset = page.keyset()?;
+    let tasks = Task::find_page_with_attempt_status(pool, query.project_id, keyset).await?;
+    let total = Task::count_by_project_id(pool, query.project_id).await?;
+    let (tasks, pagination) = page.keyset_page(tasks, total as u64, |task| {
+        Keyset::new(task.created_at, task.id)
+    });
 
-    Ok(ResponseJson(ApiResponse::success(tasks)))
+    Ok(ResponseJson(ApiResponse::success_page(tasks, pagination)))
+}
+
+/// The whole board of a project for its initial render; live updates come from the task stream
+pub async fn get_board(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+    Query(query): Query<TaskQuery>,
+) -> Result<ResponseJson<ApiResponse<BoardSnapshot>>, ApiError> {
+    ensure_project_in_tenant(&deployment.db().pool, &tenant, query.project_id).await?;
+    let board = BoardSnapshot::for_project(&deployment.db().read_pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(board)))
 }
 
 pub async fn stream_tasks_ws(
@@ -107,10 +148,127 @@ pub async fn get_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+pub async fn get_task_comments(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(page): Query<PageQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskComment>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let keyset = page.keyset()?;
+    let comments = TaskComment::find_page_by_task_id(pool, task.id, keyset).await?;
+    let total = TaskComment::count_by_task_id(pool, task.id).await?;
+    let (comments, pagination) = page.keyset_page(comments, total as u64, |comment| {
+        Keyset::new(comment.created_at, comment.id)
+    });
+    Ok(ResponseJson(ApiResponse::success_page(
+        comments, pagination,
+    )))
+}
+
+/// Activity feed of a task, from the task event log
+pub async fn get_task_events(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(page): Query<PageQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskEvent>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let keyset = page.keyset()?;
+    let events = TaskEvent::find_page_by_task_id(pool, task.id, keyset).await?;
+    let total = TaskEvent::count_by_task_id(pool, task.id).await?;
+    let (events, pagination) = page.keyset_page(events, total as u64, |event| {
+        Keyset::new(event.created_at, event.id)
+    });
+    Ok(ResponseJson(ApiResponse::success_page(events, pagination)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTaskCommentRequest {
+    pub body: String,
+}
+
+pub async fn create_task_comment(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskCommentRequest>,
+) -> Result<ResponseJson<ApiResponse<TaskComment>>, ApiError> {
+    if payload.body.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Comment body must not be empty".to_string(),
+        ));
+    }
+
+    let author = deployment
+        .config()
+        .read()
+        .await
+        .github
+        .username
+        .clone()
+        .unwrap_or_else(|| "user".to_string());
+    let comment = TaskComment::create(
+        &deployment.db().pool,
+        task.id,
+        &CreateTaskComment {
+            author,
+            body: payload.body,
+        },
+    )
+    .await?;
+
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+impl Validate for CreateTask {
+    fn validate(&self, v: &mut Validator) {
+        v.title("title", &self.title);
+        v.body("description", self.description.as_deref());
+    }
+}
+
+impl Validate for UpdateTask {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(title) = &self.title {
+            v.title("title", title);
+        }
+        v.body("description", self.description.as_deref());
+    }
+}
+
+/// Check that the project, parent attempt and images referenced by a new task exist
+async fn check_task_references(
+    v: &mut Validator,
+    pool: &SqlitePool,
+    task: &CreateTask,
+    field_prefix: &str,
+) -> Result<(), ApiError> {
+    v.project_exists(pool, &format!("{field_prefix}project_id"), task.project_id)
+        .await?;
+    if let Some(parent_workspace_id) = task.parent_workspace_id {
+        v.workspace_exists(
+            pool,
+            &format!("{field_prefix}parent_workspace_id"),
+            parent_workspace_id,
+        )
+        .await?;
+    }
+    if let Some(image_ids) = &task.image_ids {
+        v.images_exist(pool, &format!("{field_prefix}image_ids"), image_ids)
+            .await?;
+    }
+    Ok(())
+}
+
 pub async fn create_task(
     State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
+    Extension(tenant): Extension<Tenant>,
     Json(payload): Json<CreateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    ensure_project_in_tenant(&deployment.db().pool, &tenant, payload.project_id).await?;
+    let mut v = Validator::check(&payload);
+    check_task_references(&mut v, &deployment.db().pool, &payload, "").await?;
+    v.finish()?;
+
     let id = Uuid::new_v4();
 
     tracing::debug!(
@@ -120,6 +278,7 @@ pub async fn create_task(
     );
 
     let task = Task::create(&deployment.db().pool, &payload, id).await?;
+    deployment.task_events().created(&task, &actor).await?;
 
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
@@ -147,20 +306,36 @@ pub struct CreateAndStartTaskRequest {
     pub repos: Vec<WorkspaceRepoInput>,
 }
 
+impl Validate for CreateAndStartTaskRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.nested("task", |v| self.task.validate(v));
+        task_attempts::validate_repos(v, &self.repos);
+    }
+}
+
 pub async fn create_task_and_start(
     State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
+    Extension(tenant): Extension<Tenant>,
     Json(payload): Json<CreateAndStartTaskRequest>,
 ) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
-    if payload.repos.is_empty() {
-        return Err(ApiError::BadRequest(
-            "At least one repository is required".to_string(),
-        ));
-    }
-
     let pool = &deployment.db().pool;
+    ensure_project_in_tenant(pool, &tenant, payload.task.project_id).await?;
+
+    let mut v = Validator::check(&payload);
+    check_task_references(&mut v, pool, &payload.task, "task.").await?;
+    task_attempts::check_repo_references(&mut v, pool, &payload.repos).await?;
+    v.finish()?;
+    permissions::require_permission(
+        &deployment,
+        payload.task.project_id,
+        ProjectPermission::StartExecution,
+    )
+    .await?;
 
     let task_id = Uuid::new_v4();
     let task = Task::create(pool, &payload.task, task_id).await?;
+    deployment.task_events().created(&task, &actor).await?;
 
     if let Some(image_ids) = &payload.task.image_ids {
         TaskImage::associate_many_dedup(pool, task.id, image_ids).await?;
@@ -249,10 +424,33 @@ pub async fn create_task_and_start(
 pub async fn update_task(
     Extension(existing_task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
-
+    Actor(actor): Actor,
     Json(payload): Json<UpdateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let mut v = Validator::check(&payload);
+    if let Some(parent_workspace_id) = payload.parent_workspace_id {
+        v.workspace_exists(
+            &deployment.db().pool,
+            "parent_workspace_id",
+            parent_workspace_id,
+        )
+        .await?;
+    }
+    if let Some(image_ids) = &payload.image_ids {
+        v.images_exist(&deployment.db().pool, "image_ids", image_ids)
+            .await?;
+    }
+    v.finish()?;
+
+    if payload
+        .expected_updated_at
+        .is_some_and(|expected| expected != existing_task.updated_at)
+    {
+        return Err(ApiError::stale("task"));
+    }
+
     ensure_shared_task_auth(&existing_task, &deployment).await?;
+    let before = existing_task.clone();
 
     // Use existing values if not provided in update
     let title = payload.title.unwrap_or(existing_task.title);
@@ -265,7 +463,13 @@ pub async fn update_task(
     let parent_workspace_id = payload
         .parent_workspace_id
         .or(existing_task.parent_workspace_id);
+    let due_at = if payload.clear_due_at {
+        None
+    } else {
+        payload.due_at.or(existing_task.due_at)
+    };
 
+    // The timestamp is checked again on write, in case someone else saved in the meantime
     let task = Task::update(
         &deployment.db().pool,
         existing_task.id,
@@ -274,22 +478,48 @@ pub async fn update_task(
         description,
         status,
         parent_workspace_id,
+        due_at,
+        payload.expected_updated_at,
     )
-    .await?;
+    .await?
+    .ok_or_else(|| match payload.expected_updated_at {
+        Some(_) => ApiError::stale("task"),
+        None => ApiError::Database(SqlxError::RowNotFound),
+    })?;
 
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::delete_by_task_id(&deployment.db().pool, task.id).await?;
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
 
-    // If task has been shared, broadcast update
-    if task.shared_task_id.is_some() {
-        let Ok(publisher) = deployment.share_publisher() else {
-            return Err(ShareError::MissingConfig("share publisher unavailable").into());
-        };
-        publisher.update_shared_task(&task).await?;
-    }
+    // Shared tasks are pushed to the remote from the event
+    deployment
+        .task_events()
+        .updated(&before, &task, &actor)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
 
+/// Revert the last change of the task, e.g. an accidental drag to another column
+pub async fn undo_task_change(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    ensure_shared_task_auth(&task, &deployment).await?;
+    let task = deployment.task_events().undo(task.id, &actor).await?;
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+/// Reapply the last undone change of the task
+pub async fn redo_task_change(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    ensure_shared_task_auth(&task, &deployment).await?;
+    let task = deployment.task_events().redo(task.id, &actor).await?;
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
@@ -311,6 +541,7 @@ async fn ensure_shared_task_auth(
 pub async fn delete_task(
     Extension(task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
+    Actor(actor): Actor,
 ) -> Result<(StatusCode, ResponseJson<ApiResponse<()>>), ApiError> {
     ensure_shared_task_auth(&task, &deployment).await?;
 
@@ -370,6 +601,8 @@ pub async fn delete_task(
     // Commit the transaction - if this fails, all changes are rolled back
     tx.commit().await?;
 
+    deployment.task_events().deleted(&task, &actor).await?;
+
     if total_children_affected > 0 {
         tracing::info!(
             "Nullified {} child task references before deleting task {}",
@@ -460,21 +693,129 @@ pub async fn share_task(
     })))
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct EstimateTaskRequest {
+    pub project_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+}
+
+/// Suggest effort and cost for a task being written, from similar tasks finished in the project.
+/// Returns `None` when there is no comparable history.
+pub async fn estimate_task(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+    Json(payload): Json<EstimateTaskRequest>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskEstimate>>>, ApiError> {
+    ensure_project_in_tenant(&deployment.db().pool, &tenant, payload.project_id).await?;
+    let history =
+        TaskMetrics::find_done_by_project_id(&deployment.db().read_pool, payload.project_id)
+            .await?;
+    let estimate = estimation::estimate(&payload.title, payload.description.as_deref(), &history);
+    Ok(ResponseJson(ApiResponse::success(estimate)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RefineTaskRequest {
+    /// Refine this title instead of the saved one, e.g. from an unsaved draft
+    pub title: Option<String>,
+    /// Refine this description instead of the saved one
+    pub description: Option<String>,
+    /// Defaults to the configured executor profile
+    pub executor_profile_id: Option<ExecutorProfileId>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RefineTaskResponse {
+    pub refinement: TaskRefinement,
+    /// The task description to save when the refinement is accepted
+    pub description: String,
+}
+
+/// Have the agent turn a rough task description into a structured one. The agent runs read-only
+/// in the project's first repository; the result is returned for review and not saved.
+pub async fn refine_task(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RefineTaskRequest>,
+) -> Result<ResponseJson<ApiResponse<RefineTaskResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let repos = ProjectRepo::find_repos_for_project(pool, task.project_id).await?;
+    let Some(repo) = repos.first() else {
+        return Err(ApiError::BadRequest(
+            "Add a repository to the project before refining tasks".to_string(),
+        ));
+    };
+
+    let executor_profile_id = match payload.executor_profile_id {
+        Some(profile) => profile,
+        None => deployment.config().read().await.executor_profile.clone(),
+    };
+    let agent = ExecutorConfigs::get_cached()
+        .get_coding_agent(&executor_profile_id)
+        .and_then(|agent| agent.into_read_only())
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "{} cannot refine tasks without being able to edit files",
+                executor_profile_id.executor
+            ))
+        })?;
+
+    let title = payload.title.unwrap_or(task.title);
+    let description = payload.description.or(task.description);
+    let prompt = refine::refine_prompt(&title, description.as_deref());
+    let answer = refine::run_agent_once(agent, &repo.path, &prompt).await?;
+    let refinement = refine::parse_refinement(&answer)?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_refined",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "executor": executor_profile_id.executor.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(RefineTaskResponse {
+        description: refinement.to_description(),
+        refinement,
+    })))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_actions_router = Router::new()
         .route("/", put(update_task))
         .route("/", delete(delete_task))
-        .route("/share", post(share_task));
+        .route("/share", post(share_task))
+        .route("/refine", post(refine_task))
+        .route("/comments", post(create_task_comment))
+        .route("/undo", post(undo_task_change))
+        .route("/redo", post(redo_task_change))
+        .route("/share-links", post(share_links::create_share_link));
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
+        .route("/comments", get(get_task_comments))
+        .route("/events", get(get_task_events))
+        .route("/share-links", get(share_links::get_share_links))
         .merge(task_actions_router)
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
-    let inner = Router::new()
+    // Polled reads answered with 304 Not Modified while the project's tasks are unchanged
+    let conditional_router = Router::new()
         .route("/", get(get_tasks).post(create_task))
+        .route("/board", get(get_board))
+        .route_layer(from_fn_with_state(
+            deployment.clone(),
+            conditional_project_tasks,
+        ));
+
+    let inner = Router::new()
+        .merge(conditional_router)
         .route("/stream/ws", get(stream_tasks_ws))
         .route("/create-and-start", post(create_task_and_start))
+        .route("/estimate", post(estimate_task))
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
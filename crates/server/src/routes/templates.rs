@@ -0,0 +1,84 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::template::{CreateTemplate, Template, UpdateTemplate};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn get_templates(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Template>>>, ApiError> {
+    let templates = Template::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(templates)))
+}
+
+pub async fn create_template(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTemplate>,
+) -> Result<ResponseJson<ApiResponse<Template>>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Template name is required".to_string(),
+        ));
+    }
+
+    let template = Template::create(&deployment.db().pool, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "template_created",
+            serde_json::json!({
+                "template_id": template.id.to_string(),
+                "kind": template.kind,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn update_template(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTemplate>,
+) -> Result<ResponseJson<ApiResponse<Template>>, ApiError> {
+    if payload
+        .name
+        .as_deref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        return Err(ApiError::BadRequest(
+            "Template name is required".to_string(),
+        ));
+    }
+
+    let template = Template::update(&deployment.db().pool, id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn delete_template(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = Template::delete(&deployment.db().pool, id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/templates", get(get_templates).post(create_template))
+        .route(
+            "/templates/{id}",
+            put(update_template).delete(delete_template),
+        )
+}
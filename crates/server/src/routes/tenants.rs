@@ -0,0 +1,305 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{delete, get, patch, put},
+};
+use db::models::{
+    tenant::{AddTenantMember, CreateTenant, Tenant, TenantMember, UpdateTenant},
+    tenant_quota::{TenantQuota, TenantUsage, UpdateTenantQuota},
+};
+use deployment::Deployment;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{login_sessions::SignedInUser, tenants::require_instance_admin},
+    validation::{Validate, Validator},
+};
+
+impl Validate for CreateTenant {
+    fn validate(&self, v: &mut Validator) {
+        v.title("name", &self.name);
+    }
+}
+
+impl Validate for UpdateTenant {
+    fn validate(&self, v: &mut Validator) {
+        v.title("name", &self.name);
+    }
+}
+
+impl Validate for UpdateTenantQuota {
+    fn validate(&self, v: &mut Validator) {
+        if self.max_concurrent_executions.is_some_and(|max| max < 1) {
+            v.error("max_concurrent_executions", "must allow at least one run");
+        }
+        if self
+            .monthly_spend_limit
+            .is_some_and(|limit| !limit.is_finite() || limit < 0.0)
+        {
+            v.error("monthly_spend_limit", "must not be negative");
+        }
+        if self.storage_limit_bytes.is_some_and(|limit| limit < 0) {
+            v.error("storage_limit_bytes", "must not be negative");
+        }
+    }
+}
+
+/// Quotas of an organization next to what it uses of them
+#[derive(Debug, Serialize, TS)]
+pub struct TenantUsageReport {
+    pub tenant_id: Uuid,
+    /// None when the organization has no quotas
+    pub quota: Option<TenantQuota>,
+    pub usage: TenantUsage,
+}
+
+async fn ensure_name_available(
+    deployment: &DeploymentImpl,
+    name: &str,
+    except: Option<Uuid>,
+) -> Result<(), ApiError> {
+    match Tenant::find_by_name(&deployment.db().pool, name).await? {
+        Some(existing) if Some(existing.id) != except => Err(ApiError::Conflict(format!(
+            "An organization named '{name}' already exists"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn sign_in_required() -> ApiError {
+    ApiError::Forbidden("Sign in to manage organizations".to_string())
+}
+
+async fn find_tenant(deployment: &DeploymentImpl, id: Uuid) -> Result<Tenant, ApiError> {
+    Tenant::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))
+}
+
+/// Fail unless the account the request is signed in as may manage an organization. The default
+/// one has no members, so an instance admin manages it. Unknown organizations are reported as
+/// missing.
+async fn require_member(
+    deployment: &DeploymentImpl,
+    user: Option<SignedInUser>,
+    id: Uuid,
+) -> Result<Tenant, ApiError> {
+    let pool = &deployment.db().pool;
+    let tenant = find_tenant(deployment, id).await?;
+    if id == Tenant::DEFAULT_ID {
+        require_instance_admin(deployment, user).await?;
+        return Ok(tenant);
+    }
+    let SignedInUser(user_id) = user.ok_or_else(sign_in_required)?;
+    if !Tenant::has_member(pool, id, user_id).await? {
+        return Err(ApiError::Forbidden(format!(
+            "You are not a member of organization {id}"
+        )));
+    }
+    Ok(tenant)
+}
+
+/// Organizations to switch between; pick one by sending its id in `X-Tenant-Id`. Only the
+/// default organization and those the signed-in account is a member of are listed.
+pub async fn list_tenants(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<SignedInUser>,
+) -> Result<ResponseJson<ApiResponse<Vec<Tenant>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let tenants = match user {
+        Some(SignedInUser(user_id)) => Tenant::find_for_user(pool, user_id).await?,
+        None => Tenant::find_by_id(pool, Tenant::DEFAULT_ID)
+            .await?
+            .into_iter()
+            .collect(),
+    };
+    Ok(ResponseJson(ApiResponse::success(tenants)))
+}
+
+/// The organization this request works in
+pub async fn get_current_tenant(
+    Extension(tenant): Extension<Tenant>,
+) -> Result<ResponseJson<ApiResponse<Tenant>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(tenant)))
+}
+
+pub async fn get_current_tenant_usage(
+    State(deployment): State<DeploymentImpl>,
+    Extension(tenant): Extension<Tenant>,
+) -> Result<ResponseJson<ApiResponse<TenantUsageReport>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let quota = TenantQuota::find_by_tenant_id(pool, tenant.id).await?;
+    let usage = TenantUsage::for_tenant(pool, tenant.id).await?;
+    Ok(ResponseJson(ApiResponse::success(TenantUsageReport {
+        tenant_id: tenant.id,
+        quota,
+        usage,
+    })))
+}
+
+/// Create an organization with the signed-in account as its first member
+pub async fn create_tenant(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<SignedInUser>,
+    Json(payload): Json<CreateTenant>,
+) -> Result<ResponseJson<ApiResponse<Tenant>>, ApiError> {
+    Validator::check(&payload).finish()?;
+    let SignedInUser(user_id) = user.ok_or_else(sign_in_required)?;
+    let name = payload.name.trim();
+    ensure_name_available(&deployment, name, None).await?;
+
+    let pool = &deployment.db().pool;
+    let tenant = Tenant::create(
+        pool,
+        &CreateTenant {
+            name: name.to_string(),
+        },
+    )
+    .await?;
+    Tenant::add_member(pool, tenant.id, user_id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "tenant_created",
+            serde_json::json!({
+                "tenant_id": tenant.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(tenant)))
+}
+
+pub async fn update_tenant(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<SignedInUser>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTenant>,
+) -> Result<ResponseJson<ApiResponse<Tenant>>, ApiError> {
+    Validator::check(&payload).finish()?;
+    require_member(&deployment, user, id).await?;
+    let name = payload.name.trim();
+    ensure_name_available(&deployment, name, Some(id)).await?;
+
+    let tenant = Tenant::rename(&deployment.db().pool, id, name)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(tenant)))
+}
+
+/// Replace the quotas of an organization; limits left out are lifted. Quotas bound what an
+/// organization may use of the instance, so only an instance admin sets them.
+pub async fn set_tenant_quota(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<SignedInUser>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTenantQuota>,
+) -> Result<ResponseJson<ApiResponse<TenantQuota>>, ApiError> {
+    Validator::check(&payload).finish()?;
+    find_tenant(&deployment, id).await?;
+    require_instance_admin(&deployment, user).await?;
+    let quota = TenantQuota::upsert(&deployment.db().pool, id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "tenant_quota_updated",
+            serde_json::json!({
+                "tenant_id": id.to_string(),
+                "max_concurrent_executions": payload.max_concurrent_executions,
+                "has_spend_limit": payload.monthly_spend_limit.is_some(),
+                "has_storage_limit": payload.storage_limit_bytes.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(quota)))
+}
+
+/// Delete an organization once its projects and repositories are gone. The default organization
+/// is kept, since requests without `X-Tenant-Id` work in it.
+pub async fn delete_tenant(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<SignedInUser>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if id == Tenant::DEFAULT_ID {
+        return Err(ApiError::BadRequest(
+            "The default organization cannot be deleted".to_string(),
+        ));
+    }
+    require_member(&deployment, user, id).await?;
+    if Tenant::delete(&deployment.db().pool, id).await? == 0 {
+        return Err(ApiError::Conflict(
+            "Delete the organization's projects and repositories first".to_string(),
+        ));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Accounts that may work in an organization
+pub async fn list_tenant_members(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<SignedInUser>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<TenantMember>>>, ApiError> {
+    require_member(&deployment, user, id).await?;
+    let members = Tenant::find_members(&deployment.db().pool, id).await?;
+    Ok(ResponseJson(ApiResponse::success(members)))
+}
+
+pub async fn add_tenant_member(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<SignedInUser>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<AddTenantMember>,
+) -> Result<ResponseJson<ApiResponse<TenantMember>>, ApiError> {
+    if id == Tenant::DEFAULT_ID {
+        return Err(ApiError::BadRequest(
+            "Everyone is a member of the default organization".to_string(),
+        ));
+    }
+    require_member(&deployment, user, id).await?;
+    let member = Tenant::add_member(&deployment.db().pool, id, payload.user_id).await?;
+    Ok(ResponseJson(ApiResponse::success(member)))
+}
+
+pub async fn remove_tenant_member(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<SignedInUser>,
+    Path((id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    require_member(&deployment, user, id).await?;
+    let pool = &deployment.db().pool;
+    if id == Tenant::DEFAULT_ID || !Tenant::has_member(pool, id, user_id).await? {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    if Tenant::remove_member(pool, id, user_id).await? == 0 {
+        return Err(ApiError::Conflict(
+            "An organization keeps at least one member".to_string(),
+        ));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/tenants", get(list_tenants).post(create_tenant))
+        .route("/tenants/current", get(get_current_tenant))
+        .route("/tenants/current/usage", get(get_current_tenant_usage))
+        .route("/tenants/{id}", patch(update_tenant).delete(delete_tenant))
+        .route("/tenants/{id}/quota", put(set_tenant_quota))
+        .route(
+            "/tenants/{id}/members",
+            get(list_tenant_members).post(add_tenant_member),
+        )
+        .route(
+            "/tenants/{id}/members/{user_id}",
+            delete(remove_tenant_member),
+        )
+}
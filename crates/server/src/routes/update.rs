@@ -0,0 +1,66 @@
+use axum::{
+    Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use deployment::Deployment;
+use services::services::self_update::{self, UpdateConfig, UpdateStatus};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+async fn update_config(deployment: &DeploymentImpl) -> UpdateConfig {
+    deployment.config().read().await.update.clone()
+}
+
+/// Latest release of the configured channel, and any update being installed
+pub async fn get_update_status(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UpdateStatus>>, ApiError> {
+    let config = update_config(&deployment).await;
+    Ok(ResponseJson(ApiResponse::success(
+        self_update::check(&config).await?,
+    )))
+}
+
+/// Download and verify the latest release, ready to install
+pub async fn download_update(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UpdateStatus>>, ApiError> {
+    let config = update_config(&deployment).await;
+    self_update::download(&config).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        self_update::check(&config).await?,
+    )))
+}
+
+/// Install the downloaded release once running executions finish, then restart
+pub async fn install_update(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UpdateStatus>>, ApiError> {
+    let config = update_config(&deployment).await;
+    self_update::install(deployment.db().pool.clone(), &config).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        self_update::check(&config).await?,
+    )))
+}
+
+/// Stop waiting for executions to finish; the downloaded release stays ready to install
+pub async fn cancel_update(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UpdateStatus>>, ApiError> {
+    self_update::cancel();
+    let config = update_config(&deployment).await;
+    Ok(ResponseJson(ApiResponse::success(
+        self_update::check(&config).await?,
+    )))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/update", get(get_update_status))
+        .route("/update/download", post(download_update))
+        .route("/update/install", post(install_update))
+        .route("/update/cancel", post(cancel_update))
+}
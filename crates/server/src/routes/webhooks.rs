@@ -0,0 +1,204 @@
+use axum::{
+    Router, body::Bytes, extract::State, http::HeaderMap, response::Json as ResponseJson,
+    routing::post,
+};
+use db::models::{
+    merge::{Merge, PrMerge},
+    repo::Repo,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use serde::Serialize;
+use serde_json::{Value, json};
+use services::services::{
+    comment_triggers::{self, COMMENT_TRIGGER_JOB, PrCommentEvent},
+    git_provider,
+    jobs::JobHandler,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::sessions::{FollowUpStart, start_or_queue_follow_up},
+};
+
+/// What a webhook delivery led to
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum CommentTriggerOutcome {
+    /// Not a new comment with a trigger phrase on an open pull request opened from a workspace
+    Ignored,
+    /// The delivery is queued as a job that starts or queues the follow-up
+    Accepted { job_id: Uuid },
+    Started {
+        workspace_id: Uuid,
+        execution_process_id: Uuid,
+    },
+    /// The workspace is busy; the follow-up runs when the current turn finishes
+    Queued { workspace_id: Uuid },
+}
+
+pub async fn github_webhook(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<CommentTriggerOutcome>>, ApiError> {
+    let config = deployment.config().read().await.comment_triggers.clone();
+    if !config.enabled {
+        return Err(ApiError::Forbidden(
+            "Comment triggers are disabled".to_string(),
+        ));
+    }
+    let Some(secret) = config.webhook_secret.as_deref().filter(|s| !s.is_empty()) else {
+        return Err(ApiError::Forbidden(
+            "Set a webhook secret before receiving webhooks".to_string(),
+        ));
+    };
+    let signature = headers
+        .get("X-Hub-Signature-256")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if !comment_triggers::verify_signature(secret, signature, &body) {
+        return Err(ApiError::Forbidden("Invalid webhook signature".to_string()));
+    }
+
+    let event_type = headers
+        .get("X-GitHub-Event")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let payload: Value = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid webhook payload: {e}")))?;
+
+    let triggered =
+        comment_triggers::parse_github_event(event_type, &payload).is_some_and(|event| {
+            comment_triggers::author_allowed(&config, &event)
+                && comment_triggers::matched_phrase(&config, &event.body).is_some()
+        });
+    if !triggered {
+        return Ok(ResponseJson(ApiResponse::success(
+            CommentTriggerOutcome::Ignored,
+        )));
+    }
+
+    let job = deployment
+        .jobs()
+        .enqueue(
+            COMMENT_TRIGGER_JOB,
+            json!({ "event_type": event_type, "payload": payload }),
+        )
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(
+        CommentTriggerOutcome::Accepted { job_id: job.id },
+    )))
+}
+
+/// The hunk of the pull request diff a review comment is on, for more context than the hunk in
+/// the delivery; None for conversation comments and when the diff cannot be fetched
+async fn fetch_diff_hunk(
+    deployment: &DeploymentImpl,
+    pr_merge: &PrMerge,
+    event: &PrCommentEvent,
+) -> Option<String> {
+    let (Some(path), Some(line)) = (event.path.as_deref(), event.line) else {
+        return None;
+    };
+    let repo = Repo::find_by_id(&deployment.db().pool, pr_merge.repo_id)
+        .await
+        .ok()
+        .flatten()?;
+    let provider = git_provider::create_provider(&repo.path).ok()?;
+    let (_, repo_id) = git_provider::detect_provider(&repo.path).ok()?;
+
+    let diff = match provider
+        .get_diff(&repo_id, pr_merge.pr_info.number as u64)
+        .await
+    {
+        Ok(diff) => diff,
+        Err(e) => {
+            tracing::warn!("Failed to fetch the diff of {}: {}", event.pr_url, e);
+            return None;
+        }
+    };
+    diff.iter()
+        .find(|file| file.path == path)
+        .and_then(|file| file.hunk_at(u64::try_from(line).ok()?))
+        .map(str::to_string)
+}
+
+/// Start or queue the follow-up requested by a delivery queued by [`github_webhook`]
+async fn run_comment_trigger(
+    deployment: &DeploymentImpl,
+    delivery: &Value,
+) -> Result<CommentTriggerOutcome, ApiError> {
+    let config = deployment.config().read().await.comment_triggers.clone();
+    let event_type = delivery["event_type"].as_str().unwrap_or_default();
+    let ignored = Ok(CommentTriggerOutcome::Ignored);
+    let Some(event) = comment_triggers::parse_github_event(event_type, &delivery["payload"]) else {
+        return ignored;
+    };
+    if !config.enabled
+        || !comment_triggers::author_allowed(&config, &event)
+        || comment_triggers::matched_phrase(&config, &event.body).is_none()
+    {
+        return ignored;
+    }
+
+    let pool = &deployment.db().pool;
+    let Some(pr_merge) = Merge::get_open_prs(pool)
+        .await?
+        .into_iter()
+        .find(|pr| comment_triggers::same_pr_url(&pr.pr_info.url, &event.pr_url))
+    else {
+        tracing::debug!("No open pull request for {}", event.pr_url);
+        return ignored;
+    };
+    let Some(workspace) = Workspace::find_by_id(pool, pr_merge.workspace_id).await? else {
+        return ignored;
+    };
+    let diff_hunk = fetch_diff_hunk(deployment, &pr_merge, &event).await;
+    let prompt = comment_triggers::follow_up_prompt(&event, diff_hunk.as_deref());
+
+    let outcome = match start_or_queue_follow_up(deployment, &workspace, prompt).await? {
+        FollowUpStart::Queued => CommentTriggerOutcome::Queued {
+            workspace_id: workspace.id,
+        },
+        FollowUpStart::Started(execution_process) => CommentTriggerOutcome::Started {
+            workspace_id: workspace.id,
+            execution_process_id: execution_process.id,
+        },
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_comment_follow_up",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "queued": matches!(outcome, CommentTriggerOutcome::Queued { .. }),
+                "review_comment": event.path.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(outcome)
+}
+
+/// Handler of [`COMMENT_TRIGGER_JOB`] jobs
+pub fn comment_trigger_handler(deployment: DeploymentImpl) -> impl JobHandler {
+    move |delivery: Value| {
+        let deployment = deployment.clone();
+        async move {
+            let outcome = run_comment_trigger(&deployment, &delivery)
+                .await
+                .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+            tracing::debug!("Comment trigger delivery handled: {:?}", outcome);
+            Ok::<_, anyhow::Error>(())
+        }
+    }
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/webhooks/github", post(github_webhook))
+}
@@ -0,0 +1,69 @@
+//! Startup of the server in test mode (`--test-mode`), see `utils::test_mode`.
+
+use std::path::PathBuf;
+
+use deployment::{Deployment, DeploymentError};
+use git2::Repository;
+use services::services::{
+    config::save_config_to_file,
+    git_provider::{self, ProviderHostMapping, ProviderType},
+};
+use utils::assets::{asset_dir, config_path};
+
+use crate::DeploymentImpl;
+
+/// Host of the demo repository's remote. It never resolves; test mode maps it to GitHub, whose
+/// calls the mock provider answers.
+pub const DEMO_REMOTE_HOST: &str = "mock.invalid";
+
+/// Data directory of a test run without `--data-dir`, fresh for every process
+pub fn data_dir() -> Result<PathBuf, std::io::Error> {
+    let dir = std::env::temp_dir().join(format!("vibe-kanban-test-{}", std::process::id()));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Create the demo repository and seed the database with the project using it. The repository
+/// fetches from a remote on [`DEMO_REMOTE_HOST`] and pushes to a bare repository next to it, so
+/// branches can be pushed and pull requests opened without a network.
+pub async fn seed(deployment: &DeploymentImpl) -> Result<(), DeploymentError> {
+    {
+        let mut config = deployment.config().write().await;
+        if !config
+            .git_provider_hosts
+            .iter()
+            .any(|mapping| mapping.host == DEMO_REMOTE_HOST)
+        {
+            config.git_provider_hosts.push(ProviderHostMapping {
+                host: DEMO_REMOTE_HOST.to_string(),
+                provider: ProviderType::GitHub,
+            });
+            save_config_to_file(&config, &config_path()).await?;
+            git_provider::configure_host_mappings(config.git_provider_hosts.clone());
+        }
+    }
+
+    let repos = asset_dir().join("test-repos");
+    let repo_path = repos.join("demo");
+    if !repo_path.exists() {
+        let origin = repos.join("demo-origin.git");
+        Repository::init_bare(&origin)?;
+        deployment
+            .git()
+            .initialize_repo_with_main_branch(&repo_path)?;
+        let repo = Repository::open(&repo_path)?;
+        repo.remote(
+            "origin",
+            &format!("https://{DEMO_REMOTE_HOST}/vibe-kanban/demo.git"),
+        )?;
+        repo.remote_set_pushurl("origin", origin.to_str())?;
+    }
+
+    if db::seed::seed(&deployment.db().pool, &repo_path).await? {
+        tracing::info!("Seeded the test database");
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+//! Up-front checks of request bodies. Malformed input is rejected with field-level 422 errors
+//! before it reaches the git or provider layers, where it would fail as an opaque 500.
+
+use db::models::{image::Image, project::Project, repo::Repo, task::Task, workspace::Workspace};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+
+pub const MAX_TITLE_LEN: usize = 255;
+pub const MAX_BODY_LEN: usize = 65_536;
+pub const MAX_BRANCH_NAME_LEN: usize = 255;
+
+/// Why one field of a request was rejected, e.g. `repos[0].target_branch`
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Request bodies that can be checked without touching the database
+pub trait Validate {
+    fn validate(&self, v: &mut Validator);
+}
+
+/// Collects the field errors of one request, so all of them are reported at once
+#[derive(Debug, Default)]
+pub struct Validator {
+    prefix: String,
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    /// Start with the field checks of `request`; existence checks can be added before `finish`
+    pub fn check<T: Validate>(request: &T) -> Self {
+        let mut v = Self::default();
+        request.validate(&mut v);
+        v
+    }
+
+    /// Run `f` with field names nested under `prefix`, e.g. `task.title`
+    pub fn nested(&mut self, prefix: &str, f: impl FnOnce(&mut Self)) {
+        let outer = std::mem::replace(&mut self.prefix, format!("{}{prefix}.", self.prefix));
+        f(self);
+        self.prefix = outer;
+    }
+
+    pub fn error(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: format!("{}{field}", self.prefix),
+            message: message.into(),
+        });
+    }
+
+    pub fn title(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.error(field, "must not be empty");
+        } else {
+            self.max_len(field, value, MAX_TITLE_LEN);
+        }
+    }
+
+    pub fn body(&mut self, field: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            self.max_len(field, value, MAX_BODY_LEN);
+        }
+    }
+
+    pub fn branch_name(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.error(field, "must not be empty");
+        } else if value.chars().count() > MAX_BRANCH_NAME_LEN {
+            self.max_len(field, value, MAX_BRANCH_NAME_LEN);
+        } else if !utils::git::is_valid_branch_name(value) {
+            self.error(field, format!("'{value}' is not a valid git branch name"));
+        }
+    }
+
+    pub fn not_empty<T>(&mut self, field: &str, items: &[T]) {
+        if items.is_empty() {
+            self.error(field, "must not be empty");
+        }
+    }
+
+    fn max_len(&mut self, field: &str, value: &str, max: usize) {
+        let len = value.chars().count();
+        if len > max {
+            self.error(
+                field,
+                format!("must be at most {max} characters long (got {len})"),
+            );
+        }
+    }
+
+    pub async fn project_exists(
+        &mut self,
+        pool: &SqlitePool,
+        field: &str,
+        id: Uuid,
+    ) -> Result<(), ApiError> {
+        if Project::find_by_id(pool, id).await?.is_none() {
+            self.error(field, format!("no project with id {id}"));
+        }
+        Ok(())
+    }
+
+    pub async fn task_exists(
+        &mut self,
+        pool: &SqlitePool,
+        field: &str,
+        id: Uuid,
+    ) -> Result<(), ApiError> {
+        if Task::find_by_id(pool, id).await?.is_none() {
+            self.error(field, format!("no task with id {id}"));
+        }
+        Ok(())
+    }
+
+    pub async fn repo_exists(
+        &mut self,
+        pool: &SqlitePool,
+        field: &str,
+        id: Uuid,
+    ) -> Result<(), ApiError> {
+        if Repo::find_by_id(pool, id).await?.is_none() {
+            self.error(field, format!("no repository with id {id}"));
+        }
+        Ok(())
+    }
+
+    pub async fn workspace_exists(
+        &mut self,
+        pool: &SqlitePool,
+        field: &str,
+        id: Uuid,
+    ) -> Result<(), ApiError> {
+        if Workspace::find_by_id(pool, id).await?.is_none() {
+            self.error(field, format!("no task attempt with id {id}"));
+        }
+        Ok(())
+    }
+
+    pub async fn images_exist(
+        &mut self,
+        pool: &SqlitePool,
+        field: &str,
+        ids: &[Uuid],
+    ) -> Result<(), ApiError> {
+        for (i, id) in ids.iter().enumerate() {
+            if Image::find_by_id(pool, *id).await?.is_none() {
+                self.error(&format!("{field}[{i}]"), format!("no image with id {id}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// `Err(ApiError::Validation)` with every collected error, if there are any
+    pub fn finish(self) -> Result<(), ApiError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::Validation(self.errors))
+        }
+    }
+}
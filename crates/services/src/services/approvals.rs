@@ -3,10 +3,7 @@ pub mod executor_approvals;
 use std::{collections::HashMap, sync::Arc, time::Duration as StdDuration};
 
 use dashmap::DashMap;
-use db::models::{
-    execution_process::ExecutionProcess,
-    task::{Task, TaskStatus},
-};
+use db::models::{execution_process::ExecutionProcess, task::TaskStatus};
 use executors::{
     approvals::ToolCallMetadata,
     logs::{
@@ -25,6 +22,8 @@ use utils::{
 };
 use uuid::Uuid;
 
+use crate::services::task_events::{TaskActor, TaskEventLog};
+
 #[derive(Debug)]
 struct PendingApproval {
     entry_index: usize,
@@ -137,6 +136,7 @@ impl Approvals {
     pub async fn respond(
         &self,
         pool: &SqlitePool,
+        task_events: &TaskEventLog,
         id: &str,
         req: ApprovalResponse,
     ) -> Result<(ApprovalStatus, ToolContext), ApprovalError> {
@@ -173,7 +173,9 @@ impl Approvals {
             ) && let Ok(ctx) =
                 ExecutionProcess::load_context(pool, tool_ctx.execution_process_id).await
                 && ctx.task.status == TaskStatus::InReview
-                && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InProgress).await
+                && let Err(e) = task_events
+                    .set_status(ctx.task.id, TaskStatus::InProgress, &TaskActor::user())
+                    .await
             {
                 tracing::warn!(
                     "Failed to update task status to InProgress after approval response: {}",
@@ -258,10 +260,20 @@ impl Approvals {
     }
 }
 
-pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_id: Uuid) {
+pub(crate) async fn ensure_task_in_review(
+    pool: &SqlitePool,
+    task_events: &TaskEventLog,
+    execution_process_id: Uuid,
+) {
     if let Ok(ctx) = ExecutionProcess::load_context(pool, execution_process_id).await
         && ctx.task.status == TaskStatus::InProgress
-        && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InReview).await
+        && let Err(e) = task_events
+            .set_status(
+                ctx.task.id,
+                TaskStatus::InReview,
+                &TaskActor::system("approval_request"),
+            )
+            .await
     {
         tracing::warn!(
             "Failed to update task status to InReview for approval request: {}",
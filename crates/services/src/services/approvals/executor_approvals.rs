@@ -7,12 +7,15 @@ use serde_json::Value;
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::Approvals, notification::NotificationService, task_events::TaskEventLog,
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
     db: DBService,
     notification_service: NotificationService,
+    task_events: TaskEventLog,
     execution_process_id: Uuid,
 }
 
@@ -21,12 +24,14 @@ impl ExecutorApprovalBridge {
         approvals: Approvals,
         db: DBService,
         notification_service: NotificationService,
+        task_events: TaskEventLog,
         execution_process_id: Uuid,
     ) -> Arc<Self> {
         Arc::new(Self {
             approvals,
             db,
             notification_service,
+            task_events,
             execution_process_id,
         })
     }
@@ -40,7 +45,8 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_input: Value,
         tool_call_id: &str,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
-        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
+        super::ensure_task_in_review(&self.db.pool, &self.task_events, self.execution_process_id)
+            .await;
 
         let request = ApprovalRequest::from_create(
             CreateApprovalRequest {
@@ -0,0 +1,270 @@
+//! Execution artifacts and exported transcripts in object storage.
+//!
+//! The logs of each finished execution are copied to object storage by a
+//! [`STORE_EXECUTION_LOGS_JOB`], and sessions are exported as Markdown transcripts on request.
+//! Both are written through [`quota::store_object`], so they count against the storage quota of
+//! the tenant of their project, and are downloaded through signed URLs like attachments.
+
+use std::{fmt::Write, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::ExecutionProcess,
+    execution_process_logs::ExecutionProcessLogs,
+    session::Session,
+    stored_object::{StoredObject, StoredObjectKind},
+    tenant::Tenant,
+    workspace::Workspace,
+};
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::services::{
+    jobs::JobQueue,
+    object_store::{ObjectStore, SignedUrl, StorageError},
+    quota::{self, QuotaError},
+};
+
+/// Job that copies the logs of a finished execution to object storage, with the
+/// `execution_process_id`
+pub const STORE_EXECUTION_LOGS_JOB: &str = "artifacts.store_execution_logs";
+
+/// Give the log writer of a finished execution time to flush before its logs are copied
+const STORE_LOGS_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+pub enum ArtifactError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Quota(#[from] QuotaError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+#[derive(Clone)]
+pub struct ArtifactService {
+    pool: SqlitePool,
+    store: Arc<dyn ObjectStore>,
+    jobs: JobQueue,
+    signed_url_ttl: Duration,
+}
+
+impl ArtifactService {
+    pub fn new(
+        pool: SqlitePool,
+        store: Arc<dyn ObjectStore>,
+        jobs: JobQueue,
+        signed_url_ttl: Duration,
+    ) -> Self {
+        Self {
+            pool,
+            store,
+            jobs,
+            signed_url_ttl,
+        }
+    }
+
+    fn execution_logs_key(execution_process_id: Uuid) -> String {
+        format!("artifacts/executions/{execution_process_id}/logs.jsonl")
+    }
+
+    fn transcript_key(session_id: Uuid) -> String {
+        format!("transcripts/sessions/{session_id}.md")
+    }
+
+    /// The tenant whose quota the objects of a session count against
+    async fn tenant_of_session(&self, session: &Session) -> Result<Uuid, ArtifactError> {
+        let workspace = Workspace::find_by_id(&self.pool, session.workspace_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let task = workspace
+            .parent_task(&self.pool)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        Ok(Tenant::id_for_project(&self.pool, task.project_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?)
+    }
+
+    async fn signed_url(&self, key: &str) -> Result<SignedUrl, ArtifactError> {
+        Ok(self.store.signed_url(key, self.signed_url_ttl).await?)
+    }
+
+    /// Queue copying the logs of an execution that just finished
+    pub async fn queue_execution_logs(&self, execution_process_id: Uuid) {
+        if let Err(e) = self
+            .jobs
+            .enqueue_after(
+                STORE_EXECUTION_LOGS_JOB,
+                json!({ "execution_process_id": execution_process_id }),
+                STORE_LOGS_DELAY,
+            )
+            .await
+        {
+            tracing::error!(
+                "Failed to queue storing the logs of execution {}: {}",
+                execution_process_id,
+                e
+            );
+        }
+    }
+
+    /// Copy the logs of an execution to object storage. An execution whose tenant is out of
+    /// storage keeps its logs in the database only.
+    pub async fn store_execution_logs(
+        &self,
+        execution_process_id: Uuid,
+    ) -> Result<(), ArtifactError> {
+        let Some(execution) =
+            ExecutionProcess::find_by_id(&self.pool, execution_process_id).await?
+        else {
+            return Ok(());
+        };
+        let session = Session::find_by_id(&self.pool, execution.session_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let tenant_id = self.tenant_of_session(&session).await?;
+
+        let records =
+            ExecutionProcessLogs::find_by_execution_id(&self.pool, execution_process_id).await?;
+        let mut logs = String::new();
+        for line in records.iter().flat_map(|record| record.logs.lines()) {
+            if !line.trim().is_empty() {
+                logs.push_str(line);
+                logs.push('\n');
+            }
+        }
+
+        match quota::store_object(
+            &self.pool,
+            self.store.as_ref(),
+            tenant_id,
+            StoredObjectKind::Artifact,
+            &Self::execution_logs_key(execution_process_id),
+            logs.into_bytes(),
+            Some("application/x-ndjson"),
+        )
+        .await
+        {
+            Ok(_) => Ok(()),
+            Err(QuotaError::Exceeded(exceeded)) => {
+                tracing::warn!(
+                    "Not storing the logs of execution {}: {}",
+                    execution_process_id,
+                    exceeded
+                );
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Run a [`STORE_EXECUTION_LOGS_JOB`] job
+    pub async fn store_execution_logs_job(&self, payload: Value) -> anyhow::Result<()> {
+        let execution_process_id: Uuid =
+            serde_json::from_value(payload["execution_process_id"].clone())?;
+        Ok(self.store_execution_logs(execution_process_id).await?)
+    }
+
+    /// URL to download the stored logs of an execution; None until they are stored
+    pub async fn execution_logs_url(
+        &self,
+        execution_process_id: Uuid,
+    ) -> Result<Option<SignedUrl>, ArtifactError> {
+        let key = Self::execution_logs_key(execution_process_id);
+        if StoredObject::find_by_key(&self.pool, &key).await?.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(self.signed_url(&key).await?))
+    }
+
+    /// Export the turns of a session as a Markdown transcript, replacing an earlier export, and
+    /// return the URL to download it
+    pub async fn export_transcript(&self, session: &Session) -> Result<SignedUrl, ArtifactError> {
+        let tenant_id = self.tenant_of_session(session).await?;
+        let turns = CodingAgentTurn::find_by_session_id(&self.pool, session.id).await?;
+        let key = Self::transcript_key(session.id);
+        quota::store_object(
+            &self.pool,
+            self.store.as_ref(),
+            tenant_id,
+            StoredObjectKind::Transcript,
+            &key,
+            render_transcript(session, &turns).into_bytes(),
+            Some("text/markdown; charset=utf-8"),
+        )
+        .await?;
+        self.signed_url(&key).await
+    }
+}
+
+fn render_transcript(session: &Session, turns: &[CodingAgentTurn]) -> String {
+    let mut out = format!(
+        "# Session {}\n\nExported {}\n",
+        session.id,
+        Utc::now().to_rfc3339()
+    );
+    for (index, turn) in turns.iter().enumerate() {
+        let _ = write!(
+            out,
+            "\n## Turn {} ({})\n\n### Prompt\n\n{}\n\n### Response\n\n{}\n",
+            index + 1,
+            turn.created_at.to_rfc3339(),
+            turn.prompt
+                .as_deref()
+                .unwrap_or("_No prompt recorded_")
+                .trim(),
+            turn.summary
+                .as_deref()
+                .unwrap_or("_No response recorded_")
+                .trim(),
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(prompt: Option<&str>, summary: Option<&str>) -> CodingAgentTurn {
+        CodingAgentTurn {
+            id: Uuid::new_v4(),
+            execution_process_id: Uuid::new_v4(),
+            agent_session_id: None,
+            prompt: prompt.map(str::to_string),
+            summary: summary.map(str::to_string),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn transcript_lists_turns_in_order() {
+        let session = Session {
+            id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            executor: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let transcript = render_transcript(
+            &session,
+            &[
+                turn(Some("Add a login page\n"), Some("Added the page.")),
+                turn(Some("Fix the tests"), None),
+            ],
+        );
+
+        assert!(transcript.starts_with(&format!("# Session {}", session.id)));
+        let first = transcript.find("## Turn 1").unwrap();
+        let second = transcript.find("## Turn 2").unwrap();
+        assert!(first < second);
+        assert!(transcript.contains("### Prompt\n\nAdd a login page\n\n### Response"));
+        assert!(transcript.contains("_No response recorded_"));
+    }
+}
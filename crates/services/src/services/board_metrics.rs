@@ -0,0 +1,439 @@
+//! Kanban reports of a project: cycle time, throughput and aging work in progress.
+//!
+//! The reports are computed from the task event log, which records every task entering a board
+//! column. Work on a task starts when it first enters a working column and finishes when it last
+//! enters done, so a task that is reopened only counts once it is done again. Tasks that had no
+//! recorded column change since they were created age from their creation.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Duration, Utc};
+use db::models::{board::BoardSnapshot, task::TaskStatus, task_event::StatusChange};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Weeks reported when no start is asked for
+pub const DEFAULT_WEEKS: i64 = 12;
+/// Longest period reported at once
+pub const MAX_WEEKS: i64 = 104;
+
+/// Columns a task is being worked on in; work starts when a task first enters one
+const WORKING_COLUMNS: [TaskStatus; 2] = [TaskStatus::InProgress, TaskStatus::InReview];
+
+/// Columns work in progress ages in
+const WIP_COLUMNS: [TaskStatus; 3] = [
+    TaskStatus::Todo,
+    TaskStatus::InProgress,
+    TaskStatus::InReview,
+];
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TaskCycleTime {
+    pub task_id: Uuid,
+    pub title: String,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CycleTimeSummary {
+    pub average_hours: Option<f64>,
+    pub median_hours: Option<f64>,
+    /// 85% of the tasks were done within this time
+    pub p85_hours: Option<f64>,
+    /// Tasks completed in the period that were worked on, most recently completed first
+    pub tasks: Vec<TaskCycleTime>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WeeklyThroughput {
+    /// Monday the week starts on, at midnight UTC
+    pub week_start: DateTime<Utc>,
+    pub completed: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AgingTask {
+    pub task_id: Uuid,
+    pub title: String,
+    pub entered_at: DateTime<Utc>,
+    pub age_hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ColumnAging {
+    pub status: TaskStatus,
+    /// Oldest first
+    pub tasks: Vec<AgingTask>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BoardMetrics {
+    pub project_id: Uuid,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub cycle_time: CycleTimeSummary,
+    /// Tasks completed per week of the period, including weeks without any
+    pub throughput: Vec<WeeklyThroughput>,
+    pub aging_wip: Vec<ColumnAging>,
+}
+
+/// Report of [`BoardMetrics`] to export as CSV
+#[derive(Debug, Clone, Copy, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricsReport {
+    CycleTime,
+    Throughput,
+    AgingWip,
+}
+
+impl MetricsReport {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::CycleTime => "cycle-time.csv",
+            Self::Throughput => "throughput.csv",
+            Self::AgingWip => "aging-wip.csv",
+        }
+    }
+}
+
+fn hours(duration: Duration) -> f64 {
+    duration.num_seconds() as f64 / 3600.0
+}
+
+/// Midnight of the Monday starting the week of `at`
+fn week_start(at: DateTime<Utc>) -> DateTime<Utc> {
+    let monday = at.date_naive() - Duration::days(at.weekday().num_days_from_monday() as i64);
+    monday.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], percent: usize) -> Option<f64> {
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+#[derive(Default)]
+struct TaskHistory<'a> {
+    title: Option<&'a str>,
+    started_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+    /// Column entries, oldest first
+    entries: Vec<(TaskStatus, DateTime<Utc>)>,
+}
+
+fn histories(changes: &[StatusChange]) -> HashMap<Uuid, TaskHistory<'_>> {
+    let mut histories: HashMap<Uuid, TaskHistory> = HashMap::new();
+    for change in changes {
+        let history = histories.entry(change.task_id).or_default();
+        if let Some(title) = &change.title {
+            history.title = Some(title.as_str());
+        }
+        let Some(status) = &change.status else {
+            continue;
+        };
+        history.entries.push((status.clone(), change.created_at));
+        if WORKING_COLUMNS.contains(status) && history.started_at.is_none() {
+            history.started_at = Some(change.created_at);
+        }
+        history.completed_at = (*status == TaskStatus::Done).then_some(change.created_at);
+    }
+    histories
+}
+
+/// Reports of the period from `since` to `until` from the column changes of the project, oldest
+/// first, and its current board
+pub fn compute(
+    board: &BoardSnapshot,
+    changes: &[StatusChange],
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> BoardMetrics {
+    let histories = histories(changes);
+    let in_period = |at: DateTime<Utc>| at >= since && at < until;
+
+    let mut tasks: Vec<TaskCycleTime> = histories
+        .iter()
+        .filter_map(|(task_id, history)| {
+            let completed_at = history.completed_at.filter(|at| in_period(*at))?;
+            let started_at = history.started_at.filter(|at| *at <= completed_at)?;
+            Some(TaskCycleTime {
+                task_id: *task_id,
+                title: history.title.unwrap_or_default().to_string(),
+                started_at,
+                completed_at,
+                hours: hours(completed_at - started_at),
+            })
+        })
+        .collect();
+    tasks.sort_by(|a, b| b.completed_at.cmp(&a.completed_at));
+    let mut sorted: Vec<f64> = tasks.iter().map(|task| task.hours).collect();
+    sorted.sort_by(f64::total_cmp);
+    let cycle_time = CycleTimeSummary {
+        average_hours: (!sorted.is_empty())
+            .then(|| sorted.iter().sum::<f64>() / sorted.len() as f64),
+        median_hours: percentile(&sorted, 50),
+        p85_hours: percentile(&sorted, 85),
+        tasks,
+    };
+
+    let mut throughput = Vec::new();
+    let mut week = week_start(since);
+    while week < until {
+        let next = week + Duration::weeks(1);
+        let completed = histories
+            .values()
+            .filter_map(|history| history.completed_at)
+            .filter(|at| in_period(*at) && *at >= week && *at < next)
+            .count();
+        throughput.push(WeeklyThroughput {
+            week_start: week,
+            completed,
+        });
+        week = next;
+    }
+
+    let aging_wip = board
+        .columns
+        .iter()
+        .filter(|column| WIP_COLUMNS.contains(&column.status))
+        .map(|column| {
+            let mut tasks: Vec<AgingTask> = column
+                .tasks
+                .iter()
+                .map(|card| {
+                    let entered_at = histories
+                        .get(&card.id)
+                        .and_then(|history| {
+                            history
+                                .entries
+                                .iter()
+                                .rev()
+                                .find(|(status, _)| *status == card.status)
+                        })
+                        .map(|(_, entered_at)| *entered_at)
+                        .unwrap_or(card.created_at);
+                    AgingTask {
+                        task_id: card.id,
+                        title: card.title.clone(),
+                        entered_at,
+                        age_hours: hours(now - entered_at),
+                    }
+                })
+                .collect();
+            tasks.sort_by_key(|task| task.entered_at);
+            ColumnAging {
+                status: column.status.clone(),
+                tasks,
+            }
+        })
+        .collect();
+
+    BoardMetrics {
+        project_id: board.project_id,
+        since,
+        until,
+        cycle_time,
+        throughput,
+        aging_wip,
+    }
+}
+
+/// Quote a field if needed, and keep spreadsheets from running titles that look like formulas
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn csv_row(fields: &[String]) -> String {
+    let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    format!("{}\r\n", fields.join(","))
+}
+
+/// One report as CSV with a header row
+pub fn to_csv(metrics: &BoardMetrics, report: MetricsReport) -> String {
+    let mut csv = String::new();
+    match report {
+        MetricsReport::CycleTime => {
+            csv.push_str("task_id,title,started_at,completed_at,cycle_time_hours\r\n");
+            for task in &metrics.cycle_time.tasks {
+                csv.push_str(&csv_row(&[
+                    task.task_id.to_string(),
+                    task.title.clone(),
+                    task.started_at.to_rfc3339(),
+                    task.completed_at.to_rfc3339(),
+                    format!("{:.2}", task.hours),
+                ]));
+            }
+        }
+        MetricsReport::Throughput => {
+            csv.push_str("week_start,completed\r\n");
+            for week in &metrics.throughput {
+                csv.push_str(&csv_row(&[
+                    week.week_start.date_naive().to_string(),
+                    week.completed.to_string(),
+                ]));
+            }
+        }
+        MetricsReport::AgingWip => {
+            csv.push_str("status,task_id,title,entered_at,age_hours\r\n");
+            for column in &metrics.aging_wip {
+                for task in &column.tasks {
+                    csv.push_str(&csv_row(&[
+                        column.status.to_string(),
+                        task.task_id.to_string(),
+                        task.title.clone(),
+                        task.entered_at.to_rfc3339(),
+                        format!("{:.2}", task.age_hours),
+                    ]));
+                }
+            }
+        }
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use db::models::board::{BoardCard, BoardColumn};
+
+    use super::*;
+
+    /// Hours after Monday 2026-02-02 00:00 UTC
+    fn at(hours: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 2, 2, 0, 0, 0).unwrap() + Duration::hours(hours)
+    }
+
+    fn change(task_id: Uuid, status: Option<TaskStatus>, hours: i64) -> StatusChange {
+        StatusChange {
+            task_id,
+            title: status.as_ref().map(|_| "Fix login, again".to_string()),
+            status,
+            created_at: at(hours),
+        }
+    }
+
+    fn card(id: Uuid, status: TaskStatus, created_hours: i64) -> BoardCard {
+        BoardCard {
+            id,
+            title: "=cmd".to_string(),
+            status,
+            parent_workspace_id: None,
+            shared_task_id: None,
+            due_at: None,
+            created_at: at(created_hours),
+            updated_at: at(created_hours),
+            workspace_count: 0,
+            has_in_progress_attempt: false,
+            last_attempt_failed: false,
+            executor: None,
+            pr_status: None,
+            pr_number: None,
+            pr_url: None,
+        }
+    }
+
+    fn board(columns: Vec<BoardColumn>) -> BoardSnapshot {
+        BoardSnapshot {
+            project_id: Uuid::new_v4(),
+            columns,
+        }
+    }
+
+    #[test]
+    fn cycle_time_runs_from_first_start_to_last_completion() {
+        let (reopened, straight, unfinished) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let changes = vec![
+            change(reopened, Some(TaskStatus::Todo), 0),
+            change(reopened, Some(TaskStatus::InProgress), 2),
+            change(reopened, Some(TaskStatus::Done), 10),
+            change(reopened, Some(TaskStatus::InProgress), 20),
+            change(reopened, Some(TaskStatus::Done), 26),
+            change(straight, Some(TaskStatus::Todo), 1),
+            change(straight, Some(TaskStatus::Done), 5),
+            change(unfinished, Some(TaskStatus::InReview), 3),
+        ];
+        let metrics = compute(&board(vec![]), &changes, at(0), at(24 * 14), at(24 * 14));
+
+        let tasks = &metrics.cycle_time.tasks;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!((tasks[0].task_id, tasks[0].hours), (reopened, 24.0));
+        assert_eq!(metrics.cycle_time.median_hours, Some(24.0));
+
+        let weeks: Vec<_> = metrics
+            .throughput
+            .iter()
+            .map(|week| (week.week_start, week.completed))
+            .collect();
+        assert_eq!(weeks, [(at(0), 2), (at(24 * 7), 0)]);
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        assert_eq!(percentile(&sorted, 50), Some(5.0));
+        assert_eq!(percentile(&sorted, 85), Some(9.0));
+        assert_eq!(percentile(&[], 85), None);
+    }
+
+    #[test]
+    fn work_ages_from_entering_its_column_or_from_creation() {
+        let (moved, untracked) = (Uuid::new_v4(), Uuid::new_v4());
+        let changes = vec![
+            change(moved, Some(TaskStatus::Todo), 0),
+            change(moved, Some(TaskStatus::InProgress), 30),
+        ];
+        let board = board(vec![
+            BoardColumn {
+                status: TaskStatus::InProgress,
+                tasks: vec![
+                    card(moved, TaskStatus::InProgress, 0),
+                    card(untracked, TaskStatus::InProgress, 6),
+                ],
+            },
+            BoardColumn {
+                status: TaskStatus::Done,
+                tasks: vec![card(Uuid::new_v4(), TaskStatus::Done, 0)],
+            },
+        ]);
+        let metrics = compute(&board, &changes, at(0), at(48), at(48));
+
+        assert_eq!(metrics.aging_wip.len(), 1);
+        let ages: Vec<_> = metrics.aging_wip[0]
+            .tasks
+            .iter()
+            .map(|task| (task.task_id, task.age_hours))
+            .collect();
+        assert_eq!(ages, [(untracked, 42.0), (moved, 18.0)]);
+
+        let csv = to_csv(&metrics, MetricsReport::AgingWip);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("status,task_id,title,entered_at,age_hours")
+        );
+        assert_eq!(
+            lines.next(),
+            Some(format!("inprogress,{untracked},'=cmd,2026-02-02T06:00:00+00:00,42.00").as_str())
+        );
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("Fix login, again"), "\"Fix login, again\"");
+        assert_eq!(csv_field("Say \"hi\""), "\"Say \"\"hi\"\"\"");
+        assert_eq!(csv_field("plain"), "plain");
+    }
+}
@@ -0,0 +1,165 @@
+//! iCalendar feeds of task due dates.
+//!
+//! Every open or finished task with a due date becomes an all-day event on the day it is due in
+//! the user's timezone, so a calendar app shows it on the same day the board does. Cancelled
+//! tasks are left out.
+
+use std::fmt::Write;
+
+use chrono::Duration;
+use db::models::task::{Task, TaskStatus};
+
+use crate::services::schedule::Schedule;
+
+/// Content lines are folded after this many octets (RFC 5545, section 3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+/// The feed for `tasks` under the calendar name `name`
+pub fn feed(name: &str, tasks: &[Task], schedule: &Schedule) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//vibe-kanban//Due dates//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape(name)),
+        format!("X-WR-TIMEZONE:{}", schedule.timezone().name()),
+    ];
+    for task in tasks {
+        let Some(due_at) = task.due_at else {
+            continue;
+        };
+        if task.status == TaskStatus::Cancelled {
+            continue;
+        }
+        let due_on = schedule.local_date(due_at);
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@vibe-kanban", task.id));
+        lines.push(format!(
+            "DTSTAMP:{}",
+            task.updated_at.format("%Y%m%dT%H%M%SZ")
+        ));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", due_on.format("%Y%m%d")));
+        lines.push(format!(
+            "DTEND;VALUE=DATE:{}",
+            (due_on + Duration::days(1)).format("%Y%m%d")
+        ));
+        lines.push(format!("SUMMARY:{}", escape(&task.title)));
+        if let Some(description) = task.description.as_deref().filter(|d| !d.is_empty()) {
+            lines.push(format!("DESCRIPTION:{}", escape(description)));
+        }
+        lines.push(format!("CATEGORIES:{}", task.status));
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        let _ = write!(out, "{}\r\n", fold(&line));
+    }
+    out
+}
+
+/// Escapes a TEXT value
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Splits a content line into lines of at most 75 octets, never inside a character
+fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::services::schedule::{ScheduleConfig, WeekStart};
+
+    fn task(title: &str, status: TaskStatus, due_at: &str) -> Task {
+        let updated_at: DateTime<Utc> = "2025-01-02T03:04:05Z".parse().unwrap();
+        Task {
+            id: Uuid::nil(),
+            project_id: Uuid::nil(),
+            title: title.to_string(),
+            description: Some("First line\nsecond; third, fourth".to_string()),
+            status,
+            parent_workspace_id: None,
+            shared_task_id: None,
+            due_at: Some(due_at.parse().unwrap()),
+            created_at: updated_at,
+            updated_at,
+        }
+    }
+
+    fn schedule(timezone: &str) -> Schedule {
+        Schedule::new(&ScheduleConfig {
+            timezone: Some(timezone.to_string()),
+            week_start: WeekStart::Monday,
+            sprint_weeks: 2,
+            sprint_anchor: None,
+        })
+    }
+
+    #[test]
+    fn events_fall_on_the_local_due_day() {
+        let tasks = [task("Ship it", TaskStatus::Todo, "2025-01-22T20:00:00Z")];
+
+        let tokyo = feed("Board", &tasks, &schedule("Asia/Tokyo"));
+        assert!(tokyo.contains("X-WR-TIMEZONE:Asia/Tokyo\r\n"));
+        assert!(tokyo.contains("DTSTART;VALUE=DATE:20250123\r\nDTEND;VALUE=DATE:20250124\r\n"));
+
+        let utc = feed("Board", &tasks, &schedule("UTC"));
+        assert!(utc.contains("DTSTART;VALUE=DATE:20250122\r\n"));
+        assert!(utc.contains("DTSTAMP:20250102T030405Z\r\n"));
+        assert!(utc.contains("DESCRIPTION:First line\\nsecond\\; third\\, fourth\r\n"));
+        assert!(utc.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(utc.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn cancelled_tasks_are_left_out() {
+        let tasks = [
+            task("Kept", TaskStatus::Done, "2025-01-22T20:00:00Z"),
+            task("Dropped", TaskStatus::Cancelled, "2025-01-22T20:00:00Z"),
+        ];
+        let feed = feed("Board", &tasks, &schedule("UTC"));
+        assert!(feed.contains("SUMMARY:Kept\r\n"));
+        assert!(!feed.contains("Dropped"));
+    }
+
+    #[test]
+    fn long_lines_are_folded_between_characters() {
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = fold(&line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
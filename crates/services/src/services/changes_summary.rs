@@ -0,0 +1,237 @@
+//! A running summary of the agent's changes kept in the workspace.
+//!
+//! After every coding agent turn, `CHANGES.md` at the workspace root is rewritten from the
+//! structured tool-call timeline of the workspace's turns: what was asked, which files were
+//! touched and which commands ran. It sits next to the repositories rather than inside one, so it
+//! never ends up in a commit, and someone opening the worktree mid-attempt can see at a glance
+//! what has been done so far.
+
+use std::{collections::BTreeSet, fmt::Write, path::Path};
+
+use chrono::{DateTime, Utc};
+use executors::logs::{FileChange, NormalizedEntry};
+
+use crate::services::replay::{self, ReplayStep};
+
+pub const FILE_NAME: &str = "CHANGES.md";
+
+/// Longest request line kept for a turn
+const PROMPT_CHARS: usize = 200;
+/// Commands listed per turn before the rest are counted
+const MAX_COMMANDS: usize = 10;
+
+/// What one coding agent turn did
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnChanges {
+    pub started_at: DateTime<Utc>,
+    pub prompt: Option<String>,
+    pub summary: Option<String>,
+    /// Whether the turn finished successfully; None while it is still running
+    pub succeeded: Option<bool>,
+    /// Touched paths relative to the workspace root
+    pub files: BTreeSet<String>,
+    pub commands: Vec<String>,
+}
+
+impl TurnChanges {
+    pub fn from_entries(
+        started_at: DateTime<Utc>,
+        prompt: Option<String>,
+        summary: Option<String>,
+        succeeded: Option<bool>,
+        entries: &[NormalizedEntry],
+        workspace_root: &Path,
+    ) -> Self {
+        let mut files = BTreeSet::new();
+        let mut commands = Vec::new();
+        for step in replay::steps_from_entries(entries) {
+            match step {
+                ReplayStep::FileEdit { path, change } => {
+                    files.insert(relative(&path, workspace_root));
+                    if let FileChange::Rename { new_path } = change {
+                        files.insert(relative(&new_path, workspace_root));
+                    }
+                }
+                ReplayStep::Command { command } => commands.push(command),
+            }
+        }
+        Self {
+            started_at,
+            prompt,
+            summary,
+            succeeded,
+            files,
+            commands,
+        }
+    }
+}
+
+fn relative(path: &str, workspace_root: &Path) -> String {
+    Path::new(path)
+        .strip_prefix(workspace_root)
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+fn first_line(text: &str, max_chars: usize) -> String {
+    let line = text.trim().lines().next().unwrap_or_default().trim();
+    if line.chars().count() <= max_chars {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(max_chars).collect();
+    format!("{cut}...")
+}
+
+/// Render the summary for the turns of a workspace, oldest first
+pub fn render(task_title: &str, turns: &[TurnChanges]) -> String {
+    let mut content = format!(
+        "# Agent changes: {task_title}\n\nGenerated after every agent turn; edits to this file \
+         are overwritten.\n"
+    );
+
+    let all_files: BTreeSet<&String> = turns.iter().flat_map(|turn| &turn.files).collect();
+    let _ = write!(
+        content,
+        "\n{} turns so far, {} files touched.\n",
+        turns.len(),
+        all_files.len()
+    );
+    if !all_files.is_empty() {
+        content.push_str("\n## Files touched\n\n");
+        for file in &all_files {
+            let _ = writeln!(content, "- `{file}`");
+        }
+    }
+
+    for (index, turn) in turns.iter().enumerate() {
+        let status = match turn.succeeded {
+            Some(true) => "",
+            Some(false) => " (failed)",
+            None => " (running)",
+        };
+        let _ = write!(
+            content,
+            "\n## Turn {}{status}, {}\n\n",
+            index + 1,
+            turn.started_at.format("%Y-%m-%d %H:%M UTC")
+        );
+        if let Some(prompt) = turn.prompt.as_deref().filter(|p| !p.trim().is_empty()) {
+            let _ = writeln!(content, "Request: {}\n", first_line(prompt, PROMPT_CHARS));
+        }
+        if let Some(summary) = turn.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+            let _ = writeln!(content, "{}\n", summary.trim());
+        }
+        if turn.files.is_empty() {
+            content.push_str("No files changed.\n");
+        } else {
+            content.push_str("Files:\n");
+            for file in &turn.files {
+                let _ = writeln!(content, "- `{file}`");
+            }
+        }
+        if !turn.commands.is_empty() {
+            content.push_str("\nCommands:\n");
+            for command in turn.commands.iter().take(MAX_COMMANDS) {
+                let _ = writeln!(content, "- `{}`", first_line(command, PROMPT_CHARS));
+            }
+            if turn.commands.len() > MAX_COMMANDS {
+                let _ = writeln!(content, "- and {} more", turn.commands.len() - MAX_COMMANDS);
+            }
+        }
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{ActionType, NormalizedEntryType, ToolStatus};
+
+    use super::*;
+
+    fn tool(action_type: ActionType, status: ToolStatus) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "tool".to_string(),
+                action_type,
+                status,
+            },
+            content: String::new(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn collects_files_relative_to_the_workspace_and_commands() {
+        let entries = vec![
+            tool(
+                ActionType::FileEdit {
+                    path: "/work/ws/api/src/lib.rs".to_string(),
+                    changes: vec![FileChange::Write {
+                        content: "x".to_string(),
+                    }],
+                },
+                ToolStatus::Success,
+            ),
+            tool(
+                ActionType::FileEdit {
+                    path: "api/old.rs".to_string(),
+                    changes: vec![FileChange::Rename {
+                        new_path: "api/new.rs".to_string(),
+                    }],
+                },
+                ToolStatus::Success,
+            ),
+            tool(
+                ActionType::CommandRun {
+                    command: "cargo test".to_string(),
+                    result: None,
+                },
+                ToolStatus::Success,
+            ),
+        ];
+        let turn = TurnChanges::from_entries(
+            Utc::now(),
+            Some("Add a flag".to_string()),
+            None,
+            Some(true),
+            &entries,
+            Path::new("/work/ws"),
+        );
+        assert_eq!(
+            turn.files.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec!["api/new.rs", "api/old.rs", "api/src/lib.rs"]
+        );
+        assert_eq!(turn.commands, vec!["cargo test"]);
+    }
+
+    #[test]
+    fn renders_turns_in_order_with_their_status() {
+        let turn = |prompt: &str, file: &str, succeeded| TurnChanges {
+            started_at: Utc::now(),
+            prompt: Some(prompt.to_string()),
+            summary: Some("Done.".to_string()),
+            succeeded,
+            files: [file.to_string()].into(),
+            commands: Vec::new(),
+        };
+        let content = render(
+            "Rename the flag",
+            &[
+                turn(
+                    "Rename the flag\nin all places",
+                    "api/src/lib.rs",
+                    Some(true),
+                ),
+                turn("Fix the tests", "api/tests/flag.rs", None),
+            ],
+        );
+        assert!(content.starts_with("# Agent changes: Rename the flag\n"));
+        assert!(content.contains("2 turns so far, 2 files touched."));
+        assert!(content.contains("Request: Rename the flag\n"));
+        assert!(!content.contains("in all places"));
+        let first = content.find("## Turn 1,").unwrap();
+        let second = content.find("## Turn 2 (running),").unwrap();
+        assert!(first < second);
+    }
+}
@@ -0,0 +1,269 @@
+//! Circuit breakers for provider APIs and executor launches.
+//!
+//! Each backend, like the GitLab API of one host or one coding agent executor, has a breaker that
+//! opens after [`FAILURE_THRESHOLD`] failures in a row. While open, calls fail right away with a
+//! [`CircuitOpen`] error instead of piling up retries against a backend that is down. Once
+//! [`OPEN_DURATION`] has passed a single probe call is let through: its success closes the
+//! breaker, its failure opens it again. Breakers are shared across the process and listed in the
+//! health check.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+use ts_rs::TS;
+
+/// Failures in a row that open a breaker
+pub const FAILURE_THRESHOLD: u32 = 5;
+
+/// How long an open breaker rejects calls before letting a probe through
+pub const OPEN_DURATION: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, Serialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Calls go through
+    Closed,
+    /// Calls are rejected until the next probe
+    Open,
+    /// A probe call is deciding whether the backend recovered
+    HalfOpen,
+}
+
+/// State of a breaker, for the health check
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CircuitStatus {
+    /// Backend the breaker guards, e.g. `GitLab API at gitlab.example.com`
+    pub name: String,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    /// When the next probe is let through, while open
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+/// A call rejected by an open breaker
+#[derive(Debug, Clone, Error)]
+#[error(
+    "{name} is unavailable after {failures} failures in a row, retrying in {retry_in_secs}s: {last_error}"
+)]
+pub struct CircuitOpen {
+    pub name: String,
+    pub failures: u32,
+    pub last_error: String,
+    pub retry_in_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Phase {
+    Closed,
+    Open {
+        until: Instant,
+    },
+    /// A probe is running; another one is let through if it hasn't finished by `until`
+    HalfOpen {
+        until: Instant,
+    },
+}
+
+#[derive(Debug)]
+struct Circuit {
+    phase: Phase,
+    failures: u32,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    name: String,
+    threshold: u32,
+    open_for: Duration,
+    circuit: Arc<Mutex<Circuit>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self::with_limits(name, FAILURE_THRESHOLD, OPEN_DURATION)
+    }
+
+    pub fn with_limits(name: impl Into<String>, threshold: u32, open_for: Duration) -> Self {
+        Self {
+            name: name.into(),
+            threshold: threshold.max(1),
+            open_for,
+            circuit: Arc::new(Mutex::new(Circuit {
+                phase: Phase::Closed,
+                failures: 0,
+                last_error: None,
+            })),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Circuit> {
+        self.circuit
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Admit a call, unless the breaker is open or a probe is already running
+    pub fn check(&self) -> Result<(), CircuitOpen> {
+        let now = Instant::now();
+        let mut circuit = self.lock();
+        match circuit.phase {
+            Phase::Closed => Ok(()),
+            Phase::Open { until } | Phase::HalfOpen { until } if now < until => Err(CircuitOpen {
+                name: self.name.clone(),
+                failures: circuit.failures,
+                last_error: circuit.last_error.clone().unwrap_or_default(),
+                retry_in_secs: (until - now).as_secs().max(1),
+            }),
+            Phase::Open { .. } | Phase::HalfOpen { .. } => {
+                tracing::info!("Probing whether {} recovered", self.name);
+                circuit.phase = Phase::HalfOpen {
+                    until: now + self.open_for,
+                };
+                Ok(())
+            }
+        }
+    }
+
+    /// Record a call that reached the backend, closing the breaker
+    pub fn record_success(&self) {
+        let mut circuit = self.lock();
+        if !matches!(circuit.phase, Phase::Closed) {
+            tracing::info!("{} recovered", self.name);
+        }
+        circuit.phase = Phase::Closed;
+        circuit.failures = 0;
+        circuit.last_error = None;
+    }
+
+    /// Record a call the backend failed, opening the breaker at the threshold or when a probe
+    /// failed
+    pub fn record_failure(&self, error: impl Display) {
+        let mut circuit = self.lock();
+        circuit.failures += 1;
+        circuit.last_error = Some(error.to_string());
+        let probing = matches!(circuit.phase, Phase::HalfOpen { .. });
+        if probing || circuit.failures >= self.threshold {
+            if !matches!(circuit.phase, Phase::Open { .. }) {
+                tracing::warn!(
+                    "{} failed {} times in a row, pausing calls for {}s: {}",
+                    self.name,
+                    circuit.failures,
+                    self.open_for.as_secs(),
+                    error
+                );
+            }
+            circuit.phase = Phase::Open {
+                until: Instant::now() + self.open_for,
+            };
+        }
+    }
+
+    pub fn status(&self) -> CircuitStatus {
+        let now = Instant::now();
+        let circuit = self.lock();
+        let (state, retry_at) = match circuit.phase {
+            Phase::Closed => (CircuitState::Closed, None),
+            Phase::Open { until } => (
+                CircuitState::Open,
+                Some(Utc::now() + until.saturating_duration_since(now)),
+            ),
+            Phase::HalfOpen { .. } => (CircuitState::HalfOpen, None),
+        };
+        CircuitStatus {
+            name: self.name.clone(),
+            state,
+            consecutive_failures: circuit.failures,
+            last_error: circuit.last_error.clone(),
+            retry_at,
+        }
+    }
+}
+
+fn breakers() -> &'static Mutex<BTreeMap<String, CircuitBreaker>> {
+    static BREAKERS: OnceLock<Mutex<BTreeMap<String, CircuitBreaker>>> = OnceLock::new();
+    BREAKERS.get_or_init(Default::default)
+}
+
+/// The process-wide breaker of a backend, created on first use
+pub fn breaker(name: &str) -> CircuitBreaker {
+    let mut breakers = breakers()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    breakers
+        .entry(name.to_string())
+        .or_insert_with(|| CircuitBreaker::new(name))
+        .clone()
+}
+
+/// Status of every process-wide breaker, by name
+pub fn statuses() -> Vec<CircuitStatus> {
+    let breakers = breakers()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    breakers.values().map(CircuitBreaker::status).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_after_repeated_failures() {
+        let breaker = CircuitBreaker::with_limits("GitLab API", 3, Duration::from_secs(60));
+        breaker.record_failure("connection refused");
+        breaker.record_failure("connection refused");
+        breaker.record_success();
+        breaker.record_failure("connection refused");
+        breaker.record_failure("connection refused");
+        assert!(breaker.check().is_ok());
+
+        breaker.record_failure("502 Bad Gateway");
+        let open = breaker.check().unwrap_err();
+        assert_eq!(open.failures, 3);
+        assert_eq!(open.last_error, "502 Bad Gateway");
+        assert_eq!(breaker.status().state, CircuitState::Open);
+    }
+
+    #[test]
+    fn probes_once_before_closing() {
+        let breaker = CircuitBreaker::with_limits("claude-code executor", 1, Duration::ZERO);
+        breaker.record_failure("spawn failed");
+        assert_eq!(breaker.status().state, CircuitState::Open);
+
+        // The probe fails, so the breaker opens again
+        assert!(breaker.check().is_ok());
+        assert_eq!(breaker.status().state, CircuitState::HalfOpen);
+        breaker.record_failure("spawn failed");
+        assert_eq!(breaker.status().state, CircuitState::Open);
+
+        assert!(breaker.check().is_ok());
+        breaker.record_success();
+        let status = breaker.status();
+        assert_eq!(status.state, CircuitState::Closed);
+        assert_eq!(status.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn rejects_calls_while_a_probe_runs() {
+        let breaker = CircuitBreaker::with_limits("GitHub API", 1, Duration::from_secs(60));
+        breaker.record_failure("timeout");
+        breaker.circuit.lock().unwrap().phase = Phase::Open {
+            until: Instant::now(),
+        };
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_err());
+    }
+}
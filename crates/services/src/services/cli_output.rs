@@ -0,0 +1,83 @@
+//! Parsing of `gh` and `glab` output, which changes between CLI releases
+//!
+//! JSON output is read leniently: when a release prints a notice ahead of the JSON, like an
+//! update reminder, parsing falls back to the JSON after it. Output that still can't be parsed is
+//! reported along with the CLI version from [`parse_version`], since a new release is the usual
+//! cause.
+
+use serde_json::Value;
+
+/// Parse JSON printed by a CLI, skipping any lines printed before it
+pub fn parse_json(raw: &str) -> Result<Value, serde_json::Error> {
+    let trimmed = raw.trim();
+    serde_json::from_str(trimmed).or_else(|err| {
+        // The JSON starts on the first line opening an object or array
+        let mut offset = 0;
+        for line in trimmed.split_inclusive('\n') {
+            if offset > 0 && line.trim_start().starts_with(['{', '[']) {
+                return serde_json::from_str(&trimmed[offset..]);
+            }
+            offset += line.len();
+        }
+        Err(err)
+    })
+}
+
+/// Version number from the output of `gh --version` or `glab --version`, e.g. `2.40.1` from
+/// `gh version 2.40.1 (2023-12-13)`
+pub fn parse_version(raw: &str) -> Option<String> {
+    raw.lines()
+        .next()?
+        .split_whitespace()
+        .map(|token| token.trim_start_matches('v'))
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()) && token.contains('.'))
+        .map(str::to_string)
+}
+
+/// Outputs recorded from released CLI versions, under `tests/fixtures/cli/<cli>/<version>/`
+#[cfg(test)]
+pub mod fixtures {
+    use std::{fs, path::Path};
+
+    /// Every recorded version of one output of a CLI, as `(version, output)` pairs
+    pub fn recorded(cli: &str, file: &str) -> Vec<(String, String)> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/cli")
+            .join(cli);
+        let mut outputs: Vec<(String, String)> = fs::read_dir(&dir)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {e}", dir.display()))
+            .filter_map(|entry| {
+                let entry = entry.unwrap();
+                let output = fs::read_to_string(entry.path().join(file)).ok()?;
+                Some((entry.file_name().to_string_lossy().into_owned(), output))
+            })
+            .collect();
+        assert!(!outputs.is_empty(), "No recorded {cli} output {file}");
+        outputs.sort();
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_notices_before_json() {
+        let raw = "A new release of glab is available: 1.36.0 → 1.37.0\n\n{\"iid\": 7}\n";
+        assert_eq!(parse_json(raw).unwrap()["iid"], 7);
+        assert_eq!(parse_json("[1, 2]").unwrap(), serde_json::json!([1, 2]));
+        assert!(parse_json("Not JSON\nat all").is_err());
+    }
+
+    #[test]
+    fn reads_the_version_of_every_recorded_release() {
+        for cli in ["gh", "glab"] {
+            for (version, raw) in fixtures::recorded(cli, "version.txt") {
+                assert_eq!(parse_version(&raw), Some(version), "{cli}");
+            }
+        }
+        assert_eq!(parse_version("glab v1.22.0"), Some("1.22.0".to_string()));
+        assert_eq!(parse_version(""), None);
+    }
+}
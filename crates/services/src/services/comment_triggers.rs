@@ -0,0 +1,304 @@
+//! Follow-ups requested from pull request comments.
+//!
+//! GitHub delivers a repository's webhook events to the local webhook receiver, for example
+//! through `gh webhook forward`. A new comment on an open pull request that contains one of the
+//! configured trigger phrases, such as `@agent fix this`, becomes a follow-up in the workspace the
+//! pull request was opened from. The receiver only checks the delivery and queues it as a
+//! [`COMMENT_TRIGGER_JOB`], so GitHub gets its answer quickly and deliveries are not lost when the
+//! app restarts. The follow-up starts right away when the workspace is idle and is queued behind
+//! the running turn otherwise. Only comments by allowed authors trigger follow-ups, by default
+//! the repository's owners, organization members and collaborators, so anyone able to comment on
+//! a public repository cannot make the agent run.
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use ts_rs::TS;
+
+/// Job that acts on a webhook delivery, with `event_type` and the delivered `payload`
+pub const COMMENT_TRIGGER_JOB: &str = "webhooks.github_comment";
+
+fn default_phrases() -> Vec<String> {
+    vec!["@agent".to_string()]
+}
+
+fn default_allowed_associations() -> Vec<String> {
+    ["OWNER", "MEMBER", "COLLABORATOR"]
+        .map(str::to_string)
+        .to_vec()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct CommentTriggerConfig {
+    pub enabled: bool,
+    /// Secret configured on the GitHub webhook; deliveries without a valid signature are rejected
+    pub webhook_secret: Option<String>,
+    /// Comments containing any of these phrases are sent to the agent, ignoring case
+    #[serde(default = "default_phrases")]
+    pub phrases: Vec<String>,
+    /// Logins whose comments trigger follow-ups whatever their relation to the repository
+    pub allowed_authors: Vec<String>,
+    /// GitHub `author_association` values whose comments trigger follow-ups, such as `OWNER`,
+    /// `MEMBER`, `COLLABORATOR` or `CONTRIBUTOR`
+    #[serde(default = "default_allowed_associations")]
+    pub allowed_associations: Vec<String>,
+}
+
+impl Default for CommentTriggerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            webhook_secret: None,
+            phrases: default_phrases(),
+            allowed_authors: Vec::new(),
+            allowed_associations: default_allowed_associations(),
+        }
+    }
+}
+
+/// A new comment on a pull request
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrCommentEvent {
+    pub pr_url: String,
+    pub author: String,
+    /// How the author relates to the repository, as GitHub's `author_association`
+    pub author_association: Option<String>,
+    pub body: String,
+    pub url: String,
+    /// File and line of review comments
+    pub path: Option<String>,
+    pub line: Option<i64>,
+    pub diff_hunk: Option<String>,
+}
+
+/// Check the `X-Hub-Signature-256` header, `sha256=<hex>`, against the delivered body
+pub fn verify_signature(secret: &str, signature_header: &str, body: &[u8]) -> bool {
+    let Some(signature) = signature_header
+        .strip_prefix("sha256=")
+        .and_then(|hex_signature| hex::decode(hex_signature).ok())
+    else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    // Constant-time comparison
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// The comment created by a GitHub webhook delivery, if it is a new comment on a pull request
+/// written by a person
+pub fn parse_github_event(event_type: &str, payload: &Value) -> Option<PrCommentEvent> {
+    if payload["action"].as_str() != Some("created") {
+        return None;
+    }
+    let comment = &payload["comment"];
+    // Comments posted by bots, including our own, must not start another round
+    if comment["user"]["type"].as_str() == Some("Bot") {
+        return None;
+    }
+    let pr_url = match event_type {
+        // Issue comments also arrive for plain issues, which have no pull_request field
+        "issue_comment" => payload["issue"]["pull_request"]["html_url"].as_str()?,
+        "pull_request_review_comment" => payload["pull_request"]["html_url"].as_str()?,
+        _ => return None,
+    };
+    let text = |value: &Value| value.as_str().map(str::to_string);
+
+    Some(PrCommentEvent {
+        pr_url: pr_url.to_string(),
+        author: text(&comment["user"]["login"]).unwrap_or_else(|| "unknown".to_string()),
+        author_association: text(&comment["author_association"]),
+        body: text(&comment["body"])?,
+        url: text(&comment["html_url"]).unwrap_or_else(|| pr_url.to_string()),
+        path: text(&comment["path"]),
+        line: comment["line"]
+            .as_i64()
+            .or_else(|| comment["original_line"].as_i64()),
+        diff_hunk: text(&comment["diff_hunk"]),
+    })
+}
+
+/// The first trigger phrase the comment contains
+pub fn matched_phrase<'a>(config: &'a CommentTriggerConfig, body: &str) -> Option<&'a str> {
+    let body = body.to_lowercase();
+    config
+        .phrases
+        .iter()
+        .map(|phrase| phrase.trim())
+        .find(|phrase| !phrase.is_empty() && body.contains(&phrase.to_lowercase()))
+}
+
+/// Whether the author of a comment may trigger follow-ups, by login or by their relation to the
+/// repository, both ignoring case
+pub fn author_allowed(config: &CommentTriggerConfig, event: &PrCommentEvent) -> bool {
+    login_allowed(config, &event.author, event.author_association.as_deref())
+}
+
+/// [`author_allowed`] for an author known by login and `author_association`
+pub fn login_allowed(
+    config: &CommentTriggerConfig,
+    author: &str,
+    author_association: Option<&str>,
+) -> bool {
+    let listed = |values: &[String], value: &str| {
+        values
+            .iter()
+            .any(|allowed| allowed.trim().eq_ignore_ascii_case(value))
+    };
+    listed(&config.allowed_authors, author)
+        || author_association
+            .is_some_and(|association| listed(&config.allowed_associations, association))
+}
+
+/// Whether two pull request URLs point at the same pull request
+pub fn same_pr_url(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/')
+        .eq_ignore_ascii_case(b.trim_end_matches('/'))
+}
+
+/// Prompt asking the agent to address a comment. `diff_hunk` is the hunk of the pull request
+/// diff around a review comment when it could be fetched; the one in the event stops at the
+/// commented line.
+pub fn follow_up_prompt(event: &PrCommentEvent, diff_hunk: Option<&str>) -> String {
+    let quoted = event
+        .body
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut prompt = format!(
+        "@{} commented on the pull request {}:\n\n{}\n",
+        event.author, event.pr_url, quoted
+    );
+    if let Some(path) = &event.path {
+        match event.line {
+            Some(line) => prompt.push_str(&format!("\nThe comment is on `{path}`, line {line}.\n")),
+            None => prompt.push_str(&format!("\nThe comment is on `{path}`.\n")),
+        }
+    }
+    if let Some(hunk) = diff_hunk
+        .or(event.diff_hunk.as_deref())
+        .filter(|hunk| !hunk.is_empty())
+    {
+        prompt.push_str(&format!("\n```diff\n{hunk}\n```\n"));
+    }
+    prompt.push_str(
+        "\nAddress this comment with changes in this workspace. If it asks a question or you \
+         disagree with it, explain why instead of changing code.",
+    );
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn signatures_are_checked_against_the_body() {
+        let body = br#"{"action":"created"}"#;
+        let signature = sign("s3cret", body);
+        assert!(verify_signature("s3cret", &signature, body));
+        assert!(!verify_signature("other", &signature, body));
+        assert!(!verify_signature("s3cret", &signature, b"{}"));
+        assert!(!verify_signature("s3cret", "sha1=abc", body));
+    }
+
+    #[test]
+    fn parses_review_comments_and_skips_issues_and_bots() {
+        let review = json!({
+            "action": "created",
+            "comment": {
+                "user": { "login": "sam", "type": "User" },
+                "author_association": "MEMBER",
+                "body": "@agent fix this off-by-one",
+                "html_url": "https://github.com/acme/app/pull/7#discussion_r1",
+                "path": "src/lib.rs",
+                "line": 42,
+                "diff_hunk": "@@ -40,3 +40,3 @@"
+            },
+            "pull_request": { "html_url": "https://github.com/acme/app/pull/7" }
+        });
+        let event = parse_github_event("pull_request_review_comment", &review).unwrap();
+        assert_eq!(event.pr_url, "https://github.com/acme/app/pull/7");
+        assert_eq!(event.path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(event.line, Some(42));
+        assert_eq!(event.author_association.as_deref(), Some("MEMBER"));
+        assert!(follow_up_prompt(&event, None).contains("`src/lib.rs`, line 42"));
+        assert!(follow_up_prompt(&event, None).contains("@@ -40,3 +40,3 @@"));
+        assert!(follow_up_prompt(&event, Some("@@ -40,5 +40,5 @@")).contains("@@ -40,5 +40,5 @@"));
+
+        let on_issue = json!({
+            "action": "created",
+            "comment": { "user": { "login": "sam", "type": "User" }, "body": "@agent" },
+            "issue": { "number": 3 }
+        });
+        assert_eq!(parse_github_event("issue_comment", &on_issue), None);
+
+        let mut from_bot = review.clone();
+        from_bot["comment"]["user"]["type"] = json!("Bot");
+        assert_eq!(
+            parse_github_event("pull_request_review_comment", &from_bot),
+            None
+        );
+
+        let mut edited = review;
+        edited["action"] = json!("edited");
+        assert_eq!(
+            parse_github_event("pull_request_review_comment", &edited),
+            None
+        );
+    }
+
+    #[test]
+    fn phrases_match_ignoring_case() {
+        let config = CommentTriggerConfig {
+            enabled: true,
+            phrases: vec![" ".to_string(), "@Agent".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(matched_phrase(&config, "@agent please fix"), Some("@Agent"));
+        assert_eq!(matched_phrase(&config, "looks good to me"), None);
+        assert!(same_pr_url(
+            "https://github.com/acme/app/pull/7/",
+            "https://github.com/Acme/app/pull/7"
+        ));
+    }
+
+    #[test]
+    fn only_allowed_authors_trigger_follow_ups() {
+        let event = |author: &str, association: Option<&str>| PrCommentEvent {
+            pr_url: "https://github.com/acme/app/pull/7".to_string(),
+            author: author.to_string(),
+            author_association: association.map(str::to_string),
+            body: "@agent fix this".to_string(),
+            url: "https://github.com/acme/app/pull/7#issuecomment-1".to_string(),
+            path: None,
+            line: None,
+            diff_hunk: None,
+        };
+        let mut config = CommentTriggerConfig::default();
+        assert!(author_allowed(&config, &event("sam", Some("COLLABORATOR"))));
+        assert!(author_allowed(&config, &event("sam", Some("owner"))));
+        assert!(!author_allowed(&config, &event("drive-by", Some("NONE"))));
+        assert!(!author_allowed(
+            &config,
+            &event("drive-by", Some("CONTRIBUTOR"))
+        ));
+        assert!(!author_allowed(&config, &event("drive-by", None)));
+
+        config.allowed_authors = vec!["Drive-By".to_string()];
+        assert!(author_allowed(&config, &event("drive-by", Some("NONE"))));
+    }
+}
@@ -0,0 +1,188 @@
+//! Commit statuses for agent runs.
+//!
+//! When enabled in the config, the outcome of a workspace's latest coding agent run is published
+//! as an [`AGENT_RUN_CONTEXT`] status on the head of its branch, so branch protection can require
+//! the agent workflow to have completed before a merge. Statuses are published by a
+//! [`PUBLISH_AGENT_RUN_STATUS_JOB`] queued whenever a run finishes or the branch is pushed, and
+//! only for repositories where the pushed branch matches the local one: a status on a commit the
+//! provider doesn't have yet would be rejected. Statuses for an unreachable provider are queued
+//! by the [`OfflineService`] until it answers again.
+
+use std::sync::Arc;
+
+use db::{
+    DBService,
+    models::{
+        execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+        workspace::Workspace,
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use serde_json::{Value, json};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::services::{
+    config::Config,
+    git::GitService,
+    git_provider::{self, CommitStatusState, ProviderError},
+    jobs::JobQueue,
+    offline::{OfflineService, StatusWrite},
+};
+
+/// Context of the status published for agent runs, to name in branch protection rules
+pub const AGENT_RUN_CONTEXT: &str = "agentic-kanban/agent-run";
+
+/// Job that publishes the agent run status of a workspace's branch, with the `workspace_id`
+pub const PUBLISH_AGENT_RUN_STATUS_JOB: &str = "commit_status.agent_run";
+
+/// Status for the outcome of a coding agent run
+pub fn agent_run_state(status: &ExecutionProcessStatus) -> CommitStatusState {
+    match status {
+        ExecutionProcessStatus::Running => CommitStatusState::Pending,
+        ExecutionProcessStatus::Completed => CommitStatusState::Success,
+        ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed => {
+            CommitStatusState::Failure
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CommitStatusService {
+    db: DBService,
+    git: GitService,
+    config: Arc<RwLock<Config>>,
+    jobs: JobQueue,
+    offline: OfflineService,
+}
+
+impl CommitStatusService {
+    pub fn new(
+        db: DBService,
+        git: GitService,
+        config: Arc<RwLock<Config>>,
+        jobs: JobQueue,
+        offline: OfflineService,
+    ) -> Self {
+        Self {
+            db,
+            git,
+            config,
+            jobs,
+            offline,
+        }
+    }
+
+    /// Queue publishing the agent run status of a workspace, if statuses are enabled
+    pub async fn queue_agent_run(&self, workspace_id: Uuid) {
+        if !self.config.read().await.publish_agent_run_statuses {
+            return;
+        }
+        let payload = json!({ "workspace_id": workspace_id });
+        if let Err(e) = self
+            .jobs
+            .enqueue(PUBLISH_AGENT_RUN_STATUS_JOB, payload)
+            .await
+        {
+            tracing::warn!(
+                "Failed to queue agent run status for workspace {}: {}",
+                workspace_id,
+                e
+            );
+        }
+    }
+
+    /// Run a [`PUBLISH_AGENT_RUN_STATUS_JOB`] job. Repositories whose provider has no commit
+    /// statuses are skipped; other provider errors fail the job so it is retried.
+    pub async fn publish_job(&self, payload: Value) -> anyhow::Result<()> {
+        let workspace_id: Uuid = serde_json::from_value(payload["workspace_id"].clone())?;
+        let pool = &self.db.pool;
+        let Some(workspace) = Workspace::find_by_id(pool, workspace_id).await? else {
+            return Ok(());
+        };
+        let Some(run) = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?
+        else {
+            return Ok(());
+        };
+        let state = agent_run_state(&run.status);
+
+        for repo in WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await? {
+            // Only a head the provider already has can carry a status
+            match self
+                .git
+                .get_pushed_branch_status(&repo.path, &workspace.branch)
+            {
+                Ok(Some((0, 0))) => {}
+                Ok(_) => continue,
+                Err(e) => {
+                    tracing::debug!("Not publishing agent run status for {}: {}", repo.name, e);
+                    continue;
+                }
+            }
+            let sha = self.git.get_branch_oid(&repo.path, &workspace.branch)?;
+            // Remotes no provider recognizes have nowhere to publish to
+            let (Ok(provider), Ok((_, repo_id))) = (
+                git_provider::create_provider(&repo.path),
+                git_provider::detect_provider(&repo.path),
+            ) else {
+                continue;
+            };
+            let status = StatusWrite {
+                sha: sha.clone(),
+                state,
+                context: AGENT_RUN_CONTEXT.to_string(),
+                url: None,
+            };
+            match self
+                .offline
+                .set_commit_status(provider.as_ref(), &repo.path, &repo_id, status)
+                .await
+            {
+                Ok(None) => tracing::debug!(
+                    "Published {:?} agent run status on {} of {}",
+                    state,
+                    sha,
+                    repo_id.full_path()
+                ),
+                Ok(Some(job_id)) => tracing::debug!(
+                    "Queued {:?} agent run status on {} of {} as job {}",
+                    state,
+                    sha,
+                    repo_id.full_path(),
+                    job_id
+                ),
+                Err(ProviderError::NotSupported { feature }) => {
+                    tracing::debug!("Skipping agent run status: {} not supported", feature)
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_run_outcomes_to_states() {
+        assert_eq!(
+            agent_run_state(&ExecutionProcessStatus::Running),
+            CommitStatusState::Pending
+        );
+        assert_eq!(
+            agent_run_state(&ExecutionProcessStatus::Completed),
+            CommitStatusState::Success
+        );
+        assert_eq!(
+            agent_run_state(&ExecutionProcessStatus::Killed),
+            CommitStatusState::Failure
+        );
+    }
+}
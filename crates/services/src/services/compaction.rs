@@ -0,0 +1,346 @@
+//! Context compaction for turns that cannot resume the agent's own session.
+//!
+//! Follow-ups normally continue the executor's native session. When there is none to resume, for
+//! instance because the executor never reported a session id, the new turn would start with no
+//! memory of the work so far. It gets a context block instead, built from the session's earlier
+//! turns and the current diff: recent turns keep their request and outcome, older turns shrink to
+//! one line, and patches fill whatever room is left under the size limit.
+
+use std::fmt::Write;
+
+use db::models::coding_agent_turn::CodingAgentTurn;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::{
+    diff::{Diff, DiffChangeKind, compute_line_change_counts, create_unified_diff},
+    text::truncate_to_char_boundary,
+};
+
+/// Longest single line kept for an older turn's request or outcome
+const LINE_CHARS: usize = 160;
+
+const HEADER: &str = "## Context from earlier turns\n\nThis session continues earlier work that \
+you have no memory of. Below are the previous requests with their outcomes and the changes \
+currently in the workspace. Build on this work rather than starting over.\n";
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_max_context_chars() -> u32 {
+    24_000
+}
+
+fn default_recent_turns() -> u32 {
+    3
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct CompactionConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Upper bound on the size of the context block
+    #[serde(default = "default_max_context_chars")]
+    pub max_context_chars: u32,
+    /// Latest turns kept with their full request and outcome
+    #[serde(default = "default_recent_turns")]
+    pub recent_turns: u32,
+}
+
+impl Default for CompactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            max_context_chars: default_max_context_chars(),
+            recent_turns: default_recent_turns(),
+        }
+    }
+}
+
+/// A file changed in one of the workspace repositories
+#[derive(Debug, Clone)]
+pub struct RepoDiff {
+    pub repo: String,
+    pub diff: Diff,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Compaction {
+    pub content: String,
+    pub turns_summarized: usize,
+    pub turns_included: usize,
+    pub diff_files: usize,
+    pub source_chars: usize,
+}
+
+/// Build the context block for a session, or None when there is nothing to carry over
+pub fn compact(
+    turns: &[CodingAgentTurn],
+    changes: &[RepoDiff],
+    config: &CompactionConfig,
+) -> Option<Compaction> {
+    let turns: Vec<&CodingAgentTurn> = turns
+        .iter()
+        .filter(|turn| turn.prompt.as_deref().is_some_and(|p| !p.trim().is_empty()))
+        .collect();
+    if turns.is_empty() && changes.is_empty() {
+        return None;
+    }
+    let max_chars = config.max_context_chars as usize;
+    let recent = (config.recent_turns as usize).min(turns.len());
+    let (older, recent_turns) = turns.split_at(turns.len() - recent);
+
+    let source_chars = turns
+        .iter()
+        .map(|turn| {
+            turn.prompt.as_ref().map_or(0, String::len)
+                + turn.summary.as_ref().map_or(0, String::len)
+        })
+        .chain(changes.iter().map(|change| {
+            change.diff.old_content.as_ref().map_or(0, String::len)
+                + change.diff.new_content.as_ref().map_or(0, String::len)
+        }))
+        .sum();
+
+    let mut content = String::from(HEADER);
+    if !older.is_empty() {
+        content.push_str("\n### Earlier turns\n\n");
+        for (index, turn) in older.iter().enumerate() {
+            let _ = writeln!(
+                content,
+                "{}. {} -> {}",
+                index + 1,
+                one_line(turn.prompt.as_deref().unwrap_or_default()),
+                turn.summary
+                    .as_deref()
+                    .map_or_else(|| "no outcome recorded".to_string(), one_line)
+            );
+        }
+    }
+
+    if !recent_turns.is_empty() {
+        // Recent requests and outcomes share half of the budget; patches get what is left
+        let field_chars = max_chars / 2 / (recent_turns.len() * 2);
+        content.push_str("\n### Recent turns\n");
+        for (index, turn) in recent_turns.iter().enumerate() {
+            let _ = write!(
+                content,
+                "\n#### Turn {}\n\nRequest:\n{}\n\nOutcome:\n{}\n",
+                older.len() + index + 1,
+                clip(turn.prompt.as_deref().unwrap_or_default(), field_chars),
+                turn.summary.as_deref().map_or_else(
+                    || "No outcome recorded.".to_string(),
+                    |s| { clip(s, field_chars) }
+                )
+            );
+        }
+    }
+
+    if !changes.is_empty() {
+        content.push_str("\n### Current changes\n\n");
+        for change in changes {
+            let (additions, deletions) = line_counts(&change.diff);
+            let _ = writeln!(
+                content,
+                "- {} ({}, +{additions} -{deletions})",
+                display_path(change),
+                change_label(&change.diff.change)
+            );
+        }
+        let mut omitted = 0;
+        for change in changes {
+            let Some(patch) = patch(change) else {
+                continue;
+            };
+            if content.len() + patch.len() + 16 > max_chars {
+                omitted += 1;
+                continue;
+            }
+            let _ = write!(content, "\n```diff\n{patch}```\n");
+        }
+        if omitted > 0 {
+            let _ = writeln!(
+                content,
+                "\nLeft out {omitted} of {} patches to stay within the context limit.",
+                changes.len()
+            );
+        }
+    }
+
+    Some(Compaction {
+        content: clip(&content, max_chars),
+        turns_summarized: older.len(),
+        turns_included: recent_turns.len(),
+        diff_files: changes.len(),
+        source_chars,
+    })
+}
+
+/// Prepend a context block to the prompt of the new turn
+pub fn with_context(context: &str, prompt: &str) -> String {
+    format!("{context}\n## New request\n\n{prompt}")
+}
+
+fn one_line(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    let cut = truncate_to_char_boundary(line, LINE_CHARS);
+    if cut.len() < line.len() {
+        format!("{cut}...")
+    } else {
+        cut.to_string()
+    }
+}
+
+fn clip(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    if text.len() <= max_chars {
+        return text.to_string();
+    }
+    let marker = "\n[truncated]";
+    let cut = truncate_to_char_boundary(text, max_chars.saturating_sub(marker.len()));
+    format!("{cut}{marker}")
+}
+
+fn display_path(change: &RepoDiff) -> String {
+    let path = change
+        .diff
+        .new_path
+        .as_deref()
+        .or(change.diff.old_path.as_deref())
+        .unwrap_or_default();
+    format!("{}/{path}", change.repo)
+}
+
+fn change_label(change: &DiffChangeKind) -> &'static str {
+    match change {
+        DiffChangeKind::Added => "added",
+        DiffChangeKind::Deleted => "deleted",
+        DiffChangeKind::Modified => "modified",
+        DiffChangeKind::Renamed => "renamed",
+        DiffChangeKind::Copied => "copied",
+        DiffChangeKind::PermissionChange => "permissions changed",
+    }
+}
+
+fn line_counts(diff: &Diff) -> (usize, usize) {
+    match (diff.additions, diff.deletions) {
+        (Some(additions), Some(deletions)) => (additions, deletions),
+        _ => compute_line_change_counts(
+            diff.old_content.as_deref().unwrap_or_default(),
+            diff.new_content.as_deref().unwrap_or_default(),
+        ),
+    }
+}
+
+/// Unified diff of one change with the repository in the file name, or None when there is
+/// nothing to show
+pub(crate) fn patch(change: &RepoDiff) -> Option<String> {
+    let diff = &change.diff;
+    if diff.content_omitted || (diff.old_content.is_none() && diff.new_content.is_none()) {
+        return None;
+    }
+    if diff.old_content == diff.new_content {
+        return None;
+    }
+    Some(create_unified_diff(
+        &display_path(change),
+        diff.old_content.as_deref().unwrap_or_default(),
+        diff.new_content.as_deref().unwrap_or_default(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn turn(prompt: &str, summary: Option<&str>) -> CodingAgentTurn {
+        CodingAgentTurn {
+            id: Uuid::new_v4(),
+            execution_process_id: Uuid::new_v4(),
+            agent_session_id: None,
+            prompt: Some(prompt.to_string()),
+            summary: summary.map(str::to_string),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn modified(path: &str, old: &str, new: &str) -> RepoDiff {
+        RepoDiff {
+            repo: "app".to_string(),
+            diff: Diff {
+                change: DiffChangeKind::Modified,
+                old_path: Some(path.to_string()),
+                new_path: Some(path.to_string()),
+                old_content: Some(old.to_string()),
+                new_content: Some(new.to_string()),
+                content_omitted: false,
+                additions: None,
+                deletions: None,
+            },
+        }
+    }
+
+    #[test]
+    fn older_turns_shrink_to_one_line() {
+        let turns = vec![
+            turn("Add a login form\nwith validation", Some("Added LoginForm")),
+            turn("Style the form", None),
+            turn("Add tests", Some("Added three tests")),
+        ];
+        let config = CompactionConfig {
+            recent_turns: 1,
+            ..Default::default()
+        };
+        let compaction = compact(&turns, &[], &config).unwrap();
+        assert_eq!(compaction.turns_summarized, 2);
+        assert_eq!(compaction.turns_included, 1);
+        assert!(
+            compaction
+                .content
+                .contains("1. Add a login form -> Added LoginForm")
+        );
+        assert!(
+            compaction
+                .content
+                .contains("2. Style the form -> no outcome recorded")
+        );
+        assert!(!compaction.content.contains("with validation"));
+        assert!(compaction.content.contains("#### Turn 3"));
+    }
+
+    #[test]
+    fn patches_are_dropped_before_the_limit_is_exceeded() {
+        let changes = vec![
+            modified("src/a.rs", "fn a() {}\n", "fn a() { 1 }\n"),
+            modified("src/b.rs", "", &"let x = 1;\n".repeat(500)),
+        ];
+        let config = CompactionConfig {
+            max_context_chars: 1500,
+            ..Default::default()
+        };
+        let compaction = compact(&[turn("Do it", None)], &changes, &config).unwrap();
+        assert!(compaction.content.len() <= 1500);
+        assert!(
+            compaction
+                .content
+                .contains("- app/src/a.rs (modified, +1 -1)")
+        );
+        assert!(compaction.content.contains("--- a/app/src/a.rs"));
+        assert!(!compaction.content.contains("--- a/app/src/b.rs"));
+        assert!(compaction.content.contains("Left out 1 of 2 patches"));
+    }
+
+    #[test]
+    fn nothing_to_carry_over() {
+        assert_eq!(compact(&[], &[], &CompactionConfig::default()), None);
+    }
+}
@@ -0,0 +1,322 @@
+//! Version checks for the command-line tools the app drives.
+//!
+//! Pull requests go through `gh` and `glab`, whose output formats the providers parse, and most
+//! coding agents run through `npx`. Each tool's installed version is compared with the range it
+//! is known to work in, so that an upgrade that changes an output format shows up as a warning
+//! instead of as a parse error halfway through a task. Checking runs every tool, so results are
+//! cached for a few minutes.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::{process::Command, sync::Mutex};
+use ts_rs::TS;
+use utils::shell::resolve_executable_path;
+
+/// How long a report is served from cache
+const CACHE_TTL: Duration = Duration::from_secs(600);
+/// How long a `--version` call may take
+const VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+static CACHE: Mutex<Option<(Instant, CompatibilityReport)>> = Mutex::const_new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// The first dotted version number in a tool's `--version` output
+    pub fn find(output: &str) -> Option<Self> {
+        output
+            .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .find_map(|token| {
+                let mut parts = token.split('.').filter(|part| !part.is_empty());
+                let major = parts.next()?.parse().ok()?;
+                let minor = parts.next()?.parse().ok()?;
+                let patch = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+                Some(Self::new(major, minor, patch))
+            })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Versions of a tool known to work: from `min` up to but excluding `below`
+#[derive(Debug, Clone, Copy)]
+pub struct SupportedRange {
+    pub min: Version,
+    pub below: Option<Version>,
+}
+
+impl SupportedRange {
+    pub fn check(&self, version: Version) -> CompatibilityStatus {
+        if version < self.min {
+            CompatibilityStatus::TooOld
+        } else if self.below.is_some_and(|below| version >= below) {
+            CompatibilityStatus::TooNew
+        } else {
+            CompatibilityStatus::Compatible
+        }
+    }
+}
+
+impl fmt::Display for SupportedRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.below {
+            Some(below) => write!(f, ">= {}, < {}", self.min, below),
+            None => write!(f, ">= {}", self.min),
+        }
+    }
+}
+
+struct Tool {
+    name: &'static str,
+    used_by: &'static str,
+    /// None for tools without a tested range, whose version is only reported
+    range: Option<SupportedRange>,
+    /// What breaks outside the range
+    breaks: &'static str,
+}
+
+const TOOLS: &[Tool] = &[
+    Tool {
+        name: "gh",
+        used_by: "GitHub pull requests without an API token",
+        range: Some(SupportedRange {
+            min: Version::new(2, 40, 0),
+            below: Some(Version::new(3, 0, 0)),
+        }),
+        breaks: "pull request status and comments are read from the JSON output of `gh pr view` and `gh api`",
+    },
+    Tool {
+        name: "glab",
+        used_by: "GitLab merge requests without an API token",
+        range: Some(SupportedRange {
+            min: Version::new(1, 46, 0),
+            below: Some(Version::new(2, 0, 0)),
+        }),
+        breaks: "merge requests are looked up through the JSON output of `glab mr list`, and auto-merge needs `glab mr merge --auto-merge`",
+    },
+    Tool {
+        name: "node",
+        used_by: "Claude Code, Amp, Gemini, Codex, Opencode, Qwen Code and Copilot, run through npx",
+        range: Some(SupportedRange {
+            min: Version::new(20, 0, 0),
+            below: None,
+        }),
+        breaks: "the pinned agent packages need Node.js 20 or later",
+    },
+    Tool {
+        name: "cursor-agent",
+        used_by: "Cursor Agent",
+        range: None,
+        breaks: "",
+    },
+    Tool {
+        name: "droid",
+        used_by: "Droid",
+        range: None,
+        breaks: "",
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatibilityStatus {
+    Compatible,
+    TooOld,
+    TooNew,
+    /// Installed, but no version could be read from its output
+    Unrecognized,
+    /// Installed, without a known-good range to compare against
+    Unchecked,
+    NotInstalled,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ToolCompatibility {
+    pub name: String,
+    /// What the app uses the tool for
+    pub used_by: String,
+    pub version: Option<String>,
+    /// Known-good versions, like `>= 2.40.0, < 3.0.0`
+    pub supported: Option<String>,
+    pub status: CompatibilityStatus,
+    /// Set when the installed version is outside the supported range
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CompatibilityReport {
+    pub tools: Vec<ToolCompatibility>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl CompatibilityReport {
+    pub fn warnings(&self) -> impl Iterator<Item = &str> {
+        self.tools.iter().filter_map(|tool| tool.warning.as_deref())
+    }
+}
+
+/// The cached report, checking again once it is older than a few minutes
+pub async fn report() -> CompatibilityReport {
+    let mut cache = CACHE.lock().await;
+    if let Some((at, report)) = cache.as_ref()
+        && at.elapsed() < CACHE_TTL
+    {
+        return report.clone();
+    }
+    let report = check().await;
+    *cache = Some((Instant::now(), report.clone()));
+    report
+}
+
+/// Check every tool now
+pub async fn check() -> CompatibilityReport {
+    let tools = futures::future::join_all(TOOLS.iter().map(check_tool)).await;
+    CompatibilityReport {
+        tools,
+        checked_at: Utc::now(),
+    }
+}
+
+/// Check the tools and log a warning for each one outside its supported range
+pub async fn log_warnings() {
+    let report = report().await;
+    for warning in report.warnings() {
+        tracing::warn!("{warning}");
+    }
+}
+
+async fn check_tool(tool: &Tool) -> ToolCompatibility {
+    let output = installed_version_output(tool.name).await;
+    let version = output.as_deref().and_then(Version::find);
+    let status = match (&output, version, tool.range) {
+        (None, _, _) => CompatibilityStatus::NotInstalled,
+        (Some(_), None, _) => CompatibilityStatus::Unrecognized,
+        (Some(_), Some(_), None) => CompatibilityStatus::Unchecked,
+        (Some(_), Some(version), Some(range)) => range.check(version),
+    };
+    compatibility(tool, version, status)
+}
+
+fn compatibility(
+    tool: &Tool,
+    version: Option<Version>,
+    status: CompatibilityStatus,
+) -> ToolCompatibility {
+    let warning = match (status, version, tool.range) {
+        (CompatibilityStatus::TooOld, Some(version), Some(range)) => Some(format!(
+            "{} {version} is older than the supported versions ({range}); {}",
+            tool.name, tool.breaks
+        )),
+        (CompatibilityStatus::TooNew, Some(version), Some(range)) => Some(format!(
+            "{} {version} is newer than the tested versions ({range}) and may have changed its output; {}",
+            tool.name, tool.breaks
+        )),
+        _ => None,
+    };
+    ToolCompatibility {
+        name: tool.name.to_string(),
+        used_by: tool.used_by.to_string(),
+        version: version.map(|v| v.to_string()),
+        supported: tool.range.map(|range| range.to_string()),
+        status,
+        warning,
+    }
+}
+
+/// Output of `<tool> --version`; None when the tool is missing or the call fails
+async fn installed_version_output(name: &str) -> Option<String> {
+    let path = resolve_executable_path(name).await?;
+    let mut command = Command::new(path);
+    command.arg("--version").kill_on_drop(true);
+    let output = tokio::time::timeout(VERSION_TIMEOUT, command.output())
+        .await
+        .ok()?
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if stdout.is_empty() {
+        Some(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    } else {
+        Some(stdout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_versions_in_tool_output() {
+        assert_eq!(
+            Version::find("gh version 2.45.0 (2024-03-04)\nhttps://github.com/cli/cli"),
+            Some(Version::new(2, 45, 0))
+        );
+        assert_eq!(
+            Version::find("glab 1.46.1 (abc123)"),
+            Some(Version::new(1, 46, 1))
+        );
+        assert_eq!(Version::find("v20.11"), Some(Version::new(20, 11, 0)));
+        assert_eq!(Version::find("no version here"), None);
+    }
+
+    #[test]
+    fn ranges_exclude_their_upper_bound() {
+        let range = SupportedRange {
+            min: Version::new(1, 46, 0),
+            below: Some(Version::new(2, 0, 0)),
+        };
+        assert_eq!(
+            range.check(Version::new(1, 45, 9)),
+            CompatibilityStatus::TooOld
+        );
+        assert_eq!(
+            range.check(Version::new(1, 46, 0)),
+            CompatibilityStatus::Compatible
+        );
+        assert_eq!(
+            range.check(Version::new(2, 0, 0)),
+            CompatibilityStatus::TooNew
+        );
+    }
+
+    #[test]
+    fn only_out_of_range_versions_warn() {
+        let glab = TOOLS.iter().find(|tool| tool.name == "glab").unwrap();
+        let newer = compatibility(
+            glab,
+            Some(Version::new(2, 1, 0)),
+            CompatibilityStatus::TooNew,
+        );
+        assert!(newer.warning.unwrap().contains("glab mr list"));
+        let current = compatibility(
+            glab,
+            Some(Version::new(1, 50, 0)),
+            CompatibilityStatus::Compatible,
+        );
+        assert!(current.warning.is_none());
+    }
+}
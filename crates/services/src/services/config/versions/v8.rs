@@ -1,13 +1,31 @@
 use anyhow::Error;
+use db::DatabaseTuning;
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
+use utils::{log_shipping::LogShippingConfig, redact::RedactionOptions};
 pub use v7::{
     EditorConfig, EditorType, GitHubConfig, NotificationConfig, ShowcaseState, SoundFile,
     ThemeMode, UiLanguage,
 };
 
-use crate::services::config::versions::v7;
+use crate::services::{
+    comment_triggers::CommentTriggerConfig,
+    compaction::CompactionConfig,
+    config::versions::v7,
+    coverage::CoverageConfig,
+    dependency_policy::DependencyPolicyConfig,
+    flaky::FlakyConfig,
+    git_provider::{self, ProviderHostMapping, ProviderRetryPolicy},
+    lint::LintConfig,
+    object_store::StorageConfig,
+    policy::ExecutorPolicy,
+    repo_fetch::FetchConfig,
+    routing::RoutingConfig,
+    schedule::ScheduleConfig,
+    self_update::UpdateConfig,
+    throttle::ExecutorLimit,
+};
 
 fn default_git_branch_prefix() -> String {
     "vk".to_string()
@@ -17,6 +35,90 @@ fn default_pr_auto_description_enabled() -> bool {
     true
 }
 
+fn default_secret_scan_enabled() -> bool {
+    true
+}
+
+fn default_git_provider_max_pages() -> u32 {
+    git_provider::DEFAULT_MAX_PAGES as u32
+}
+
+/// Secret scrubbing applied to prompts sent to coding agents and to stored execution logs
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    /// Also redact long random-looking tokens that match no known format
+    pub entropy_detection: bool,
+    /// Redact values of environment variables whose names look like secrets
+    pub redact_env_values: bool,
+    /// Extra regular expressions; a `secret` named group limits what gets replaced
+    pub extra_patterns: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            entropy_detection: true,
+            redact_env_values: true,
+            extra_patterns: Vec::new(),
+        }
+    }
+}
+
+/// Branding the auto-description prompt asks for in pull request titles and descriptions.
+/// Prompts place it with the `{title_branding}` and `{footer_branding}` placeholders.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct PrBrandingConfig {
+    /// Appended to pull request titles; no suffix when unset
+    pub title_suffix: Option<String>,
+    /// Note at the end of pull request descriptions; no footer when unset
+    pub footer: Option<String>,
+}
+
+impl Default for PrBrandingConfig {
+    fn default() -> Self {
+        Self {
+            title_suffix: Some("(Vibe Kanban)".to_string()),
+            footer: Some(
+                "This PR was written using [Vibe Kanban](https://vibekanban.com)".to_string(),
+            ),
+        }
+    }
+}
+
+impl PrBrandingConfig {
+    /// Fill the branding placeholders of a prompt, dropping the instructions for unset branding
+    pub fn apply(&self, prompt: &str) -> String {
+        let title = match self.title_suffix.as_deref().map(str::trim) {
+            Some(suffix) if !suffix.is_empty() => format!(", postfixed with \"{suffix}\""),
+            _ => String::new(),
+        };
+        let footer = match self.footer.as_deref().map(str::trim) {
+            Some(footer) if !footer.is_empty() => {
+                format!("\n   - At the end, include a note: \"{footer}\"")
+            }
+            _ => String::new(),
+        };
+        prompt
+            .replace("{title_branding}", &title)
+            .replace("{footer_branding}", &footer)
+    }
+}
+
+impl From<&RedactionConfig> for RedactionOptions {
+    fn from(config: &RedactionConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            entropy_detection: config.entropy_detection,
+            redact_env_values: config.redact_env_values,
+            extra_patterns: config.extra_patterns.clone(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -41,6 +143,79 @@ pub struct Config {
     pub pr_auto_description_enabled: bool,
     #[serde(default)]
     pub pr_auto_description_prompt: Option<String>,
+    #[serde(default)]
+    pub pr_branding: PrBrandingConfig,
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    /// Repository and path restrictions enforced on coding agent changes
+    #[serde(default)]
+    pub executor_policies: Vec<ExecutorPolicy>,
+    /// Concurrency and start-rate limits per executor profile
+    #[serde(default)]
+    pub executor_limits: Vec<ExecutorLimit>,
+    /// Scan branches for secrets before pushing or opening a pull request
+    #[serde(default = "default_secret_scan_enabled")]
+    pub secret_scan_enabled: bool,
+    /// License, banned package and pinning rules for dependencies added by coding agents
+    #[serde(default)]
+    pub dependency_policy: DependencyPolicyConfig,
+    /// Lint commands run after each coding agent turn
+    #[serde(default)]
+    pub lint: LintConfig,
+    /// Coverage reports read after each coding agent turn
+    #[serde(default)]
+    pub coverage: CoverageConfig,
+    /// Detection and quarantine of setup, cleanup and lint commands that fail intermittently
+    #[serde(default)]
+    pub flaky: FlakyConfig,
+    /// Rules that pick the executor profile per coding agent turn
+    #[serde(default)]
+    pub routing: RoutingConfig,
+    /// Context carried into turns that cannot resume the agent's session
+    #[serde(default)]
+    pub compaction: CompactionConfig,
+    /// Background fetching of registered repositories
+    #[serde(default)]
+    pub fetch: FetchConfig,
+    /// Follow-ups started by trigger phrases in pull request comments
+    #[serde(default)]
+    pub comment_triggers: CommentTriggerConfig,
+    /// Language for pull request descriptions, commit messages and agent summaries, as a locale
+    /// tag like `ja` or `de`; English when unset
+    #[serde(default)]
+    pub content_locale: Option<String>,
+    /// Hosts whose remotes belong to a git provider, for self-hosted instances under custom
+    /// domains
+    #[serde(default)]
+    pub git_provider_hosts: Vec<ProviderHostMapping>,
+    /// Pages read from a provider API when listing comments, merge requests and files; longer
+    /// listings are cut off with a warning in the log
+    #[serde(default = "default_git_provider_max_pages")]
+    pub git_provider_max_pages: u32,
+    /// How failed provider calls are retried, per provider; providers without a policy here
+    /// use the default
+    #[serde(default)]
+    pub git_provider_retry: Vec<ProviderRetryPolicy>,
+    /// Publish the outcome of each coding agent run as an `agentic-kanban/agent-run` commit
+    /// status on the pushed branch, for branch protection to require
+    #[serde(default)]
+    pub publish_agent_run_statuses: bool,
+    /// SQLite connection settings, applied on the next start
+    #[serde(default)]
+    pub database: DatabaseTuning,
+    /// Where attachments, artifacts and transcripts are stored, applied on the next start
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// Export of server and execution logs to an external aggregator
+    #[serde(default)]
+    pub log_shipping: LogShippingConfig,
+    /// Release channel and checks for updating a standalone binary from within the app
+    #[serde(default)]
+    pub update: UpdateConfig,
+    /// Timezone, week start and sprint length applied to due dates in digests, calendar feeds
+    /// and overdue counts
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
 }
 
 impl Config {
@@ -66,6 +241,29 @@ impl Config {
             showcases: old_config.showcases,
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            pr_branding: PrBrandingConfig::default(),
+            redaction: RedactionConfig::default(),
+            executor_policies: Vec::new(),
+            executor_limits: Vec::new(),
+            secret_scan_enabled: true,
+            dependency_policy: DependencyPolicyConfig::default(),
+            lint: LintConfig::default(),
+            coverage: CoverageConfig::default(),
+            flaky: FlakyConfig::default(),
+            routing: RoutingConfig::default(),
+            compaction: CompactionConfig::default(),
+            fetch: FetchConfig::default(),
+            comment_triggers: CommentTriggerConfig::default(),
+            content_locale: None,
+            git_provider_hosts: Vec::new(),
+            git_provider_max_pages: default_git_provider_max_pages(),
+            git_provider_retry: Vec::new(),
+            publish_agent_run_statuses: false,
+            database: DatabaseTuning::default(),
+            storage: StorageConfig::default(),
+            log_shipping: LogShippingConfig::default(),
+            update: UpdateConfig::default(),
+            schedule: ScheduleConfig::default(),
         }
     }
 
@@ -73,6 +271,15 @@ impl Config {
         let old_config = v7::Config::from(raw_config.to_string());
         Ok(Self::from_v7_config(old_config))
     }
+
+    /// A copy without tokens and secrets, to start another tenant's settings from
+    pub fn without_secrets(&self) -> Self {
+        let mut config = self.clone();
+        config.github = GitHubConfig::default();
+        config.comment_triggers.webhook_secret = None;
+        config.log_shipping.authorization = None;
+        config
+    }
 }
 
 impl From<String> for Config {
@@ -116,6 +323,29 @@ impl Default for Config {
             showcases: ShowcaseState::default(),
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            pr_branding: PrBrandingConfig::default(),
+            redaction: RedactionConfig::default(),
+            executor_policies: Vec::new(),
+            executor_limits: Vec::new(),
+            secret_scan_enabled: true,
+            dependency_policy: DependencyPolicyConfig::default(),
+            lint: LintConfig::default(),
+            coverage: CoverageConfig::default(),
+            flaky: FlakyConfig::default(),
+            routing: RoutingConfig::default(),
+            compaction: CompactionConfig::default(),
+            fetch: FetchConfig::default(),
+            comment_triggers: CommentTriggerConfig::default(),
+            content_locale: None,
+            git_provider_hosts: Vec::new(),
+            git_provider_max_pages: default_git_provider_max_pages(),
+            git_provider_retry: Vec::new(),
+            publish_agent_run_statuses: false,
+            database: DatabaseTuning::default(),
+            storage: StorageConfig::default(),
+            log_shipping: LogShippingConfig::default(),
+            update: UpdateConfig::default(),
+            schedule: ScheduleConfig::default(),
         }
     }
 }
@@ -10,6 +10,7 @@ use db::{
     DBService,
     models::{
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+        context_compaction::{ContextCompaction, CreateContextCompaction},
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
             ExecutionProcessStatus,
@@ -21,8 +22,10 @@ use db::{
         project::{Project, UpdateProject},
         project_repo::{ProjectRepo, ProjectRepoWithName},
         repo::Repo,
+        routing_decision::{CreateRoutingDecision, RoutingDecision, TurnPurpose},
         session::{CreateSession, Session, SessionError},
-        task::{Task, TaskStatus},
+        task::TaskStatus,
+        tenant::Tenant,
         workspace::{Workspace, WorkspaceError},
         workspace_repo::WorkspaceRepo,
     },
@@ -43,15 +46,24 @@ use thiserror::Error;
 use tokio::{sync::RwLock, task::JoinHandle};
 use utils::{
     log_msg::LogMsg,
+    log_shipping::{self, ExecutionForwarder, ExecutionLogContext},
     msg_store::MsgStore,
     text::{git_branch_id, short_uuid},
 };
 use uuid::Uuid;
 
 use crate::services::{
-    git::{GitService, GitServiceError},
+    commit_status::CommitStatusService,
+    compaction::{self, CompactionConfig, RepoDiff},
+    content_locale,
+    git::{DiffTarget, GitService, GitServiceError},
     notification::NotificationService,
+    quota::{self, QuotaError, QuotaExceeded},
+    routing::{self, RoutingConfig, RoutingInput},
+    self_update,
     share::SharePublisher,
+    task_events::{TaskActor, TaskEventLog},
+    throttle::ExecutorThrottle,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
@@ -78,6 +90,10 @@ pub enum ContainerError {
     #[error("Failed to kill process: {0}")]
     KillFailed(std::io::Error),
     #[error(transparent)]
+    Quota(#[from] QuotaError),
+    #[error("An update is being installed; new executions start after the restart")]
+    UpdatePending,
+    #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
 
@@ -93,6 +109,13 @@ pub trait ContainerService {
 
     fn notification_service(&self) -> &NotificationService;
 
+    fn task_events(&self) -> &TaskEventLog;
+
+    fn commit_statuses(&self) -> &CommitStatusService;
+
+    /// Executor slots of coding agent runs, and the runs waiting for one
+    fn executor_throttle(&self) -> &ExecutorThrottle;
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
 
     async fn create(&self, workspace: &Workspace) -> Result<ContainerRef, ContainerError>;
@@ -162,26 +185,26 @@ pub trait ContainerService {
     }
 
     /// Finalize task execution by updating status to InReview and sending notifications
-    async fn finalize_task(
-        &self,
-        share_publisher: Option<&SharePublisher>,
-        ctx: &ExecutionContext,
-    ) {
-        match Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await {
-            Ok(_) => {
-                if let Some(publisher) = share_publisher
-                    && let Err(err) = publisher.update_shared_task_by_id(ctx.task.id).await
-                {
-                    tracing::warn!(
-                        ?err,
-                        "Failed to propagate shared task update for {}",
-                        ctx.task.id
-                    );
-                }
-            }
-            Err(e) => {
-                tracing::error!("Failed to update task status to InReview: {e}");
-            }
+    async fn finalize_task(&self, ctx: &ExecutionContext) {
+        // Questions answered with a read-only policy don't move the task or notify
+        if ctx
+            .execution_process
+            .executor_action()
+            .is_ok_and(|action| action.is_read_only())
+        {
+            return;
+        }
+
+        if let Err(e) = self
+            .task_events()
+            .set_status(
+                ctx.task.id,
+                TaskStatus::InReview,
+                &TaskActor::system("execution"),
+            )
+            .await
+        {
+            tracing::error!("Failed to update task status to InReview: {e}");
         }
 
         // Skip notification if process was intentionally killed by user
@@ -274,24 +297,15 @@ pub trait ContainerService {
                     Workspace::find_by_id(&self.db().pool, session.workspace_id).await
                 && let Ok(Some(task)) = workspace.parent_task(&self.db().pool).await
             {
-                match Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await {
-                    Ok(_) => {
-                        if let Some(publisher) = self.share_publisher()
-                            && let Err(err) = publisher.update_shared_task_by_id(task.id).await
-                        {
-                            tracing::warn!(
-                                ?err,
-                                "Failed to propagate shared task update for {}",
-                                task.id
-                            );
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to update task status to InReview for orphaned session: {}",
-                            e
-                        );
-                    }
+                if let Err(e) = self
+                    .task_events()
+                    .set_status(task.id, TaskStatus::InReview, &TaskActor::system("cleanup"))
+                    .await
+                {
+                    tracing::error!(
+                        "Failed to update task status to InReview for orphaned session: {}",
+                        e
+                    );
                 }
             }
         }
@@ -609,6 +623,13 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    async fn routing_config(&self) -> RoutingConfig;
+
+    async fn compaction_config(&self) -> CompactionConfig;
+
+    /// Locale tag for text the agent writes for the repository, if one is configured
+    async fn content_locale(&self) -> Option<String>;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         let prefix = self.git_branch_prefix().await;
@@ -817,11 +838,33 @@ pub trait ContainerService {
             };
 
             if let Some(store) = store {
+                let mut forwarder = if log_shipping::ships_execution_logs() {
+                    match ExecutionProcess::load_context(&db.pool, execution_id).await {
+                        Ok(ctx) => Some(ExecutionForwarder::new(ExecutionLogContext {
+                            workspace_id: ctx.workspace.id,
+                            task_id: ctx.task.id,
+                            execution_process_id: execution_id,
+                        })),
+                        Err(e) => {
+                            tracing::warn!(
+                                "Not shipping logs of execution {}, context lookup failed: {}",
+                                execution_id,
+                                e
+                            );
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
                 let mut stream = store.history_plus_stream();
 
                 while let Some(Ok(msg)) = stream.next().await {
                     match &msg {
                         LogMsg::Stdout(_) | LogMsg::Stderr(_) => {
+                            if let Some(forwarder) = &mut forwarder {
+                                forwarder.push_msg(&msg);
+                            }
                             // Serialize this individual message as a JSONL line
                             match serde_json::to_string(&msg) {
                                 Ok(jsonl_line) => {
@@ -874,6 +917,9 @@ pub trait ContainerService {
                         LogMsg::JsonPatch(_) => continue,
                     }
                 }
+                if let Some(forwarder) = &mut forwarder {
+                    forwarder.finish();
+                }
             }
         })
     }
@@ -987,26 +1033,101 @@ pub trait ContainerService {
         executor_action: &ExecutorAction,
         run_reason: &ExecutionProcessRunReason,
     ) -> Result<ExecutionProcess, ContainerError> {
+        self.start_execution_for_purpose(workspace, session, executor_action, run_reason, None)
+            .await
+    }
+
+    /// Start an execution, telling model routing what a coding agent turn is for. Without a
+    /// purpose it is inferred from the action.
+    async fn start_execution_for_purpose(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        executor_action: &ExecutorAction,
+        run_reason: &ExecutionProcessRunReason,
+        purpose: Option<TurnPurpose>,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        if self_update::is_draining() {
+            return Err(ContainerError::UpdatePending);
+        }
         // Update task status to InProgress when starting an execution
         let task = workspace
             .parent_task(&self.db().pool)
             .await?
             .ok_or(SqlxError::RowNotFound)?;
+        // Tenant quotas only cover coding agent runs, scripts and dev servers always start
+        let concurrency_limit = if run_reason == &ExecutionProcessRunReason::CodingAgent {
+            let tenant_id = Tenant::id_for_project(&self.db().pool, task.project_id)
+                .await?
+                .ok_or(SqlxError::RowNotFound)?;
+            quota::check_execution(&self.db().pool, tenant_id).await?
+        } else {
+            None
+        };
         if task.status != TaskStatus::InProgress
             && run_reason != &ExecutionProcessRunReason::DevServer
+            && !executor_action.is_read_only()
         {
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
-
-            if let Some(publisher) = self.share_publisher()
-                && let Err(err) = publisher.update_shared_task_by_id(task.id).await
-            {
-                tracing::warn!(
-                    ?err,
-                    "Failed to propagate shared task update for {}",
-                    task.id
-                );
-            }
+            self.task_events()
+                .set_status(
+                    task.id,
+                    TaskStatus::InProgress,
+                    &TaskActor::system("execution"),
+                )
+                .await?;
         }
+        // Scrub secrets before the prompt is stored or handed to the agent
+        let redactor = utils::redact::global();
+        let executor_action = &executor_action
+            .clone()
+            .map_prompts(&|prompt| redactor.redact(&prompt).into_owned());
+
+        let execution_process_id = Uuid::new_v4();
+        let routing_decision = self
+            .route_turn(
+                workspace,
+                session,
+                executor_action,
+                purpose,
+                execution_process_id,
+            )
+            .await;
+        let executor_action = &match &routing_decision {
+            Some(decision) => executor_action
+                .clone()
+                .with_executor_profile_id(decision.selected_profile.clone()),
+            None => executor_action.clone(),
+        };
+
+        // Prompts that place the language themselves get it filled in; the rest are told which
+        // language to write in after the turn prompt is recorded
+        let content_locale = self.content_locale().await;
+        let places_language = executor_action
+            .prompt()
+            .is_some_and(content_locale::has_placeholder);
+        let executor_action = &executor_action.clone().map_prompts(&|prompt| {
+            content_locale::fill_placeholder(&prompt, content_locale.as_deref())
+        });
+
+        // Turns record the request itself, not the context carried over from earlier turns
+        let turn_prompt = executor_action.prompt().map(str::to_string);
+        let compaction = self
+            .compact_context(workspace, session, executor_action, execution_process_id)
+            .await;
+        let executor_action = &match &compaction {
+            Some(compaction) => executor_action.clone().map_prompts(&|prompt| {
+                compaction::with_context(&redactor.redact(&compaction.content), &prompt)
+            }),
+            None => executor_action.clone(),
+        };
+        let executor_action = &if places_language {
+            executor_action.clone()
+        } else {
+            executor_action.clone().map_prompts(&|prompt| {
+                content_locale::with_instruction(&prompt, content_locale.as_deref())
+            })
+        };
+
         // Create new execution process record
         // Capture current HEAD per repository as the "before" commit for this execution
         let repositories =
@@ -1040,23 +1161,39 @@ pub trait ContainerService {
             run_reason: run_reason.clone(),
         };
 
-        let execution_process = ExecutionProcess::create(
+        let Some(execution_process) = ExecutionProcess::create(
             &self.db().pool,
             &create_execution_process,
-            Uuid::new_v4(),
+            execution_process_id,
             &repo_states,
+            concurrency_limit,
         )
-        .await?;
+        .await?
+        else {
+            let limit = concurrency_limit.map_or(0, |limit| limit.max_running);
+            return Err(QuotaError::from(QuotaExceeded::ConcurrentExecutions { limit }).into());
+        };
 
-        if let Some(prompt) = match executor_action.typ() {
-            ExecutorActionType::CodingAgentInitialRequest(coding_agent_request) => {
-                Some(coding_agent_request.prompt.clone())
-            }
-            ExecutorActionType::CodingAgentFollowUpRequest(follow_up_request) => {
-                Some(follow_up_request.prompt.clone())
-            }
-            _ => None,
-        } {
+        if let Some(decision) = &routing_decision
+            && let Err(e) = RoutingDecision::create(&self.db().pool, decision).await
+        {
+            tracing::warn!(
+                "Failed to record routing decision for execution {}: {}",
+                execution_process_id,
+                e
+            );
+        }
+        if let Some(compaction) = &compaction
+            && let Err(e) = ContextCompaction::create(&self.db().pool, compaction).await
+        {
+            tracing::warn!(
+                "Failed to record context compaction for execution {}: {}",
+                execution_process_id,
+                e
+            );
+        }
+
+        if let Some(prompt) = turn_prompt {
             let create_coding_agent_turn = CreateCodingAgentTurn {
                 execution_process_id: execution_process.id,
                 prompt: Some(prompt),
@@ -1091,7 +1228,13 @@ pub trait ContainerService {
                     update_error
                 );
             }
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await?;
+            self.task_events()
+                .set_status(
+                    task.id,
+                    TaskStatus::InReview,
+                    &TaskActor::system("execution"),
+                )
+                .await?;
 
             // Emit stderr error message
             let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
@@ -1158,6 +1301,190 @@ pub trait ContainerService {
         Ok(execution_process)
     }
 
+    /// Build a context block from the session's earlier turns and the current diff when a coding
+    /// agent turn starts without an agent session to resume
+    async fn compact_context(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        executor_action: &ExecutorAction,
+        execution_process_id: Uuid,
+    ) -> Option<CreateContextCompaction> {
+        if !matches!(
+            executor_action.typ(),
+            ExecutorActionType::CodingAgentInitialRequest(_)
+        ) {
+            return None;
+        }
+        let config = self.compaction_config().await;
+        if !config.enabled {
+            return None;
+        }
+        let pool = &self.db().pool;
+        let turns = match CodingAgentTurn::find_by_session_id(pool, session.id).await {
+            Ok(turns) if !turns.is_empty() => turns,
+            Ok(_) => return None,
+            Err(e) => {
+                tracing::warn!("Failed to load turns of session {}: {}", session.id, e);
+                return None;
+            }
+        };
+
+        let mut changes = Vec::new();
+        if let Some(workspace_root) = workspace.container_ref.as_ref().map(PathBuf::from) {
+            let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id)
+                .await
+                .unwrap_or_default();
+            let target_branches = WorkspaceRepo::find_by_workspace_id(pool, workspace.id)
+                .await
+                .unwrap_or_default();
+            for repo in &repos {
+                let Some(target_branch) = target_branches
+                    .iter()
+                    .find(|wr| wr.repo_id == repo.id)
+                    .map(|wr| wr.target_branch.as_str())
+                else {
+                    continue;
+                };
+                let worktree_path = workspace_root.join(&repo.name);
+                let diffs = self
+                    .git()
+                    .get_base_commit(&repo.path, &workspace.branch, target_branch)
+                    .and_then(|base| {
+                        self.git().get_diffs(
+                            DiffTarget::Worktree {
+                                worktree_path: &worktree_path,
+                                base_commit: &base,
+                            },
+                            None,
+                        )
+                    });
+                match diffs {
+                    Ok(diffs) => changes.extend(diffs.into_iter().map(|diff| RepoDiff {
+                        repo: repo.name.clone(),
+                        diff,
+                    })),
+                    Err(e) => {
+                        tracing::warn!("Failed to diff {} for context compaction: {}", repo.name, e)
+                    }
+                }
+            }
+        }
+
+        let compaction = compaction::compact(&turns, &changes, &config)?;
+        tracing::info!(
+            "Compacted {} turns and {} changed files into {} of {} characters for session {}",
+            compaction.turns_summarized + compaction.turns_included,
+            compaction.diff_files,
+            compaction.content.len(),
+            compaction.source_chars,
+            session.id
+        );
+        Some(CreateContextCompaction {
+            session_id: session.id,
+            execution_process_id,
+            turns_summarized: compaction.turns_summarized as i64,
+            turns_included: compaction.turns_included as i64,
+            diff_files: compaction.diff_files as i64,
+            source_chars: compaction.source_chars as i64,
+            content: compaction.content,
+        })
+    }
+
+    /// Pick the executor profile for a coding agent turn when model routing is enabled. Returns
+    /// None for script actions or when routing is off.
+    async fn route_turn(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        executor_action: &ExecutorAction,
+        purpose: Option<TurnPurpose>,
+        execution_process_id: Uuid,
+    ) -> Option<CreateRoutingDecision> {
+        let config = self.routing_config().await;
+        if !config.enabled {
+            return None;
+        }
+        let requested = executor_action.executor_profile_id()?;
+        let prompt = executor_action.prompt()?;
+        let read_only = executor_action.is_read_only();
+        let resumes_session = matches!(
+            executor_action.typ(),
+            ExecutorActionType::CodingAgentFollowUpRequest(_)
+        );
+        let purpose = purpose.unwrap_or(if read_only {
+            TurnPurpose::Question
+        } else if resumes_session {
+            TurnPurpose::FollowUp
+        } else {
+            TurnPurpose::Initial
+        });
+
+        let pool = &self.db().pool;
+        let previous_failures =
+            match ExecutionProcess::find_by_session_id(pool, session.id, false).await {
+                Ok(processes) => routing::consecutive_failures(&processes),
+                Err(e) => {
+                    tracing::warn!("Failed to load session {} for routing: {}", session.id, e);
+                    0
+                }
+            };
+        let budget_remaining = match config.attempt_budget {
+            Some(budget) => {
+                match RoutingDecision::total_cost_for_workspace(pool, workspace.id).await {
+                    Ok(spent) => Some(budget - spent),
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to load routing spend for workspace {}: {}",
+                            workspace.id,
+                            e
+                        );
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let input = RoutingInput {
+            purpose,
+            task_size: routing::estimate_task_size(prompt),
+            previous_failures,
+            budget_remaining,
+            resumes_session,
+        };
+        let executor_configs = ExecutorConfigs::get_cached();
+        let route = config.route(&requested, &input, |profile| {
+            executor_configs
+                .get_coding_agent(profile)
+                .is_some_and(|agent| !read_only || agent.into_read_only().is_some())
+        });
+        if route.profile != requested {
+            tracing::info!(
+                "Routing {:?} turn in workspace {} from {} to {}: {}",
+                purpose,
+                workspace.id,
+                requested,
+                route.profile,
+                route.reason
+            );
+        }
+
+        Some(CreateRoutingDecision {
+            execution_process_id,
+            workspace_id: workspace.id,
+            purpose,
+            task_size: input.task_size,
+            estimated_cost: config.cost_of(&route.profile),
+            requested_profile: requested,
+            selected_profile: route.profile,
+            rule_name: route.rule_name,
+            reason: route.reason,
+            previous_failures: previous_failures.into(),
+            budget_remaining,
+        })
+    }
+
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
         let action = ctx.execution_process.executor_action()?;
         let next_action = if let Some(next_action) = action.next_action() {
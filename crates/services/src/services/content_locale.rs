@@ -0,0 +1,121 @@
+//! Language of the text coding agents write for the repository.
+//!
+//! Pull request titles and descriptions, commit messages (taken from the agent's summary of its
+//! turn) and the summaries themselves are written by the agent, so the configured locale reaches
+//! them through the prompt. Prompt templates can place the language themselves with the
+//! `{language}` placeholder; other prompts get a short instruction appended when a locale is set.
+
+/// Placeholder prompt templates use for the language name
+pub const LANGUAGE_PLACEHOLDER: &str = "{language}";
+
+/// Language used when no locale is configured
+const DEFAULT_LANGUAGE: &str = "English";
+
+/// English name of a locale tag like `ja`, `de-AT` or `zh-Hans`, or the setting as written when
+/// it is not a known tag, so names like "Brazilian Portuguese" work as well
+pub fn language_name(locale: &str) -> String {
+    let locale = locale.trim();
+    let lower = locale.to_lowercase().replace('_', "-");
+    let name = match lower.as_str() {
+        "zh-hans" | "zh-cn" | "zh-sg" => Some("Simplified Chinese"),
+        "zh-hant" | "zh-tw" | "zh-hk" => Some("Traditional Chinese"),
+        "pt-br" => Some("Brazilian Portuguese"),
+        _ => match lower.split('-').next().unwrap_or_default() {
+            "en" => Some("English"),
+            "ja" => Some("Japanese"),
+            "de" => Some("German"),
+            "fr" => Some("French"),
+            "es" => Some("Spanish"),
+            "it" => Some("Italian"),
+            "pt" => Some("Portuguese"),
+            "nl" => Some("Dutch"),
+            "pl" => Some("Polish"),
+            "ru" => Some("Russian"),
+            "uk" => Some("Ukrainian"),
+            "tr" => Some("Turkish"),
+            "ko" => Some("Korean"),
+            "zh" => Some("Chinese"),
+            "sv" => Some("Swedish"),
+            "da" => Some("Danish"),
+            "nb" | "no" => Some("Norwegian"),
+            "fi" => Some("Finnish"),
+            "cs" => Some("Czech"),
+            "hu" => Some("Hungarian"),
+            "he" => Some("Hebrew"),
+            "ar" => Some("Arabic"),
+            "hi" => Some("Hindi"),
+            "id" => Some("Indonesian"),
+            "vi" => Some("Vietnamese"),
+            "th" => Some("Thai"),
+            _ => None,
+        },
+    };
+    name.map(str::to_string)
+        .unwrap_or_else(|| locale.to_string())
+}
+
+fn configured(locale: Option<&str>) -> Option<&str> {
+    locale.map(str::trim).filter(|locale| !locale.is_empty())
+}
+
+/// Whether a prompt places the language itself
+pub fn has_placeholder(prompt: &str) -> bool {
+    prompt.contains(LANGUAGE_PLACEHOLDER)
+}
+
+/// Replace the `{language}` placeholder, with English when no locale is set
+pub fn fill_placeholder(prompt: &str, locale: Option<&str>) -> String {
+    let language = configured(locale)
+        .map(language_name)
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+    prompt.replace(LANGUAGE_PLACEHOLDER, &language)
+}
+
+/// Append the language instruction to a prompt that does not place the language itself
+pub fn with_instruction(prompt: &str, locale: Option<&str>) -> String {
+    let Some(locale) = configured(locale) else {
+        return prompt.to_string();
+    };
+    format!(
+        "{prompt}\n\nWrite pull request titles and descriptions, commit messages and your final \
+         summary in {}. Keep code, identifiers and quoted output unchanged.",
+        language_name(locale)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_locale_tags() {
+        assert_eq!(language_name("ja"), "Japanese");
+        assert_eq!(language_name("de_AT"), "German");
+        assert_eq!(language_name("zh-Hans"), "Simplified Chinese");
+        assert_eq!(
+            language_name(" Brazilian Portuguese "),
+            "Brazilian Portuguese"
+        );
+    }
+
+    #[test]
+    fn placeholder_defaults_to_english() {
+        let prompt = "Write the description in {language}.";
+        assert_eq!(
+            fill_placeholder(prompt, Some("de")),
+            "Write the description in German."
+        );
+        assert_eq!(
+            fill_placeholder(prompt, Some(" ")),
+            "Write the description in English."
+        );
+    }
+
+    #[test]
+    fn instruction_only_when_locale_is_set() {
+        assert_eq!(with_instruction("Fix the bug", None), "Fix the bug");
+        let localized = with_instruction("Fix the bug", Some("ja"));
+        assert!(localized.starts_with("Fix the bug\n\n"));
+        assert!(localized.contains("in Japanese"));
+    }
+}
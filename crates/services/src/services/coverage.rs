@@ -0,0 +1,292 @@
+//! Coverage reports and the coverage delta of a workspace branch.
+//!
+//! Reports written by the project's own test commands (lcov or Cobertura) are read from the
+//! workspace worktree after a coding agent turn. The main checkout's report, when it is on the
+//! target branch, serves as the baseline; patch coverage counts only the instrumented lines the
+//! branch added or changed.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use db::models::coverage_report::CoverageReport;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::diff::{Diff, added_line_numbers};
+
+fn default_report_paths() -> Vec<String> {
+    [
+        "coverage/lcov.info",
+        "lcov.info",
+        "coverage/cobertura-coverage.xml",
+        "coverage.xml",
+        "cobertura.xml",
+    ]
+    .into_iter()
+    .map(str::to_string)
+    .collect()
+}
+
+fn default_include_in_pr_body() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct CoverageConfig {
+    pub enabled: bool,
+    /// Report locations relative to each repository root, tried in order. Files ending in `.xml`
+    /// are read as Cobertura, anything else as lcov.
+    #[serde(default = "default_report_paths")]
+    pub report_paths: Vec<String>,
+    /// Add a coverage summary to pull request descriptions
+    #[serde(default = "default_include_in_pr_body")]
+    pub include_in_pr_body: bool,
+}
+
+impl Default for CoverageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            report_paths: default_report_paths(),
+            include_in_pr_body: default_include_in_pr_body(),
+        }
+    }
+}
+
+/// Hit counts per instrumented line, keyed by path relative to the repository root
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Coverage {
+    files: BTreeMap<String, BTreeMap<u32, u64>>,
+}
+
+impl Coverage {
+    /// `(total, covered)` instrumented lines
+    pub fn totals(&self) -> (i64, i64) {
+        self.files
+            .values()
+            .flat_map(|lines| lines.values())
+            .fold((0, 0), |(total, covered), hits| {
+                (total + 1, covered + i64::from(*hits > 0))
+            })
+    }
+
+    /// `(total, covered)` instrumented lines among those the diffs add or change
+    pub fn patch_totals(&self, diffs: &[Diff]) -> (i64, i64) {
+        let mut totals = (0, 0);
+        for diff in diffs {
+            let (Some(path), Some(new_content)) = (&diff.new_path, &diff.new_content) else {
+                continue;
+            };
+            let Some(lines) = self.files.get(path) else {
+                continue;
+            };
+            let old_content = diff.old_content.as_deref().unwrap_or_default();
+            for line in added_line_numbers(old_content, new_content) {
+                if let Some(hits) = u32::try_from(line).ok().and_then(|l| lines.get(&l)) {
+                    totals.0 += 1;
+                    totals.1 += i64::from(*hits > 0);
+                }
+            }
+        }
+        totals
+    }
+
+    fn record(&mut self, path: String, line: u32, hits: u64) {
+        *self.files.entry(path).or_default().entry(line).or_default() += hits;
+    }
+}
+
+/// Read the first report found under `repo_root`
+pub fn load(repo_root: &Path, report_paths: &[String]) -> Option<Coverage> {
+    report_paths.iter().find_map(|report_path| {
+        let content = std::fs::read_to_string(repo_root.join(report_path)).ok()?;
+        let coverage = if report_path.ends_with(".xml") {
+            parse_cobertura(&content, repo_root)
+        } else {
+            parse_lcov(&content, repo_root)
+        };
+        (!coverage.files.is_empty()).then_some(coverage)
+    })
+}
+
+fn relative_path(path: &Path, repo_root: &Path) -> String {
+    path.strip_prefix(repo_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .trim_start_matches("./")
+        .replace('\\', "/")
+}
+
+pub fn parse_lcov(content: &str, repo_root: &Path) -> Coverage {
+    let mut coverage = Coverage::default();
+    let mut current: Option<String> = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(path) = line.strip_prefix("SF:") {
+            current = Some(relative_path(Path::new(path), repo_root));
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            let Some(path) = &current else {
+                continue;
+            };
+            let mut parts = data.split(',');
+            if let (Some(Ok(line)), Some(Ok(hits))) = (
+                parts.next().map(str::parse::<u32>),
+                parts.next().map(str::parse::<u64>),
+            ) {
+                coverage.record(path.clone(), line, hits);
+            }
+        } else if line == "end_of_record" {
+            current = None;
+        }
+    }
+    coverage
+}
+
+static COBERTURA_SOURCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<source>\s*([^<]*?)\s*</source>").expect("valid regex"));
+static COBERTURA_ELEMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<(class|line)\s([^>]*)>"#).expect("valid regex"));
+static XML_ATTRIBUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([\w-]+)="([^"]*)""#).expect("valid regex"));
+
+/// Cobertura XML, read with a small tag scanner rather than a full XML parser; only `<source>`,
+/// `<class filename>` and `<line number hits>` are needed
+pub fn parse_cobertura(content: &str, repo_root: &Path) -> Coverage {
+    let sources: Vec<PathBuf> = COBERTURA_SOURCE
+        .captures_iter(content)
+        .map(|caps| PathBuf::from(&caps[1]))
+        .collect();
+    let resolve = |filename: &str| {
+        let path = sources
+            .iter()
+            .map(|source| source.join(filename))
+            .find(|path| path.starts_with(repo_root) || path.exists())
+            .unwrap_or_else(|| PathBuf::from(filename));
+        relative_path(&path, repo_root)
+    };
+
+    let mut coverage = Coverage::default();
+    let mut current: Option<String> = None;
+    for element in COBERTURA_ELEMENT.captures_iter(content) {
+        let attributes: BTreeMap<&str, &str> = XML_ATTRIBUTE
+            .captures_iter(element.get(2).map_or("", |m| m.as_str()))
+            .filter_map(|caps| Some((caps.get(1)?.as_str(), caps.get(2)?.as_str())))
+            .collect();
+        match &element[1] {
+            "class" => current = attributes.get("filename").map(|f| resolve(f)),
+            _ => {
+                let (Some(path), Some(Ok(line)), Some(Ok(hits))) = (
+                    &current,
+                    attributes.get("number").map(|n| n.parse::<u32>()),
+                    attributes.get("hits").map(|h| h.parse::<u64>()),
+                ) else {
+                    continue;
+                };
+                coverage.record(path.clone(), line, hits);
+            }
+        }
+    }
+    coverage
+}
+
+/// Coverage section appended to pull request descriptions
+pub fn pr_body_section(report: &CoverageReport) -> Option<String> {
+    let line_rate = report.line_rate()?;
+    let mut section = format!(
+        "### Coverage\n\n| | |\n|---|---|\n| Line coverage | {line_rate:.1}% ({}/{} lines) |\n",
+        report.lines_covered, report.lines_total
+    );
+    if let (Some(base), Some(delta)) = (report.base_line_rate(), report.delta()) {
+        section.push_str(&format!(
+            "| Target branch | {base:.1}% ({delta:+.1} pts) |\n"
+        ));
+    }
+    if let Some(patch_rate) = report.patch_line_rate() {
+        section.push_str(&format!(
+            "| Changed lines | {patch_rate:.1}% ({}/{} lines) |\n",
+            report.patch_lines_covered, report.patch_lines_total
+        ));
+    }
+    Some(section)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use utils::diff::DiffChangeKind;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn parses_lcov() {
+        let lcov = "TN:\nSF:/repo/src/lib.rs\nDA:1,3\nDA:2,0\nDA:3,1\nend_of_record\nSF:src/main.rs\nDA:1,0\nend_of_record\n";
+        let coverage = parse_lcov(lcov, Path::new("/repo"));
+        assert_eq!(coverage.totals(), (4, 2));
+        assert!(coverage.files.contains_key("src/lib.rs"));
+        assert!(coverage.files.contains_key("src/main.rs"));
+    }
+
+    #[test]
+    fn parses_cobertura() {
+        let xml = r#"<?xml version="1.0" ?>
+<coverage line-rate="0.5">
+  <sources><source>/repo</source></sources>
+  <packages><package name="app"><classes>
+    <class name="util" filename="app/util.py" line-rate="0.5">
+      <lines>
+        <line number="1" hits="1"/>
+        <line number="2" hits="0"/>
+      </lines>
+    </class>
+  </classes></package></packages>
+</coverage>"#;
+        let coverage = parse_cobertura(xml, Path::new("/repo"));
+        assert_eq!(coverage.totals(), (2, 1));
+        assert!(coverage.files.contains_key("app/util.py"));
+    }
+
+    #[test]
+    fn patch_coverage_counts_added_instrumented_lines() {
+        let coverage = parse_lcov(
+            "SF:src/lib.rs\nDA:1,1\nDA:2,0\nDA:3,1\nend_of_record\n",
+            Path::new("/repo"),
+        );
+        let diff = Diff {
+            change: DiffChangeKind::Modified,
+            old_path: Some("src/lib.rs".to_string()),
+            new_path: Some("src/lib.rs".to_string()),
+            old_content: Some("fn a() {}\n".to_string()),
+            new_content: Some("fn a() {}\nfn b() {}\nfn c() {}\n// end\n".to_string()),
+            content_omitted: false,
+            additions: None,
+            deletions: None,
+        };
+        // Lines 2 and 3 are instrumented, line 4 is not
+        assert_eq!(coverage.patch_totals(&[diff]), (2, 1));
+    }
+
+    #[test]
+    fn pr_section_includes_delta() {
+        let report = CoverageReport {
+            id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            execution_process_id: None,
+            repo_id: Uuid::new_v4(),
+            lines_total: 200,
+            lines_covered: 160,
+            base_lines_total: Some(180),
+            base_lines_covered: Some(135),
+            patch_lines_total: 20,
+            patch_lines_covered: 15,
+            created_at: Utc::now(),
+        };
+        let section = pr_body_section(&report).unwrap();
+        assert!(section.contains("| Line coverage | 80.0% (160/200 lines) |"));
+        assert!(section.contains("| Target branch | 75.0% (+5.0 pts) |"));
+        assert!(section.contains("| Changed lines | 75.0% (15/20 lines) |"));
+    }
+}
@@ -0,0 +1,454 @@
+//! License and dependency checks for manifest changes made by agents.
+//!
+//! Dependencies added or re-versioned in `Cargo.toml`, `package.json` or `go.mod` are checked
+//! against banned packages, version pinning and the licence allowlist. Licences are looked up
+//! on crates.io and the npm registry; Go modules have no licence metadata to query and are
+//! reported as unknown when an allowlist is configured.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+use db::models::dependency_report::{
+    DependencyEcosystem, DependencyFinding, DependencyFindingKind, FindingSeverity,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+use utils::diff::Diff;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct DependencyPolicyConfig {
+    pub enabled: bool,
+    /// SPDX identifiers, e.g. `MIT`; empty allows any licence
+    pub allowed_licenses: Vec<String>,
+    /// Package names that may not be added, e.g. `left-pad`
+    pub banned_packages: Vec<String>,
+    /// Require exact versions (`=1.2.3` in Cargo, `1.2.3` in npm)
+    pub require_pinned_versions: bool,
+    /// Refuse to open a pull request while the branch has error findings
+    pub block_pull_requests: bool,
+}
+
+/// A dependency that is new or has a different version than before
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedDependency {
+    pub manifest: String,
+    pub ecosystem: DependencyEcosystem,
+    pub name: String,
+    pub version: String,
+}
+
+/// Dependencies added or changed by a set of file diffs
+pub fn changed_dependencies(diffs: &[Diff]) -> Vec<ChangedDependency> {
+    let mut changed = Vec::new();
+    for diff in diffs {
+        let Some(path) = diff.new_path.as_deref() else {
+            continue;
+        };
+        let Some(ecosystem) = manifest_ecosystem(path) else {
+            continue;
+        };
+        let Some(new_content) = diff.new_content.as_deref() else {
+            continue;
+        };
+        let before = diff
+            .old_content
+            .as_deref()
+            .map(|content| parse_manifest(ecosystem, content))
+            .unwrap_or_default();
+        for (name, version) in parse_manifest(ecosystem, new_content) {
+            if before.get(&name) != Some(&version) {
+                changed.push(ChangedDependency {
+                    manifest: path.to_string(),
+                    ecosystem,
+                    name,
+                    version,
+                });
+            }
+        }
+    }
+    changed
+}
+
+fn manifest_ecosystem(path: &str) -> Option<DependencyEcosystem> {
+    match path.rsplit('/').next()? {
+        "Cargo.toml" => Some(DependencyEcosystem::Cargo),
+        "package.json" => Some(DependencyEcosystem::Npm),
+        "go.mod" => Some(DependencyEcosystem::Go),
+        _ => None,
+    }
+}
+
+/// Package name to version requirement. Local, workspace-inherited and VCS dependencies are
+/// skipped since there is nothing to look up or pin.
+fn parse_manifest(ecosystem: DependencyEcosystem, content: &str) -> BTreeMap<String, String> {
+    match ecosystem {
+        DependencyEcosystem::Cargo => parse_cargo_toml(content),
+        DependencyEcosystem::Npm => parse_package_json(content),
+        DependencyEcosystem::Go => parse_go_mod(content),
+    }
+}
+
+fn parse_cargo_toml(content: &str) -> BTreeMap<String, String> {
+    let mut deps = BTreeMap::new();
+    let Ok(manifest) = toml::from_str::<toml::Table>(content) else {
+        return deps;
+    };
+
+    let mut tables: Vec<&toml::Table> = dependency_tables(&manifest).collect();
+    if let Some(workspace) = manifest.get("workspace").and_then(|v| v.as_table()) {
+        tables.extend(dependency_tables(workspace));
+    }
+    if let Some(targets) = manifest.get("target").and_then(|v| v.as_table()) {
+        for target in targets.values().filter_map(|v| v.as_table()) {
+            tables.extend(dependency_tables(target));
+        }
+    }
+
+    for table in tables {
+        for (key, spec) in table {
+            let (name, version) = match spec {
+                toml::Value::String(version) => (key.clone(), version.clone()),
+                toml::Value::Table(spec) => {
+                    if spec.contains_key("path") || spec.contains_key("git") {
+                        continue;
+                    }
+                    let Some(version) = spec.get("version").and_then(|v| v.as_str()) else {
+                        continue;
+                    };
+                    let name = spec
+                        .get("package")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(key)
+                        .to_string();
+                    (name, version.to_string())
+                }
+                _ => continue,
+            };
+            deps.insert(name, version);
+        }
+    }
+    deps
+}
+
+fn dependency_tables(table: &toml::Table) -> impl Iterator<Item = &toml::Table> {
+    ["dependencies", "dev-dependencies", "build-dependencies"]
+        .into_iter()
+        .filter_map(|key| table.get(key).and_then(|v| v.as_table()))
+}
+
+fn parse_package_json(content: &str) -> BTreeMap<String, String> {
+    let mut deps = BTreeMap::new();
+    let Ok(manifest) = serde_json::from_str::<Value>(content) else {
+        return deps;
+    };
+    for key in [
+        "dependencies",
+        "devDependencies",
+        "optionalDependencies",
+        "peerDependencies",
+    ] {
+        let Some(section) = manifest.get(key).and_then(Value::as_object) else {
+            continue;
+        };
+        for (name, version) in section {
+            let Some(version) = version.as_str() else {
+                continue;
+            };
+            let local = ["workspace:", "file:", "link:", "git", "http", "github:"]
+                .iter()
+                .any(|prefix| version.starts_with(prefix));
+            if !local {
+                deps.insert(name.clone(), version.to_string());
+            }
+        }
+    }
+    deps
+}
+
+fn parse_go_mod(content: &str) -> BTreeMap<String, String> {
+    let mut deps = BTreeMap::new();
+    let mut in_require_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let spec = if in_require_block {
+            if line == ")" {
+                in_require_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_require_block = true;
+            continue;
+        } else if let Some(spec) = line.strip_prefix("require ") {
+            spec
+        } else {
+            continue;
+        };
+        let mut parts = spec.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            deps.insert(module.to_string(), version.to_string());
+        }
+    }
+    deps
+}
+
+fn is_pinned(ecosystem: DependencyEcosystem, version: &str) -> bool {
+    let exact = |v: &str| {
+        let core = v.split(['-', '+']).next().unwrap_or(v);
+        let parts: Vec<&str> = core.split('.').collect();
+        parts.len() == 3 && parts.iter().all(|p| p.parse::<u64>().is_ok())
+    };
+    match ecosystem {
+        DependencyEcosystem::Cargo => version.strip_prefix('=').is_some_and(|v| exact(v.trim())),
+        DependencyEcosystem::Npm => exact(version),
+        // go.mod always records exact module versions
+        DependencyEcosystem::Go => true,
+    }
+}
+
+/// Whether an SPDX expression is satisfied by the allowlist: any `OR` alternative whose
+/// `AND` terms are all allowed
+pub fn license_allowed(expression: &str, allowed: &[String]) -> bool {
+    let is_allowed = |id: &str| allowed.iter().any(|a| a.eq_ignore_ascii_case(id));
+    expression
+        .replace(['(', ')'], " ")
+        .split(" OR ")
+        .flat_map(|alt| alt.split('/'))
+        .any(|alternative| {
+            alternative
+                .split(" AND ")
+                .map(str::trim)
+                .filter(|term| !term.is_empty())
+                .all(is_allowed)
+        })
+}
+
+/// Findings for one dependency; `license` is `None` when it could not be determined
+pub fn evaluate(
+    policy: &DependencyPolicyConfig,
+    repo: &str,
+    dep: &ChangedDependency,
+    license: Option<&str>,
+) -> Vec<DependencyFinding> {
+    let finding = |kind, severity| DependencyFinding {
+        repo: repo.to_string(),
+        manifest: dep.manifest.clone(),
+        ecosystem: dep.ecosystem,
+        package: dep.name.clone(),
+        version: dep.version.clone(),
+        kind,
+        severity,
+    };
+
+    let mut findings = Vec::new();
+    if policy
+        .banned_packages
+        .iter()
+        .any(|banned| *banned == dep.name)
+    {
+        findings.push(finding(
+            DependencyFindingKind::BannedPackage,
+            FindingSeverity::Error,
+        ));
+    }
+    if policy.require_pinned_versions && !is_pinned(dep.ecosystem, &dep.version) {
+        findings.push(finding(
+            DependencyFindingKind::UnpinnedVersion,
+            FindingSeverity::Error,
+        ));
+    }
+    if !policy.allowed_licenses.is_empty() {
+        match license {
+            Some(license) if !license_allowed(license, &policy.allowed_licenses) => {
+                findings.push(finding(
+                    DependencyFindingKind::LicenseNotAllowed {
+                        license: license.to_string(),
+                    },
+                    FindingSeverity::Error,
+                ));
+            }
+            Some(_) => {}
+            None => findings.push(finding(
+                DependencyFindingKind::LicenseUnknown,
+                FindingSeverity::Warning,
+            )),
+        }
+    }
+    findings
+}
+
+pub fn has_blocking_findings(findings: &[DependencyFinding]) -> bool {
+    findings
+        .iter()
+        .any(|f| f.severity == FindingSeverity::Error)
+}
+
+pub struct DependencyChecker {
+    policy: DependencyPolicyConfig,
+    http_client: reqwest::Client,
+    licenses: HashMap<(DependencyEcosystem, String), Option<String>>,
+}
+
+impl DependencyChecker {
+    pub fn new(policy: DependencyPolicyConfig) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .user_agent(concat!("vibe-kanban/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self {
+            policy,
+            http_client,
+            licenses: HashMap::new(),
+        }
+    }
+
+    /// Check every dependency the diffs add or change in one repository
+    pub async fn check(&mut self, repo: &str, diffs: &[Diff]) -> Vec<DependencyFinding> {
+        let mut findings = Vec::new();
+        for dep in changed_dependencies(diffs) {
+            let license = if self.policy.allowed_licenses.is_empty() {
+                None
+            } else {
+                self.license(dep.ecosystem, &dep.name).await
+            };
+            findings.extend(evaluate(&self.policy, repo, &dep, license.as_deref()));
+        }
+        findings
+    }
+
+    async fn license(&mut self, ecosystem: DependencyEcosystem, name: &str) -> Option<String> {
+        let key = (ecosystem, name.to_string());
+        if let Some(cached) = self.licenses.get(&key) {
+            return cached.clone();
+        }
+        let license = match self.fetch_license(ecosystem, name).await {
+            Ok(license) => license,
+            Err(e) => {
+                tracing::warn!("Failed to look up licence for {}: {}", name, e);
+                None
+            }
+        };
+        self.licenses.insert(key, license.clone());
+        license
+    }
+
+    async fn fetch_license(
+        &self,
+        ecosystem: DependencyEcosystem,
+        name: &str,
+    ) -> Result<Option<String>, reqwest::Error> {
+        let url = match ecosystem {
+            DependencyEcosystem::Cargo => format!("https://crates.io/api/v1/crates/{name}"),
+            DependencyEcosystem::Npm => format!("https://registry.npmjs.org/{name}/latest"),
+            DependencyEcosystem::Go => return Ok(None),
+        };
+        let body: Value = self
+            .http_client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let license = match ecosystem {
+            DependencyEcosystem::Cargo => body.pointer("/versions/0/license"),
+            // Older packages use `{ "type": "MIT" }`
+            _ => body.get("license").map(|l| l.get("type").unwrap_or(l)),
+        };
+        Ok(license.and_then(Value::as_str).map(str::to_string))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::diff::DiffChangeKind;
+
+    use super::*;
+
+    fn diff(path: &str, old: Option<&str>, new: &str) -> Diff {
+        Diff {
+            change: DiffChangeKind::Modified,
+            old_path: Some(path.to_string()),
+            new_path: Some(path.to_string()),
+            old_content: old.map(str::to_string),
+            new_content: Some(new.to_string()),
+            content_omitted: false,
+            additions: None,
+            deletions: None,
+        }
+    }
+
+    #[test]
+    fn detects_added_and_bumped_cargo_dependencies() {
+        let old = "[dependencies]\nserde = \"1\"\nlocal = { path = \"../local\" }\n";
+        let new = "[dependencies]\nserde = \"1\"\nlocal = { path = \"../local\" }\nregex = \"=1.11.1\"\n\n[dev-dependencies]\ntempfile = { version = \"3\" }\n";
+        let changed = changed_dependencies(&[diff("crates/x/Cargo.toml", Some(old), new)]);
+        let names: Vec<&str> = changed.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["regex", "tempfile"]);
+    }
+
+    #[test]
+    fn parses_package_json_and_go_mod() {
+        let pkg = r#"{"dependencies":{"react":"^18.2.0","shared":"workspace:*"},"devDependencies":{"vite":"5.0.0"}}"#;
+        let changed = changed_dependencies(&[diff("frontend/package.json", None, pkg)]);
+        assert_eq!(changed.len(), 2);
+
+        let go = "module x\n\nrequire github.com/a/b v1.0.0\nrequire (\n\tgithub.com/c/d v0.2.1 // indirect\n)\n";
+        let changed = changed_dependencies(&[diff("go.mod", None, go)]);
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed[1].name, "github.com/c/d");
+        assert_eq!(changed[1].version, "v0.2.1");
+    }
+
+    #[test]
+    fn evaluates_policy_rules() {
+        let policy = DependencyPolicyConfig {
+            enabled: true,
+            allowed_licenses: vec!["MIT".to_string(), "Apache-2.0".to_string()],
+            banned_packages: vec!["left-pad".to_string()],
+            require_pinned_versions: true,
+            block_pull_requests: true,
+        };
+        let dep = |name: &str, version: &str| ChangedDependency {
+            manifest: "package.json".to_string(),
+            ecosystem: DependencyEcosystem::Npm,
+            name: name.to_string(),
+            version: version.to_string(),
+        };
+
+        assert!(evaluate(&policy, "web", &dep("react", "18.2.0"), Some("MIT")).is_empty());
+
+        let findings = evaluate(&policy, "web", &dep("left-pad", "^1.3.0"), Some("WTFPL"));
+        let kinds: Vec<_> = findings.iter().map(|f| f.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DependencyFindingKind::BannedPackage,
+                DependencyFindingKind::UnpinnedVersion,
+                DependencyFindingKind::LicenseNotAllowed {
+                    license: "WTFPL".to_string()
+                },
+            ]
+        );
+        assert!(has_blocking_findings(&findings));
+
+        let unknown = evaluate(&policy, "web", &dep("mystery", "1.0.0"), None);
+        assert_eq!(unknown[0].severity, FindingSeverity::Warning);
+        assert!(!has_blocking_findings(&unknown));
+    }
+
+    #[test]
+    fn spdx_expressions() {
+        let allowed = vec!["MIT".to_string(), "Apache-2.0".to_string()];
+        assert!(license_allowed("MIT OR Apache-2.0", &allowed));
+        assert!(license_allowed("(MIT OR GPL-3.0)", &allowed));
+        assert!(license_allowed("MIT/Apache-2.0", &allowed));
+        assert!(!license_allowed("MIT AND GPL-3.0", &allowed));
+        assert!(!license_allowed("GPL-3.0-only", &allowed));
+    }
+}
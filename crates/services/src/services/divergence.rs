@@ -0,0 +1,92 @@
+//! Ahead/behind indicators for workspace branches.
+//!
+//! Counts are taken from local refs only, so they are as fresh as the last fetch of the
+//! repository. The fetch scheduler recomputes them for every workspace of a repository it has just
+//! fetched, which lets the UI suggest a rebase while the target branch is moving, before the pull
+//! request runs into conflicts.
+
+use chrono::Utc;
+use db::models::{
+    branch_divergence::BranchDivergence,
+    repo::Repo,
+    workspace::Workspace,
+    workspace_repo::{WorkspaceBranch, WorkspaceRepo},
+};
+use sqlx::SqlitePool;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::services::git::{GitService, GitServiceError};
+
+fn compute(
+    git: &GitService,
+    repo: &Repo,
+    branch: &WorkspaceBranch,
+) -> Result<BranchDivergence, GitServiceError> {
+    let (ahead, behind) =
+        git.get_branch_status(&repo.path, &branch.branch, &branch.target_branch)?;
+    let remote = git.get_pushed_branch_status(&repo.path, &branch.branch)?;
+    Ok(BranchDivergence {
+        workspace_id: branch.workspace_id,
+        repo_id: repo.id,
+        target_branch: branch.target_branch.clone(),
+        commits_ahead: ahead as i64,
+        commits_behind: behind as i64,
+        remote_commits_ahead: remote.map(|(ahead, _)| ahead as i64),
+        remote_commits_behind: remote.map(|(_, behind)| behind as i64),
+        computed_at: Utc::now(),
+    })
+}
+
+async fn refresh(
+    pool: &SqlitePool,
+    git: &GitService,
+    repo: &Repo,
+    branches: &[WorkspaceBranch],
+) -> Result<(), sqlx::Error> {
+    for branch in branches {
+        match compute(git, repo, branch) {
+            Ok(divergence) => BranchDivergence::upsert(pool, &divergence).await?,
+            // The branch or its target may be gone; the workspace shows no indicator then
+            Err(e) => debug!(
+                "Skipping divergence of {} in {}: {}",
+                branch.branch, repo.name, e
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Recompute the divergence of every workspace branch of `repo` that has a worktree
+pub async fn refresh_repo(
+    pool: &SqlitePool,
+    git: &GitService,
+    repo: &Repo,
+) -> Result<(), sqlx::Error> {
+    let branches = WorkspaceRepo::find_branches_with_worktrees(pool, repo.id).await?;
+    refresh(pool, git, repo, &branches).await
+}
+
+/// Recompute the divergence of `workspace` in each of its repositories, or only in `repo_id`
+pub async fn refresh_workspace(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+    repo_id: Option<Uuid>,
+) -> Result<Vec<BranchDivergence>, sqlx::Error> {
+    for workspace_repo in WorkspaceRepo::find_by_workspace_id(pool, workspace.id).await? {
+        if repo_id.is_some_and(|id| id != workspace_repo.repo_id) {
+            continue;
+        }
+        let Some(repo) = Repo::find_by_id(pool, workspace_repo.repo_id).await? else {
+            continue;
+        };
+        let branch = WorkspaceBranch {
+            workspace_id: workspace.id,
+            branch: workspace.branch.clone(),
+            target_branch: workspace_repo.target_branch,
+        };
+        refresh(pool, git, &repo, &[branch]).await?;
+    }
+    BranchDivergence::find_by_workspace_id(pool, workspace.id).await
+}
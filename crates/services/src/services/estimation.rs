@@ -0,0 +1,188 @@
+//! Effort and cost estimates for new tasks, from how similar finished tasks went.
+//!
+//! The finished tasks of the project most similar to the new one are looked up in the similarity
+//! index, and their turns, agent time and estimated cost are combined into a median weighted by
+//! how close each one is. Costs come from the routing cost estimates, so they are only as good as
+//! the configured per-turn costs.
+
+use db::models::task_metrics::TaskMetrics;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::similarity::SimilarityIndex;
+
+/// Most similar tasks an estimate is based on
+const MAX_SIMILAR_TASKS: usize = 5;
+/// Tasks less similar than this are not comparable enough to learn from
+const MIN_SIMILARITY: f64 = 0.15;
+
+/// At most this many turns and minutes of agent time is a small task
+const SMALL_TURNS: f64 = 2.0;
+const SMALL_MINUTES: f64 = 15.0;
+/// At least this many turns or minutes of agent time is a large task
+const LARGE_TURNS: f64 = 6.0;
+const LARGE_MINUTES: f64 = 60.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Effort {
+    Small,
+    Medium,
+    Large,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SimilarTask {
+    pub task_id: Uuid,
+    pub title: String,
+    /// Cosine similarity between 0 and 1
+    pub similarity: f64,
+    pub turns: u32,
+    pub duration_seconds: f64,
+    pub estimated_cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskEstimate {
+    pub effort: Effort,
+    pub turns: f64,
+    /// Agent time, not including review or waiting
+    pub duration_seconds: f64,
+    pub estimated_cost: f64,
+    /// The finished tasks the estimate is based on, most similar first
+    pub similar_tasks: Vec<SimilarTask>,
+}
+
+fn document(title: &str, description: Option<&str>) -> String {
+    format!("{title}\n{}", description.unwrap_or_default())
+}
+
+/// Estimate a new task from the project's finished tasks, or None when none are similar enough
+pub fn estimate(
+    title: &str,
+    description: Option<&str>,
+    history: &[TaskMetrics],
+) -> Option<TaskEstimate> {
+    let documents: Vec<String> = history
+        .iter()
+        .map(|task| document(&task.title, task.description.as_deref()))
+        .collect();
+    let index = SimilarityIndex::new(documents.iter().map(String::as_str));
+    let matches = index.query(
+        &document(title, description),
+        MAX_SIMILAR_TASKS,
+        MIN_SIMILARITY,
+    );
+    if matches.is_empty() {
+        return None;
+    }
+
+    let similar_tasks: Vec<SimilarTask> = matches
+        .into_iter()
+        .map(|(index, similarity)| {
+            let task = &history[index];
+            SimilarTask {
+                task_id: task.task_id,
+                title: task.title.clone(),
+                similarity,
+                turns: task.turns as u32,
+                duration_seconds: task.duration_seconds,
+                estimated_cost: task.estimated_cost,
+            }
+        })
+        .collect();
+    let median = |value: fn(&SimilarTask) -> f64| {
+        weighted_median(
+            similar_tasks
+                .iter()
+                .map(|task| (value(task), task.similarity))
+                .collect(),
+        )
+    };
+    let turns = median(|task| task.turns as f64);
+    let duration_seconds = median(|task| task.duration_seconds);
+    let estimated_cost = median(|task| task.estimated_cost);
+
+    Some(TaskEstimate {
+        effort: effort(turns, duration_seconds),
+        turns,
+        duration_seconds,
+        estimated_cost,
+        similar_tasks,
+    })
+}
+
+fn effort(turns: f64, duration_seconds: f64) -> Effort {
+    let minutes = duration_seconds / 60.0;
+    if turns >= LARGE_TURNS || minutes >= LARGE_MINUTES {
+        Effort::Large
+    } else if turns <= SMALL_TURNS && minutes <= SMALL_MINUTES {
+        Effort::Small
+    } else {
+        Effort::Medium
+    }
+}
+
+/// The value at which half of the total weight is reached
+fn weighted_median(mut values: Vec<(f64, f64)>) -> f64 {
+    values.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let half = values.iter().map(|(_, weight)| weight).sum::<f64>() / 2.0;
+    let mut seen = 0.0;
+    for (value, weight) in &values {
+        seen += weight;
+        if seen >= half {
+            return *value;
+        }
+    }
+    values.last().map_or(0.0, |(value, _)| *value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn done(title: &str, turns: i64, minutes: f64, cost: f64) -> TaskMetrics {
+        TaskMetrics {
+            task_id: Uuid::new_v4(),
+            title: title.to_string(),
+            description: None,
+            turns,
+            duration_seconds: minutes * 60.0,
+            estimated_cost: cost,
+        }
+    }
+
+    #[test]
+    fn estimates_from_similar_tasks_only() {
+        let history = vec![
+            done("Fix login form validation", 2, 10.0, 0.5),
+            done("Login form rejects valid email", 3, 12.0, 0.8),
+            done(
+                "Migrate billing service to new payment provider",
+                9,
+                120.0,
+                6.0,
+            ),
+        ];
+        let estimate = estimate("Login form validation for email", None, &history).unwrap();
+        assert_eq!(estimate.similar_tasks.len(), 2);
+        assert!(estimate.turns <= 3.0);
+        assert_ne!(estimate.effort, Effort::Large);
+    }
+
+    #[test]
+    fn nothing_similar_gives_no_estimate() {
+        let history = vec![done("Fix login form validation", 2, 10.0, 0.5)];
+        assert!(estimate("Upgrade the Helm chart", None, &history).is_none());
+    }
+
+    #[test]
+    fn weighted_median_leans_towards_closer_tasks() {
+        assert_eq!(weighted_median(vec![(1.0, 0.9), (10.0, 0.2)]), 1.0);
+        assert_eq!(weighted_median(vec![(1.0, 0.2), (10.0, 0.9)]), 10.0);
+        assert_eq!(effort(1.0, 300.0), Effort::Small);
+        assert_eq!(effort(4.0, 300.0), Effort::Medium);
+        assert_eq!(effort(2.0, 4000.0), Effort::Large);
+    }
+}
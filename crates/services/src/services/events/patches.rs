@@ -5,6 +5,8 @@ use db::models::{
 use json_patch::{AddOperation, Patch, PatchOperation, RemoveOperation, ReplaceOperation};
 use uuid::Uuid;
 
+use crate::services::offline::ProviderStatus;
+
 // Shared helper to escape JSON Pointer segments
 fn escape_pointer_segment(s: &str) -> String {
     s.replace('~', "~0").replace('/', "~1")
@@ -217,3 +219,26 @@ pub mod scratch_patch {
         })])
     }
 }
+
+/// Helper functions for creating provider reachability patches, at `/provider_status/<provider>`
+pub mod provider_status_patch {
+    use super::*;
+
+    /// Create patch for the reachability of a provider. Add replaces an earlier status, so clients
+    /// need no prior state.
+    pub fn set(status: &ProviderStatus) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: format!(
+                "/provider_status/{}",
+                serde_json::to_value(status.provider)
+                    .ok()
+                    .and_then(|name| name.as_str().map(escape_pointer_segment))
+                    .expect("Provider type should serialize to a string")
+            )
+            .try_into()
+            .expect("Provider status path should be valid"),
+            value: serde_json::to_value(status)
+                .expect("Provider status serialization should not fail"),
+        })])
+    }
+}
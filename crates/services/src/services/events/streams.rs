@@ -4,9 +4,12 @@ use db::models::{
     scratch::Scratch,
     session::Session,
     task::{Task, TaskWithAttemptStatus},
+    tenant::Tenant,
 };
 use futures::StreamExt;
+use json_patch::{Patch, PatchOperation};
 use serde_json::json;
+use sqlx::SqlitePool;
 use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
 use utils::log_msg::LogMsg;
 use uuid::Uuid;
@@ -14,10 +17,100 @@ use uuid::Uuid;
 use super::{
     EventService,
     patches::execution_process_patch,
-    types::{EventError, EventPatch, RecordTypes},
+    types::{EventError, EventPatch, EventPatchInner, RecordTypes},
 };
 
+/// Whether a record may be shown to clients working in a tenant. Unknown and deleted records are
+/// not, since their tenant cannot be told.
+async fn record_in_tenant(pool: &SqlitePool, tenant_id: Uuid, record: &RecordTypes) -> bool {
+    let owned = match record {
+        RecordTypes::Task(task) => Tenant::owns_project(pool, tenant_id, task.project_id).await,
+        RecordTypes::Project(project) => Tenant::owns_project(pool, tenant_id, project.id).await,
+        RecordTypes::Workspace(workspace) => {
+            Tenant::owns_task(pool, tenant_id, workspace.task_id).await
+        }
+        RecordTypes::ExecutionProcess(process) => {
+            Tenant::owns_session(pool, tenant_id, process.session_id).await
+        }
+        _ => return false,
+    };
+    owned.unwrap_or(false)
+}
+
+/// Whether a patch operation may be sent to clients working in a tenant. Records are checked by
+/// the id in their path. Removals are let through: the record is gone, so only its id is left.
+async fn operation_in_tenant(pool: &SqlitePool, tenant_id: Uuid, op: &PatchOperation) -> bool {
+    let value = match op {
+        PatchOperation::Add(op) => &op.value,
+        PatchOperation::Replace(op) => &op.value,
+        PatchOperation::Remove(_) => return true,
+        _ => return false,
+    };
+    let path = op.path().to_string();
+    let mut segments = path.trim_start_matches('/').split('/');
+    let collection = segments.next().unwrap_or_default();
+    let id = segments.next().and_then(|id| Uuid::parse_str(id).ok());
+
+    let owned = match (collection, id) {
+        ("projects", Some(id)) => Tenant::owns_project(pool, tenant_id, id).await,
+        ("tasks", Some(id)) => Tenant::owns_task(pool, tenant_id, id).await,
+        ("workspaces", Some(id)) => Tenant::owns_workspace(pool, tenant_id, id).await,
+        ("execution_processes", Some(id)) => {
+            Tenant::owns_execution_process(pool, tenant_id, id).await
+        }
+        // Follow-up drafts belong to a session; other drafts hold no records of a tenant
+        ("scratch", None) => match value["id"].as_str().and_then(|id| Uuid::parse_str(id).ok()) {
+            Some(id) if value["payload"]["type"] == "DRAFT_FOLLOW_UP" => {
+                Tenant::owns_session(pool, tenant_id, id).await
+            }
+            _ => Ok(true),
+        },
+        ("entries", _) => match serde_json::from_value::<EventPatchInner>(value.clone()) {
+            Ok(inner) => return record_in_tenant(pool, tenant_id, &inner.record).await,
+            Err(_) => Ok(false),
+        },
+        // Instance-wide state such as provider reachability
+        ("provider_status", _) => Ok(true),
+        _ => Ok(false),
+    };
+    owned.unwrap_or(false)
+}
+
+/// The operations of a message that clients working in a tenant may see; None when there are none
+async fn visible_in_tenant(pool: &SqlitePool, tenant_id: Uuid, msg: LogMsg) -> Option<LogMsg> {
+    let LogMsg::JsonPatch(patch) = msg else {
+        return Some(msg);
+    };
+    let mut kept = Vec::with_capacity(patch.0.len());
+    for op in patch.0 {
+        if operation_in_tenant(pool, tenant_id, &op).await {
+            kept.push(op);
+        }
+    }
+    (!kept.is_empty()).then(|| LogMsg::JsonPatch(Patch(kept)))
+}
+
 impl EventService {
+    /// History and live updates of every record, limited to those of one tenant
+    pub fn stream_events_for_tenant(
+        &self,
+        tenant_id: Uuid,
+    ) -> futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>> {
+        let db_pool = self.db.pool.clone();
+        self.msg_store
+            .history_plus_stream()
+            .filter_map(move |msg_result| {
+                let db_pool = db_pool.clone();
+                async move {
+                    match msg_result {
+                        Ok(msg) => visible_in_tenant(&db_pool, tenant_id, msg).await.map(Ok),
+                        Err(e) => Some(Err(e)),
+                    }
+                }
+            })
+            .boxed()
+    }
+
     /// Stream raw task messages for a specific project with initial snapshot
     pub async fn stream_tasks_raw(
         &self,
@@ -150,6 +243,7 @@ impl EventService {
     /// Stream raw project messages with initial snapshot
     pub async fn stream_projects_raw(
         &self,
+        tenant_id: Uuid,
     ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
     {
         fn build_projects_snapshot(projects: Vec<Project>) -> LogMsg {
@@ -176,7 +270,7 @@ impl EventService {
         }
 
         // Get initial snapshot of projects
-        let projects = Project::find_all(&self.db.pool).await?;
+        let projects = Project::find_by_tenant_id(&self.db.pool, tenant_id).await?;
         let initial_msg = build_projects_snapshot(projects);
 
         let db_pool = self.db.pool.clone();
@@ -191,7 +285,13 @@ impl EventService {
                             if let Some(patch_op) = patch.0.first()
                                 && patch_op.path().starts_with("/projects")
                             {
-                                return Some(Ok(LogMsg::JsonPatch(patch)));
+                                return visible_in_tenant(
+                                    &db_pool,
+                                    tenant_id,
+                                    LogMsg::JsonPatch(patch),
+                                )
+                                .await
+                                .map(Ok);
                             }
                             None
                         }
@@ -202,7 +302,7 @@ impl EventService {
                                 "projects stream lagged; resyncing snapshot"
                             );
 
-                            match Project::find_all(&db_pool).await {
+                            match Project::find_by_tenant_id(&db_pool, tenant_id).await {
                                 Ok(projects) => Some(Ok(build_projects_snapshot(projects))),
                                 Err(err) => {
                                     tracing::error!(
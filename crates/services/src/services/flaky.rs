@@ -0,0 +1,254 @@
+//! Flaky command detection.
+//!
+//! Every setup script, cleanup script and lint command run is recorded per repository. A failure
+//! followed by a pass in the same workspace at the same commit can only be flakiness, since
+//! nothing changed in between. Commands that flip like this often enough are quarantined, and
+//! quarantined commands no longer trigger fix-up turns.
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    command_quarantine::CommandQuarantine,
+    command_run::{CommandKind, CommandRun, CreateCommandRun},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Stored command text is cut to this many characters
+const MAX_COMMAND_LEN: usize = 500;
+
+fn default_auto_quarantine_threshold() -> u32 {
+    2
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct FlakyConfig {
+    /// Flaky failures after which a command is quarantined automatically; 0 disables it
+    #[serde(default = "default_auto_quarantine_threshold")]
+    pub auto_quarantine_threshold: u32,
+}
+
+impl Default for FlakyConfig {
+    fn default() -> Self {
+        Self {
+            auto_quarantine_threshold: default_auto_quarantine_threshold(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct FlakyCommand {
+    pub command_hash: String,
+    pub command: String,
+    pub kind: CommandKind,
+    pub runs: usize,
+    pub failures: usize,
+    /// Failures that passed on a retry at the same commit
+    pub flaky_failures: usize,
+    pub last_run_at: DateTime<Utc>,
+    pub quarantined: bool,
+}
+
+/// Identifies a command across attempts, independent of surrounding whitespace
+pub fn command_hash(command: &str) -> String {
+    format!("{:x}", Sha256::digest(command.trim().as_bytes()))
+}
+
+/// Per-command statistics for runs given oldest first. Commands that never failed are left out.
+pub fn classify(runs: &[CommandRun], quarantined: &HashSet<String>) -> Vec<FlakyCommand> {
+    let mut by_command: BTreeMap<&str, Vec<&CommandRun>> = BTreeMap::new();
+    for run in runs {
+        by_command.entry(&run.command_hash).or_default().push(run);
+    }
+
+    let mut commands: Vec<FlakyCommand> = by_command
+        .into_values()
+        .filter_map(|runs| {
+            let last = *runs.last()?;
+            let failures = runs.iter().filter(|run| !run.succeeded).count();
+            if failures == 0 {
+                return None;
+            }
+            let flaky_failures = runs
+                .iter()
+                .enumerate()
+                .filter(|(_, run)| !run.succeeded && run.head_commit.is_some())
+                .filter(|(index, failed)| {
+                    runs[index + 1..]
+                        .iter()
+                        .find(|retry| {
+                            retry.workspace_id == failed.workspace_id
+                                && retry.head_commit == failed.head_commit
+                        })
+                        .is_some_and(|retry| retry.succeeded)
+                })
+                .count();
+            Some(FlakyCommand {
+                command_hash: last.command_hash.clone(),
+                command: last.command.clone(),
+                kind: last.kind,
+                runs: runs.len(),
+                failures,
+                flaky_failures,
+                last_run_at: last.created_at,
+                quarantined: quarantined.contains(&last.command_hash),
+            })
+        })
+        .collect();
+    commands.sort_by(|a, b| {
+        b.flaky_failures
+            .cmp(&a.flaky_failures)
+            .then(b.failures.cmp(&a.failures))
+    });
+    commands
+}
+
+/// Commands with failures in a repository, most flaky first
+pub async fn flaky_commands(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+) -> Result<Vec<FlakyCommand>, sqlx::Error> {
+    let runs = CommandRun::find_recent_by_repo_id(pool, repo_id).await?;
+    let quarantined = quarantined_hashes(pool, repo_id).await?;
+    Ok(classify(&runs, &quarantined))
+}
+
+pub async fn quarantined_hashes(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+) -> Result<HashSet<String>, sqlx::Error> {
+    Ok(CommandQuarantine::find_by_repo_id(pool, repo_id)
+        .await?
+        .into_iter()
+        .map(|quarantine| quarantine.command_hash)
+        .collect())
+}
+
+pub struct CommandOutcome<'a> {
+    pub repo_id: Uuid,
+    pub workspace_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    pub kind: CommandKind,
+    pub command: &'a str,
+    pub head_commit: Option<String>,
+    pub succeeded: bool,
+}
+
+/// Record a command run and quarantine the command once it has flipped from failing to passing
+/// `threshold` times. Returns whether the command was quarantined by this run.
+pub async fn record(
+    pool: &SqlitePool,
+    outcome: CommandOutcome<'_>,
+    threshold: u32,
+) -> Result<bool, sqlx::Error> {
+    let command_hash = command_hash(outcome.command);
+    let command: String = outcome
+        .command
+        .trim()
+        .chars()
+        .take(MAX_COMMAND_LEN)
+        .collect();
+    CommandRun::create(
+        pool,
+        &CreateCommandRun {
+            repo_id: outcome.repo_id,
+            workspace_id: outcome.workspace_id,
+            execution_process_id: outcome.execution_process_id,
+            kind: outcome.kind,
+            command_hash: command_hash.clone(),
+            command: command.clone(),
+            head_commit: outcome.head_commit,
+            succeeded: outcome.succeeded,
+        },
+    )
+    .await?;
+
+    // Only a pass can turn an earlier failure into a flaky one
+    if !outcome.succeeded || threshold == 0 {
+        return Ok(false);
+    }
+    let quarantined = quarantined_hashes(pool, outcome.repo_id).await?;
+    if quarantined.contains(&command_hash) {
+        return Ok(false);
+    }
+    let runs: Vec<CommandRun> = CommandRun::find_recent_by_repo_id(pool, outcome.repo_id)
+        .await?
+        .into_iter()
+        .filter(|run| run.command_hash == command_hash)
+        .collect();
+    let is_flaky = classify(&runs, &quarantined)
+        .first()
+        .is_some_and(|stats| stats.flaky_failures >= threshold as usize);
+    if is_flaky {
+        tracing::info!(
+            "Quarantining flaky command in repo {}: {}",
+            outcome.repo_id,
+            command
+        );
+        CommandQuarantine::create(pool, outcome.repo_id, &command_hash, &command, true).await?;
+    }
+    Ok(is_flaky)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(workspace_id: Uuid, head: &str, succeeded: bool, command: &str) -> CommandRun {
+        CommandRun {
+            id: Uuid::new_v4(),
+            repo_id: Uuid::nil(),
+            workspace_id,
+            execution_process_id: None,
+            kind: CommandKind::CleanupScript,
+            command_hash: command_hash(command),
+            command: command.to_string(),
+            head_commit: Some(head.to_string()),
+            succeeded,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn failure_then_pass_at_same_commit_is_flaky() {
+        let ws = Uuid::new_v4();
+        let runs = vec![
+            run(ws, "a1", false, "npm test"),
+            run(ws, "a1", true, "npm test"),
+            // A fix in a new commit is a real failure, not flakiness
+            run(ws, "b2", false, "npm test"),
+            run(ws, "c3", true, "npm test"),
+            run(ws, "c3", true, "cargo test"),
+        ];
+        let commands = classify(&runs, &HashSet::new());
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].command, "npm test");
+        assert_eq!(commands[0].runs, 4);
+        assert_eq!(commands[0].failures, 2);
+        assert_eq!(commands[0].flaky_failures, 1);
+        assert!(!commands[0].quarantined);
+    }
+
+    #[test]
+    fn retries_only_count_within_the_same_workspace() {
+        let (ws1, ws2) = (Uuid::new_v4(), Uuid::new_v4());
+        let runs = vec![
+            run(ws1, "a1", false, "pytest"),
+            run(ws2, "a1", true, "pytest"),
+        ];
+        let quarantined = HashSet::from([command_hash("pytest")]);
+        let commands = classify(&runs, &quarantined);
+        assert_eq!(commands[0].flaky_failures, 0);
+        assert!(commands[0].quarantined);
+    }
+
+    #[test]
+    fn hash_ignores_surrounding_whitespace() {
+        assert_eq!(command_hash("  npm test\n"), command_hash("npm test"));
+    }
+}
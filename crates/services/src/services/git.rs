@@ -1,4 +1,7 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::Path,
+};
 
 use chrono::{DateTime, Utc};
 use git2::{
@@ -79,6 +82,9 @@ impl Commit {
     pub fn new(id: git2::Oid) -> Self {
         Self(id)
     }
+    pub fn from_sha(sha: &str) -> Result<Self, GitServiceError> {
+        Ok(Self(git2::Oid::from_str(sha)?))
+    }
     pub fn as_oid(&self) -> git2::Oid {
         self.0
     }
@@ -181,6 +187,12 @@ impl GitService {
         &self,
         repo: &'a Repository,
     ) -> Result<git2::Signature<'a>, GitServiceError> {
+        // Fixed author and time, so commits made in test mode get the same ids every run
+        if utils::test_mode::is_enabled() {
+            let time = git2::Time::new(utils::test_mode::now().timestamp(), 0);
+            return git2::Signature::new("Vibe Kanban", "noreply@vibekanban.com", &time)
+                .map_err(GitServiceError::from);
+        }
         match repo.signature() {
             Ok(sig) => Ok(sig),
             Err(_) => git2::Signature::now("Vibe Kanban", "noreply@vibekanban.com")
@@ -189,6 +201,10 @@ impl GitService {
     }
 
     pub fn default_remote_name(&self, repo: &Repository) -> String {
+        // In a fork checkout `origin` is the fork, which is where branches are pushed
+        if repo.find_remote("origin").is_ok() {
+            return "origin".to_string();
+        }
         if let Ok(repos) = repo.remotes() {
             repos
                 .iter()
@@ -290,6 +306,28 @@ impl GitService {
         Ok(true)
     }
 
+    /// Paths changed from one commit to another, with both sides of renames
+    pub fn changed_paths_between(
+        &self,
+        repo_path: &Path,
+        from: &Commit,
+        to: &Commit,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let from_tree = repo.find_commit(from.as_oid())?.tree()?;
+        let to_tree = repo.find_commit(to.as_oid())?.tree()?;
+        let mut diff = repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None)?;
+        diff.find_similar(None)?;
+
+        let paths: BTreeSet<String> = diff
+            .deltas()
+            .flat_map(|delta| [delta.old_file().path(), delta.new_file().path()])
+            .flatten()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        Ok(paths.into_iter().collect())
+    }
+
     /// Get diffs between branches or worktree changes
     pub fn get_diffs(
         &self,
@@ -956,6 +994,34 @@ impl GitService {
         self.get_branch_status_inner(&repo, &branch_ref, &base_branch_ref)
     }
 
+    /// Commits `branch_name` is ahead of and behind its remote branch as of the last fetch, or
+    /// None when the branch has not been pushed. Unlike `get_remote_branch_status` this does not
+    /// fetch.
+    pub fn get_pushed_branch_status(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<Option<(usize, usize)>, GitServiceError> {
+        let repo = Repository::open(repo_path)?;
+        let branch = repo.find_branch(branch_name, BranchType::Local)?;
+        let remote_branch = match branch.upstream() {
+            Ok(upstream) => upstream,
+            Err(_) => {
+                let remote = self.default_remote_name(&repo);
+                match repo.find_branch(&format!("{remote}/{branch_name}"), BranchType::Remote) {
+                    Ok(remote_branch) => remote_branch,
+                    Err(_) => return Ok(None),
+                }
+            }
+        };
+        self.get_branch_status_inner(
+            &repo,
+            &branch.into_reference(),
+            &remote_branch.into_reference(),
+        )
+        .map(Some)
+    }
+
     pub fn is_worktree_clean(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
         let repo = self.open_repo(worktree_path)?;
         match self.check_worktree_clean(&repo) {
@@ -1491,6 +1557,68 @@ impl GitService {
         Ok(())
     }
 
+    /// Make sure `branch_name` exists locally, creating it from the default remote's branch of
+    /// the same name, tracking it, when the branch only exists there.
+    pub fn ensure_local_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        if repo.find_branch(branch_name, BranchType::Local).is_ok() {
+            return Ok(());
+        }
+        let remote_branch_name = format!("{}/{branch_name}", self.default_remote_name(&repo));
+        let remote_branch = repo
+            .find_branch(&remote_branch_name, BranchType::Remote)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?;
+        let commit = remote_branch.get().peel_to_commit()?;
+        let mut branch = repo.branch(branch_name, &commit, false)?;
+        branch.set_upstream(Some(&remote_branch_name))?;
+        Ok(())
+    }
+
+    /// Guess the branch `branch_name` was started from: the local branch it has the fewest
+    /// commits on top of, preferring the checked-out branch on a tie. Branches that already
+    /// contain all of `branch_name` are only considered when no other branch is left.
+    pub fn infer_base_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let tip = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?
+            .get()
+            .peel_to_commit()?
+            .id();
+        let current_branch = self.get_current_branch(repo_path).ok();
+
+        // Ordered so that the smallest key is the best candidate
+        let mut best: Option<(bool, usize, bool, String)> = None;
+        for branch in repo.branches(Some(BranchType::Local))? {
+            let (branch, _) = branch?;
+            let (Some(name), Some(target)) = (branch.name()?, branch.get().target()) else {
+                continue;
+            };
+            if name == branch_name {
+                continue;
+            }
+            let (ahead, _) = repo.graph_ahead_behind(tip, target)?;
+            let key = (
+                ahead == 0,
+                ahead,
+                current_branch.as_deref() != Some(name),
+                name.to_string(),
+            );
+            if best.as_ref().is_none_or(|best| key < *best) {
+                best = Some(key);
+            }
+        }
+        Ok(best.map(|(_, _, _, name)| name))
+    }
+
     /// Return true if a rebase is currently in progress in this worktree.
     pub fn is_rebase_in_progress(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
         let git = GitCli::new();
@@ -16,6 +16,7 @@
 //! `git` CLI, while keeping libgit2 for read‑only graph queries and credentialed
 //! network operations when useful.
 use std::{
+    collections::HashMap,
     ffi::{OsStr, OsString},
     io::Write as _,
     path::Path,
@@ -364,6 +365,132 @@ impl GitCli {
         }
     }
 
+    /// Clone `remote_url` into `parent_dir/dir_name` using native git authentication. With a
+    /// depth, only that much history is fetched, for every branch.
+    pub fn clone_repo(
+        &self,
+        parent_dir: &Path,
+        remote_url: &str,
+        dir_name: &str,
+        depth: Option<u32>,
+    ) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+
+        let mut args = vec![OsString::from("clone")];
+        if let Some(depth) = depth {
+            args.push(OsString::from(format!("--depth={depth}")));
+            args.push(OsString::from("--no-single-branch"));
+        }
+        args.extend([
+            OsString::from("--"),
+            OsString::from(remote_url),
+            OsString::from(dir_name),
+        ]);
+
+        match self.git_with_env(parent_dir, args, &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fetch all branches of `remote` using native git authentication, pruning deleted ones.
+    pub fn fetch_prune(&self, repo_path: &Path, remote: &str) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        match self.git_with_env(repo_path, ["fetch", "--prune", remote], &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Names of the remotes configured in `repo_path`.
+    pub fn remotes(&self, repo_path: &Path) -> Result<Vec<String>, GitCliError> {
+        let out = self.git(repo_path, ["remote"])?;
+        Ok(out
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect())
+    }
+
+    pub fn set_remote_url(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+        url: &str,
+    ) -> Result<(), GitCliError> {
+        self.git(repo_path, ["remote", "set-url", remote, url])?;
+        Ok(())
+    }
+
+    /// Detach HEAD at its current commit so no branch stays checked out in `repo_path`.
+    pub fn detach_head(&self, repo_path: &Path) -> Result<(), GitCliError> {
+        self.git(repo_path, ["checkout", "--detach"])?;
+        Ok(())
+    }
+
+    /// Create a local branch for every branch of `remote` and fast-forward local branches that
+    /// are behind their remote counterpart. Branches checked out in a worktree or with local
+    /// commits are left alone. Returns the names of the created or updated branches.
+    pub fn sync_local_branches(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+    ) -> Result<Vec<String>, GitCliError> {
+        let remote_prefix = format!("refs/remotes/{remote}/");
+        let out = self.git(
+            repo_path,
+            [
+                "for-each-ref",
+                "--format=%(refname)%00%(objectname)%00%(worktreepath)",
+                "refs/heads",
+                remote_prefix.trim_end_matches('/'),
+            ],
+        )?;
+        let mut local: HashMap<&str, (&str, bool)> = HashMap::new();
+        let mut remote_heads: Vec<(&str, &str)> = Vec::new();
+        for line in out.lines() {
+            let mut parts = line.split('\0');
+            let (Some(refname), Some(oid)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            let checked_out = parts.next().is_some_and(|path| !path.is_empty());
+            if let Some(name) = refname.strip_prefix("refs/heads/") {
+                local.insert(name, (oid, checked_out));
+            } else if let Some(name) = refname.strip_prefix(&remote_prefix)
+                && name != "HEAD"
+            {
+                remote_heads.push((name, oid));
+            }
+        }
+
+        let mut updated = Vec::new();
+        for (name, oid) in remote_heads {
+            let refname = format!("refs/heads/{name}");
+            match local.get(name) {
+                None => {
+                    self.git(repo_path, ["update-ref", refname.as_str(), oid])?;
+                }
+                Some((local_oid, checked_out)) => {
+                    if *checked_out || *local_oid == oid {
+                        continue;
+                    }
+                    let fast_forward = self
+                        .git(repo_path, ["merge-base", "--is-ancestor", local_oid, oid])
+                        .is_ok();
+                    if !fast_forward {
+                        continue;
+                    }
+                    self.git(repo_path, ["update-ref", refname.as_str(), oid, local_oid])?;
+                }
+            }
+            updated.push(name.to_string());
+        }
+        Ok(updated)
+    }
+
     /// Push a branch to the given remote using native git authentication.
     pub fn push(
         &self,
@@ -392,6 +519,36 @@ impl GitCli {
         }
     }
 
+    /// Push a branch to Gerrit's magic `refs/for/<target>` ref, which opens or updates a change
+    /// for every commit not yet on the target branch.
+    pub fn push_for_review(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        branch: &str,
+        target_branch: &str,
+        push_options: &[String],
+    ) -> Result<(), GitCliError> {
+        let mut refspec = format!("refs/heads/{branch}:refs/for/{target_branch}");
+        if !push_options.is_empty() {
+            refspec.push('%');
+            refspec.push_str(&push_options.join(","));
+        }
+        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+
+        let args = [
+            OsString::from("push"),
+            OsString::from(remote_url),
+            OsString::from(refspec),
+        ];
+
+        match self.git_with_env(repo_path, args, &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
     /// This directly queries the remote without fetching.
     pub fn check_remote_branch_exists(
         &self,
@@ -704,6 +861,11 @@ impl GitCli {
         let git = resolve_executable_path_blocking("git").ok_or(GitCliError::NotAvailable)?;
         let mut cmd = Command::new(&git);
         cmd.arg("-C").arg(repo_path);
+        // Worktrees live deep under the temp dir; without this git fails to check out
+        // files whose full path exceeds MAX_PATH on Windows.
+        if cfg!(windows) {
+            cmd.arg("-c").arg("core.longpaths=true");
+        }
 
         if let Some(envs) = envs {
             for (k, v) in envs {
@@ -0,0 +1,541 @@
+//! Azure DevOps provider implementation
+//!
+//! Uses the Azure DevOps REST API (7.1) for all operations. Works for remotes on dev.azure.com
+//! as well as the older `{organization}.visualstudio.com` hosts, which serve the same API.
+//!
+//! Credentials come from the environment: a personal access token with the Code (Read & Write)
+//! scope in `AZURE_DEVOPS_TOKEN`, or in `AZURE_DEVOPS_EXT_PAT` as used by the `az` CLI.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use backon::Retryable;
+use chrono::{DateTime, Utc};
+use reqwest::{RequestBuilder, StatusCode};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{
+    CommitStatusState, CreateMrRequest, GitProvider, GuardedSend, PrInfo, PrState, ProviderError,
+    ProviderType, RepoIdentifier, RetryPolicy, UnifiedComment, max_pages,
+};
+
+const DEFAULT_BASE_URL: &str = "https://dev.azure.com";
+const PROFILE_URL: &str = "https://app.vssps.visualstudio.com/_apis/profile/profiles/me";
+const API_VERSION: &str = "7.1";
+
+/// Pull requests requested per page when listing
+const PAGE_SIZE: usize = 100;
+
+/// Azure DevOps pull request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzurePullRequest {
+    pub pull_request_id: u64,
+    /// active, abandoned or completed
+    pub status: String,
+    pub closed_date: Option<DateTime<Utc>>,
+    pub last_merge_commit: Option<AzureCommitRef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureCommitRef {
+    pub commit_id: String,
+}
+
+/// Discussion thread on a pull request; code comments carry a thread context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureThread {
+    pub id: i64,
+    #[serde(default)]
+    pub comments: Vec<AzureComment>,
+    pub thread_context: Option<AzureThreadContext>,
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureComment {
+    pub id: i64,
+    pub author: Option<AzureIdentity>,
+    pub content: Option<String>,
+    pub published_date: DateTime<Utc>,
+    /// text, codeChange or system
+    pub comment_type: Option<String>,
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureIdentity {
+    pub display_name: Option<String>,
+    pub unique_name: Option<String>,
+}
+
+/// Position of a code comment; right is the new file, left the old one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureThreadContext {
+    pub file_path: String,
+    pub right_file_start: Option<AzureFilePosition>,
+    pub left_file_start: Option<AzureFilePosition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AzureFilePosition {
+    pub line: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AzureList<T> {
+    value: Vec<T>,
+}
+
+/// Azure DevOps error response
+#[derive(Debug, Clone, Deserialize)]
+struct AzureError {
+    message: String,
+}
+
+impl AzurePullRequest {
+    fn into_pr_info(self, web_url: &str) -> PrInfo {
+        let state = match self.status.as_str() {
+            "active" => PrState::Open,
+            "completed" => PrState::Merged,
+            "abandoned" => PrState::Closed,
+            _ => PrState::Unknown,
+        };
+        PrInfo {
+            number: self.pull_request_id,
+            url: format!("{}/pullrequest/{}", web_url, self.pull_request_id),
+            state,
+            merged_at: if state == PrState::Merged {
+                self.closed_date
+            } else {
+                None
+            },
+            merge_commit_sha: if state == PrState::Merged {
+                self.last_merge_commit.map(|commit| commit.commit_id)
+            } else {
+                None
+            },
+            review_state: None,
+        }
+    }
+}
+
+impl AzureThread {
+    /// Comments of the thread written by people, leaving out votes and other system messages
+    fn into_comments(self, pr_url: &str) -> Vec<UnifiedComment> {
+        if self.is_deleted {
+            return Vec::new();
+        }
+        let url = format!("{}?discussionId={}", pr_url, self.id);
+        let thread_id = self.id;
+        let context = self.thread_context;
+        self.comments
+            .into_iter()
+            .filter(|comment| {
+                !comment.is_deleted && comment.comment_type.as_deref() != Some("system")
+            })
+            .map(|comment| {
+                let author = comment
+                    .author
+                    .and_then(|author| author.unique_name.or(author.display_name))
+                    .unwrap_or_else(|| "unknown".to_string());
+                let body = comment.content.unwrap_or_default();
+                match &context {
+                    Some(context) => UnifiedComment::Review {
+                        // Comment ids are only unique within their thread
+                        id: thread_id * 1000 + comment.id,
+                        author,
+                        author_association: "MEMBER".to_string(),
+                        body,
+                        created_at: comment.published_date,
+                        url: url.clone(),
+                        path: context.file_path.trim_start_matches('/').to_string(),
+                        start_line: None,
+                        line: context
+                            .right_file_start
+                            .as_ref()
+                            .or(context.left_file_start.as_ref())
+                            .map(|position| position.line),
+                        // The threads API does not include the surrounding diff
+                        diff_hunk: String::new(),
+                    },
+                    None => UnifiedComment::General {
+                        id: format!("{}-{}", thread_id, comment.id),
+                        author,
+                        author_association: "MEMBER".to_string(),
+                        body,
+                        created_at: comment.published_date,
+                        url: url.clone(),
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+/// Azure DevOps provider implementation using the REST API
+#[derive(Debug, Clone)]
+pub struct AzureDevOpsProvider {
+    base_url: String,
+    token: Option<SecretString>,
+    http_client: reqwest::Client,
+}
+
+impl AzureDevOpsProvider {
+    /// Create new Azure DevOps provider from environment credentials
+    ///
+    /// Set `AZURE_DEVOPS_URL` to talk to another endpoint, e.g. a proxy.
+    pub fn new() -> Self {
+        let base_url = std::env::var("AZURE_DEVOPS_URL")
+            .map(|url| url.trim_end_matches('/').to_string())
+            .unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+
+        let token = std::env::var("AZURE_DEVOPS_TOKEN")
+            .or_else(|_| std::env::var("AZURE_DEVOPS_EXT_PAT"))
+            .ok()
+            .map(SecretString::from);
+
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            base_url,
+            token,
+            http_client,
+        }
+    }
+
+    /// Check if credentials are configured
+    pub fn has_credentials(&self) -> bool {
+        self.token.is_some()
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> Result<RequestBuilder, ProviderError> {
+        match &self.token {
+            // Personal access tokens go in the password of basic auth, with any user name
+            Some(token) => Ok(request
+                .basic_auth("", Some(token.expose_secret()))
+                .query(&[("api-version", API_VERSION)])),
+            None => Err(ProviderError::NotAuthenticated(
+                "Set AZURE_DEVOPS_TOKEN to a personal access token".to_string(),
+            )),
+        }
+    }
+
+    fn project(repo: &RepoIdentifier) -> Result<&str, ProviderError> {
+        repo.project.as_deref().ok_or_else(|| {
+            ProviderError::Git(format!(
+                "Azure DevOps repository {} has no project",
+                repo.full_path()
+            ))
+        })
+    }
+
+    fn api_url(&self, repo: &RepoIdentifier) -> Result<String, ProviderError> {
+        Ok(format!(
+            "{}/{}/{}/_apis/git/repositories/{}",
+            self.base_url,
+            repo.owner,
+            Self::project(repo)?,
+            repo.name
+        ))
+    }
+
+    fn web_url(&self, repo: &RepoIdentifier) -> Result<String, ProviderError> {
+        Ok(format!(
+            "{}/{}/{}/_git/{}",
+            self.base_url,
+            repo.owner,
+            Self::project(repo)?,
+            repo.name
+        ))
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ProviderError> {
+        let response = self
+            .authorize(request)?
+            .send_guarded(ProviderType::AzureDevOps)
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(parse_error(status, &error_text));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ParseError(format!("Failed to parse response: {e}")))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, ProviderError> {
+        (|| async { self.send(self.http_client.get(url).query(query)).await })
+            .retry(retry_policy().backoff())
+            .when(|e: &ProviderError| retry_policy().should_retry(e))
+            .notify(|err, dur: Duration| {
+                tracing::warn!(
+                    "Azure DevOps API retry after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            })
+            .await
+    }
+}
+
+impl Default for AzureDevOpsProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse error response
+fn parse_error(status: StatusCode, body: &str) -> ProviderError {
+    // An expired or missing token is answered with a sign-in page rather than a 401
+    if status == StatusCode::UNAUTHORIZED
+        || status == StatusCode::FORBIDDEN
+        || status == StatusCode::NON_AUTHORITATIVE_INFORMATION
+    {
+        return ProviderError::NotAuthenticated(format!(
+            "Azure DevOps authentication failed: {}",
+            body
+        ));
+    }
+
+    let message = serde_json::from_str::<AzureError>(body)
+        .map(|error| error.message)
+        .unwrap_or_else(|_| body.to_string());
+    ProviderError::ApiError {
+        status: status.as_u16(),
+        message,
+    }
+}
+
+fn retry_policy() -> RetryPolicy {
+    super::retry_policy(ProviderType::AzureDevOps)
+}
+
+fn branch_ref(branch: &str) -> String {
+    format!("refs/heads/{}", branch.trim_start_matches("refs/heads/"))
+}
+
+#[async_trait]
+impl GitProvider for AzureDevOpsProvider {
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::AzureDevOps
+    }
+
+    async fn check_auth(&self) -> Result<(), ProviderError> {
+        let _: serde_json::Value = self.get(PROFILE_URL, &[]).await?;
+        Ok(())
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &RepoIdentifier,
+        req: &CreateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        let body = serde_json::json!({
+            "title": req.title,
+            "description": req.body.clone().unwrap_or_default(),
+            "sourceRefName": branch_ref(&req.head_branch),
+            "targetRefName": branch_ref(&req.base_branch),
+            "isDraft": req.draft.unwrap_or(false),
+        });
+        // Not retried, so a timed-out request cannot open the pull request twice
+        let pr: AzurePullRequest = self
+            .send(
+                self.http_client
+                    .post(format!("{}/pullrequests", self.api_url(repo)?))
+                    .json(&body),
+            )
+            .await?;
+        Ok(pr.into_pr_info(&self.web_url(repo)?))
+    }
+
+    async fn get_mr_status(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<PrInfo, ProviderError> {
+        let pr: AzurePullRequest = self
+            .get(
+                &format!("{}/pullrequests/{}", self.api_url(repo)?, number),
+                &[],
+            )
+            .await?;
+        Ok(pr.into_pr_info(&self.web_url(repo)?))
+    }
+
+    async fn list_mrs_for_branch(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<Vec<PrInfo>, ProviderError> {
+        let url = format!("{}/pullrequests", self.api_url(repo)?);
+        let web_url = self.web_url(repo)?;
+        let mut prs = Vec::new();
+        for page in 0..max_pages() {
+            let list: AzureList<AzurePullRequest> = self
+                .get(
+                    &url,
+                    &[
+                        ("searchCriteria.sourceRefName", branch_ref(branch)),
+                        ("searchCriteria.status", "all".to_string()),
+                        ("$top", PAGE_SIZE.to_string()),
+                        ("$skip", (page * PAGE_SIZE).to_string()),
+                    ],
+                )
+                .await?;
+            let count = list.value.len();
+            prs.extend(list.value.into_iter().map(|pr| pr.into_pr_info(&web_url)));
+            if count < PAGE_SIZE {
+                break;
+            }
+        }
+        Ok(prs)
+    }
+
+    async fn get_comments(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<UnifiedComment>, ProviderError> {
+        let threads: AzureList<AzureThread> = self
+            .get(
+                &format!("{}/pullrequests/{}/threads", self.api_url(repo)?, number),
+                &[],
+            )
+            .await?;
+
+        let pr_url = format!("{}/pullrequest/{}", self.web_url(repo)?, number);
+        let mut unified: Vec<UnifiedComment> = threads
+            .value
+            .into_iter()
+            .flat_map(|thread| thread.into_comments(&pr_url))
+            .collect();
+        unified.sort_by_key(|c| c.created_at());
+        Ok(unified)
+    }
+
+    /// Azure DevOps keeps every status posted and branch policies go by the latest one of each
+    /// genre and name, which are split from `context` at its last `/`
+    async fn set_commit_status(
+        &self,
+        repo: &RepoIdentifier,
+        sha: &str,
+        state: CommitStatusState,
+        context: &str,
+        url: Option<&str>,
+    ) -> Result<(), ProviderError> {
+        let state = match state {
+            CommitStatusState::Pending => "pending",
+            CommitStatusState::Success => "succeeded",
+            CommitStatusState::Failure => "failed",
+            CommitStatusState::Error => "error",
+        };
+        let (genre, name) = context.rsplit_once('/').unwrap_or(("", context));
+        let body = serde_json::json!({
+            "state": state,
+            "context": { "genre": genre, "name": name },
+            "targetUrl": url,
+        });
+        let status_url = format!("{}/commits/{}/statuses", self.api_url(repo)?, sha);
+        let _: serde_json::Value = (|| async {
+            self.send(self.http_client.post(&status_url).json(&body))
+                .await
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEB_URL: &str = "https://dev.azure.com/org/project/_git/app";
+
+    #[test]
+    fn converts_pull_request_states() {
+        let pr: AzurePullRequest = serde_json::from_value(serde_json::json!({
+            "pullRequestId": 7,
+            "status": "completed",
+            "closedDate": "2025-01-02T03:04:05Z",
+            "lastMergeCommit": { "commitId": "abc123" }
+        }))
+        .unwrap();
+        let info = pr.into_pr_info(WEB_URL);
+        assert_eq!(info.number, 7);
+        assert_eq!(info.state, PrState::Merged);
+        assert_eq!(info.url, format!("{WEB_URL}/pullrequest/7"));
+        assert_eq!(info.merge_commit_sha.as_deref(), Some("abc123"));
+        assert!(info.merged_at.is_some());
+
+        let active: AzurePullRequest = serde_json::from_value(serde_json::json!({
+            "pullRequestId": 8,
+            "status": "active",
+            "lastMergeCommit": { "commitId": "def456" }
+        }))
+        .unwrap();
+        let info = active.into_pr_info(WEB_URL);
+        assert_eq!(info.state, PrState::Open);
+        assert!(info.merge_commit_sha.is_none());
+    }
+
+    #[test]
+    fn code_threads_become_review_comments_without_system_messages() {
+        let thread: AzureThread = serde_json::from_value(serde_json::json!({
+            "id": 3,
+            "threadContext": {
+                "filePath": "/src/lib.rs",
+                "rightFileStart": { "line": 12, "offset": 1 }
+            },
+            "comments": [
+                {
+                    "id": 1,
+                    "author": { "displayName": "Sam Lee", "uniqueName": "slee@example.com" },
+                    "content": "Rename this",
+                    "publishedDate": "2025-01-02T03:04:05Z",
+                    "commentType": "text"
+                },
+                {
+                    "id": 2,
+                    "content": "Sam Lee voted 10",
+                    "publishedDate": "2025-01-02T03:05:05Z",
+                    "commentType": "system"
+                }
+            ]
+        }))
+        .unwrap();
+        let comments = thread.into_comments(&format!("{WEB_URL}/pullrequest/7"));
+        assert_eq!(comments.len(), 1);
+        match &comments[0] {
+            UnifiedComment::Review {
+                author, path, line, ..
+            } => {
+                assert_eq!(author, "slee@example.com");
+                assert_eq!(path, "src/lib.rs");
+                assert_eq!(*line, Some(12));
+            }
+            other => panic!("expected a review comment, got {other:?}"),
+        }
+    }
+}
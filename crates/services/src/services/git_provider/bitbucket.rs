@@ -0,0 +1,522 @@
+//! Bitbucket Cloud provider implementation
+//!
+//! Uses the Bitbucket 2.0 REST API for all operations, since there is no official CLI.
+//!
+//! Credentials come from the environment: either `BITBUCKET_USERNAME` with
+//! `BITBUCKET_APP_PASSWORD` (an app password or API token), or `BITBUCKET_TOKEN` holding a
+//! repository, project or workspace access token.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use backon::Retryable;
+use chrono::{DateTime, Utc};
+use reqwest::{RequestBuilder, StatusCode};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{
+    CommitStatusState, CreateMrRequest, GitProvider, GuardedSend, PrInfo, PrState, ProviderError,
+    ProviderType, RepoIdentifier, RetryPolicy, UnifiedComment, max_pages,
+};
+
+const DEFAULT_API_URL: &str = "https://api.bitbucket.org/2.0";
+
+#[derive(Debug, Clone)]
+enum BitbucketAuth {
+    AppPassword {
+        username: String,
+        password: SecretString,
+    },
+    AccessToken(SecretString),
+}
+
+/// Bitbucket Cloud pull request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketPullRequest {
+    pub id: u64,
+    /// OPEN, MERGED, DECLINED or SUPERSEDED
+    pub state: String,
+    pub links: BitbucketLinks,
+    pub merge_commit: Option<BitbucketCommit>,
+    pub updated_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketLinks {
+    pub html: Option<BitbucketLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketLink {
+    pub href: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketCommit {
+    pub hash: String,
+}
+
+/// Bitbucket Cloud pull request comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketComment {
+    pub id: i64,
+    pub content: BitbucketContent,
+    pub user: Option<BitbucketUser>,
+    pub created_on: DateTime<Utc>,
+    pub links: BitbucketLinks,
+    pub inline: Option<BitbucketInline>,
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketContent {
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketUser {
+    pub display_name: Option<String>,
+    pub nickname: Option<String>,
+}
+
+/// Position of an inline comment; `to` is the line in the new file, `from` in the old one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketInline {
+    pub path: String,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BitbucketPage<T> {
+    values: Vec<T>,
+    next: Option<String>,
+}
+
+/// Bitbucket error response
+#[derive(Debug, Clone, Deserialize)]
+struct BitbucketError {
+    error: BitbucketErrorDetail,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BitbucketErrorDetail {
+    message: String,
+}
+
+impl From<BitbucketPullRequest> for PrInfo {
+    fn from(pr: BitbucketPullRequest) -> Self {
+        let state = match pr.state.as_str() {
+            "OPEN" => PrState::Open,
+            "MERGED" => PrState::Merged,
+            "DECLINED" | "SUPERSEDED" => PrState::Closed,
+            _ => PrState::Unknown,
+        };
+        PrInfo {
+            number: pr.id,
+            url: pr.links.html.map(|link| link.href).unwrap_or_default(),
+            state,
+            // Bitbucket has no merge timestamp; a merged PR is not updated afterwards
+            merged_at: if state == PrState::Merged {
+                pr.updated_on
+            } else {
+                None
+            },
+            merge_commit_sha: pr.merge_commit.map(|commit| commit.hash),
+            review_state: None,
+        }
+    }
+}
+
+impl From<BitbucketComment> for UnifiedComment {
+    fn from(comment: BitbucketComment) -> Self {
+        let author = comment
+            .user
+            .and_then(|user| user.nickname.or(user.display_name))
+            .unwrap_or_else(|| "unknown".to_string());
+        let url = comment.links.html.map(|link| link.href).unwrap_or_default();
+        match comment.inline {
+            Some(inline) => UnifiedComment::Review {
+                id: comment.id,
+                author,
+                author_association: "MEMBER".to_string(),
+                body: comment.content.raw,
+                created_at: comment.created_on,
+                url,
+                path: inline.path,
+                start_line: None,
+                line: inline.to.or(inline.from),
+                // The comments API does not include the surrounding diff
+                diff_hunk: String::new(),
+            },
+            None => UnifiedComment::General {
+                id: comment.id.to_string(),
+                author,
+                author_association: "MEMBER".to_string(),
+                body: comment.content.raw,
+                created_at: comment.created_on,
+                url,
+            },
+        }
+    }
+}
+
+/// Bitbucket Cloud provider implementation using the REST API
+#[derive(Debug, Clone)]
+pub struct BitbucketProvider {
+    base_url: String,
+    auth: Option<BitbucketAuth>,
+    http_client: reqwest::Client,
+}
+
+impl BitbucketProvider {
+    /// Create new Bitbucket provider from environment credentials
+    ///
+    /// Set `BITBUCKET_API_URL` to talk to another API endpoint, e.g. a proxy.
+    pub fn new() -> Self {
+        let base_url = std::env::var("BITBUCKET_API_URL")
+            .map(|url| url.trim_end_matches('/').to_string())
+            .unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+
+        let app_password = match (
+            std::env::var("BITBUCKET_USERNAME"),
+            std::env::var("BITBUCKET_APP_PASSWORD"),
+        ) {
+            (Ok(username), Ok(password)) => Some(BitbucketAuth::AppPassword {
+                username,
+                password: SecretString::from(password),
+            }),
+            _ => None,
+        };
+        let auth = app_password.or_else(|| {
+            std::env::var("BITBUCKET_TOKEN")
+                .ok()
+                .map(|token| BitbucketAuth::AccessToken(SecretString::from(token)))
+        });
+
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            base_url,
+            auth,
+            http_client,
+        }
+    }
+
+    /// Check if credentials are configured
+    pub fn has_credentials(&self) -> bool {
+        self.auth.is_some()
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> Result<RequestBuilder, ProviderError> {
+        match &self.auth {
+            Some(BitbucketAuth::AppPassword { username, password }) => {
+                Ok(request.basic_auth(username, Some(password.expose_secret())))
+            }
+            Some(BitbucketAuth::AccessToken(token)) => {
+                Ok(request.bearer_auth(token.expose_secret()))
+            }
+            None => Err(ProviderError::NotAuthenticated(
+                "Set BITBUCKET_USERNAME and BITBUCKET_APP_PASSWORD, or BITBUCKET_TOKEN".to_string(),
+            )),
+        }
+    }
+
+    fn repo_url(&self, repo: &RepoIdentifier) -> String {
+        format!(
+            "{}/repositories/{}/{}",
+            self.base_url, repo.owner, repo.name
+        )
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ProviderError> {
+        let response = self
+            .authorize(request)?
+            .send_guarded(ProviderType::Bitbucket)
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(parse_error(status, &error_text));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ParseError(format!("Failed to parse response: {e}")))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, ProviderError> {
+        (|| async { self.send(self.http_client.get(url).query(query)).await })
+            .retry(retry_policy().backoff())
+            .when(|e: &ProviderError| retry_policy().should_retry(e))
+            .notify(|err, dur: Duration| {
+                tracing::warn!(
+                    "Bitbucket API retry after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            })
+            .await
+    }
+
+    /// All values of a paginated listing
+    async fn get_all<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>, ProviderError> {
+        let mut page: BitbucketPage<T> = self.get(url, query).await?;
+        let mut values = std::mem::take(&mut page.values);
+        let mut pages = 1;
+        while let Some(next) = page.next.take()
+            && pages < max_pages()
+        {
+            // The next link carries the query of the first request
+            page = self.get(&next, &[]).await?;
+            values.append(&mut page.values);
+            pages += 1;
+        }
+        Ok(values)
+    }
+}
+
+impl Default for BitbucketProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse error response
+fn parse_error(status: StatusCode, body: &str) -> ProviderError {
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return ProviderError::NotAuthenticated(format!(
+            "Bitbucket authentication failed: {}",
+            body
+        ));
+    }
+
+    let message = serde_json::from_str::<BitbucketError>(body)
+        .map(|error| error.error.message)
+        .unwrap_or_else(|_| body.to_string());
+    ProviderError::ApiError {
+        status: status.as_u16(),
+        message,
+    }
+}
+
+fn retry_policy() -> RetryPolicy {
+    super::retry_policy(ProviderType::Bitbucket)
+}
+
+#[async_trait]
+impl GitProvider for BitbucketProvider {
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::Bitbucket
+    }
+
+    async fn check_auth(&self) -> Result<(), ProviderError> {
+        match &self.auth {
+            // Access tokens are scoped to a repository, project or workspace and cannot read
+            // the current user, so they are only checked on first use
+            Some(BitbucketAuth::AccessToken(_)) => Ok(()),
+            _ => {
+                let _: serde_json::Value =
+                    self.get(&format!("{}/user", self.base_url), &[]).await?;
+                Ok(())
+            }
+        }
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &RepoIdentifier,
+        req: &CreateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        let mut source = serde_json::json!({ "branch": { "name": req.head_branch } });
+        if let Some(head_repo) = &req.head_repo {
+            let full_name = format!("{}/{}", head_repo.owner, head_repo.name);
+            source["repository"] = serde_json::json!({ "full_name": full_name });
+        }
+        let body = serde_json::json!({
+            "title": req.title,
+            "description": req.body.clone().unwrap_or_default(),
+            "source": source,
+            "destination": { "branch": { "name": req.base_branch } },
+            "draft": req.draft.unwrap_or(false),
+            "close_source_branch": false,
+        });
+        // Not retried, so a timed-out request cannot open the pull request twice
+        let pr: BitbucketPullRequest = self
+            .send(
+                self.http_client
+                    .post(format!("{}/pullrequests", self.repo_url(repo)))
+                    .json(&body),
+            )
+            .await?;
+        Ok(pr.into())
+    }
+
+    async fn get_mr_status(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<PrInfo, ProviderError> {
+        let pr: BitbucketPullRequest = self
+            .get(
+                &format!("{}/pullrequests/{}", self.repo_url(repo), number),
+                &[],
+            )
+            .await?;
+        Ok(pr.into())
+    }
+
+    async fn list_mrs_for_branch(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<Vec<PrInfo>, ProviderError> {
+        let filter = format!("source.branch.name = \"{}\"", branch.replace('"', "\\\""));
+        let prs: Vec<BitbucketPullRequest> = self
+            .get_all(
+                &format!("{}/pullrequests", self.repo_url(repo)),
+                &[
+                    ("q", filter.as_str()),
+                    ("state", "OPEN"),
+                    ("state", "MERGED"),
+                    ("state", "DECLINED"),
+                    ("state", "SUPERSEDED"),
+                    ("sort", "-updated_on"),
+                ],
+            )
+            .await?;
+        Ok(prs.into_iter().map(PrInfo::from).collect())
+    }
+
+    async fn get_comments(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<UnifiedComment>, ProviderError> {
+        let comments: Vec<BitbucketComment> = self
+            .get_all(
+                &format!("{}/pullrequests/{}/comments", self.repo_url(repo), number),
+                &[("sort", "created_on")],
+            )
+            .await?;
+
+        let mut unified: Vec<UnifiedComment> = comments
+            .into_iter()
+            .filter(|comment| !comment.deleted)
+            .map(UnifiedComment::from)
+            .collect();
+        unified.sort_by_key(|c| c.created_at());
+        Ok(unified)
+    }
+
+    /// Published as a build status keyed by `context`. Bitbucket requires a link, so statuses
+    /// without one link to the commit.
+    async fn set_commit_status(
+        &self,
+        repo: &RepoIdentifier,
+        sha: &str,
+        state: CommitStatusState,
+        context: &str,
+        url: Option<&str>,
+    ) -> Result<(), ProviderError> {
+        let state = match state {
+            CommitStatusState::Pending => "INPROGRESS",
+            CommitStatusState::Success => "SUCCESSFUL",
+            CommitStatusState::Failure | CommitStatusState::Error => "FAILED",
+        };
+        let url = url.map(str::to_string).unwrap_or_else(|| {
+            format!(
+                "https://bitbucket.org/{}/{}/commits/{}",
+                repo.owner, repo.name, sha
+            )
+        });
+        let body = serde_json::json!({
+            "key": context,
+            "name": context,
+            "state": state,
+            "url": url,
+        });
+        let status_url = format!("{}/commit/{}/statuses/build", self.repo_url(repo), sha);
+        // Retried: a status with the same key replaces the earlier one
+        let _: serde_json::Value = (|| async {
+            self.send(self.http_client.post(&status_url).json(&body))
+                .await
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_pull_request_states() {
+        let pr: BitbucketPullRequest = serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "state": "MERGED",
+            "links": { "html": { "href": "https://bitbucket.org/team/app/pull-requests/7" } },
+            "merge_commit": { "hash": "abc123" },
+            "updated_on": "2025-01-02T03:04:05+00:00"
+        }))
+        .unwrap();
+        let info = PrInfo::from(pr);
+        assert_eq!(info.number, 7);
+        assert_eq!(info.state, PrState::Merged);
+        assert_eq!(info.url, "https://bitbucket.org/team/app/pull-requests/7");
+        assert_eq!(info.merge_commit_sha.as_deref(), Some("abc123"));
+        assert!(info.merged_at.is_some());
+
+        let declined: BitbucketPullRequest = serde_json::from_value(serde_json::json!({
+            "id": 8,
+            "state": "DECLINED",
+            "links": {},
+            "merge_commit": null
+        }))
+        .unwrap();
+        let info = PrInfo::from(declined);
+        assert_eq!(info.state, PrState::Closed);
+        assert!(info.merged_at.is_none());
+    }
+
+    #[test]
+    fn inline_comments_become_review_comments() {
+        let comment: BitbucketComment = serde_json::from_value(serde_json::json!({
+            "id": 42,
+            "content": { "raw": "Rename this" },
+            "user": { "display_name": "Sam Lee", "nickname": "slee" },
+            "created_on": "2025-01-02T03:04:05+00:00",
+            "links": { "html": { "href": "https://bitbucket.org/c/42" } },
+            "inline": { "path": "src/lib.rs", "from": null, "to": 12 }
+        }))
+        .unwrap();
+        match UnifiedComment::from(comment) {
+            UnifiedComment::Review {
+                author, path, line, ..
+            } => {
+                assert_eq!(author, "slee");
+                assert_eq!(path, "src/lib.rs");
+                assert_eq!(line, Some(12));
+            }
+            other => panic!("expected a review comment, got {other:?}"),
+        }
+    }
+}
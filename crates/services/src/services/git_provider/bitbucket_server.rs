@@ -0,0 +1,589 @@
+//! Bitbucket Server/Data Center provider implementation
+//!
+//! Self-hosted Bitbucket has its own 1.0 REST API, unrelated to the 2.0 API of Bitbucket Cloud:
+//! repositories are addressed by project key and slug, pull requests reference branches by their
+//! full ref, and comments come from the pull request activity.
+//!
+//! The API lives on the host of the git remote, or at `BITBUCKET_SERVER_URL` when it differs,
+//! e.g. for SSH remotes of instances served under a context path. Credentials are an HTTP access
+//! token in `BITBUCKET_SERVER_TOKEN`, or `BITBUCKET_SERVER_USERNAME` with
+//! `BITBUCKET_SERVER_PASSWORD`.
+
+use std::{path::PathBuf, time::Duration};
+
+use async_trait::async_trait;
+use backon::Retryable;
+use chrono::{DateTime, Utc};
+use reqwest::{RequestBuilder, StatusCode};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{
+    CreateMrRequest, GitProvider, GuardedSend, PrInfo, PrState, ProviderError, ProviderType,
+    RepoIdentifier, RetryPolicy, UnifiedComment, detect_provider, max_pages,
+};
+
+/// Items requested per page when listing
+const PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Clone)]
+enum BitbucketServerAuth {
+    Basic {
+        username: String,
+        password: SecretString,
+    },
+    AccessToken(SecretString),
+}
+
+/// Bitbucket Data Center pull request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitbucketServerPullRequest {
+    pub id: u64,
+    /// OPEN, MERGED or DECLINED
+    pub state: String,
+    pub links: BitbucketServerLinks,
+    /// Milliseconds since the epoch
+    pub updated_date: Option<i64>,
+    #[serde(default)]
+    pub properties: Option<BitbucketServerPrProperties>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BitbucketServerLinks {
+    #[serde(rename = "self", default)]
+    pub self_links: Vec<BitbucketServerLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketServerLink {
+    pub href: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitbucketServerPrProperties {
+    pub merge_commit: Option<BitbucketServerCommit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketServerCommit {
+    pub id: String,
+}
+
+/// Entry of a pull request's activity; only `COMMENTED` entries carry a comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitbucketServerActivity {
+    pub action: String,
+    pub comment: Option<BitbucketServerComment>,
+    pub comment_anchor: Option<BitbucketServerAnchor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitbucketServerComment {
+    pub id: i64,
+    pub text: String,
+    pub author: Option<BitbucketServerUser>,
+    /// Milliseconds since the epoch
+    pub created_date: i64,
+    /// Replies, nested to any depth
+    #[serde(default)]
+    pub comments: Vec<BitbucketServerComment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitbucketServerUser {
+    pub name: Option<String>,
+    pub display_name: Option<String>,
+}
+
+/// Position of an inline comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitbucketServerAnchor {
+    pub path: Option<String>,
+    pub line: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitbucketServerPage<T> {
+    values: Vec<T>,
+    #[serde(default = "default_last_page")]
+    is_last_page: bool,
+    next_page_start: Option<u64>,
+}
+
+fn default_last_page() -> bool {
+    true
+}
+
+/// Bitbucket Data Center error response
+#[derive(Debug, Clone, Deserialize)]
+struct BitbucketServerErrors {
+    errors: Vec<BitbucketServerErrorDetail>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BitbucketServerErrorDetail {
+    message: String,
+}
+
+fn from_millis(millis: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(millis)
+}
+
+impl From<BitbucketServerPullRequest> for PrInfo {
+    fn from(pr: BitbucketServerPullRequest) -> Self {
+        let state = match pr.state.as_str() {
+            "OPEN" => PrState::Open,
+            "MERGED" => PrState::Merged,
+            "DECLINED" => PrState::Closed,
+            _ => PrState::Unknown,
+        };
+        PrInfo {
+            number: pr.id,
+            url: pr
+                .links
+                .self_links
+                .into_iter()
+                .next()
+                .map(|link| link.href)
+                .unwrap_or_default(),
+            state,
+            // There is no merge timestamp; a merged pull request is not updated afterwards
+            merged_at: if state == PrState::Merged {
+                pr.updated_date.and_then(from_millis)
+            } else {
+                None
+            },
+            merge_commit_sha: pr
+                .properties
+                .and_then(|properties| properties.merge_commit)
+                .map(|commit| commit.id),
+            review_state: None,
+        }
+    }
+}
+
+/// Comments of an activity entry with their replies, in the unified shape
+fn unified_comments(activity: BitbucketServerActivity, pr_url: &str) -> Vec<UnifiedComment> {
+    let Some(comment) = activity.comment else {
+        return Vec::new();
+    };
+    let anchor = activity
+        .comment_anchor
+        .and_then(|anchor| anchor.path.map(|path| (path, anchor.line)));
+
+    let mut unified = Vec::new();
+    let mut pending = vec![(comment, anchor)];
+    while let Some((comment, anchor)) = pending.pop() {
+        let author = comment
+            .author
+            .as_ref()
+            .and_then(|user| user.name.clone().or_else(|| user.display_name.clone()))
+            .unwrap_or_else(|| "unknown".to_string());
+        let created_at = from_millis(comment.created_date).unwrap_or_default();
+        let url = format!("{pr_url}/overview?commentId={}", comment.id);
+        // Replies sit on the same line as the comment they answer
+        pending.extend(
+            comment
+                .comments
+                .into_iter()
+                .map(|reply| (reply, anchor.clone())),
+        );
+        unified.push(match &anchor {
+            Some((path, line)) => UnifiedComment::Review {
+                id: comment.id,
+                author,
+                author_association: "MEMBER".to_string(),
+                body: comment.text,
+                created_at,
+                url,
+                path: path.clone(),
+                start_line: None,
+                line: *line,
+                // The activity carries the diff as structured hunks, not as text
+                diff_hunk: String::new(),
+            },
+            None => UnifiedComment::General {
+                id: comment.id.to_string(),
+                author,
+                author_association: "MEMBER".to_string(),
+                body: comment.text,
+                created_at,
+                url,
+            },
+        });
+    }
+    unified
+}
+
+/// Bitbucket Server/Data Center provider implementation using the 1.0 REST API
+#[derive(Debug, Clone)]
+pub struct BitbucketServerProvider {
+    repo_path: Option<PathBuf>,
+    auth: Option<BitbucketServerAuth>,
+    http_client: reqwest::Client,
+}
+
+impl BitbucketServerProvider {
+    /// Create new Bitbucket Data Center provider from environment credentials
+    pub fn new(repo_path: Option<PathBuf>) -> Self {
+        let basic = match (
+            std::env::var("BITBUCKET_SERVER_USERNAME"),
+            std::env::var("BITBUCKET_SERVER_PASSWORD"),
+        ) {
+            (Ok(username), Ok(password)) => Some(BitbucketServerAuth::Basic {
+                username,
+                password: SecretString::from(password),
+            }),
+            _ => None,
+        };
+        let auth = std::env::var("BITBUCKET_SERVER_TOKEN")
+            .ok()
+            .map(|token| BitbucketServerAuth::AccessToken(SecretString::from(token)))
+            .or(basic);
+
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            repo_path,
+            auth,
+            http_client,
+        }
+    }
+
+    /// Check if credentials are configured
+    pub fn has_credentials(&self) -> bool {
+        self.auth.is_some()
+    }
+
+    /// Web UI and REST API root of the repository's server
+    fn base_url(repo: &RepoIdentifier) -> Result<String, ProviderError> {
+        if let Ok(url) = std::env::var("BITBUCKET_SERVER_URL") {
+            return Ok(url.trim_end_matches('/').to_string());
+        }
+        let host = repo.host.as_deref().ok_or_else(|| {
+            ProviderError::Git(format!(
+                "Bitbucket Data Center repository {} has no host; set BITBUCKET_SERVER_URL",
+                repo.full_path()
+            ))
+        })?;
+        Ok(format!("https://{}", host.trim_end_matches('/')))
+    }
+
+    fn repo_url(repo: &RepoIdentifier) -> Result<String, ProviderError> {
+        Ok(format!(
+            "{}/rest/api/1.0/projects/{}/repos/{}",
+            Self::base_url(repo)?,
+            repo.owner,
+            repo.name
+        ))
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> Result<RequestBuilder, ProviderError> {
+        match &self.auth {
+            Some(BitbucketServerAuth::Basic { username, password }) => {
+                Ok(request.basic_auth(username, Some(password.expose_secret())))
+            }
+            Some(BitbucketServerAuth::AccessToken(token)) => {
+                Ok(request.bearer_auth(token.expose_secret()))
+            }
+            None => Err(ProviderError::NotAuthenticated(
+                "Set BITBUCKET_SERVER_TOKEN, or BITBUCKET_SERVER_USERNAME and \
+                 BITBUCKET_SERVER_PASSWORD"
+                    .to_string(),
+            )),
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ProviderError> {
+        let response = self
+            .authorize(request)?
+            .send_guarded(ProviderType::BitbucketServer)
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(parse_error(status, &error_text));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ParseError(format!("Failed to parse response: {e}")))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, ProviderError> {
+        (|| async { self.send(self.http_client.get(url).query(query)).await })
+            .retry(retry_policy().backoff())
+            .when(|e: &ProviderError| retry_policy().should_retry(e))
+            .notify(|err, dur: Duration| {
+                tracing::warn!(
+                    "Bitbucket Data Center API retry after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            })
+            .await
+    }
+
+    /// All values of a paged listing
+    async fn get_all<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, String)],
+    ) -> Result<Vec<T>, ProviderError> {
+        let mut values = Vec::new();
+        let mut start = 0;
+        for _ in 0..max_pages() {
+            let mut paged_query = query.to_vec();
+            paged_query.push(("limit", PAGE_SIZE.to_string()));
+            paged_query.push(("start", start.to_string()));
+            let mut page: BitbucketServerPage<T> = self.get(url, &paged_query).await?;
+            values.append(&mut page.values);
+            match page.next_page_start {
+                Some(next) if !page.is_last_page => start = next,
+                _ => break,
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// Parse error response
+fn parse_error(status: StatusCode, body: &str) -> ProviderError {
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return ProviderError::NotAuthenticated(format!(
+            "Bitbucket Data Center authentication failed: {}",
+            body
+        ));
+    }
+
+    let message = serde_json::from_str::<BitbucketServerErrors>(body)
+        .ok()
+        .and_then(|errors| errors.errors.into_iter().next())
+        .map(|error| error.message)
+        .unwrap_or_else(|| body.to_string());
+    ProviderError::ApiError {
+        status: status.as_u16(),
+        message,
+    }
+}
+
+fn retry_policy() -> RetryPolicy {
+    super::retry_policy(ProviderType::BitbucketServer)
+}
+
+#[async_trait]
+impl GitProvider for BitbucketServerProvider {
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::BitbucketServer
+    }
+
+    async fn check_auth(&self) -> Result<(), ProviderError> {
+        if self.auth.is_none() {
+            return Err(ProviderError::NotAuthenticated(
+                "Set BITBUCKET_SERVER_TOKEN, or BITBUCKET_SERVER_USERNAME and \
+                 BITBUCKET_SERVER_PASSWORD"
+                    .to_string(),
+            ));
+        }
+        // The server is known from the environment or from the repository's remote
+        let base_url = match std::env::var("BITBUCKET_SERVER_URL") {
+            Ok(url) => url.trim_end_matches('/').to_string(),
+            Err(_) => {
+                let repo_path =
+                    self.repo_path
+                        .clone()
+                        .ok_or_else(|| ProviderError::NotSupported {
+                            feature: "checking Bitbucket Data Center credentials without a \
+                                      repository"
+                                .to_string(),
+                        })?;
+                let (_, repo) = detect_provider(&repo_path)?;
+                Self::base_url(&repo)?
+            }
+        };
+        // Anonymous access may be enabled, so read something that needs an account
+        let _: serde_json::Value = self
+            .get(
+                &format!("{base_url}/rest/api/1.0/dashboard/pull-requests"),
+                &[("limit", "1".to_string())],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &RepoIdentifier,
+        req: &CreateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        let mut body = serde_json::json!({
+            "title": req.title,
+            "description": req.body.clone().unwrap_or_default(),
+            "fromRef": { "id": format!("refs/heads/{}", req.head_branch) },
+            "toRef": { "id": format!("refs/heads/{}", req.base_branch) },
+        });
+        // Drafts need Data Center 8.18; older versions reject the field
+        if req.draft.unwrap_or(false) {
+            body["draft"] = serde_json::Value::Bool(true);
+        }
+        // Not retried, so a timed-out request cannot open the pull request twice
+        let pr: BitbucketServerPullRequest = self
+            .send(
+                self.http_client
+                    .post(format!("{}/pull-requests", Self::repo_url(repo)?))
+                    .json(&body),
+            )
+            .await?;
+        Ok(pr.into())
+    }
+
+    async fn get_mr_status(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<PrInfo, ProviderError> {
+        let pr: BitbucketServerPullRequest = self
+            .get(
+                &format!("{}/pull-requests/{}", Self::repo_url(repo)?, number),
+                &[],
+            )
+            .await?;
+        Ok(pr.into())
+    }
+
+    async fn list_mrs_for_branch(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<Vec<PrInfo>, ProviderError> {
+        let prs: Vec<BitbucketServerPullRequest> = self
+            .get_all(
+                &format!("{}/pull-requests", Self::repo_url(repo)?),
+                &[
+                    ("at", format!("refs/heads/{branch}")),
+                    ("direction", "OUTGOING".to_string()),
+                    ("state", "ALL".to_string()),
+                    ("order", "NEWEST".to_string()),
+                ],
+            )
+            .await?;
+        Ok(prs.into_iter().map(PrInfo::from).collect())
+    }
+
+    async fn get_comments(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<UnifiedComment>, ProviderError> {
+        let activities: Vec<BitbucketServerActivity> = self
+            .get_all(
+                &format!(
+                    "{}/pull-requests/{}/activities",
+                    Self::repo_url(repo)?,
+                    number
+                ),
+                &[],
+            )
+            .await?;
+
+        let web_url = format!(
+            "{}/projects/{}/repos/{}/pull-requests/{}",
+            Self::base_url(repo)?,
+            repo.owner,
+            repo.name,
+            number
+        );
+        let mut unified: Vec<UnifiedComment> = activities
+            .into_iter()
+            .filter(|activity| activity.action == "COMMENTED")
+            .flat_map(|activity| unified_comments(activity, &web_url))
+            .collect();
+        unified.sort_by_key(|c| c.created_at());
+        Ok(unified)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_pull_request_states() {
+        let pr: BitbucketServerPullRequest = serde_json::from_value(serde_json::json!({
+            "id": 12,
+            "state": "MERGED",
+            "updatedDate": 1735787045000i64,
+            "links": { "self": [{ "href": "https://bb.example.com/projects/APP/repos/api/pull-requests/12" }] },
+            "properties": { "mergeCommit": { "id": "abc123" } }
+        }))
+        .unwrap();
+        let info = PrInfo::from(pr);
+        assert_eq!(info.number, 12);
+        assert_eq!(info.state, PrState::Merged);
+        assert_eq!(
+            info.url,
+            "https://bb.example.com/projects/APP/repos/api/pull-requests/12"
+        );
+        assert_eq!(info.merge_commit_sha.as_deref(), Some("abc123"));
+        assert!(info.merged_at.is_some());
+
+        let declined: BitbucketServerPullRequest = serde_json::from_value(serde_json::json!({
+            "id": 13,
+            "state": "DECLINED",
+            "links": {}
+        }))
+        .unwrap();
+        assert_eq!(PrInfo::from(declined).state, PrState::Closed);
+    }
+
+    #[test]
+    fn inline_comments_and_replies_become_review_comments() {
+        let activity: BitbucketServerActivity = serde_json::from_value(serde_json::json!({
+            "action": "COMMENTED",
+            "comment": {
+                "id": 5,
+                "text": "Rename this",
+                "author": { "name": "slee", "displayName": "Sam Lee" },
+                "createdDate": 1735787045000i64,
+                "comments": [{
+                    "id": 6,
+                    "text": "Done",
+                    "author": { "name": "kim" },
+                    "createdDate": 1735787999000i64
+                }]
+            },
+            "commentAnchor": { "path": "src/lib.rs", "line": 12 }
+        }))
+        .unwrap();
+        let comments = unified_comments(activity, "https://bb.example.com/pr/1");
+        assert_eq!(comments.len(), 2);
+        for comment in &comments {
+            match comment {
+                UnifiedComment::Review {
+                    path, line, url, ..
+                } => {
+                    assert_eq!(path, "src/lib.rs");
+                    assert_eq!(*line, Some(12));
+                    assert!(url.starts_with("https://bb.example.com/pr/1/overview?commentId="));
+                }
+                other => panic!("expected a review comment, got {other:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Provider API requests through the circuit breaker of their host
+//!
+//! Requests that get no response or a server error count as failures; any other response shows
+//! the API is up, even when it rejects the request. While a host's breaker is open its requests
+//! fail with [`ProviderError::CircuitOpen`], which is not retried. The rate-limit quota of every
+//! response is recorded for the health check.
+
+use std::future::Future;
+
+use reqwest::{RequestBuilder, Response};
+
+use super::{ProviderError, ProviderType, rate_limit};
+use crate::services::circuit_breaker;
+
+pub trait GuardedSend {
+    /// Send the request unless the breaker of the provider's API at its host is open
+    fn send_guarded(
+        self,
+        provider: ProviderType,
+    ) -> impl Future<Output = Result<Response, ProviderError>> + Send;
+}
+
+impl GuardedSend for RequestBuilder {
+    async fn send_guarded(self, provider: ProviderType) -> Result<Response, ProviderError> {
+        let (client, request) = self.build_split();
+        let request = request.map_err(ProviderError::request_failed)?;
+        let host = request.url().host_str().unwrap_or_default();
+        let name = format!("{provider} API at {host}");
+        let breaker = circuit_breaker::breaker(&name);
+        breaker
+            .check()
+            .map_err(|e| ProviderError::CircuitOpen(e.to_string()))?;
+
+        let response = client.execute(request).await;
+        if let Ok(response) = &response {
+            rate_limit::record(&name, response.headers());
+        }
+        match response {
+            Ok(response) if response.status().is_server_error() => {
+                breaker.record_failure(format!("HTTP {}", response.status()));
+                Ok(response)
+            }
+            Ok(response) => {
+                breaker.record_success();
+                Ok(response)
+            }
+            Err(e) => {
+                breaker.record_failure(&e);
+                Err(ProviderError::request_failed(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,450 @@
+//! AWS CodeCommit provider implementation
+//!
+//! Uses the AWS SDK for all operations. Credentials and the default region come from the
+//! standard AWS credential chain (environment variables, shared config and credentials files,
+//! SSO, and instance or container roles), and requests are signed with SigV4. Each repository is
+//! addressed in the region of its remote URL.
+//!
+//! CodeCommit identifies pull requests and comments by strings; pull request ids are numeric in
+//! practice, while comment ids are hashed into numbers for review comments.
+
+use async_trait::async_trait;
+use aws_config::{BehaviorVersion, SdkConfig};
+use aws_sdk_codecommit::{
+    Client,
+    config::{Region, http::HttpResponse},
+    error::{DisplayErrorContext, ProvideErrorMetadata, SdkError},
+    primitives::DateTime as AwsDateTime,
+    types::{Comment, PullRequest, PullRequestStatusEnum, Target},
+};
+use chrono::{DateTime, Utc};
+use tokio::sync::OnceCell;
+
+use super::{
+    CreateMrRequest, GitProvider, PrInfo, PrState, ProviderError, ProviderType, RepoIdentifier,
+    UnifiedComment, max_pages, types::numeric_comment_id,
+};
+
+/// Error codes CodeCommit returns for missing, expired or insufficient credentials
+const AUTH_ERROR_CODES: &[&str] = &[
+    "AccessDeniedException",
+    "UnrecognizedClientException",
+    "InvalidSignatureException",
+    "ExpiredTokenException",
+    "InvalidClientTokenId",
+];
+
+fn to_chrono(date: &AwsDateTime) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(date.secs(), date.subsec_nanos())
+}
+
+/// Name of the IAM user or role session from an author ARN
+fn author_name(arn: Option<&str>) -> String {
+    arn.and_then(|arn| arn.rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+fn pr_url(region: &str, repository: &str, id: &str) -> String {
+    format!(
+        "https://{region}.console.aws.amazon.com/codesuite/codecommit/repositories/{repository}/pull-requests/{id}/details?region={region}"
+    )
+}
+
+fn branch_ref(branch: &str) -> String {
+    format!("refs/heads/{branch}")
+}
+
+fn parse_pr_number(id: &str) -> Result<u64, ProviderError> {
+    id.parse()
+        .map_err(|_| ProviderError::ParseError(format!("Non-numeric pull request id: {id}")))
+}
+
+fn sdk_error<E>(err: SdkError<E, HttpResponse>) -> ProviderError
+where
+    E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+{
+    let message = DisplayErrorContext(&err).to_string();
+    if let Some(code) = err.code()
+        && AUTH_ERROR_CODES.contains(&code)
+    {
+        return ProviderError::NotAuthenticated(message);
+    }
+    match &err {
+        SdkError::ServiceError(service) => ProviderError::ApiError {
+            status: service.raw().status().as_u16(),
+            message,
+        },
+        // Includes credentials that could not be loaded from the chain
+        SdkError::ConstructionFailure(_) | SdkError::DispatchFailure(_)
+            if message.to_lowercase().contains("credentials") =>
+        {
+            ProviderError::NotAuthenticated(message)
+        }
+        // The request never got a response
+        SdkError::DispatchFailure(_) | SdkError::TimeoutError(_) => {
+            ProviderError::Unreachable(message)
+        }
+        _ => ProviderError::CommandFailed(message),
+    }
+}
+
+fn into_pr_info(region: &str, pr: &PullRequest) -> Result<PrInfo, ProviderError> {
+    let id = pr
+        .pull_request_id()
+        .ok_or_else(|| ProviderError::ParseError("Pull request without an id".to_string()))?;
+    let merge = pr
+        .pull_request_targets()
+        .first()
+        .and_then(|target| target.merge_metadata());
+    let merged = merge.is_some_and(|merge| merge.is_merged());
+    let state = match pr.pull_request_status() {
+        Some(PullRequestStatusEnum::Open) => PrState::Open,
+        Some(PullRequestStatusEnum::Closed) if merged => PrState::Merged,
+        Some(PullRequestStatusEnum::Closed) => PrState::Closed,
+        _ => PrState::Unknown,
+    };
+    let repository = pr
+        .pull_request_targets()
+        .first()
+        .and_then(|target| target.repository_name())
+        .unwrap_or_default();
+
+    Ok(PrInfo {
+        number: parse_pr_number(id)?,
+        url: pr_url(region, repository, id),
+        state,
+        // CodeCommit does not record when a pull request was merged; merging is its last activity
+        merged_at: if merged {
+            pr.last_activity_date().and_then(to_chrono)
+        } else {
+            None
+        },
+        merge_commit_sha: merge.and_then(|merge| merge.merge_commit_id().map(str::to_string)),
+        review_state: None,
+    })
+}
+
+/// Comments on a file location become review comments, the rest general comments
+fn unified_comment(
+    url: &str,
+    file: Option<(&str, Option<i64>)>,
+    comment: &Comment,
+) -> Option<UnifiedComment> {
+    if comment.deleted() {
+        return None;
+    }
+    let id = comment.comment_id()?;
+    let author = author_name(comment.author_arn());
+    let body = comment.content().unwrap_or_default().to_string();
+    let created_at = comment.creation_date().and_then(to_chrono)?;
+    Some(match file {
+        Some((path, line)) => UnifiedComment::Review {
+            id: numeric_comment_id(id),
+            author,
+            author_association: "MEMBER".to_string(),
+            body,
+            created_at,
+            url: url.to_string(),
+            path: path.to_string(),
+            start_line: None,
+            line,
+            // CodeCommit comments carry no surrounding diff
+            diff_hunk: String::new(),
+        },
+        None => UnifiedComment::General {
+            id: id.to_string(),
+            author,
+            author_association: "MEMBER".to_string(),
+            body,
+            created_at,
+            url: url.to_string(),
+        },
+    })
+}
+
+/// AWS CodeCommit provider implementation using the AWS SDK
+#[derive(Debug, Default)]
+pub struct CodeCommitProvider {
+    /// Loaded on first use, since resolving the credential chain is async
+    config: OnceCell<SdkConfig>,
+}
+
+impl CodeCommitProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn sdk_config(&self) -> &SdkConfig {
+        self.config
+            .get_or_init(|| aws_config::load_defaults(BehaviorVersion::latest()))
+            .await
+    }
+
+    /// Client for a region, or for the region of the AWS configuration
+    async fn client(&self, region: Option<&str>) -> Client {
+        let mut config = aws_sdk_codecommit::config::Builder::from(self.sdk_config().await);
+        if let Some(region) = region {
+            config = config.region(Region::new(region.to_string()));
+        }
+        Client::from_conf(config.build())
+    }
+
+    async fn get_pull_request(
+        &self,
+        client: &Client,
+        id: &str,
+    ) -> Result<PullRequest, ProviderError> {
+        client
+            .get_pull_request()
+            .pull_request_id(id)
+            .send()
+            .await
+            .map_err(sdk_error)?
+            .pull_request
+            .ok_or_else(|| ProviderError::ParseError(format!("Pull request {id} missing")))
+    }
+}
+
+#[async_trait]
+impl GitProvider for CodeCommitProvider {
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::CodeCommit
+    }
+
+    async fn check_auth(&self) -> Result<(), ProviderError> {
+        if self.sdk_config().await.region().is_none() {
+            return Err(ProviderError::NotAuthenticated(
+                "No AWS region configured; set AWS_REGION or a region in ~/.aws/config".to_string(),
+            ));
+        }
+        self.client(None)
+            .await
+            .list_repositories()
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        Ok(())
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &RepoIdentifier,
+        req: &CreateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        let target = Target::builder()
+            .repository_name(&repo.name)
+            .source_reference(branch_ref(&req.head_branch))
+            .destination_reference(branch_ref(&req.base_branch))
+            .build()
+            .map_err(|e| ProviderError::CommandFailed(e.to_string()))?;
+        // CodeCommit has no draft pull requests
+        let pr = self
+            .client(Some(&repo.owner))
+            .await
+            .create_pull_request()
+            .title(&req.title)
+            .set_description(req.body.clone())
+            .targets(target)
+            .send()
+            .await
+            .map_err(sdk_error)?
+            .pull_request
+            .ok_or_else(|| ProviderError::ParseError("Created pull request missing".to_string()))?;
+        into_pr_info(&repo.owner, &pr)
+    }
+
+    async fn get_mr_status(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<PrInfo, ProviderError> {
+        let client = self.client(Some(&repo.owner)).await;
+        let pr = self.get_pull_request(&client, &number.to_string()).await?;
+        into_pr_info(&repo.owner, &pr)
+    }
+
+    async fn list_mrs_for_branch(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<Vec<PrInfo>, ProviderError> {
+        let client = self.client(Some(&repo.owner)).await;
+        // Listing returns ids only, so each pull request is fetched to check its source branch
+        let mut ids = Vec::new();
+        let mut next_token = None;
+        for _ in 0..max_pages() {
+            let page = client
+                .list_pull_requests()
+                .repository_name(&repo.name)
+                .set_next_token(next_token)
+                .send()
+                .await
+                .map_err(sdk_error)?;
+            ids.extend(page.pull_request_ids);
+            next_token = page.next_token;
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        let source = branch_ref(branch);
+        let mut prs = Vec::new();
+        for id in ids {
+            let pr = self.get_pull_request(&client, &id).await?;
+            let from_branch = pr.pull_request_targets().iter().any(|target| {
+                target
+                    .source_reference()
+                    .is_some_and(|reference| reference == source || reference == branch)
+            });
+            if from_branch {
+                prs.push(into_pr_info(&repo.owner, &pr)?);
+            }
+        }
+        Ok(prs)
+    }
+
+    async fn get_comments(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<UnifiedComment>, ProviderError> {
+        let client = self.client(Some(&repo.owner)).await;
+        let id = number.to_string();
+        let url = pr_url(&repo.owner, &repo.name, &id);
+
+        let mut comments = Vec::new();
+        let mut next_token = None;
+        for _ in 0..max_pages() {
+            let page = client
+                .get_comments_for_pull_request()
+                .pull_request_id(&id)
+                .repository_name(&repo.name)
+                .set_next_token(next_token)
+                .send()
+                .await
+                .map_err(sdk_error)?;
+            for thread in page.comments_for_pull_request_data() {
+                let file = thread.location().and_then(|location| {
+                    let path = location.file_path()?;
+                    Some((path, location.file_position()))
+                });
+                comments.extend(
+                    thread
+                        .comments()
+                        .iter()
+                        .filter_map(|comment| unified_comment(&url, file, comment)),
+                );
+            }
+            next_token = page.next_token;
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        comments.sort_by_key(|c| c.created_at());
+        Ok(comments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_codecommit::types::{MergeMetadata, PullRequestTarget};
+
+    use super::*;
+
+    fn pull_request(status: PullRequestStatusEnum, merged: bool) -> PullRequest {
+        PullRequest::builder()
+            .pull_request_id("42")
+            .pull_request_status(status)
+            .last_activity_date(AwsDateTime::from_secs(1_735_787_045))
+            .pull_request_targets(
+                PullRequestTarget::builder()
+                    .repository_name("billing")
+                    .source_reference("refs/heads/vk/fix-totals")
+                    .merge_metadata(
+                        MergeMetadata::builder()
+                            .is_merged(merged)
+                            .set_merge_commit_id(merged.then(|| "abc123".to_string()))
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn converts_pull_request_states() {
+        let info = into_pr_info(
+            "eu-west-1",
+            &pull_request(PullRequestStatusEnum::Closed, true),
+        )
+        .unwrap();
+        assert_eq!(info.number, 42);
+        assert_eq!(info.state, PrState::Merged);
+        assert_eq!(info.merge_commit_sha.as_deref(), Some("abc123"));
+        assert_eq!(
+            info.merged_at,
+            Some("2025-01-02T03:04:05Z".parse::<DateTime<Utc>>().unwrap())
+        );
+        assert!(info.url.contains("/repositories/billing/pull-requests/42/"));
+
+        let closed = into_pr_info(
+            "eu-west-1",
+            &pull_request(PullRequestStatusEnum::Closed, false),
+        )
+        .unwrap();
+        assert_eq!(closed.state, PrState::Closed);
+        assert!(closed.merged_at.is_none());
+
+        let open = into_pr_info(
+            "eu-west-1",
+            &pull_request(PullRequestStatusEnum::Open, false),
+        )
+        .unwrap();
+        assert_eq!(open.state, PrState::Open);
+    }
+
+    #[test]
+    fn comments_on_files_are_review_comments() {
+        let comment = |id: &str, deleted: bool| {
+            Comment::builder()
+                .comment_id(id)
+                .content("Use checked_add here")
+                .author_arn("arn:aws:sts::123456789012:assumed-role/Developer/sam")
+                .creation_date(AwsDateTime::from_secs(1_735_787_045))
+                .deleted(deleted)
+                .build()
+        };
+
+        let review = unified_comment(
+            "url",
+            Some(("src/totals.rs", Some(12))),
+            &comment("c1", false),
+        )
+        .unwrap();
+        match review {
+            UnifiedComment::Review {
+                id,
+                author,
+                path,
+                line,
+                ..
+            } => {
+                assert_eq!(id, numeric_comment_id("c1"));
+                assert_eq!(author, "sam");
+                assert_eq!(path, "src/totals.rs");
+                assert_eq!(line, Some(12));
+            }
+            other => panic!("expected a review comment, got {other:?}"),
+        }
+
+        assert!(matches!(
+            unified_comment("url", None, &comment("c2", false)),
+            Some(UnifiedComment::General { .. })
+        ));
+        assert!(unified_comment("url", None, &comment("c3", true)).is_none());
+        assert_eq!(author_name(None), "unknown");
+    }
+}
@@ -4,14 +4,47 @@ use git2::Repository;
 use regex::Regex;
 use std::path::Path;
 
-use super::{ProviderError, ProviderType, RepoIdentifier};
+use super::{ProviderError, ProviderType, RepoIdentifier, registry};
 
-/// Detect provider and repo info from repository path
+/// Detect provider and repo info from repository path. In a fork checkout this is the upstream
+/// repository, where pull requests are opened.
 pub fn detect_provider(repo_path: &Path) -> Result<(ProviderType, RepoIdentifier), ProviderError> {
+    if let Some(fork) = detect_fork(repo_path)? {
+        return Ok((fork.upstream.provider, fork.upstream));
+    }
     let url = get_remote_url(repo_path)?;
     detect_provider_from_url(&url)
 }
 
+/// Remotes of a fork checkout: `origin` is the user's fork and `upstream` the repository it was
+/// forked from
+#[derive(Debug, Clone)]
+pub struct ForkTopology {
+    pub fork: RepoIdentifier,
+    pub upstream: RepoIdentifier,
+}
+
+/// Detect a fork checkout, where `origin` and `upstream` are different repositories on the same
+/// provider and host. Branches are pushed to the fork and pull requests opened against upstream.
+pub fn detect_fork(repo_path: &Path) -> Result<Option<ForkTopology>, ProviderError> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| ProviderError::Git(format!("Failed to open repo: {e}")))?;
+    let remote_repo = |name: &str| {
+        let remote = repo.find_remote(name).ok()?;
+        detect_provider_from_url(remote.url()?).ok()
+    };
+
+    let (Some((fork_provider, fork)), Some((upstream_provider, upstream))) =
+        (remote_repo("origin"), remote_repo("upstream"))
+    else {
+        return Ok(None);
+    };
+    let is_fork = fork_provider == upstream_provider
+        && fork.host == upstream.host
+        && (fork.owner != upstream.owner || fork.name != upstream.name);
+    Ok(is_fork.then_some(ForkTopology { fork, upstream }))
+}
+
 /// Get remote URL from repository path
 pub fn get_remote_url(repo_path: &Path) -> Result<String, ProviderError> {
     let repo = Repository::open(repo_path)
@@ -41,23 +74,14 @@ pub fn get_remote_url(repo_path: &Path) -> Result<String, ProviderError> {
     Err(ProviderError::Git("No remote URL found".into()))
 }
 
-/// Detect provider type and extract repo info from URL
+/// Detect provider type and extract repo info from URL, using the registered providers and host
+/// mappings
 pub fn detect_provider_from_url(url: &str) -> Result<(ProviderType, RepoIdentifier), ProviderError> {
-    // Try GitHub first
-    if let Some(repo_id) = parse_github_url(url) {
-        return Ok((ProviderType::GitHub, repo_id));
-    }
-
-    // Try GitLab
-    if let Some(repo_id) = parse_gitlab_url(url) {
-        return Ok((ProviderType::GitLab, repo_id));
-    }
-
-    Err(ProviderError::UnknownProvider(url.to_string()))
+    registry::global().detect(url)
 }
 
 /// Parse GitHub URLs (SSH and HTTPS)
-fn parse_github_url(url: &str) -> Option<RepoIdentifier> {
+pub(super) fn parse_github_url(url: &str) -> Option<RepoIdentifier> {
     // Patterns:
     // - git@github.com:owner/repo.git
     // - https://github.com/owner/repo
@@ -74,8 +98,189 @@ fn parse_github_url(url: &str) -> Option<RepoIdentifier> {
     Some(RepoIdentifier::new_github(owner, name))
 }
 
+/// Parse Bitbucket Cloud URLs (SSH and HTTPS)
+pub(super) fn parse_bitbucket_url(url: &str) -> Option<RepoIdentifier> {
+    // Patterns:
+    // - git@bitbucket.org:workspace/repo.git
+    // - https://bitbucket.org/workspace/repo
+    // - https://user@bitbucket.org/workspace/repo.git
+    // - ssh://git@bitbucket.org/workspace/repo.git
+
+    let re = Regex::new(
+        r"bitbucket\.org[:/](?P<workspace>[^/]+)/(?P<repo>[^/]+?)(?:\.git)?(?:/|$)",
+    )
+    .ok()?;
+
+    let caps = re.captures(url)?;
+    let workspace = caps.name("workspace")?.as_str().to_string();
+    let slug = caps.name("repo")?.as_str().to_string();
+
+    Some(RepoIdentifier::new_bitbucket(workspace, slug))
+}
+
+/// Parse Bitbucket Server/Data Center URLs (SSH and HTTPS)
+///
+/// Clone URLs over HTTP go through `/scm/`, possibly under a context path, and SSH uses port
+/// 7999 unless the admin changed it.
+pub(super) fn parse_bitbucket_server_url(url: &str) -> Option<RepoIdentifier> {
+    // Patterns:
+    // - https://bitbucket.example.com/scm/PROJ/repo.git
+    // - https://user@example.com/bitbucket/scm/PROJ/repo.git
+    // - https://bitbucket.example.com/scm/~user/repo.git
+    // - ssh://git@bitbucket.example.com:7999/PROJ/repo.git
+
+    let http = Regex::new(
+        r"^https?://(?:[^@/]+@)?(?P<host>[^/]+(?:/[^/]+)*?)/scm/(?P<project>[^/]+)/(?P<repo>[^/]+?)(?:\.git)?/?$",
+    )
+    .ok()?;
+    if let Some(caps) = http.captures(url) {
+        return Some(RepoIdentifier::new_bitbucket_server(
+            caps.name("project")?.as_str(),
+            caps.name("repo")?.as_str(),
+            caps.name("host")?.as_str(),
+        ));
+    }
+
+    let ssh = Regex::new(
+        r"^ssh://(?:[^@/]+@)?(?P<host>[^/:]+):7999/(?P<project>[^/]+)/(?P<repo>[^/]+?)(?:\.git)?/?$",
+    )
+    .ok()?;
+    let caps = ssh.captures(url)?;
+    // The web UI is not on the SSH port
+    Some(RepoIdentifier::new_bitbucket_server(
+        caps.name("project")?.as_str(),
+        caps.name("repo")?.as_str(),
+        caps.name("host")?.as_str(),
+    ))
+}
+
+/// Parse Azure DevOps URLs (dev.azure.com and visualstudio.com, SSH and HTTPS)
+pub(super) fn parse_azure_devops_url(url: &str) -> Option<RepoIdentifier> {
+    // Patterns:
+    // - https://dev.azure.com/org/project/_git/repo
+    // - https://org@dev.azure.com/org/project/_git/repo
+    // - git@ssh.dev.azure.com:v3/org/project/repo
+    // - https://org.visualstudio.com/project/_git/repo
+    // - https://org.visualstudio.com/DefaultCollection/project/_git/repo
+    // - org@vs-ssh.visualstudio.com:v3/org/project/repo
+
+    let ssh = Regex::new(
+        r"(?:ssh\.dev\.azure\.com|vs-ssh\.visualstudio\.com)[:/]v3/(?P<org>[^/]+)/(?P<project>[^/]+)/(?P<repo>[^/]+?)(?:\.git)?/?$",
+    )
+    .ok()?;
+    if let Some(caps) = ssh.captures(url) {
+        return Some(RepoIdentifier::new_azure_devops(
+            caps.name("org")?.as_str(),
+            caps.name("project")?.as_str(),
+            caps.name("repo")?.as_str(),
+        ));
+    }
+
+    let dev_azure = Regex::new(
+        r"dev\.azure\.com/(?P<org>[^/]+)/(?P<project>[^/]+)/_git/(?P<repo>[^/]+?)(?:\.git)?/?$",
+    )
+    .ok()?;
+    if let Some(caps) = dev_azure.captures(url) {
+        return Some(RepoIdentifier::new_azure_devops(
+            caps.name("org")?.as_str(),
+            caps.name("project")?.as_str(),
+            caps.name("repo")?.as_str(),
+        ));
+    }
+
+    let visualstudio = Regex::new(
+        r"(?P<org>[^/@.]+)\.visualstudio\.com/(?:DefaultCollection/)?(?P<project>[^/]+)/_git/(?P<repo>[^/]+?)(?:\.git)?/?$",
+    )
+    .ok()?;
+    let caps = visualstudio.captures(url)?;
+    Some(RepoIdentifier::new_azure_devops(
+        caps.name("org")?.as_str(),
+        caps.name("project")?.as_str(),
+        caps.name("repo")?.as_str(),
+    ))
+}
+
+/// Parse AWS CodeCommit URLs (HTTPS, SSH and git-remote-codecommit)
+pub(super) fn parse_codecommit_url(url: &str) -> Option<RepoIdentifier> {
+    // Patterns:
+    // - https://git-codecommit.us-east-1.amazonaws.com/v1/repos/repo
+    // - ssh://APKAEIBAERJR2EXAMPLE@git-codecommit.eu-west-1.amazonaws.com/v1/repos/repo
+    // - https://git-codecommit-fips.us-gov-west-1.amazonaws.com/v1/repos/repo
+    // - codecommit::us-east-1://repo
+    // - codecommit::us-east-1://profile@repo
+
+    let endpoint = Regex::new(
+        r"git-codecommit(?:-fips)?\.(?P<region>[a-z0-9-]+)\.amazonaws\.com(?:\.cn)?/v1/repos/(?P<repo>[^/]+?)(?:\.git)?/?$",
+    )
+    .ok()?;
+    if let Some(caps) = endpoint.captures(url) {
+        return Some(RepoIdentifier::new_codecommit(
+            caps.name("region")?.as_str(),
+            caps.name("repo")?.as_str(),
+        ));
+    }
+
+    // Without a region, git-remote-codecommit uses the one of the AWS profile, which we
+    // cannot know here
+    let grc = Regex::new(r"^codecommit::(?P<region>[a-z0-9-]+)://(?:[^@/]+@)?(?P<repo>[^/@]+)$")
+        .ok()?;
+    let caps = grc.captures(url)?;
+    Some(RepoIdentifier::new_codecommit(
+        caps.name("region")?.as_str(),
+        caps.name("repo")?.as_str(),
+    ))
+}
+
+/// Parse Gerrit URLs (SSH and HTTPS)
+///
+/// Gerrit runs on any host, so a URL counts as Gerrit when it uses the Gerrit SSH port, the
+/// `/a/` prefix of authenticated HTTP access, a googlesource.com host or a host named gerrit.
+pub(super) fn parse_gerrit_url(url: &str) -> Option<RepoIdentifier> {
+    // Patterns:
+    // - ssh://user@review.example.com:29418/project/path
+    // - https://review.example.com/a/project/path
+    // - https://gerrit.example.com:8443/project.git
+    // - https://android.googlesource.com/platform/build
+
+    let re = Regex::new(
+        r"^(?P<scheme>https?|ssh)://(?:[^@/]+@)?(?P<host>[^/:]+)(?::(?P<port>\d+))?/(?P<path>.+?)(?:\.git)?/?$",
+    )
+    .ok()?;
+    let caps = re.captures(url)?;
+    let host = caps.name("host")?.as_str();
+    let port = caps.name("port").map(|port| port.as_str());
+    let path = caps.name("path")?.as_str();
+
+    let authenticated_path = path.strip_prefix("a/");
+    let is_gerrit = port == Some("29418")
+        || authenticated_path.is_some()
+        || host.to_lowercase().contains("gerrit")
+        || host.to_lowercase().ends_with(".googlesource.com");
+    if !is_gerrit {
+        return None;
+    }
+
+    // Project paths may be nested like GitLab groups
+    let path = authenticated_path.unwrap_or(path);
+    let (owner, name) = match path.rsplit_once('/') {
+        Some((owner, name)) => (owner.to_string(), name.to_string()),
+        None => (String::new(), path.to_string()),
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    // The web UI and REST API share the HTTP port; the SSH port is only for git
+    let web_host = match (caps.name("scheme")?.as_str(), port) {
+        ("ssh", _) | (_, None) => host.to_string(),
+        (_, Some(port)) => format!("{host}:{port}"),
+    };
+
+    Some(RepoIdentifier::new_gerrit(owner, name, web_host))
+}
+
 /// Parse GitLab URLs (SSH and HTTPS, including self-hosted)
-fn parse_gitlab_url(url: &str) -> Option<RepoIdentifier> {
+pub(super) fn parse_gitlab_url(url: &str) -> Option<RepoIdentifier> {
     // Patterns:
     // - git@gitlab.com:group/project.git
     // - https://gitlab.com/group/project
@@ -216,9 +421,106 @@ mod tests {
         assert_eq!(repo.host, Some("gitlab.company.io".to_string()));
     }
 
+    #[test]
+    fn test_bitbucket_https() {
+        let (ptype, repo) =
+            detect_provider_from_url("https://user@bitbucket.org/team/app.git").unwrap();
+        assert_eq!(ptype, ProviderType::Bitbucket);
+        assert_eq!(repo.owner, "team");
+        assert_eq!(repo.name, "app");
+        assert!(repo.host.is_none());
+    }
+
+    #[test]
+    fn test_bitbucket_ssh() {
+        let (ptype, repo) =
+            detect_provider_from_url("git@bitbucket.org:team/app.git").unwrap();
+        assert_eq!(ptype, ProviderType::Bitbucket);
+        assert_eq!(repo.owner, "team");
+        assert_eq!(repo.name, "app");
+    }
+
+    #[test]
+    fn test_azure_devops_https() {
+        let (ptype, repo) =
+            detect_provider_from_url("https://org@dev.azure.com/org/Web%20App/_git/frontend")
+                .unwrap();
+        assert_eq!(ptype, ProviderType::AzureDevOps);
+        assert_eq!(repo.owner, "org");
+        assert_eq!(repo.project.as_deref(), Some("Web%20App"));
+        assert_eq!(repo.name, "frontend");
+    }
+
+    #[test]
+    fn test_azure_devops_ssh() {
+        let (ptype, repo) =
+            detect_provider_from_url("git@ssh.dev.azure.com:v3/org/project/repo").unwrap();
+        assert_eq!(ptype, ProviderType::AzureDevOps);
+        assert_eq!(repo.full_path(), "org/project/repo");
+    }
+
+    #[test]
+    fn test_codecommit() {
+        let (ptype, repo) = detect_provider_from_url(
+            "ssh://APKAEIBAERJR2EXAMPLE@git-codecommit.eu-west-1.amazonaws.com/v1/repos/billing",
+        )
+        .unwrap();
+        assert_eq!(ptype, ProviderType::CodeCommit);
+        assert_eq!(repo.owner, "eu-west-1");
+        assert_eq!(repo.name, "billing");
+
+        let (ptype, repo) =
+            detect_provider_from_url("codecommit::us-east-1://dev@gitlab-mirror").unwrap();
+        assert_eq!(ptype, ProviderType::CodeCommit);
+        assert_eq!(repo.owner, "us-east-1");
+        assert_eq!(repo.name, "gitlab-mirror");
+    }
+
+    #[test]
+    fn test_azure_devops_visualstudio() {
+        let (ptype, repo) = detect_provider_from_url(
+            "https://org.visualstudio.com/DefaultCollection/project/_git/repo",
+        )
+        .unwrap();
+        assert_eq!(ptype, ProviderType::AzureDevOps);
+        assert_eq!(repo.full_path(), "org/project/repo");
+
+        let (ptype, repo) =
+            detect_provider_from_url("org@vs-ssh.visualstudio.com:v3/org/project/repo").unwrap();
+        assert_eq!(ptype, ProviderType::AzureDevOps);
+        assert_eq!(repo.full_path(), "org/project/repo");
+    }
+
+    #[test]
+    fn test_gerrit_ssh() {
+        let (ptype, repo) =
+            detect_provider_from_url("ssh://jdoe@review.example.com:29418/platform/build")
+                .unwrap();
+        assert_eq!(ptype, ProviderType::Gerrit);
+        assert_eq!(repo.full_path(), "platform/build");
+        assert_eq!(repo.host.as_deref(), Some("review.example.com"));
+    }
+
+    #[test]
+    fn test_gerrit_https() {
+        let (ptype, repo) =
+            detect_provider_from_url("https://review.example.com:8443/a/tools.git").unwrap();
+        assert_eq!(ptype, ProviderType::Gerrit);
+        assert_eq!(repo.owner, "");
+        assert_eq!(repo.full_path(), "tools");
+        assert_eq!(repo.host.as_deref(), Some("review.example.com:8443"));
+
+        let (ptype, repo) =
+            detect_provider_from_url("https://android.googlesource.com/platform/frameworks/base")
+                .unwrap();
+        assert_eq!(ptype, ProviderType::Gerrit);
+        assert_eq!(repo.owner, "platform/frameworks");
+        assert_eq!(repo.name, "base");
+    }
+
     #[test]
     fn test_unknown_provider() {
-        let result = detect_provider_from_url("https://bitbucket.org/owner/repo");
+        let result = detect_provider_from_url("https://codeberg.org/owner/repo");
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), ProviderError::UnknownProvider(_)));
     }
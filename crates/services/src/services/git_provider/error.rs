@@ -1,5 +1,7 @@
 //! Provider error types
 
+use std::time::Duration;
+
 use thiserror::Error;
 
 /// Errors from git provider operations
@@ -17,12 +19,27 @@ pub enum ProviderError {
     #[error("API error ({status}): {message}")]
     ApiError { status: u16, message: String },
 
+    /// The provider rejected the request for its rate limit, with the wait it asked for
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+
     #[error("Failed to parse: {0}")]
     ParseError(String),
 
     #[error("Command failed: {0}")]
     CommandFailed(String),
 
+    /// The provider could not be reached, e.g. while the machine is offline
+    #[error("Provider unreachable: {0}")]
+    Unreachable(String),
+
+    /// The provider's API failed repeatedly, so requests to it are paused for a while
+    #[error("Provider unavailable: {0}")]
+    CircuitOpen(String),
+
     #[error("Git error: {0}")]
     Git(String),
 
@@ -30,16 +47,57 @@ pub enum ProviderError {
     UnknownProvider(String),
 }
 
+/// Lowercase fragments of the errors CLIs print when they cannot reach the provider
+const UNREACHABLE_MARKERS: &[&str] = &[
+    "error connecting to",
+    "could not resolve host",
+    "no such host",
+    "network is unreachable",
+    "connection refused",
+    "connection reset",
+    "i/o timeout",
+    "tls handshake timeout",
+    "temporary failure in name resolution",
+];
+
 impl ProviderError {
-    /// Check if error is retryable
+    /// Error for an HTTP request that got no response: unreachable when the connection failed
+    /// or timed out
+    pub fn request_failed(err: reqwest::Error) -> Self {
+        if err.is_connect() || err.is_timeout() {
+            ProviderError::Unreachable(format!("API request failed: {err}"))
+        } else {
+            ProviderError::CommandFailed(format!("API request failed: {err}"))
+        }
+    }
+
+    /// Error for a failed CLI command, telling apart the messages of network failures
+    pub fn command_failed(message: String) -> Self {
+        let lower = message.to_ascii_lowercase();
+        if UNREACHABLE_MARKERS
+            .iter()
+            .any(|marker| lower.contains(marker))
+        {
+            ProviderError::Unreachable(message)
+        } else {
+            ProviderError::CommandFailed(message)
+        }
+    }
+
+    /// Check if error is retryable. API errors are only retried when the request may pass
+    /// later: timeouts, rate limits (429) and server errors. Other client errors, like a missing
+    /// pull request or a 403 without rate-limit headers, fail the same way again. Providers
+    /// narrow this further with their [`RetryPolicy`](super::RetryPolicy).
     pub fn should_retry(&self) -> bool {
-        !matches!(
-            self,
+        match self {
+            ProviderError::ApiError { status, .. } => matches!(status, 408 | 429) || *status >= 500,
             ProviderError::NotInstalled { .. }
-                | ProviderError::NotAuthenticated(_)
-                | ProviderError::NotSupported { .. }
-                | ProviderError::UnknownProvider(_)
-        )
+            | ProviderError::NotAuthenticated(_)
+            | ProviderError::NotSupported { .. }
+            | ProviderError::CircuitOpen(_)
+            | ProviderError::UnknownProvider(_) => false,
+            _ => true,
+        }
     }
 
     /// Check if error is auth-related
@@ -47,6 +105,15 @@ impl ProviderError {
         matches!(self, ProviderError::NotAuthenticated(_))
     }
 
+    /// Check if the provider could not be reached, so the call may pass once the network is back
+    /// or the provider recovers
+    pub fn is_unreachable(&self) -> bool {
+        matches!(
+            self,
+            ProviderError::Unreachable(_) | ProviderError::CircuitOpen(_)
+        )
+    }
+
     /// Check if error is install-related
     pub fn is_not_installed(&self) -> bool {
         matches!(self, ProviderError::NotInstalled { .. })
@@ -0,0 +1,598 @@
+//! Gerrit provider implementation
+//!
+//! Gerrit reviews changes instead of branches: pushing a branch to `refs/for/<target>` opens a
+//! change per commit, and pushing again with the same Change-Id footers adds patch sets. Changes
+//! are tagged with the branch name as their topic, so a branch maps onto its changes much like a
+//! pull request, and the change number stands in for the pull request number. Changes take their
+//! title and description from the commit message, so the ones given when creating are unused.
+//!
+//! Status and comments come from the REST API. Credentials are the Gerrit user name in
+//! `GERRIT_USERNAME` and the HTTP password from the Gerrit settings page in
+//! `GERRIT_HTTP_PASSWORD`; without them only public projects can be read.
+
+use std::{path::PathBuf, time::Duration};
+
+use async_trait::async_trait;
+use backon::Retryable;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use reqwest::{RequestBuilder, StatusCode};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Deserializer, Serialize, de::DeserializeOwned};
+
+use super::{
+    CreateMrRequest, GitProvider, GuardedSend, PrInfo, PrState, ProviderError, ProviderType,
+    RepoIdentifier, RetryPolicy, UnifiedComment, detect_provider, get_remote_url, max_pages,
+    types::numeric_comment_id,
+};
+use crate::services::git::{GitCli, GitCliError};
+
+/// Prefix Gerrit puts before JSON responses against cross-site script inclusion
+const XSSI_PREFIX: &str = ")]}'";
+
+/// Changes requested per page when listing
+const PAGE_SIZE: usize = 100;
+
+/// Gerrit change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GerritChange {
+    #[serde(rename = "_number")]
+    pub number: u64,
+    pub project: String,
+    /// NEW, MERGED or ABANDONED
+    pub status: String,
+    #[serde(default, deserialize_with = "deserialize_optional_timestamp")]
+    pub submitted: Option<DateTime<Utc>>,
+    pub current_revision: Option<String>,
+    /// Set on the last change of a page when more changes match
+    #[serde(rename = "_more_changes", default)]
+    pub more_changes: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GerritAccount {
+    pub name: Option<String>,
+    pub username: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Comment on a line or file of a patch set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GerritComment {
+    pub id: String,
+    pub author: Option<GerritAccount>,
+    #[serde(default)]
+    pub message: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub updated: DateTime<Utc>,
+    pub line: Option<i64>,
+}
+
+/// Message on the change itself, such as a review summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GerritChangeMessage {
+    pub id: String,
+    pub author: Option<GerritAccount>,
+    #[serde(default)]
+    pub message: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub date: DateTime<Utc>,
+    /// Messages posted by bots and Gerrit itself are tagged `autogenerated:...`
+    pub tag: Option<String>,
+}
+
+/// Gerrit timestamps are UTC in the form `2025-01-02 03:04:05.000000000`
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    parse_timestamp(&value)
+        .ok_or_else(|| serde::de::Error::custom(format!("invalid Gerrit timestamp: {value}")))
+}
+
+fn deserialize_optional_timestamp<'de, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?
+        .as_deref()
+        .and_then(parse_timestamp))
+}
+
+impl GerritAccount {
+    fn display(account: Option<Self>) -> String {
+        account
+            .and_then(|account| account.username.or(account.email).or(account.name))
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+impl GerritChange {
+    fn into_pr_info(self, web_url: &str) -> PrInfo {
+        let state = match self.status.as_str() {
+            "NEW" => PrState::Open,
+            "MERGED" => PrState::Merged,
+            "ABANDONED" => PrState::Closed,
+            _ => PrState::Unknown,
+        };
+        PrInfo {
+            number: self.number,
+            url: change_url(web_url, &self.project, self.number),
+            state,
+            merged_at: if state == PrState::Merged {
+                self.submitted
+            } else {
+                None
+            },
+            merge_commit_sha: if state == PrState::Merged {
+                self.current_revision
+            } else {
+                None
+            },
+            review_state: None,
+        }
+    }
+}
+
+fn change_url(web_url: &str, project: &str, number: u64) -> String {
+    format!("{web_url}/c/{project}/+/{number}")
+}
+
+/// Comments of a change, oldest first, leaving out messages from bots and Gerrit itself
+fn unified_comments(
+    change_url: &str,
+    messages: Vec<GerritChangeMessage>,
+    comments: Vec<(String, GerritComment)>,
+) -> Vec<UnifiedComment> {
+    let mut unified: Vec<UnifiedComment> = messages
+        .into_iter()
+        .filter(|message| {
+            message.author.is_some()
+                && !message
+                    .tag
+                    .as_deref()
+                    .is_some_and(|tag| tag.starts_with("autogenerated:"))
+        })
+        .map(|message| UnifiedComment::General {
+            id: message.id,
+            author: GerritAccount::display(message.author),
+            author_association: "MEMBER".to_string(),
+            body: message.message,
+            created_at: message.date,
+            url: change_url.to_string(),
+        })
+        .collect();
+    unified.extend(comments.into_iter().map(|(path, comment)| {
+        UnifiedComment::Review {
+            id: numeric_comment_id(&comment.id),
+            author: GerritAccount::display(comment.author),
+            author_association: "MEMBER".to_string(),
+            body: comment.message,
+            created_at: comment.updated,
+            url: format!("{change_url}/comment/{}/", comment.id),
+            path,
+            start_line: None,
+            line: comment.line,
+            // The comments API does not include the surrounding diff
+            diff_hunk: String::new(),
+        }
+    }));
+    unified.sort_by_key(|c| c.created_at());
+    unified
+}
+
+/// Gerrit provider implementation using git push and the REST API
+#[derive(Debug, Clone)]
+pub struct GerritProvider {
+    /// Local repository to push from; changes cannot be created without it
+    repo_path: Option<PathBuf>,
+    credentials: Option<(String, SecretString)>,
+    http_client: reqwest::Client,
+}
+
+impl GerritProvider {
+    /// Create new Gerrit provider from environment credentials
+    pub fn new(repo_path: Option<PathBuf>) -> Self {
+        let credentials = std::env::var("GERRIT_USERNAME")
+            .ok()
+            .zip(std::env::var("GERRIT_HTTP_PASSWORD").ok())
+            .map(|(username, password)| (username, SecretString::from(password)));
+
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            repo_path,
+            credentials,
+            http_client,
+        }
+    }
+
+    /// Check if credentials are configured
+    pub fn has_credentials(&self) -> bool {
+        self.credentials.is_some()
+    }
+
+    /// Web UI and REST API root of the repository's Gerrit server
+    ///
+    /// Set `GERRIT_URL` when it differs from the git remote host, e.g. behind a proxy.
+    fn web_url(repo: &RepoIdentifier) -> Result<String, ProviderError> {
+        if let Ok(url) = std::env::var("GERRIT_URL") {
+            return Ok(url.trim_end_matches('/').to_string());
+        }
+        let host = repo.host.as_deref().ok_or_else(|| {
+            ProviderError::Git(format!(
+                "Gerrit repository {} has no host",
+                repo.full_path()
+            ))
+        })?;
+        // googlesource.com serves git and review from separate hosts
+        let host = match host.strip_suffix(".googlesource.com") {
+            Some(name) if !name.ends_with("-review") => {
+                format!("{name}-review.googlesource.com")
+            }
+            _ => host.to_string(),
+        };
+        Ok(format!("https://{host}"))
+    }
+
+    /// REST endpoint; authenticated calls go through the `/a/` prefix
+    fn api_url(&self, repo: &RepoIdentifier, path: &str) -> Result<String, ProviderError> {
+        let prefix = if self.credentials.is_some() { "/a" } else { "" };
+        Ok(format!("{}{}{}", Self::web_url(repo)?, prefix, path))
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ProviderError> {
+        let request = match &self.credentials {
+            Some((username, password)) => {
+                request.basic_auth(username, Some(password.expose_secret()))
+            }
+            None => request,
+        };
+        let response = request.send_guarded(ProviderType::Gerrit).await?;
+
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(ProviderError::request_failed)?;
+        if !status.is_success() {
+            return Err(parse_error(status, &text));
+        }
+
+        serde_json::from_str(text.trim_start_matches(XSSI_PREFIX))
+            .map_err(|e| ProviderError::ParseError(format!("Failed to parse response: {e}")))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, ProviderError> {
+        (|| async { self.send(self.http_client.get(url).query(query)).await })
+            .retry(retry_policy().backoff())
+            .when(|e: &ProviderError| retry_policy().should_retry(e))
+            .notify(|err, dur: Duration| {
+                tracing::warn!("Gerrit API retry after {:.2}s: {}", dur.as_secs_f64(), err);
+            })
+            .await
+    }
+
+    async fn query_changes(
+        &self,
+        repo: &RepoIdentifier,
+        query: String,
+    ) -> Result<Vec<GerritChange>, ProviderError> {
+        let url = self.api_url(repo, "/changes/")?;
+        let mut changes = Vec::new();
+        for page in 0..max_pages() {
+            let batch: Vec<GerritChange> = self
+                .get(
+                    &url,
+                    &[
+                        ("q", query.clone()),
+                        ("o", "CURRENT_REVISION".to_string()),
+                        ("n", PAGE_SIZE.to_string()),
+                        ("S", (page * PAGE_SIZE).to_string()),
+                    ],
+                )
+                .await?;
+            let more = batch.last().is_some_and(|change| change.more_changes);
+            changes.extend(batch);
+            if !more {
+                break;
+            }
+        }
+        Ok(changes)
+    }
+}
+
+/// Parse error response
+fn parse_error(status: StatusCode, body: &str) -> ProviderError {
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return ProviderError::NotAuthenticated(format!(
+            "Gerrit authentication failed: {}",
+            body.trim()
+        ));
+    }
+    // Gerrit answers errors in plain text
+    ProviderError::ApiError {
+        status: status.as_u16(),
+        message: body.trim().to_string(),
+    }
+}
+
+fn retry_policy() -> RetryPolicy {
+    super::retry_policy(ProviderType::Gerrit)
+}
+
+/// Change id naming the project as well as the change number, as the REST API recommends
+fn change_id(repo: &RepoIdentifier, number: u64) -> String {
+    let project: String =
+        url::form_urlencoded::byte_serialize(repo.full_path().as_bytes()).collect();
+    format!("{project}~{number}")
+}
+
+/// Query for the changes pushed from a branch
+fn branch_query(repo: &RepoIdentifier, branch: &str) -> String {
+    format!("project:\"{}\" topic:\"{}\"", repo.full_path(), branch)
+}
+
+#[async_trait]
+impl GitProvider for GerritProvider {
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::Gerrit
+    }
+
+    fn pushes_on_create(&self) -> bool {
+        true
+    }
+
+    async fn check_auth(&self) -> Result<(), ProviderError> {
+        if self.credentials.is_none() {
+            return Err(ProviderError::NotAuthenticated(
+                "Set GERRIT_USERNAME and GERRIT_HTTP_PASSWORD".to_string(),
+            ));
+        }
+        // Any repository of the server will do, the account is server-wide
+        let repo_path = self
+            .repo_path
+            .clone()
+            .ok_or_else(|| ProviderError::NotSupported {
+                feature: "checking Gerrit credentials without a repository".to_string(),
+            })?;
+        let (_, repo) = detect_provider(&repo_path)?;
+        let _: serde_json::Value = self
+            .get(&self.api_url(&repo, "/accounts/self")?, &[])
+            .await?;
+        Ok(())
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &RepoIdentifier,
+        req: &CreateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        let repo_path = self
+            .repo_path
+            .clone()
+            .ok_or_else(|| ProviderError::NotSupported {
+                feature: "creating Gerrit changes without a repository".to_string(),
+            })?;
+        let remote_url = get_remote_url(&repo_path)?;
+        let mut push_options = vec![format!("topic={}", req.head_branch)];
+        if req.draft.unwrap_or(false) {
+            push_options.push("wip".to_string());
+        }
+        let branch = req.head_branch.clone();
+        let target_branch = req.base_branch.clone();
+
+        tokio::task::spawn_blocking(move || {
+            GitCli::new().push_for_review(
+                &repo_path,
+                &remote_url,
+                &branch,
+                &target_branch,
+                &push_options,
+            )
+        })
+        .await
+        .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+        .map_err(|e| match e {
+            GitCliError::AuthFailed(msg) => ProviderError::NotAuthenticated(msg),
+            GitCliError::NotAvailable => ProviderError::NotInstalled {
+                cli_name: "git".to_string(),
+            },
+            // Includes pushes Gerrit rejects, e.g. for a missing Change-Id footer
+            other => ProviderError::CommandFailed(other.to_string()),
+        })?;
+
+        // The newest open change of the topic is the tip of the pushed branch
+        let query = format!(
+            "{} branch:\"{}\" status:open",
+            branch_query(repo, &req.head_branch),
+            req.base_branch
+        );
+        let change = self
+            .query_changes(repo, query)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                ProviderError::CommandFailed(
+                    "Gerrit accepted the push but no open change was found".to_string(),
+                )
+            })?;
+        Ok(change.into_pr_info(&Self::web_url(repo)?))
+    }
+
+    async fn get_mr_status(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<PrInfo, ProviderError> {
+        let change: GerritChange = self
+            .get(
+                &self.api_url(repo, &format!("/changes/{}", change_id(repo, number)))?,
+                &[("o", "CURRENT_REVISION".to_string())],
+            )
+            .await?;
+        Ok(change.into_pr_info(&Self::web_url(repo)?))
+    }
+
+    async fn list_mrs_for_branch(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<Vec<PrInfo>, ProviderError> {
+        let web_url = Self::web_url(repo)?;
+        Ok(self
+            .query_changes(repo, branch_query(repo, branch))
+            .await?
+            .into_iter()
+            .map(|change| change.into_pr_info(&web_url))
+            .collect())
+    }
+
+    async fn get_comments(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<UnifiedComment>, ProviderError> {
+        let messages: Vec<GerritChangeMessage> = self
+            .get(
+                &self.api_url(
+                    repo,
+                    &format!("/changes/{}/messages", change_id(repo, number)),
+                )?,
+                &[],
+            )
+            .await?;
+        let comments: std::collections::BTreeMap<String, Vec<GerritComment>> = self
+            .get(
+                &self.api_url(
+                    repo,
+                    &format!("/changes/{}/comments", change_id(repo, number)),
+                )?,
+                &[],
+            )
+            .await?;
+        let comments = comments
+            .into_iter()
+            .flat_map(|(path, comments)| {
+                comments
+                    .into_iter()
+                    .map(move |comment| (path.clone(), comment))
+            })
+            .collect();
+
+        let url = change_url(&Self::web_url(repo)?, &repo.full_path(), number);
+        Ok(unified_comments(&url, messages, comments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEB_URL: &str = "https://review.example.com";
+
+    #[test]
+    fn converts_change_states() {
+        let change: GerritChange = serde_json::from_value(serde_json::json!({
+            "_number": 4711,
+            "project": "platform/build",
+            "status": "MERGED",
+            "submitted": "2025-01-02 03:04:05.000000000",
+            "current_revision": "abc123"
+        }))
+        .unwrap();
+        let info = change.into_pr_info(WEB_URL);
+        assert_eq!(info.number, 4711);
+        assert_eq!(info.state, PrState::Merged);
+        assert_eq!(info.url, format!("{WEB_URL}/c/platform/build/+/4711"));
+        assert_eq!(info.merge_commit_sha.as_deref(), Some("abc123"));
+        assert_eq!(
+            info.merged_at,
+            Some("2025-01-02T03:04:05Z".parse::<DateTime<Utc>>().unwrap())
+        );
+
+        let open: GerritChange = serde_json::from_value(serde_json::json!({
+            "_number": 4712,
+            "project": "platform/build",
+            "status": "NEW",
+            "current_revision": "def456"
+        }))
+        .unwrap();
+        let info = open.into_pr_info(WEB_URL);
+        assert_eq!(info.state, PrState::Open);
+        assert!(info.merge_commit_sha.is_none());
+    }
+
+    #[test]
+    fn comments_skip_generated_messages() {
+        let messages: Vec<GerritChangeMessage> = serde_json::from_value(serde_json::json!([
+            {
+                "id": "m1",
+                "author": { "name": "Build Bot", "username": "ci" },
+                "message": "Patch Set 1: Verified+1",
+                "date": "2025-01-02 03:00:00.000000000",
+                "tag": "autogenerated:ci"
+            },
+            {
+                "id": "m2",
+                "author": { "name": "Sam Lee", "username": "slee" },
+                "message": "Patch Set 1: Code-Review-1\n\n(1 comment)",
+                "date": "2025-01-02 03:05:00.000000000"
+            },
+            {
+                "id": "m3",
+                "message": "Uploaded patch set 1.",
+                "date": "2025-01-02 02:00:00.000000000"
+            }
+        ]))
+        .unwrap();
+        let comment: GerritComment = serde_json::from_value(serde_json::json!({
+            "id": "TvcXrmjM",
+            "author": { "username": "slee" },
+            "message": "Rename this",
+            "updated": "2025-01-02 03:04:00.000000000",
+            "line": 12
+        }))
+        .unwrap();
+
+        let url = format!("{WEB_URL}/c/tools/+/1");
+        let comments = unified_comments(&url, messages, vec![("src/lib.rs".to_string(), comment)]);
+        assert_eq!(comments.len(), 2);
+        match &comments[0] {
+            UnifiedComment::Review {
+                id,
+                path,
+                line,
+                url,
+                ..
+            } => {
+                assert_eq!(*id, numeric_comment_id("TvcXrmjM"));
+                assert!(*id >= 0);
+                assert_eq!(path, "src/lib.rs");
+                assert_eq!(*line, Some(12));
+                assert_eq!(url, &format!("{WEB_URL}/c/tools/+/1/comment/TvcXrmjM/"));
+            }
+            other => panic!("expected a review comment, got {other:?}"),
+        }
+        assert!(matches!(
+            &comments[1],
+            UnifiedComment::General { author, .. } if author == "slee"
+        ));
+    }
+}
@@ -1,26 +1,94 @@
 //! GitHub provider implementation
 
+mod api;
+mod graphql;
+
 use std::time::Duration;
 
+use api::{DEFAULT_API_URL, GitHubApiClient};
 use async_trait::async_trait;
-use backon::{ExponentialBuilder, Retryable};
+use backon::Retryable;
+use chrono::{DateTime, Utc};
+use secrecy::SecretString;
+use serde_json::json;
 use tokio::task;
 
 use super::{
-    CreateMrRequest, GitProvider, PrInfo, ProviderError, ProviderType, RepoIdentifier,
-    UnifiedComment,
+    BranchProtection, ChangedFile, CheckRun, CommitStatusState, CreateMrRequest, FileChangeKind,
+    FileDiff, GitProvider, MergeMethod, Milestone, MrDetails, PrInfo, ProviderError, ProviderType,
+    ReleaseInfo, RepoIdentifier, RetryPolicy, ReviewRequest, ReviewVerdict, UnifiedComment,
+    UnifiedReview, UpdateMrRequest,
+};
+use crate::services::github::cli::{
+    BranchRule, GhCli, GhCliError, IssueComment, PrFile, PrRequestedReviewers, PrReview,
+    PrReviewComment, RepoBranch, RequiredReviews,
 };
-use crate::services::github::cli::{GhCli, GhCliError};
 
-/// GitHub provider implementation using gh CLI
+/// GitHub provider implementation
+///
+/// Uses the REST API when a token is configured and the gh CLI otherwise, so servers without
+/// `gh` installed can still work with pull requests.
 #[derive(Debug, Clone)]
 pub struct GitHubProvider {
     cli: GhCli,
+    api_client: Option<GitHubApiClient>,
 }
 
 impl GitHubProvider {
+    /// Create new GitHub provider
+    ///
+    /// - With a `GITHUB_TOKEN` or `GH_TOKEN` env var: talks to the REST API directly
+    /// - Without one: uses the `gh` CLI (requires `gh auth login`)
+    ///
+    /// For GitHub Enterprise Server, set `GITHUB_API_URL` to `https://<host>/api/v3`.
     pub fn new() -> Self {
-        Self { cli: GhCli::new() }
+        Self::with_token(None)
+    }
+
+    /// Create provider with explicit token (for config-based token)
+    pub fn with_token(token: Option<String>) -> Self {
+        let api_url =
+            std::env::var("GITHUB_API_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+
+        // Use provided token, falling back to the env vars gh itself reads
+        let api_client = token
+            .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+            .or_else(|| std::env::var("GH_TOKEN").ok())
+            .filter(|token| !token.is_empty())
+            .map(|token| GitHubApiClient::new(api_url, SecretString::from(token)));
+
+        Self {
+            cli: GhCli::new(),
+            api_client,
+        }
+    }
+
+    /// Check if API client is available (token configured)
+    pub fn has_api_token(&self) -> bool {
+        self.api_client.is_some()
+    }
+
+    async fn get_pr_files(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<PrFile>, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.get_files(repo, number).await;
+        }
+
+        (|| async {
+            let cli = self.cli.clone();
+            let owner = repo.owner.clone();
+            let name = repo.name.clone();
+            task::spawn_blocking(move || cli.get_pr_files(&owner, &name, number as i64))
+                .await
+                .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+                .map_err(ProviderError::from)
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .await
     }
 }
 
@@ -37,7 +105,7 @@ impl From<GhCliError> for ProviderError {
                 cli_name: "gh".into(),
             },
             GhCliError::AuthFailed(msg) => ProviderError::NotAuthenticated(msg),
-            GhCliError::CommandFailed(msg) => ProviderError::CommandFailed(msg),
+            GhCliError::CommandFailed(msg) => ProviderError::command_failed(msg),
             GhCliError::UnexpectedOutput(msg) => ProviderError::ParseError(msg),
         }
     }
@@ -50,6 +118,10 @@ impl GitProvider for GitHubProvider {
     }
 
     async fn check_auth(&self) -> Result<(), ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.check_auth().await;
+        }
+
         let cli = self.cli.clone();
         task::spawn_blocking(move || cli.check_auth())
             .await
@@ -57,19 +129,40 @@ impl GitProvider for GitHubProvider {
             .map_err(ProviderError::from)
     }
 
+    async fn authenticated_username(&self) -> Result<String, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.authenticated_username().await;
+        }
+
+        let cli = self.cli.clone();
+        task::spawn_blocking(move || cli.authenticated_username())
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)
+    }
+
     async fn create_merge_request(
         &self,
         repo: &RepoIdentifier,
         req: &CreateMrRequest,
     ) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.create_pr(repo, req).await;
+        }
+
         let cli = self.cli.clone();
         let owner = repo.owner.clone();
         let name = repo.name.clone();
         let title = req.title.clone();
         let body = req.body.clone();
-        let head = req.head_branch.clone();
+        // A branch on a fork is named as owner:branch
+        let head = match &req.head_repo {
+            Some(head_repo) => format!("{}:{}", head_repo.owner, req.head_branch),
+            None => req.head_branch.clone(),
+        };
         let base = req.base_branch.clone();
         let draft = req.draft;
+        let milestone = req.milestone.clone();
 
         let result = (|| async {
             let cli = cli.clone();
@@ -79,6 +172,7 @@ impl GitProvider for GitHubProvider {
             let body = body.clone();
             let head = head.clone();
             let base = base.clone();
+            let milestone = milestone.clone();
 
             let pr_info = task::spawn_blocking(move || {
                 use crate::services::github::{CreatePrRequest as GhCreatePrRequest, GitHubRepoInfo};
@@ -93,6 +187,7 @@ impl GitProvider for GitHubProvider {
                     head_branch: head,
                     base_branch: base,
                     draft,
+                    milestone,
                 };
                 cli.create_pr(&request, &repo_info)
             })
@@ -102,8 +197,8 @@ impl GitProvider for GitHubProvider {
 
             Ok(convert_pr_info(pr_info))
         })
-        .retry(retry_config())
-        .when(|e: &ProviderError| e.should_retry())
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
         .notify(|err, dur: Duration| {
             tracing::warn!("GitHub API retry after {:.2}s: {}", dur.as_secs_f64(), err);
         })
@@ -117,6 +212,10 @@ impl GitProvider for GitHubProvider {
         repo: &RepoIdentifier,
         number: u64,
     ) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return Ok(api_client.get_pr(repo, number).await?.into_pr_info());
+        }
+
         let cli = self.cli.clone();
         let owner = repo.owner.clone();
         let name = repo.name.clone();
@@ -133,8 +232,8 @@ impl GitProvider for GitHubProvider {
 
             Ok(convert_pr_info(pr_info))
         })
-        .retry(retry_config())
-        .when(|e: &ProviderError| e.should_retry())
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
         .notify(|err, dur: Duration| {
             tracing::warn!("GitHub API retry after {:.2}s: {}", dur.as_secs_f64(), err);
         })
@@ -146,6 +245,10 @@ impl GitProvider for GitHubProvider {
         repo: &RepoIdentifier,
         branch: &str,
     ) -> Result<Vec<PrInfo>, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.list_prs_for_branch(repo, branch).await;
+        }
+
         let cli = self.cli.clone();
         let owner = repo.owner.clone();
         let name = repo.name.clone();
@@ -164,19 +267,61 @@ impl GitProvider for GitHubProvider {
 
             Ok(prs.into_iter().map(convert_pr_info).collect())
         })
-        .retry(retry_config())
-        .when(|e: &ProviderError| e.should_retry())
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
         .notify(|err, dur: Duration| {
             tracing::warn!("GitHub API retry after {:.2}s: {}", dur.as_secs_f64(), err);
         })
         .await
     }
 
+    async fn list_mrs_for_reviewer(
+        &self,
+        repo: &RepoIdentifier,
+        username: &str,
+    ) -> Result<Vec<ReviewRequest>, ProviderError> {
+        let prs = match self.api_client {
+            Some(ref api_client) => api_client.list_open_prs(repo).await?,
+            None => {
+                let cli = self.cli.clone();
+                let owner = repo.owner.clone();
+                let name = repo.name.clone();
+                task::spawn_blocking(move || cli.list_open_prs(&owner, &name))
+                    .await
+                    .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+                    .map_err(ProviderError::from)?
+            }
+        };
+
+        // Reviewers leave `requested_reviewers` once they submit a review, so these are the
+        // pull requests still waiting for the user
+        Ok(prs
+            .into_iter()
+            .filter(|pr| {
+                pr.requested_reviewers
+                    .iter()
+                    .any(|reviewer| reviewer.login.eq_ignore_ascii_case(username))
+            })
+            .map(|pr| ReviewRequest {
+                number: pr.number as u64,
+                url: pr.html_url,
+                title: pr.title,
+                author: pr.user.map(|user| user.login).unwrap_or_default(),
+                draft: pr.draft,
+                updated_at: pr.updated_at,
+            })
+            .collect())
+    }
+
     async fn get_comments(
         &self,
         repo: &RepoIdentifier,
         number: u64,
     ) -> Result<Vec<UnifiedComment>, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_comments(api_client, repo, number, None).await;
+        }
+
         let cli = self.cli.clone();
         let owner = repo.owner.clone();
         let name = repo.name.clone();
@@ -196,8 +341,8 @@ impl GitProvider for GitHubProvider {
                         .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
                         .map_err(ProviderError::from)
                 })
-                .retry(retry_config())
-                .when(|e: &ProviderError| e.should_retry())
+                .retry(retry_policy().backoff())
+                .when(|e: &ProviderError| retry_policy().should_retry(e))
                 .await
             }
         };
@@ -218,8 +363,8 @@ impl GitProvider for GitHubProvider {
                     .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
                     .map_err(ProviderError::from)
                 })
-                .retry(retry_config())
-                .when(|e: &ProviderError| e.should_retry())
+                .retry(retry_policy().backoff())
+                .when(|e: &ProviderError| retry_policy().should_retry(e))
                 .await
             }
         };
@@ -242,25 +387,549 @@ impl GitProvider for GitHubProvider {
             });
         }
 
-        for c in review {
-            unified.push(UnifiedComment::Review {
-                id: c.id,
-                author: c.user.login,
-                author_association: c.author_association,
-                body: c.body,
-                created_at: c.created_at,
-                url: c.html_url,
-                path: c.path,
-                line: c.line,
-                diff_hunk: c.diff_hunk,
-            });
-        }
+        unified.extend(review.into_iter().map(convert_review_comment));
 
         // Sort by creation time
         unified.sort_by_key(|c| c.created_at());
 
         Ok(unified)
     }
+
+    async fn get_comments_since(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<UnifiedComment>, ProviderError> {
+        let mut unified = match self.api_client {
+            // The API filters by when comments were last updated, so edited older ones still come
+            Some(ref api_client) => api_comments(api_client, repo, number, Some(since)).await?,
+            None => self.get_comments(repo, number).await?,
+        };
+        unified.retain(|comment| comment.created_at() >= since);
+        Ok(unified)
+    }
+
+    async fn post_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            let comment = api_client.post_comment(repo, number, body).await?;
+            return Ok(convert_issue_comment(comment));
+        }
+
+        let cli = self.cli.clone();
+        let owner = repo.owner.clone();
+        let name = repo.name.clone();
+        let body = body.to_string();
+
+        // Not retried, a comment that timed out may have been posted anyway
+        let comment =
+            task::spawn_blocking(move || cli.post_pr_comment(&owner, &name, number as i64, &body))
+                .await
+                .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+                .map_err(ProviderError::from)?;
+
+        Ok(convert_issue_comment(comment))
+    }
+
+    async fn post_review_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        path: &str,
+        line: u64,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            let comment = api_client
+                .post_review_comment(repo, number, path, line, body)
+                .await?;
+            return Ok(convert_review_comment(comment));
+        }
+
+        let cli = self.cli.clone();
+        let owner = repo.owner.clone();
+        let name = repo.name.clone();
+        let path = path.to_string();
+        let body = body.to_string();
+
+        // Not retried, a comment that timed out may have been posted anyway
+        let comment = task::spawn_blocking(move || {
+            let commit_id = cli.get_pr_head_sha(&owner, &name, number as i64)?;
+            cli.post_review_comment(&owner, &name, number as i64, &commit_id, &path, line, &body)
+        })
+        .await
+        .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+        .map_err(ProviderError::from)?;
+
+        Ok(convert_review_comment(comment))
+    }
+
+    async fn reply_to_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        thread_id: &str,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        // Conversation comments have node ids and no threads; only review comments take replies
+        let comment_id: i64 = thread_id.parse().map_err(|_| ProviderError::NotSupported {
+            feature: "replying to conversation comments on GitHub".to_string(),
+        })?;
+        if let Some(ref api_client) = self.api_client {
+            let reply = api_client
+                .reply_to_review_comment(repo, number, comment_id, body)
+                .await?;
+            return Ok(convert_review_comment(reply));
+        }
+
+        let cli = self.cli.clone();
+        let owner = repo.owner.clone();
+        let name = repo.name.clone();
+        let body = body.to_string();
+
+        // Not retried, a reply that timed out may have been posted anyway
+        let reply = task::spawn_blocking(move || {
+            cli.reply_to_review_comment(&owner, &name, number as i64, comment_id, &body)
+        })
+        .await
+        .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+        .map_err(ProviderError::from)?;
+
+        Ok(convert_review_comment(reply))
+    }
+
+    async fn get_diff(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<FileDiff>, ProviderError> {
+        let files = self.get_pr_files(repo, number).await?;
+        Ok(files
+            .into_iter()
+            .map(|file| FileDiff {
+                change: convert_file_change(&file),
+                path: file.filename,
+                old_path: file.previous_filename,
+                patch: file.patch,
+            })
+            .collect())
+    }
+
+    async fn get_changed_files(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<ChangedFile>, ProviderError> {
+        // GitHub counts the lines itself, also for files whose patch it leaves out
+        let files = self.get_pr_files(repo, number).await?;
+        Ok(files
+            .into_iter()
+            .map(|file| ChangedFile {
+                change: convert_file_change(&file),
+                path: file.filename,
+                old_path: file.previous_filename,
+                additions: file.additions,
+                deletions: file.deletions,
+            })
+            .collect())
+    }
+
+    async fn get_reviews(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<UnifiedReview>, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            let (reviews, requested) = tokio::try_join!(
+                api_client.get_reviews(repo, number),
+                api_client.get_requested_reviewers(repo, number)
+            )?;
+            return Ok(convert_reviews(reviews, requested));
+        }
+
+        let cli = self.cli.clone();
+        let owner = repo.owner.clone();
+        let name = repo.name.clone();
+
+        let (reviews, requested) = task::spawn_blocking(move || {
+            let reviews = cli.get_pr_reviews(&owner, &name, number as i64)?;
+            let requested = cli.get_pr_requested_reviewers(&owner, &name, number as i64)?;
+            Ok::<_, GhCliError>((reviews, requested))
+        })
+        .await
+        .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+        .map_err(ProviderError::from)?;
+
+        Ok(convert_reviews(reviews, requested))
+    }
+
+    /// One GraphQL query instead of a call each for the status, reviews, requested reviewers
+    /// and both kinds of comments
+    async fn get_mr_details(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<MrDetails, ProviderError> {
+        let data = match self.api_client {
+            Some(ref api_client) => {
+                api_client
+                    .query(
+                        graphql::PR_DETAILS_QUERY,
+                        json!({ "owner": repo.owner, "name": repo.name, "number": number }),
+                    )
+                    .await?
+            }
+            None => {
+                (|| async {
+                    let cli = self.cli.clone();
+                    let owner = repo.owner.clone();
+                    let name = repo.name.clone();
+                    task::spawn_blocking(move || {
+                        cli.graphql(
+                            graphql::PR_DETAILS_QUERY,
+                            &[("owner", owner.as_str()), ("name", name.as_str())],
+                            &[("number", number as i64)],
+                        )
+                    })
+                    .await
+                    .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+                    .map_err(ProviderError::from)
+                })
+                .retry(retry_policy().backoff())
+                .when(|e: &ProviderError| retry_policy().should_retry(e))
+                .await?
+            }
+        };
+        graphql::parse_pr_details(data)
+    }
+
+    /// Checks come with the rest of the pull request from a single query
+    async fn get_checks(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<CheckRun>, ProviderError> {
+        Ok(self.get_mr_details(repo, number).await?.checks)
+    }
+
+    /// Logs of GitHub Actions jobs; a check run's id is the id of its job
+    async fn get_check_logs(
+        &self,
+        repo: &RepoIdentifier,
+        _number: u64,
+        check_id: &str,
+    ) -> Result<String, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.get_job_logs(repo, check_id).await;
+        }
+
+        (|| async {
+            let cli = self.cli.clone();
+            let owner = repo.owner.clone();
+            let name = repo.name.clone();
+            let job_id = check_id.to_string();
+            task::spawn_blocking(move || cli.get_job_logs(&owner, &name, &job_id))
+                .await
+                .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+                .map_err(ProviderError::from)
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .await
+    }
+
+    async fn set_commit_status(
+        &self,
+        repo: &RepoIdentifier,
+        sha: &str,
+        state: CommitStatusState,
+        context: &str,
+        url: Option<&str>,
+    ) -> Result<(), ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client
+                .set_commit_status(repo, sha, state, context, url)
+                .await;
+        }
+
+        (|| async {
+            let cli = self.cli.clone();
+            let owner = repo.owner.clone();
+            let name = repo.name.clone();
+            let sha = sha.to_string();
+            let context = context.to_string();
+            let url = url.map(str::to_string);
+            task::spawn_blocking(move || {
+                cli.set_commit_status(&owner, &name, &sha, state, &context, url.as_deref())
+            })
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .await
+    }
+
+    async fn list_milestones(
+        &self,
+        repo: &RepoIdentifier,
+    ) -> Result<Vec<Milestone>, ProviderError> {
+        let milestones = match self.api_client {
+            Some(ref api_client) => api_client.list_milestones(repo).await?,
+            None => {
+                let cli = self.cli.clone();
+                let owner = repo.owner.clone();
+                let name = repo.name.clone();
+                task::spawn_blocking(move || cli.list_milestones(&owner, &name))
+                    .await
+                    .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+                    .map_err(ProviderError::from)?
+            }
+        };
+
+        Ok(milestones
+            .into_iter()
+            .map(|milestone| Milestone {
+                id: milestone.number.to_string(),
+                title: milestone.title,
+                description: milestone.description,
+                due_date: milestone.due_on.map(|due_on| due_on.date_naive()),
+            })
+            .collect())
+    }
+
+    /// Classic branch protection and repository rulesets combined. Review requirements of
+    /// classic protection are only visible to repository admins and count as none otherwise.
+    async fn get_branch_protection(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<BranchProtection, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            let (info, rules) = tokio::try_join!(
+                api_client.get_branch(repo, branch),
+                api_client.get_branch_rules(repo, branch)
+            )?;
+            let reviews = if info.protected {
+                api_client.get_required_reviews(repo, branch).await.ok()
+            } else {
+                None
+            };
+            return Ok(convert_branch_protection(info, rules, reviews));
+        }
+
+        let (info, rules, reviews) = (|| async {
+            let cli = self.cli.clone();
+            let owner = repo.owner.clone();
+            let name = repo.name.clone();
+            let branch = branch.to_string();
+            task::spawn_blocking(move || {
+                let info = cli.get_branch(&owner, &name, &branch)?;
+                let rules = cli.get_branch_rules(&owner, &name, &branch)?;
+                let reviews = info
+                    .protected
+                    .then(|| cli.get_required_reviews(&owner, &name, &branch).ok())
+                    .flatten();
+                Ok::<_, GhCliError>((info, rules, reviews))
+            })
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .await?;
+        Ok(convert_branch_protection(info, rules, reviews))
+    }
+
+    async fn create_release(
+        &self,
+        repo: &RepoIdentifier,
+        tag: &str,
+        name: &str,
+        notes: &str,
+    ) -> Result<ReleaseInfo, ProviderError> {
+        let release = match self.api_client {
+            Some(ref api_client) => api_client.create_release(repo, tag, name, notes).await?,
+            None => {
+                let cli = self.cli.clone();
+                let owner = repo.owner.clone();
+                let repo_name = repo.name.clone();
+                let tag = tag.to_string();
+                let name = name.to_string();
+                let notes = notes.to_string();
+                // Not retried: a release that timed out may still have been created
+                task::spawn_blocking(move || {
+                    cli.create_release(&owner, &repo_name, &tag, &name, &notes)
+                })
+                .await
+                .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+                .map_err(ProviderError::from)?
+            }
+        };
+
+        Ok(ReleaseInfo {
+            name: release.name.unwrap_or_else(|| release.tag_name.clone()),
+            tag: release.tag_name,
+            url: release.html_url,
+        })
+    }
+
+    async fn merge_mr(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        method: MergeMethod,
+    ) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            api_client.merge_pr(repo, number, method).await?;
+            return self.get_mr_status(repo, number).await;
+        }
+
+        let cli = self.cli.clone();
+        let owner = repo.owner.clone();
+        let name = repo.name.clone();
+
+        // Not retried: a merge that timed out may still have gone through
+        task::spawn_blocking(move || cli.merge_pr(&owner, &name, number as i64, method))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn enable_auto_merge(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        method: MergeMethod,
+    ) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            api_client.enable_auto_merge(repo, number, method).await?;
+            return self.get_mr_status(repo, number).await;
+        }
+
+        let cli = self.cli.clone();
+        let owner = repo.owner.clone();
+        let name = repo.name.clone();
+
+        task::spawn_blocking(move || cli.enable_auto_merge(&owner, &name, number as i64, method))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn close_mr(&self, repo: &RepoIdentifier, number: u64) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.set_open(repo, number, false).await;
+        }
+
+        let cli = self.cli.clone();
+        let owner = repo.owner.clone();
+        let name = repo.name.clone();
+
+        task::spawn_blocking(move || cli.close_pr(&owner, &name, number as i64))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn reopen_mr(&self, repo: &RepoIdentifier, number: u64) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.set_open(repo, number, true).await;
+        }
+
+        let cli = self.cli.clone();
+        let owner = repo.owner.clone();
+        let name = repo.name.clone();
+
+        task::spawn_blocking(move || cli.reopen_pr(&owner, &name, number as i64))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn update_mr(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        req: &UpdateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.update_pr(repo, number, req).await;
+        }
+
+        let cli = self.cli.clone();
+        let owner = repo.owner.clone();
+        let name = repo.name.clone();
+        let req = req.clone();
+
+        task::spawn_blocking(move || cli.edit_pr(&owner, &name, number as i64, &req))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn set_draft(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        draft: bool,
+    ) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            api_client.set_draft(repo, number, draft).await?;
+            return self.get_mr_status(repo, number).await;
+        }
+
+        let cli = self.cli.clone();
+        let owner = repo.owner.clone();
+        let name = repo.name.clone();
+
+        task::spawn_blocking(move || cli.set_pr_draft(&owner, &name, number as i64, draft))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn request_reviewers(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        reviewers: &[String],
+    ) -> Result<(), ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.request_reviewers(repo, number, reviewers).await;
+        }
+
+        let cli = self.cli.clone();
+        let owner = repo.owner.clone();
+        let name = repo.name.clone();
+        let reviewers = reviewers.to_vec();
+
+        task::spawn_blocking(move || {
+            cli.request_reviewers(&owner, &name, number as i64, &reviewers)
+        })
+        .await
+        .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+        .map_err(ProviderError::from)
+    }
 }
 
 /// Convert db::models::merge::PullRequestInfo to PrInfo
@@ -271,13 +940,184 @@ fn convert_pr_info(pr: db::models::merge::PullRequestInfo) -> PrInfo {
         state: pr.status.into(),
         merged_at: pr.merged_at,
         merge_commit_sha: pr.merge_commit_sha,
+        review_state: None,
+    }
+}
+
+/// Conversation comments are identified by node id, like the ones gh lists
+/// Conversation and review comments from the API, oldest first
+async fn api_comments(
+    api_client: &GitHubApiClient,
+    repo: &RepoIdentifier,
+    number: u64,
+    since: Option<DateTime<Utc>>,
+) -> Result<Vec<UnifiedComment>, ProviderError> {
+    let (general, review) = tokio::join!(
+        api_client.get_issue_comments(repo, number, since),
+        api_client.get_review_comments(repo, number, since)
+    );
+    let mut unified: Vec<UnifiedComment> =
+        general?.into_iter().map(convert_issue_comment).collect();
+    unified.extend(review?.into_iter().map(convert_review_comment));
+    unified.sort_by_key(|c| c.created_at());
+    Ok(unified)
+}
+
+fn convert_issue_comment(c: IssueComment) -> UnifiedComment {
+    UnifiedComment::General {
+        id: c.node_id,
+        author: c.user.login,
+        author_association: c.author_association,
+        body: c.body,
+        created_at: c.created_at,
+        url: c.html_url,
     }
 }
 
-fn retry_config() -> ExponentialBuilder {
-    ExponentialBuilder::default()
-        .with_min_delay(Duration::from_secs(1))
-        .with_max_delay(Duration::from_secs(30))
-        .with_max_times(3)
-        .with_jitter()
+fn convert_review_comment(c: PrReviewComment) -> UnifiedComment {
+    UnifiedComment::Review {
+        id: c.id,
+        author: c.user.login,
+        author_association: c.author_association,
+        body: c.body,
+        created_at: c.created_at,
+        url: c.html_url,
+        path: c.path,
+        start_line: c.start_line,
+        line: c.line,
+        diff_hunk: c.diff_hunk,
+    }
+}
+
+/// Required checks and approvals of a branch, from its classic protection and the ruleset rules
+/// that apply to it, whichever is stricter
+fn convert_branch_protection(
+    branch: RepoBranch,
+    rules: Vec<BranchRule>,
+    reviews: Option<RequiredReviews>,
+) -> BranchProtection {
+    let mut required_checks: Vec<String> = branch
+        .protection
+        .and_then(|protection| protection.required_status_checks)
+        .map(|checks| checks.contexts)
+        .unwrap_or_default();
+    let mut required_approvals = reviews.map_or(0, |r| r.required_approving_review_count);
+    for rule in &rules {
+        match rule.kind.as_str() {
+            "pull_request" => {
+                let count = rule.parameters["required_approving_review_count"]
+                    .as_u64()
+                    .unwrap_or(0);
+                required_approvals = required_approvals.max(count as u32);
+            }
+            "required_status_checks" => {
+                let checks = rule.parameters["required_status_checks"].as_array();
+                for check in checks.into_iter().flatten() {
+                    if let Some(context) = check["context"].as_str()
+                        && !required_checks.iter().any(|c| c == context)
+                    {
+                        required_checks.push(context.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    BranchProtection {
+        protected: branch.protected || !rules.is_empty(),
+        required_checks,
+        required_approvals,
+    }
+}
+
+/// Submitted reviews plus one pending review per requested user or team. Dismissed reviews and
+/// unsubmitted drafts carry no verdict and are left out.
+fn convert_reviews(reviews: Vec<PrReview>, requested: PrRequestedReviewers) -> Vec<UnifiedReview> {
+    let submitted = reviews.into_iter().filter_map(|review| {
+        let verdict = match review.state.as_str() {
+            "APPROVED" => ReviewVerdict::Approved,
+            "CHANGES_REQUESTED" => ReviewVerdict::ChangesRequested,
+            "COMMENTED" => ReviewVerdict::Commented,
+            _ => return None,
+        };
+        Some(UnifiedReview {
+            reviewer: review.user.map(|user| user.login).unwrap_or_default(),
+            verdict,
+            submitted_at: review.submitted_at,
+        })
+    });
+    let pending = requested
+        .users
+        .into_iter()
+        .map(|user| user.login)
+        .chain(requested.teams.into_iter().map(|team| team.slug))
+        .map(|reviewer| UnifiedReview {
+            reviewer,
+            verdict: ReviewVerdict::Pending,
+            submitted_at: None,
+        });
+    submitted.chain(pending).collect()
+}
+
+/// Copied files count as added, and files changed only in mode as modified
+fn convert_file_change(file: &PrFile) -> FileChangeKind {
+    match file.status.as_str() {
+        "added" | "copied" => FileChangeKind::Added,
+        "removed" => FileChangeKind::Deleted,
+        "renamed" => FileChangeKind::Renamed,
+        _ => FileChangeKind::Modified,
+    }
+}
+
+fn retry_policy() -> RetryPolicy {
+    super::retry_policy(ProviderType::GitHub)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn combines_classic_protection_and_rulesets() {
+        let branch: RepoBranch = serde_json::from_value(json!({
+            "name": "main",
+            "protected": true,
+            "protection": {
+                "enabled": true,
+                "required_status_checks": { "enforcement_level": "everyone", "contexts": ["build"] }
+            }
+        }))
+        .unwrap();
+        let rules: Vec<BranchRule> = serde_json::from_value(json!([
+            { "type": "deletion" },
+            {
+                "type": "pull_request",
+                "parameters": { "required_approving_review_count": 2 }
+            },
+            {
+                "type": "required_status_checks",
+                "parameters": {
+                    "required_status_checks": [{ "context": "build" }, { "context": "lint" }]
+                }
+            }
+        ]))
+        .unwrap();
+        let reviews = RequiredReviews {
+            required_approving_review_count: 1,
+        };
+
+        let protection = convert_branch_protection(branch, rules, Some(reviews));
+        assert!(protection.protected);
+        assert_eq!(protection.required_checks, ["build", "lint"]);
+        assert_eq!(protection.required_approvals, 2);
+        assert!(protection.blocks_direct_merge());
+
+        let unprotected: RepoBranch =
+            serde_json::from_value(json!({ "name": "dev", "protected": false })).unwrap();
+        let protection = convert_branch_protection(unprotected, Vec::new(), None);
+        assert_eq!(protection, BranchProtection::default());
+        assert!(!protection.blocks_direct_merge());
+    }
 }
@@ -0,0 +1,865 @@
+//! GitHub REST API client, used instead of the `gh` CLI when a token is configured
+//!
+//! Covers every operation the provider runs through `gh`, so servers without the CLI can work
+//! with pull requests. Auto-merge and draft changes only exist in GitHub's GraphQL API and go
+//! through its GraphQL endpoint, as do the provider's batched queries. Listings follow the
+//! `Link` header for up to [`max_pages`] pages of 100.
+
+use std::time::Duration;
+
+use backon::Retryable;
+use chrono::{DateTime, SecondsFormat, Utc};
+use reqwest::{Method, RequestBuilder, StatusCode, header::HeaderMap};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use crate::services::{
+    git_provider::{
+        self, CommitStatusState, CreateMrRequest, GuardedSend, MergeMethod, PrInfo, PrState,
+        ProviderError, ProviderType, RepoIdentifier, RetryPolicy, UpdateMrRequest, is_rate_limited,
+        max_pages, retry_after,
+    },
+    github::cli::{
+        BranchRule, IssueComment, OpenPullRequest, PrFile, PrRequestedReviewers, PrReview,
+        PrReviewComment, RepoBranch, RepoMilestone, RepoRelease, RequiredReviews,
+    },
+};
+
+/// API of github.com; GitHub Enterprise Server serves it under `https://<host>/api/v3`
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+
+/// Items requested per page of a listing, the most GitHub returns
+const PER_PAGE: usize = 100;
+
+/// A pull request as the REST API returns it
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubPullRequest {
+    pub number: u64,
+    /// GraphQL id, which auto-merge and draft changes take
+    pub node_id: String,
+    pub html_url: String,
+    /// open or closed; merged pull requests are closed with `merged_at` set
+    pub state: String,
+    pub merged_at: Option<DateTime<Utc>>,
+    /// Set on open pull requests too, to the commit of a test merge
+    pub merge_commit_sha: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
+    pub head: GitHubCommitRef,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubCommitRef {
+    pub sha: String,
+}
+
+impl GitHubPullRequest {
+    pub fn into_pr_info(self) -> PrInfo {
+        let state = if self.merged_at.is_some() {
+            PrState::Merged
+        } else if self.state == "open" {
+            PrState::Open
+        } else {
+            PrState::Closed
+        };
+        PrInfo {
+            number: self.number,
+            url: self.html_url,
+            merge_commit_sha: self.merge_commit_sha.filter(|_| state == PrState::Merged),
+            state,
+            merged_at: self.merged_at,
+            review_state: None,
+        }
+    }
+}
+
+/// GitHub error response
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubError {
+    pub message: String,
+}
+
+/// Token-backed GitHub REST API client
+#[derive(Debug, Clone)]
+pub struct GitHubApiClient {
+    base_url: String,
+    token: SecretString,
+    http_client: reqwest::Client,
+}
+
+impl GitHubApiClient {
+    /// Create new GitHub API client
+    pub fn new(base_url: String, token: SecretString) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            // GitHub rejects requests without a user agent
+            .user_agent(concat!("vibe-kanban/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+            http_client,
+        }
+    }
+
+    /// Check that the token is accepted
+    pub async fn check_auth(&self) -> Result<(), ProviderError> {
+        let _: Value = self.get_json(&format!("{}/user", self.base_url)).await?;
+        Ok(())
+    }
+
+    /// Login of the user the token belongs to
+    pub async fn authenticated_username(&self) -> Result<String, ProviderError> {
+        let user: Value = self.get_json(&format!("{}/user", self.base_url)).await?;
+        user["login"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| ProviderError::ParseError("user without a login".to_string()))
+    }
+
+    pub async fn create_pr(
+        &self,
+        repo: &RepoIdentifier,
+        req: &CreateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        // A branch on a fork is named as owner:branch
+        let head = match &req.head_repo {
+            Some(head_repo) => format!("{}:{}", head_repo.owner, req.head_branch),
+            None => req.head_branch.clone(),
+        };
+        // Looked up first, so an unknown milestone fails before the pull request exists
+        let milestone = match &req.milestone {
+            Some(title) => Some(self.find_milestone(repo, title).await?),
+            None => None,
+        };
+
+        // Not retried: a pull request that timed out may still have been created
+        let pr: GitHubPullRequest = self
+            .send(
+                self.request(Method::POST, &self.repo_url(repo, "pulls"))
+                    .json(&json!({
+                        "title": req.title,
+                        "body": req.body.as_deref().unwrap_or(""),
+                        "head": head,
+                        "base": req.base_branch,
+                        "draft": req.draft.unwrap_or(false),
+                    })),
+            )
+            .await?;
+
+        if let Some(milestone) = milestone {
+            let _: Value = self
+                .send(
+                    self.request(
+                        Method::PATCH,
+                        &self.repo_url(repo, &format!("issues/{}", pr.number)),
+                    )
+                    .json(&json!({ "milestone": milestone })),
+                )
+                .await?;
+        }
+        Ok(pr.into_pr_info())
+    }
+
+    /// Number of the open milestone with a title
+    async fn find_milestone(
+        &self,
+        repo: &RepoIdentifier,
+        title: &str,
+    ) -> Result<i64, ProviderError> {
+        self.list_milestones(repo)
+            .await?
+            .into_iter()
+            .find(|milestone| milestone.title == title)
+            .map(|milestone| milestone.number)
+            .ok_or_else(|| ProviderError::ApiError {
+                status: StatusCode::NOT_FOUND.as_u16(),
+                message: format!("No open milestone named {title}"),
+            })
+    }
+
+    pub async fn get_pr(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<GitHubPullRequest, ProviderError> {
+        self.get_json(&self.repo_url(repo, &format!("pulls/{number}")))
+            .await
+    }
+
+    /// List the pull requests opened from a branch of the repository, in any state
+    pub async fn list_prs_for_branch(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<Vec<PrInfo>, ProviderError> {
+        let head: String =
+            url::form_urlencoded::byte_serialize(format!("{}:{branch}", repo.owner).as_bytes())
+                .collect();
+        let prs: Vec<GitHubPullRequest> = self
+            .get_all(&self.repo_url(repo, &format!("pulls?state=all&head={head}")))
+            .await?;
+        Ok(prs
+            .into_iter()
+            .map(GitHubPullRequest::into_pr_info)
+            .collect())
+    }
+
+    /// List the open pull requests of the repository
+    pub async fn list_open_prs(
+        &self,
+        repo: &RepoIdentifier,
+    ) -> Result<Vec<OpenPullRequest>, ProviderError> {
+        self.get_all(&self.repo_url(repo, "pulls?state=open")).await
+    }
+
+    /// Conversation comments, oldest first; with `since`, only those updated at or after it
+    pub async fn get_issue_comments(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<IssueComment>, ProviderError> {
+        let path = format!("issues/{number}/comments{}", since_query(since));
+        self.get_all(&self.repo_url(repo, &path)).await
+    }
+
+    /// Inline review comments; with `since`, only those updated at or after it
+    pub async fn get_review_comments(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<PrReviewComment>, ProviderError> {
+        let path = format!("pulls/{number}/comments{}", since_query(since));
+        self.get_all(&self.repo_url(repo, &path)).await
+    }
+
+    pub async fn post_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        body: &str,
+    ) -> Result<IssueComment, ProviderError> {
+        // Not retried: a comment that timed out may still have been posted
+        self.send(
+            self.request(
+                Method::POST,
+                &self.repo_url(repo, &format!("issues/{number}/comments")),
+            )
+            .json(&json!({ "body": body })),
+        )
+        .await
+    }
+
+    /// Comment on `line` of `path` in the new version of the pull request, at its head commit
+    pub async fn post_review_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        path: &str,
+        line: u64,
+        body: &str,
+    ) -> Result<PrReviewComment, ProviderError> {
+        let pr = self.get_pr(repo, number).await?;
+        // Not retried: a comment that timed out may still have been posted
+        self.send(
+            self.request(
+                Method::POST,
+                &self.repo_url(repo, &format!("pulls/{number}/comments")),
+            )
+            .json(&json!({
+                "body": body,
+                "commit_id": pr.head.sha,
+                "path": path,
+                "line": line,
+                "side": "RIGHT",
+            })),
+        )
+        .await
+    }
+
+    /// Reply to the thread of an inline review comment
+    pub async fn reply_to_review_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<PrReviewComment, ProviderError> {
+        // Not retried: a reply that timed out may still have been posted
+        self.send(
+            self.request(
+                Method::POST,
+                &self.repo_url(
+                    repo,
+                    &format!("pulls/{number}/comments/{comment_id}/replies"),
+                ),
+            )
+            .json(&json!({ "body": body })),
+        )
+        .await
+    }
+
+    /// Files the pull request changes, with their patches
+    pub async fn get_files(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<PrFile>, ProviderError> {
+        self.get_all(&self.repo_url(repo, &format!("pulls/{number}/files")))
+            .await
+    }
+
+    /// Submitted reviews, oldest first
+    pub async fn get_reviews(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<PrReview>, ProviderError> {
+        self.get_all(&self.repo_url(repo, &format!("pulls/{number}/reviews")))
+            .await
+    }
+
+    pub async fn get_requested_reviewers(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<PrRequestedReviewers, ProviderError> {
+        self.get_json(&self.repo_url(repo, &format!("pulls/{number}/requested_reviewers")))
+            .await
+    }
+
+    /// A branch with the status checks its classic protection requires
+    pub async fn get_branch(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<RepoBranch, ProviderError> {
+        self.get_json(&self.repo_url(repo, &format!("branches/{branch}")))
+            .await
+    }
+
+    /// Ruleset rules that apply to a branch
+    pub async fn get_branch_rules(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<Vec<BranchRule>, ProviderError> {
+        self.get_all(&self.repo_url(repo, &format!("rules/branches/{branch}")))
+            .await
+    }
+
+    /// Review requirements of a branch's classic protection. Fails for tokens that don't
+    /// administer the repository and for branches that require no reviews.
+    pub async fn get_required_reviews(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<RequiredReviews, ProviderError> {
+        self.get_json(&self.repo_url(
+            repo,
+            &format!("branches/{branch}/protection/required_pull_request_reviews"),
+        ))
+        .await
+    }
+
+    /// Log of a GitHub Actions job, as plain text. GitHub answers with a redirect to a
+    /// short-lived download URL, which is followed without the token.
+    pub async fn get_job_logs(
+        &self,
+        repo: &RepoIdentifier,
+        job_id: &str,
+    ) -> Result<String, ProviderError> {
+        let url = self.repo_url(repo, &format!("actions/jobs/{job_id}/logs"));
+        (|| async {
+            let response = self
+                .request(Method::GET, &url)
+                .send_guarded(ProviderType::GitHub)
+                .await?;
+
+            let status = response.status();
+            if !status.is_success() {
+                return Err(self.error_response(response).await);
+            }
+
+            response
+                .text()
+                .await
+                .map_err(|e| ProviderError::ParseError(format!("Failed to read job log: {e}")))
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .adjust(|e: &ProviderError, delay| retry_policy().delay(e, delay))
+        .await
+    }
+
+    /// Create a commit status. Retried, since posting the same status twice leaves one.
+    pub async fn set_commit_status(
+        &self,
+        repo: &RepoIdentifier,
+        sha: &str,
+        state: CommitStatusState,
+        context: &str,
+        url: Option<&str>,
+    ) -> Result<(), ProviderError> {
+        let url_path = self.repo_url(repo, &format!("statuses/{sha}"));
+        let body = json!({
+            "state": commit_status_state_name(state),
+            "context": context,
+            "target_url": url,
+        });
+        let _: Value = (|| async {
+            self.send(self.request(Method::POST, &url_path).json(&body))
+                .await
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .adjust(|e: &ProviderError, delay| retry_policy().delay(e, delay))
+        .await?;
+        Ok(())
+    }
+
+    /// Publish a release, creating its tag from the default branch if needed
+    pub async fn create_release(
+        &self,
+        repo: &RepoIdentifier,
+        tag: &str,
+        name: &str,
+        notes: &str,
+    ) -> Result<RepoRelease, ProviderError> {
+        // Not retried: a release that timed out may still have been created
+        self.send(
+            self.request(Method::POST, &self.repo_url(repo, "releases"))
+                .json(&json!({
+                    "tag_name": tag,
+                    "name": name,
+                    "body": notes,
+                })),
+        )
+        .await
+    }
+
+    /// Open milestones of the repository
+    pub async fn list_milestones(
+        &self,
+        repo: &RepoIdentifier,
+    ) -> Result<Vec<RepoMilestone>, ProviderError> {
+        self.get_all(&self.repo_url(repo, "milestones?state=open"))
+            .await
+    }
+
+    pub async fn merge_pr(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        method: MergeMethod,
+    ) -> Result<(), ProviderError> {
+        // Not retried: a merge that timed out may still have gone through
+        let _: Value = self
+            .send(
+                self.request(
+                    Method::PUT,
+                    &self.repo_url(repo, &format!("pulls/{number}/merge")),
+                )
+                .json(&json!({ "merge_method": merge_method_name(method) })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Enable auto-merge, so GitHub merges the pull request once its requirements pass
+    pub async fn enable_auto_merge(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        method: MergeMethod,
+    ) -> Result<(), ProviderError> {
+        let pr = self.get_pr(repo, number).await?;
+        self.graphql(
+            "mutation($id: ID!, $method: PullRequestMergeMethod!) {
+                enablePullRequestAutoMerge(input: {pullRequestId: $id, mergeMethod: $method}) {
+                    clientMutationId
+                }
+            }",
+            json!({
+                "id": pr.node_id,
+                "method": merge_method_name(method).to_ascii_uppercase(),
+            }),
+        )
+        .await
+    }
+
+    /// Close or reopen a pull request
+    pub async fn set_open(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        open: bool,
+    ) -> Result<PrInfo, ProviderError> {
+        let pr: GitHubPullRequest = self
+            .send(
+                self.request(
+                    Method::PATCH,
+                    &self.repo_url(repo, &format!("pulls/{number}")),
+                )
+                .json(&json!({ "state": if open { "open" } else { "closed" } })),
+            )
+            .await?;
+        Ok(pr.into_pr_info())
+    }
+
+    /// Change the title, body or base branch of a pull request
+    pub async fn update_pr(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        req: &UpdateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        let mut changes = serde_json::Map::new();
+        if let Some(title) = &req.title {
+            changes.insert("title".to_string(), json!(title));
+        }
+        if let Some(body) = &req.body {
+            changes.insert("body".to_string(), json!(body));
+        }
+        if let Some(base_branch) = &req.base_branch {
+            changes.insert("base".to_string(), json!(base_branch));
+        }
+        let pr: GitHubPullRequest = self
+            .send(
+                self.request(
+                    Method::PATCH,
+                    &self.repo_url(repo, &format!("pulls/{number}")),
+                )
+                .json(&changes),
+            )
+            .await?;
+        Ok(pr.into_pr_info())
+    }
+
+    /// Convert a pull request to a draft, or mark it ready for review
+    pub async fn set_draft(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        draft: bool,
+    ) -> Result<(), ProviderError> {
+        let pr = self.get_pr(repo, number).await?;
+        if pr.draft == draft {
+            return Ok(());
+        }
+        let query = if draft {
+            "mutation($id: ID!) {
+                convertPullRequestToDraft(input: {pullRequestId: $id}) { clientMutationId }
+            }"
+        } else {
+            "mutation($id: ID!) {
+                markPullRequestReadyForReview(input: {pullRequestId: $id}) { clientMutationId }
+            }"
+        };
+        self.graphql(query, json!({ "id": pr.node_id })).await
+    }
+
+    /// Request reviews from users, on top of the reviewers already requested
+    pub async fn request_reviewers(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        reviewers: &[String],
+    ) -> Result<(), ProviderError> {
+        let _: Value = self
+            .send(
+                self.request(
+                    Method::POST,
+                    &self.repo_url(repo, &format!("pulls/{number}/requested_reviewers")),
+                )
+                .json(&json!({ "reviewers": reviewers })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    fn repo_url(&self, repo: &RepoIdentifier, path: &str) -> String {
+        format!(
+            "{}/repos/{}/{}/{}",
+            self.base_url, repo.owner, repo.name, path
+        )
+    }
+
+    /// GraphQL endpoint: `/graphql` on github.com, `/api/graphql` on GitHub Enterprise Server
+    fn graphql_url(&self) -> String {
+        match self.base_url.strip_suffix("/api/v3") {
+            Some(host) => format!("{host}/api/graphql"),
+            None => format!("{}/graphql", self.base_url),
+        }
+    }
+
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        self.http_client
+            .request(method, url)
+            .bearer_auth(self.token.expose_secret())
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+    }
+
+    /// Send a request once and parse the response
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ProviderError> {
+        let response = request.send_guarded(ProviderType::GitHub).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(self.error_response(response).await);
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ParseError(format!("Failed to parse response: {e}")))
+    }
+
+    /// Run a GraphQL query and return the `data` of its response
+    pub async fn query(&self, query: &str, variables: Value) -> Result<Value, ProviderError> {
+        (|| self.post_graphql(query, &variables))
+            .retry(retry_policy().backoff())
+            .when(|e: &ProviderError| retry_policy().should_retry(e))
+            .adjust(|e: &ProviderError, delay| retry_policy().delay(e, delay))
+            .await
+    }
+
+    /// Run a GraphQL mutation
+    async fn graphql(&self, query: &str, variables: Value) -> Result<(), ProviderError> {
+        // Not retried: mutations that timed out may still have been applied
+        self.post_graphql(query, &variables).await.map(|_| ())
+    }
+
+    /// Send a GraphQL request once, failing on the errors GitHub reports alongside a 200
+    async fn post_graphql(&self, query: &str, variables: &Value) -> Result<Value, ProviderError> {
+        let mut response: Value = self
+            .send(
+                self.request(Method::POST, &self.graphql_url())
+                    .json(&json!({ "query": query, "variables": variables })),
+            )
+            .await?;
+        match response["errors"]
+            .as_array()
+            .and_then(|errors| errors.first())
+        {
+            Some(error) => Err(ProviderError::ApiError {
+                status: StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+                message: error["message"]
+                    .as_str()
+                    .unwrap_or("GraphQL request failed")
+                    .to_string(),
+            }),
+            None => Ok(response["data"].take()),
+        }
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, ProviderError> {
+        self.get_page(url).await.map(|(value, _)| value)
+    }
+
+    /// Every item of a listing, following the `next` link up to [`max_pages`] pages
+    async fn get_all<T: DeserializeOwned>(&self, url: &str) -> Result<Vec<T>, ProviderError> {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        let mut items = Vec::new();
+        let mut next = Some(format!("{url}{separator}per_page={PER_PAGE}"));
+        for _ in 0..max_pages() {
+            let Some(page_url) = next else {
+                return Ok(items);
+            };
+            let (mut batch, next_url): (Vec<T>, _) = self.get_page(&page_url).await?;
+            items.append(&mut batch);
+            next = next_url;
+        }
+        if next.is_some() {
+            tracing::warn!(
+                "Stopped reading {} after {} pages; raise the page limit to see the rest",
+                url,
+                max_pages()
+            );
+        }
+        Ok(items)
+    }
+
+    /// One response along with the URL of the page after it, if any
+    async fn get_page<T: DeserializeOwned>(
+        &self,
+        url: &str,
+    ) -> Result<(T, Option<String>), ProviderError> {
+        (|| async {
+            let response = self
+                .request(Method::GET, url)
+                .send_guarded(ProviderType::GitHub)
+                .await?;
+
+            let status = response.status();
+            if !status.is_success() {
+                return Err(self.error_response(response).await);
+            }
+
+            let next = next_link(response.headers());
+            let value = response
+                .json()
+                .await
+                .map_err(|e| ProviderError::ParseError(format!("Failed to parse response: {e}")))?;
+            Ok((value, next))
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .adjust(|e: &ProviderError, delay| retry_policy().delay(e, delay))
+        .await
+    }
+
+    /// Error for a failed response
+    async fn error_response(&self, response: reqwest::Response) -> ProviderError {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.text().await.unwrap_or_default();
+        self.parse_error(status, &headers, &body)
+    }
+
+    /// Parse error response
+    fn parse_error(&self, status: StatusCode, headers: &HeaderMap, body: &str) -> ProviderError {
+        let message = serde_json::from_str::<GitHubError>(body)
+            .map(|error| error.message)
+            .unwrap_or_else(|_| body.to_string());
+
+        // Secondary rate limits are answered with 403 too, which only the headers tell apart from
+        // a refused request
+        if status == StatusCode::TOO_MANY_REQUESTS
+            || (status == StatusCode::FORBIDDEN && is_rate_limited(headers))
+        {
+            return ProviderError::RateLimited {
+                message,
+                retry_after: retry_after(headers),
+            };
+        }
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return ProviderError::NotAuthenticated(format!(
+                "GitHub authentication failed: {message}"
+            ));
+        }
+
+        ProviderError::ApiError {
+            status: status.as_u16(),
+            message,
+        }
+    }
+}
+
+/// URL of the next page from a `Link` header, which GitHub leaves out on the last page
+fn next_link(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all("link")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find_map(|link| {
+            let (target, params) = link.split_once(';')?;
+            params
+                .split(';')
+                .any(|param| param.trim() == r#"rel="next""#)
+                .then(|| {
+                    target
+                        .trim()
+                        .trim_start_matches('<')
+                        .trim_end_matches('>')
+                        .to_string()
+                })
+        })
+}
+
+/// `?since=` query of comment listings, in the ISO 8601 format GitHub expects
+fn since_query(since: Option<DateTime<Utc>>) -> String {
+    since.map_or_else(String::new, |since| {
+        format!(
+            "?since={}",
+            since.to_rfc3339_opts(SecondsFormat::Secs, true)
+        )
+    })
+}
+
+fn commit_status_state_name(state: CommitStatusState) -> &'static str {
+    match state {
+        CommitStatusState::Pending => "pending",
+        CommitStatusState::Success => "success",
+        CommitStatusState::Failure => "failure",
+        CommitStatusState::Error => "error",
+    }
+}
+
+fn merge_method_name(method: MergeMethod) -> &'static str {
+    match method {
+        MergeMethod::Merge => "merge",
+        MergeMethod::Squash => "squash",
+        MergeMethod::Rebase => "rebase",
+    }
+}
+
+fn retry_policy() -> RetryPolicy {
+    git_provider::retry_policy(ProviderType::GitHub)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_the_next_link() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(next_link(&headers), None);
+        headers.insert(
+            "link",
+            r#"<https://api.github.com/repositories/1/pulls?page=3>; rel="next", <https://api.github.com/repositories/1/pulls?page=9>; rel="last""#
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            next_link(&headers).as_deref(),
+            Some("https://api.github.com/repositories/1/pulls?page=3")
+        );
+        headers.insert(
+            "link",
+            r#"<https://api.github.com/repositories/1/pulls?page=1>; rel="first""#
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(next_link(&headers), None);
+    }
+
+    #[test]
+    fn merged_pull_requests_keep_their_merge_commit() {
+        let pr: GitHubPullRequest = serde_json::from_value(json!({
+            "number": 7,
+            "node_id": "PR_kw",
+            "html_url": "https://github.com/o/r/pull/7",
+            "state": "closed",
+            "merged_at": "2024-05-01T10:00:00Z",
+            "merge_commit_sha": "abc",
+            "head": { "sha": "def" },
+        }))
+        .unwrap();
+        let info = pr.into_pr_info();
+        assert_eq!(info.state, PrState::Merged);
+        assert_eq!(info.merge_commit_sha.as_deref(), Some("abc"));
+
+        let open: GitHubPullRequest = serde_json::from_value(json!({
+            "number": 8,
+            "node_id": "PR_kx",
+            "html_url": "https://github.com/o/r/pull/8",
+            "state": "open",
+            "merged_at": null,
+            "merge_commit_sha": "test-merge",
+            "head": { "sha": "def" },
+        }))
+        .unwrap();
+        let info = open.into_pr_info();
+        assert_eq!(info.state, PrState::Open);
+        assert_eq!(info.merge_commit_sha, None);
+    }
+}
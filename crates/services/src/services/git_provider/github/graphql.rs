@@ -0,0 +1,452 @@
+//! Batched fetch of a pull request through GitHub's GraphQL API
+//!
+//! Status, checks, reviews and comments otherwise take five REST calls (or `gh` invocations) per
+//! pull request; [`PR_DETAILS_QUERY`] gets them in one. Each connection is read up to its first
+//! 100 nodes, without paging; the comments listing pages through the rest.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::services::git_provider::{
+    CheckRun, CheckStatus, MrDetails, PrInfo, PrState, ProviderError, ReviewVerdict,
+    UnifiedComment, UnifiedReview,
+};
+
+/// Query for one pull request, with variables `owner`, `name` and `number`
+pub const PR_DETAILS_QUERY: &str = "query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      number url state mergedAt
+      mergeCommit { oid }
+      commits(last: 1) {
+        nodes {
+          commit {
+            statusCheckRollup {
+              contexts(first: 100) {
+                nodes {
+                  __typename
+                  ... on CheckRun {
+                    name status conclusion detailsUrl databaseId
+                    checkSuite { app { slug } }
+                  }
+                  ... on StatusContext { context state targetUrl }
+                }
+              }
+            }
+          }
+        }
+      }
+      reviews(first: 100) {
+        nodes { author { login } state submittedAt }
+      }
+      reviewRequests(first: 100) {
+        nodes {
+          requestedReviewer {
+            __typename
+            ... on User { login }
+            ... on Team { slug }
+          }
+        }
+      }
+      comments(first: 100) {
+        nodes { id author { login } authorAssociation body createdAt url }
+      }
+      reviewThreads(first: 100) {
+        nodes {
+          comments(first: 100) {
+            nodes {
+              databaseId author { login } authorAssociation body createdAt url
+              path startLine line diffHunk
+            }
+          }
+        }
+      }
+    }
+  }
+}";
+
+#[derive(Debug, Deserialize)]
+struct Connection<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequest {
+    number: u64,
+    url: String,
+    /// OPEN, CLOSED or MERGED
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit: Option<Commit>,
+    commits: Connection<CommitNode>,
+    reviews: Connection<Review>,
+    review_requests: Connection<ReviewRequest>,
+    comments: Connection<IssueComment>,
+    review_threads: Connection<ReviewThread>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Commit {
+    oid: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitNode {
+    commit: HeadCommit,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HeadCommit {
+    /// None until a check or status is reported on the commit
+    status_check_rollup: Option<CheckRollup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRollup {
+    contexts: Connection<CheckContext>,
+}
+
+/// A check run of GitHub Actions or an app, or a commit status set through the statuses API
+#[derive(Debug, Deserialize)]
+#[serde(tag = "__typename")]
+enum CheckContext {
+    #[serde(rename_all = "camelCase")]
+    CheckRun {
+        name: String,
+        /// QUEUED, IN_PROGRESS, COMPLETED, ...
+        status: String,
+        /// Set once completed: SUCCESS, FAILURE, NEUTRAL, SKIPPED, CANCELLED, TIMED_OUT, ...
+        conclusion: Option<String>,
+        details_url: Option<String>,
+        /// Same as the job id for runs of GitHub Actions
+        database_id: Option<i64>,
+        check_suite: Option<CheckSuite>,
+    },
+    #[serde(rename_all = "camelCase")]
+    StatusContext {
+        context: String,
+        /// EXPECTED, PENDING, SUCCESS, FAILURE or ERROR
+        state: String,
+        target_url: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckSuite {
+    app: Option<App>,
+}
+
+#[derive(Debug, Deserialize)]
+struct App {
+    slug: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Review {
+    /// None when the reviewer's account was deleted
+    author: Option<Author>,
+    state: String,
+    submitted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewRequest {
+    requested_reviewer: Option<RequestedReviewer>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "__typename")]
+enum RequestedReviewer {
+    User {
+        login: String,
+    },
+    Team {
+        slug: String,
+    },
+    /// Bots and mannequins
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IssueComment {
+    /// Node id, the same as the REST API's `node_id`
+    id: String,
+    author: Option<Author>,
+    author_association: String,
+    body: String,
+    created_at: DateTime<Utc>,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewThread {
+    comments: Connection<ReviewComment>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewComment {
+    database_id: i64,
+    author: Option<Author>,
+    author_association: String,
+    body: String,
+    created_at: DateTime<Utc>,
+    url: String,
+    path: String,
+    start_line: Option<i64>,
+    line: Option<i64>,
+    diff_hunk: String,
+}
+
+/// Details from the `data` of a [`PR_DETAILS_QUERY`] response
+pub fn parse_pr_details(data: Value) -> Result<MrDetails, ProviderError> {
+    let pr: PullRequest = serde_json::from_value(data["repository"]["pullRequest"].clone())
+        .map_err(|e| {
+            ProviderError::ParseError(format!("Failed to parse pull request details: {e}"))
+        })?;
+
+    let reviews = convert_reviews(pr.reviews.nodes, pr.review_requests.nodes);
+    let checks = pr
+        .commits
+        .nodes
+        .into_iter()
+        .filter_map(|node| node.commit.status_check_rollup)
+        .flat_map(|rollup| rollup.contexts.nodes)
+        .map(convert_check)
+        .collect();
+    let mut comments: Vec<UnifiedComment> = pr
+        .comments
+        .nodes
+        .into_iter()
+        .map(|c| UnifiedComment::General {
+            id: c.id,
+            author: login(c.author),
+            author_association: c.author_association,
+            body: c.body,
+            created_at: c.created_at,
+            url: c.url,
+        })
+        .collect();
+    comments.extend(
+        pr.review_threads
+            .nodes
+            .into_iter()
+            .flat_map(|thread| thread.comments.nodes)
+            .map(|c| UnifiedComment::Review {
+                id: c.database_id,
+                author: login(c.author),
+                author_association: c.author_association,
+                body: c.body,
+                created_at: c.created_at,
+                url: c.url,
+                path: c.path,
+                start_line: c.start_line,
+                line: c.line,
+                diff_hunk: c.diff_hunk,
+            }),
+    );
+    comments.sort_by_key(|c| c.created_at());
+
+    let state = match pr.state.as_str() {
+        "OPEN" => PrState::Open,
+        "MERGED" => PrState::Merged,
+        "CLOSED" => PrState::Closed,
+        _ => PrState::Unknown,
+    };
+    let info = PrInfo {
+        number: pr.number,
+        url: pr.url,
+        state,
+        merged_at: pr.merged_at,
+        // Only a merged pull request's merge commit is on the target branch
+        merge_commit_sha: pr
+            .merge_commit
+            .filter(|_| state == PrState::Merged)
+            .map(|commit| commit.oid),
+        review_state: None,
+    }
+    .with_reviews(&reviews);
+
+    Ok(MrDetails {
+        info,
+        checks,
+        reviews,
+        comments,
+    })
+}
+
+fn login(author: Option<Author>) -> String {
+    author.map(|author| author.login).unwrap_or_default()
+}
+
+fn convert_check(context: CheckContext) -> CheckRun {
+    match context {
+        CheckContext::CheckRun {
+            name,
+            status,
+            conclusion,
+            details_url,
+            database_id,
+            check_suite,
+        } => {
+            let status = match (status.as_str(), conclusion.as_deref()) {
+                ("COMPLETED", Some("SUCCESS")) => CheckStatus::Success,
+                ("COMPLETED", Some("NEUTRAL" | "SKIPPED")) => CheckStatus::Skipped,
+                ("COMPLETED", _) => CheckStatus::Failure,
+                _ => CheckStatus::Pending,
+            };
+            // Only GitHub Actions serves logs; other apps keep theirs behind the details URL
+            let actions = check_suite
+                .and_then(|suite| suite.app)
+                .is_some_and(|app| app.slug == "github-actions");
+            CheckRun {
+                name,
+                status,
+                url: details_url,
+                id: database_id.filter(|_| actions).map(|id| id.to_string()),
+            }
+        }
+        CheckContext::StatusContext {
+            context,
+            state,
+            target_url,
+        } => {
+            let status = match state.as_str() {
+                "SUCCESS" => CheckStatus::Success,
+                "FAILURE" | "ERROR" => CheckStatus::Failure,
+                _ => CheckStatus::Pending,
+            };
+            CheckRun {
+                name: context,
+                status,
+                url: target_url,
+                id: None,
+            }
+        }
+    }
+}
+
+/// Same rules as the REST conversion: dismissed reviews and drafts are left out, and every
+/// requested user or team adds a pending review
+fn convert_reviews(reviews: Vec<Review>, requests: Vec<ReviewRequest>) -> Vec<UnifiedReview> {
+    let submitted = reviews.into_iter().filter_map(|review| {
+        let verdict = match review.state.as_str() {
+            "APPROVED" => ReviewVerdict::Approved,
+            "CHANGES_REQUESTED" => ReviewVerdict::ChangesRequested,
+            "COMMENTED" => ReviewVerdict::Commented,
+            _ => return None,
+        };
+        Some(UnifiedReview {
+            reviewer: login(review.author),
+            verdict,
+            submitted_at: review.submitted_at,
+        })
+    });
+    let pending = requests
+        .into_iter()
+        .filter_map(|request| match request.requested_reviewer? {
+            RequestedReviewer::User { login } => Some(login),
+            RequestedReviewer::Team { slug } => Some(slug),
+            RequestedReviewer::Other => None,
+        })
+        .map(|reviewer| UnifiedReview {
+            reviewer,
+            verdict: ReviewVerdict::Pending,
+            submitted_at: None,
+        });
+    submitted.chain(pending).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::services::git_provider::ReviewState;
+
+    #[test]
+    fn one_response_holds_status_checks_reviews_and_comments() {
+        let data = json!({
+            "repository": {
+                "pullRequest": {
+                    "number": 42,
+                    "url": "https://github.com/acme/app/pull/42",
+                    "state": "OPEN",
+                    "mergedAt": null,
+                    "mergeCommit": { "oid": "abc123" },
+                    "commits": { "nodes": [{ "commit": { "statusCheckRollup": { "contexts": { "nodes": [
+                        { "__typename": "CheckRun", "name": "test", "status": "COMPLETED",
+                          "conclusion": "FAILURE", "detailsUrl": "https://ci.example/1",
+                          "databaseId": 1234, "checkSuite": { "app": { "slug": "github-actions" } } },
+                        { "__typename": "CheckRun", "name": "lint", "status": "IN_PROGRESS",
+                          "conclusion": null, "detailsUrl": null, "databaseId": 1235,
+                          "checkSuite": { "app": { "slug": "lint-bot" } } },
+                        { "__typename": "StatusContext", "context": "deploy", "state": "SUCCESS",
+                          "targetUrl": null }
+                    ] } } } }] },
+                    "reviews": { "nodes": [
+                        { "author": { "login": "ann" }, "state": "APPROVED",
+                          "submittedAt": "2025-01-01T12:00:00Z" },
+                        { "author": null, "state": "DISMISSED", "submittedAt": null }
+                    ] },
+                    "reviewRequests": { "nodes": [
+                        { "requestedReviewer": { "__typename": "Team", "slug": "core" } },
+                        { "requestedReviewer": { "__typename": "Bot" } }
+                    ] },
+                    "comments": { "nodes": [
+                        { "id": "IC_1", "author": { "login": "bob" }, "authorAssociation": "MEMBER",
+                          "body": "Later", "createdAt": "2025-01-01T13:00:00Z",
+                          "url": "https://github.com/acme/app/pull/42#issuecomment-1" }
+                    ] },
+                    "reviewThreads": { "nodes": [{ "comments": { "nodes": [
+                        { "databaseId": 7, "author": { "login": "ann" }, "authorAssociation": "OWNER",
+                          "body": "Rename this", "createdAt": "2025-01-01T12:30:00Z",
+                          "url": "https://github.com/acme/app/pull/42#discussion_r7",
+                          "path": "src/lib.rs", "startLine": null, "line": 3,
+                          "diffHunk": "@@ -1,2 +1,3 @@" }
+                    ] } }] }
+                }
+            }
+        });
+
+        let details = parse_pr_details(data).unwrap();
+        assert_eq!(details.info.number, 42);
+        assert_eq!(details.info.state, PrState::Open);
+        assert_eq!(details.info.merge_commit_sha, None);
+        assert_eq!(details.info.review_state, Some(ReviewState::Approved));
+
+        let checks: Vec<_> = details.checks.iter().map(|c| c.status).collect();
+        assert_eq!(
+            checks,
+            [
+                CheckStatus::Failure,
+                CheckStatus::Pending,
+                CheckStatus::Success
+            ]
+        );
+        let ids: Vec<_> = details.checks.iter().map(|c| c.id.as_deref()).collect();
+        assert_eq!(ids, [Some("1234"), None, None]);
+
+        let reviewers: Vec<_> = details
+            .reviews
+            .iter()
+            .map(|r| r.reviewer.as_str())
+            .collect();
+        assert_eq!(reviewers, ["ann", "core"]);
+
+        let keys: Vec<_> = details.comments.iter().map(UnifiedComment::key).collect();
+        assert_eq!(keys, ["review:7", "general:IC_1"]);
+    }
+}
@@ -1,10 +1,8 @@
 //! GitLab provider implementation
 //!
-//! Uses `glab` CLI for core MR operations (create, list, status).
-//! Uses REST API for comments (requires GitLab token in config).
-//!
-//! If `glab` CLI is authenticated, core MR operations just work.
-//! For comments, user must configure GitLab API token in app settings.
+//! Uses the REST API for every MR operation when a GitLab token is configured, so `glab` is not
+//! needed at all. Without a token, core MR operations (create, list, status, merge) fall back to
+//! the `glab` CLI, and comments, diffs and reviews are unavailable.
 
 mod api;
 mod cli;
@@ -16,13 +14,14 @@ pub use cli::{GlabCli, GlabCliError};
 
 use self::api::GitLabApiClient;
 use super::{
-    CreateMrRequest, GitProvider, PrInfo, ProviderError, ProviderType, RepoIdentifier,
-    UnifiedComment,
+    BranchProtection, CheckRun, CommitStatusState, CreateMrRequest, FileDiff, GitProvider,
+    MergeMethod, Milestone, PrInfo, ProviderError, ProviderType, ReleaseInfo, RepoIdentifier,
+    ReviewRequest, UnifiedComment, UnifiedReview, UpdateMrRequest,
 };
 
 /// GitLab provider implementation
 ///
-/// Core MR operations use glab CLI.
+/// Prefers the API when a token is configured, falling back to the glab CLI.
 /// Comments require API token (configured in app settings).
 #[derive(Debug, Clone)]
 pub struct GitLabProvider {
@@ -37,8 +36,8 @@ pub struct GitLabProvider {
 impl GitLabProvider {
     /// Create new GitLab provider
     ///
-    /// - For core MR operations: uses `glab` CLI (requires `glab auth login`)
-    /// - For comments: requires `GITLAB_TOKEN` env var or config setting
+    /// - With a `GITLAB_TOKEN` env var or config setting: uses the API for everything
+    /// - Without one: uses `glab` CLI for core MR operations (requires `glab auth login`)
     ///
     /// For self-hosted instances, set `GITLAB_BASE_URL` environment variable.
     pub fn new() -> Self {
@@ -130,7 +129,7 @@ impl From<GlabCliError> for ProviderError {
                 cli_name: "glab".into(),
             },
             GlabCliError::AuthFailed(msg) => ProviderError::NotAuthenticated(msg),
-            GlabCliError::CommandFailed(msg) => ProviderError::CommandFailed(msg),
+            GlabCliError::CommandFailed(msg) => ProviderError::command_failed(msg),
             GlabCliError::UnexpectedOutput(msg) => ProviderError::ParseError(msg),
             GlabCliError::NotSupported(msg) => ProviderError::NotSupported { feature: msg },
         }
@@ -144,6 +143,10 @@ impl GitProvider for GitLabProvider {
     }
 
     async fn check_auth(&self) -> Result<(), ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.check_auth().await;
+        }
+
         // Check CLI auth (for core MR operations)
         let cli = self.cli.clone();
         tokio::task::spawn_blocking(move || cli.check_auth())
@@ -152,11 +155,27 @@ impl GitProvider for GitLabProvider {
             .map_err(ProviderError::from)
     }
 
+    async fn authenticated_username(&self) -> Result<String, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.authenticated_username().await;
+        }
+
+        let cli = self.cli.clone();
+        tokio::task::spawn_blocking(move || cli.authenticated_username())
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)
+    }
+
     async fn create_merge_request(
         &self,
         repo: &RepoIdentifier,
         req: &CreateMrRequest,
     ) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.create_mr(repo, req).await;
+        }
+
         let cli = self.cli.clone();
         let repo_clone = repo.clone();
         let req_clone = req.clone();
@@ -172,6 +191,10 @@ impl GitProvider for GitLabProvider {
         repo: &RepoIdentifier,
         number: u64,
     ) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.get_mr_status(repo, number).await;
+        }
+
         let cli = self.cli.clone();
         let repo_clone = repo.clone();
 
@@ -186,6 +209,10 @@ impl GitProvider for GitLabProvider {
         repo: &RepoIdentifier,
         branch: &str,
     ) -> Result<Vec<PrInfo>, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.list_mrs_for_branch(repo, branch).await;
+        }
+
         let cli = self.cli.clone();
         let repo_clone = repo.clone();
         let branch_clone = branch.to_string();
@@ -196,6 +223,25 @@ impl GitProvider for GitLabProvider {
             .map_err(ProviderError::from)
     }
 
+    async fn list_mrs_for_reviewer(
+        &self,
+        repo: &RepoIdentifier,
+        username: &str,
+    ) -> Result<Vec<ReviewRequest>, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.list_mrs_for_reviewer(repo, username).await;
+        }
+
+        let cli = self.cli.clone();
+        let repo_clone = repo.clone();
+        let username = username.to_string();
+
+        tokio::task::spawn_blocking(move || cli.list_mrs_for_reviewer(&repo_clone, &username))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)
+    }
+
     async fn get_comments(
         &self,
         repo: &RepoIdentifier,
@@ -214,4 +260,311 @@ impl GitProvider for GitLabProvider {
         );
         Ok(vec![])
     }
+
+    async fn get_diff(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<FileDiff>, ProviderError> {
+        match self.api_client {
+            Some(ref api_client) => api_client.get_diffs(repo, number).await,
+            None => Err(ProviderError::NotSupported {
+                feature: "merge request diffs on GitLab without an API token".to_string(),
+            }),
+        }
+    }
+
+    async fn get_reviews(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<UnifiedReview>, ProviderError> {
+        match self.api_client {
+            Some(ref api_client) => api_client.get_reviews(repo, number).await,
+            None => Err(ProviderError::NotSupported {
+                feature: "merge request reviews on GitLab without an API token".to_string(),
+            }),
+        }
+    }
+
+    async fn get_checks(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<CheckRun>, ProviderError> {
+        match self.api_client {
+            Some(ref api_client) => api_client.get_checks(repo, number).await,
+            None => Err(ProviderError::NotSupported {
+                feature: "merge request pipelines on GitLab without an API token".to_string(),
+            }),
+        }
+    }
+
+    async fn get_check_logs(
+        &self,
+        repo: &RepoIdentifier,
+        _number: u64,
+        check_id: &str,
+    ) -> Result<String, ProviderError> {
+        match self.api_client {
+            Some(ref api_client) => api_client.get_job_trace(repo, check_id).await,
+            None => Err(ProviderError::NotSupported {
+                feature: "CI job logs on GitLab without an API token".to_string(),
+            }),
+        }
+    }
+
+    async fn set_commit_status(
+        &self,
+        repo: &RepoIdentifier,
+        sha: &str,
+        state: CommitStatusState,
+        context: &str,
+        url: Option<&str>,
+    ) -> Result<(), ProviderError> {
+        match self.api_client {
+            Some(ref api_client) => {
+                api_client
+                    .set_commit_status(repo, sha, state, context, url)
+                    .await
+            }
+            None => Err(ProviderError::NotSupported {
+                feature: "commit statuses on GitLab without an API token".to_string(),
+            }),
+        }
+    }
+
+    async fn create_release(
+        &self,
+        repo: &RepoIdentifier,
+        tag: &str,
+        name: &str,
+        notes: &str,
+    ) -> Result<ReleaseInfo, ProviderError> {
+        match self.api_client {
+            Some(ref api_client) => api_client.create_release(repo, tag, name, notes).await,
+            None => Err(ProviderError::NotSupported {
+                feature: "releases on GitLab without an API token".to_string(),
+            }),
+        }
+    }
+
+    async fn get_branch_protection(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<BranchProtection, ProviderError> {
+        match self.api_client {
+            Some(ref api_client) => api_client.get_branch_protection(repo, branch).await,
+            None => Err(ProviderError::NotSupported {
+                feature: "branch protection on GitLab without an API token".to_string(),
+            }),
+        }
+    }
+
+    async fn post_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        match self.api_client {
+            Some(ref api_client) => api_client.post_note(repo, number, body).await,
+            None => Err(ProviderError::NotSupported {
+                feature: "posting merge request comments on GitLab without an API token"
+                    .to_string(),
+            }),
+        }
+    }
+
+    async fn post_review_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        path: &str,
+        line: u64,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        match self.api_client {
+            Some(ref api_client) => {
+                api_client
+                    .post_diff_note(repo, number, path, line, body)
+                    .await
+            }
+            None => Err(ProviderError::NotSupported {
+                feature: "posting review comments on GitLab without an API token".to_string(),
+            }),
+        }
+    }
+
+    async fn reply_to_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        thread_id: &str,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        let Some(ref api_client) = self.api_client else {
+            return Err(ProviderError::NotSupported {
+                feature: "replying to merge request comments on GitLab without an API token"
+                    .to_string(),
+            });
+        };
+        let note_id = thread_id.parse().map_err(|_| ProviderError::ApiError {
+            status: 404,
+            message: format!("No note with id {thread_id}"),
+        })?;
+        api_client.reply_to_note(repo, number, note_id, body).await
+    }
+
+    async fn list_milestones(
+        &self,
+        repo: &RepoIdentifier,
+    ) -> Result<Vec<Milestone>, ProviderError> {
+        match self.api_client {
+            Some(ref api_client) => api_client.list_milestones(repo).await,
+            None => Err(ProviderError::NotSupported {
+                feature: "milestones on GitLab without an API token".to_string(),
+            }),
+        }
+    }
+
+    async fn merge_mr(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        method: MergeMethod,
+    ) -> Result<PrInfo, ProviderError> {
+        // The merge endpoint cannot rebase; glab rebases first and then merges
+        if let Some(ref api_client) = self.api_client
+            && method != MergeMethod::Rebase
+        {
+            return api_client
+                .merge_mr(repo, number, method == MergeMethod::Squash, false)
+                .await;
+        }
+
+        let cli = self.cli.clone();
+        let repo_clone = repo.clone();
+        tokio::task::spawn_blocking(move || cli.merge_mr(&repo_clone, number, method))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn enable_auto_merge(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        method: MergeMethod,
+    ) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client
+            && method != MergeMethod::Rebase
+        {
+            return api_client
+                .merge_mr(repo, number, method == MergeMethod::Squash, true)
+                .await;
+        }
+
+        let cli = self.cli.clone();
+        let repo_clone = repo.clone();
+        tokio::task::spawn_blocking(move || cli.enable_auto_merge(&repo_clone, number, method))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn close_mr(&self, repo: &RepoIdentifier, number: u64) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.set_open(repo, number, false).await;
+        }
+
+        let cli = self.cli.clone();
+        let repo_clone = repo.clone();
+        tokio::task::spawn_blocking(move || cli.close_mr(&repo_clone, number))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn reopen_mr(&self, repo: &RepoIdentifier, number: u64) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.set_open(repo, number, true).await;
+        }
+
+        let cli = self.cli.clone();
+        let repo_clone = repo.clone();
+        tokio::task::spawn_blocking(move || cli.reopen_mr(&repo_clone, number))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn update_mr(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        req: &UpdateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.update_mr(repo, number, req).await;
+        }
+
+        let cli = self.cli.clone();
+        let repo_clone = repo.clone();
+        let req = req.clone();
+        tokio::task::spawn_blocking(move || cli.update_mr(&repo_clone, number, &req))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn set_draft(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        draft: bool,
+    ) -> Result<PrInfo, ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.set_draft(repo, number, draft).await;
+        }
+
+        let cli = self.cli.clone();
+        let repo_clone = repo.clone();
+        tokio::task::spawn_blocking(move || cli.set_draft(&repo_clone, number, draft))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)?;
+
+        self.get_mr_status(repo, number).await
+    }
+
+    async fn request_reviewers(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        reviewers: &[String],
+    ) -> Result<(), ProviderError> {
+        if let Some(ref api_client) = self.api_client {
+            return api_client.request_reviewers(repo, number, reviewers).await;
+        }
+
+        let cli = self.cli.clone();
+        let repo_clone = repo.clone();
+        let reviewers = reviewers.to_vec();
+        tokio::task::spawn_blocking(move || cli.request_reviewers(&repo_clone, number, &reviewers))
+            .await
+            .map_err(|e| ProviderError::CommandFailed(format!("Task join error: {e}")))?
+            .map_err(ProviderError::from)
+    }
 }
@@ -1,17 +1,40 @@
-//! GitLab REST API client - used only for features not available via CLI
+//! GitLab REST API client - used for every MR operation once a token is configured
 //!
 //! Currently supports:
+//! - Checking the token, creating MRs and reading their status (requires API token)
+//! - Closing and reopening MRs (requires API token)
 //! - Fetching MR comments/notes (requires API token)
+//! - Merging MRs (requires API token)
+//! - Marking MRs as draft or ready (requires API token)
+//! - Fetching MR approvals and reviewer states (requires API token)
+//! - Listing project milestones (requires API token)
+//! - Posting MR comments, inline diff comments and replies to MR discussions (requires API token)
+//! - Fetching MR diffs (requires API token)
+//! - Listing the MRs of a branch (requires API token)
+//! - Fetching the jobs of an MR's latest pipeline and their logs (requires API token)
+//! - Setting commit statuses (requires API token)
+//! - Reading branch protection and merge requirements (requires API token)
+//!
+//! Listings follow the `X-Next-Page` header for up to [`max_pages`] pages of 100.
 
 use std::time::Duration;
 
-use backon::{ExponentialBuilder, Retryable};
-use chrono::{DateTime, Utc};
-use reqwest::StatusCode;
+use backon::Retryable;
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::{StatusCode, header::HeaderMap};
 use secrecy::{ExposeSecret, SecretString};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::cli::GlabCli;
+use crate::services::git_provider::{
+    self, BranchProtection, CheckRun, CheckStatus, CommitStatusState, CreateMrRequest,
+    FileChangeKind, FileDiff, GuardedSend, Milestone, PrInfo, ProviderError, ProviderType,
+    ReleaseInfo, RepoIdentifier, RetryPolicy, ReviewRequest, ReviewVerdict, UnifiedComment,
+    UnifiedReview, UpdateMrRequest, is_rate_limited, max_pages, retry_after,
+};
 
-use crate::services::git_provider::{ProviderError, RepoIdentifier, UnifiedComment};
+/// Items requested per page of a listing, the most GitLab returns
+const PER_PAGE: usize = 100;
 
 /// GitLab note/comment on MR
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +44,50 @@ pub struct GitLabNote {
     pub author: GitLabNoteAuthor,
     pub created_at: DateTime<Utc>,
     pub system: bool,
+    /// Set on notes of diff discussions
+    #[serde(default)]
+    pub position: Option<GitLabDiffPosition>,
+}
+
+/// Commits a merge request diff is computed from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabDiffRefs {
+    pub base_sha: String,
+    pub head_sha: String,
+    pub start_sha: String,
+}
+
+/// Merge request fields needed to position diff notes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabMergeRequestDiff {
+    /// None while GitLab is still computing the diff
+    pub diff_refs: Option<GitLabDiffRefs>,
+}
+
+/// Position of a note on a line of a merge request diff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabDiffPosition {
+    /// `text` for notes on lines
+    pub position_type: String,
+    pub base_sha: String,
+    pub start_sha: String,
+    pub head_sha: String,
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub old_line: Option<u64>,
+    pub new_line: Option<u64>,
+}
+
+/// Diff of one file of a merge request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabFileDiff {
+    pub old_path: String,
+    pub new_path: String,
+    pub new_file: bool,
+    pub renamed_file: bool,
+    pub deleted_file: bool,
+    /// Empty for binary files and collapsed diffs
+    pub diff: String,
 }
 
 /// GitLab note author
@@ -31,10 +98,112 @@ pub struct GitLabNoteAuthor {
     pub name: String,
 }
 
+/// GitLab MR discussion, a thread of notes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabDiscussion {
+    pub id: String,
+    #[serde(default)]
+    pub notes: Vec<GitLabNote>,
+}
+
+/// GitLab MR approvals response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabApprovals {
+    #[serde(default)]
+    pub approved_by: Vec<GitLabApprover>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabApprover {
+    pub user: GitLabNoteAuthor,
+}
+
+/// GitLab MR reviewer with their review state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabReviewer {
+    pub user: GitLabNoteAuthor,
+    /// unreviewed, reviewed, requested_changes or approved
+    pub state: String,
+}
+
+/// GitLab project milestone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabMilestone {
+    pub id: u64,
+    pub title: String,
+    pub description: Option<String>,
+    pub due_date: Option<NaiveDate>,
+}
+
+/// GitLab pipeline of a merge request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabPipeline {
+    pub id: u64,
+}
+
+/// GitLab CI job of a pipeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabJob {
+    pub id: u64,
+    pub name: String,
+    /// created, pending, running, success, failed, canceled, skipped, manual, ...
+    pub status: String,
+    pub web_url: Option<String>,
+    /// Whether the pipeline passes even when this job fails
+    #[serde(default)]
+    pub allow_failure: bool,
+}
+
+impl GitLabJob {
+    fn into_check(self) -> CheckRun {
+        let status = match self.status.as_str() {
+            "success" => CheckStatus::Success,
+            // Allowed failures don't hold the merge request back, like neutral GitHub checks
+            "failed" if self.allow_failure => CheckStatus::Skipped,
+            "failed" | "canceled" => CheckStatus::Failure,
+            "skipped" | "manual" => CheckStatus::Skipped,
+            _ => CheckStatus::Pending,
+        };
+        CheckRun {
+            name: self.name,
+            status,
+            url: self.web_url,
+            id: Some(self.id.to_string()),
+        }
+    }
+}
+
 /// GitLab project response (for getting project ID)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitLabProject {
     pub id: u64,
+    #[serde(default)]
+    pub only_allow_merge_if_pipeline_succeeds: bool,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+}
+
+/// GitLab release
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabRelease {
+    pub tag_name: String,
+    pub name: Option<String>,
+    #[serde(rename = "_links")]
+    pub links: GitLabReleaseLinks,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabReleaseLinks {
+    /// Web page of the release
+    #[serde(rename = "self")]
+    pub self_url: String,
+}
+
+/// Approval settings of a GitLab project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabApprovals {
+    #[serde(default)]
+    pub approvals_before_merge: u32,
 }
 
 /// GitLab error response
@@ -76,6 +245,107 @@ impl GitLabApiClient {
         }
     }
 
+    /// Check that the token is accepted
+    pub async fn check_auth(&self) -> Result<(), ProviderError> {
+        let _: serde_json::Value = self.get_json(&format!("{}/user", self.base_url)).await?;
+        Ok(())
+    }
+
+    /// Username of the user the token belongs to
+    pub async fn authenticated_username(&self) -> Result<String, ProviderError> {
+        let user: serde_json::Value = self.get_json(&format!("{}/user", self.base_url)).await?;
+        user["username"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| ProviderError::ParseError("user without a username".to_string()))
+    }
+
+    /// Create a merge request. For a branch on a fork, it is opened from the fork's project and
+    /// targets the repository's project.
+    pub async fn create_mr(
+        &self,
+        repo: &RepoIdentifier,
+        req: &CreateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        let target_project_id = self.get_project_id(repo).await?;
+        let source_project_id = match &req.head_repo {
+            Some(head_repo) => self.get_project_id(head_repo).await?,
+            None => target_project_id,
+        };
+        // Looked up first, so an unknown milestone fails before the merge request exists
+        let milestone_id = match &req.milestone {
+            Some(title) => Some(self.find_milestone(target_project_id, title).await?),
+            None => None,
+        };
+        // GitLab derives the draft state from the title
+        let title = draft_title(&req.title, req.draft.unwrap_or(false));
+
+        // Not retried: a merge request that timed out may still have been created
+        let response = self
+            .http_client
+            .post(format!(
+                "{}/projects/{}/merge_requests",
+                self.base_url, source_project_id
+            ))
+            .header("PRIVATE-TOKEN", self.token.expose_secret())
+            .json(&serde_json::json!({
+                "source_branch": req.head_branch,
+                "target_branch": req.base_branch,
+                "target_project_id": target_project_id,
+                "title": title,
+                "description": req.body.as_deref().unwrap_or(""),
+                "milestone_id": milestone_id,
+            }))
+            .send_guarded(ProviderType::GitLab)
+            .await?;
+        let mr = self.parse_mr_response(response).await?;
+        GlabCli::extract_mr_info(&mr).ok_or_else(|| {
+            ProviderError::ParseError(format!("Create response missing required fields: {mr}"))
+        })
+    }
+
+    /// Get the state of a merge request
+    pub async fn get_mr_status(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+    ) -> Result<PrInfo, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let mr: serde_json::Value = self
+            .get_json(&format!(
+                "{}/projects/{}/merge_requests/{}",
+                self.base_url, project_id, mr_number
+            ))
+            .await?;
+        GlabCli::extract_mr_info(&mr).ok_or_else(|| {
+            ProviderError::ParseError(format!("Merge request missing required fields: {mr}"))
+        })
+    }
+
+    /// Close a merge request without merging it, or reopen a closed one
+    pub async fn set_open(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+        open: bool,
+    ) -> Result<PrInfo, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let response = self
+            .http_client
+            .put(format!(
+                "{}/projects/{}/merge_requests/{}",
+                self.base_url, project_id, mr_number
+            ))
+            .header("PRIVATE-TOKEN", self.token.expose_secret())
+            .json(&serde_json::json!({ "state_event": if open { "reopen" } else { "close" } }))
+            .send_guarded(ProviderType::GitLab)
+            .await?;
+        let mr = self.parse_mr_response(response).await?;
+        GlabCli::extract_mr_info(&mr).ok_or_else(|| {
+            ProviderError::ParseError(format!("Update response missing required fields: {mr}"))
+        })
+    }
+
     /// Get comments/notes for merge request
     pub async fn get_comments(
         &self,
@@ -84,58 +354,803 @@ impl GitLabApiClient {
     ) -> Result<Vec<UnifiedComment>, ProviderError> {
         let project_id = self.get_project_id(repo).await?;
 
-        // Fetch general notes (comments)
-        let notes_result = (|| async {
+        let notes: Vec<GitLabNote> = self
+            .get_all(&format!(
+                "{}/projects/{}/merge_requests/{}/notes?sort=asc&order_by=created_at",
+                self.base_url, project_id, mr_number
+            ))
+            .await?;
+
+        // Convert to unified format, filtering out system notes
+        let mut unified: Vec<UnifiedComment> = notes
+            .into_iter()
+            .filter(|note| !note.system)
+            .map(|note| self.convert_note(project_id, mr_number, note))
+            .collect();
+
+        // Sort by creation time
+        unified.sort_by_key(|c| c.created_at());
+
+        Ok(unified)
+    }
+
+    /// Post a note that starts no discussion on a merge request
+    pub async fn post_note(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+
+        // Not retried: a note that timed out may still have been posted
+        let response = self
+            .http_client
+            .post(format!(
+                "{}/projects/{}/merge_requests/{}/notes",
+                self.base_url, project_id, mr_number
+            ))
+            .header("PRIVATE-TOKEN", self.token.expose_secret())
+            .json(&serde_json::json!({ "body": body }))
+            .send_guarded(ProviderType::GitLab)
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(self.error_response(response).await);
+        }
+
+        let note: GitLabNote = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ParseError(format!("Failed to parse note: {e}")))?;
+        Ok(self.convert_note(project_id, mr_number, note))
+    }
+
+    /// Start a diff discussion on `line` of `path` in the new version of a merge request
+    pub async fn post_diff_note(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+        path: &str,
+        line: u64,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let mr_url = format!(
+            "{}/projects/{}/merge_requests/{}",
+            self.base_url, project_id, mr_number
+        );
+
+        let mr: GitLabMergeRequestDiff = self.get_json(&mr_url).await?;
+        let refs = mr.diff_refs.ok_or_else(|| ProviderError::ApiError {
+            status: StatusCode::CONFLICT.as_u16(),
+            message: "The merge request diff is not ready yet".to_string(),
+        })?;
+        let position = GitLabDiffPosition {
+            position_type: "text".to_string(),
+            base_sha: refs.base_sha,
+            start_sha: refs.start_sha,
+            head_sha: refs.head_sha,
+            old_path: Some(path.to_string()),
+            new_path: Some(path.to_string()),
+            old_line: None,
+            new_line: Some(line),
+        };
+
+        // Not retried: a note that timed out may still have been posted
+        let response = self
+            .http_client
+            .post(format!("{mr_url}/discussions"))
+            .header("PRIVATE-TOKEN", self.token.expose_secret())
+            .json(&serde_json::json!({ "body": body, "position": position }))
+            .send_guarded(ProviderType::GitLab)
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(self.error_response(response).await);
+        }
+
+        let discussion: GitLabDiscussion = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ParseError(format!("Failed to parse discussion: {e}")))?;
+        let note = discussion.notes.into_iter().next().ok_or_else(|| {
+            ProviderError::ParseError(format!("Discussion {} has no notes", discussion.id))
+        })?;
+
+        Ok(UnifiedComment::Review {
+            id: note.id as i64,
+            author: note.author.username,
+            author_association: "MEMBER".to_string(),
+            body: note.body,
+            created_at: note.created_at,
+            url: format!("{mr_url}#note_{}", note.id),
+            path: path.to_string(),
+            start_line: None,
+            line: Some(line as i64),
+            diff_hunk: String::new(),
+        })
+    }
+
+    /// Reply to the discussion that contains a note
+    pub async fn reply_to_note(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+        note_id: u64,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let mr_url = format!(
+            "{}/projects/{}/merge_requests/{}",
+            self.base_url, project_id, mr_number
+        );
+
+        let discussions: Vec<GitLabDiscussion> =
+            self.get_all(&format!("{mr_url}/discussions")).await?;
+        let discussion_id = discussions
+            .into_iter()
+            .find(|d| d.notes.iter().any(|note| note.id == note_id))
+            .map(|d| d.id)
+            .ok_or_else(|| ProviderError::ApiError {
+                status: StatusCode::NOT_FOUND.as_u16(),
+                message: format!("No discussion contains note {note_id}"),
+            })?;
+
+        // Not retried: a reply that timed out may still have been posted
+        let response = self
+            .http_client
+            .post(format!("{mr_url}/discussions/{discussion_id}/notes"))
+            .header("PRIVATE-TOKEN", self.token.expose_secret())
+            .json(&serde_json::json!({ "body": body }))
+            .send_guarded(ProviderType::GitLab)
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(self.error_response(response).await);
+        }
+
+        let note: GitLabNote = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ParseError(format!("Failed to parse note: {e}")))?;
+        Ok(self.convert_note(project_id, mr_number, note))
+    }
+
+    /// Get the diff of a merge request, one entry per changed file
+    pub async fn get_diffs(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+    ) -> Result<Vec<FileDiff>, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let diffs: Vec<GitLabFileDiff> = self
+            .get_all(&format!(
+                "{}/projects/{}/merge_requests/{}/diffs",
+                self.base_url, project_id, mr_number
+            ))
+            .await?;
+
+        Ok(diffs
+            .into_iter()
+            .map(|diff| {
+                let change = if diff.new_file {
+                    FileChangeKind::Added
+                } else if diff.deleted_file {
+                    FileChangeKind::Deleted
+                } else if diff.renamed_file {
+                    FileChangeKind::Renamed
+                } else {
+                    FileChangeKind::Modified
+                };
+                FileDiff {
+                    old_path: diff.renamed_file.then_some(diff.old_path),
+                    path: diff.new_path,
+                    change,
+                    patch: Some(diff.diff).filter(|patch| !patch.is_empty()),
+                }
+            })
+            .collect())
+    }
+
+    /// List the merge requests opened from a branch, in any state, newest first
+    pub async fn list_mrs_for_branch(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<Vec<PrInfo>, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let encoded_branch: String =
+            url::form_urlencoded::byte_serialize(branch.as_bytes()).collect();
+        let mrs: Vec<serde_json::Value> = self
+            .get_all(&format!(
+                "{}/projects/{}/merge_requests?state=all&source_branch={}",
+                self.base_url, project_id, encoded_branch
+            ))
+            .await?;
+        Ok(mrs.iter().filter_map(GlabCli::extract_mr_info).collect())
+    }
+
+    /// List the open merge requests that have a user as reviewer
+    pub async fn list_mrs_for_reviewer(
+        &self,
+        repo: &RepoIdentifier,
+        username: &str,
+    ) -> Result<Vec<ReviewRequest>, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let encoded_username: String =
+            url::form_urlencoded::byte_serialize(username.as_bytes()).collect();
+        let mrs: Vec<serde_json::Value> = self
+            .get_all(&format!(
+                "{}/projects/{}/merge_requests?state=opened&reviewer_username={}",
+                self.base_url, project_id, encoded_username
+            ))
+            .await?;
+        Ok(mrs
+            .iter()
+            .filter_map(GlabCli::extract_review_request)
+            .collect())
+    }
+
+    /// Merge a merge request, squashing its commits when asked. With `when_pipeline_succeeds`
+    /// GitLab merges it once its pipeline passes instead of right away.
+    pub async fn merge_mr(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+        squash: bool,
+        when_pipeline_succeeds: bool,
+    ) -> Result<PrInfo, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+
+        // Not retried: a merge that timed out may still have gone through
+        let response = self
+            .http_client
+            .put(format!(
+                "{}/projects/{}/merge_requests/{}/merge",
+                self.base_url, project_id, mr_number
+            ))
+            .header("PRIVATE-TOKEN", self.token.expose_secret())
+            .json(&serde_json::json!({
+                "squash": squash,
+                "merge_when_pipeline_succeeds": when_pipeline_succeeds,
+            }))
+            .send_guarded(ProviderType::GitLab)
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(self.error_response(response).await);
+        }
+
+        let mr: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ParseError(format!("Failed to parse merge request: {e}")))?;
+        GlabCli::extract_mr_info(&mr).ok_or_else(|| {
+            ProviderError::ParseError(format!("Merge response missing required fields: {mr}"))
+        })
+    }
+
+    /// Mark a merge request as draft or ready. GitLab derives the draft state from the title, so
+    /// this rewrites the title's draft prefix.
+    pub async fn set_draft(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+        draft: bool,
+    ) -> Result<PrInfo, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let url = format!(
+            "{}/projects/{}/merge_requests/{}",
+            self.base_url, project_id, mr_number
+        );
+
+        let response = self
+            .http_client
+            .get(&url)
+            .header("PRIVATE-TOKEN", self.token.expose_secret())
+            .send_guarded(ProviderType::GitLab)
+            .await?;
+        let mr = self.parse_mr_response(response).await?;
+        let title = mr["title"].as_str().unwrap_or_default();
+
+        let response = self
+            .http_client
+            .put(&url)
+            .header("PRIVATE-TOKEN", self.token.expose_secret())
+            .json(&serde_json::json!({ "title": draft_title(title, draft) }))
+            .send_guarded(ProviderType::GitLab)
+            .await?;
+        let mr = self.parse_mr_response(response).await?;
+        GlabCli::extract_mr_info(&mr).ok_or_else(|| {
+            ProviderError::ParseError(format!("Update response missing required fields: {mr}"))
+        })
+    }
+
+    /// Change the title, description or target branch of a merge request. A new title keeps the
+    /// draft prefix of the current one, so retitling doesn't mark a draft as ready.
+    pub async fn update_mr(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+        req: &UpdateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let url = format!(
+            "{}/projects/{}/merge_requests/{}",
+            self.base_url, project_id, mr_number
+        );
+
+        let mut changes = serde_json::Map::new();
+        if let Some(title) = &req.title {
             let response = self
                 .http_client
-                .get(format!(
-                    "{}/projects/{}/merge_requests/{}/notes",
-                    self.base_url, project_id, mr_number
+                .get(&url)
+                .header("PRIVATE-TOKEN", self.token.expose_secret())
+                .send_guarded(ProviderType::GitLab)
+                .await?;
+            let mr = self.parse_mr_response(response).await?;
+            let draft = mr["draft"].as_bool().unwrap_or(false);
+            changes.insert("title".to_string(), draft_title(title, draft).into());
+        }
+        if let Some(body) = &req.body {
+            changes.insert("description".to_string(), body.clone().into());
+        }
+        if let Some(base_branch) = &req.base_branch {
+            changes.insert("target_branch".to_string(), base_branch.clone().into());
+        }
+
+        let response = self
+            .http_client
+            .put(&url)
+            .header("PRIVATE-TOKEN", self.token.expose_secret())
+            .json(&changes)
+            .send_guarded(ProviderType::GitLab)
+            .await?;
+        let mr = self.parse_mr_response(response).await?;
+        GlabCli::extract_mr_info(&mr).ok_or_else(|| {
+            ProviderError::ParseError(format!("Update response missing required fields: {mr}"))
+        })
+    }
+
+    /// Add users, by username, to the reviewers of a merge request
+    pub async fn request_reviewers(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+        reviewers: &[String],
+    ) -> Result<(), ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let url = format!(
+            "{}/projects/{}/merge_requests/{}",
+            self.base_url, project_id, mr_number
+        );
+
+        // The API replaces the reviewers with the ids it is given, so keep the current ones
+        let mr: serde_json::Value = self.get_json(&url).await?;
+        let mut reviewer_ids: Vec<u64> = mr["reviewers"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|reviewer| reviewer["id"].as_u64())
+            .collect();
+        for username in reviewers {
+            let encoded_username: String =
+                url::form_urlencoded::byte_serialize(username.as_bytes()).collect();
+            let users: Vec<GitLabNoteAuthor> = self
+                .get_json(&format!(
+                    "{}/users?username={}",
+                    self.base_url, encoded_username
                 ))
+                .await?;
+            let user = users
+                .into_iter()
+                .next()
+                .ok_or_else(|| ProviderError::ApiError {
+                    status: StatusCode::NOT_FOUND.as_u16(),
+                    message: format!("No GitLab user named {username}"),
+                })?;
+            if !reviewer_ids.contains(&user.id) {
+                reviewer_ids.push(user.id);
+            }
+        }
+
+        let response = self
+            .http_client
+            .put(&url)
+            .header("PRIVATE-TOKEN", self.token.expose_secret())
+            .json(&serde_json::json!({ "reviewer_ids": reviewer_ids }))
+            .send_guarded(ProviderType::GitLab)
+            .await?;
+        self.parse_mr_response(response).await?;
+        Ok(())
+    }
+
+    /// Get approvals and reviewer states of a merge request. Reviewer states need GitLab 16.x;
+    /// on older instances only approvals are reported.
+    pub async fn get_reviews(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+    ) -> Result<Vec<UnifiedReview>, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let mr_url = format!(
+            "{}/projects/{}/merge_requests/{}",
+            self.base_url, project_id, mr_number
+        );
+
+        let approvals: GitLabApprovals = self.get_json(&format!("{mr_url}/approvals")).await?;
+        let reviewers: Vec<GitLabReviewer> = self
+            .get_json(&format!("{mr_url}/reviewers"))
+            .await
+            .unwrap_or_else(|e| {
+                tracing::debug!("GitLab reviewer states unavailable: {}", e);
+                vec![]
+            });
+
+        let mut reviews: Vec<UnifiedReview> = approvals
+            .approved_by
+            .into_iter()
+            .map(|approver| UnifiedReview {
+                reviewer: approver.user.username,
+                verdict: ReviewVerdict::Approved,
+                submitted_at: None,
+            })
+            .collect();
+        for reviewer in reviewers {
+            let verdict = match reviewer.state.as_str() {
+                "requested_changes" => ReviewVerdict::ChangesRequested,
+                "reviewed" => ReviewVerdict::Commented,
+                "unreviewed" => ReviewVerdict::Pending,
+                // Already reported through the approvals
+                _ => continue,
+            };
+            // An approval outranks a stale reviewer state of the same user
+            if reviews.iter().any(|r| r.reviewer == reviewer.user.username) {
+                continue;
+            }
+            reviews.push(UnifiedReview {
+                reviewer: reviewer.user.username,
+                verdict,
+                submitted_at: None,
+            });
+        }
+
+        Ok(reviews)
+    }
+
+    /// Jobs of the latest pipeline of a merge request, as checks; none before it has a pipeline
+    pub async fn get_checks(
+        &self,
+        repo: &RepoIdentifier,
+        mr_number: u64,
+    ) -> Result<Vec<CheckRun>, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        // Newest first
+        let pipelines: Vec<GitLabPipeline> = self
+            .get_json(&format!(
+                "{}/projects/{}/merge_requests/{}/pipelines?per_page=1",
+                self.base_url, project_id, mr_number
+            ))
+            .await?;
+        let Some(pipeline) = pipelines.first() else {
+            return Ok(Vec::new());
+        };
+
+        let jobs: Vec<GitLabJob> = self
+            .get_all(&format!(
+                "{}/projects/{}/pipelines/{}/jobs",
+                self.base_url, project_id, pipeline.id
+            ))
+            .await?;
+        Ok(jobs.into_iter().map(GitLabJob::into_check).collect())
+    }
+
+    /// Log of a CI job, as plain text
+    pub async fn get_job_trace(
+        &self,
+        repo: &RepoIdentifier,
+        job_id: &str,
+    ) -> Result<String, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let url = format!(
+            "{}/projects/{}/jobs/{}/trace",
+            self.base_url, project_id, job_id
+        );
+        (|| async {
+            let response = self
+                .http_client
+                .get(&url)
                 .header("PRIVATE-TOKEN", self.token.expose_secret())
-                .query(&[("sort", "asc"), ("order_by", "created_at")])
-                .send()
-                .await
-                .map_err(|e| ProviderError::CommandFailed(format!("API request failed: {e}")))?;
+                .send_guarded(ProviderType::GitLab)
+                .await?;
 
             let status = response.status();
             if !status.is_success() {
-                let error_text = response.text().await.unwrap_or_default();
-                return Err(self.parse_error(status, &error_text));
+                return Err(self.error_response(response).await);
             }
 
-            let notes: Vec<GitLabNote> = response
-                .json()
+            response
+                .text()
                 .await
-                .map_err(|e| ProviderError::ParseError(format!("Failed to parse notes: {e}")))?;
+                .map_err(|e| ProviderError::ParseError(format!("Failed to read job log: {e}")))
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .adjust(|e: &ProviderError, delay| retry_policy().delay(e, delay))
+        .await
+    }
+
+    /// Set a commit status, named after `context`. GitLab has no error state, so errors are
+    /// reported as failed.
+    pub async fn set_commit_status(
+        &self,
+        repo: &RepoIdentifier,
+        sha: &str,
+        state: CommitStatusState,
+        context: &str,
+        url: Option<&str>,
+    ) -> Result<(), ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let state = match state {
+            CommitStatusState::Pending => "pending",
+            CommitStatusState::Success => "success",
+            CommitStatusState::Failure | CommitStatusState::Error => "failed",
+        };
+        let url_path = format!("{}/projects/{}/statuses/{}", self.base_url, project_id, sha);
+        (|| async {
+            let response = self
+                .http_client
+                .post(&url_path)
+                .header("PRIVATE-TOKEN", self.token.expose_secret())
+                .json(&serde_json::json!({
+                    "state": state,
+                    "name": context,
+                    "target_url": url,
+                }))
+                .send_guarded(ProviderType::GitLab)
+                .await?;
+
+            let status = response.status();
+            if !status.is_success() {
+                return Err(self.error_response(response).await);
+            }
+            Ok(())
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .adjust(|e: &ProviderError, delay| retry_policy().delay(e, delay))
+        .await
+    }
+
+    /// Publish a release, creating its tag from the default branch if needed
+    pub async fn create_release(
+        &self,
+        repo: &RepoIdentifier,
+        tag: &str,
+        name: &str,
+        notes: &str,
+    ) -> Result<ReleaseInfo, ProviderError> {
+        let project: GitLabProject = self
+            .get_json(&format!(
+                "{}/projects/{}",
+                self.base_url,
+                repo.full_path().replace('/', "%2F")
+            ))
+            .await?;
+        let mut body = serde_json::json!({
+            "tag_name": tag,
+            "name": name,
+            "description": notes,
+        });
+        // Only used when the tag doesn't exist yet
+        if let Some(default_branch) = project.default_branch {
+            body["ref"] = serde_json::Value::String(default_branch);
+        }
+
+        // Not retried: a release that timed out may still have been created
+        let response = self
+            .http_client
+            .post(format!(
+                "{}/projects/{}/releases",
+                self.base_url, project.id
+            ))
+            .header("PRIVATE-TOKEN", self.token.expose_secret())
+            .json(&body)
+            .send_guarded(ProviderType::GitLab)
+            .await?;
+        if !response.status().is_success() {
+            return Err(self.error_response(response).await);
+        }
+        let release: GitLabRelease = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ParseError(format!("Failed to parse release: {e}")))?;
 
-            Ok(notes)
+        Ok(ReleaseInfo {
+            name: release.name.unwrap_or_else(|| release.tag_name.clone()),
+            tag: release.tag_name,
+            url: release.links.self_url,
         })
-        .retry(retry_config())
-        .when(|e: &ProviderError| e.should_retry())
-        .await?;
+    }
 
-        // Convert to unified format, filtering out system notes
-        let mut unified: Vec<UnifiedComment> = notes_result
+    /// Protection of a branch along with the project's merge requirements: a successful
+    /// pipeline, reported as the `pipeline` check, and approvals. Approval requirements need a
+    /// paid tier; without them none are required.
+    pub async fn get_branch_protection(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<BranchProtection, ProviderError> {
+        let project: GitLabProject = self
+            .get_json(&format!(
+                "{}/projects/{}",
+                self.base_url,
+                repo.full_path().replace('/', "%2F")
+            ))
+            .await?;
+        let encoded_branch: String =
+            url::form_urlencoded::byte_serialize(branch.as_bytes()).collect();
+        let protected = match self
+            .get_json::<serde_json::Value>(&format!(
+                "{}/projects/{}/protected_branches/{}",
+                self.base_url, project.id, encoded_branch
+            ))
+            .await
+        {
+            Ok(_) => true,
+            Err(ProviderError::ApiError { status: 404, .. }) => false,
+            Err(e) => return Err(e),
+        };
+        let required_approvals = self
+            .get_json::<GitLabApprovals>(&format!(
+                "{}/projects/{}/approvals",
+                self.base_url, project.id
+            ))
+            .await
+            .map_or(0, |approvals| approvals.approvals_before_merge);
+
+        Ok(BranchProtection {
+            protected,
+            required_checks: if project.only_allow_merge_if_pipeline_succeeds {
+                vec!["pipeline".to_string()]
+            } else {
+                Vec::new()
+            },
+            required_approvals,
+        })
+    }
+
+    /// Get the active milestones of a project
+    pub async fn list_milestones(
+        &self,
+        repo: &RepoIdentifier,
+    ) -> Result<Vec<Milestone>, ProviderError> {
+        let project_id = self.get_project_id(repo).await?;
+        let milestones: Vec<GitLabMilestone> = self
+            .get_all(&format!(
+                "{}/projects/{}/milestones?state=active",
+                self.base_url, project_id
+            ))
+            .await?;
+
+        Ok(milestones
             .into_iter()
-            .filter(|note| !note.system)
-            .map(|note| UnifiedComment::General {
-                id: note.id.to_string(),
-                author: note.author.username.clone(),
-                author_association: "MEMBER".to_string(),
-                body: note.body,
-                created_at: note.created_at,
-                url: format!(
-                    "{}/projects/{}/merge_requests/{}#note_{}",
-                    self.base_url, project_id, mr_number, note.id
-                ),
+            .map(|milestone| Milestone {
+                id: milestone.id.to_string(),
+                title: milestone.title,
+                description: milestone.description.filter(|d| !d.is_empty()),
+                due_date: milestone.due_date,
             })
-            .collect();
+            .collect())
+    }
 
-        // Sort by creation time
-        unified.sort_by_key(|c| c.created_at());
+    /// Id of the active milestone with a title
+    async fn find_milestone(&self, project_id: u64, title: &str) -> Result<u64, ProviderError> {
+        let milestones: Vec<GitLabMilestone> = self
+            .get_all(&format!(
+                "{}/projects/{}/milestones?state=active",
+                self.base_url, project_id
+            ))
+            .await?;
+        milestones
+            .into_iter()
+            .find(|milestone| milestone.title == title)
+            .map(|milestone| milestone.id)
+            .ok_or_else(|| ProviderError::ApiError {
+                status: StatusCode::NOT_FOUND.as_u16(),
+                message: format!("No active milestone named {title}"),
+            })
+    }
 
-        Ok(unified)
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, ProviderError> {
+        self.get_page(url).await.map(|(value, _)| value)
+    }
+
+    /// Every item of a listing, following `X-Next-Page` up to [`max_pages`] pages
+    async fn get_all<T: DeserializeOwned>(&self, url: &str) -> Result<Vec<T>, ProviderError> {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        let mut items = Vec::new();
+        let mut page = 1;
+        for _ in 0..max_pages() {
+            let (mut batch, next): (Vec<T>, _) = self
+                .get_page(&format!("{url}{separator}per_page={PER_PAGE}&page={page}"))
+                .await?;
+            items.append(&mut batch);
+            match next {
+                Some(next) => page = next,
+                None => return Ok(items),
+            }
+        }
+        tracing::warn!(
+            "Stopped reading {} after {} pages; raise the page limit to see the rest",
+            url,
+            max_pages()
+        );
+        Ok(items)
+    }
+
+    /// One response along with the page after it, if any
+    async fn get_page<T: DeserializeOwned>(
+        &self,
+        url: &str,
+    ) -> Result<(T, Option<u64>), ProviderError> {
+        (|| async {
+            let response = self
+                .http_client
+                .get(url)
+                .header("PRIVATE-TOKEN", self.token.expose_secret())
+                .send_guarded(ProviderType::GitLab)
+                .await?;
+
+            let status = response.status();
+            if !status.is_success() {
+                return Err(self.error_response(response).await);
+            }
+
+            let next = next_page(response.headers());
+            let value = response
+                .json()
+                .await
+                .map_err(|e| ProviderError::ParseError(format!("Failed to parse response: {e}")))?;
+            Ok((value, next))
+        })
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .adjust(|e: &ProviderError, delay| retry_policy().delay(e, delay))
+        .await
+    }
+
+    async fn parse_mr_response(
+        &self,
+        response: reqwest::Response,
+    ) -> Result<serde_json::Value, ProviderError> {
+        let status = response.status();
+        if !status.is_success() {
+            return Err(self.error_response(response).await);
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ParseError(format!("Failed to parse merge request: {e}")))
+    }
+
+    fn convert_note(&self, project_id: u64, mr_number: u64, note: GitLabNote) -> UnifiedComment {
+        UnifiedComment::General {
+            id: note.id.to_string(),
+            author: note.author.username,
+            author_association: "MEMBER".to_string(),
+            body: note.body,
+            created_at: note.created_at,
+            url: format!(
+                "{}/projects/{}/merge_requests/{}#note_{}",
+                self.base_url, project_id, mr_number, note.id
+            ),
+        }
     }
 
     /// Get project ID from path
@@ -149,14 +1164,12 @@ impl GitLabApiClient {
                 .http_client
                 .get(format!("{}/projects/{}", self.base_url, encoded_path))
                 .header("PRIVATE-TOKEN", self.token.expose_secret())
-                .send()
-                .await
-                .map_err(|e| ProviderError::CommandFailed(format!("API request failed: {e}")))?;
+                .send_guarded(ProviderType::GitLab)
+                .await?;
 
             let status = response.status();
             if !status.is_success() {
-                let error_text = response.text().await.unwrap_or_default();
-                return Err(self.parse_error(status, &error_text));
+                return Err(self.error_response(response).await);
             }
 
             let project: GitLabProject = response.json().await.map_err(|e| {
@@ -165,15 +1178,33 @@ impl GitLabApiClient {
 
             Ok(project.id)
         })
-        .retry(retry_config())
-        .when(|e: &ProviderError| e.should_retry())
+        .retry(retry_policy().backoff())
+        .when(|e: &ProviderError| retry_policy().should_retry(e))
+        .adjust(|e: &ProviderError, delay| retry_policy().delay(e, delay))
         .await?;
 
         Ok(result)
     }
 
+    /// Error for a failed response
+    async fn error_response(&self, response: reqwest::Response) -> ProviderError {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.text().await.unwrap_or_default();
+        self.parse_error(status, &headers, &body)
+    }
+
     /// Parse error response
-    fn parse_error(&self, status: StatusCode, body: &str) -> ProviderError {
+    fn parse_error(&self, status: StatusCode, headers: &HeaderMap, body: &str) -> ProviderError {
+        // A 403 with rate-limit headers is a throttled request rather than a refused one
+        if status == StatusCode::TOO_MANY_REQUESTS
+            || (status == StatusCode::FORBIDDEN && is_rate_limited(headers))
+        {
+            return ProviderError::RateLimited {
+                message: body.to_string(),
+                retry_after: retry_after(headers),
+            };
+        }
         if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
             return ProviderError::NotAuthenticated(format!(
                 "GitLab authentication failed: {}",
@@ -196,10 +1227,84 @@ impl GitLabApiClient {
     }
 }
 
-fn retry_config() -> ExponentialBuilder {
-    ExponentialBuilder::default()
-        .with_min_delay(Duration::from_secs(1))
-        .with_max_delay(Duration::from_secs(30))
-        .with_max_times(3)
-        .with_jitter()
+/// The page after the one these response headers came with; GitLab leaves `X-Next-Page` empty on
+/// the last page
+fn next_page(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get("x-next-page")?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn retry_policy() -> RetryPolicy {
+    git_provider::retry_policy(ProviderType::GitLab)
+}
+
+/// Title of a merge request with its draft prefix set or removed. GitLab also treats the older
+/// `WIP:` and bracketed prefixes as drafts, so those are removed too.
+pub fn draft_title(title: &str, draft: bool) -> String {
+    const PREFIXES: [&str; 5] = ["draft:", "[draft]", "(draft)", "wip:", "[wip]"];
+    let mut rest = title.trim_start();
+    while let Some(prefix) = PREFIXES.iter().find(|prefix| {
+        rest.get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+    }) {
+        rest = rest[prefix.len()..].trim_start();
+    }
+    if draft {
+        format!("Draft: {rest}")
+    } else {
+        rest.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draft_prefixes_are_replaced() {
+        assert_eq!(draft_title("Add login", true), "Draft: Add login");
+        assert_eq!(draft_title("Draft: Add login", true), "Draft: Add login");
+        assert_eq!(draft_title("Draft: Add login", false), "Add login");
+        assert_eq!(draft_title("[WIP] draft: Add login", false), "Add login");
+        assert_eq!(draft_title("Drafting rules", false), "Drafting rules");
+    }
+
+    #[test]
+    fn next_page_is_empty_on_the_last_page() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(next_page(&headers), None);
+        headers.insert("x-next-page", "3".parse().unwrap());
+        assert_eq!(next_page(&headers), Some(3));
+        headers.insert("x-next-page", "".parse().unwrap());
+        assert_eq!(next_page(&headers), None);
+    }
+
+    #[test]
+    fn allowed_failures_do_not_fail_the_checks() {
+        let job = |status: &str, allow_failure: bool| GitLabJob {
+            id: 7,
+            name: "test".to_string(),
+            status: status.to_string(),
+            web_url: None,
+            allow_failure,
+        };
+        assert_eq!(
+            job("failed", false).into_check().status,
+            CheckStatus::Failure
+        );
+        assert_eq!(
+            job("failed", true).into_check().status,
+            CheckStatus::Skipped
+        );
+        assert_eq!(
+            job("running", false).into_check().status,
+            CheckStatus::Pending
+        );
+        assert_eq!(job("success", false).into_check().id.as_deref(), Some("7"));
+    }
 }
@@ -0,0 +1,503 @@
+//! In-memory git provider for test mode
+//!
+//! Stands in for every provider when `utils::test_mode` is on, so end-to-end tests can create,
+//! comment on and merge pull requests without a network or credentials. All instances share one
+//! store for the life of the process; numbers count up from 1 per repository and timestamps come
+//! from `utils::test_mode::now`, so a run replays the same way every time.
+
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use utils::test_mode;
+
+use super::{
+    BranchProtection, CommitStatusState, CreateMrRequest, FileDiff, GitProvider, MergeMethod,
+    Milestone, PrInfo, PrState, ProviderError, ProviderType, ReleaseInfo, RepoIdentifier,
+    ReviewRequest, ReviewVerdict, UnifiedComment, UnifiedReview, UpdateMrRequest,
+    types::numeric_comment_id,
+};
+
+/// Host of the URLs the mock hands out, which never resolves
+const MOCK_HOST: &str = "mock.invalid";
+
+#[derive(Debug, Clone)]
+struct MockPullRequest {
+    branch: String,
+    title: String,
+    info: PrInfo,
+    draft: bool,
+    comments: Vec<UnifiedComment>,
+    /// Usernames whose review is requested
+    reviewers: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct MockStore {
+    /// Pull requests by repository path, in order of creation
+    pull_requests: HashMap<String, Vec<MockPullRequest>>,
+    next_comment_id: i64,
+}
+
+static STORE: LazyLock<Mutex<MockStore>> = LazyLock::new(Mutex::default);
+
+fn store() -> MutexGuard<'static, MockStore> {
+    STORE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Provider answering every call from the shared in-memory store
+#[derive(Debug, Clone)]
+pub struct MockProvider {
+    provider_type: ProviderType,
+}
+
+impl MockProvider {
+    /// A mock reporting itself as `provider_type`, so the UI labels it like the real one
+    pub fn new(provider_type: ProviderType) -> Self {
+        Self { provider_type }
+    }
+
+    fn with_pr<T>(
+        repo: &RepoIdentifier,
+        number: u64,
+        f: impl FnOnce(&mut MockPullRequest, &mut i64) -> T,
+    ) -> Result<T, ProviderError> {
+        let mut store = store();
+        let MockStore {
+            pull_requests,
+            next_comment_id,
+        } = &mut *store;
+        let pr = pull_requests
+            .get_mut(&repo.full_path())
+            .and_then(|prs| prs.iter_mut().find(|pr| pr.info.number == number))
+            .ok_or_else(|| ProviderError::ApiError {
+                status: 404,
+                message: format!("No pull request #{number} in {}", repo.full_path()),
+            })?;
+        Ok(f(pr, next_comment_id))
+    }
+
+    fn set_state(
+        repo: &RepoIdentifier,
+        number: u64,
+        state: PrState,
+    ) -> Result<PrInfo, ProviderError> {
+        Self::with_pr(repo, number, |pr, _| {
+            pr.info.state = state;
+            if state == PrState::Merged {
+                pr.info.merged_at = Some(test_mode::now());
+                pr.info.merge_commit_sha = Some(format!(
+                    "{:040x}",
+                    numeric_comment_id(&format!("{}#{number}", repo.full_path()))
+                ));
+            }
+            pr.info.clone()
+        })
+    }
+
+    fn comment_url(repo: &RepoIdentifier, number: u64, id: i64) -> String {
+        format!(
+            "https://{MOCK_HOST}/{}/pull/{number}#comment-{id}",
+            repo.full_path()
+        )
+    }
+}
+
+#[async_trait]
+impl GitProvider for MockProvider {
+    fn provider_type(&self) -> ProviderType {
+        self.provider_type
+    }
+
+    async fn check_auth(&self) -> Result<(), ProviderError> {
+        Ok(())
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &RepoIdentifier,
+        req: &CreateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        let mut store = store();
+        let prs = store.pull_requests.entry(repo.full_path()).or_default();
+        if prs
+            .iter()
+            .any(|pr| pr.branch == req.head_branch && pr.info.state == PrState::Open)
+        {
+            return Err(ProviderError::ApiError {
+                status: 422,
+                message: format!("A pull request for {} already exists", req.head_branch),
+            });
+        }
+        let number = prs.len() as u64 + 1;
+        let info = PrInfo {
+            number,
+            url: format!("https://{MOCK_HOST}/{}/pull/{number}", repo.full_path()),
+            state: PrState::Open,
+            merged_at: None,
+            merge_commit_sha: None,
+            review_state: None,
+        };
+        prs.push(MockPullRequest {
+            branch: req.head_branch.clone(),
+            title: req.title.clone(),
+            info: info.clone(),
+            draft: req.draft.unwrap_or(false),
+            comments: Vec::new(),
+            reviewers: Vec::new(),
+        });
+        Ok(info)
+    }
+
+    async fn get_mr_status(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<PrInfo, ProviderError> {
+        Self::with_pr(repo, number, |pr, _| pr.info.clone())
+    }
+
+    async fn list_mrs_for_branch(
+        &self,
+        repo: &RepoIdentifier,
+        branch: &str,
+    ) -> Result<Vec<PrInfo>, ProviderError> {
+        Ok(store()
+            .pull_requests
+            .get(&repo.full_path())
+            .map(|prs| {
+                prs.iter()
+                    .filter(|pr| pr.branch == branch)
+                    .map(|pr| pr.info.clone())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn list_mrs_for_reviewer(
+        &self,
+        repo: &RepoIdentifier,
+        username: &str,
+    ) -> Result<Vec<ReviewRequest>, ProviderError> {
+        Ok(store()
+            .pull_requests
+            .get(&repo.full_path())
+            .map(|prs| {
+                prs.iter()
+                    .filter(|pr| {
+                        pr.info.state == PrState::Open
+                            && pr.reviewers.iter().any(|reviewer| reviewer == username)
+                    })
+                    .map(|pr| ReviewRequest {
+                        number: pr.info.number,
+                        url: pr.info.url.clone(),
+                        title: pr.title.clone(),
+                        author: "vibe-kanban".to_string(),
+                        draft: pr.draft,
+                        updated_at: test_mode::now(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn get_comments(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<UnifiedComment>, ProviderError> {
+        Self::with_pr(repo, number, |pr, _| pr.comments.clone())
+    }
+
+    async fn post_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        Self::with_pr(repo, number, |pr, next_id| {
+            *next_id += 1;
+            let comment = UnifiedComment::General {
+                id: next_id.to_string(),
+                author: "vibe-kanban".to_string(),
+                author_association: "OWNER".to_string(),
+                body: body.to_string(),
+                created_at: test_mode::now(),
+                url: Self::comment_url(repo, number, *next_id),
+            };
+            pr.comments.push(comment.clone());
+            comment
+        })
+    }
+
+    async fn post_review_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        path: &str,
+        line: u64,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        Self::with_pr(repo, number, |pr, next_id| {
+            *next_id += 1;
+            let comment = UnifiedComment::Review {
+                id: *next_id,
+                author: "vibe-kanban".to_string(),
+                author_association: "OWNER".to_string(),
+                body: body.to_string(),
+                created_at: test_mode::now(),
+                url: Self::comment_url(repo, number, *next_id),
+                path: path.to_string(),
+                start_line: None,
+                line: Some(line as i64),
+                diff_hunk: String::new(),
+            };
+            pr.comments.push(comment.clone());
+            comment
+        })
+    }
+
+    async fn reply_to_comment(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        _thread_id: &str,
+        body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        self.post_comment(repo, number, body).await
+    }
+
+    async fn get_diff(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<FileDiff>, ProviderError> {
+        // The mock knows nothing of the commits; the local diff is what tests look at
+        Self::with_pr(repo, number, |_, _| Vec::new())
+    }
+
+    async fn get_reviews(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<UnifiedReview>, ProviderError> {
+        Self::with_pr(repo, number, |pr, _| {
+            pr.reviewers
+                .iter()
+                .map(|reviewer| UnifiedReview {
+                    reviewer: reviewer.clone(),
+                    verdict: ReviewVerdict::Pending,
+                    submitted_at: None,
+                })
+                .collect()
+        })
+    }
+
+    async fn set_commit_status(
+        &self,
+        _repo: &RepoIdentifier,
+        _sha: &str,
+        _state: CommitStatusState,
+        _context: &str,
+        _url: Option<&str>,
+    ) -> Result<(), ProviderError> {
+        // Checks aren't mocked, so statuses are accepted and dropped
+        Ok(())
+    }
+
+    async fn list_milestones(
+        &self,
+        _repo: &RepoIdentifier,
+    ) -> Result<Vec<Milestone>, ProviderError> {
+        Ok(Vec::new())
+    }
+
+    async fn get_branch_protection(
+        &self,
+        _repo: &RepoIdentifier,
+        _branch: &str,
+    ) -> Result<BranchProtection, ProviderError> {
+        Ok(BranchProtection::default())
+    }
+
+    async fn create_release(
+        &self,
+        repo: &RepoIdentifier,
+        tag: &str,
+        name: &str,
+        _notes: &str,
+    ) -> Result<ReleaseInfo, ProviderError> {
+        Ok(ReleaseInfo {
+            tag: tag.to_string(),
+            name: name.to_string(),
+            url: format!(
+                "https://github.com/{}/{}/releases/tag/{tag}",
+                repo.owner, repo.name
+            ),
+        })
+    }
+
+    async fn merge_mr(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        _method: MergeMethod,
+    ) -> Result<PrInfo, ProviderError> {
+        let (state, draft) = Self::with_pr(repo, number, |pr, _| (pr.info.state, pr.draft))?;
+        if state != PrState::Open || draft {
+            return Err(ProviderError::ApiError {
+                status: 405,
+                message: format!("Pull request #{number} is not mergeable"),
+            });
+        }
+        Self::set_state(repo, number, PrState::Merged)
+    }
+
+    async fn enable_auto_merge(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        method: MergeMethod,
+    ) -> Result<PrInfo, ProviderError> {
+        // There are no checks to wait for
+        self.merge_mr(repo, number, method).await
+    }
+
+    async fn close_mr(&self, repo: &RepoIdentifier, number: u64) -> Result<PrInfo, ProviderError> {
+        Self::set_state(repo, number, PrState::Closed)
+    }
+
+    async fn reopen_mr(&self, repo: &RepoIdentifier, number: u64) -> Result<PrInfo, ProviderError> {
+        Self::set_state(repo, number, PrState::Open)
+    }
+
+    async fn update_mr(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        req: &UpdateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        // Descriptions and base branches aren't stored, only the title shows anywhere
+        Self::with_pr(repo, number, |pr, _| {
+            if let Some(title) = &req.title {
+                pr.title = title.clone();
+            }
+            pr.info.clone()
+        })
+    }
+
+    async fn set_draft(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        draft: bool,
+    ) -> Result<PrInfo, ProviderError> {
+        Self::with_pr(repo, number, |pr, _| {
+            pr.draft = draft;
+            pr.info.clone()
+        })
+    }
+
+    async fn request_reviewers(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        reviewers: &[String],
+    ) -> Result<(), ProviderError> {
+        Self::with_pr(repo, number, |pr, _| {
+            for reviewer in reviewers {
+                if !pr.reviewers.contains(reviewer) {
+                    pr.reviewers.push(reviewer.clone());
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(branch: &str) -> CreateMrRequest {
+        CreateMrRequest {
+            title: "Add login".to_string(),
+            body: None,
+            head_branch: branch.to_string(),
+            base_branch: "main".to_string(),
+            draft: None,
+            head_repo: None,
+            milestone: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn pull_requests_go_through_their_lifecycle() {
+        let provider = MockProvider::new(ProviderType::GitHub);
+        let repo = RepoIdentifier::new_github("mock-lifecycle", "repo");
+
+        let pr = provider
+            .create_merge_request(&repo, &request("vk/login"))
+            .await
+            .unwrap();
+        assert_eq!(pr.number, 1);
+        assert_eq!(pr.url, "https://mock.invalid/mock-lifecycle/repo/pull/1");
+        assert!(
+            provider
+                .create_merge_request(&repo, &request("vk/login"))
+                .await
+                .is_err()
+        );
+
+        provider.post_comment(&repo, 1, "Looks good").await.unwrap();
+        assert_eq!(provider.get_comments(&repo, 1).await.unwrap().len(), 1);
+
+        let merged = provider
+            .merge_mr(&repo, 1, MergeMethod::Squash)
+            .await
+            .unwrap();
+        assert_eq!(merged.state, PrState::Merged);
+        assert!(merged.merge_commit_sha.is_some());
+        let listed = provider
+            .list_mrs_for_branch(&repo, "vk/login")
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].state, PrState::Merged);
+    }
+
+    #[tokio::test]
+    async fn requested_reviewers_see_the_pull_request_in_their_inbox() {
+        let provider = MockProvider::new(ProviderType::GitHub);
+        let repo = RepoIdentifier::new_github("mock-inbox", "repo");
+        provider
+            .create_merge_request(&repo, &request("vk/inbox"))
+            .await
+            .unwrap();
+
+        let reviewers = ["sam".to_string(), "kim".to_string()];
+        provider
+            .request_reviewers(&repo, 1, &reviewers)
+            .await
+            .unwrap();
+        provider
+            .request_reviewers(&repo, 1, &reviewers[..1])
+            .await
+            .unwrap();
+        assert_eq!(provider.get_reviews(&repo, 1).await.unwrap().len(), 2);
+
+        let inbox = provider.list_mrs_for_reviewer(&repo, "sam").await.unwrap();
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(inbox[0].title, "Add login");
+        assert!(
+            provider
+                .list_mrs_for_reviewer(&repo, "lee")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -1,34 +1,98 @@
 //! Git Provider Abstraction Layer
 //!
-//! Provides unified interface for GitHub and GitLab operations.
-//! Auto-detects provider from git remote URL.
+//! Provides unified interface for GitHub, GitLab, Bitbucket Cloud, Bitbucket Data Center, Azure
+//! DevOps, Gerrit and AWS CodeCommit operations.
+//! Auto-detects provider from git remote URL through the provider registry.
 
+mod azure_devops;
+mod bitbucket;
+mod bitbucket_server;
+mod breaker;
+mod codecommit;
 mod detection;
 mod error;
+mod gerrit;
 mod github;
 mod gitlab;
+mod mock;
+mod rate_limit;
+mod registry;
+mod retry;
 mod types;
 
-pub use detection::{detect_provider, detect_provider_from_url, get_remote_url};
+pub use azure_devops::AzureDevOpsProvider;
+pub use bitbucket::BitbucketProvider;
+pub use bitbucket_server::BitbucketServerProvider;
+pub use breaker::GuardedSend;
+pub use codecommit::CodeCommitProvider;
+pub use detection::{
+    ForkTopology, detect_fork, detect_provider, detect_provider_from_url, get_remote_url,
+};
 pub use error::ProviderError;
+pub use gerrit::GerritProvider;
 pub use github::GitHubProvider;
 pub use gitlab::GitLabProvider;
+pub use mock::MockProvider;
+pub use rate_limit::{RateLimitStatus, statuses as rate_limit_statuses};
+pub use registry::{
+    ProviderFactory, ProviderHostMapping, ProviderRegistry, UrlParser, configure_host_mappings,
+    normalize_host, register_provider, use_mock_providers,
+};
+pub use retry::{
+    ProviderRetryPolicy, RetryPolicy, configure_retry_policies, is_rate_limited, retry_after,
+    retry_policy,
+};
 pub use types::{
-    CreateMrRequest, PrInfo, PrState, ProviderType, RepoIdentifier, UnifiedComment,
+    BranchProtection, ChangedFile, CheckRun, CheckStatus, CommentFilter, CommitStatusState,
+    CreateMrRequest, FileChangeKind, FileDiff, MergeMethod, Milestone, MrDetails, PrInfo, PrState,
+    ProviderType, ReleaseInfo, RepoIdentifier, ReviewRequest, ReviewState, ReviewVerdict,
+    UnifiedComment, UnifiedReview, UpdateMrRequest,
 };
 
 use async_trait::async_trait;
-use std::path::Path;
+use chrono::{DateTime, Utc};
+use std::{
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Default for [`max_pages`]
+pub const DEFAULT_MAX_PAGES: usize = 20;
+
+static MAX_PAGES: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_PAGES);
 
-/// Core trait for git provider operations (GitHub, GitLab, etc.)
+/// Most pages providers read from one paginated listing, like the comments of a busy MR/PR;
+/// longer listings are cut off there
+pub fn max_pages() -> usize {
+    MAX_PAGES.load(Ordering::Relaxed)
+}
+
+/// Set [`max_pages`], e.g. after the user changes it. At least one page is always read.
+pub fn configure_max_pages(pages: usize) {
+    MAX_PAGES.store(pages.max(1), Ordering::Relaxed);
+}
+
+/// Core trait for git provider operations (GitHub, GitLab, Bitbucket, Azure DevOps, Gerrit, CodeCommit)
 #[async_trait]
 pub trait GitProvider: Send + Sync {
-    /// Returns provider type (GitHub/GitLab)
+    /// Returns provider type (GitHub/GitLab/Bitbucket/Bitbucket Data Center/Azure DevOps/Gerrit/CodeCommit)
     fn provider_type(&self) -> ProviderType;
 
     /// Check if provider CLI is authenticated
     async fn check_auth(&self) -> Result<(), ProviderError>;
 
+    /// Username of the account the provider is signed in as
+    async fn authenticated_username(&self) -> Result<String, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("looking up the signed-in user on {}", self.provider_type()),
+        })
+    }
+
+    /// Whether create_merge_request pushes the branch itself, so callers must not push it first
+    fn pushes_on_create(&self) -> bool {
+        false
+    }
+
     /// Create a merge/pull request
     async fn create_merge_request(
         &self,
@@ -50,27 +114,310 @@ pub trait GitProvider: Send + Sync {
         branch: &str,
     ) -> Result<Vec<PrInfo>, ProviderError>;
 
+    /// Open MRs/PRs of the repository whose review is requested from a user, by username
+    async fn list_mrs_for_reviewer(
+        &self,
+        _repo: &RepoIdentifier,
+        _username: &str,
+    ) -> Result<Vec<ReviewRequest>, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("review requests on {}", self.provider_type()),
+        })
+    }
+
     /// Fetch comments/notes for MR/PR
     async fn get_comments(
         &self,
         repo: &RepoIdentifier,
         number: u64,
     ) -> Result<Vec<UnifiedComment>, ProviderError>;
+
+    /// Fetch the comments created at or after `since`. Providers that can ask for recent comments
+    /// only do so; the others fetch every comment and drop the older ones.
+    async fn get_comments_since(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<UnifiedComment>, ProviderError> {
+        let mut comments = self.get_comments(repo, number).await?;
+        comments.retain(|comment| comment.created_at() >= since);
+        Ok(comments)
+    }
+
+    /// Post a general comment on an MR/PR, e.g. a status update about work done in the attempt
+    async fn post_comment(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+        _body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("posting comments on {}", self.provider_type()),
+        })
+    }
+
+    /// Post an inline review comment on `line` of `path` in the new version of the MR/PR diff,
+    /// for tooling that annotates specific lines
+    async fn post_review_comment(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+        _path: &str,
+        _line: u64,
+        _body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("posting review comments on {}", self.provider_type()),
+        })
+    }
+
+    /// Reply to a comment thread of an MR/PR. `thread_id` is the id of any comment in the thread,
+    /// as in [`UnifiedComment`], and the reply is returned in the same form.
+    async fn reply_to_comment(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+        _thread_id: &str,
+        _body: &str,
+    ) -> Result<UnifiedComment, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("replying to comments on {}", self.provider_type()),
+        })
+    }
+
+    /// Fetch the diff of an MR/PR against its target branch, one entry per changed file
+    async fn get_diff(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+    ) -> Result<Vec<FileDiff>, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("pull request diffs on {}", self.provider_type()),
+        })
+    }
+
+    /// Files an MR/PR changes. Providers without a cheaper listing derive it from `get_diff`.
+    async fn get_changed_files(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<Vec<ChangedFile>, ProviderError> {
+        let diff = self.get_diff(repo, number).await?;
+        Ok(diff.iter().map(FileDiff::changed_file).collect())
+    }
+
+    /// Fetch reviews of an MR/PR: approvals, change requests, and reviews requested but not
+    /// given yet. Summarize them with `ReviewState::summarize`.
+    async fn get_reviews(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+    ) -> Result<Vec<UnifiedReview>, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("pull request reviews on {}", self.provider_type()),
+        })
+    }
+
+    /// Fetch the CI checks on the head commit of an MR/PR
+    async fn get_checks(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+    ) -> Result<Vec<CheckRun>, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("CI checks on {}", self.provider_type()),
+        })
+    }
+
+    /// Fetch the log of the CI job behind a check of an MR/PR, by the check's `id`, as plain
+    /// text; e.g. to show an agent why its build failed
+    async fn get_check_logs(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+        _check_id: &str,
+    ) -> Result<String, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("CI job logs on {}", self.provider_type()),
+        })
+    }
+
+    /// Publish a status on a commit, shown next to CI checks and usable as a required check by
+    /// branch protection. A later status with the same `context` replaces the earlier one.
+    async fn set_commit_status(
+        &self,
+        _repo: &RepoIdentifier,
+        _sha: &str,
+        _state: CommitStatusState,
+        _context: &str,
+        _url: Option<&str>,
+    ) -> Result<(), ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("commit statuses on {}", self.provider_type()),
+        })
+    }
+
+    /// Fetch the status, checks, reviews and comments of an MR/PR at once. Providers that can
+    /// get them in a single request override this; the default makes one call for each.
+    /// Providers without checks or reviews report none.
+    async fn get_mr_details(
+        &self,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<MrDetails, ProviderError> {
+        let (info, checks, reviews, comments) = tokio::join!(
+            self.get_mr_status(repo, number),
+            self.get_checks(repo, number),
+            self.get_reviews(repo, number),
+            self.get_comments(repo, number)
+        );
+        let checks = match checks {
+            Ok(checks) => checks,
+            Err(ProviderError::NotSupported { .. }) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        let (info, reviews) = match reviews {
+            Ok(reviews) => (info?.with_reviews(&reviews), reviews),
+            Err(ProviderError::NotSupported { .. }) => (info?, Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(MrDetails {
+            info,
+            checks,
+            reviews,
+            comments: comments?,
+        })
+    }
+
+    /// Open milestones of the repository, to offer when creating an MR/PR
+    async fn list_milestones(
+        &self,
+        _repo: &RepoIdentifier,
+    ) -> Result<Vec<Milestone>, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("milestones on {}", self.provider_type()),
+        })
+    }
+
+    /// Protection of a branch MRs/PRs are merged into, to tell up front whether they can be
+    /// merged right away
+    async fn get_branch_protection(
+        &self,
+        _repo: &RepoIdentifier,
+        _branch: &str,
+    ) -> Result<BranchProtection, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("branch protection on {}", self.provider_type()),
+        })
+    }
+
+    /// Publish a release with its tag. A tag that doesn't exist yet is created from the head
+    /// of the default branch.
+    async fn create_release(
+        &self,
+        _repo: &RepoIdentifier,
+        _tag: &str,
+        _name: &str,
+        _notes: &str,
+    ) -> Result<ReleaseInfo, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("releases on {}", self.provider_type()),
+        })
+    }
+
+    /// Merge an open MR/PR. The provider enforces its own merge rules, like required checks and
+    /// approvals, and the returned status reflects the merge.
+    async fn merge_mr(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+        _method: MergeMethod,
+    ) -> Result<PrInfo, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("merging pull requests on {}", self.provider_type()),
+        })
+    }
+
+    /// Have the provider merge an open MR/PR with `method` once its required checks and
+    /// approvals pass: auto-merge on GitHub, merge when pipeline succeeds on GitLab
+    async fn enable_auto_merge(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+        _method: MergeMethod,
+    ) -> Result<PrInfo, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("auto-merge on {}", self.provider_type()),
+        })
+    }
+
+    /// Close an open MR/PR without merging it
+    async fn close_mr(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+    ) -> Result<PrInfo, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("closing pull requests on {}", self.provider_type()),
+        })
+    }
+
+    /// Reopen a closed MR/PR
+    async fn reopen_mr(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+    ) -> Result<PrInfo, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("reopening pull requests on {}", self.provider_type()),
+        })
+    }
+
+    /// Change the title, description or target branch of an open MR/PR
+    async fn update_mr(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+        _req: &UpdateMrRequest,
+    ) -> Result<PrInfo, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("editing pull requests on {}", self.provider_type()),
+        })
+    }
+
+    /// Mark an open MR/PR as a draft, or as ready for review
+    async fn set_draft(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+        _draft: bool,
+    ) -> Result<PrInfo, ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("draft pull requests on {}", self.provider_type()),
+        })
+    }
+
+    /// Ask users, by username, to review an open MR/PR, keeping the reviewers it already has
+    async fn request_reviewers(
+        &self,
+        _repo: &RepoIdentifier,
+        _number: u64,
+        _reviewers: &[String],
+    ) -> Result<(), ProviderError> {
+        Err(ProviderError::NotSupported {
+            feature: format!("requesting reviewers on {}", self.provider_type()),
+        })
+    }
 }
 
 /// Create provider from repo path (auto-detects from remote URL)
 pub fn create_provider(repo_path: &Path) -> Result<Box<dyn GitProvider>, ProviderError> {
     let (provider_type, _repo_id) = detect_provider(repo_path)?;
-    match provider_type {
-        ProviderType::GitHub => Ok(Box::new(GitHubProvider::new())),
-        ProviderType::GitLab => Ok(Box::new(GitLabProvider::new())),
-    }
+    registry::global().create(provider_type, Some(repo_path))
 }
 
 /// Create provider from known type
 pub fn create_provider_by_type(provider: ProviderType) -> Result<Box<dyn GitProvider>, ProviderError> {
-    match provider {
-        ProviderType::GitHub => Ok(Box::new(GitHubProvider::new())),
-        ProviderType::GitLab => Ok(Box::new(GitLabProvider::new())),
-    }
+    registry::global().create(provider, None)
 }
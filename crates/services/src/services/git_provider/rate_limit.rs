@@ -0,0 +1,140 @@
+//! Rate-limit quotas reported by provider APIs
+//!
+//! GitHub sends `X-RateLimit-Limit`, `X-RateLimit-Remaining` and `X-RateLimit-Reset` with its
+//! responses, GitLab the same headers without the `X-` prefix. The latest quota seen from each
+//! API is kept for the health check, and a spent quota tells a rate-limited request how long to
+//! wait when the response has no `Retry-After`.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Latest quota of a provider API, for the health check
+#[derive(Debug, Clone, Serialize, TS, PartialEq)]
+pub struct RateLimitStatus {
+    /// API the quota applies to, e.g. `GitHub API at api.github.com`
+    pub name: String,
+    /// Requests allowed per window, if the API says
+    pub limit: Option<u64>,
+    /// Requests left in the current window
+    pub remaining: u64,
+    /// When the window resets and the quota is refilled
+    pub reset_at: Option<DateTime<Utc>>,
+    pub observed_at: DateTime<Utc>,
+}
+
+fn header(headers: &HeaderMap, name: &str) -> Option<u64> {
+    [format!("x-ratelimit-{name}"), format!("ratelimit-{name}")]
+        .iter()
+        .find_map(|key| headers.get(key.as_str()))?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Quota from the rate-limit headers of a response, if it has any
+pub fn parse(name: &str, headers: &HeaderMap) -> Option<RateLimitStatus> {
+    Some(RateLimitStatus {
+        name: name.to_string(),
+        limit: header(headers, "limit"),
+        remaining: header(headers, "remaining")?,
+        reset_at: header(headers, "reset")
+            .and_then(|reset| DateTime::from_timestamp(i64::try_from(reset).ok()?, 0)),
+        observed_at: Utc::now(),
+    })
+}
+
+/// Wait until the quota resets, when the response says it is spent
+pub fn reset_wait(headers: &HeaderMap) -> Option<Duration> {
+    let quota = parse("", headers)?;
+    if quota.remaining > 0 {
+        return None;
+    }
+    Some((quota.reset_at? - Utc::now()).to_std().unwrap_or_default())
+}
+
+fn quotas() -> &'static Mutex<BTreeMap<String, RateLimitStatus>> {
+    static QUOTAS: OnceLock<Mutex<BTreeMap<String, RateLimitStatus>>> = OnceLock::new();
+    QUOTAS.get_or_init(Default::default)
+}
+
+/// Remember the quota a response of the API reports
+pub fn record(name: &str, headers: &HeaderMap) {
+    let Some(quota) = parse(name, headers) else {
+        return;
+    };
+    if quota.remaining == 0 {
+        tracing::warn!(
+            "{} rate limit spent, resets at {}",
+            name,
+            quota
+                .reset_at
+                .map_or_else(|| "an unknown time".to_string(), |at| at.to_rfc3339())
+        );
+    }
+    quotas()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(name.to_string(), quota);
+}
+
+/// Latest quota of every API seen so far, by name
+pub fn statuses() -> Vec<RateLimitStatus> {
+    let quotas = quotas()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    quotas.values().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn reads_github_and_gitlab_headers() {
+        let mut github = HeaderMap::new();
+        github.insert("x-ratelimit-limit", HeaderValue::from_static("5000"));
+        github.insert("x-ratelimit-remaining", HeaderValue::from_static("4987"));
+        github.insert("x-ratelimit-reset", HeaderValue::from_static("1445412480"));
+        let quota = parse("GitHub API at api.github.com", &github).unwrap();
+        assert_eq!(quota.limit, Some(5000));
+        assert_eq!(quota.remaining, 4987);
+        assert_eq!(
+            quota.reset_at,
+            DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+                .ok()
+                .map(|at| at.with_timezone(&Utc))
+        );
+
+        let mut gitlab = HeaderMap::new();
+        gitlab.insert("ratelimit-remaining", HeaderValue::from_static("0"));
+        let quota = parse("GitLab API at gitlab.com", &gitlab).unwrap();
+        assert_eq!((quota.limit, quota.remaining), (None, 0));
+
+        assert_eq!(parse("Gerrit API", &HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn waits_for_the_reset_only_once_the_quota_is_spent() {
+        let reset = (Utc::now() + chrono::Duration::seconds(90)).timestamp();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("3"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from(reset));
+        assert_eq!(reset_wait(&headers), None);
+
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        let wait = reset_wait(&headers).unwrap();
+        assert!(wait > Duration::from_secs(80) && wait <= Duration::from_secs(90));
+    }
+}
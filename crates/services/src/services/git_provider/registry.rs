@@ -0,0 +1,332 @@
+//! Registry of git providers
+//!
+//! Each provider registers how to recognize its remote URLs and how to build it. The built-in
+//! providers are registered on first use; downstream builds add their own with
+//! [`register_provider`] at startup, and configured host mappings route self-hosted instances
+//! whose URLs carry no recognizable name to the right provider.
+
+use std::{
+    path::Path,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{
+    AzureDevOpsProvider, BitbucketProvider, BitbucketServerProvider, CodeCommitProvider,
+    GerritProvider, GitHubProvider, GitLabProvider, GitProvider, MockProvider, ProviderError,
+    ProviderType, RepoIdentifier, detection,
+};
+use crate::services::repo::remote_location;
+
+/// Parses a remote URL into the repository it points at, if the provider hosts it
+pub type UrlParser = Arc<dyn Fn(&str) -> Option<RepoIdentifier> + Send + Sync>;
+
+/// Builds a provider, given the local repository when it is known
+pub type ProviderFactory = Arc<dyn Fn(Option<&Path>) -> Box<dyn GitProvider> + Send + Sync>;
+
+/// Remotes on a host that are served by a provider, e.g. a GitHub Enterprise or self-hosted
+/// GitLab instance under a custom domain
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct ProviderHostMapping {
+    /// Host name without scheme or port, e.g. `git.example.com`
+    pub host: String,
+    pub provider: ProviderType,
+}
+
+#[derive(Clone)]
+struct Registration {
+    provider_type: ProviderType,
+    parse_url: UrlParser,
+    create: ProviderFactory,
+}
+
+#[derive(Clone, Default)]
+pub struct ProviderRegistry {
+    /// In detection order
+    registrations: Vec<Registration>,
+    host_mappings: Vec<ProviderHostMapping>,
+}
+
+impl ProviderRegistry {
+    /// Registry with the providers that ship with the app
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        // Pushed in detection order: Azure DevOps, CodeCommit and Bitbucket Data Center come
+        // before GitLab, whose parser matches "gitlab" anywhere in the URL, and Gerrit comes
+        // last since its hosts have no fixed name
+        registry.push(ProviderType::GitHub, detection::parse_github_url, |_| {
+            Box::new(GitHubProvider::new())
+        });
+        registry.push(
+            ProviderType::AzureDevOps,
+            detection::parse_azure_devops_url,
+            |_| Box::new(AzureDevOpsProvider::new()),
+        );
+        registry.push(
+            ProviderType::CodeCommit,
+            detection::parse_codecommit_url,
+            |_| Box::new(CodeCommitProvider::new()),
+        );
+        registry.push(
+            ProviderType::BitbucketServer,
+            detection::parse_bitbucket_server_url,
+            |repo_path| {
+                Box::new(BitbucketServerProvider::new(
+                    repo_path.map(Path::to_path_buf),
+                ))
+            },
+        );
+        registry.push(ProviderType::GitLab, detection::parse_gitlab_url, |_| {
+            Box::new(GitLabProvider::new())
+        });
+        registry.push(
+            ProviderType::Bitbucket,
+            detection::parse_bitbucket_url,
+            |_| Box::new(BitbucketProvider::new()),
+        );
+        registry.push(
+            ProviderType::Gerrit,
+            detection::parse_gerrit_url,
+            |repo_path| Box::new(GerritProvider::new(repo_path.map(Path::to_path_buf))),
+        );
+        registry
+    }
+
+    fn push(
+        &mut self,
+        provider_type: ProviderType,
+        parse_url: impl Fn(&str) -> Option<RepoIdentifier> + Send + Sync + 'static,
+        create: impl Fn(Option<&Path>) -> Box<dyn GitProvider> + Send + Sync + 'static,
+    ) {
+        self.registrations.push(Registration {
+            provider_type,
+            parse_url: Arc::new(parse_url),
+            create: Arc::new(create),
+        });
+    }
+
+    /// Register a provider. It replaces an earlier registration of the same type in place;
+    /// otherwise its URLs are checked before those of the providers already registered.
+    pub fn register(
+        &mut self,
+        provider_type: ProviderType,
+        parse_url: impl Fn(&str) -> Option<RepoIdentifier> + Send + Sync + 'static,
+        create: impl Fn(Option<&Path>) -> Box<dyn GitProvider> + Send + Sync + 'static,
+    ) {
+        let registration = Registration {
+            provider_type,
+            parse_url: Arc::new(parse_url),
+            create: Arc::new(create),
+        };
+        match self
+            .registrations
+            .iter_mut()
+            .find(|existing| existing.provider_type == provider_type)
+        {
+            Some(existing) => *existing = registration,
+            None => self.registrations.insert(0, registration),
+        }
+    }
+
+    /// Build every registered type as a [`MockProvider`], keeping URL detection as it is
+    pub fn mock_all(&mut self) {
+        for registration in &mut self.registrations {
+            let provider_type = registration.provider_type;
+            registration.create = Arc::new(move |_| Box::new(MockProvider::new(provider_type)));
+        }
+    }
+
+    pub fn set_host_mappings(&mut self, host_mappings: Vec<ProviderHostMapping>) {
+        self.host_mappings = host_mappings;
+    }
+
+    /// Detect the provider and repository of a remote URL. A host mapping takes precedence
+    /// over the URL patterns of the providers.
+    pub fn detect(&self, url: &str) -> Result<(ProviderType, RepoIdentifier), ProviderError> {
+        if let Some(detected) = self.detect_mapped(url) {
+            return Ok(detected);
+        }
+        self.registrations
+            .iter()
+            .find_map(|registration| {
+                (registration.parse_url)(url).map(|repo| (registration.provider_type, repo))
+            })
+            .ok_or_else(|| ProviderError::UnknownProvider(url.to_string()))
+    }
+
+    fn detect_mapped(&self, url: &str) -> Option<(ProviderType, RepoIdentifier)> {
+        let (host, mut segments) = remote_location(url)?;
+        let mapping = self
+            .host_mappings
+            .iter()
+            .find(|mapping| normalize_host(&mapping.host).as_deref() == Some(host.as_str()))?;
+
+        // Prefer the provider's own parser, which knows its URL layout
+        if let Some(repo) = self
+            .registrations
+            .iter()
+            .find(|registration| registration.provider_type == mapping.provider)
+            .and_then(|registration| (registration.parse_url)(url))
+        {
+            return Some((mapping.provider, repo));
+        }
+
+        // Otherwise read the path as owner/name, with nested owners like GitLab groups
+        let name = segments.pop()?;
+        Some((
+            mapping.provider,
+            RepoIdentifier {
+                provider: mapping.provider,
+                owner: segments.join("/"),
+                project: None,
+                name,
+                host: Some(host),
+            },
+        ))
+    }
+
+    /// Build a provider of a registered type
+    pub fn create(
+        &self,
+        provider_type: ProviderType,
+        repo_path: Option<&Path>,
+    ) -> Result<Box<dyn GitProvider>, ProviderError> {
+        self.registrations
+            .iter()
+            .find(|registration| registration.provider_type == provider_type)
+            .map(|registration| (registration.create)(repo_path))
+            .ok_or_else(|| ProviderError::NotSupported {
+                feature: format!("{provider_type} provider"),
+            })
+    }
+}
+
+/// Host of a mapping as remotes are matched against it: lowercase, without scheme, user or port.
+/// None when nothing host-like is left.
+pub fn normalize_host(host: &str) -> Option<String> {
+    let host = host.trim();
+    let host = host.split_once("://").map_or(host, |(_, rest)| rest);
+    let host = host.split('/').next()?;
+    let host = host.rsplit('@').next()?;
+    let host = host.split(':').next()?.to_lowercase();
+    let valid = !host.is_empty()
+        && !host.starts_with(['.', '-'])
+        && host
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '.'));
+    valid.then_some(host)
+}
+
+static GLOBAL: OnceLock<RwLock<ProviderRegistry>> = OnceLock::new();
+
+fn global_cell() -> &'static RwLock<ProviderRegistry> {
+    GLOBAL.get_or_init(|| RwLock::new(ProviderRegistry::builtin()))
+}
+
+/// The process-wide registry used for provider detection and creation
+pub fn global() -> ProviderRegistry {
+    global_cell()
+        .read()
+        .map(|registry| registry.clone())
+        .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+}
+
+fn update(f: impl FnOnce(&mut ProviderRegistry)) {
+    match global_cell().write() {
+        Ok(mut guard) => f(&mut guard),
+        Err(poisoned) => f(&mut poisoned.into_inner()),
+    }
+}
+
+/// Add a provider to the process-wide registry, or replace the one of the same type
+pub fn register_provider(
+    provider_type: ProviderType,
+    parse_url: impl Fn(&str) -> Option<RepoIdentifier> + Send + Sync + 'static,
+    create: impl Fn(Option<&Path>) -> Box<dyn GitProvider> + Send + Sync + 'static,
+) {
+    update(|registry| registry.register(provider_type, parse_url, create));
+}
+
+/// Answer every provider call of the process from the in-memory mock, for test mode
+pub fn use_mock_providers() {
+    update(ProviderRegistry::mock_all);
+}
+
+/// Replace the host mappings of the process-wide registry, e.g. after the user changes them
+pub fn configure_host_mappings(host_mappings: Vec<ProviderHostMapping>) {
+    update(|registry| registry.set_host_mappings(host_mappings));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_mappings_take_precedence() {
+        let mut registry = ProviderRegistry::builtin();
+        registry.set_host_mappings(vec![ProviderHostMapping {
+            host: "Git.Example.com".to_string(),
+            provider: ProviderType::GitLab,
+        }]);
+
+        let (provider, repo) = registry
+            .detect("git@git.example.com:platform/tools/cli.git")
+            .unwrap();
+        assert_eq!(provider, ProviderType::GitLab);
+        assert_eq!(repo.owner, "platform/tools");
+        assert_eq!(repo.name, "cli");
+        assert_eq!(repo.host.as_deref(), Some("git.example.com"));
+
+        let (provider, _) = registry.detect("https://github.com/owner/repo").unwrap();
+        assert_eq!(provider, ProviderType::GitHub);
+    }
+
+    #[test]
+    fn hosts_are_normalized() {
+        assert_eq!(
+            normalize_host("https://git@Code.Acme.io:8443/").as_deref(),
+            Some("code.acme.io")
+        );
+        assert_eq!(
+            normalize_host(" code.acme.io ").as_deref(),
+            Some("code.acme.io")
+        );
+        assert_eq!(normalize_host("https://"), None);
+        assert_eq!(normalize_host("code acme"), None);
+    }
+
+    #[test]
+    fn registered_providers_replace_builtin_ones() {
+        let mut registry = ProviderRegistry::builtin();
+        registry.register(
+            ProviderType::Bitbucket,
+            |url| {
+                url.contains("bitbucket.internal")
+                    .then(|| RepoIdentifier::new_bitbucket("team", "repo"))
+            },
+            |_| Box::new(BitbucketProvider::new()),
+        );
+
+        let (provider, repo) = registry
+            .detect("https://bitbucket.internal/scm/team/repo.git")
+            .unwrap();
+        assert_eq!(provider, ProviderType::Bitbucket);
+        assert_eq!(repo.owner, "team");
+        // The replaced parser no longer recognizes Bitbucket Cloud
+        assert!(registry.detect("https://bitbucket.org/owner/repo").is_err());
+        assert!(registry.create(ProviderType::Bitbucket, None).is_ok());
+    }
+
+    #[test]
+    fn mocked_registries_still_detect_providers() {
+        let mut registry = ProviderRegistry::builtin();
+        registry.mock_all();
+
+        let (provider, _) = registry.detect("git@gitlab.com:group/project.git").unwrap();
+        assert_eq!(provider, ProviderType::GitLab);
+        let mock = registry.create(provider, None).unwrap();
+        assert_eq!(mock.provider_type(), ProviderType::GitLab);
+    }
+}
@@ -0,0 +1,249 @@
+//! Retry policies of git providers
+//!
+//! Provider calls that fail in a way that may pass later, like a rate limit or a server error, are
+//! retried with exponential backoff. Each provider has its own [`RetryPolicy`], configured with
+//! [`configure_retry_policies`], since a small self-hosted instance may need to be retried more
+//! gently than github.com. When a rate-limited response says how long to wait, either with
+//! `Retry-After` or with the reset time of a spent quota, that wait is used instead of the backoff
+//! delay, up to the policy's maximum delay.
+
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+    time::Duration,
+};
+
+use backon::ExponentialBuilder;
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{ProviderError, ProviderType, rate_limit};
+
+/// How failed calls to a provider are retried
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Delay before the first retry, doubled for each one after it
+    pub min_delay_ms: u64,
+    /// Longest delay between two attempts, also for waits a rate-limited response asks for
+    pub max_delay_ms: u64,
+    /// Retries after the first attempt; 0 turns retrying off
+    pub max_retries: usize,
+    /// Retry rate-limited requests (429, or 403 with rate-limit headers)
+    pub retry_rate_limits: bool,
+    /// Retry timeouts (408) and server errors (5xx)
+    pub retry_server_errors: bool,
+    /// Retry requests that got no response, failed CLI commands and unreadable responses
+    pub retry_network_errors: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            min_delay_ms: 1000,
+            max_delay_ms: 30_000,
+            max_retries: 3,
+            retry_rate_limits: true,
+            retry_server_errors: true,
+            retry_network_errors: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff between attempts
+    pub fn backoff(&self) -> ExponentialBuilder {
+        ExponentialBuilder::default()
+            .with_min_delay(Duration::from_millis(self.min_delay_ms))
+            .with_max_delay(self.max_delay())
+            .with_max_times(self.max_retries)
+            .with_jitter()
+    }
+
+    /// Whether a failed attempt is retried. Errors that fail the same way again, like a missing
+    /// pull request or a missing CLI, never are.
+    pub fn should_retry(&self, e: &ProviderError) -> bool {
+        if !e.should_retry() {
+            return false;
+        }
+        match e {
+            ProviderError::RateLimited { .. } | ProviderError::ApiError { status: 429, .. } => {
+                self.retry_rate_limits
+            }
+            ProviderError::ApiError { .. } => self.retry_server_errors,
+            _ => self.retry_network_errors,
+        }
+    }
+
+    /// Delay before retrying after `e`: the wait a rate-limited response asked for, if any, or
+    /// else the backoff `delay`. `None` once the retries are used up.
+    pub fn delay(&self, e: &ProviderError, delay: Option<Duration>) -> Option<Duration> {
+        match e {
+            ProviderError::RateLimited {
+                retry_after: Some(wait),
+                ..
+            } => delay.map(|_| (*wait).min(self.max_delay())),
+            _ => delay,
+        }
+    }
+
+    fn max_delay(&self) -> Duration {
+        Duration::from_millis(self.max_delay_ms.max(self.min_delay_ms))
+    }
+}
+
+/// Retry policy of one provider, overriding the default
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct ProviderRetryPolicy {
+    pub provider: ProviderType,
+    pub policy: RetryPolicy,
+}
+
+fn policies() -> &'static RwLock<HashMap<ProviderType, RetryPolicy>> {
+    static POLICIES: OnceLock<RwLock<HashMap<ProviderType, RetryPolicy>>> = OnceLock::new();
+    POLICIES.get_or_init(Default::default)
+}
+
+/// Retry policy of a provider, the default unless one was configured
+pub fn retry_policy(provider: ProviderType) -> RetryPolicy {
+    let policies = policies()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    policies.get(&provider).copied().unwrap_or_default()
+}
+
+/// Replace the configured retry policies, e.g. after the user changes them. Providers without
+/// one use the default.
+pub fn configure_retry_policies(configured: Vec<ProviderRetryPolicy>) {
+    let mut policies = policies()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *policies = configured
+        .into_iter()
+        .map(|configured| (configured.provider, configured.policy))
+        .collect();
+}
+
+/// Wait from the `Retry-After` header of a response, given either in seconds or as a date, or
+/// else until the rate-limit quota resets if the response says it is spent
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let Some(value) = headers.get("retry-after") else {
+        return rate_limit::reset_wait(headers);
+    };
+    let value = value.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let until = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some((until - Utc::now()).to_std().unwrap_or_default())
+}
+
+/// Whether a response carries rate-limit headers saying the request was throttled: a
+/// `Retry-After`, or a spent quota. Providers also answer 403 to requests they refuse outright,
+/// which must not be retried.
+pub fn is_rate_limited(headers: &HeaderMap) -> bool {
+    headers.contains_key("retry-after")
+        || rate_limit::parse("", headers).is_some_and(|quota| quota.remaining == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn rate_limited(retry_after: Option<Duration>) -> ProviderError {
+        ProviderError::RateLimited {
+            message: "API rate limit exceeded".to_string(),
+            retry_after,
+        }
+    }
+
+    #[test]
+    fn retries_only_the_enabled_kinds_of_errors() {
+        let policy = RetryPolicy {
+            retry_server_errors: false,
+            ..RetryPolicy::default()
+        };
+        assert!(policy.should_retry(&rate_limited(None)));
+        assert!(policy.should_retry(&ProviderError::Unreachable("timeout".to_string())));
+        assert!(!policy.should_retry(&ProviderError::ApiError {
+            status: 502,
+            message: "Bad Gateway".to_string(),
+        }));
+        assert!(!policy.should_retry(&ProviderError::ApiError {
+            status: 404,
+            message: "Not Found".to_string(),
+        }));
+        assert!(!policy.should_retry(&ProviderError::NotAuthenticated("expired".to_string())));
+        // A 403 only counts as a rate limit once a provider saw rate-limit headers on it
+        assert!(!policy.should_retry(&ProviderError::ApiError {
+            status: 403,
+            message: "Forbidden".to_string(),
+        }));
+    }
+
+    #[test]
+    fn rate_limits_are_told_apart_by_their_headers() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_rate_limited(&headers));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("12"));
+        assert!(!is_rate_limited(&headers));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        assert!(is_rate_limited(&headers));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("30"));
+        assert!(is_rate_limited(&headers));
+    }
+
+    #[test]
+    fn waits_as_long_as_rate_limits_ask_up_to_the_max_delay() {
+        let policy = RetryPolicy {
+            max_delay_ms: 10_000,
+            ..RetryPolicy::default()
+        };
+        let backoff = Some(Duration::from_secs(2));
+        assert_eq!(
+            policy.delay(&rate_limited(Some(Duration::from_secs(5))), backoff),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            policy.delay(&rate_limited(Some(Duration::from_secs(60))), backoff),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(policy.delay(&rate_limited(None), backoff), backoff);
+        // Nothing left to retry
+        assert_eq!(
+            policy.delay(&rate_limited(Some(Duration::from_secs(5))), None),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_retry_after_in_seconds_or_as_a_date() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert("retry-after", HeaderValue::from_static("120"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+        headers.insert(
+            "retry-after",
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn falls_back_to_the_reset_of_a_spent_quota() {
+        let mut headers = HeaderMap::new();
+        headers.insert("ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("ratelimit-reset", HeaderValue::from_static("1445412480"));
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+        headers.insert("retry-after", HeaderValue::from_static("30"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(30)));
+    }
+}
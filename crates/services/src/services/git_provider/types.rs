@@ -1,16 +1,21 @@
 //! Shared types for git provider abstraction
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use db::models::merge::MergeStatus;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 /// Git hosting provider type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
 #[serde(rename_all = "lowercase")]
 pub enum ProviderType {
     GitHub,
     GitLab,
+    Bitbucket,
+    BitbucketServer,
+    AzureDevOps,
+    Gerrit,
+    CodeCommit,
 }
 
 impl std::fmt::Display for ProviderType {
@@ -18,18 +23,28 @@ impl std::fmt::Display for ProviderType {
         match self {
             ProviderType::GitHub => write!(f, "GitHub"),
             ProviderType::GitLab => write!(f, "GitLab"),
+            ProviderType::Bitbucket => write!(f, "Bitbucket"),
+            ProviderType::BitbucketServer => write!(f, "Bitbucket Data Center"),
+            ProviderType::AzureDevOps => write!(f, "Azure DevOps"),
+            ProviderType::Gerrit => write!(f, "Gerrit"),
+            ProviderType::CodeCommit => write!(f, "AWS CodeCommit"),
         }
     }
 }
 
-/// Repository identifier (works for both GitHub and GitLab)
+/// Repository identifier (works for GitHub, GitLab, Bitbucket, Azure DevOps, Gerrit and CodeCommit)
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
 pub struct RepoIdentifier {
-    /// Provider type (GitHub/GitLab)
+    /// Provider type (GitHub/GitLab/Bitbucket/Bitbucket Data Center/Azure DevOps/Gerrit/CodeCommit)
     pub provider: ProviderType,
-    /// Owner (GitHub) or Group/Namespace (GitLab)
+    /// Owner (GitHub), Group/Namespace (GitLab), Workspace (Bitbucket), Project key (Bitbucket
+    /// Data Center), Organization (Azure DevOps) or AWS region (CodeCommit)
     pub owner: String,
-    /// Repository name (GitHub) or Project name (GitLab)
+    /// Project the repository belongs to (Azure DevOps only)
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Repository name (GitHub), Project name (GitLab) or Repository slug (Bitbucket);
+    /// on Gerrit, the project path is split into owner and name like GitLab groups
     pub name: String,
     /// Custom host for self-hosted instances (None for cloud)
     pub host: Option<String>,
@@ -40,6 +55,7 @@ impl RepoIdentifier {
         Self {
             provider: ProviderType::GitHub,
             owner: owner.into(),
+            project: None,
             name: name.into(),
             host: None,
         }
@@ -53,14 +69,83 @@ impl RepoIdentifier {
         Self {
             provider: ProviderType::GitLab,
             owner: owner.into(),
+            project: None,
             name: name.into(),
             host,
         }
     }
 
-    /// Full path (owner/name)
+    pub fn new_bitbucket(workspace: impl Into<String>, slug: impl Into<String>) -> Self {
+        Self {
+            provider: ProviderType::Bitbucket,
+            owner: workspace.into(),
+            project: None,
+            name: slug.into(),
+            host: None,
+        }
+    }
+
+    /// Repository on Bitbucket Server/Data Center; personal repositories have `~user` as
+    /// project key. The host keeps the context path of instances not served from the root.
+    pub fn new_bitbucket_server(
+        project_key: impl Into<String>,
+        slug: impl Into<String>,
+        host: impl Into<String>,
+    ) -> Self {
+        Self {
+            provider: ProviderType::BitbucketServer,
+            owner: project_key.into(),
+            project: None,
+            name: slug.into(),
+            host: Some(host.into()),
+        }
+    }
+
+    pub fn new_azure_devops(
+        organization: impl Into<String>,
+        project: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Self {
+        Self {
+            provider: ProviderType::AzureDevOps,
+            owner: organization.into(),
+            project: Some(project.into()),
+            name: name.into(),
+            host: None,
+        }
+    }
+
+    pub fn new_gerrit(
+        owner: impl Into<String>,
+        name: impl Into<String>,
+        host: impl Into<String>,
+    ) -> Self {
+        Self {
+            provider: ProviderType::Gerrit,
+            owner: owner.into(),
+            project: None,
+            name: name.into(),
+            host: Some(host.into()),
+        }
+    }
+
+    pub fn new_codecommit(region: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            provider: ProviderType::CodeCommit,
+            owner: region.into(),
+            project: None,
+            name: name.into(),
+            host: None,
+        }
+    }
+
+    /// Full path (owner/name, or organization/project/name on Azure DevOps)
     pub fn full_path(&self) -> String {
-        format!("{}/{}", self.owner, self.name)
+        match &self.project {
+            Some(project) => format!("{}/{}/{}", self.owner, project, self.name),
+            None if self.owner.is_empty() => self.name.clone(),
+            None => format!("{}/{}", self.owner, self.name),
+        }
     }
 }
 
@@ -104,6 +189,137 @@ pub struct PrInfo {
     pub state: PrState,
     pub merged_at: Option<DateTime<Utc>>,
     pub merge_commit_sha: Option<String>,
+    /// Summary of the reviews, when they were fetched along with the status
+    #[serde(default)]
+    pub review_state: Option<ReviewState>,
+}
+
+impl PrInfo {
+    pub fn with_reviews(mut self, reviews: &[UnifiedReview]) -> Self {
+        self.review_state = Some(ReviewState::summarize(reviews));
+        self
+    }
+}
+
+/// What a reviewer said about a PR/MR (unified)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewVerdict {
+    Approved,
+    ChangesRequested,
+    Commented,
+    /// Review requested but not given yet
+    Pending,
+}
+
+/// One reviewer's review of a PR/MR (works for GitHub reviews and GitLab approvals)
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UnifiedReview {
+    pub reviewer: String,
+    pub verdict: ReviewVerdict,
+    /// None for pending reviews
+    pub submitted_at: Option<DateTime<Utc>>,
+}
+
+/// Overall review state of a PR/MR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewState {
+    /// Nobody reviewed or was asked to
+    Unreviewed,
+    /// Reviews were requested and none is blocking or approving yet
+    Pending,
+    Approved,
+    ChangesRequested,
+}
+
+impl ReviewState {
+    /// Only each reviewer's latest verdict counts; comments don't change the state, and one
+    /// outstanding change request outweighs any number of approvals.
+    pub fn summarize(reviews: &[UnifiedReview]) -> Self {
+        let mut latest: Vec<&UnifiedReview> = Vec::new();
+        for review in reviews
+            .iter()
+            .filter(|review| review.verdict != ReviewVerdict::Commented)
+        {
+            match latest
+                .iter_mut()
+                .find(|seen| seen.reviewer == review.reviewer)
+            {
+                Some(seen) if review.submitted_at >= seen.submitted_at => *seen = review,
+                Some(_) => {}
+                None => latest.push(review),
+            }
+        }
+
+        let has = |verdict| latest.iter().any(|review| review.verdict == verdict);
+        if has(ReviewVerdict::ChangesRequested) {
+            ReviewState::ChangesRequested
+        } else if has(ReviewVerdict::Approved) {
+            ReviewState::Approved
+        } else if has(ReviewVerdict::Pending) {
+            ReviewState::Pending
+        } else {
+            ReviewState::Unreviewed
+        }
+    }
+}
+
+/// Where a CI check on the head commit of a PR/MR stands (unified)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// Queued or running
+    Pending,
+    Success,
+    /// Failed, errored, timed out or cancelled
+    Failure,
+    /// Skipped or finished as neutral
+    Skipped,
+}
+
+/// State of a commit status published by the app, e.g. for an agent run on a branch head
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitStatusState {
+    Pending,
+    Success,
+    Failure,
+    /// The status itself couldn't be determined, as opposed to a failed run
+    Error,
+}
+
+/// One CI check or commit status on the head commit of a PR/MR
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CheckRun {
+    pub name: String,
+    pub status: CheckStatus,
+    /// Page with the check's details or logs, when the provider links one
+    pub url: Option<String>,
+    /// Id of the CI job behind the check, to fetch its log with `get_check_logs`. Unset for
+    /// checks whose logs the provider doesn't serve, like commit statuses.
+    pub id: Option<String>,
+}
+
+/// A PR/MR together with its checks, reviews and comments, as a workspace shows them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MrDetails {
+    /// Status, with `review_state` summarizing `reviews`
+    pub info: PrInfo,
+    pub checks: Vec<CheckRun>,
+    pub reviews: Vec<UnifiedReview>,
+    pub comments: Vec<UnifiedComment>,
+}
+
+/// How a PR/MR is merged into its target branch: with a merge commit, squashed into one commit,
+/// or rebased onto the target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMethod {
+    #[default]
+    Merge,
+    Squash,
+    Rebase,
 }
 
 /// Request to create MR/PR
@@ -114,6 +330,155 @@ pub struct CreateMrRequest {
     pub head_branch: String,
     pub base_branch: String,
     pub draft: Option<bool>,
+    /// Repository holding the head branch when it is not the target repository, i.e. a fork
+    pub head_repo: Option<RepoIdentifier>,
+    /// Title of the milestone to assign, as listed by `GitProvider::list_milestones`
+    pub milestone: Option<String>,
+}
+
+/// Changes to an open MR/PR; fields left `None` are kept as they are
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct UpdateMrRequest {
+    pub title: Option<String>,
+    /// Description of the MR/PR
+    pub body: Option<String>,
+    /// Branch the MR/PR merges into
+    pub base_branch: Option<String>,
+}
+
+/// What a provider requires before an MR/PR into a branch can be merged
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct BranchProtection {
+    /// Whether any protection rules apply to the branch
+    pub protected: bool,
+    /// Checks that must pass, by name; `pipeline` on GitLab projects that require a successful
+    /// pipeline
+    pub required_checks: Vec<String>,
+    /// Approving reviews needed
+    pub required_approvals: u32,
+}
+
+impl BranchProtection {
+    /// Whether a new MR/PR has to wait for checks or approvals, so it can only be merged
+    /// unattended with auto-merge
+    pub fn blocks_direct_merge(&self) -> bool {
+        !self.required_checks.is_empty() || self.required_approvals > 0
+    }
+}
+
+/// An open MR/PR that waits for someone's review
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReviewRequest {
+    pub number: u64,
+    pub url: String,
+    pub title: String,
+    /// Username of whoever opened it
+    pub author: String,
+    pub draft: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A release published on the provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct ReleaseInfo {
+    pub tag: String,
+    pub name: String,
+    /// Web page of the release
+    pub url: String,
+}
+
+/// An open milestone of a repository that new MRs/PRs can be assigned to
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct Milestone {
+    /// Provider's id: the milestone number on GitHub, the global id on GitLab
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub due_date: Option<NaiveDate>,
+}
+
+/// How a PR/MR changes a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+/// A file changed by a PR/MR (unified)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedFile {
+    pub path: String,
+    /// Path before a rename
+    pub old_path: Option<String>,
+    pub change: FileChangeKind,
+    pub additions: u64,
+    pub deletions: u64,
+}
+
+/// Diff of one file of a PR/MR (unified)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub path: String,
+    /// Path before a rename
+    pub old_path: Option<String>,
+    pub change: FileChangeKind,
+    /// Hunks of the file in unified diff format, starting at the first `@@` line; None for
+    /// binary files and diffs too large for the provider to return
+    pub patch: Option<String>,
+}
+
+impl FileDiff {
+    /// The file without its patch; added and deleted lines are counted from the patch
+    pub fn changed_file(&self) -> ChangedFile {
+        let (additions, deletions) = self.patch.as_deref().map_or((0, 0), |patch| {
+            patch.lines().fold((0, 0), |(added, deleted), line| {
+                if line.starts_with('+') {
+                    (added + 1, deleted)
+                } else if line.starts_with('-') {
+                    (added, deleted + 1)
+                } else {
+                    (added, deleted)
+                }
+            })
+        });
+        ChangedFile {
+            path: self.path.clone(),
+            old_path: self.old_path.clone(),
+            change: self.change,
+            additions,
+            deletions,
+        }
+    }
+
+    /// The hunk that covers `line` of the new version of the file
+    pub fn hunk_at(&self, line: u64) -> Option<&str> {
+        let patch = self.patch.as_deref()?;
+        let mut starts: Vec<usize> = patch
+            .match_indices("@@ -")
+            .map(|(index, _)| index)
+            .filter(|&index| index == 0 || patch.as_bytes()[index - 1] == b'\n')
+            .collect();
+        starts.push(patch.len());
+        starts.windows(2).map(|w| &patch[w[0]..w[1]]).find(|hunk| {
+            new_range(hunk).is_some_and(|(start, count)| start <= line && line < start + count)
+        })
+    }
+}
+
+/// Start and length of the new side of a hunk, from its `@@ -a,b +c,d @@` header
+fn new_range(hunk: &str) -> Option<(u64, u64)> {
+    let header = hunk.lines().next()?;
+    let new_side = header.split(' ').find(|part| part.starts_with('+'))?;
+    let mut numbers = new_side[1..].split(',');
+    let start = numbers.next()?.parse().ok()?;
+    let count = match numbers.next() {
+        Some(count) => count.parse().ok()?,
+        None => 1,
+    };
+    Some((start, count))
 }
 
 /// Unified comment type (works for both GitHub PR and GitLab MR)
@@ -139,6 +504,8 @@ pub enum UnifiedComment {
         created_at: DateTime<Utc>,
         url: String,
         path: String,
+        /// First line of a comment spanning several lines; None when it covers `line` only
+        start_line: Option<i64>,
         line: Option<i64>,
         diff_hunk: String,
     },
@@ -151,4 +518,168 @@ impl UnifiedComment {
             UnifiedComment::Review { created_at, .. } => *created_at,
         }
     }
+
+    /// Identifies the comment among those of its pull request, whatever its kind
+    pub fn key(&self) -> String {
+        match self {
+            UnifiedComment::General { id, .. } => format!("general:{id}"),
+            UnifiedComment::Review { id, .. } => format!("review:{id}"),
+        }
+    }
+
+    pub fn author(&self) -> &str {
+        match self {
+            UnifiedComment::General { author, .. } | UnifiedComment::Review { author, .. } => {
+                author
+            }
+        }
+    }
+
+    /// How the author relates to the repository, as GitHub's `author_association`
+    pub fn author_association(&self) -> &str {
+        match self {
+            UnifiedComment::General {
+                author_association, ..
+            }
+            | UnifiedComment::Review {
+                author_association, ..
+            } => author_association,
+        }
+    }
+
+    /// Written by an app, like `dependabot[bot]` on GitHub or `project_12_bot` on GitLab
+    pub fn is_from_bot(&self) -> bool {
+        let author = self.author().to_lowercase();
+        author.ends_with("[bot]") || author.ends_with("_bot")
+    }
+}
+
+/// Narrows the comments of an MR/PR; the default keeps every comment
+#[derive(Debug, Clone, Default)]
+pub struct CommentFilter {
+    /// Created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    pub exclude_bots: bool,
+    /// Usernames to keep the comments of, compared case-insensitively; empty keeps every author
+    pub authors: Vec<String>,
+}
+
+impl CommentFilter {
+    pub fn matches(&self, comment: &UnifiedComment) -> bool {
+        self.since.is_none_or(|since| comment.created_at() >= since)
+            && !(self.exclude_bots && comment.is_from_bot())
+            && (self.authors.is_empty()
+                || self
+                    .authors
+                    .iter()
+                    .any(|author| author.eq_ignore_ascii_case(comment.author())))
+    }
+}
+
+/// Review comments need a numeric id; for providers whose ids are opaque strings, hash them
+/// (FNV-1a) into a stable non-negative one
+pub(super) fn numeric_comment_id(id: &str) -> i64 {
+    let hash = id.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    (hash >> 1) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn review(reviewer: &str, verdict: ReviewVerdict, minute: Option<u32>) -> UnifiedReview {
+        UnifiedReview {
+            reviewer: reviewer.to_string(),
+            verdict,
+            submitted_at: minute.map(|m| Utc.with_ymd_and_hms(2025, 1, 1, 12, m, 0).unwrap()),
+        }
+    }
+
+    #[test]
+    fn hunks_are_found_by_new_line() {
+        let diff = FileDiff {
+            path: "src/lib.rs".to_string(),
+            old_path: None,
+            change: FileChangeKind::Modified,
+            patch: Some(
+                "@@ -1,3 +1,4 @@\n use a;\n+use b;\n \n fn f() {}\n@@ -40,2 +41,2 @@ fn g() {\n-    old();\n+    new();\n     done();"
+                    .to_string(),
+            ),
+        };
+        assert!(diff.hunk_at(2).unwrap().starts_with("@@ -1,3 +1,4 @@"));
+        assert!(diff.hunk_at(42).unwrap().contains("+    new();"));
+        assert_eq!(diff.hunk_at(10), None);
+
+        let file = diff.changed_file();
+        assert_eq!((file.additions, file.deletions), (2, 1));
+    }
+
+    #[test]
+    fn latest_verdict_of_each_reviewer_decides() {
+        assert_eq!(ReviewState::summarize(&[]), ReviewState::Unreviewed);
+        assert_eq!(
+            ReviewState::summarize(&[review("ann", ReviewVerdict::Pending, None)]),
+            ReviewState::Pending
+        );
+
+        let addressed = [
+            review("ann", ReviewVerdict::ChangesRequested, Some(1)),
+            review("ann", ReviewVerdict::Approved, Some(5)),
+            review("bob", ReviewVerdict::Commented, Some(6)),
+        ];
+        assert_eq!(ReviewState::summarize(&addressed), ReviewState::Approved);
+
+        let blocked = [
+            review("ann", ReviewVerdict::Approved, Some(1)),
+            review("bob", ReviewVerdict::ChangesRequested, Some(2)),
+            review("cat", ReviewVerdict::Pending, None),
+        ];
+        assert_eq!(
+            ReviewState::summarize(&blocked),
+            ReviewState::ChangesRequested
+        );
+    }
+
+    #[test]
+    fn comment_filter_keeps_recent_comments_of_the_chosen_people() {
+        let comment = |author: &str, minute: u32| UnifiedComment::General {
+            id: minute.to_string(),
+            author: author.to_string(),
+            author_association: "MEMBER".to_string(),
+            body: "LGTM".to_string(),
+            created_at: Utc.with_ymd_and_hms(2026, 2, 8, 9, minute, 0).unwrap(),
+            url: String::new(),
+        };
+        let comments = [
+            comment("Ann", 1),
+            comment("ann", 20),
+            comment("github-actions[bot]", 21),
+            comment("gitlab_project_7_bot", 22),
+            comment("bob", 23),
+        ];
+        let kept = |filter: &CommentFilter| -> Vec<String> {
+            comments
+                .iter()
+                .filter(|comment| filter.matches(comment))
+                .map(UnifiedComment::key)
+                .collect()
+        };
+
+        assert_eq!(kept(&CommentFilter::default()).len(), 5);
+        let filter = CommentFilter {
+            since: Some(Utc.with_ymd_and_hms(2026, 2, 8, 9, 10, 0).unwrap()),
+            exclude_bots: true,
+            authors: Vec::new(),
+        };
+        assert_eq!(kept(&filter), ["general:20", "general:23"]);
+        let filter = CommentFilter {
+            authors: vec!["ANN".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(kept(&filter), ["general:1", "general:20"]);
+    }
 }
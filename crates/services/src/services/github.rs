@@ -37,6 +37,8 @@ pub enum UnifiedPrComment {
         created_at: DateTime<Utc>,
         url: String,
         path: String,
+        /// First line of a comment spanning several lines; None when it covers `line` only
+        start_line: Option<i64>,
         line: Option<i64>,
         diff_hunk: String,
     },
@@ -149,6 +151,8 @@ pub struct CreatePrRequest {
     pub head_branch: String,
     pub base_branch: String,
     pub draft: Option<bool>,
+    /// Milestone title
+    pub milestone: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -359,6 +363,7 @@ impl GitHubService {
                 created_at: c.created_at,
                 url: c.html_url,
                 path: c.path,
+                start_line: c.start_line,
                 line: c.line,
                 diff_hunk: c.diff_hunk,
             });
@@ -3,6 +3,9 @@
 //! This module deliberately mirrors the ergonomics of `git_cli.rs` so we can
 //! plug in the GitHub CLI for operations the REST client does not cover well.
 //! Future work will flesh out richer error handling and testing.
+//!
+//! Output parsing is tested against outputs recorded from several gh releases, under
+//! `tests/fixtures/cli/gh`.
 
 use std::{
     ffi::{OsStr, OsString},
@@ -11,13 +14,17 @@ use std::{
 
 use chrono::{DateTime, Utc};
 use db::models::merge::{MergeStatus, PullRequestInfo};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::Value;
 use thiserror::Error;
 use ts_rs::TS;
 use utils::shell::resolve_executable_path_blocking;
 
-use crate::services::github::{CreatePrRequest, GitHubRepoInfo};
+use crate::services::{
+    cli_output,
+    git_provider::{CommitStatusState, MergeMethod, UpdateMrRequest, max_pages},
+    github::{CreatePrRequest, GitHubRepoInfo},
+};
 
 /// Author information for a PR comment
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -37,6 +44,18 @@ pub struct PrComment {
     pub url: String,
 }
 
+/// A conversation comment on a GitHub PR, as returned when posting one (from gh api)
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueComment {
+    /// Same id as `PrComment::id`
+    pub node_id: String,
+    pub user: ReviewCommentUser,
+    pub author_association: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub html_url: String,
+}
+
 /// User information for a review comment (from API response)
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ReviewCommentUser {
@@ -52,12 +71,120 @@ pub struct PrReviewComment {
     pub created_at: DateTime<Utc>,
     pub html_url: String,
     pub path: String,
+    /// Set when the comment spans several lines
+    #[serde(default)]
+    pub start_line: Option<i64>,
     pub line: Option<i64>,
     pub side: Option<String>,
     pub diff_hunk: String,
     pub author_association: String,
 }
 
+/// A file changed by a GitHub PR (from gh api)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PrFile {
+    pub filename: String,
+    /// added, removed, modified, renamed, copied, changed or unchanged
+    pub status: String,
+    pub additions: u64,
+    pub deletions: u64,
+    /// Missing for binary files and large diffs
+    pub patch: Option<String>,
+    pub previous_filename: Option<String>,
+}
+
+/// A submitted review on a GitHub PR (from gh api)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PrReview {
+    /// None when the reviewer's account was deleted
+    pub user: Option<ReviewCommentUser>,
+    /// APPROVED, CHANGES_REQUESTED, COMMENTED, DISMISSED or PENDING
+    pub state: String,
+    pub submitted_at: Option<DateTime<Utc>>,
+}
+
+/// Reviewers asked to review a GitHub PR who have not done so yet (from gh api)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PrRequestedReviewers {
+    #[serde(default)]
+    pub users: Vec<ReviewCommentUser>,
+    #[serde(default)]
+    pub teams: Vec<RequestedTeam>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestedTeam {
+    pub slug: String,
+}
+
+/// An open PR with the reviewers it still waits for (from gh api)
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenPullRequest {
+    pub number: i64,
+    pub html_url: String,
+    pub title: String,
+    /// None when the author's account was deleted
+    pub user: Option<ReviewCommentUser>,
+    #[serde(default)]
+    pub draft: bool,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub requested_reviewers: Vec<ReviewCommentUser>,
+}
+
+/// A milestone of a GitHub repository (from gh api)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepoMilestone {
+    pub number: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub due_on: Option<DateTime<Utc>>,
+}
+
+/// A branch as shown to anyone who can read the repository (from gh api)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepoBranch {
+    pub protected: bool,
+    /// Classic branch protection; only its required status checks are shown
+    #[serde(default)]
+    pub protection: Option<RepoBranchProtection>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepoBranchProtection {
+    pub required_status_checks: Option<RequiredStatusChecks>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequiredStatusChecks {
+    #[serde(default)]
+    pub contexts: Vec<String>,
+}
+
+/// A repository ruleset rule that applies to a branch (from gh api)
+#[derive(Debug, Clone, Deserialize)]
+pub struct BranchRule {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub parameters: Value,
+}
+
+/// Review requirements of classic branch protection, readable by repository admins only
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequiredReviews {
+    #[serde(default)]
+    pub required_approving_review_count: u32,
+}
+
+/// A published release (from gh api)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepoRelease {
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub html_url: String,
+}
+
 /// High-level errors originating from the GitHub CLI.
 #[derive(Debug, Error)]
 pub enum GhCliError {
@@ -71,6 +198,9 @@ pub enum GhCliError {
     UnexpectedOutput(String),
 }
 
+/// Items requested per page of a REST listing, the most GitHub returns
+const API_PER_PAGE: usize = 100;
+
 /// Newtype wrapper for invoking the `gh` command.
 #[derive(Debug, Clone, Default)]
 pub struct GhCli;
@@ -86,6 +216,35 @@ impl GhCli {
         Ok(())
     }
 
+    /// Every item of a paginated REST listing, reading up to [`max_pages`] pages.
+    fn api_list<T: DeserializeOwned>(&self, path: &str, what: &str) -> Result<Vec<T>, GhCliError> {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        let mut items = Vec::new();
+        for page in 1..=max_pages() {
+            let raw = self.run([
+                "api",
+                &format!("{path}{separator}per_page={API_PER_PAGE}&page={page}"),
+            ])?;
+            let batch: Vec<T> = serde_json::from_str(raw.trim()).map_err(|err| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Failed to parse {what} API response: {err}; raw: {raw}"
+                ))
+            })?;
+            let last = batch.len() < API_PER_PAGE;
+            items.extend(batch);
+            if last {
+                return Ok(items);
+            }
+        }
+        tracing::warn!(
+            "Stopped reading {} of {} after {} pages",
+            what,
+            path,
+            max_pages()
+        );
+        Ok(items)
+    }
+
     /// Generic helper to execute `gh <args>` and return stdout on success.
     fn run<I, S>(&self, args: I) -> Result<String, GhCliError>
     where
@@ -127,6 +286,25 @@ impl GhCli {
         Err(GhCliError::CommandFailed(stderr))
     }
 
+    /// Version of the installed gh, for errors about output it can't parse
+    fn version(&self) -> String {
+        self.run(["--version"])
+            .ok()
+            .and_then(|raw| cli_output::parse_version(&raw))
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    /// Add the gh version to an error about unparseable output, which usually comes from a
+    /// release that changed it
+    fn with_version(&self, err: GhCliError) -> GhCliError {
+        match err {
+            GhCliError::UnexpectedOutput(msg) => {
+                GhCliError::UnexpectedOutput(format!("{msg} (gh {})", self.version()))
+            }
+            err => err,
+        }
+    }
+
     /// Run `gh pr create` and parse the response.
     ///
     /// TODO: support writing the body to a temp file (`--body-file`) for large/multi-line
@@ -159,8 +337,13 @@ impl GhCli {
             args.push(OsString::from("--draft"));
         }
 
+        if let Some(milestone) = &request.milestone {
+            args.push(OsString::from("--milestone"));
+            args.push(OsString::from(milestone));
+        }
+
         let raw = self.run(args)?;
-        Self::parse_pr_create_text(&raw)
+        Self::parse_pr_create_text(&raw).map_err(|e| self.with_version(e))
     }
 
     /// Ensure the GitHub CLI has valid auth.
@@ -172,6 +355,17 @@ impl GhCli {
         }
     }
 
+    /// Login of the user `gh` is signed in as.
+    pub fn authenticated_username(&self) -> Result<String, GhCliError> {
+        let raw = self.run(["api", "user", "--jq", ".login"])?;
+        match raw.trim() {
+            "" => Err(GhCliError::UnexpectedOutput(
+                "gh api user returned no login".to_string(),
+            )),
+            login => Ok(login.to_string()),
+        }
+    }
+
     /// Retrieve details for a single pull request.
     pub fn view_pr(
         &self,
@@ -188,7 +382,139 @@ impl GhCli {
             "--json",
             "number,url,state,mergedAt,mergeCommit",
         ])?;
-        Self::parse_pr_view(&raw)
+        Self::parse_pr_view(&raw).map_err(|e| self.with_version(e))
+    }
+
+    /// Merge a pull request with `gh pr merge`.
+    pub fn merge_pr(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        method: MergeMethod,
+    ) -> Result<(), GhCliError> {
+        self.run([
+            "pr",
+            "merge",
+            &pr_number.to_string(),
+            "--repo",
+            &format!("{owner}/{repo}"),
+            merge_method_flag(method),
+        ])?;
+        Ok(())
+    }
+
+    /// Enable auto-merge on a pull request, so GitHub merges it once its requirements pass.
+    pub fn enable_auto_merge(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        method: MergeMethod,
+    ) -> Result<(), GhCliError> {
+        self.run([
+            "pr",
+            "merge",
+            &pr_number.to_string(),
+            "--repo",
+            &format!("{owner}/{repo}"),
+            "--auto",
+            merge_method_flag(method),
+        ])?;
+        Ok(())
+    }
+
+    /// Close a pull request without merging it.
+    pub fn close_pr(&self, owner: &str, repo: &str, pr_number: i64) -> Result<(), GhCliError> {
+        self.run([
+            "pr",
+            "close",
+            &pr_number.to_string(),
+            "--repo",
+            &format!("{owner}/{repo}"),
+        ])?;
+        Ok(())
+    }
+
+    /// Reopen a closed pull request.
+    pub fn reopen_pr(&self, owner: &str, repo: &str, pr_number: i64) -> Result<(), GhCliError> {
+        self.run([
+            "pr",
+            "reopen",
+            &pr_number.to_string(),
+            "--repo",
+            &format!("{owner}/{repo}"),
+        ])?;
+        Ok(())
+    }
+
+    /// Mark a pull request as ready for review with `gh pr ready`, or back as a draft.
+    pub fn set_pr_draft(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        draft: bool,
+    ) -> Result<(), GhCliError> {
+        let number = pr_number.to_string();
+        let repo = format!("{owner}/{repo}");
+        let mut args = vec!["pr", "ready", number.as_str(), "--repo", repo.as_str()];
+        if draft {
+            args.push("--undo");
+        }
+        self.run(args)?;
+        Ok(())
+    }
+
+    /// Change the title, body or base branch of a pull request with `gh pr edit`.
+    pub fn edit_pr(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        request: &UpdateMrRequest,
+    ) -> Result<(), GhCliError> {
+        let mut args: Vec<OsString> = vec![
+            OsString::from("pr"),
+            OsString::from("edit"),
+            OsString::from(pr_number.to_string()),
+            OsString::from("--repo"),
+            OsString::from(format!("{owner}/{repo}")),
+        ];
+        if let Some(title) = &request.title {
+            args.push(OsString::from("--title"));
+            args.push(OsString::from(title));
+        }
+        if let Some(body) = &request.body {
+            args.push(OsString::from("--body"));
+            args.push(OsString::from(body));
+        }
+        if let Some(base_branch) = &request.base_branch {
+            args.push(OsString::from("--base"));
+            args.push(OsString::from(base_branch));
+        }
+        self.run(args)?;
+        Ok(())
+    }
+
+    /// Request reviews from users, on top of the reviewers already requested.
+    pub fn request_reviewers(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        reviewers: &[String],
+    ) -> Result<(), GhCliError> {
+        self.run([
+            "pr".to_string(),
+            "edit".to_string(),
+            pr_number.to_string(),
+            "--repo".to_string(),
+            format!("{owner}/{repo}"),
+            "--add-reviewer".to_string(),
+            reviewers.join(","),
+        ])?;
+        Ok(())
     }
 
     /// List pull requests for a branch (includes closed/merged).
@@ -207,10 +533,12 @@ impl GhCli {
             "all",
             "--head",
             &format!("{owner}:{branch}"),
+            "--limit",
+            &(max_pages() * API_PER_PAGE).to_string(),
             "--json",
             "number,url,state,mergedAt,mergeCommit",
         ])?;
-        Self::parse_pr_list(&raw)
+        Self::parse_pr_list(&raw).map_err(|e| self.with_version(e))
     }
 
     /// Fetch comments for a pull request.
@@ -229,7 +557,7 @@ impl GhCli {
             "--json",
             "comments",
         ])?;
-        Self::parse_pr_comments(&raw)
+        Self::parse_pr_comments(&raw).map_err(|e| self.with_version(e))
     }
 
     /// Fetch inline review comments for a pull request via API.
@@ -239,11 +567,339 @@ impl GhCli {
         repo: &str,
         pr_number: i64,
     ) -> Result<Vec<PrReviewComment>, GhCliError> {
+        self.api_list(
+            &format!("repos/{owner}/{repo}/pulls/{pr_number}/comments"),
+            "review comments",
+        )
+    }
+
+    /// Post a conversation comment on a pull request via API.
+    pub fn post_pr_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<IssueComment, GhCliError> {
+        let raw = self.run([
+            "api",
+            "--method",
+            "POST",
+            &format!("repos/{owner}/{repo}/issues/{pr_number}/comments"),
+            "-f",
+            &format!("body={body}"),
+        ])?;
+        serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse comment API response: {err}; raw: {raw}"
+            ))
+        })
+    }
+
+    /// Commit at the head of a pull request, which inline review comments are anchored to.
+    pub fn get_pr_head_sha(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<String, GhCliError> {
+        let raw = self.run([
+            "pr",
+            "view",
+            &pr_number.to_string(),
+            "--repo",
+            &format!("{owner}/{repo}"),
+            "--json",
+            "headRefOid",
+            "--jq",
+            ".headRefOid",
+        ])?;
+        let sha = raw.trim();
+        if sha.is_empty() {
+            return Err(GhCliError::UnexpectedOutput(
+                "Pull request has no head commit".to_string(),
+            ));
+        }
+        Ok(sha.to_string())
+    }
+
+    /// Post an inline review comment on a line of the new side of a pull request diff via API.
+    #[allow(clippy::too_many_arguments)]
+    pub fn post_review_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        commit_id: &str,
+        path: &str,
+        line: u64,
+        body: &str,
+    ) -> Result<PrReviewComment, GhCliError> {
         let raw = self.run([
             "api",
+            "--method",
+            "POST",
             &format!("repos/{owner}/{repo}/pulls/{pr_number}/comments"),
+            "-f",
+            &format!("body={body}"),
+            "-f",
+            &format!("commit_id={commit_id}"),
+            "-f",
+            &format!("path={path}"),
+            "-F",
+            &format!("line={line}"),
+            "-f",
+            "side=RIGHT",
+        ])?;
+        serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse review comment API response: {err}; raw: {raw}"
+            ))
+        })
+    }
+
+    /// Reply to the thread of an inline review comment via API.
+    pub fn reply_to_review_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<PrReviewComment, GhCliError> {
+        let raw = self.run([
+            "api",
+            "--method",
+            "POST",
+            &format!("repos/{owner}/{repo}/pulls/{pr_number}/comments/{comment_id}/replies"),
+            "-f",
+            &format!("body={body}"),
+        ])?;
+        serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse review comment reply API response: {err}; raw: {raw}"
+            ))
+        })
+    }
+
+    /// Fetch the files a pull request changes, with their patches, via API.
+    pub fn get_pr_files(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<PrFile>, GhCliError> {
+        self.api_list(
+            &format!("repos/{owner}/{repo}/pulls/{pr_number}/files"),
+            "PR files",
+        )
+    }
+
+    /// Fetch the submitted reviews of a pull request via API, oldest first.
+    pub fn get_pr_reviews(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<PrReview>, GhCliError> {
+        self.api_list(
+            &format!("repos/{owner}/{repo}/pulls/{pr_number}/reviews"),
+            "reviews",
+        )
+    }
+
+    /// Fetch the open pull requests of a repository via API.
+    pub fn list_open_prs(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<OpenPullRequest>, GhCliError> {
+        self.api_list(
+            &format!("repos/{owner}/{repo}/pulls?state=open"),
+            "open pull requests",
+        )
+    }
+
+    /// Fetch the open milestones of a repository via API.
+    pub fn list_milestones(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<RepoMilestone>, GhCliError> {
+        self.api_list(
+            &format!("repos/{owner}/{repo}/milestones?state=open"),
+            "milestones",
+        )
+    }
+
+    /// Fetch a branch with the status checks its classic protection requires via API.
+    pub fn get_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<RepoBranch, GhCliError> {
+        let raw = self.run(["api", &format!("repos/{owner}/{repo}/branches/{branch}")])?;
+        cli_output::parse_json(&raw)
+            .and_then(serde_json::from_value)
+            .map_err(|err| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Failed to parse branch API response: {err}; raw: {raw}"
+                ))
+            })
+    }
+
+    /// Fetch the ruleset rules that apply to a branch via API.
+    pub fn get_branch_rules(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Vec<BranchRule>, GhCliError> {
+        self.api_list(
+            &format!("repos/{owner}/{repo}/rules/branches/{branch}"),
+            "branch rules",
+        )
+    }
+
+    /// Fetch the review requirements of a branch's classic protection via API. Fails for users
+    /// who don't administer the repository and for branches that require no reviews.
+    pub fn get_required_reviews(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<RequiredReviews, GhCliError> {
+        let raw = self.run([
+            "api",
+            &format!(
+                "repos/{owner}/{repo}/branches/{branch}/protection/required_pull_request_reviews"
+            ),
+        ])?;
+        cli_output::parse_json(&raw)
+            .and_then(serde_json::from_value)
+            .map_err(|err| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Failed to parse required reviews API response: {err}; raw: {raw}"
+                ))
+            })
+    }
+
+    /// Fetch the users and teams whose review of a pull request is still requested.
+    pub fn get_pr_requested_reviewers(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<PrRequestedReviewers, GhCliError> {
+        let raw = self.run([
+            "api",
+            &format!("repos/{owner}/{repo}/pulls/{pr_number}/requested_reviewers"),
         ])?;
-        Self::parse_pr_review_comments(&raw)
+        serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse requested reviewers API response: {err}; raw: {raw}"
+            ))
+        })
+    }
+
+    /// Fetch the log of a GitHub Actions job via API, as plain text.
+    pub fn get_job_logs(
+        &self,
+        owner: &str,
+        repo: &str,
+        job_id: &str,
+    ) -> Result<String, GhCliError> {
+        self.run([
+            "api",
+            &format!("repos/{owner}/{repo}/actions/jobs/{job_id}/logs"),
+        ])
+    }
+
+    /// Create a commit status via `gh api`
+    pub fn set_commit_status(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+        state: CommitStatusState,
+        context: &str,
+        url: Option<&str>,
+    ) -> Result<(), GhCliError> {
+        let mut args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "POST".to_string(),
+            format!("repos/{owner}/{repo}/statuses/{sha}"),
+            "-f".to_string(),
+            format!("state={}", commit_status_state_name(state)),
+            "-f".to_string(),
+            format!("context={context}"),
+        ];
+        if let Some(url) = url {
+            args.extend(["-f".to_string(), format!("target_url={url}")]);
+        }
+        self.run(args)?;
+        Ok(())
+    }
+
+    /// Publish a release via API, creating its tag from the default branch if needed.
+    pub fn create_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        name: &str,
+        notes: &str,
+    ) -> Result<RepoRelease, GhCliError> {
+        let raw = self.run([
+            "api".to_string(),
+            "--method".to_string(),
+            "POST".to_string(),
+            format!("repos/{owner}/{repo}/releases"),
+            "-f".to_string(),
+            format!("tag_name={tag}"),
+            "-f".to_string(),
+            format!("name={name}"),
+            "-f".to_string(),
+            format!("body={notes}"),
+        ])?;
+        cli_output::parse_json(&raw)
+            .and_then(serde_json::from_value)
+            .map_err(|err| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Failed to parse release API response: {err}; raw: {raw}"
+                ))
+            })
+    }
+
+    /// Run a GraphQL query via `gh api graphql` and return the `data` of its response. String
+    /// variables are passed as given and integer ones as numbers; gh fails on GraphQL errors.
+    pub fn graphql(
+        &self,
+        query: &str,
+        strings: &[(&str, &str)],
+        integers: &[(&str, i64)],
+    ) -> Result<Value, GhCliError> {
+        let mut args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "-f".to_string(),
+            format!("query={query}"),
+        ];
+        for (name, value) in strings {
+            args.extend(["-f".to_string(), format!("{name}={value}")]);
+        }
+        for (name, value) in integers {
+            args.extend(["-F".to_string(), format!("{name}={value}")]);
+        }
+        let raw = self.run(args)?;
+        let mut response: Value = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse GraphQL response: {err}; raw: {raw}"
+            ))
+        })?;
+        Ok(response["data"].take())
     }
 }
 
@@ -289,7 +945,7 @@ impl GhCli {
     }
 
     fn parse_pr_view(raw: &str) -> Result<PullRequestInfo, GhCliError> {
-        let value: Value = serde_json::from_str(raw.trim()).map_err(|err| {
+        let value = cli_output::parse_json(raw).map_err(|err| {
             GhCliError::UnexpectedOutput(format!(
                 "Failed to parse gh pr view response: {err}; raw: {raw}"
             ))
@@ -302,7 +958,7 @@ impl GhCli {
     }
 
     fn parse_pr_list(raw: &str) -> Result<Vec<PullRequestInfo>, GhCliError> {
-        let value: Value = serde_json::from_str(raw.trim()).map_err(|err| {
+        let value = cli_output::parse_json(raw).map_err(|err| {
             GhCliError::UnexpectedOutput(format!(
                 "Failed to parse gh pr list response: {err}; raw: {raw}"
             ))
@@ -322,7 +978,7 @@ impl GhCli {
     }
 
     fn parse_pr_comments(raw: &str) -> Result<Vec<PrComment>, GhCliError> {
-        let value: Value = serde_json::from_str(raw.trim()).map_err(|err| {
+        let value = cli_output::parse_json(raw).map_err(|err| {
             GhCliError::UnexpectedOutput(format!(
                 "Failed to parse gh pr view --json comments response: {err}; raw: {raw}"
             ))
@@ -349,14 +1005,6 @@ impl GhCli {
             .collect()
     }
 
-    fn parse_pr_review_comments(raw: &str) -> Result<Vec<PrReviewComment>, GhCliError> {
-        serde_json::from_str(raw.trim()).map_err(|err| {
-            GhCliError::UnexpectedOutput(format!(
-                "Failed to parse review comments API response: {err}; raw: {raw}"
-            ))
-        })
-    }
-
     fn extract_pr_info(value: &Value) -> Option<PullRequestInfo> {
         let number = value.get("number")?.as_i64()?;
         let url = value.get("url")?.as_str()?.to_string();
@@ -369,7 +1017,9 @@ impl GhCli {
             .get("mergedAt")
             .and_then(Value::as_str)
             .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-            .map(|dt| dt.with_timezone(&Utc));
+            .map(|dt| dt.with_timezone(&Utc))
+            // gh versions print Go's zero time instead of null for unmerged pull requests
+            .filter(|dt| dt.timestamp() > 0);
         let merge_commit_sha = value
             .get("mergeCommit")
             .and_then(|v| v.get("oid"))
@@ -389,3 +1039,90 @@ impl GhCli {
         })
     }
 }
+
+fn commit_status_state_name(state: CommitStatusState) -> &'static str {
+    match state {
+        CommitStatusState::Pending => "pending",
+        CommitStatusState::Success => "success",
+        CommitStatusState::Failure => "failure",
+        CommitStatusState::Error => "error",
+    }
+}
+
+fn merge_method_flag(method: MergeMethod) -> &'static str {
+    match method {
+        MergeMethod::Merge => "--merge",
+        MergeMethod::Squash => "--squash",
+        MergeMethod::Rebase => "--rebase",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::cli_output::fixtures::recorded;
+
+    const PR_URL: &str = "https://github.com/acme/app/pull/42";
+
+    #[test]
+    fn parses_pr_create_output_of_every_release() {
+        for (version, raw) in recorded("gh", "pr_create.txt") {
+            let pr =
+                GhCli::parse_pr_create_text(&raw).unwrap_or_else(|e| panic!("gh {version}: {e}"));
+            assert_eq!(pr.number, 42, "gh {version}");
+            assert_eq!(pr.url, PR_URL, "gh {version}");
+        }
+    }
+
+    #[test]
+    fn parses_pr_view_output_of_every_release() {
+        for (version, raw) in recorded("gh", "pr_view.json") {
+            let pr = GhCli::parse_pr_view(&raw).unwrap_or_else(|e| panic!("gh {version}: {e}"));
+            assert_eq!(pr.number, 42, "gh {version}");
+            assert_eq!(pr.url, PR_URL, "gh {version}");
+            assert!(matches!(pr.status, MergeStatus::Merged), "gh {version}");
+            assert_eq!(
+                pr.merge_commit_sha.as_deref(),
+                Some("abc123"),
+                "gh {version}"
+            );
+            assert!(pr.merged_at.is_some(), "gh {version}");
+        }
+    }
+
+    #[test]
+    fn parses_pr_list_output_of_every_release() {
+        for (version, raw) in recorded("gh", "pr_list.json") {
+            let prs = GhCli::parse_pr_list(&raw).unwrap_or_else(|e| panic!("gh {version}: {e}"));
+            let numbers: Vec<_> = prs.iter().map(|pr| pr.number).collect();
+            assert_eq!(numbers, [43, 42], "gh {version}");
+            assert!(matches!(prs[0].status, MergeStatus::Open), "gh {version}");
+            assert!(prs[0].merged_at.is_none(), "gh {version}");
+            assert!(matches!(prs[1].status, MergeStatus::Merged), "gh {version}");
+        }
+    }
+
+    #[test]
+    fn parses_pr_comments_output_of_every_release() {
+        for (version, raw) in recorded("gh", "pr_comments.json") {
+            let comments =
+                GhCli::parse_pr_comments(&raw).unwrap_or_else(|e| panic!("gh {version}: {e}"));
+            assert_eq!(comments.len(), 1, "gh {version}");
+            assert_eq!(comments[0].id, "IC_kwDOAbc123", "gh {version}");
+            assert_eq!(comments[0].author.login, "octocat", "gh {version}");
+            assert_eq!(comments[0].body, "Looks good", "gh {version}");
+        }
+    }
+
+    #[test]
+    fn unparseable_output_is_an_unexpected_output_error() {
+        assert!(matches!(
+            GhCli::parse_pr_create_text("Pull request created"),
+            Err(GhCliError::UnexpectedOutput(_))
+        ));
+        assert!(matches!(
+            GhCli::parse_pr_list("{\"number\": 42}"),
+            Err(GhCliError::UnexpectedOutput(_))
+        ));
+    }
+}
@@ -1,13 +1,21 @@
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use std::{path::Path, sync::Arc, time::Duration};
 
-use db::models::image::{CreateImage, Image};
+use db::models::{
+    image::{CreateImage, Image},
+    stored_object::StoredObjectKind,
+};
 use sha2::{Digest, Sha256};
 use sqlx::SqlitePool;
 use uuid::Uuid;
 
+use crate::services::{
+    object_store::{ObjectStore, SignedUrl, StorageError},
+    quota::{self, QuotaError},
+};
+
+/// Job that deletes images no task or draft refers to anymore, queued at startup
+pub const DELETE_ORPHANED_IMAGES_JOB: &str = "images.delete_orphaned";
+
 #[derive(Debug, thiserror::Error)]
 pub enum ImageError {
     #[error("IO error: {0}")]
@@ -25,32 +33,55 @@ pub enum ImageError {
     #[error("Image not found")]
     NotFound,
 
+    #[error(transparent)]
+    Quota(#[from] QuotaError),
+
+    #[error(transparent)]
+    Storage(StorageError),
+
     #[error("Failed to build response: {0}")]
     ResponseBuildError(String),
 }
 
+impl From<StorageError> for ImageError {
+    fn from(err: StorageError) -> Self {
+        match err {
+            StorageError::NotFound(_) => ImageError::NotFound,
+            err => ImageError::Storage(err),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ImageService {
-    cache_dir: PathBuf,
+    store: Arc<dyn ObjectStore>,
     pool: SqlitePool,
     max_size_bytes: u64,
+    signed_url_ttl: Duration,
 }
 
 impl ImageService {
-    pub fn new(pool: SqlitePool) -> Result<Self, ImageError> {
-        let cache_dir = utils::cache_dir().join("images");
-        fs::create_dir_all(&cache_dir)?;
-        Ok(Self {
-            cache_dir,
+    pub fn new(pool: SqlitePool, store: Arc<dyn ObjectStore>, signed_url_ttl: Duration) -> Self {
+        Self {
+            store,
             pool,
             max_size_bytes: 20 * 1024 * 1024, // 20MB default
-        })
+            signed_url_ttl,
+        }
     }
 
+    /// Images are stored under `images/`, which keeps the files of local installs where they were
+    fn object_key(image_file: &str) -> String {
+        format!("images/{image_file}")
+    }
+
+    /// Store an uploaded image for a tenant. Identical images are stored once and count against
+    /// the storage quota of the tenant that uploaded them first.
     pub async fn store_image(
         &self,
         data: &[u8],
         original_filename: &str,
+        tenant_id: Uuid,
     ) -> Result<Image, ImageError> {
         let file_size = data.len() as u64;
 
@@ -88,8 +119,16 @@ impl ImageService {
         }
 
         let new_filename = format!("{}.{}", Uuid::new_v4(), extension);
-        let cached_path = self.cache_dir.join(&new_filename);
-        fs::write(&cached_path, data)?;
+        quota::store_object(
+            &self.pool,
+            self.store.as_ref(),
+            tenant_id,
+            StoredObjectKind::Image,
+            &Self::object_key(&new_filename),
+            data.to_vec(),
+            mime_type.as_deref(),
+        )
+        .await?;
 
         let image = Image::create(
             &self.pool,
@@ -100,6 +139,7 @@ impl ImageService {
                 size_bytes: file_size as i64,
                 hash,
             },
+            tenant_id,
         )
         .await?;
         Ok(image)
@@ -141,8 +181,17 @@ impl ImageService {
         Ok(())
     }
 
-    pub fn get_absolute_path(&self, image: &Image) -> PathBuf {
-        self.cache_dir.join(&image.file_path)
+    /// Contents of an image
+    pub async fn read_image(&self, image: &Image) -> Result<Vec<u8>, ImageError> {
+        Ok(self.store.get(&Self::object_key(&image.file_path)).await?)
+    }
+
+    /// URL to download an image without authentication for a while
+    pub async fn download_url(&self, image: &Image) -> Result<SignedUrl, ImageError> {
+        Ok(self
+            .store
+            .signed_url(&Self::object_key(&image.file_path), self.signed_url_ttl)
+            .await?)
     }
 
     pub async fn get_image(&self, id: Uuid) -> Result<Option<Image>, ImageError> {
@@ -151,10 +200,12 @@ impl ImageService {
 
     pub async fn delete_image(&self, id: Uuid) -> Result<(), ImageError> {
         if let Some(image) = Image::find_by_id(&self.pool, id).await? {
-            let file_path = self.cache_dir.join(&image.file_path);
-            if file_path.exists() {
-                fs::remove_file(file_path)?;
-            }
+            quota::delete_object(
+                &self.pool,
+                self.store.as_ref(),
+                &Self::object_key(&image.file_path),
+            )
+            .await?;
 
             Image::delete(&self.pool, id).await?;
         }
@@ -168,7 +219,7 @@ impl ImageService {
         task_id: Uuid,
     ) -> Result<(), ImageError> {
         let images = Image::find_by_task_id(&self.pool, task_id).await?;
-        self.copy_images(worktree_path, images)
+        self.copy_images(worktree_path, images).await
     }
 
     pub async fn copy_images_by_ids_to_worktree(
@@ -182,11 +233,15 @@ impl ImageService {
                 images.push(image);
             }
         }
-        self.copy_images(worktree_path, images)
+        self.copy_images(worktree_path, images).await
     }
 
     /// Copy images to the worktree. Skips images that already exist at target.
-    fn copy_images(&self, worktree_path: &Path, images: Vec<Image>) -> Result<(), ImageError> {
+    async fn copy_images(
+        &self,
+        worktree_path: &Path,
+        images: Vec<Image>,
+    ) -> Result<(), ImageError> {
         if images.is_empty() {
             return Ok(());
         }
@@ -210,21 +265,24 @@ impl ImageService {
         }
 
         for image in images {
-            let src = self.cache_dir.join(&image.file_path);
             let dst = images_dir.join(&image.file_path);
 
             if dst.exists() {
                 continue;
             }
 
-            if src.exists() {
-                if let Err(e) = std::fs::copy(&src, &dst) {
-                    tracing::error!("Failed to copy {}: {}", image.file_path, e);
-                } else {
-                    tracing::debug!("Copied {}", image.file_path);
+            match self.store.get(&Self::object_key(&image.file_path)).await {
+                Ok(data) => {
+                    if let Err(e) = std::fs::write(&dst, data) {
+                        tracing::error!("Failed to copy {}: {}", image.file_path, e);
+                    } else {
+                        tracing::debug!("Copied {}", image.file_path);
+                    }
+                }
+                Err(StorageError::NotFound(_)) => {
+                    tracing::warn!("Missing stored image: {}", image.file_path);
                 }
-            } else {
-                tracing::warn!("Missing cache file: {}", src.display());
+                Err(e) => tracing::error!("Failed to read {}: {}", image.file_path, e),
             }
         }
 
@@ -0,0 +1,451 @@
+//! Background jobs.
+//!
+//! Work that runs outside of a request (polling pull requests, fetching repositories, delivering
+//! notifications and webhooks, pruning old rows) is queued in the `jobs` table and picked up by a
+//! pool of workers, so it survives restarts instead of vanishing with a spawned task. A failed
+//! attempt is retried with exponential backoff; a job that runs out of attempts moves to the
+//! dead-letter queue, where it stays until it is retried or deleted. Recurring jobs are
+//! rescheduled after every run instead of finishing, and are never dead-lettered: their next run
+//! is the retry.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use db::models::job::Job;
+use serde_json::Value;
+use sqlx::{SqliteConnection, SqlitePool};
+use tokio::{
+    sync::Notify,
+    task::{AbortHandle, JoinHandle},
+};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Attempts of a job before it is dead-lettered
+pub const DEFAULT_MAX_ATTEMPTS: i64 = 5;
+
+/// Workers started by default
+const DEFAULT_WORKERS: usize = 4;
+
+/// How long an idle worker waits before looking for due jobs again, unless it is woken by a new job
+const IDLE_POLL: Duration = Duration::from_secs(5);
+
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(15);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// Deletes finished jobs after their retention period
+pub const PRUNE_JOBS: &str = "jobs.prune";
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const SUCCEEDED_RETENTION_DAYS: i64 = 7;
+const DEAD_RETENTION_DAYS: i64 = 30;
+
+/// Runs the jobs of one kind. An error fails the attempt; its message is kept on the job.
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    async fn run(&self, payload: Value) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+impl<F, Fut> JobHandler for F
+where
+    F: Fn(Value) -> Fut + Send + Sync,
+    Fut: Future<Output = anyhow::Result<()>> + Send,
+{
+    async fn run(&self, payload: Value) -> anyhow::Result<()> {
+        self(payload).await
+    }
+}
+
+/// Error kept on jobs cancelled by an operator
+const CANCELLED: &str = "Cancelled";
+
+/// Handle for queueing jobs; cheap to clone
+#[derive(Clone)]
+pub struct JobQueue {
+    pool: SqlitePool,
+    wake: Arc<Notify>,
+    /// Handler tasks of the jobs the workers are running
+    running: Arc<Mutex<HashMap<Uuid, AbortHandle>>>,
+}
+
+/// Result of cancelling a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelOutcome {
+    /// The job had not started and is now dead-lettered
+    Cancelled,
+    /// The job's handler was aborted; the worker dead-letters it
+    Aborted,
+    /// The job does not exist or has already finished
+    NotCancellable,
+}
+
+impl JobQueue {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            wake: Arc::new(Notify::new()),
+            running: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Stop a job. A pending job is dead-lettered so it can still be retried; a running job has
+    /// its handler aborted at its next await point. Recurring jobs stay stopped until they are
+    /// retried or the app restarts.
+    pub async fn cancel(&self, id: Uuid) -> Result<CancelOutcome, sqlx::Error> {
+        if let Some(handle) = self.running.lock().unwrap().get(&id) {
+            handle.abort();
+            return Ok(CancelOutcome::Aborted);
+        }
+        Ok(if Job::cancel_pending(&self.pool, id).await? > 0 {
+            CancelOutcome::Cancelled
+        } else {
+            CancelOutcome::NotCancellable
+        })
+    }
+
+    /// Queue a job to run as soon as a worker is free
+    pub async fn enqueue(&self, kind: &str, payload: Value) -> Result<Job, sqlx::Error> {
+        self.enqueue_after(kind, payload, Duration::ZERO).await
+    }
+
+    /// Queue a job to run once `delay` has passed
+    pub async fn enqueue_after(
+        &self,
+        kind: &str,
+        payload: Value,
+        delay: Duration,
+    ) -> Result<Job, sqlx::Error> {
+        self.enqueue_with_attempts(kind, payload, delay, DEFAULT_MAX_ATTEMPTS)
+            .await
+    }
+
+    /// Queue a job that gets `max_attempts` instead of [`DEFAULT_MAX_ATTEMPTS`] before it is
+    /// dead-lettered, for work that may have to wait out a long outage
+    pub async fn enqueue_with_attempts(
+        &self,
+        kind: &str,
+        payload: Value,
+        delay: Duration,
+        max_attempts: i64,
+    ) -> Result<Job, sqlx::Error> {
+        let job = Job::enqueue(
+            &self.pool,
+            kind,
+            &payload,
+            max_attempts.max(1),
+            delay.as_secs() as i64,
+        )
+        .await?;
+        if delay.is_zero() {
+            self.wake.notify_one();
+        }
+        Ok(job)
+    }
+
+    /// Queue a job as part of a transaction, so it only exists if the transaction commits
+    pub async fn enqueue_in(
+        &self,
+        conn: &mut SqliteConnection,
+        kind: &str,
+        payload: Value,
+    ) -> Result<Job, sqlx::Error> {
+        let job = Job::enqueue(conn, kind, &payload, DEFAULT_MAX_ATTEMPTS, 0).await?;
+        // Until the commit the worker finds nothing; it also polls, so an early wake-up is fine
+        self.wake.notify_one();
+        Ok(job)
+    }
+}
+
+/// What happens to a job after an attempt
+#[derive(Debug, PartialEq, Eq)]
+pub enum NextStep {
+    Finish,
+    /// Run again after the delay; a new run of a recurring job starts with fresh attempts
+    Reschedule {
+        delay: Duration,
+        reset_attempts: bool,
+    },
+    DeadLetter,
+}
+
+/// Delay before retrying a job whose attempt number `attempts` failed: 15 seconds, doubling with
+/// every attempt up to an hour
+pub fn retry_delay(attempts: i64) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    BASE_RETRY_DELAY
+        .saturating_mul(2u32.saturating_pow(exponent))
+        .min(MAX_RETRY_DELAY)
+}
+
+pub fn next_step(job: &Job, succeeded: bool) -> NextStep {
+    let interval = job
+        .interval_secs
+        .map(|secs| Duration::from_secs(secs.max(1) as u64));
+    match (interval, succeeded) {
+        (Some(interval), true) => NextStep::Reschedule {
+            delay: interval,
+            reset_attempts: true,
+        },
+        (None, true) => NextStep::Finish,
+        (interval, false) if job.attempts < job.max_attempts => NextStep::Reschedule {
+            // Never wait longer for a retry than for the next regular run
+            delay: interval.map_or(retry_delay(job.attempts), |interval| {
+                retry_delay(job.attempts).min(interval)
+            }),
+            reset_attempts: false,
+        },
+        (Some(interval), false) => NextStep::Reschedule {
+            delay: interval,
+            reset_attempts: true,
+        },
+        (None, false) => NextStep::DeadLetter,
+    }
+}
+
+/// Worker pool that runs queued jobs with the registered handlers
+pub struct JobRunner {
+    queue: JobQueue,
+    handlers: HashMap<String, Arc<dyn JobHandler>>,
+    recurring: Vec<(String, Duration)>,
+    workers: usize,
+}
+
+impl JobRunner {
+    /// Runner with the pruning of finished jobs registered
+    pub fn new(queue: JobQueue) -> Self {
+        let pool = queue.pool.clone();
+        Self {
+            queue,
+            handlers: HashMap::new(),
+            recurring: Vec::new(),
+            workers: DEFAULT_WORKERS,
+        }
+        .recurring(PRUNE_JOBS, PRUNE_INTERVAL, move |_: Value| {
+            let pool = pool.clone();
+            async move {
+                let pruned =
+                    Job::prune_finished(&pool, SUCCEEDED_RETENTION_DAYS, DEAD_RETENTION_DAYS)
+                        .await?;
+                if pruned > 0 {
+                    debug!("Pruned {} finished jobs", pruned);
+                }
+                Ok::<_, anyhow::Error>(())
+            }
+        })
+    }
+
+    /// Run jobs of `kind` with `handler`, replacing an earlier handler of the same kind
+    pub fn handler(mut self, kind: &str, handler: impl JobHandler + 'static) -> Self {
+        self.handlers.insert(kind.to_string(), Arc::new(handler));
+        self
+    }
+
+    /// Run `handler` every `interval`, starting right away the first time the job is scheduled
+    pub fn recurring(
+        mut self,
+        kind: &str,
+        interval: Duration,
+        handler: impl JobHandler + 'static,
+    ) -> Self {
+        self.recurring.retain(|(existing, _)| existing != kind);
+        self.recurring.push((kind.to_string(), interval));
+        self.handler(kind, handler)
+    }
+
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Requeue jobs interrupted by the last shutdown, schedule the recurring jobs and start the
+    /// workers
+    pub async fn spawn(self) -> Result<Vec<JoinHandle<()>>, sqlx::Error> {
+        let pool = &self.queue.pool;
+        let interrupted = Job::requeue_interrupted(pool).await?;
+        if interrupted > 0 {
+            info!(
+                "Requeued {} jobs interrupted by the last shutdown",
+                interrupted
+            );
+        }
+        for (kind, interval) in &self.recurring {
+            Job::ensure_recurring(
+                pool,
+                kind,
+                interval.as_secs().max(1) as i64,
+                DEFAULT_MAX_ATTEMPTS,
+            )
+            .await?;
+        }
+
+        info!("Starting {} job workers", self.workers);
+        let handlers = Arc::new(self.handlers);
+        Ok((0..self.workers)
+            .map(|_| {
+                let queue = self.queue.clone();
+                let handlers = handlers.clone();
+                tokio::spawn(async move { work(queue, handlers).await })
+            })
+            .collect())
+    }
+}
+
+async fn work(queue: JobQueue, handlers: Arc<HashMap<String, Arc<dyn JobHandler>>>) {
+    loop {
+        match Job::claim_next(&queue.pool).await {
+            Ok(Some(job)) => {
+                if let Err(e) = run_job(&queue, &handlers, job).await {
+                    warn!("Failed to record job outcome: {}", e);
+                }
+            }
+            Ok(None) => {
+                tokio::select! {
+                    _ = queue.wake.notified() => {}
+                    _ = tokio::time::sleep(IDLE_POLL) => {}
+                }
+            }
+            Err(e) => {
+                warn!("Failed to claim a job: {}", e);
+                tokio::time::sleep(IDLE_POLL).await;
+            }
+        }
+    }
+}
+
+async fn run_job(
+    queue: &JobQueue,
+    handlers: &HashMap<String, Arc<dyn JobHandler>>,
+    job: Job,
+) -> Result<(), sqlx::Error> {
+    let pool = &queue.pool;
+    let Some(handler) = handlers.get(&job.kind).cloned() else {
+        warn!("No handler for job {} of kind {}", job.id, job.kind);
+        return Job::mark_dead(pool, job.id, &format!("No handler for {}", job.kind)).await;
+    };
+
+    // Run on its own task so a panicking handler fails the attempt instead of the worker
+    let payload = job.payload.0.clone();
+    let task = tokio::spawn(async move { handler.run(payload).await });
+    queue
+        .running
+        .lock()
+        .unwrap()
+        .insert(job.id, task.abort_handle());
+    let joined = task.await;
+    queue.running.lock().unwrap().remove(&job.id);
+    let result = match joined {
+        Ok(result) => result.map_err(|e| format!("{e:#}")),
+        Err(e) if e.is_cancelled() => {
+            info!("Job {} ({}) was cancelled", job.id, job.kind);
+            return Job::mark_dead(pool, job.id, CANCELLED).await;
+        }
+        Err(e) => Err(format!("Job handler panicked: {e}")),
+    };
+    if let Err(e) = &result {
+        warn!(
+            "Job {} ({}) failed on attempt {}/{}: {}",
+            job.id, job.kind, job.attempts, job.max_attempts, e
+        );
+    }
+
+    match next_step(&job, result.is_ok()) {
+        NextStep::Finish => Job::mark_succeeded(pool, job.id).await,
+        NextStep::Reschedule {
+            delay,
+            reset_attempts,
+        } => {
+            Job::reschedule(
+                pool,
+                job.id,
+                delay.as_secs() as i64,
+                result.err().as_deref(),
+                reset_attempts,
+            )
+            .await
+        }
+        NextStep::DeadLetter => {
+            Job::mark_dead(pool, job.id, result.err().as_deref().unwrap_or_default()).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::job::JobStatus;
+    use sqlx::types::Json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn job(attempts: i64, interval_secs: Option<i64>) -> Job {
+        let now = Utc::now();
+        Job {
+            id: Uuid::new_v4(),
+            kind: "test".to_string(),
+            payload: Json(Value::Null),
+            status: JobStatus::Running,
+            attempts,
+            max_attempts: 3,
+            interval_secs,
+            run_at: now,
+            started_at: Some(now),
+            finished_at: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_an_hour() {
+        assert_eq!(retry_delay(1), Duration::from_secs(15));
+        assert_eq!(retry_delay(2), Duration::from_secs(30));
+        assert_eq!(retry_delay(4), Duration::from_secs(120));
+        assert_eq!(retry_delay(20), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn one_off_jobs_are_retried_then_dead_lettered() {
+        assert_eq!(next_step(&job(1, None), true), NextStep::Finish);
+        assert_eq!(
+            next_step(&job(2, None), false),
+            NextStep::Reschedule {
+                delay: Duration::from_secs(30),
+                reset_attempts: false,
+            }
+        );
+        assert_eq!(next_step(&job(3, None), false), NextStep::DeadLetter);
+    }
+
+    #[test]
+    fn recurring_jobs_are_never_dead_lettered() {
+        assert_eq!(
+            next_step(&job(1, Some(60)), true),
+            NextStep::Reschedule {
+                delay: Duration::from_secs(60),
+                reset_attempts: true,
+            }
+        );
+        // Retries never wait past the next regular run
+        assert_eq!(
+            next_step(&job(2, Some(20)), false),
+            NextStep::Reschedule {
+                delay: Duration::from_secs(20),
+                reset_attempts: false,
+            }
+        );
+        assert_eq!(
+            next_step(&job(3, Some(60)), false),
+            NextStep::Reschedule {
+                delay: Duration::from_secs(60),
+                reset_attempts: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Organization libraries of prompt templates, task templates and automation rules.
+//!
+//! Publishing sends a local item to an organization's library, and pulling copies a library item
+//! into this instance. The library bumps an item's version on every update, while this instance
+//! remembers the version each local copy was last published or pulled at, so a library version
+//! ahead of that one means an update is available. Pulling an update replaces the local copy.
+//! Automation rules are pulled disabled and without a project, since projects only exist on the
+//! instance they were created on.
+
+use db::models::{
+    automation_rule::{AutomationRule, CreateAutomationRule, RuleAction, UpdateAutomationRule},
+    library_link::LibraryLink,
+    template::{CreateTemplate, Template, TemplateKind, UpdateTemplate},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::api::library::{
+    LibraryItem, LibraryItemKind, PublishLibraryItemRequest, UpdateLibraryItemRequest,
+};
+use uuid::Uuid;
+
+use crate::services::remote_client::{RemoteClient, RemoteClientError};
+
+#[derive(Debug, Error)]
+pub enum LibraryError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Remote(#[from] RemoteClientError),
+    #[error("library item has invalid content: {0}")]
+    InvalidContent(#[from] serde_json::Error),
+    #[error("no local {0:?} with id {1}")]
+    LocalItemNotFound(LibraryItemKind, Uuid),
+    #[error("library item {0} not found")]
+    ItemNotFound(Uuid),
+    #[error("already published to organization {0}")]
+    LinkedElsewhere(Uuid),
+}
+
+/// Content of prompt and task templates
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct TemplateContent {
+    #[serde(default)]
+    title: Option<String>,
+    content: String,
+}
+
+/// Content of automation rules
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct RuleContent {
+    condition: String,
+    action: RuleAction,
+}
+
+/// A library item as seen from this instance
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct LibraryEntry {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub item: LibraryItem,
+    /// The local copy, when the item was published from or pulled to this instance
+    pub local_id: Option<Uuid>,
+    pub local_version: Option<i64>,
+    pub update_available: bool,
+}
+
+impl LibraryEntry {
+    fn new(item: LibraryItem, link: Option<&LibraryLink>) -> Self {
+        Self {
+            update_available: update_available(link, &item),
+            local_id: link.map(|link| link.local_id),
+            local_version: link.map(|link| link.version),
+            item,
+        }
+    }
+}
+
+/// Whether the library has a newer version than the local copy
+pub fn update_available(link: Option<&LibraryLink>, item: &LibraryItem) -> bool {
+    link.is_some_and(|link| item.version > link.version)
+}
+
+fn template_kind(kind: LibraryItemKind) -> Option<TemplateKind> {
+    match kind {
+        LibraryItemKind::PromptTemplate => Some(TemplateKind::Prompt),
+        LibraryItemKind::TaskTemplate => Some(TemplateKind::Task),
+        LibraryItemKind::AutomationRule => None,
+    }
+}
+
+/// Name and library content of a local item
+async fn local_content(
+    pool: &SqlitePool,
+    kind: LibraryItemKind,
+    local_id: Uuid,
+) -> Result<(String, Value), LibraryError> {
+    let not_found = || LibraryError::LocalItemNotFound(kind, local_id);
+    match template_kind(kind) {
+        Some(template_kind) => {
+            let template = Template::find_by_id(pool, local_id)
+                .await?
+                .filter(|template| template.kind == template_kind)
+                .ok_or_else(not_found)?;
+            let content = serde_json::to_value(TemplateContent {
+                title: template.title,
+                content: template.content,
+            })?;
+            Ok((template.name, content))
+        }
+        None => {
+            let rule = AutomationRule::find_by_id(pool, local_id)
+                .await?
+                .ok_or_else(not_found)?;
+            let content = serde_json::to_value(RuleContent {
+                condition: rule.condition,
+                action: rule.action.0,
+            })?;
+            Ok((rule.name, content))
+        }
+    }
+}
+
+/// Items of an organization's library with the state of their local copies
+pub async fn list(
+    pool: &SqlitePool,
+    client: &RemoteClient,
+    organization_id: Uuid,
+) -> Result<Vec<LibraryEntry>, LibraryError> {
+    let items = client.list_library_items(organization_id).await?.items;
+    let links = LibraryLink::find_by_organization_id(pool, organization_id).await?;
+    Ok(items
+        .into_iter()
+        .map(|item| {
+            let link = links.iter().find(|link| link.library_item_id == item.id);
+            LibraryEntry::new(item, link)
+        })
+        .collect())
+}
+
+/// Publish a local item to an organization, as a new version when it was published before
+pub async fn publish(
+    pool: &SqlitePool,
+    client: &RemoteClient,
+    organization_id: Uuid,
+    kind: LibraryItemKind,
+    local_id: Uuid,
+) -> Result<LibraryEntry, LibraryError> {
+    let (name, content) = local_content(pool, kind, local_id).await?;
+
+    let updated = match LibraryLink::find_by_local_id(pool, local_id).await? {
+        Some(link) if link.organization_id != organization_id => {
+            return Err(LibraryError::LinkedElsewhere(link.organization_id));
+        }
+        Some(link) => {
+            let request = UpdateLibraryItemRequest {
+                name: name.clone(),
+                content: content.clone(),
+            };
+            match client
+                .update_library_item(link.library_item_id, &request)
+                .await
+            {
+                Ok(item) => Some(item),
+                // Removed from the library since; publish it again as a new item
+                Err(RemoteClientError::Http { status: 404, .. }) => {
+                    LibraryLink::delete(pool, link.library_item_id).await?;
+                    None
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        None => None,
+    };
+    let item = match updated {
+        Some(item) => item,
+        None => {
+            client
+                .publish_library_item(&PublishLibraryItemRequest {
+                    organization_id,
+                    kind,
+                    name,
+                    content,
+                })
+                .await?
+        }
+    };
+
+    let link = LibraryLink::upsert(pool, item.id, organization_id, local_id, item.version).await?;
+    Ok(LibraryEntry::new(item, Some(&link)))
+}
+
+/// Copy a library item to this instance, or update the local copy to the library version
+pub async fn pull(
+    pool: &SqlitePool,
+    client: &RemoteClient,
+    organization_id: Uuid,
+    item_id: Uuid,
+) -> Result<LibraryEntry, LibraryError> {
+    let item = client
+        .list_library_items(organization_id)
+        .await?
+        .items
+        .into_iter()
+        .find(|item| item.id == item_id)
+        .ok_or(LibraryError::ItemNotFound(item_id))?;
+    let existing = LibraryLink::find_by_library_item_id(pool, item.id)
+        .await?
+        .map(|link| link.local_id);
+
+    let local_id = match template_kind(item.kind) {
+        Some(template_kind) => {
+            let content: TemplateContent = serde_json::from_value(item.content.clone())?;
+            let template = match existing {
+                Some(id) if Template::find_by_id(pool, id).await?.is_some() => {
+                    Template::update(
+                        pool,
+                        id,
+                        &UpdateTemplate {
+                            name: Some(item.name.clone()),
+                            title: content.title,
+                            content: Some(content.content),
+                        },
+                    )
+                    .await?
+                }
+                _ => {
+                    Template::create(
+                        pool,
+                        &CreateTemplate {
+                            kind: template_kind,
+                            name: item.name.clone(),
+                            title: content.title,
+                            content: content.content,
+                        },
+                    )
+                    .await?
+                }
+            };
+            template.id
+        }
+        None => {
+            let content: RuleContent = serde_json::from_value(item.content.clone())?;
+            let rule = match existing {
+                Some(id) if AutomationRule::find_by_id(pool, id).await?.is_some() => {
+                    AutomationRule::update(
+                        pool,
+                        id,
+                        &UpdateAutomationRule {
+                            name: Some(item.name.clone()),
+                            condition: Some(content.condition),
+                            action: Some(content.action),
+                            enabled: None,
+                        },
+                    )
+                    .await?
+                }
+                _ => {
+                    AutomationRule::create(
+                        pool,
+                        &CreateAutomationRule {
+                            project_id: None,
+                            name: item.name.clone(),
+                            condition: content.condition,
+                            action: content.action,
+                            enabled: Some(false),
+                        },
+                    )
+                    .await?
+                }
+            };
+            rule.id
+        }
+    };
+
+    let link = LibraryLink::upsert(pool, item.id, organization_id, local_id, item.version).await?;
+    Ok(LibraryEntry::new(item, Some(&link)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn item(version: i64, content: Value) -> LibraryItem {
+        LibraryItem {
+            id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            kind: LibraryItemKind::TaskTemplate,
+            name: "Bug report".to_string(),
+            content,
+            version,
+            published_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn update_is_available_only_for_newer_library_versions() {
+        let item = item(3, Value::Null);
+        let link = |version| LibraryLink {
+            library_item_id: item.id,
+            organization_id: item.organization_id,
+            local_id: Uuid::new_v4(),
+            version,
+            synced_at: Utc::now(),
+        };
+        assert!(update_available(Some(&link(2)), &item));
+        assert!(!update_available(Some(&link(3)), &item));
+        assert!(!update_available(None, &item));
+    }
+
+    #[test]
+    fn template_content_without_title_is_accepted() {
+        let item = item(1, serde_json::json!({ "content": "Steps to reproduce:" }));
+        let content: TemplateContent = serde_json::from_value(item.content).unwrap();
+        assert_eq!(content.title, None);
+        assert_eq!(content.content, "Steps to reproduce:");
+
+        let rule: Result<RuleContent, _> =
+            serde_json::from_value(serde_json::json!({ "content": "Steps to reproduce:" }));
+        assert!(rule.is_err());
+    }
+}
@@ -0,0 +1,427 @@
+//! Post-execution lint stage.
+//!
+//! Configured lint commands run in each repository worktree once a coding agent turn has
+//! finished. Their output is parsed into [`LintFinding`]s so the findings can be attached to the
+//! attempt and, optionally, handed back to the agent as a fix-up turn.
+
+use std::{path::Path, process::Stdio, sync::LazyLock, time::Duration};
+
+use db::models::lint_report::{LintFinding, LintSeverity};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use tokio::process::Command;
+use ts_rs::TS;
+use utils::shell::get_shell_command;
+
+/// Findings listed in a fix-up prompt before the rest are summarised
+const MAX_PROMPT_FINDINGS: usize = 50;
+
+fn default_changed_files_only() -> bool {
+    true
+}
+
+fn default_max_fix_attempts() -> u32 {
+    2
+}
+
+fn default_timeout_secs() -> u32 {
+    600
+}
+
+#[derive(Debug, Error)]
+pub enum LintError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Lint command '{command}' timed out after {secs}s")]
+    Timeout { command: String, secs: u64 },
+}
+
+/// How a lint command's output is parsed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintFormat {
+    /// `cargo clippy --message-format=json`
+    Clippy,
+    /// `eslint -f json`
+    Eslint,
+    /// `ruff check --output-format json`
+    Ruff,
+    /// `path:line[:column]: message` lines, as printed by most compilers and linters
+    Custom,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct LintCommand {
+    pub name: String,
+    /// Repository the command runs in; `None` runs it in every repository
+    #[serde(default)]
+    pub repo: Option<String>,
+    pub command: String,
+    pub format: LintFormat,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct LintConfig {
+    pub enabled: bool,
+    pub commands: Vec<LintCommand>,
+    /// Only keep findings in files the workspace branch changed
+    #[serde(default = "default_changed_files_only")]
+    pub changed_files_only: bool,
+    /// Start a follow-up turn asking the agent to fix the findings
+    pub auto_fix: bool,
+    /// Fix-up turns in a row before the attempt is left for review as is
+    #[serde(default = "default_max_fix_attempts")]
+    pub max_fix_attempts: u32,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u32,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            commands: Vec::new(),
+            changed_files_only: default_changed_files_only(),
+            auto_fix: false,
+            max_fix_attempts: default_max_fix_attempts(),
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+}
+
+impl LintConfig {
+    pub fn commands_for_repo<'a>(&'a self, repo: &'a str) -> impl Iterator<Item = &'a LintCommand> {
+        self.commands
+            .iter()
+            .filter(move |command| command.repo.as_deref().is_none_or(|r| r == repo))
+    }
+}
+
+/// Run one lint command in `repo_path` and parse its diagnostics
+pub async fn run_command(
+    repo: &str,
+    repo_path: &Path,
+    command: &LintCommand,
+    timeout: Duration,
+) -> Result<Vec<LintFinding>, LintError> {
+    let (shell, shell_arg) = get_shell_command();
+    let output = Command::new(shell)
+        .arg(shell_arg)
+        .arg(&command.command)
+        .current_dir(repo_path)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output =
+        tokio::time::timeout(timeout, output)
+            .await
+            .map_err(|_| LintError::Timeout {
+                command: command.name.clone(),
+                secs: timeout.as_secs(),
+            })??;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let combined;
+    let text = match command.format {
+        LintFormat::Custom => {
+            combined = format!("{stdout}\n{stderr}");
+            combined.as_str()
+        }
+        _ => stdout.as_ref(),
+    };
+    let mut findings = parse_output(command.format, text, repo_path)
+        .into_iter()
+        .map(|diagnostic| diagnostic.into_finding(repo, &command.name))
+        .collect::<Vec<_>>();
+
+    // A failing command that printed nothing we understand is itself a finding, otherwise a
+    // broken lint setup would silently pass
+    if findings.is_empty() && !output.status.success() {
+        let tail: Vec<&str> = stderr.lines().rev().take(5).collect();
+        let details = tail.into_iter().rev().collect::<Vec<_>>().join("\n");
+        findings.push(LintFinding {
+            repo: repo.to_string(),
+            command: command.name.clone(),
+            path: String::new(),
+            line: None,
+            column: None,
+            severity: LintSeverity::Error,
+            rule: None,
+            message: format!(
+                "`{}` exited with {}\n{}",
+                command.command, output.status, details
+            )
+            .trim_end()
+            .to_string(),
+        });
+    }
+    Ok(findings)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Diagnostic {
+    path: String,
+    line: Option<u32>,
+    column: Option<u32>,
+    severity: LintSeverity,
+    rule: Option<String>,
+    message: String,
+}
+
+impl Diagnostic {
+    fn into_finding(self, repo: &str, command: &str) -> LintFinding {
+        LintFinding {
+            repo: repo.to_string(),
+            command: command.to_string(),
+            path: self.path,
+            line: self.line,
+            column: self.column,
+            severity: self.severity,
+            rule: self.rule,
+            message: self.message,
+        }
+    }
+}
+
+fn parse_output(format: LintFormat, output: &str, repo_path: &Path) -> Vec<Diagnostic> {
+    match format {
+        LintFormat::Clippy => parse_clippy(output),
+        LintFormat::Eslint => parse_eslint(output, repo_path),
+        LintFormat::Ruff => parse_ruff(output, repo_path),
+        LintFormat::Custom => parse_custom(output, repo_path),
+    }
+}
+
+fn relative_path(path: &str, repo_path: &Path) -> String {
+    Path::new(path)
+        .strip_prefix(repo_path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.trim_start_matches("./").to_string())
+        .replace('\\', "/")
+}
+
+fn as_u32(value: Option<&Value>) -> Option<u32> {
+    value
+        .and_then(Value::as_u64)
+        .and_then(|n| u32::try_from(n).ok())
+}
+
+fn parse_clippy(output: &str) -> Vec<Diagnostic> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|value| value["reason"] == "compiler-message")
+        .filter_map(|value| {
+            let message = &value["message"];
+            let span = message["spans"]
+                .as_array()?
+                .iter()
+                .find(|span| span["is_primary"] == true)?;
+            let severity = match message["level"].as_str()? {
+                "error" => LintSeverity::Error,
+                "warning" => LintSeverity::Warning,
+                _ => LintSeverity::Info,
+            };
+            Some(Diagnostic {
+                path: span["file_name"].as_str()?.to_string(),
+                line: as_u32(span.get("line_start")),
+                column: as_u32(span.get("column_start")),
+                severity,
+                rule: message["code"]["code"].as_str().map(str::to_string),
+                message: message["message"].as_str()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn parse_eslint(output: &str, repo_path: &Path) -> Vec<Diagnostic> {
+    let Ok(Value::Array(files)) = serde_json::from_str::<Value>(output.trim()) else {
+        return Vec::new();
+    };
+    files
+        .iter()
+        .flat_map(|file| {
+            let path = file["filePath"]
+                .as_str()
+                .map(|p| relative_path(p, repo_path))
+                .unwrap_or_default();
+            file["messages"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(move |message| {
+                    Some(Diagnostic {
+                        path: path.clone(),
+                        line: as_u32(message.get("line")),
+                        column: as_u32(message.get("column")),
+                        severity: match message["severity"].as_u64() {
+                            Some(2) => LintSeverity::Error,
+                            _ => LintSeverity::Warning,
+                        },
+                        rule: message["ruleId"].as_str().map(str::to_string),
+                        message: message["message"].as_str()?.to_string(),
+                    })
+                })
+        })
+        .collect()
+}
+
+fn parse_ruff(output: &str, repo_path: &Path) -> Vec<Diagnostic> {
+    let Ok(Value::Array(violations)) = serde_json::from_str::<Value>(output.trim()) else {
+        return Vec::new();
+    };
+    violations
+        .iter()
+        .filter_map(|violation| {
+            Some(Diagnostic {
+                path: relative_path(violation["filename"].as_str()?, repo_path),
+                line: as_u32(violation["location"].get("row")),
+                column: as_u32(violation["location"].get("column")),
+                severity: LintSeverity::Error,
+                rule: violation["code"].as_str().map(str::to_string),
+                message: violation["message"].as_str()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+static CUSTOM_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?P<path>[^:\s][^:]*):(?P<line>\d+)(?::(?P<column>\d+))?:\s*(?:(?P<severity>error|warning|note|info)\b:?\s*)?(?P<message>.+)$",
+    )
+    .expect("valid lint line regex")
+});
+
+fn parse_custom(output: &str, repo_path: &Path) -> Vec<Diagnostic> {
+    output
+        .lines()
+        .filter_map(|line| CUSTOM_LINE.captures(line.trim_end()))
+        .map(|caps| Diagnostic {
+            path: relative_path(&caps["path"], repo_path),
+            line: caps["line"].parse().ok(),
+            column: caps.name("column").and_then(|c| c.as_str().parse().ok()),
+            severity: match caps.name("severity").map(|s| s.as_str()) {
+                Some("warning") => LintSeverity::Warning,
+                Some("note" | "info") => LintSeverity::Info,
+                _ => LintSeverity::Error,
+            },
+            rule: None,
+            message: caps["message"].trim().to_string(),
+        })
+        .collect()
+}
+
+/// Drop findings outside `changed_paths`; findings without a file are kept
+pub fn retain_changed(findings: &mut Vec<LintFinding>, changed_paths: &[String]) {
+    findings.retain(|finding| {
+        finding.path.is_empty() || changed_paths.iter().any(|path| *path == finding.path)
+    });
+}
+
+/// Follow-up prompt asking the agent to fix lint findings
+pub fn fix_prompt(findings: &[LintFinding]) -> String {
+    let mut prompt = String::from(
+        "The lint stage reported the following problems with your changes. Fix them without \
+         changing behaviour, and do not silence the lints unless a fix is impossible.\n\n",
+    );
+    for finding in findings.iter().take(MAX_PROMPT_FINDINGS) {
+        let mut location = format!("{}/{}", finding.repo, finding.path);
+        if let Some(line) = finding.line {
+            location.push_str(&format!(":{line}"));
+            if let Some(column) = finding.column {
+                location.push_str(&format!(":{column}"));
+            }
+        }
+        let rule = finding
+            .rule
+            .as_deref()
+            .map(|rule| format!(" [{rule}]"))
+            .unwrap_or_default();
+        prompt.push_str(&format!(
+            "- {location} ({}){rule}: {}\n",
+            finding.command, finding.message
+        ));
+    }
+    if findings.len() > MAX_PROMPT_FINDINGS {
+        prompt.push_str(&format!(
+            "- ... and {} more\n",
+            findings.len() - MAX_PROMPT_FINDINGS
+        ));
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_clippy_json() {
+        let output = concat!(
+            r#"{"reason":"compiler-artifact","package_id":"x"}"#,
+            "\n",
+            r#"{"reason":"compiler-message","message":{"level":"warning","code":{"code":"clippy::needless_return"},"message":"unneeded `return` statement","spans":[{"file_name":"src/lib.rs","line_start":4,"column_start":5,"is_primary":true}]}}"#,
+            "\n",
+            r#"{"reason":"compiler-message","message":{"level":"warning","code":null,"message":"1 warning emitted","spans":[]}}"#,
+        );
+        let diagnostics = parse_clippy(output);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic {
+                path: "src/lib.rs".to_string(),
+                line: Some(4),
+                column: Some(5),
+                severity: LintSeverity::Warning,
+                rule: Some("clippy::needless_return".to_string()),
+                message: "unneeded `return` statement".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_eslint_and_ruff_json() {
+        let root = Path::new("/work/web");
+        let eslint = r#"[{"filePath":"/work/web/src/App.tsx","messages":[{"ruleId":"no-unused-vars","severity":2,"message":"'x' is unused","line":3,"column":7}]}]"#;
+        let diagnostics = parse_eslint(eslint, root);
+        assert_eq!(diagnostics[0].path, "src/App.tsx");
+        assert_eq!(diagnostics[0].severity, LintSeverity::Error);
+        assert_eq!(diagnostics[0].rule.as_deref(), Some("no-unused-vars"));
+
+        let ruff = r#"[{"code":"F401","message":"`os` imported but unused","filename":"/work/web/tools/gen.py","location":{"row":1,"column":8}}]"#;
+        let diagnostics = parse_ruff(ruff, root);
+        assert_eq!(diagnostics[0].path, "tools/gen.py");
+        assert_eq!(diagnostics[0].line, Some(1));
+    }
+
+    #[test]
+    fn parses_custom_lines() {
+        let output = "main.go:12:2: warning: unused variable x\n./pkg/a.py:7: missing docstring\nSummary: 2 issues\n";
+        let diagnostics = parse_custom(output, Path::new("/repo"));
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, LintSeverity::Warning);
+        assert_eq!(diagnostics[0].message, "unused variable x");
+        assert_eq!(diagnostics[1].path, "pkg/a.py");
+        assert_eq!(diagnostics[1].column, None);
+    }
+
+    #[test]
+    fn fix_prompt_lists_findings() {
+        let finding = Diagnostic {
+            path: "src/lib.rs".to_string(),
+            line: Some(4),
+            column: Some(5),
+            severity: LintSeverity::Warning,
+            rule: Some("clippy::needless_return".to_string()),
+            message: "unneeded `return` statement".to_string(),
+        }
+        .into_finding("api", "clippy");
+        let prompt = fix_prompt(&[finding]);
+        assert!(prompt.contains(
+            "- api/src/lib.rs:4:5 (clippy) [clippy::needless_return]: unneeded `return` statement"
+        ));
+    }
+}
@@ -1,25 +1,67 @@
 pub mod analytics;
 pub mod approvals;
+pub mod artifacts;
 pub mod auth;
+pub mod board_metrics;
+pub mod calendar;
+pub mod changes_summary;
+pub mod circuit_breaker;
+pub mod cli_output;
+pub mod comment_triggers;
+pub mod commit_status;
+pub mod compaction;
+pub mod compatibility;
 pub mod config;
 pub mod container;
+pub mod content_locale;
+pub mod coverage;
+pub mod dependency_policy;
 pub mod diff_stream;
+pub mod divergence;
+pub mod estimation;
 pub mod events;
 pub mod file_ranker;
 pub mod file_search_cache;
 pub mod filesystem;
 pub mod filesystem_watcher;
+pub mod flaky;
 pub mod git;
 pub mod git_provider;
 pub mod github;
 pub mod image;
+pub mod jobs;
+pub mod library;
+pub mod lint;
 pub mod notification;
 pub mod oauth_credentials;
+pub mod object_store;
+pub mod offline;
+pub mod plugins;
+pub mod policy;
 pub mod pr_monitor;
 pub mod project;
 pub mod queued_message;
+pub mod quota;
+pub mod refine;
+pub mod release;
 pub mod remote_client;
+pub mod replay;
 pub mod repo;
+pub mod repo_chain;
+pub mod repo_fetch;
+pub mod review_follow_ups;
+pub mod routing;
+pub mod rules;
+pub mod saved_views;
+pub mod schedule;
+pub mod scope;
+pub mod secret_scan;
+pub mod self_update;
 pub mod share;
+pub mod similarity;
+pub mod sla;
+pub mod suggestions;
+pub mod task_events;
+pub mod throttle;
 pub mod workspace_manager;
 pub mod worktree_manager;
@@ -1,14 +1,22 @@
 use std::sync::{Arc, OnceLock};
 
+use serde_json::{Value, json};
 use tokio::sync::RwLock;
 use utils;
 
-use crate::services::config::{Config, NotificationConfig, SoundFile};
+use crate::services::{
+    config::{Config, NotificationConfig, SoundFile},
+    jobs::JobQueue,
+};
+
+/// Job that delivers a notification, with `title` and `message` in its payload
+pub const DELIVER_NOTIFICATION_JOB: &str = "notification.deliver";
 
 /// Service for handling cross-platform notifications including sound alerts and push notifications
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct NotificationService {
     config: Arc<RwLock<Config>>,
+    jobs: Option<JobQueue>,
 }
 
 /// Cache for WSL root path from PowerShell
@@ -16,15 +24,45 @@ static WSL_ROOT_PATH_CACHE: OnceLock<Option<String>> = OnceLock::new();
 
 impl NotificationService {
     pub fn new(config: Arc<RwLock<Config>>) -> Self {
-        Self { config }
+        Self { config, jobs: None }
+    }
+
+    /// Deliver notifications as [`DELIVER_NOTIFICATION_JOB`] jobs instead of right away
+    pub fn with_jobs(mut self, jobs: JobQueue) -> Self {
+        self.jobs = Some(jobs);
+        self
     }
 
     /// Send both sound and push notifications if enabled
     pub async fn notify(&self, title: &str, message: &str) {
+        if utils::test_mode::is_enabled() {
+            tracing::debug!("Test mode, not sending notification: {}", title);
+            return;
+        }
+        if let Some(jobs) = &self.jobs {
+            let payload = json!({ "title": title, "message": message });
+            match jobs.enqueue(DELIVER_NOTIFICATION_JOB, payload).await {
+                Ok(_) => return,
+                Err(e) => tracing::warn!("Failed to queue notification, sending it now: {}", e),
+            }
+        }
+        self.deliver(title, message).await;
+    }
+
+    /// Send a notification right away
+    pub async fn deliver(&self, title: &str, message: &str) {
         let config = self.config.read().await.notifications.clone();
         Self::send_notification(&config, title, message).await;
     }
 
+    /// Run a [`DELIVER_NOTIFICATION_JOB`] job
+    pub async fn deliver_job(&self, payload: Value) -> anyhow::Result<()> {
+        let title = payload["title"].as_str().unwrap_or_default();
+        let message = payload["message"].as_str().unwrap_or_default();
+        self.deliver(title, message).await;
+        Ok(())
+    }
+
     /// Internal method to send notifications with a given config
     async fn send_notification(config: &NotificationConfig, title: &str, message: &str) {
         if config.sound_enabled {
@@ -0,0 +1,459 @@
+//! Object storage for files the server keeps: image attachments, execution artifacts and exported
+//! transcripts.
+//!
+//! Objects live on local disk under the cache directory by default. Server deployments can keep
+//! them in S3 or an S3-compatible service like MinIO instead, so they are not bound by the disk of
+//! one machine. Credentials of the S3 backend come from the usual AWS configuration: environment
+//! variables, `~/.aws` profiles or an instance role.
+//!
+//! Downloads go through signed URLs that work without further authentication until they expire.
+//! The S3 backend presigns them; the local backend signs links to the server's own
+//! `/api/storage` route with a key kept next to the objects.
+
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use aws_config::BehaviorVersion;
+use aws_sdk_s3::{
+    Client, config::Region, error::DisplayErrorContext, presigning::PresigningConfig,
+    primitives::ByteStream,
+};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Route of the server that serves objects of the local backend by signed URL
+pub const LOCAL_DOWNLOAD_PATH: &str = "/api/storage";
+
+/// File in the local root holding the key that signs download URLs
+const SIGNING_KEY_FILE: &str = ".signing-key";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    #[default]
+    Local,
+    /// Amazon S3 or an S3-compatible service like MinIO
+    S3,
+}
+
+fn default_signed_url_ttl_secs() -> u64 {
+    3600
+}
+
+/// Where the server keeps attachments, artifacts and transcripts; applied on the next start
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// Bucket of the S3 backend
+    pub bucket: Option<String>,
+    /// Region of the bucket; the region of the AWS configuration when unset
+    pub region: Option<String>,
+    /// Endpoint of an S3-compatible service like MinIO; AWS when unset
+    pub endpoint: Option<String>,
+    /// Address the bucket in the path instead of the host name, which MinIO needs
+    pub force_path_style: bool,
+    /// Prefix of every key, to share a bucket with other data
+    pub prefix: Option<String>,
+    /// Seconds download URLs stay valid
+    #[serde(default = "default_signed_url_ttl_secs")]
+    pub signed_url_ttl_secs: u64,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackend::Local,
+            bucket: None,
+            region: None,
+            endpoint: None,
+            force_path_style: false,
+            prefix: None,
+            signed_url_ttl_secs: default_signed_url_ttl_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Object not found: {0}")]
+    NotFound(String),
+    #[error("Invalid object key: {0}")]
+    InvalidKey(String),
+    #[error("Storage is misconfigured: {0}")]
+    Config(String),
+    #[error("S3 request failed: {0}")]
+    S3(String),
+}
+
+/// A download URL and when it stops working
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SignedUrl {
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Keys are relative paths like `images/<uuid>.png`, the same on every backend
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    async fn put(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<(), StorageError>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError>;
+
+    /// Delete an object; deleting a missing object is not an error
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// URL to download an object without further authentication, valid for `ttl`
+    async fn signed_url(&self, key: &str, ttl: Duration) -> Result<SignedUrl, StorageError>;
+
+    /// Check a signature made by `signed_url` for the server's download route. Backends whose
+    /// URLs point elsewhere never accept one.
+    fn verify_signature(&self, _key: &str, _expires: i64, _signature: &str) -> bool {
+        false
+    }
+}
+
+/// The store the configuration asks for
+pub async fn from_config(config: &StorageConfig) -> Result<Arc<dyn ObjectStore>, StorageError> {
+    match config.backend {
+        StorageBackend::Local => Ok(Arc::new(LocalStore::new(utils::cache_dir())?)),
+        StorageBackend::S3 => Ok(Arc::new(S3Store::new(config).await?)),
+    }
+}
+
+/// Reject keys that could leave the storage root
+fn validate_key(key: &str) -> Result<(), StorageError> {
+    let valid = !key.is_empty()
+        && !key.contains('\\')
+        && Path::new(key)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if valid {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidKey(key.to_string()))
+    }
+}
+
+/// Objects as files under a root directory
+#[derive(Clone)]
+pub struct LocalStore {
+    root: PathBuf,
+    signing_key: Vec<u8>,
+}
+
+impl LocalStore {
+    /// Store under `root`, creating it and its signing key when missing
+    pub fn new(root: PathBuf) -> Result<Self, StorageError> {
+        std::fs::create_dir_all(&root)?;
+        let key_path = root.join(SIGNING_KEY_FILE);
+        let signing_key = match std::fs::read(&key_path) {
+            Ok(key) if !key.is_empty() => key,
+            _ => {
+                let key = [Uuid::new_v4().as_bytes(), Uuid::new_v4().as_bytes()].concat();
+                std::fs::write(&key_path, &key)?;
+                key
+            }
+        };
+        Ok(Self { root, signing_key })
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+
+    fn mac(&self, key: &str, expires: i64) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.signing_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(format!("{key}\n{expires}").as_bytes());
+        mac
+    }
+
+    fn signature(&self, key: &str, expires: i64) -> String {
+        hex::encode(self.mac(key, expires).finalize().into_bytes())
+    }
+}
+
+#[async_trait]
+impl ObjectStore for LocalStore {
+    async fn put(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        _content_type: Option<&str>,
+    ) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, data).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(data) => Ok(data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(StorageError::NotFound(key.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        Ok(tokio::fs::try_exists(self.path(key)?).await?)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn signed_url(&self, key: &str, ttl: Duration) -> Result<SignedUrl, StorageError> {
+        validate_key(key)?;
+        let expires_at = Utc::now()
+            + chrono::Duration::from_std(ttl)
+                .map_err(|e| StorageError::Config(format!("Invalid URL lifetime: {e}")))?;
+        let expires = expires_at.timestamp();
+        Ok(SignedUrl {
+            url: format!(
+                "{LOCAL_DOWNLOAD_PATH}/{key}?expires={expires}&signature={}",
+                self.signature(key, expires)
+            ),
+            expires_at,
+        })
+    }
+
+    fn verify_signature(&self, key: &str, expires: i64, signature: &str) -> bool {
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        if validate_key(key).is_err() || expires < Utc::now().timestamp() {
+            return false;
+        }
+        // Constant-time comparison
+        self.mac(key, expires).verify_slice(&signature).is_ok()
+    }
+}
+
+/// Objects in an S3 bucket
+#[derive(Clone)]
+pub struct S3Store {
+    client: Client,
+    bucket: String,
+    prefix: String,
+}
+
+impl S3Store {
+    pub async fn new(config: &StorageConfig) -> Result<Self, StorageError> {
+        let bucket = config
+            .bucket
+            .clone()
+            .filter(|bucket| !bucket.trim().is_empty())
+            .ok_or_else(|| StorageError::Config("The S3 backend needs a bucket".to_string()))?;
+
+        let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+        let mut builder = aws_sdk_s3::config::Builder::from(&sdk_config)
+            .force_path_style(config.force_path_style);
+        if let Some(region) = &config.region {
+            builder = builder.region(Region::new(region.clone()));
+        }
+        if let Some(endpoint) = &config.endpoint {
+            builder = builder.endpoint_url(endpoint);
+        }
+
+        let prefix = config
+            .prefix
+            .as_deref()
+            .map(|prefix| prefix.trim_matches('/'))
+            .filter(|prefix| !prefix.is_empty())
+            .map(|prefix| format!("{prefix}/"))
+            .unwrap_or_default();
+
+        Ok(Self {
+            client: Client::from_conf(builder.build()),
+            bucket,
+            prefix,
+        })
+    }
+
+    fn object_key(&self, key: &str) -> Result<String, StorageError> {
+        validate_key(key)?;
+        Ok(format!("{}{key}", self.prefix))
+    }
+}
+
+fn s3_error<E: std::error::Error>(e: E) -> StorageError {
+    StorageError::S3(DisplayErrorContext(e).to_string())
+}
+
+#[async_trait]
+impl ObjectStore for S3Store {
+    async fn put(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<(), StorageError> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(key)?)
+            .body(ByteStream::from(data))
+            .set_content_type(content_type.map(str::to_string))
+            .send()
+            .await
+            .map_err(s3_error)?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let object = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(key)?)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.as_service_error().is_some_and(|e| e.is_no_such_key()) {
+                    StorageError::NotFound(key.to_string())
+                } else {
+                    s3_error(e)
+                }
+            })?;
+        let data = object.body.collect().await.map_err(s3_error)?;
+        Ok(data.into_bytes().to_vec())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        match self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(key)?)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_not_found()) => Ok(false),
+            Err(e) => Err(s3_error(e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        // S3 reports success for missing keys too
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(key)?)
+            .send()
+            .await
+            .map_err(s3_error)?;
+        Ok(())
+    }
+
+    async fn signed_url(&self, key: &str, ttl: Duration) -> Result<SignedUrl, StorageError> {
+        let presigning = PresigningConfig::expires_in(ttl)
+            .map_err(|e| StorageError::Config(format!("Invalid URL lifetime: {e}")))?;
+        let request = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(key)?)
+            .presigned(presigning)
+            .await
+            .map_err(s3_error)?;
+        Ok(SignedUrl {
+            url: request.uri().to_string(),
+            expires_at: Utc::now()
+                + chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::zero()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_stay_inside_the_root() {
+        assert!(validate_key("images/abc.png").is_ok());
+        assert!(validate_key("transcripts/2025/session.jsonl").is_ok());
+        for key in [
+            "",
+            "../secret",
+            "images/../../etc/passwd",
+            "/etc/passwd",
+            "a\\b",
+        ] {
+            assert!(validate_key(key).is_err(), "{key} should be rejected");
+        }
+    }
+
+    #[tokio::test]
+    async fn local_store_round_trips_objects() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalStore::new(dir.path().to_path_buf()).unwrap();
+
+        store
+            .put("artifacts/run.log", b"done".to_vec(), Some("text/plain"))
+            .await
+            .unwrap();
+        assert!(store.exists("artifacts/run.log").await.unwrap());
+        assert_eq!(store.get("artifacts/run.log").await.unwrap(), b"done");
+
+        store.delete("artifacts/run.log").await.unwrap();
+        store.delete("artifacts/run.log").await.unwrap();
+        assert!(matches!(
+            store.get("artifacts/run.log").await,
+            Err(StorageError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn local_urls_are_signed_per_key_and_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalStore::new(dir.path().to_path_buf()).unwrap();
+
+        let signed = store
+            .signed_url("images/a.png", Duration::from_secs(60))
+            .await
+            .unwrap();
+        let expires = signed.expires_at.timestamp();
+        let signature = signed.url.rsplit("signature=").next().unwrap();
+        assert!(signed.url.starts_with("/api/storage/images/a.png?"));
+        assert!(store.verify_signature("images/a.png", expires, signature));
+        assert!(!store.verify_signature("images/b.png", expires, signature));
+        assert!(!store.verify_signature("images/a.png", expires + 1, signature));
+
+        // The key survives restarts, so URLs handed out before stay valid
+        let reopened = LocalStore::new(dir.path().to_path_buf()).unwrap();
+        assert!(reopened.verify_signature("images/a.png", expires, signature));
+
+        let past = Utc::now().timestamp() - 1;
+        let stale = store.signature("images/a.png", past);
+        assert!(!store.verify_signature("images/a.png", past, &stale));
+    }
+}
@@ -0,0 +1,553 @@
+//! Working through provider outages.
+//!
+//! Laptops drop off the network, and a provider that can't be reached shouldn't turn every MR/PR
+//! view into an error. Reads go through [`OfflineService::read`], which keeps the last response
+//! of each read and serves it, marked with when it was fetched, while the provider is
+//! unreachable. Comments, replies and commit statuses posted meanwhile are queued as
+//! [`REPLAY_PROVIDER_WRITE_JOB`] jobs and posted once the provider answers again. Whenever a provider goes from reachable to unreachable
+//! or back, its [`ProviderStatus`] is pushed to the event stream at `/provider_status/<provider>`.
+
+use std::{
+    any::Any,
+    collections::HashMap,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+use utils::msg_store::MsgStore;
+use uuid::Uuid;
+
+use crate::services::{
+    events::provider_status_patch,
+    git_provider::{
+        self, CommitStatusState, GitProvider, ProviderError, ProviderType, RepoIdentifier,
+        UnifiedComment,
+    },
+    jobs::JobQueue,
+};
+
+/// Job that posts a comment or commit status queued while its provider was unreachable
+pub const REPLAY_PROVIDER_WRITE_JOB: &str = "offline.replay_write";
+
+/// Delay before the first attempt of a queued write
+const REPLAY_DELAY: Duration = Duration::from_secs(30);
+
+/// Attempts of a queued write; with the backoff of the job queue they span about five hours
+const REPLAY_ATTEMPTS: i64 = 12;
+
+/// Whether a provider answers, as pushed to the event stream
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProviderStatus {
+    pub provider: ProviderType,
+    pub reachable: bool,
+    /// When a call to the provider first failed to reach it; None while it is reachable
+    pub unreachable_since: Option<DateTime<Utc>>,
+    /// Error of that call
+    pub error: Option<String>,
+}
+
+/// Result of a read through [`OfflineService::read`]
+#[derive(Debug)]
+pub struct Fetched<T> {
+    pub value: T,
+    /// When the value was fetched, if it was served from the cache because the provider is
+    /// unreachable; None for a fresh value
+    pub cached_at: Option<DateTime<Utc>>,
+}
+
+/// A comment to post on an MR/PR
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProviderWrite {
+    Comment {
+        body: String,
+    },
+    ReviewComment {
+        path: String,
+        line: u64,
+        body: String,
+    },
+    Reply {
+        thread_id: String,
+        body: String,
+    },
+}
+
+impl ProviderWrite {
+    async fn apply(
+        &self,
+        provider: &dyn GitProvider,
+        repo: &RepoIdentifier,
+        number: u64,
+    ) -> Result<UnifiedComment, ProviderError> {
+        match self {
+            ProviderWrite::Comment { body } => provider.post_comment(repo, number, body).await,
+            ProviderWrite::ReviewComment { path, line, body } => {
+                provider
+                    .post_review_comment(repo, number, path, *line, body)
+                    .await
+            }
+            ProviderWrite::Reply { thread_id, body } => {
+                provider
+                    .reply_to_comment(repo, number, thread_id, body)
+                    .await
+            }
+        }
+    }
+}
+
+/// A commit status to publish, see [`GitProvider::set_commit_status`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusWrite {
+    pub sha: String,
+    pub state: CommitStatusState,
+    pub context: String,
+    pub url: Option<String>,
+}
+
+impl StatusWrite {
+    async fn apply(
+        &self,
+        provider: &dyn GitProvider,
+        repo: &RepoIdentifier,
+    ) -> Result<(), ProviderError> {
+        provider
+            .set_commit_status(
+                repo,
+                &self.sha,
+                self.state,
+                &self.context,
+                self.url.as_deref(),
+            )
+            .await
+    }
+}
+
+/// Payload of [`REPLAY_PROVIDER_WRITE_JOB`]
+#[derive(Debug, Serialize, Deserialize)]
+struct QueuedWrite {
+    /// Local repository, from which the provider is detected again when the write is replayed
+    repo_path: PathBuf,
+    #[serde(flatten)]
+    target: QueuedTarget,
+}
+
+/// What a queued write goes to
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum QueuedTarget {
+    /// A comment on MR/PR `number`
+    Comment { number: u64, write: ProviderWrite },
+    /// A status on a commit, which belongs to no MR/PR
+    CommitStatus { status: StatusWrite },
+}
+
+/// Outcome of a write that is queued when the provider can't be reached
+enum Written<T> {
+    Done(T),
+    Queued(Uuid),
+}
+
+/// A comment posted right away, or queued until the provider is reachable
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "status", rename_all = "snake_case")]
+#[ts(tag = "status", rename_all = "snake_case")]
+pub enum PostedComment {
+    Posted {
+        comment: UnifiedComment,
+    },
+    /// Posted by a background job once the provider answers again
+    Queued {
+        job_id: Uuid,
+    },
+}
+
+struct CacheEntry {
+    value: Box<dyn Any + Send + Sync>,
+    fetched_at: DateTime<Utc>,
+}
+
+/// Handle for provider calls that tolerate outages; cheap to clone
+#[derive(Clone)]
+pub struct OfflineService {
+    events: Arc<MsgStore>,
+    jobs: JobQueue,
+    cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
+    /// Providers last found unreachable, with when that started and the error
+    unreachable: Arc<Mutex<HashMap<ProviderType, (DateTime<Utc>, String)>>>,
+}
+
+impl OfflineService {
+    pub fn new(events: Arc<MsgStore>, jobs: JobQueue) -> Self {
+        Self {
+            events,
+            jobs,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            unreachable: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Key of a read about MR/PR `number` of `repo`, where `what` tells reads of the same MR/PR
+    /// apart
+    pub fn cache_key(repo: &RepoIdentifier, number: u64, what: &str) -> String {
+        format!(
+            "{}:{}/{}#{number}:{what}",
+            repo.provider,
+            repo.host.as_deref().unwrap_or_default(),
+            repo.full_path()
+        )
+    }
+
+    /// Run a provider read, keeping its result under `key`. When the provider can't be reached,
+    /// the last result kept under `key` is returned instead, or the error if there is none.
+    pub async fn read<T>(
+        &self,
+        provider: ProviderType,
+        key: String,
+        fetch: impl Future<Output = Result<T, ProviderError>>,
+    ) -> Result<Fetched<T>, ProviderError>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        match fetch.await {
+            Ok(value) => {
+                self.mark_reachable(provider);
+                self.cache.write().unwrap().insert(
+                    key,
+                    CacheEntry {
+                        value: Box::new(value.clone()),
+                        fetched_at: Utc::now(),
+                    },
+                );
+                Ok(Fetched {
+                    value,
+                    cached_at: None,
+                })
+            }
+            Err(e) if e.is_unreachable() => {
+                self.mark_unreachable(provider, &e);
+                let cache = self.cache.read().unwrap();
+                let Some((value, fetched_at)) = cache.get(&key).and_then(|entry| {
+                    let value = entry.value.downcast_ref::<T>()?;
+                    Some((value.clone(), entry.fetched_at))
+                }) else {
+                    return Err(e);
+                };
+                Ok(Fetched {
+                    value,
+                    cached_at: Some(fetched_at),
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Post a comment or reply on MR/PR `number`, queueing it when the provider can't be reached
+    pub async fn write(
+        &self,
+        provider: &dyn GitProvider,
+        repo_path: &Path,
+        repo: &RepoIdentifier,
+        number: u64,
+        write: ProviderWrite,
+    ) -> Result<PostedComment, ProviderError> {
+        let result = write.apply(provider, repo, number).await;
+        let queued = QueuedWrite {
+            repo_path: repo_path.to_path_buf(),
+            target: QueuedTarget::Comment { number, write },
+        };
+        let written = self
+            .queue_if_unreachable(provider.provider_type(), queued, result)
+            .await?;
+        Ok(match written {
+            Written::Done(comment) => PostedComment::Posted { comment },
+            Written::Queued(job_id) => PostedComment::Queued { job_id },
+        })
+    }
+
+    /// Publish a commit status, queueing it when the provider can't be reached. Returns the id
+    /// of the job it was queued as, if it was.
+    pub async fn set_commit_status(
+        &self,
+        provider: &dyn GitProvider,
+        repo_path: &Path,
+        repo: &RepoIdentifier,
+        status: StatusWrite,
+    ) -> Result<Option<Uuid>, ProviderError> {
+        let result = status.apply(provider, repo).await;
+        let queued = QueuedWrite {
+            repo_path: repo_path.to_path_buf(),
+            target: QueuedTarget::CommitStatus { status },
+        };
+        let written = self
+            .queue_if_unreachable(provider.provider_type(), queued, result)
+            .await?;
+        Ok(match written {
+            Written::Done(()) => None,
+            Written::Queued(job_id) => Some(job_id),
+        })
+    }
+
+    /// Pass on the `result` of a write, or queue the write for replay if it failed to reach the
+    /// provider. The error is passed on when the write can't be queued either.
+    async fn queue_if_unreachable<T>(
+        &self,
+        provider: ProviderType,
+        queued: QueuedWrite,
+        result: Result<T, ProviderError>,
+    ) -> Result<Written<T>, ProviderError> {
+        let e = match result {
+            Ok(value) => {
+                self.mark_reachable(provider);
+                return Ok(Written::Done(value));
+            }
+            Err(e) if e.is_unreachable() => e,
+            Err(e) => return Err(e),
+        };
+        self.mark_unreachable(provider, &e);
+
+        let payload =
+            serde_json::to_value(&queued).expect("Queued write serialization should not fail");
+        match self
+            .jobs
+            .enqueue_with_attempts(
+                REPLAY_PROVIDER_WRITE_JOB,
+                payload,
+                REPLAY_DELAY,
+                REPLAY_ATTEMPTS,
+            )
+            .await
+        {
+            Ok(job) => Ok(Written::Queued(job.id)),
+            Err(db_err) => {
+                tracing::error!("Failed to queue a write to {}: {}", provider, db_err);
+                Err(e)
+            }
+        }
+    }
+
+    /// Post a write queued by [`Self::write`] or [`Self::set_commit_status`]. The job fails, and
+    /// is retried later, while the provider is still unreachable.
+    pub async fn replay_job(&self, payload: Value) -> anyhow::Result<()> {
+        let queued: QueuedWrite = serde_json::from_value(payload)?;
+        let provider = git_provider::create_provider(&queued.repo_path)?;
+        let (_, repo) = git_provider::detect_provider(&queued.repo_path)?;
+        let provider_type = provider.provider_type();
+
+        let result = match &queued.target {
+            QueuedTarget::Comment { number, write } => write
+                .apply(provider.as_ref(), &repo, *number)
+                .await
+                .map(|_| ()),
+            QueuedTarget::CommitStatus { status } => status.apply(provider.as_ref(), &repo).await,
+        };
+        match result {
+            Ok(()) => {
+                self.mark_reachable(provider_type);
+                Ok(())
+            }
+            Err(e) => {
+                if e.is_unreachable() {
+                    self.mark_unreachable(provider_type, &e);
+                }
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Current reachability of a provider
+    pub fn status(&self, provider: ProviderType) -> ProviderStatus {
+        let outage = self.unreachable.lock().unwrap().get(&provider).cloned();
+        ProviderStatus {
+            provider,
+            reachable: outage.is_none(),
+            unreachable_since: outage.as_ref().map(|(since, _)| *since),
+            error: outage.map(|(_, error)| error),
+        }
+    }
+
+    fn mark_reachable(&self, provider: ProviderType) {
+        if self.unreachable.lock().unwrap().remove(&provider).is_none() {
+            return;
+        }
+        tracing::info!("{} is reachable again", provider);
+        self.events
+            .push_patch(provider_status_patch::set(&self.status(provider)));
+    }
+
+    fn mark_unreachable(&self, provider: ProviderType, err: &ProviderError) {
+        {
+            let mut unreachable = self.unreachable.lock().unwrap();
+            if unreachable.contains_key(&provider) {
+                return;
+            }
+            unreachable.insert(provider, (Utc::now(), err.to_string()));
+        }
+        tracing::warn!("{} is unreachable: {}", provider, err);
+        self.events
+            .push_patch(provider_status_patch::set(&self.status(provider)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+    use utils::log_msg::LogMsg;
+
+    use super::*;
+
+    fn service() -> (OfflineService, Arc<MsgStore>) {
+        let events = Arc::new(MsgStore::new());
+        let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
+        (
+            OfflineService::new(events.clone(), JobQueue::new(pool)),
+            events,
+        )
+    }
+
+    fn unreachable() -> ProviderError {
+        ProviderError::Unreachable("error connecting to api.github.com".to_string())
+    }
+
+    /// `reachable` of every provider status pushed to the event stream, in order
+    fn pushed_statuses(events: &MsgStore) -> Vec<bool> {
+        events
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => Some(patch),
+                _ => None,
+            })
+            .map(|patch| {
+                let value = serde_json::to_value(&patch).unwrap();
+                assert_eq!(value[0]["path"], "/provider_status/github");
+                value[0]["value"]["reachable"].as_bool().unwrap()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn serves_the_last_read_while_unreachable() {
+        let (offline, events) = service();
+        let key = "github:/acme/app#42:comments".to_string();
+
+        let fresh = offline
+            .read(ProviderType::GitHub, key.clone(), async { Ok(vec![1, 2]) })
+            .await
+            .unwrap();
+        assert_eq!(fresh.value, [1, 2]);
+        assert!(fresh.cached_at.is_none());
+
+        let cached = offline
+            .read::<Vec<i32>>(ProviderType::GitHub, key.clone(), async {
+                Err(unreachable())
+            })
+            .await
+            .unwrap();
+        assert_eq!(cached.value, [1, 2]);
+        assert!(cached.cached_at.is_some());
+        let status = offline.status(ProviderType::GitHub);
+        assert!(!status.reachable);
+        assert!(status.error.unwrap().contains("error connecting"));
+
+        offline
+            .read(ProviderType::GitHub, key, async { Ok(vec![3]) })
+            .await
+            .unwrap();
+        assert!(offline.status(ProviderType::GitHub).reachable);
+        assert_eq!(pushed_statuses(&events), [false, true]);
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_served_from_the_cache() {
+        let (offline, events) = service();
+        let key = "github:/acme/app#42:details".to_string();
+        offline
+            .read(ProviderType::GitHub, key.clone(), async { Ok(1) })
+            .await
+            .unwrap();
+
+        let missing = offline
+            .read::<i32>(ProviderType::GitHub, key, async {
+                Err(ProviderError::ApiError {
+                    status: 404,
+                    message: "Not Found".to_string(),
+                })
+            })
+            .await;
+        assert!(matches!(
+            missing,
+            Err(ProviderError::ApiError { status: 404, .. })
+        ));
+
+        let uncached = offline
+            .read::<i32>(ProviderType::GitHub, "other".to_string(), async {
+                Err(unreachable())
+            })
+            .await;
+        assert!(matches!(uncached, Err(ProviderError::Unreachable(_))));
+        assert_eq!(pushed_statuses(&events), [false]);
+    }
+
+    #[test]
+    fn queued_writes_keep_their_target() {
+        let comment: QueuedWrite = serde_json::from_value(serde_json::json!({
+            "repo_path": "/repos/app",
+            "number": 42,
+            "write": { "type": "reply", "thread_id": "7", "body": "Done" },
+        }))
+        .unwrap();
+        assert!(matches!(
+            comment.target,
+            QueuedTarget::Comment {
+                number: 42,
+                write: ProviderWrite::Reply { .. }
+            }
+        ));
+
+        let status = QueuedWrite {
+            repo_path: PathBuf::from("/repos/app"),
+            target: QueuedTarget::CommitStatus {
+                status: StatusWrite {
+                    sha: "abc123".to_string(),
+                    state: CommitStatusState::Success,
+                    context: "agentic-kanban/agent-run".to_string(),
+                    url: None,
+                },
+            },
+        };
+        let status: QueuedWrite =
+            serde_json::from_value(serde_json::to_value(&status).unwrap()).unwrap();
+        assert!(matches!(
+            status.target,
+            QueuedTarget::CommitStatus {
+                status: StatusWrite {
+                    state: CommitStatusState::Success,
+                    ..
+                }
+            }
+        ));
+    }
+
+    #[test]
+    fn cli_network_failures_are_unreachable() {
+        assert!(
+            ProviderError::command_failed(
+                "error connecting to api.github.com\ncheck your internet connection".to_string()
+            )
+            .is_unreachable()
+        );
+        assert!(
+            !ProviderError::command_failed(
+                "GraphQL: Could not resolve to a PullRequest".to_string()
+            )
+            .is_unreachable()
+        );
+    }
+}
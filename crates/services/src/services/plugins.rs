@@ -0,0 +1,542 @@
+//! External-process plugins for custom automations.
+//!
+//! A plugin is any executable described by a `plugin.json` manifest in
+//! `<data dir>/plugins/<name>/`. The host starts it with piped stdio and speaks
+//! newline-delimited JSON-RPC 2.0 in both directions:
+//!
+//! - host → plugin: `board.event` notifications for every subscribed board change
+//! - plugin → host: requests against a deliberately small API (`tasks.create`,
+//!   `tasks.comment`, `tasks.set_status`, `tasks.start`, `tasks.mark_pr_ready`,
+//!   `tasks.request_reviewers`, `tasks.notify`), answered with regular JSON-RPC responses
+//!
+//! Plugins never get database or filesystem access through the host; everything they can do
+//! goes through [`PluginApi`].
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use db::models::{
+    execution_process::ExecutionProcess,
+    task::{Task, TaskStatus},
+    task_comment::TaskComment,
+};
+use executors::profile::ExecutorProfileId;
+use json_patch::PatchOperation;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::{Child, ChildStdin, Command},
+    sync::{Mutex, broadcast::error::RecvError},
+};
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+pub const MANIFEST_FILE: &str = "plugin.json";
+
+const JSONRPC_VERSION: &str = "2.0";
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid plugin manifest {path}: {reason}")]
+    InvalidManifest { path: String, reason: String },
+    #[error("{0} not found")]
+    NotFound(String),
+    #[error("{0}")]
+    Rejected(String),
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl PluginError {
+    fn rpc_code(&self) -> i64 {
+        match self {
+            PluginError::NotFound(_) | PluginError::Rejected(_) | PluginError::Json(_) => {
+                INVALID_PARAMS
+            }
+            _ => INTERNAL_ERROR,
+        }
+    }
+}
+
+/// Contents of a plugin's `plugin.json`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PluginManifest {
+    pub name: String,
+    /// Executable to launch, resolved relative to the plugin directory when not absolute
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Event kinds to forward (e.g. `task.updated`); empty means all events
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl PluginManifest {
+    fn wants(&self, kind: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == kind)
+    }
+}
+
+/// A board change as delivered to plugins in `board.event` notifications
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardEvent {
+    /// `<entity>.<created|updated|deleted>`, e.g. `task.created`
+    pub kind: String,
+    pub id: Uuid,
+    /// Full record for created/updated events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl BoardEvent {
+    /// Translate a single event-stream patch operation into a board event.
+    /// Only collection entries (`/<collection>/<id>`) are reported; scratch updates are skipped.
+    pub fn from_patch_operation(op: &PatchOperation) -> Option<Self> {
+        let (path, action, data) = match op {
+            PatchOperation::Add(op) => (op.path.to_string(), "created", Some(op.value.clone())),
+            PatchOperation::Replace(op) => (op.path.to_string(), "updated", Some(op.value.clone())),
+            PatchOperation::Remove(op) => (op.path.to_string(), "deleted", None),
+            _ => return None,
+        };
+
+        let mut segments = path.trim_start_matches('/').splitn(2, '/');
+        let entity = match segments.next()? {
+            "tasks" => "task",
+            "projects" => "project",
+            "workspaces" => "workspace",
+            "execution_processes" => "execution_process",
+            _ => return None,
+        };
+        let id = Uuid::parse_str(segments.next()?).ok()?;
+
+        Some(Self {
+            kind: format!("{entity}.{action}"),
+            id,
+            data,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTaskParams {
+    pub project_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentParams {
+    pub task_id: Uuid,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetStatusParams {
+    pub task_id: Uuid,
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartTaskParams {
+    pub task_id: Uuid,
+    /// Defaults to the executor profile from the user's config
+    pub executor_profile_id: Option<ExecutorProfileId>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MarkPrReadyParams {
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequestReviewersParams {
+    pub task_id: Uuid,
+    /// Provider usernames
+    pub reviewers: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotifyParams {
+    pub task_id: Uuid,
+    pub message: String,
+}
+
+/// The operations a plugin or automation rule may invoke. Implemented by the deployment so
+/// automations act through the same code paths as the UI. `actor` identifies the caller, e.g.
+/// `plugin:<name>`, and is recorded as the author of comments.
+#[async_trait]
+pub trait PluginApi: Send + Sync {
+    async fn create_task(&self, actor: &str, params: CreateTaskParams)
+    -> Result<Task, PluginError>;
+
+    async fn comment(&self, actor: &str, params: CommentParams)
+    -> Result<TaskComment, PluginError>;
+
+    async fn set_status(&self, actor: &str, params: SetStatusParams) -> Result<Task, PluginError>;
+
+    async fn start_task(
+        &self,
+        actor: &str,
+        params: StartTaskParams,
+    ) -> Result<ExecutionProcess, PluginError>;
+
+    /// Mark every open pull request of the task ready for review
+    async fn mark_pr_ready(
+        &self,
+        actor: &str,
+        params: MarkPrReadyParams,
+    ) -> Result<(), PluginError>;
+
+    /// Request reviews on every open pull request of the task
+    async fn request_reviewers(
+        &self,
+        actor: &str,
+        params: RequestReviewersParams,
+    ) -> Result<(), PluginError>;
+
+    /// Send a desktop notification titled with the task
+    async fn notify(&self, actor: &str, params: NotifyParams) -> Result<(), PluginError>;
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Read every `*/plugin.json` under `dir`. Invalid manifests are logged and skipped so one
+/// broken plugin does not disable the others.
+pub async fn load_manifests(dir: &Path) -> Vec<(PathBuf, PluginManifest)> {
+    let mut manifests = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return manifests;
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let plugin_dir = entry.path();
+        let manifest_path = plugin_dir.join(MANIFEST_FILE);
+        if !manifest_path.is_file() {
+            continue;
+        }
+        match read_manifest(&manifest_path).await {
+            Ok(manifest) => manifests.push((plugin_dir, manifest)),
+            Err(e) => tracing::warn!("Skipping plugin: {}", e),
+        }
+    }
+
+    manifests.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+    manifests
+}
+
+async fn read_manifest(path: &Path) -> Result<PluginManifest, PluginError> {
+    let raw = tokio::fs::read_to_string(path).await?;
+    let manifest: PluginManifest =
+        serde_json::from_str(&raw).map_err(|e| PluginError::InvalidManifest {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+    if manifest.name.trim().is_empty() || manifest.command.trim().is_empty() {
+        return Err(PluginError::InvalidManifest {
+            path: path.display().to_string(),
+            reason: "name and command are required".to_string(),
+        });
+    }
+    Ok(manifest)
+}
+
+pub fn plugins_dir() -> PathBuf {
+    utils::assets::asset_dir().join("plugins")
+}
+
+/// Runs all enabled plugins and bridges them to the board event stream
+pub struct PluginHost;
+
+impl PluginHost {
+    pub async fn spawn(
+        dir: &Path,
+        events: Arc<MsgStore>,
+        api: Arc<dyn PluginApi>,
+    ) -> Vec<tokio::task::JoinHandle<()>> {
+        let mut handles = Vec::new();
+        for (plugin_dir, manifest) in load_manifests(dir).await {
+            if !manifest.enabled {
+                tracing::debug!("Plugin '{}' is disabled", manifest.name);
+                continue;
+            }
+            match PluginProcess::start(&plugin_dir, manifest.clone()) {
+                Ok(process) => {
+                    tracing::info!("Started plugin '{}'", manifest.name);
+                    handles.push(process.run(events.clone(), api.clone()));
+                }
+                Err(e) => tracing::error!("Failed to start plugin '{}': {}", manifest.name, e),
+            }
+        }
+        handles
+    }
+}
+
+struct PluginProcess {
+    manifest: PluginManifest,
+    child: Child,
+    stdin: Arc<Mutex<ChildStdin>>,
+}
+
+impl PluginProcess {
+    fn start(plugin_dir: &Path, manifest: PluginManifest) -> Result<Self, PluginError> {
+        let command = if Path::new(&manifest.command).is_absolute() {
+            PathBuf::from(&manifest.command)
+        } else if plugin_dir.join(&manifest.command).exists() {
+            plugin_dir.join(&manifest.command)
+        } else {
+            PathBuf::from(&manifest.command)
+        };
+
+        let mut child = Command::new(command)
+            .args(&manifest.args)
+            .current_dir(plugin_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| PluginError::Internal("plugin stdin unavailable".to_string()))?;
+
+        Ok(Self {
+            manifest,
+            child,
+            stdin: Arc::new(Mutex::new(stdin)),
+        })
+    }
+
+    fn run(
+        mut self,
+        events: Arc<MsgStore>,
+        api: Arc<dyn PluginApi>,
+    ) -> tokio::task::JoinHandle<()> {
+        let name = self.manifest.name.clone();
+
+        if let Some(stderr) = self.child.stderr.take() {
+            let name = name.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    tracing::info!(plugin = %name, "{}", line);
+                }
+            });
+        }
+
+        let stdout = self.child.stdout.take();
+        let stdin = self.stdin.clone();
+        let requests = tokio::spawn({
+            let name = name.clone();
+            async move {
+                let Some(stdout) = stdout else { return };
+                let mut lines = BufReader::new(stdout).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let response = handle_line(&name, &line, api.as_ref()).await;
+                    if let Some(response) = response
+                        && let Err(e) = write_message(&stdin, &response).await
+                    {
+                        tracing::warn!("Plugin '{}' stopped reading responses: {}", name, e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        tokio::spawn(async move {
+            let mut receiver = events.get_receiver();
+            loop {
+                tokio::select! {
+                    msg = receiver.recv() => match msg {
+                        Ok(LogMsg::JsonPatch(patch)) => {
+                            for event in patch.0.iter().filter_map(BoardEvent::from_patch_operation) {
+                                if !self.manifest.wants(&event.kind) {
+                                    continue;
+                                }
+                                let notification = json!({
+                                    "jsonrpc": JSONRPC_VERSION,
+                                    "method": "board.event",
+                                    "params": event,
+                                });
+                                if let Err(e) = write_message(&self.stdin, &notification).await {
+                                    tracing::warn!("Failed to deliver event to plugin '{}': {}", name, e);
+                                }
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(RecvError::Lagged(skipped)) => {
+                            tracing::warn!("Plugin '{}' missed {} events", name, skipped);
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                    status = self.child.wait() => {
+                        match status {
+                            Ok(status) => tracing::warn!("Plugin '{}' exited with {}", name, status),
+                            Err(e) => tracing::error!("Plugin '{}' failed: {}", name, e),
+                        }
+                        break;
+                    }
+                }
+            }
+            requests.abort();
+        })
+    }
+}
+
+async fn write_message(stdin: &Mutex<ChildStdin>, message: &Value) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    let mut stdin = stdin.lock().await;
+    stdin.write_all(&line).await?;
+    stdin.flush().await
+}
+
+/// Handle one line from a plugin's stdout. Returns the response to send back, or `None` for
+/// notifications.
+async fn handle_line(plugin: &str, line: &str, api: &dyn PluginApi) -> Option<Value> {
+    let request: RpcRequest = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => {
+            tracing::warn!("Plugin '{}' sent invalid JSON-RPC: {}", plugin, e);
+            return None;
+        }
+    };
+    let id = request.id.clone()?;
+
+    let result = dispatch(&format!("plugin:{plugin}"), request, api).await;
+    Some(match result {
+        Ok(value) => json!({ "jsonrpc": JSONRPC_VERSION, "id": id, "result": value }),
+        Err((code, message)) => json!({
+            "jsonrpc": JSONRPC_VERSION,
+            "id": id,
+            "error": { "code": code, "message": message },
+        }),
+    })
+}
+
+async fn dispatch(
+    actor: &str,
+    request: RpcRequest,
+    api: &dyn PluginApi,
+) -> Result<Value, (i64, String)> {
+    fn params<T: serde::de::DeserializeOwned>(value: Value) -> Result<T, (i64, String)> {
+        serde_json::from_value(value).map_err(|e| (INVALID_PARAMS, e.to_string()))
+    }
+    fn to_rpc(e: PluginError) -> (i64, String) {
+        (e.rpc_code(), e.to_string())
+    }
+
+    tracing::debug!("{} called {}", actor, request.method);
+    let value = match request.method.as_str() {
+        "tasks.create" => serde_json::to_value(
+            api.create_task(actor, params(request.params)?)
+                .await
+                .map_err(to_rpc)?,
+        ),
+        "tasks.comment" => serde_json::to_value(
+            api.comment(actor, params(request.params)?)
+                .await
+                .map_err(to_rpc)?,
+        ),
+        "tasks.set_status" => serde_json::to_value(
+            api.set_status(actor, params(request.params)?)
+                .await
+                .map_err(to_rpc)?,
+        ),
+        "tasks.start" => serde_json::to_value(
+            api.start_task(actor, params(request.params)?)
+                .await
+                .map_err(to_rpc)?,
+        ),
+        "tasks.mark_pr_ready" => serde_json::to_value(
+            api.mark_pr_ready(actor, params(request.params)?)
+                .await
+                .map_err(to_rpc)?,
+        ),
+        "tasks.request_reviewers" => serde_json::to_value(
+            api.request_reviewers(actor, params(request.params)?)
+                .await
+                .map_err(to_rpc)?,
+        ),
+        "tasks.notify" => serde_json::to_value(
+            api.notify(actor, params(request.params)?)
+                .await
+                .map_err(to_rpc)?,
+        ),
+        other => return Err((METHOD_NOT_FOUND, format!("Unknown method: {other}"))),
+    };
+    value.map_err(|e| (INTERNAL_ERROR, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use json_patch::{AddOperation, RemoveOperation};
+
+    use super::*;
+
+    #[test]
+    fn translates_task_patches_into_board_events() {
+        let id = Uuid::new_v4();
+        let add = PatchOperation::Add(AddOperation {
+            path: format!("/tasks/{id}").try_into().unwrap(),
+            value: json!({ "title": "x" }),
+        });
+        let event = BoardEvent::from_patch_operation(&add).unwrap();
+        assert_eq!(event.kind, "task.created");
+        assert_eq!(event.id, id);
+        assert!(event.data.is_some());
+
+        let remove = PatchOperation::Remove(RemoveOperation {
+            path: format!("/execution_processes/{id}").try_into().unwrap(),
+        });
+        let event = BoardEvent::from_patch_operation(&remove).unwrap();
+        assert_eq!(event.kind, "execution_process.deleted");
+        assert!(event.data.is_none());
+
+        let scratch = PatchOperation::Replace(json_patch::ReplaceOperation {
+            path: "/scratch".try_into().unwrap(),
+            value: json!({}),
+        });
+        assert!(BoardEvent::from_patch_operation(&scratch).is_none());
+    }
+
+    #[test]
+    fn empty_event_list_subscribes_to_everything() {
+        let mut manifest: PluginManifest =
+            serde_json::from_str(r#"{"name":"n","command":"c"}"#).unwrap();
+        assert!(manifest.enabled);
+        assert!(manifest.wants("task.updated"));
+        manifest.events = vec!["task.created".to_string()];
+        assert!(!manifest.wants("task.updated"));
+    }
+}
@@ -0,0 +1,293 @@
+//! Per-executor restrictions on which repositories and paths a coding agent may change.
+//!
+//! Policies are enforced on the diff rather than inside the agent: once after each coding agent
+//! run, before its changes are committed, and again on the commits of every run before a
+//! workspace branch is pushed.
+
+use std::{collections::BTreeSet, path::Path};
+
+use db::models::{
+    execution_process::ExecutionProcess,
+    execution_process_repo_state::ExecutionProcessRepoState,
+    policy_violation_report::{PolicyViolation, PolicyViolationKind},
+    repo::Repo,
+    session::Session,
+};
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::git::{Commit, DiffTarget, GitService, GitServiceError};
+
+#[derive(Debug, Error)]
+pub enum PolicyError {
+    #[error("Invalid protected path pattern '{pattern}': {message}")]
+    InvalidPattern { pattern: String, message: String },
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct ExecutorPolicy {
+    pub executor: BaseCodingAgent,
+    /// Limit the policy to one variant; `None` applies it to every variant
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// Repository names the executor may change; empty allows every repository
+    #[serde(default)]
+    pub allowed_repos: Vec<String>,
+    /// Gitignore-style patterns the executor may not write, e.g. `.github/workflows/` or `infra/`
+    #[serde(default)]
+    pub protected_paths: Vec<String>,
+}
+
+impl ExecutorPolicy {
+    pub fn applies_to(&self, profile: &ExecutorProfileId) -> bool {
+        self.executor == profile.executor
+            && (self.variant.is_none() || self.variant == profile.variant)
+    }
+
+    pub fn validate(&self) -> Result<(), PolicyError> {
+        self.protected_matcher().map(|_| ())
+    }
+
+    fn protected_matcher(&self) -> Result<Gitignore, PolicyError> {
+        let mut builder = GitignoreBuilder::new("");
+        for pattern in &self.protected_paths {
+            builder
+                .add_line(None, pattern)
+                .map_err(|e| PolicyError::InvalidPattern {
+                    pattern: pattern.clone(),
+                    message: e.to_string(),
+                })?;
+        }
+        builder.build().map_err(|e| PolicyError::InvalidPattern {
+            pattern: self.protected_paths.join(", "),
+            message: e.to_string(),
+        })
+    }
+
+    /// Violations of this policy among the `paths` changed in `repo`
+    pub fn check(
+        &self,
+        profile: &ExecutorProfileId,
+        repo: &str,
+        paths: &[String],
+    ) -> Result<Vec<PolicyViolation>, PolicyError> {
+        let violation = |path: &String, kind| PolicyViolation {
+            executor_profile_id: profile.clone(),
+            repo: repo.to_string(),
+            path: path.clone(),
+            kind,
+        };
+
+        if !self.allowed_repos.is_empty() && !self.allowed_repos.iter().any(|r| r == repo) {
+            return Ok(paths
+                .iter()
+                .map(|path| violation(path, PolicyViolationKind::RepoNotAllowed))
+                .collect());
+        }
+
+        let matcher = self.protected_matcher()?;
+        Ok(paths
+            .iter()
+            .filter_map(|path| {
+                let matched = matcher.matched_path_or_any_parents(path, false);
+                matched.is_ignore().then(|| {
+                    let pattern = matched
+                        .inner()
+                        .map(|glob| glob.original().to_string())
+                        .unwrap_or_default();
+                    violation(path, PolicyViolationKind::ProtectedPath { pattern })
+                })
+            })
+            .collect())
+    }
+}
+
+/// Check changed paths against every policy that applies to `profile`
+pub fn check_policies(
+    policies: &[ExecutorPolicy],
+    profile: &ExecutorProfileId,
+    repo: &str,
+    paths: &[String],
+) -> Result<Vec<PolicyViolation>, PolicyError> {
+    let mut violations: Vec<PolicyViolation> = Vec::new();
+    for policy in policies.iter().filter(|p| p.applies_to(profile)) {
+        for violation in policy.check(profile, repo, paths)? {
+            if !violations
+                .iter()
+                .any(|v| v.repo == violation.repo && v.path == violation.path)
+            {
+                violations.push(violation);
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// Paths changed in a worktree since `base`, including uncommitted and untracked files. Both
+/// sides of a rename are listed, so moving a protected file away is caught too.
+pub fn changed_paths(
+    git: &GitService,
+    worktree_path: &Path,
+    base: &Commit,
+) -> Result<Vec<String>, PolicyError> {
+    let diffs = git.get_diffs(
+        DiffTarget::Worktree {
+            worktree_path,
+            base_commit: base,
+        },
+        None,
+    )?;
+    let paths: BTreeSet<String> = diffs
+        .into_iter()
+        .flat_map(|diff| [diff.old_path, diff.new_path])
+        .flatten()
+        .collect();
+    Ok(paths.into_iter().collect())
+}
+
+/// Check what each coding agent run in a workspace changed in one repository, from the commit
+/// before the run to the one after it, against the policies of its executor. Commits made
+/// outside the runs, such as rebases onto the target branch or the user's own edits, are left
+/// alone.
+pub async fn check_agent_commits(
+    pool: &SqlitePool,
+    git: &GitService,
+    policies: &[ExecutorPolicy],
+    workspace_id: Uuid,
+    repo: &Repo,
+) -> Result<Vec<PolicyViolation>, PolicyError> {
+    let mut violations: Vec<PolicyViolation> = Vec::new();
+    for session in Session::find_by_workspace_id(pool, workspace_id).await? {
+        for process in ExecutionProcess::find_by_session_id(pool, session.id, false).await? {
+            let Some(profile) = process
+                .executor_action()
+                .ok()
+                .and_then(|action| action.executor_profile_id())
+            else {
+                continue;
+            };
+            if !policies.iter().any(|p| p.applies_to(&profile)) {
+                continue;
+            }
+            let Some((Some(before), Some(after))) =
+                ExecutionProcessRepoState::find_by_execution_process_id(pool, process.id)
+                    .await?
+                    .into_iter()
+                    .find(|state| state.repo_id == repo.id)
+                    .map(|state| (state.before_head_commit, state.after_head_commit))
+            else {
+                continue;
+            };
+            if before == after {
+                continue;
+            }
+            let paths = git.changed_paths_between(
+                &repo.path,
+                &Commit::from_sha(&before)?,
+                &Commit::from_sha(&after)?,
+            )?;
+            for violation in check_policies(policies, &profile, &repo.name, &paths)? {
+                if !violations
+                    .iter()
+                    .any(|v| v.repo == violation.repo && v.path == violation.path)
+                {
+                    violations.push(violation);
+                }
+            }
+        }
+    }
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> ExecutorPolicy {
+        ExecutorPolicy {
+            executor: BaseCodingAgent::ClaudeCode,
+            variant: None,
+            allowed_repos: vec![],
+            protected_paths: vec![".github/workflows/".to_string(), "infra/".to_string()],
+        }
+    }
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_protected_paths() {
+        let profile = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+        let violations = check_policies(
+            &[policy()],
+            &profile,
+            "api",
+            &paths(&[
+                ".github/workflows/ci.yml",
+                "src/main.rs",
+                "deploy/infra/main.tf",
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].path, ".github/workflows/ci.yml");
+        assert_eq!(
+            violations[0].kind,
+            PolicyViolationKind::ProtectedPath {
+                pattern: ".github/workflows/".to_string()
+            }
+        );
+        assert_eq!(violations[1].path, "deploy/infra/main.tf");
+    }
+
+    #[test]
+    fn flags_repos_outside_allowlist() {
+        let mut policy = policy();
+        policy.allowed_repos = vec!["web".to_string()];
+        let profile = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+
+        let violations =
+            check_policies(&[policy], &profile, "api", &paths(&["README.md"])).unwrap();
+        assert_eq!(violations[0].kind, PolicyViolationKind::RepoNotAllowed);
+    }
+
+    #[test]
+    fn skips_policies_for_other_executors_and_variants() {
+        let mut policy = policy();
+        policy.variant = Some("PLAN".to_string());
+        let changed = paths(&["infra/main.tf"]);
+
+        let other = ExecutorProfileId::new(BaseCodingAgent::Codex);
+        assert!(
+            check_policies(&[policy.clone()], &other, "api", &changed)
+                .unwrap()
+                .is_empty()
+        );
+
+        let default_variant = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+        assert!(
+            check_policies(&[policy.clone()], &default_variant, "api", &changed)
+                .unwrap()
+                .is_empty()
+        );
+
+        let plan = ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "PLAN".to_string());
+        assert_eq!(
+            check_policies(&[policy], &plan, "api", &changed)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
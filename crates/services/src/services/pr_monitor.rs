@@ -1,25 +1,32 @@
 use std::time::Duration;
 
+use async_trait::async_trait;
 use db::{
     DBService,
     models::{
         merge::{Merge, MergeStatus, PrMerge},
-        task::{Task, TaskStatus},
+        task::TaskStatus,
         workspace::{Workspace, WorkspaceError},
     },
 };
-use serde_json::json;
+use serde_json::{Value, json};
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
-use tokio::time::interval;
 use tracing::{debug, error, info};
 
 use crate::services::{
     analytics::AnalyticsContext,
     github::{GitHubRepoInfo, GitHubService, GitHubServiceError},
-    share::SharePublisher,
+    jobs::JobHandler,
+    task_events::{TaskActor, TaskEventLog},
 };
 
+/// Recurring job that polls the open pull requests
+pub const PR_MONITOR_JOB: &str = "pr_monitor.check_open_prs";
+
+/// How often open pull requests are checked
+pub const PR_MONITOR_INTERVAL: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Error)]
 enum PrMonitorError {
     #[error(transparent)]
@@ -30,44 +37,31 @@ enum PrMonitorError {
     Sqlx(#[from] SqlxError),
 }
 
-/// Service to monitor GitHub PRs and update task status when they are merged
+/// Service to monitor GitHub PRs and update task status when they are merged. Runs as the
+/// recurring [`PR_MONITOR_JOB`].
 pub struct PrMonitorService {
     db: DBService,
-    poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
-    publisher: Option<SharePublisher>,
+    task_events: TaskEventLog,
+}
+
+#[async_trait]
+impl JobHandler for PrMonitorService {
+    async fn run(&self, _payload: Value) -> anyhow::Result<()> {
+        Ok(self.check_all_open_prs().await?)
+    }
 }
 
 impl PrMonitorService {
-    pub async fn spawn(
+    pub fn new(
         db: DBService,
         analytics: Option<AnalyticsContext>,
-        publisher: Option<SharePublisher>,
-    ) -> tokio::task::JoinHandle<()> {
-        let service = Self {
+        task_events: TaskEventLog,
+    ) -> Self {
+        Self {
             db,
-            poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
-            publisher,
-        };
-        tokio::spawn(async move {
-            service.start().await;
-        })
-    }
-
-    async fn start(&self) {
-        info!(
-            "Starting PR monitoring service with interval {:?}",
-            self.poll_interval
-        );
-
-        let mut interval = interval(self.poll_interval);
-
-        loop {
-            interval.tick().await;
-            if let Err(e) = self.check_all_open_prs().await {
-                error!("Error checking open PRs: {}", e);
-            }
+            task_events,
         }
     }
 
@@ -128,11 +122,18 @@ impl PrMonitorService {
                     "PR #{} was merged, updating task {} to done",
                     pr_merge.pr_info.number, workspace.task_id
                 );
-                Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                let task = self
+                    .task_events
+                    .set_status(
+                        workspace.task_id,
+                        TaskStatus::Done,
+                        &TaskActor::webhook("pr_monitor"),
+                    )
+                    .await?;
 
                 // Track analytics event
                 if let Some(analytics) = &self.analytics
-                    && let Ok(Some(task)) = Task::find_by_id(&self.db.pool, workspace.task_id).await
+                    && let Some(task) = task
                 {
                     analytics.analytics_service.track_event(
                         &analytics.user_id,
@@ -144,16 +145,6 @@ impl PrMonitorService {
                         })),
                     );
                 }
-
-                if let Some(publisher) = &self.publisher
-                    && let Err(err) = publisher.update_shared_task_by_id(workspace.task_id).await
-                {
-                    tracing::warn!(
-                        ?err,
-                        "Failed to propagate shared task update for {}",
-                        workspace.task_id
-                    );
-                }
             }
         }
 
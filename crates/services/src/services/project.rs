@@ -78,6 +78,7 @@ impl ProjectService {
         pool: &SqlitePool,
         repo_service: &RepoService,
         payload: CreateProject,
+        tenant_id: Uuid,
     ) -> Result<Project> {
         // Validate all repository paths and check for duplicates within the payload
         let mut seen_names = HashSet::new();
@@ -106,15 +107,19 @@ impl ProjectService {
 
         let id = Uuid::new_v4();
 
-        let project = Project::create(pool, &payload, id)
+        let project = Project::create(pool, &payload, id, tenant_id)
             .await
             .map_err(|e| ProjectServiceError::Project(ProjectError::CreateFailed(e.to_string())))?;
 
         let mut created_repo: Option<Repo> = None;
         for repo in &normalized_repos {
-            let repo_entity =
-                Repo::find_or_create(pool, Path::new(&repo.git_repo_path), &repo.display_name)
-                    .await?;
+            let repo_entity = Repo::find_or_create(
+                pool,
+                Path::new(&repo.git_repo_path),
+                &repo.display_name,
+                tenant_id,
+            )
+            .await?;
             ProjectRepo::create(pool, project.id, repo_entity.id).await?;
             if created_repo.is_none() {
                 created_repo = Some(repo_entity);
@@ -194,6 +199,7 @@ impl ProjectService {
         repo_service: &RepoService,
         project_id: Uuid,
         payload: &CreateProjectRepo,
+        tenant_id: Uuid,
     ) -> Result<Repo> {
         tracing::debug!(
             "Adding repository '{}' to project {} (path: {})",
@@ -215,6 +221,7 @@ impl ProjectService {
             project_id,
             &path.to_string_lossy(),
             &payload.display_name,
+            tenant_id,
         )
         .await
         .map_err(|e| match e {
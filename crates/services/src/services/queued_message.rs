@@ -71,6 +71,13 @@ impl QueuedMessageService {
         self.queue.remove(&session_id).map(|(_, v)| v)
     }
 
+    /// Every queued message, oldest first
+    pub fn list(&self) -> Vec<QueuedMessage> {
+        let mut queued: Vec<QueuedMessage> = self.queue.iter().map(|r| r.clone()).collect();
+        queued.sort_by_key(|msg| msg.queued_at);
+        queued
+    }
+
     /// Check if a session has a queued message
     pub fn has_queued(&self, session_id: Uuid) -> bool {
         self.queue.contains_key(&session_id)
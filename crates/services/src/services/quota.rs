@@ -0,0 +1,232 @@
+//! Usage quotas per tenant.
+//!
+//! A tenant can cap how many coding agent runs it has going at once, its estimated agent spend per
+//! calendar month and the bytes it keeps in object storage. Coding agent runs are refused when they
+//! would break the first two, writes to object storage when they would break the last one. Spend
+//! is the estimate model routing records per turn, so tenants without routing costs configured
+//! never reach their limit.
+//!
+//! Objects are written through [`store_object`], which records each one against its tenant, so
+//! attachments, execution artifacts and exported transcripts all count towards storage.
+
+use db::models::{
+    execution_process::ConcurrencyLimit,
+    stored_object::{StoredObject, StoredObjectKind},
+    tenant_quota::{TenantQuota, TenantUsage},
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::services::object_store::{ObjectStore, StorageError};
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum QuotaExceeded {
+    #[error("The organization already runs {limit} coding agents at once, its quota")]
+    ConcurrentExecutions { limit: i64 },
+    #[error("The organization has used {spent:.2} of its monthly agent spend quota of {limit:.2}")]
+    MonthlySpend { limit: f64, spent: f64 },
+    #[error(
+        "Storing {requested} more bytes would exceed the organization's storage quota of {limit} bytes ({used} used)"
+    )]
+    Storage {
+        limit: i64,
+        used: i64,
+        requested: i64,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum QuotaError {
+    #[error(transparent)]
+    Exceeded(#[from] QuotaExceeded),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+/// The first quota a new coding agent run would break
+pub fn execution_blocked(quota: &TenantQuota, usage: &TenantUsage) -> Option<QuotaExceeded> {
+    if let Some(limit) = quota.max_concurrent_executions
+        && usage.running_executions >= limit
+    {
+        return Some(QuotaExceeded::ConcurrentExecutions { limit });
+    }
+    if let Some(limit) = quota.monthly_spend_limit
+        && usage.monthly_spend >= limit
+    {
+        return Some(QuotaExceeded::MonthlySpend {
+            limit,
+            spent: usage.monthly_spend,
+        });
+    }
+    None
+}
+
+/// Whether storing `requested` more bytes breaks the storage quota
+pub fn storage_blocked(
+    quota: &TenantQuota,
+    usage: &TenantUsage,
+    requested: i64,
+) -> Option<QuotaExceeded> {
+    let limit = quota.storage_limit_bytes?;
+    (usage.storage_bytes + requested > limit).then_some(QuotaExceeded::Storage {
+        limit,
+        used: usage.storage_bytes,
+        requested,
+    })
+}
+
+/// Refuse a coding agent run of the tenant when it is over a quota. Runs starting at the same time
+/// could all pass this check, so the concurrency limit it returns has to be enforced again when
+/// the run is created.
+pub async fn check_execution(
+    pool: &SqlitePool,
+    tenant_id: Uuid,
+) -> Result<Option<ConcurrencyLimit>, QuotaError> {
+    let Some(quota) = TenantQuota::find_by_tenant_id(pool, tenant_id).await? else {
+        return Ok(None);
+    };
+    let usage = TenantUsage::for_tenant(pool, tenant_id).await?;
+    match execution_blocked(&quota, &usage) {
+        Some(exceeded) => Err(exceeded.into()),
+        None => Ok(quota
+            .max_concurrent_executions
+            .map(|max_running| ConcurrencyLimit {
+                tenant_id,
+                max_running,
+            })),
+    }
+}
+
+/// Refuse storing `requested` bytes for the tenant when that would exceed its storage quota
+pub async fn check_storage(
+    pool: &SqlitePool,
+    tenant_id: Uuid,
+    requested: i64,
+) -> Result<(), QuotaError> {
+    let Some(quota) = TenantQuota::find_by_tenant_id(pool, tenant_id).await? else {
+        return Ok(());
+    };
+    let usage = TenantUsage::for_tenant(pool, tenant_id).await?;
+    match storage_blocked(&quota, &usage, requested) {
+        Some(exceeded) => Err(exceeded.into()),
+        None => Ok(()),
+    }
+}
+
+/// Write an object for a tenant, refused when it would exceed the tenant's storage quota.
+/// Overwriting a key only counts the growth.
+pub async fn store_object(
+    pool: &SqlitePool,
+    store: &dyn ObjectStore,
+    tenant_id: Uuid,
+    kind: StoredObjectKind,
+    key: &str,
+    data: Vec<u8>,
+    content_type: Option<&str>,
+) -> Result<StoredObject, QuotaError> {
+    let size_bytes = data.len() as i64;
+    let replaced = StoredObject::find_by_key(pool, key)
+        .await?
+        .filter(|existing| existing.tenant_id == tenant_id)
+        .map_or(0, |existing| existing.size_bytes);
+    check_storage(pool, tenant_id, (size_bytes - replaced).max(0)).await?;
+    store.put(key, data, content_type).await?;
+    Ok(StoredObject::record(pool, key, tenant_id, kind, size_bytes).await?)
+}
+
+/// Delete an object written with [`store_object`], giving its bytes back to its tenant
+pub async fn delete_object(
+    pool: &SqlitePool,
+    store: &dyn ObjectStore,
+    key: &str,
+) -> Result<(), QuotaError> {
+    store.delete(key).await?;
+    StoredObject::delete(pool, key).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn quota(
+        max_concurrent_executions: Option<i64>,
+        monthly_spend_limit: Option<f64>,
+        storage_limit_bytes: Option<i64>,
+    ) -> TenantQuota {
+        TenantQuota {
+            tenant_id: Uuid::nil(),
+            max_concurrent_executions,
+            monthly_spend_limit,
+            storage_limit_bytes,
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn usage(running_executions: i64, monthly_spend: f64, storage_bytes: i64) -> TenantUsage {
+        TenantUsage {
+            running_executions,
+            monthly_spend,
+            storage_bytes,
+        }
+    }
+
+    #[test]
+    fn no_limits_never_block() {
+        let quota = quota(None, None, None);
+        let usage = usage(50, 1000.0, i64::MAX / 2);
+        assert_eq!(execution_blocked(&quota, &usage), None);
+        assert_eq!(storage_blocked(&quota, &usage, 1024), None);
+    }
+
+    #[test]
+    fn concurrency_blocks_at_the_limit() {
+        let quota = quota(Some(2), None, None);
+        assert_eq!(execution_blocked(&quota, &usage(1, 0.0, 0)), None);
+        assert_eq!(
+            execution_blocked(&quota, &usage(2, 0.0, 0)),
+            Some(QuotaExceeded::ConcurrentExecutions { limit: 2 })
+        );
+    }
+
+    #[test]
+    fn spend_blocks_once_used_up() {
+        let quota = quota(None, Some(10.0), None);
+        assert_eq!(execution_blocked(&quota, &usage(0, 9.5, 0)), None);
+        assert_eq!(
+            execution_blocked(&quota, &usage(0, 10.0, 0)),
+            Some(QuotaExceeded::MonthlySpend {
+                limit: 10.0,
+                spent: 10.0
+            })
+        );
+    }
+
+    #[test]
+    fn concurrency_is_reported_before_spend() {
+        let quota = quota(Some(1), Some(1.0), None);
+        assert_eq!(
+            execution_blocked(&quota, &usage(1, 5.0, 0)),
+            Some(QuotaExceeded::ConcurrentExecutions { limit: 1 })
+        );
+    }
+
+    #[test]
+    fn storage_counts_the_new_upload() {
+        let quota = quota(None, None, Some(1000));
+        assert_eq!(storage_blocked(&quota, &usage(0, 0.0, 600), 400), None);
+        assert_eq!(
+            storage_blocked(&quota, &usage(0, 0.0, 600), 401),
+            Some(QuotaExceeded::Storage {
+                limit: 1000,
+                used: 600,
+                requested: 401
+            })
+        );
+    }
+}
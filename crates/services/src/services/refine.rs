@@ -0,0 +1,230 @@
+//! Task description co-authoring.
+//!
+//! A rough task description is handed to a coding agent running read-only in the project's
+//! repository, outside any workspace. The agent may look around the code to name the affected
+//! areas and answers with a structured version of the task. Nothing is saved: the user reviews
+//! the result and accepts it into the task like any other edit.
+
+use std::{fmt::Write, path::Path, sync::Arc, time::Duration};
+
+use executors::{
+    env::ExecutionEnv,
+    executors::{CodingAgent, ExecutorError, StandardCodingAgentExecutor},
+    logs::{NormalizedEntry, NormalizedEntryType},
+};
+use futures::{StreamExt, TryStreamExt, future, stream::select};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio_util::io::ReaderStream;
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+use crate::services::replay::collect_entries;
+
+/// Longest the agent may take to answer
+const REFINE_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Error)]
+pub enum RefineError {
+    #[error(transparent)]
+    Executor(#[from] ExecutorError),
+    #[error("The agent did not answer within {}s", REFINE_TIMEOUT.as_secs())]
+    Timeout,
+    #[error("The agent finished without an answer")]
+    NoAnswer,
+    #[error("The agent's answer is not a valid task refinement: {0}")]
+    InvalidAnswer(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct TaskRefinement {
+    pub title: String,
+    /// What should change and why, without the lists below
+    pub summary: String,
+    #[serde(default)]
+    pub acceptance_criteria: Vec<String>,
+    /// Modules, files or components likely to be touched
+    #[serde(default)]
+    pub affected_areas: Vec<String>,
+    #[serde(default)]
+    pub test_plan: Vec<String>,
+}
+
+impl TaskRefinement {
+    /// The task description to store when the refinement is accepted
+    pub fn to_description(&self) -> String {
+        let mut description = self.summary.trim().to_string();
+        for (heading, items) in [
+            ("Acceptance criteria", &self.acceptance_criteria),
+            ("Affected areas", &self.affected_areas),
+            ("Test plan", &self.test_plan),
+        ] {
+            if items.is_empty() {
+                continue;
+            }
+            let _ = write!(description, "\n\n## {heading}\n");
+            for item in items {
+                let _ = write!(description, "\n- {}", item.trim());
+            }
+        }
+        description
+    }
+}
+
+pub fn refine_prompt(title: &str, description: Option<&str>) -> String {
+    format!(
+        "You are helping write a task for a coding agent before any code is written. Improve the \
+rough task below. You may read the repository to find out which parts of the code are involved, \
+but do not modify anything.
+
+Answer with only a JSON object of this shape and nothing else:
+{{\"title\": string, \"summary\": string, \"acceptance_criteria\": [string], \
+\"affected_areas\": [string], \"test_plan\": [string]}}
+
+Keep the original intent. The summary explains what should change and why in a few sentences. \
+Acceptance criteria are concrete and checkable. Affected areas name modules or paths in this \
+repository. The test plan lists the checks that show the task is done.
+
+## Task
+
+Title: {title}
+
+{}",
+        description
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .unwrap_or("(no description)")
+    )
+}
+
+/// Read the refinement out of the agent's answer, which may wrap the JSON in a code fence or
+/// surround it with prose
+pub fn parse_refinement(answer: &str) -> Result<TaskRefinement, RefineError> {
+    let (Some(start), Some(end)) = (answer.find('{'), answer.rfind('}')) else {
+        return Err(RefineError::InvalidAnswer(
+            "no JSON object found".to_string(),
+        ));
+    };
+    if end < start {
+        return Err(RefineError::InvalidAnswer(
+            "no JSON object found".to_string(),
+        ));
+    }
+    let refinement: TaskRefinement = serde_json::from_str(&answer[start..=end])
+        .map_err(|e| RefineError::InvalidAnswer(e.to_string()))?;
+    if refinement.title.trim().is_empty() {
+        return Err(RefineError::InvalidAnswer("empty title".to_string()));
+    }
+    Ok(refinement)
+}
+
+/// Run a read-only agent to completion in `current_dir` and return its last message
+pub async fn run_agent_once(
+    agent: CodingAgent,
+    current_dir: &Path,
+    prompt: &str,
+) -> Result<String, RefineError> {
+    let mut spawned = agent
+        .spawn(current_dir, prompt, &ExecutionEnv::new())
+        .await?;
+    let store = Arc::new(MsgStore::new());
+    let out = spawned.child.inner().stdout.take().map(|out| {
+        ReaderStream::new(out)
+            .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()))
+    });
+    let err = spawned.child.inner().stderr.take().map(|err| {
+        ReaderStream::new(err)
+            .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()))
+    });
+    let forwarder = match (out, err) {
+        (Some(out), Some(err)) => Some(store.clone().spawn_forwarder(select(out, err))),
+        _ => None,
+    };
+    agent.normalize_logs(store.clone(), current_dir);
+
+    let exit_signal = spawned.exit_signal.take();
+    let finished = tokio::time::timeout(REFINE_TIMEOUT, async {
+        tokio::select! {
+            _ = spawned.child.wait() => {}
+            _ = async {
+                match exit_signal {
+                    Some(signal) => {
+                        let _ = signal.await;
+                    }
+                    None => future::pending::<()>().await,
+                }
+            } => {}
+        }
+    })
+    .await
+    .is_ok();
+    let _ = spawned.child.kill().await;
+    if !finished {
+        return Err(RefineError::Timeout);
+    }
+    if let Some(forwarder) = forwarder {
+        let _ = forwarder.await;
+    }
+    store.push_finished();
+
+    let patches = store
+        .history_plus_stream()
+        .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+        .chain(futures::stream::once(async {
+            Ok::<_, std::io::Error>(LogMsg::Finished)
+        }));
+    drop(store);
+    last_assistant_message(&collect_entries(patches).await).ok_or(RefineError::NoAnswer)
+}
+
+fn last_assistant_message(entries: &[NormalizedEntry]) -> Option<String> {
+    entries
+        .iter()
+        .rev()
+        .find(|entry| matches!(entry.entry_type, NormalizedEntryType::AssistantMessage))
+        .map(|entry| entry.content.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fenced_answers() {
+        let answer = "Here is the refined task:\n\n```json\n{\"title\": \"Add login\", \
+            \"summary\": \"Users need to sign in.\", \"acceptance_criteria\": [\"Form validates \
+            email\"], \"affected_areas\": [\"frontend/src/pages/login\"]}\n```";
+        let refinement = parse_refinement(answer).unwrap();
+        assert_eq!(refinement.title, "Add login");
+        assert_eq!(refinement.affected_areas, vec!["frontend/src/pages/login"]);
+        assert!(refinement.test_plan.is_empty());
+    }
+
+    #[test]
+    fn rejects_answers_without_a_refinement() {
+        assert!(matches!(
+            parse_refinement("I could not find the repository."),
+            Err(RefineError::InvalidAnswer(_))
+        ));
+        assert!(matches!(
+            parse_refinement("{\"title\": \"\", \"summary\": \"x\"}"),
+            Err(RefineError::InvalidAnswer(_))
+        ));
+    }
+
+    #[test]
+    fn renders_the_description_with_sections() {
+        let refinement = TaskRefinement {
+            title: "Add login".to_string(),
+            summary: "Users need to sign in.".to_string(),
+            acceptance_criteria: vec!["Form validates email".to_string()],
+            affected_areas: vec![],
+            test_plan: vec!["Unit test the validator".to_string()],
+        };
+        assert_eq!(
+            refinement.to_description(),
+            "Users need to sign in.\n\n## Acceptance criteria\n\n- Form validates email\n\n\
+             ## Test plan\n\n- Unit test the validator"
+        );
+    }
+}
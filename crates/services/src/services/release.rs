@@ -0,0 +1,87 @@
+//! Release notes for releases cut from the board.
+//!
+//! A release covers the PRs merged since the previous one, which is taken to be the newest tag of
+//! the repository. Its notes list those PRs by the titles of the tasks they were opened for, or
+//! are written from that list by a coding agent running read-only in the repository.
+
+use std::{fmt::Write, path::Path};
+
+use chrono::{DateTime, Utc};
+use db::models::merge::MergedPr;
+
+use crate::services::git::GitCli;
+
+/// When the newest tag of the repository was created, if it has any
+pub fn latest_tag_date(git: &GitCli, repo_path: &Path) -> Option<DateTime<Utc>> {
+    let raw = git
+        .git(
+            repo_path,
+            [
+                "for-each-ref",
+                "--sort=-creatordate",
+                "--count=1",
+                "--format=%(creatordate:iso-strict)",
+                "refs/tags",
+            ],
+        )
+        .ok()?;
+    DateTime::parse_from_rfc3339(raw.trim())
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Notes listing the merged PRs
+pub fn list_notes(prs: &[MergedPr]) -> String {
+    if prs.is_empty() {
+        return "No changes merged since the previous release.".to_string();
+    }
+    let mut notes = "## What's changed\n".to_string();
+    for pr in prs {
+        let _ = write!(notes, "\n- {} (#{})", pr.title.trim(), pr.number);
+    }
+    notes
+}
+
+pub fn notes_prompt(tag: &str, prs: &[MergedPr]) -> String {
+    format!(
+        "Write the release notes for release {tag} of this repository. The pull requests merged \
+for this release are listed below by title. You may read the repository to understand what they \
+changed, but do not modify anything.
+
+Group related changes under short headings, like new features and fixes, and describe each in one \
+line for someone using the project rather than working on it. Mention the pull request number of \
+each change. Answer with only the release notes in Markdown and nothing else.
+
+## Merged pull requests
+
+{}",
+        list_notes(prs)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merged(number: i64, title: &str) -> MergedPr {
+        MergedPr {
+            number,
+            url: format!("https://github.com/acme/app/pull/{number}"),
+            title: title.to_string(),
+            merged_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn lists_merged_prs_by_title() {
+        let notes = list_notes(&[merged(12, "Add login form "), merged(15, "Fix logout")]);
+        assert_eq!(
+            notes,
+            "## What's changed\n\n- Add login form (#12)\n- Fix logout (#15)"
+        );
+        assert_eq!(
+            list_notes(&[]),
+            "No changes merged since the previous release."
+        );
+    }
+}
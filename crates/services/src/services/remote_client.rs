@@ -5,8 +5,8 @@ use std::time::Duration;
 use backon::{ExponentialBuilder, Retryable};
 use chrono::Duration as ChronoDuration;
 use remote::routes::tasks::{
-    AssignSharedTaskRequest, CheckTasksRequest, CreateSharedTaskRequest, SharedTaskResponse,
-    UpdateSharedTaskRequest,
+    AssignSharedTaskRequest, CheckTasksRequest, CreateSharedTaskRequest, ListSharedTasksResponse,
+    SharedTaskResponse, UpdateSharedTaskRequest,
 };
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
@@ -16,6 +16,10 @@ use tracing::warn;
 use url::Url;
 use utils::{
     api::{
+        library::{
+            LibraryItem, ListLibraryItemsResponse, PublishLibraryItemRequest,
+            UpdateLibraryItemRequest,
+        },
         oauth::{
             HandoffInitRequest, HandoffInitResponse, HandoffRedeemRequest, HandoffRedeemResponse,
             ProfileResponse, TokenRefreshRequest, TokenRefreshResponse,
@@ -27,7 +31,10 @@ use utils::{
             ListOrganizationsResponse, Organization, RevokeInvitationRequest,
             UpdateMemberRoleRequest, UpdateMemberRoleResponse, UpdateOrganizationRequest,
         },
-        projects::{ListProjectsResponse, RemoteProject},
+        projects::{
+            EffectiveProjectPermissions, ListProjectPermissionOverridesResponse,
+            ListProjectsResponse, RemoteProject, SetProjectPermissionOverrideRequest,
+        },
     },
     jwt::extract_expiration,
 };
@@ -432,6 +439,34 @@ impl RemoteClient {
         self.post_authed("/v1/projects", Some(request)).await
     }
 
+    /// What the signed-in user may do in a project.
+    pub async fn get_my_project_permissions(
+        &self,
+        project_id: Uuid,
+    ) -> Result<EffectiveProjectPermissions, RemoteClientError> {
+        self.get_authed(&format!("/v1/projects/{project_id}/permissions/me"))
+            .await
+    }
+
+    /// Lists the permission overrides of a project.
+    pub async fn list_project_permission_overrides(
+        &self,
+        project_id: Uuid,
+    ) -> Result<ListProjectPermissionOverridesResponse, RemoteClientError> {
+        self.get_authed(&format!("/v1/projects/{project_id}/permissions"))
+            .await
+    }
+
+    /// Sets or removes a permission override of a project (admin only).
+    pub async fn set_project_permission_override(
+        &self,
+        project_id: Uuid,
+        request: &SetProjectPermissionOverrideRequest,
+    ) -> Result<ListProjectPermissionOverridesResponse, RemoteClientError> {
+        self.patch_authed(&format!("/v1/projects/{project_id}/permissions"), request)
+            .await
+    }
+
     /// Gets a specific organization by ID.
     pub async fn get_organization(
         &self,
@@ -553,6 +588,15 @@ impl RemoteClient {
         self.post_authed("/v1/tasks", Some(request)).await
     }
 
+    /// Lists the shared tasks of a remote project, deleted ones included.
+    pub async fn list_shared_tasks(
+        &self,
+        project_id: Uuid,
+    ) -> Result<ListSharedTasksResponse, RemoteClientError> {
+        self.get_authed(&format!("/v1/tasks?project_id={project_id}"))
+            .await
+    }
+
     /// Updates a shared task.
     pub async fn update_shared_task(
         &self,
@@ -591,6 +635,38 @@ impl RemoteClient {
             .map_err(|e| RemoteClientError::Serde(e.to_string()))
     }
 
+    /// Lists the templates and automation rules published to an organization.
+    pub async fn list_library_items(
+        &self,
+        organization_id: Uuid,
+    ) -> Result<ListLibraryItemsResponse, RemoteClientError> {
+        self.get_authed(&format!("/v1/library?organization_id={organization_id}"))
+            .await
+    }
+
+    /// Publishes a template or automation rule to an organization.
+    pub async fn publish_library_item(
+        &self,
+        request: &PublishLibraryItemRequest,
+    ) -> Result<LibraryItem, RemoteClientError> {
+        self.post_authed("/v1/library", Some(request)).await
+    }
+
+    /// Publishes a new version of a library item.
+    pub async fn update_library_item(
+        &self,
+        item_id: Uuid,
+        request: &UpdateLibraryItemRequest,
+    ) -> Result<LibraryItem, RemoteClientError> {
+        self.patch_authed(&format!("/v1/library/{item_id}"), request)
+            .await
+    }
+
+    /// Removes a library item from its organization.
+    pub async fn delete_library_item(&self, item_id: Uuid) -> Result<(), RemoteClientError> {
+        self.delete_authed(&format!("/v1/library/{item_id}")).await
+    }
+
     /// Checks if shared tasks exist.
     pub async fn check_tasks(&self, task_ids: Vec<Uuid>) -> Result<Vec<Uuid>, RemoteClientError> {
         let request = CheckTasksRequest { task_ids };
@@ -0,0 +1,448 @@
+//! Replay of a past coding agent execution without calling the agent.
+//!
+//! The file edits recorded in an execution's normalized logs, and optionally its commands, are
+//! applied in order to fresh detached worktrees at the commits the execution started from. The
+//! result is then compared with the commits it ended on. This answers "how did the repo end up
+//! like this" without the model, and the recorded steps double as fixtures for executor tests.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use executors::logs::{
+    ActionType, FileChange, NormalizedEntry, NormalizedEntryType, ToolStatus,
+    utils::patch::extract_normalized_entry_from_patch,
+};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::process::Command;
+use ts_rs::TS;
+use utils::{diff::extract_unified_diff_hunks, log_msg::LogMsg, shell::get_shell_command};
+use uuid::Uuid;
+
+use crate::services::git::{GitCli, GitCliError};
+
+/// Longest a replayed command may run
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error(transparent)]
+    GitCli(#[from] GitCliError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Hunk {hunk} does not apply")]
+    HunkDoesNotApply { hunk: usize },
+}
+
+/// A recorded tool call that changes the workspace
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplayStep {
+    FileEdit { path: String, change: FileChange },
+    Command { command: String },
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct ReplayRequest {
+    /// Also run the recorded commands; off by default since they may not be repeatable
+    #[serde(default)]
+    pub run_commands: bool,
+    /// Leave the replay worktrees on disk for inspection
+    #[serde(default)]
+    pub keep_worktree: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum StepOutcome {
+    Applied,
+    Skipped { reason: String },
+    Failed { message: String },
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ReplayStepResult {
+    pub step: ReplayStep,
+    pub outcome: StepOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RepoReplayResult {
+    pub repo: String,
+    /// Commit the execution ended on
+    pub expected_commit: Option<String>,
+    /// Whether the replayed tree matches it; None when the end state was not recorded
+    pub matches: Option<bool>,
+    /// Paths that differ from the expected commit
+    pub differing_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ReplayReport {
+    pub execution_process_id: Uuid,
+    pub steps: Vec<ReplayStepResult>,
+    pub repos: Vec<RepoReplayResult>,
+    /// Every repository with a recorded end state was reproduced
+    pub reproduced: bool,
+    /// Where the replay worktrees were left, when kept
+    pub worktree_path: Option<String>,
+}
+
+/// A repository of the workspace as it was when the execution started
+#[derive(Debug, Clone)]
+pub struct ReplayRepo {
+    pub name: String,
+    pub repo_path: PathBuf,
+    pub before_commit: String,
+    pub after_commit: Option<String>,
+}
+
+/// Collect the final state of each normalized entry from a normalized log stream
+pub async fn collect_entries(
+    stream: impl Stream<Item = Result<LogMsg, std::io::Error>>,
+) -> Vec<NormalizedEntry> {
+    let mut entries = BTreeMap::new();
+    let mut stream = std::pin::pin!(stream);
+    while let Some(Ok(msg)) = stream.next().await {
+        match msg {
+            LogMsg::JsonPatch(patch) => {
+                if let Some((index, entry)) = extract_normalized_entry_from_patch(&patch) {
+                    entries.insert(index, entry);
+                }
+            }
+            LogMsg::Finished => break,
+            _ => {}
+        }
+    }
+    entries.into_values().collect()
+}
+
+/// File edits and commands among the entries, skipping tool calls that failed or were denied
+pub fn steps_from_entries(entries: &[NormalizedEntry]) -> Vec<ReplayStep> {
+    entries
+        .iter()
+        .filter_map(|entry| match &entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type,
+                status: ToolStatus::Created | ToolStatus::Success,
+                ..
+            } => Some(action_type),
+            _ => None,
+        })
+        .flat_map(|action| match action {
+            ActionType::FileEdit { path, changes } => changes
+                .iter()
+                .map(|change| ReplayStep::FileEdit {
+                    path: path.clone(),
+                    change: change.clone(),
+                })
+                .collect(),
+            ActionType::CommandRun { command, .. } => vec![ReplayStep::Command {
+                command: command.clone(),
+            }],
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// Apply the hunks of a unified diff by matching their context rather than their line numbers,
+/// since not every executor reports reliable ones
+pub fn apply_edit(content: &str, unified_diff: &str) -> Result<String, ReplayError> {
+    let mut result = content.to_string();
+    let mut cursor = 0;
+    for (index, hunk) in extract_unified_diff_hunks(unified_diff).iter().enumerate() {
+        let mut lines = hunk.split_inclusive('\n');
+        let header = lines.next().unwrap_or_default();
+        let (mut old, mut new) = (String::new(), String::new());
+        for line in lines {
+            match line.split_at_checked(1) {
+                Some((" ", text)) => {
+                    old.push_str(text);
+                    new.push_str(text);
+                }
+                Some(("-", text)) => old.push_str(text),
+                Some(("+", text)) => new.push_str(text),
+                _ => {}
+            }
+        }
+
+        if old.is_empty() {
+            let at = new_start_line(header)
+                .and_then(|line| line_offset(&result, line.saturating_sub(1)))
+                .unwrap_or(result.len());
+            result.insert_str(at, &new);
+            cursor = at + new.len();
+            continue;
+        }
+        let found = result[cursor..]
+            .find(&old)
+            .map(|at| (cursor + at, old.as_str(), new.as_str()))
+            .or_else(|| {
+                // The file may not end with a newline while the hunk does
+                let (old, new) = (old.strip_suffix('\n')?, new.strip_suffix('\n')?);
+                let at = result[cursor..].find(old)?;
+                Some((cursor + at, old, new))
+            });
+        let Some((at, old, new)) = found else {
+            return Err(ReplayError::HunkDoesNotApply { hunk: index + 1 });
+        };
+        result.replace_range(at..at + old.len(), new);
+        cursor = at + new.len();
+    }
+    Ok(result)
+}
+
+fn new_start_line(header: &str) -> Option<usize> {
+    let range = header
+        .split_whitespace()
+        .find(|part| part.starts_with('+'))?;
+    range[1..].split(',').next()?.parse().ok()
+}
+
+fn line_offset(content: &str, line: usize) -> Option<usize> {
+    if line == 0 {
+        return Some(0);
+    }
+    content
+        .match_indices('\n')
+        .nth(line - 1)
+        .map(|(at, _)| at + 1)
+}
+
+/// Map a recorded path into the replay root; recorded paths are relative to the workspace or
+/// absolute under it
+fn resolve(replay_root: &Path, workspace_root: &Path, path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    let relative = if path.is_absolute() {
+        path.strip_prefix(workspace_root).ok()?
+    } else {
+        path
+    };
+    if relative
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return None;
+    }
+    Some(replay_root.join(relative))
+}
+
+fn apply_file_change(
+    target: &Path,
+    change: &FileChange,
+    replay_root: &Path,
+    workspace_root: &Path,
+) -> Result<(), String> {
+    match change {
+        FileChange::Write { content } => {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::write(target, content).map_err(|e| e.to_string())
+        }
+        FileChange::Delete => std::fs::remove_file(target).map_err(|e| e.to_string()),
+        FileChange::Rename { new_path } => {
+            let destination = resolve(replay_root, workspace_root, new_path)
+                .ok_or_else(|| format!("{new_path} is outside the workspace"))?;
+            if let Some(parent) = destination.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::rename(target, destination).map_err(|e| e.to_string())
+        }
+        FileChange::Edit { unified_diff, .. } => {
+            let content = std::fs::read_to_string(target).unwrap_or_default();
+            let edited = apply_edit(&content, unified_diff).map_err(|e| e.to_string())?;
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::write(target, edited).map_err(|e| e.to_string())
+        }
+    }
+}
+
+async fn run_command(replay_root: &Path, command: &str) -> Result<(), String> {
+    let (shell, shell_arg) = get_shell_command();
+    let output = Command::new(shell)
+        .arg(shell_arg)
+        .arg(command)
+        .current_dir(replay_root)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(COMMAND_TIMEOUT, output)
+        .await
+        .map_err(|_| format!("Timed out after {}s", COMMAND_TIMEOUT.as_secs()))?
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("Exited with {}", output.status))
+    }
+}
+
+/// Replay `steps` into fresh worktrees of `repos` and compare the outcome with the recorded end
+/// state. `workspace_root` is where the execution originally ran.
+pub async fn replay(
+    git: &GitCli,
+    execution_process_id: Uuid,
+    workspace_root: &Path,
+    repos: &[ReplayRepo],
+    steps: Vec<ReplayStep>,
+    request: &ReplayRequest,
+) -> Result<ReplayReport, ReplayError> {
+    let replay_root = std::env::temp_dir().join(format!("vk-replay-{execution_process_id}"));
+    if replay_root.exists() {
+        std::fs::remove_dir_all(&replay_root)?;
+    }
+    std::fs::create_dir_all(&replay_root)?;
+    for repo in repos {
+        git.worktree_add(
+            &repo.repo_path,
+            &replay_root.join(&repo.name),
+            &repo.before_commit,
+            false,
+        )?;
+    }
+
+    let mut results = Vec::with_capacity(steps.len());
+    for step in steps {
+        let outcome = match &step {
+            ReplayStep::FileEdit { path, change } => {
+                match resolve(&replay_root, workspace_root, path) {
+                    Some(target) => {
+                        match apply_file_change(&target, change, &replay_root, workspace_root) {
+                            Ok(()) => StepOutcome::Applied,
+                            Err(message) => StepOutcome::Failed { message },
+                        }
+                    }
+                    None => StepOutcome::Skipped {
+                        reason: "Path is outside the workspace".to_string(),
+                    },
+                }
+            }
+            ReplayStep::Command { .. } if !request.run_commands => StepOutcome::Skipped {
+                reason: "Commands are not replayed".to_string(),
+            },
+            ReplayStep::Command { command } => match run_command(&replay_root, command).await {
+                Ok(()) => StepOutcome::Applied,
+                Err(message) => StepOutcome::Failed { message },
+            },
+        };
+        results.push(ReplayStepResult { step, outcome });
+    }
+
+    let mut repo_results = Vec::with_capacity(repos.len());
+    for repo in repos {
+        let worktree_path = replay_root.join(&repo.name);
+        let differing_paths = match &repo.after_commit {
+            Some(after) => {
+                git.add_all(&worktree_path)?;
+                let out = git.git(
+                    &worktree_path,
+                    ["diff", "--cached", "--name-only", after.as_str()],
+                )?;
+                Some(out.lines().map(str::to_string).collect::<Vec<_>>())
+            }
+            None => None,
+        };
+        repo_results.push(RepoReplayResult {
+            repo: repo.name.clone(),
+            expected_commit: repo.after_commit.clone(),
+            matches: differing_paths.as_ref().map(Vec::is_empty),
+            differing_paths: differing_paths.unwrap_or_default(),
+        });
+    }
+
+    let worktree_path = if request.keep_worktree {
+        Some(replay_root.to_string_lossy().into_owned())
+    } else {
+        for repo in repos {
+            if let Err(e) =
+                git.worktree_remove(&repo.repo_path, &replay_root.join(&repo.name), true)
+            {
+                tracing::warn!("Failed to remove replay worktree for {}: {}", repo.name, e);
+            }
+        }
+        let _ = std::fs::remove_dir_all(&replay_root);
+        None
+    };
+
+    Ok(ReplayReport {
+        execution_process_id,
+        reproduced: repo_results.iter().all(|repo| repo.matches != Some(false)),
+        steps: results,
+        repos: repo_results,
+        worktree_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_hunks_by_context() {
+        let content = "fn main() {\n    println!(\"hi\");\n}\n";
+        // Line numbers are wrong on purpose; matching uses the context lines
+        let diff = "--- a/main.rs\n+++ b/main.rs\n@@ -10,3 +10,4 @@\n fn main() {\n-    println!(\"hi\");\n+    println!(\"hello\");\n+    run();\n }\n";
+        assert_eq!(
+            apply_edit(content, diff).unwrap(),
+            "fn main() {\n    println!(\"hello\");\n    run();\n}\n"
+        );
+    }
+
+    #[test]
+    fn rejects_hunks_that_do_not_match() {
+        let diff = "@@ -1,1 +1,1 @@\n-missing\n+present\n";
+        assert!(matches!(
+            apply_edit("other\n", diff),
+            Err(ReplayError::HunkDoesNotApply { hunk: 1 })
+        ));
+    }
+
+    #[test]
+    fn handles_a_missing_trailing_newline() {
+        let diff = "@@ -1,1 +1,1 @@\n-a\n+b\n";
+        assert_eq!(apply_edit("a", diff).unwrap(), "b");
+    }
+
+    #[test]
+    fn resolves_paths_into_the_replay_root() {
+        let root = Path::new("/tmp/replay");
+        let workspace = Path::new("/work/ws");
+        assert_eq!(
+            resolve(root, workspace, "app/src/lib.rs"),
+            Some(root.join("app/src/lib.rs"))
+        );
+        assert_eq!(
+            resolve(root, workspace, "/work/ws/app/a.rs"),
+            Some(root.join("app/a.rs"))
+        );
+        assert_eq!(resolve(root, workspace, "/etc/passwd"), None);
+        assert_eq!(resolve(root, workspace, "../escape"), None);
+    }
+
+    #[test]
+    fn skips_failed_tool_calls() {
+        let entry = |status| NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "cargo fmt".to_string(),
+                    result: None,
+                },
+                status,
+            },
+            content: String::new(),
+            metadata: None,
+        };
+        let steps = steps_from_entries(&[entry(ToolStatus::Success), entry(ToolStatus::Failed)]);
+        assert_eq!(steps.len(), 1);
+    }
+}
@@ -1,12 +1,20 @@
 use std::path::{Path, PathBuf};
 
-use db::models::repo::Repo as RepoModel;
+use db::models::{
+    managed_repo::ManagedRepo, repo::Repo as RepoModel, workspace_repo::WorkspaceRepo,
+};
 use sqlx::SqlitePool;
 use thiserror::Error;
-use utils::path::expand_tilde;
+use utils::{assets::asset_dir, path::expand_tilde};
 use uuid::Uuid;
 
-use super::git::{GitService, GitServiceError};
+use super::{
+    git::{GitCli, GitCliError, GitService, GitServiceError},
+    git_provider::{ProviderType, detect_provider_from_url, get_remote_url},
+};
+
+/// History fetched for repositories cloned by URL unless a full clone is asked for
+const SHALLOW_CLONE_DEPTH: u32 = 1;
 
 #[derive(Debug, Error)]
 pub enum RepoError {
@@ -28,13 +36,115 @@ pub enum RepoError {
     Git(#[from] GitServiceError),
     #[error("Invalid folder name: {0}")]
     InvalidFolderName(String),
+    #[error("Invalid remote URL: {0}")]
+    InvalidRemoteUrl(String),
+    #[error("Clone failed: {0}")]
+    Clone(GitCliError),
+    #[error("Git error: {0}")]
+    GitCli(#[from] GitCliError),
+    #[error("Repository is used by {0} workspaces with worktrees")]
+    InUse(i64),
 }
 
 pub type Result<T> = std::result::Result<T, RepoError>;
 
+/// Where repositories registered by URL are cloned to
+pub fn managed_repos_dir() -> PathBuf {
+    asset_dir().join("repos")
+}
+
+/// Host and path of a remote URL, e.g. `github.com/owner/name` for
+/// `git@github.com:owner/name.git`. Only network remotes are accepted.
+pub(crate) fn remote_location(url: &str) -> Option<(String, Vec<String>)> {
+    let url = url.trim();
+    let (host, path) = if let Some((scheme, rest)) = url.split_once("://") {
+        if !matches!(scheme, "https" | "http" | "ssh" | "git") {
+            return None;
+        }
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+        (host, path)
+    } else {
+        // scp-like syntax: [user@]host:path
+        let (authority, path) = url.split_once(':')?;
+        if authority.contains('/') {
+            return None;
+        }
+        (authority.rsplit('@').next()?, path)
+    };
+    let segments: Vec<String> = path
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect();
+    let valid = |part: &str| {
+        !part.is_empty()
+            && !part.starts_with(['.', '-'])
+            && part
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    if !valid(host) || segments.is_empty() || !segments.iter().all(|s| valid(s)) {
+        return None;
+    }
+    Some((host.to_lowercase(), segments))
+}
+
 #[derive(Clone, Default)]
 pub struct RepoService;
 
+/// Clone `source` into `dest` as a primary clone: HEAD is detached so no branch is checked out
+/// there, and every branch of the source gets a local branch. With `remote_url`, origin is then
+/// pointed at it and fetched. Nothing is left behind on failure.
+pub fn create_primary_clone(
+    git: &GitCli,
+    source: &str,
+    dest: &Path,
+    depth: Option<u32>,
+    remote_url: Option<&str>,
+) -> Result<()> {
+    let (Some(parent), Some(name)) = (dest.parent(), dest.file_name()) else {
+        return Err(RepoError::InvalidFolderName(dest.display().to_string()));
+    };
+    std::fs::create_dir_all(parent)?;
+    let result = git
+        .clone_repo(parent, source, &name.to_string_lossy(), depth)
+        .map_err(RepoError::Clone)
+        .and_then(|()| {
+            git.detach_head(dest)?;
+            git.sync_local_branches(dest, "origin")?;
+            if let Some(url) = remote_url {
+                git.set_remote_url(dest, "origin", url)?;
+                // Without credentials for the upstream the clone still works from the source's
+                // branches until the next successful fetch
+                match git.fetch_prune(dest, "origin") {
+                    Ok(()) => {
+                        git.sync_local_branches(dest, "origin")?;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to fetch {} into {}: {}", url, dest.display(), e)
+                    }
+                }
+            }
+            Ok(())
+        });
+    if result.is_err() {
+        // Leave nothing half-cloned behind for the next attempt to trip over
+        let _ = std::fs::remove_dir_all(dest);
+    }
+    result
+}
+
+/// Fetch origin into a primary clone and fast-forward its local branches. Returns the branches
+/// that changed.
+pub fn sync_primary_clone(git: &GitCli, path: &Path) -> Result<Vec<String>> {
+    git.fetch_prune(path, "origin")?;
+    Ok(git.sync_local_branches(path, "origin")?)
+}
+
 impl RepoService {
     pub fn new() -> Self {
         Self
@@ -65,6 +175,7 @@ impl RepoService {
         pool: &SqlitePool,
         path: &str,
         display_name: Option<&str>,
+        tenant_id: Uuid,
     ) -> Result<RepoModel> {
         let normalized_path = self.normalize_path(path)?;
         self.validate_git_repo_path(&normalized_path)?;
@@ -76,10 +187,124 @@ impl RepoService {
 
         let display_name = display_name.unwrap_or(&name);
 
-        let repo = RepoModel::find_or_create(pool, &normalized_path, display_name).await?;
+        let repo =
+            RepoModel::find_or_create(pool, &normalized_path, display_name, tenant_id).await?;
         Ok(repo)
     }
 
+    /// Clone a repository by its remote URL into the managed directory and register it. The
+    /// clone is shallow unless `full_clone` is set. Registering a URL that was cloned before
+    /// returns the existing repository.
+    pub async fn register_from_url(
+        &self,
+        pool: &SqlitePool,
+        git: &GitCli,
+        url: &str,
+        display_name: Option<&str>,
+        full_clone: bool,
+        tenant_id: Uuid,
+    ) -> Result<(RepoModel, Option<ProviderType>)> {
+        let (host, segments) =
+            remote_location(url).ok_or_else(|| RepoError::InvalidRemoteUrl(url.to_string()))?;
+        let provider = detect_provider_from_url(url)
+            .ok()
+            .map(|(provider, _)| provider);
+        let name = segments.last().cloned().unwrap_or_default();
+
+        let repo_path = segments
+            .iter()
+            .fold(managed_repos_dir().join(&host), |dir, segment| {
+                dir.join(segment)
+            });
+        let url = url.trim().to_string();
+        if repo_path.exists() {
+            self.validate_git_repo_path(&repo_path)?;
+        } else {
+            let (git, source, dest) = (git.clone(), url.clone(), repo_path.clone());
+            let depth = (!full_clone).then_some(SHALLOW_CLONE_DEPTH);
+            tokio::task::spawn_blocking(move || {
+                create_primary_clone(&git, &source, &dest, depth, None)
+            })
+            .await
+            .map_err(|e| RepoError::Io(std::io::Error::other(e)))??;
+        }
+
+        let display_name = display_name.unwrap_or(&name);
+        let repo = RepoModel::find_or_create(pool, &repo_path, display_name, tenant_id).await?;
+        ManagedRepo::create(pool, repo.id, None, Some(&url)).await?;
+        Ok((repo, provider))
+    }
+
+    /// Move a repository registered from a user checkout to a managed primary clone. The clone
+    /// gets a local branch for every branch of the checkout and fetches from the checkout's
+    /// upstream from then on. Workspaces created afterwards are worktrees of the clone, so the
+    /// checkout's dirty files or current branch no longer leak into attempts.
+    pub async fn adopt_into_managed_storage(
+        &self,
+        pool: &SqlitePool,
+        git: &GitCli,
+        repo_id: Uuid,
+    ) -> Result<RepoModel> {
+        let repo = self.get_by_id(pool, repo_id).await?;
+        if ManagedRepo::find_by_repo_id(pool, repo.id).await?.is_some() {
+            return Ok(repo);
+        }
+        // Existing worktrees are linked to the checkout and would lose their branches
+        let in_use = WorkspaceRepo::count_workspaces_with_worktrees(pool, repo.id).await?;
+        if in_use > 0 {
+            return Err(RepoError::InUse(in_use));
+        }
+        self.validate_git_repo_path(&repo.path)?;
+
+        let remote_url = get_remote_url(&repo.path).ok();
+        let managed_path = managed_repos_dir()
+            .join("local")
+            .join(repo.id.to_string())
+            .join(&repo.name);
+        if managed_path.exists() {
+            // Left over from an adoption that failed before it was recorded
+            std::fs::remove_dir_all(&managed_path)?;
+        }
+        let (clone_git, source, dest, upstream) = (
+            git.clone(),
+            repo.path.to_string_lossy().to_string(),
+            managed_path.clone(),
+            remote_url.clone(),
+        );
+        tokio::task::spawn_blocking(move || {
+            create_primary_clone(&clone_git, &source, &dest, None, upstream.as_deref())
+        })
+        .await
+        .map_err(|e| RepoError::Io(std::io::Error::other(e)))??;
+
+        let source_path = repo.path.to_string_lossy().to_string();
+        RepoModel::update_path(pool, repo.id, &managed_path).await?;
+        ManagedRepo::create(pool, repo.id, Some(&source_path), remote_url.as_deref()).await?;
+        tracing::info!(
+            "Moved repository {} from {} to managed storage at {}",
+            repo.name,
+            source_path,
+            managed_path.display()
+        );
+        self.get_by_id(pool, repo.id).await
+    }
+
+    /// Fetch a managed repository and bring its local branches up to date with the remote.
+    /// Returns the branches that changed.
+    pub async fn sync_managed(
+        &self,
+        pool: &SqlitePool,
+        git: &GitCli,
+        repo: &RepoModel,
+    ) -> Result<Vec<String>> {
+        let (git, path) = (git.clone(), repo.path.clone());
+        let updated = tokio::task::spawn_blocking(move || sync_primary_clone(&git, &path))
+            .await
+            .map_err(|e| RepoError::Io(std::io::Error::other(e)))??;
+        ManagedRepo::mark_fetched(pool, repo.id).await?;
+        Ok(updated)
+    }
+
     pub async fn find_by_id(&self, pool: &SqlitePool, repo_id: Uuid) -> Result<Option<RepoModel>> {
         let repo = RepoModel::find_by_id(pool, repo_id).await?;
         Ok(repo)
@@ -97,6 +322,7 @@ impl RepoService {
         git: &GitService,
         parent_path: &str,
         folder_name: &str,
+        tenant_id: Uuid,
     ) -> Result<RepoModel> {
         if folder_name.is_empty()
             || folder_name.contains('/')
@@ -122,7 +348,46 @@ impl RepoService {
 
         git.initialize_repo_with_main_branch(&repo_path)?;
 
-        let repo = RepoModel::find_or_create(pool, &repo_path, folder_name).await?;
+        let repo = RepoModel::find_or_create(pool, &repo_path, folder_name, tenant_id).await?;
         Ok(repo)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_network_remotes() {
+        let location = |host: &str, path: &[&str]| {
+            Some((
+                host.to_string(),
+                path.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            ))
+        };
+        assert_eq!(
+            remote_location("https://github.com/owner/name.git"),
+            location("github.com", &["owner", "name"])
+        );
+        assert_eq!(
+            remote_location("git@GitLab.com:group/sub/project.git"),
+            location("gitlab.com", &["group", "sub", "project"])
+        );
+        assert_eq!(
+            remote_location("ssh://git@git.example.com:2222/team/repo"),
+            location("git.example.com", &["team", "repo"])
+        );
+    }
+
+    #[test]
+    fn rejects_local_and_unsafe_remotes() {
+        assert_eq!(remote_location("/home/me/repo"), None);
+        assert_eq!(remote_location("file:///home/me/repo"), None);
+        assert_eq!(remote_location("https://github.com/owner/../etc"), None);
+        assert_eq!(
+            remote_location("https://github.com/--upload-pack=x/repo"),
+            None
+        );
+        assert_eq!(remote_location("https://github.com/"), None);
+    }
+}
@@ -0,0 +1,148 @@
+//! Dependency-ordered execution across the repositories of a workspace.
+//!
+//! When a change in one repository depends on another, for instance an API change followed by
+//! the client update, the workspace's repositories can be given an order. Each turn of the chain
+//! then works in a single repository, and its prompt carries what the earlier repositories
+//! changed: the pull request link when one was opened and the diff, clipped to a budget.
+
+use std::fmt::Write;
+
+use utils::text::truncate_to_char_boundary;
+
+use crate::services::compaction::{self, RepoDiff};
+
+/// Room for the diffs of earlier repositories in a step's prompt
+pub const MAX_EARLIER_DIFF_CHARS: usize = 24_000;
+
+/// What an earlier step of the chain produced
+#[derive(Debug, Clone)]
+pub struct EarlierStep {
+    pub repo: String,
+    pub pr_url: Option<String>,
+    pub changes: Vec<RepoDiff>,
+}
+
+/// Prompt for the step working in `repo`, after the steps in `earlier`
+pub fn step_prompt(
+    task_prompt: &str,
+    repo: &str,
+    instructions: Option<&str>,
+    earlier: &[EarlierStep],
+    max_diff_chars: usize,
+) -> String {
+    let mut content = format!(
+        "## Repository order\n\nThis task spans several repositories that are changed one after \
+         another. In this turn, only change the `{repo}` repository and leave the others as they \
+         are."
+    );
+    if !earlier.is_empty() {
+        content.push_str(
+            " The repositories below were already changed for this task; build on their changes.\n",
+        );
+        let mut remaining = max_diff_chars;
+        for step in earlier {
+            let _ = write!(content, "\n### {}\n\n", step.repo);
+            if let Some(url) = &step.pr_url {
+                let _ = writeln!(content, "Pull request: {url}\n");
+            }
+            let patches: Vec<String> = step.changes.iter().filter_map(compaction::patch).collect();
+            if patches.is_empty() {
+                content.push_str("No file changes.\n");
+                continue;
+            }
+            let diff = patches.join("\n");
+            if remaining == 0 {
+                content.push_str("Diff left out for length.\n");
+                continue;
+            }
+            let clipped = truncate_to_char_boundary(&diff, remaining);
+            remaining -= clipped.len();
+            let _ = write!(content, "```diff\n{}\n```\n", clipped.trim_end());
+            if clipped.len() < diff.len() {
+                content.push_str("[diff truncated]\n");
+            }
+        }
+    } else {
+        content.push('\n');
+    }
+    if let Some(instructions) = instructions.map(str::trim).filter(|i| !i.is_empty()) {
+        let _ = write!(content, "\n## Instructions for {repo}\n\n{instructions}\n");
+    }
+    format!("{content}\n## Task\n\n{task_prompt}")
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::diff::{Diff, DiffChangeKind};
+
+    use super::*;
+
+    fn change(repo: &str, path: &str, old: &str, new: &str) -> RepoDiff {
+        RepoDiff {
+            repo: repo.to_string(),
+            diff: Diff {
+                change: DiffChangeKind::Modified,
+                old_path: Some(path.to_string()),
+                new_path: Some(path.to_string()),
+                old_content: Some(old.to_string()),
+                new_content: Some(new.to_string()),
+                content_omitted: false,
+                additions: None,
+                deletions: None,
+            },
+        }
+    }
+
+    #[test]
+    fn first_step_names_only_its_repository() {
+        let prompt = step_prompt("Rename the user endpoint", "api", None, &[], 1000);
+        assert!(prompt.contains("only change the `api` repository"));
+        assert!(!prompt.contains("already changed"));
+        assert!(prompt.ends_with("## Task\n\nRename the user endpoint"));
+    }
+
+    #[test]
+    fn later_steps_carry_earlier_links_and_diffs() {
+        let earlier = [EarlierStep {
+            repo: "api".to_string(),
+            pr_url: Some("https://github.com/acme/api/pull/7".to_string()),
+            changes: vec![change(
+                "api",
+                "src/routes.rs",
+                "get(\"/user\")\n",
+                "get(\"/users\")\n",
+            )],
+        }];
+        let prompt = step_prompt(
+            "Rename the user endpoint",
+            "web",
+            Some("Update the fetch calls"),
+            &earlier,
+            1000,
+        );
+        assert!(prompt.contains("only change the `web` repository"));
+        assert!(prompt.contains("### api\n\nPull request: https://github.com/acme/api/pull/7"));
+        assert!(prompt.contains("api/src/routes.rs"));
+        assert!(prompt.contains("+get(\"/users\")"));
+        assert!(prompt.contains("## Instructions for web\n\nUpdate the fetch calls"));
+    }
+
+    #[test]
+    fn diffs_are_clipped_to_the_budget() {
+        let earlier = [
+            EarlierStep {
+                repo: "api".to_string(),
+                pr_url: None,
+                changes: vec![change("api", "a.txt", "", &"x\n".repeat(200))],
+            },
+            EarlierStep {
+                repo: "sdk".to_string(),
+                pr_url: None,
+                changes: vec![change("sdk", "b.txt", "", "y\n")],
+            },
+        ];
+        let prompt = step_prompt("Task", "web", None, &earlier, 100);
+        assert!(prompt.contains("[diff truncated]"));
+        assert!(prompt.contains("### sdk\n\nDiff left out for length."));
+    }
+}
@@ -0,0 +1,235 @@
+//! Background fetching of registered repositories.
+//!
+//! Branch checks and target-branch comparisons read remote-tracking refs, which are only as fresh
+//! as the last fetch. The scheduler fetches every registered repository with a remote on an
+//! interval, which repositories can override or turn off, and records the outcome of each fetch.
+//! It runs as the recurring [`REPO_FETCH_JOB`].
+//! Only remote-tracking refs move in the user's own checkouts; managed clones also fast-forward
+//! their local branches, since nobody pulls them by hand. After each fetch the divergence of the
+//! repository's workspace branches is recomputed.
+
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{managed_repo::ManagedRepo, repo::Repo, repo_fetch_state::RepoFetchState},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use ts_rs::TS;
+
+use crate::services::{
+    config::Config,
+    divergence,
+    git::{GitCli, GitService},
+    jobs::JobHandler,
+    repo::{RepoError, RepoService},
+};
+
+/// Recurring job that fetches the repositories that are due
+pub const REPO_FETCH_JOB: &str = "repo_fetch.fetch_due";
+
+/// How often the scheduler looks for repositories that are due for a fetch
+pub const REPO_FETCH_INTERVAL: Duration = Duration::from_secs(60);
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval_minutes() -> u32 {
+    10
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct FetchConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Minutes between fetches of each repository, unless the repository sets its own
+    #[serde(default = "default_interval_minutes")]
+    pub interval_minutes: u32,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            interval_minutes: default_interval_minutes(),
+        }
+    }
+}
+
+/// Whether a repository with `state` should be fetched at `now`
+pub fn is_due(state: Option<&RepoFetchState>, config: &FetchConfig, now: DateTime<Utc>) -> bool {
+    let Some(state) = state else {
+        return true;
+    };
+    if !state.enabled {
+        return false;
+    }
+    let minutes = state
+        .interval_minutes
+        .unwrap_or(config.interval_minutes as i64)
+        .max(1);
+    state
+        .last_attempt_at
+        .is_none_or(|last| now - last >= chrono::Duration::minutes(minutes))
+}
+
+/// Fetch `repo` now and record the outcome, or return None when it has no remote to fetch from
+pub async fn fetch_repo(
+    pool: &SqlitePool,
+    repo_service: &RepoService,
+    git: &GitCli,
+    repo: &Repo,
+) -> Result<Option<RepoFetchState>, sqlx::Error> {
+    let result = if ManagedRepo::find_by_repo_id(pool, repo.id).await?.is_some() {
+        repo_service
+            .sync_managed(pool, git, repo)
+            .await
+            .map(|updated| {
+                if !updated.is_empty() {
+                    info!("Updated {} branches of {}", updated.len(), repo.name);
+                }
+            })
+    } else {
+        let (git, path) = (git.clone(), repo.path.clone());
+        let fetched = tokio::task::spawn_blocking(move || {
+            let remotes = git.remotes(&path)?;
+            let Some(remote) = remotes
+                .iter()
+                .find(|remote| *remote == "origin")
+                .or(remotes.first())
+            else {
+                return Ok(false);
+            };
+            git.fetch_prune(&path, remote)?;
+            Ok::<_, RepoError>(true)
+        })
+        .await
+        .map_err(|e| RepoError::Io(std::io::Error::other(e)))
+        .and_then(|fetched| fetched);
+        match fetched {
+            Ok(false) => {
+                // Nothing to fetch, but local target branches still move
+                divergence::refresh_repo(pool, &GitService::new(), repo).await?;
+                return Ok(None);
+            }
+            Ok(true) => Ok(()),
+            Err(e) => Err(e),
+        }
+    };
+
+    let state = match result {
+        Ok(()) => {
+            debug!("Fetched {}", repo.name);
+            RepoFetchState::record_success(pool, repo.id).await?
+        }
+        Err(e) => {
+            warn!("Failed to fetch {}: {}", repo.name, e);
+            RepoFetchState::record_failure(pool, repo.id, &e.to_string()).await?
+        }
+    };
+    divergence::refresh_repo(pool, &GitService::new(), repo).await?;
+    Ok(Some(state))
+}
+
+pub struct RepoFetchScheduler {
+    db: DBService,
+    repo_service: RepoService,
+    config: Arc<RwLock<Config>>,
+    git: GitCli,
+}
+
+#[async_trait]
+impl JobHandler for RepoFetchScheduler {
+    async fn run(&self, _payload: Value) -> anyhow::Result<()> {
+        let config = self.config.read().await.fetch.clone();
+        if config.enabled {
+            self.fetch_due(&config).await?;
+        }
+        Ok(())
+    }
+}
+
+impl RepoFetchScheduler {
+    pub fn new(db: DBService, repo_service: RepoService, config: Arc<RwLock<Config>>) -> Self {
+        Self {
+            db,
+            repo_service,
+            config,
+            git: GitCli::new(),
+        }
+    }
+
+    /// Fetch every repository that is due. Failed fetches are recorded on the repository and do
+    /// not fail the job; only failing to load the repositories does.
+    async fn fetch_due(&self, config: &FetchConfig) -> Result<(), sqlx::Error> {
+        let pool = &self.db.pool;
+        let repos = Repo::find_all(pool).await?;
+        let states = RepoFetchState::find_all(pool).await?;
+        for repo in repos {
+            let state = states.iter().find(|state| state.repo_id == repo.id);
+            if !is_due(state, config, Utc::now()) {
+                continue;
+            }
+            if let Err(e) = fetch_repo(pool, &self.repo_service, &self.git, &repo).await {
+                warn!("Failed to record fetch of {}: {}", repo.name, e);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn state(enabled: bool, interval_minutes: Option<i64>, minutes_ago: i64) -> RepoFetchState {
+        let now = Utc::now();
+        RepoFetchState {
+            repo_id: Uuid::new_v4(),
+            enabled,
+            interval_minutes,
+            last_attempt_at: Some(now - chrono::Duration::minutes(minutes_ago)),
+            last_success_at: None,
+            last_error: None,
+            consecutive_failures: 0,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn never_fetched_repositories_are_due() {
+        assert!(is_due(None, &FetchConfig::default(), Utc::now()));
+    }
+
+    #[test]
+    fn uses_the_repository_interval_over_the_global_one() {
+        let config = FetchConfig::default();
+        assert!(!is_due(Some(&state(true, None, 5)), &config, Utc::now()));
+        assert!(is_due(Some(&state(true, None, 11)), &config, Utc::now()));
+        assert!(is_due(Some(&state(true, Some(2), 5)), &config, Utc::now()));
+        assert!(!is_due(
+            Some(&state(true, Some(60), 30)),
+            &config,
+            Utc::now()
+        ));
+    }
+
+    #[test]
+    fn disabled_repositories_are_never_due() {
+        assert!(!is_due(
+            Some(&state(false, None, 600)),
+            &FetchConfig::default(),
+            Utc::now()
+        ));
+    }
+}
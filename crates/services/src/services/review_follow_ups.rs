@@ -0,0 +1,201 @@
+//! Follow-ups from new pull request review comments.
+//!
+//! Workspaces can opt into having the comments reviewers post on their open pull requests sent to
+//! the coding agent. The recurring [`REVIEW_FOLLOW_UP_JOB`] fetches the comments of each such pull
+//! request and compares them with the ones seen before. Comments that arrived since, written by
+//! someone other than the user or a bot, are gathered into one follow-up prompt, so a review with
+//! several comments leads to a single agent turn. Like comment triggers, only comments by the
+//! authors [`comment_triggers::login_allowed`] accepts are sent, by default the repository's
+//! owners, organization members and collaborators.
+
+use std::{collections::HashSet, time::Duration};
+
+use chrono::{DateTime, Utc};
+
+use crate::services::{
+    comment_triggers::{self, CommentTriggerConfig},
+    git_provider::UnifiedComment,
+};
+
+/// Recurring job that turns new review comments into follow-ups
+pub const REVIEW_FOLLOW_UP_JOB: &str = "review_follow_ups.check_open_prs";
+
+/// How often the comments of opted-in pull requests are checked
+pub const REVIEW_FOLLOW_UP_INTERVAL: Duration = Duration::from_secs(120);
+
+/// Comments to follow up on: posted after `since`, not in `seen`, written by an author `triggers`
+/// allows, and neither by a bot nor by `own_username`, whose comments include the agent's own
+/// replies
+pub fn new_comments<'a>(
+    comments: &'a [UnifiedComment],
+    seen: &HashSet<String>,
+    since: DateTime<Utc>,
+    triggers: &CommentTriggerConfig,
+    own_username: Option<&str>,
+) -> Vec<&'a UnifiedComment> {
+    comments
+        .iter()
+        .filter(|comment| comment.created_at() > since && !seen.contains(&comment.key()))
+        .filter(|comment| {
+            !comment.is_from_bot()
+                && !own_username.is_some_and(|own| own.eq_ignore_ascii_case(comment.author()))
+        })
+        .filter(|comment| {
+            comment_triggers::login_allowed(
+                triggers,
+                comment.author(),
+                Some(comment.author_association()),
+            )
+        })
+        .collect()
+}
+
+fn quote(body: &str) -> String {
+    body.lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Prompt asking the agent to address the new comments, oldest first
+pub fn follow_up_prompt(pr_url: &str, comments: &[&UnifiedComment]) -> String {
+    let mut comments = comments.to_vec();
+    comments.sort_by_key(|comment| comment.created_at());
+
+    let mut prompt = format!(
+        "Reviewers left {} new comment{} on the pull request {pr_url}.\n",
+        comments.len(),
+        if comments.len() == 1 { "" } else { "s" }
+    );
+    for (index, comment) in comments.iter().enumerate() {
+        prompt.push_str(&format!("\n## Comment {}\n\n", index + 1));
+        match comment {
+            UnifiedComment::General {
+                author, body, url, ..
+            } => {
+                prompt.push_str(&format!(
+                    "@{author} commented ({url}):\n\n{}\n",
+                    quote(body)
+                ));
+            }
+            UnifiedComment::Review {
+                author,
+                body,
+                url,
+                path,
+                start_line,
+                line,
+                diff_hunk,
+                ..
+            } => {
+                let location = match (start_line, line) {
+                    (Some(start), Some(end)) => format!("`{path}`, lines {start}-{end}"),
+                    (None, Some(line)) => format!("`{path}`, line {line}"),
+                    _ => format!("`{path}`"),
+                };
+                prompt.push_str(&format!(
+                    "@{author} commented on {location} ({url}):\n\n{}\n",
+                    quote(body)
+                ));
+                if !diff_hunk.is_empty() {
+                    prompt.push_str(&format!("\n```diff\n{diff_hunk}\n```\n"));
+                }
+            }
+        }
+    }
+    prompt.push_str(
+        "\nAddress each comment with changes in this workspace. If a comment asks a question or \
+         you disagree with it, explain why instead of changing code.",
+    );
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 2, 6, 9, minute, 0).unwrap()
+    }
+
+    fn general(id: &str, author: &str, minute: u32) -> UnifiedComment {
+        general_as(id, author, "MEMBER", minute)
+    }
+
+    fn general_as(id: &str, author: &str, association: &str, minute: u32) -> UnifiedComment {
+        UnifiedComment::General {
+            id: id.to_string(),
+            author: author.to_string(),
+            author_association: association.to_string(),
+            body: "Please add a changelog entry".to_string(),
+            created_at: at(minute),
+            url: format!("https://github.com/acme/app/pull/7#issuecomment-{id}"),
+        }
+    }
+
+    fn review(id: i64, author: &str, minute: u32) -> UnifiedComment {
+        UnifiedComment::Review {
+            id,
+            author: author.to_string(),
+            author_association: "MEMBER".to_string(),
+            body: "Off by one here\nShould be `<=`".to_string(),
+            created_at: at(minute),
+            url: format!("https://github.com/acme/app/pull/7#discussion_r{id}"),
+            path: "src/lib.rs".to_string(),
+            start_line: None,
+            line: Some(42),
+            diff_hunk: "@@ -40,3 +40,3 @@".to_string(),
+        }
+    }
+
+    #[test]
+    fn only_unseen_comments_from_reviewers_are_new() {
+        let comments = vec![
+            general("1", "sam", 5),
+            review(2, "sam", 20),
+            review(3, "sam", 25),
+            general("4", "dependabot[bot]", 30),
+            general("5", "Me", 35),
+            general("6", "kim", 40),
+            general_as("7", "drive-by", "NONE", 45),
+        ];
+        let seen = HashSet::from(["review:3".to_string()]);
+        let triggers = CommentTriggerConfig::default();
+        let new: Vec<String> = new_comments(&comments, &seen, at(10), &triggers, Some("me"))
+            .into_iter()
+            .map(UnifiedComment::key)
+            .collect();
+        assert_eq!(new, ["review:2", "general:6"]);
+    }
+
+    #[test]
+    fn comments_by_listed_authors_are_new_whatever_their_association() {
+        let comments = vec![general_as("1", "drive-by", "CONTRIBUTOR", 20)];
+        let mut triggers = CommentTriggerConfig::default();
+        assert!(new_comments(&comments, &HashSet::new(), at(10), &triggers, None).is_empty());
+
+        triggers.allowed_authors = vec!["Drive-By".to_string()];
+        assert_eq!(
+            new_comments(&comments, &HashSet::new(), at(10), &triggers, None).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn prompt_lists_comments_oldest_first_with_their_location() {
+        let (late, early) = (general("6", "kim", 40), review(2, "sam", 20));
+        let prompt = follow_up_prompt("https://github.com/acme/app/pull/7", &[&late, &early]);
+        assert!(prompt.starts_with(
+            "Reviewers left 2 new comments on the pull request https://github.com/acme/app/pull/7."
+        ));
+        let sam = prompt
+            .find("@sam commented on `src/lib.rs`, line 42")
+            .unwrap();
+        let kim = prompt.find("@kim commented").unwrap();
+        assert!(sam < kim);
+        assert!(prompt.contains("> Off by one here\n> Should be `<=`"));
+        assert!(prompt.contains("```diff\n@@ -40,3 +40,3 @@\n```"));
+    }
+}
@@ -0,0 +1,309 @@
+//! Cost-aware model routing.
+//!
+//! Before a coding agent turn starts, the configured rules are checked in order and the first one
+//! that matches picks the executor profile for the turn. Rules can match on what the turn is for,
+//! a rough size estimate of the prompt, how many turns in a row have failed and how much of the
+//! attempt budget is left. Costs are per-turn estimates from the config, not billed amounts.
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    routing_decision::{TaskSize, TurnPurpose},
+};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Prompts up to this many characters are small tasks
+const SMALL_PROMPT_CHARS: usize = 600;
+/// Prompts up to this many characters are medium tasks; anything longer is large
+const MEDIUM_PROMPT_CHARS: usize = 3000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Default)]
+#[serde(default)]
+pub struct RoutingConfig {
+    pub enabled: bool,
+    /// Checked in order; the first matching rule picks the profile
+    pub rules: Vec<RoutingRule>,
+    /// Estimated cost of one turn per profile
+    pub profile_costs: Vec<ProfileCost>,
+    /// Estimated spend allowed per attempt; None for no budget
+    pub attempt_budget: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct RoutingRule {
+    pub name: String,
+    /// Turn purposes the rule applies to; empty matches any
+    #[serde(default)]
+    pub purposes: Vec<TurnPurpose>,
+    /// Task sizes the rule applies to; empty matches any
+    #[serde(default)]
+    pub task_sizes: Vec<TaskSize>,
+    /// Only match after at least this many consecutive failed turns
+    #[serde(default)]
+    pub min_previous_failures: Option<u32>,
+    /// Only match when no more than this much budget is left
+    #[serde(default)]
+    pub max_budget_remaining: Option<f64>,
+    pub executor_profile_id: ExecutorProfileId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct ProfileCost {
+    /// A cost without a variant applies to every variant of the executor
+    pub executor_profile_id: ExecutorProfileId,
+    pub cost_per_turn: f64,
+}
+
+/// What a turn looks like when it is routed
+#[derive(Debug, Clone)]
+pub struct RoutingInput {
+    pub purpose: TurnPurpose,
+    pub task_size: TaskSize,
+    pub previous_failures: u32,
+    pub budget_remaining: Option<f64>,
+    /// Resuming an agent session pins the turn to the requested executor
+    pub resumes_session: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub profile: ExecutorProfileId,
+    pub rule_name: Option<String>,
+    pub reason: String,
+}
+
+pub fn estimate_task_size(prompt: &str) -> TaskSize {
+    match prompt.chars().count() {
+        n if n <= SMALL_PROMPT_CHARS => TaskSize::Small,
+        n if n <= MEDIUM_PROMPT_CHARS => TaskSize::Medium,
+        _ => TaskSize::Large,
+    }
+}
+
+/// Failed coding agent turns at the end of a session's history, given oldest first
+pub fn consecutive_failures(processes: &[ExecutionProcess]) -> u32 {
+    processes
+        .iter()
+        .rev()
+        .filter(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent)
+        .filter(|process| process.status != ExecutionProcessStatus::Running)
+        .take_while(|process| process.status == ExecutionProcessStatus::Failed)
+        .count() as u32
+}
+
+impl RoutingConfig {
+    pub fn cost_of(&self, profile: &ExecutorProfileId) -> f64 {
+        let exact = self
+            .profile_costs
+            .iter()
+            .find(|cost| &cost.executor_profile_id == profile);
+        let executor_wide = || {
+            self.profile_costs.iter().find(|cost| {
+                cost.executor_profile_id.executor == profile.executor
+                    && cost.executor_profile_id.variant.is_none()
+            })
+        };
+        exact
+            .or_else(executor_wide)
+            .map_or(0.0, |cost| cost.cost_per_turn)
+    }
+
+    /// Pick the profile for a turn. Rules naming a profile that `available` rejects are skipped,
+    /// as are rules that would switch executor on a resumed session.
+    pub fn route(
+        &self,
+        requested: &ExecutorProfileId,
+        input: &RoutingInput,
+        available: impl Fn(&ExecutorProfileId) -> bool,
+    ) -> Route {
+        let matched = self.rules.iter().find(|rule| {
+            rule.matches(input)
+                && (!input.resumes_session
+                    || rule.executor_profile_id.executor == requested.executor)
+                && available(&rule.executor_profile_id)
+        });
+        if let Some(rule) = matched {
+            return Route {
+                profile: rule.executor_profile_id.clone(),
+                rule_name: Some(rule.name.clone()),
+                reason: rule.describe(input),
+            };
+        }
+
+        if let Some(remaining) = input.budget_remaining
+            && remaining <= 0.0
+        {
+            return Route {
+                profile: requested.clone(),
+                rule_name: None,
+                reason: format!(
+                    "Attempt budget exhausted ({remaining:.2} left); no rule picked a cheaper profile"
+                ),
+            };
+        }
+        Route {
+            profile: requested.clone(),
+            rule_name: None,
+            reason: "No routing rule matched; using the requested profile".to_string(),
+        }
+    }
+}
+
+impl RoutingRule {
+    fn matches(&self, input: &RoutingInput) -> bool {
+        (self.purposes.is_empty() || self.purposes.contains(&input.purpose))
+            && (self.task_sizes.is_empty() || self.task_sizes.contains(&input.task_size))
+            && self
+                .min_previous_failures
+                .is_none_or(|min| input.previous_failures >= min)
+            && self.max_budget_remaining.is_none_or(|max| {
+                input
+                    .budget_remaining
+                    .is_some_and(|remaining| remaining <= max)
+            })
+    }
+
+    fn describe(&self, input: &RoutingInput) -> String {
+        let mut conditions = Vec::new();
+        if !self.purposes.is_empty() {
+            conditions.push(format!("purpose {:?}", input.purpose));
+        }
+        if !self.task_sizes.is_empty() {
+            conditions.push(format!("task size {:?}", input.task_size));
+        }
+        if self.min_previous_failures.is_some() {
+            conditions.push(format!("{} previous failures", input.previous_failures));
+        }
+        if let Some(remaining) = input
+            .budget_remaining
+            .filter(|_| self.max_budget_remaining.is_some())
+        {
+            conditions.push(format!("{remaining:.2} budget left"));
+        }
+        if conditions.is_empty() {
+            format!("Rule '{}' matches every turn", self.name)
+        } else {
+            format!("Rule '{}' matched on {}", self.name, conditions.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+
+    fn profile(executor: BaseCodingAgent, variant: Option<&str>) -> ExecutorProfileId {
+        ExecutorProfileId {
+            executor,
+            variant: variant.map(str::to_string),
+        }
+    }
+
+    fn input(purpose: TurnPurpose) -> RoutingInput {
+        RoutingInput {
+            purpose,
+            task_size: TaskSize::Medium,
+            previous_failures: 0,
+            budget_remaining: None,
+            resumes_session: false,
+        }
+    }
+
+    fn config() -> RoutingConfig {
+        RoutingConfig {
+            enabled: true,
+            rules: vec![
+                RoutingRule {
+                    name: "cheap descriptions".to_string(),
+                    purposes: vec![TurnPurpose::PrDescription],
+                    task_sizes: vec![],
+                    min_previous_failures: None,
+                    max_budget_remaining: None,
+                    executor_profile_id: profile(BaseCodingAgent::ClaudeCode, Some("HAIKU")),
+                },
+                RoutingRule {
+                    name: "escalate".to_string(),
+                    purposes: vec![],
+                    task_sizes: vec![],
+                    min_previous_failures: Some(2),
+                    max_budget_remaining: None,
+                    executor_profile_id: profile(BaseCodingAgent::Codex, None),
+                },
+            ],
+            profile_costs: vec![
+                ProfileCost {
+                    executor_profile_id: profile(BaseCodingAgent::ClaudeCode, None),
+                    cost_per_turn: 1.0,
+                },
+                ProfileCost {
+                    executor_profile_id: profile(BaseCodingAgent::ClaudeCode, Some("HAIKU")),
+                    cost_per_turn: 0.1,
+                },
+            ],
+            attempt_budget: Some(10.0),
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let requested = profile(BaseCodingAgent::ClaudeCode, None);
+        let route = config().route(&requested, &input(TurnPurpose::PrDescription), |_| true);
+        assert_eq!(route.rule_name.as_deref(), Some("cheap descriptions"));
+        assert_eq!(route.profile.variant.as_deref(), Some("HAIKU"));
+
+        let route = config().route(&requested, &input(TurnPurpose::Initial), |_| true);
+        assert_eq!(route.rule_name, None);
+        assert_eq!(route.profile, requested);
+    }
+
+    #[test]
+    fn resumed_sessions_keep_their_executor() {
+        let requested = profile(BaseCodingAgent::ClaudeCode, None);
+        let failing = RoutingInput {
+            previous_failures: 3,
+            ..input(TurnPurpose::FollowUp)
+        };
+        let route = config().route(&requested, &failing, |_| true);
+        assert_eq!(route.rule_name.as_deref(), Some("escalate"));
+
+        let resumed = RoutingInput {
+            resumes_session: true,
+            ..failing
+        };
+        let route = config().route(&requested, &resumed, |_| true);
+        assert_eq!(route.profile, requested);
+    }
+
+    #[test]
+    fn unavailable_profiles_are_skipped() {
+        let requested = profile(BaseCodingAgent::ClaudeCode, None);
+        let route = config().route(&requested, &input(TurnPurpose::PrDescription), |p| {
+            p.variant.is_none()
+        });
+        assert_eq!(route.profile, requested);
+    }
+
+    #[test]
+    fn costs_fall_back_to_the_executor() {
+        let config = config();
+        assert_eq!(
+            config.cost_of(&profile(BaseCodingAgent::ClaudeCode, Some("HAIKU"))),
+            0.1
+        );
+        assert_eq!(
+            config.cost_of(&profile(BaseCodingAgent::ClaudeCode, Some("OPUS"))),
+            1.0
+        );
+        assert_eq!(config.cost_of(&profile(BaseCodingAgent::Codex, None)), 0.0);
+    }
+
+    #[test]
+    fn estimates_task_size_from_prompt_length() {
+        assert_eq!(estimate_task_size("Fix the typo"), TaskSize::Small);
+        assert_eq!(estimate_task_size(&"x".repeat(2000)), TaskSize::Medium);
+        assert_eq!(estimate_task_size(&"x".repeat(5000)), TaskSize::Large);
+    }
+}
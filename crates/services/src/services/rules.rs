@@ -0,0 +1,474 @@
+//! Automation rules evaluated against the board event stream.
+//!
+//! Each rule pairs a condition written in the [`expr`] language with a single [`RuleAction`].
+//! Rules are edge-triggered: an action runs when the condition for a task goes from not
+//! matching to matching, so a rule that moves a card does not fire again on the update it
+//! caused.
+//!
+//! Transitions reuse the same actions as named sequences that are applied on request instead of
+//! on events, e.g. "start review round" marking a task's PR ready, requesting reviewers and
+//! moving the card in one call.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use db::{
+    DBService,
+    models::{
+        automation_rule::{AutomationRule, RuleAction},
+        automation_transition::AutomationTransition,
+        execution_process::ExecutionProcess,
+        task::Task,
+    },
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::sync::{Mutex, broadcast::error::RecvError};
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use crate::services::plugins::{
+    BoardEvent, CommentParams, MarkPrReadyParams, NotifyParams, PluginApi, PluginError,
+    RequestReviewersParams, SetStatusParams, StartTaskParams,
+};
+
+pub mod expr;
+
+pub use expr::{Expr, ExprError};
+
+/// Check that a condition parses, for validating rules before they are saved
+pub fn validate_condition(condition: &str) -> Result<(), ExprError> {
+    Expr::parse(condition).map(|_| ())
+}
+
+/// Build the evaluation context for an event. `task` is always the affected task (if any), and
+/// the entity that changed is also exposed under its own name, e.g. `workspace.branch`.
+pub fn event_context(event: &BoardEvent, task: Option<&Value>) -> Value {
+    let mut ctx = json!({
+        "event": event.kind,
+        "task": task.cloned().unwrap_or(Value::Null),
+    });
+    if let Some((entity, _)) = event.kind.split_once('.')
+        && entity != "task"
+    {
+        ctx[entity] = event.data.clone().unwrap_or(Value::Null);
+    }
+    ctx
+}
+
+/// Run one action on a task on behalf of `actor`
+pub async fn run_action(
+    api: &dyn PluginApi,
+    actor: &str,
+    action: RuleAction,
+    task_id: Uuid,
+) -> Result<(), PluginError> {
+    match action {
+        RuleAction::Comment { body } => api
+            .comment(actor, CommentParams { task_id, body })
+            .await
+            .map(|_| ()),
+        RuleAction::SetStatus { status } => api
+            .set_status(actor, SetStatusParams { task_id, status })
+            .await
+            .map(|_| ()),
+        RuleAction::StartTask {
+            executor_profile_id,
+        } => api
+            .start_task(
+                actor,
+                StartTaskParams {
+                    task_id,
+                    executor_profile_id,
+                },
+            )
+            .await
+            .map(|_| ()),
+        RuleAction::MarkPrReady => {
+            api.mark_pr_ready(actor, MarkPrReadyParams { task_id })
+                .await
+        }
+        RuleAction::RequestReviewers { reviewers } => {
+            api.request_reviewers(actor, RequestReviewersParams { task_id, reviewers })
+                .await
+        }
+        RuleAction::Notify { message } => {
+            api.notify(actor, NotifyParams { task_id, message }).await
+        }
+    }
+}
+
+/// Whether an action works on the task's pull requests, so the task needs an open one
+pub fn acts_on_pr(action: &RuleAction) -> bool {
+    matches!(
+        action,
+        RuleAction::MarkPrReady | RuleAction::RequestReviewers { .. }
+    )
+}
+
+/// How far a transition got on one task
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TransitionOutcome {
+    pub task_id: Uuid,
+    /// Steps that ran, in order; the step after them failed if there is an error
+    pub completed_steps: usize,
+    pub error: Option<String>,
+}
+
+/// Apply a transition's steps in order to each task. A failing step stops the transition for
+/// that task; the steps before it are not undone, since provider changes like a requested review
+/// cannot be taken back.
+pub async fn apply_transition(
+    api: &dyn PluginApi,
+    transition: &AutomationTransition,
+    task_ids: &[Uuid],
+) -> Vec<TransitionOutcome> {
+    let actor = format!("transition:{}", transition.name);
+    let mut outcomes = Vec::with_capacity(task_ids.len());
+    for &task_id in task_ids {
+        let mut outcome = TransitionOutcome {
+            task_id,
+            completed_steps: 0,
+            error: None,
+        };
+        for step in transition.steps.0.iter().cloned() {
+            if let Err(e) = run_action(api, &actor, step, task_id).await {
+                tracing::warn!(
+                    "Transition '{}' stopped on task {} after {} steps: {}",
+                    transition.name,
+                    task_id,
+                    outcome.completed_steps,
+                    e
+                );
+                outcome.error = Some(e.to_string());
+                break;
+            }
+            outcome.completed_steps += 1;
+        }
+        outcomes.push(outcome);
+    }
+    outcomes
+}
+
+/// Enabled rules, loaded when first needed and dropped whenever a rule is created, updated or
+/// deleted, so board events don't each read the rules table. Cheap to clone.
+#[derive(Clone, Default)]
+pub struct RuleCache(Arc<Mutex<Option<Arc<Vec<AutomationRule>>>>>);
+
+impl RuleCache {
+    pub async fn enabled(&self, db: &DBService) -> Result<Arc<Vec<AutomationRule>>, sqlx::Error> {
+        // Held while loading, so an invalidation meanwhile waits and drops the loaded rules
+        let mut cached = self.0.lock().await;
+        if let Some(rules) = cached.as_ref() {
+            return Ok(rules.clone());
+        }
+        let rules = Arc::new(AutomationRule::find_enabled(&db.pool).await?);
+        *cached = Some(rules.clone());
+        Ok(rules)
+    }
+
+    /// Reload the rules on the next event, after they changed
+    pub async fn invalidate(&self) {
+        *self.0.lock().await = None;
+    }
+}
+
+pub struct RuleEngine {
+    db: DBService,
+    api: Arc<dyn PluginApi>,
+    rules: RuleCache,
+    /// (rule, task) pairs whose condition matched on the last evaluation
+    active: HashSet<(Uuid, Uuid)>,
+    /// Parsed conditions keyed by rule id and the source they were parsed from
+    compiled: HashMap<Uuid, (String, Result<Expr, String>)>,
+}
+
+impl RuleEngine {
+    pub fn spawn(
+        db: DBService,
+        events: Arc<MsgStore>,
+        api: Arc<dyn PluginApi>,
+        rules: RuleCache,
+    ) -> tokio::task::JoinHandle<()> {
+        let mut engine = Self {
+            db,
+            api,
+            rules,
+            active: HashSet::new(),
+            compiled: HashMap::new(),
+        };
+        tokio::spawn(async move {
+            let mut receiver = events.get_receiver();
+            loop {
+                match receiver.recv().await {
+                    Ok(LogMsg::JsonPatch(patch)) => {
+                        for event in patch.0.iter().filter_map(BoardEvent::from_patch_operation) {
+                            engine.handle_event(&event).await;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Automation rules missed {} events", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    async fn handle_event(&mut self, event: &BoardEvent) {
+        let rules = match self.rules.enabled(&self.db).await {
+            Ok(rules) => rules,
+            Err(e) => {
+                tracing::error!("Failed to load automation rules: {}", e);
+                return;
+            }
+        };
+        if rules.is_empty() {
+            return;
+        }
+
+        let Some((task_id, task)) = self.resolve_task(event).await else {
+            return;
+        };
+        if event.kind == "task.deleted" {
+            self.active.retain(|(_, t)| *t != task_id);
+            return;
+        }
+        let project_id = task
+            .get("project_id")
+            .and_then(Value::as_str)
+            .and_then(|s| Uuid::parse_str(s).ok());
+        let ctx = event_context(event, Some(&task));
+
+        for rule in rules.iter() {
+            if rule.project_id.is_some() && rule.project_id != project_id {
+                continue;
+            }
+            let matched = self
+                .compile(rule)
+                .and_then(|expr| expr.matches(&ctx).map_err(|e| e.to_string()));
+            let matched = match matched {
+                Ok(matched) => matched,
+                Err(e) => {
+                    tracing::warn!("Automation rule '{}' failed: {}", rule.name, e);
+                    continue;
+                }
+            };
+
+            let key = (rule.id, task_id);
+            if !matched {
+                self.active.remove(&key);
+                continue;
+            }
+            if !self.active.insert(key) {
+                continue;
+            }
+
+            tracing::info!(
+                "Automation rule '{}' matched {} for task {}",
+                rule.name,
+                event.kind,
+                task_id
+            );
+            if let Err(e) = self.run_action(rule, task_id).await {
+                tracing::error!("Automation rule '{}' action failed: {}", rule.name, e);
+            }
+        }
+    }
+
+    fn compile(&mut self, rule: &AutomationRule) -> Result<&Expr, String> {
+        let stale = self
+            .compiled
+            .get(&rule.id)
+            .is_none_or(|(source, _)| *source != rule.condition);
+        if stale {
+            let parsed = Expr::parse(&rule.condition).map_err(|e| e.to_string());
+            self.compiled
+                .insert(rule.id, (rule.condition.clone(), parsed));
+        }
+        self.compiled[&rule.id].1.as_ref().map_err(Clone::clone)
+    }
+
+    /// Find the task an event belongs to, preferring the event payload over a database read
+    async fn resolve_task(&self, event: &BoardEvent) -> Option<(Uuid, Value)> {
+        let pool = &self.db.pool;
+        let task_id = match event.kind.split_once('.')?.0 {
+            "task" => {
+                return Some((
+                    event.id,
+                    event
+                        .data
+                        .clone()
+                        .unwrap_or_else(|| json!({ "id": event.id })),
+                ));
+            }
+            "workspace" => event
+                .data
+                .as_ref()?
+                .get("task_id")?
+                .as_str()
+                .and_then(|s| Uuid::parse_str(s).ok())?,
+            "execution_process" => {
+                ExecutionProcess::load_context(pool, event.id)
+                    .await
+                    .ok()?
+                    .task
+                    .id
+            }
+            _ => return None,
+        };
+
+        let task = Task::find_by_id(pool, task_id).await.ok()??;
+        Some((task_id, serde_json::to_value(task).ok()?))
+    }
+
+    async fn run_action(&self, rule: &AutomationRule, task_id: Uuid) -> Result<(), String> {
+        let actor = format!("rule:{}", rule.name);
+        run_action(self.api.as_ref(), &actor, rule.action.0.clone(), task_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use db::models::task_comment::TaskComment;
+
+    use super::*;
+    use crate::services::plugins::CreateTaskParams;
+
+    /// Records the PR actions it is asked for; tasks in `without_pr` have no open PR
+    #[derive(Default)]
+    struct RecordingApi {
+        without_pr: Vec<Uuid>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl RecordingApi {
+        fn pr_action(&self, name: &str, task_id: Uuid) -> Result<(), PluginError> {
+            if self.without_pr.contains(&task_id) {
+                return Err(PluginError::Rejected(
+                    "Task has no open pull request".to_string(),
+                ));
+            }
+            self.calls.lock().unwrap().push(format!("{name} {task_id}"));
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl PluginApi for RecordingApi {
+        async fn create_task(&self, _: &str, _: CreateTaskParams) -> Result<Task, PluginError> {
+            unreachable!()
+        }
+
+        async fn comment(&self, _: &str, _: CommentParams) -> Result<TaskComment, PluginError> {
+            unreachable!()
+        }
+
+        async fn set_status(&self, _: &str, _: SetStatusParams) -> Result<Task, PluginError> {
+            unreachable!()
+        }
+
+        async fn start_task(
+            &self,
+            _: &str,
+            _: StartTaskParams,
+        ) -> Result<ExecutionProcess, PluginError> {
+            unreachable!()
+        }
+
+        async fn mark_pr_ready(
+            &self,
+            _: &str,
+            params: MarkPrReadyParams,
+        ) -> Result<(), PluginError> {
+            self.pr_action("ready", params.task_id)
+        }
+
+        async fn request_reviewers(
+            &self,
+            _: &str,
+            params: RequestReviewersParams,
+        ) -> Result<(), PluginError> {
+            self.pr_action("review", params.task_id)
+        }
+
+        async fn notify(&self, _: &str, params: NotifyParams) -> Result<(), PluginError> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("notify {}", params.task_id));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn transitions_stop_at_the_first_failing_step_of_each_task() {
+        let (ready, draftless) = (Uuid::new_v4(), Uuid::new_v4());
+        let api = RecordingApi {
+            without_pr: vec![draftless],
+            ..Default::default()
+        };
+        let transition = AutomationTransition {
+            id: Uuid::new_v4(),
+            project_id: None,
+            name: "start review round".to_string(),
+            steps: sqlx::types::Json(vec![
+                RuleAction::Notify {
+                    message: "Review round started".to_string(),
+                },
+                RuleAction::MarkPrReady,
+                RuleAction::RequestReviewers {
+                    reviewers: vec!["sam".to_string()],
+                },
+            ]),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let outcomes = apply_transition(&api, &transition, &[ready, draftless]).await;
+        assert_eq!(outcomes[0].completed_steps, 3);
+        assert!(outcomes[0].error.is_none());
+        assert_eq!(outcomes[1].completed_steps, 1);
+        assert_eq!(
+            outcomes[1].error.as_deref(),
+            Some("Task has no open pull request")
+        );
+        assert_eq!(
+            *api.calls.lock().unwrap(),
+            [
+                format!("notify {ready}"),
+                format!("ready {ready}"),
+                format!("review {ready}"),
+                format!("notify {draftless}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn context_exposes_task_and_changed_entity() {
+        let task_id = Uuid::new_v4();
+        let event = BoardEvent {
+            kind: "workspace.updated".to_string(),
+            id: Uuid::new_v4(),
+            data: Some(json!({ "branch": "vk/fix", "task_id": task_id })),
+        };
+        let task = json!({ "id": task_id, "status": "inprogress" });
+        let ctx = event_context(&event, Some(&task));
+
+        let expr = Expr::parse(
+            r#"event == "workspace.updated" and task.status == "inprogress" and starts_with(workspace.branch, "vk/")"#,
+        )
+        .unwrap();
+        assert!(expr.matches(&ctx).unwrap());
+    }
+}
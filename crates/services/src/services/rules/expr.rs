@@ -0,0 +1,638 @@
+//! A deliberately small expression language for automation rule conditions.
+//!
+//! ```text
+//! task.status == "inreview" and not task.last_attempt_failed
+//! event == "task.updated" and starts_with(lower(task.title), "hotfix")
+//! task.status in ["todo", "inprogress"]
+//! ```
+//!
+//! Expressions are evaluated against a JSON context. There are no loops, assignments or side
+//! effects, and both source length and nesting depth are capped, so evaluation is always cheap
+//! and terminates.
+
+use std::fmt;
+
+use serde_json::Value;
+use thiserror::Error;
+
+const MAX_SOURCE_LEN: usize = 2048;
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ExprError {
+    #[error("Parse error at {pos}: {message}")]
+    Parse { pos: usize, message: String },
+    #[error("Evaluation error: {0}")]
+    Eval(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Str(String),
+    Ident(String),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Dot,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+    Not,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Num(n) => write!(f, "{n}"),
+            Token::Str(s) => write!(f, "\"{s}\""),
+            Token::Ident(s) => write!(f, "{s}"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::LBracket => write!(f, "["),
+            Token::RBracket => write!(f, "]"),
+            Token::Comma => write!(f, ","),
+            Token::Dot => write!(f, "."),
+            Token::Eq => write!(f, "=="),
+            Token::Ne => write!(f, "!="),
+            Token::Lt => write!(f, "<"),
+            Token::Le => write!(f, "<="),
+            Token::Gt => write!(f, ">"),
+            Token::Ge => write!(f, ">="),
+            Token::And => write!(f, "and"),
+            Token::Or => write!(f, "or"),
+            Token::Not => write!(f, "not"),
+        }
+    }
+}
+
+fn tokenize(src: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let err = |pos: usize, message: &str| ExprError::Parse {
+        pos,
+        message: message.to_string(),
+    };
+
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => tokens.push((start, Token::LParen)),
+            ')' => tokens.push((start, Token::RParen)),
+            '[' => tokens.push((start, Token::LBracket)),
+            ']' => tokens.push((start, Token::RBracket)),
+            ',' => tokens.push((start, Token::Comma)),
+            '.' => tokens.push((start, Token::Dot)),
+            '=' | '!' | '<' | '>' | '&' | '|' => {
+                let next = chars.get(i + 1).copied();
+                let (token, width) = match (c, next) {
+                    ('=', Some('=')) => (Token::Eq, 2),
+                    ('!', Some('=')) => (Token::Ne, 2),
+                    ('<', Some('=')) => (Token::Le, 2),
+                    ('>', Some('=')) => (Token::Ge, 2),
+                    ('&', Some('&')) => (Token::And, 2),
+                    ('|', Some('|')) => (Token::Or, 2),
+                    ('!', _) => (Token::Not, 1),
+                    ('<', _) => (Token::Lt, 1),
+                    ('>', _) => (Token::Gt, 1),
+                    _ => return Err(err(start, &format!("unexpected '{c}'"))),
+                };
+                tokens.push((start, token));
+                i += width;
+                continue;
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(err(start, "unterminated string")),
+                        Some('\\') => {
+                            match chars.get(i + 1) {
+                                Some('n') => value.push('\n'),
+                                Some('t') => value.push('\t'),
+                                Some(other) => value.push(*other),
+                                None => return Err(err(start, "unterminated string")),
+                            }
+                            i += 2;
+                        }
+                        Some(ch) if *ch == quote => break,
+                        Some(ch) => {
+                            value.push(*ch);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push((start, Token::Str(value)));
+            }
+            c if c.is_ascii_digit() => {
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let raw: String = chars[start..i].iter().collect();
+                let num = raw
+                    .parse::<f64>()
+                    .map_err(|_| err(start, &format!("invalid number '{raw}'")))?;
+                tokens.push((start, Token::Num(num)));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                let token = match word.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Ident(word),
+                };
+                tokens.push((start, token));
+                continue;
+            }
+            other => return Err(err(start, &format!("unexpected '{other}'"))),
+        }
+        i += 1;
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    In,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Literal(Value),
+    Path(Vec<String>),
+    List(Vec<Node>),
+    Call(String, Vec<Node>),
+    Not(Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Cmp(CmpOp, Box<Node>, Box<Node>),
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    src_len: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|(p, _)| *p)
+            .unwrap_or(self.src_len)
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ExprError> {
+        Err(ExprError::Parse {
+            pos: self.offset(),
+            message: message.into(),
+        })
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(_, t)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ExprError> {
+        match self.peek() {
+            Some(t) if *t == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(t) => self.error(format!("expected '{expected}', found '{t}'")),
+            None => self.error(format!("expected '{expected}'")),
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), ExprError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return self.error("expression is nested too deeply");
+        }
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> Result<Node, ExprError> {
+        let mut lhs = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let rhs = self.parse_and()?;
+            lhs = Node::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Node, ExprError> {
+        let mut lhs = self.parse_not()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            let rhs = self.parse_not()?;
+            lhs = Node::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_not(&mut self) -> Result<Node, ExprError> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            self.enter()?;
+            let inner = self.parse_not()?;
+            self.depth -= 1;
+            return Ok(Node::Not(Box::new(inner)));
+        }
+        self.parse_cmp()
+    }
+
+    fn parse_cmp(&mut self) -> Result<Node, ExprError> {
+        let lhs = self.parse_primary()?;
+        let op = match self.peek() {
+            Some(Token::Eq) => CmpOp::Eq,
+            Some(Token::Ne) => CmpOp::Ne,
+            Some(Token::Lt) => CmpOp::Lt,
+            Some(Token::Le) => CmpOp::Le,
+            Some(Token::Gt) => CmpOp::Gt,
+            Some(Token::Ge) => CmpOp::Ge,
+            Some(Token::Ident(word)) if word == "in" => CmpOp::In,
+            _ => return Ok(lhs),
+        };
+        self.pos += 1;
+        let rhs = self.parse_primary()?;
+        Ok(Node::Cmp(op, Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn parse_primary(&mut self) -> Result<Node, ExprError> {
+        self.enter()?;
+        let node = match self.next() {
+            Some(Token::Num(n)) => Node::Literal(
+                serde_json::Number::from_f64(n)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null),
+            ),
+            Some(Token::Str(s)) => Node::Literal(Value::String(s)),
+            Some(Token::LParen) => {
+                let inner = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                inner
+            }
+            Some(Token::LBracket) => Node::List(self.parse_args(Token::RBracket)?),
+            Some(Token::Ident(word)) => match word.as_str() {
+                "true" => Node::Literal(Value::Bool(true)),
+                "false" => Node::Literal(Value::Bool(false)),
+                "null" => Node::Literal(Value::Null),
+                _ if self.peek() == Some(&Token::LParen) => {
+                    self.pos += 1;
+                    if !is_known_function(&word) {
+                        self.pos -= 2;
+                        return self.error(format!("unknown function '{word}'"));
+                    }
+                    Node::Call(word, self.parse_args(Token::RParen)?)
+                }
+                _ => {
+                    let mut path = vec![word];
+                    while self.peek() == Some(&Token::Dot) {
+                        self.pos += 1;
+                        match self.next() {
+                            Some(Token::Ident(segment)) => path.push(segment),
+                            _ => {
+                                self.pos -= 1;
+                                return self.error("expected field name after '.'");
+                            }
+                        }
+                    }
+                    Node::Path(path)
+                }
+            },
+            Some(other) => {
+                self.pos -= 1;
+                return self.error(format!("unexpected '{other}'"));
+            }
+            None => return self.error("unexpected end of expression"),
+        };
+        self.depth -= 1;
+        Ok(node)
+    }
+
+    fn parse_args(&mut self, close: Token) -> Result<Vec<Node>, ExprError> {
+        let mut args = Vec::new();
+        if self.peek() == Some(&close) {
+            self.pos += 1;
+            return Ok(args);
+        }
+        loop {
+            args.push(self.parse_or()?);
+            match self.peek() {
+                Some(Token::Comma) => self.pos += 1,
+                Some(t) if *t == close => {
+                    self.pos += 1;
+                    return Ok(args);
+                }
+                _ => return self.error(format!("expected ',' or '{close}'")),
+            }
+        }
+    }
+}
+
+const FUNCTIONS: &[&str] = &[
+    "lower",
+    "upper",
+    "len",
+    "contains",
+    "starts_with",
+    "ends_with",
+];
+
+fn is_known_function(name: &str) -> bool {
+    FUNCTIONS.contains(&name)
+}
+
+/// A parsed condition, ready to be evaluated any number of times
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    root: Node,
+}
+
+impl Expr {
+    pub fn parse(src: &str) -> Result<Self, ExprError> {
+        if src.len() > MAX_SOURCE_LEN {
+            return Err(ExprError::Parse {
+                pos: MAX_SOURCE_LEN,
+                message: format!("expression is longer than {MAX_SOURCE_LEN} characters"),
+            });
+        }
+        let mut parser = Parser {
+            tokens: tokenize(src)?,
+            pos: 0,
+            src_len: src.chars().count(),
+            depth: 0,
+        };
+        if parser.peek().is_none() {
+            return parser.error("expression is empty");
+        }
+        let root = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return parser.error(format!("unexpected '{token}'"));
+        }
+        Ok(Self { root })
+    }
+
+    /// Evaluate to a JSON value. Unknown fields resolve to `null` rather than failing, so a rule
+    /// written for task events simply does not match other events.
+    pub fn eval(&self, ctx: &Value) -> Result<Value, ExprError> {
+        eval(&self.root, ctx)
+    }
+
+    /// Evaluate and coerce the result to a boolean
+    pub fn matches(&self, ctx: &Value) -> Result<bool, ExprError> {
+        self.eval(ctx).map(|v| truthy(&v))
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+fn eval(node: &Node, ctx: &Value) -> Result<Value, ExprError> {
+    match node {
+        Node::Literal(v) => Ok(v.clone()),
+        Node::Path(path) => Ok(path
+            .iter()
+            .try_fold(ctx, |v, key| v.get(key))
+            .cloned()
+            .unwrap_or(Value::Null)),
+        Node::List(items) => items
+            .iter()
+            .map(|item| eval(item, ctx))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        Node::Not(inner) => Ok(Value::Bool(!truthy(&eval(inner, ctx)?))),
+        Node::And(lhs, rhs) => Ok(Value::Bool(
+            truthy(&eval(lhs, ctx)?) && truthy(&eval(rhs, ctx)?),
+        )),
+        Node::Or(lhs, rhs) => Ok(Value::Bool(
+            truthy(&eval(lhs, ctx)?) || truthy(&eval(rhs, ctx)?),
+        )),
+        Node::Cmp(op, lhs, rhs) => compare(*op, &eval(lhs, ctx)?, &eval(rhs, ctx)?),
+        Node::Call(name, args) => {
+            let args = args
+                .iter()
+                .map(|arg| eval(arg, ctx))
+                .collect::<Result<Vec<_>, _>>()?;
+            call(name, &args)
+        }
+    }
+}
+
+fn compare(op: CmpOp, lhs: &Value, rhs: &Value) -> Result<Value, ExprError> {
+    let result = match op {
+        CmpOp::Eq => lhs == rhs || numbers_equal(lhs, rhs),
+        CmpOp::Ne => !(lhs == rhs || numbers_equal(lhs, rhs)),
+        CmpOp::In => match rhs {
+            Value::Array(items) => items.iter().any(|item| item == lhs),
+            Value::String(haystack) => lhs.as_str().is_some_and(|n| haystack.contains(n)),
+            Value::Null => false,
+            _ => return Err(ExprError::Eval("'in' expects a list or string".to_string())),
+        },
+        CmpOp::Lt | CmpOp::Le | CmpOp::Gt | CmpOp::Ge => {
+            let ordering = match (lhs, rhs) {
+                (Value::Number(a), Value::Number(b)) => a
+                    .as_f64()
+                    .partial_cmp(&b.as_f64())
+                    .unwrap_or(std::cmp::Ordering::Equal),
+                (Value::String(a), Value::String(b)) => a.cmp(b),
+                (Value::Null, _) | (_, Value::Null) => return Ok(Value::Bool(false)),
+                _ => {
+                    return Err(ExprError::Eval(format!(
+                        "cannot order {} and {}",
+                        type_name(lhs),
+                        type_name(rhs)
+                    )));
+                }
+            };
+            match op {
+                CmpOp::Lt => ordering.is_lt(),
+                CmpOp::Le => ordering.is_le(),
+                CmpOp::Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            }
+        }
+    };
+    Ok(Value::Bool(result))
+}
+
+fn numbers_equal(lhs: &Value, rhs: &Value) -> bool {
+    matches!((lhs.as_f64(), rhs.as_f64()), (Some(a), Some(b)) if a == b)
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "list",
+        Value::Object(_) => "object",
+    }
+}
+
+fn call(name: &str, args: &[Value]) -> Result<Value, ExprError> {
+    let arity = |n: usize| {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(ExprError::Eval(format!(
+                "{name}() takes {n} argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+    let text = |v: &Value| -> String {
+        match v {
+            Value::String(s) => s.clone(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        }
+    };
+
+    match name {
+        "lower" => {
+            arity(1)?;
+            Ok(Value::String(text(&args[0]).to_lowercase()))
+        }
+        "upper" => {
+            arity(1)?;
+            Ok(Value::String(text(&args[0]).to_uppercase()))
+        }
+        "len" => {
+            arity(1)?;
+            let len = match &args[0] {
+                Value::String(s) => s.chars().count(),
+                Value::Array(a) => a.len(),
+                Value::Object(o) => o.len(),
+                _ => 0,
+            };
+            Ok(Value::from(len))
+        }
+        "contains" => {
+            arity(2)?;
+            compare(CmpOp::In, &args[1], &args[0])
+        }
+        "starts_with" => {
+            arity(2)?;
+            Ok(Value::Bool(text(&args[0]).starts_with(&text(&args[1]))))
+        }
+        "ends_with" => {
+            arity(2)?;
+            Ok(Value::Bool(text(&args[0]).ends_with(&text(&args[1]))))
+        }
+        _ => Err(ExprError::Eval(format!("unknown function '{name}'"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn ctx() -> Value {
+        json!({
+            "event": "task.updated",
+            "task": {
+                "title": "Hotfix: login loop",
+                "status": "inreview",
+                "last_attempt_failed": false,
+                "priority": 2,
+            }
+        })
+    }
+
+    fn check(src: &str) -> bool {
+        Expr::parse(src).unwrap().matches(&ctx()).unwrap()
+    }
+
+    #[test]
+    fn evaluates_comparisons_and_logic() {
+        assert!(check(
+            r#"task.status == "inreview" and not task.last_attempt_failed"#
+        ));
+        assert!(check(r#"event == 'task.created' || task.priority >= 2"#));
+        assert!(!check(r#"task.status != "inreview""#));
+        assert!(check(r#"task.status in ["todo", "inreview"]"#));
+        assert!(check(r#"starts_with(lower(task.title), "hotfix")"#));
+        assert!(check("(task.priority > 1) and len(task.title) > 5"));
+    }
+
+    #[test]
+    fn missing_fields_are_null() {
+        assert!(check("task.labels == null"));
+        assert!(!check(r#"contains(task.labels, "urgent")"#));
+        assert!(!check("workspace.branch > 'a'"));
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for src in [
+            "",
+            "task.status ==",
+            "foo(1)",
+            "(a == 1",
+            "a = 1",
+            "'unterminated",
+        ] {
+            assert!(
+                matches!(Expr::parse(src), Err(ExprError::Parse { .. })),
+                "{src}"
+            );
+        }
+        let deep = format!("{}true{}", "(".repeat(64), ")".repeat(64));
+        assert!(Expr::parse(&deep).is_err());
+    }
+
+    #[test]
+    fn type_mismatch_is_an_eval_error() {
+        let expr = Expr::parse("task.title < 3").unwrap();
+        assert!(matches!(expr.eval(&ctx()), Err(ExprError::Eval(_))));
+    }
+}
@@ -0,0 +1,240 @@
+//! Board views saved under a name.
+//!
+//! A view narrows the board of its project to the cards matching its filter, written in the
+//! automation rule [`expr`](crate::services::rules::expr) language against the card as `task`,
+//! and lays them out by its sort order and grouping. Views are evaluated on the board snapshot,
+//! so a view always shows the same badges as the board itself.
+
+use std::cmp::Ordering;
+
+use db::models::{
+    board::{BoardCard, BoardSnapshot},
+    saved_view::{SavedView, ViewGrouping, ViewSortField},
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::rules::expr::{Expr, ExprError};
+
+/// Cards of a view sharing the value they are grouped by
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ViewGroup {
+    /// The value, e.g. a task status or an executor; `None` for cards without one and for views
+    /// that don't group
+    pub key: Option<String>,
+    pub tasks: Vec<BoardCard>,
+}
+
+/// The board of a project as a saved view shows it
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ViewBoard {
+    pub view_id: Uuid,
+    pub project_id: Uuid,
+    pub groups: Vec<ViewGroup>,
+}
+
+/// Check a filter before saving it; an empty filter matches every card
+pub fn validate_filter(filter: &str) -> Result<(), ExprError> {
+    if filter.trim().is_empty() {
+        return Ok(());
+    }
+    Expr::parse(filter).map(|_| ())
+}
+
+/// Value as a group key, e.g. `"inreview"` for a task status
+fn key_of(value: impl Serialize) -> Option<String> {
+    match serde_json::to_value(value).ok()? {
+        Value::String(key) => Some(key),
+        _ => None,
+    }
+}
+
+fn compare(field: ViewSortField, a: &BoardCard, b: &BoardCard) -> Ordering {
+    match field {
+        ViewSortField::CreatedAt => a.created_at.cmp(&b.created_at),
+        ViewSortField::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+        ViewSortField::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+    }
+}
+
+/// Apply a view to the board of its project. Cards the filter can't be evaluated on, e.g. for
+/// comparing a missing pull request status, are left out.
+pub fn apply(view: &SavedView, board: BoardSnapshot) -> Result<ViewBoard, ExprError> {
+    let filter = match view.filter.trim() {
+        "" => None,
+        filter => Some(Expr::parse(filter)?),
+    };
+
+    // Status groups keep the board's columns, empty or not
+    let mut groups: Vec<ViewGroup> = match view.grouping {
+        ViewGrouping::Status => board
+            .columns
+            .iter()
+            .map(|column| ViewGroup {
+                key: key_of(&column.status),
+                tasks: Vec::new(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut cards: Vec<BoardCard> = board
+        .columns
+        .into_iter()
+        .flat_map(|column| column.tasks)
+        .filter(|card| {
+            filter.as_ref().is_none_or(|filter| {
+                let ctx = json!({ "task": card });
+                filter.matches(&ctx).unwrap_or(false)
+            })
+        })
+        .collect();
+    cards.sort_by(|a, b| {
+        let order = compare(view.sort_field, a, b);
+        if view.sort_descending {
+            order.reverse()
+        } else {
+            order
+        }
+    });
+
+    for card in cards {
+        let key = match view.grouping {
+            ViewGrouping::Status => key_of(&card.status),
+            ViewGrouping::Executor => card.executor.clone(),
+            ViewGrouping::PrStatus => card.pr_status.as_ref().and_then(key_of),
+            ViewGrouping::None => None,
+        };
+        match groups.iter_mut().find(|group| group.key == key) {
+            Some(group) => group.tasks.push(card),
+            None => groups.push(ViewGroup {
+                key,
+                tasks: vec![card],
+            }),
+        }
+    }
+    if !matches!(view.grouping, ViewGrouping::Status) {
+        // Named groups alphabetically, then the cards without a value
+        groups.sort_by(|a, b| match (&a.key, &b.key) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (a, b) => a.is_none().cmp(&b.is_none()),
+        });
+    }
+
+    Ok(ViewBoard {
+        view_id: view.id,
+        project_id: board.project_id,
+        groups,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use db::models::{board::BoardColumn, merge::MergeStatus, task::TaskStatus};
+
+    use super::*;
+
+    fn card(title: &str, status: TaskStatus, minute: u32) -> BoardCard {
+        let at = Utc.with_ymd_and_hms(2026, 2, 7, 10, minute, 0).unwrap();
+        BoardCard {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            status,
+            parent_workspace_id: None,
+            shared_task_id: None,
+            due_at: None,
+            created_at: at,
+            updated_at: at,
+            workspace_count: 1,
+            has_in_progress_attempt: false,
+            last_attempt_failed: false,
+            executor: None,
+            pr_status: None,
+            pr_number: None,
+            pr_url: None,
+        }
+    }
+
+    fn view(filter: &str, grouping: ViewGrouping) -> SavedView {
+        SavedView {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            owner_user_id: None,
+            name: "Mine".to_string(),
+            filter: filter.to_string(),
+            sort_field: ViewSortField::Title,
+            sort_descending: false,
+            grouping,
+            shared: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn board() -> BoardSnapshot {
+        let mut failing = card("Fix login", TaskStatus::InReview, 1);
+        failing.last_attempt_failed = true;
+        failing.executor = Some("CLAUDE_CODE".to_string());
+        failing.pr_status = Some(MergeStatus::Open);
+        let mut other = card("Add export", TaskStatus::InProgress, 2);
+        other.executor = Some("CODEX".to_string());
+        BoardSnapshot {
+            project_id: Uuid::new_v4(),
+            columns: vec![
+                BoardColumn {
+                    status: TaskStatus::Todo,
+                    tasks: vec![card("Write docs", TaskStatus::Todo, 3)],
+                },
+                BoardColumn {
+                    status: TaskStatus::InProgress,
+                    tasks: vec![other],
+                },
+                BoardColumn {
+                    status: TaskStatus::InReview,
+                    tasks: vec![failing],
+                },
+            ],
+        }
+    }
+
+    fn titles(group: &ViewGroup) -> Vec<&str> {
+        group.tasks.iter().map(|card| card.title.as_str()).collect()
+    }
+
+    #[test]
+    fn filters_cards_and_keeps_the_board_columns() {
+        let view = view(
+            r#"task.last_attempt_failed or task.status == "todo""#,
+            ViewGrouping::Status,
+        );
+        let board = apply(&view, board()).unwrap();
+        let keys: Vec<_> = board.groups.iter().map(|g| g.key.as_deref()).collect();
+        assert_eq!(keys, [Some("todo"), Some("inprogress"), Some("inreview")]);
+        assert_eq!(titles(&board.groups[0]), ["Write docs"]);
+        assert!(board.groups[1].tasks.is_empty());
+        assert_eq!(titles(&board.groups[2]), ["Fix login"]);
+    }
+
+    #[test]
+    fn groups_by_value_with_cards_lacking_one_last() {
+        let board = apply(&view("", ViewGrouping::Executor), board()).unwrap();
+        let keys: Vec<_> = board.groups.iter().map(|g| g.key.as_deref()).collect();
+        assert_eq!(keys, [Some("CLAUDE_CODE"), Some("CODEX"), None]);
+
+        let board = apply(&view("", ViewGrouping::None), board()).unwrap();
+        assert_eq!(
+            titles(&board.groups[0]),
+            ["Add export", "Fix login", "Write docs"]
+        );
+    }
+
+    #[test]
+    fn rejects_filters_that_do_not_parse() {
+        assert!(validate_filter("  ").is_ok());
+        assert!(validate_filter("task.status ==").is_err());
+        assert!(apply(&view("task.status ==", ViewGrouping::None), board()).is_err());
+    }
+}
@@ -0,0 +1,388 @@
+//! Due dates in the user's timezone.
+//!
+//! Due dates are stored in UTC, but the day a task is due on, whether it is due today and which
+//! sprint it falls in all depend on where the user is. Digests, calendar feeds and overdue counts
+//! therefore go through a [`Schedule`] built from the user's timezone and week start. Sprints are
+//! whole weeks counted from an anchor week, so their boundaries always fall on the week start.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use db::models::task::{Task, TaskStatus};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Sprints are counted from the week of this day when no anchor is configured
+const DEFAULT_SPRINT_ANCHOR: NaiveDate = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+const MAX_SPRINT_WEEKS: u32 = 8;
+
+fn default_sprint_weeks() -> u32 {
+    2
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+    Saturday,
+}
+
+impl WeekStart {
+    fn weekday(self) -> Weekday {
+        match self {
+            Self::Monday => Weekday::Mon,
+            Self::Sunday => Weekday::Sun,
+            Self::Saturday => Weekday::Sat,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    /// IANA timezone such as `Europe/Berlin`; the system timezone when unset
+    pub timezone: Option<String>,
+    pub week_start: WeekStart,
+    /// Sprint length in weeks
+    #[serde(default = "default_sprint_weeks")]
+    pub sprint_weeks: u32,
+    /// Any day of the first sprint; sprints start on the week start either way
+    pub sprint_anchor: Option<NaiveDate>,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            timezone: None,
+            week_start: WeekStart::default(),
+            sprint_weeks: default_sprint_weeks(),
+            sprint_anchor: None,
+        }
+    }
+}
+
+impl ScheduleConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(timezone) = &self.timezone {
+            parse_timezone(timezone)?;
+        }
+        if !(1..=MAX_SPRINT_WEEKS).contains(&self.sprint_weeks) {
+            return Err(format!(
+                "Sprints must be between 1 and {MAX_SPRINT_WEEKS} weeks long"
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.parse().map_err(|_| {
+        format!("Unknown timezone '{name}', expected an IANA name such as Europe/Berlin")
+    })
+}
+
+fn system_timezone() -> Option<Tz> {
+    iana_time_zone::get_timezone().ok()?.parse().ok()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct Sprint {
+    /// Sprints before the anchor have numbers below 1
+    pub number: i32,
+    /// First day of the sprint, in the user's timezone
+    pub starts_on: NaiveDate,
+    /// Last day of the sprint, in the user's timezone
+    pub ends_on: NaiveDate,
+    pub start: DateTime<Utc>,
+    /// First instant after the sprint
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct DueTask {
+    pub task_id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+    pub due_at: DateTime<Utc>,
+    /// The day the task is due, in the user's timezone
+    pub due_on: NaiveDate,
+    /// Whole days past the due day; 0 when overdue since earlier today
+    pub days_overdue: u32,
+}
+
+/// Open tasks by due date, as of today in the user's timezone
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct DueDigest {
+    pub date: NaiveDate,
+    pub timezone: String,
+    pub sprint: Sprint,
+    pub overdue: Vec<DueTask>,
+    /// Due later today
+    pub due_today: Vec<DueTask>,
+    /// Due after today but before the current sprint ends
+    pub due_this_sprint: Vec<DueTask>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    timezone: Tz,
+    week_start: Weekday,
+    sprint_weeks: u32,
+    /// First day of sprint 1
+    anchor: NaiveDate,
+}
+
+impl Schedule {
+    pub fn new(config: &ScheduleConfig) -> Self {
+        let timezone = config
+            .timezone
+            .as_deref()
+            .and_then(|name| parse_timezone(name).ok())
+            .or_else(system_timezone)
+            .unwrap_or(Tz::UTC);
+        let week_start = config.week_start.weekday();
+        let anchor = config.sprint_anchor.unwrap_or(DEFAULT_SPRINT_ANCHOR);
+        Self {
+            timezone,
+            week_start,
+            sprint_weeks: config.sprint_weeks.clamp(1, MAX_SPRINT_WEEKS),
+            anchor: anchor.week(week_start).first_day(),
+        }
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// The day `at` falls on in the user's timezone
+    pub fn local_date(&self, at: DateTime<Utc>) -> NaiveDate {
+        at.with_timezone(&self.timezone).date_naive()
+    }
+
+    /// The first instant of a day in the user's timezone
+    pub fn start_of_day(&self, date: NaiveDate) -> DateTime<Utc> {
+        let midnight = date.and_time(NaiveTime::MIN);
+        // Where clocks jump forward at midnight, the day starts once they have
+        (0..=24 * 4)
+            .map(|quarter| midnight + Duration::minutes(15 * quarter))
+            .find_map(|local| self.timezone.from_local_datetime(&local).earliest())
+            .map(|start| start.with_timezone(&Utc))
+            .unwrap_or_else(|| midnight.and_utc())
+    }
+
+    /// The sprint a day in the user's timezone belongs to
+    pub fn sprint(&self, date: NaiveDate) -> Sprint {
+        let length = 7 * i64::from(self.sprint_weeks);
+        let index = (date.week(self.week_start).first_day() - self.anchor)
+            .num_days()
+            .div_euclid(length);
+        let starts_on = self.anchor + Duration::days(index * length);
+        let ends_on = starts_on + Duration::days(length - 1);
+        Sprint {
+            number: i32::try_from(index + 1).unwrap_or(i32::MAX),
+            starts_on,
+            ends_on,
+            start: self.start_of_day(starts_on),
+            end: self.start_of_day(ends_on + Duration::days(1)),
+        }
+    }
+
+    /// Open tasks that are overdue or due before the current sprint ends
+    pub fn digest(&self, tasks: &[Task], now: DateTime<Utc>) -> DueDigest {
+        let today = self.local_date(now);
+        let sprint = self.sprint(today);
+        let mut digest = DueDigest {
+            date: today,
+            timezone: self.timezone.name().to_string(),
+            sprint,
+            overdue: Vec::new(),
+            due_today: Vec::new(),
+            due_this_sprint: Vec::new(),
+        };
+
+        let mut tasks: Vec<&Task> = tasks.iter().filter(|task| is_open(task)).collect();
+        tasks.sort_by_key(|task| task.due_at);
+        for task in tasks {
+            let Some(due_at) = task.due_at else {
+                continue;
+            };
+            let due_on = self.local_date(due_at);
+            let due = DueTask {
+                task_id: task.id,
+                title: task.title.clone(),
+                status: task.status.clone(),
+                due_at,
+                due_on,
+                days_overdue: u32::try_from((today - due_on).num_days()).unwrap_or(0),
+            };
+            if due_at <= now {
+                digest.overdue.push(due);
+            } else if due_on == today {
+                digest.due_today.push(due);
+            } else if due_on <= digest.sprint.ends_on {
+                digest.due_this_sprint.push(due);
+            }
+        }
+        digest
+    }
+}
+
+fn is_open(task: &Task) -> bool {
+    !matches!(task.status, TaskStatus::Done | TaskStatus::Cancelled)
+}
+
+/// Whether an open task is past its due date
+pub fn is_overdue(task: &Task, now: DateTime<Utc>) -> bool {
+    is_open(task) && task.due_at.is_some_and(|due_at| due_at <= now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(timezone: &str, week_start: WeekStart) -> Schedule {
+        Schedule::new(&ScheduleConfig {
+            timezone: Some(timezone.to_string()),
+            week_start,
+            sprint_weeks: 2,
+            sprint_anchor: Some(date(2025, 1, 6)),
+        })
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn task(title: &str, status: TaskStatus, due_at: Option<&str>) -> Task {
+        let created_at = utc("2025-01-01T00:00:00Z");
+        Task {
+            id: Uuid::new_v4(),
+            project_id: Uuid::nil(),
+            title: title.to_string(),
+            description: None,
+            status,
+            parent_workspace_id: None,
+            shared_task_id: None,
+            due_at: due_at.map(utc),
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    #[test]
+    fn local_date_follows_the_timezone() {
+        let at = utc("2025-03-10T23:30:00Z");
+        assert_eq!(
+            schedule("UTC", WeekStart::Monday).local_date(at),
+            date(2025, 3, 10)
+        );
+        assert_eq!(
+            schedule("Asia/Tokyo", WeekStart::Monday).local_date(at),
+            date(2025, 3, 11)
+        );
+        assert_eq!(
+            schedule("America/Los_Angeles", WeekStart::Monday).local_date(at),
+            date(2025, 3, 10)
+        );
+    }
+
+    #[test]
+    fn start_of_day_skips_a_dst_gap_at_midnight() {
+        // Santiago moved its clocks from midnight to 01:00 on 7 September 2025
+        let schedule = schedule("America/Santiago", WeekStart::Monday);
+        assert_eq!(
+            schedule.start_of_day(date(2025, 9, 7)),
+            utc("2025-09-07T04:00:00Z")
+        );
+        assert_eq!(
+            schedule.start_of_day(date(2025, 9, 8)),
+            utc("2025-09-08T03:00:00Z")
+        );
+    }
+
+    #[test]
+    fn sprints_start_on_the_week_start() {
+        let monday = schedule("UTC", WeekStart::Monday);
+        let sprint = monday.sprint(date(2025, 1, 22));
+        assert_eq!(sprint.number, 2);
+        assert_eq!(sprint.starts_on, date(2025, 1, 20));
+        assert_eq!(sprint.ends_on, date(2025, 2, 2));
+        assert_eq!(sprint.end, utc("2025-02-03T00:00:00Z"));
+
+        let sunday = schedule("UTC", WeekStart::Sunday);
+        let sprint = sunday.sprint(date(2025, 1, 22));
+        assert_eq!(sprint.starts_on, date(2025, 1, 19));
+        assert_eq!(sprint.ends_on, date(2025, 2, 1));
+
+        assert_eq!(monday.sprint(date(2025, 1, 5)).number, 0);
+    }
+
+    #[test]
+    fn digest_groups_open_tasks_in_the_users_day() {
+        let schedule = schedule("Asia/Tokyo", WeekStart::Monday);
+        // 10:00 on Wednesday 22 January in Tokyo
+        let now = utc("2025-01-22T01:00:00Z");
+        let tasks = vec![
+            task("late", TaskStatus::InProgress, Some("2025-01-20T03:00:00Z")),
+            task(
+                "this morning",
+                TaskStatus::Todo,
+                Some("2025-01-22T00:00:00Z"),
+            ),
+            // 23:00 in Tokyo
+            task("tonight", TaskStatus::Todo, Some("2025-01-22T14:00:00Z")),
+            task("friday", TaskStatus::Todo, Some("2025-01-24T09:00:00Z")),
+            task(
+                "next sprint",
+                TaskStatus::Todo,
+                Some("2025-02-04T09:00:00Z"),
+            ),
+            task("done", TaskStatus::Done, Some("2025-01-20T03:00:00Z")),
+            task("undated", TaskStatus::Todo, None),
+        ];
+
+        let digest = schedule.digest(&tasks, now);
+        let titles = |tasks: &[DueTask]| tasks.iter().map(|t| t.title.clone()).collect::<Vec<_>>();
+        assert_eq!(digest.date, date(2025, 1, 22));
+        assert_eq!(digest.timezone, "Asia/Tokyo");
+        assert_eq!(titles(&digest.overdue), ["late", "this morning"]);
+        assert_eq!(digest.overdue[0].days_overdue, 2);
+        assert_eq!(digest.overdue[1].days_overdue, 0);
+        assert_eq!(titles(&digest.due_today), ["tonight"]);
+        assert_eq!(titles(&digest.due_this_sprint), ["friday"]);
+    }
+
+    #[test]
+    fn only_open_tasks_are_overdue() {
+        let now = utc("2025-01-22T01:00:00Z");
+        let due = Some("2025-01-21T00:00:00Z");
+        assert!(is_overdue(&task("a", TaskStatus::InReview, due), now));
+        assert!(!is_overdue(&task("b", TaskStatus::Cancelled, due), now));
+        assert!(!is_overdue(
+            &task("c", TaskStatus::Todo, Some("2025-01-23T00:00:00Z")),
+            now
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_unknown_timezones_and_sprint_lengths() {
+        let mut config = ScheduleConfig {
+            timezone: Some("Mars/Olympus".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        config.timezone = Some("Europe/Berlin".to_string());
+        assert!(config.validate().is_ok());
+        config.sprint_weeks = 0;
+        assert!(config.validate().is_err());
+    }
+}
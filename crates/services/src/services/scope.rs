@@ -0,0 +1,104 @@
+//! Follow-ups scoped to files and hunks selected in the diff view.
+//!
+//! The selection is written into the prompt so the agent knows exactly what to touch. With write
+//! restriction on, the run's changes are also checked against it alongside the executor policies,
+//! and anything outside the selection fails the run instead of being committed.
+
+use std::fmt::Write;
+
+use db::models::{
+    execution_scope::ScopedFile,
+    policy_violation_report::{PolicyViolation, PolicyViolationKind},
+};
+use executors::profile::ExecutorProfileId;
+
+/// Prepend the selected files and hunks to the follow-up prompt
+pub fn scoped_prompt(prompt: &str, files: &[ScopedFile], restrict_writes: bool) -> String {
+    let mut content = String::from(
+        "## Scope\n\nThis is a targeted fix after review. Limit your changes to the files and \
+         hunks below and leave everything else as it is.",
+    );
+    if restrict_writes {
+        content.push_str(" Changes to any other file will be rejected.");
+    }
+    content.push_str("\n\n");
+    for file in files {
+        if file.hunks.is_empty() {
+            let _ = writeln!(content, "- {}/{}", file.repo, file.path);
+            continue;
+        }
+        let _ = writeln!(
+            content,
+            "- {}/{}, only the selected hunks:",
+            file.repo, file.path
+        );
+        for hunk in &file.hunks {
+            let hunk = hunk.trim_end();
+            let _ = write!(content, "\n```diff\n{hunk}\n```\n");
+        }
+    }
+    format!("{content}\n## Request\n\n{prompt}")
+}
+
+/// Violations for `paths` changed in `repo` that are outside the selected files
+pub fn check_scope(
+    files: &[ScopedFile],
+    profile: &ExecutorProfileId,
+    repo: &str,
+    paths: &[String],
+) -> Vec<PolicyViolation> {
+    paths
+        .iter()
+        .filter(|path| !files.iter().any(|f| f.repo == repo && &f.path == *path))
+        .map(|path| PolicyViolation {
+            executor_profile_id: profile.clone(),
+            repo: repo.to_string(),
+            path: path.clone(),
+            kind: PolicyViolationKind::OutsideScope,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+
+    fn file(path: &str, hunks: &[&str]) -> ScopedFile {
+        ScopedFile {
+            repo: "api".to_string(),
+            path: path.to_string(),
+            hunks: hunks.iter().map(|h| h.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn prompt_lists_files_and_hunks_before_the_request() {
+        let files = [
+            file("src/lib.rs", &[]),
+            file("src/main.rs", &["@@ -1,1 +1,1 @@\n-a\n+b\n"]),
+        ];
+        let prompt = scoped_prompt("Rename the flag", &files, true);
+        assert!(prompt.contains("- api/src/lib.rs\n"));
+        assert!(prompt.contains("- api/src/main.rs, only the selected hunks:"));
+        assert!(prompt.contains("```diff\n@@ -1,1 +1,1 @@\n-a\n+b\n```"));
+        assert!(prompt.contains("will be rejected"));
+        assert!(prompt.ends_with("## Request\n\nRename the flag"));
+    }
+
+    #[test]
+    fn flags_changes_outside_the_selection() {
+        let profile = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+        let files = [file("src/lib.rs", &[])];
+        let changed = vec!["src/lib.rs".to_string(), "Cargo.toml".to_string()];
+
+        let violations = check_scope(&files, &profile, "api", &changed);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, "Cargo.toml");
+        assert_eq!(violations[0].kind, PolicyViolationKind::OutsideScope);
+
+        // The same path in another repository is not in scope
+        assert_eq!(check_scope(&files, &profile, "web", &changed).len(), 2);
+    }
+}
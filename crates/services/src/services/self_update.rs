@@ -0,0 +1,700 @@
+//! Checking for and installing new releases of standalone binaries.
+//!
+//! Releases are published as a zip per platform and binary, listed with their SHA-256 and Ed25519
+//! signature in a manifest per release tag; a top-level manifest names the latest tag of each
+//! channel. The signature covers the tag, platform, binary and SHA-256 together (see
+//! [`signed_payload`]), so an older signed archive cannot be passed off as a newer release.
+//! Installs started through npx already run the latest release, so only standalone binaries
+//! update themselves. An update is downloaded, verified and staged beside the running binary.
+//! Installing it refuses new executions, waits for the running ones to finish, swaps the binaries
+//! and restarts the server on the same port.
+
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read},
+    path::PathBuf,
+    sync::{
+        LazyLock, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chrono::{DateTime, Utc};
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessRunReason};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::sync::Notify;
+use ts_rs::TS;
+use utils::version::APP_VERSION;
+
+use crate::services::compatibility::Version;
+
+/// Where releases are published, set when building release binaries
+const RELEASE_BASE_URL: Option<&str> = option_env!("VK_RELEASE_BASE_URL");
+/// Base64 Ed25519 public key the release archives are signed with, set with the base URL
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("VK_RELEASE_PUBLIC_KEY");
+const BINARY_NAME: &str = "vibe-kanban";
+/// Set on the restarted process, so it does not open another browser tab
+const RESTARTED_ENV: &str = "VK_UPDATED_FROM";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Set while an install waits for executions to finish; no new ones start meanwhile
+static DRAINING: AtomicBool = AtomicBool::new(false);
+static STATE: Mutex<UpdateState> = Mutex::new(UpdateState::Idle);
+/// Version of the binary staged beside the running one
+static STAGED: Mutex<Option<String>> = Mutex::new(None);
+static RESTART: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    /// Pre-releases, published before they reach stable
+    Beta,
+}
+
+impl UpdateChannel {
+    fn as_str(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct UpdateConfig {
+    pub channel: UpdateChannel,
+    /// Look for a new release at startup and log when one is available
+    pub check_on_startup: bool,
+    /// Release server to use instead of the one the binary was built with
+    pub base_url: Option<String>,
+    /// How long an install waits for running executions before it is called off
+    pub drain_timeout_mins: u32,
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::Stable,
+            check_on_startup: true,
+            base_url: None,
+            drain_timeout_mins: 60,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SelfUpdateError {
+    #[error("No release server is configured")]
+    NoReleaseServer,
+    #[error("Release request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Invalid release manifest: {0}")]
+    Manifest(String),
+    #[error("Release {tag} has no build for {platform}")]
+    NoBuild { tag: String, platform: String },
+    #[error("The release archive failed verification: {0}")]
+    Verification(String),
+    #[error("Invalid release archive: {0}")]
+    Archive(#[from] zip::result::ZipError),
+    #[error("This install cannot update itself: {0}")]
+    NotSupported(String),
+    #[error("Already running the latest {0} release")]
+    UpToDate(String),
+    #[error("No update has been downloaded")]
+    NothingStaged,
+    #[error("An update to {0} is already being installed")]
+    InProgress(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// How the running binary was installed
+#[derive(Clone, Copy, Debug, Serialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallKind {
+    Standalone,
+    /// Started by the npx launcher, which fetches the latest release itself
+    Npx,
+    Development,
+}
+
+#[derive(Clone, Debug, Serialize, TS, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum UpdateState {
+    Idle,
+    /// Waiting for running executions to finish before restarting
+    Draining {
+        version: String,
+        since: DateTime<Utc>,
+        running: usize,
+    },
+    Restarting {
+        version: String,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct UpdateStatus {
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    /// Latest release of the channel; None when no release server is configured
+    pub latest_version: Option<String>,
+    pub update_available: bool,
+    pub install: InstallKind,
+    /// Why updating from within the app is not possible here
+    pub unsupported_reason: Option<String>,
+    /// Downloaded and verified version, ready to install
+    pub staged_version: Option<String>,
+    pub state: UpdateState,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseIndex {
+    latest: String,
+    /// Latest tag per channel other than stable
+    #[serde(default)]
+    channels: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseManifest {
+    /// Tag of the release the manifest lists
+    version: String,
+    platforms: HashMap<String, HashMap<String, ReleaseBinary>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseBinary {
+    sha256: String,
+    /// Base64 Ed25519 signature of the [`signed_payload`] of the zip
+    signature: Option<String>,
+}
+
+/// Release platform of this build, named as in the release manifest
+pub fn platform() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Some("linux-x64"),
+        ("linux", "aarch64") => Some("linux-arm64"),
+        ("windows", "x86_64") => Some("windows-x64"),
+        ("windows", "aarch64") => Some("windows-arm64"),
+        ("macos", "x86_64") => Some("macos-x64"),
+        ("macos", "aarch64") => Some("macos-arm64"),
+        _ => None,
+    }
+}
+
+pub fn install_kind() -> InstallKind {
+    if cfg!(debug_assertions) {
+        return InstallKind::Development;
+    }
+    // The npx launcher extracts binaries into ~/.vibe-kanban/bin/<tag>/<platform>
+    let npx_cache = dirs::home_dir().map(|home| home.join(".vibe-kanban").join("bin"));
+    match (std::env::current_exe(), npx_cache) {
+        (Ok(exe), Some(cache)) if exe.starts_with(&cache) => InstallKind::Npx,
+        _ => InstallKind::Standalone,
+    }
+}
+
+fn unsupported_reason(install: InstallKind) -> Option<String> {
+    match install {
+        InstallKind::Development => Some("development builds are not updated".to_string()),
+        InstallKind::Npx => Some(
+            "started through npx, which runs the latest release; restart with `npx vibe-kanban@latest`"
+                .to_string(),
+        ),
+        InstallKind::Standalone if RELEASE_PUBLIC_KEY.is_none() => {
+            Some("this build has no release signing key to verify updates with".to_string())
+        }
+        InstallKind::Standalone if platform().is_none() => {
+            Some("no releases are published for this platform".to_string())
+        }
+        InstallKind::Standalone => None,
+    }
+}
+
+/// Version of a release tag like `v0.0.135-20251215122030`
+pub fn tag_version(tag: &str) -> Option<Version> {
+    Version::find(tag.split('-').next()?)
+}
+
+fn base_url(config: &UpdateConfig) -> Result<String, SelfUpdateError> {
+    config
+        .base_url
+        .as_deref()
+        .or(RELEASE_BASE_URL)
+        .map(|url| url.trim_end_matches('/').to_string())
+        .ok_or(SelfUpdateError::NoReleaseServer)
+}
+
+fn client(timeout: Duration) -> Result<reqwest::Client, SelfUpdateError> {
+    Ok(reqwest::Client::builder()
+        .user_agent(concat!("vibe-kanban/", env!("CARGO_PKG_VERSION")))
+        .timeout(timeout)
+        .build()?)
+}
+
+async fn fetch_json<T: DeserializeOwned>(url: &str) -> Result<T, SelfUpdateError> {
+    let response = client(REQUEST_TIMEOUT)?
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?;
+    let body = response.text().await?;
+    serde_json::from_str(&body).map_err(|e| SelfUpdateError::Manifest(format!("{url}: {e}")))
+}
+
+/// Latest release tag of the channel
+async fn latest_tag(base: &str, channel: UpdateChannel) -> Result<String, SelfUpdateError> {
+    let index: ReleaseIndex = fetch_json(&format!("{base}/binaries/manifest.json")).await?;
+    match channel {
+        UpdateChannel::Stable => Ok(index.latest),
+        // Between pre-releases the beta channel follows stable
+        other => Ok(index
+            .channels
+            .get(other.as_str())
+            .cloned()
+            .unwrap_or(index.latest)),
+    }
+}
+
+fn is_newer(tag: &str) -> bool {
+    match (tag_version(tag), Version::find(APP_VERSION)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+pub fn state() -> UpdateState {
+    STATE.lock().unwrap().clone()
+}
+
+fn set_state(state: UpdateState) {
+    *STATE.lock().unwrap() = state;
+}
+
+pub fn staged_version() -> Option<String> {
+    STAGED.lock().unwrap().clone()
+}
+
+/// Look up the latest release of the configured channel
+pub async fn check(config: &UpdateConfig) -> Result<UpdateStatus, SelfUpdateError> {
+    let install = install_kind();
+    let latest = match base_url(config) {
+        Ok(base) => Some(latest_tag(&base, config.channel).await?),
+        Err(SelfUpdateError::NoReleaseServer) => None,
+        Err(e) => return Err(e),
+    };
+    Ok(UpdateStatus {
+        current_version: APP_VERSION.to_string(),
+        channel: config.channel,
+        update_available: latest.as_deref().is_some_and(is_newer),
+        latest_version: latest
+            .as_deref()
+            .map(|tag| tag_version(tag).map_or_else(|| tag.to_string(), |v| v.to_string())),
+        install,
+        unsupported_reason: unsupported_reason(install),
+        staged_version: staged_version(),
+        state: state(),
+        checked_at: Utc::now(),
+    })
+}
+
+/// Check at startup and log when a newer release is out
+pub async fn log_available(config: UpdateConfig) {
+    if !config.check_on_startup {
+        return;
+    }
+    match check(&config).await {
+        Ok(status) if status.update_available => tracing::info!(
+            "Version {} is available (running {})",
+            status.latest_version.unwrap_or_default(),
+            status.current_version
+        ),
+        Ok(_) => {}
+        Err(e) => tracing::debug!("Update check failed: {e}"),
+    }
+}
+
+/// What a release archive's signature is made over: its release tag, platform, binary name and
+/// lowercase hex SHA-256, one per line
+pub fn signed_payload(tag: &str, platform: &str, binary: &str, sha256: &str) -> String {
+    format!(
+        "{tag}\n{platform}\n{binary}\n{}",
+        sha256.trim().to_ascii_lowercase()
+    )
+}
+
+/// Check the release archive of `tag` against the SHA-256 and signature listed in its manifest
+pub fn verify(
+    data: &[u8],
+    tag: &str,
+    platform: &str,
+    sha256: &str,
+    signature: Option<&str>,
+    public_key: &str,
+) -> Result<(), SelfUpdateError> {
+    let actual = hex::encode(Sha256::digest(data));
+    if !actual.eq_ignore_ascii_case(sha256.trim()) {
+        return Err(SelfUpdateError::Verification(format!(
+            "checksum mismatch: expected {sha256}, got {actual}"
+        )));
+    }
+    let signature = signature
+        .ok_or_else(|| SelfUpdateError::Verification("the release is not signed".to_string()))?;
+    let key: [u8; 32] = BASE64
+        .decode(public_key.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| SelfUpdateError::Verification("invalid public key".to_string()))?;
+    let key = VerifyingKey::from_bytes(&key)
+        .map_err(|e| SelfUpdateError::Verification(format!("invalid public key: {e}")))?;
+    let signature: [u8; 64] = BASE64
+        .decode(signature.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| SelfUpdateError::Verification("invalid signature".to_string()))?;
+    let payload = signed_payload(tag, platform, BINARY_NAME, sha256);
+    key.verify(payload.as_bytes(), &Signature::from_bytes(&signature))
+        .map_err(|_| SelfUpdateError::Verification("signature does not match".to_string()))
+}
+
+/// The server binary inside a release zip
+fn extract_binary(data: &[u8]) -> Result<Vec<u8>, SelfUpdateError> {
+    let name = format!("{BINARY_NAME}{}", std::env::consts::EXE_SUFFIX);
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))?;
+    let mut file = archive.by_name(&name)?;
+    let mut binary = Vec::with_capacity(file.size() as usize);
+    file.read_to_end(&mut binary)?;
+    Ok(binary)
+}
+
+fn staged_path() -> Result<PathBuf, SelfUpdateError> {
+    Ok(std::env::current_exe()?.with_extension("update"))
+}
+
+/// Download, verify and stage the latest release of the channel; returns its version
+pub async fn download(config: &UpdateConfig) -> Result<String, SelfUpdateError> {
+    let install = install_kind();
+    let (None, Some(platform), Some(public_key)) =
+        (unsupported_reason(install), platform(), RELEASE_PUBLIC_KEY)
+    else {
+        return Err(SelfUpdateError::NotSupported(
+            unsupported_reason(install).unwrap_or_default(),
+        ));
+    };
+    if let UpdateState::Draining { version, .. } | UpdateState::Restarting { version } = state() {
+        return Err(SelfUpdateError::InProgress(version));
+    }
+    let base = base_url(config)?;
+    let tag = latest_tag(&base, config.channel).await?;
+    if !is_newer(&tag) {
+        return Err(SelfUpdateError::UpToDate(
+            config.channel.as_str().to_string(),
+        ));
+    }
+    let version = tag_version(&tag).map_or_else(|| tag.clone(), |v| v.to_string());
+
+    let manifest: ReleaseManifest =
+        fetch_json(&format!("{base}/binaries/{tag}/manifest.json")).await?;
+    if manifest.version != tag {
+        return Err(SelfUpdateError::Manifest(format!(
+            "the manifest of {tag} is for {}",
+            manifest.version
+        )));
+    }
+    let release = manifest
+        .platforms
+        .get(platform)
+        .and_then(|binaries| binaries.get(BINARY_NAME))
+        .ok_or_else(|| SelfUpdateError::NoBuild {
+            tag: tag.clone(),
+            platform: platform.to_string(),
+        })?;
+    let data = client(DOWNLOAD_TIMEOUT)?
+        .get(format!(
+            "{base}/binaries/{tag}/{platform}/{BINARY_NAME}.zip"
+        ))
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    verify(
+        &data,
+        &tag,
+        platform,
+        &release.sha256,
+        release.signature.as_deref(),
+        public_key,
+    )?;
+
+    let binary = extract_binary(&data)?;
+    let path = staged_path()?;
+    tokio::fs::write(&path, binary).await?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).await?;
+    }
+    *STAGED.lock().unwrap() = Some(version.clone());
+    tracing::info!("Staged version {version} at {}", path.display());
+    Ok(version)
+}
+
+/// True while an install waits for executions to finish
+pub fn is_draining() -> bool {
+    DRAINING.load(Ordering::SeqCst)
+}
+
+/// Executions an install waits for; dev servers run until stopped, so they are not waited for
+async fn running_executions(pool: &SqlitePool) -> Result<usize, sqlx::Error> {
+    Ok(ExecutionProcess::find_running(pool)
+        .await?
+        .iter()
+        .filter(|process| process.run_reason != ExecutionProcessRunReason::DevServer)
+        .count())
+}
+
+/// Install the staged update: refuse new executions, wait for the running ones and restart.
+/// Returns once draining started; an install that waits longer than the drain timeout is
+/// called off and executions start again.
+pub async fn install(pool: SqlitePool, config: &UpdateConfig) -> Result<(), SelfUpdateError> {
+    let version = staged_version().ok_or(SelfUpdateError::NothingStaged)?;
+    if DRAINING.swap(true, Ordering::SeqCst) {
+        return Err(SelfUpdateError::InProgress(version));
+    }
+    let running = match running_executions(&pool).await {
+        Ok(running) => running,
+        Err(e) => {
+            DRAINING.store(false, Ordering::SeqCst);
+            return Err(e.into());
+        }
+    };
+    let since = Utc::now();
+    set_state(UpdateState::Draining {
+        version: version.clone(),
+        since,
+        running,
+    });
+    tracing::info!("Installing version {version} once {running} running executions finish");
+
+    let timeout = Duration::from_secs(u64::from(config.drain_timeout_mins) * 60);
+    tokio::spawn(async move {
+        let started = Instant::now();
+        loop {
+            if !is_draining() {
+                return;
+            }
+            match running_executions(&pool).await {
+                Ok(0) => break,
+                Ok(running) => set_state(UpdateState::Draining {
+                    version: version.clone(),
+                    since,
+                    running,
+                }),
+                Err(e) => tracing::warn!("Failed to count running executions: {e}"),
+            }
+            if started.elapsed() >= timeout {
+                tracing::warn!(
+                    "Called off installing version {version}: executions still running after {} minutes",
+                    timeout.as_secs() / 60
+                );
+                cancel();
+                return;
+            }
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+
+        if let Err(e) = swap_binaries() {
+            tracing::error!("Failed to install version {version}: {e}");
+            cancel();
+            return;
+        }
+        *STAGED.lock().unwrap() = None;
+        set_state(UpdateState::Restarting { version });
+        RESTART.notify_one();
+    });
+    Ok(())
+}
+
+/// Call off an install that is waiting for executions; the update stays staged
+pub fn cancel() {
+    DRAINING.store(false, Ordering::SeqCst);
+    let mut state = STATE.lock().unwrap();
+    if matches!(*state, UpdateState::Draining { .. }) {
+        *state = UpdateState::Idle;
+    }
+}
+
+/// Put the staged binary in place of the running one, keeping the old one beside it
+fn swap_binaries() -> Result<(), SelfUpdateError> {
+    let exe = std::env::current_exe()?;
+    let staged = staged_path()?;
+    let old = exe.with_extension("old");
+    match std::fs::remove_file(&old) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    // Renaming works on a running binary, including on Windows
+    std::fs::rename(&exe, &old)?;
+    if let Err(e) = std::fs::rename(&staged, &exe) {
+        std::fs::rename(&old, &exe)?;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Resolves once an installed update is ready for the server to shut down and restart
+pub async fn restart_requested() {
+    RESTART.notified().await;
+}
+
+pub fn restart_pending() -> bool {
+    matches!(state(), UpdateState::Restarting { .. })
+}
+
+/// Whether this process was started by an update restart
+pub fn restarted() -> bool {
+    std::env::var_os(RESTARTED_ENV).is_some()
+}
+
+/// Start the installed binary with the same arguments on the same port. On Unix the process is
+/// replaced in place; elsewhere the new one is spawned and this one exits. Returns only on error.
+pub fn restart(port: u16) -> std::io::Error {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => return e,
+    };
+    let mut command = std::process::Command::new(exe);
+    command
+        .args(std::env::args_os().skip(1))
+        .env("BACKEND_PORT", port.to_string())
+        .env(RESTARTED_ENV, APP_VERSION);
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.exec()
+    }
+    #[cfg(not(unix))]
+    {
+        match command.spawn() {
+            Ok(_) => std::process::exit(0),
+            Err(e) => e,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    #[test]
+    fn reads_versions_from_release_tags() {
+        assert_eq!(
+            tag_version("v0.0.135-20251215122030"),
+            Some(Version::new(0, 0, 135))
+        );
+        assert_eq!(tag_version("v1.2.0"), Some(Version::new(1, 2, 0)));
+        assert_eq!(tag_version("nightly"), None);
+    }
+
+    #[test]
+    fn verifies_checksum_and_signature() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let public_key = BASE64.encode(signing_key.verifying_key().to_bytes());
+        let tag = "v0.0.136-20260101000000";
+        let data = b"release archive";
+        let sha256 = hex::encode(Sha256::digest(data));
+        let payload = signed_payload(tag, "linux-x64", BINARY_NAME, &sha256);
+        let signature = BASE64.encode(signing_key.sign(payload.as_bytes()).to_bytes());
+
+        assert!(
+            verify(
+                data,
+                tag,
+                "linux-x64",
+                &sha256,
+                Some(&signature),
+                &public_key
+            )
+            .is_ok()
+        );
+        assert!(matches!(
+            verify(
+                b"tampered archive",
+                tag,
+                "linux-x64",
+                &sha256,
+                Some(&signature),
+                &public_key
+            ),
+            Err(SelfUpdateError::Verification(_))
+        ));
+        let other_sha256 = hex::encode(Sha256::digest(b"tampered archive"));
+        assert!(matches!(
+            verify(
+                b"tampered archive",
+                tag,
+                "linux-x64",
+                &other_sha256,
+                Some(&signature),
+                &public_key
+            ),
+            Err(SelfUpdateError::Verification(_))
+        ));
+        assert!(matches!(
+            verify(data, tag, "linux-x64", &sha256, None, &public_key),
+            Err(SelfUpdateError::Verification(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_signed_archives_of_other_releases() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let public_key = BASE64.encode(signing_key.verifying_key().to_bytes());
+        let data = b"old release archive";
+        let sha256 = hex::encode(Sha256::digest(data));
+        let payload = signed_payload("v0.0.100-20250101000000", "linux-x64", BINARY_NAME, &sha256);
+        let signature = BASE64.encode(signing_key.sign(payload.as_bytes()).to_bytes());
+
+        assert!(matches!(
+            verify(
+                data,
+                "v0.0.136-20260101000000",
+                "linux-x64",
+                &sha256,
+                Some(&signature),
+                &public_key
+            ),
+            Err(SelfUpdateError::Verification(_))
+        ));
+        assert!(matches!(
+            verify(
+                data,
+                "v0.0.100-20250101000000",
+                "macos-arm64",
+                &sha256,
+                Some(&signature),
+                &public_key
+            ),
+            Err(SelfUpdateError::Verification(_))
+        ));
+    }
+}
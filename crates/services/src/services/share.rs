@@ -1,8 +1,10 @@
 mod config;
+mod mirror;
 mod publisher;
 mod status;
 
 pub use config::ShareConfig;
+pub use mirror::{MirrorSide, MirrorSyncReport, merge_mirrored};
 pub use publisher::{SharePublisher, SharedTaskDetails};
 use thiserror::Error;
 use uuid::Uuid;
@@ -34,6 +36,10 @@ pub enum ShareError {
     InvalidResponse,
     #[error("task {0} is already shared")]
     AlreadyShared(Uuid),
+    #[error("project {0} is not mirrored")]
+    MirrorNotEnabled(Uuid),
+    #[error("task {0} has no mirror conflict")]
+    NoMirrorConflict(Uuid),
     #[error("GitHub token is required to fetch repository ID")]
     MissingGitHubToken,
     #[error(transparent)]
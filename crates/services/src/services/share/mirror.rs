@@ -0,0 +1,320 @@
+//! Mirroring a project between instances through its remote project.
+//!
+//! Every instance that mirrors a project publishes its new tasks to the remote project and
+//! copies the tasks other instances published, so work done offline is picked up on the next
+//! sync. For each task we remember the version both sides last agreed on; a field changed on
+//! only one side since then wins, and a field changed differently on both sides is a conflict
+//! that waits for someone to pick a side. Merge outcomes travel as task status. Tasks created or
+//! changed by a sync are recorded in the task event log like any other change.
+
+use db::models::{
+    project::Project,
+    project_mirror::{MirroredFields, ProjectMirror, TaskMirrorState},
+    task::{CreateTask, Task},
+};
+use remote::routes::tasks::UpdateSharedTaskRequest;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{ShareError, SharePublisher, status};
+use crate::services::{
+    remote_client::RemoteClientError,
+    task_events::{TaskActor, TaskEventLog},
+};
+
+/// Actor of the changes a sync copies from the remote project
+const MIRROR_ACTOR: &str = "project_mirror";
+
+/// Which version of a conflicting task to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum MirrorSide {
+    Local,
+    Remote,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct MirrorSyncReport {
+    /// Local tasks published to the remote project
+    pub published: usize,
+    /// Tasks from other instances created here
+    pub created: usize,
+    /// Local changes sent to the remote project
+    pub pushed: usize,
+    /// Remote changes applied here
+    pub pulled: usize,
+    /// Tasks deleted remotely and no longer mirrored; the local task is kept
+    pub unlinked: usize,
+    /// Local changes the remote project refused, retried on the next sync
+    pub skipped: usize,
+    pub conflicts: usize,
+}
+
+fn merge_field<T: PartialEq + Clone>(base: &T, local: &T, remote: &T) -> Option<T> {
+    if local == remote || remote == base {
+        Some(local.clone())
+    } else if local == base {
+        Some(remote.clone())
+    } else {
+        None
+    }
+}
+
+/// Three-way merge of a task, or None when a field changed differently on both sides
+pub fn merge_mirrored(
+    base: &MirroredFields,
+    local: &MirroredFields,
+    remote: &MirroredFields,
+) -> Option<MirroredFields> {
+    Some(MirroredFields {
+        title: merge_field(&base.title, &local.title, &remote.title)?,
+        description: merge_field(&base.description, &local.description, &remote.description)?,
+        status: merge_field(&base.status, &local.status, &remote.status)?,
+    })
+}
+
+impl SharePublisher {
+    /// Exchange changes between a mirrored project and its remote project
+    pub async fn sync_project(
+        &self,
+        project_id: Uuid,
+        user_id: Uuid,
+        events: &TaskEventLog,
+    ) -> Result<MirrorSyncReport, ShareError> {
+        let pool = &self.db.pool;
+        let actor = TaskActor::system(MIRROR_ACTOR);
+        if ProjectMirror::find_by_project_id(pool, project_id)
+            .await?
+            .is_none()
+        {
+            return Err(ShareError::MirrorNotEnabled(project_id));
+        }
+        let project = Project::find_by_id(pool, project_id)
+            .await?
+            .ok_or(ShareError::ProjectNotFound(project_id))?;
+        let remote_project_id = project
+            .remote_project_id
+            .ok_or(ShareError::ProjectNotLinked(project.id))?;
+        let mut report = MirrorSyncReport::default();
+
+        let local_tasks = Task::find_by_project_id_with_attempt_status(pool, project_id).await?;
+        for task in local_tasks.iter().map(|t| &t.task) {
+            if task.shared_task_id.is_some() {
+                continue;
+            }
+            let shared_task_id = self.share_task(task.id, user_id).await?;
+            TaskMirrorState::upsert(pool, task.id, shared_task_id, &MirroredFields::from(task))
+                .await?;
+            report.published += 1;
+        }
+
+        let remote_tasks = self
+            .client
+            .list_shared_tasks(remote_project_id)
+            .await?
+            .tasks;
+        for remote_task in remote_tasks {
+            let local = Task::find_by_shared_task_id(pool, remote_task.id).await?;
+            if remote_task.deleted_at.is_some() {
+                if let Some(task) = local {
+                    Task::set_shared_task_id(pool, task.id, None).await?;
+                    TaskMirrorState::delete(pool, task.id).await?;
+                    report.unlinked += 1;
+                }
+                continue;
+            }
+
+            let remote = MirroredFields {
+                title: remote_task.title.clone(),
+                description: remote_task.description.clone(),
+                status: status::from_remote(remote_task.status),
+            };
+            let Some(task) = local else {
+                let create_task = CreateTask::from_shared_task(
+                    project_id,
+                    remote.title.clone(),
+                    remote.description.clone(),
+                    remote.status.clone(),
+                    remote_task.id,
+                );
+                let task = Task::create(pool, &create_task, Uuid::new_v4()).await?;
+                events.created(&task, &actor).await?;
+                TaskMirrorState::upsert(pool, task.id, remote_task.id, &remote).await?;
+                report.created += 1;
+                continue;
+            };
+            if task.project_id != project_id {
+                continue;
+            }
+
+            let local = MirroredFields::from(&task);
+            // Tasks shared before mirroring started pushed every local edit right away, so any
+            // difference to the remote version was made elsewhere
+            let base = TaskMirrorState::find_by_task_id(pool, task.id)
+                .await?
+                .map(|state| state.base())
+                .unwrap_or_else(|| local.clone());
+            let Some(merged) = merge_mirrored(&base, &local, &remote) else {
+                TaskMirrorState::set_conflict(pool, task.id, remote_task.id, &base, &remote)
+                    .await?;
+                report.conflicts += 1;
+                continue;
+            };
+
+            if merged != remote {
+                let request = UpdateSharedTaskRequest {
+                    title: Some(merged.title.clone()),
+                    description: merged.description.clone(),
+                    status: Some(status::to_remote(&merged.status)),
+                    expected_updated_at: Some(remote_task.updated_at),
+                };
+                match self
+                    .client
+                    .update_shared_task(remote_task.id, &request)
+                    .await
+                {
+                    Ok(_) => report.pushed += 1,
+                    // Changed again since we listed it, or assigned to someone else
+                    Err(RemoteClientError::Http { status: 409, .. } | RemoteClientError::Auth) => {
+                        tracing::debug!("Skipping mirror push of task {}", task.id);
+                        report.skipped += 1;
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            if merged != local {
+                let updated = Task::update(
+                    pool,
+                    task.id,
+                    project_id,
+                    merged.title.clone(),
+                    merged.description.clone(),
+                    merged.status.clone(),
+                    task.parent_workspace_id,
+                    task.due_at,
+                    None,
+                )
+                .await?
+                .ok_or(ShareError::TaskNotFound(task.id))?;
+                events.updated(&task, &updated, &actor).await?;
+                report.pulled += 1;
+            }
+            TaskMirrorState::upsert(pool, task.id, remote_task.id, &merged).await?;
+        }
+
+        ProjectMirror::mark_synced(pool, project_id).await?;
+        Ok(report)
+    }
+
+    /// Settle a conflict by keeping one version of the task on both sides
+    pub async fn resolve_mirror_conflict(
+        &self,
+        task_id: Uuid,
+        keep: MirrorSide,
+        events: &TaskEventLog,
+        actor: &TaskActor,
+    ) -> Result<Task, ShareError> {
+        let pool = &self.db.pool;
+        let task = Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(ShareError::TaskNotFound(task_id))?;
+        let (state, remote) = TaskMirrorState::find_by_task_id(pool, task_id)
+            .await?
+            .and_then(|state| {
+                let remote = state.conflict.clone()?.0;
+                Some((state, remote))
+            })
+            .ok_or(ShareError::NoMirrorConflict(task_id))?;
+
+        let (task, kept) = match keep {
+            MirrorSide::Local => {
+                let local = MirroredFields::from(&task);
+                let request = UpdateSharedTaskRequest {
+                    title: Some(local.title.clone()),
+                    description: local.description.clone(),
+                    status: Some(status::to_remote(&local.status)),
+                    expected_updated_at: None,
+                };
+                self.client
+                    .update_shared_task(state.shared_task_id, &request)
+                    .await?;
+                (task, local)
+            }
+            MirrorSide::Remote => {
+                let updated = Task::update(
+                    pool,
+                    task.id,
+                    task.project_id,
+                    remote.title.clone(),
+                    remote.description.clone(),
+                    remote.status.clone(),
+                    task.parent_workspace_id,
+                    task.due_at,
+                    None,
+                )
+                .await?
+                .ok_or(ShareError::TaskNotFound(task_id))?;
+                events.updated(&task, &updated, actor).await?;
+                (updated, remote)
+            }
+        };
+        TaskMirrorState::upsert(pool, task.id, state.shared_task_id, &kept).await?;
+        Ok(task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::task::TaskStatus;
+
+    use super::*;
+
+    fn fields(title: &str, description: Option<&str>, status: TaskStatus) -> MirroredFields {
+        MirroredFields {
+            title: title.to_string(),
+            description: description.map(str::to_string),
+            status,
+        }
+    }
+
+    #[test]
+    fn changes_on_different_fields_are_combined() {
+        let base = fields("Fix login", None, TaskStatus::Todo);
+        let local = fields("Fix login", None, TaskStatus::InProgress);
+        let remote = fields(
+            "Fix login redirect",
+            Some("Loops on Safari"),
+            TaskStatus::Todo,
+        );
+
+        let merged = merge_mirrored(&base, &local, &remote).unwrap();
+        assert_eq!(
+            merged,
+            fields(
+                "Fix login redirect",
+                Some("Loops on Safari"),
+                TaskStatus::InProgress
+            )
+        );
+    }
+
+    #[test]
+    fn same_change_on_both_sides_is_not_a_conflict() {
+        let base = fields("Fix login", None, TaskStatus::InReview);
+        let done = fields("Fix login", None, TaskStatus::Done);
+
+        assert_eq!(merge_mirrored(&base, &done, &done), Some(done.clone()));
+        assert_eq!(merge_mirrored(&base, &base, &base), Some(base.clone()));
+    }
+
+    #[test]
+    fn different_changes_to_one_field_conflict() {
+        let base = fields("Fix login", None, TaskStatus::InReview);
+        let local = fields("Fix login", None, TaskStatus::Done);
+        let remote = fields("Fix login", None, TaskStatus::Cancelled);
+
+        assert_eq!(merge_mirrored(&base, &local, &remote), None);
+    }
+}
@@ -15,8 +15,8 @@ use crate::services::remote_client::RemoteClient;
 
 #[derive(Clone)]
 pub struct SharePublisher {
-    db: DBService,
-    client: RemoteClient,
+    pub(super) db: DBService,
+    pub(super) client: RemoteClient,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, ts_rs::TS)]
@@ -72,6 +72,7 @@ impl SharePublisher {
             title: Some(task.title.clone()),
             description: task.description.clone(),
             status: Some(status::to_remote(&task.status)),
+            expected_updated_at: None,
         };
 
         self.client
@@ -0,0 +1,128 @@
+//! Text similarity between tasks.
+//!
+//! A small TF-IDF index over task titles and descriptions, built on demand from a project's
+//! tasks. Words shared by many tasks count for little, so two tasks are close when they share the
+//! rarer words that say what the task is actually about.
+
+use std::collections::{HashMap, HashSet};
+
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "from", "into", "are", "was", "were", "should",
+    "will", "when", "then", "than", "its", "not", "but", "all", "any", "can", "add", "use", "make",
+    "have", "has", "there", "their", "them", "they", "also", "only", "more", "some", "which",
+];
+
+/// Lowercased words of three or more characters, without stop words
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+type Vector = HashMap<String, f64>;
+
+pub struct SimilarityIndex {
+    idf: HashMap<String, f64>,
+    documents: Vec<Vector>,
+}
+
+impl SimilarityIndex {
+    pub fn new<'a>(documents: impl IntoIterator<Item = &'a str>) -> Self {
+        let tokenized: Vec<Vec<String>> = documents.into_iter().map(tokenize).collect();
+        let mut document_frequency: HashMap<String, usize> = HashMap::new();
+        for tokens in &tokenized {
+            for token in tokens.iter().collect::<HashSet<_>>() {
+                *document_frequency.entry(token.clone()).or_default() += 1;
+            }
+        }
+        let count = tokenized.len() as f64;
+        let idf = document_frequency
+            .into_iter()
+            .map(|(token, df)| (token, ((count + 1.0) / (df as f64 + 1.0)).ln() + 1.0))
+            .collect();
+        let mut index = Self {
+            idf,
+            documents: Vec::new(),
+        };
+        index.documents = tokenized
+            .iter()
+            .map(|tokens| index.vector(tokens))
+            .collect();
+        index
+    }
+
+    fn vector(&self, tokens: &[String]) -> Vector {
+        let mut vector = Vector::new();
+        for token in tokens {
+            // Words the index has never seen cannot match anything
+            if let Some(idf) = self.idf.get(token) {
+                *vector.entry(token.clone()).or_default() += idf;
+            }
+        }
+        let norm = vector.values().map(|w| w * w).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            vector.values_mut().for_each(|w| *w /= norm);
+        }
+        vector
+    }
+
+    /// Indexes of the documents most similar to `text` with their cosine similarity, best first,
+    /// leaving out those below `min_similarity`
+    pub fn query(&self, text: &str, limit: usize, min_similarity: f64) -> Vec<(usize, f64)> {
+        let query = self.vector(&tokenize(text));
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut scored: Vec<(usize, f64)> = self
+            .documents
+            .iter()
+            .enumerate()
+            .map(|(index, document)| {
+                let score = query
+                    .iter()
+                    .filter_map(|(token, w)| document.get(token).map(|d| w * d))
+                    .sum::<f64>();
+                (index, score)
+            })
+            .filter(|(_, score)| *score >= min_similarity)
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(limit);
+        scored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenizes_without_stop_words_and_short_words() {
+        assert_eq!(
+            tokenize("Add a retry to the GitHub PR-sync job"),
+            vec!["retry", "github", "sync", "job"]
+        );
+    }
+
+    #[test]
+    fn ranks_documents_sharing_rare_words_first() {
+        let index = SimilarityIndex::new([
+            "Fix login form validation",
+            "Add dark mode to settings page",
+            "Login page crashes on invalid email",
+            "Refactor settings page layout",
+        ]);
+        let results = index.query("Validate email on the login form", 3, 0.05);
+        assert_eq!(results[0].0, 0);
+        assert_eq!(results[1].0, 2);
+        assert!(results.iter().all(|(index, _)| *index != 1));
+    }
+
+    #[test]
+    fn unknown_words_match_nothing() {
+        let index = SimilarityIndex::new(["Fix login form validation"]);
+        assert!(index.query("kubernetes helm chart", 5, 0.0).is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+//! Column SLAs and the escalation of tasks that stalled.
+//!
+//! A project can give each board column an SLA, the longest a task should stay in it. The
+//! recurring [`SLA_ESCALATION_JOB`] compares how long every task has been in its current column,
+//! taken from the task event log like the aging report of
+//! [`board_metrics`](crate::services::board_metrics), with the SLA of that column. A task that
+//! stayed longer is escalated once per stay, by the action of the SLA.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    board::BoardSnapshot,
+    column_sla::{ColumnSla, SlaAction},
+    task::TaskStatus,
+    task_event::StatusChange,
+};
+use serde::Serialize;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Recurring job that escalates tasks staying in a column longer than its SLA
+pub const SLA_ESCALATION_JOB: &str = "sla.escalate_stalled_tasks";
+
+/// How often tasks are checked against the SLAs
+pub const SLA_ESCALATION_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Author of the comments left on escalated tasks
+pub const SLA_COMMENT_AUTHOR: &str = "sla";
+
+/// A task that stayed in its column longer than the column's SLA
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SlaBreach {
+    pub task_id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+    pub entered_at: DateTime<Utc>,
+    pub hours_in_column: f64,
+    pub max_hours: i64,
+    pub action: SlaAction,
+}
+
+fn hours(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_seconds() as f64 / 3600.0
+}
+
+/// When a task last entered `status`, from the project's column changes, oldest first
+fn entered_at(
+    changes: &[StatusChange],
+    task_id: Uuid,
+    status: &TaskStatus,
+) -> Option<DateTime<Utc>> {
+    changes
+        .iter()
+        .rev()
+        .find(|change| change.task_id == task_id && change.status.as_ref() == Some(status))
+        .map(|change| change.created_at)
+}
+
+/// Tasks of the board past the SLA of their column at `now`, longest stalled first. Tasks that had
+/// no recorded column change since they were created count from their creation.
+pub fn breaches(
+    board: &BoardSnapshot,
+    changes: &[StatusChange],
+    slas: &[ColumnSla],
+    now: DateTime<Utc>,
+) -> Vec<SlaBreach> {
+    let mut breaches: Vec<SlaBreach> = board
+        .columns
+        .iter()
+        .filter_map(|column| {
+            let sla = slas.iter().find(|sla| sla.status == column.status)?;
+            Some((column, sla))
+        })
+        .flat_map(|(column, sla)| {
+            column.tasks.iter().filter_map(move |card| {
+                let entered_at =
+                    entered_at(changes, card.id, &card.status).unwrap_or(card.created_at);
+                let hours_in_column = hours(entered_at, now);
+                (hours_in_column > sla.max_hours as f64).then(|| SlaBreach {
+                    task_id: card.id,
+                    title: card.title.clone(),
+                    status: card.status.clone(),
+                    entered_at,
+                    hours_in_column,
+                    max_hours: sla.max_hours,
+                    action: sla.action,
+                })
+            })
+        })
+        .collect();
+    breaches.sort_by_key(|breach| breach.entered_at);
+    breaches
+}
+
+/// Comment left on the task when it is escalated
+pub fn escalation_comment(breach: &SlaBreach) -> String {
+    format!(
+        "This task has been in {} for {:.0} hours, longer than the {} hours it should take.",
+        breach.status, breach.hours_in_column, breach.max_hours
+    )
+}
+
+/// Prompt asking the agent to summarize what keeps the task from moving on
+pub fn nudge_prompt(breach: &SlaBreach) -> String {
+    format!(
+        "{}\n\nPlease summarize the blockers: what is left to do, what is keeping the task from \
+         moving on, and what you need from the user to get it unstuck. Don't make changes to the \
+         code for now.",
+        escalation_comment(breach)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use db::models::board::{BoardCard, BoardColumn};
+
+    use super::*;
+
+    fn at(hours: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 2, 9, 0, 0, 0).unwrap() + chrono::Duration::hours(hours)
+    }
+
+    fn card(id: Uuid, status: TaskStatus, created_hours: i64) -> BoardCard {
+        BoardCard {
+            id,
+            title: "Fix login".to_string(),
+            status,
+            parent_workspace_id: None,
+            shared_task_id: None,
+            due_at: None,
+            created_at: at(created_hours),
+            updated_at: at(created_hours),
+            workspace_count: 1,
+            has_in_progress_attempt: false,
+            last_attempt_failed: false,
+            executor: None,
+            pr_status: None,
+            pr_number: None,
+            pr_url: None,
+        }
+    }
+
+    fn sla(status: TaskStatus, max_hours: i64, action: SlaAction) -> ColumnSla {
+        ColumnSla {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            status,
+            max_hours,
+            action,
+            created_at: at(0),
+            updated_at: at(0),
+        }
+    }
+
+    fn change(task_id: Uuid, status: TaskStatus, hours: i64) -> StatusChange {
+        StatusChange {
+            task_id,
+            title: Some("Fix login".to_string()),
+            status: Some(status),
+            created_at: at(hours),
+        }
+    }
+
+    #[test]
+    fn tasks_breach_from_entering_their_column() {
+        let (stalled, moved, untracked, todo) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let changes = vec![
+            change(stalled, TaskStatus::InProgress, 0),
+            change(moved, TaskStatus::InProgress, 0),
+            change(moved, TaskStatus::InReview, 10),
+            change(moved, TaskStatus::InProgress, 40),
+        ];
+        let board = BoardSnapshot {
+            project_id: Uuid::new_v4(),
+            columns: vec![
+                BoardColumn {
+                    status: TaskStatus::Todo,
+                    tasks: vec![card(todo, TaskStatus::Todo, 0)],
+                },
+                BoardColumn {
+                    status: TaskStatus::InProgress,
+                    tasks: vec![
+                        card(stalled, TaskStatus::InProgress, 0),
+                        card(moved, TaskStatus::InProgress, 0),
+                        card(untracked, TaskStatus::InProgress, 20),
+                    ],
+                },
+            ],
+        };
+        let slas = [sla(TaskStatus::InProgress, 24, SlaAction::Nudge)];
+        let breaches = breaches(&board, &changes, &slas, at(48));
+
+        let found: Vec<_> = breaches
+            .iter()
+            .map(|breach| (breach.task_id, breach.hours_in_column))
+            .collect();
+        assert_eq!(found, [(stalled, 48.0), (untracked, 28.0)]);
+        assert_eq!(breaches[0].action, SlaAction::Nudge);
+    }
+
+    #[test]
+    fn nudge_asks_for_blockers() {
+        let breach = SlaBreach {
+            task_id: Uuid::new_v4(),
+            title: "Fix login".to_string(),
+            status: TaskStatus::InReview,
+            entered_at: at(0),
+            hours_in_column: 30.4,
+            max_hours: 24,
+            action: SlaAction::Nudge,
+        };
+        let prompt = nudge_prompt(&breach);
+        assert!(prompt.starts_with("This task has been in inreview for 30 hours"));
+        assert!(prompt.contains("summarize the blockers"));
+    }
+}
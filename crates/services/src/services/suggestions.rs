@@ -0,0 +1,334 @@
+//! Suggested changes from review comments.
+//!
+//! Reviewers propose replacement code in a ```` ```suggestion ```` block of an inline comment; the
+//! block replaces the lines the comment covers. GitLab can widen the range with
+//! ```` ```suggestion:-2+1 ````, two lines above and one below the commented line. Applying a
+//! suggestion rewrites those lines in the workspace worktree and commits the result.
+
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path},
+};
+
+use thiserror::Error;
+
+use crate::services::{
+    git::{GitService, GitServiceError},
+    git_provider::UnifiedComment,
+};
+
+/// A suggestion block as written in a comment body
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestionBlock {
+    /// Lines above the commented range that the block also replaces
+    pub lines_above: u64,
+    /// Lines below the commented range that the block also replaces
+    pub lines_below: u64,
+    pub replacement: String,
+}
+
+/// A suggestion resolved to the lines of a file it replaces
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub comment_id: i64,
+    /// Path relative to the repository root
+    pub path: String,
+    /// First replaced line, starting at 1
+    pub start_line: u64,
+    /// Last replaced line, inclusive
+    pub end_line: u64,
+    /// The line the comment is on, within the replaced range
+    pub line: u64,
+    pub replacement: String,
+    /// What the commented line read when the comment was written, when the diff hunk shows it
+    pub expected_line: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum SuggestionError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("Suggestion path is outside the repository: {0}")]
+    InvalidPath(String),
+    #[error("{path} has no line {line}")]
+    OutOfRange { path: String, line: u64 },
+    #[error("Line {line} of {path} changed since the suggestion was made")]
+    Outdated { path: String, line: u64 },
+    #[error("Suggestions on {path} overlap; apply them one at a time")]
+    Overlapping { path: String },
+}
+
+/// Suggestion blocks in a comment body, in order
+pub fn parse_suggestions(body: &str) -> Vec<SuggestionBlock> {
+    let mut blocks = Vec::new();
+    let mut lines = body.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        let fence_len = trimmed.chars().take_while(|c| *c == '`').count();
+        if fence_len < 3 {
+            continue;
+        }
+        let Some((lines_above, lines_below)) = parse_info(&trimmed[fence_len..]) else {
+            continue;
+        };
+        let mut replacement = Vec::new();
+        let mut closed = false;
+        for line in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed.len() >= fence_len && trimmed.chars().all(|c| c == '`') {
+                closed = true;
+                break;
+            }
+            replacement.push(line);
+        }
+        if !closed {
+            break;
+        }
+        blocks.push(SuggestionBlock {
+            lines_above,
+            lines_below,
+            replacement: replacement.join("\n"),
+        });
+    }
+    blocks
+}
+
+/// The range offsets of a `suggestion` fence info string; None for other code blocks
+fn parse_info(info: &str) -> Option<(u64, u64)> {
+    let rest = info.trim().strip_prefix("suggestion")?;
+    if rest.is_empty() {
+        return Some((0, 0));
+    }
+    let (above, below) = rest.strip_prefix(":-")?.split_once('+')?;
+    Some((above.parse().ok()?, below.parse().ok()?))
+}
+
+/// The suggestions of an inline review comment; none for general comments and outdated inline
+/// ones whose line is gone
+pub fn suggestions_from_comment(comment: &UnifiedComment) -> Vec<Suggestion> {
+    let UnifiedComment::Review {
+        id,
+        body,
+        path,
+        start_line,
+        line: Some(line),
+        diff_hunk,
+        ..
+    } = comment
+    else {
+        return Vec::new();
+    };
+    let Ok(line) = u64::try_from(*line) else {
+        return Vec::new();
+    };
+    let first = start_line
+        .and_then(|start| u64::try_from(start).ok())
+        .filter(|start| (1..=line).contains(start))
+        .unwrap_or(line);
+    let expected_line = diff_hunk
+        .lines()
+        .next_back()
+        .and_then(|last| last.strip_prefix(['+', ' ']))
+        .map(str::to_string);
+
+    parse_suggestions(body)
+        .into_iter()
+        .filter(|block| block.lines_above < first)
+        .map(|block| Suggestion {
+            comment_id: *id,
+            path: path.clone(),
+            start_line: first - block.lines_above,
+            end_line: line + block.lines_below,
+            line,
+            replacement: block.replacement,
+            expected_line: expected_line.clone(),
+        })
+        .collect()
+}
+
+/// Replace the lines of `content` a suggestion covers, keeping the file's line endings
+pub fn apply_to_content(content: &str, suggestion: &Suggestion) -> Result<String, SuggestionError> {
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let trailing_newline = content.ends_with('\n');
+    let mut lines: Vec<&str> = content.lines().collect();
+
+    let last = suggestion.end_line as usize;
+    if suggestion.start_line == 0 || last > lines.len() {
+        return Err(SuggestionError::OutOfRange {
+            path: suggestion.path.clone(),
+            line: suggestion.end_line,
+        });
+    }
+    if let Some(expected) = &suggestion.expected_line
+        && lines[suggestion.line as usize - 1].trim_end() != expected.trim_end()
+    {
+        return Err(SuggestionError::Outdated {
+            path: suggestion.path.clone(),
+            line: suggestion.line,
+        });
+    }
+
+    let replacement: Vec<&str> = suggestion.replacement.lines().collect();
+    lines.splice(suggestion.start_line as usize - 1..last, replacement);
+    let mut result = lines.join(newline);
+    if trailing_newline && !lines.is_empty() {
+        result.push_str(newline);
+    }
+    Ok(result)
+}
+
+/// Apply one suggestion to the worktree and commit it; false when the file already read as
+/// suggested
+pub fn apply_suggestion(
+    git: &GitService,
+    worktree_path: &Path,
+    suggestion: &Suggestion,
+    message: &str,
+) -> Result<bool, SuggestionError> {
+    apply_suggestions(
+        git,
+        worktree_path,
+        std::slice::from_ref(suggestion),
+        message,
+    )
+}
+
+/// Apply suggestions to the worktree and commit them together, along with anything else left
+/// uncommitted there. Nothing is written unless every suggestion applies.
+pub fn apply_suggestions(
+    git: &GitService,
+    worktree_path: &Path,
+    suggestions: &[Suggestion],
+    message: &str,
+) -> Result<bool, SuggestionError> {
+    let mut by_path: BTreeMap<&str, Vec<&Suggestion>> = BTreeMap::new();
+    for suggestion in suggestions {
+        by_path
+            .entry(&suggestion.path)
+            .or_default()
+            .push(suggestion);
+    }
+
+    let mut updated = Vec::new();
+    for (path, mut suggestions) in by_path {
+        let relative = Path::new(path);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(SuggestionError::InvalidPath(path.to_string()));
+        }
+        // Bottom-up, so replacing a range never shifts the lines of the ones still to apply
+        suggestions.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.start_line));
+        if suggestions
+            .windows(2)
+            .any(|pair| pair[1].end_line >= pair[0].start_line)
+        {
+            return Err(SuggestionError::Overlapping {
+                path: path.to_string(),
+            });
+        }
+
+        let file_path = worktree_path.join(relative);
+        let mut content = std::fs::read_to_string(&file_path)?;
+        for suggestion in suggestions {
+            content = apply_to_content(&content, suggestion)?;
+        }
+        updated.push((file_path, content));
+    }
+
+    for (file_path, content) in updated {
+        std::fs::write(file_path, content)?;
+    }
+    Ok(git.commit(worktree_path, message)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn review(body: &str, start_line: Option<i64>, line: i64, diff_hunk: &str) -> UnifiedComment {
+        UnifiedComment::Review {
+            id: 7,
+            author: "reviewer".to_string(),
+            author_association: "MEMBER".to_string(),
+            body: body.to_string(),
+            created_at: Utc::now(),
+            url: String::new(),
+            path: "src/lib.rs".to_string(),
+            start_line,
+            line: Some(line),
+            diff_hunk: diff_hunk.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_suggestion_blocks_only() {
+        let body = "Nit:\n```suggestion\nlet total = 0;\n```\n\n```rust\nignored();\n```\n\
+                    ```suggestion:-1+2\na\nb\n```";
+        assert_eq!(
+            parse_suggestions(body),
+            vec![
+                SuggestionBlock {
+                    lines_above: 0,
+                    lines_below: 0,
+                    replacement: "let total = 0;".to_string(),
+                },
+                SuggestionBlock {
+                    lines_above: 1,
+                    lines_below: 2,
+                    replacement: "a\nb".to_string(),
+                },
+            ]
+        );
+        assert!(parse_suggestions("```suggestion\nnever closed").is_empty());
+    }
+
+    #[test]
+    fn empty_suggestion_deletes_the_lines() {
+        let blocks = parse_suggestions("```suggestion\n```");
+        assert_eq!(blocks[0].replacement, "");
+
+        let comment = review("```suggestion\n```", None, 2, "");
+        let suggestion = &suggestions_from_comment(&comment)[0];
+        assert_eq!(apply_to_content("a\nb\nc\n", suggestion).unwrap(), "a\nc\n");
+    }
+
+    #[test]
+    fn multi_line_comments_replace_their_whole_range() {
+        let comment = review(
+            "```suggestion\nx\n```",
+            Some(2),
+            3,
+            "@@ -1,3 +1,3 @@\n a\n+b\n+c",
+        );
+        let suggestion = &suggestions_from_comment(&comment)[0];
+        assert_eq!((suggestion.start_line, suggestion.end_line), (2, 3));
+        assert_eq!(
+            apply_to_content("a\nb\nc\nd\n", suggestion).unwrap(),
+            "a\nx\nd\n"
+        );
+    }
+
+    #[test]
+    fn changed_lines_are_not_overwritten() {
+        let comment = review("```suggestion\nx\n```", None, 2, "@@ -1,2 +1,2 @@\n a\n+b");
+        let suggestion = &suggestions_from_comment(&comment)[0];
+        assert!(matches!(
+            apply_to_content("a\nchanged\n", suggestion),
+            Err(SuggestionError::Outdated { line: 2, .. })
+        ));
+        assert!(matches!(
+            apply_to_content("a\n", suggestion),
+            Err(SuggestionError::OutOfRange { .. })
+        ));
+    }
+}
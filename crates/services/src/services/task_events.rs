@@ -0,0 +1,522 @@
+//! The task event log.
+//!
+//! Every change to a task is recorded as an immutable [`TaskEvent`] with who made it and the task
+//! before and after. The activity feed of a task and the audit log are read from the log, and
+//! shared tasks are pushed to the remote by a [`PUBLISH_TASK_EVENT_JOB`] queued for each event, so
+//! code that changes a task only has to record what it did instead of publishing it by hand.
+//!
+//! A task that moves to review marks its open draft MRs/PRs ready for review through a
+//! [`READY_PRS_FOR_REVIEW_JOB`], whichever way it was moved.
+//!
+//! Recent changes can be undone and redone. An undo or redo is recorded as one more update that
+//! points at the change it reverts or reapplies, so the undo and redo stacks of a task are rebuilt
+//! from its log rather than kept in memory.
+
+use chrono::{DateTime, Duration, Utc};
+use db::{
+    DBService,
+    models::{
+        merge::Merge,
+        repo::Repo,
+        task::{Task, TaskStatus},
+        task_event::{CreateTaskEvent, TaskEvent, TaskEventKind, TaskEventSource, TaskSnapshot},
+    },
+};
+use serde_json::{Value, json};
+use sqlx::{SqliteConnection, types::Json};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::services::{
+    git_provider::{self, ProviderError},
+    jobs::JobQueue,
+    share::{ShareError, SharePublisher},
+};
+
+/// Job that pushes the task of an event to the remote, with the `event_id`
+pub const PUBLISH_TASK_EVENT_JOB: &str = "task_events.publish";
+
+/// Job that marks the open MRs/PRs of a task that moved to review ready for review, with the
+/// `task_id`
+pub const READY_PRS_FOR_REVIEW_JOB: &str = "task_events.ready_prs_for_review";
+
+/// How long a change can be undone, or an undo redone
+pub const UNDO_WINDOW_MINUTES: i64 = 10;
+
+#[derive(Debug, Error)]
+pub enum UndoError {
+    #[error("there is no change to undo")]
+    NothingToUndo,
+    #[error("there is no undone change to redo")]
+    NothingToRedo,
+    #[error(
+        "changes can only be undone or redone within {} minutes",
+        UNDO_WINDOW_MINUTES
+    )]
+    Expired,
+    #[error("the task was changed since")]
+    Stale,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Who made a change to a task
+#[derive(Debug, Clone)]
+pub struct TaskActor {
+    pub source: TaskEventSource,
+    pub name: Option<String>,
+}
+
+impl TaskActor {
+    pub fn user() -> Self {
+        Self {
+            source: TaskEventSource::User,
+            name: None,
+        }
+    }
+
+    /// An automation rule or plugin, e.g. `rule:Close merged`
+    pub fn automation(name: impl Into<String>) -> Self {
+        Self {
+            source: TaskEventSource::Automation,
+            name: Some(name.into()),
+        }
+    }
+
+    /// A provider callback or poller, e.g. `pr_monitor`
+    pub fn webhook(name: impl Into<String>) -> Self {
+        Self {
+            source: TaskEventSource::Webhook,
+            name: Some(name.into()),
+        }
+    }
+
+    /// The app itself, e.g. `execution` when an attempt finishes
+    pub fn system(name: impl Into<String>) -> Self {
+        Self {
+            source: TaskEventSource::System,
+            name: Some(name.into()),
+        }
+    }
+
+    /// An integration calling the API with the key of a service account, e.g.
+    /// `service_account:scheduler`
+    pub fn service_account(name: &str) -> Self {
+        Self {
+            source: TaskEventSource::Automation,
+            name: Some(format!("service_account:{name}")),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TaskEventLog {
+    db: DBService,
+    jobs: JobQueue,
+}
+
+impl TaskEventLog {
+    pub fn new(db: DBService, jobs: JobQueue) -> Self {
+        Self { db, jobs }
+    }
+
+    pub async fn created(&self, task: &Task, actor: &TaskActor) -> Result<TaskEvent, sqlx::Error> {
+        let event = new_event(task, TaskEventKind::Created, actor, None, Some(task.into()));
+        self.record(task, event).await
+    }
+
+    /// Record the change from `before` to `after`; nothing is recorded when no field changed
+    pub async fn updated(
+        &self,
+        before: &Task,
+        after: &Task,
+        actor: &TaskActor,
+    ) -> Result<Option<TaskEvent>, sqlx::Error> {
+        let mut conn = self.db.pool.acquire().await?;
+        self.updated_in(&mut conn, before, after, actor).await
+    }
+
+    /// Like [`Self::updated`], as part of the transaction that made the change
+    pub async fn updated_in(
+        &self,
+        conn: &mut SqliteConnection,
+        before: &Task,
+        after: &Task,
+        actor: &TaskActor,
+    ) -> Result<Option<TaskEvent>, sqlx::Error> {
+        let (before_snapshot, after_snapshot) = (TaskSnapshot::from(before), after.into());
+        if before_snapshot == after_snapshot {
+            return Ok(None);
+        }
+        let event = new_event(
+            after,
+            TaskEventKind::Updated,
+            actor,
+            Some(before_snapshot),
+            Some(after_snapshot),
+        );
+        let event = self.record_in(&mut *conn, after, event).await?;
+
+        if before.status != TaskStatus::InReview && after.status == TaskStatus::InReview {
+            self.jobs
+                .enqueue_in(
+                    conn,
+                    READY_PRS_FOR_REVIEW_JOB,
+                    json!({ "task_id": after.id }),
+                )
+                .await?;
+        }
+        Ok(Some(event))
+    }
+
+    pub async fn deleted(&self, task: &Task, actor: &TaskActor) -> Result<TaskEvent, sqlx::Error> {
+        let event = new_event(task, TaskEventKind::Deleted, actor, Some(task.into()), None);
+        self.record(task, event).await
+    }
+
+    /// Move a task to `status` and record the change. None if the task does not exist.
+    pub async fn set_status(
+        &self,
+        task_id: Uuid,
+        status: TaskStatus,
+        actor: &TaskActor,
+    ) -> Result<Option<Task>, sqlx::Error> {
+        let pool = &self.db.pool;
+        let Some(before) = Task::find_by_id(pool, task_id).await? else {
+            return Ok(None);
+        };
+        Task::update_status(pool, task_id, status).await?;
+        let Some(after) = Task::find_by_id(pool, task_id).await? else {
+            return Ok(None);
+        };
+        self.updated(&before, &after, actor).await?;
+        Ok(Some(after))
+    }
+
+    /// Revert the last change of a task that has not been undone yet
+    pub async fn undo(&self, task_id: Uuid, actor: &TaskActor) -> Result<Task, UndoError> {
+        self.step(task_id, actor, Step::Undo).await
+    }
+
+    /// Reapply the last undone change of a task, if nothing else changed it since
+    pub async fn redo(&self, task_id: Uuid, actor: &TaskActor) -> Result<Task, UndoError> {
+        self.step(task_id, actor, Step::Redo).await
+    }
+
+    async fn step(&self, task_id: Uuid, actor: &TaskActor, step: Step) -> Result<Task, UndoError> {
+        let pool = &self.db.pool;
+        let current = Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let mut events = TaskEvent::find_by_task_id(pool, task_id).await?;
+        events.reverse();
+        let history = History::replay(&events);
+
+        let (entry, nothing) = match step {
+            Step::Undo => (history.undo.last(), UndoError::NothingToUndo),
+            Step::Redo => (history.redo.last(), UndoError::NothingToRedo),
+        };
+        let Some(entry) = entry else {
+            return Err(nothing);
+        };
+        if Utc::now() - entry.at > Duration::minutes(UNDO_WINDOW_MINUTES) {
+            return Err(UndoError::Expired);
+        }
+        let change = entry.change;
+        let (from, to) = match step {
+            Step::Undo => (&change.after, &change.before),
+            Step::Redo => (&change.before, &change.after),
+        };
+        let (Some(Json(from)), Some(Json(to))) = (from, to) else {
+            return Err(nothing);
+        };
+        if !has_fields_of(&current, from) {
+            return Err(UndoError::Stale);
+        }
+
+        let restored = Task::update(
+            pool,
+            current.id,
+            current.project_id,
+            to.title.clone(),
+            to.description.clone(),
+            to.status.clone(),
+            to.parent_workspace_id,
+            to.due_at,
+            Some(current.updated_at),
+        )
+        .await?
+        .ok_or(UndoError::Stale)?;
+
+        let mut event = new_event(
+            &restored,
+            TaskEventKind::Updated,
+            actor,
+            Some((&current).into()),
+            Some((&restored).into()),
+        );
+        match step {
+            Step::Undo => event.undo_of = Some(change.id),
+            Step::Redo => event.redo_of = Some(change.id),
+        }
+        self.record(&restored, event).await?;
+        Ok(restored)
+    }
+
+    async fn record(&self, task: &Task, data: CreateTaskEvent) -> Result<TaskEvent, sqlx::Error> {
+        let mut conn = self.db.pool.acquire().await?;
+        self.record_in(&mut conn, task, data).await
+    }
+
+    async fn record_in(
+        &self,
+        conn: &mut SqliteConnection,
+        task: &Task,
+        data: CreateTaskEvent,
+    ) -> Result<TaskEvent, sqlx::Error> {
+        let event = TaskEvent::create(&mut *conn, &data).await?;
+
+        // Deleting a shared task is a request to the remote that has to succeed first, so only
+        // updates are published from the log
+        if data.kind == TaskEventKind::Updated && task.shared_task_id.is_some() {
+            self.jobs
+                .enqueue_in(
+                    conn,
+                    PUBLISH_TASK_EVENT_JOB,
+                    json!({ "event_id": event.id }),
+                )
+                .await?;
+        }
+        Ok(event)
+    }
+
+    /// Run a [`PUBLISH_TASK_EVENT_JOB`] job; without a publisher there is nowhere to push to
+    pub async fn publish_job(
+        db: &DBService,
+        publisher: Option<&SharePublisher>,
+        payload: Value,
+    ) -> anyhow::Result<()> {
+        let Some(publisher) = publisher else {
+            return Ok(());
+        };
+        let event_id: Uuid = serde_json::from_value(payload["event_id"].clone())?;
+        let Some(event) = TaskEvent::find_by_id(&db.pool, event_id).await? else {
+            return Ok(());
+        };
+        match publisher.update_shared_task_by_id(event.task_id).await {
+            // Deleted since; the deletion is pushed by whoever deleted it
+            Err(ShareError::TaskNotFound(_)) => Ok(()),
+            result => Ok(result?),
+        }
+    }
+
+    /// Run a [`READY_PRS_FOR_REVIEW_JOB`] job. MRs/PRs that are no drafts stay as they are, and
+    /// a provider that failed only leaves its MR/PR a draft.
+    pub async fn ready_prs_for_review_job(db: &DBService, payload: Value) -> anyhow::Result<()> {
+        let task_id: Uuid = serde_json::from_value(payload["task_id"].clone())?;
+        for pr_merge in Merge::find_open_prs_by_task_id(&db.pool, task_id).await? {
+            let Some(repo) = Repo::find_by_id(&db.pool, pr_merge.repo_id).await? else {
+                continue;
+            };
+            let result = async {
+                let provider = git_provider::create_provider(&repo.path)?;
+                let (_, repo_id) = git_provider::detect_provider(&repo.path)?;
+                provider
+                    .set_draft(&repo_id, pr_merge.pr_info.number as u64, false)
+                    .await
+            }
+            .await;
+            match result {
+                Ok(_) | Err(ProviderError::NotSupported { .. }) => {}
+                Err(e) => tracing::warn!(
+                    "Failed to mark {} ready for review: {}",
+                    pr_merge.pr_info.url,
+                    e
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn new_event(
+    task: &Task,
+    kind: TaskEventKind,
+    actor: &TaskActor,
+    before: Option<TaskSnapshot>,
+    after: Option<TaskSnapshot>,
+) -> CreateTaskEvent {
+    CreateTaskEvent {
+        task_id: task.id,
+        project_id: task.project_id,
+        kind,
+        source: actor.source,
+        actor: actor.name.clone(),
+        before,
+        after,
+        undo_of: None,
+        redo_of: None,
+    }
+}
+
+/// Whether the task still has the fields an undo or redo starts from
+fn has_fields_of(task: &Task, snapshot: &TaskSnapshot) -> bool {
+    task.title == snapshot.title
+        && task.description == snapshot.description
+        && task.status == snapshot.status
+        && task.parent_workspace_id == snapshot.parent_workspace_id
+        && task.due_at == snapshot.due_at
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Undo,
+    Redo,
+}
+
+/// A change on the undo or redo stack of a task, and when it was put there
+#[derive(Debug)]
+struct StackEntry<'a> {
+    change: &'a TaskEvent,
+    at: DateTime<Utc>,
+}
+
+/// The undo and redo stacks of a task, most recent last
+#[derive(Debug, Default)]
+struct History<'a> {
+    undo: Vec<StackEntry<'a>>,
+    redo: Vec<StackEntry<'a>>,
+}
+
+impl<'a> History<'a> {
+    /// Rebuild the stacks from the events of a task, oldest first
+    fn replay(events: &'a [TaskEvent]) -> Self {
+        let mut history = Self::default();
+        for event in events {
+            if event.kind != TaskEventKind::Updated {
+                // Nothing before a task was created can be undone
+                history = Self::default();
+                continue;
+            }
+            let undone = event
+                .undo_of
+                .and_then(|id| history.undo.pop_if(|entry| entry.change.id == id));
+            if let Some(entry) = undone {
+                history.redo.push(StackEntry {
+                    change: entry.change,
+                    at: event.created_at,
+                });
+                continue;
+            }
+            let redone = event
+                .redo_of
+                .and_then(|id| history.redo.pop_if(|entry| entry.change.id == id));
+            if let Some(entry) = redone {
+                history.undo.push(StackEntry {
+                    change: entry.change,
+                    at: event.created_at,
+                });
+                continue;
+            }
+            history.undo.push(StackEntry {
+                change: event,
+                at: event.created_at,
+            });
+            history.redo.clear();
+        }
+        history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: TaskEventKind, minute: i64) -> TaskEvent {
+        TaskEvent {
+            id: Uuid::new_v4(),
+            task_id: Uuid::nil(),
+            project_id: Uuid::nil(),
+            kind,
+            source: TaskEventSource::User,
+            actor: None,
+            before: None,
+            after: None,
+            undo_of: None,
+            redo_of: None,
+            created_at: DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(minute),
+        }
+    }
+
+    fn undo(of: &TaskEvent, minute: i64) -> TaskEvent {
+        TaskEvent {
+            undo_of: Some(of.id),
+            ..event(TaskEventKind::Updated, minute)
+        }
+    }
+
+    fn redo(of: &TaskEvent, minute: i64) -> TaskEvent {
+        TaskEvent {
+            redo_of: Some(of.id),
+            ..event(TaskEventKind::Updated, minute)
+        }
+    }
+
+    fn ids(stack: &[StackEntry]) -> Vec<Uuid> {
+        stack.iter().map(|entry| entry.change.id).collect()
+    }
+
+    #[test]
+    fn undo_moves_the_last_change_to_the_redo_stack() {
+        let first = event(TaskEventKind::Updated, 1);
+        let second = event(TaskEventKind::Updated, 2);
+        let events = vec![first.clone(), second.clone(), undo(&second, 3)];
+
+        let history = History::replay(&events);
+
+        assert_eq!(ids(&history.undo), vec![first.id]);
+        assert_eq!(ids(&history.redo), vec![second.id]);
+        assert_eq!(history.redo[0].at, events[2].created_at);
+    }
+
+    #[test]
+    fn undos_walk_back_and_redos_walk_forward() {
+        let first = event(TaskEventKind::Updated, 1);
+        let second = event(TaskEventKind::Updated, 2);
+        let events = vec![
+            first.clone(),
+            second.clone(),
+            undo(&second, 3),
+            undo(&first, 4),
+            redo(&first, 5),
+        ];
+
+        let history = History::replay(&events);
+
+        assert_eq!(ids(&history.undo), vec![first.id]);
+        assert_eq!(ids(&history.redo), vec![second.id]);
+        assert_eq!(history.undo[0].at, events[4].created_at);
+    }
+
+    #[test]
+    fn a_new_change_clears_the_redo_stack() {
+        let first = event(TaskEventKind::Updated, 1);
+        let second = event(TaskEventKind::Updated, 2);
+        let events = vec![first.clone(), undo(&first, 2), second.clone()];
+
+        let history = History::replay(&events);
+
+        assert_eq!(ids(&history.undo), vec![second.id]);
+        assert!(history.redo.is_empty());
+    }
+
+    #[test]
+    fn creation_is_not_undoable() {
+        let events = vec![event(TaskEventKind::Created, 1)];
+
+        let history = History::replay(&events);
+
+        assert!(history.undo.is_empty());
+        assert!(history.redo.is_empty());
+    }
+}
@@ -0,0 +1,293 @@
+//! Per-executor concurrency and start-rate limits.
+//!
+//! Coding agent runs acquire a slot before their process is spawned. When an executor is at its
+//! limit the run stays queued, still marked as running, until a slot frees up or the rate window
+//! moves on. Agent CLIs make their own API calls, so the rate limit counts agent runs started per
+//! minute rather than individual provider requests.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Notify, RwLock};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::config::Config;
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Waiters re-check at least this often so config changes take effect
+const RECHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct ExecutorLimit {
+    pub executor: BaseCodingAgent,
+    /// Limit one variant; `None` counts every variant of the executor together
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// Runs allowed at the same time
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+    /// Runs allowed to start per minute
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+}
+
+impl ExecutorLimit {
+    pub fn applies_to(&self, profile: &ExecutorProfileId) -> bool {
+        self.executor == profile.executor
+            && (self.variant.is_none() || self.variant == profile.variant)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_concurrent == Some(0) || self.requests_per_minute == Some(0) {
+            return Err(format!(
+                "Limits for {} must allow at least one run",
+                self.executor
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Why a run cannot start yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blocked {
+    Concurrency,
+    /// The start-rate window frees up a slot at this instant
+    Rate(Instant),
+}
+
+/// The first limit that keeps `profile` from starting now, if any
+pub fn blocked(
+    limits: &[ExecutorLimit],
+    profile: &ExecutorProfileId,
+    running: &[&ExecutorProfileId],
+    starts: &VecDeque<(Instant, ExecutorProfileId)>,
+    now: Instant,
+) -> Option<Blocked> {
+    limits
+        .iter()
+        .filter(|limit| limit.applies_to(profile))
+        .find_map(|limit| {
+            if let Some(max) = limit.max_concurrent {
+                let count = running.iter().filter(|p| limit.applies_to(p)).count();
+                if count >= max as usize {
+                    return Some(Blocked::Concurrency);
+                }
+            }
+            let rpm = limit.requests_per_minute? as usize;
+            let recent: Vec<Instant> = starts
+                .iter()
+                .filter(|(at, p)| now.duration_since(*at) < RATE_WINDOW && limit.applies_to(p))
+                .map(|(at, _)| *at)
+                .collect();
+            (recent.len() >= rpm).then(|| Blocked::Rate(recent[recent.len() - rpm] + RATE_WINDOW))
+        })
+}
+
+/// A coding agent run waiting for a slot of its executor
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ThrottledExecution {
+    pub execution_process_id: Uuid,
+    pub executor_profile_id: ExecutorProfileId,
+    pub waiting_since: DateTime<Utc>,
+}
+
+#[derive(Default)]
+struct ThrottleState {
+    running: HashMap<Uuid, ExecutorProfileId>,
+    starts: VecDeque<(Instant, ExecutorProfileId)>,
+    waiting: HashMap<Uuid, ThrottledExecution>,
+}
+
+impl ThrottleState {
+    fn try_acquire(
+        &mut self,
+        execution_id: Uuid,
+        profile: &ExecutorProfileId,
+        limits: &[ExecutorLimit],
+    ) -> Result<(), Blocked> {
+        let now = Instant::now();
+        while self
+            .starts
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) >= RATE_WINDOW)
+        {
+            self.starts.pop_front();
+        }
+        let running: Vec<&ExecutorProfileId> = self.running.values().collect();
+        if let Some(blocked) = blocked(limits, profile, &running, &self.starts, now) {
+            return Err(blocked);
+        }
+        self.running.insert(execution_id, profile.clone());
+        self.starts.push_back((now, profile.clone()));
+        Ok(())
+    }
+}
+
+/// Slots held by running coding agent processes
+#[derive(Clone, Default)]
+pub struct ExecutorThrottle {
+    state: Arc<Mutex<ThrottleState>>,
+    released: Arc<Notify>,
+}
+
+impl ExecutorThrottle {
+    /// Take a slot for an execution if its executor is under its limits
+    pub fn try_acquire(
+        &self,
+        execution_id: Uuid,
+        profile: &ExecutorProfileId,
+        limits: &[ExecutorLimit],
+    ) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .try_acquire(execution_id, profile, limits)
+            .is_ok()
+    }
+
+    /// Wait for a slot, re-reading the limits from `config` on every check
+    pub async fn acquire(
+        &self,
+        execution_id: Uuid,
+        profile: &ExecutorProfileId,
+        config: &RwLock<Config>,
+    ) {
+        self.state.lock().unwrap().waiting.insert(
+            execution_id,
+            ThrottledExecution {
+                execution_process_id: execution_id,
+                executor_profile_id: profile.clone(),
+                waiting_since: Utc::now(),
+            },
+        );
+        loop {
+            let released = self.released.notified();
+            let limits = config.read().await.executor_limits.clone();
+            let mut state = self.state.lock().unwrap();
+            let wait = match state.try_acquire(execution_id, profile, &limits) {
+                Ok(()) => {
+                    state.waiting.remove(&execution_id);
+                    return;
+                }
+                Err(Blocked::Rate(until)) => until
+                    .saturating_duration_since(Instant::now())
+                    .min(RECHECK_INTERVAL),
+                Err(Blocked::Concurrency) => RECHECK_INTERVAL,
+            };
+            drop(state);
+            let _ = tokio::time::timeout(wait, released).await;
+        }
+    }
+
+    /// Runs waiting for a slot, longest-waiting first
+    pub fn waiting(&self) -> Vec<ThrottledExecution> {
+        let mut waiting: Vec<ThrottledExecution> = self
+            .state
+            .lock()
+            .unwrap()
+            .waiting
+            .values()
+            .cloned()
+            .collect();
+        waiting.sort_by_key(|execution| execution.waiting_since);
+        waiting
+    }
+
+    /// Free the slot of a finished or stopped execution; a no-op if it held none
+    pub fn release(&self, execution_id: &Uuid) {
+        let mut state = self.state.lock().unwrap();
+        state.waiting.remove(execution_id);
+        if state.running.remove(execution_id).is_some() {
+            self.released.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(executor: BaseCodingAgent, variant: Option<&str>) -> ExecutorProfileId {
+        ExecutorProfileId {
+            executor,
+            variant: variant.map(str::to_string),
+        }
+    }
+
+    fn limit(max_concurrent: Option<u32>, requests_per_minute: Option<u32>) -> ExecutorLimit {
+        ExecutorLimit {
+            executor: BaseCodingAgent::ClaudeCode,
+            variant: None,
+            max_concurrent,
+            requests_per_minute,
+        }
+    }
+
+    #[test]
+    fn concurrency_counts_every_variant_of_the_executor() {
+        let throttle = ExecutorThrottle::default();
+        let limits = [limit(Some(2), None)];
+        let claude = profile(BaseCodingAgent::ClaudeCode, None);
+        let opus = profile(BaseCodingAgent::ClaudeCode, Some("OPUS"));
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        assert!(throttle.try_acquire(a, &claude, &limits));
+        assert!(throttle.try_acquire(b, &opus, &limits));
+        assert!(!throttle.try_acquire(c, &claude, &limits));
+        // Other executors are not limited
+        assert!(throttle.try_acquire(c, &profile(BaseCodingAgent::Codex, None), &limits));
+
+        throttle.release(&a);
+        assert!(throttle.try_acquire(Uuid::new_v4(), &claude, &limits));
+    }
+
+    #[test]
+    fn start_rate_blocks_until_the_window_moves_on() {
+        let claude = profile(BaseCodingAgent::ClaudeCode, None);
+        let now = Instant::now();
+        let first = now - Duration::from_secs(50);
+        let starts = VecDeque::from([
+            (first, claude.clone()),
+            (now - Duration::from_secs(10), claude.clone()),
+        ]);
+        let limits = [limit(None, Some(2))];
+        assert_eq!(
+            blocked(&limits, &claude, &[], &starts, now),
+            Some(Blocked::Rate(first + RATE_WINDOW))
+        );
+        let limits = [limit(None, Some(3))];
+        assert_eq!(blocked(&limits, &claude, &[], &starts, now), None);
+    }
+
+    #[test]
+    fn variant_limits_only_count_that_variant() {
+        let limits = [ExecutorLimit {
+            variant: Some("LOCAL".to_string()),
+            ..limit(Some(1), None)
+        }];
+        let local = profile(BaseCodingAgent::ClaudeCode, Some("LOCAL"));
+        let default = profile(BaseCodingAgent::ClaudeCode, None);
+        let now = Instant::now();
+        assert_eq!(
+            blocked(&limits, &default, &[&default], &VecDeque::new(), now),
+            None
+        );
+        assert_eq!(
+            blocked(&limits, &local, &[&local], &VecDeque::new(), now),
+            Some(Blocked::Concurrency)
+        );
+        assert_eq!(
+            blocked(&limits, &default, &[&local], &VecDeque::new(), now),
+            None
+        );
+    }
+}
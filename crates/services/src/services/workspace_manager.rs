@@ -1,9 +1,11 @@
 use std::path::{Path, PathBuf};
 
 use db::models::{repo::Repo, workspace::Workspace as DbWorkspace};
+use git2::{BranchType, Repository};
 use sqlx::{Pool, Sqlite};
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
+use utils::path::to_long_path;
 use uuid::Uuid;
 
 use super::worktree_manager::{WorktreeCleanup, WorktreeError, WorktreeManager};
@@ -84,12 +86,16 @@ impl WorkspaceManager {
                 worktree_path.display()
             );
 
+            // Workspaces adopted from an existing branch start from that branch as it is
+            let branch_exists = Repository::open(&input.repo.path)
+                .is_ok_and(|repo| repo.find_branch(branch_name, BranchType::Local).is_ok());
+
             match WorktreeManager::create_worktree(
                 &input.repo.path,
                 branch_name,
                 &worktree_path,
                 &input.target_branch,
-                true,
+                !branch_exists,
             )
             .await
             {
@@ -192,7 +198,7 @@ impl WorkspaceManager {
 
         // Remove the workspace directory itself
         if workspace_dir.exists()
-            && let Err(e) = tokio::fs::remove_dir_all(workspace_dir).await
+            && let Err(e) = tokio::fs::remove_dir_all(to_long_path(workspace_dir)).await
         {
             debug!(
                 "Could not remove workspace directory {}: {}",
@@ -359,7 +365,7 @@ impl WorkspaceManager {
                     workspace_dir.display(),
                     e
                 );
-                return tokio::fs::remove_dir_all(workspace_dir)
+                return tokio::fs::remove_dir_all(to_long_path(workspace_dir))
                     .await
                     .map_err(WorkspaceError::Io);
             }
@@ -375,7 +381,7 @@ impl WorkspaceManager {
         }
 
         if workspace_dir.exists()
-            && let Err(e) = tokio::fs::remove_dir_all(workspace_dir).await
+            && let Err(e) = tokio::fs::remove_dir_all(to_long_path(workspace_dir)).await
         {
             debug!(
                 "Could not remove workspace directory {}: {}",
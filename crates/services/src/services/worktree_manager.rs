@@ -8,7 +8,10 @@ use std::{
 use git2::{Error as GitError, Repository};
 use thiserror::Error;
 use tracing::{debug, info, trace};
-use utils::{path::normalize_macos_private_alias, shell::resolve_executable_path};
+use utils::{
+    path::{paths_equivalent, to_long_path},
+    shell::resolve_executable_path,
+};
 
 use super::git::{GitService, GitServiceError};
 
@@ -253,9 +256,9 @@ impl WorktreeManager {
             let gitdir_path = entry.path().join("gitdir");
             if gitdir_path.exists()
                 && let Ok(gitdir_content) = fs::read_to_string(&gitdir_path)
-                && normalize_macos_private_alias(Path::new(gitdir_content.trim()))
+                && Path::new(gitdir_content.trim())
                     .parent()
-                    .map(|p| p == worktree_path)
+                    .map(|p| paths_equivalent(p, worktree_path))
                     .unwrap_or(false)
             {
                 return Ok(Some(entry.file_name().to_string_lossy().to_string()));
@@ -292,7 +295,7 @@ impl WorktreeManager {
                 "Removing existing worktree directory: {}",
                 worktree_path.display()
             );
-            std::fs::remove_dir_all(worktree_path).map_err(WorktreeError::Io)?;
+            std::fs::remove_dir_all(to_long_path(worktree_path)).map_err(WorktreeError::Io)?;
         }
 
         // Step 4: Good-practice to clean up any other stale admin entries
@@ -381,7 +384,8 @@ impl WorktreeManager {
                     // Clean up physical directory if it exists
                     // Needed if previous attempt failed after directory creation
                     if worktree_path.exists() {
-                        std::fs::remove_dir_all(&worktree_path).map_err(WorktreeError::Io)?;
+                        std::fs::remove_dir_all(to_long_path(&worktree_path))
+                            .map_err(WorktreeError::Io)?;
                     }
                     if let Err(e2) = git_service.add_worktree(
                         &git_repo_path,
@@ -572,7 +576,8 @@ impl WorktreeManager {
 
         tokio::task::spawn_blocking(move || -> Result<(), WorktreeError> {
             if worktree_path_owned.exists() {
-                std::fs::remove_dir_all(&worktree_path_owned).map_err(WorktreeError::Io)?;
+                std::fs::remove_dir_all(to_long_path(&worktree_path_owned))
+                    .map_err(WorktreeError::Io)?;
                 info!(
                     "Removed worktree directory: {}",
                     worktree_path_owned.display()
@@ -0,0 +1,953 @@
+//! Git Provider Conformance Tests
+//!
+//! Runs the same checks against every provider that talks to a REST API (GitHub and GitLab with
+//! a token, Bitbucket Cloud and Data Center, Azure DevOps and Gerrit), so they agree on what a
+//! pull request's state is, read every page of a listing, ask for drafts and edits the same way,
+//! publish commit statuses under the same context and classify errors alike. Features a provider
+//! does not support must fail without sending anything.
+//!
+//! The providers talk to a mock HTTP server that answers the way each API does. Each test uses
+//! its own repository, named after its scenario, and the server records the requests per
+//! repository so tests can check what was sent.
+
+use std::{
+    collections::HashSet,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Mutex, OnceLock},
+    thread,
+};
+
+use futures::future::join_all;
+use serde_json::{Value, json};
+use services::services::git_provider::{
+    AzureDevOpsProvider, BitbucketProvider, BitbucketServerProvider, CommitStatusState,
+    CreateMrRequest, GerritProvider, GitHubProvider, GitLabProvider, GitProvider, PrState,
+    ProviderError, RepoIdentifier, UnifiedComment, UpdateMrRequest,
+};
+
+/// Repositories of the mock server, one per scenario; GitLab project ids are their position + 1
+const SCENARIOS: &[&str] = &[
+    "states",
+    "comments",
+    "drafts",
+    "set-draft",
+    "unauthorized",
+    "missing",
+    "rate-limited",
+    "statuses",
+    "update",
+];
+
+/// Comments on every pull request, oldest first, with the time they were written
+const COMMENTS: &[(&str, &str)] = &[
+    ("first", "2025-01-01T00:00:01Z"),
+    ("second", "2025-01-01T00:00:02Z"),
+    ("third", "2025-01-01T00:00:03Z"),
+];
+
+/// Comments on the first page of paginated listings; the rest are on the second
+const FIRST_PAGE: usize = 2;
+
+const MERGED_AT: &str = "2025-01-02T03:04:05Z";
+const MERGE_SHA: &str = "abc123";
+/// Commit of the test merge GitHub and Azure DevOps report for open pull requests
+const TEST_MERGE_SHA: &str = "def456";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Api {
+    GitHub,
+    GitLab,
+    Bitbucket,
+    BitbucketServer,
+    AzureDevOps,
+    Gerrit,
+}
+
+const APIS: [Api; 6] = [
+    Api::GitHub,
+    Api::GitLab,
+    Api::Bitbucket,
+    Api::BitbucketServer,
+    Api::AzureDevOps,
+    Api::Gerrit,
+];
+
+/// Prefix Gerrit puts before JSON responses against cross-site script inclusion
+const GERRIT_XSSI_PREFIX: &str = ")]}'";
+
+#[derive(Debug, Clone)]
+struct Request {
+    api: Api,
+    scenario: String,
+    method: String,
+    /// Path segments after the repository
+    route: Vec<String>,
+    query: String,
+    body: Value,
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: json!({ "message": message }),
+        }
+    }
+}
+
+static REQUESTS: Mutex<Vec<Request>> = Mutex::new(Vec::new());
+/// APIs that have already answered the rate-limited repository with a 429
+static RATE_LIMITED: Mutex<Option<HashSet<&'static str>>> = Mutex::new(None);
+
+/// Address of the mock server, which is started on first use. Every provider created
+/// afterwards talks to it.
+fn server() -> &'static str {
+    static ADDRESS: OnceLock<String> = OnceLock::new();
+    ADDRESS.get_or_init(|| {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        // Set once, before any provider reads them
+        unsafe {
+            std::env::set_var("GITHUB_API_URL", format!("{address}/github"));
+            std::env::set_var("GITHUB_TOKEN", "github-token");
+            std::env::set_var("GITLAB_BASE_URL", format!("{address}/gitlab"));
+            std::env::set_var("GITLAB_TOKEN", "gitlab-token");
+            std::env::set_var("BITBUCKET_API_URL", format!("{address}/bitbucket"));
+            std::env::set_var("BITBUCKET_TOKEN", "bitbucket-token");
+            std::env::remove_var("BITBUCKET_USERNAME");
+            std::env::remove_var("BITBUCKET_APP_PASSWORD");
+            std::env::set_var(
+                "BITBUCKET_SERVER_URL",
+                format!("{address}/bitbucket-server"),
+            );
+            std::env::set_var("BITBUCKET_SERVER_TOKEN", "bitbucket-server-token");
+            std::env::set_var("AZURE_DEVOPS_URL", format!("{address}/azure"));
+            std::env::set_var("AZURE_DEVOPS_TOKEN", "azure-token");
+            std::env::set_var("GERRIT_URL", format!("{address}/gerrit"));
+            std::env::set_var("GERRIT_USERNAME", "gerrit");
+            std::env::set_var("GERRIT_HTTP_PASSWORD", "gerrit-password");
+        }
+        let base = address.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let base = base.clone();
+                thread::spawn(move || serve(stream, &base));
+            }
+        });
+        address
+    })
+}
+
+/// Answer one request and close the connection
+fn serve(mut stream: TcpStream, base: &str) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap();
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let (response, api) = match parse_request(&method, path, query, body) {
+        Some(request) => {
+            let response = respond(&request, base);
+            let api = request.api;
+            REQUESTS.lock().unwrap().push(request);
+            (response, Some(api))
+        }
+        None => (
+            Response::error(404, &format!("No route for {method} {target}")),
+            None,
+        ),
+    };
+
+    let body = match api {
+        Some(Api::Gerrit) if response.status == 200 => {
+            format!("{GERRIT_XSSI_PREFIX}\n{}", response.body)
+        }
+        _ => response.body.to_string(),
+    };
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    stream
+        .write_all(format!("{head}\r\n{body}").as_bytes())
+        .unwrap();
+}
+
+/// Work out the API, repository and route a request is for
+fn parse_request(method: &str, path: &str, query: &str, body: Value) -> Option<Request> {
+    let segments: Vec<String> = path
+        .trim_start_matches('/')
+        .split('/')
+        .map(|segment| segment.replace("%2F", "/"))
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let (api, scenario, route) = match segments.as_slice() {
+        ["github", "repos", "acme", scenario, route @ ..] => (Api::GitHub, *scenario, route),
+        // Mutations name the pull request by its node id
+        ["github", "graphql"] => {
+            let id = body["variables"]["id"].as_str()?;
+            let (scenario, _) = id.strip_prefix("PR_")?.rsplit_once('_')?;
+            (Api::GitHub, scenario, &[][..])
+        }
+        ["gitlab", "api", "v4", "projects", project, route @ ..] => {
+            let scenario = match project.strip_prefix("acme/") {
+                Some(scenario) => scenario,
+                None => *SCENARIOS.get(project.parse::<usize>().ok()?.checked_sub(1)?)?,
+            };
+            (Api::GitLab, scenario, route)
+        }
+        ["bitbucket", "repositories", "acme", scenario, route @ ..] => {
+            (Api::Bitbucket, *scenario, route)
+        }
+        [
+            "bitbucket-server",
+            "rest",
+            "api",
+            "1.0",
+            "projects",
+            "ACME",
+            "repos",
+            scenario,
+            route @ ..,
+        ] => (Api::BitbucketServer, *scenario, route),
+        // Changes are named `<project>~<number>`; the route starts with the number
+        ["gerrit", "a", "changes", change, route @ ..] => {
+            let (project, number) = change.split_once('~')?;
+            let scenario = project.strip_prefix("acme/")?;
+            return Some(Request {
+                api: Api::Gerrit,
+                scenario: scenario.to_string(),
+                method: method.to_string(),
+                route: std::iter::once(number)
+                    .chain(route.iter().copied())
+                    .map(str::to_string)
+                    .collect(),
+                query: query.to_string(),
+                body,
+            });
+        }
+        [
+            "azure",
+            "acme",
+            "app",
+            "_apis",
+            "git",
+            "repositories",
+            scenario,
+            route @ ..,
+        ] => (Api::AzureDevOps, *scenario, route),
+        _ => return None,
+    };
+    Some(Request {
+        api,
+        scenario: scenario.to_string(),
+        method: method.to_string(),
+        route: route.iter().map(|segment| segment.to_string()).collect(),
+        query: query.to_string(),
+        body,
+    })
+}
+
+fn respond(request: &Request, base: &str) -> Response {
+    match request.scenario.as_str() {
+        "unauthorized" => return Response::error(401, "Bad credentials"),
+        "missing" => return Response::error(404, "Not Found"),
+        "rate-limited" => {
+            let mut limited = RATE_LIMITED.lock().unwrap();
+            if limited
+                .get_or_insert_with(HashSet::new)
+                .insert(request.api.prefix())
+            {
+                // Honored by the APIs that read it, the others back off as usual
+                let mut response = Response::error(429, "API rate limit exceeded");
+                response.headers.push(("Retry-After", "0".to_string()));
+                return response;
+            }
+        }
+        _ => {}
+    }
+
+    let route: Vec<&str> = request.route.iter().map(String::as_str).collect();
+    let number = |n: &str| n.parse::<u64>().unwrap();
+    let page = if request.query.contains("page=2") || request.query.contains("start=2") {
+        2
+    } else {
+        1
+    };
+    let scenario = request.scenario.as_str();
+    match (request.api, request.method.as_str(), route.as_slice()) {
+        (Api::GitHub, "GET", ["pulls", n]) => {
+            Response::ok(github_pull(base, scenario, number(n), false))
+        }
+        (Api::GitHub, "PATCH", ["pulls", n]) => {
+            Response::ok(github_pull(base, scenario, number(n), false))
+        }
+        (Api::GitHub, "POST", ["pulls"]) => Response::ok(github_pull(
+            base,
+            scenario,
+            4,
+            request.body["draft"] == json!(true),
+        )),
+        (Api::GitHub, "GET", ["issues", n, "comments"]) => {
+            let mut response = Response::ok(Value::Array(page_of_comments(page, |i, body, at| {
+                json!({
+                    "node_id": format!("IC_{i}"),
+                    "user": { "login": "octocat" },
+                    "author_association": "MEMBER",
+                    "body": body,
+                    "created_at": at,
+                    "html_url": format!("{base}/acme/{scenario}/pull/{n}#issuecomment-{i}"),
+                })
+            })));
+            if page == 1 {
+                response.headers.push((
+                    "Link",
+                    format!(
+                        "<{base}/github/repos/acme/{scenario}/issues/{n}/comments?per_page=100&page=2>; rel=\"next\""
+                    ),
+                ));
+            }
+            response
+        }
+        (Api::GitHub, "GET", ["pulls", _, "comments"]) => Response::ok(json!([])),
+        (Api::GitHub, "POST", []) => Response::ok(json!({ "data": {} })),
+        (Api::GitHub, "POST", ["statuses", _]) => Response::ok(request.body.clone()),
+
+        (Api::GitLab, "GET", []) => {
+            let id = SCENARIOS.iter().position(|s| *s == scenario).unwrap() + 1;
+            Response::ok(json!({ "id": id }))
+        }
+        (Api::GitLab, "GET", ["merge_requests", n]) => {
+            Response::ok(gitlab_merge_request(base, number(n), "Add feature"))
+        }
+        (Api::GitLab, "PUT", ["merge_requests", n]) => {
+            let title = request.body["title"].as_str().unwrap_or("Add feature");
+            Response::ok(gitlab_merge_request(base, number(n), title))
+        }
+        (Api::GitLab, "POST", ["merge_requests"]) => {
+            let title = request.body["title"].as_str().unwrap();
+            Response::ok(gitlab_merge_request(base, 4, title))
+        }
+        (Api::GitLab, "GET", ["merge_requests", _, "notes"]) => {
+            let mut response = Response::ok(Value::Array(page_of_comments(page, |i, body, at| {
+                json!({
+                    "id": i,
+                    "body": body,
+                    "author": { "id": 1, "username": "tanuki", "name": "Tanuki" },
+                    "created_at": at,
+                    "system": false,
+                })
+            })));
+            let next = if page == 1 { "2" } else { "" };
+            response.headers.push(("X-Next-Page", next.to_string()));
+            response
+        }
+        (Api::GitLab, "POST", ["statuses", _]) => Response::ok(request.body.clone()),
+
+        (Api::Bitbucket, "GET", ["pullrequests", n]) => {
+            Response::ok(bitbucket_pull_request(base, number(n)))
+        }
+        (Api::Bitbucket, "POST", ["pullrequests"]) => Response::ok(bitbucket_pull_request(base, 4)),
+        (Api::Bitbucket, "POST", ["commit", _, "statuses", "build"]) => {
+            Response::ok(request.body.clone())
+        }
+        (Api::Bitbucket, "GET", ["pullrequests", n, "comments"]) => {
+            let values = page_of_comments(page, |i, body, at| {
+                json!({
+                    "id": i,
+                    "content": { "raw": body },
+                    "user": { "nickname": "atlassian" },
+                    "created_on": at,
+                    "links": { "html": { "href": format!("{base}/pull-requests/{n}#comment-{i}") } },
+                    "inline": null,
+                })
+            });
+            let next = (page == 1).then(|| {
+                format!(
+                    "{base}/bitbucket/repositories/acme/{scenario}/pullrequests/{n}/comments?sort=created_on&page=2"
+                )
+            });
+            Response::ok(json!({ "values": values, "next": next }))
+        }
+
+        (Api::BitbucketServer, "GET", ["pull-requests", n]) => {
+            Response::ok(bitbucket_server_pull_request(base, number(n)))
+        }
+        (Api::BitbucketServer, "POST", ["pull-requests"]) => {
+            Response::ok(bitbucket_server_pull_request(base, 4))
+        }
+        (Api::BitbucketServer, "GET", ["pull-requests", _, "activities"]) => {
+            let values = page_of_comments(page, |i, body, at| {
+                json!({
+                    "action": "COMMENTED",
+                    "comment": {
+                        "id": i,
+                        "text": body,
+                        "author": { "name": "atlassian" },
+                        "createdDate": millis(at),
+                    },
+                    "commentAnchor": null,
+                })
+            });
+            Response::ok(json!({
+                "values": values,
+                "isLastPage": page == 2,
+                "nextPageStart": (page == 1).then_some(FIRST_PAGE),
+            }))
+        }
+
+        (Api::AzureDevOps, "GET", ["pullrequests", n]) => {
+            Response::ok(azure_pull_request(number(n)))
+        }
+        (Api::AzureDevOps, "POST", ["pullrequests"]) => Response::ok(azure_pull_request(4)),
+        (Api::AzureDevOps, "POST", ["commits", _, "statuses"]) => {
+            Response::ok(request.body.clone())
+        }
+        // Threads are not paginated
+        (Api::AzureDevOps, "GET", ["pullrequests", _, "threads"]) => {
+            let threads: Vec<Value> = (1..=2)
+                .flat_map(|page| {
+                    page_of_comments(page, |i, body, at| {
+                        json!({
+                            "id": i,
+                            "comments": [{
+                                "id": 1,
+                                "author": { "uniqueName": "devops" },
+                                "content": body,
+                                "publishedDate": at,
+                                "commentType": "text",
+                            }],
+                            "threadContext": null,
+                        })
+                    })
+                })
+                .collect();
+            Response::ok(json!({ "value": threads, "count": threads.len() }))
+        }
+
+        (Api::Gerrit, "GET", [n]) => Response::ok(gerrit_change(scenario, number(n))),
+        // Neither messages nor comments are paginated
+        (Api::Gerrit, "GET", [_, "messages"]) => {
+            let messages: Vec<Value> = (1..=2)
+                .flat_map(|page| {
+                    page_of_comments(page, |i, body, at| {
+                        json!({
+                            "id": format!("message{i}"),
+                            "author": { "username": "gerrit" },
+                            "message": body,
+                            "date": gerrit_timestamp(at),
+                        })
+                    })
+                })
+                .collect();
+            Response::ok(Value::Array(messages))
+        }
+        (Api::Gerrit, "GET", [_, "comments"]) => Response::ok(json!({})),
+
+        _ => Response::error(
+            404,
+            &format!("No route for {} {:?}", request.method, request.route),
+        ),
+    }
+}
+
+/// Pull requests 1 and 4 are open, 2 is merged and 3 closed
+fn state_of(number: u64) -> PrState {
+    match number {
+        2 => PrState::Merged,
+        3 => PrState::Closed,
+        _ => PrState::Open,
+    }
+}
+
+/// One page of [`COMMENTS`], built with their 1-based index, body and creation time
+fn page_of_comments(page: usize, comment: impl Fn(usize, &str, &str) -> Value) -> Vec<Value> {
+    let range = if page == 1 {
+        0..FIRST_PAGE
+    } else {
+        FIRST_PAGE..COMMENTS.len()
+    };
+    COMMENTS[range.clone()]
+        .iter()
+        .zip(range)
+        .map(|((body, at), i)| comment(i + 1, body, at))
+        .collect()
+}
+
+fn github_pull(base: &str, scenario: &str, number: u64, draft: bool) -> Value {
+    let state = state_of(number);
+    json!({
+        "number": number,
+        "node_id": format!("PR_{scenario}_{number}"),
+        "html_url": format!("{base}/acme/{scenario}/pull/{number}"),
+        "state": if state == PrState::Open { "open" } else { "closed" },
+        "draft": draft,
+        "merged_at": (state == PrState::Merged).then_some(MERGED_AT),
+        "merge_commit_sha": if state == PrState::Merged { MERGE_SHA } else { TEST_MERGE_SHA },
+        "head": { "sha": "0123abc" },
+    })
+}
+
+fn gitlab_merge_request(base: &str, number: u64, title: &str) -> Value {
+    let state = state_of(number);
+    json!({
+        "iid": number,
+        "title": title,
+        "web_url": format!("{base}/acme/merge_requests/{number}"),
+        "state": match state {
+            PrState::Merged => "merged",
+            PrState::Closed => "closed",
+            _ => "opened",
+        },
+        "draft": title.starts_with("Draft:"),
+        "merged_at": (state == PrState::Merged).then_some(MERGED_AT),
+        "merge_commit_sha": (state == PrState::Merged).then_some(MERGE_SHA),
+    })
+}
+
+fn bitbucket_pull_request(base: &str, number: u64) -> Value {
+    let state = state_of(number);
+    json!({
+        "id": number,
+        "state": match state {
+            PrState::Merged => "MERGED",
+            PrState::Closed => "DECLINED",
+            _ => "OPEN",
+        },
+        "links": { "html": { "href": format!("{base}/pull-requests/{number}") } },
+        "merge_commit": (state == PrState::Merged).then(|| json!({ "hash": MERGE_SHA })),
+        "updated_on": MERGED_AT,
+    })
+}
+
+fn bitbucket_server_pull_request(base: &str, number: u64) -> Value {
+    let state = state_of(number);
+    json!({
+        "id": number,
+        "state": match state {
+            PrState::Merged => "MERGED",
+            PrState::Closed => "DECLINED",
+            _ => "OPEN",
+        },
+        "links": { "self": [{ "href": format!("{base}/pull-requests/{number}") }] },
+        "updatedDate": millis(MERGED_AT),
+        "properties": {
+            "mergeCommit": (state == PrState::Merged).then(|| json!({ "id": MERGE_SHA })),
+        },
+    })
+}
+
+fn gerrit_change(scenario: &str, number: u64) -> Value {
+    let state = state_of(number);
+    json!({
+        "_number": number,
+        "project": format!("acme/{scenario}"),
+        "status": match state {
+            PrState::Merged => "MERGED",
+            PrState::Closed => "ABANDONED",
+            _ => "NEW",
+        },
+        "submitted": (state == PrState::Merged).then(|| gerrit_timestamp(MERGED_AT)),
+        // The patch set under review of open changes
+        "current_revision": if state == PrState::Merged { MERGE_SHA } else { TEST_MERGE_SHA },
+    })
+}
+
+/// Milliseconds since the epoch, as Bitbucket Data Center writes times
+fn millis(at: &str) -> i64 {
+    chrono::DateTime::parse_from_rfc3339(at)
+        .unwrap()
+        .timestamp_millis()
+}
+
+/// Gerrit writes UTC times as `2025-01-02 03:04:05.000000000`
+fn gerrit_timestamp(at: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(at)
+        .unwrap()
+        .format("%Y-%m-%d %H:%M:%S%.9f")
+        .to_string()
+}
+
+fn azure_pull_request(number: u64) -> Value {
+    let state = state_of(number);
+    json!({
+        "pullRequestId": number,
+        "status": match state {
+            PrState::Merged => "completed",
+            PrState::Closed => "abandoned",
+            _ => "active",
+        },
+        "closedDate": (state != PrState::Open).then_some(MERGED_AT),
+        "lastMergeCommit": {
+            "commitId": if state == PrState::Merged { MERGE_SHA } else { TEST_MERGE_SHA },
+        },
+    })
+}
+
+impl Api {
+    fn prefix(self) -> &'static str {
+        match self {
+            Api::GitHub => "github",
+            Api::GitLab => "gitlab",
+            Api::Bitbucket => "bitbucket",
+            Api::BitbucketServer => "bitbucket-server",
+            Api::AzureDevOps => "azure",
+            Api::Gerrit => "gerrit",
+        }
+    }
+
+    fn provider(self) -> Box<dyn GitProvider> {
+        server();
+        match self {
+            Api::GitHub => Box::new(GitHubProvider::new()),
+            Api::GitLab => Box::new(GitLabProvider::new()),
+            Api::Bitbucket => Box::new(BitbucketProvider::new()),
+            Api::BitbucketServer => Box::new(BitbucketServerProvider::new(None)),
+            Api::AzureDevOps => Box::new(AzureDevOpsProvider::new()),
+            // Without a repository to push from, so creating changes is unsupported
+            Api::Gerrit => Box::new(GerritProvider::new(None)),
+        }
+    }
+
+    fn repo(self, scenario: &str) -> RepoIdentifier {
+        match self {
+            Api::GitHub => RepoIdentifier::new_github("acme", scenario),
+            Api::GitLab => RepoIdentifier::new_gitlab("acme", scenario, None),
+            Api::Bitbucket => RepoIdentifier::new_bitbucket("acme", scenario),
+            Api::BitbucketServer => {
+                RepoIdentifier::new_bitbucket_server("ACME", scenario, "bitbucket.example.com")
+            }
+            Api::AzureDevOps => RepoIdentifier::new_azure_devops("acme", "app", scenario),
+            Api::Gerrit => RepoIdentifier::new_gerrit("acme", scenario, "review.example.com"),
+        }
+    }
+
+    /// Whether a write request asks for the pull request to be a draft
+    fn marks_draft(self, request: &Request) -> bool {
+        match self {
+            Api::GitHub if request.route.is_empty() => request.body["query"]
+                .as_str()
+                .is_some_and(|query| query.contains("convertPullRequestToDraft")),
+            Api::GitHub | Api::Bitbucket | Api::BitbucketServer => {
+                request.body["draft"] == json!(true)
+            }
+            // GitLab derives the draft state from the title
+            Api::GitLab => request.body["title"]
+                .as_str()
+                .is_some_and(|title| title.starts_with("Draft:")),
+            Api::AzureDevOps => request.body["isDraft"] == json!(true),
+            Api::Gerrit => unreachable!("Gerrit changes are opened by pushing"),
+        }
+    }
+
+    /// Context and state of a commit status request, in the API's own terms
+    fn commit_status(self, request: &Request) -> (String, String) {
+        let body = &request.body;
+        let context = match self {
+            Api::GitHub => body["context"].as_str().unwrap().to_string(),
+            Api::GitLab => body["name"].as_str().unwrap().to_string(),
+            Api::Bitbucket => body["key"].as_str().unwrap().to_string(),
+            Api::AzureDevOps => format!(
+                "{}/{}",
+                body["context"]["genre"].as_str().unwrap(),
+                body["context"]["name"].as_str().unwrap()
+            ),
+            Api::BitbucketServer | Api::Gerrit => {
+                unreachable!("{self:?} publishes no commit statuses")
+            }
+        };
+        (context, body["state"].as_str().unwrap().to_string())
+    }
+}
+
+/// Requests the mock server received for a scenario's repository on an API
+fn requests(api: Api, scenario: &str) -> Vec<Request> {
+    REQUESTS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|request| request.api == api && request.scenario == scenario)
+        .cloned()
+        .collect()
+}
+
+fn mr_request(draft: bool) -> CreateMrRequest {
+    CreateMrRequest {
+        title: "Add feature".to_string(),
+        body: Some("Adds the feature".to_string()),
+        head_branch: "feature".to_string(),
+        base_branch: "main".to_string(),
+        draft: Some(draft),
+        head_repo: None,
+        milestone: None,
+    }
+}
+
+#[tokio::test]
+async fn pull_request_states_map_alike() {
+    for api in APIS {
+        let provider = api.provider();
+        let repo = api.repo("states");
+        for number in 1..=3 {
+            let info = provider.get_mr_status(&repo, number).await.unwrap();
+            let state = state_of(number);
+            assert_eq!(info.number, number, "{api:?}");
+            assert_eq!(info.state, state, "{api:?} pull request {number}");
+            assert!(!info.url.is_empty(), "{api:?}");
+            if state == PrState::Merged {
+                assert_eq!(info.merge_commit_sha.as_deref(), Some(MERGE_SHA), "{api:?}");
+                assert!(info.merged_at.is_some(), "{api:?}");
+            } else {
+                // Including the test merges of open pull requests
+                assert_eq!(info.merge_commit_sha, None, "{api:?} pull request {number}");
+                assert_eq!(info.merged_at, None, "{api:?} pull request {number}");
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn comments_are_read_across_pages_oldest_first() {
+    for api in APIS {
+        let comments = api
+            .provider()
+            .get_comments(&api.repo("comments"), 1)
+            .await
+            .unwrap();
+        let bodies: Vec<&str> = comments
+            .iter()
+            .map(|comment| match comment {
+                UnifiedComment::General { body, .. } => body.as_str(),
+                UnifiedComment::Review { .. } => {
+                    panic!("{api:?} made {comment:?} a review comment")
+                }
+            })
+            .collect();
+        let expected: Vec<&str> = COMMENTS.iter().map(|(body, _)| *body).collect();
+        assert_eq!(bodies, expected, "{api:?}");
+    }
+}
+
+#[tokio::test]
+async fn drafts_are_requested_on_create() {
+    for api in APIS {
+        let provider = api.provider();
+        let repo = api.repo("drafts");
+        let mut supported = true;
+        for draft in [true, false] {
+            match provider
+                .create_merge_request(&repo, &mr_request(draft))
+                .await
+            {
+                Ok(info) => {
+                    assert_eq!(info.number, 4, "{api:?}");
+                    assert_eq!(info.state, PrState::Open, "{api:?}");
+                }
+                Err(ProviderError::NotSupported { .. }) => supported = false,
+                Err(e) => panic!("{api:?} failed to create a pull request: {e}"),
+            }
+        }
+        if !supported {
+            let requests = requests(api, "drafts");
+            assert!(requests.is_empty(), "{api:?} sent {requests:?}");
+            continue;
+        }
+        let drafts: Vec<bool> = requests(api, "drafts")
+            .iter()
+            .filter(|request| request.method == "POST")
+            .map(|request| api.marks_draft(request))
+            .collect();
+        assert_eq!(drafts, [true, false], "{api:?}");
+    }
+}
+
+#[tokio::test]
+async fn set_draft_marks_the_pull_request_or_is_unsupported() {
+    for api in APIS {
+        let result = api
+            .provider()
+            .set_draft(&api.repo("set-draft"), 1, true)
+            .await;
+        let requests = requests(api, "set-draft");
+        match result {
+            Ok(info) => {
+                assert_eq!(info.number, 1, "{api:?}");
+                assert_eq!(info.state, PrState::Open, "{api:?}");
+                let writes: Vec<&Request> = requests
+                    .iter()
+                    .filter(|request| request.method != "GET")
+                    .collect();
+                assert_eq!(writes.len(), 1, "{api:?}");
+                assert!(api.marks_draft(writes[0]), "{api:?}");
+            }
+            Err(ProviderError::NotSupported { .. }) => {
+                assert!(requests.is_empty(), "{api:?} sent {requests:?}");
+            }
+            Err(e) => panic!("{api:?} failed to set a draft: {e}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn updates_send_only_the_changed_fields_or_are_unsupported() {
+    let update = UpdateMrRequest {
+        title: Some("Better title".to_string()),
+        body: None,
+        base_branch: Some("develop".to_string()),
+    };
+    for api in APIS {
+        let result = api
+            .provider()
+            .update_mr(&api.repo("update"), 1, &update)
+            .await;
+        let requests = requests(api, "update");
+        match result {
+            Ok(info) => {
+                assert_eq!(info.number, 1, "{api:?}");
+                assert_eq!(info.state, PrState::Open, "{api:?}");
+                let writes: Vec<&Request> = requests
+                    .iter()
+                    .filter(|request| request.method != "GET")
+                    .collect();
+                assert_eq!(writes.len(), 1, "{api:?}");
+                let expected = match api {
+                    Api::GitHub => json!({ "title": "Better title", "base": "develop" }),
+                    Api::GitLab => json!({ "title": "Better title", "target_branch": "develop" }),
+                    _ => panic!("{api:?} edited a pull request without a request to check"),
+                };
+                assert_eq!(writes[0].body, expected, "{api:?}");
+            }
+            Err(ProviderError::NotSupported { .. }) => {
+                assert!(requests.is_empty(), "{api:?} sent {requests:?}");
+            }
+            Err(e) => panic!("{api:?} failed to update a pull request: {e}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn commit_statuses_are_published_under_their_context() {
+    for api in APIS {
+        let provider = api.provider();
+        let repo = api.repo("statuses");
+        let mut supported = true;
+        for state in [CommitStatusState::Pending, CommitStatusState::Success] {
+            match provider
+                .set_commit_status(&repo, MERGE_SHA, state, "agentic-kanban/agent-run", None)
+                .await
+            {
+                Ok(()) => {}
+                Err(ProviderError::NotSupported { .. }) => supported = false,
+                Err(e) => panic!("{api:?} failed to publish a commit status: {e}"),
+            }
+        }
+        if !supported {
+            let requests = requests(api, "statuses");
+            assert!(requests.is_empty(), "{api:?} sent {requests:?}");
+            continue;
+        }
+        let statuses: Vec<(String, String)> = requests(api, "statuses")
+            .iter()
+            .filter(|request| request.method == "POST")
+            .map(|request| api.commit_status(request))
+            .collect();
+        let (pending, success) = match api {
+            Api::GitHub | Api::GitLab => ("pending", "success"),
+            Api::Bitbucket => ("INPROGRESS", "SUCCESSFUL"),
+            Api::AzureDevOps => ("pending", "succeeded"),
+            Api::BitbucketServer | Api::Gerrit => unreachable!(),
+        };
+        let context = "agentic-kanban/agent-run".to_string();
+        assert_eq!(
+            statuses,
+            [
+                (context.clone(), pending.to_string()),
+                (context, success.to_string())
+            ],
+            "{api:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn rejected_credentials_are_not_retried() {
+    for api in APIS {
+        let result = api
+            .provider()
+            .get_mr_status(&api.repo("unauthorized"), 1)
+            .await;
+        assert!(
+            matches!(result, Err(ProviderError::NotAuthenticated(_))),
+            "{api:?}: {result:?}"
+        );
+        assert_eq!(requests(api, "unauthorized").len(), 1, "{api:?}");
+    }
+}
+
+#[tokio::test]
+async fn missing_pull_requests_are_not_retried() {
+    for api in APIS {
+        let result = api.provider().get_mr_status(&api.repo("missing"), 1).await;
+        assert!(
+            matches!(result, Err(ProviderError::ApiError { status: 404, .. })),
+            "{api:?}: {result:?}"
+        );
+        assert_eq!(requests(api, "missing").len(), 1, "{api:?}");
+    }
+}
+
+#[tokio::test]
+async fn rate_limits_are_retried() {
+    // Concurrently, since every provider waits before retrying
+    let results = join_all(APIS.map(|api| async move {
+        let result = api
+            .provider()
+            .get_mr_status(&api.repo("rate-limited"), 1)
+            .await;
+        (api, result)
+    }))
+    .await;
+    for (api, result) in results {
+        assert_eq!(result.unwrap().state, PrState::Open, "{api:?}");
+        assert!(requests(api, "rate-limited").len() > 1, "{api:?}");
+    }
+}
@@ -6,7 +6,12 @@
 //! - Self-hosted instances with custom domains and ports
 //! - Case sensitivity, hyphenated/underscore names
 
-use services::services::git_provider::{detect_provider_from_url, ProviderType};
+use git2::Repository;
+use services::services::git_provider::{
+    detect_fork, detect_provider, detect_provider_from_url, ProviderHostMapping,
+    ProviderRegistry, ProviderType,
+};
+use tempfile::TempDir;
 
 #[test]
 fn test_github_https_basic() {
@@ -160,8 +165,60 @@ fn test_gitlab_subdomain() {
 }
 
 #[test]
-fn test_unknown_provider_bitbucket() {
-    let result = detect_provider_from_url("https://bitbucket.org/owner/repo");
+fn test_bitbucket_cloud() {
+    let (provider, repo) = detect_provider_from_url("https://bitbucket.org/owner/repo").unwrap();
+    assert_eq!(provider, ProviderType::Bitbucket);
+    assert_eq!(repo.full_path(), "owner/repo");
+}
+
+#[test]
+fn test_bitbucket_server() {
+    let (provider, repo) =
+        detect_provider_from_url("https://git.example.com/bitbucket/scm/APP/api.git").unwrap();
+    assert_eq!(provider, ProviderType::BitbucketServer);
+    assert_eq!(repo.full_path(), "APP/api");
+    assert_eq!(repo.host, Some("git.example.com/bitbucket".to_string()));
+
+    let (provider, repo) =
+        detect_provider_from_url("ssh://git@bitbucket.example.com:7999/~sam/tools.git").unwrap();
+    assert_eq!(provider, ProviderType::BitbucketServer);
+    assert_eq!(repo.owner, "~sam");
+    assert_eq!(repo.host, Some("bitbucket.example.com".to_string()));
+}
+
+#[test]
+fn test_azure_devops() {
+    let (provider, repo) =
+        detect_provider_from_url("https://dev.azure.com/contoso/platform/_git/api.git").unwrap();
+    assert_eq!(provider, ProviderType::AzureDevOps);
+    assert_eq!(repo.owner, "contoso");
+    assert_eq!(repo.project.as_deref(), Some("platform"));
+    assert_eq!(repo.name, "api");
+}
+
+#[test]
+fn test_codecommit() {
+    let (provider, repo) = detect_provider_from_url(
+        "https://git-codecommit.us-east-2.amazonaws.com/v1/repos/payments",
+    )
+    .unwrap();
+    assert_eq!(provider, ProviderType::CodeCommit);
+    assert_eq!(repo.owner, "us-east-2");
+    assert_eq!(repo.name, "payments");
+}
+
+#[test]
+fn test_gerrit() {
+    let (provider, repo) =
+        detect_provider_from_url("ssh://dev@gerrit.example.com:29418/platform/build").unwrap();
+    assert_eq!(provider, ProviderType::Gerrit);
+    assert_eq!(repo.full_path(), "platform/build");
+    assert_eq!(repo.host, Some("gerrit.example.com".to_string()));
+}
+
+#[test]
+fn test_unknown_provider_codeberg() {
+    let result = detect_provider_from_url("https://codeberg.org/owner/repo");
     assert!(result.is_err());
 }
 
@@ -171,6 +228,22 @@ fn test_unknown_provider_generic_git() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_host_mapping_self_hosted_gitlab() {
+    let mut registry = ProviderRegistry::builtin();
+    assert!(registry.detect("https://code.acme.io/team/app.git").is_err());
+
+    registry.set_host_mappings(vec![ProviderHostMapping {
+        host: "code.acme.io".to_string(),
+        provider: ProviderType::GitLab,
+    }]);
+    let (provider, repo) = registry.detect("https://code.acme.io/team/app.git").unwrap();
+    assert_eq!(provider, ProviderType::GitLab);
+    assert_eq!(repo.owner, "team");
+    assert_eq!(repo.name, "app");
+    assert_eq!(repo.host, Some("code.acme.io".to_string()));
+}
+
 #[test]
 fn test_invalid_url_format() {
     let result = detect_provider_from_url("not-a-valid-url");
@@ -225,3 +298,33 @@ fn test_case_sensitivity() {
     assert_eq!(repo.owner, "MyOrg");
     assert_eq!(repo.name, "MyProject");
 }
+
+#[test]
+fn test_fork_checkout_targets_upstream() {
+    let dir = TempDir::new().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    repo.remote("origin", "git@github.com:contributor/project.git")
+        .unwrap();
+    repo.remote("upstream", "https://github.com/acme/project.git")
+        .unwrap();
+
+    let fork = detect_fork(dir.path()).unwrap().unwrap();
+    assert_eq!(fork.fork.owner, "contributor");
+    assert_eq!(fork.upstream.owner, "acme");
+
+    let (provider, repo_id) = detect_provider(dir.path()).unwrap();
+    assert_eq!(provider, ProviderType::GitHub);
+    assert_eq!(repo_id.owner, "acme");
+}
+
+#[test]
+fn test_same_repository_remotes_are_not_a_fork() {
+    let dir = TempDir::new().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    repo.remote("origin", "git@github.com:acme/project.git")
+        .unwrap();
+    repo.remote("upstream", "https://github.com/acme/project")
+        .unwrap();
+
+    assert!(detect_fork(dir.path()).unwrap().is_none());
+}
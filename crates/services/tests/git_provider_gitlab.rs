@@ -24,6 +24,8 @@ fn test_mr_request() -> CreateMrRequest {
         head_branch: "feature-branch".to_string(),
         base_branch: "main".to_string(),
         draft: Some(false),
+        head_repo: None,
+        milestone: None,
     }
 }
 
@@ -109,6 +111,8 @@ fn test_create_mr_request_draft() {
         head_branch: "wip-branch".to_string(),
         base_branch: "develop".to_string(),
         draft: Some(true),
+        head_repo: None,
+        milestone: None,
     };
 
     assert!(req.draft.unwrap());
@@ -123,6 +127,8 @@ fn test_create_mr_request_no_body() {
         head_branch: "feature".to_string(),
         base_branch: "main".to_string(),
         draft: Some(false),
+        head_repo: None,
+        milestone: None,
     };
 
     assert!(req.body.is_none());
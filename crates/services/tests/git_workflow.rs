@@ -6,7 +6,7 @@ use std::{
 
 use git2::{Repository, build::CheckoutBuilder};
 use services::services::{
-    git::{DiffTarget, GitCli, GitService},
+    git::{Commit, DiffTarget, GitCli, GitService},
     github::{GitHubRepoInfo, GitHubServiceError},
 };
 use tempfile::TempDir;
@@ -217,6 +217,31 @@ fn diff_added_binary_file_has_no_content() {
     assert!(bin.new_content.is_none());
 }
 
+#[test]
+fn changed_paths_between_lists_both_sides_of_renames() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "infra/main.tf", "resource {}\n");
+    write_file(&repo_path, "src/lib.rs", "fn a() {}\n");
+    let s = GitService::new();
+    let _ = s.commit(&repo_path, "seed").unwrap();
+    let before = s.get_head_info(&repo_path).unwrap().oid;
+
+    std::fs::rename(repo_path.join("infra/main.tf"), repo_path.join("moved.tf")).unwrap();
+    write_file(&repo_path, "README.md", "hi\n");
+    let _ = s.commit(&repo_path, "agent changes").unwrap();
+    let after = s.get_head_info(&repo_path).unwrap().oid;
+
+    let paths = s
+        .changed_paths_between(
+            &repo_path,
+            &Commit::from_sha(&before).unwrap(),
+            &Commit::from_sha(&after).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(paths, vec!["README.md", "infra/main.tf", "moved.tf"]);
+}
+
 #[test]
 fn initialize_and_default_branch_and_head_info() {
     let td = TempDir::new().unwrap();
@@ -311,6 +336,108 @@ fn branch_status_ahead_and_behind() {
     assert_eq!((ahead2, behind2), (2, 1));
 }
 
+#[test]
+fn infers_the_branch_a_branch_was_started_from() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+
+    write_file(&repo_path, "base.txt", "base\n");
+    let _ = s.commit(&repo_path, "base").unwrap();
+    create_branch(&repo_path, "develop");
+    checkout_branch(&repo_path, "develop");
+    write_file(&repo_path, "develop.txt", "d1\n");
+    let _ = s.commit(&repo_path, "d1").unwrap();
+
+    // feature starts from develop, which then moves on
+    create_branch(&repo_path, "feature");
+    write_file(&repo_path, "develop2.txt", "d2\n");
+    let _ = s.commit(&repo_path, "d2").unwrap();
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "feature.txt", "f1\n");
+    let _ = s.commit(&repo_path, "f1").unwrap();
+    // A branch made from feature contains all of it and must not win
+    create_branch(&repo_path, "feature-copy");
+    checkout_branch(&repo_path, "main");
+
+    assert_eq!(
+        s.infer_base_branch(&repo_path, "feature").unwrap(),
+        Some("develop".to_string())
+    );
+}
+
+#[test]
+fn ensure_local_branch_creates_it_from_the_remote() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let git = GitCli::new();
+    git.git(
+        &repo_path,
+        ["remote", "add", "origin", "https://example.com/none.git"],
+    )
+    .unwrap();
+    write_file(&repo_path, "base.txt", "base\n");
+    let _ = s.commit(&repo_path, "base").unwrap();
+    git.git(
+        &repo_path,
+        [
+            "update-ref",
+            "refs/remotes/origin/started-elsewhere",
+            "main",
+        ],
+    )
+    .unwrap();
+
+    s.ensure_local_branch(&repo_path, "started-elsewhere")
+        .unwrap();
+    let upstream = git
+        .git(
+            &repo_path,
+            ["rev-parse", "--abbrev-ref", "started-elsewhere@{upstream}"],
+        )
+        .unwrap();
+    assert_eq!(upstream.trim(), "origin/started-elsewhere");
+    assert!(s.ensure_local_branch(&repo_path, "nowhere").is_err());
+}
+
+#[test]
+fn pushed_branch_status_reads_remote_refs_without_fetching() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let git = GitCli::new();
+    git.git(
+        &repo_path,
+        ["remote", "add", "origin", "https://example.com/none.git"],
+    )
+    .unwrap();
+
+    write_file(&repo_path, "base.txt", "base\n");
+    let _ = s.commit(&repo_path, "base").unwrap();
+    create_branch(&repo_path, "feature");
+    create_branch(&repo_path, "unpushed");
+    // Pretend feature was pushed at this commit
+    git.git(
+        &repo_path,
+        ["update-ref", "refs/remotes/origin/feature", "feature"],
+    )
+    .unwrap();
+
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "feature.txt", "f1\n");
+    let _ = s.commit(&repo_path, "f1").unwrap();
+
+    assert_eq!(
+        s.get_pushed_branch_status(&repo_path, "feature").unwrap(),
+        Some((1, 0))
+    );
+    assert_eq!(
+        s.get_pushed_branch_status(&repo_path, "unpushed").unwrap(),
+        None
+    );
+}
+
 #[test]
 fn get_all_branches_lists_current_and_others() {
     let td = TempDir::new().unwrap();
@@ -71,6 +71,8 @@ fn test_create_mr_request_construction() {
         head_branch: "feature/new-thing".to_string(),
         base_branch: "main".to_string(),
         draft: Some(false),
+        head_repo: None,
+        milestone: None,
     };
 
     assert_eq!(req.title, "Add new feature");
@@ -88,6 +90,8 @@ fn test_draft_mr_request() {
         head_branch: "wip-branch".to_string(),
         base_branch: "develop".to_string(),
         draft: Some(true),
+        head_repo: None,
+        milestone: None,
     };
 
     assert!(req.draft.unwrap());
@@ -96,11 +100,11 @@ fn test_draft_mr_request() {
 /// Test error handling for unsupported provider
 #[test]
 fn test_unsupported_provider_error() {
-    let result = detect_provider_from_url("https://bitbucket.org/owner/repo");
+    let result = detect_provider_from_url("https://codeberg.org/owner/repo");
     assert!(result.is_err());
     match result.unwrap_err() {
         ProviderError::UnknownProvider(url) => {
-            assert!(url.contains("bitbucket"));
+            assert!(url.contains("codeberg"));
         }
         _ => panic!("Expected UnknownProvider error"),
     }
@@ -0,0 +1,101 @@
+use std::{fs, path::Path};
+
+use services::services::{
+    git::{GitCli, GitService},
+    repo::{create_primary_clone, sync_primary_clone},
+};
+use tempfile::TempDir;
+
+fn commit_file(git: &GitCli, repo_path: &Path, name: &str, content: &str) {
+    fs::write(repo_path.join(name), content).unwrap();
+    git.git(repo_path, ["add", name]).unwrap();
+    git.git(
+        repo_path,
+        [
+            "-c",
+            "user.name=Test User",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-m",
+            name,
+        ],
+    )
+    .unwrap();
+}
+
+fn rev(git: &GitCli, repo_path: &Path, refname: &str) -> String {
+    git.git(repo_path, ["rev-parse", refname])
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+fn init_source(root: &TempDir) -> std::path::PathBuf {
+    let path = root.path().join("source");
+    GitService::new()
+        .initialize_repo_with_main_branch(&path)
+        .unwrap();
+    let git = GitCli::new();
+    git.git(&path, ["branch", "develop"]).unwrap();
+    path
+}
+
+#[test]
+fn primary_clone_has_local_branches_and_no_checked_out_branch() {
+    let root = TempDir::new().unwrap();
+    let source = init_source(&root);
+    let dest = root.path().join("managed").join("source");
+    let git = GitCli::new();
+
+    create_primary_clone(&git, &source.to_string_lossy(), &dest, None, None).unwrap();
+
+    assert_eq!(rev(&git, &dest, "main"), rev(&git, &source, "main"));
+    assert_eq!(rev(&git, &dest, "develop"), rev(&git, &source, "develop"));
+    // Detached, so merges into main never touch a working tree
+    assert!(git.git(&dest, ["symbolic-ref", "-q", "HEAD"]).is_err());
+}
+
+#[test]
+fn sync_fast_forwards_branches_but_keeps_local_work() {
+    let root = TempDir::new().unwrap();
+    let source = init_source(&root);
+    let dest = root.path().join("managed").join("source");
+    let git = GitCli::new();
+    create_primary_clone(&git, &source.to_string_lossy(), &dest, None, None).unwrap();
+
+    // A workspace commits on develop in a worktree of the clone
+    let worktree = root.path().join("wt");
+    git.worktree_add(&dest, &worktree, "develop", false)
+        .unwrap();
+    commit_file(&git, &worktree, "local.txt", "local");
+    let local_develop = rev(&git, &dest, "develop");
+
+    commit_file(&git, &source, "upstream.txt", "upstream");
+    git.git(&source, ["branch", "release"]).unwrap();
+
+    let mut updated = sync_primary_clone(&git, &dest).unwrap();
+    updated.sort();
+    assert_eq!(updated, vec!["main", "release"]);
+    assert_eq!(rev(&git, &dest, "main"), rev(&git, &source, "main"));
+    assert_eq!(rev(&git, &dest, "develop"), local_develop);
+}
+
+#[test]
+fn failed_clone_leaves_nothing_behind() {
+    let root = TempDir::new().unwrap();
+    let dest = root.path().join("managed").join("missing");
+    let missing = root.path().join("does-not-exist");
+
+    assert!(
+        create_primary_clone(
+            &GitCli::new(),
+            &missing.to_string_lossy(),
+            &dest,
+            None,
+            None
+        )
+        .is_err()
+    );
+    assert!(!dest.exists());
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Type;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, TS)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "library_item_kind", rename_all = "snake_case")]
+pub enum LibraryItemKind {
+    PromptTemplate,
+    TaskTemplate,
+    AutomationRule,
+}
+
+/// A template or automation rule published to an organization
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct LibraryItem {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub kind: LibraryItemKind,
+    pub name: String,
+    /// Kind-specific payload, as published by the instance it came from
+    #[ts(type = "Record<string, unknown>")]
+    pub content: Value,
+    /// Starts at 1 and goes up with every update
+    pub version: i64,
+    pub published_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListLibraryItemsResponse {
+    pub items: Vec<LibraryItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PublishLibraryItemRequest {
+    pub organization_id: Uuid,
+    pub kind: LibraryItemKind,
+    pub name: String,
+    #[ts(type = "Record<string, unknown>")]
+    pub content: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateLibraryItemRequest {
+    pub name: String,
+    #[ts(type = "Record<string, unknown>")]
+    pub content: Value,
+}
@@ -1,3 +1,4 @@
+pub mod library;
 pub mod oauth;
 pub mod organizations;
 pub mod projects;
@@ -1,10 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sqlx::Type;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::organizations::OrganizationMemberWithProfile;
+use super::organizations::{MemberRole, OrganizationMemberWithProfile};
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct RemoteProject {
@@ -27,3 +28,193 @@ pub struct RemoteProjectMembersResponse {
     pub organization_id: Uuid,
     pub members: Vec<OrganizationMemberWithProfile>,
 }
+
+/// Something a member may or may not be allowed to do in a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type, TS)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "project_permission", rename_all = "snake_case")]
+pub enum ProjectPermission {
+    StartExecution,
+    CreatePr,
+    Merge,
+    EditAutomationRules,
+}
+
+impl ProjectPermission {
+    pub const ALL: [Self; 4] = [
+        Self::StartExecution,
+        Self::CreatePr,
+        Self::Merge,
+        Self::EditAutomationRules,
+    ];
+
+    /// Whether a role has the permission in projects that do not override it
+    pub fn granted_to(self, role: MemberRole) -> bool {
+        match role {
+            MemberRole::Admin => true,
+            MemberRole::Member => self != Self::EditAutomationRules,
+        }
+    }
+
+    /// What the permission allows, e.g. for "not allowed to ..." errors
+    pub fn action(self) -> &'static str {
+        match self {
+            Self::StartExecution => "start executions",
+            Self::CreatePr => "create pull requests",
+            Self::Merge => "merge",
+            Self::EditAutomationRules => "change automation rules",
+        }
+    }
+}
+
+/// Grants or revokes a permission in one project, for everyone with a role or for one member
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectPermissionOverride {
+    pub project_id: Uuid,
+    /// Set for overrides of a whole role
+    pub role: Option<MemberRole>,
+    /// Set for overrides of one member, which win over the override of their role
+    pub user_id: Option<Uuid>,
+    pub permission: ProjectPermission,
+    pub allowed: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListProjectPermissionOverridesResponse {
+    pub overrides: Vec<ProjectPermissionOverride>,
+}
+
+/// Sets the override of either a role or one member
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SetProjectPermissionOverrideRequest {
+    pub role: Option<MemberRole>,
+    pub user_id: Option<Uuid>,
+    pub permission: ProjectPermission,
+    /// None removes the override, so the permission is inherited from the role again
+    pub allowed: Option<bool>,
+}
+
+/// What the current user may do in a project
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct EffectiveProjectPermissions {
+    /// None for projects that are not shared with an organization
+    pub role: Option<MemberRole>,
+    pub permissions: Vec<ProjectPermission>,
+}
+
+impl EffectiveProjectPermissions {
+    /// Everything is allowed in projects nobody else has a say in
+    pub fn unrestricted() -> Self {
+        Self {
+            role: None,
+            permissions: ProjectPermission::ALL.to_vec(),
+        }
+    }
+
+    /// The permissions of a member: their own override, else the override of their role, else
+    /// what the role is granted by default
+    pub fn resolve(
+        role: MemberRole,
+        user_id: Uuid,
+        overrides: &[ProjectPermissionOverride],
+    ) -> Self {
+        let allowed = |permission: ProjectPermission| {
+            let mut of_permission = overrides.iter().filter(|o| o.permission == permission);
+            let own = of_permission.clone().find(|o| o.user_id == Some(user_id));
+            let of_role = of_permission.find(|o| o.role == Some(role));
+            own.or(of_role)
+                .map_or_else(|| permission.granted_to(role), |o| o.allowed)
+        };
+        Self {
+            role: Some(role),
+            permissions: ProjectPermission::ALL
+                .into_iter()
+                .filter(|permission| allowed(*permission))
+                .collect(),
+        }
+    }
+
+    pub fn allows(&self, permission: ProjectPermission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project_override(
+        role: Option<MemberRole>,
+        user_id: Option<Uuid>,
+        permission: ProjectPermission,
+        allowed: bool,
+    ) -> ProjectPermissionOverride {
+        ProjectPermissionOverride {
+            project_id: Uuid::nil(),
+            role,
+            user_id,
+            permission,
+            allowed,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn roles_inherit_their_defaults() {
+        let user = Uuid::new_v4();
+
+        let admin = EffectiveProjectPermissions::resolve(MemberRole::Admin, user, &[]);
+        let member = EffectiveProjectPermissions::resolve(MemberRole::Member, user, &[]);
+
+        assert_eq!(admin.permissions, ProjectPermission::ALL.to_vec());
+        assert!(member.allows(ProjectPermission::Merge));
+        assert!(!member.allows(ProjectPermission::EditAutomationRules));
+    }
+
+    #[test]
+    fn role_overrides_replace_the_defaults() {
+        let overrides = [project_override(
+            Some(MemberRole::Member),
+            None,
+            ProjectPermission::Merge,
+            false,
+        )];
+
+        let member =
+            EffectiveProjectPermissions::resolve(MemberRole::Member, Uuid::new_v4(), &overrides);
+        let admin =
+            EffectiveProjectPermissions::resolve(MemberRole::Admin, Uuid::new_v4(), &overrides);
+
+        assert!(!member.allows(ProjectPermission::Merge));
+        assert!(admin.allows(ProjectPermission::Merge));
+    }
+
+    #[test]
+    fn member_overrides_win_over_role_overrides() {
+        let user = Uuid::new_v4();
+        let overrides = [
+            project_override(
+                Some(MemberRole::Member),
+                None,
+                ProjectPermission::Merge,
+                false,
+            ),
+            project_override(None, Some(user), ProjectPermission::Merge, true),
+            project_override(
+                None,
+                Some(user),
+                ProjectPermission::EditAutomationRules,
+                true,
+            ),
+        ];
+
+        let member = EffectiveProjectPermissions::resolve(MemberRole::Member, user, &overrides);
+        let other =
+            EffectiveProjectPermissions::resolve(MemberRole::Member, Uuid::new_v4(), &overrides);
+
+        assert!(member.allows(ProjectPermission::Merge));
+        assert!(member.allows(ProjectPermission::EditAutomationRules));
+        assert!(!other.allows(ProjectPermission::Merge));
+    }
+}
@@ -1,10 +1,29 @@
+use std::{path::PathBuf, sync::OnceLock};
+
 use directories::ProjectDirs;
 use rust_embed::RustEmbed;
 
 const PROJECT_ROOT: &str = env!("CARGO_MANIFEST_DIR");
 
+/// Environment variable that relocates all persistent data (db, config, credentials).
+pub const DATA_DIR_ENV: &str = "VK_DATA_DIR";
+
+static DATA_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Override the data directory for the lifetime of the process (e.g. from `--data-dir`).
+///
+/// Takes precedence over `VK_DATA_DIR`. Only the first call has an effect; subsequent calls
+/// return `false`.
+pub fn set_data_dir(path: PathBuf) -> bool {
+    DATA_DIR_OVERRIDE.set(path).is_ok()
+}
+
 pub fn asset_dir() -> std::path::PathBuf {
-    let path = if cfg!(debug_assertions) {
+    let path = if let Some(dir) = DATA_DIR_OVERRIDE.get() {
+        dir.clone()
+    } else if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|d| !d.is_empty()) {
+        PathBuf::from(dir)
+    } else if cfg!(debug_assertions) {
         std::path::PathBuf::from(PROJECT_ROOT).join("../../dev_assets")
     } else {
         ProjectDirs::from("ai", "bloop", "vibe-kanban")
@@ -36,6 +55,10 @@ pub fn credentials_path() -> std::path::PathBuf {
     asset_dir().join("credentials.json")
 }
 
+pub fn database_path() -> std::path::PathBuf {
+    asset_dir().join("db.sqlite")
+}
+
 #[derive(RustEmbed)]
 #[folder = "../../assets/sounds"]
 pub struct SoundAssets;
@@ -89,6 +89,15 @@ pub fn compute_line_change_counts(old: &str, new: &str) -> (usize, usize) {
     }
 }
 
+/// 1-based numbers of the lines in `new` that were added or changed relative to `old`.
+pub fn added_line_numbers(old: &str, new: &str) -> Vec<usize> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .filter(|change| change.tag() == similar::ChangeTag::Insert)
+        .filter_map(|change| change.new_index().map(|index| index + 1))
+        .collect()
+}
+
 // ensure a line ends with a newline character
 fn ensure_newline(line: &str) -> Cow<'_, str> {
     if line.ends_with('\n') {
@@ -10,6 +10,11 @@ pub fn is_valid_branch_prefix(prefix: &str) -> bool {
     git2::Branch::name_is_valid(&format!("{prefix}/x")).unwrap_or_default()
 }
 
+/// Whether `name` can be used as a local branch name, e.g. `feature/login`
+pub fn is_valid_branch_name(name: &str) -> bool {
+    !name.is_empty() && git2::Branch::name_is_valid(name).unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,4 +49,15 @@ mod tests {
         assert!(!is_valid_branch_prefix("foo/"));
         assert!(!is_valid_branch_prefix(".foo"));
     }
+
+    #[test]
+    fn test_branch_names() {
+        assert!(is_valid_branch_name("main"));
+        assert!(is_valid_branch_name("vk/1a2b-fix-login"));
+        assert!(!is_valid_branch_name(""));
+        assert!(!is_valid_branch_name("foo..bar"));
+        assert!(!is_valid_branch_name("foo bar"));
+        assert!(!is_valid_branch_name("foo/"));
+        assert!(!is_valid_branch_name("-foo"));
+    }
 }
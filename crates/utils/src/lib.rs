@@ -10,13 +10,16 @@ pub mod diff;
 pub mod git;
 pub mod jwt;
 pub mod log_msg;
+pub mod log_shipping;
 pub mod msg_store;
 pub mod path;
 pub mod port_file;
+pub mod redact;
 pub mod response;
 pub mod sentry;
 pub mod shell;
 pub mod stream_lines;
+pub mod test_mode;
 pub mod text;
 pub mod tokio;
 pub mod version;
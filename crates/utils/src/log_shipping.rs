@@ -0,0 +1,717 @@
+//! Shipping of structured logs to an external aggregator.
+//!
+//! Server logs (through [`layer`]) and the output of execution processes (through
+//! [`ExecutionForwarder`]) become [`LogRecord`]s, which a background task sends in batches to the
+//! configured sink: newline-delimited JSON over HTTP, Loki's push API, Elasticsearch's bulk API or
+//! RFC 5424 syslog over UDP. Execution records carry the workspace, task and execution process
+//! ids, so agent activity can be lined up with infrastructure logs.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    str::FromStr,
+    sync::{Arc, OnceLock, RwLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+use tokio::{net::UdpSocket, sync::mpsc};
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, layer::Context};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::log_msg::LogMsg;
+
+/// Records waiting for the sender; more are dropped until it catches up
+const QUEUE_CAPACITY: usize = 10_000;
+const BATCH_SIZE: usize = 500;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// APP-NAME of syslog messages
+const SYSLOG_APP_NAME: &str = "vibe-kanban";
+/// SD-ID of the structured data carrying the ids, under the private enterprise number for examples
+const SYSLOG_SD_ID: &str = "vk@32473";
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSink {
+    /// Newline-delimited JSON records POSTed to the endpoint
+    #[default]
+    JsonHttp,
+    /// Grafana Loki push API, e.g. `http://loki:3100/loki/api/v1/push`
+    Loki,
+    /// Bulk API of the Elasticsearch cluster at the endpoint, e.g. `http://elastic:9200`
+    Elasticsearch,
+    /// RFC 5424 syslog over UDP to the endpoint, as `host:port`
+    Syslog,
+}
+
+/// Export of server and execution logs to an external aggregator
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq)]
+#[serde(default)]
+pub struct LogShippingConfig {
+    pub enabled: bool,
+    pub sink: LogSink,
+    /// URL of the aggregator, or `host:port` for syslog
+    pub endpoint: Option<String>,
+    /// Authorization header of HTTP sinks, e.g. `Bearer <token>`
+    pub authorization: Option<String>,
+    /// Elasticsearch index records are written to
+    pub index: String,
+    /// Ship the server's own logs
+    pub server_logs: bool,
+    /// Least severe server log level shipped: trace, debug, info, warn or error
+    pub server_log_level: String,
+    /// Ship the stdout and stderr of execution processes
+    pub execution_logs: bool,
+    /// Added to every record, e.g. the environment or cluster name
+    pub labels: BTreeMap<String, String>,
+}
+
+impl Default for LogShippingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sink: LogSink::default(),
+            endpoint: None,
+            authorization: None,
+            index: "vibe-kanban-logs".to_string(),
+            server_logs: true,
+            server_log_level: "info".to_string(),
+            execution_logs: true,
+            labels: BTreeMap::new(),
+        }
+    }
+}
+
+impl LogShippingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let endpoint = self.endpoint.as_deref().map(str::trim).unwrap_or_default();
+        if endpoint.is_empty() {
+            return Err("Log shipping needs an endpoint".to_string());
+        }
+        match self.sink {
+            LogSink::Syslog => {
+                if endpoint.contains("://") || !endpoint.contains(':') {
+                    return Err(format!("Syslog endpoint '{endpoint}' must be host:port"));
+                }
+            }
+            _ => {
+                url::Url::parse(endpoint)
+                    .map_err(|e| format!("Invalid log shipping endpoint '{endpoint}': {e}"))?;
+            }
+        }
+        if self.sink == LogSink::Elasticsearch && self.index.trim().is_empty() {
+            return Err("Elasticsearch log shipping needs an index".to_string());
+        }
+        Level::from_str(&self.server_log_level)
+            .map_err(|_| format!("Unknown server log level '{}'", self.server_log_level))?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSource {
+    Server,
+    Execution,
+}
+
+impl LogSource {
+    fn name(self) -> &'static str {
+        match self {
+            LogSource::Server => "server",
+            LogSource::Execution => "execution",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// One shipped log line
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct LogRecord {
+    pub timestamp: DateTime<Utc>,
+    /// trace, debug, info, warn or error
+    pub level: &'static str,
+    pub source: LogSource,
+    pub message: String,
+    /// Module the server log came from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<LogStream>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_process_id: Option<Uuid>,
+    /// Structured fields of the server log event
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+}
+
+impl LogRecord {
+    fn json(&self, labels: &BTreeMap<String, String>) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(object) = value.as_object_mut() {
+            for (key, label) in labels {
+                object
+                    .entry(key.clone())
+                    .or_insert_with(|| label.clone().into());
+            }
+        }
+        value
+    }
+}
+
+fn level_name(level: &Level) -> &'static str {
+    match *level {
+        Level::TRACE => "trace",
+        Level::DEBUG => "debug",
+        Level::INFO => "info",
+        Level::WARN => "warn",
+        Level::ERROR => "error",
+    }
+}
+
+/// Records as newline-delimited JSON
+pub fn json_lines(records: &[LogRecord], labels: &BTreeMap<String, String>) -> String {
+    records
+        .iter()
+        .map(|record| format!("{}\n", record.json(labels)))
+        .collect()
+}
+
+/// Body of a Loki push request. Records are grouped into streams by source, level and the
+/// workspace and task they belong to, next to the configured labels.
+pub fn loki_push(records: &[LogRecord], labels: &BTreeMap<String, String>) -> serde_json::Value {
+    let mut streams: BTreeMap<BTreeMap<String, String>, Vec<serde_json::Value>> = BTreeMap::new();
+    for record in records {
+        let mut stream = labels.clone();
+        stream.insert("source".to_string(), record.source.name().to_string());
+        stream.insert("level".to_string(), record.level.to_string());
+        if let Some(workspace_id) = record.workspace_id {
+            stream.insert("workspace_id".to_string(), workspace_id.to_string());
+        }
+        if let Some(task_id) = record.task_id {
+            stream.insert("task_id".to_string(), task_id.to_string());
+        }
+        let nanos = record
+            .timestamp
+            .timestamp_nanos_opt()
+            .unwrap_or_default()
+            .to_string();
+        streams
+            .entry(stream)
+            .or_default()
+            .push(json!([nanos, record.json(&BTreeMap::new()).to_string()]));
+    }
+    json!({
+        "streams": streams
+            .into_iter()
+            .map(|(stream, values)| json!({ "stream": stream, "values": values }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Body of an Elasticsearch bulk request indexing the records
+pub fn elasticsearch_bulk(
+    index: &str,
+    records: &[LogRecord],
+    labels: &BTreeMap<String, String>,
+) -> String {
+    let action = json!({ "index": { "_index": index } }).to_string();
+    records
+        .iter()
+        .map(|record| {
+            let mut document = record.json(labels);
+            if let Some(object) = document.as_object_mut() {
+                object.insert(
+                    "@timestamp".to_string(),
+                    record
+                        .timestamp
+                        .to_rfc3339_opts(SecondsFormat::Millis, true)
+                        .into(),
+                );
+            }
+            format!("{action}\n{document}\n")
+        })
+        .collect()
+}
+
+/// A record as an RFC 5424 message of the user facility, the ids and labels as structured data
+pub fn syslog_message(record: &LogRecord, labels: &BTreeMap<String, String>) -> String {
+    let severity = match record.level {
+        "error" => 3,
+        "warn" => 4,
+        "info" => 6,
+        _ => 7,
+    };
+    let mut params: Vec<(String, String)> = labels
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    for (key, id) in [
+        ("workspace_id", record.workspace_id),
+        ("task_id", record.task_id),
+        ("execution_process_id", record.execution_process_id),
+    ] {
+        if let Some(id) = id {
+            params.push((key.to_string(), id.to_string()));
+        }
+    }
+    let structured_data = if params.is_empty() {
+        "-".to_string()
+    } else {
+        let params: String = params
+            .iter()
+            .map(|(key, value)| format!(" {}=\"{}\"", sd_name(key), sd_escape(value)))
+            .collect();
+        format!("[{SYSLOG_SD_ID}{params}]")
+    };
+    format!(
+        "<{}>1 {} - {SYSLOG_APP_NAME} - {} {structured_data} {}",
+        8 + severity,
+        record
+            .timestamp
+            .to_rfc3339_opts(SecondsFormat::Micros, true),
+        record.source.name(),
+        record.message
+    )
+}
+
+/// PARAM-NAMEs are printable ASCII without `=`, space, `]` and `"`, at most 32 characters
+fn sd_name(key: &str) -> String {
+    key.chars()
+        .filter(|c| c.is_ascii_graphic() && !matches!(c, '=' | ']' | '"'))
+        .take(32)
+        .collect()
+}
+
+fn sd_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+#[derive(Debug, Error)]
+enum ShipError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("aggregator answered {0}")]
+    Status(reqwest::StatusCode),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+struct Shipper {
+    config: LogShippingConfig,
+    server_level: Level,
+    sender: mpsc::Sender<LogRecord>,
+}
+
+impl Shipper {
+    fn send(&self, record: LogRecord) {
+        // A full queue means the aggregator is down or slow; dropping beats blocking the caller
+        let _ = self.sender.try_send(record);
+    }
+}
+
+static GLOBAL: OnceLock<RwLock<Option<Arc<Shipper>>>> = OnceLock::new();
+
+fn global_cell() -> &'static RwLock<Option<Arc<Shipper>>> {
+    GLOBAL.get_or_init(|| RwLock::new(None))
+}
+
+fn current() -> Option<Arc<Shipper>> {
+    global_cell()
+        .read()
+        .map(|r| r.clone())
+        .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+}
+
+/// Start shipping with the given settings, replacing the previous shipper, whose queued records
+/// are still delivered. Must be called within a Tokio runtime.
+pub fn configure(config: &LogShippingConfig) {
+    let shipper = (config.enabled && config.validate().is_ok()).then(|| {
+        let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        tokio::spawn(run(config.clone(), receiver));
+        Arc::new(Shipper {
+            config: config.clone(),
+            server_level: Level::from_str(&config.server_log_level).unwrap_or(Level::INFO),
+            sender,
+        })
+    });
+    match global_cell().write() {
+        Ok(mut guard) => *guard = shipper,
+        Err(poisoned) => *poisoned.into_inner() = shipper,
+    }
+}
+
+async fn run(config: LogShippingConfig, mut receiver: mpsc::Receiver<LogRecord>) {
+    let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!("Failed to create log shipping client: {}", e);
+            return;
+        }
+    };
+    let socket = if config.sink == LogSink::Syslog {
+        match UdpSocket::bind("0.0.0.0:0").await {
+            Ok(socket) => Some(socket),
+            Err(e) => {
+                tracing::error!("Failed to open syslog socket: {}", e);
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+    loop {
+        let closed = tokio::select! {
+            received = receiver.recv_many(&mut batch, BATCH_SIZE - batch.len()) => {
+                if received > 0 && batch.len() < BATCH_SIZE {
+                    continue;
+                }
+                received == 0
+            }
+            _ = ticker.tick() => false,
+        };
+        if !batch.is_empty() {
+            if let Err(e) = deliver(&client, socket.as_ref(), &config, &batch).await {
+                tracing::warn!("Failed to ship {} log records: {}", batch.len(), e);
+            }
+            batch.clear();
+        }
+        if closed {
+            return;
+        }
+    }
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    socket: Option<&UdpSocket>,
+    config: &LogShippingConfig,
+    records: &[LogRecord],
+) -> Result<(), ShipError> {
+    let endpoint = config.endpoint.as_deref().unwrap_or_default().trim();
+    let (url, content_type, body) = match config.sink {
+        LogSink::JsonHttp => (
+            endpoint.to_string(),
+            "application/x-ndjson",
+            json_lines(records, &config.labels),
+        ),
+        LogSink::Loki => (
+            endpoint.to_string(),
+            "application/json",
+            loki_push(records, &config.labels).to_string(),
+        ),
+        LogSink::Elasticsearch => (
+            format!("{}/_bulk", endpoint.trim_end_matches('/')),
+            "application/x-ndjson",
+            elasticsearch_bulk(&config.index, records, &config.labels),
+        ),
+        LogSink::Syslog => {
+            if let Some(socket) = socket {
+                for record in records {
+                    let message = syslog_message(record, &config.labels);
+                    socket.send_to(message.as_bytes(), endpoint).await?;
+                }
+            }
+            return Ok(());
+        }
+    };
+
+    let mut request = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, content_type)
+        .body(body);
+    if let Some(authorization) = &config.authorization {
+        request = request.header(reqwest::header::AUTHORIZATION, authorization);
+    }
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        return Err(ShipError::Status(response.status()));
+    }
+    Ok(())
+}
+
+/// Whether execution output is shipped, so callers can skip looking up its ids otherwise
+pub fn ships_execution_logs() -> bool {
+    current().is_some_and(|shipper| shipper.config.execution_logs)
+}
+
+/// Ids execution records are keyed by
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExecutionLogContext {
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub execution_process_id: Uuid,
+}
+
+/// Turns the output chunks of one execution process into records, one per line
+pub struct ExecutionForwarder {
+    context: ExecutionLogContext,
+    stdout: String,
+    stderr: String,
+}
+
+impl ExecutionForwarder {
+    pub fn new(context: ExecutionLogContext) -> Self {
+        Self {
+            context,
+            stdout: String::new(),
+            stderr: String::new(),
+        }
+    }
+
+    /// Ship the complete lines of a chunk; a trailing partial line waits for the next chunk
+    pub fn push(&mut self, stream: LogStream, chunk: &str) {
+        let buffer = match stream {
+            LogStream::Stdout => &mut self.stdout,
+            LogStream::Stderr => &mut self.stderr,
+        };
+        buffer.push_str(chunk);
+        let Some(end) = buffer.rfind('\n') else {
+            return;
+        };
+        let complete: String = buffer.drain(..=end).collect();
+        for line in complete.lines() {
+            self.ship(stream, line);
+        }
+    }
+
+    /// Ship the output of a log message; other messages carry none
+    pub fn push_msg(&mut self, msg: &LogMsg) {
+        match msg {
+            LogMsg::Stdout(text) => self.push(LogStream::Stdout, text),
+            LogMsg::Stderr(text) => self.push(LogStream::Stderr, text),
+            _ => {}
+        }
+    }
+
+    /// Ship what is left of partial lines once the process is done
+    pub fn finish(&mut self) {
+        for stream in [LogStream::Stdout, LogStream::Stderr] {
+            let rest = match stream {
+                LogStream::Stdout => std::mem::take(&mut self.stdout),
+                LogStream::Stderr => std::mem::take(&mut self.stderr),
+            };
+            self.ship(stream, &rest);
+        }
+    }
+
+    fn ship(&self, stream: LogStream, line: &str) {
+        if line.trim().is_empty() {
+            return;
+        }
+        if let Some(shipper) = current()
+            && shipper.config.execution_logs
+        {
+            shipper.send(self.record(stream, line));
+        }
+    }
+
+    fn record(&self, stream: LogStream, line: &str) -> LogRecord {
+        LogRecord {
+            timestamp: Utc::now(),
+            level: "info",
+            source: LogSource::Execution,
+            message: line.trim_end_matches('\r').to_string(),
+            target: None,
+            stream: Some(stream),
+            workspace_id: Some(self.context.workspace_id),
+            task_id: Some(self.context.task_id),
+            execution_process_id: Some(self.context.execution_process_id),
+            fields: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: BTreeMap<String, String>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields
+                .insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.fields
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+}
+
+/// Tracing layer shipping the server's own log events while shipping is configured
+pub struct LogShippingLayer;
+
+pub fn layer() -> LogShippingLayer {
+    LogShippingLayer
+}
+
+impl<S: Subscriber> Layer<S> for LogShippingLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        // Failures to ship are logged from here; shipping those would feed back on itself
+        if metadata.target().starts_with(module_path!()) {
+            return;
+        }
+        let Some(shipper) = current() else {
+            return;
+        };
+        if !shipper.config.server_logs || *metadata.level() > shipper.server_level {
+            return;
+        }
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        shipper.send(LogRecord {
+            timestamp: Utc::now(),
+            level: level_name(metadata.level()),
+            source: LogSource::Server,
+            message: visitor.message,
+            target: Some(metadata.target().to_string()),
+            stream: None,
+            workspace_id: None,
+            task_id: None,
+            execution_process_id: None,
+            fields: visitor.fields,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn execution_record(message: &str) -> LogRecord {
+        LogRecord {
+            timestamp: Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap(),
+            level: "info",
+            source: LogSource::Execution,
+            message: message.to_string(),
+            target: None,
+            stream: Some(LogStream::Stdout),
+            workspace_id: Some(Uuid::from_u128(1)),
+            task_id: Some(Uuid::from_u128(2)),
+            execution_process_id: Some(Uuid::from_u128(3)),
+            fields: BTreeMap::new(),
+        }
+    }
+
+    fn labels() -> BTreeMap<String, String> {
+        BTreeMap::from([("env".to_string(), "prod".to_string())])
+    }
+
+    #[test]
+    fn json_lines_carry_ids_and_labels() {
+        let body = json_lines(&[execution_record("cargo build")], &labels());
+        let value: serde_json::Value = serde_json::from_str(body.trim_end()).unwrap();
+        assert_eq!(value["message"], "cargo build");
+        assert_eq!(value["source"], "execution");
+        assert_eq!(value["stream"], "stdout");
+        assert_eq!(value["workspace_id"], Uuid::from_u128(1).to_string());
+        assert_eq!(value["task_id"], Uuid::from_u128(2).to_string());
+        assert_eq!(value["env"], "prod");
+        assert!(value.get("target").is_none());
+    }
+
+    #[test]
+    fn loki_streams_are_keyed_by_workspace_and_task() {
+        let mut other = execution_record("other");
+        other.workspace_id = Some(Uuid::from_u128(9));
+        let body = loki_push(
+            &[execution_record("a"), execution_record("b"), other],
+            &labels(),
+        );
+        let streams = body["streams"].as_array().unwrap();
+        assert_eq!(streams.len(), 2);
+        let first = &streams[0];
+        assert_eq!(
+            first["stream"]["workspace_id"],
+            Uuid::from_u128(1).to_string()
+        );
+        assert_eq!(first["stream"]["task_id"], Uuid::from_u128(2).to_string());
+        assert_eq!(first["stream"]["source"], "execution");
+        assert_eq!(first["stream"]["env"], "prod");
+        assert_eq!(first["values"].as_array().unwrap().len(), 2);
+        assert_eq!(first["values"][0][0], "1740830400000000000");
+    }
+
+    #[test]
+    fn elasticsearch_bulk_pairs_actions_with_documents() {
+        let body = elasticsearch_bulk("logs", &[execution_record("a")], &labels());
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], r#"{"index":{"_index":"logs"}}"#);
+        let document: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(document["@timestamp"], "2025-03-01T12:00:00.000Z");
+    }
+
+    #[test]
+    fn syslog_messages_follow_rfc_5424() {
+        let message = syslog_message(&execution_record("done \"ok\""), &BTreeMap::new());
+        assert_eq!(
+            message,
+            format!(
+                "<14>1 2025-03-01T12:00:00.000000Z - vibe-kanban - execution \
+                 [vk@32473 workspace_id=\"{}\" task_id=\"{}\" execution_process_id=\"{}\"] done \"ok\"",
+                Uuid::from_u128(1),
+                Uuid::from_u128(2),
+                Uuid::from_u128(3)
+            )
+        );
+    }
+
+    #[test]
+    fn enabled_config_needs_a_usable_endpoint() {
+        let mut config = LogShippingConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        config.endpoint = Some("http://collector:8080/logs".to_string());
+        assert!(config.validate().is_ok());
+        config.sink = LogSink::Syslog;
+        assert!(config.validate().is_err());
+        config.endpoint = Some("syslog.internal:514".to_string());
+        assert!(config.validate().is_ok());
+        config.server_log_level = "loud".to_string();
+        assert!(config.validate().is_err());
+    }
+}
@@ -101,6 +101,73 @@ pub fn normalize_macos_private_alias<P: AsRef<Path>>(p: P) -> PathBuf {
     p.to_path_buf()
 }
 
+/// Windows refuses plain paths longer than `MAX_PATH` (260 chars) unless they use the
+/// verbatim `\\?\` prefix. Deeply nested worktrees under the temp dir hit this quickly.
+const WINDOWS_MAX_PATH: usize = 260;
+
+/// Join a user-supplied relative path (e.g. a `working_dir` stored in the DB) onto `base`.
+///
+/// Relative paths are persisted with either `/` or `\` separators depending on the platform
+/// they were entered on, so split on both and push each component individually. This keeps
+/// the result consistent with the native separator instead of producing mixed paths like
+/// `C:\work\repo/packages/app` that some Windows tools reject.
+pub fn join_relative<P: AsRef<Path>>(base: P, relative: &str) -> PathBuf {
+    let mut joined = base.as_ref().to_path_buf();
+    for component in relative
+        .split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+    {
+        joined.push(component);
+    }
+    joined
+}
+
+/// Make an absolute path safe to hand to Windows filesystem APIs regardless of its length.
+///
+/// On Windows, paths longer than `MAX_PATH` are converted to the verbatim form (`\\?\C:\...`
+/// or `\\?\UNC\server\share\...`). Shorter paths, relative paths and all paths on other
+/// platforms are returned unchanged.
+pub fn to_long_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = path.as_ref();
+    if !cfg!(windows) || !path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    let raw = path.to_string_lossy();
+    if raw.len() < WINDOWS_MAX_PATH || raw.starts_with(r"\\?\") {
+        return path.to_path_buf();
+    }
+
+    let normalized = raw.replace('/', "\\");
+    match normalized.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{unc}")),
+        None => PathBuf::from(format!(r"\\?\{normalized}")),
+    }
+}
+
+/// Compare two paths for equality, ignoring separator style and the Windows verbatim prefix.
+///
+/// Git records worktree locations with forward slashes even on Windows (`C:/Users/...`), so a
+/// byte-wise comparison against a native `PathBuf` never matches there.
+pub fn paths_equivalent<A: AsRef<Path>, B: AsRef<Path>>(a: A, b: B) -> bool {
+    fn canonical_form(p: &Path) -> String {
+        let p = normalize_macos_private_alias(p);
+        let s = p.to_string_lossy();
+        let s = s
+            .strip_prefix(r"\\?\UNC\")
+            .map(|rest| format!(r"\\{rest}"))
+            .unwrap_or_else(|| s.strip_prefix(r"\\?\").unwrap_or(&*s).to_string());
+        let s = s.replace('\\', "/");
+        let s = s.trim_end_matches('/');
+        if cfg!(windows) {
+            s.to_lowercase()
+        } else {
+            s.to_string()
+        }
+    }
+    canonical_form(a.as_ref()) == canonical_form(b.as_ref())
+}
+
 pub fn get_vibe_kanban_temp_dir() -> std::path::PathBuf {
     let dir_name = if cfg!(debug_assertions) {
         "vibe-kanban-dev"
@@ -150,6 +217,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_join_relative_mixed_separators() {
+        let base = Path::new("/tmp/workspace");
+        let expected = base.join("packages").join("app");
+        assert_eq!(join_relative(base, "packages/app"), expected);
+        assert_eq!(join_relative(base, "packages\\app"), expected);
+        assert_eq!(join_relative(base, "./packages//app/"), expected);
+        assert_eq!(join_relative(base, ""), base.to_path_buf());
+    }
+
+    #[test]
+    fn test_paths_equivalent_ignores_separator_style() {
+        assert!(paths_equivalent("/tmp/wt/repo", "/tmp/wt/repo/"));
+        assert!(paths_equivalent("/tmp/wt/repo", "\\tmp\\wt\\repo"));
+        assert!(!paths_equivalent("/tmp/wt/repo", "/tmp/wt/other"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_to_long_path_is_noop_off_windows() {
+        let long = format!("/tmp/{}", "a".repeat(300));
+        assert_eq!(to_long_path(&long), PathBuf::from(&long));
+    }
+
     #[cfg(target_os = "macos")]
     #[test]
     fn test_make_path_relative_macos_private_alias() {
@@ -1,5 +1,9 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
 use ts_rs::TS;
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, TS)]
 pub struct ApiResponse<T, E = T> {
@@ -7,6 +11,184 @@ pub struct ApiResponse<T, E = T> {
     data: Option<T>,
     error_data: Option<E>,
     message: Option<String>,
+    /// Set when `data` is one page of a longer list
+    #[serde(default)]
+    pagination: Option<Pagination>,
+}
+
+/// Where a page sits in a list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct Pagination {
+    /// Pass as `cursor` to fetch the next page; None on the last page
+    pub next_cursor: Option<String>,
+    pub limit: u32,
+    /// Length of the whole list when the page was read; it may change before the next page
+    pub total_estimate: u64,
+}
+
+/// Query parameters of list endpoints. Without either, the whole list is returned.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct PageQuery {
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    /// Page size, 50 by default and at most 500
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Error)]
+#[error("Unknown cursor {0}; the item it points at may have been deleted")]
+pub struct InvalidCursor(pub String);
+
+/// Position of a row in a list ordered by `(created_at, id)`, which is what the cursors of such
+/// lists point at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keyset {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Keyset {
+    pub fn new(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { created_at, id }
+    }
+
+    fn to_cursor(self) -> String {
+        format!("{}_{}", self.created_at.timestamp_micros(), self.id)
+    }
+
+    fn from_cursor(cursor: &str) -> Option<Self> {
+        let (micros, id) = cursor.split_once('_')?;
+        Some(Self {
+            created_at: DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+/// The rows to read in SQL for one page of a list ordered by `(created_at, id)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeysetPage {
+    /// Only rows after this one in list order; None from the start of the list
+    pub after: Option<Keyset>,
+    /// `LIMIT` of the query: one row more than the page, which tells whether there is a next
+    /// page, or -1 for the whole list
+    pub fetch: i64,
+}
+
+impl KeysetPage {
+    /// The whole list
+    pub const ALL: Self = Self {
+        after: None,
+        fetch: -1,
+    };
+}
+
+impl PageQuery {
+    pub const DEFAULT_LIMIT: u32 = 50;
+    pub const MAX_LIMIT: u32 = 500;
+
+    /// Page size, or None when the whole list was asked for
+    fn page_limit(&self) -> Option<u32> {
+        if self.cursor.is_none() && self.limit.is_none() {
+            return None;
+        }
+        Some(
+            self.limit
+                .unwrap_or(Self::DEFAULT_LIMIT)
+                .clamp(1, Self::MAX_LIMIT),
+        )
+    }
+
+    /// What to read for this page of a list the database orders by `(created_at, id)`; the rows
+    /// read go to [`Self::keyset_page`]
+    pub fn keyset(&self) -> Result<KeysetPage, InvalidCursor> {
+        let Some(limit) = self.page_limit() else {
+            return Ok(KeysetPage::ALL);
+        };
+        let after = match &self.cursor {
+            Some(cursor) => {
+                Some(Keyset::from_cursor(cursor).ok_or_else(|| InvalidCursor(cursor.clone()))?)
+            }
+            None => None,
+        };
+        Ok(KeysetPage {
+            after,
+            fetch: i64::from(limit) + 1,
+        })
+    }
+
+    /// The page among `rows`, read for [`Self::keyset`], in a list of about `total_estimate` rows
+    pub fn keyset_page<T>(
+        &self,
+        mut rows: Vec<T>,
+        total_estimate: u64,
+        key: impl Fn(&T) -> Keyset,
+    ) -> (Vec<T>, Option<Pagination>) {
+        let Some(limit) = self.page_limit() else {
+            return (rows, None);
+        };
+        // The row past the page tells whether there is a next one
+        let next_cursor = if rows.len() > limit as usize {
+            rows.truncate(limit as usize);
+            rows.last().map(|row| key(row).to_cursor())
+        } else {
+            None
+        };
+        (
+            rows,
+            Some(Pagination {
+                next_cursor,
+                limit,
+                total_estimate,
+            }),
+        )
+    }
+
+    /// Take the page after the item whose key is the cursor from a list in its final order, for
+    /// lists that are not read from the database, like the comments of a pull request. Keys must
+    /// be unique within the list and are handed out as cursors.
+    pub fn paginate<T>(
+        &self,
+        items: Vec<T>,
+        key: impl Fn(&T) -> String,
+    ) -> Result<(Vec<T>, Option<Pagination>), InvalidCursor> {
+        let Some(limit) = self.page_limit() else {
+            return Ok((items, None));
+        };
+        let total_estimate = items.len() as u64;
+
+        let start = match &self.cursor {
+            Some(cursor) => {
+                items
+                    .iter()
+                    .position(|item| &key(item) == cursor)
+                    .ok_or_else(|| InvalidCursor(cursor.clone()))?
+                    + 1
+            }
+            None => 0,
+        };
+        let mut page: Vec<T> = items
+            .into_iter()
+            .skip(start)
+            .take(limit as usize + 1)
+            .collect();
+        // One item past the page tells whether there is a next one
+        let next_cursor = if page.len() > limit as usize {
+            page.truncate(limit as usize);
+            page.last().map(&key)
+        } else {
+            None
+        };
+
+        Ok((
+            page,
+            Some(Pagination {
+                next_cursor,
+                limit,
+                total_estimate,
+            }),
+        ))
+    }
 }
 
 impl<T, E> ApiResponse<T, E> {
@@ -17,6 +199,16 @@ impl<T, E> ApiResponse<T, E> {
             data: Some(data),
             message: None,
             error_data: None,
+            pagination: None,
+        }
+    }
+
+    /// Creates a successful response holding one page of a list, or the whole list when
+    /// `pagination` is None.
+    pub fn success_page(data: T, pagination: Option<Pagination>) -> Self {
+        ApiResponse {
+            pagination,
+            ..Self::success(data)
         }
     }
 
@@ -27,6 +219,7 @@ impl<T, E> ApiResponse<T, E> {
             data: None,
             message: Some(message.to_string()),
             error_data: None,
+            pagination: None,
         }
     }
     /// Creates an error response, with no `data`, no `message`, but with arbitrary `error_data`.
@@ -36,6 +229,7 @@ impl<T, E> ApiResponse<T, E> {
             data: None,
             error_data: Some(data),
             message: None,
+            pagination: None,
         }
     }
 
@@ -53,4 +247,186 @@ impl<T, E> ApiResponse<T, E> {
     pub fn message(&self) -> Option<&str> {
         self.message.as_deref()
     }
+
+    /// Returns the position of the page if `data` is one page of a list.
+    pub fn pagination(&self) -> Option<&Pagination> {
+        self.pagination.as_ref()
+    }
+}
+
+/// Media type of [`ProblemDetails`] bodies
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Error body in the RFC 7807 `application/problem+json` format. The last segment of `type` is a
+/// stable snake_case code to branch on; `extensions` holds the fields of the problem, e.g. the
+/// `branch` of a `target_branch_not_found`, as top-level members.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Path of the request that failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+impl ProblemDetails {
+    pub const TYPE_PREFIX: &str = "/problems/";
+
+    pub fn new(status: u16, code: &str) -> Self {
+        ProblemDetails {
+            problem_type: format!("{}{code}", Self::TYPE_PREFIX),
+            title: title_from_code(code),
+            status,
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+        }
+    }
+
+    /// Problem for a typed route error serialized with `#[serde(tag = "type")]`: the tag becomes
+    /// the code and the variant's fields become extension members.
+    pub fn from_error_data<E: Serialize>(status: u16, error: &E) -> Self {
+        match serde_json::to_value(error) {
+            Ok(Value::Object(mut fields)) => {
+                let code = match fields.remove("type") {
+                    Some(Value::String(code)) => code,
+                    _ => "error".to_string(),
+                };
+                ProblemDetails {
+                    extensions: fields,
+                    ..Self::new(status, &code)
+                }
+            }
+            Ok(Value::String(code)) => Self::new(status, &code.to_lowercase()),
+            _ => Self::new(status, "error"),
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn with_extension(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.extensions.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// The snake_case code at the end of `type`
+    pub fn code(&self) -> &str {
+        self.problem_type
+            .rsplit('/')
+            .next()
+            .unwrap_or(&self.problem_type)
+    }
+}
+
+/// "target_branch_not_found" -> "Target branch not found"
+fn title_from_code(code: &str) -> String {
+    let words = code.replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(cursor: Option<&str>, limit: Option<u32>) -> PageQuery {
+        PageQuery {
+            cursor: cursor.map(str::to_string),
+            limit,
+        }
+    }
+
+    #[test]
+    fn pages_follow_cursors_to_the_end() {
+        let items: Vec<u32> = (1..=5).collect();
+        let key = |n: &u32| n.to_string();
+
+        let (all, pagination) = query(None, None).paginate(items.clone(), key).unwrap();
+        assert_eq!(all.len(), 5);
+        assert!(pagination.is_none());
+
+        let (first, pagination) = query(None, Some(2)).paginate(items.clone(), key).unwrap();
+        let pagination = pagination.unwrap();
+        assert_eq!(first, vec![1, 2]);
+        assert_eq!(pagination.next_cursor.as_deref(), Some("2"));
+        assert_eq!(pagination.total_estimate, 5);
+
+        let (last, pagination) = query(Some("4"), Some(2))
+            .paginate(items.clone(), key)
+            .unwrap();
+        assert_eq!(last, vec![5]);
+        assert_eq!(pagination.unwrap().next_cursor, None);
+
+        assert!(query(Some("9"), None).paginate(items, key).is_err());
+    }
+
+    #[test]
+    fn keyset_pages_read_one_row_past_the_page() {
+        assert_eq!(query(None, None).keyset().unwrap(), KeysetPage::ALL);
+
+        let rows: Vec<Keyset> = (1..=3)
+            .map(|second| Keyset::new(DateTime::from_timestamp(second, 0).unwrap(), Uuid::new_v4()))
+            .collect();
+        let first = query(None, Some(2));
+        assert_eq!(
+            first.keyset().unwrap(),
+            KeysetPage {
+                after: None,
+                fetch: 3
+            }
+        );
+        let (page, pagination) = first.keyset_page(rows.clone(), 5, |row| *row);
+        assert_eq!(page, rows[..2]);
+        let pagination = pagination.unwrap();
+        assert_eq!(pagination.total_estimate, 5);
+
+        let next = query(pagination.next_cursor.as_deref(), Some(2));
+        assert_eq!(next.keyset().unwrap().after, Some(rows[1]));
+        let (page, pagination) = next.keyset_page(rows[2..].to_vec(), 5, |row| *row);
+        assert_eq!(page, rows[2..]);
+        assert_eq!(pagination.unwrap().next_cursor, None);
+
+        assert!(query(Some("not-a-cursor"), None).keyset().is_err());
+    }
+
+    #[derive(Serialize)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    enum CreateError {
+        TargetBranchNotFound { branch: String },
+        NotLoggedIn,
+    }
+
+    #[test]
+    fn typed_errors_become_problems_with_extensions() {
+        let problem = ProblemDetails::from_error_data(
+            422,
+            &CreateError::TargetBranchNotFound {
+                branch: "main".to_string(),
+            },
+        );
+        assert_eq!(problem.problem_type, "/problems/target_branch_not_found");
+        assert_eq!(problem.code(), "target_branch_not_found");
+        assert_eq!(problem.title, "Target branch not found");
+
+        let body = serde_json::to_value(&problem).unwrap();
+        assert_eq!(body["branch"], "main");
+        assert_eq!(body["status"], 422);
+        assert!(body.get("detail").is_none());
+
+        let problem = ProblemDetails::from_error_data(424, &CreateError::NotLoggedIn);
+        assert_eq!(problem.code(), "not_logged_in");
+        assert!(problem.extensions.is_empty());
+    }
 }
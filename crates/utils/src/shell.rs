@@ -35,8 +35,14 @@ pub async fn resolve_executable_path(executable: &str) -> Option<PathBuf> {
     }
 
     let path = Path::new(executable);
-    if path.is_absolute() && path.is_file() {
-        return Some(path.to_path_buf());
+    if path.is_absolute() {
+        if path.is_file() {
+            return Some(path.to_path_buf());
+        }
+        // On Windows an explicit path may omit the extension of an `.exe`/`.cmd` shim
+        if let Some(found) = with_windows_extensions(path) {
+            return Some(found);
+        }
     }
 
     if let Some(found) = which(executable).await {
@@ -56,6 +62,57 @@ pub fn resolve_executable_path_blocking(executable: &str) -> Option<PathBuf> {
     block_on(resolve_executable_path(executable))
 }
 
+/// Returns the shell used to run a hook script (setup, cleanup, dev server).
+///
+/// Scripts run in the platform shell, `cmd /C` on Windows. A script opts into bash on Windows
+/// with a `#!` line naming `bash` or `sh`, e.g. `#!/usr/bin/env bash`, and then runs under a
+/// `bash` from Git for Windows or MSYS2 when one is installed; the WSL launcher in `System32` is
+/// skipped because it runs in a separate Linux filesystem.
+pub async fn get_script_shell_command(script: &str) -> (String, &'static str) {
+    if cfg!(windows)
+        && wants_bash(script)
+        && let Some(bash) = resolve_executable_path("bash").await
+        && !bash
+            .to_string_lossy()
+            .to_ascii_lowercase()
+            .contains("\\system32\\")
+    {
+        return (bash.to_string_lossy().into_owned(), "-c");
+    }
+    get_shell_command()
+}
+
+/// Whether the `#!` line of a script asks for bash or sh
+fn wants_bash(script: &str) -> bool {
+    let Some(interpreter) = script
+        .trim_start()
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("#!"))
+    else {
+        return false;
+    };
+    interpreter
+        .split(|c: char| c == '/' || c.is_whitespace())
+        .any(|part| part == "bash" || part == "sh")
+}
+
+/// Try the extensions listed in `PATHEXT` (falling back to the usual `.exe`, `.cmd`, `.bat`)
+/// for an explicit path that has none. Always `None` on non-Windows platforms.
+fn with_windows_extensions(path: &Path) -> Option<PathBuf> {
+    if !cfg!(windows) || path.extension().is_some() {
+        return None;
+    }
+
+    let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| ".EXE;.CMD;.BAT;.COM".to_string());
+    pathext
+        .split(';')
+        .map(|ext| ext.trim().trim_start_matches('.'))
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| path.with_extension(ext.to_ascii_lowercase()))
+        .find(|candidate| candidate.is_file())
+}
+
 /// Merge two PATH strings into a single, de-duplicated PATH.
 ///
 /// - Keeps the order of entries from `primary`.
@@ -0,0 +1,49 @@
+//! Deterministic test mode, turned on by the server's `--test-mode` flag.
+//!
+//! End-to-end tests of the server and frontend need runs that look the same every time and touch
+//! nothing outside the machine. In test mode the server sends no analytics or error reports,
+//! opens no browser, plays and shows no notifications and answers every git provider call from an
+//! in-memory mock. Timestamps the app makes itself, like those of seeded rows, mock pull requests
+//! and commits, come from [`now`], which stays at [`FROZEN_TIME`]. Rows stamped by SQLite defaults
+//! still get the real time.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{DateTime, Utc};
+
+/// What [`now`] returns in test mode: 2024-01-01T00:00:00Z
+pub const FROZEN_TIME: i64 = 1_704_067_200;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn test mode on for the rest of the process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// The current time, or [`FROZEN_TIME`] in test mode
+pub fn now() -> DateTime<Utc> {
+    if is_enabled() {
+        frozen_time()
+    } else {
+        Utc::now()
+    }
+}
+
+pub fn frozen_time() -> DateTime<Utc> {
+    DateTime::from_timestamp(FROZEN_TIME, 0).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frozen_time_is_new_year_2024() {
+        assert_eq!(frozen_time().to_rfc3339(), "2024-01-01T00:00:00+00:00");
+    }
+}
@@ -7,6 +7,7 @@ import {
   useMemo,
 } from 'react';
 import { useQuery, useQueryClient } from '@tanstack/react-query';
+import * as Sentry from '@sentry/react';
 import {
   type Config,
   type Environment,
@@ -84,6 +85,13 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
       BaseAgentCapability[]
     > | null) || null;
 
+  // A server in test mode runs end-to-end tests, whose errors are not reported
+  useEffect(() => {
+    if (userSystemInfo?.test_mode) {
+      Sentry.close();
+    }
+  }, [userSystemInfo?.test_mode]);
+
   // Sync language with i18n when config changes
   useEffect(() => {
     if (config?.language) {
@@ -0,0 +1,139 @@
+import { useState, useEffect, useCallback } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import { Loader2 } from 'lucide-react';
+import { updateApi } from '@/lib/api';
+import type { UpdateStatus } from 'shared/types';
+
+const POLL_INTERVAL_MS = 5000;
+
+export function UpdateManager() {
+  const { t } = useTranslation('settings');
+  const [status, setStatus] = useState<UpdateStatus | null>(null);
+  const [loading, setLoading] = useState(true);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchStatus = useCallback(async () => {
+    try {
+      setStatus(await updateApi.getStatus());
+      setError(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchStatus();
+  }, [fetchStatus]);
+
+  // Follow the install until the server restarts into the new version
+  const installing = status !== null && status.state.state !== 'idle';
+  useEffect(() => {
+    if (!installing) return;
+    const timer = setInterval(fetchStatus, POLL_INTERVAL_MS);
+    return () => clearInterval(timer);
+  }, [installing, fetchStatus]);
+
+  const run = useCallback(async (action: () => Promise<UpdateStatus>) => {
+    setBusy(true);
+    try {
+      setStatus(await action());
+      setError(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }, []);
+
+  if (loading) {
+    return (
+      <div className="flex items-center text-sm text-muted-foreground">
+        <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+        {t('settings.general.updates.checking')}
+      </div>
+    );
+  }
+
+  if (!status) {
+    return <p className="text-sm text-destructive">{error}</p>;
+  }
+
+  const state = status.state;
+  return (
+    <div className="space-y-3">
+      <p className="text-sm">
+        {t('settings.general.updates.current', {
+          version: status.current_version,
+        })}
+        {status.latest_version && (
+          <>
+            {' · '}
+            {t('settings.general.updates.latest', {
+              version: status.latest_version,
+            })}
+          </>
+        )}
+      </p>
+
+      {status.unsupported_reason ? (
+        <p className="text-sm text-muted-foreground">
+          {t('settings.general.updates.unsupported', {
+            reason: status.unsupported_reason,
+          })}
+        </p>
+      ) : state.state === 'draining' ? (
+        <div className="flex items-center justify-between">
+          <p className="text-sm text-muted-foreground">
+            {t('settings.general.updates.draining', {
+              version: state.version,
+              count: state.running,
+            })}
+          </p>
+          <Button
+            variant="outline"
+            onClick={() => run(updateApi.cancel)}
+            disabled={busy}
+          >
+            {t('settings.general.updates.cancel')}
+          </Button>
+        </div>
+      ) : state.state === 'restarting' ? (
+        <p className="text-sm text-muted-foreground">
+          {t('settings.general.updates.restarting', { version: state.version })}
+        </p>
+      ) : status.staged_version ? (
+        <div className="flex items-center justify-between">
+          <p className="text-sm text-muted-foreground">
+            {t('settings.general.updates.staged', {
+              version: status.staged_version,
+            })}
+          </p>
+          <Button onClick={() => run(updateApi.install)} disabled={busy}>
+            {busy && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            {t('settings.general.updates.install')}
+          </Button>
+        </div>
+      ) : status.update_available ? (
+        <div className="flex items-center justify-between">
+          <p className="text-sm text-muted-foreground">
+            {t('settings.general.updates.available')}
+          </p>
+          <Button onClick={() => run(updateApi.download)} disabled={busy}>
+            {busy && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            {t('settings.general.updates.download')}
+          </Button>
+        </div>
+      ) : (
+        <p className="text-sm text-muted-foreground">
+          {t('settings.general.updates.upToDate')}
+        </p>
+      )}
+
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </div>
+  );
+}
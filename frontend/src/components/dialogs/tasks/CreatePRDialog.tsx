@@ -11,9 +11,17 @@ import { Textarea } from '@/components/ui/textarea.tsx';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { Checkbox } from '@/components/ui/checkbox';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
 import { Alert, AlertDescription, AlertTitle } from '@/components/ui/alert';
 import BranchSelector from '@/components/tasks/BranchSelector';
 import { useCallback, useEffect, useMemo, useState } from 'react';
+import { useQuery } from '@tanstack/react-query';
 import { attemptsApi } from '@/lib/api.ts';
 import { useTranslation } from 'react-i18next';
 
@@ -35,6 +43,8 @@ import type { GhCliSetupError } from 'shared/types';
 import { useUserSystem } from '@/components/ConfigProvider';
 import { defineModal } from '@/lib/modals';
 
+const NO_MILESTONE = '__none__';
+
 interface CreatePRDialogProps {
   attempt: Workspace;
   task: TaskWithAttemptStatus;
@@ -58,6 +68,8 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
       null
     );
     const [isDraft, setIsDraft] = useState(false);
+    const [autoMerge, setAutoMerge] = useState(false);
+    const [milestone, setMilestone] = useState<string | null>(null);
     const [autoGenerateDescription, setAutoGenerateDescription] = useState(
       config?.pr_auto_description_enabled ?? false
     );
@@ -67,6 +79,29 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
       { enabled: modal.visible && !!repoId }
     );
 
+    // Providers without milestones fail the request; the picker is hidden
+    const { data: milestones = [] } = useQuery({
+      queryKey: ['milestones', attempt.id, repoId],
+      queryFn: () => attemptsApi.listMilestones(attempt.id, repoId),
+      enabled: modal.visible && !!repoId,
+      retry: false,
+      staleTime: 60_000,
+    });
+
+    // Providers without branch protection fail the request; no warning is shown
+    const { data: protection } = useQuery({
+      queryKey: ['branchProtection', attempt.id, repoId, prBaseBranch],
+      queryFn: () =>
+        attemptsApi.getBranchProtection(attempt.id, repoId, prBaseBranch),
+      enabled: modal.visible && !!repoId && !!prBaseBranch,
+      retry: false,
+      staleTime: 60_000,
+    });
+    const mergeBlocked =
+      !!protection &&
+      (protection.required_checks.length > 0 ||
+        protection.required_approvals > 0);
+
     const getGhCliHelpTitle = (variant: GhCliSupportVariant) =>
       variant === 'homebrew'
         ? 'Homebrew is required for automatic setup'
@@ -143,6 +178,8 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
         draft: isDraft,
         auto_generate_description: autoGenerateDescription,
         repo_id: repoId,
+        milestone,
+        auto_merge: autoMerge ? 'merge' : null,
       });
 
       if (result.success) {
@@ -150,6 +187,8 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
         setPrBody('');
         setPrBaseBranch('');
         setIsDraft(false);
+        setAutoMerge(false);
+        setMilestone(null);
         setAutoGenerateDescription(
           config?.pr_auto_description_enabled ?? false
         );
@@ -225,6 +264,8 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
       prBody,
       prTitle,
       isDraft,
+      autoMerge,
+      milestone,
       autoGenerateDescription,
       config?.pr_auto_description_enabled,
       modal,
@@ -239,6 +280,8 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
       setPrBody('');
       setPrBaseBranch('');
       setIsDraft(false);
+      setAutoMerge(false);
+      setMilestone(null);
       setAutoGenerateDescription(config?.pr_auto_description_enabled ?? false);
     }, [modal, config?.pr_auto_description_enabled]);
 
@@ -328,6 +371,34 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
                     }
                   />
                 </div>
+                {milestones.length > 0 && (
+                  <div className="space-y-2">
+                    <Label htmlFor="pr-milestone">
+                      {t('createPrDialog.milestoneLabel')}
+                    </Label>
+                    <Select
+                      value={milestone ?? NO_MILESTONE}
+                      onValueChange={(value) =>
+                        setMilestone(value === NO_MILESTONE ? null : value)
+                      }
+                    >
+                      <SelectTrigger id="pr-milestone">
+                        <SelectValue />
+                      </SelectTrigger>
+                      <SelectContent>
+                        <SelectItem value={NO_MILESTONE}>
+                          {t('createPrDialog.noMilestone')}
+                        </SelectItem>
+                        {milestones.map((m) => (
+                          <SelectItem key={m.id} value={m.title}>
+                            {m.title}
+                            {m.due_date && ` (${m.due_date})`}
+                          </SelectItem>
+                        ))}
+                      </SelectContent>
+                    </Select>
+                  </div>
+                )}
                 <div className="flex items-center space-x-2">
                   <Checkbox
                     id="pr-draft"
@@ -339,6 +410,29 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
                     {t('createPrDialog.draftLabel')}
                   </Label>
                 </div>
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="pr-auto-merge"
+                    checked={autoMerge}
+                    onCheckedChange={setAutoMerge}
+                    className="h-5 w-5"
+                  />
+                  <Label
+                    htmlFor="pr-auto-merge"
+                    className="cursor-pointer text-sm"
+                  >
+                    {t('createPrDialog.autoMergeLabel')}
+                  </Label>
+                </div>
+                {mergeBlocked && !autoMerge && (
+                  <Alert variant="default">
+                    <AlertDescription>
+                      {t('createPrDialog.protectedBranchWarning', {
+                        branch: prBaseBranch,
+                      })}
+                    </AlertDescription>
+                  </Alert>
+                )}
                 {ghCliHelp?.variant && (
                   <Alert variant="default">
                     <AlertTitle>
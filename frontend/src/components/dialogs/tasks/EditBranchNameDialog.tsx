@@ -17,6 +17,8 @@ import { useRenameBranch } from '@/hooks/useRenameBranch';
 export interface EditBranchNameDialogProps {
   attemptId: string;
   currentBranchName: string;
+  /** `updated_at` of the attempt when the dialog was opened */
+  attemptUpdatedAt?: string;
 }
 
 export type EditBranchNameDialogResult = {
@@ -25,7 +27,7 @@ export type EditBranchNameDialogResult = {
 };
 
 const EditBranchNameDialogImpl = NiceModal.create<EditBranchNameDialogProps>(
-  ({ attemptId, currentBranchName }) => {
+  ({ attemptId, currentBranchName, attemptUpdatedAt }) => {
     const modal = useModal();
     const { t } = useTranslation(['tasks', 'common']);
     const [branchName, setBranchName] = useState<string>(currentBranchName);
@@ -47,7 +49,8 @@ const EditBranchNameDialogImpl = NiceModal.create<EditBranchNameDialogProps>(
       },
       (err: unknown) => {
         setError(getErrorMessage(err) || 'Failed to rename branch');
-      }
+      },
+      attemptUpdatedAt
     );
 
     const handleConfirm = () => {
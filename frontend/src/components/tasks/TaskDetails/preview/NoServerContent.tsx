@@ -134,6 +134,7 @@ export function NoServerContent({
         parent_workspace_id: null,
         image_ids: null,
         shared_task_id: null,
+        due_at: null,
       },
       executor_profile_id: config.executor_profile,
       repos,
@@ -29,7 +29,7 @@ import { CreatePRDialog } from '@/components/dialogs/tasks/CreatePRDialog';
 import { useTranslation } from 'react-i18next';
 import { useAttemptRepo } from '@/hooks/useAttemptRepo';
 import { useGitOperations } from '@/hooks/useGitOperations';
-import { useRepoBranches } from '@/hooks';
+import { useProjectPermissions, useRepoBranches } from '@/hooks';
 
 interface GitOperationsProps {
   selectedAttempt: Workspace;
@@ -57,6 +57,7 @@ function GitOperations({
   );
   const git = useGitOperations(selectedAttempt.id, selectedRepoId ?? undefined);
   const { data: branches = [] } = useRepoBranches(selectedRepoId);
+  const { can } = useProjectPermissions(task.project_id);
   const isChangingTargetBranch = git.states.changeTargetBranchPending;
 
   // Local state for git operations
@@ -73,6 +74,7 @@ function GitOperations({
     await git.actions.changeTargetBranch({
       newTargetBranch: newBranch,
       repoId,
+      expectedUpdatedAt: selectedAttempt.updated_at,
     });
   };
 
@@ -465,49 +467,55 @@ function GitOperations({
         {/* Right: Actions */}
         {selectedRepoStatus && (
           <div className={actionsClasses}>
-            <Button
-              onClick={handleMergeClick}
-              disabled={
-                mergeInfo.hasMergedPR ||
-                mergeInfo.hasOpenPR ||
-                merging ||
-                hasConflictsCalculated ||
-                isAttemptRunning ||
-                ((selectedRepoStatus?.commits_ahead ?? 0) === 0 &&
-                  !pushSuccess &&
-                  !mergeSuccess)
-              }
-              variant="outline"
-              size="xs"
-              className="border-success text-success hover:bg-success gap-1 shrink-0"
-              aria-label={mergeButtonLabel}
-            >
-              <GitBranchIcon className="h-3.5 w-3.5" />
-              <span className="truncate max-w-[10ch]">{mergeButtonLabel}</span>
-            </Button>
+            {can('merge') && (
+              <Button
+                onClick={handleMergeClick}
+                disabled={
+                  mergeInfo.hasMergedPR ||
+                  mergeInfo.hasOpenPR ||
+                  merging ||
+                  hasConflictsCalculated ||
+                  isAttemptRunning ||
+                  ((selectedRepoStatus?.commits_ahead ?? 0) === 0 &&
+                    !pushSuccess &&
+                    !mergeSuccess)
+                }
+                variant="outline"
+                size="xs"
+                className="border-success text-success hover:bg-success gap-1 shrink-0"
+                aria-label={mergeButtonLabel}
+              >
+                <GitBranchIcon className="h-3.5 w-3.5" />
+                <span className="truncate max-w-[10ch]">
+                  {mergeButtonLabel}
+                </span>
+              </Button>
+            )}
 
-            <Button
-              onClick={handlePRButtonClick}
-              disabled={
-                mergeInfo.hasMergedPR ||
-                pushing ||
-                isAttemptRunning ||
-                hasConflictsCalculated ||
-                (mergeInfo.hasOpenPR &&
-                  (selectedRepoStatus?.remote_commits_ahead ?? 0) === 0) ||
-                ((selectedRepoStatus?.commits_ahead ?? 0) === 0 &&
-                  (selectedRepoStatus?.remote_commits_ahead ?? 0) === 0 &&
-                  !pushSuccess &&
-                  !mergeSuccess)
-              }
-              variant="outline"
-              size="xs"
-              className="border-info text-info hover:bg-info gap-1 shrink-0"
-              aria-label={prButtonLabel}
-            >
-              <GitPullRequest className="h-3.5 w-3.5" />
-              <span className="truncate max-w-[10ch]">{prButtonLabel}</span>
-            </Button>
+            {can('create_pr') && (
+              <Button
+                onClick={handlePRButtonClick}
+                disabled={
+                  mergeInfo.hasMergedPR ||
+                  pushing ||
+                  isAttemptRunning ||
+                  hasConflictsCalculated ||
+                  (mergeInfo.hasOpenPR &&
+                    (selectedRepoStatus?.remote_commits_ahead ?? 0) === 0) ||
+                  ((selectedRepoStatus?.commits_ahead ?? 0) === 0 &&
+                    (selectedRepoStatus?.remote_commits_ahead ?? 0) === 0 &&
+                    !pushSuccess &&
+                    !mergeSuccess)
+                }
+                variant="outline"
+                size="xs"
+                className="border-info text-info hover:bg-info gap-1 shrink-0"
+                aria-label={prButtonLabel}
+              >
+                <GitPullRequest className="h-3.5 w-3.5" />
+                <span className="truncate max-w-[10ch]">{prButtonLabel}</span>
+              </Button>
+            )}
 
             <Button
               onClick={handleRebaseDialogOpen}
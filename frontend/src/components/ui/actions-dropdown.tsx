@@ -138,6 +138,7 @@ export function ActionsDropdown({
     EditBranchNameDialog.show({
       attemptId: attempt.id,
       currentBranchName: attempt.branch,
+      attemptUpdatedAt: attempt.updated_at,
     });
   };
   const handleShare = (e: React.MouseEvent) => {
@@ -9,6 +9,7 @@ import { repoBranchKeys } from './useRepoBranches';
 type ChangeTargetBranchParams = {
   newTargetBranch: string;
   repoId: string;
+  expectedUpdatedAt?: string;
 };
 
 export function useChangeTargetBranch(
@@ -24,7 +25,7 @@ export function useChangeTargetBranch(
     unknown,
     ChangeTargetBranchParams
   >({
-    mutationFn: async ({ newTargetBranch, repoId }) => {
+    mutationFn: async ({ newTargetBranch, repoId, expectedUpdatedAt }) => {
       if (!attemptId) {
         throw new Error('Attempt id is not set');
       }
@@ -32,6 +33,7 @@ export function useChangeTargetBranch(
       const payload: ChangeTargetBranchRequest = {
         new_target_branch: newTargetBranch,
         repo_id: repoId,
+        expected_updated_at: expectedUpdatedAt ?? null,
       };
       return attemptsApi.change_target_branch(attemptId, payload);
     },
@@ -1,6 +1,7 @@
 import { useEffect, useState, useRef } from 'react';
 import { applyPatch } from 'rfc6902';
 import type { Operation } from 'rfc6902';
+import { withTenant } from '@/lib/api';
 
 type WsJsonPatchMsg = { JsonPatch: Operation[] };
 type WsFinishedMsg = { finished: boolean };
@@ -92,7 +93,7 @@ export const useJsonPatchWsStream = <T extends object>(
       finishedRef.current = false;
 
       // Convert HTTP endpoint to WebSocket endpoint
-      const wsEndpoint = endpoint.replace(/^http/, 'ws');
+      const wsEndpoint = withTenant(endpoint).replace(/^http/, 'ws');
       const ws = new WebSocket(wsEndpoint);
 
       ws.onopen = () => {
@@ -1,5 +1,6 @@
 import { useEffect, useState, useRef } from 'react';
 import type { PatchType } from 'shared/types';
+import { withTenant } from '@/lib/api';
 
 type LogEntry = Extract<PatchType, { type: 'STDOUT' } | { type: 'STDERR' }>;
 
@@ -29,7 +30,9 @@ export const useLogStream = (processId: string): UseLogStreamResult => {
       const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
       const host = window.location.host;
       const ws = new WebSocket(
-        `${protocol}//${host}/api/execution-processes/${processId}/raw-logs/ws`
+        withTenant(
+          `${protocol}//${host}/api/execution-processes/${processId}/raw-logs/ws`
+        )
       );
       wsRef.current = ws;
       isIntentionallyClosed.current = false;
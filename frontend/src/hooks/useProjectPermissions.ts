@@ -0,0 +1,29 @@
+import { useCallback } from 'react';
+import { useQuery } from '@tanstack/react-query';
+import { projectsApi } from '@/lib/api';
+import type {
+  EffectiveProjectPermissions,
+  ProjectPermission,
+} from 'shared/types';
+
+/**
+ * What the signed-in user may do in a project. Actions are allowed while
+ * loading, as the server refuses them anyway.
+ */
+export function useProjectPermissions(projectId?: string) {
+  const query = useQuery<EffectiveProjectPermissions, Error>({
+    queryKey: ['project', 'permissions', projectId],
+    queryFn: () => projectsApi.getPermissions(projectId!),
+    enabled: Boolean(projectId),
+    staleTime: 60 * 1000,
+  });
+
+  const permissions = query.data?.permissions;
+  const can = useCallback(
+    (permission: ProjectPermission) =>
+      !permissions || permissions.includes(permission),
+    [permissions]
+  );
+
+  return { ...query, can };
+}
@@ -4,14 +4,19 @@ import { attemptsApi } from '@/lib/api';
 export function useRenameBranch(
   attemptId?: string,
   onSuccess?: (newBranchName: string) => void,
-  onError?: (err: unknown) => void
+  onError?: (err: unknown) => void,
+  expectedUpdatedAt?: string
 ) {
   const queryClient = useQueryClient();
 
   return useMutation<{ branch: string }, unknown, string>({
     mutationFn: async (newBranchName) => {
       if (!attemptId) throw new Error('Attempt id is not set');
-      return attemptsApi.renameBranch(attemptId, newBranchName);
+      return attemptsApi.renameBranch(
+        attemptId,
+        newBranchName,
+        expectedUpdatedAt
+      );
     },
     onSuccess: (data) => {
       if (attemptId) {
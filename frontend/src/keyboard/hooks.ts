@@ -83,6 +83,22 @@ export const useKeyShowHelp = createSemanticHook(Action.SHOW_HELP);
  */
 export const useKeyDeleteTask = createSemanticHook(Action.DELETE_TASK);
 
+/**
+ * Undo action - typically Cmd+Z
+ *
+ * @example
+ * useKeyUndo(() => undoLastChange(), { scope: Scope.KANBAN });
+ */
+export const useKeyUndo = createSemanticHook(Action.UNDO);
+
+/**
+ * Redo action - typically Cmd+Shift+Z
+ *
+ * @example
+ * useKeyRedo(() => redoLastChange(), { scope: Scope.KANBAN });
+ */
+export const useKeyRedo = createSemanticHook(Action.REDO);
+
 /**
  * Approve pending approval action - typically Enter key
  *
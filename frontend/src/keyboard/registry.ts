@@ -30,6 +30,8 @@ export enum Action {
   SUBMIT_TASK_ALT = 'submit_task_alt',
   SUBMIT_COMMENT = 'submit_comment',
   CYCLE_VIEW_BACKWARD = 'cycle_view_backward',
+  UNDO = 'undo',
+  REDO = 'redo',
 }
 
 export interface KeyBinding {
@@ -172,6 +174,20 @@ export const keyBindings: KeyBinding[] = [
     description: 'Delete selected task',
     group: 'Task Details',
   },
+  {
+    action: Action.UNDO,
+    keys: ['meta+z', 'ctrl+z'],
+    scopes: [Scope.KANBAN],
+    description: 'Undo the last change to a task',
+    group: 'Task Details',
+  },
+  {
+    action: Action.REDO,
+    keys: ['meta+shift+z', 'ctrl+shift+z'],
+    scopes: [Scope.KANBAN],
+    description: 'Redo the last undone change',
+    group: 'Task Details',
+  },
 
   // Approval actions
   {
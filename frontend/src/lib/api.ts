@@ -33,6 +33,7 @@ import {
   UpdateProject,
   UpdateTask,
   UpdateTag,
+  UpdateStatus,
   UserSystemInfo,
   McpServerQuery,
   UpdateMcpServersBody,
@@ -57,6 +58,9 @@ import {
   OrganizationMemberWithProfile,
   ListMembersResponse,
   RemoteProjectMembersResponse,
+  EffectiveProjectPermissions,
+  ListProjectPermissionOverridesResponse,
+  SetProjectPermissionOverrideRequest,
   CreateOrganizationRequest,
   CreateOrganizationResponse,
   CreateInvitationRequest,
@@ -83,12 +87,49 @@ import {
   SharedTaskDetails,
   QueueStatus,
   PrCommentsResponse,
+  GetPrCommentsQuery,
+  PrDetailsResponse,
+  CheckLogsResponse,
+  PostPrCommentRequest,
+  PostPrReviewCommentRequest,
+  ReplyToPrCommentRequest,
+  MarkPrCommentAddressedRequest,
+  PrComment,
+  ApplySuggestionsRequest,
+  SetReviewFollowUpsRequest,
+  ApplySuggestionsResponse,
+  PostedComment,
+  Milestone,
+  BranchProtection,
+  CreateReleaseRequest,
+  ReleaseInfo,
+  RepoReviewRequests,
   MergeTaskAttemptRequest,
   PushTaskAttemptRequest,
   RepoBranchStatus,
   AbortConflictsRequest,
   Session,
   Workspace,
+  Tenant,
+  CreateTenant,
+  UpdateTenant,
+  TenantQuota,
+  UpdateTenantQuota,
+  TenantUsageReport,
+  TenantMember,
+  AddTenantMember,
+  SignedUrl,
+  BoardMetrics,
+  MetricsReport,
+  SavedView,
+  CreateSavedView,
+  UpdateSavedView,
+  ViewBoard,
+  ColumnSla,
+  SetColumnSla,
+  SlaBreach,
+  SlaEscalation,
+  TaskStatus,
 } from 'shared/types';
 import type { WorkspaceWithSession } from '@/types/attempt';
 import { createWorkspaceWithSession } from '@/types/attempt';
@@ -110,11 +151,40 @@ export class ApiError<E = unknown> extends Error {
   }
 }
 
+const TENANT_STORAGE_KEY = 'vk-active-tenant';
+
+/** Organization picked in the switcher; null means the default one */
+export const getActiveTenantId = (): string | null =>
+  localStorage.getItem(TENANT_STORAGE_KEY);
+
+export const setActiveTenantId = (tenantId: string | null) => {
+  if (tenantId) {
+    localStorage.setItem(TENANT_STORAGE_KEY, tenantId);
+  } else {
+    localStorage.removeItem(TENANT_STORAGE_KEY);
+  }
+};
+
+/**
+ * WebSockets cannot send X-Tenant-Id, so stream URLs carry the organization
+ * as a `tenant_id` query parameter instead.
+ */
+export const withTenant = (url: string): string => {
+  const tenantId = getActiveTenantId();
+  if (!tenantId) return url;
+  const separator = url.includes('?') ? '&' : '?';
+  return `${url}${separator}tenant_id=${encodeURIComponent(tenantId)}`;
+};
+
 const makeRequest = async (url: string, options: RequestInit = {}) => {
   const headers = new Headers(options.headers ?? {});
   if (!headers.has('Content-Type')) {
     headers.set('Content-Type', 'application/json');
   }
+  const tenantId = getActiveTenantId();
+  if (tenantId && !headers.has('X-Tenant-Id')) {
+    headers.set('X-Tenant-Id', tenantId);
+  }
 
   return fetch(url, {
     ...options,
@@ -122,6 +192,45 @@ const makeRequest = async (url: string, options: RequestInit = {}) => {
   });
 };
 
+/**
+ * RFC 7807 error body sent with every non-2xx response. The last segment of
+ * `type` is a snake_case code; fields of typed errors are extension members.
+ */
+export type ProblemDetails = {
+  type: string;
+  title: string;
+  status: number;
+  detail?: string;
+  instance?: string;
+  [extension: string]: unknown;
+};
+
+const readProblem = async (
+  response: Response
+): Promise<ProblemDetails | null> => {
+  try {
+    const body = await response.json();
+    return body &&
+      typeof body.type === 'string' &&
+      typeof body.title === 'string'
+      ? (body as ProblemDetails)
+      : null;
+  } catch {
+    return null;
+  }
+};
+
+const PROBLEM_MEMBERS = ['type', 'title', 'status', 'detail', 'instance'];
+
+// Rebuild the tagged error the route declares, e.g.
+// `{ type: 'target_branch_not_found', branch }`, from a problem
+const problemErrorData = <E>(problem: ProblemDetails): E => {
+  const extensions = Object.fromEntries(
+    Object.entries(problem).filter(([key]) => !PROBLEM_MEMBERS.includes(key))
+  );
+  return { ...extensions, type: problem.type.split('/').pop() } as E;
+};
+
 export type Ok<T> = { success: true; data: T };
 export type Err<E> = { success: false; error: E | undefined; message?: string };
 
@@ -133,22 +242,19 @@ const handleApiResponseAsResult = async <T, E>(
   response: Response
 ): Promise<Result<T, E>> => {
   if (!response.ok) {
-    // HTTP error - no structured error data
-    let errorMessage = `Request failed with status ${response.status}`;
-
-    try {
-      const errorData = await response.json();
-      if (errorData.message) {
-        errorMessage = errorData.message;
-      }
-    } catch {
-      errorMessage = response.statusText || errorMessage;
+    const problem = await readProblem(response);
+    if (problem) {
+      return {
+        success: false,
+        error: problemErrorData<E>(problem),
+        message: problem.detail,
+      };
     }
 
     return {
       success: false,
       error: undefined,
-      message: errorMessage,
+      message: `Request failed with status ${response.status}`,
     };
   }
 
@@ -169,26 +275,26 @@ export const handleApiResponse = async <T, E = T>(
   response: Response
 ): Promise<T> => {
   if (!response.ok) {
-    let errorMessage = `Request failed with status ${response.status}`;
-
-    try {
-      const errorData = await response.json();
-      if (errorData.message) {
-        errorMessage = errorData.message;
-      }
-    } catch {
-      // Fallback to status text if JSON parsing fails
-      errorMessage = response.statusText || errorMessage;
-    }
+    const problem = await readProblem(response);
+    const errorMessage =
+      problem?.detail ??
+      problem?.title ??
+      `Request failed with status ${response.status}`;
 
     console.error('[API Error]', {
       message: errorMessage,
+      problem,
       status: response.status,
       response,
       endpoint: response.url,
       timestamp: new Date().toISOString(),
     });
-    throw new ApiError<E>(errorMessage, response.status, response);
+    throw new ApiError<E>(
+      errorMessage,
+      response.status,
+      response,
+      problem ? problemErrorData<E>(problem) : undefined
+    );
   }
 
   if (response.status === 204) {
@@ -261,6 +367,38 @@ export const projectsApi = {
     return handleApiResponse<RemoteProjectMembersResponse>(response);
   },
 
+  getPermissions: async (
+    projectId: string
+  ): Promise<EffectiveProjectPermissions> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/permissions`
+    );
+    return handleApiResponse<EffectiveProjectPermissions>(response);
+  },
+
+  getPermissionOverrides: async (
+    projectId: string
+  ): Promise<ListProjectPermissionOverridesResponse> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/permissions/overrides`
+    );
+    return handleApiResponse<ListProjectPermissionOverridesResponse>(response);
+  },
+
+  setPermissionOverride: async (
+    projectId: string,
+    data: SetProjectPermissionOverrideRequest
+  ): Promise<ListProjectPermissionOverridesResponse> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/permissions/overrides`,
+      {
+        method: 'PATCH',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ListProjectPermissionOverridesResponse>(response);
+  },
+
   delete: async (id: string): Promise<void> => {
     const response = await makeRequest(`/api/projects/${id}`, {
       method: 'DELETE',
@@ -383,6 +521,75 @@ export const projectsApi = {
     );
     return handleApiResponse<ProjectRepo>(response);
   },
+
+  getMetrics: async (
+    projectId: string,
+    period: { since?: string; until?: string } = {}
+  ): Promise<BoardMetrics> => {
+    const params = new URLSearchParams();
+    if (period.since) params.set('since', period.since);
+    if (period.until) params.set('until', period.until);
+    const query = params.toString();
+    const response = await makeRequest(
+      `/api/projects/${projectId}/metrics${query ? `?${query}` : ''}`
+    );
+    return handleApiResponse<BoardMetrics>(response);
+  },
+
+  /** One metrics report as a CSV file */
+  exportMetrics: async (
+    projectId: string,
+    report: MetricsReport,
+    period: { since?: string; until?: string } = {}
+  ): Promise<Blob> => {
+    const params = new URLSearchParams({ report });
+    if (period.since) params.set('since', period.since);
+    if (period.until) params.set('until', period.until);
+    const response = await makeRequest(
+      `/api/projects/${projectId}/metrics/export?${params.toString()}`
+    );
+    if (!response.ok) {
+      return handleApiResponse<Blob>(response);
+    }
+    return response.blob();
+  },
+
+  getSlas: async (projectId: string): Promise<ColumnSla[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/slas`);
+    return handleApiResponse<ColumnSla[]>(response);
+  },
+
+  /** Set the SLA of one board column, replacing the one it had */
+  setSla: async (projectId: string, data: SetColumnSla): Promise<ColumnSla> => {
+    const response = await makeRequest(`/api/projects/${projectId}/slas`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ColumnSla>(response);
+  },
+
+  deleteSla: async (projectId: string, status: TaskStatus): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/slas?status=${status}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  /** Tasks currently past the SLA of their column */
+  getSlaBreaches: async (projectId: string): Promise<SlaBreach[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/slas/breaches`
+    );
+    return handleApiResponse<SlaBreach[]>(response);
+  },
+
+  getSlaEscalations: async (projectId: string): Promise<SlaEscalation[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/slas/escalations`
+    );
+    return handleApiResponse<SlaEscalation[]>(response);
+  },
 };
 
 // Task Management APIs
@@ -425,6 +632,20 @@ export const tasksApi = {
     return handleApiResponse<void>(response);
   },
 
+  undo: async (taskId: string): Promise<Task> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/undo`, {
+      method: 'POST',
+    });
+    return handleApiResponse<Task>(response);
+  },
+
+  redo: async (taskId: string): Promise<Task> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/redo`, {
+      method: 'POST',
+    });
+    return handleApiResponse<Task>(response);
+  },
+
   share: async (taskId: string): Promise<ShareTaskResponse> => {
     const response = await makeRequest(`/api/tasks/${taskId}/share`, {
       method: 'POST',
@@ -492,6 +713,14 @@ export const sessionsApi = {
     return handleApiResponse<Session>(response);
   },
 
+  exportTranscript: async (sessionId: string): Promise<SignedUrl> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/transcript`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<SignedUrl>(response);
+  },
+
   followUp: async (
     sessionId: string,
     data: CreateFollowUpAttempt
@@ -656,10 +885,12 @@ export const attemptsApi = {
 
   renameBranch: async (
     attemptId: string,
-    newBranchName: string
+    newBranchName: string,
+    expectedUpdatedAt: string | null = null
   ): Promise<RenameBranchResponse> => {
     const payload: RenameBranchRequest = {
       new_branch_name: newBranchName,
+      expected_updated_at: expectedUpdatedAt,
     };
     const response = await makeRequest(
       `/api/task-attempts/${attemptId}/rename-branch`,
@@ -746,13 +977,149 @@ export const attemptsApi = {
 
   getPrComments: async (
     attemptId: string,
-    repoId: string
+    repoId: string,
+    filters: Partial<Omit<GetPrCommentsQuery, 'repo_id'>> = {}
   ): Promise<PrCommentsResponse> => {
+    const params = new URLSearchParams({ repo_id: repoId });
+    if (filters.since) params.set('since', filters.since);
+    if (filters.exclude_bots) params.set('exclude_bots', 'true');
+    if (filters.authors) params.set('authors', filters.authors);
+    if (filters.only_unresolved) params.set('only_unresolved', 'true');
     const response = await makeRequest(
-      `/api/task-attempts/${attemptId}/pr/comments?repo_id=${encodeURIComponent(repoId)}`
+      `/api/task-attempts/${attemptId}/pr/comments?${params.toString()}`
     );
     return handleApiResponse<PrCommentsResponse>(response);
   },
+
+  postPrComment: async (
+    attemptId: string,
+    data: PostPrCommentRequest
+  ): Promise<PostedComment> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-request/comments`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<PostedComment>(response);
+  },
+
+  postPrReviewComment: async (
+    attemptId: string,
+    data: PostPrReviewCommentRequest
+  ): Promise<PostedComment> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-request/comments/review`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<PostedComment>(response);
+  },
+
+  replyToPrComment: async (
+    attemptId: string,
+    data: ReplyToPrCommentRequest
+  ): Promise<PostedComment> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-request/comments/reply`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<PostedComment>(response);
+  },
+
+  markPrCommentAddressed: async (
+    attemptId: string,
+    data: MarkPrCommentAddressedRequest
+  ): Promise<PrComment> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-request/comments/addressed`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<PrComment>(response);
+  },
+
+  applyPrSuggestions: async (
+    attemptId: string,
+    data: ApplySuggestionsRequest
+  ): Promise<ApplySuggestionsResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-request/suggestions/apply`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ApplySuggestionsResponse>(response);
+  },
+
+  listMilestones: async (
+    attemptId: string,
+    repoId: string
+  ): Promise<Milestone[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-request/milestones?repo_id=${encodeURIComponent(repoId)}`
+    );
+    return handleApiResponse<Milestone[]>(response);
+  },
+
+  getBranchProtection: async (
+    attemptId: string,
+    repoId: string,
+    targetBranch: string
+  ): Promise<BranchProtection> => {
+    const params = new URLSearchParams({
+      repo_id: repoId,
+      target_branch: targetBranch,
+    });
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-request/branch-protection?${params.toString()}`
+    );
+    return handleApiResponse<BranchProtection>(response);
+  },
+
+  getPrDetails: async (
+    attemptId: string,
+    repoId: string
+  ): Promise<PrDetailsResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-request/details?repo_id=${encodeURIComponent(repoId)}`
+    );
+    return handleApiResponse<PrDetailsResponse>(response);
+  },
+
+  getCheckLogs: async (
+    attemptId: string,
+    repoId: string,
+    checkId: string
+  ): Promise<CheckLogsResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-request/checks/logs?repo_id=${encodeURIComponent(repoId)}&check_id=${encodeURIComponent(checkId)}`
+    );
+    return handleApiResponse<CheckLogsResponse>(response);
+  },
+
+  setReviewFollowUps: async (
+    attemptId: string,
+    data: SetReviewFollowUpsRequest
+  ): Promise<Workspace> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-request/review-follow-ups`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<Workspace>(response);
+  },
 };
 
 // Execution Process APIs
@@ -780,6 +1147,13 @@ export const executionProcessesApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  getLogsUrl: async (processId: string): Promise<SignedUrl | null> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/logs-url`
+    );
+    return handleApiResponse<SignedUrl | null>(response);
+  },
 };
 
 // File System APIs
@@ -829,6 +1203,29 @@ export const repoApi = {
     });
     return handleApiResponse<Repo>(response);
   },
+
+  createRelease: async (
+    repoId: string,
+    data: CreateReleaseRequest
+  ): Promise<ReleaseInfo> => {
+    const response = await makeRequest(`/api/repos/${repoId}/releases`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ReleaseInfo>(response);
+  },
+
+  getReviewRequests: async (
+    username?: string
+  ): Promise<RepoReviewRequests[]> => {
+    const params = new URLSearchParams();
+    if (username) params.set('username', username);
+    const query = params.toString();
+    const response = await makeRequest(
+      `/api/repos/review-requests${query ? `?${query}` : ''}`
+    );
+    return handleApiResponse<RepoReviewRequests[]>(response);
+  },
 };
 
 // Config APIs (backwards compatible)
@@ -862,6 +1259,32 @@ export const configApi = {
   },
 };
 
+// Self-update APIs
+export const updateApi = {
+  getStatus: async (): Promise<UpdateStatus> => {
+    const response = await makeRequest('/api/update', { cache: 'no-store' });
+    return handleApiResponse<UpdateStatus>(response);
+  },
+  download: async (): Promise<UpdateStatus> => {
+    const response = await makeRequest('/api/update/download', {
+      method: 'POST',
+    });
+    return handleApiResponse<UpdateStatus>(response);
+  },
+  install: async (): Promise<UpdateStatus> => {
+    const response = await makeRequest('/api/update/install', {
+      method: 'POST',
+    });
+    return handleApiResponse<UpdateStatus>(response);
+  },
+  cancel: async (): Promise<UpdateStatus> => {
+    const response = await makeRequest('/api/update/cancel', {
+      method: 'POST',
+    });
+    return handleApiResponse<UpdateStatus>(response);
+  },
+};
+
 // Task Tags APIs (all tags are global)
 export const tagsApi = {
   list: async (params?: TagSearchParams): Promise<Tag[]> => {
@@ -896,6 +1319,47 @@ export const tagsApi = {
   },
 };
 
+// Saved board views: the user's own and those shared in the project
+export const savedViewsApi = {
+  list: async (projectId: string): Promise<SavedView[]> => {
+    const response = await makeRequest(
+      `/api/saved-views?project_id=${encodeURIComponent(projectId)}`
+    );
+    return handleApiResponse<SavedView[]>(response);
+  },
+
+  create: async (data: CreateSavedView): Promise<SavedView> => {
+    const response = await makeRequest('/api/saved-views', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<SavedView>(response);
+  },
+
+  update: async (
+    viewId: string,
+    data: UpdateSavedView
+  ): Promise<SavedView> => {
+    const response = await makeRequest(`/api/saved-views/${viewId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<SavedView>(response);
+  },
+
+  delete: async (viewId: string): Promise<void> => {
+    const response = await makeRequest(`/api/saved-views/${viewId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getBoard: async (viewId: string): Promise<ViewBoard> => {
+    const response = await makeRequest(`/api/saved-views/${viewId}/board`);
+    return handleApiResponse<ViewBoard>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (query: McpServerQuery): Promise<GetMcpServerResponse> => {
@@ -914,15 +1378,15 @@ export const mcpServersApi = {
       body: JSON.stringify(data),
     });
     if (!response.ok) {
-      const errorData = await response.json();
+      const problem = await readProblem(response);
       console.error('[API Error] Failed to save MCP servers', {
-        message: errorData.message,
+        message: problem?.detail,
         status: response.status,
         response,
         timestamp: new Date().toISOString(),
       });
       throw new ApiError(
-        errorData.message || 'Failed to save MCP servers',
+        problem?.detail || 'Failed to save MCP servers',
         response.status,
         response
       );
@@ -1041,6 +1505,11 @@ export const imagesApi = {
   getImageUrl: (imageId: string): string => {
     return `/api/images/${imageId}/file`;
   },
+
+  getDownloadUrl: async (imageId: string): Promise<SignedUrl> => {
+    const response = await makeRequest(`/api/images/${imageId}/url`);
+    return handleApiResponse<SignedUrl>(response);
+  },
 };
 
 // Approval API
@@ -1290,3 +1759,79 @@ export const queueApi = {
     return handleApiResponse<QueueStatus>(response);
   },
 };
+
+// Isolated organizations on this server, switched with X-Tenant-Id
+export const tenantsApi = {
+  list: async (): Promise<Tenant[]> => {
+    const response = await makeRequest('/api/tenants');
+    return handleApiResponse<Tenant[]>(response);
+  },
+
+  current: async (): Promise<Tenant> => {
+    const response = await makeRequest('/api/tenants/current');
+    return handleApiResponse<Tenant>(response);
+  },
+
+  getUsage: async (): Promise<TenantUsageReport> => {
+    const response = await makeRequest('/api/tenants/current/usage');
+    return handleApiResponse<TenantUsageReport>(response);
+  },
+
+  create: async (data: CreateTenant): Promise<Tenant> => {
+    const response = await makeRequest('/api/tenants', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Tenant>(response);
+  },
+
+  update: async (tenantId: string, data: UpdateTenant): Promise<Tenant> => {
+    const response = await makeRequest(`/api/tenants/${tenantId}`, {
+      method: 'PATCH',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Tenant>(response);
+  },
+
+  setQuota: async (
+    tenantId: string,
+    data: UpdateTenantQuota
+  ): Promise<TenantQuota> => {
+    const response = await makeRequest(`/api/tenants/${tenantId}/quota`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TenantQuota>(response);
+  },
+
+  delete: async (tenantId: string): Promise<void> => {
+    const response = await makeRequest(`/api/tenants/${tenantId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  listMembers: async (tenantId: string): Promise<TenantMember[]> => {
+    const response = await makeRequest(`/api/tenants/${tenantId}/members`);
+    return handleApiResponse<TenantMember[]>(response);
+  },
+
+  addMember: async (
+    tenantId: string,
+    data: AddTenantMember
+  ): Promise<TenantMember> => {
+    const response = await makeRequest(`/api/tenants/${tenantId}/members`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TenantMember>(response);
+  },
+
+  removeMember: async (tenantId: string, userId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/tenants/${tenantId}/members/${userId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
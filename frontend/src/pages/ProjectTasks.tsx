@@ -1,4 +1,4 @@
-import { useCallback, useEffect, useMemo, useState } from 'react';
+import { useCallback, useEffect, useMemo, useRef, useState } from 'react';
 import { useNavigate, useParams, useSearchParams } from 'react-router-dom';
 import { useTranslation } from 'react-i18next';
 import { Button } from '@/components/ui/button';
@@ -39,6 +39,8 @@ import {
   Scope,
   useKeyDeleteTask,
   useKeyCycleViewBackward,
+  useKeyUndo,
+  useKeyRedo,
 } from '@/keyboard';
 
 import TaskKanbanBoard, {
@@ -746,6 +748,9 @@ export function ProjectTasks() {
     }
   }, [selectedTask, visibleTasksByStatus, handleViewTaskDetails]);
 
+  // Task moved by the last drag, so an accidental drop can be undone
+  const lastMovedTaskIdRef = useRef<string | null>(null);
+
   const handleDragEnd = useCallback(
     async (event: DragEndEvent) => {
       const { active, over } = event;
@@ -763,7 +768,11 @@ export function ProjectTasks() {
           status: newStatus,
           parent_workspace_id: task.parent_workspace_id,
           image_ids: null,
+          due_at: null,
+          clear_due_at: false,
+          expected_updated_at: task.updated_at,
         });
+        lastMovedTaskIdRef.current = draggedTaskId;
       } catch (err) {
         console.error('Failed to update task status:', err);
       }
@@ -771,6 +780,28 @@ export function ProjectTasks() {
     [tasksById]
   );
 
+  useKeyUndo(
+    () => {
+      const taskId = lastMovedTaskIdRef.current;
+      if (!taskId) return;
+      tasksApi.undo(taskId).catch((err) => {
+        console.error('Failed to undo task change:', err);
+      });
+    },
+    { scope: Scope.KANBAN, preventDefault: true }
+  );
+
+  useKeyRedo(
+    () => {
+      const taskId = lastMovedTaskIdRef.current;
+      if (!taskId) return;
+      tasksApi.redo(taskId).catch((err) => {
+        console.error('Failed to redo task change:', err);
+      });
+    },
+    { scope: Scope.KANBAN, preventDefault: true }
+  );
+
   const getSharedTask = useCallback(
     (task: Task | null | undefined) => {
       if (!task) return undefined;
@@ -27,6 +27,7 @@ import {
   SoundFile,
   ThemeMode,
   UiLanguage,
+  UpdateChannel,
 } from 'shared/types';
 import { getLanguageOptions } from '@/i18n/languages';
 
@@ -36,6 +37,7 @@ import { EditorAvailabilityIndicator } from '@/components/EditorAvailabilityIndi
 import { useTheme } from '@/components/ThemeProvider';
 import { useUserSystem } from '@/components/ConfigProvider';
 import { TagManager } from '@/components/TagManager';
+import { UpdateManager } from '@/components/UpdateManager';
 
 export function GeneralSettings() {
   const { t } = useTranslation(['settings', 'common']);
@@ -674,6 +676,44 @@ export function GeneralSettings() {
         </CardContent>
       </Card>
 
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.general.updates.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.general.updates.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="space-y-2">
+            <Label htmlFor="update-channel">
+              {t('settings.general.updates.channel.label')}
+            </Label>
+            <Select
+              value={draft?.update.channel}
+              onValueChange={(value: UpdateChannel) =>
+                draft &&
+                updateDraft({ update: { ...draft.update, channel: value } })
+              }
+            >
+              <SelectTrigger id="update-channel">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {(['stable', 'beta'] as UpdateChannel[]).map((channel) => (
+                  <SelectItem key={channel} value={channel}>
+                    {toPrettyCase(channel)}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.updates.channel.helper')}
+            </p>
+          </div>
+          <UpdateManager key={config?.update.channel} />
+        </CardContent>
+      </Card>
+
       <Card>
         <CardHeader>
           <CardTitle>{t('settings.general.safety.title')}</CardTitle>
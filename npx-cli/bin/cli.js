@@ -1,9 +1,10 @@
 #!/usr/bin/env node
 
-const { execSync, spawn } = require("child_process");
+const { execFileSync, execSync, spawn } = require("child_process");
 const AdmZip = require("adm-zip");
 const path = require("path");
 const fs = require("fs");
+const { gitKanban } = require("./git-kanban");
 const { ensureBinary, BINARY_TAG, CACHE_DIR, LOCAL_DEV_MODE, LOCAL_DIST_DIR, R2_BASE_URL, getLatestVersion } = require("./download");
 
 const CLI_VERSION = require("../package.json").version;
@@ -142,9 +143,16 @@ async function extractAndRun(baseName, launch) {
 }
 
 async function main() {
+  const args = process.argv.slice(2);
+
+  // Talks to an already running server, so there is no binary to download
+  if (args[0] === "git-kanban") {
+    await gitKanban(args.slice(1));
+    return;
+  }
+
   fs.mkdirSync(versionCacheDir, { recursive: true });
 
-  const args = process.argv.slice(2);
   const isMcpMode = args.includes("--mcp");
   const isReviewMode = args[0] === "review";
 
@@ -190,11 +198,8 @@ async function main() {
     const modeLabel = LOCAL_DEV_MODE ? " (local dev)" : "";
     console.log(`Starting vibe-kanban v${CLI_VERSION}${modeLabel}...`);
     await extractAndRun("vibe-kanban", (bin) => {
-      if (platform === "win32") {
-        execSync(`"${bin}"`, { stdio: "inherit" });
-      } else {
-        execSync(`"${bin}"`, { stdio: "inherit" });
-      }
+      // Forward server flags such as --data-dir to the binary
+      execFileSync(bin, args, { stdio: "inherit" });
     });
   }
 }
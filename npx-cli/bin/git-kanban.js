@@ -0,0 +1,79 @@
+// `npx vibe-kanban git-kanban [git branch args...]`
+//
+// Prints `git branch` output with the task and pull request each workspace branch belongs to,
+// read from a running vibe-kanban server. `--json` prints the raw mapping instead.
+
+const { execFileSync } = require("child_process");
+const fs = require("fs");
+const os = require("os");
+const path = require("path");
+
+function readPort() {
+  if (process.env.VIBE_KANBAN_PORT) return process.env.VIBE_KANBAN_PORT;
+  const portFile = path.join(os.tmpdir(), "vibe-kanban", "vibe-kanban.port");
+  try {
+    return fs.readFileSync(portFile, "utf8").trim();
+  } catch {
+    return null;
+  }
+}
+
+async function fetchMapping(port) {
+  const res = await fetch(`http://127.0.0.1:${port}/api/task-attempts/branch-mapping`);
+  if (!res.ok) {
+    throw new Error(`Server responded with ${res.status}`);
+  }
+  const body = await res.json();
+  if (!body.success) {
+    throw new Error(body.message || "Failed to load branch mapping");
+  }
+  return body.data;
+}
+
+function describe(mapping) {
+  let label = `${mapping.task_title} [${mapping.task_status}]`;
+  if (mapping.pr_number != null) {
+    label += ` PR #${mapping.pr_number} (${mapping.pr_status})`;
+  }
+  return label;
+}
+
+// Branch name of a `git branch` line, e.g. "* main", "+ vk/1234-fix" or "  remotes/origin/x"
+function branchOf(line) {
+  const name = line.slice(2).split(" -> ")[0].trim().split(/\s+/)[0];
+  return name.replace(/^remotes\/[^/]+\//, "");
+}
+
+async function gitKanban(args) {
+  const json = args.includes("--json");
+  const gitArgs = args.filter((arg) => arg !== "--json");
+
+  const port = readPort();
+  if (!port) {
+    console.error("vibe-kanban does not seem to be running (no port file found)");
+    process.exit(1);
+  }
+  const mappings = await fetchMapping(port);
+
+  if (json) {
+    console.log(JSON.stringify(mappings, null, 2));
+    return;
+  }
+
+  // Workspaces of several tasks may share a branch name across repositories; the newest wins
+  const byBranch = new Map();
+  for (const mapping of mappings) {
+    if (!byBranch.has(mapping.branch)) byBranch.set(mapping.branch, mapping);
+  }
+
+  const output = execFileSync("git", ["branch", "--no-color", ...gitArgs], {
+    encoding: "utf8",
+  });
+  for (const line of output.split("\n")) {
+    if (!line) continue;
+    const mapping = byBranch.get(branchOf(line));
+    console.log(mapping ? `${line}  # ${describe(mapping)}` : line);
+  }
+}
+
+module.exports = { gitKanban };
@@ -0,0 +1,59 @@
+#!/usr/bin/env bash
+# Performance regression check for the execution pipeline benchmarks
+# Runs crates/local-deployment/benches/pipeline.rs on the base branch to record a baseline, then
+# on this branch against it, and fails when a benchmark's mean time grew by more than
+# BENCH_THRESHOLD percent (default 20)
+set -eo pipefail
+
+THRESHOLD="${BENCH_THRESHOLD:-20}"
+BENCH_ARGS=(bench -p local-deployment --bench pipeline)
+
+REPO_ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+# Both runs write their results here, so the second can compare against the first
+export CRITERION_HOME="$REPO_ROOT/target/criterion"
+rm -rf "$CRITERION_HOME"
+
+BASE_REF="${GITHUB_BASE_REF:-main}"
+echo "▶️  Fetching $BASE_REF for baseline (shallow clone)..."
+REMOTE_URL=$(git -C "$REPO_ROOT" remote get-url origin)
+BASE_DIR="$(mktemp -d)"
+cleanup_base() { rm -rf "$BASE_DIR"; }
+trap cleanup_base EXIT
+
+HAS_BASELINE=0
+if git clone --depth=1 --branch "$BASE_REF" --single-branch "$REMOTE_URL" "$BASE_DIR" >/dev/null 2>&1 \
+  && [ -f "$BASE_DIR/crates/local-deployment/benches/pipeline.rs" ]; then
+  echo "▶️  Benchmarking $BASE_REF..."
+  (cd "$BASE_DIR" && CARGO_TARGET_DIR="$REPO_ROOT/target" cargo "${BENCH_ARGS[@]}" -- --save-baseline base)
+  HAS_BASELINE=1
+else
+  echo "⚠️  No benchmarks on $BASE_REF; recording this branch without a comparison."
+fi
+
+echo "▶️  Benchmarking PR branch..."
+if [ "$HAS_BASELINE" = "1" ]; then
+  (cd "$REPO_ROOT" && cargo "${BENCH_ARGS[@]}" -- --baseline base)
+else
+  (cd "$REPO_ROOT" && cargo "${BENCH_ARGS[@]}")
+  exit 0
+fi
+
+echo ""
+echo "📊 Benchmark Summary (mean time change against $BASE_REF):"
+EXIT_STATUS=0
+while IFS= read -r estimates; do
+  name="${estimates#$CRITERION_HOME/}"
+  name="${name%/change/estimates.json}"
+  change=$(jq '.mean.point_estimate * 100' "$estimates")
+  printf '   %-40s %+.1f%%\n' "$name" "$change"
+  if jq -e --argjson limit "$THRESHOLD" '.mean.point_estimate * 100 > $limit' "$estimates" > /dev/null; then
+    echo "❌ $name got slower by more than $THRESHOLD%."
+    EXIT_STATUS=1
+  fi
+done < <(find "$CRITERION_HOME" -path '*/change/estimates.json' | LC_ALL=C sort)
+
+if [ "$EXIT_STATUS" = "0" ]; then
+  echo "✅ No benchmark regressed by more than $THRESHOLD%."
+fi
+
+exit "$EXIT_STATUS"
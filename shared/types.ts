@@ -24,6 +24,45 @@ export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
 export type Repo = { id: string, path: string, name: string, display_name: string, created_at: Date, updated_at: Date, };
 
+/**
+ * A repository whose path is a primary clone owned by the server. Workspaces are worktrees of
+ * that clone, so the user's own checkout is never touched.
+ */
+export type ManagedRepo = { repo_id: string, 
+/**
+ * The user checkout the clone was made from, if it was adopted
+ */
+source_path: string | null, remote_url: string | null, last_fetched_at: string | null, created_at: string, };
+
+/**
+ * Background fetch settings of a repository and the outcome of its last fetch
+ */
+export type RepoFetchState = { repo_id: string, enabled: boolean, 
+/**
+ * Overrides the global fetch interval when set
+ */
+interval_minutes: bigint | null, last_attempt_at: string | null, last_success_at: string | null, 
+/**
+ * Error of the last fetch, cleared once a fetch succeeds
+ */
+last_error: string | null, consecutive_failures: bigint, updated_at: string, };
+
+export type UpdateRepoFetchSettings = { enabled: boolean, interval_minutes: bigint | null, };
+
+/**
+ * How far a workspace branch has moved from its target branch and from its remote tracking
+ * branch, as of the last fetch of the repository
+ */
+export type BranchDivergence = { workspace_id: string, repo_id: string, target_branch: string, commits_ahead: bigint, 
+/**
+ * Commits on the target branch that the workspace branch does not have yet
+ */
+commits_behind: bigint, 
+/**
+ * None when the branch has not been pushed
+ */
+remote_commits_ahead: bigint | null, remote_commits_behind: bigint | null, computed_at: string, };
+
 export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, };
 
 export type CreateProjectRepo = { display_name: string, git_repo_path: string, };
@@ -44,290 +83,2235 @@ export type UpdateTag = { tag_name: string | null, content: string | null, };
 
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
-export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
+export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, 
+/**
+ * When the task is due, in UTC
+ */
+due_at: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, 
+/**
+ * When the task is due, in UTC
+ */
+due_at: string | null, created_at: string, updated_at: string, };
 
-export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
+/**
+ * A task as the board renders it: key metadata and status badges, without the description
+ */
+export type BoardCard = { id: string, title: string, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, due_at: string | null, created_at: string, updated_at: string, workspace_count: bigint, has_in_progress_attempt: boolean, last_attempt_failed: boolean, 
+/**
+ * Executor of the most recent session
+ */
+executor: string | null, 
+/**
+ * Most recent pull request of any workspace of the task
+ */
+pr_status: MergeStatus | null, pr_number: bigint | null, pr_url: string | null, };
 
-export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, shared_task_id: string | null, };
+export type BoardColumn = { status: TaskStatus, tasks: Array<BoardCard>, };
 
-export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
+/**
+ * Everything the board needs for its first render
+ */
+export type BoardSnapshot = { project_id: string, columns: Array<BoardColumn>, };
 
-export type DraftFollowUpData = { message: string, variant: string | null, };
+/**
+ * Card field a view sorts by
+ */
+export type ViewSortField = "created_at" | "updated_at" | "title";
 
-export type ScratchPayload = { "type": "DRAFT_TASK", "data": string } | { "type": "DRAFT_FOLLOW_UP", "data": DraftFollowUpData };
+/**
+ * How a view groups its cards
+ */
+export type ViewGrouping = "status" | "executor" | "pr_status" | "none";
 
-export enum ScratchType { DRAFT_TASK = "DRAFT_TASK", DRAFT_FOLLOW_UP = "DRAFT_FOLLOW_UP" }
+export type SavedView = { id: string, project_id: string, 
+/**
+ * Signed-in user who saved the view; `None` for views saved while signed out
+ */
+owner_user_id: string | null, name: string, 
+/**
+ * Condition expression each card has to match, e.g.
+ * `task.status == "inprogress" and task.last_attempt_failed`; empty matches every card
+ */
+filter: string, sort_field: ViewSortField, sort_descending: boolean, grouping: ViewGrouping, 
+/**
+ * Whether everyone working on the project sees the view, not just its owner
+ */
+shared: boolean, created_at: string, updated_at: string, };
 
-export type Scratch = { id: string, payload: ScratchPayload, created_at: string, updated_at: string, };
+export type CreateSavedView = { project_id: string, name: string, filter: string | null, sort_field: ViewSortField | null, sort_descending: boolean | null, grouping: ViewGrouping | null, shared: boolean | null, };
 
-export type CreateScratch = { payload: ScratchPayload, };
+export type UpdateSavedView = { name: string | null, filter: string | null, sort_field: ViewSortField | null, sort_descending: boolean | null, grouping: ViewGrouping | null, shared: boolean | null, };
 
-export type UpdateScratch = { payload: ScratchPayload, };
+/**
+ * Cards of a view sharing the value they are grouped by
+ */
+export type ViewGroup = { 
+/**
+ * The value, e.g. a task status or an executor; `None` for cards without one and for views
+ * that don't group
+ */
+key: string | null, tasks: Array<BoardCard>, };
 
-export type Image = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
+/**
+ * The board of a project as a saved view shows it
+ */
+export type ViewBoard = { view_id: string, project_id: string, groups: Array<ViewGroup>, };
 
-export type CreateImage = { file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, };
+/**
+ * What happens to a task once it stayed in its column longer than the column's SLA
+ */
+export type SlaAction = "label" | "notify" | "nudge";
 
-export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, git_provider: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, };
+export type ColumnSla = { id: string, project_id: string, status: TaskStatus, 
+/**
+ * Longest a task should stay in the column
+ */
+max_hours: bigint, action: SlaAction, created_at: string, updated_at: string, };
 
-export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
+export type SetColumnSla = { status: TaskStatus, max_hours: bigint, action: SlaAction | null, };
 
-export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
 /**
- * dropped: true if this process is excluded from the current
- * history view (due to restore/trimming). Hidden from logs/timeline;
- * still listed in the Processes tab.
+ * A task escalated for staying in a column too long
  */
-dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
+export type SlaEscalation = { id: string, task_id: string, status: TaskStatus, 
+/**
+ * When the task entered the column it stalled in
+ */
+entered_at: string, action: SlaAction, created_at: string, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+/**
+ * A task that stayed in its column longer than the column's SLA
+ */
+export type SlaBreach = { task_id: string, title: string, status: TaskStatus, entered_at: string, hours_in_column: number, max_hours: bigint, action: SlaAction, };
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type TaskComment = { id: string, task_id: string, 
+/**
+ * Display name of the writer, e.g. a username or `plugin:<name>`
+ */
+author: string, body: string, created_at: string, };
 
-export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
+export type CreateTaskComment = { author: string, body: string, };
 
-export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
+export type TaskEventKind = "created" | "updated" | "deleted";
 
-export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
+/**
+ * What made a change to a task
+ */
+export type TaskEventSource = "user" | "automation" | "webhook" | "system";
 
-export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
+/**
+ * The fields of a task recorded before and after each change
+ */
+export type TaskSnapshot = { title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, 
+/**
+ * Missing from events recorded before tasks had due dates
+ */
+due_at: string | null, };
 
-export type MergeStatus = "open" | "merged" | "closed" | "unknown";
+/**
+ * One change to a task. Events are never updated, so the log is the history of the task.
+ */
+export type TaskEvent = { id: string, task_id: string, project_id: string, kind: TaskEventKind, source: TaskEventSource, 
+/**
+ * Who made the change within its source, e.g. `rule:Close merged` or `pr_monitor`
+ */
+actor: string | null, 
+/**
+ * None for created tasks
+ */
+before: TaskSnapshot | null, 
+/**
+ * None for deleted tasks
+ */
+after: TaskSnapshot | null, 
+/**
+ * The change this event undid
+ */
+undo_of: string | null, 
+/**
+ * The undone change this event reapplied
+ */
+redo_of: string | null, created_at: string, };
 
-export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
+/**
+ * Read-only link to a task, or to one of its attempts, that works without access to the app
+ */
+export type ShareLink = { id: string, 
+/**
+ * Secret part of the link
+ */
+token: string, task_id: string, 
+/**
+ * Only this attempt is shared when set, otherwise every attempt of the task
+ */
+workspace_id: string | null, expires_at: string | null, revoked_at: string | null, created_at: string, };
 
-export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
+/**
+ * What a service account may do through the API
+ */
+export type ServiceAccountScope = "read" | "write_tasks" | "start_attempts" | "receive_webhooks";
 
-export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue, tool_call_id: string, };
+/**
+ * Non-interactive identity of an integration, like the scheduler or the MCP server. Changes made
+ * with its API key are recorded under its name instead of as a person's.
+ */
+export type ServiceAccount = { id: string, tenant_id: string, name: string, description: string | null, scopes: Array<ServiceAccountScope>, 
+/**
+ * Start of the current API key; None until a key is issued
+ */
+key_prefix: string | null, key_issued_at: string | null, last_used_at: string | null, created_at: string, updated_at: string, };
 
-export type ApprovalResponse = { execution_process_id: string, status: ApprovalStatus, };
+export type CreateServiceAccount = { name: string, description: string | null, scopes: Array<ServiceAccountScope>, };
+
+export type UpdateServiceAccount = { description: string | null, scopes: Array<ServiceAccountScope> | null, };
 
-export type Diff = { change: DiffChangeKind, oldPath: string | null, newPath: string | null, oldContent: string | null, newContent: string | null, 
 /**
- * True when file contents are intentionally omitted (e.g., too large)
+ * An isolated organization on a shared server. Projects, repositories and service accounts
+ * belong to one tenant, and tasks, attempts and sessions to the tenant of their project.
  */
-contentOmitted: boolean, 
+export type Tenant = { id: string, name: string, created_at: string, updated_at: string, };
+
 /**
- * Optional precomputed stats for omitted content
+ * Someone who may work in a tenant, by the user id of the account they sign in with. The default
+ * tenant has no members since everyone may work in it.
  */
-additions: number | null, deletions: number | null, };
+export type TenantMember = { tenant_id: string, user_id: string, created_at: string, };
 
-export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "copied" | "permissionChange";
+export type AddTenantMember = { user_id: string, };
 
-export type ApiResponse<T, E = T> = { success: boolean, data: T | null, error_data: E | null, message: string | null, };
+export type CreateTenant = { name: string, };
 
-export type LoginStatus = { "status": "loggedout" } | { "status": "loggedin", profile: ProfileResponse, };
+export type UpdateTenant = { name: string, };
 
-export type ProfileResponse = { user_id: string, username: string | null, email: string, providers: Array<ProviderProfile>, };
+/**
+ * Usage limits of a tenant; `None` means no limit
+ */
+export type TenantQuota = { tenant_id: string, 
+/**
+ * Coding agent runs allowed at the same time
+ */
+max_concurrent_executions: bigint | null, 
+/**
+ * Estimated coding agent spend allowed per calendar month (UTC)
+ */
+monthly_spend_limit: number | null, 
+/**
+ * Bytes allowed in object storage: attachments, execution artifacts and exported transcripts
+ */
+storage_limit_bytes: bigint | null, updated_at: string, };
 
-export type ProviderProfile = { provider: string, username: string | null, display_name: string | null, email: string | null, avatar_url: string | null, };
+export type UpdateTenantQuota = { max_concurrent_executions: bigint | null, monthly_spend_limit: number | null, storage_limit_bytes: bigint | null, };
 
-export type StatusResponse = { logged_in: boolean, profile: ProfileResponse | null, degraded: boolean | null, };
+/**
+ * What a tenant currently uses of each quota
+ */
+export type TenantUsage = { running_executions: bigint, 
+/**
+ * Estimated coding agent spend since the start of the month (UTC)
+ */
+monthly_spend: number, 
+/**
+ * Bytes in object storage
+ */
+storage_bytes: bigint, };
 
-export enum MemberRole { ADMIN = "ADMIN", MEMBER = "MEMBER" }
+/**
+ * A project kept in sync with its remote project
+ */
+export type ProjectMirror = { project_id: string, last_synced_at: string | null, created_at: string, };
 
-export enum InvitationStatus { PENDING = "PENDING", ACCEPTED = "ACCEPTED", DECLINED = "DECLINED", EXPIRED = "EXPIRED" }
+/**
+ * The parts of a task that mirroring keeps in sync
+ */
+export type MirroredFields = { title: string, description: string | null, status: TaskStatus, };
 
-export type Organization = { id: string, name: string, slug: string, is_personal: boolean, created_at: string, updated_at: string, };
+export type TemplateKind = "prompt" | "task";
 
-export type OrganizationWithRole = { id: string, name: string, slug: string, is_personal: boolean, created_at: string, updated_at: string, user_role: MemberRole, };
+export type Template = { id: string, kind: TemplateKind, name: string, 
+/**
+ * Title of tasks created from a task template
+ */
+title: string | null, content: string, created_at: string, updated_at: string, };
 
-export type ListOrganizationsResponse = { organizations: Array<OrganizationWithRole>, };
+export type CreateTemplate = { kind: TemplateKind, name: string, title: string | null, content: string, };
 
-export type GetOrganizationResponse = { organization: Organization, user_role: string, };
+export type UpdateTemplate = { name: string | null, title: string | null, content: string | null, };
 
-export type CreateOrganizationRequest = { name: string, slug: string, };
+/**
+ * A local template or automation rule kept in sync with an item of an organization library
+ */
+export type LibraryLink = { library_item_id: string, organization_id: string, 
+/**
+ * The template or automation rule on this instance
+ */
+local_id: string, 
+/**
+ * Library version the local item was last published or pulled at
+ */
+version: bigint, synced_at: string, };
 
-export type CreateOrganizationResponse = { organization: OrganizationWithRole, };
+export type RuleAction = { "type": "comment", body: string, } | { "type": "set_status", status: TaskStatus, } | { "type": "start_task", executor_profile_id: ExecutorProfileId | null, } | { "type": "mark_pr_ready" } | { "type": "request_reviewers", reviewers: Array<string>, } | { "type": "notify", message: string, };
 
-export type UpdateOrganizationRequest = { name: string, };
+export type AutomationRule = { id: string, 
+/**
+ * Limit the rule to one project; `None` applies it to every project
+ */
+project_id: string | null, name: string, 
+/**
+ * Condition expression, e.g. `event == "task.updated" and task.status == "inreview"`
+ */
+condition: string, action: RuleAction, enabled: boolean, created_at: string, updated_at: string, };
 
-export type Invitation = { id: string, organization_id: string, invited_by_user_id: string | null, email: string, role: MemberRole, status: InvitationStatus, token: string, created_at: string, expires_at: string, };
+export type AutomationTransition = { id: string, 
+/**
+ * Limit the transition to tasks of one project; `None` allows it in every project
+ */
+project_id: string | null, name: string, 
+/**
+ * Actions run in order on each task
+ */
+steps: Array<RuleAction>, created_at: string, updated_at: string, };
 
-export type CreateInvitationRequest = { email: string, role: MemberRole, };
+export type JobStatus = "pending" | "running" | "succeeded" | "dead";
 
-export type CreateInvitationResponse = { invitation: Invitation, };
+/**
+ * A unit of background work, persisted so it survives restarts
+ */
+export type Job = { id: string, 
+/**
+ * Name of the handler that runs the job, e.g. `repo_fetch.fetch_due`
+ */
+kind: string, payload: unknown, status: JobStatus, attempts: bigint, max_attempts: bigint, 
+/**
+ * Seconds between runs of a recurring job; None for one-off jobs
+ */
+interval_secs: bigint | null, run_at: string, started_at: string | null, finished_at: string | null, 
+/**
+ * Error of the last failed attempt
+ */
+last_error: string | null, created_at: string, updated_at: string, };
 
-export type ListInvitationsResponse = { invitations: Array<Invitation>, };
+export type JobStatusCount = { status: JobStatus, count: bigint, };
 
-export type GetInvitationResponse = { id: string, organization_slug: string, role: MemberRole, expires_at: string, };
+export type PolicyViolationKind = { "type": "repo_not_allowed" } | { "type": "protected_path", pattern: string, } | { "type": "outside_scope" };
 
-export type AcceptInvitationResponse = { organization_id: string, organization_slug: string, role: MemberRole, };
+export type PolicyViolation = { executor_profile_id: ExecutorProfileId, repo: string, path: string, kind: PolicyViolationKind, };
 
-export type RevokeInvitationRequest = { invitation_id: string, };
+export type PolicyViolationReport = { id: string, workspace_id: string, 
+/**
+ * The blocked coding agent run; `None` when a push was blocked
+ */
+execution_process_id: string | null, violations: Array<PolicyViolation>, created_at: string, };
 
-export type OrganizationMember = { user_id: string, role: MemberRole, joined_at: string, };
+export type DependencyEcosystem = "cargo" | "npm" | "go";
 
-export type OrganizationMemberWithProfile = { user_id: string, role: MemberRole, joined_at: string, first_name: string | null, last_name: string | null, username: string | null, email: string | null, avatar_url: string | null, };
+export type FindingSeverity = "error" | "warning";
 
-export type ListMembersResponse = { members: Array<OrganizationMemberWithProfile>, };
+export type DependencyFindingKind = { "type": "banned_package" } | { "type": "license_not_allowed", license: string, } | { "type": "license_unknown" } | { "type": "unpinned_version" };
 
-export type UpdateMemberRoleRequest = { role: MemberRole, };
+export type DependencyFinding = { repo: string, manifest: string, ecosystem: DependencyEcosystem, package: string, version: string, kind: DependencyFindingKind, severity: FindingSeverity, };
 
-export type UpdateMemberRoleResponse = { user_id: string, role: MemberRole, };
+export type DependencyReport = { id: string, workspace_id: string, execution_process_id: string | null, 
+/**
+ * Empty when the changed dependencies passed every check
+ */
+findings: Array<DependencyFinding>, created_at: string, };
 
-export type RemoteProject = { id: string, organization_id: string, name: string, metadata: Record<string, unknown>, created_at: string, };
+export type LintSeverity = "error" | "warning" | "info";
 
-export type ListProjectsResponse = { projects: Array<RemoteProject>, };
+export type LintFinding = { repo: string, 
+/**
+ * Name of the configured lint command that reported it
+ */
+command: string, 
+/**
+ * Relative to the repository root
+ */
+path: string, line: number | null, column: number | null, severity: LintSeverity, 
+/**
+ * Tool-specific rule identifier, e.g. `clippy::needless_return` or `no-unused-vars`
+ */
+rule: string | null, message: string, };
 
-export type RemoteProjectMembersResponse = { organization_id: string, members: Array<OrganizationMemberWithProfile>, };
+export type LintReport = { id: string, workspace_id: string, execution_process_id: string | null, findings: Array<LintFinding>, 
+/**
+ * Whether a follow-up turn was started asking the agent to fix the findings
+ */
+fix_requested: boolean, created_at: string, };
 
-export type CreateRemoteProjectRequest = { organization_id: string, name: string, };
+export type CoverageReport = { id: string, workspace_id: string, execution_process_id: string | null, repo_id: string, lines_total: bigint, lines_covered: bigint, 
+/**
+ * Coverage of the target branch; `None` when no baseline report was available
+ */
+base_lines_total: bigint | null, base_lines_covered: bigint | null, 
+/**
+ * Instrumented lines added or changed by the workspace branch
+ */
+patch_lines_total: bigint, patch_lines_covered: bigint, created_at: string, };
 
-export type LinkToExistingRequest = { remote_project_id: string, };
+export type CommandKind = "setupscript" | "cleanupscript" | "lint";
 
-export type RegisterRepoRequest = { path: string, display_name: string | null, };
+export type CommandQuarantine = { id: string, repo_id: string, command_hash: string, command: string, 
+/**
+ * Quarantined by flaky detection rather than by a user
+ */
+automatic: boolean, created_at: string, };
 
-export type InitRepoRequest = { parent_path: string, folder_name: string, };
+export type ContextCompaction = { id: string, session_id: string, 
+/**
+ * The turn whose prompt the context was prepended to
+ */
+execution_process_id: string, 
+/**
+ * Earlier turns reduced to a single line
+ */
+turns_summarized: bigint, 
+/**
+ * Recent turns carried over with their prompt and outcome
+ */
+turns_included: bigint, diff_files: bigint, 
+/**
+ * Size of the turns and diffs before compaction
+ */
+source_chars: bigint, content: string, created_at: string, };
+
+/**
+ * What a coding agent turn is for
+ */
+export type TurnPurpose = "initial" | "followup" | "question" | "prdescription" | "lintfix";
+
+export type TaskSize = "small" | "medium" | "large";
+
+export type RoutingDecision = { id: string, execution_process_id: string, workspace_id: string, purpose: TurnPurpose, task_size: TaskSize, 
+/**
+ * Profile the turn was started with before routing
+ */
+requested_profile: ExecutorProfileId, selected_profile: ExecutorProfileId, 
+/**
+ * Name of the matching rule; None when no rule applied
+ */
+rule_name: string | null, reason: string, 
+/**
+ * Consecutive failed coding agent turns in the session before this one
+ */
+previous_failures: bigint, 
+/**
+ * Attempt budget left before this turn; None without a budget
+ */
+budget_remaining: number | null, estimated_cost: number, created_at: string, };
+
+export type CreateAutomationRule = { project_id: string | null, name: string, condition: string, action: RuleAction, enabled: boolean | null, };
+
+export type UpdateAutomationRule = { name: string | null, condition: string | null, action: RuleAction | null, enabled: boolean | null, };
+
+export type CreateAutomationTransition = { project_id: string | null, name: string, steps: Array<RuleAction>, };
+
+export type UpdateAutomationTransition = { name: string | null, steps: Array<RuleAction> | null, };
+
+export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
+
+export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, shared_task_id: string | null, 
+/**
+ * Sent with any UTC offset and stored in UTC
+ */
+due_at: string | null, };
+
+export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, 
+/**
+ * Sent with any UTC offset and stored in UTC
+ */
+due_at: string | null, 
+/**
+ * Remove the due date; `due_at` is ignored when set
+ */
+clear_due_at: boolean, 
+/**
+ * Only update when the task still has this `updated_at`, so concurrent edits are detected
+ */
+expected_updated_at: string | null, };
+
+export type DraftFollowUpData = { message: string, variant: string | null, };
+
+export type ScratchPayload = { "type": "DRAFT_TASK", "data": string } | { "type": "DRAFT_FOLLOW_UP", "data": DraftFollowUpData };
+
+export enum ScratchType { DRAFT_TASK = "DRAFT_TASK", DRAFT_FOLLOW_UP = "DRAFT_FOLLOW_UP" }
+
+export type Scratch = { id: string, payload: ScratchPayload, created_at: string, updated_at: string, };
+
+export type CreateScratch = { payload: ScratchPayload, };
+
+export type UpdateScratch = { payload: ScratchPayload, };
+
+export type Image = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
+
+export type CreateImage = { file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, };
+
+export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, git_provider: string | null, setup_completed_at: string | null, 
+/**
+ * When new pull request review comments started becoming follow-ups; None when they don't
+ */
+review_follow_ups_enabled_at: string | null, created_at: string, updated_at: string, };
+
+export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
+
+export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
+/**
+ * dropped: true if this process is excluded from the current
+ * history view (due to restore/trimming). Hidden from logs/timeline;
+ * still listed in the Processes tab.
+ */
+dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
+
+export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+
+export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
+
+/**
+ * A file selected in the diff view, optionally narrowed to some of its hunks
+ */
+export type ScopedFile = { repo: string, 
+/**
+ * Path relative to the repository root
+ */
+path: string, 
+/**
+ * Selected hunks as unified diff text; empty selects the whole file
+ */
+hunks: Array<string>, };
+
+export type ExecutionScope = { id: string, execution_process_id: string, files: Array<ScopedFile>, 
+/**
+ * Changes outside the selected files fail the run
+ */
+restrict_writes: boolean, created_at: string, };
+
+export type RepoChainStepStatus = "pending" | "running" | "completed" | "failed";
+
+/**
+ * One repository in the order a multi-repo workspace is worked through
+ */
+export type RepoChainStep = { id: string, workspace_id: string, repo_id: string, position: bigint, 
+/**
+ * Extra instructions for this repository on top of the task
+ */
+instructions: string | null, status: RepoChainStepStatus, 
+/**
+ * The coding agent turn that worked on this repository
+ */
+execution_process_id: string | null, created_at: string, updated_at: string, };
+
+export type CreateRepoChainStep = { repo_id: string, instructions: string | null, };
+
+export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
+
+export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
+
+export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
+
+export type MergeStatus = "open" | "merged" | "closed" | "unknown";
+
+export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
+
+/**
+ * A comment fetched from a pull request, and whether it was addressed
+ */
+export type PrComment = { id: string, merge_id: string, 
+/**
+ * Kind and provider id of the comment, as `UnifiedComment::key`, e.g. `review:1234`
+ */
+comment_key: string, author: string, body: string, url: string, commented_at: string, addressed_at: string | null, 
+/**
+ * Agent turn that addressed the comment; `None` when it was handled by hand
+ */
+addressed_by_execution_id: string | null, created_at: string, updated_at: string, };
+
+/**
+ * An addressed comment, for listing next to the comments fetched from the provider
+ */
+export type AddressedPrComment = { comment_key: string, addressed_at: string, addressed_by_execution_id: string | null, };
+
+export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
+
+export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue, tool_call_id: string, };
+
+export type ApprovalResponse = { execution_process_id: string, status: ApprovalStatus, };
+
+export type Diff = { change: DiffChangeKind, oldPath: string | null, newPath: string | null, oldContent: string | null, newContent: string | null, 
+/**
+ * True when file contents are intentionally omitted (e.g., too large)
+ */
+contentOmitted: boolean, 
+/**
+ * Optional precomputed stats for omitted content
+ */
+additions: number | null, deletions: number | null, };
+
+export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "copied" | "permissionChange";
+
+export type ApiResponse<T, E = T> = { success: boolean, data: T | null, error_data: E | null, message: string | null, 
+/**
+ * Set when `data` is one page of a longer list
+ */
+pagination: Pagination | null, };
+
+/**
+ * Where a page sits in a list
+ */
+export type Pagination = { 
+/**
+ * Pass as `cursor` to fetch the next page; None on the last page
+ */
+next_cursor: string | null, limit: number, 
+/**
+ * Length of the whole list when the page was read; it may change before the next page
+ */
+total_estimate: bigint, };
+
+/**
+ * Query parameters of list endpoints. Without either, the whole list is returned.
+ */
+export type PageQuery = { 
+/**
+ * `next_cursor` of the previous page
+ */
+cursor: string | null, 
+/**
+ * Page size, 50 by default and at most 500
+ */
+limit: number | null, };
+
+export type LoginStatus = { "status": "loggedout" } | { "status": "loggedin", profile: ProfileResponse, };
+
+export type ProfileResponse = { user_id: string, username: string | null, email: string, providers: Array<ProviderProfile>, };
+
+export type ProviderProfile = { provider: string, username: string | null, display_name: string | null, email: string | null, avatar_url: string | null, };
+
+export type StatusResponse = { logged_in: boolean, profile: ProfileResponse | null, degraded: boolean | null, };
+
+export enum MemberRole { ADMIN = "ADMIN", MEMBER = "MEMBER" }
+
+export enum InvitationStatus { PENDING = "PENDING", ACCEPTED = "ACCEPTED", DECLINED = "DECLINED", EXPIRED = "EXPIRED" }
+
+export type Organization = { id: string, name: string, slug: string, is_personal: boolean, created_at: string, updated_at: string, };
+
+export type OrganizationWithRole = { id: string, name: string, slug: string, is_personal: boolean, created_at: string, updated_at: string, user_role: MemberRole, };
+
+export type ListOrganizationsResponse = { organizations: Array<OrganizationWithRole>, };
+
+export type GetOrganizationResponse = { organization: Organization, user_role: string, };
+
+export type CreateOrganizationRequest = { name: string, slug: string, };
+
+export type CreateOrganizationResponse = { organization: OrganizationWithRole, };
+
+export type UpdateOrganizationRequest = { name: string, };
+
+export type Invitation = { id: string, organization_id: string, invited_by_user_id: string | null, email: string, role: MemberRole, status: InvitationStatus, token: string, created_at: string, expires_at: string, };
+
+export type CreateInvitationRequest = { email: string, role: MemberRole, };
+
+export type CreateInvitationResponse = { invitation: Invitation, };
+
+export type ListInvitationsResponse = { invitations: Array<Invitation>, };
+
+export type GetInvitationResponse = { id: string, organization_slug: string, role: MemberRole, expires_at: string, };
+
+export type AcceptInvitationResponse = { organization_id: string, organization_slug: string, role: MemberRole, };
+
+export type RevokeInvitationRequest = { invitation_id: string, };
+
+export type OrganizationMember = { user_id: string, role: MemberRole, joined_at: string, };
+
+export type OrganizationMemberWithProfile = { user_id: string, role: MemberRole, joined_at: string, first_name: string | null, last_name: string | null, username: string | null, email: string | null, avatar_url: string | null, };
+
+export type ListMembersResponse = { members: Array<OrganizationMemberWithProfile>, };
+
+export type UpdateMemberRoleRequest = { role: MemberRole, };
+
+export type UpdateMemberRoleResponse = { user_id: string, role: MemberRole, };
+
+export type LibraryItemKind = "prompt_template" | "task_template" | "automation_rule";
+
+/**
+ * A template or automation rule published to an organization
+ */
+export type LibraryItem = { id: string, organization_id: string, kind: LibraryItemKind, name: string, 
+/**
+ * Kind-specific payload, as published by the instance it came from
+ */
+content: Record<string, unknown>, 
+/**
+ * Starts at 1 and goes up with every update
+ */
+version: bigint, published_by: string | null, created_at: string, updated_at: string, };
+
+export type ListLibraryItemsResponse = { items: Array<LibraryItem>, };
+
+export type PublishLibraryItemRequest = { organization_id: string, kind: LibraryItemKind, name: string, content: Record<string, unknown>, };
+
+export type UpdateLibraryItemRequest = { name: string, content: Record<string, unknown>, };
+
+/**
+ * A library item as seen from this instance
+ */
+export type LibraryEntry = { 
+/**
+ * The local copy, when the item was published from or pulled to this instance
+ */
+local_id: string | null, local_version: bigint | null, update_available: boolean, id: string, organization_id: string, kind: LibraryItemKind, name: string, 
+/**
+ * Kind-specific payload, as published by the instance it came from
+ */
+content: Record<string, unknown>, 
+/**
+ * Starts at 1 and goes up with every update
+ */
+version: bigint, published_by: string | null, created_at: string, updated_at: string, };
+
+export type RemoteProject = { id: string, organization_id: string, name: string, metadata: Record<string, unknown>, created_at: string, };
+
+export type ListProjectsResponse = { projects: Array<RemoteProject>, };
+
+export type RemoteProjectMembersResponse = { organization_id: string, members: Array<OrganizationMemberWithProfile>, };
+
+/**
+ * Something a member may or may not be allowed to do in a project
+ */
+export type ProjectPermission = "start_execution" | "create_pr" | "merge" | "edit_automation_rules";
+
+/**
+ * Grants or revokes a permission in one project, for everyone with a role or for one member
+ */
+export type ProjectPermissionOverride = { project_id: string, 
+/**
+ * Set for overrides of a whole role
+ */
+role: MemberRole | null, 
+/**
+ * Set for overrides of one member, which win over the override of their role
+ */
+user_id: string | null, permission: ProjectPermission, allowed: boolean, updated_at: string, };
+
+export type ListProjectPermissionOverridesResponse = { overrides: Array<ProjectPermissionOverride>, };
+
+/**
+ * Sets the override of either a role or one member
+ */
+export type SetProjectPermissionOverrideRequest = { role: MemberRole | null, user_id: string | null, permission: ProjectPermission, 
+/**
+ * None removes the override, so the permission is inherited from the role again
+ */
+allowed: boolean | null, };
+
+/**
+ * What the current user may do in a project
+ */
+export type EffectiveProjectPermissions = { 
+/**
+ * None for projects that are not shared with an organization
+ */
+role: MemberRole | null, permissions: Array<ProjectPermission>, };
+
+export type CreateRemoteProjectRequest = { organization_id: string, name: string, };
+
+export type LinkToExistingRequest = { remote_project_id: string, };
+
+/**
+ * Git hosting provider type
+ */
+export type ProviderType = "github" | "gitlab" | "bitbucket" | "bitbucketserver" | "azuredevops" | "gerrit" | "codecommit";
+
+/**
+ * Remotes on a host that are served by a provider, e.g. a GitHub Enterprise or self-hosted
+ * GitLab instance under a custom domain
+ */
+export type ProviderHostMapping = { 
+/**
+ * Host name without scheme or port, e.g. `git.example.com`
+ */
+host: string, provider: ProviderType, };
+
+/**
+ * How failed calls to a provider are retried
+ */
+export type RetryPolicy = { 
+/**
+ * Delay before the first retry, doubled for each one after it
+ */
+min_delay_ms: bigint, 
+/**
+ * Longest delay between two attempts, also for waits a rate-limited response asks for
+ */
+max_delay_ms: bigint, 
+/**
+ * Retries after the first attempt; 0 turns retrying off
+ */
+max_retries: number, 
+/**
+ * Retry rate-limited requests (429, or 403 with rate-limit headers)
+ */
+retry_rate_limits: boolean, 
+/**
+ * Retry timeouts (408) and server errors (5xx)
+ */
+retry_server_errors: boolean, 
+/**
+ * Retry requests that got no response, failed CLI commands and unreadable responses
+ */
+retry_network_errors: boolean, };
+
+/**
+ * Retry policy of one provider, overriding the default
+ */
+export type ProviderRetryPolicy = { provider: ProviderType, policy: RetryPolicy, };
+
+/**
+ * How a PR/MR is merged into its target branch: with a merge commit, squashed into one commit,
+ * or rebased onto the target
+ */
+export type MergeMethod = "merge" | "squash" | "rebase";
+
+/**
+ * Overall review state of a PR/MR
+ */
+export type ReviewState = "unreviewed" | "pending" | "approved" | "changes_requested";
+
+/**
+ * What a reviewer said about a PR/MR (unified)
+ */
+export type ReviewVerdict = "approved" | "changes_requested" | "commented" | "pending";
+
+/**
+ * One reviewer's review of a PR/MR (works for GitHub reviews and GitLab approvals)
+ */
+export type UnifiedReview = { reviewer: string, verdict: ReviewVerdict, 
+/**
+ * None for pending reviews
+ */
+submitted_at: string | null, };
+
+/**
+ * Where a CI check on the head commit of a PR/MR stands (unified)
+ */
+export type CheckStatus = "pending" | "success" | "failure" | "skipped";
+
+/**
+ * State of a commit status published by the app, e.g. for an agent run on a branch head
+ */
+export type CommitStatusState = "pending" | "success" | "failure" | "error";
+
+/**
+ * One CI check or commit status on the head commit of a PR/MR
+ */
+export type CheckRun = { name: string, status: CheckStatus, 
+/**
+ * Page with the check's details or logs, when the provider links one
+ */
+url: string | null, 
+/**
+ * Id of the CI job behind the check, to fetch its log with `get_check_logs`. Unset for
+ * checks whose logs the provider doesn't serve, like commit statuses.
+ */
+id: string | null, };
+
+/**
+ * An open milestone of a repository that new MRs/PRs can be assigned to
+ */
+export type Milestone = { 
+/**
+ * Provider's id: the milestone number on GitHub, the global id on GitLab
+ */
+id: string, title: string, description: string | null, due_date: string | null, };
+
+/**
+ * What a provider requires before an MR/PR into a branch can be merged
+ */
+export type BranchProtection = { 
+/**
+ * Whether any protection rules apply to the branch
+ */
+protected: boolean, 
+/**
+ * Checks that must pass, by name; `pipeline` on GitLab projects that require a successful
+ * pipeline
+ */
+required_checks: Array<string>, 
+/**
+ * Approving reviews needed
+ */
+required_approvals: number, };
+
+/**
+ * An open MR/PR that waits for someone's review
+ */
+export type ReviewRequest = { number: bigint, url: string, title: string, 
+/**
+ * Username of whoever opened it
+ */
+author: string, draft: boolean, updated_at: string, };
+
+/**
+ * A release published on the provider
+ */
+export type ReleaseInfo = { tag: string, name: string, 
+/**
+ * Web page of the release
+ */
+url: string, };
+
+/**
+ * Whether a provider answers, as pushed to the event stream
+ */
+export type ProviderStatus = { provider: ProviderType, reachable: boolean, 
+/**
+ * When a call to the provider first failed to reach it; None while it is reachable
+ */
+unreachable_since: string | null, 
+/**
+ * Error of that call
+ */
+error: string | null, };
+
+/**
+ * A comment posted right away, or queued until the provider is reachable
+ */
+export type PostedComment = { "status": "posted", comment: UnifiedComment, } | { "status": "queued", job_id: string, };
+
+export type HealthStatus = { status: string, 
+/**
+ * Installed versions of gh, glab and the agent tools against the ones known to work
+ */
+compatibility: CompatibilityReport, 
+/**
+ * Circuit breakers of the provider APIs and executors used so far
+ */
+circuits: Array<CircuitStatus>, 
+/**
+ * Latest rate-limit quota of each provider API used so far
+ */
+rate_limits: Array<RateLimitStatus>, };
+
+export type CompatibilityReport = { tools: Array<ToolCompatibility>, checked_at: string, };
+
+export type ToolCompatibility = { name: string, 
+/**
+ * What the app uses the tool for
+ */
+used_by: string, version: string | null, 
+/**
+ * Known-good versions, like `>= 2.40.0, < 3.0.0`
+ */
+supported: string | null, status: CompatibilityStatus, 
+/**
+ * Set when the installed version is outside the supported range
+ */
+warning: string | null, };
+
+export type CompatibilityStatus = "compatible" | "too_old" | "too_new" | "unrecognized" | "unchecked" | "not_installed";
+
+/**
+ * State of a breaker, for the health check
+ */
+export type CircuitStatus = { 
+/**
+ * Backend the breaker guards, e.g. `GitLab API at gitlab.example.com`
+ */
+name: string, state: CircuitState, consecutive_failures: number, last_error: string | null, 
+/**
+ * When the next probe is let through, while open
+ */
+retry_at: string | null, };
+
+export type CircuitState = "closed" | "open" | "half_open";
+
+export type RateLimitStatus = { 
+/**
+ * API the quota applies to, e.g. `GitHub API at api.github.com`
+ */
+name: string, 
+/**
+ * Requests allowed per window, if the API says
+ */
+limit: bigint | null, 
+/**
+ * Requests left in the current window
+ */
+remaining: bigint, 
+/**
+ * When the window resets and the quota is refilled
+ */
+reset_at: string | null, observed_at: string, };
+
+export type RegisterRepoRequest = { path: string, display_name: string | null, };
+
+export type CloneRepoRequest = { 
+/**
+ * HTTPS or SSH remote URL
+ */
+url: string, display_name: string | null, 
+/**
+ * Fetch the whole history instead of a shallow clone
+ */
+full_clone: boolean, };
+
+export type ClonedRepo = { repo: Repo, 
+/**
+ * Hosting provider detected from the URL, if it is a known one
+ */
+provider: ProviderType | null, };
+
+export type InitRepoRequest = { parent_path: string, folder_name: string, };
+
+export type QuarantineCommandRequest = { command_hash: string, };
+
+export type CreateReleaseRequest = { tag: string, 
+/**
+ * Defaults to the tag
+ */
+name: string | null, 
+/**
+ * Notes written by hand; made from the PRs merged since the previous release when absent
+ */
+notes: string | null, 
+/**
+ * Have a coding agent write the notes from the merged PRs instead of listing them
+ */
+generate_notes: boolean, 
+/**
+ * Agent writing the notes; defaults to the configured executor profile
+ */
+executor_profile_id: ExecutorProfileId | null, };
+
+export type ReviewRequestsQuery = { 
+/**
+ * Reviewer's username on the providers; defaults to the configured GitHub username
+ */
+username: string | null, };
+
+/**
+ * Open MRs/PRs of one repository waiting for the user's review
+ */
+export type RepoReviewRequests = { repo_id: string, repo_name: string, provider: ProviderType, review_requests: Array<ReviewRequest>, 
+/**
+ * Why the provider could not be asked, in which case there are no review requests
+ */
+error: string | null, };
+
+export type TagSearchParams = { search: string | null, };
+
+export type TokenResponse = { access_token: string, expires_at: string | null, };
+
+export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, environment: Environment, 
+/**
+ * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
+ */
+capabilities: { [key in string]?: Array<BaseAgentCapability> }, 
+/**
+ * The server runs with `--test-mode`, so the frontend sends no telemetry either
+ */
+test_mode: boolean, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+
+export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
+
+export type McpServerQuery = { executor: BaseCodingAgent, };
+
+export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, };
+
+export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
+
+export type CheckEditorAvailabilityQuery = { editor_type: EditorType, };
+
+export type CheckEditorAvailabilityResponse = { available: boolean, };
+
+export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
+
+export type UpsertProviderHostRequest = { provider: ProviderType, };
+
+export type SetupStatus = { 
+/**
+ * True until the wizard has been completed once
+ */
+first_run: boolean, data_dir: string, database_path: string, config_path: string, project_count: bigint, 
+/**
+ * Whether this binary was built with the frontend bundle embedded
+ */
+frontend_embedded: boolean, executor_profile: ExecutorProfileId, workspace_dir: string | null, };
+
+export type CompleteSetupRequest = { executor_profile: ExecutorProfileId | null, workspace_dir: string | null, git_branch_prefix: string | null, analytics_enabled: boolean | null, };
+
+export type CurrentUserResponse = { user_id: string, };
+
+export type CreateFollowUpAttempt = { prompt: string, variant: string | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
+
+export type CreateScopedFollowUp = { prompt: string, variant: string | null, 
+/**
+ * Files and hunks selected in the diff view
+ */
+files: Array<ScopedFile>, 
+/**
+ * Fail the run if it changes anything outside the selected files
+ */
+restrict_writes: boolean, };
+
+export type AskAgentRequest = { question: string, variant: string | null, };
+
+export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, 
+/**
+ * Only change the target branch when the attempt still has this `updated_at`
+ */
+expected_updated_at: string | null, };
+
+export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
+
+export type MergeTaskAttemptRequest = { repo_id: string, };
+
+export type PushTaskAttemptRequest = { repo_id: string, };
+
+export type RenameBranchRequest = { new_branch_name: string, 
+/**
+ * Only rename when the attempt still has this `updated_at`
+ */
+expected_updated_at: string | null, };
+
+export type RenameBranchResponse = { branch: string, };
+
+export type OpenEditorRequest = { editor_type: string | null, file_path: string | null, };
+
+export type OpenEditorResponse = { url: string | null, };
+
+export type AssignSharedTaskRequest = { new_assignee_user_id: string | null, };
+
+export type CreateShareLinkRequest = { 
+/**
+ * Share only this attempt instead of every attempt of the task
+ */
+workspace_id: string | null, 
+/**
+ * Hours until the link stops working; it never expires when unset
+ */
+expires_in_hours: number | null, };
+
+/**
+ * What a share link shows. Logs, file contents and local paths are left out on purpose.
+ */
+export type SharedTaskView = { title: string, description: string | null, status: TaskStatus, created_at: string, updated_at: string, expires_at: string | null, 
+/**
+ * Newest first
+ */
+attempts: Array<SharedAttempt>, };
+
+export type SharedAttempt = { branch: string, executor: string | null, created_at: string, repos: Array<SharedRepoChanges>, 
+/**
+ * Coding agent turns, oldest first
+ */
+timeline: Array<SharedTimelineEntry>, };
+
+export type SharedRepoChanges = { repo_name: string, target_branch: string, 
+/**
+ * Committed changes against the target branch, or None when the branch is gone
+ */
+diff: SharedDiffSummary | null, 
+/**
+ * Latest pull request opened from the branch
+ */
+pull_request: SharedPullRequest | null, };
+
+export type SharedDiffSummary = { files_changed: number, additions: number, deletions: number, };
+
+export type SharedPullRequest = { number: bigint, url: string, status: MergeStatus, };
+
+export type SharedTimelineEntry = { at: string, prompt: string | null, summary: string | null, };
+
+/**
+ * A newly issued key. It is only returned here; afterwards only its prefix is known.
+ */
+export type IssuedServiceAccountKey = { account: ServiceAccount, key: string, };
+
+/**
+ * Quotas of an organization next to what it uses of them
+ */
+export type TenantUsageReport = { tenant_id: string, 
+/**
+ * None when the organization has no quotas
+ */
+quota: TenantQuota | null, usage: TenantUsage, };
+
+export type ProjectMirrorStatus = { 
+/**
+ * None when the project is not mirrored
+ */
+mirror: ProjectMirror | null, conflicts: Array<MirrorConflict>, };
+
+/**
+ * A task changed differently here and on another instance since they last agreed
+ */
+export type MirrorConflict = { task_id: string, base: MirroredFields, local: MirroredFields, remote: MirroredFields, };
+
+export type ResolveMirrorConflictRequest = { task_id: string, keep: MirrorSide, };
+
+export type BoardMetricsQuery = { 
+/**
+ * Defaults to twelve weeks before `until`
+ */
+since: string | null, 
+/**
+ * Defaults to now
+ */
+until: string | null, };
+
+export type BoardMetricsExportQuery = { report: MetricsReport, since: string | null, until: string | null, };
+
+export type ColumnSlaQuery = { status: TaskStatus, };
+
+export type ShareTaskResponse = { shared_task_id: string, };
+
+export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+
+export type CreateTaskCommentRequest = { body: string, };
+
+export type EstimateTaskRequest = { project_id: string, title: string, description: string | null, };
+
+export type RefineTaskRequest = { 
+/**
+ * Refine this title instead of the saved one, e.g. from an unsaved draft
+ */
+title: string | null, 
+/**
+ * Refine this description instead of the saved one
+ */
+description: string | null, 
+/**
+ * Defaults to the configured executor profile
+ */
+executor_profile_id: ExecutorProfileId | null, };
+
+/**
+ * Why one field of a request was rejected, e.g. `repos[0].target_branch`
+ */
+export type FieldError = { field: string, message: string, };
+
+export type RefineTaskResponse = { refinement: TaskRefinement, 
+/**
+ * The task description to save when the refinement is accepted
+ */
+description: string, };
+
+export type PublishToLibraryRequest = { organization_id: string, kind: LibraryItemKind, 
+/**
+ * Id of the local template or automation rule
+ */
+local_id: string, };
+
+export type PullFromLibraryRequest = { organization_id: string, };
+
+/**
+ * What a webhook delivery led to
+ */
+export type CommentTriggerOutcome = { "outcome": "ignored" } | { "outcome": "accepted", job_id: string, } | { "outcome": "started", workspace_id: string, execution_process_id: string, } | { "outcome": "queued", workspace_id: string, };
+
+export type ValidateConditionRequest = { condition: string, };
+
+export type ValidateConditionResponse = { valid: boolean, error: string | null, 
+/**
+ * Character offset of a parse error
+ */
+position: number | null, };
+
+export type ApplyTransitionRequest = { task_ids: Array<string>, };
+
+export type TransitionOutcome = { task_id: string, 
+/**
+ * Steps that ran, in order; the step after them failed if there is an error
+ */
+completed_steps: number, error: string | null, };
+
+/**
+ * A read that can be part of a batch
+ */
+export type BatchOperation = { "op": "task", task_id: string, } | { "op": "workspaces", task_id: string, } | { "op": "merges", workspace_id: string, } | { "op": "latest_execution", workspace_id: string, run_reason: ExecutionProcessRunReason | null, };
+
+export type BatchRequest = { operations: Array<BatchOperation>, };
+
+export type BatchData = { "op": "task", task: Task, } | { "op": "workspaces", workspaces: Array<Workspace>, } | { "op": "merges", merges: Array<Merge>, } | { "op": "latest_execution", execution_process: ExecutionProcess | null, };
+
+/**
+ * Outcome of one operation; a failed operation does not fail the others
+ */
+export type BatchResult = { data: BatchData | null, error: string | null, };
+
+export type JobListQuery = { status: JobStatus | null, kind: string | null, 
+/**
+ * Only jobs created at least this many seconds ago
+ */
+min_age_secs: bigint | null, 
+/**
+ * Only jobs created at most this many seconds ago
+ */
+max_age_secs: bigint | null, limit: bigint | null, };
+
+/**
+ * Everything that is waiting on something, to answer "why is nothing happening"
+ */
+export type QueueOverview = { jobs: Array<JobStatusCount>, 
+/**
+ * When the longest-waiting due job became due; long ago means the workers are stuck or busy
+ */
+oldest_due_job_at: string | null, 
+/**
+ * Webhook and notification deliveries per status
+ */
+deliveries: Array<JobStatusCount>, 
+/**
+ * Coding agent runs waiting for a slot under the executor limits
+ */
+queued_executions: Array<ThrottledExecution>, 
+/**
+ * Follow-ups waiting for the running turn of their session to finish
+ */
+queued_messages: Array<QueuedMessage>, };
+
+export type QueuedAgeQuery = { 
+/**
+ * Only entries waiting at least this many seconds
+ */
+min_age_secs: bigint | null, };
+
+export type AuditLogQuery = { project_id: string | null, task_id: string | null, source: TaskEventSource | null, 
+/**
+ * e.g. `rule:Close merged` for the changes of one automation rule
+ */
+actor: string | null, since: string | null, until: string | null, limit: bigint | null, };
+
+export type CreateGitHubPrRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, 
+/**
+ * Title of a milestone to assign, from the repository's milestones
+ */
+milestone: string | null, 
+/**
+ * Have the provider merge the PR with this method once its checks and approvals pass
+ */
+auto_merge: MergeMethod | null, };
+
+export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
+
+export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
+
+export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+
+export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
+
+export type AdoptBranchRepo = { repo_id: string, 
+/**
+ * Inferred from the branch history when not given
+ */
+target_branch: string | null, };
+
+export type AdoptBranchRequest = { task_id: string, executor_profile_id: ExecutorProfileId, 
+/**
+ * A local branch, or a branch of the default remote named without the remote prefix
+ */
+branch: string, repos: Array<AdoptBranchRepo>, };
+
+/**
+ * Which task and pull request a workspace branch belongs to
+ */
+export type BranchMapping = { branch: string, target_branch: string, repo_id: string, repo_name: string, workspace_id: string, task_id: string, task_title: string, task_status: TaskStatus, 
+/**
+ * Latest pull request opened from the branch
+ */
+pr_number: bigint | null, pr_url: string | null, pr_status: MergeStatus | null, };
+
+export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
+
+export type RunAgentSetupResponse = Record<string, never>;
+
+export type GhCliSetupError = "BREW_MISSING" | "SETUP_HELPER_NOT_SUPPORTED" | { "OTHER": { message: string, } };
+
+export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, };
+
+export type AbortConflictsRequest = { repo_id: string, };
+
+export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, } | { "type": "rebase_in_progress" };
+
+export type PushError = { "type": "force_push_required" } | { "type": "policy_violation", violations: Array<PolicyViolation>, } | { "type": "secrets_detected", findings: Array<SecretFinding>, };
+
+export type AcknowledgeSecretsRequest = { fingerprints: Array<string>, };
+
+export type StartRepoChainRequest = { 
+/**
+ * Repositories in the order they are changed, each in its own turn
+ */
+steps: Array<CreateRepoChainStep>, variant: string | null, };
+
+export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "policy_violation", violations: Array<PolicyViolation>, } | { "type": "secrets_detected", findings: Array<SecretFinding>, } | { "type": "dependency_policy_violation", findings: Array<DependencyFinding>, };
+
+export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+/**
+ * True if a `git rebase` is currently in progress in this worktree
+ */
+is_rebase_in_progress: boolean, 
+/**
+ * Current conflict operation if any
+ */
+conflict_op: ConflictOp | null, 
+/**
+ * List of files currently in conflicted (unmerged) state
+ */
+conflicted_files: Array<string>, };
+
+export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
+
+export type AttachPrResponse = { pr_attached: boolean, pr_url: string | null, pr_number: bigint | null, pr_status: MergeStatus | null, 
+/**
+ * Summary of the reviews, when the provider reports them
+ */
+review_state: ReviewState | null, };
+
+export type AttachExistingPrRequest = { repo_id: string, };
+
+export type PrCommentsResponse = { comments: Array<UnifiedComment>, 
+/**
+ * Set when the provider is unreachable and these are the comments last fetched, to when
+ * they were fetched
+ */
+cached_at: string | null, 
+/**
+ * Comments of the PR marked addressed, on any page
+ */
+addressed: Array<AddressedPrComment>, };
+
+export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "provider_unreachable" };
+
+export type GetPrCommentsQuery = { repo_id: string, 
+/**
+ * Only comments created at or after this time, asked of the provider when it can filter
+ */
+since: string | null, 
+/**
+ * Leave out the comments of bots, like CI reports
+ */
+exclude_bots: boolean, 
+/**
+ * Comma-separated usernames to keep the comments of
+ */
+authors: string | null, 
+/**
+ * Leave out the comments marked addressed
+ */
+only_unresolved: boolean, };
+
+export type MergePrRequest = { repo_id: string, method: MergeMethod, };
+
+export type ListMilestonesQuery = { repo_id: string, };
+
+export type BranchProtectionQuery = { repo_id: string, 
+/**
+ * Branch the MR/PR would target; the workspace's target branch when unset
+ */
+target_branch: string | null, };
+
+export type PrDetailsQuery = { repo_id: string, };
+
+/**
+ * The attached MR/PR with its checks, reviews and comments, fetched together
+ */
+export type PrDetailsResponse = { pr_number: bigint, pr_url: string, pr_status: MergeStatus, 
+/**
+ * Summary of the reviews, when the provider reports them
+ */
+review_state: ReviewState | null, 
+/**
+ * Checks on the head commit; empty on providers that don't report them
+ */
+checks: Array<CheckRun>, reviews: Array<UnifiedReview>, comments: Array<UnifiedComment>, 
+/**
+ * Set when the provider is unreachable and this is the response last fetched, to when it
+ * was fetched
+ */
+cached_at: string | null, };
+
+export type CheckLogsQuery = { repo_id: string, 
+/**
+ * `id` of one of the checks in [`PrDetailsResponse`]
+ */
+check_id: string, };
+
+/**
+ * Log of the CI job behind a check of the attached MR/PR
+ */
+export type CheckLogsResponse = { 
+/**
+ * The end of the log, where jobs report why they failed
+ */
+log: string, 
+/**
+ * Whether earlier output was cut off to keep the log prompt-sized
+ */
+truncated: boolean, };
+
+export type ClosePrRequest = { repo_id: string, 
+/**
+ * Also move the task to cancelled, for abandoning the work
+ */
+abandon_task: boolean, };
+
+export type ReopenPrRequest = { repo_id: string, };
+
+export type SetPrDraftRequest = { repo_id: string, 
+/**
+ * `false` marks the PR ready for review and moves an in-progress task to in review
+ */
+draft: boolean, };
+
+export type UpdatePrRequest = { repo_id: string, title: string | null, body: string | null, 
+/**
+ * Branch the PR merges into; the workspace targets it from then on
+ */
+base_branch: string | null, };
+
+export type SetReviewFollowUpsRequest = { enabled: boolean, };
+
+export type PostPrCommentRequest = { repo_id: string, body: string, };
+
+export type PostPrReviewCommentRequest = { repo_id: string, 
+/**
+ * File path relative to the repository root
+ */
+path: string, 
+/**
+ * Line in the new version of the file, starting at 1
+ */
+line: bigint, body: string, };
+
+export type ReplyToPrCommentRequest = { repo_id: string, 
+/**
+ * Id of any comment in the thread, as in `UnifiedComment`
+ */
+thread_id: string, body: string, };
+
+export type MarkPrCommentAddressedRequest = { repo_id: string, 
+/**
+ * Key of the comment, `general:<id>` or `review:<id>` with its id as in `UnifiedComment`
+ */
+comment_key: string, 
+/**
+ * False to mark the comment not addressed again
+ */
+addressed: boolean, 
+/**
+ * Agent turn of this workspace that addressed the comment
+ */
+execution_process_id: string | null, };
+
+export type ApplySuggestionsRequest = { repo_id: string, 
+/**
+ * Review comment whose suggestions to apply; every suggestion on the MR/PR when None
+ */
+comment_id: bigint | null, };
+
+export type ApplySuggestionsResponse = { 
+/**
+ * Review comments whose suggestions were applied
+ */
+applied: Array<bigint>, 
+/**
+ * False when the worktree already read as suggested
+ */
+committed: boolean, };
+
+export type ApplySuggestionsError = { "type": "no_pr_attached" } | { "type": "no_suggestions" } | { "type": "suggestion_outdated", path: string, line: bigint, } | { "type": "suggestions_overlap", path: string, } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" };
+
+export type PrActionResponse = { pr_status: MergeStatus, merge_commit_sha: string | null, };
+
+export type PrActionError = { "type": "no_pr_attached" } | { "type": "pr_not_open", status: MergeStatus, } | { "type": "pr_not_closed", status: MergeStatus, } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "provider_not_supported" } | { "type": "provider_unreachable" };
+
+export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string, body: string, created_at: string, url: string, } | { "comment_type": "review", id: bigint, author: string, author_association: string, body: string, created_at: string, url: string, path: string, 
+/**
+ * First line of a comment spanning several lines; None when it covers `line` only
+ */
+start_line: bigint | null, line: bigint | null, diff_hunk: string, };
+
+export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+/**
+ * True if a `git rebase` is currently in progress in this worktree
+ */
+is_rebase_in_progress: boolean, 
+/**
+ * Current conflict operation if any
+ */
+conflict_op: ConflictOp | null, 
+/**
+ * List of files currently in conflicted (unmerged) state
+ */
+conflicted_files: Array<string>, };
+
+export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
+
+export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
+
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, pr_branding: PrBrandingConfig, redaction: RedactionConfig, 
+/**
+ * Repository and path restrictions enforced on coding agent changes
+ */
+executor_policies: Array<ExecutorPolicy>, 
+/**
+ * Concurrency and start-rate limits per executor profile
+ */
+executor_limits: Array<ExecutorLimit>, 
+/**
+ * Scan branches for secrets before pushing or opening a pull request
+ */
+secret_scan_enabled: boolean, 
+/**
+ * License, banned package and pinning rules for dependencies added by coding agents
+ */
+dependency_policy: DependencyPolicyConfig, 
+/**
+ * Lint commands run after each coding agent turn
+ */
+lint: LintConfig, 
+/**
+ * Coverage reports read after each coding agent turn
+ */
+coverage: CoverageConfig, 
+/**
+ * Detection and quarantine of setup, cleanup and lint commands that fail intermittently
+ */
+flaky: FlakyConfig, 
+/**
+ * Rules that pick the executor profile per coding agent turn
+ */
+routing: RoutingConfig, 
+/**
+ * Context carried into turns that cannot resume the agent's session
+ */
+compaction: CompactionConfig, 
+/**
+ * Background fetching of registered repositories
+ */
+fetch: FetchConfig, 
+/**
+ * Follow-ups started by trigger phrases in pull request comments
+ */
+comment_triggers: CommentTriggerConfig, 
+/**
+ * Language for pull request descriptions, commit messages and agent summaries, as a locale
+ * tag like `ja` or `de`; English when unset
+ */
+content_locale: string | null, 
+/**
+ * Hosts whose remotes belong to a git provider, for self-hosted instances under custom
+ * domains
+ */
+git_provider_hosts: Array<ProviderHostMapping>, 
+/**
+ * Pages read from a provider API when listing comments, merge requests and files; longer
+ * listings are cut off with a warning in the log
+ */
+git_provider_max_pages: number, 
+/**
+ * How failed provider calls are retried, per provider; providers without a policy here
+ * use the default
+ */
+git_provider_retry: Array<ProviderRetryPolicy>, 
+/**
+ * Publish the outcome of each coding agent run as an `agentic-kanban/agent-run` commit
+ * status on the pushed branch, for branch protection to require
+ */
+publish_agent_run_statuses: boolean, 
+/**
+ * SQLite connection settings, applied on the next start
+ */
+database: DatabaseTuning, 
+/**
+ * Where attachments, artifacts and transcripts are stored, applied on the next start
+ */
+storage: StorageConfig, 
+/**
+ * Export of server and execution logs to an external aggregator
+ */
+log_shipping: LogShippingConfig, 
+/**
+ * Release channel and checks for updating a standalone binary from within the app
+ */
+update: UpdateConfig, 
+/**
+ * Timezone, week start and sprint length applied to due dates in digests, calendar feeds
+ * and overdue counts
+ */
+schedule: ScheduleConfig, };
+
+/**
+ * SQLite connection settings. Concurrent execution logging writes a lot, so the defaults use
+ * write-ahead logging and wait for locks instead of failing with `database is locked`.
+ */
+export type DatabaseTuning = { 
+/**
+ * Write-ahead logging, so reads do not block on writes and the other way around
+ */
+wal: boolean, 
+/**
+ * How long a connection waits for a lock before giving up
+ */
+busy_timeout_ms: bigint, 
+/**
+ * Bytes of the database file to memory-map for reads; 0 turns it off
+ */
+mmap_size: bigint, 
+/**
+ * Connections of the main pool
+ */
+max_connections: number, 
+/**
+ * Connections of a separate read-only pool for heavy queries like reports and board
+ * snapshots; 0 runs them on the main pool. Only used with write-ahead logging.
+ */
+read_pool_connections: number, };
+
+/**
+ * Where the server keeps attachments, artifacts and transcripts; applied on the next start
+ */
+export type StorageConfig = { backend: StorageBackend, 
+/**
+ * Bucket of the S3 backend
+ */
+bucket: string | null, 
+/**
+ * Region of the bucket; the region of the AWS configuration when unset
+ */
+region: string | null, 
+/**
+ * Endpoint of an S3-compatible service like MinIO; AWS when unset
+ */
+endpoint: string | null, 
+/**
+ * Address the bucket in the path instead of the host name, which MinIO needs
+ */
+force_path_style: boolean, 
+/**
+ * Prefix of every key, to share a bucket with other data
+ */
+prefix: string | null, 
+/**
+ * Seconds download URLs stay valid
+ */
+signed_url_ttl_secs: bigint, };
+
+export type StorageBackend = "local" | "s3";
+
+/**
+ * A download URL and when it stops working
+ */
+export type SignedUrl = { url: string, expires_at: string, };
 
-export type TagSearchParams = { search: string | null, };
+/**
+ * Export of server and execution logs to an external aggregator
+ */
+export type LogShippingConfig = { enabled: boolean, sink: LogSink, 
+/**
+ * URL of the aggregator, or `host:port` for syslog
+ */
+endpoint: string | null, 
+/**
+ * Authorization header of HTTP sinks, e.g. `Bearer <token>`
+ */
+authorization: string | null, 
+/**
+ * Elasticsearch index records are written to
+ */
+index: string, 
+/**
+ * Ship the server's own logs
+ */
+server_logs: boolean, 
+/**
+ * Least severe server log level shipped: trace, debug, info, warn or error
+ */
+server_log_level: string, 
+/**
+ * Ship the stdout and stderr of execution processes
+ */
+execution_logs: boolean, 
+/**
+ * Added to every record, e.g. the environment or cluster name
+ */
+labels: { [key in string]?: string }, };
 
-export type TokenResponse = { access_token: string, expires_at: string | null, };
+export type LogSink = "json_http" | "loki" | "elasticsearch" | "syslog";
 
-export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, environment: Environment, 
+export type UpdateConfig = { channel: UpdateChannel, 
 /**
- * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
+ * Look for a new release at startup and log when one is available
+ */
+check_on_startup: boolean, 
+/**
+ * Release server to use instead of the one the binary was built with
  */
-capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+base_url: string | null, 
+/**
+ * How long an install waits for running executions before it is called off
+ */
+drain_timeout_mins: number, };
 
-export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
+export type UpdateChannel = "stable" | "beta";
 
-export type McpServerQuery = { executor: BaseCodingAgent, };
+export type UpdateStatus = { current_version: string, channel: UpdateChannel, 
+/**
+ * Latest release of the channel; None when no release server is configured
+ */
+latest_version: string | null, update_available: boolean, install: InstallKind, 
+/**
+ * Why updating from within the app is not possible here
+ */
+unsupported_reason: string | null, 
+/**
+ * Downloaded and verified version, ready to install
+ */
+staged_version: string | null, state: UpdateState, checked_at: string, };
 
-export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, };
+export type UpdateState = { "state": "idle" } | { "state": "draining", version: string, since: string, running: number, } | { "state": "restarting", version: string, };
 
-export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
+export type InstallKind = "standalone" | "npx" | "development";
 
-export type CheckEditorAvailabilityQuery = { editor_type: EditorType, };
+export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
-export type CheckEditorAvailabilityResponse = { available: boolean, };
+export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
 
-export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
+export type EditorConfig = { editor_type: EditorType, custom_command: string | null, remote_ssh_host: string | null, remote_ssh_user: string | null, };
 
-export type CurrentUserResponse = { user_id: string, };
+export enum EditorType { VS_CODE = "VS_CODE", CURSOR = "CURSOR", WINDSURF = "WINDSURF", INTELLI_J = "INTELLI_J", ZED = "ZED", XCODE = "XCODE", CUSTOM = "CUSTOM" }
 
-export type CreateFollowUpAttempt = { prompt: string, variant: string | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
+export type EditorOpenError = { "type": "executable_not_found", executable: string, editor_type: EditorType, } | { "type": "invalid_command", details: string, editor_type: EditorType, } | { "type": "launch_failed", executable: string, details: string, editor_type: EditorType, };
 
-export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
+export type GitHubConfig = { pat: string | null, oauth_token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, };
 
-export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
+export enum SoundFile { ABSTRACT_SOUND1 = "ABSTRACT_SOUND1", ABSTRACT_SOUND2 = "ABSTRACT_SOUND2", ABSTRACT_SOUND3 = "ABSTRACT_SOUND3", ABSTRACT_SOUND4 = "ABSTRACT_SOUND4", COW_MOOING = "COW_MOOING", PHONE_VIBRATION = "PHONE_VIBRATION", ROOSTER = "ROOSTER" }
 
-export type MergeTaskAttemptRequest = { repo_id: string, };
+export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO" | "ZH_HANS";
 
-export type PushTaskAttemptRequest = { repo_id: string, };
+export type ShowcaseState = { seen_features: Array<string>, };
 
-export type RenameBranchRequest = { new_branch_name: string, };
+/**
+ * Branding the auto-description prompt asks for in pull request titles and descriptions.
+ * Prompts place it with the `{title_branding}` and `{footer_branding}` placeholders.
+ */
+export type PrBrandingConfig = { 
+/**
+ * Appended to pull request titles; no suffix when unset
+ */
+title_suffix: string | null, 
+/**
+ * Note at the end of pull request descriptions; no footer when unset
+ */
+footer: string | null, };
 
-export type RenameBranchResponse = { branch: string, };
+/**
+ * Secret scrubbing applied to prompts sent to coding agents and to stored execution logs
+ */
+export type RedactionConfig = { enabled: boolean, 
+/**
+ * Also redact long random-looking tokens that match no known format
+ */
+entropy_detection: boolean, 
+/**
+ * Redact values of environment variables whose names look like secrets
+ */
+redact_env_values: boolean, 
+/**
+ * Extra regular expressions; a `secret` named group limits what gets replaced
+ */
+extra_patterns: Array<string>, };
 
-export type OpenEditorRequest = { editor_type: string | null, file_path: string | null, };
+export type ExecutorPolicy = { executor: BaseCodingAgent, 
+/**
+ * Limit the policy to one variant; `None` applies it to every variant
+ */
+variant: string | null, 
+/**
+ * Repository names the executor may change; empty allows every repository
+ */
+allowed_repos: Array<string>, 
+/**
+ * Gitignore-style patterns the executor may not write, e.g. `.github/workflows/` or `infra/`
+ */
+protected_paths: Array<string>, };
 
-export type OpenEditorResponse = { url: string | null, };
+export type ExecutorLimit = { executor: BaseCodingAgent, 
+/**
+ * Limit one variant; `None` counts every variant of the executor together
+ */
+variant: string | null, 
+/**
+ * Runs allowed at the same time
+ */
+max_concurrent: number | null, 
+/**
+ * Runs allowed to start per minute
+ */
+requests_per_minute: number | null, };
 
-export type AssignSharedTaskRequest = { new_assignee_user_id: string | null, };
+/**
+ * A coding agent run waiting for a slot of its executor
+ */
+export type ThrottledExecution = { execution_process_id: string, executor_profile_id: ExecutorProfileId, waiting_since: string, };
 
-export type ShareTaskResponse = { shared_task_id: string, };
+export type SecretFinding = { repo: string, path: string, 
+/**
+ * 1-based line number in the new version of the file
+ */
+line: number, rule: string, 
+/**
+ * Stable identifier used to acknowledge a false positive; independent of the line number
+ */
+fingerprint: string, 
+/**
+ * The offending line with the secret itself redacted
+ */
+preview: string, acknowledged: boolean, };
 
-export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type DependencyPolicyConfig = { enabled: boolean, 
+/**
+ * SPDX identifiers, e.g. `MIT`; empty allows any licence
+ */
+allowed_licenses: Array<string>, 
+/**
+ * Package names that may not be added, e.g. `left-pad`
+ */
+banned_packages: Array<string>, 
+/**
+ * Require exact versions (`=1.2.3` in Cargo, `1.2.3` in npm)
+ */
+require_pinned_versions: boolean, 
+/**
+ * Refuse to open a pull request while the branch has error findings
+ */
+block_pull_requests: boolean, };
 
-export type CreateGitHubPrRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
+/**
+ * How a lint command's output is parsed
+ */
+export type LintFormat = "clippy" | "eslint" | "ruff" | "custom";
 
-export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
+export type LintCommand = { name: string, 
+/**
+ * Repository the command runs in; `None` runs it in every repository
+ */
+repo: string | null, command: string, format: LintFormat, };
 
-export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
+export type LintConfig = { enabled: boolean, commands: Array<LintCommand>, 
+/**
+ * Only keep findings in files the workspace branch changed
+ */
+changed_files_only: boolean, 
+/**
+ * Start a follow-up turn asking the agent to fix the findings
+ */
+auto_fix: boolean, 
+/**
+ * Fix-up turns in a row before the attempt is left for review as is
+ */
+max_fix_attempts: number, timeout_secs: number, };
 
-export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CoverageConfig = { enabled: boolean, 
+/**
+ * Report locations relative to each repository root, tried in order. Files ending in `.xml`
+ * are read as Cobertura, anything else as lcov.
+ */
+report_paths: Array<string>, 
+/**
+ * Add a coverage summary to pull request descriptions
+ */
+include_in_pr_body: boolean, };
 
-export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
+export type FlakyConfig = { 
+/**
+ * Flaky failures after which a command is quarantined automatically; 0 disables it
+ */
+auto_quarantine_threshold: number, };
 
-export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
+export type FlakyCommand = { command_hash: string, command: string, kind: CommandKind, runs: number, failures: number, 
+/**
+ * Failures that passed on a retry at the same commit
+ */
+flaky_failures: number, last_run_at: string, quarantined: boolean, };
 
-export type RunAgentSetupResponse = Record<string, never>;
+export type RoutingConfig = { enabled: boolean, 
+/**
+ * Checked in order; the first matching rule picks the profile
+ */
+rules: Array<RoutingRule>, 
+/**
+ * Estimated cost of one turn per profile
+ */
+profile_costs: Array<ProfileCost>, 
+/**
+ * Estimated spend allowed per attempt; None for no budget
+ */
+attempt_budget: number | null, };
 
-export type GhCliSetupError = "BREW_MISSING" | "SETUP_HELPER_NOT_SUPPORTED" | { "OTHER": { message: string, } };
+export type RoutingRule = { name: string, 
+/**
+ * Turn purposes the rule applies to; empty matches any
+ */
+purposes: Array<TurnPurpose>, 
+/**
+ * Task sizes the rule applies to; empty matches any
+ */
+task_sizes: Array<TaskSize>, 
+/**
+ * Only match after at least this many consecutive failed turns
+ */
+min_previous_failures: number | null, 
+/**
+ * Only match when no more than this much budget is left
+ */
+max_budget_remaining: number | null, executor_profile_id: ExecutorProfileId, };
 
-export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, };
+export type ProfileCost = { 
+/**
+ * A cost without a variant applies to every variant of the executor
+ */
+executor_profile_id: ExecutorProfileId, cost_per_turn: number, };
 
-export type AbortConflictsRequest = { repo_id: string, };
+export type CompactionConfig = { enabled: boolean, 
+/**
+ * Upper bound on the size of the context block
+ */
+max_context_chars: number, 
+/**
+ * Latest turns kept with their full request and outcome
+ */
+recent_turns: number, };
 
-export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, } | { "type": "rebase_in_progress" };
+export type FetchConfig = { enabled: boolean, 
+/**
+ * Minutes between fetches of each repository, unless the repository sets its own
+ */
+interval_minutes: number, };
 
-export type PushError = { "type": "force_push_required" };
+export type CommentTriggerConfig = { enabled: boolean, 
+/**
+ * Secret configured on the GitHub webhook; deliveries without a valid signature are rejected
+ */
+webhook_secret: string | null, 
+/**
+ * Comments containing any of these phrases are sent to the agent, ignoring case
+ */
+phrases: Array<string>, 
+/**
+ * Logins whose comments trigger follow-ups whatever their relation to the repository
+ */
+allowed_authors: Array<string>, 
+/**
+ * GitHub `author_association` values whose comments trigger follow-ups, such as `OWNER`,
+ * `MEMBER`, `COLLABORATOR` or `CONTRIBUTOR`
+ */
+allowed_associations: Array<string>, };
 
-export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, };
+export type WeekStart = "monday" | "sunday" | "saturday";
 
-export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+export type ScheduleConfig = { 
 /**
- * True if a `git rebase` is currently in progress in this worktree
+ * IANA timezone such as `Europe/Berlin`; the system timezone when unset
  */
-is_rebase_in_progress: boolean, 
+timezone: string | null, week_start: WeekStart, 
 /**
- * Current conflict operation if any
+ * Sprint length in weeks
  */
-conflict_op: ConflictOp | null, 
+sprint_weeks: number, 
 /**
- * List of files currently in conflicted (unmerged) state
+ * Any day of the first sprint; sprints start on the week start either way
  */
-conflicted_files: Array<string>, };
+sprint_anchor: string | null, };
 
-export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
+export type Sprint = { 
+/**
+ * Sprints before the anchor have numbers below 1
+ */
+number: number, 
+/**
+ * First day of the sprint, in the user's timezone
+ */
+starts_on: string, 
+/**
+ * Last day of the sprint, in the user's timezone
+ */
+ends_on: string, start: string, 
+/**
+ * First instant after the sprint
+ */
+end: string, };
 
-export type AttachPrResponse = { pr_attached: boolean, pr_url: string | null, pr_number: bigint | null, pr_status: MergeStatus | null, };
+export type DueTask = { task_id: string, title: string, status: TaskStatus, due_at: string, 
+/**
+ * The day the task is due, in the user's timezone
+ */
+due_on: string, 
+/**
+ * Whole days past the due day; 0 when overdue since earlier today
+ */
+days_overdue: number, };
 
-export type AttachExistingPrRequest = { repo_id: string, };
+/**
+ * Open tasks by due date, as of today in the user's timezone
+ */
+export type DueDigest = { date: string, timezone: string, sprint: Sprint, overdue: Array<DueTask>, 
+/**
+ * Due later today
+ */
+due_today: Array<DueTask>, 
+/**
+ * Due after today but before the current sprint ends
+ */
+due_this_sprint: Array<DueTask>, };
 
-export type PrCommentsResponse = { comments: Array<UnifiedComment>, };
+/**
+ * A recorded tool call that changes the workspace
+ */
+export type ReplayStep = { "type": "file_edit", path: string, change: FileChange, } | { "type": "command", command: string, };
 
-export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" };
+export type ReplayRequest = { 
+/**
+ * Also run the recorded commands; off by default since they may not be repeatable
+ */
+run_commands: boolean, 
+/**
+ * Leave the replay worktrees on disk for inspection
+ */
+keep_worktree: boolean, };
 
-export type GetPrCommentsQuery = { repo_id: string, };
+export type StepOutcome = { "status": "applied" } | { "status": "skipped", reason: string, } | { "status": "failed", message: string, };
 
-export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string, body: string, created_at: string, url: string, } | { "comment_type": "review", id: bigint, author: string, author_association: string, body: string, created_at: string, url: string, path: string, line: bigint | null, diff_hunk: string, };
+export type ReplayStepResult = { step: ReplayStep, outcome: StepOutcome, };
 
-export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+export type RepoReplayResult = { repo: string, 
 /**
- * True if a `git rebase` is currently in progress in this worktree
+ * Commit the execution ended on
  */
-is_rebase_in_progress: boolean, 
+expected_commit: string | null, 
 /**
- * Current conflict operation if any
+ * Whether the replayed tree matches it; None when the end state was not recorded
  */
-conflict_op: ConflictOp | null, 
+matches: boolean | null, 
 /**
- * List of files currently in conflicted (unmerged) state
+ * Paths that differ from the expected commit
  */
-conflicted_files: Array<string>, };
+differing_paths: Array<string>, };
 
-export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
+export type ReplayReport = { execution_process_id: string, steps: Array<ReplayStepResult>, repos: Array<RepoReplayResult>, 
+/**
+ * Every repository with a recorded end state was reproduced
+ */
+reproduced: boolean, 
+/**
+ * Where the replay worktrees were left, when kept
+ */
+worktree_path: string | null, };
 
-export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
+export type TaskRefinement = { title: string, 
+/**
+ * What should change and why, without the lists below
+ */
+summary: string, acceptance_criteria: Array<string>, 
+/**
+ * Modules, files or components likely to be touched
+ */
+affected_areas: Array<string>, test_plan: Array<string>, };
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, };
+export type Effort = "small" | "medium" | "large";
 
-export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
+export type SimilarTask = { task_id: string, title: string, 
+/**
+ * Cosine similarity between 0 and 1
+ */
+similarity: number, turns: number, duration_seconds: number, estimated_cost: number, };
 
-export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
+export type TaskEstimate = { effort: Effort, turns: number, 
+/**
+ * Agent time, not including review or waiting
+ */
+duration_seconds: number, estimated_cost: number, 
+/**
+ * The finished tasks the estimate is based on, most similar first
+ */
+similar_tasks: Array<SimilarTask>, };
 
-export type EditorConfig = { editor_type: EditorType, custom_command: string | null, remote_ssh_host: string | null, remote_ssh_user: string | null, };
+export type TaskCycleTime = { task_id: string, title: string, started_at: string, completed_at: string, hours: number, };
 
-export enum EditorType { VS_CODE = "VS_CODE", CURSOR = "CURSOR", WINDSURF = "WINDSURF", INTELLI_J = "INTELLI_J", ZED = "ZED", XCODE = "XCODE", CUSTOM = "CUSTOM" }
+export type CycleTimeSummary = { average_hours: number | null, median_hours: number | null, 
+/**
+ * 85% of the tasks were done within this time
+ */
+p85_hours: number | null, 
+/**
+ * Tasks completed in the period that were worked on, most recently completed first
+ */
+tasks: Array<TaskCycleTime>, };
 
-export type EditorOpenError = { "type": "executable_not_found", executable: string, editor_type: EditorType, } | { "type": "invalid_command", details: string, editor_type: EditorType, } | { "type": "launch_failed", executable: string, details: string, editor_type: EditorType, };
+export type WeeklyThroughput = { 
+/**
+ * Monday the week starts on, at midnight UTC
+ */
+week_start: string, completed: number, };
 
-export type GitHubConfig = { pat: string | null, oauth_token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, };
+export type AgingTask = { task_id: string, title: string, entered_at: string, age_hours: number, };
 
-export enum SoundFile { ABSTRACT_SOUND1 = "ABSTRACT_SOUND1", ABSTRACT_SOUND2 = "ABSTRACT_SOUND2", ABSTRACT_SOUND3 = "ABSTRACT_SOUND3", ABSTRACT_SOUND4 = "ABSTRACT_SOUND4", COW_MOOING = "COW_MOOING", PHONE_VIBRATION = "PHONE_VIBRATION", ROOSTER = "ROOSTER" }
+export type ColumnAging = { status: TaskStatus, 
+/**
+ * Oldest first
+ */
+tasks: Array<AgingTask>, };
 
-export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO" | "ZH_HANS";
+export type BoardMetrics = { project_id: string, since: string, until: string, cycle_time: CycleTimeSummary, 
+/**
+ * Tasks completed per week of the period, including weeks without any
+ */
+throughput: Array<WeeklyThroughput>, aging_wip: Array<ColumnAging>, };
 
-export type ShowcaseState = { seen_features: Array<string>, };
+/**
+ * Report of [`BoardMetrics`] to export as CSV
+ */
+export type MetricsReport = "cycle_time" | "throughput" | "aging_wip";
 
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
 export type SharedTaskDetails = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, };
 
+/**
+ * Which version of a conflicting task to keep
+ */
+export type MirrorSide = "local" | "remote";
+
+export type MirrorSyncReport = { 
+/**
+ * Local tasks published to the remote project
+ */
+published: number, 
+/**
+ * Tasks from other instances created here
+ */
+created: number, 
+/**
+ * Local changes sent to the remote project
+ */
+pushed: number, 
+/**
+ * Remote changes applied here
+ */
+pulled: number, 
+/**
+ * Tasks deleted remotely and no longer mirrored; the local task is kept
+ */
+unlinked: number, 
+/**
+ * Local changes the remote project refused, retried on the next sync
+ */
+skipped: number, conflicts: number, };
+
 export type QueuedMessage = { 
 /**
  * The session this message is queued for
@@ -453,7 +2437,11 @@ executor_profile_id: ExecutorProfileId,
  * Optional relative path to execute the agent in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Run with a read-only tool policy, for questions that should not change the workspace
+ */
+read_only: boolean, };
 
 export type CommandExitStatus = { "type": "exit_code", code: number, } | { "type": "success", success: boolean, };
 
@@ -493,15 +2481,17 @@ export type PatchType = { "type": "NORMALIZED_ENTRY", "content": NormalizedEntry
 
 export type JsonValue = number | string | boolean | Array<JsonValue> | { [key in string]?: JsonValue } | null;
 
-export const DEFAULT_PR_DESCRIPTION_PROMPT = `Update the GitHub PR that was just created with a better title and description.
+export const DEFAULT_PR_DESCRIPTION_PROMPT = `Update the pull request that was just created with a better title and description.
 The PR number is #{pr_number} and the URL is {pr_url}.
 
 Analyze the changes in this branch and write:
-1. A concise, descriptive title that summarizes the changes, postfixed with "(Vibe Kanban)"
+1. A concise, descriptive title that summarizes the changes{title_branding}
 2. A detailed description that explains:
    - What changes were made
    - Why they were made (based on the task context)
-   - Any important implementation details
-   - At the end, include a note: "This PR was written using [Vibe Kanban](https://vibekanban.com)"
+   - Any important implementation details{footer_branding}
+
+Write the title and description in {language}.
 
-Use \`gh pr edit\` to update the PR.`;export type UnifiedComment = UnifiedPrComment;
+Use the \`update_pull_request\` tool of the vibe-kanban MCP server to update the PR, with the
+workspace and repository ids from \`get_context\`.`;export type UnifiedComment = UnifiedPrComment;